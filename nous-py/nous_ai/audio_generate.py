@@ -374,21 +374,7 @@ async def _generate_podcast_audio(
         combined = AudioSegment.empty()
 
         for i, chunk in enumerate(audio_chunks):
-            import io
-
-            try:
-                segment = AudioSegment.from_mp3(io.BytesIO(chunk))
-            except Exception as mp3_err:
-                # Try WAV fallback (e.g. from Kokoro without ffmpeg mp3 support)
-                try:
-                    segment = AudioSegment.from_wav(io.BytesIO(chunk))
-                except Exception as wav_err:
-                    raise RuntimeError(
-                        f"Failed to decode audio chunk {i} as MP3 ({mp3_err}) "
-                        f"or WAV ({wav_err}). The TTS provider may have returned "
-                        f"invalid audio data ({len(chunk)} bytes)."
-                    ) from wav_err
-            combined += segment
+            combined += _decode_audio(chunk, f"chunk {i}")
             if i < len(audio_chunks) - 1:
                 combined += silence
 
@@ -406,6 +392,38 @@ async def _generate_podcast_audio(
     return duration
 
 
+def _decode_audio(chunk: bytes, label: str) -> Any:
+    """Decode synthesized audio into a pydub AudioSegment."""
+    import io
+
+    from pydub import AudioSegment
+
+    try:
+        return AudioSegment.from_mp3(io.BytesIO(chunk))
+    except Exception as mp3_err:
+        # Try WAV fallback (e.g. from Kokoro without ffmpeg mp3 support)
+        try:
+            return AudioSegment.from_wav(io.BytesIO(chunk))
+        except Exception as wav_err:
+            raise RuntimeError(
+                f"Failed to decode audio {label} as MP3 ({mp3_err}) "
+                f"or WAV ({wav_err}). The TTS provider may have returned "
+                f"invalid audio data ({len(chunk)} bytes)."
+            ) from wav_err
+
+
+def _tts_config_from_dict(tts_config: dict[str, Any] | None) -> TTSConfig:
+    tts_config = tts_config or {}
+    return TTSConfig(
+        provider=TTSProviderType(tts_config.get("provider", "openai")),
+        api_key=tts_config.get("api_key"),
+        base_url=tts_config.get("base_url"),
+        voice=tts_config.get("voice", "alloy"),
+        model=tts_config.get("model"),
+        speed=tts_config.get("speed", 1.0),
+    )
+
+
 # ===== Main Entry Points =====
 
 
@@ -438,15 +456,7 @@ async def generate_page_audio(
     """
     start_time = time.time()
 
-    tts_config = tts_config or {}
-    config_a = TTSConfig(
-        provider=TTSProviderType(tts_config.get("provider", "openai")),
-        api_key=tts_config.get("api_key"),
-        base_url=tts_config.get("base_url"),
-        voice=tts_config.get("voice", "alloy"),
-        model=tts_config.get("model"),
-        speed=tts_config.get("speed", 1.0),
-    )
+    config_a = _tts_config_from_dict(tts_config)
 
     # Ensure output directory exists
     Path(output_dir).mkdir(parents=True, exist_ok=True)
@@ -555,6 +565,101 @@ def generate_page_audio_sync(
     )
 
 
+async def generate_reading_queue_audio(
+    chapters: list[dict[str, str]],
+    output_path: str,
+    tts_config: dict[str, Any] | None = None,
+) -> dict[str, Any]:
+    """Narrate several pages into one MP3, one chapter per page.
+
+    Args:
+        chapters: {"title", "content"} per page, in reading order.
+        output_path: File to write the MP3 to.
+        tts_config: TTSConfig fields as a dict.
+
+    Returns:
+        The audio file's details, with the start and end of each chapter
+        in seconds under "chapters".
+    """
+    start_time = time.time()
+    config = _tts_config_from_dict(tts_config)
+    Path(output_path).parent.mkdir(parents=True, exist_ok=True)
+
+    # Each chapter opens with its title so the listener knows where they are
+    audio_chunks: list[bytes] = []
+    for chapter in chapters:
+        text = f"{chapter['title']}.\n\n{chapter['content']}"
+        chunk = await synthesize(text, config)
+        if not chunk:
+            raise RuntimeError(
+                f"TTS provider '{config.provider.value}' returned empty audio for "
+                f"\"{chapter['title']}\"."
+            )
+        audio_chunks.append(chunk)
+
+    markers: list[dict[str, Any]] = []
+    if PYDUB_AVAILABLE:
+        from pydub import AudioSegment
+
+        pause = AudioSegment.silent(duration=1200)
+        combined = AudioSegment.empty()
+        for i, (chapter, chunk) in enumerate(zip(chapters, audio_chunks)):
+            start = len(combined)
+            combined += _decode_audio(chunk, f"for \"{chapter['title']}\"")
+            markers.append(
+                {
+                    "title": chapter["title"],
+                    "start_seconds": start / 1000.0,
+                    "end_seconds": len(combined) / 1000.0,
+                }
+            )
+            if i < len(audio_chunks) - 1:
+                combined += pause
+        combined.export(output_path, format="mp3")
+        duration = len(combined) / 1000.0
+    else:
+        # Raw MP3 concatenation; chapter times are estimated from size
+        # (~16kB/s for 128kbps MP3)
+        offset = 0.0
+        with open(output_path, "wb") as f:
+            for chapter, chunk in zip(chapters, audio_chunks):
+                f.write(chunk)
+                length = len(chunk) / 16000.0
+                markers.append(
+                    {
+                        "title": chapter["title"],
+                        "start_seconds": offset,
+                        "end_seconds": offset + length,
+                    }
+                )
+                offset += length
+        duration = offset
+
+    return {
+        "audio_path": output_path,
+        "duration_seconds": duration,
+        "format": "mp3",
+        "file_size_bytes": os.path.getsize(output_path),
+        "generation_time_seconds": round(time.time() - start_time, 2),
+        "chapters": markers,
+    }
+
+
+def generate_reading_queue_audio_sync(
+    chapters: list[dict[str, str]],
+    output_path: str,
+    tts_config: dict[str, Any] | None = None,
+) -> dict[str, Any]:
+    """Synchronous wrapper for generate_reading_queue_audio."""
+    return asyncio.run(
+        generate_reading_queue_audio(
+            chapters=chapters,
+            output_path=output_path,
+            tts_config=tts_config,
+        )
+    )
+
+
 # ===== Voice Listing =====
 
 
@@ -0,0 +1,98 @@
+"""Out-of-process host for the Rust bridge (python_bridge/worker.rs).
+
+Runs nous_ai functions on behalf of the app, so a crash in a native
+dependency takes down this process instead of the app. Speaks
+newline-delimited JSON on stdin/stdout:
+
+    -> {"id": 1, "module": "nous_ai.chat", "function": "chat_sync",
+        "args": [], "kwargs": {...}}
+    <- {"id": 1, "callback": 0, "args": [...]}        (zero or more)
+    <- {"id": 1, "result": ...}
+    <- {"id": 1, "error": {"type": "ValueError", "message": "..."}}
+
+An argument of the form {"__nous_callback__": n} is replaced with a function
+that reports its arguments back as callback messages. Requests run on a
+thread pool, so a long transcription doesn't hold up a quick chat call.
+Output printed by the called code goes to stderr; stdout carries only the
+protocol.
+
+Usage:
+    python -m nous_ai.bridge_worker
+"""
+
+from __future__ import annotations
+
+import importlib
+import json
+import os
+import sys
+import threading
+import traceback
+from concurrent.futures import ThreadPoolExecutor
+from typing import Any, Callable
+
+CALLBACK_KEY = "__nous_callback__"
+MAX_CONCURRENT_CALLS = 8
+
+
+def main() -> None:
+    # Keep the real stdout for the protocol and point fd 1 (and sys.stdout)
+    # at stderr, so prints from libraries can't corrupt it
+    protocol = os.fdopen(os.dup(1), "w", encoding="utf-8")
+    os.dup2(2, 1)
+    sys.stdout = sys.stderr
+    write_lock = threading.Lock()
+
+    def send(message: dict[str, Any]) -> None:
+        line = json.dumps(message, default=str)
+        with write_lock:
+            protocol.write(line + "\n")
+            protocol.flush()
+
+    with ThreadPoolExecutor(max_workers=MAX_CONCURRENT_CALLS) as pool:
+        for line in sys.stdin:
+            if not line.strip():
+                continue
+            try:
+                request = json.loads(line)
+            except json.JSONDecodeError as e:
+                print(f"bridge_worker: unreadable request: {e}", file=sys.stderr)
+                continue
+            pool.submit(run_request, request, send)
+
+
+def run_request(request: dict[str, Any], send: Callable[[dict[str, Any]], None]) -> None:
+    """Run one request and send its result or error."""
+    request_id = request.get("id")
+    try:
+        module = importlib.import_module(request["module"])
+        func = getattr(module, request["function"])
+        args = [resolve_argument(a, request_id, send) for a in request.get("args", [])]
+        kwargs = {
+            key: resolve_argument(value, request_id, send)
+            for key, value in request.get("kwargs", {}).items()
+        }
+        result = func(*args, **kwargs)
+    except Exception as e:
+        traceback.print_exc()
+        send({"id": request_id, "error": {"type": type(e).__name__, "message": str(e)}})
+        return
+    send({"id": request_id, "result": result})
+
+
+def resolve_argument(
+    value: Any, request_id: Any, send: Callable[[dict[str, Any]], None]
+) -> Any:
+    """Turn a callback marker into a function that reports back to the app."""
+    if isinstance(value, dict) and list(value) == [CALLBACK_KEY]:
+        index = value[CALLBACK_KEY]
+
+        def callback(*args: Any) -> None:
+            send({"id": request_id, "callback": index, "args": list(args)})
+
+        return callback
+    return value
+
+
+if __name__ == "__main__":
+    main()
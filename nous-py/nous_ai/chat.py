@@ -5,6 +5,7 @@ import json
 import re
 from typing import Any, Generator
 
+from nous_ai import tool_permissions
 from nous_ai.models import (
     ChatMessage,
     ChatResponse,
@@ -710,6 +711,24 @@ STORAGE_TOOL_NAMES = {t["function"]["name"] for t in STORAGE_TOOLS}
 NOTEBOOK_TOOLS.extend(STORAGE_TOOLS)
 
 
+def _tool_denial(
+    func_name: str, func_args: dict, tool_call_id: str, library_path: str | None = None
+) -> dict | None:
+    """Check a storage or MCP tool call against the library's tool permissions.
+
+    Returns the check (see ``nous_ai.tool_permissions``) when the call may
+    not run. Other tools are run by the frontend, which checks them itself.
+    """
+    if not (is_mcp_tool(func_name) or func_name in STORAGE_TOOL_NAMES):
+        return None
+    if library_path is None:
+        from nous_mcp.storage import _try_resolve_local_library
+
+        library_path = _try_resolve_local_library(None)
+    result = tool_permissions.check(library_path, func_name, func_args, tool_call_id)
+    return None if result["decision"] == "allow" else result
+
+
 def _execute_storage_tool(func_name: str, func_args: dict, library_path: str | None = None) -> str:
     """Execute a storage tool directly in Python.
 
@@ -1320,6 +1339,7 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
 
     # Handle based on provider
     actions: list[dict[str, Any]] = []
+    permission_denials: list[dict[str, Any]] = []
     response_content = ""
     thinking_content = ""
     response_model = config.model
@@ -1384,8 +1404,13 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
                 }
                 actions.append(action)
 
-                # Create a placeholder result (actual execution happens in Rust)
-                if func_name == "create_notebook":
+                # Storage tools run here if permitted; for the rest, create a
+                # placeholder result (actual execution happens in Rust)
+                denial = _tool_denial(func_name, func_args, tool_call.id)
+                if denial:
+                    permission_denials.append(denial)
+                    result = tool_permissions.message(denial)
+                elif func_name == "create_notebook":
                     result = f"Created notebook: {func_args.get('name')}"
                 elif func_name == "create_page":
                     result = f"Created page: {func_args.get('title')} in {func_args.get('notebook_name')}"
@@ -1477,7 +1502,11 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
                     actions.append(action)
 
                     # Create placeholder result
-                    if func_name == "create_notebook":
+                    denial = _tool_denial(func_name, func_args, block.id)
+                    if denial:
+                        permission_denials.append(denial)
+                        result = tool_permissions.message(denial)
+                    elif func_name == "create_notebook":
                         result = f"Created notebook: {func_args.get('name')}"
                     elif func_name == "create_page":
                         result = f"Created page: {func_args.get('title')} in {func_args.get('notebook_name')}"
@@ -1580,7 +1609,11 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
                 }
                 actions.append(action)
 
-                if func_name == "create_notebook":
+                denial = _tool_denial(func_name, func_args, tool_call.id)
+                if denial:
+                    permission_denials.append(denial)
+                    result = tool_permissions.message(denial)
+                elif func_name == "create_notebook":
                     result = f"Created notebook: {func_args.get('name')}"
                 elif func_name == "create_page":
                     result = f"Created page: {func_args.get('title')} in {func_args.get('notebook_name')}"
@@ -1624,6 +1657,7 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
         "finish_reason": "stop",
         "actions": actions,
         "thinking": thinking_content,
+        "permission_denials": permission_denials,
     }
 
 
@@ -2131,6 +2165,7 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
     system_message = "\n".join(system_parts)
 
     actions: list[dict[str, Any]] = []
+    permission_denials: list[dict[str, Any]] = []
     response_content = ""
     thinking_content = ""
     response_model = config.model
@@ -2207,7 +2242,12 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
                 callback({"type": "action", **action})
 
                 # Handle MCP tools differently - they execute and return results
-                if is_mcp_tool(func_name):
+                denial = _tool_denial(func_name, func_args, tool_call.id, library_path)
+                if denial:
+                    permission_denials.append(denial)
+                    callback({"type": "tool_permission", "check": denial})
+                    result = tool_permissions.message(denial)
+                elif is_mcp_tool(func_name):
                     if mcp_manager:
                         server_name, tool_name = parse_mcp_tool_name(func_name)
                         mcp_result = await mcp_manager.call_tool(server_name, tool_name, func_args)
@@ -2331,7 +2371,12 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
                     callback({"type": "action", **action})
 
                     # Handle MCP tools differently - they execute and return results
-                    if is_mcp_tool(func_name):
+                    denial = _tool_denial(func_name, func_args, block.id, library_path)
+                    if denial:
+                        permission_denials.append(denial)
+                        callback({"type": "tool_permission", "check": denial})
+                        result = tool_permissions.message(denial)
+                    elif is_mcp_tool(func_name):
                         if mcp_manager:
                             server_name, tool_name = parse_mcp_tool_name(func_name)
                             mcp_result = await mcp_manager.call_tool(
@@ -2500,7 +2545,12 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
                 actions.append(action)
                 callback({"type": "action", **action})
 
-                if is_mcp_tool(func_name):
+                denial = _tool_denial(func_name, func_args, tc["id"], library_path)
+                if denial:
+                    permission_denials.append(denial)
+                    callback({"type": "tool_permission", "check": denial})
+                    result = tool_permissions.message(denial)
+                elif is_mcp_tool(func_name):
                     if mcp_manager:
                         server_name, tool_name = parse_mcp_tool_name(func_name)
                         mcp_result = await mcp_manager.call_tool(server_name, tool_name, func_args)
@@ -2581,6 +2631,7 @@ When the user asks you to "create", "write", "make", "generate", or "save" conte
         "finish_reason": "stop",
         "actions": actions,
         "thinking": thinking_content,
+        "permission_denials": permission_denials,
     }
 
 
@@ -0,0 +1,258 @@
+"""PDF annotation export and import for Nous.
+
+Nous stores PDF highlights as JSON alongside the notebook assets, in the
+viewer's coordinate space: PDF points at zoom 1, origin at the top-left of
+the page, y growing downward. Real PDF annotations use the page's native
+space (origin bottom-left, y growing upward), so both directions here
+convert through the page's media box.
+
+Export writes a copy of the source PDF with each highlight as a /Highlight
+annotation (with the note in /Contents), so it shows up in Preview,
+Acrobat, etc. Import reads markup and note annotations back out of an
+externally annotated PDF.
+"""
+
+from datetime import datetime, timezone
+from pathlib import Path
+from typing import Any
+
+PYPDF_AVAILABLE = False
+try:
+    from pypdf import PdfReader, PdfWriter
+    from pypdf.generic import (
+        ArrayObject,
+        DictionaryObject,
+        FloatObject,
+        NameObject,
+        NumberObject,
+        TextStringObject,
+    )
+
+    PYPDF_AVAILABLE = True
+except ImportError:
+    pass
+
+# Markup annotation subtypes that cover text (all carry /QuadPoints)
+MARKUP_SUBTYPES = {"/Highlight", "/Underline", "/Squiggly", "/StrikeOut"}
+# Note-style annotations imported as zero-text highlights
+NOTE_SUBTYPES = {"/Text", "/FreeText"}
+
+DEFAULT_COLOR = "#facc15"
+
+
+def _require_pypdf() -> None:
+    if not PYPDF_AVAILABLE:
+        raise RuntimeError(
+            "PDF annotation support requires pypdf. Install with: uv sync --extra pdf"
+        )
+
+
+def _hex_to_rgb(color: str) -> list[float]:
+    value = color.lstrip("#")
+    if len(value) == 3:
+        value = "".join(c * 2 for c in value)
+    try:
+        return [int(value[i : i + 2], 16) / 255.0 for i in (0, 2, 4)]
+    except (ValueError, IndexError):
+        return _hex_to_rgb(DEFAULT_COLOR)
+
+
+def _rgb_to_hex(components: Any) -> str:
+    try:
+        values = [float(c) for c in components]
+    except (TypeError, ValueError):
+        return DEFAULT_COLOR
+    if len(values) != 3:
+        return DEFAULT_COLOR
+    return "#" + "".join(f"{round(max(0.0, min(1.0, v)) * 255):02x}" for v in values)
+
+
+def _page_box(page: Any) -> tuple[float, float, float, float]:
+    box = page.mediabox
+    return float(box.left), float(box.bottom), float(box.right), float(box.top)
+
+
+def viewer_rect_to_pdf(rect: dict[str, float], box: tuple[float, float, float, float]) -> list[float]:
+    """Convert a top-left viewer rect to a PDF [x1, y1, x2, y2] rect."""
+    left, _bottom, _right, top = box
+    x1 = left + rect["x"]
+    x2 = x1 + rect["width"]
+    y2 = top - rect["y"]
+    y1 = y2 - rect["height"]
+    return [x1, y1, x2, y2]
+
+
+def pdf_rect_to_viewer(pdf_rect: list[float], box: tuple[float, float, float, float]) -> dict[str, float]:
+    """Convert a PDF [x1, y1, x2, y2] rect to a top-left viewer rect."""
+    left, _bottom, _right, top = box
+    x1, y1, x2, y2 = (float(v) for v in pdf_rect)
+    x_min, x_max = min(x1, x2), max(x1, x2)
+    y_min, y_max = min(y1, y2), max(y1, y2)
+    return {
+        "x": x_min - left,
+        "y": top - y_max,
+        "width": x_max - x_min,
+        "height": y_max - y_min,
+    }
+
+
+def _quad_points(pdf_rects: list[list[float]]) -> list[float]:
+    # Per the PDF spec each quad is (x1,y1 x2,y2 x3,y3 x4,y4) in the order
+    # top-left, top-right, bottom-left, bottom-right (what viewers expect).
+    points: list[float] = []
+    for x1, y1, x2, y2 in pdf_rects:
+        points.extend([x1, y2, x2, y2, x1, y1, x2, y1])
+    return points
+
+
+def _quads_to_rects(quads: list[float]) -> list[list[float]]:
+    rects = []
+    for i in range(0, len(quads) - 7, 8):
+        xs = [float(quads[i + j]) for j in (0, 2, 4, 6)]
+        ys = [float(quads[i + j]) for j in (1, 3, 5, 7)]
+        rects.append([min(xs), min(ys), max(xs), max(ys)])
+    return rects
+
+
+def _highlight_annotation(highlight: dict[str, Any], box: tuple[float, float, float, float]) -> Any:
+    pdf_rects = [viewer_rect_to_pdf(r, box) for r in highlight.get("rects", [])]
+    bounds = [
+        min(r[0] for r in pdf_rects),
+        min(r[1] for r in pdf_rects),
+        max(r[2] for r in pdf_rects),
+        max(r[3] for r in pdf_rects),
+    ]
+    annot = DictionaryObject()
+    annot.update(
+        {
+            NameObject("/Type"): NameObject("/Annot"),
+            NameObject("/Subtype"): NameObject("/Highlight"),
+            NameObject("/Rect"): ArrayObject([FloatObject(v) for v in bounds]),
+            NameObject("/QuadPoints"): ArrayObject(
+                [FloatObject(v) for v in _quad_points(pdf_rects)]
+            ),
+            NameObject("/C"): ArrayObject(
+                [FloatObject(v) for v in _hex_to_rgb(highlight.get("color") or DEFAULT_COLOR)]
+            ),
+            # /NM carries the Nous highlight id so a re-import can de-duplicate
+            NameObject("/NM"): TextStringObject(str(highlight.get("id", ""))),
+            NameObject("/T"): TextStringObject("Nous"),
+            NameObject("/F"): NumberObject(4),  # Print
+        }
+    )
+    note = highlight.get("note")
+    if note:
+        annot[NameObject("/Contents")] = TextStringObject(note)
+    return annot
+
+
+def export_annotated_pdf_sync(
+    source_path: str,
+    output_path: str,
+    highlights: list[dict[str, Any]],
+) -> dict[str, Any]:
+    """Write a copy of `source_path` with `highlights` embedded as annotations.
+
+    Returns a dict with `output_path`, `annotation_count` and `skipped`
+    (highlights whose page number was out of range or that had no rects).
+    """
+    _require_pypdf()
+
+    reader = PdfReader(source_path)
+    writer = PdfWriter()
+    writer.append(reader)
+
+    count = 0
+    skipped = 0
+    for highlight in highlights:
+        page_index = int(highlight.get("pageNumber", 0)) - 1
+        if page_index < 0 or page_index >= len(writer.pages) or not highlight.get("rects"):
+            skipped += 1
+            continue
+        box = _page_box(writer.pages[page_index])
+        writer.add_annotation(page_index, _highlight_annotation(highlight, box))
+        count += 1
+
+    Path(output_path).parent.mkdir(parents=True, exist_ok=True)
+    with open(output_path, "wb") as fh:
+        writer.write(fh)
+
+    return {"output_path": output_path, "annotation_count": count, "skipped": skipped}
+
+
+def _text_in_rects(page: Any, pdf_rects: list[list[float]]) -> str:
+    """Best-effort extraction of the text whose origin falls inside `pdf_rects`."""
+    pieces: list[str] = []
+
+    def visitor(text: str, cm: list[float], tm: list[float], _font: Any, _size: Any) -> None:
+        if not text.strip():
+            return
+        x = tm[4] * cm[0] + tm[5] * cm[2] + cm[4]
+        y = tm[4] * cm[1] + tm[5] * cm[3] + cm[5]
+        for x1, y1, x2, y2 in pdf_rects:
+            # Baselines sit slightly below the top of the highlighted box
+            if x1 - 1 <= x <= x2 + 1 and y1 - 2 <= y <= y2 + 1:
+                pieces.append(text)
+                return
+
+    try:
+        page.extract_text(visitor_text=visitor)
+    except Exception:  # noqa: BLE001 - extraction is best-effort
+        return ""
+    return " ".join(" ".join(pieces).split())
+
+
+def import_pdf_annotations_sync(source_path: str) -> dict[str, Any]:
+    """Read markup and note annotations from an externally annotated PDF.
+
+    Returns `{"highlights": [...]}` shaped like Nous' PDFHighlight JSON.
+    """
+    _require_pypdf()
+
+    reader = PdfReader(source_path)
+    now = datetime.now(timezone.utc).isoformat()
+    highlights: list[dict[str, Any]] = []
+
+    for page_index, page in enumerate(reader.pages):
+        annots = page.get("/Annots")
+        if not annots:
+            continue
+        box = _page_box(page)
+        for ref in annots:
+            annot = ref.get_object()
+            subtype = annot.get("/Subtype")
+            if subtype not in MARKUP_SUBTYPES and subtype not in NOTE_SUBTYPES:
+                continue
+
+            if subtype in MARKUP_SUBTYPES and annot.get("/QuadPoints"):
+                pdf_rects = _quads_to_rects(list(annot["/QuadPoints"]))
+            elif annot.get("/Rect"):
+                pdf_rects = [[float(v) for v in annot["/Rect"]]]
+            else:
+                continue
+
+            contents = annot.get("/Contents")
+            note = str(contents) if contents else None
+            selected_text = ""
+            if subtype in MARKUP_SUBTYPES:
+                selected_text = _text_in_rects(page, pdf_rects)
+
+            highlights.append(
+                {
+                    "id": str(annot.get("/NM") or f"pdf-{page_index + 1}-{len(highlights)}"),
+                    "pageNumber": page_index + 1,
+                    "rects": [pdf_rect_to_viewer(r, box) for r in pdf_rects],
+                    "selectedText": selected_text,
+                    "note": note,
+                    "color": _rgb_to_hex(annot.get("/C")) if annot.get("/C") else DEFAULT_COLOR,
+                    "createdAt": now,
+                    "updatedAt": now,
+                }
+            )
+
+    return {"highlights": highlights}
+
+
+def check_pdf_annotation_availability() -> dict[str, bool]:
+    """Check whether PDF annotation export/import is available."""
+    return {"pypdf": PYPDF_AVAILABLE}
@@ -654,6 +654,9 @@ class RAGChunk(BaseModel):
     title: str  # page title
     content: str
     score: float
+    # Set when the chunk was retrieved from one of several libraries
+    library_id: str | None = None
+    library_name: str | None = None
 
 
 class Citation(BaseModel):
@@ -664,6 +667,8 @@ class Citation(BaseModel):
     page_title: str
     excerpt: str  # Relevant excerpt from the source
     relevance_score: float
+    library_id: str | None = None
+    library_name: str | None = None
 
 
 class CitedResponse(BaseModel):
@@ -699,9 +704,10 @@ def chat_with_citations(
     # Build context with numbered sources
     context_parts = []
     for i, chunk in enumerate(context_chunks[:max_citations], 1):
-        context_parts.append(
-            f"[Source {i}] (Page: {chunk.title})\n{chunk.content}"
-        )
+        origin = f"Page: {chunk.title}"
+        if chunk.library_name:
+            origin += f", Library: {chunk.library_name}"
+        context_parts.append(f"[Source {i}] ({origin})\n{chunk.content}")
 
     context_text = "\n\n".join(context_parts)
 
@@ -762,6 +768,8 @@ Only output the JSON, no additional text."""
                     page_title=chunk.title,
                     excerpt=cit.get("excerpt", chunk.content[:100]),
                     relevance_score=cit.get("relevance_score", chunk.score),
+                    library_id=chunk.library_id,
+                    library_name=chunk.library_name,
                 ))
         return CitedResponse(
             content=data.get("content", ""),
@@ -0,0 +1,179 @@
+"""Permissions for the tools the chat loop runs in Python.
+
+This module mirrors the Rust ``ai_permissions`` module so that the ``nous_*``
+storage tools and MCP tools run here obey the same policies as the app.
+Policies live in the ``ai.toolPermissions`` library setting::
+
+    {
+        "default": {"read": "allow", "write": "allow", "destructive": "ask",
+                    "tools": {"mcp:github:create_issue": "deny"}},
+        "notebooks": {"<notebook-id>": {"write": "ask"}}
+    }
+
+A tool is classified read, write or destructive: built-in tools by a fixed
+table, MCP tools (``mcp:<server>:<tool>``) by the first word of their name,
+with anything unrecognised counted as a write. Keep both sides in sync.
+
+Usage:
+    from nous_ai.tool_permissions import check, message
+
+    result = check(library_path, "mcp:files:delete_file", {"path": "a.txt"})
+    if result["decision"] != "allow":
+        print(message(result))
+"""
+
+from __future__ import annotations
+
+import json
+import logging
+import re
+from pathlib import Path
+
+logger = logging.getLogger(__name__)
+
+PERMISSIONS_SETTING = "ai.toolPermissions"
+MCP_TOOL_PREFIX = "mcp:"
+
+BUILT_IN_TOOLS = {
+    "list_actions": "read",
+    "nous_list_notebooks": "read",
+    "nous_list_sections": "read",
+    "nous_list_folders": "read",
+    "nous_list_pages": "read",
+    "nous_get_page": "read",
+    "nous_search_pages": "read",
+    "nous_list_databases": "read",
+    "nous_get_database": "read",
+    "create_notebook": "write",
+    "create_page": "write",
+    "run_action": "write",
+    "browse_web": "write",
+    "nous_append_to_page": "write",
+    "nous_create_folder": "write",
+    "nous_move_page": "write",
+    "nous_manage_tags": "write",
+    "nous_create_database": "write",
+    "nous_add_database_rows": "write",
+    "nous_update_page": "write",
+    "nous_update_database_rows": "write",
+}
+
+READ_VERBS = {
+    "get", "list", "search", "read", "find", "query", "fetch", "describe",
+    "show", "view", "count", "lookup", "check", "browse",
+}  # fmt: skip
+
+DESTRUCTIVE_VERBS = {
+    "delete", "remove", "drop", "destroy", "purge", "erase", "wipe",
+    "truncate", "reset", "clear", "overwrite", "revoke", "kill", "terminate",
+    "uninstall",
+}  # fmt: skip
+
+DEFAULT_POLICY = {"read": "allow", "write": "allow", "destructive": "ask"}
+
+NOTEBOOK_KEYS = ("notebook", "notebook_name", "notebook_id", "notebookId")
+
+UUID_RE = re.compile(r"^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$", re.I)
+
+
+def classify(tool: str) -> str:
+    """Return "read", "write" or "destructive" for a tool name."""
+    if tool in BUILT_IN_TOOLS:
+        return BUILT_IN_TOOLS[tool]
+    name = tool
+    if tool.startswith(MCP_TOOL_PREFIX):
+        parts = tool[len(MCP_TOOL_PREFIX) :].split(":", 1)
+        if len(parts) == 2:
+            name = parts[1]
+    match = re.match(r"[a-z0-9]*", name.lstrip("_-"))
+    verb = match.group(0) if match else ""
+    if verb in DESTRUCTIVE_VERBS:
+        return "destructive"
+    if verb in READ_VERBS:
+        return "read"
+    return "write"
+
+
+def load_permissions(library_path: str | Path | None) -> dict:
+    """The library's policies; missing or unreadable settings give the defaults."""
+    if not library_path:
+        return {}
+    path = Path(library_path) / "settings.json"
+    try:
+        settings = json.loads(path.read_text())
+    except FileNotFoundError:
+        return {}
+    except (OSError, ValueError) as e:
+        logger.warning("Ignoring unreadable %s: %s", path, e)
+        return {}
+    value = settings.get(PERMISSIONS_SETTING, {}).get("value")
+    return value if isinstance(value, dict) else {}
+
+
+def target_notebook(library_path: str | Path | None, arguments: dict) -> str | None:
+    """The ID of the notebook a tool call targets, by ID or name."""
+    value = next(
+        (arguments[k] for k in NOTEBOOK_KEYS if isinstance(arguments.get(k), str)),
+        None,
+    )
+    if value is None:
+        return None
+    value = value.strip()
+    if UUID_RE.match(value):
+        return value.lower()
+    if not library_path:
+        return None
+    for notebook_file in (Path(library_path) / "notebooks").glob("*/notebook.json"):
+        try:
+            notebook = json.loads(notebook_file.read_text())
+        except (OSError, ValueError):
+            continue
+        if str(notebook.get("name", "")).lower() == value.lower():
+            return notebook.get("id")
+    return None
+
+
+def _decide(policy: dict, tool: str, tool_class: str) -> tuple[str, str]:
+    decision = policy.get("tools", {}).get(tool)
+    if decision:
+        return decision, f"set for {tool}"
+    decision = policy.get(tool_class, DEFAULT_POLICY[tool_class])
+    return decision, f"set for {tool_class} tools"
+
+
+def check(
+    library_path: str | Path | None,
+    tool: str,
+    arguments: dict | None = None,
+    tool_call_id: str | None = None,
+) -> dict:
+    """Decide a tool call. The result has the camelCase fields of the Rust
+    ``ToolPermissionCheck``: tool, class, decision, notebookId, reason and
+    toolCallId."""
+    permissions = load_permissions(library_path)
+    tool_class = classify(tool)
+    notebook_id = target_notebook(library_path, arguments or {})
+    notebook_policy = permissions.get("notebooks", {}).get(notebook_id) if notebook_id else None
+    if notebook_policy is not None:
+        decision, reason = _decide(notebook_policy, tool, tool_class)
+        reason = f"notebook policy, {reason}"
+    else:
+        decision, reason = _decide(permissions.get("default", {}), tool, tool_class)
+        reason = f"library policy, {reason}"
+
+    result = {"tool": tool, "class": tool_class, "decision": decision, "reason": reason}
+    if notebook_id:
+        result["notebookId"] = notebook_id
+    if tool_call_id:
+        result["toolCallId"] = tool_call_id
+    return result
+
+
+def message(result: dict) -> str:
+    """Explanation of a check for the model."""
+    tool, reason = result["tool"], result["reason"]
+    if result["decision"] == "ask":
+        return f"{tool} needs the user's approval ({reason}); it was not run"
+    if result["decision"] == "deny":
+        return f"{tool} is not permitted ({reason}); it was not run"
+    return f"{tool} is allowed"
@@ -7,6 +7,7 @@ System requirement: ffmpeg must be installed for audio extraction.
 """
 
 import asyncio
+import os
 import shutil
 import subprocess
 import tempfile
@@ -25,6 +26,18 @@ try:
 except ImportError:
     pass
 
+# Speaker diarization is optional too: uv pip install pyannote.audio
+PYANNOTE_AVAILABLE = False
+try:
+    from pyannote.audio import Pipeline as DiarizationPipeline
+
+    PYANNOTE_AVAILABLE = True
+except ImportError:
+    pass
+
+# Gated on Hugging Face: accept its terms and set HF_TOKEN to download it
+DIARIZATION_MODEL = "pyannote/speaker-diarization-3.1"
+
 
 # Supported video formats
 SUPPORTED_VIDEO_EXTENSIONS: dict[str, str] = {
@@ -67,6 +80,14 @@ class TranscriptSegment(BaseModel):
     words: list[TranscriptWord] = Field(default_factory=list)
 
 
+class SpeakerTurn(BaseModel):
+    """A stretch of audio attributed to one speaker."""
+
+    start: float  # seconds
+    end: float  # seconds
+    speaker: str  # label such as SPEAKER_00, stable within one recording
+
+
 class TranscriptionResult(BaseModel):
     """Result of video transcription."""
 
@@ -511,6 +532,78 @@ def transcribe_audio_sync(
     )
 
 
+async def diarize_audio(
+    audio_path: str,
+    hf_token: str | None = None,
+    num_speakers: int | None = None,
+) -> list[dict[str, Any]]:
+    """Work out who spoke when in an audio file using pyannote.audio.
+
+    Args:
+        audio_path: Path to the audio file.
+        hf_token: Hugging Face token for the gated model. Falls back to the
+            HF_TOKEN or HUGGINGFACE_TOKEN environment variable.
+        num_speakers: Number of speakers, if known.
+
+    Returns:
+        List of SpeakerTurn dictionaries, in time order.
+    """
+    if not PYANNOTE_AVAILABLE:
+        raise ImportError(
+            "pyannote.audio is not installed. Install with: uv pip install pyannote.audio"
+        )
+
+    path = Path(audio_path)
+    if not path.exists():
+        raise FileNotFoundError(f"Audio file not found: {audio_path}")
+
+    token = hf_token or os.environ.get("HF_TOKEN") or os.environ.get("HUGGINGFACE_TOKEN")
+    pipeline = DiarizationPipeline.from_pretrained(DIARIZATION_MODEL, use_auth_token=token)
+    if pipeline is None:
+        raise RuntimeError(
+            f"Could not load {DIARIZATION_MODEL}. Accept its terms on Hugging Face "
+            "and set HF_TOKEN."
+        )
+
+    if path.suffix.lower() != ".wav":
+        wav_path = await convert_audio_to_wav(audio_path)
+    else:
+        wav_path = audio_path
+
+    try:
+        if num_speakers:
+            diarization = pipeline(wav_path, num_speakers=num_speakers)
+        else:
+            diarization = pipeline(wav_path)
+
+        turns = [
+            SpeakerTurn(start=turn.start, end=turn.end, speaker=str(speaker))
+            for turn, _, speaker in diarization.itertracks(yield_label=True)
+        ]
+        turns.sort(key=lambda t: t.start)
+        return [t.model_dump() for t in turns]
+    except Exception as e:
+        raise RuntimeError(f"Speaker diarization failed: {e}") from e
+    finally:
+        if wav_path != audio_path:
+            Path(wav_path).unlink(missing_ok=True)
+
+
+def diarize_audio_sync(
+    audio_path: str,
+    hf_token: str | None = None,
+    num_speakers: int | None = None,
+) -> list[dict[str, Any]]:
+    """Synchronous wrapper for diarize_audio (for PyO3 bridge)."""
+    return asyncio.run(
+        diarize_audio(
+            audio_path=audio_path,
+            hf_token=hf_token,
+            num_speakers=num_speakers,
+        )
+    )
+
+
 def transcribe_video_sync(
     video_path: str,
     model_size: str = "base",
@@ -0,0 +1,55 @@
+"""Tests for the Python side of the AI tool permissions.
+
+The classification and decisions must match the Rust ``ai_permissions``
+module, which reads the same ``ai.toolPermissions`` library setting.
+"""
+
+from __future__ import annotations
+
+import json
+
+from nous_ai.tool_permissions import check, classify, message
+
+JOURNAL_ID = "11111111-1111-4111-8111-111111111111"
+
+
+def write_library(tmp_path, permissions: dict) -> None:
+    settings = {
+        "ai.toolPermissions": {"value": permissions, "updated_at": "2026-10-01T00:00:00Z"}
+    }
+    (tmp_path / "settings.json").write_text(json.dumps(settings))
+    notebook_dir = tmp_path / "notebooks" / JOURNAL_ID
+    notebook_dir.mkdir(parents=True)
+    (notebook_dir / "notebook.json").write_text(json.dumps({"id": JOURNAL_ID, "name": "Journal"}))
+
+
+def test_classifies_built_in_and_mcp_tools():
+    assert classify("nous_get_page") == "read"
+    assert classify("nous_update_page") == "write"
+    assert classify("mcp:github:list_issues") == "read"
+    assert classify("mcp:files:deleteFile") == "destructive"
+    assert classify("mcp:odd:frobnicate") == "write"
+
+
+def test_defaults_without_settings(tmp_path):
+    assert check(tmp_path, "nous_append_to_page")["decision"] == "allow"
+    result = check(tmp_path, "mcp:files:delete_file", {}, "call-1")
+    assert result["decision"] == "ask"
+    assert result["toolCallId"] == "call-1"
+    assert "approval" in message(result)
+
+
+def test_notebook_policy_and_tool_overrides(tmp_path):
+    write_library(
+        tmp_path,
+        {
+            "default": {"tools": {"mcp:github:create_issue": "deny"}},
+            "notebooks": {JOURNAL_ID: {"write": "deny"}},
+        },
+    )
+    denied = check(tmp_path, "nous_append_to_page", {"notebook": "journal"})
+    assert denied["decision"] == "deny"
+    assert denied["notebookId"] == JOURNAL_ID
+    assert denied["reason"] == "notebook policy, set for write tools"
+    assert check(tmp_path, "nous_append_to_page", {"notebook": "Other"})["decision"] == "allow"
+    assert check(tmp_path, "mcp:github:create_issue")["decision"] == "deny"
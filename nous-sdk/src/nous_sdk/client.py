@@ -16,6 +16,7 @@ from nous_sdk.models import (
     Goal,
     Database,
     SearchResult,
+    TranscriptHit,
 )
 
 DEFAULT_BASE_URL = "http://127.0.0.1:7667"
@@ -409,6 +410,25 @@ class Nous:
         data = self._get("/api/search", **params)
         return [SearchResult.from_dict(d) for d in data]
 
+    def search_transcripts(
+        self,
+        query: str,
+        notebook: str | None = None,
+        limit: int = 20,
+    ) -> list[TranscriptHit]:
+        """Search video transcripts; each hit carries the segment's start time.
+
+        Args:
+            query: Search query.
+            notebook: Optional notebook name or ID to scope the search.
+            limit: Maximum results (default 20).
+        """
+        params: dict[str, Any] = {"q": query, "limit": limit}
+        if notebook:
+            params["notebook_id"] = self._resolve_notebook_id(notebook)
+        data = self._get("/api/search/transcripts", **params)
+        return [TranscriptHit.from_dict(d) for d in data]
+
     # ─── Daily Notes ────────────────────────────────────────────────────
 
     def get_daily_note(self, notebook: str, date: str) -> Page | None:
@@ -197,3 +197,30 @@ class SearchResult:
             snippet=d.get("snippet", ""),
             score=d.get("score", 0.0),
         )
+
+
+@dataclass
+class TranscriptHit:
+    page_id: str
+    notebook_id: str
+    page_title: str
+    block_id: str
+    video_name: str
+    start: float
+    end: float
+    text: str
+    score: float = 0.0
+
+    @classmethod
+    def from_dict(cls, d: dict[str, Any]) -> TranscriptHit:
+        return cls(
+            page_id=d.get("pageId", ""),
+            notebook_id=d.get("notebookId", ""),
+            page_title=d.get("pageTitle", ""),
+            block_id=d.get("blockId", ""),
+            video_name=d.get("videoName", ""),
+            start=d.get("start", 0.0),
+            end=d.get("end", 0.0),
+            text=d.get("text", ""),
+            score=d.get("score", 0.0),
+        )
@@ -73,6 +73,10 @@ case "${OS}" in
         ;;
 esac
 
+# ---- 1b. Copy the interpreter (for NOUS_PYTHON_ISOLATION=subprocess) ----
+mkdir -p "${BUNDLE_DIR}/bin"
+cp -L "${PYTHON}" "${BUNDLE_DIR}/bin/python3"
+
 # ---- 2. Copy stdlib (pruned) ----
 echo "  Copying stdlib..."
 rsync -a --exclude='__pycache__' \
@@ -112,6 +116,8 @@ rm -rf "${TMPVENV}"
 echo "  Copying nous-py source..."
 mkdir -p "${BUNDLE_DIR}/nous-py"
 cp -a "${PROJECT_ROOT}/nous-py/nous_ai" "${BUNDLE_DIR}/nous-py/"
+# Requirements, for the Python environment health check
+cp "${PROJECT_ROOT}/nous-py/pyproject.toml" "${BUNDLE_DIR}/nous-py/"
 
 # ---- Summary ----
 echo ""
@@ -25,6 +25,7 @@ pub fn get_builtin_actions() -> Vec<Action> {
         create_daily_learning_summary_action(),
         create_daily_goal_nudge_action(),
         create_goal_brainstorm_action(),
+        create_spaced_review_action(),
     ]
 }
 
@@ -534,6 +535,7 @@ fn create_weekly_study_review_action() -> Action {
                     "Focus on key concepts learned this week, connections between topics, and areas that need further review."
                         .to_string(),
                 ),
+                ai_profile: None,
             },
             ActionStep::GenerateFlashcards {
                 selector: PageSelector {
@@ -544,6 +546,7 @@ fn create_weekly_study_review_action() -> Action {
                 deck_id: "weekly-review".to_string(),
                 num_cards: Some(20),
                 card_types: vec!["basic".to_string(), "cloze".to_string()],
+                ai_profile: None,
             },
         ],
         enabled: true,
@@ -593,6 +596,7 @@ fn create_exam_prep_workflow_action() -> Action {
                 title_template: "Exam Prep - Study Guide ({{date}})".to_string(),
                 depth: Some("comprehensive".to_string()),
                 focus_areas: Vec::new(),
+                ai_profile: None,
             },
             ActionStep::GenerateFlashcards {
                 selector: PageSelector {
@@ -606,6 +610,7 @@ fn create_exam_prep_workflow_action() -> Action {
                     "cloze".to_string(),
                     "reversible".to_string(),
                 ],
+                ai_profile: None,
             },
             ActionStep::GenerateFaq {
                 selector: PageSelector {
@@ -617,6 +622,7 @@ fn create_exam_prep_workflow_action() -> Action {
                     title_template: "Exam Prep - Practice Questions ({{date}})".to_string(),
                 },
                 num_questions: Some(15),
+                ai_profile: None,
             },
         ],
         enabled: true,
@@ -677,6 +683,7 @@ fn create_daily_learning_summary_action() -> Action {
                 "Summarize the key concepts learned today, highlight connections between topics, and suggest follow-up areas to explore."
                     .to_string(),
             ),
+            ai_profile: None,
         }],
         enabled: true,
         is_built_in: true,
@@ -772,6 +779,7 @@ fn create_goal_brainstorm_action() -> Action {
             title_template: "{{date}} - Goal Brainstorm".to_string(),
             lookback_days: Some(7),
             custom_prompt: None,
+            ai_profile: None,
         }],
         enabled: true,
         is_built_in: true,
@@ -791,6 +799,48 @@ fn create_goal_brainstorm_action() -> Action {
     }
 }
 
+/// Spaced Writing Review action - recall questions about old notes, in the
+/// notebooks that have review turned on
+fn create_spaced_review_action() -> Action {
+    let id = Uuid::parse_str("00000000-0000-0000-0001-00000000000e").unwrap();
+
+    Action {
+        id,
+        name: "Spaced Writing Review".to_string(),
+        description: "Ask recall questions about old notes due for review and file them as flashcards or in today's daily note".to_string(),
+        icon: Some("brain".to_string()),
+        category: ActionCategory::DailyRoutines,
+        triggers: vec![
+            ActionTrigger::Manual,
+            ActionTrigger::AiChat {
+                keywords: vec![
+                    "review old notes".to_string(),
+                    "quiz me on old notes".to_string(),
+                    "spaced review".to_string(),
+                ],
+            },
+            ActionTrigger::Scheduled {
+                schedule: Schedule::Daily {
+                    time: "09:00".to_string(),
+                    skip_weekends: false,
+                },
+            },
+        ],
+        steps: vec![ActionStep::SpacedReview {
+            notebook: None,
+            ai_profile: None,
+        }],
+        enabled: true,
+        is_built_in: true,
+        variables: Vec::new(),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+        last_run: None,
+        next_run: None,
+        default_notebook_id: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -798,7 +848,7 @@ mod tests {
     #[test]
     fn test_builtin_actions_created() {
         let actions = get_builtin_actions();
-        assert_eq!(actions.len(), 13);
+        assert_eq!(actions.len(), 14);
     }
 
     #[test]
@@ -11,10 +11,17 @@ use crate::energy::EnergyStorage;
 use crate::external_sources::{
     read_file_content, ExternalFileFormat, ExternalSourcesError, ExternalSourcesStorage,
 };
+use crate::flashcards::{CardType, FlashcardStorage};
 use crate::goals::GoalsStorage;
+use crate::history::{review::review_candidates, review_feed, HistoryStorage};
 use crate::inbox::{CaptureRequest, CaptureSource, InboxStorage};
 use crate::python_bridge::{AIConfig, PageSummaryInput, PythonAI, StudyPageContent, StudyGuideOptions};
-use crate::storage::{EditorBlock, EditorData, FileStorage, NotebookType, StorageError};
+use crate::secrets::SecretStore;
+use crate::spaced_review::{
+    add_to_review_section, due_pages, review_blocks, PageQuestions, ReviewDestination,
+    SpacedReviewStorage, REVIEW_TAG,
+};
+use crate::storage::{EditorBlock, EditorData, NotebookType, StorageError, StorageLock};
 
 /// Represents a checklist item that was carried forward with source tracking
 #[derive(Debug, Clone)]
@@ -114,13 +121,17 @@ impl Default for ExecutionContext {
 
 /// Action executor
 pub struct ActionExecutor {
-    storage: Arc<Mutex<FileStorage>>,
+    storage: Arc<StorageLock>,
     action_storage: Arc<Mutex<ActionStorage>>,
     python_ai: Arc<Mutex<PythonAI>>,
     external_sources_storage: Option<Arc<Mutex<ExternalSourcesStorage>>>,
     goals_storage: Option<Arc<Mutex<GoalsStorage>>>,
     energy_storage: Option<Arc<Mutex<EnergyStorage>>>,
     inbox_storage: Option<Arc<Mutex<InboxStorage>>>,
+    /// Page views, so spaced review skips pages opened recently
+    history_storage: Option<Arc<Mutex<HistoryStorage>>>,
+    /// Source of API keys for steps run with an AI profile
+    secrets: Option<Arc<SecretStore>>,
     #[cfg(feature = "plugins")]
     plugin_host: Option<Arc<Mutex<crate::plugins::PluginHost>>>,
     variable_resolver: VariableResolver,
@@ -130,7 +141,7 @@ pub struct ActionExecutor {
 
 impl ActionExecutor {
     pub fn new(
-        storage: Arc<Mutex<FileStorage>>,
+        storage: Arc<StorageLock>,
         action_storage: Arc<Mutex<ActionStorage>>,
         python_ai: Arc<Mutex<PythonAI>>,
     ) -> Self {
@@ -142,6 +153,8 @@ impl ActionExecutor {
             goals_storage: None,
             energy_storage: None,
             inbox_storage: None,
+            history_storage: None,
+            secrets: None,
             #[cfg(feature = "plugins")]
             plugin_host: None,
             variable_resolver: VariableResolver::new(),
@@ -172,6 +185,16 @@ impl ActionExecutor {
         self.inbox_storage = Some(storage);
     }
 
+    /// Set the page view history reference
+    pub fn set_history_storage(&mut self, storage: Arc<Mutex<HistoryStorage>>) {
+        self.history_storage = Some(storage);
+    }
+
+    /// Set the secrets store AI profiles take their keys from
+    pub fn set_secrets(&mut self, secrets: Arc<SecretStore>) {
+        self.secrets = Some(secrets);
+    }
+
     /// Set the plugin host reference for plugin-based action steps
     #[cfg(feature = "plugins")]
     pub fn set_plugin_host(&mut self, host: Option<Arc<Mutex<crate::plugins::PluginHost>>>) {
@@ -193,7 +216,7 @@ impl ActionExecutor {
     /// Best-effort page title lookup for event emission.
     fn get_page_title(&self, page_id_str: &str, _result: &ActionExecutionResult) -> String {
         if let Ok(uuid) = Uuid::parse_str(page_id_str) {
-            if let Ok(storage) = self.storage.lock() {
+            if let Ok(storage) = self.storage.read() {
                 let notebooks = storage.list_notebooks().unwrap_or_default();
                 for nb in &notebooks {
                     if let Ok(page) = storage.get_page(nb.id, uuid) {
@@ -318,11 +341,55 @@ impl ActionExecutor {
         Ok(result)
     }
 
+    /// Execute steps that aren't part of a saved action (e.g. the daily note
+    /// rollover). Like `execute_action`, a failed step doesn't stop the rest;
+    /// its error is collected in the returned context.
+    pub fn execute_steps(
+        &self,
+        steps: &[ActionStep],
+        current_notebook_id: Option<Uuid>,
+    ) -> ExecutionContext {
+        let mut context = ExecutionContext::new()
+            .with_variables(self.variable_resolver.build_context(&[]));
+        context.current_notebook_id = current_notebook_id;
+        for (i, step) in steps.iter().enumerate() {
+            if let Err(e) = self.execute_step(step, &mut context) {
+                context.errors.push(format!("Step {} failed: {}", i + 1, e));
+            }
+        }
+        context
+    }
+
     /// Execute a single step
     fn execute_step(
         &self,
         step: &ActionStep,
         context: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        let Some(profile_id) = step.ai_profile() else {
+            return self.run_step(step, context);
+        };
+
+        // Run this one step with the profile's settings
+        let library_path = self.storage.read().unwrap().notebooks_dir().clone();
+        let Some(profile) = crate::ai_profiles::get_profile(&library_path, profile_id) else {
+            log::warn!("AI profile {} not found; running step with default AI settings", profile_id);
+            return self.run_step(step, context);
+        };
+        let mut config = profile.to_ai_config(None);
+        if let Some(secrets) = &self.secrets {
+            secrets.fill_api_key(&mut config.api_key, &crate::secrets::ai_key_id(profile.key_ref()));
+        }
+        let previous = context.ai_config.replace(config);
+        let result = self.run_step(step, context);
+        context.ai_config = previous;
+        result
+    }
+
+    fn run_step(
+        &self,
+        step: &ActionStep,
+        context: &mut ExecutionContext,
     ) -> Result<(), ExecutionError> {
         match step {
             ActionStep::CreatePageFromTemplate {
@@ -420,7 +487,7 @@ impl ActionExecutor {
                 Ok(())
             }
 
-            ActionStep::AiSummarize { selector, output_target, custom_prompt } => {
+            ActionStep::AiSummarize { selector, output_target, custom_prompt, .. } => {
                 self.execute_ai_summarize(selector, output_target, custom_prompt.as_ref(), context)
             }
 
@@ -430,6 +497,7 @@ impl ActionExecutor {
                 title_template,
                 depth,
                 focus_areas,
+                ..
             } => {
                 self.execute_generate_study_guide(
                     selector,
@@ -445,6 +513,7 @@ impl ActionExecutor {
                 selector,
                 output_target,
                 num_questions,
+                ..
             } => {
                 self.execute_generate_faq(selector, output_target, *num_questions, context)
             }
@@ -454,6 +523,7 @@ impl ActionExecutor {
                 deck_id,
                 num_cards,
                 card_types,
+                ..
             } => {
                 self.execute_generate_flashcards(selector, deck_id, *num_cards, card_types, context)
             }
@@ -463,6 +533,7 @@ impl ActionExecutor {
                 notebook_target,
                 title_template,
                 include_action_items,
+                ..
             } => {
                 self.execute_generate_briefing(
                     selector,
@@ -477,6 +548,7 @@ impl ActionExecutor {
                 selector,
                 notebook_target,
                 title_template,
+                ..
             } => {
                 self.execute_extract_timeline(selector, notebook_target, title_template, context)
             }
@@ -486,6 +558,7 @@ impl ActionExecutor {
                 notebook_target,
                 title_template,
                 max_nodes,
+                ..
             } => {
                 self.execute_extract_concept_map(
                     selector,
@@ -512,6 +585,7 @@ impl ActionExecutor {
                 title_template,
                 lookback_days,
                 custom_prompt,
+                ..
             } => {
                 self.execute_goal_brainstorm(
                     notebook_target,
@@ -532,6 +606,7 @@ impl ActionExecutor {
                 include_source_link,
                 incremental,
                 tags,
+                ..
             } => {
                 self.execute_process_external_source(
                     source_id.as_ref(),
@@ -547,6 +622,10 @@ impl ActionExecutor {
                 )
             }
 
+            ActionStep::SpacedReview { notebook, .. } => {
+                self.execute_spaced_review(notebook.as_ref(), context)
+            }
+
             ActionStep::Plugin {
                 plugin_id,
                 function,
@@ -602,7 +681,7 @@ impl ActionExecutor {
         target: &NotebookTarget,
         context: &ExecutionContext,
     ) -> Result<Uuid, ExecutionError> {
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -663,7 +742,7 @@ impl ActionExecutor {
     ) -> Result<(), ExecutionError> {
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -698,7 +777,7 @@ impl ActionExecutor {
         _notebook_type: Option<&String>,
         context: &mut ExecutionContext,
     ) -> Result<(), ExecutionError> {
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -722,7 +801,7 @@ impl ActionExecutor {
     ) -> Result<(), ExecutionError> {
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -745,7 +824,7 @@ impl ActionExecutor {
         let pages = self.find_pages(selector, context)?;
         let dest_notebook_id = self.resolve_notebook_target(&destination.notebook, context)?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -777,7 +856,7 @@ impl ActionExecutor {
         let pages = self.find_pages(selector, context)?;
 
         for page in pages {
-            let storage = self.storage.lock().map_err(|e| {
+            let storage = self.storage.write().map_err(|e| {
                 ExecutionError::StepFailed(format!(
                     "Failed to lock storage: {}",
                     e
@@ -801,7 +880,7 @@ impl ActionExecutor {
     ) -> Result<(), ExecutionError> {
         let pages = self.find_pages(selector, context)?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -923,7 +1002,7 @@ impl ActionExecutor {
 
         // Reuse the destination page we already found (or None if find_existing wasn't set)
         let existing_page = if let Some(page_id) = dest_page_id {
-            let storage = self.storage.lock().map_err(|e| {
+            let storage = self.storage.read().map_err(|e| {
                 ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
             })?;
             storage.get_page(notebook_id, page_id).ok()
@@ -998,7 +1077,7 @@ impl ActionExecutor {
                 existing.content.blocks = new_blocks;
                 existing.content.time = Some(chrono::Utc::now().timestamp_millis());
 
-                let storage = self.storage.lock().map_err(|e| {
+                let storage = self.storage.write().map_err(|e| {
                     ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
                 })?;
                 storage.update_page(&existing)?;
@@ -1012,7 +1091,7 @@ impl ActionExecutor {
             }
         } else {
             // Create new destination page
-            let storage = self.storage.lock().map_err(|e| {
+            let storage = self.storage.write().map_err(|e| {
                 ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
             })?;
 
@@ -1143,7 +1222,7 @@ impl ActionExecutor {
                     continue;
                 }
 
-                let storage = self.storage.lock().map_err(|e| {
+                let storage = self.storage.write().map_err(|e| {
                     ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
                 })?;
 
@@ -1290,7 +1369,7 @@ impl ActionExecutor {
         match output_target {
             SummaryOutput::NewPage { notebook_target, title_template } => {
                 let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-                let storage = self.storage.lock().map_err(|e| {
+                let storage = self.storage.write().map_err(|e| {
                     ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
                 })?;
 
@@ -1381,7 +1460,7 @@ impl ActionExecutor {
                 // Find target page and prepend summary
                 let target_pages = self.find_pages(page_selector, context)?;
                 if let Some(target_page) = target_pages.first() {
-                    let storage = self.storage.lock().map_err(|e| {
+                    let storage = self.storage.write().map_err(|e| {
                         ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
                     })?;
 
@@ -1515,7 +1594,7 @@ impl ActionExecutor {
 
         // Create page with study guide content
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
         })?;
 
@@ -1688,7 +1767,7 @@ impl ActionExecutor {
         match output_target {
             SummaryOutput::NewPage { notebook_target, title_template } => {
                 let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-                let storage = self.storage.lock().map_err(|e| {
+                let storage = self.storage.write().map_err(|e| {
                     ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
                 })?;
 
@@ -1709,7 +1788,7 @@ impl ActionExecutor {
             SummaryOutput::PrependToPage { page_selector } => {
                 let target_pages = self.find_pages(page_selector, context)?;
                 if let Some(target_page) = target_pages.first() {
-                    let storage = self.storage.lock().map_err(|e| {
+                    let storage = self.storage.write().map_err(|e| {
                         ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
                     })?;
 
@@ -1789,6 +1868,212 @@ impl ActionExecutor {
         Ok(())
     }
 
+    /// Execute spaced review step
+    fn execute_spaced_review(
+        &self,
+        notebook: Option<&NotebookTarget>,
+        context: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        let library_dir = {
+            let storage = self.storage.read().map_err(|e| {
+                ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
+            })?;
+            storage.notebooks_dir().clone()
+        };
+        let mut review_storage = SpacedReviewStorage::new(library_dir.clone())?;
+
+        // A notebook named in the step is reviewed whether or not it was
+        // turned on in its settings
+        let notebooks = match notebook {
+            Some(target) => {
+                let id = self.resolve_notebook_target(target, context)?;
+                vec![(id, review_storage.settings(id))]
+            }
+            None => review_storage.enabled_notebooks(),
+        };
+        if notebooks.is_empty() {
+            log::info!("SpacedReview: No notebooks have review turned on");
+            return Ok(());
+        }
+
+        let ai_config = context.ai_config.clone().unwrap_or_default();
+        let now = Utc::now();
+        let mut question_count = 0;
+
+        for (notebook_id, settings) in notebooks {
+            let pages = {
+                let storage = self.storage.read().map_err(|e| {
+                    ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
+                })?;
+                let Ok(nb) = storage.get_notebook(notebook_id) else {
+                    log::warn!("SpacedReview: Notebook {} not found", notebook_id);
+                    continue;
+                };
+                if nb.archived || nb.is_encrypted() {
+                    continue;
+                }
+                // Daily notes are left out, or the questions filed in them
+                // would come back as pages to review
+                storage
+                    .list_pages(notebook_id)?
+                    .into_iter()
+                    .filter(|p| p.deleted_at.is_none() && !p.is_archived && !p.is_daily_note)
+                    .collect::<Vec<_>>()
+            };
+
+            let candidates = match &self.history_storage {
+                Some(history) => {
+                    let history = history.lock().map_err(|e| {
+                        ExecutionError::StepFailed(format!("Failed to lock view history: {}", e))
+                    })?;
+                    review_candidates(pages.clone(), |page_id| {
+                        history.get_view(page_id).map(|v| v.last_viewed_at)
+                    })
+                }
+                None => review_candidates(pages.clone(), |_| None),
+            };
+            // Take the whole feed, so pages reviewed recently can be skipped
+            // without shrinking the selection
+            let feed = review_feed(candidates, Local::now().date_naive(), now, usize::MAX);
+            let due = due_pages(
+                feed,
+                review_storage.reviewed(),
+                now,
+                settings.pages_per_run as usize,
+            );
+            if due.is_empty() {
+                log::info!("SpacedReview: No pages due in notebook {}", notebook_id);
+                continue;
+            }
+
+            let mut reviewed = Vec::new();
+            for item in &due {
+                let Some(page) = pages.iter().find(|p| p.id == item.page_id) else {
+                    continue;
+                };
+                let study_content = self.pages_to_study_content(std::slice::from_ref(page));
+                let python_ai = self.python_ai.lock().map_err(|e| {
+                    ExecutionError::StepFailed(format!("Failed to lock Python AI: {}", e))
+                })?;
+                let generated = python_ai
+                    .generate_flashcards(
+                        study_content,
+                        ai_config.clone(),
+                        Some(settings.questions_per_page as i32),
+                        Some(vec!["basic".to_string()]),
+                    )
+                    .map_err(|e| {
+                        ExecutionError::StepFailed(format!("Question generation failed: {}", e))
+                    })?;
+                drop(python_ai);
+
+                reviewed.push(PageQuestions {
+                    page_id: page.id,
+                    title: page.title.clone(),
+                    questions: generated
+                        .cards
+                        .into_iter()
+                        .take(settings.questions_per_page as usize)
+                        .map(|card| (card.front, card.back))
+                        .collect(),
+                });
+            }
+
+            question_count += reviewed.iter().map(|p| p.questions.len()).sum::<usize>();
+            self.file_review_questions(
+                notebook_id,
+                &settings.destination,
+                &library_dir,
+                &reviewed,
+                context,
+            )?;
+            let page_ids: Vec<Uuid> = reviewed.iter().map(|p| p.page_id).collect();
+            review_storage.mark_reviewed(&page_ids, now)?;
+
+            log::info!(
+                "SpacedReview: Filed questions for {} pages in notebook {}",
+                page_ids.len(),
+                notebook_id
+            );
+        }
+
+        context.variables.insert(
+            "_review_question_count".to_string(),
+            question_count.to_string(),
+        );
+        Ok(())
+    }
+
+    /// File spaced review questions where the notebook's settings say
+    fn file_review_questions(
+        &self,
+        notebook_id: Uuid,
+        destination: &ReviewDestination,
+        library_dir: &std::path::Path,
+        pages: &[PageQuestions],
+        context: &mut ExecutionContext,
+    ) -> Result<(), ExecutionError> {
+        if pages.iter().all(|p| p.questions.is_empty()) {
+            return Ok(());
+        }
+
+        match destination {
+            ReviewDestination::Flashcards { deck_name } => {
+                let flashcard_err = |e: crate::flashcards::FlashcardStorageError| {
+                    ExecutionError::StepFailed(format!("Failed to save flashcards: {}", e))
+                };
+                let flashcards = FlashcardStorage::new(library_dir.join("notebooks"));
+                let existing = flashcards
+                    .list_decks(notebook_id)
+                    .map_err(flashcard_err)?
+                    .into_iter()
+                    .find(|d| d.name.eq_ignore_ascii_case(deck_name));
+                let deck = match existing {
+                    Some(deck) => deck,
+                    None => flashcards
+                        .create_deck(
+                            notebook_id,
+                            deck_name.clone(),
+                            Some("Recall questions about old notes".to_string()),
+                            None,
+                        )
+                        .map_err(flashcard_err)?,
+                };
+                for page in pages {
+                    for (question, answer) in &page.questions {
+                        flashcards
+                            .create_card(
+                                notebook_id,
+                                deck.id,
+                                question.clone(),
+                                answer.clone(),
+                                Some(CardType::Basic),
+                                Some(vec![REVIEW_TAG.to_string()]),
+                            )
+                            .map_err(flashcard_err)?;
+                    }
+                }
+            }
+            ReviewDestination::DailyNote => {
+                let today = Local::now().format("%Y-%m-%d").to_string();
+                let storage = self.storage.write().map_err(|e| {
+                    ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
+                })?;
+                let mut note =
+                    crate::commands::create_daily_note_core(&storage, notebook_id, &today, None)
+                        .map_err(|e| ExecutionError::StepFailed(e.message))?;
+                note.content.blocks = add_to_review_section(
+                    std::mem::take(&mut note.content.blocks),
+                    review_blocks(pages),
+                );
+                note.content.time = Some(Utc::now().timestamp_millis());
+                storage.update_page(&note)?;
+                context.modified_pages.push(note.id.to_string());
+            }
+        }
+        Ok(())
+    }
+
     /// Execute generate briefing step
     fn execute_generate_briefing(
         &self,
@@ -1818,7 +2103,7 @@ impl ActionExecutor {
         drop(python_ai);
 
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
         })?;
 
@@ -1960,7 +2245,7 @@ impl ActionExecutor {
         }
 
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
         })?;
 
@@ -2055,7 +2340,7 @@ impl ActionExecutor {
         }
 
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
         })?;
 
@@ -2401,7 +2686,7 @@ impl ActionExecutor {
 
         // 6. Create output page
         let notebook_id = self.resolve_notebook_target(notebook_target, context)?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
         })?;
 
@@ -2635,7 +2920,7 @@ impl ActionExecutor {
             }
 
             // Create the page
-            let storage = self.storage.lock().map_err(|e| {
+            let storage = self.storage.write().map_err(|e| {
                 ExecutionError::StepFailed(format!("Failed to lock storage: {}", e))
             })?;
 
@@ -2750,7 +3035,7 @@ impl ActionExecutor {
             ExecutionError::InvalidConfig("No notebook specified for page selector".to_string())
         })?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             ExecutionError::StepFailed(format!(
                 "Failed to lock storage: {}",
                 e
@@ -22,6 +22,7 @@
 //! - CarryForwardItems: Copy incomplete checklist items
 //! - Delay: Wait between steps
 //! - Conditional: Execute steps based on conditions
+//! - SpacedReview: Recall questions about old notes due for review
 //!
 //! # Variable Substitution
 //!
@@ -214,6 +214,9 @@ pub enum ActionStep {
         output_target: SummaryOutput,
         /// Custom prompt for summarization
         custom_prompt: Option<String>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Generate a study guide from pages
     #[serde(rename_all = "camelCase")]
@@ -229,6 +232,9 @@ pub enum ActionStep {
         /// Focus areas for the study guide
         #[serde(default)]
         focus_areas: Vec<String>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Generate FAQ from pages
     #[serde(rename_all = "camelCase")]
@@ -238,6 +244,9 @@ pub enum ActionStep {
         output_target: SummaryOutput,
         /// Number of Q&A pairs to generate
         num_questions: Option<i32>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Generate flashcards from pages and add to a deck
     #[serde(rename_all = "camelCase")]
@@ -250,6 +259,9 @@ pub enum ActionStep {
         /// Card types to generate: "basic", "cloze", "reversible"
         #[serde(default)]
         card_types: Vec<String>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Generate briefing document from pages
     #[serde(rename_all = "camelCase")]
@@ -262,6 +274,9 @@ pub enum ActionStep {
         /// Include action items section
         #[serde(default = "default_true")]
         include_action_items: bool,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Extract timeline events from pages
     #[serde(rename_all = "camelCase")]
@@ -271,6 +286,9 @@ pub enum ActionStep {
         notebook_target: NotebookTarget,
         /// Title template
         title_template: String,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Extract concept map from pages
     #[serde(rename_all = "camelCase")]
@@ -282,6 +300,9 @@ pub enum ActionStep {
         title_template: String,
         /// Maximum number of concept nodes
         max_nodes: Option<i32>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Carry forward incomplete checklist items
     #[serde(rename_all = "camelCase")]
@@ -329,6 +350,9 @@ pub enum ActionStep {
         lookback_days: Option<u32>,
         /// Extra guidance for the AI prompt
         custom_prompt: Option<String>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
     /// Execute a function in a plugin
     #[serde(rename_all = "camelCase")]
@@ -365,7 +389,42 @@ pub enum ActionStep {
         /// Tags to apply to created pages
         #[serde(default)]
         tags: Vec<String>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
     },
+    /// Ask recall questions about old pages due for review and file them as
+    /// flashcards or in today's daily note (see `crate::spaced_review`)
+    #[serde(rename_all = "camelCase")]
+    SpacedReview {
+        /// Notebook to review (None = every notebook with review turned on)
+        #[serde(default)]
+        notebook: Option<NotebookTarget>,
+        /// Library AI profile to run this step with (see `ai_profiles`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ai_profile: Option<String>,
+    },
+}
+
+impl ActionStep {
+    /// The AI profile this step asks for, if it is an AI step with one set
+    pub fn ai_profile(&self) -> Option<&str> {
+        match self {
+            ActionStep::AiSummarize { ai_profile, .. }
+            | ActionStep::GenerateStudyGuide { ai_profile, .. }
+            | ActionStep::GenerateFaq { ai_profile, .. }
+            | ActionStep::GenerateFlashcards { ai_profile, .. }
+            | ActionStep::GenerateBriefing { ai_profile, .. }
+            | ActionStep::ExtractTimeline { ai_profile, .. }
+            | ActionStep::ExtractConceptMap { ai_profile, .. }
+            | ActionStep::GoalBrainstorm { ai_profile, .. }
+            | ActionStep::ProcessExternalSource { ai_profile, .. }
+            | ActionStep::SpacedReview { ai_profile, .. } => {
+                ai_profile.as_deref().filter(|id| !id.is_empty())
+            }
+            _ => None,
+        }
+    }
 }
 
 // ===== Variable Types =====
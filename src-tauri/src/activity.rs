@@ -0,0 +1,344 @@
+//! Activity feed: what happened to which page, and on which device.
+//!
+//! Local page creations, edits, deletions and imports are recorded by
+//! [`FileStorage`](crate::storage::FileStorage) as they are saved. Changes
+//! made on other devices are recorded by sync from the notebook's changelog,
+//! named after the device registry. Both land in `{library}/activity.jsonl`,
+//! which [`ActivityLog::feed`] reads back newest first.
+//!
+//! Writes that sync applies aren't recorded as local activity; sync records
+//! them under the device that made them instead.
+//!
+//! The log also remembers when the feed was last looked at, so the app can
+//! show "what changed since I was last here" after a sync.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::oplog;
+use crate::sync::devices::DeviceRegistry;
+use crate::sync::{ChangeOperation, Changelog, PageMeta};
+
+/// Edits to a page on this device closer together than this are one entry
+const EDIT_COALESCE_MINUTES: i64 = 30;
+
+/// Once the log grows past this, it is trimmed to [`KEEP_ENTRIES`]
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+const KEEP_ENTRIES: usize = 5000;
+
+/// Default number of entries a feed returns
+pub const DEFAULT_FEED_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    Created,
+    Edited,
+    Deleted,
+    Imported,
+}
+
+/// Where an entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivitySource {
+    /// Done on this device
+    Local,
+    /// Done on another device and pulled in by sync
+    Sync,
+}
+
+/// One thing that happened to a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    pub timestamp: DateTime<Utc>,
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub page_title: String,
+    pub kind: ActivityKind,
+    pub source: ActivitySource,
+    /// Name of the device the change was made on
+    pub device: String,
+    /// Sync client id of the device, for changes pulled by sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+impl ActivityEntry {
+    /// An entry for something done on this device just now
+    pub fn local(notebook_id: Uuid, page_id: Uuid, page_title: &str, kind: ActivityKind) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            notebook_id,
+            page_id,
+            page_title: page_title.to_string(),
+            kind,
+            source: ActivitySource::Local,
+            device: oplog::get_client_id(),
+            client_id: None,
+        }
+    }
+}
+
+/// Which entries a feed includes. Empty fields don't filter.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ActivityFilter {
+    pub notebook_id: Option<Uuid>,
+    pub kinds: Vec<ActivityKind>,
+    pub source: Option<ActivitySource>,
+    /// Only changes made on this device (by name)
+    pub device: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl ActivityFilter {
+    fn matches(&self, entry: &ActivityEntry) -> bool {
+        self.notebook_id.map_or(true, |id| entry.notebook_id == id)
+            && (self.kinds.is_empty() || self.kinds.contains(&entry.kind))
+            && self.source.map_or(true, |source| entry.source == source)
+            && self.device.as_ref().map_or(true, |device| &entry.device == device)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeenFile {
+    last_seen: Option<DateTime<Utc>>,
+}
+
+/// The activity log of a library
+pub struct ActivityLog {
+    path: PathBuf,
+    seen_path: PathBuf,
+    /// When each page's last local edit entry was written, for coalescing
+    last_edits: Mutex<HashMap<Uuid, DateTime<Utc>>>,
+}
+
+impl ActivityLog {
+    pub fn new(library_path: PathBuf) -> Self {
+        Self {
+            path: library_path.join("activity.jsonl"),
+            seen_path: library_path.join("activity_seen.json"),
+            last_edits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record entries (best-effort: a failure is logged, never returned, so
+    /// it can't fail the save that caused it)
+    pub fn record(&self, entries: &[ActivityEntry]) {
+        let mut last_edits = self.last_edits.lock().unwrap();
+        let mut lines = String::new();
+        for entry in entries {
+            if entry.source == ActivitySource::Local {
+                if entry.kind == ActivityKind::Edited {
+                    let recent = last_edits.get(&entry.page_id).is_some_and(|at| {
+                        entry.timestamp - *at < Duration::minutes(EDIT_COALESCE_MINUTES)
+                    });
+                    if recent {
+                        continue;
+                    }
+                }
+                last_edits.insert(entry.page_id, entry.timestamp);
+            }
+            match serde_json::to_string(entry) {
+                Ok(line) => {
+                    lines.push_str(&line);
+                    lines.push('\n');
+                }
+                Err(e) => log::warn!("Failed to encode activity entry: {}", e),
+            }
+        }
+        drop(last_edits);
+        if lines.is_empty() {
+            return;
+        }
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()));
+        if let Err(e) = result {
+            log::warn!("Failed to record activity: {}", e);
+            return;
+        }
+
+        if fs::metadata(&self.path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+            if let Err(e) = self.trim() {
+                log::warn!("Failed to trim activity log: {}", e);
+            }
+        }
+    }
+
+    /// Record one entry
+    pub fn record_one(&self, entry: ActivityEntry) {
+        self.record(std::slice::from_ref(&entry));
+    }
+
+    fn read_all(&self) -> Vec<ActivityEntry> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        // A torn last line (crash mid-append) is skipped
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    fn trim(&self) -> std::io::Result<()> {
+        let entries = self.read_all();
+        let skip = entries.len().saturating_sub(KEEP_ENTRIES);
+        let mut content = String::new();
+        for entry in &entries[skip..] {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        crate::storage::atomic::write_str(&self.path, &content)
+    }
+
+    /// Entries after `since` (all when `None`) that pass the filter, newest
+    /// first
+    pub fn feed(&self, since: Option<DateTime<Utc>>, filter: &ActivityFilter) -> Vec<ActivityEntry> {
+        let mut entries: Vec<ActivityEntry> = self
+            .read_all()
+            .into_iter()
+            .filter(|e| since.map_or(true, |since| e.timestamp > since))
+            .filter(|e| filter.matches(e))
+            .collect();
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        entries.truncate(filter.limit.unwrap_or(DEFAULT_FEED_LIMIT));
+        entries
+    }
+
+    /// When the feed was last marked seen
+    pub fn last_seen(&self) -> Option<DateTime<Utc>> {
+        fs::read_to_string(&self.seen_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<SeenFile>(&s).ok())
+            .and_then(|f| f.last_seen)
+    }
+
+    /// Mark the feed seen up to `at`. Returns when it was last seen before.
+    pub fn mark_seen(&self, at: DateTime<Utc>) -> std::io::Result<Option<DateTime<Utc>>> {
+        let previous = self.last_seen();
+        let content = serde_json::to_string(&SeenFile { last_seen: Some(at) })?;
+        crate::storage::atomic::write_str(&self.seen_path, &content)?;
+        Ok(previous)
+    }
+}
+
+/// Entries for what other devices changed in a notebook, from its sync
+/// changelog after `since_seq`. Several changes to a page by one device are
+/// one entry. `known_pages` are the pages this device had before the sync;
+/// the others were created elsewhere.
+pub fn from_changelog(
+    notebook_id: Uuid,
+    changelog: &Changelog,
+    since_seq: u64,
+    own_client_id: &str,
+    devices: &DeviceRegistry,
+    pages_meta: &HashMap<Uuid, PageMeta>,
+    known_pages: &HashSet<Uuid>,
+) -> Vec<ActivityEntry> {
+    let mut latest: HashMap<(Uuid, &str), ActivityEntry> = HashMap::new();
+    for change in changelog.entries_since(since_seq, own_client_id) {
+        let meta = pages_meta.get(&change.page_id);
+        let kind = match change.operation {
+            ChangeOperation::Deleted => ActivityKind::Deleted,
+            ChangeOperation::Updated if meta.is_some_and(|m| m.deleted_at.is_some()) => {
+                ActivityKind::Deleted
+            }
+            ChangeOperation::Updated if !known_pages.contains(&change.page_id) => {
+                ActivityKind::Created
+            }
+            ChangeOperation::Updated => ActivityKind::Edited,
+        };
+        let device = devices
+            .devices
+            .get(&change.client_id)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| change.client_id.clone());
+        let entry = ActivityEntry {
+            timestamp: change.timestamp,
+            notebook_id,
+            page_id: change.page_id,
+            page_title: meta.map_or_else(|| "Untitled".to_string(), |m| m.title.clone()),
+            kind,
+            source: ActivitySource::Sync,
+            device,
+            client_id: Some(change.client_id.clone()),
+        };
+        latest.insert((change.page_id, change.client_id.as_str()), entry);
+    }
+
+    let mut entries: Vec<ActivityEntry> = latest.into_values().collect();
+    entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(notebook_id: Uuid, page_id: Uuid, kind: ActivityKind, at: DateTime<Utc>) -> ActivityEntry {
+        ActivityEntry {
+            timestamp: at,
+            notebook_id,
+            page_id,
+            page_title: "Remote".to_string(),
+            kind,
+            source: ActivitySource::Sync,
+            device: "Phone".to_string(),
+            client_id: Some("phone-1".to_string()),
+        }
+    }
+
+    #[test]
+    fn feed_filters_and_coalesces_local_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(dir.path().to_path_buf());
+        let notebook_id = Uuid::new_v4();
+        let other_notebook = Uuid::new_v4();
+        let page_id = Uuid::new_v4();
+
+        log.record_one(ActivityEntry::local(notebook_id, page_id, "Plans", ActivityKind::Created));
+        log.record_one(ActivityEntry::local(notebook_id, page_id, "Plans", ActivityKind::Edited));
+        log.record_one(ActivityEntry::local(notebook_id, page_id, "Plans", ActivityKind::Edited));
+        let start = Utc::now();
+        log.record(&[
+            remote(notebook_id, page_id, ActivityKind::Edited, start + Duration::seconds(1)),
+            remote(other_notebook, Uuid::new_v4(), ActivityKind::Deleted, start + Duration::seconds(2)),
+        ]);
+
+        let all = log.feed(None, &ActivityFilter::default());
+        // The edits right after creating the page fold into it
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].kind, ActivityKind::Deleted);
+
+        let since = log.feed(Some(start), &ActivityFilter::default());
+        assert_eq!(since.len(), 2);
+
+        let filter = ActivityFilter {
+            notebook_id: Some(notebook_id),
+            source: Some(ActivitySource::Sync),
+            ..Default::default()
+        };
+        let remote_edits = log.feed(None, &filter);
+        assert_eq!(remote_edits.len(), 1);
+        assert_eq!(remote_edits[0].device, "Phone");
+
+        assert_eq!(log.last_seen(), None);
+        assert_eq!(log.mark_seen(start).unwrap(), None);
+        assert_eq!(log.mark_seen(Utc::now()).unwrap(), Some(start));
+    }
+}
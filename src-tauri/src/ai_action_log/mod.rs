@@ -0,0 +1,437 @@
+//! Audit log and undo for changes the AI applies to the library.
+//!
+//! Tool calls reach the library two ways. The `nous_*` tools run inside the
+//! Python chat loop and write pages directly (oplog client `nous-agent`);
+//! [`AiActionRecorder`] copies the library's pages before the call and, after
+//! it, logs which pages the agent created or changed, attributing them to the
+//! tool calls by their `page`/`title` arguments. `create_page` and
+//! `create_notebook` are carried out by the frontend, which reports what it
+//! created with [`record_created`].
+//!
+//! Every entry keeps the page as it was before, so [`undo_entry`] can put it
+//! back — unless the page was edited again since, which undo refuses rather
+//! than losing that edit. Created pages go to the trash, and created
+//! notebooks are removed once empty.
+
+mod models;
+mod storage;
+
+pub use models::*;
+pub use storage::*;
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::python_bridge::AIAction;
+use crate::storage::oplog::{content_hash, diff_blocks};
+use crate::storage::{EditorData, FileStorage, FileStorageWriter, Page, StorageError};
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Oplog client ID of the Python agent's page writes
+pub const AGENT_CLIENT_ID: &str = "nous-agent";
+
+/// `nous_*` tools that write to the library
+const AGENT_WRITE_TOOLS: &[&str] = &[
+    "nous_append_to_page",
+    "nous_update_page",
+    "nous_create_folder",
+    "nous_move_page",
+    "nous_manage_tags",
+    "nous_create_database",
+    "nous_add_database_rows",
+    "nous_update_database_rows",
+];
+
+pub fn is_agent_write_tool(tool: &str) -> bool {
+    AGENT_WRITE_TOOLS.contains(&tool)
+}
+
+/// The change from `before` (None for a new page) to `after`
+pub fn page_change(before: Option<Page>, after: &Page) -> AiPageChange {
+    let empty = EditorData {
+        time: None,
+        version: None,
+        blocks: Vec::new(),
+    };
+    let diff = diff_blocks(
+        before.as_ref().map_or(&empty, |page| &page.content),
+        &after.content,
+    );
+    AiPageChange {
+        notebook_id: after.notebook_id,
+        page_id: after.id,
+        title: after.title.clone(),
+        kind: if before.is_some() {
+            AiPageChangeKind::Updated
+        } else {
+            AiPageChangeKind::Created
+        },
+        diff,
+        after_hash: content_hash(&after.content),
+        before,
+    }
+}
+
+/// Watches the library across an AI call whose tools write pages directly
+pub struct AiActionRecorder {
+    notebooks: Vec<Uuid>,
+    before: HashMap<Uuid, Page>,
+}
+
+impl AiActionRecorder {
+    /// Copy the pages of every unencrypted notebook (the agent can't open
+    /// encrypted ones)
+    pub fn begin(storage: &FileStorage) -> Result<Self> {
+        let notebooks: Vec<Uuid> = storage
+            .list_notebooks()?
+            .into_iter()
+            .filter(|notebook| !notebook.is_encrypted())
+            .map(|notebook| notebook.id)
+            .collect();
+        let before = notebooks
+            .iter()
+            .flat_map(|id| storage.list_pages(*id).unwrap_or_default())
+            .map(|page| (page.id, page))
+            .collect();
+        Ok(Self { notebooks, before })
+    }
+
+    /// Pages the agent created or changed since [`begin`](Self::begin).
+    /// Pages last saved by anything else (the editor, sync) are left out.
+    pub fn changes(&self, storage: &FileStorage) -> Vec<AiPageChange> {
+        let mut changes = Vec::new();
+        for notebook_id in &self.notebooks {
+            for page in storage.list_pages(*notebook_id).unwrap_or_default() {
+                let before = self.before.get(&page.id);
+                let unchanged = before.is_some_and(|before| {
+                    before.updated_at == page.updated_at
+                        && before.title == page.title
+                        && content_hash(&before.content) == content_hash(&page.content)
+                });
+                let by_agent = || {
+                    storage
+                        .last_page_op(page.notebook_id, page.id)
+                        .is_some_and(|op| op.client_id == AGENT_CLIENT_ID)
+                };
+                if !unchanged && by_agent() {
+                    changes.push(page_change(before.cloned(), &page));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Log the write actions of an AI reply with the page changes each made
+    pub fn finish(
+        self,
+        storage: &FileStorage,
+        log: &AiActionLogStorage,
+        session_id: Option<Uuid>,
+        actions: &[AIAction],
+    ) -> Result<Vec<AiActionEntry>> {
+        let entries = attribute(session_id, actions, self.changes(storage));
+        for entry in &entries {
+            log.save_entry(entry)?;
+        }
+        Ok(entries)
+    }
+}
+
+/// Whether a tool call's arguments name the changed page
+fn names_page(arguments: &serde_json::Value, change: &AiPageChange) -> bool {
+    ["page", "title", "name"].iter().any(|key| {
+        arguments
+            .get(key)
+            .and_then(|v| v.as_str())
+            .is_some_and(|value| {
+                value == change.page_id.to_string()
+                    || value.eq_ignore_ascii_case(&change.title)
+                    || change
+                        .before
+                        .as_ref()
+                        .is_some_and(|before| value.eq_ignore_ascii_case(&before.title))
+            })
+    })
+}
+
+/// One entry per write action. Each change goes to the first action naming
+/// its page, or else to the first write action.
+fn attribute(
+    session_id: Option<Uuid>,
+    actions: &[AIAction],
+    changes: Vec<AiPageChange>,
+) -> Vec<AiActionEntry> {
+    let mut entries: Vec<AiActionEntry> = actions
+        .iter()
+        .filter(|action| is_agent_write_tool(&action.tool))
+        .map(|action| {
+            AiActionEntry::new(
+                session_id,
+                action.tool.clone(),
+                action.arguments.clone(),
+                action.tool_call_id.clone(),
+            )
+        })
+        .collect();
+    if entries.is_empty() {
+        if !changes.is_empty() {
+            log::warn!(
+                "{} page change(s) by the agent with no write tool call",
+                changes.len()
+            );
+        }
+        return entries;
+    }
+    for change in changes {
+        let index = entries
+            .iter()
+            .position(|entry| names_page(&entry.arguments, &change))
+            .unwrap_or(0);
+        entries[index].changes.push(change);
+    }
+    entries
+}
+
+/// Log pages and notebooks the frontend created for a tool call
+#[allow(clippy::too_many_arguments)]
+pub fn record_created(
+    storage: &FileStorage,
+    log: &AiActionLogStorage,
+    session_id: Option<Uuid>,
+    tool: String,
+    arguments: serde_json::Value,
+    tool_call_id: String,
+    pages: &[(Uuid, Uuid)],
+    notebooks: Vec<Uuid>,
+) -> Result<AiActionEntry> {
+    let mut entry = AiActionEntry::new(session_id, tool, arguments, tool_call_id);
+    for (notebook_id, page_id) in pages {
+        entry.changes.push(page_change(
+            None,
+            &storage.get_page(*notebook_id, *page_id)?,
+        ));
+    }
+    entry.created_notebooks = notebooks;
+    log.save_entry(&entry)?;
+    Ok(entry)
+}
+
+/// Revert an entry's changes, newest first. Fails without changing anything
+/// if the entry was undone already or one of its pages was edited since.
+pub fn undo_entry(
+    storage: &FileStorageWriter,
+    log: &AiActionLogStorage,
+    id: Uuid,
+) -> Result<AiActionEntry> {
+    let mut entry = log.get_entry(id)?;
+    if entry.undone_at.is_some() {
+        return Err(StorageError::InvalidOperation(format!(
+            "{} was already undone",
+            entry.tool
+        )));
+    }
+
+    for change in &entry.changes {
+        let Ok(current) = storage.get_page(change.notebook_id, change.page_id) else {
+            continue;
+        };
+        if content_hash(&current.content) != change.after_hash {
+            return Err(StorageError::InvalidOperation(format!(
+                "\"{}\" was edited after the AI changed it",
+                current.title
+            )));
+        }
+    }
+
+    for change in entry.changes.iter().rev() {
+        match (&change.kind, &change.before) {
+            (AiPageChangeKind::Updated, Some(before)) => {
+                let mut restored = before.clone();
+                restored.updated_at = Utc::now();
+                storage.update_page(&restored)?;
+            }
+            (AiPageChangeKind::Created, _) => {
+                if storage.get_page(change.notebook_id, change.page_id).is_ok() {
+                    storage.delete_page(change.notebook_id, change.page_id)?;
+                }
+            }
+            (AiPageChangeKind::Updated, None) => {}
+        }
+    }
+    for notebook_id in &entry.created_notebooks {
+        let empty = storage
+            .list_page_summaries(*notebook_id)
+            .is_ok_and(|pages| pages.is_empty());
+        if empty {
+            storage.delete_notebook(*notebook_id)?;
+        }
+    }
+
+    entry.undone_at = Some(Utc::now());
+    log.save_entry(&entry)?;
+    Ok(entry)
+}
+
+/// Undo everything a chat session applied, newest first. Entries that can't
+/// be undone are reported and the rest carry on.
+pub fn undo_session(
+    storage: &FileStorageWriter,
+    log: &AiActionLogStorage,
+    session_id: Uuid,
+) -> Result<AiSessionUndo> {
+    let mut result = AiSessionUndo::default();
+    for entry in log.list_entries(Some(session_id))? {
+        if entry.undone_at.is_some() {
+            continue;
+        }
+        match undo_entry(storage, log, entry.id) {
+            Ok(undone) => result.undone.push(undone),
+            Err(e) => result.failed.push(AiUndoFailure {
+                entry_id: entry.id,
+                tool: entry.tool,
+                message: e.to_string(),
+            }),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{EditorBlock, NotebookType};
+
+    fn paragraph(id: &str, text: &str) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: "paragraph".to_string(),
+            data: serde_json::json!({ "text": text }),
+        }
+    }
+
+    fn action(tool: &str, arguments: serde_json::Value) -> AIAction {
+        AIAction {
+            tool: tool.to_string(),
+            arguments,
+            tool_call_id: format!("call_{}", tool),
+        }
+    }
+
+    #[test]
+    fn changes_go_to_the_tool_call_naming_the_page() {
+        let notebook_id = Uuid::new_v4();
+        let mut plan = Page::new(notebook_id, "Plan".to_string());
+        let before = plan.clone();
+        plan.content.blocks.push(paragraph("b1", "Ship it"));
+        let notes = Page::new(notebook_id, "Notes".to_string());
+
+        let entries = attribute(
+            None,
+            &[
+                action("nous_search_pages", serde_json::json!({ "query": "plan" })),
+                action("nous_manage_tags", serde_json::json!({ "page": "notes" })),
+                action("nous_append_to_page", serde_json::json!({ "page": "plan" })),
+            ],
+            vec![page_change(None, &notes), page_change(Some(before), &plan)],
+        );
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].tool, "nous_manage_tags");
+        assert_eq!(entries[0].changes[0].page_id, notes.id);
+        assert_eq!(entries[1].changes[0].page_id, plan.id);
+        assert_eq!(entries[1].changes[0].kind, AiPageChangeKind::Updated);
+        assert_eq!(entries[1].changes[0].diff.len(), 1);
+    }
+
+    #[test]
+    fn undo_restores_updates_and_trashes_created_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let log = AiActionLogStorage::new(dir.path().to_path_buf()).unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let session = Uuid::new_v4();
+
+        let mut page = storage
+            .create_page(notebook.id, "Plan".to_string())
+            .unwrap();
+        let before = page.clone();
+        page.content
+            .blocks
+            .push(paragraph("b1", "Rewritten by the AI"));
+        storage.update_page(&page).unwrap();
+        let mut entry = AiActionEntry::new(
+            Some(session),
+            "nous_update_page".to_string(),
+            serde_json::json!({ "page": "Plan" }),
+            "call_1".to_string(),
+        );
+        entry.changes.push(page_change(Some(before), &page));
+        log.save_entry(&entry).unwrap();
+
+        let created = storage
+            .create_page(notebook.id, "Draft".to_string())
+            .unwrap();
+        record_created(
+            &storage,
+            &log,
+            Some(session),
+            "create_page".to_string(),
+            serde_json::json!({ "title": "Draft" }),
+            "call_2".to_string(),
+            &[(notebook.id, created.id)],
+            Vec::new(),
+        )
+        .unwrap();
+
+        let result = undo_session(&storage, &log, session).unwrap();
+        assert_eq!(result.undone.len(), 2);
+        assert!(result.failed.is_empty());
+        assert!(storage
+            .get_page(notebook.id, page.id)
+            .unwrap()
+            .content
+            .blocks
+            .is_empty());
+        assert!(storage.get_page(notebook.id, created.id).is_err());
+        assert!(undo_entry(&storage, &log, entry.id).is_err());
+    }
+
+    #[test]
+    fn undo_refuses_pages_edited_since() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let log = AiActionLogStorage::new(dir.path().to_path_buf()).unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let page = storage
+            .create_page(notebook.id, "Draft".to_string())
+            .unwrap();
+        let entry = record_created(
+            &storage,
+            &log,
+            None,
+            "create_page".to_string(),
+            serde_json::json!({}),
+            "call_1".to_string(),
+            &[(notebook.id, page.id)],
+            Vec::new(),
+        )
+        .unwrap();
+
+        let mut edited = page.clone();
+        edited.content.blocks.push(paragraph("b1", "My own words"));
+        storage.update_page(&edited).unwrap();
+
+        assert!(undo_entry(&storage, &log, entry.id).is_err());
+        assert!(storage.get_page(notebook.id, page.id).is_ok());
+        assert!(log.get_entry(entry.id).unwrap().undone_at.is_none());
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::oplog::BlockChange;
+use crate::storage::Page;
+
+/// How an AI action changed a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AiPageChangeKind {
+    Created,
+    Updated,
+}
+
+/// One page an AI action created or changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiPageChange {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    pub kind: AiPageChangeKind,
+    /// Block-level diff from the page before the action to after it
+    pub diff: Vec<BlockChange>,
+    /// Content hash after the action; undo refuses once the page has moved on
+    pub after_hash: String,
+    /// The page before the action, for undoing updates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Page>,
+}
+
+/// A tool call the AI applied to the library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiActionEntry {
+    pub id: Uuid,
+    /// Chat session the action came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<Uuid>,
+    pub tool: String,
+    pub arguments: serde_json::Value,
+    pub tool_call_id: String,
+    pub applied_at: DateTime<Utc>,
+    #[serde(default)]
+    pub changes: Vec<AiPageChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created_notebooks: Vec<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undone_at: Option<DateTime<Utc>>,
+}
+
+impl AiActionEntry {
+    pub fn new(
+        session_id: Option<Uuid>,
+        tool: String,
+        arguments: serde_json::Value,
+        tool_call_id: String,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            session_id,
+            tool,
+            arguments,
+            tool_call_id,
+            applied_at: Utc::now(),
+            changes: Vec::new(),
+            created_notebooks: Vec::new(),
+            undone_at: None,
+        }
+    }
+
+    /// The entry without the page copies kept for undo
+    pub fn summary(&self) -> Self {
+        let mut entry = self.clone();
+        for change in &mut entry.changes {
+            change.before = None;
+        }
+        entry
+    }
+}
+
+/// A session entry that couldn't be undone
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiUndoFailure {
+    pub entry_id: Uuid,
+    pub tool: String,
+    pub message: String,
+}
+
+/// Result of undoing a chat session's actions
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiSessionUndo {
+    pub undone: Vec<AiActionEntry>,
+    pub failed: Vec<AiUndoFailure>,
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::StorageError;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Storage for the AI action log (one JSON file per action)
+pub struct AiActionLogStorage {
+    log_dir: PathBuf,
+}
+
+impl AiActionLogStorage {
+    /// Open the log of the library at `library_path`, creating the directory
+    /// if needed
+    pub fn new(library_path: PathBuf) -> Result<Self> {
+        let log_dir = library_path.join("ai_action_log");
+        fs::create_dir_all(&log_dir)?;
+        Ok(Self { log_dir })
+    }
+
+    fn entry_path(&self, id: Uuid) -> PathBuf {
+        self.log_dir.join(format!("{}.json", id))
+    }
+
+    pub fn save_entry(&self, entry: &AiActionEntry) -> Result<()> {
+        let json = serde_json::to_string_pretty(entry)?;
+        crate::storage::atomic::write_str(&self.entry_path(entry.id), &json)?;
+        Ok(())
+    }
+
+    pub fn get_entry(&self, id: Uuid) -> Result<AiActionEntry> {
+        let path = self.entry_path(id);
+        if !path.exists() {
+            return Err(StorageError::NotFound(format!("AI action {}", id)));
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Entries newest first, optionally only one session's
+    pub fn list_entries(&self, session_id: Option<Uuid>) -> Result<Vec<AiActionEntry>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.log_dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |e| e != "json") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            match serde_json::from_str::<AiActionEntry>(&content) {
+                Ok(entry) if session_id.is_none() || entry.session_id == session_id => {
+                    entries.push(entry)
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Skipping unreadable AI action {:?}: {}", path, e),
+            }
+        }
+        entries.sort_by(|a, b| b.applied_at.cmp(&a.applied_at));
+        Ok(entries)
+    }
+}
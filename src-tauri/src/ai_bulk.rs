@@ -0,0 +1,454 @@
+//! Prompt-based transformations across many pages at once
+//!
+//! An AI bulk operation (retitle, summarize into a page property,
+//! translate, fix formatting, or a custom instruction) runs in two steps.
+//! The preview sends each page through the Python queue and collects the
+//! proposed changes, with the page's Markdown before and after so the
+//! frontend can show a diff; nothing is written. Applying the accepted
+//! changes first takes a library snapshot (see `crate::library::snapshots`),
+//! so the whole batch can be undone with one rollback.
+//!
+//! Content is rewritten through Markdown. Pages with blocks that don't
+//! survive a Markdown round trip (drawings, embeds, databases, ...) are
+//! skipped by the operations that rewrite content rather than losing them.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::markdown::{export_blocks_to_markdown, parse_markdown_to_blocks};
+use crate::python_bridge::ChatMessage;
+use crate::storage::{EditorBlock, EditorData, Page, PageType};
+
+/// Block types that come back unchanged from Markdown
+const ROUND_TRIP_BLOCKS: &[&str] = &[
+    "header",
+    "paragraph",
+    "list",
+    "checklist",
+    "code",
+    "quote",
+    "delimiter",
+    "table",
+    "callout",
+    "image",
+];
+
+/// Page text sent for a title or summary; the start of a page is enough
+const EXCERPT_CHARS: usize = 12_000;
+
+/// Longest page rewritten in one request
+const MAX_REWRITE_CHARS: usize = 40_000;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum BulkError {
+    #[error("page is encrypted")]
+    Encrypted,
+
+    #[error("not a block page")]
+    NotBlockPage,
+
+    #[error("contains {0} blocks, which can't be rewritten without losing them")]
+    UnsupportedBlocks(String),
+
+    #[error("too long to rewrite in one request")]
+    TooLong,
+
+    #[error("the AI returned an empty reply")]
+    EmptyReply,
+
+    #[error("no changes")]
+    Unchanged,
+}
+
+/// What to do to each page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BulkOperation {
+    /// Give the page a better title
+    Retitle,
+    /// Write a short summary into the page property `property`
+    Summarize { property: String },
+    /// Translate the page's content into `language`
+    Translate { language: String },
+    /// Tidy headings, lists and spacing without changing the wording
+    FixFormatting,
+    /// Rewrite the page following `prompt`
+    Custom { prompt: String },
+}
+
+impl BulkOperation {
+    /// Short description, used to label the undo snapshot
+    pub fn label(&self) -> String {
+        match self {
+            Self::Retitle => "Retitle pages".to_string(),
+            Self::Summarize { property } => format!("Summarize into \"{}\"", property),
+            Self::Translate { language } => format!("Translate to {}", language),
+            Self::FixFormatting => "Fix formatting".to_string(),
+            Self::Custom { prompt } => {
+                let short: String = prompt.chars().take(40).collect();
+                if short.len() < prompt.len() {
+                    format!("Rewrite: {}...", short)
+                } else {
+                    format!("Rewrite: {}", short)
+                }
+            }
+        }
+    }
+
+    /// Check the operation's own parameters
+    pub fn validate(&self) -> Result<(), String> {
+        let (name, value) = match self {
+            Self::Summarize { property } => ("Property name", property),
+            Self::Translate { language } => ("Target language", language),
+            Self::Custom { prompt } => ("Instruction", prompt),
+            Self::Retitle | Self::FixFormatting => return Ok(()),
+        };
+        if value.trim().is_empty() {
+            return Err(format!("{} is required", name));
+        }
+        Ok(())
+    }
+
+    fn rewrites_content(&self) -> bool {
+        matches!(
+            self,
+            Self::Translate { .. } | Self::FixFormatting | Self::Custom { .. }
+        )
+    }
+
+    /// The chat request for `page`, or why the page is skipped
+    pub fn messages(&self, page: &Page) -> Result<Vec<ChatMessage>, BulkError> {
+        if page.is_encrypted() {
+            return Err(BulkError::Encrypted);
+        }
+        if page.page_type != PageType::Standard || page.plugin_page_type.is_some() {
+            return Err(BulkError::NotBlockPage);
+        }
+        let markdown = export_blocks_to_markdown(&page.content.blocks);
+
+        let system = match self {
+            Self::Retitle => "Write a short, descriptive title for the note. Reply \
+                with the title only, without quotes."
+                .to_string(),
+            Self::Summarize { .. } => "Summarize the note in one or two sentences. \
+                Reply with the summary only."
+                .to_string(),
+            Self::Translate { language } => format!(
+                "Translate the Markdown note into {}. Keep its structure, code \
+                 blocks, links and URLs unchanged. Reply with the translated \
+                 Markdown only.",
+                language.trim()
+            ),
+            Self::FixFormatting => "Fix the formatting of the Markdown note: \
+                consistent headings, lists, spacing and code fences. Do not change \
+                the wording or meaning. Reply with the Markdown only."
+                .to_string(),
+            Self::Custom { prompt } => format!(
+                "Rewrite the Markdown note following this instruction: {}\n\
+                 Reply with the complete rewritten note in Markdown only.",
+                prompt.trim()
+            ),
+        };
+
+        let user = if self.rewrites_content() {
+            if let Some(block_type) = page
+                .content
+                .blocks
+                .iter()
+                .map(|b| b.block_type.as_str())
+                .find(|t| !ROUND_TRIP_BLOCKS.contains(t))
+            {
+                return Err(BulkError::UnsupportedBlocks(block_type.to_string()));
+            }
+            if markdown.chars().count() > MAX_REWRITE_CHARS {
+                return Err(BulkError::TooLong);
+            }
+            markdown
+        } else {
+            let excerpt: String = markdown.chars().take(EXCERPT_CHARS).collect();
+            format!("Title: {}\n\n{}", page.title, excerpt)
+        };
+
+        Ok(vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system,
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: user,
+            },
+        ])
+    }
+
+    /// Turn the AI's `reply` for `page` into a proposed change
+    pub fn change(&self, page: &Page, reply: &str) -> Result<BulkPageChange, BulkError> {
+        let reply = strip_fences(reply.trim());
+        if reply.is_empty() {
+            return Err(BulkError::EmptyReply);
+        }
+
+        let before = export_blocks_to_markdown(&page.content.blocks);
+        let mut change = BulkPageChange {
+            page_id: page.id,
+            notebook_id: page.notebook_id,
+            base_updated_at: page.updated_at,
+            title: page.title.clone(),
+            new_title: None,
+            property: None,
+            before_markdown: before.clone(),
+            after_markdown: before,
+            content: None,
+        };
+
+        match self {
+            Self::Retitle => {
+                let title = clean_title(reply);
+                if title.is_empty() {
+                    return Err(BulkError::EmptyReply);
+                }
+                if title == page.title {
+                    return Err(BulkError::Unchanged);
+                }
+                change.new_title = Some(title);
+            }
+            Self::Summarize { property } => {
+                let summary = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+                let name = property.trim().to_string();
+                let old_value = page.properties.get(&name).cloned();
+                if old_value.as_ref() == Some(&summary) {
+                    return Err(BulkError::Unchanged);
+                }
+                change.property = Some(BulkProperty {
+                    name,
+                    old_value,
+                    value: summary,
+                });
+            }
+            _ => {
+                let mut blocks = parse_markdown_to_blocks(reply);
+                reuse_block_ids(&page.content.blocks, &mut blocks);
+                let after = export_blocks_to_markdown(&blocks);
+                if after == change.before_markdown {
+                    return Err(BulkError::Unchanged);
+                }
+                change.after_markdown = after;
+                change.content = Some(EditorData {
+                    time: Some(Utc::now().timestamp_millis()),
+                    version: page.content.version.clone(),
+                    blocks,
+                });
+            }
+        }
+        Ok(change)
+    }
+}
+
+/// A page property set by a bulk operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkProperty {
+    pub name: String,
+    pub old_value: Option<String>,
+    pub value: String,
+}
+
+/// The change proposed for one page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkPageChange {
+    pub page_id: Uuid,
+    pub notebook_id: Uuid,
+    /// The page's `updated_at` when previewed; a page edited since is left
+    /// alone when applying
+    pub base_updated_at: DateTime<Utc>,
+    pub title: String,
+    pub new_title: Option<String>,
+    pub property: Option<BulkProperty>,
+    pub before_markdown: String,
+    pub after_markdown: String,
+    /// New content, for operations that rewrite it
+    pub content: Option<EditorData>,
+}
+
+impl BulkPageChange {
+    /// Apply the change to `page`
+    pub fn apply_to(&self, page: &mut Page) {
+        if let Some(title) = &self.new_title {
+            page.title = title.clone();
+        }
+        if let Some(property) = &self.property {
+            page.properties
+                .insert(property.name.clone(), property.value.clone());
+        }
+        if let Some(content) = &self.content {
+            page.content = content.clone();
+        }
+        page.updated_at = Utc::now();
+    }
+}
+
+/// A page a bulk operation left alone, and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkSkipped {
+    pub page_id: Uuid,
+    pub title: String,
+    pub reason: String,
+}
+
+/// Result of a preview
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTransformPreview {
+    pub changes: Vec<BulkPageChange>,
+    pub skipped: Vec<BulkSkipped>,
+}
+
+/// Drop a code fence wrapped around the whole reply
+fn strip_fences(reply: &str) -> &str {
+    let Some(rest) = reply.strip_prefix("```") else {
+        return reply;
+    };
+    let Some(body) = rest.strip_suffix("```") else {
+        return reply;
+    };
+    // Skip the info string ("markdown", "md", ...) on the opening line
+    match body.split_once('\n') {
+        Some((_, inner)) => inner.trim(),
+        None => reply,
+    }
+}
+
+/// First line of a title reply, without heading marks or quotes
+fn clean_title(reply: &str) -> String {
+    let line = reply.lines().next().unwrap_or("").trim();
+    let line = line.trim_start_matches('#').trim();
+    let line = line.strip_prefix("Title:").map(str::trim).unwrap_or(line);
+    line.trim_matches(|c| matches!(c, '"' | '\'' | '“' | '”' | '*'))
+        .trim()
+        .to_string()
+}
+
+/// Keep block ids where the rewritten page has a block of the same type in
+/// the same place, so links to blocks and block history survive a rewrite
+/// that keeps the page's structure
+fn reuse_block_ids(old: &[EditorBlock], new: &mut [EditorBlock]) {
+    for (old, new) in old.iter().zip(new.iter_mut()) {
+        if old.block_type == new.block_type {
+            new.id = old.id.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(id: &str, block_type: &str, data: serde_json::Value) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn page(blocks: Vec<EditorBlock>) -> Page {
+        let mut page = Page::new(Uuid::new_v4(), "notes".to_string());
+        page.content.blocks = blocks;
+        page
+    }
+
+    fn simple_page() -> Page {
+        page(vec![
+            block(
+                "h1",
+                "header",
+                serde_json::json!({"text": "Plan", "level": 2}),
+            ),
+            block(
+                "p1",
+                "paragraph",
+                serde_json::json!({"text": "Ship it friday"}),
+            ),
+        ])
+    }
+
+    #[test]
+    fn retitle_cleans_up_the_reply() {
+        let page = simple_page();
+        let change = BulkOperation::Retitle
+            .change(
+                &page,
+                "Title: \"Release plan\"\nBecause it is about a release.",
+            )
+            .unwrap();
+        assert_eq!(change.new_title.as_deref(), Some("Release plan"));
+        assert!(change.content.is_none());
+
+        assert_eq!(
+            BulkOperation::Retitle.change(&page, "notes").unwrap_err(),
+            BulkError::Unchanged
+        );
+    }
+
+    #[test]
+    fn summary_goes_into_the_property() {
+        let mut page = simple_page();
+        let op = BulkOperation::Summarize {
+            property: "summary".to_string(),
+        };
+        let change = op.change(&page, "  A release\n plan.  ").unwrap();
+        let property = change.property.clone().unwrap();
+        assert_eq!(property.value, "A release plan.");
+        assert_eq!(property.old_value, None);
+
+        change.apply_to(&mut page);
+        assert_eq!(page.properties["summary"], "A release plan.");
+        assert_eq!(page.title, "notes");
+    }
+
+    #[test]
+    fn rewrite_keeps_ids_of_matching_blocks() {
+        let page = simple_page();
+        let op = BulkOperation::Translate {
+            language: "French".to_string(),
+        };
+        let change = op
+            .change(&page, "```markdown\n## Plan\n\nLivrer vendredi\n```")
+            .unwrap();
+        let content = change.content.unwrap();
+        assert_eq!(content.blocks.len(), 2);
+        assert_eq!(content.blocks[0].id, "h1");
+        assert_eq!(content.blocks[1].id, "p1");
+        assert!(change.after_markdown.contains("Livrer vendredi"));
+        assert!(change.before_markdown.contains("Ship it friday"));
+    }
+
+    #[test]
+    fn content_rewrites_skip_pages_that_would_lose_blocks() {
+        let mut page = simple_page();
+        page.content
+            .blocks
+            .push(block("d1", "drawing", serde_json::json!({})));
+
+        assert_eq!(
+            BulkOperation::FixFormatting.messages(&page).unwrap_err(),
+            BulkError::UnsupportedBlocks("drawing".to_string())
+        );
+        // Reading the page for a title is fine
+        assert!(BulkOperation::Retitle.messages(&page).is_ok());
+    }
+
+    #[test]
+    fn operations_need_their_parameters() {
+        assert!(BulkOperation::Custom {
+            prompt: "  ".to_string()
+        }
+        .validate()
+        .is_err());
+        assert!(BulkOperation::FixFormatting.validate().is_ok());
+    }
+}
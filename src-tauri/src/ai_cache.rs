@@ -0,0 +1,310 @@
+//! Cache of AI responses keyed by a hash of their inputs
+//!
+//! Summaries of unchanged pages, tag suggestions, embeddings and study
+//! tool output are deterministic enough that asking again only costs time
+//! and money. Callers hash everything that shapes the response (the kind of
+//! request, provider and model settings, and the content itself; never the
+//! API key) with [`AiCache::key`], look it up before calling the provider,
+//! and store the result after. Entries expire after a per-kind TTL.
+//!
+//! The cache is a SQLite file in the app data directory, so it is
+//! per-device and shared by all libraries; the keys are content hashes, so
+//! libraries never see each other's entries unless the input is identical.
+//! Failures to read or write it are logged and treated as misses.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::python_bridge::AIConfig;
+
+#[derive(Error, Debug)]
+pub enum AiCacheError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, AiCacheError>;
+
+/// What a cached response is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AiCacheKind {
+    Summary,
+    Tags,
+    Embedding,
+    StudyTool,
+}
+
+impl AiCacheKind {
+    pub const ALL: [AiCacheKind; 4] = [
+        AiCacheKind::Summary,
+        AiCacheKind::Tags,
+        AiCacheKind::Embedding,
+        AiCacheKind::StudyTool,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Summary => "summary",
+            Self::Tags => "tags",
+            Self::Embedding => "embedding",
+            Self::StudyTool => "studyTool",
+        }
+    }
+
+    /// How long entries stay fresh. Embeddings only change with the model,
+    /// which is part of the key, so they live longest.
+    pub fn ttl(&self) -> Duration {
+        match self {
+            Self::Embedding => Duration::days(90),
+            Self::Summary | Self::StudyTool => Duration::days(30),
+            Self::Tags => Duration::days(7),
+        }
+    }
+}
+
+/// Entry counts and sizes of one kind
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiCacheStats {
+    pub kind: AiCacheKind,
+    pub entries: u64,
+    pub bytes: u64,
+    pub hits: u64,
+}
+
+pub struct AiCache {
+    conn: Mutex<Connection>,
+}
+
+impl AiCache {
+    /// Open (creating if needed) the cache database at `db_path`
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS ai_cache (
+                key TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                value TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                hits INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE INDEX IF NOT EXISTS idx_ai_cache_kind ON ai_cache(kind);
+            "#,
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// The cache key of a request: `kind` plus everything that shapes the
+    /// response, hashed
+    pub fn key(kind: AiCacheKind, inputs: &impl Serialize) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(kind.as_str().as_bytes());
+        hasher.update([0]);
+        // Serializing plain data to a Vec can't fail
+        hasher.update(serde_json::to_vec(inputs).unwrap_or_default());
+        hex::encode(hasher.finalize())
+    }
+
+    /// The parts of a provider config that change responses
+    pub fn config_inputs(config: &AIConfig) -> serde_json::Value {
+        serde_json::json!({
+            "provider": config.provider_type,
+            "baseUrl": config.base_url,
+            "model": config.model,
+            "temperature": config.temperature,
+            "maxTokens": config.max_tokens,
+        })
+    }
+
+    /// The key of an embedding of `text`, given the embedding config JSON
+    /// the Python helpers take (its API key left out)
+    pub fn embedding_key(config_json: &str, text: &str) -> String {
+        let mut config: serde_json::Value =
+            serde_json::from_str(config_json).unwrap_or(serde_json::Value::Null);
+        if let Some(map) = config.as_object_mut() {
+            map.remove("api_key");
+            map.remove("apiKey");
+        }
+        Self::key(AiCacheKind::Embedding, &(config, text))
+    }
+
+    fn try_get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM ai_cache WHERE key = ?1 AND expires_at > ?2",
+                params![key, now],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        conn.execute(
+            "UPDATE ai_cache SET hits = hits + 1 WHERE key = ?1",
+            params![key],
+        )?;
+        Ok(Some(serde_json::from_str(&value)?))
+    }
+
+    /// A fresh cached response, if any
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.try_get(key).unwrap_or_else(|e| {
+            log::warn!("AI cache read failed: {}", e);
+            None
+        })
+    }
+
+    fn try_put<T: Serialize>(&self, kind: AiCacheKind, key: &str, value: &T) -> Result<()> {
+        let now = Utc::now();
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO ai_cache (key, kind, value, created_at, expires_at, hits)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0)",
+            params![
+                key,
+                kind.as_str(),
+                serde_json::to_string(value)?,
+                now.timestamp(),
+                (now + kind.ttl()).timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Store a response
+    pub fn put<T: Serialize>(&self, kind: AiCacheKind, key: &str, value: &T) {
+        if let Err(e) = self.try_put(kind, key, value) {
+            log::warn!("AI cache write failed: {}", e);
+        }
+    }
+
+    /// Remove all entries, or only those of `kind`. Returns how many.
+    pub fn clear(&self, kind: Option<AiCacheKind>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let removed = match kind {
+            Some(kind) => conn.execute(
+                "DELETE FROM ai_cache WHERE kind = ?1",
+                params![kind.as_str()],
+            )?,
+            None => conn.execute("DELETE FROM ai_cache", [])?,
+        };
+        Ok(removed)
+    }
+
+    /// Remove expired entries. Returns how many.
+    pub fn prune_expired(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute(
+            "DELETE FROM ai_cache WHERE expires_at <= ?1",
+            params![Utc::now().timestamp()],
+        )?)
+    }
+
+    pub fn stats(&self) -> Result<Vec<AiCacheStats>> {
+        let conn = self.conn.lock().unwrap();
+        AiCacheKind::ALL
+            .iter()
+            .map(|kind| {
+                conn.query_row(
+                    "SELECT COUNT(*), COALESCE(SUM(LENGTH(value)), 0), COALESCE(SUM(hits), 0)
+                     FROM ai_cache WHERE kind = ?1",
+                    params![kind.as_str()],
+                    |row| {
+                        Ok(AiCacheStats {
+                            kind: *kind,
+                            entries: row.get::<_, i64>(0)? as u64,
+                            bytes: row.get::<_, i64>(1)? as u64,
+                            hits: row.get::<_, i64>(2)? as u64,
+                        })
+                    },
+                )
+                .map_err(Into::into)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_depend_on_kind_and_inputs() {
+        let a = AiCache::key(AiCacheKind::Summary, &("model", "page text"));
+        assert_eq!(a, AiCache::key(AiCacheKind::Summary, &("model", "page text")));
+        assert_ne!(a, AiCache::key(AiCacheKind::Tags, &("model", "page text")));
+        assert_ne!(a, AiCache::key(AiCacheKind::Summary, &("model", "edited text")));
+
+        // Rotating an API key keeps cached embeddings
+        assert_eq!(
+            AiCache::embedding_key(r#"{"model":"m","api_key":"old"}"#, "text"),
+            AiCache::embedding_key(r#"{"model":"m","api_key":"new"}"#, "text")
+        );
+    }
+
+    #[test]
+    fn stores_counts_and_clears_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AiCache::open(&dir.path().join("ai_cache.db")).unwrap();
+        let key = AiCache::key(AiCacheKind::Tags, &"content");
+
+        assert_eq!(cache.get::<Vec<String>>(&key), None);
+        cache.put(AiCacheKind::Tags, &key, &vec!["rust".to_string()]);
+        assert_eq!(cache.get::<Vec<String>>(&key), Some(vec!["rust".to_string()]));
+
+        let tags = cache
+            .stats()
+            .unwrap()
+            .into_iter()
+            .find(|s| s.kind == AiCacheKind::Tags)
+            .unwrap();
+        assert_eq!((tags.entries, tags.hits), (1, 1));
+
+        assert_eq!(cache.clear(Some(AiCacheKind::Summary)).unwrap(), 0);
+        assert_eq!(cache.clear(None).unwrap(), 1);
+        assert_eq!(cache.get::<Vec<String>>(&key), None);
+    }
+
+    #[test]
+    fn expired_entries_are_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AiCache::open(&dir.path().join("ai_cache.db")).unwrap();
+        let key = AiCache::key(AiCacheKind::Summary, &"content");
+        cache.put(AiCacheKind::Summary, &key, &"summary");
+        cache
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE ai_cache SET expires_at = ?1",
+                params![(Utc::now() - Duration::hours(1)).timestamp()],
+            )
+            .unwrap();
+
+        assert_eq!(cache.get::<String>(&key), None);
+        assert_eq!(cache.prune_expired().unwrap(), 1);
+    }
+}
@@ -31,6 +31,10 @@ pub struct AiProviderConfig {
     pub base_url: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_model: Option<String>,
+    /// Secret the key is stored under (see `crate::secrets`). Once set,
+    /// `api_key` is only filled in memory and never written back here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_secret: Option<String>,
 }
 
 impl AiSection {
@@ -46,6 +50,18 @@ impl AiSection {
         }
         out
     }
+
+    /// The section as written to `daemon-config.toml`: keys held by the
+    /// secrets store are left out
+    pub fn for_disk(&self) -> Self {
+        let mut out = self.clone();
+        for provider in out.providers.values_mut() {
+            if provider.api_key_secret.is_some() {
+                provider.api_key = None;
+            }
+        }
+        out
+    }
 }
 
 /// Fill fields the request left empty from the provider entry. Request
@@ -72,6 +88,7 @@ mod tests {
             api_key: Some(key.to_string()),
             base_url: Some(url.to_string()),
             default_model: Some(model.to_string()),
+            api_key_secret: None,
         }
     }
 
@@ -130,6 +147,7 @@ mod tests {
                 api_key: Some(String::new()),
                 base_url: None,
                 default_model: None,
+                api_key_secret: None,
             },
         );
         assert_eq!(config.api_key, None);
@@ -147,6 +165,7 @@ mod tests {
                 api_key: None,
                 base_url: Some("http://localhost:11434".into()),
                 default_model: None,
+                api_key_secret: None,
             },
         );
 
@@ -0,0 +1,354 @@
+//! Permissions for AI chat tools and MCP tools
+//!
+//! Every tool is classified as read, write or destructive: the built-in
+//! tools by a fixed table, MCP tools (`mcp:<server>:<tool>`) by the verb in
+//! their name, with anything unrecognised counted as a write. A policy maps
+//! each class to allow, ask or deny, and can override single tools by name.
+//! The library has a default policy, and notebooks can have their own for
+//! tool calls that target them (by a `notebook` argument).
+//!
+//! Policies are the `ai.toolPermissions` library setting, so they sync with
+//! the library. The Python chat loop runs the `nous_*` and MCP tools itself
+//! and reads the same setting (`nous_ai/tool_permissions.py`, which mirrors
+//! this classification); `mcp_call_tool` and the frontend's tool executor
+//! check here. Anything not allowed is reported as an `ai-tool-permission`
+//! event carrying a [`ToolPermissionCheck`].
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::library::{LibraryError, LibrarySettings};
+use crate::storage::FileStorage;
+
+/// Library setting holding the policies
+pub const PERMISSIONS_SETTING: &str = "ai.toolPermissions";
+
+/// Event emitted when a tool call is denied or needs approval
+pub const PERMISSION_EVENT: &str = "ai-tool-permission";
+
+/// Prefix of MCP tool names in the chat (`mcp:<server>:<tool>`)
+pub const MCP_TOOL_PREFIX: &str = "mcp:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolClass {
+    Read,
+    Write,
+    Destructive,
+}
+
+impl ToolClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Destructive => "destructive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolDecision {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// Decisions per tool class, with per-tool overrides
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPolicy {
+    #[serde(default = "allow")]
+    pub read: ToolDecision,
+    #[serde(default = "allow")]
+    pub write: ToolDecision,
+    #[serde(default = "ask")]
+    pub destructive: ToolDecision,
+    /// Decisions for single tools by name, e.g. `nous_update_page` or
+    /// `mcp:github:create_issue`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, ToolDecision>,
+}
+
+fn allow() -> ToolDecision {
+    ToolDecision::Allow
+}
+
+fn ask() -> ToolDecision {
+    ToolDecision::Ask
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        Self {
+            read: allow(),
+            write: allow(),
+            destructive: ask(),
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+impl ToolPolicy {
+    fn decide(&self, tool: &str, class: ToolClass) -> (ToolDecision, String) {
+        if let Some(decision) = self.tools.get(tool) {
+            return (*decision, format!("set for {}", tool));
+        }
+        let decision = match class {
+            ToolClass::Read => self.read,
+            ToolClass::Write => self.write,
+            ToolClass::Destructive => self.destructive,
+        };
+        (decision, format!("set for {} tools", class.as_str()))
+    }
+}
+
+/// The library's policies
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPermissions {
+    #[serde(default)]
+    pub default: ToolPolicy,
+    /// Policies replacing the default for calls that target a notebook
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notebooks: BTreeMap<Uuid, ToolPolicy>,
+}
+
+/// The outcome of checking a tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPermissionCheck {
+    pub tool: String,
+    pub class: ToolClass,
+    pub decision: ToolDecision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notebook_id: Option<Uuid>,
+    /// Which rule decided, for showing the user
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ToolPermissionCheck {
+    pub fn allowed(&self) -> bool {
+        self.decision == ToolDecision::Allow
+    }
+
+    /// Explanation for the model or an error message
+    pub fn message(&self) -> String {
+        match self.decision {
+            ToolDecision::Allow => format!("{} is allowed", self.tool),
+            ToolDecision::Ask => format!(
+                "{} needs the user's approval ({}); it was not run",
+                self.tool, self.reason
+            ),
+            ToolDecision::Deny => format!(
+                "{} is not permitted ({}); it was not run",
+                self.tool, self.reason
+            ),
+        }
+    }
+}
+
+impl ToolPermissions {
+    /// The library's policies; an unreadable setting is logged and the
+    /// defaults used
+    pub fn load(library_path: &Path) -> Self {
+        let settings = LibrarySettings::load(library_path);
+        match settings.get(PERMISSIONS_SETTING) {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {} setting: {}", PERMISSIONS_SETTING, e);
+                Self::default()
+            }),
+            None => Self::default(),
+        }
+    }
+
+    pub fn save(&self, library_path: &Path) -> Result<(), LibraryError> {
+        let mut settings = LibrarySettings::load(library_path);
+        settings.set(PERMISSIONS_SETTING, serde_json::to_value(self)?);
+        settings.save()
+    }
+
+    /// Decide a call of `tool` targeting `notebook_id`
+    pub fn check(&self, tool: &str, notebook_id: Option<Uuid>) -> ToolPermissionCheck {
+        let class = classify(tool);
+        let notebook_policy = notebook_id.and_then(|id| self.notebooks.get(&id));
+        let (decision, reason) = match notebook_policy {
+            Some(policy) => {
+                let (decision, reason) = policy.decide(tool, class);
+                (decision, format!("notebook policy, {}", reason))
+            }
+            None => {
+                let (decision, reason) = self.default.decide(tool, class);
+                (decision, format!("library policy, {}", reason))
+            }
+        };
+        ToolPermissionCheck {
+            tool: tool.to_string(),
+            class,
+            decision,
+            notebook_id,
+            reason,
+            tool_call_id: None,
+        }
+    }
+}
+
+/// Built-in chat tools and their classes
+const BUILT_IN_TOOLS: &[(&str, ToolClass)] = &[
+    ("list_actions", ToolClass::Read),
+    ("nous_list_notebooks", ToolClass::Read),
+    ("nous_list_sections", ToolClass::Read),
+    ("nous_list_folders", ToolClass::Read),
+    ("nous_list_pages", ToolClass::Read),
+    ("nous_get_page", ToolClass::Read),
+    ("nous_search_pages", ToolClass::Read),
+    ("nous_list_databases", ToolClass::Read),
+    ("nous_get_database", ToolClass::Read),
+    ("create_notebook", ToolClass::Write),
+    ("create_page", ToolClass::Write),
+    ("run_action", ToolClass::Write),
+    ("browse_web", ToolClass::Write),
+    ("nous_append_to_page", ToolClass::Write),
+    ("nous_create_folder", ToolClass::Write),
+    ("nous_move_page", ToolClass::Write),
+    ("nous_manage_tags", ToolClass::Write),
+    ("nous_create_database", ToolClass::Write),
+    ("nous_add_database_rows", ToolClass::Write),
+    ("nous_update_page", ToolClass::Write),
+    ("nous_update_database_rows", ToolClass::Write),
+];
+
+const READ_VERBS: &[&str] = &[
+    "get", "list", "search", "read", "find", "query", "fetch", "describe", "show", "view", "count",
+    "lookup", "check", "browse",
+];
+
+const DESTRUCTIVE_VERBS: &[&str] = &[
+    "delete",
+    "remove",
+    "drop",
+    "destroy",
+    "purge",
+    "erase",
+    "wipe",
+    "truncate",
+    "reset",
+    "clear",
+    "overwrite",
+    "revoke",
+    "kill",
+    "terminate",
+    "uninstall",
+];
+
+/// The class of a tool. MCP tools go by the first word of their name
+/// (`delete_issue`, `listFiles`); unknown verbs count as writes.
+pub fn classify(tool: &str) -> ToolClass {
+    if let Some((_, class)) = BUILT_IN_TOOLS.iter().find(|(name, _)| *name == tool) {
+        return *class;
+    }
+    let name = tool
+        .strip_prefix(MCP_TOOL_PREFIX)
+        .and_then(|rest| rest.split_once(':').map(|(_, name)| name))
+        .unwrap_or(tool);
+    let verb: String = name
+        .trim_start_matches(['_', '-'])
+        .chars()
+        .take_while(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        .collect();
+    if DESTRUCTIVE_VERBS.contains(&verb.as_str()) {
+        ToolClass::Destructive
+    } else if READ_VERBS.contains(&verb.as_str()) {
+        ToolClass::Read
+    } else {
+        ToolClass::Write
+    }
+}
+
+/// The chat name of an MCP server's tool
+pub fn mcp_tool_name(server: &str, tool: &str) -> String {
+    format!("{}{}:{}", MCP_TOOL_PREFIX, server, tool)
+}
+
+/// The notebook a tool call targets: a `notebook`, `notebook_name` or
+/// `notebook_id` argument holding a notebook's ID or name
+pub fn target_notebook(storage: &FileStorage, arguments: &serde_json::Value) -> Option<Uuid> {
+    let value = ["notebook", "notebook_name", "notebook_id", "notebookId"]
+        .iter()
+        .find_map(|key| arguments.get(key).and_then(|v| v.as_str()))?
+        .trim();
+    if let Ok(id) = Uuid::parse_str(value) {
+        return Some(id);
+    }
+    storage
+        .list_notebooks()
+        .ok()?
+        .into_iter()
+        .find(|notebook| notebook.name.eq_ignore_ascii_case(value))
+        .map(|notebook| notebook.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_built_in_and_mcp_tools() {
+        assert_eq!(classify("nous_get_page"), ToolClass::Read);
+        assert_eq!(classify("create_page"), ToolClass::Write);
+        assert_eq!(classify("mcp:github:list_issues"), ToolClass::Read);
+        assert_eq!(classify("mcp:files:deleteFile"), ToolClass::Destructive);
+        assert_eq!(classify("mcp:github:create_issue"), ToolClass::Write);
+        assert_eq!(classify("mcp:odd:frobnicate"), ToolClass::Write);
+    }
+
+    #[test]
+    fn notebook_policies_and_tool_overrides_win() {
+        let journal = Uuid::new_v4();
+        let mut permissions = ToolPermissions::default();
+        permissions
+            .default
+            .tools
+            .insert("mcp:github:create_issue".to_string(), ToolDecision::Deny);
+        permissions.notebooks.insert(
+            journal,
+            ToolPolicy {
+                write: ToolDecision::Ask,
+                ..ToolPolicy::default()
+            },
+        );
+
+        assert!(permissions.check("create_page", None).allowed());
+        assert_eq!(
+            permissions.check("create_page", Some(journal)).decision,
+            ToolDecision::Ask
+        );
+        assert_eq!(
+            permissions.check("mcp:files:delete_file", None).decision,
+            ToolDecision::Ask
+        );
+        let denied = permissions.check("mcp:github:create_issue", None);
+        assert_eq!(denied.decision, ToolDecision::Deny);
+        assert!(denied.message().contains("not permitted"));
+    }
+
+    #[test]
+    fn policies_round_trip_through_library_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            ToolPermissions::load(dir.path()),
+            ToolPermissions::default()
+        );
+
+        let mut permissions = ToolPermissions::default();
+        permissions.default.destructive = ToolDecision::Deny;
+        permissions.save(dir.path()).unwrap();
+        assert_eq!(ToolPermissions::load(dir.path()), permissions);
+    }
+}
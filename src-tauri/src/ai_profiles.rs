@@ -0,0 +1,204 @@
+//! Named AI provider profiles, shared by a library's notebooks and actions
+//!
+//! A profile bundles a provider, model, sampling settings and a system
+//! prompt under a name, so a notebook (`Notebook::ai_profile_id`) or an
+//! action step (`ai_profile` on the AI steps) can pick "Local drafts" or
+//! "Careful review" instead of repeating the settings. Profiles are stored
+//! as the `ai.profiles` library setting, so they sync with the library.
+//!
+//! API keys never go into a profile: synced settings are readable on every
+//! device. `api_key_ref` names the provider whose key to use, which each
+//! device resolves from its own secrets store (`ai.<ref>`, see
+//! `crate::secrets`) or its daemon config.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ai_config::AiSection;
+use crate::library::{LibraryError, LibrarySettings};
+use crate::python_bridge::AIConfig;
+
+/// Library setting holding the profile list
+pub const PROFILES_SETTING: &str = "ai.profiles";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiProfile {
+    pub id: String,
+    pub name: String,
+    /// Provider type ("openai", "anthropic", "ollama", "lmstudio", ...)
+    pub provider_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i64>,
+    /// Provider entry whose API key to use; the profile's own provider when
+    /// unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+}
+
+impl AiProfile {
+    /// Provider entry the key comes from
+    pub fn key_ref(&self) -> &str {
+        self.api_key_ref
+            .as_deref()
+            .filter(|r| !r.is_empty())
+            .unwrap_or(&self.provider_type)
+    }
+
+    /// The call configuration for this profile, with the API key (and any
+    /// unset base URL) taken from `credentials` when given
+    pub fn to_ai_config(&self, credentials: Option<&AiSection>) -> AIConfig {
+        let defaults = AIConfig::default();
+        let mut config = AIConfig {
+            provider_type: self.provider_type.clone(),
+            api_key: None,
+            base_url: self.base_url.clone(),
+            model: self.model.clone(),
+            temperature: self.temperature.or(defaults.temperature),
+            max_tokens: self.max_tokens.or(defaults.max_tokens),
+        };
+        if let Some(provider) = credentials.and_then(|c| c.providers.get(self.key_ref())) {
+            config.api_key = provider.api_key.clone().filter(|k| !k.is_empty());
+            if config.base_url.is_none() {
+                config.base_url = provider.base_url.clone().filter(|u| !u.is_empty());
+            }
+        }
+        config
+    }
+}
+
+/// Profiles of the library at `library_path`. An unreadable setting is
+/// logged and treated as empty.
+pub fn list_profiles(library_path: &Path) -> Vec<AiProfile> {
+    let settings = LibrarySettings::load(library_path);
+    match settings.get(PROFILES_SETTING) {
+        Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable {} setting: {}", PROFILES_SETTING, e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    }
+}
+
+pub fn get_profile(library_path: &Path, id: &str) -> Option<AiProfile> {
+    list_profiles(library_path).into_iter().find(|p| p.id == id)
+}
+
+/// Add `profile`, or replace the one with its id. A profile without an id
+/// gets a new one.
+pub fn save_profile(
+    library_path: &Path,
+    mut profile: AiProfile,
+) -> Result<AiProfile, LibraryError> {
+    if profile.id.is_empty() {
+        profile.id = Uuid::new_v4().to_string();
+    }
+    let mut profiles = list_profiles(library_path);
+    match profiles.iter_mut().find(|p| p.id == profile.id) {
+        Some(existing) => *existing = profile.clone(),
+        None => profiles.push(profile.clone()),
+    }
+    write_profiles(library_path, &profiles)?;
+    Ok(profile)
+}
+
+/// Remove a profile. Notebooks and steps still naming it fall back to their
+/// own settings.
+pub fn delete_profile(library_path: &Path, id: &str) -> Result<bool, LibraryError> {
+    let mut profiles = list_profiles(library_path);
+    let before = profiles.len();
+    profiles.retain(|p| p.id != id);
+    if profiles.len() == before {
+        return Ok(false);
+    }
+    write_profiles(library_path, &profiles)?;
+    Ok(true)
+}
+
+fn write_profiles(library_path: &Path, profiles: &[AiProfile]) -> Result<(), LibraryError> {
+    let mut settings = LibrarySettings::load(library_path);
+    settings.set(PROFILES_SETTING, serde_json::to_value(profiles)?);
+    settings.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_config::AiProviderConfig;
+    use tempfile::TempDir;
+
+    fn profile(name: &str, provider: &str) -> AiProfile {
+        AiProfile {
+            id: String::new(),
+            name: name.to_string(),
+            provider_type: provider.to_string(),
+            model: Some("llama3.2".to_string()),
+            base_url: None,
+            temperature: Some(0.2),
+            max_tokens: None,
+            api_key_ref: None,
+            system_prompt: Some("Be brief.".to_string()),
+        }
+    }
+
+    #[test]
+    fn profiles_round_trip_through_library_settings() {
+        let dir = TempDir::new().unwrap();
+        assert!(list_profiles(dir.path()).is_empty());
+
+        let saved = save_profile(dir.path(), profile("Local drafts", "ollama")).unwrap();
+        assert!(!saved.id.is_empty());
+
+        let mut renamed = saved.clone();
+        renamed.name = "Local".to_string();
+        save_profile(dir.path(), renamed).unwrap();
+        save_profile(dir.path(), profile("Review", "anthropic")).unwrap();
+
+        let profiles = list_profiles(dir.path());
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(get_profile(dir.path(), &saved.id).unwrap().name, "Local");
+
+        assert!(delete_profile(dir.path(), &saved.id).unwrap());
+        assert!(!delete_profile(dir.path(), &saved.id).unwrap());
+        assert_eq!(list_profiles(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn config_takes_key_from_referenced_provider() {
+        let mut credentials = AiSection::default();
+        credentials.providers.insert(
+            "openrouter".to_string(),
+            AiProviderConfig {
+                api_key: Some("sk-router".to_string()),
+                base_url: Some("https://openrouter.ai/api/v1".to_string()),
+                default_model: None,
+                api_key_secret: None,
+            },
+        );
+        let mut p = profile("Routed", "openai");
+
+        let config = p.to_ai_config(Some(&credentials));
+        assert_eq!(config.api_key, None);
+        assert_eq!(config.temperature, Some(0.2));
+        assert_eq!(config.max_tokens, AIConfig::default().max_tokens);
+
+        p.api_key_ref = Some("openrouter".to_string());
+        let config = p.to_ai_config(Some(&credentials));
+        assert_eq!(config.provider_type, "openai");
+        assert_eq!(config.api_key.as_deref(), Some("sk-router"));
+        assert_eq!(
+            config.base_url.as_deref(),
+            Some("https://openrouter.ai/api/v1")
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! Export and import of the app's configuration
+//!
+//! One JSON bundle holding everything needed to set the app up again on a
+//! new machine, or after a reset, apart from the notes themselves: the
+//! library's configuration and synced settings, custom actions, external
+//! sources, notebook sync configs, MCP servers and the user's page
+//! templates (which live in the frontend and are passed through as-is).
+//!
+//! Passwords and API keys are never part of a bundle. Sync configs only
+//! hold server details, and one imported without matching credentials on
+//! this device comes back disabled until its password is entered again.
+//! Per-device state (last sync and run times, processed files) is dropped
+//! on export.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::actions::Action;
+use crate::external_sources::ExternalSource;
+use crate::library::{Library, SettingEntry};
+use crate::markdown::mirror::MarkdownMirrorConfig;
+use crate::share::upload::ShareUploadConfig;
+use crate::storage::backup_destination::BackupDestination;
+use crate::sync::config::{LibrarySyncConfig, SyncConfig};
+
+/// `format` of every bundle, so an unrelated JSON file is rejected
+pub const BUNDLE_FORMAT: &str = "nous-app-config";
+
+/// Current bundle version; newer bundles are refused
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// MCP server config file in a library (read and written by `nous_ai.mcp_client`)
+const MCP_CONFIG_FILE: &str = "mcp_servers.json";
+
+#[derive(Error, Debug)]
+pub enum AppConfigError {
+    #[error("Not a Nous configuration export")]
+    UnknownFormat,
+
+    #[error("Configuration export version {0} is newer than this version of Nous supports")]
+    UnsupportedVersion(u32),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type AppConfigResult<T> = Result<T, AppConfigError>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfigBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub library: LibraryConfig,
+    /// Synced library settings (`{library}/settings.json`)
+    #[serde(default)]
+    pub settings: BTreeMap<String, SettingEntry>,
+    /// Custom actions; built-in ones are recreated by every install
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub external_sources: Vec<ExternalSource>,
+    #[serde(default)]
+    pub notebook_sync: Vec<NotebookSyncEntry>,
+    /// Contents of `mcp_servers.json`, when the library has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_servers: Option<serde_json::Value>,
+    /// User page templates, opaque to the backend
+    #[serde(default)]
+    pub templates: Vec<serde_json::Value>,
+}
+
+/// The library's own configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryConfig {
+    /// Name of the exported library, for display only
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_config: Option<LibrarySyncConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub share_upload_config: Option<ShareUploadConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markdown_mirror: Option<MarkdownMirrorConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_destination: Option<BackupDestination>,
+}
+
+impl LibraryConfig {
+    pub fn from_library(library: &Library) -> Self {
+        Self {
+            name: library.name.clone(),
+            icon: library.icon.clone(),
+            color: library.color.clone(),
+            sync_config: library.sync_config.clone(),
+            share_upload_config: library.share_upload_config.clone(),
+            markdown_mirror: library.markdown_mirror.clone(),
+            backup_destination: library.backup_destination.clone(),
+        }
+    }
+}
+
+/// A notebook's sync config, with the notebook's name for matching when
+/// its id differs on the importing device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookSyncEntry {
+    pub notebook_id: Uuid,
+    pub notebook_name: String,
+    pub config: SyncConfig,
+}
+
+/// What a bundle holds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfigCounts {
+    pub settings: usize,
+    pub actions: usize,
+    pub external_sources: usize,
+    pub notebook_sync: usize,
+    pub mcp_servers: bool,
+    pub templates: usize,
+}
+
+impl AppConfigBundle {
+    /// An empty bundle for `library`
+    pub fn new(library: &Library) -> Self {
+        Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            library: LibraryConfig::from_library(library),
+            settings: BTreeMap::new(),
+            actions: Vec::new(),
+            external_sources: Vec::new(),
+            notebook_sync: Vec::new(),
+            mcp_servers: None,
+            templates: Vec::new(),
+        }
+    }
+
+    /// Parse a bundle, refusing other files and newer versions
+    pub fn from_json(json: &str) -> AppConfigResult<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if value.get("format").and_then(|f| f.as_str()) != Some(BUNDLE_FORMAT) {
+            return Err(AppConfigError::UnknownFormat);
+        }
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        if version > BUNDLE_VERSION {
+            return Err(AppConfigError::UnsupportedVersion(version));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn to_json(&self) -> AppConfigResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Add the library's custom actions
+    pub fn add_actions(&mut self, actions: impl IntoIterator<Item = Action>) {
+        self.actions.extend(
+            actions
+                .into_iter()
+                .filter(|action| !action.is_built_in)
+                .map(|mut action| {
+                    action.last_run = None;
+                    action.next_run = None;
+                    action
+                }),
+        );
+    }
+
+    pub fn add_external_sources(&mut self, sources: impl IntoIterator<Item = ExternalSource>) {
+        self.external_sources
+            .extend(sources.into_iter().map(|mut source| {
+                source.last_processed = None;
+                source.processed_files.clear();
+                source
+            }));
+    }
+
+    pub fn add_notebook_sync(
+        &mut self,
+        notebook_id: Uuid,
+        notebook_name: &str,
+        config: &SyncConfig,
+    ) {
+        let mut config = config.clone();
+        config.last_sync = None;
+        self.notebook_sync.push(NotebookSyncEntry {
+            notebook_id,
+            notebook_name: notebook_name.to_string(),
+            config,
+        });
+    }
+
+    pub fn counts(&self) -> AppConfigCounts {
+        AppConfigCounts {
+            settings: self.settings.len(),
+            actions: self.actions.len(),
+            external_sources: self.external_sources.len(),
+            notebook_sync: self.notebook_sync.len(),
+            mcp_servers: self.mcp_servers.is_some(),
+            templates: self.templates.len(),
+        }
+    }
+}
+
+/// Pick the notebook an exported sync config belongs to: the same id, or
+/// else the only notebook with the same name
+pub fn match_notebook<'a>(
+    entry: &NotebookSyncEntry,
+    notebooks: &'a [(Uuid, String)],
+) -> Option<&'a Uuid> {
+    if let Some((id, _)) = notebooks.iter().find(|(id, _)| *id == entry.notebook_id) {
+        return Some(id);
+    }
+    let mut by_name = notebooks
+        .iter()
+        .filter(|(_, name)| *name == entry.notebook_name);
+    match (by_name.next(), by_name.next()) {
+        (Some((id, _)), None) => Some(id),
+        _ => None,
+    }
+}
+
+/// The library's MCP server config, if it has one
+pub fn read_mcp_servers(library_path: &Path) -> AppConfigResult<Option<serde_json::Value>> {
+    let path = library_path.join(MCP_CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+pub fn write_mcp_servers(library_path: &Path, config: &serde_json::Value) -> AppConfigResult<()> {
+    fs::create_dir_all(library_path)?;
+    fs::write(
+        library_path.join(MCP_CONFIG_FILE),
+        serde_json::to_string_pretty(config)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn library() -> Library {
+        Library::new("Work".to_string(), PathBuf::from("/tmp/work"))
+    }
+
+    #[test]
+    fn bundle_round_trip_drops_device_state() {
+        let mut bundle = AppConfigBundle::new(&library());
+
+        let mut custom = Action::new("Weekly review".to_string(), String::new());
+        custom.last_run = Some(Utc::now());
+        let mut builtin = Action::new("Daily note".to_string(), String::new());
+        builtin.is_built_in = true;
+        bundle.add_actions(vec![custom, builtin]);
+
+        let mut source = ExternalSource::new("Research".to_string(), "~/research/*.md".to_string());
+        source.last_processed = Some(Utc::now());
+        bundle.add_external_sources(vec![source]);
+
+        let config = SyncConfig {
+            enabled: true,
+            server_url: "https://dav.example.com".to_string(),
+            last_sync: Some(Utc::now()),
+            ..Default::default()
+        };
+        bundle.add_notebook_sync(Uuid::new_v4(), "Journal", &config);
+        bundle
+            .templates
+            .push(json!({ "id": "t1", "name": "Standup" }));
+
+        let parsed = AppConfigBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.library.name, "Work");
+        assert_eq!(parsed.actions.len(), 1);
+        assert_eq!(parsed.actions[0].last_run, None);
+        assert_eq!(parsed.external_sources[0].last_processed, None);
+        assert_eq!(parsed.notebook_sync[0].config.last_sync, None);
+        assert!(parsed.notebook_sync[0].config.enabled);
+        assert_eq!(
+            parsed.counts(),
+            AppConfigCounts {
+                actions: 1,
+                external_sources: 1,
+                notebook_sync: 1,
+                templates: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_other_files_and_newer_versions() {
+        assert!(matches!(
+            AppConfigBundle::from_json(r#"{"servers": []}"#),
+            Err(AppConfigError::UnknownFormat)
+        ));
+
+        let mut bundle = serde_json::to_value(AppConfigBundle::new(&library())).unwrap();
+        bundle["version"] = json!(BUNDLE_VERSION + 1);
+        assert!(matches!(
+            AppConfigBundle::from_json(&bundle.to_string()),
+            Err(AppConfigError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn matches_notebooks_by_id_then_unique_name() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let notebooks = vec![
+            (a, "Journal".to_string()),
+            (b, "Projects".to_string()),
+            (c, "Projects".to_string()),
+        ];
+        let entry = |id, name: &str| NotebookSyncEntry {
+            notebook_id: id,
+            notebook_name: name.to_string(),
+            config: SyncConfig::default(),
+        };
+
+        assert_eq!(match_notebook(&entry(b, "Renamed"), &notebooks), Some(&b));
+        assert_eq!(
+            match_notebook(&entry(Uuid::new_v4(), "Journal"), &notebooks),
+            Some(&a)
+        );
+        assert_eq!(
+            match_notebook(&entry(Uuid::new_v4(), "Projects"), &notebooks),
+            None
+        );
+    }
+
+    #[test]
+    fn mcp_config_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(read_mcp_servers(dir.path()).unwrap().is_none());
+
+        let config = json!({ "servers": [{ "name": "fs", "command": "mcp-fs" }] });
+        write_mcp_servers(dir.path(), &config).unwrap();
+        assert_eq!(read_mcp_servers(dir.path()).unwrap(), Some(config));
+    }
+}
@@ -0,0 +1,435 @@
+//! Turning accepted related-page suggestions into wiki-links
+//!
+//! Each accepted target is linked where the page already mentions it: the
+//! first whole-word occurrence of its title in a paragraph, list, checklist
+//! or quote is wrapped in a wiki-link. Targets the page never mentions are
+//! listed under a "Related" heading at the end, created if missing. Targets
+//! the page already links to are left alone, so applying the same
+//! suggestions twice changes nothing.
+//!
+//! Title matching ignores ASCII case only, and skips text inside existing
+//! links and code.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::storage::EditorBlock;
+
+/// Heading of the section unmentioned targets are listed under
+pub const RELATED_SECTION: &str = "Related";
+
+/// Shorter titles are too likely to match unrelated words to link inline
+const MIN_INLINE_TITLE_CHARS: usize = 3;
+
+/// Block types whose text is searched for mentions
+const TEXT_BLOCKS: &[&str] = &["paragraph", "list", "checklist", "quote"];
+
+/// Elements whose text is never turned into a link
+const SKIPPED_ELEMENTS: &[&str] = &["a", "wiki-link", "code"];
+
+/// A page to link to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkTarget {
+    pub page_id: Uuid,
+    pub title: String,
+}
+
+/// The accepted suggestions for one page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageLinks {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub targets: Vec<LinkTarget>,
+}
+
+/// Where each target of a page ended up
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkInsertion {
+    /// Linked at a mention in the text
+    pub inline: Vec<Uuid>,
+    /// Listed under the "Related" heading
+    pub related: Vec<Uuid>,
+    /// Already linked from the page; nothing was added
+    pub existing: Vec<Uuid>,
+}
+
+impl LinkInsertion {
+    pub fn is_empty(&self) -> bool {
+        self.inline.is_empty() && self.related.is_empty()
+    }
+}
+
+pub(crate) fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// How the title appears in block HTML (quotes aren't escaped in text)
+fn escape_text(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub(crate) fn wiki_link(target: &LinkTarget, label: &str) -> String {
+    format!(
+        "<wiki-link data-page-title=\"{}\" data-page-id=\"{}\">{}</wiki-link>",
+        html_escape(&target.title),
+        target.page_id,
+        label
+    )
+}
+
+fn new_block(block_type: &str, data: Value) -> EditorBlock {
+    EditorBlock {
+        id: Uuid::new_v4().to_string(),
+        block_type: block_type.to_string(),
+        data,
+    }
+}
+
+fn collect_strings<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) => out.push(s),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Whether any block already links to `target`, by id or by title
+fn is_linked(blocks: &[EditorBlock], target: &LinkTarget) -> bool {
+    let by_id = format!("data-page-id=\"{}\"", target.page_id);
+    let by_title = format!("data-page-title=\"{}\"", html_escape(&target.title)).to_lowercase();
+    let mut strings = Vec::new();
+    for block in blocks {
+        collect_strings(&block.data, &mut strings);
+    }
+    strings
+        .iter()
+        .any(|s| s.contains(&by_id) || s.to_lowercase().contains(&by_title))
+}
+
+/// Tag name of the tag starting at `tag` (which begins with `<`), and
+/// whether it closes an element
+fn tag_name(tag: &str) -> (String, bool) {
+    let inner = tag.trim_start_matches('<');
+    let closing = inner.starts_with('/');
+    let name = inner
+        .trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    (name, closing)
+}
+
+/// Wrap the first whole-word mention of `needle` in `html` that isn't inside
+/// a link or code, or `None` if there is none
+fn link_first_mention(html: &str, needle: &str, target: &LinkTarget) -> Option<String> {
+    let mut skip_depth = 0usize;
+    let mut chars = html.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '<' {
+            let end = html[i..].find('>').map_or(html.len(), |e| i + e + 1);
+            let tag = &html[i..end];
+            let (name, closing) = tag_name(tag);
+            if SKIPPED_ELEMENTS.contains(&name.as_str()) {
+                if closing {
+                    skip_depth = skip_depth.saturating_sub(1);
+                } else if !tag.ends_with("/>") {
+                    skip_depth += 1;
+                }
+            }
+            while chars.peek().is_some_and(|&(j, _)| j < end) {
+                chars.next();
+            }
+            continue;
+        }
+        if skip_depth > 0 {
+            continue;
+        }
+        let Some(candidate) = html.get(i..i + needle.len()) else {
+            continue;
+        };
+        if !candidate.eq_ignore_ascii_case(needle) {
+            continue;
+        }
+        let before = html[..i].chars().next_back();
+        let after = html[i + needle.len()..].chars().next();
+        if before.is_some_and(char::is_alphanumeric) || after.is_some_and(char::is_alphanumeric) {
+            continue;
+        }
+        return Some(format!(
+            "{}{}{}",
+            &html[..i],
+            wiki_link(target, candidate),
+            &html[i + needle.len()..]
+        ));
+    }
+    None
+}
+
+/// Try `link` on every text field of a list or checklist item, including
+/// nested items
+fn link_in_items(items: &mut [Value], link: &dyn Fn(&str) -> Option<String>) -> bool {
+    for item in items {
+        match item {
+            Value::String(text) => {
+                if let Some(linked) = link(text) {
+                    *text = linked;
+                    return true;
+                }
+            }
+            Value::Object(map) => {
+                for key in ["content", "text"] {
+                    if let Some(Value::String(text)) = map.get_mut(key) {
+                        if let Some(linked) = link(text) {
+                            *text = linked;
+                            return true;
+                        }
+                    }
+                }
+                if let Some(Value::Array(nested)) = map.get_mut("items") {
+                    if link_in_items(nested, link) {
+                        return true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Link the first mention of `target` in the page's text blocks
+fn link_inline(blocks: &mut [EditorBlock], target: &LinkTarget) -> bool {
+    let title = target.title.trim();
+    if title.chars().count() < MIN_INLINE_TITLE_CHARS {
+        return false;
+    }
+    let needle = escape_text(title);
+    let link = |html: &str| link_first_mention(html, &needle, target);
+
+    for block in blocks
+        .iter_mut()
+        .filter(|b| TEXT_BLOCKS.contains(&b.block_type.as_str()))
+    {
+        if let Some(Value::String(text)) = block.data.get_mut("text") {
+            if let Some(linked) = link(text) {
+                *text = linked;
+                return true;
+            }
+        }
+        if let Some(Value::Array(items)) = block.data.get_mut("items") {
+            if link_in_items(items, &link) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn header_level(block: &EditorBlock) -> Option<u64> {
+    if block.block_type != "header" {
+        return None;
+    }
+    Some(
+        block
+            .data
+            .get("level")
+            .and_then(|l| l.as_u64())
+            .unwrap_or(2),
+    )
+}
+
+/// Add a list of links to `targets` at the end of the "Related" section
+fn add_related_links(blocks: &mut Vec<EditorBlock>, targets: &[&LinkTarget]) {
+    let items: Vec<String> = targets
+        .iter()
+        .map(|target| wiki_link(target, &escape_text(&target.title)))
+        .collect();
+    let list = new_block(
+        "list",
+        serde_json::json!({ "style": "unordered", "items": items }),
+    );
+
+    let section = blocks.iter().position(|b| {
+        header_level(b).is_some()
+            && b.data
+                .get("text")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(RELATED_SECTION))
+    });
+    let Some(start) = section else {
+        blocks.push(new_block(
+            "header",
+            serde_json::json!({ "text": RELATED_SECTION, "level": 2 }),
+        ));
+        blocks.push(list);
+        return;
+    };
+    let level = header_level(&blocks[start]).unwrap_or(2);
+    let end = blocks[start + 1..]
+        .iter()
+        .position(|b| header_level(b).is_some_and(|l| l <= level))
+        .map_or(blocks.len(), |i| start + 1 + i);
+    blocks.insert(end, list);
+}
+
+/// Link `targets` from the page with `blocks`. A target the page links to
+/// itself (`page_id`) is ignored.
+pub fn insert_links(
+    blocks: &mut Vec<EditorBlock>,
+    page_id: Uuid,
+    targets: &[LinkTarget],
+) -> LinkInsertion {
+    let mut insertion = LinkInsertion::default();
+    let mut unmentioned = Vec::new();
+    for target in targets {
+        if target.page_id == page_id
+            || insertion.inline.contains(&target.page_id)
+            || unmentioned
+                .iter()
+                .any(|t: &&LinkTarget| t.page_id == target.page_id)
+        {
+            continue;
+        }
+        if is_linked(blocks, target) {
+            insertion.existing.push(target.page_id);
+        } else if link_inline(blocks, target) {
+            insertion.inline.push(target.page_id);
+        } else {
+            unmentioned.push(target);
+        }
+    }
+    if !unmentioned.is_empty() {
+        add_related_links(blocks, &unmentioned);
+        insertion.related = unmentioned.iter().map(|t| t.page_id).collect();
+    }
+    insertion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paragraph(text: &str) -> EditorBlock {
+        new_block("paragraph", json!({ "text": text }))
+    }
+
+    fn target(title: &str) -> LinkTarget {
+        LinkTarget {
+            page_id: Uuid::new_v4(),
+            title: title.to_string(),
+        }
+    }
+
+    fn text(block: &EditorBlock) -> &str {
+        block.data["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn links_the_first_whole_word_mention() {
+        let rust = target("Rust");
+        let mut blocks = vec![
+            paragraph("Trusty tools"),
+            paragraph("We moved to <b>rust</b> last year. Rust is fast."),
+        ];
+        let insertion = insert_links(&mut blocks, Uuid::new_v4(), std::slice::from_ref(&rust));
+
+        assert_eq!(insertion.inline, vec![rust.page_id]);
+        assert_eq!(text(&blocks[0]), "Trusty tools");
+        assert_eq!(
+            text(&blocks[1]),
+            format!(
+                "We moved to <b><wiki-link data-page-title=\"Rust\" data-page-id=\"{}\">rust</wiki-link></b> last year. Rust is fast.",
+                rust.page_id
+            )
+        );
+    }
+
+    #[test]
+    fn skips_mentions_inside_links_and_code() {
+        let mut blocks = vec![
+            paragraph("<a href=\"https://x\">Tokio</a> and <code>Tokio</code>"),
+            new_block(
+                "list",
+                json!({ "style": "unordered", "items": ["Use Tokio here"] }),
+            ),
+        ];
+        let tokio = target("Tokio");
+        insert_links(&mut blocks, Uuid::new_v4(), std::slice::from_ref(&tokio));
+
+        assert!(!text(&blocks[0]).contains("wiki-link"));
+        assert!(blocks[1].data["items"][0]
+            .as_str()
+            .unwrap()
+            .contains(&format!(
+                "data-page-id=\"{}\">Tokio</wiki-link> here",
+                tokio.page_id
+            )));
+    }
+
+    #[test]
+    fn unmentioned_targets_go_under_related() {
+        let mut blocks = vec![
+            paragraph("Notes"),
+            new_block("header", json!({ "text": "Related", "level": 2 })),
+            paragraph("See also"),
+            new_block("header", json!({ "text": "Appendix", "level": 2 })),
+        ];
+        let (a, b) = (target("Alpha & Beta"), target("Gamma"));
+        let insertion = insert_links(&mut blocks, Uuid::new_v4(), &[a.clone(), b.clone()]);
+
+        assert_eq!(insertion.related, vec![a.page_id, b.page_id]);
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[3].block_type, "list");
+        let items = blocks[3].data["items"].as_array().unwrap();
+        assert!(items[0]
+            .as_str()
+            .unwrap()
+            .contains(">Alpha &amp; Beta</wiki-link>"));
+        assert_eq!(text(&blocks[4]), "Appendix");
+    }
+
+    #[test]
+    fn applying_twice_changes_nothing() {
+        let mut blocks = vec![paragraph("About Gardening")];
+        let targets = [target("gardening"), target("Compost")];
+        let first = insert_links(&mut blocks, Uuid::new_v4(), &targets);
+        assert!(!first.is_empty());
+        let after_first = blocks.clone();
+
+        let second = insert_links(&mut blocks, Uuid::new_v4(), &targets);
+        assert!(second.is_empty());
+        assert_eq!(second.existing.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&blocks).unwrap(),
+            serde_json::to_value(&after_first).unwrap()
+        );
+    }
+
+    #[test]
+    fn ignores_self_links_and_short_titles_inline() {
+        let page_id = Uuid::new_v4();
+        let own = LinkTarget {
+            page_id,
+            title: "Notes".to_string(),
+        };
+        let short = target("AI");
+        let mut blocks = vec![paragraph("Notes on AI")];
+        let insertion = insert_links(&mut blocks, page_id, &[own, short.clone()]);
+
+        assert!(insertion.inline.is_empty());
+        assert_eq!(insertion.related, vec![short.page_id]);
+        assert_eq!(text(&blocks[0]), "Notes on AI");
+    }
+}
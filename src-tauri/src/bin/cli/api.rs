@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, Request, State, WebSocketUpgrade, ws::{Message, WebSocket}},
-    http::{HeaderValue, Method, StatusCode},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
     middleware::{self, Next},
     response::{Html, IntoResponse},
     routing::{delete, get, post, put},
@@ -19,13 +19,21 @@ use uuid::Uuid;
 
 use super::auth::{ApiKeySet, Scope};
 
-use nous_lib::commands::{create_daily_note_core, find_daily_note, list_daily_notes_core};
+use nous_lib::block_ops::{self, BlockEdit, BlockOpError, TocStyle};
+use nous_lib::deep_link::page_deep_link;
+use nous_lib::clipper::{simplified_html_to_markdown, ClipTarget, ClipperClientInfo};
+use nous_lib::commands::{
+    create_daily_note_core, create_daily_note_with_rollover, find_daily_note,
+    list_daily_notes_core, DailyNoteRollover,
+};
+use nous_lib::history::HistoryStorage;
 use nous_lib::inbox::{CaptureRequest, CaptureSource};
 use nous_lib::markdown::{export_page_to_markdown, import_markdown_to_page, parse_markdown_to_blocks};
 use nous_lib::python_bridge::{
     AIConfig, ChatMessage, NotebookInfo, PageContext, PageInfo, PageSummaryInput, PythonAI,
     StreamEvent,
 };
+use nous_lib::search::{rank_hits, RankingContext, RankingWeights};
 use nous_lib::share::html_gen::{generate_share_site, render_share_html};
 use nous_lib::share::publish;
 use nous_lib::share::storage::{
@@ -33,7 +41,10 @@ use nous_lib::share::storage::{
 };
 use nous_lib::plugins::api::HostApi;
 use nous_lib::git;
-use nous_lib::storage::{EditorBlock, EditorData, FileStorageMode, Notebook, NotebookType, Page, PageType, SystemPromptMode};
+use nous_lib::storage::{
+    EditorBlock, EditorData, FileStorage, FileStorageMode, Notebook, NotebookType, Page,
+    PageSort, PageType, StorageError, SystemPromptMode,
+};
 
 use super::daemon::DaemonState;
 use nous_lib::events::AppEvent;
@@ -49,6 +60,10 @@ fn emit_event(state: &DaemonState, event: &str, data: serde_json::Value) {
 /// fast-path cost when RAG is off is one async-trait dispatch + a
 /// config read lock, no network.
 fn spawn_rag_index(state: &Arc<DaemonState>, page: &Page) {
+    if page.is_encrypted() {
+        spawn_rag_delete(state, page.id);
+        return;
+    }
     let rag = Arc::clone(&state.rag);
     let page_id = page.id;
     let page_text = plain_text_for_page(page);
@@ -203,9 +218,60 @@ struct InsertAfterBlockRequest {
     blocks: Option<Vec<EditorBlock>>,
 }
 
+#[derive(Deserialize)]
+struct MoveBlocksRequest {
+    block_ids: Vec<String>,
+    /// The same page to reorder the blocks within it
+    target_page_id: String,
+    /// Index in the target page to insert at (default: the end)
+    position: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ExtractBlocksRequest {
+    block_ids: Vec<String>,
+    /// Title of the new page (default: the text of the first block)
+    title: Option<String>,
+    /// Leave a link to the new page where the blocks were (default: true)
+    leave_link: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct MergePageRequest {
+    target_page_id: String,
+}
+
+#[derive(Deserialize)]
+struct SplitByHeadingsRequest {
+    /// Split at headings of this level and above, 1 or 2 (default: 2)
+    max_level: Option<u8>,
+    /// "links" (default) or "transclusions"
+    #[serde(default)]
+    toc_style: TocStyle,
+}
+
+/// Pages written by a move/extract/merge/split
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockEditResponse {
+    /// Changed pages, including those whose links were updated
+    updated: Vec<Page>,
+    /// Page an extract created
+    created: Option<Page>,
+    /// Pages a split created, in page order
+    subpages: Vec<Page>,
+    /// Page a merge moved to trash
+    trashed_page_id: Option<Uuid>,
+}
+
 #[derive(Deserialize)]
 struct CreateDailyNoteRequest {
     template_id: Option<String>,
+    /// Carry unchecked items of recent daily notes into a new note for today
+    #[serde(default)]
+    rollover: bool,
+    rollover_lookback_days: Option<u32>,
+    rollover_after_section: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -215,6 +281,37 @@ struct InboxCaptureRequest {
     tags: Option<Vec<String>>,
 }
 
+#[derive(Deserialize)]
+struct ClipperPairRequest {
+    code: String,
+    /// Shown in the paired-clients list (e.g. "Firefox on laptop")
+    client_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WebClipRequest {
+    url: String,
+    title: Option<String>,
+    /// Markdown (highest priority, e.g. from Turndown in the extension)
+    markdown: Option<String>,
+    /// Simplified article HTML or the selected fragment
+    html: Option<String>,
+    /// Plain-text selection (lowest priority)
+    selection: Option<String>,
+    site_name: Option<String>,
+    tags: Option<Vec<String>>,
+    /// Save straight into this notebook instead of routing to the inbox
+    notebook_id: Option<String>,
+    folder_id: Option<String>,
+    /// Clip even if this URL was clipped before
+    allow_duplicate: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct ClipCheckQuery {
+    url: String,
+}
+
 #[derive(Deserialize)]
 struct RecordProgressRequest {
     date: String,
@@ -242,16 +339,26 @@ struct SearchQuery {
     notebook_id: Option<String>,
     limit: Option<usize>,
     /// When `1`, use Tantivy's FuzzyTermQuery on the title field
-    /// (autocomplete-friendly; edit distance 2). Otherwise, use the
-    /// standard QueryParser across title/content/tags. Only meaningful
-    /// for `mode=keyword` (the default).
+    /// (autocomplete-friendly; edit distance 2), ranked with the library's
+    /// ranking boosts. Otherwise, use the standard QueryParser across
+    /// title/content/tags. Only meaningful for `mode=keyword` (the default).
     fuzzy: Option<u8>,
+    /// Notebook the user is in, boosted by fuzzy ranking (unlike
+    /// `notebook_id`, it doesn't filter)
+    current_notebook_id: Option<String>,
     /// Search backend: "keyword" (default, Tantivy), "semantic" (RAG
     /// embeddings — requires RAG configured), "hybrid" (Tantivy
     /// candidates reranked by RAG embeddings).
     mode: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct TranscriptSearchQuery {
+    q: String,
+    notebook_id: Option<String>,
+    limit: Option<usize>,
+}
+
 #[derive(Deserialize)]
 struct ResolvePageQuery {
     title: String,
@@ -302,9 +409,11 @@ struct UpdateNotebookRequest {
     system_prompt_mode: Option<String>,
     ai_provider: Option<String>,
     ai_model: Option<String>,
+    ai_profile_id: Option<String>,
     is_pinned: Option<bool>,
     page_sort_by: Option<String>,
     cover_image: Option<String>,
+    exclude_from_review: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -367,6 +476,9 @@ struct UpdateFolderRequest {
     color: Option<Option<String>>,
     #[serde(default, deserialize_with = "deserialize_optional_field")]
     section_id: Option<Option<String>>,
+    /// null falls back to the section/notebook sort
+    #[serde(default, deserialize_with = "deserialize_optional_field")]
+    page_sort_by: Option<Option<String>>,
 }
 
 #[derive(Deserialize)]
@@ -415,6 +527,11 @@ struct ReorderPagesRequest {
     page_ids: Vec<String>,
 }
 
+#[derive(Deserialize)]
+struct PinPageRequest {
+    pinned: bool,
+}
+
 #[derive(Deserialize)]
 struct ReorderFoldersRequest {
     /// Optional parent folder context; null means top-level folders.
@@ -439,6 +556,21 @@ struct MergeTagsRequest {
     into: String,
 }
 
+#[derive(Deserialize)]
+struct TagPagesQuery {
+    tag: String,
+    /// Include pages tagged below `tag` (`project/alpha` for `project`).
+    /// Defaults to true.
+    include_children: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct TestTagRuleRequest {
+    rule: nous_lib::tags::TagRule,
+    /// Max pages to return (default 100)
+    limit: Option<usize>,
+}
+
 /// Body for `POST /api/search/rag/configure`. Same shape as the
 /// `[search.rag]` table in `daemon-config.toml` — pass exactly the
 /// fields you want to set; missing fields keep their current values
@@ -508,6 +640,11 @@ fn is_public_route(path: &str) -> bool {
         || path.starts_with("/gallery/")
         || path.starts_with("/finance/")
         || path.starts_with("/api/image-cache/")
+        // Browser-extension clipper: pairing is code-gated and clip/check
+        // validate their own clip_ tokens (see clipper_client)
+        || path == "/api/clipper/pair"
+        || path == "/api/clipper/clip"
+        || path == "/api/clipper/check"
         // The web bundle is static UI, not data; /api/* stays key-gated and
         // the homelab front door adds SSO on top.
         || path == "/app"
@@ -601,7 +738,10 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
 
     Router::new()
         .route("/api/status", get(get_status))
+        .route("/api/diagnostics/performance", get(get_performance_report))
+        .route("/api/diagnostics/performance/reset", post(reset_performance_metrics))
         .route("/api/search", get(search_pages))
+        .route("/api/search/transcripts", get(search_transcripts))
         .route("/api/search/rebuild", post(rebuild_search_index))
         .route("/api/search/rag/configure", post(rag_configure))
         .route("/api/search/rag/reindex", post(rag_reindex))
@@ -632,6 +772,22 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
             "/api/notebooks/{notebook_id}/pages/{page_id}/append",
             post(append_to_page),
         )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/encrypt",
+            post(encrypt_page),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/decrypt",
+            post(decrypt_page),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/unlock",
+            post(unlock_page),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/lock",
+            post(lock_page),
+        )
         .route(
             "/api/notebooks/{notebook_id}/pages/{page_id}/publish-nous",
             post(publish_page_to_nous),
@@ -660,6 +816,22 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
             "/api/notebooks/{notebook_id}/pages/{page_id}/insert-after-block",
             post(insert_after_block),
         )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/move-blocks",
+            post(move_blocks),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/extract-blocks",
+            post(extract_blocks),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/merge-into",
+            post(merge_page_into),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/split-by-headings",
+            post(split_page_by_headings),
+        )
         .route(
             "/api/notebooks/{notebook_id}/pages/{page_id}/versions",
             get(list_page_versions),
@@ -686,6 +858,15 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
         )
         .route("/api/inbox", get(list_inbox))
         .route("/api/inbox", post(capture_inbox))
+        .route("/api/clipper/pairing", post(start_clipper_pairing))
+        .route("/api/clipper/pair", post(complete_clipper_pairing))
+        .route("/api/clipper/clients", get(list_clipper_clients))
+        .route(
+            "/api/clipper/clients/{client_id}",
+            delete(revoke_clipper_client),
+        )
+        .route("/api/clipper/check", get(check_web_clip))
+        .route("/api/clipper/clip", post(clip_web_content))
         .route("/api/goals", get(list_goals))
         .route("/api/goals/summary", get(get_goals_summary))
         .route("/api/goals/{goal_id}", get(get_goal))
@@ -697,6 +878,14 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
         .route("/api/energy/checkins", get(get_energy_checkins))
         .route("/api/energy/patterns", get(get_energy_patterns))
         .route("/api/sync/trigger", post(trigger_sync))
+        .route(
+            "/api/notebooks/{notebook_id}/folder-sync",
+            get(get_folder_sync).post(enable_folder_sync).delete(disable_folder_sync),
+        )
+        .route(
+            "/api/notebooks/{notebook_id}/folder-sync/run",
+            post(run_folder_sync),
+        )
         // Databases
         .route(
             "/api/notebooks/{notebook_id}/databases",
@@ -749,6 +938,14 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
         // GET is always redacted; POST merges + persists.
         .route("/api/ai/config", get(ai_get_config))
         .route("/api/ai/configure", post(ai_configure))
+        // OS automation verbs (Shortcuts, AppleScript, PowerShell)
+        .route("/api/automation/note", post(automation_create_note))
+        .route(
+            "/api/automation/daily-note/append",
+            post(automation_append_daily_note),
+        )
+        .route("/api/automation/search", get(automation_search))
+        .route("/api/automation/page-url", get(automation_page_url))
         // Agile Results daily note
         .route(
             "/api/notebooks/{notebook_id}/agile-daily",
@@ -829,6 +1026,10 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
             "/api/notebooks/{notebook_id}/pages/{page_id}/archive",
             post(archive_page),
         )
+        .route(
+            "/api/notebooks/{notebook_id}/pages/{page_id}/pin",
+            post(pin_page),
+        )
         .route(
             "/api/notebooks/{notebook_id}/pages/{page_id}/unarchive",
             post(unarchive_page),
@@ -864,6 +1065,23 @@ pub fn build_router(state: AppState, auth: AuthState) -> Router {
             "/api/notebooks/{notebook_id}/tags/{tag}/rename",
             post(rename_tag),
         )
+        // Library tag registry and hierarchy
+        .route(
+            "/api/tags/registry",
+            get(get_tag_registry).put(save_tag_definition),
+        )
+        .route(
+            "/api/tags/registry/{name}",
+            delete(delete_tag_definition),
+        )
+        .route("/api/tags/tree", get(get_tag_tree))
+        .route("/api/tags/pages", get(get_pages_by_tag))
+        .route(
+            "/api/tags/rules",
+            get(list_tag_rules).put(save_tag_rule),
+        )
+        .route("/api/tags/rules/test", post(test_tag_rule))
+        .route("/api/tags/rules/{rule_id}", delete(delete_tag_rule))
         // Inbox delete
         .route("/api/inbox/{item_id}", delete(delete_inbox_item))
         // WebSocket event stream
@@ -910,10 +1128,26 @@ async fn get_status() -> impl IntoResponse {
     })
 }
 
+/// Timing histograms for this daemon process (storage, sync, search,
+/// Python bridge). The desktop app reports its own via
+/// `get_performance_report`.
+async fn get_performance_report() -> impl IntoResponse {
+    Json(ApiResponse {
+        data: nous_lib::diagnostics::report("daemon"),
+    })
+}
+
+async fn reset_performance_metrics() -> impl IntoResponse {
+    nous_lib::diagnostics::reset();
+    Json(ApiResponse {
+        data: serde_json::json!({ "reset": true }),
+    })
+}
+
 async fn list_notebooks(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     match storage.list_notebooks() {
         Ok(notebooks) => Ok(Json(ApiResponse { data: notebooks })),
         Err(e) => Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
@@ -925,7 +1159,7 @@ async fn get_notebook(
     Path(notebook_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     match storage.get_notebook(nb_id) {
         Ok(notebook) => Ok(Json(ApiResponse { data: notebook })),
         Err(e) => Err(api_err(StatusCode::NOT_FOUND, e.to_string())),
@@ -942,7 +1176,7 @@ async fn create_notebook(
     };
 
     let notebook = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.write().unwrap();
         storage
             .create_notebook(req.name, nb_type)
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
@@ -964,7 +1198,7 @@ async fn update_notebook(
     let nb_id = parse_uuid(&notebook_id)?;
 
     let notebook = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.write().unwrap();
 
         let mut notebook: Notebook = storage
             .get_notebook(nb_id)
@@ -1002,6 +1236,9 @@ async fn update_notebook(
         if let Some(model) = req.ai_model {
             notebook.ai_model = if model.is_empty() { None } else { Some(model) };
         }
+        if let Some(profile_id) = req.ai_profile_id {
+            notebook.ai_profile_id = if profile_id.is_empty() { None } else { Some(profile_id) };
+        }
         if let Some(pinned) = req.is_pinned {
             notebook.is_pinned = pinned;
         }
@@ -1011,6 +1248,9 @@ async fn update_notebook(
         if let Some(img) = req.cover_image {
             notebook.cover_image = if img.is_empty() { None } else { Some(img) };
         }
+        if let Some(exclude) = req.exclude_from_review {
+            notebook.exclude_from_review = exclude;
+        }
         notebook.updated_at = chrono::Utc::now();
 
         storage
@@ -1044,7 +1284,7 @@ async fn delete_notebook(
     let nb_id = parse_uuid(&notebook_id)?;
 
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.write().unwrap();
         storage
             .delete_notebook(nb_id)
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -1064,8 +1304,8 @@ async fn list_pages(
     Path(notebook_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
-    match storage.list_pages(nb_id) {
+    let storage = state.storage.read().unwrap();
+    match storage.list_pages_in_order(nb_id) {
         Ok(pages) => Ok(Json(ApiResponse { data: pages })),
         Err(e) => Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
     }
@@ -1078,9 +1318,10 @@ async fn get_page(
 ) -> Result<axum::response::Response, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     match storage.get_page(nb_id, pg_id) {
         Ok(page) => {
+            let page = page_for_client(&state, page);
             if query.format.as_deref() == Some("markdown") {
                 let md = export_page_to_markdown(&page);
                 Ok(axum::response::Response::builder()
@@ -1101,7 +1342,7 @@ async fn resolve_page(
     Query(query): Query<ResolvePageQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let title_lower = query.title.to_lowercase();
 
     // Try as UUID first
@@ -1156,6 +1397,9 @@ async fn resolve_page(
     }
 }
 
+/// Fuzzy matches `/api/search?fuzzy=1` ranks before keeping `limit`
+const FUZZY_RANKING_CANDIDATES: usize = 200;
+
 async fn search_pages(
     State(state): State<AppState>,
     Query(query): Query<SearchQuery>,
@@ -1167,6 +1411,7 @@ async fn search_pages(
         Some(s) => Some(parse_uuid(s)?),
         None => None,
     };
+    let current_notebook = query.current_notebook_id.as_deref().map(parse_uuid).transpose()?;
 
     // Mode dispatch. The default (no `mode` param) preserves the
     // pre-RAG behavior — straight Tantivy keyword search.
@@ -1184,9 +1429,26 @@ async fn search_pages(
 
     let hits = match mode {
         nous_lib::search::SearchMode::Keyword => {
-            // Tantivy adapter respects the fuzzy flag.
+            // Tantivy adapter respects the fuzzy flag. Its fuzzy matches all
+            // score the same, so fetch a wider pool for the boosts to rank.
             if fuzzy {
-                state.tantivy.fuzzy_query(&query.q, limit, nb_filter).await
+                let candidates = limit.max(FUZZY_RANKING_CANDIDATES);
+                state
+                    .tantivy
+                    .fuzzy_query(&query.q, candidates, nb_filter)
+                    .await
+                    .map(|hits| {
+                        let weights = RankingWeights::load(&state.library_path);
+                        let history = HistoryStorage::new(state.library_path.clone()).ok();
+                        let context = RankingContext {
+                            current_notebook,
+                            now: None,
+                        };
+                        let mut hits =
+                            rank_hits(hits, &query.q, &weights, history.as_ref(), &context);
+                        hits.truncate(limit);
+                        hits
+                    })
             } else {
                 use nous_lib::search::SearchBackend;
                 state.tantivy.query(&query.q, limit, nb_filter).await
@@ -1265,6 +1527,29 @@ async fn search_pages(
     Ok(Json(ApiResponse { data: hits }))
 }
 
+/// `GET /api/search/transcripts?q=...` — search video transcripts at the
+/// segment level. Each hit carries the video block id and the segment's
+/// start/end seconds so the client can open the page and seek the player.
+async fn search_transcripts(
+    State(state): State<AppState>,
+    Query(query): Query<TranscriptSearchQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let limit = query.limit.unwrap_or(20);
+    let nb_filter: Option<Uuid> = match query.notebook_id.as_deref() {
+        Some(s) => Some(parse_uuid(s)?),
+        None => None,
+    };
+
+    let hits = {
+        let idx = lock_search_index(&state.search_index);
+        idx.search_transcripts(&query.q, limit, nb_filter).map_err(|e| {
+            api_err(StatusCode::INTERNAL_SERVER_ERROR, format!("Search error: {}", e))
+        })?
+    };
+
+    Ok(Json(ApiResponse { data: hits }))
+}
+
 /// Rebuild the Tantivy index from scratch by reindexing every non-deleted,
 /// non-archived page across every notebook. Useful after schema changes,
 /// corrupt segments, or when the daemon was offline during many writes.
@@ -1272,7 +1557,7 @@ async fn rebuild_search_index(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let pages = {
-        let storage = state.storage.lock().map_err(|e| {
+        let storage = state.storage.read().map_err(|e| {
             api_err(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Failed to acquire storage lock: {}", e),
@@ -1364,7 +1649,7 @@ async fn rag_configure(
         search: nous_lib::search::SearchSection {
             rag: current.clone(),
         },
-        ai: state.ai_config.read().await.clone(),
+        ai: state.ai_config.read().await.for_disk(),
     };
     if let Err(e) = nous_lib::search::save(&state.daemon_config_path, &to_persist) {
         log::warn!(
@@ -1387,7 +1672,7 @@ async fn rag_reindex(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     // Collect pages first, releasing the storage lock before async embedding work.
     let pages = {
-        let storage = state.storage.lock().map_err(|e| {
+        let storage = state.storage.read().map_err(|e| {
             api_err(StatusCode::INTERNAL_SERVER_ERROR, format!("storage lock: {}", e))
         })?;
         let notebooks = storage
@@ -1400,7 +1685,7 @@ async fn rag_reindex(
                 Err(_) => continue,
             };
             for page in nb_pages {
-                if page.deleted_at.is_some() || page.is_archived {
+                if page.deleted_at.is_some() || page.is_archived || page.is_encrypted() {
                     continue;
                 }
                 out.push(page);
@@ -1475,6 +1760,140 @@ async fn update_backup_settings(
     Ok(Json(ApiResponse { data: settings }))
 }
 
+// ===== Folder Sync =====
+
+#[derive(Deserialize)]
+struct EnableFolderSyncRequest {
+    path: String,
+}
+
+/// Refresh search, sync and clients for the pages a folder sync pass
+/// touched. Called for passes run from the API and from the background
+/// watcher alike.
+pub fn apply_folder_sync_report(
+    state: &Arc<DaemonState>,
+    report: &nous_lib::folder_sync::FolderSyncReport,
+) {
+    let nb_id = report.notebook_id;
+    let changed: Vec<(Page, &str)> = {
+        let storage = state.storage.read().unwrap();
+        report
+            .pages_created
+            .iter()
+            .map(|id| (id, "page.created"))
+            .chain(report.pages_updated.iter().map(|id| (id, "page.updated")))
+            .filter_map(|(id, event)| storage.get_page(nb_id, *id).ok().map(|p| (p, event)))
+            .collect()
+    };
+
+    for (page, event) in &changed {
+        state.sync_manager.queue_page_update(nb_id, page.id);
+        {
+            let mut idx = lock_search_index(&state.search_index);
+            if let Err(e) = idx.index_page(page) {
+                log::warn!("Failed to index page {}: {}", page.id, e);
+            }
+        }
+        spawn_rag_index(state, page);
+        emit_event(state, event, serde_json::json!({
+            "notebookId": nb_id.to_string(),
+            "pageId": page.id.to_string(),
+            "title": page.title,
+        }));
+    }
+
+    for page_id in &report.pages_trashed {
+        state.sync_manager.queue_page_update(nb_id, *page_id);
+        {
+            let mut idx = lock_search_index(&state.search_index);
+            if let Err(e) = idx.remove_page(*page_id) {
+                log::warn!("Failed to remove page {} from search index: {}", page_id, e);
+            }
+        }
+        spawn_rag_delete(state, *page_id);
+        emit_event(state, "page.deleted", serde_json::json!({
+            "notebookId": nb_id.to_string(),
+            "pageId": page_id.to_string(),
+        }));
+    }
+
+    if !report.conflicts.is_empty() {
+        emit_event(state, "folder_sync.conflict", serde_json::json!({
+            "notebookId": nb_id.to_string(),
+            "conflicts": report.conflicts,
+        }));
+    }
+}
+
+/// `GET /api/notebooks/:id/folder-sync` — folder sync status, or `null` if
+/// the notebook isn't folder-backed.
+async fn get_folder_sync(
+    State(state): State<AppState>,
+    Path(notebook_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let storage = state.storage.read().unwrap();
+    let status = nous_lib::folder_sync::folder_sync_status(&storage, nb_id);
+    Ok(Json(ApiResponse { data: status }))
+}
+
+/// `POST /api/notebooks/:id/folder-sync` — back the notebook with a folder
+/// and run the first pass.
+async fn enable_folder_sync(
+    State(state): State<AppState>,
+    Path(notebook_id): Path<String>,
+    Json(req): Json<EnableFolderSyncRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let report = {
+        let storage = state.storage.write().unwrap();
+        nous_lib::folder_sync::enable_folder_sync(&storage, nb_id, &req.path)
+            .map_err(|e| api_err(StatusCode::BAD_REQUEST, e.to_string()))?
+    };
+    apply_folder_sync_report(&state, &report);
+    emit_event(&state, "folder_sync.configured", serde_json::json!({
+        "notebookId": notebook_id,
+        "enabled": true,
+    }));
+    Ok(Json(ApiResponse { data: report }))
+}
+
+/// `DELETE /api/notebooks/:id/folder-sync` — stop syncing; pages and files
+/// are both kept.
+async fn disable_folder_sync(
+    State(state): State<AppState>,
+    Path(notebook_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    {
+        let storage = state.storage.write().unwrap();
+        nous_lib::folder_sync::disable_folder_sync(&storage, nb_id)
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    emit_event(&state, "folder_sync.configured", serde_json::json!({
+        "notebookId": notebook_id,
+        "enabled": false,
+    }));
+    Ok(Json(ApiResponse {
+        data: serde_json::json!({"ok": true}),
+    }))
+}
+
+/// `POST /api/notebooks/:id/folder-sync/run` — run a sync pass now.
+async fn run_folder_sync(
+    State(state): State<AppState>,
+    Path(notebook_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let report = {
+        let storage = state.storage.write().unwrap();
+        nous_lib::folder_sync::sync_notebook_folder(&storage, nb_id)
+            .map_err(|e| api_err(StatusCode::BAD_REQUEST, e.to_string()))?
+    };
+    apply_folder_sync_report(&state, &report);
+    Ok(Json(ApiResponse { data: report }))
+}
+
 /// Extract a plain-text body from a page for embedding. Mirrors the
 /// Tantivy `extract_text_from_blocks` shape but lives in api.rs so we
 /// don't drag SearchIndex internals into the RAG backend.
@@ -1612,7 +2031,7 @@ async fn create_page(
     Json(req): Json<CreatePageRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = match storage.create_page(nb_id, req.title) {
         Ok(p) => p,
@@ -1704,24 +2123,33 @@ async fn create_page(
         }
     }
 
+    nous_lib::tags::apply_tag_rules(&storage, &state.library_path, &mut page);
+
     if let Err(e) = storage.update_page(&page) {
         return Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
     }
 
+    after_page_created(&state, &page);
+
+    Ok((StatusCode::CREATED, Json(ApiResponse { data: page })))
+}
+
+/// Sync, index and announce a page that was just created and saved
+fn after_page_created(state: &AppState, page: &Page) {
     // Queue sync
-    state.sync_manager.queue_page_update(nb_id, page.id);
+    state.sync_manager.queue_page_update(page.notebook_id, page.id);
 
     // Index the new page (best-effort — log on failure, don't fail the request).
     {
         let mut idx = lock_search_index(&state.search_index);
-        if let Err(e) = idx.index_page(&page) {
+        if let Err(e) = idx.index_page(page) {
             log::warn!("Failed to index newly created page {}: {}", page.id, e);
         }
     }
 
     // Fire-and-forget RAG indexing if enabled. The HTTP response
     // returns immediately; embedding calls happen in the background.
-    spawn_rag_index(&state, &page);
+    spawn_rag_index(state, page);
 
     // Dispatch plugin OnPageCreated hook (background thread; never blocks).
     #[cfg(feature = "plugins")]
@@ -1729,19 +2157,17 @@ async fn create_page(
         &state.plugin_host,
         nous_lib::plugins::HookPoint::OnPageCreated,
         serde_json::json!({
-            "notebook_id": nb_id.to_string(),
+            "notebook_id": page.notebook_id.to_string(),
             "page_id": page.id.to_string(),
             "title": page.title,
         }),
     );
 
-    emit_event(&state, "page.created", serde_json::json!({
-        "notebookId": notebook_id,
+    emit_event(state, "page.created", serde_json::json!({
+        "notebookId": page.notebook_id.to_string(),
         "pageId": page.id.to_string(),
         "title": page.title,
     }));
-
-    Ok((StatusCode::CREATED, Json(ApiResponse { data: page })))
 }
 
 async fn update_page(
@@ -1751,7 +2177,7 @@ async fn update_page(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = match storage.get_page(nb_id, pg_id) {
         Ok(p) => p,
@@ -1778,13 +2204,25 @@ async fn update_page(
     };
 
     if let Some(content) = proposed_content {
-        let pane = req.pane_id.as_deref().unwrap_or("default");
-        match state.crdt_store.apply_save(pg_id, pane, &content) {
-            Ok(Some(canonical)) => page.content = canonical,
-            Ok(None) => page.content = content,
-            Err(e) => {
-                log::warn!("CRDT apply_save failed for page {}: {}", pg_id, e);
-                page.content = content;
+        if page.is_encrypted() {
+            // Encrypted pages skip the CRDT merge so the live document store
+            // never holds their plaintext
+            let key = state
+                .encryption_manager
+                .get_page_key(pg_id)
+                .map_err(|e| api_err(StatusCode::LOCKED, e.to_string()))?;
+            page.content = content;
+            nous_lib::encryption::reseal_page(&mut page, &key)
+                .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        } else {
+            let pane = req.pane_id.as_deref().unwrap_or("default");
+            match state.crdt_store.apply_save(pg_id, pane, &content) {
+                Ok(Some(canonical)) => page.content = canonical,
+                Ok(None) => page.content = content,
+                Err(e) => {
+                    log::warn!("CRDT apply_save failed for page {}: {}", pg_id, e);
+                    page.content = content;
+                }
             }
         }
     }
@@ -1839,6 +2277,7 @@ async fn update_page(
             "canvas" => PageType::Canvas,
             "database" => PageType::Database,
             "html" => PageType::Html,
+            "sql" => PageType::Sql,
             _ => PageType::Standard,
         };
     }
@@ -1895,6 +2334,7 @@ async fn update_page(
         page.plugin_data = pd_opt;
     }
 
+    nous_lib::tags::apply_tag_rules(&storage, &state.library_path, &mut page);
     page.updated_at = chrono::Utc::now();
 
     if let Err(e) = storage.update_page(&page) {
@@ -1908,16 +2348,8 @@ async fn update_page(
 
     state.sync_manager.queue_page_update(nb_id, pg_id);
 
-    // Reindex (best-effort).
-    {
-        let mut idx = lock_search_index(&state.search_index);
-        if let Err(e) = idx.index_page(&page) {
-            log::warn!("Failed to reindex page {}: {}", pg_id, e);
-        }
-    }
-
-    // Fire-and-forget RAG reindex if enabled.
-    spawn_rag_index(&state, &page);
+    // Reindex (best-effort), with a fire-and-forget RAG reindex if enabled.
+    reindex_page(&state, &page);
 
     // Plugin OnPageUpdated hook (background thread).
     #[cfg(feature = "plugins")]
@@ -1945,60 +2377,345 @@ async fn update_page(
         "title": page.title,
     }));
 
-    Ok(Json(ApiResponse { data: page }))
+    Ok(Json(ApiResponse { data: page_for_client(&state, page) }))
 }
 
-// ===== Version history (snapshots + oplog) =====
+// ===== Page encryption =====
+//
+// A single page can be encrypted inside an unencrypted notebook. Its
+// content is sealed on disk; while locked it is kept out of the search and
+// RAG indexes, and while unlocked it is indexed from its decrypted content.
 
-#[derive(Serialize)]
+#[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct VersionEntry {
-    /// Snapshot name (also its id for fetch/restore), e.g. "20260616_120000_123456_000001".
-    name: String,
-    ts: chrono::DateTime<chrono::Utc>,
-    block_count: usize,
-    content_hash: String,
-    oplog_entry_count: usize,
-    /// Oplog entries recorded AFTER this snapshot — up to the next snapshot, or to
-    /// the current head for the most recent one (edits not yet captured in a newer
-    /// snapshot). This is the "hybrid" oplog view: structure-only change counts.
-    changes_since: usize,
-    /// Short plaintext preview of the snapshot's content.
-    preview: String,
+struct EncryptPageRequest {
+    password: String,
+    #[serde(default)]
+    password_hint: Option<String>,
 }
 
-/// Pure: given each snapshot's `oplog_entry_count` (chronological, oldest first)
-/// and the current total oplog length, return how many oplog entries fall in the
-/// interval AFTER each snapshot (to the next snapshot, or to head for the last).
-fn changes_in_intervals(snapshot_oplog_counts: &[usize], total_oplog: usize) -> Vec<usize> {
-    let n = snapshot_oplog_counts.len();
-    (0..n)
-        .map(|i| {
-            let next = if i + 1 < n {
-                snapshot_oplog_counts[i + 1]
-            } else {
-                total_oplog
-            };
-            next.saturating_sub(snapshot_oplog_counts[i])
-        })
-        .collect()
+#[derive(Deserialize)]
+struct PagePasswordRequest {
+    password: String,
 }
 
-/// A snapshot name is generated as `%Y%m%d_%H%M%S_%6f_%06d` — digits and
-/// underscores only. Validating against that shape also blocks path traversal
-/// (no `/`, `\`, or `..`) before the name is used to build a file path.
-fn valid_snapshot_name(name: &str) -> bool {
-    !name.is_empty()
-        && name.len() <= 64
-        && name.chars().all(|c| c.is_ascii_digit() || c == '_')
+/// An encrypted page as a client should see it: decrypted while unlocked,
+/// otherwise as stored (empty content).
+fn page_for_client(state: &DaemonState, page: Page) -> Page {
+    if !page.is_encrypted() {
+        return page;
+    }
+    match state.encryption_manager.get_page_key(page.id) {
+        Ok(key) => nous_lib::encryption::unlocked_view(&page, &key).unwrap_or(page),
+        Err(_) => page,
+    }
 }
 
-/// Single-line, truncated plaintext preview of a page's content.
-fn snapshot_preview(page: &Page, max_chars: usize) -> String {
-    let collapsed: String = plain_text_for_page(page)
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ");
+/// Reindex a page in search and RAG. An encrypted page is indexed from its
+/// decrypted content while unlocked and dropped from both while locked.
+fn reindex_page(state: &Arc<DaemonState>, page: &Page) {
+    let mut indexed = page.clone();
+    if page.is_encrypted() {
+        let content = state
+            .encryption_manager
+            .get_page_key(page.id)
+            .ok()
+            .and_then(|key| nous_lib::encryption::decrypt_page_content(page, &key).ok());
+        let Some(content) = content else {
+            unindex_page(state, page.id);
+            return;
+        };
+        indexed.content = content;
+        indexed.encryption = None;
+    }
+    {
+        let mut idx = lock_search_index(&state.search_index);
+        if let Err(e) = idx.index_page(&indexed) {
+            log::warn!("Failed to reindex page {}: {}", page.id, e);
+        }
+    }
+    spawn_rag_index(state, &indexed);
+}
+
+fn unindex_page(state: &Arc<DaemonState>, page_id: Uuid) {
+    {
+        let mut idx = lock_search_index(&state.search_index);
+        if let Err(e) = idx.remove_page(page_id) {
+            log::warn!("Failed to remove page {} from search index: {}", page_id, e);
+        }
+    }
+    spawn_rag_delete(state, page_id);
+}
+
+/// Drop pages whose key auto-locked from the indexes. Run periodically.
+pub fn expire_unlocked_pages(state: &Arc<DaemonState>) {
+    for page_id in state.encryption_manager.expire_pages() {
+        unindex_page(state, page_id);
+    }
+}
+
+/// Remove every encrypted page from the indexes. Run at startup: keys don't
+/// survive a restart, so entries left from a previous unlock are stale.
+pub fn unindex_encrypted_pages(state: &Arc<DaemonState>) {
+    let page_ids: Vec<Uuid> = {
+        let storage = state.storage.read().unwrap();
+        storage
+            .list_notebooks()
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|nb| storage.list_pages(nb.id).unwrap_or_default())
+            .filter(|page| page.is_encrypted())
+            .map(|page| page.id)
+            .collect()
+    };
+    for page_id in page_ids {
+        unindex_page(state, page_id);
+    }
+}
+
+/// Argon2 is deliberately slow; keep it off the async workers.
+async fn run_kdf<T, F>(f: F) -> Result<T, (StatusCode, Json<ApiError>)>
+where
+    T: Send + 'static,
+    F: FnOnce() -> nous_lib::encryption::EncryptionResult<T> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(nous_lib::encryption::EncryptionError::InvalidPassword)) => {
+            Err(api_err(StatusCode::BAD_REQUEST, "Invalid password"))
+        }
+        Ok(Err(e)) => Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(e) => Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// Verify `password` against an encrypted page and return its key
+async fn page_key_from_password(
+    page: &Page,
+    password: String,
+) -> Result<nous_lib::encryption::EncryptionKey, (StatusCode, Json<ApiError>)> {
+    let config = match &page.encryption {
+        Some(encryption) if encryption.config.enabled => encryption.config.clone(),
+        _ => return Err(api_err(StatusCode::BAD_REQUEST, "Page is not encrypted")),
+    };
+    run_kdf(move || {
+        nous_lib::encryption::verify_password(&password, &config.salt, &config.verification_hash)
+    })
+    .await
+}
+
+/// Encrypt a page with its own password. The page is left locked.
+async fn encrypt_page(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<EncryptPageRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    use nous_lib::encryption::{
+        create_verification_hash, derive_key, generate_salt, Argon2Params, EncryptionConfig,
+    };
+
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+    if req.password.is_empty() {
+        return Err(api_err(StatusCode::BAD_REQUEST, "Password cannot be empty"));
+    }
+
+    let salt = generate_salt();
+    let key = {
+        let salt = salt.clone();
+        let password = req.password;
+        run_kdf(move || derive_key(&password, &salt, &Argon2Params::default())).await?
+    };
+    let hint = req.password_hint.filter(|h| !h.trim().is_empty());
+    let config = EncryptionConfig::new_page(salt, create_verification_hash(&key), hint);
+
+    let page = {
+        let storage = state.storage.write().unwrap();
+        let mut page = storage
+            .get_page(nb_id, pg_id)
+            .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?;
+        if page.is_encrypted() {
+            return Err(api_err(StatusCode::CONFLICT, "Page is already encrypted"));
+        }
+        if page.page_type != PageType::Standard {
+            return Err(api_err(
+                StatusCode::BAD_REQUEST,
+                "Only standard pages can be encrypted",
+            ));
+        }
+        nous_lib::encryption::encrypt_page(&mut page, &key, config)
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        page.updated_at = chrono::Utc::now();
+        storage
+            .update_page(&page)
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        // The oplog and snapshots hold the plaintext this just replaced
+        if let Err(e) = storage.purge_page_history(nb_id, pg_id) {
+            log::warn!("Failed to purge history of encrypted page {}: {}", pg_id, e);
+        }
+        page
+    };
+
+    state.sync_manager.queue_page_update(nb_id, pg_id);
+    unindex_page(&state, pg_id);
+    emit_event(&state, "page.encrypted", serde_json::json!({
+        "notebookId": notebook_id,
+        "pageId": page_id,
+    }));
+
+    Ok(Json(ApiResponse { data: page }))
+}
+
+/// Remove a page's encryption for good, restoring its plaintext content.
+async fn decrypt_page(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<PagePasswordRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+
+    let page = {
+        let storage = state.storage.read().unwrap();
+        storage
+            .get_page(nb_id, pg_id)
+            .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?
+    };
+    let key = page_key_from_password(&page, req.password).await?;
+
+    let page = {
+        let storage = state.storage.write().unwrap();
+        // Re-read: the page may have been edited while the key was derived
+        let mut page = storage
+            .get_page(nb_id, pg_id)
+            .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?;
+        nous_lib::encryption::decrypt_page(&mut page, &key)
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        page.updated_at = chrono::Utc::now();
+        storage
+            .update_page(&page)
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        page
+    };
+
+    state.encryption_manager.lock_page(pg_id);
+    state.sync_manager.queue_page_update(nb_id, pg_id);
+    reindex_page(&state, &page);
+    emit_event(&state, "page.decrypted", serde_json::json!({
+        "notebookId": notebook_id,
+        "pageId": page_id,
+    }));
+
+    Ok(Json(ApiResponse { data: page }))
+}
+
+/// Unlock an encrypted page for this daemon session and return it with its
+/// content decrypted. The page is indexed again until it is locked.
+async fn unlock_page(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<PagePasswordRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+
+    let page = {
+        let storage = state.storage.read().unwrap();
+        storage
+            .get_page(nb_id, pg_id)
+            .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?
+    };
+    let key = page_key_from_password(&page, req.password).await?;
+    state.encryption_manager.unlock_page(pg_id, key);
+
+    reindex_page(&state, &page);
+    emit_event(&state, "page.unlocked", serde_json::json!({
+        "notebookId": notebook_id,
+        "pageId": page_id,
+    }));
+
+    Ok(Json(ApiResponse { data: page_for_client(&state, page) }))
+}
+
+/// Forget an encrypted page's key and drop it from the indexes.
+async fn lock_page(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+
+    let page = {
+        let storage = state.storage.read().unwrap();
+        storage
+            .get_page(nb_id, pg_id)
+            .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?
+    };
+    if !page.is_encrypted() {
+        return Err(api_err(StatusCode::BAD_REQUEST, "Page is not encrypted"));
+    }
+
+    state.encryption_manager.lock_page(pg_id);
+    unindex_page(&state, pg_id);
+    emit_event(&state, "page.locked", serde_json::json!({
+        "notebookId": notebook_id,
+        "pageId": page_id,
+    }));
+
+    Ok(Json(ApiResponse { data: page }))
+}
+
+// ===== Version history (snapshots + oplog) =====
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionEntry {
+    /// Snapshot name (also its id for fetch/restore), e.g. "20260616_120000_123456_000001".
+    name: String,
+    ts: chrono::DateTime<chrono::Utc>,
+    block_count: usize,
+    content_hash: String,
+    oplog_entry_count: usize,
+    /// Oplog entries recorded AFTER this snapshot — up to the next snapshot, or to
+    /// the current head for the most recent one (edits not yet captured in a newer
+    /// snapshot). This is the "hybrid" oplog view: structure-only change counts.
+    changes_since: usize,
+    /// Short plaintext preview of the snapshot's content.
+    preview: String,
+}
+
+/// Pure: given each snapshot's `oplog_entry_count` (chronological, oldest first)
+/// and the current total oplog length, return how many oplog entries fall in the
+/// interval AFTER each snapshot (to the next snapshot, or to head for the last).
+fn changes_in_intervals(snapshot_oplog_counts: &[usize], total_oplog: usize) -> Vec<usize> {
+    let n = snapshot_oplog_counts.len();
+    (0..n)
+        .map(|i| {
+            let next = if i + 1 < n {
+                snapshot_oplog_counts[i + 1]
+            } else {
+                total_oplog
+            };
+            next.saturating_sub(snapshot_oplog_counts[i])
+        })
+        .collect()
+}
+
+/// A snapshot name is generated as `%Y%m%d_%H%M%S_%6f_%06d` — digits and
+/// underscores only. Validating against that shape also blocks path traversal
+/// (no `/`, `\`, or `..`) before the name is used to build a file path.
+fn valid_snapshot_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_digit() || c == '_')
+}
+
+/// Single-line, truncated plaintext preview of a page's content.
+fn snapshot_preview(page: &Page, max_chars: usize) -> String {
+    let collapsed: String = plain_text_for_page(page)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
     if collapsed.chars().count() <= max_chars {
         collapsed
     } else {
@@ -2021,7 +2738,7 @@ async fn list_page_versions(
     // Snapshots/oplog are immutable historical files; grab the paths under the
     // lock then read them lock-free so we don't block writers during disk I/O.
     let pages_dir = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         storage.get_notebook_path(nb_id).join("pages")
     };
 
@@ -2080,7 +2797,7 @@ async fn get_page_version(
     }
 
     let pages_dir = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         storage.get_notebook_path(nb_id).join("pages")
     };
     let snap_dir = nous_lib::storage::snapshots::snapshots_dir(&pages_dir, pg_id);
@@ -2109,7 +2826,7 @@ async fn restore_page_version(
         return Err(api_err(StatusCode::BAD_REQUEST, "Invalid snapshot name"));
     }
 
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     let pages_dir = storage.get_notebook_path(nb_id).join("pages");
     let snap_dir = nous_lib::storage::snapshots::snapshots_dir(&pages_dir, pg_id);
 
@@ -2210,7 +2927,7 @@ async fn append_to_page(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = match storage.get_page(nb_id, pg_id) {
         Ok(p) => p,
@@ -2272,7 +2989,7 @@ async fn publish_page_to_nous(
     // the async upload (never hold the lock across .await). Done before the
     // library lookup so a missing page fails fast with 404.
     let (html, record) = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         let page = storage
             .get_page(nb_id, pg_id)
             .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?;
@@ -2389,7 +3106,7 @@ async fn publish_folder_to_nous(
     // Render the mini-site under the storage lock, release before the upload.
     let allowlist = publish::parse_page_allowlist(&req.page_ids);
     let (site_dir, record) = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         let all_pages = storage
             .list_pages(nb_id)
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -2449,7 +3166,7 @@ async fn publish_section_to_nous(
 
     let allowlist = publish::parse_page_allowlist(&req.page_ids);
     let (site_dir, record) = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         let all_pages = storage
             .list_pages(nb_id)
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -2510,7 +3227,7 @@ async fn publish_notebook_to_nous(
 
     let allowlist = publish::parse_page_allowlist(&req.page_ids);
     let (site_dir, record) = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         let notebook = storage
             .get_notebook(nb_id)
             .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?;
@@ -2557,7 +3274,7 @@ async fn delete_block(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = match storage.get_page(nb_id, pg_id) {
         Ok(p) => p,
@@ -2594,7 +3311,7 @@ async fn replace_block(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = match storage.get_page(nb_id, pg_id) {
         Ok(p) => p,
@@ -2648,7 +3365,7 @@ async fn insert_after_block(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = match storage.get_page(nb_id, pg_id) {
         Ok(p) => p,
@@ -2696,12 +3413,184 @@ async fn insert_after_block(
     Ok(Json(ApiResponse { data: page }))
 }
 
+// ===== Structural block edits =====
+//
+// Moving blocks between pages, extracting them into a new page, merging
+// pages and splitting a page by headings (see `nous_lib::block_ops`). All
+// pages an edit touches are written together under one storage lock, or
+// none are.
+
+fn block_op_err(e: BlockOpError) -> (StatusCode, Json<ApiError>) {
+    let status = match &e {
+        BlockOpError::BlockNotFound(_) | BlockOpError::Storage(StorageError::PageNotFound(_)) => {
+            StatusCode::NOT_FOUND
+        }
+        BlockOpError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    api_err(status, e.to_string())
+}
+
+/// Write a planned edit. Pages open in an editor adopt their new content
+/// through the CRDT store, so the editor doesn't save the old blocks back.
+fn commit_block_edit(
+    state: &AppState,
+    storage: &nous_lib::storage::FileStorageWriter,
+    mut edit: BlockEdit,
+) -> Result<BlockEdit, (StatusCode, Json<ApiError>)> {
+    for page in edit.pages_mut() {
+        if page.deleted_at.is_some() {
+            continue;
+        }
+        match state.crdt_store.apply_save(page.id, "default", &page.content) {
+            Ok(Some(canonical)) => page.content = canonical,
+            Ok(None) => {}
+            Err(e) => log::warn!("CRDT apply_save failed for page {}: {}", page.id, e),
+        }
+    }
+    edit.commit(storage).map_err(block_op_err)?;
+    Ok(edit)
+}
+
+/// Sync, reindex and announce every page a committed edit wrote
+fn after_block_edit(state: &AppState, nb_id: Uuid, edit: &BlockEdit) -> BlockEditResponse {
+    let notebook_id = nb_id.to_string();
+    let mut response = BlockEditResponse {
+        updated: Vec::new(),
+        created: edit.created().first().cloned(),
+        subpages: Vec::new(),
+        trashed_page_id: None,
+    };
+    for page in edit.updated() {
+        state.sync_manager.queue_page_update(nb_id, page.id);
+        if page.deleted_at.is_some() {
+            unindex_page(state, page.id);
+            emit_event(state, "page.deleted", serde_json::json!({
+                "notebookId": notebook_id,
+                "pageId": page.id.to_string(),
+                "title": page.title,
+            }));
+            response.trashed_page_id = Some(page.id);
+        } else {
+            after_block_level_write(state, page, &notebook_id, &page.id.to_string());
+            response.updated.push(page.clone());
+        }
+    }
+    for page in edit.created() {
+        state.sync_manager.queue_page_update(nb_id, page.id);
+        reindex_page(state, page);
+        emit_event(state, "page.created", serde_json::json!({
+            "notebookId": notebook_id,
+            "pageId": page.id.to_string(),
+            "title": page.title,
+        }));
+    }
+    response
+}
+
+/// `POST /api/notebooks/{nb}/pages/{pg}/move-blocks` — move blocks to another
+/// page (or within this one); block refs to them follow.
+async fn move_blocks(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<MoveBlocksRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+    let target_id = parse_uuid(&req.target_page_id)?;
+    let storage = state.storage.write().unwrap();
+
+    let edit = block_ops::move_blocks(&storage, nb_id, pg_id, &req.block_ids, target_id, req.position)
+        .map_err(block_op_err)?;
+    let edit = commit_block_edit(&state, &storage, edit)?;
+    drop(storage);
+
+    Ok(Json(ApiResponse { data: after_block_edit(&state, nb_id, &edit) }))
+}
+
+/// `POST /api/notebooks/{nb}/pages/{pg}/extract-blocks` — move blocks into a
+/// new page next to this one, leaving a link to it in their place.
+async fn extract_blocks(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<ExtractBlocksRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+    let storage = state.storage.write().unwrap();
+
+    let edit = block_ops::extract_blocks(
+        &storage,
+        nb_id,
+        pg_id,
+        &req.block_ids,
+        req.title,
+        req.leave_link.unwrap_or(true),
+    )
+    .map_err(block_op_err)?;
+    let edit = commit_block_edit(&state, &storage, edit)?;
+    drop(storage);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse { data: after_block_edit(&state, nb_id, &edit) }),
+    ))
+}
+
+/// `POST /api/notebooks/{nb}/pages/{pg}/merge-into` — append this page to
+/// another and move it to trash; links to it and its subpages move over.
+async fn merge_page_into(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<MergePageRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+    let target_id = parse_uuid(&req.target_page_id)?;
+    let storage = state.storage.write().unwrap();
+
+    let edit = block_ops::merge_page_into(&storage, nb_id, pg_id, target_id).map_err(block_op_err)?;
+    let edit = commit_block_edit(&state, &storage, edit)?;
+    drop(storage);
+
+    Ok(Json(ApiResponse { data: after_block_edit(&state, nb_id, &edit) }))
+}
+
+/// `POST /api/notebooks/{nb}/pages/{pg}/split-by-headings` — turn each
+/// H1/H2 section into a subpage, leaving a table of contents on this page.
+async fn split_page_by_headings(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<SplitByHeadingsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+    let max_level = req.max_level.unwrap_or(2);
+    if !(1..=2).contains(&max_level) {
+        return Err(api_err(
+            StatusCode::BAD_REQUEST,
+            "max_level must be 1 or 2".to_string(),
+        ));
+    }
+    let storage = state.storage.write().unwrap();
+
+    let edit = block_ops::split_page_by_headings(&storage, nb_id, pg_id, max_level, req.toc_style)
+        .map_err(block_op_err)?;
+    let edit = commit_block_edit(&state, &storage, edit)?;
+    drop(storage);
+
+    let mut data = after_block_edit(&state, nb_id, &edit);
+    data.created = None;
+    data.subpages = edit.created().to_vec();
+    Ok((StatusCode::CREATED, Json(ApiResponse { data })))
+}
+
 async fn get_daily_note(
     State(state): State<AppState>,
     Path((notebook_id, date)): Path<(String, String)>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
 
     match find_daily_note(&storage, nb_id, &date) {
         Ok(Some(page)) => Ok(Json(ApiResponse { data: page })),
@@ -2725,7 +3614,7 @@ async fn list_daily_notes(
     Query(query): Query<ListDailyNotesQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let pages = list_daily_notes_core(
         &storage,
         nb_id,
@@ -2742,10 +3631,31 @@ async fn create_or_get_daily_note(
     body: Option<Json<CreateDailyNoteRequest>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let template_id = body.and_then(|b| b.template_id.clone());
-    let storage = state.storage.lock().unwrap();
+    let body = body.map(|Json(b)| b);
+    let template_id = body.as_ref().and_then(|b| b.template_id.clone());
+    let rollover = body.filter(|b| b.rollover).map(|b| DailyNoteRollover {
+        lookback_days: b.rollover_lookback_days,
+        insert_after_section: b.rollover_after_section,
+    });
+    let result = match rollover {
+        Some(rollover) => {
+            let executor = state.action_executor.lock().unwrap();
+            create_daily_note_with_rollover(
+                &state.storage,
+                &executor,
+                nb_id,
+                &date,
+                template_id,
+                &rollover,
+            )
+        }
+        None => {
+            let storage = state.storage.write().unwrap();
+            create_daily_note_core(&storage, nb_id, &date, template_id)
+        }
+    };
 
-    match create_daily_note_core(&storage, nb_id, &date, template_id) {
+    match result {
         Ok(page) => {
             state.sync_manager.queue_page_update(nb_id, page.id);
             Ok(Json(ApiResponse { data: page }))
@@ -2764,41 +3674,310 @@ async fn list_inbox(
     }
 }
 
-async fn capture_inbox(
+async fn capture_inbox(
+    State(state): State<AppState>,
+    Json(req): Json<InboxCaptureRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let inbox = state.inbox_storage.lock().unwrap();
+    let capture = CaptureRequest {
+        title: req.title,
+        content: req.content.unwrap_or_default(),
+        tags: req.tags,
+        source: Some(CaptureSource::Api {
+            source: "daemon-api".to_string(),
+        }),
+        auto_classify: None,
+    };
+
+    match inbox.capture(capture) {
+        Ok(item) => {
+            #[cfg(feature = "plugins")]
+            nous_lib::plugins::dispatch_plugin_event_bg(
+                &state.plugin_host,
+                nous_lib::plugins::HookPoint::OnInboxCaptured,
+                serde_json::json!({
+                    "item_id": item.id.to_string(),
+                    "title": item.title,
+                    "tags": item.tags,
+                }),
+            );
+            emit_event(&state, "inbox.captured", serde_json::json!({
+                "itemId": item.id.to_string(),
+                "title": item.title,
+            }));
+            Ok((StatusCode::CREATED, Json(ApiResponse { data: item })))
+        }
+        Err(e) => Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+// ===== Web clipper (browser extension) =====
+
+/// Resolve the paired extension behind a request's `Authorization: Bearer
+/// clip_...` token. Daemon API keys are deliberately not accepted here.
+fn clipper_client(
+    state: &DaemonState,
+    headers: &HeaderMap,
+) -> Result<ClipperClientInfo, (StatusCode, Json<ApiError>)> {
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            api_err(
+                StatusCode::UNAUTHORIZED,
+                "Missing clipper token. Pair the extension first",
+            )
+        })?;
+
+    let clipper = state.clipper_storage.lock().unwrap();
+    clipper
+        .authenticate(token.trim())
+        .ok_or_else(|| api_err(StatusCode::UNAUTHORIZED, "Invalid clipper token"))
+}
+
+/// Whether the page or inbox item an earlier clip produced still exists.
+fn clip_target_exists(state: &DaemonState, target: &ClipTarget) -> bool {
+    match target {
+        ClipTarget::Inbox { item_id } => state
+            .inbox_storage
+            .lock()
+            .unwrap()
+            .get_item(*item_id)
+            .is_ok(),
+        ClipTarget::Page {
+            notebook_id,
+            page_id,
+        } => state
+            .storage
+            .read()
+            .unwrap()
+            .get_page(*notebook_id, *page_id)
+            .map(|p| p.deleted_at.is_none())
+            .unwrap_or(false),
+    }
+}
+
+/// Earlier clip of `url` whose target still exists. Stale records (the
+/// page or inbox item was deleted since) are dropped.
+fn existing_clip(state: &DaemonState, url: &str) -> Option<nous_lib::clipper::ClipRecord> {
+    let record = state.clipper_storage.lock().unwrap().find_clip(url).cloned()?;
+    if clip_target_exists(state, &record.target) {
+        return Some(record);
+    }
+    let _ = state.clipper_storage.lock().unwrap().forget_clip(url);
+    None
+}
+
+async fn start_clipper_pairing(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let code = state.clipper_storage.lock().unwrap().start_pairing();
+    Ok((StatusCode::CREATED, Json(ApiResponse { data: code })))
+}
+
+async fn complete_clipper_pairing(
+    State(state): State<AppState>,
+    Json(req): Json<ClipperPairRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let mut clipper = state.clipper_storage.lock().unwrap();
+    let result = clipper
+        .complete_pairing(&req.code, req.client_name.as_deref().unwrap_or(""))
+        .map_err(|e| api_err(StatusCode::UNAUTHORIZED, e.to_string()))?;
+
+    emit_event(&state, "clipper.paired", serde_json::json!({
+        "clientId": result.client.id.to_string(),
+        "name": result.client.name,
+    }));
+    Ok(Json(ApiResponse { data: result }))
+}
+
+async fn list_clipper_clients(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let clients = state.clipper_storage.lock().unwrap().list_clients();
+    Ok(Json(ApiResponse { data: clients }))
+}
+
+async fn revoke_clipper_client(
+    State(state): State<AppState>,
+    Path(client_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let id = parse_uuid(&client_id)?;
+    let mut clipper = state.clipper_storage.lock().unwrap();
+    match clipper.revoke_client(id) {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(api_err(StatusCode::NOT_FOUND, e.to_string())),
+    }
+}
+
+/// Let the extension show "already clipped" before the user clips.
+async fn check_web_clip(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<ClipCheckQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    clipper_client(&state, &headers)?;
+    let existing = existing_clip(&state, &query.url);
+    Ok(Json(ApiResponse {
+        data: serde_json::json!({
+            "duplicate": existing.is_some(),
+            "existing": existing,
+        }),
+    }))
+}
+
+async fn clip_web_content(
     State(state): State<AppState>,
-    Json(req): Json<InboxCaptureRequest>,
+    headers: HeaderMap,
+    Json(req): Json<WebClipRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
-    let inbox = state.inbox_storage.lock().unwrap();
-    let capture = CaptureRequest {
-        title: req.title,
-        content: req.content.unwrap_or_default(),
-        tags: req.tags,
-        source: Some(CaptureSource::Api {
-            source: "daemon-api".to_string(),
-        }),
-        auto_classify: None,
+    let client = clipper_client(&state, &headers)?;
+
+    let parsed_url = reqwest::Url::parse(req.url.trim())
+        .map_err(|e| api_err(StatusCode::BAD_REQUEST, format!("Invalid URL: {}", e)))?;
+    if !matches!(parsed_url.scheme(), "http" | "https") {
+        return Err(api_err(StatusCode::BAD_REQUEST, "Only http(s) URLs can be clipped"));
+    }
+    let url = parsed_url.to_string();
+
+    if !req.allow_duplicate.unwrap_or(false) {
+        if let Some(existing) = existing_clip(&state, &url) {
+            let location = match existing.target {
+                ClipTarget::Inbox { .. } => "the inbox",
+                ClipTarget::Page { .. } => "a page",
+            };
+            return Err(api_err(
+                StatusCode::CONFLICT,
+                format!(
+                    "Already clipped to {} on {}; set allow_duplicate to clip again",
+                    location,
+                    existing.clipped_at.format("%Y-%m-%d")
+                ),
+            ));
+        }
+    }
+
+    // Content priority: markdown > simplified HTML > plain selection
+    let markdown = if let Some(md) = req.markdown.filter(|s| !s.trim().is_empty()) {
+        md
+    } else if let Some(html) = req.html.filter(|s| !s.trim().is_empty()) {
+        simplified_html_to_markdown(&html)
+    } else {
+        req.selection.unwrap_or_default()
     };
 
-    match inbox.capture(capture) {
-        Ok(item) => {
-            #[cfg(feature = "plugins")]
-            nous_lib::plugins::dispatch_plugin_event_bg(
-                &state.plugin_host,
-                nous_lib::plugins::HookPoint::OnInboxCaptured,
-                serde_json::json!({
-                    "item_id": item.id.to_string(),
-                    "title": item.title,
-                    "tags": item.tags,
+    let site = req
+        .site_name
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| parsed_url.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+        .unwrap_or_else(|| url.clone());
+    let title = req
+        .title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| site.clone());
+
+    let target = if let Some(nb) = req.notebook_id {
+        let nb_id = parse_uuid(&nb)?;
+        let folder_id = req.folder_id.as_deref().map(parse_uuid).transpose()?;
+        let page = {
+            let storage = state.storage.write().unwrap();
+            let mut page = storage
+                .create_page(nb_id, title.clone())
+                .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+            // Same source line the in-app clipper puts at the top
+            let mut blocks = vec![EditorBlock {
+                id: Uuid::new_v4().simple().to_string()[..10].to_string(),
+                block_type: "paragraph".to_string(),
+                data: serde_json::json!({
+                    "text": format!(
+                        "<i>Clipped from <a href=\"{}\">{}</a></i>",
+                        html_escape::encode_double_quoted_attribute(&url),
+                        html_escape::encode_text(&site)
+                    ),
                 }),
-            );
-            emit_event(&state, "inbox.captured", serde_json::json!({
-                "itemId": item.id.to_string(),
+            }];
+            blocks.extend(parse_markdown_to_blocks(&markdown));
+            page.content = make_block_content(blocks);
+            page.tags = req.tags.unwrap_or_default();
+            page.folder_id = folder_id;
+
+            storage
+                .update_page(&page)
+                .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            page
+        };
+
+        state.sync_manager.queue_page_update(nb_id, page.id);
+        {
+            let mut idx = lock_search_index(&state.search_index);
+            if let Err(e) = idx.index_page(&page) {
+                log::warn!("Failed to index clipped page {}: {}", page.id, e);
+            }
+        }
+        spawn_rag_index(&state, &page);
+
+        emit_event(&state, "page.created", serde_json::json!({
+            "notebookId": nb_id.to_string(),
+            "pageId": page.id.to_string(),
+            "title": page.title,
+        }));
+
+        ClipTarget::Page {
+            notebook_id: nb_id,
+            page_id: page.id,
+        }
+    } else {
+        // Default: land in the inbox, where classification routes it
+        let item = {
+            let inbox = state.inbox_storage.lock().unwrap();
+            let content = if markdown.trim().is_empty() {
+                format!("Source: {}", url)
+            } else {
+                format!("Source: {}\n\n{}", url, markdown)
+            };
+            inbox
+                .capture(CaptureRequest {
+                    title: title.clone(),
+                    content,
+                    tags: req.tags,
+                    source: Some(CaptureSource::WebClipper { url: url.clone() }),
+                    auto_classify: None,
+                })
+                .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        };
+
+        #[cfg(feature = "plugins")]
+        nous_lib::plugins::dispatch_plugin_event_bg(
+            &state.plugin_host,
+            nous_lib::plugins::HookPoint::OnInboxCaptured,
+            serde_json::json!({
+                "item_id": item.id.to_string(),
                 "title": item.title,
-            }));
-            Ok((StatusCode::CREATED, Json(ApiResponse { data: item })))
+                "tags": item.tags,
+            }),
+        );
+        emit_event(&state, "inbox.captured", serde_json::json!({
+            "itemId": item.id.to_string(),
+            "title": item.title,
+        }));
+
+        ClipTarget::Inbox { item_id: item.id }
+    };
+
+    let record = {
+        let mut clipper = state.clipper_storage.lock().unwrap();
+        if let Err(e) = clipper.touch_client(client.id) {
+            log::warn!("Failed to update clipper client {}: {}", client.id, e);
         }
-        Err(e) => Err(api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
-    }
+        clipper
+            .record_clip(&url, &title, target, Some(client.id))
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    };
+
+    Ok((StatusCode::CREATED, Json(ApiResponse { data: record })))
 }
 
 async fn trigger_sync(
@@ -2806,7 +3985,7 @@ async fn trigger_sync(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     // Trigger sync for all notebooks with sync configured
     let notebooks = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         storage.list_notebooks().unwrap_or_default()
     };
 
@@ -3670,6 +4849,20 @@ async fn ai_configure(
         apply(&mut entry.api_key, input.api_key);
         apply(&mut entry.base_url, input.base_url);
         apply(&mut entry.default_model, input.default_model);
+        // A cleared key takes its secret with it
+        if entry.api_key.is_none() {
+            if let (Some(id), Some(secrets)) = (entry.api_key_secret.take(), &state.secrets) {
+                if let Err(e) = secrets.delete(&id) {
+                    log::warn!("Failed to delete secret {}: {}", id, e);
+                }
+            }
+        }
+    }
+    // New keys go to the secrets store when it's unlocked
+    if let Some(secrets) = state.secrets.as_ref().filter(|s| s.is_unlocked()) {
+        if let Err(e) = nous_lib::secrets::migrate::store_ai_keys(secrets, &mut ai) {
+            log::warn!("Failed to store AI provider keys as secrets: {}", e);
+        }
     }
     // Drop providers with no remaining fields (a full clear removes the entry).
     ai.providers.retain(|_, p| {
@@ -3681,7 +4874,7 @@ async fn ai_configure(
         search: nous_lib::search::SearchSection {
             rag: state.rag_config.read().await.clone(),
         },
-        ai: ai.clone(),
+        ai: ai.for_disk(),
     };
     if let Err(e) = nous_lib::search::save(&state.daemon_config_path, &to_persist) {
         log::warn!(
@@ -3744,11 +4937,21 @@ async fn get_notebook_asset(
 
     let nb_id = parse_uuid(&notebook_id)?;
     let assets_dir = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         storage.notebook_assets_dir(nb_id)
     };
-    let file = resolve_notebook_asset_path(&assets_dir, &asset_path)
+    let mut file = resolve_notebook_asset_path(&assets_dir, &asset_path)
         .ok_or_else(|| api_err(StatusCode::BAD_REQUEST, "Invalid asset path"))?;
+    if !file.exists() {
+        // Not downloaded yet under an on-demand asset policy
+        if let Ok((fetched, _)) = state
+            .sync_manager
+            .ensure_asset_local(nb_id, &state.storage, &asset_path)
+            .await
+        {
+            file = fetched;
+        }
+    }
     let bytes = tokio::fs::read(&file)
         .await
         .map_err(|_| api_err(StatusCode::NOT_FOUND, "Asset not found"))?;
@@ -3774,7 +4977,7 @@ async fn put_notebook_asset(
         return Err(api_err(StatusCode::BAD_REQUEST, "Empty request body"));
     }
     let assets_dir = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         storage.notebook_assets_dir(nb_id)
     };
     let file = resolve_notebook_asset_path(&assets_dir, &asset_path)
@@ -3988,7 +5191,7 @@ async fn list_folders(
     Path(notebook_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let folders = storage
         .list_folders(nb_id)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -4000,7 +5203,7 @@ async fn list_sections(
     Path(notebook_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let sections = storage
         .list_sections(nb_id)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -4015,7 +5218,7 @@ async fn delete_page(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let page_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     // Soft delete (move to trash)
     let mut page = storage
@@ -4069,7 +5272,7 @@ async fn update_tags(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let page_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = storage
         .get_page(nb_id, page_id)
@@ -4098,7 +5301,7 @@ async fn move_page(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let page_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = storage
         .get_page(nb_id, page_id)
@@ -4256,7 +5459,7 @@ async fn handle_pane_command(
             // Load page content with the storage guard scoped to this block
             // so the !Send MutexGuard is dropped before any .await below.
             let load_result: Result<EditorData, String> = {
-                let storage = match state.storage.lock() {
+                let storage = match state.storage.read() {
                     Ok(g) => g,
                     Err(e) => {
                         log::warn!("WS pane_open: storage lock poisoned: {}", e);
@@ -4322,7 +5525,7 @@ async fn list_databases(
     Path(notebook_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let pages = storage
         .list_pages(nb_id)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -4363,7 +5566,7 @@ async fn get_database(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&db_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
 
     let page = storage
         .get_page(nb_id, pg_id)
@@ -4704,7 +5907,7 @@ async fn put_database(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&db_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = storage
         .get_page(nb_id, pg_id)
@@ -4805,7 +6008,7 @@ async fn get_file_content(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
 
     let page = storage
         .get_page_any_type(nb_id, pg_id)
@@ -4835,7 +6038,7 @@ async fn put_file_content(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut page = storage
         .get_page_any_type(nb_id, pg_id)
@@ -4894,7 +6097,7 @@ async fn create_folder(
     Json(req): Json<CreateFolderRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let parent_id = req.parent_id
         .as_deref()
@@ -4931,7 +6134,7 @@ async fn update_folder(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let fld_id = parse_uuid(&folder_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut folder = storage
         .get_folder(nb_id, fld_id)
@@ -4977,6 +6180,13 @@ async fn update_folder(
         folder.section_id = new_section_uuid;
     }
 
+    if let Some(sort_opt) = req.page_sort_by {
+        if let Some(sort) = sort_opt.as_deref().filter(|s| PageSort::parse(s).is_none()) {
+            return Err(api_err(StatusCode::BAD_REQUEST, format!("Unknown page sort: {}", sort)));
+        }
+        folder.page_sort_by = sort_opt;
+    }
+
     folder.updated_at = chrono::Utc::now();
     storage
         .update_folder(&folder)
@@ -5005,7 +6215,7 @@ async fn delete_folder(
         None | Some("") => None,
         Some(s) => Some(parse_uuid(s)?),
     };
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     storage
         .delete_folder(nb_id, fld_id, target)
@@ -5031,7 +6241,7 @@ async fn create_section(
     Json(req): Json<CreateSectionRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let section = storage
         .create_section(nb_id, req.name.clone(), req.color)
@@ -5054,7 +6264,7 @@ async fn update_section(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let sec_id = parse_uuid(&section_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let mut section = storage
         .get_section(nb_id, sec_id)
@@ -5108,7 +6318,7 @@ async fn delete_section(
         None | Some("") => None,
         Some(s) => Some(parse_uuid(s)?),
     };
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     storage
         .delete_section(nb_id, sec_id, target)
@@ -5134,7 +6344,7 @@ async fn archive_page(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&page_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let page = storage
         .archive_page(nb_id, pg_id)
@@ -5161,7 +6371,7 @@ async fn unarchive_page(
         None | Some("") => None,
         Some(s) => Some(parse_uuid(s)?),
     };
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let page = storage
         .unarchive_page(nb_id, pg_id, target_folder_id)
@@ -5184,7 +6394,7 @@ async fn archive_folder(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let fld_id = parse_uuid(&folder_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let folder = storage
         .archive_folder(nb_id, fld_id)
@@ -5206,7 +6416,7 @@ async fn unarchive_folder(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let fld_id = parse_uuid(&folder_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let folder = storage
         .unarchive_folder(nb_id, fld_id)
@@ -5239,7 +6449,7 @@ async fn reorder_pages(
         page_uuids.push(parse_uuid(s)?);
     }
 
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     storage
         .reorder_pages(nb_id, folder_id, &page_uuids)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5256,6 +6466,34 @@ async fn reorder_pages(
     }))
 }
 
+/// Pin a page to the top of its folder, section or notebook root, or unpin it
+async fn pin_page(
+    State(state): State<AppState>,
+    Path((notebook_id, page_id)): Path<(String, String)>,
+    Json(req): Json<PinPageRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_id = parse_uuid(&notebook_id)?;
+    let pg_id = parse_uuid(&page_id)?;
+
+    let storage = state.storage.write().unwrap();
+    let container = storage
+        .set_page_pinned(nb_id, pg_id, req.pinned)
+        .map_err(|e| match e {
+            StorageError::PageNotFound(_) => api_err(StatusCode::NOT_FOUND, e.to_string()),
+            e => api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    drop(storage);
+    emit_event(&state, "page.pinned", serde_json::json!({
+        "notebookId": notebook_id,
+        "pageId": page_id,
+        "pinned": req.pinned,
+        "container": container,
+    }));
+
+    Ok(Json(ApiResponse { data: container }))
+}
+
 async fn reorder_folders(
     State(state): State<AppState>,
     Path(notebook_id): Path<String>,
@@ -5271,7 +6509,7 @@ async fn reorder_folders(
         folder_uuids.push(parse_uuid(s)?);
     }
 
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     storage
         .reorder_folders(nb_id, parent_id, &folder_uuids)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5299,7 +6537,7 @@ async fn reorder_sections(
         section_uuids.push(parse_uuid(s)?);
     }
 
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     storage
         .reorder_sections(nb_id, &section_uuids)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5320,7 +6558,7 @@ async fn reorder_sections(
 async fn list_all_tags(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let tags = storage
         .get_all_tags()
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5336,7 +6574,7 @@ async fn list_notebook_tags(
     Path(notebook_id): Path<String>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.read().unwrap();
     let tags = storage
         .get_notebook_tags(nb_id)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5353,7 +6591,7 @@ async fn rename_tag(
     Json(req): Json<RenameTagRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     let updated = storage
         .rename_tag(nb_id, &tag, &req.new_name)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5377,7 +6615,7 @@ async fn merge_tags(
     Json(req): Json<MergeTagsRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     let updated = storage
         .merge_tags(nb_id, &req.from, &req.into)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5400,7 +6638,7 @@ async fn delete_tag(
     Path((notebook_id, tag)): Path<(String, String)>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
     let updated = storage
         .delete_tag(nb_id, &tag)
         .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5417,6 +6655,135 @@ async fn delete_tag(
     }))
 }
 
+fn load_tag_registry(
+    state: &DaemonState,
+) -> Result<nous_lib::tags::TagRegistry, (StatusCode, Json<ApiError>)> {
+    nous_lib::tags::TagRegistry::load(&state.library_path)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn get_tag_registry(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let registry = load_tag_registry(&state)?;
+    Ok(Json(ApiResponse { data: registry.tags }))
+}
+
+async fn save_tag_definition(
+    State(state): State<AppState>,
+    Json(definition): Json<nous_lib::tags::TagDefinition>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let mut registry = load_tag_registry(&state)?;
+    let saved = registry
+        .upsert(definition)
+        .map_err(|e| api_err(StatusCode::BAD_REQUEST, e.to_string()))?;
+    registry
+        .save(&state.library_path)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    emit_event(&state, "tag.defined", serde_json::json!({ "name": saved.name }));
+    Ok(Json(ApiResponse { data: saved }))
+}
+
+async fn delete_tag_definition(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let mut registry = load_tag_registry(&state)?;
+    registry
+        .remove(&name)
+        .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?;
+    registry
+        .save(&state.library_path)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    emit_event(&state, "tag.undefined", serde_json::json!({ "name": name }));
+    Ok(Json(ApiResponse {
+        data: serde_json::json!({ "deleted": true }),
+    }))
+}
+
+async fn get_tag_tree(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let registry = load_tag_registry(&state)?;
+    let storage = state.storage.read().unwrap();
+    let tree = nous_lib::tags::library_tag_tree(&storage, &registry)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(ApiResponse { data: tree }))
+}
+
+async fn get_pages_by_tag(
+    State(state): State<AppState>,
+    Query(query): Query<TagPagesQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let registry = load_tag_registry(&state)?;
+    let storage = state.storage.read().unwrap();
+    let pages = nous_lib::tags::pages_with_tag(
+        &storage,
+        &registry,
+        &query.tag,
+        query.include_children.unwrap_or(true),
+    )
+    .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(ApiResponse { data: pages }))
+}
+
+fn load_tag_rules(
+    state: &DaemonState,
+) -> Result<nous_lib::tags::TagRuleSet, (StatusCode, Json<ApiError>)> {
+    nous_lib::tags::TagRuleSet::load(&state.library_path)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn list_tag_rules(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let rules = load_tag_rules(&state)?;
+    Ok(Json(ApiResponse { data: rules.rules }))
+}
+
+async fn save_tag_rule(
+    State(state): State<AppState>,
+    Json(rule): Json<nous_lib::tags::TagRule>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let mut rules = load_tag_rules(&state)?;
+    let saved = rules
+        .upsert(rule)
+        .map_err(|e| api_err(StatusCode::BAD_REQUEST, e.to_string()))?;
+    rules
+        .save(&state.library_path)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    emit_event(&state, "tag_rule.saved", serde_json::json!({ "ruleId": saved.id }));
+    Ok(Json(ApiResponse { data: saved }))
+}
+
+async fn delete_tag_rule(
+    State(state): State<AppState>,
+    Path(rule_id): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let id = parse_uuid(&rule_id)?;
+    let mut rules = load_tag_rules(&state)?;
+    rules
+        .remove(id)
+        .map_err(|e| api_err(StatusCode::NOT_FOUND, e.to_string()))?;
+    rules
+        .save(&state.library_path)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    emit_event(&state, "tag_rule.deleted", serde_json::json!({ "ruleId": rule_id }));
+    Ok(Json(ApiResponse {
+        data: serde_json::json!({ "deleted": true }),
+    }))
+}
+
+async fn test_tag_rule(
+    State(state): State<AppState>,
+    Json(req): Json<TestTagRuleRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let storage = state.storage.read().unwrap();
+    let matches = nous_lib::tags::test_rule(&storage, &req.rule, req.limit.unwrap_or(100))
+        .map_err(|e| api_err(StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(Json(ApiResponse { data: matches }))
+}
+
 // ===== Database Rows =====
 
 async fn add_database_rows(
@@ -5426,7 +6793,7 @@ async fn add_database_rows(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&db_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let page = storage
         .get_page(nb_id, pg_id)
@@ -5503,7 +6870,7 @@ async fn update_database_rows(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&db_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let page = storage
         .get_page(nb_id, pg_id)
@@ -5595,7 +6962,7 @@ async fn create_database(
     Json(req): Json<CreateDatabaseRequest>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     // Create the page
     let mut page = storage
@@ -5715,7 +7082,7 @@ async fn delete_database_rows(
 ) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
     let nb_id = parse_uuid(&notebook_id)?;
     let pg_id = parse_uuid(&db_id)?;
-    let storage = state.storage.lock().unwrap();
+    let storage = state.storage.write().unwrap();
 
     let page = storage
         .get_page(nb_id, pg_id)
@@ -5816,7 +7183,7 @@ async fn import_markdown_page(
     page.section_id = section_id;
 
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.write().unwrap();
         storage
             .create_page_from(page.clone())
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
@@ -5868,7 +7235,7 @@ async fn import_artwork(
 
     // Resolve notebook name for the script
     let nb_name = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         let notebooks = storage.list_notebooks()
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
         notebooks.into_iter()
@@ -6126,7 +7493,7 @@ async fn agile_daily_note(
 
     // Resolve notebook name
     let nb_name = {
-        let storage = state.storage.lock().unwrap();
+        let storage = state.storage.read().unwrap();
         let notebooks = storage.list_notebooks()
             .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
         notebooks.into_iter()
@@ -6186,6 +7553,259 @@ async fn agile_daily_note(
     Ok((StatusCode::CREATED, Json(ApiResponse { data: result })))
 }
 
+// ===== Automation (Shortcuts, AppleScript, PowerShell) =====
+//
+// Verbs for OS automation tools. Notebooks and pages are named instead of
+// addressed by ID, and every reply carries the page's nous:// link. The
+// scripts printed by `nous-cli automation-script` wrap these routes.
+
+#[derive(Deserialize)]
+struct AutomationNoteRequest {
+    /// Notebook name or ID
+    notebook: String,
+    /// Defaults to "Quick Note YYYY-MM-DD HH:MM"
+    title: Option<String>,
+    /// Markdown
+    content: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct AutomationAppendRequest {
+    notebook: String,
+    /// Markdown
+    text: String,
+    /// YYYY-MM-DD; defaults to today
+    date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AutomationSearchQuery {
+    q: String,
+    notebook: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct AutomationPageQuery {
+    notebook: String,
+    /// Page title (exact, else unique prefix) or ID
+    page: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutomationPage {
+    notebook_id: String,
+    page_id: String,
+    title: String,
+    url: String,
+}
+
+/// ID of the library this daemon serves, for `library=` in page links
+fn daemon_library_id(state: &DaemonState) -> Option<Uuid> {
+    let library_storage = state.library_storage.lock().unwrap();
+    library_storage
+        .list_libraries()
+        .ok()?
+        .into_iter()
+        .find(|lib| lib.path == state.library_path)
+        .map(|lib| lib.id)
+}
+
+fn automation_page(state: &DaemonState, page: &Page) -> AutomationPage {
+    AutomationPage {
+        notebook_id: page.notebook_id.to_string(),
+        page_id: page.id.to_string(),
+        title: page.title.clone(),
+        url: page_deep_link(page.id, page.notebook_id, daemon_library_id(state)),
+    }
+}
+
+/// Pick the one name matching `wanted`: an exact (case-insensitive) match,
+/// else a unique prefix match
+fn match_by_name<'a, T>(
+    items: &'a [T],
+    name: impl Fn(&T) -> &str,
+    wanted: &str,
+    kind: &str,
+) -> Result<&'a T, (StatusCode, Json<ApiError>)> {
+    let wanted_lower = wanted.to_lowercase();
+    if let Some(item) = items.iter().find(|i| name(i).to_lowercase() == wanted_lower) {
+        return Ok(item);
+    }
+    let prefix: Vec<&T> = items
+        .iter()
+        .filter(|i| name(i).to_lowercase().starts_with(&wanted_lower))
+        .collect();
+    match prefix.as_slice() {
+        [item] => Ok(item),
+        [] => Err(api_err(
+            StatusCode::NOT_FOUND,
+            format!("No {} matching '{}'", kind, wanted),
+        )),
+        matches => Err(api_err(
+            StatusCode::CONFLICT,
+            format!(
+                "Ambiguous {} '{}'. Matches: {}",
+                kind,
+                wanted,
+                matches.iter().map(|i| name(i)).collect::<Vec<_>>().join(", ")
+            ),
+        )),
+    }
+}
+
+fn find_notebook_by_name(
+    storage: &FileStorage,
+    wanted: &str,
+) -> Result<Notebook, (StatusCode, Json<ApiError>)> {
+    let notebooks = storage
+        .list_notebooks()
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Ok(id) = Uuid::parse_str(wanted) {
+        return notebooks
+            .into_iter()
+            .find(|nb| nb.id == id)
+            .ok_or_else(|| api_err(StatusCode::NOT_FOUND, "Notebook not found"));
+    }
+    let active: Vec<Notebook> = notebooks.into_iter().filter(|nb| !nb.archived).collect();
+    match_by_name(&active, |nb| nb.name.as_str(), wanted, "notebook").cloned()
+}
+
+/// `POST /api/automation/note` — create a page from Markdown
+async fn automation_create_note(
+    State(state): State<AppState>,
+    Json(req): Json<AutomationNoteRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let storage = state.storage.write().unwrap();
+    let notebook = find_notebook_by_name(&storage, &req.notebook)?;
+    let title = req
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| chrono::Local::now().format("Quick Note %Y-%m-%d %H:%M").to_string());
+
+    let mut page = storage
+        .create_page(notebook.id, title)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Some(md) = req.content.filter(|c| !c.trim().is_empty()) {
+        page.content = make_block_content(parse_markdown_to_blocks(&md));
+    }
+    if let Some(tags) = req.tags {
+        page.tags = tags;
+    }
+    nous_lib::tags::apply_tag_rules(&storage, &state.library_path, &mut page);
+    storage
+        .update_page(&page)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    drop(storage);
+
+    after_page_created(&state, &page);
+    Ok((StatusCode::CREATED, Json(ApiResponse { data: automation_page(&state, &page) })))
+}
+
+/// `POST /api/automation/daily-note/append` — add Markdown to the end of a
+/// day's daily note, creating the note if needed
+async fn automation_append_daily_note(
+    State(state): State<AppState>,
+    Json(req): Json<AutomationAppendRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    if req.text.trim().is_empty() {
+        return Err(api_err(StatusCode::BAD_REQUEST, "'text' is empty"));
+    }
+    let date = match req.date {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| api_err(StatusCode::BAD_REQUEST, format!("Invalid date '{}'", date)))?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let date = date.format("%Y-%m-%d").to_string();
+
+    let storage = state.storage.write().unwrap();
+    let notebook = find_notebook_by_name(&storage, &req.notebook)?;
+    let existed = find_daily_note(&storage, notebook.id, &date)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.message))?
+        .is_some();
+    let mut page = create_daily_note_core(&storage, notebook.id, &date, None)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.message))?;
+    page.content.blocks.extend(parse_markdown_to_blocks(&req.text));
+    page.updated_at = chrono::Utc::now();
+    storage
+        .update_page(&page)
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    drop(storage);
+
+    if existed {
+        state.sync_manager.queue_page_update(page.notebook_id, page.id);
+        let (nb_id, pg_id) = (page.notebook_id.to_string(), page.id.to_string());
+        after_block_level_write(&state, &page, &nb_id, &pg_id);
+    } else {
+        after_page_created(&state, &page);
+    }
+    Ok(Json(ApiResponse { data: automation_page(&state, &page) }))
+}
+
+/// `GET /api/automation/search?q=...&notebook=...` — keyword search, each
+/// hit with its page link
+async fn automation_search(
+    State(state): State<AppState>,
+    Query(query): Query<AutomationSearchQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let nb_filter = match query.notebook.as_deref() {
+        Some(name) => Some(find_notebook_by_name(&state.storage.read().unwrap(), name)?.id),
+        None => None,
+    };
+
+    use nous_lib::search::SearchBackend;
+    let hits = state
+        .tantivy
+        .query(&query.q, query.limit.unwrap_or(20), nb_filter)
+        .await
+        .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, format!("Search error: {}", e)))?;
+
+    let library_id = daemon_library_id(&state);
+    let results: Vec<serde_json::Value> = hits
+        .into_iter()
+        .filter_map(|hit| {
+            let page_id = Uuid::parse_str(&hit.page_id).ok()?;
+            let notebook_id = Uuid::parse_str(&hit.notebook_id).ok()?;
+            Some(serde_json::json!({
+                "notebookId": hit.notebook_id,
+                "pageId": hit.page_id,
+                "title": hit.title,
+                "snippet": hit.snippet,
+                "url": page_deep_link(page_id, notebook_id, library_id),
+            }))
+        })
+        .collect();
+    Ok(Json(ApiResponse { data: results }))
+}
+
+/// `GET /api/automation/page-url?notebook=...&page=...` — a page's
+/// nous:// link
+async fn automation_page_url(
+    State(state): State<AppState>,
+    Query(query): Query<AutomationPageQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ApiError>)> {
+    let page = {
+        let storage = state.storage.read().unwrap();
+        let notebook = find_notebook_by_name(&storage, &query.notebook)?;
+        let pages: Vec<Page> = storage
+            .list_pages(notebook.id)
+            .map_err(|e| api_err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .into_iter()
+            .filter(|p| p.deleted_at.is_none())
+            .collect();
+        match Uuid::parse_str(&query.page) {
+            Ok(id) => pages
+                .into_iter()
+                .find(|p| p.id == id)
+                .ok_or_else(|| api_err(StatusCode::NOT_FOUND, "Page not found"))?,
+            Err(_) => match_by_name(&pages, |p| p.title.as_str(), &query.page, "page")?.clone(),
+        }
+    };
+    Ok(Json(ApiResponse { data: automation_page(&state, &page) }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
@@ -6291,6 +7911,17 @@ mod tests {
         assert!(!is_public_route("/apple")); // no prefix confusion
     }
 
+    #[test]
+    fn clipper_routes_use_their_own_tokens() {
+        // Extension-facing routes check clip_ tokens themselves
+        assert!(is_public_route("/api/clipper/pair"));
+        assert!(is_public_route("/api/clipper/clip"));
+        assert!(is_public_route("/api/clipper/check"));
+        // Starting a pairing and managing clients need a daemon key
+        assert!(!is_public_route("/api/clipper/pairing"));
+        assert!(!is_public_route("/api/clipper/clients"));
+    }
+
     // ----- Notebook asset serving (/api/notebooks/{nb}/assets/*) -----
 
     #[test]
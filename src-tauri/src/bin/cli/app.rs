@@ -3,13 +3,15 @@ use uuid::Uuid;
 
 use nous_lib::inbox::{CaptureRequest, CaptureSource, InboxItem, InboxStorage};
 use nous_lib::library::{Library, LibraryStorage};
-use nous_lib::storage::{EditorBlock, EditorData, FileStorage, Folder, Notebook, Page, Section};
+use nous_lib::storage::{
+    EditorBlock, EditorData, FileStorage, FileStorageWriter, Folder, Notebook, Page, Section,
+};
 
 /// Shared application state for CLI commands
 pub struct App {
     pub library_storage: LibraryStorage,
     pub current_library: Library,
-    pub storage: FileStorage,
+    pub storage: FileStorageWriter,
 }
 
 impl App {
@@ -31,7 +33,7 @@ impl App {
         };
 
         let library_path = current_library.path.clone();
-        let storage = FileStorage::new(library_path);
+        let storage = FileStorageWriter::new(FileStorage::new(library_path));
 
         Ok(Self {
             library_storage,
@@ -101,6 +103,12 @@ impl App {
         self.storage.list_pages(notebook_id).context("Failed to list pages")
     }
 
+    /// List pages in a notebook, pinned pages first in each folder and the
+    /// rest in the folder's sort
+    pub fn list_pages_in_order(&self, notebook_id: Uuid) -> Result<Vec<Page>> {
+        self.storage.list_pages_in_order(notebook_id).context("Failed to list pages")
+    }
+
     /// List folders in a notebook
     pub fn list_folders(&self, notebook_id: Uuid) -> Result<Vec<Folder>> {
         self.storage.list_folders(notebook_id).context("Failed to list folders")
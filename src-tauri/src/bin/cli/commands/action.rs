@@ -0,0 +1,259 @@
+//! `nous-cli action list|run` — run automations without the GUI or daemon,
+//! e.g. from cron on a server copy of the library.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use nous_lib::actions::{ActionExecutor, ActionStorage};
+use nous_lib::energy::EnergyStorage;
+use nous_lib::goals::GoalsStorage;
+use nous_lib::inbox::InboxStorage;
+#[cfg(feature = "plugins")]
+use nous_lib::plugins;
+use nous_lib::python_bridge::PythonAI;
+use nous_lib::storage::{FileStorage, FileStorageWriter, StorageLock};
+
+use crate::app::App;
+use crate::OutputFormat;
+
+pub fn run_list(app: &App, format: &OutputFormat, _use_color: bool) -> Result<()> {
+    let storage = ActionStorage::new(app.current_library.path.clone())
+        .context("Failed to open action storage")?;
+    let mut actions = storage.list_actions()?;
+    actions.sort_by_key(|a| a.name.to_lowercase());
+
+    match format {
+        OutputFormat::Json => {
+            let output: Vec<serde_json::Value> = actions.iter().map(|a| {
+                serde_json::json!({
+                    "id": a.id.to_string(),
+                    "name": a.name,
+                    "description": a.description,
+                    "enabled": a.enabled,
+                    "builtIn": a.is_built_in,
+                    "scheduled": a.has_schedule(),
+                    "variables": a.variables.iter().map(|v| &v.name).collect::<Vec<_>>(),
+                    "lastRun": a.last_run.map(|t| t.to_rfc3339()),
+                    "nextRun": a.next_run.map(|t| t.to_rfc3339()),
+                })
+            }).collect();
+            crate::output::print_json(&output)?;
+        }
+        OutputFormat::Plain => {
+            if actions.is_empty() {
+                println!("No actions.");
+                return Ok(());
+            }
+
+            let name_width = actions.iter().map(|a| a.name.len()).max().unwrap_or(4).clamp(4, 40);
+
+            println!("{:<nw$} {:<9} Last run", "Name", "Trigger", nw = name_width);
+            println!("{} {} {}", "\u{2500}".repeat(name_width), "\u{2500}".repeat(9), "\u{2500}".repeat(16));
+
+            for action in &actions {
+                let trigger = if !action.enabled {
+                    "disabled"
+                } else if action.has_schedule() {
+                    "scheduled"
+                } else {
+                    "manual"
+                };
+                let last_run = action
+                    .last_run
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "never".to_string());
+                println!("{:<nw$} {:<9} {}", action.name, trigger, last_run, nw = name_width);
+            }
+
+            println!("\n{} actions", actions.len());
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse `--var key=value` arguments
+fn parse_vars(vars: &[String]) -> Result<HashMap<String, String>> {
+    vars.iter()
+        .map(|var| {
+            let (key, value) = var
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid --var \"{}\" (expected key=value)", var))?;
+            let key = key.trim();
+            if key.is_empty() {
+                bail!("Invalid --var \"{}\" (empty key)", var);
+            }
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Run an action by name (or ID) and report what it did. Fails if any step
+/// failed, so cron mails the output.
+pub fn run(
+    app: &App,
+    name: &str,
+    vars: &[String],
+    notebook_name: Option<&str>,
+    format: &OutputFormat,
+) -> Result<()> {
+    let variables = parse_vars(vars)?;
+    let notebook_id = notebook_name
+        .map(|nb| app.find_notebook(nb).map(|nb| nb.id))
+        .transpose()?;
+
+    let library_path = app.current_library.path.clone();
+    let data_dir = FileStorage::default_data_dir().context("Failed to get data directory")?;
+
+    let action_storage = ActionStorage::new(library_path.clone())
+        .context("Failed to initialize action storage")?;
+    let action = match uuid::Uuid::parse_str(name) {
+        Ok(id) => action_storage.get_action(id).ok(),
+        Err(_) => action_storage.find_action_by_name(name)?,
+    }
+    .with_context(|| format!("Action '{}' not found", name))?;
+
+    // Same wiring as the daemon's executor, minus the event channel
+    let storage_arc = Arc::new(StorageLock::new(FileStorageWriter::new(FileStorage::new(
+        library_path.clone(),
+    ))));
+    let goals_storage = Arc::new(Mutex::new(
+        GoalsStorage::new(library_path.clone()).context("Failed to initialize goals storage")?,
+    ));
+    let energy_storage = Arc::new(Mutex::new(
+        EnergyStorage::new(data_dir.clone()).context("Failed to initialize energy storage")?,
+    ));
+    let inbox_storage = Arc::new(Mutex::new(
+        InboxStorage::new(library_path.clone()).context("Failed to initialize inbox storage")?,
+    ));
+    let python_ai = Arc::new(Mutex::new(PythonAI::new(crate::daemon::find_nous_py_path())));
+    let action_storage = Arc::new(Mutex::new(action_storage));
+
+    let mut executor = ActionExecutor::new(
+        Arc::clone(&storage_arc),
+        Arc::clone(&action_storage),
+        Arc::clone(&python_ai),
+    );
+    executor.set_goals_storage(Arc::clone(&goals_storage));
+    executor.set_energy_storage(Arc::clone(&energy_storage));
+    executor.set_inbox_storage(Arc::clone(&inbox_storage));
+    executor.set_secrets(nous_lib::secrets::shared(&data_dir));
+
+    // Actions can have plugin steps, so load plugins as the daemon does
+    #[cfg(feature = "plugins")]
+    {
+        let mut api = plugins::HostApi::new(
+            Arc::clone(&storage_arc),
+            Arc::clone(&goals_storage),
+            Arc::clone(&inbox_storage),
+        );
+        api.set_energy_storage(Arc::clone(&energy_storage));
+        api.set_python_ai(Arc::clone(&python_ai));
+        let mut host = plugins::PluginHost::new(Arc::new(api), library_path.join("plugins"));
+        if let Err(e) = host.load_all() {
+            log::warn!("Plugin load error: {e}");
+        }
+        executor.set_plugin_host(Some(Arc::new(Mutex::new(host))));
+    }
+
+    let result = executor
+        .execute_action(action.id, Some(variables), notebook_id)
+        .with_context(|| format!("Failed to run '{}'", action.name))?;
+
+    match format {
+        OutputFormat::Json => {
+            crate::output::print_json(&result)?;
+        }
+        OutputFormat::Plain => {
+            println!(
+                "Ran \"{}\": {}/{} steps",
+                result.action_name, result.steps_completed, result.steps_total
+            );
+            if !result.created_notebooks.is_empty() {
+                println!("  Created notebooks: {}", result.created_notebooks.len());
+            }
+            if !result.created_pages.is_empty() {
+                println!("  Created pages: {}", result.created_pages.len());
+            }
+            if !result.modified_pages.is_empty() {
+                println!("  Modified pages: {}", result.modified_pages.len());
+            }
+            for error in &result.errors {
+                eprintln!("  Error: {}", error);
+            }
+        }
+    }
+
+    if !result.success {
+        bail!("{} of {} steps failed", result.errors.len(), result.steps_total);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nous_lib::library::{Library, LibraryStorage};
+
+    fn vars(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn test_app(dir: &std::path::Path) -> App {
+        let library_path = dir.join("library");
+        App {
+            library_storage: LibraryStorage::new(dir.to_path_buf()),
+            current_library: Library::new("Test".to_string(), library_path.clone()),
+            storage: FileStorageWriter::new(FileStorage::new(library_path)),
+        }
+    }
+
+    #[test]
+    fn parses_key_value_vars() {
+        let parsed = parse_vars(&vars(&["topic=rust", " who =me", "query=a=b", "empty="])).unwrap();
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed["topic"], "rust");
+        assert_eq!(parsed["who"], "me");
+        // Only the first `=` separates the key
+        assert_eq!(parsed["query"], "a=b");
+        assert_eq!(parsed["empty"], "");
+    }
+
+    #[test]
+    fn rejects_malformed_vars() {
+        let err = parse_vars(&vars(&["topic=rust", "novalue"])).unwrap_err();
+        assert!(err.to_string().contains("expected key=value"), "{}", err);
+        let err = parse_vars(&vars(&[" =value"])).unwrap_err();
+        assert!(err.to_string().contains("empty key"), "{}", err);
+    }
+
+    #[test]
+    fn run_reports_malformed_vars_before_anything_else() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = test_app(dir.path());
+        let err = run(
+            &app,
+            "Daily",
+            &vars(&["novalue"]),
+            None,
+            &OutputFormat::Plain,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid --var"), "{}", err);
+    }
+
+    #[test]
+    fn run_reports_unknown_actions() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = test_app(dir.path());
+
+        let err = run(&app, "zz-no-such-action", &[], None, &OutputFormat::Plain).unwrap_err();
+        assert_eq!(err.to_string(), "Action 'zz-no-such-action' not found");
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let err = run(&app, &id, &[], None, &OutputFormat::Plain).unwrap_err();
+        assert_eq!(err.to_string(), format!("Action '{}' not found", id));
+    }
+}
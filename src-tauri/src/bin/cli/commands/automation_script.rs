@@ -0,0 +1,207 @@
+//! `nous-cli automation-script` — print an AppleScript library or a
+//! PowerShell module wrapping the daemon's `/api/automation/*` verbs, for
+//! Shortcuts, Keyboard Maestro, AutoHotkey and the like.
+//!
+//! The scripts read the API key from the daemon's key file on each call, so
+//! a regenerated key doesn't mean regenerating the script.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use nous_lib::storage::FileStorage;
+
+use crate::auth;
+
+/// Script flavours `nous-cli automation-script` can write
+#[derive(Clone, Debug, clap::ValueEnum)]
+pub enum ScriptLanguage {
+    Applescript,
+    Powershell,
+}
+
+const APPLESCRIPT: &str = r#"-- Nous automation library, generated by `nous-cli automation-script applescript`.
+-- Needs the Nous daemon running (`nous-cli daemon start`).
+--
+-- Save as ~/Library/Script Libraries/Nous.scpt, then from any script:
+--   tell script "Nous" to createNote("Inbox", "Call Sam", "About the *offer*")
+--   tell script "Nous" to appendToDailyNote("Journal", "- Shipped the release")
+--   tell script "Nous" to searchNotes("offer")
+--   tell script "Nous" to getPageURL("Inbox", "Call Sam")
+-- Notebooks and pages can be given by name (or unique prefix) or ID. Text is
+-- Markdown. createNote, appendToDailyNote and getPageURL return the page's
+-- nous:// link; searchNotes returns the JSON reply.
+
+property baseURL : "{{BASE_URL}}"
+property keyFile : "{{KEY_FILE}}"
+
+on createNote(notebookName, noteTitle, noteText)
+	set body to "{\"notebook\":" & my jsonString(notebookName) & ",\"title\":" & my jsonString(noteTitle) & ",\"content\":" & my jsonString(noteText) & "}"
+	return my pageURL(my apiPost("/api/automation/note", body))
+end createNote
+
+on appendToDailyNote(notebookName, noteText)
+	set body to "{\"notebook\":" & my jsonString(notebookName) & ",\"text\":" & my jsonString(noteText) & "}"
+	return my pageURL(my apiPost("/api/automation/daily-note/append", body))
+end appendToDailyNote
+
+on searchNotes(query)
+	return my apiGet("/api/automation/search", {"q=" & query})
+end searchNotes
+
+on getPageURL(notebookName, pageTitle)
+	return my pageURL(my apiGet("/api/automation/page-url", {"notebook=" & notebookName, "page=" & pageTitle}))
+end getPageURL
+
+-- Helpers
+
+on curlCommand()
+	return "curl -sS --fail-with-body -H \"Authorization: Bearer $(grep -m1 '^rw:' " & quoted form of keyFile & " 2>/dev/null)\""
+end curlCommand
+
+on apiPost(apiPath, body)
+	return do shell script my curlCommand() & " -X POST -H 'Content-Type: application/json' --data-binary " & quoted form of body & " " & quoted form of (baseURL & apiPath)
+end apiPost
+
+on apiGet(apiPath, params)
+	set args to ""
+	repeat with param in params
+		set args to args & " --data-urlencode " & quoted form of (param as text)
+	end repeat
+	return do shell script my curlCommand() & " -G" & args & " " & quoted form of (baseURL & apiPath)
+end apiGet
+
+on pageURL(reply)
+	return do shell script "printf %s " & quoted form of reply & " | plutil -extract data.url raw -o - -"
+end pageURL
+
+on jsonString(value)
+	set value to value as text
+	set value to my replaceText(value, "\\", "\\\\")
+	set value to my replaceText(value, "\"", "\\\"")
+	set value to my replaceText(value, return, "\\n")
+	set value to my replaceText(value, linefeed, "\\n")
+	set value to my replaceText(value, tab, "\\t")
+	return "\"" & value & "\""
+end jsonString
+
+on replaceText(value, search, replacement)
+	set saved to AppleScript's text item delimiters
+	set AppleScript's text item delimiters to search
+	set parts to text items of value
+	set AppleScript's text item delimiters to replacement
+	set value to parts as text
+	set AppleScript's text item delimiters to saved
+	return value
+end replaceText
+"#;
+
+const POWERSHELL: &str = r#"# Nous automation module, generated by `nous-cli automation-script powershell`.
+# Needs the Nous daemon running (`nous-cli daemon start`).
+#
+# Save as Nous.psm1 and load it with `Import-Module ./Nous.psm1` (or put it
+# in a folder named Nous under $env:PSModulePath). From AutoHotkey:
+#   Run 'pwsh -Command "Import-Module Nous; Add-NousDailyNote -Notebook Journal -Text Shipped"'
+# Notebooks and pages can be given by name (or unique prefix) or ID. Text is
+# Markdown. Every command returns objects with a `url` (nous:// link).
+
+$script:NousBaseUrl = '{{BASE_URL}}'
+$script:NousKeyFile = '{{KEY_FILE}}'
+
+function Invoke-NousApi {
+    param(
+        [Parameter(Mandatory)] [string] $Method,
+        [Parameter(Mandatory)] [string] $Path,
+        [hashtable] $Body,
+        [hashtable] $Query
+    )
+    $headers = @{}
+    if (Test-Path $script:NousKeyFile) {
+        $key = Get-Content $script:NousKeyFile | Where-Object { $_ -like 'rw:*' } | Select-Object -First 1
+        if ($key) { $headers['Authorization'] = "Bearer $key" }
+    }
+    $uri = $script:NousBaseUrl + $Path
+    if ($Query) {
+        $pairs = $Query.GetEnumerator() | Where-Object { $null -ne $_.Value } | ForEach-Object {
+            [uri]::EscapeDataString($_.Key) + '=' + [uri]::EscapeDataString([string]$_.Value)
+        }
+        $uri += '?' + ($pairs -join '&')
+    }
+    $params = @{ Method = $Method; Uri = $uri; Headers = $headers }
+    if ($Body) {
+        $params['ContentType'] = 'application/json; charset=utf-8'
+        $params['Body'] = [System.Text.Encoding]::UTF8.GetBytes(($Body | ConvertTo-Json -Depth 5))
+    }
+    (Invoke-RestMethod @params).data
+}
+
+function New-NousNote {
+    param(
+        [Parameter(Mandatory)] [string] $Notebook,
+        [string] $Title,
+        [Parameter(ValueFromPipeline)] [string] $Content,
+        [string[]] $Tags
+    )
+    $body = @{ notebook = $Notebook }
+    if ($Title) { $body['title'] = $Title }
+    if ($Content) { $body['content'] = $Content }
+    if ($Tags) { $body['tags'] = $Tags }
+    Invoke-NousApi -Method Post -Path '/api/automation/note' -Body $body
+}
+
+function Add-NousDailyNote {
+    param(
+        [Parameter(Mandatory)] [string] $Notebook,
+        [Parameter(Mandatory, ValueFromPipeline)] [string] $Text,
+        [datetime] $Date
+    )
+    $body = @{ notebook = $Notebook; text = $Text }
+    if ($Date) { $body['date'] = $Date.ToString('yyyy-MM-dd') }
+    Invoke-NousApi -Method Post -Path '/api/automation/daily-note/append' -Body $body
+}
+
+function Search-Nous {
+    param(
+        [Parameter(Mandatory)] [string] $Query,
+        [string] $Notebook,
+        [int] $Limit = 20
+    )
+    Invoke-NousApi -Method Get -Path '/api/automation/search' -Query @{ q = $Query; notebook = $Notebook; limit = $Limit }
+}
+
+function Get-NousPageUrl {
+    param(
+        [Parameter(Mandatory)] [string] $Notebook,
+        [Parameter(Mandatory)] [string] $Page
+    )
+    (Invoke-NousApi -Method Get -Path '/api/automation/page-url' -Query @{ notebook = $Notebook; page = $Page }).url
+}
+
+Export-ModuleMember -Function New-NousNote, Add-NousDailyNote, Search-Nous, Get-NousPageUrl
+"#;
+
+/// Fill in the daemon URL and key file path
+pub fn render(language: &ScriptLanguage, base_url: &str, key_file: &Path) -> String {
+    let key_file = key_file.display().to_string();
+    match language {
+        ScriptLanguage::Applescript => {
+            let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+            APPLESCRIPT
+                .replace("{{BASE_URL}}", &quote(base_url))
+                .replace("{{KEY_FILE}}", &quote(&key_file))
+        }
+        ScriptLanguage::Powershell => {
+            let quote = |s: &str| s.replace('\'', "''");
+            POWERSHELL
+                .replace("{{BASE_URL}}", &quote(base_url))
+                .replace("{{KEY_FILE}}", &quote(&key_file))
+        }
+    }
+}
+
+pub fn run(language: &ScriptLanguage, port: u16) -> Result<()> {
+    let data_dir = FileStorage::default_data_dir().context("Failed to get data directory")?;
+    let base_url = format!("http://127.0.0.1:{}", port);
+    print!("{}", render(language, &base_url, &auth::key_file_path(&data_dir)));
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+
+use crate::app::App;
+
+/// Shells `nous-cli completions` can register with
+#[derive(Clone, Debug, clap::ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+    Elvish,
+}
+
+/// Print the script that hooks `nous-cli` into the shell's completion.
+/// The script calls back into the binary (with `COMPLETE=<shell>` set), so
+/// completions follow the installed version and can list live notebook and
+/// library names.
+pub fn run(shell: &CompletionShell) -> Result<()> {
+    let name = format!("{:?}", shell).to_lowercase();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&name)
+        .with_context(|| format!("Unsupported shell '{}'", name))?;
+    let exe = std::env::current_exe().context("Failed to locate nous-cli")?;
+    // Register under the name we were invoked as (install-cli.sh installs
+    // the binary as `nous`)
+    let bin = std::env::args_os()
+        .next()
+        .and_then(|arg0| {
+            Path::new(&arg0)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "nous-cli".to_string());
+
+    let mut stdout = std::io::stdout().lock();
+    completer.write_registration("COMPLETE", &bin, &bin, &exe.to_string_lossy(), &mut stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Write the man page to stdout, or one page per subcommand into `out_dir`
+pub fn run_man(cmd: clap::Command, out_dir: Option<&str>) -> Result<()> {
+    match out_dir {
+        Some(dir) => {
+            let dir = Path::new(dir);
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            clap_mangen::generate_to(cmd, dir)
+                .with_context(|| format!("Failed to write man pages to {}", dir.display()))?;
+            eprintln!("Wrote man pages to {}", dir.display());
+        }
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            clap_mangen::Man::new(cmd).render(&mut stdout)?;
+        }
+    }
+    Ok(())
+}
+
+/// Notebook names in the current library, for `<notebook>` arguments.
+/// Completion must never fail loudly, so errors yield no candidates.
+pub fn notebook_candidates() -> Vec<CompletionCandidate> {
+    let Ok(app) = App::new(None) else {
+        return Vec::new();
+    };
+    app.list_notebooks()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|nb| !nb.archived)
+        .map(|nb| CompletionCandidate::new(nb.name))
+        .collect()
+}
+
+/// Library names, for `--library`
+pub fn library_candidates() -> Vec<CompletionCandidate> {
+    let Ok(app) = App::new(None) else {
+        return Vec::new();
+    };
+    app.list_libraries()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|lib| CompletionCandidate::new(lib.name))
+        .collect()
+}
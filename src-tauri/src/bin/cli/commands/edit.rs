@@ -0,0 +1,84 @@
+use anyhow::{bail, Context, Result};
+
+use nous_lib::external_editor::{apply_edited_markdown, EditorConfig, ExternalEditorManager};
+use nous_lib::markdown::export_page_to_markdown;
+use nous_lib::storage::{Page, PageType};
+
+use crate::app::App;
+use crate::OutputFormat;
+
+/// Open a page as Markdown in `$VISUAL`/`$EDITOR` and save the result back
+pub fn run(
+    app: &App,
+    notebook_name: &str,
+    page_title: &str,
+    format: &OutputFormat,
+) -> Result<()> {
+    let notebook = app.find_notebook(notebook_name)?;
+    let page = app.find_page(notebook.id, page_title)?;
+    let changed = edit_page(app, &page)?;
+
+    match format {
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "id": page.id.to_string(),
+                "notebookId": notebook.id.to_string(),
+                "changed": changed,
+            });
+            crate::output::print_json(&output)?;
+        }
+        OutputFormat::Plain => {
+            if changed {
+                println!("Updated page \"{}\"", page.title);
+            } else {
+                println!("No changes to \"{}\"", page.title);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the user's editor on a page and save the edits; returns whether the
+/// page changed. The terminal must be in normal (cooked) mode.
+pub fn edit_page(app: &App, page: &Page) -> Result<bool> {
+    if page.page_type != PageType::Standard {
+        bail!(
+            "Page '{}' is a {:?} page; only standard pages can be edited as Markdown",
+            page.title,
+            page.page_type
+        );
+    }
+
+    let manager = ExternalEditorManager::new().context("Failed to set up external editing")?;
+    let temp_path = manager
+        .export_page_for_editing(page)
+        .context("Failed to export page for editing")?;
+    let original = export_page_to_markdown(page);
+
+    let editor = EditorConfig::from_env();
+    manager
+        .open_in_editor(&temp_path, &editor)
+        .with_context(|| format!("Failed to run editor '{}'", editor.command))?;
+
+    let edited = manager
+        .read_temp_file(page.id)
+        .context("Failed to read edited file")?;
+
+    let changed = edited != original;
+    if changed {
+        // Re-read so edits made elsewhere while the editor was open aren't
+        // clobbered by stale metadata
+        let current = app
+            .storage
+            .get_page(page.notebook_id, page.id)
+            .context("Failed to reload page")?;
+        let updated = apply_edited_markdown(&current, &edited);
+        if let Err(e) = app.update_page(&updated) {
+            // Keep the temp file so the edits aren't lost
+            return Err(e.context(format!("Your edits are still in {}", temp_path.display())));
+        }
+    }
+    let _ = manager.end_session(page.id);
+    Ok(changed)
+}
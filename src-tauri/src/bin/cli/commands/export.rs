@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use nous_lib::markdown::mirror::export_notebook_markdown;
+use nous_lib::storage::backup::export_notebook_to_zip;
+
+use crate::app::App;
+use crate::OutputFormat;
+
+/// What `nous-cli export` writes
+#[derive(Clone, Debug, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// A folder of Markdown files, one per page
+    Md,
+    /// A Nous backup archive (re-importable with `nous-cli import`)
+    Zip,
+    /// A single JSON document with the notebook, folders, sections and pages
+    Json,
+}
+
+impl ExportFormat {
+    /// Guess the format from the output path: `.zip`, `.json`, else Markdown
+    fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+        {
+            Some(ext) if ext == "zip" => ExportFormat::Zip,
+            Some(ext) if ext == "json" => ExportFormat::Json,
+            _ => ExportFormat::Md,
+        }
+    }
+}
+
+pub fn run(
+    app: &App,
+    notebook_name: &str,
+    export_format: Option<ExportFormat>,
+    out: &str,
+    include_archived: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let notebook = app.find_notebook(notebook_name)?;
+    let out_path = Path::new(out);
+    let export_format = export_format.unwrap_or_else(|| ExportFormat::from_path(out_path));
+
+    let (pages, errors) = match export_format {
+        ExportFormat::Md => {
+            let report =
+                export_notebook_markdown(&app.storage, notebook.id, out_path, include_archived)
+                    .context("Failed to export notebook as Markdown")?;
+            (report.written, report.errors)
+        }
+        ExportFormat::Zip => {
+            let notebook_dir = app.storage.get_notebook_path(notebook.id);
+            let metadata = export_notebook_to_zip(&notebook_dir, &notebook, out_path, None)
+                .context("Failed to export notebook as ZIP")?;
+            (metadata.page_count, Vec::new())
+        }
+        ExportFormat::Json => {
+            let pages: Vec<_> = app
+                .storage
+                .list_all_pages(notebook.id)
+                .context("Failed to list pages")?
+                .into_iter()
+                .filter(|p| p.deleted_at.is_none())
+                .filter(|p| include_archived || !p.is_archived)
+                .collect();
+            let document = serde_json::json!({
+                "exportedAt": chrono::Utc::now(),
+                "notebook": notebook,
+                "sections": app.list_sections(notebook.id)?,
+                "folders": app.list_folders(notebook.id)?,
+                "pages": pages,
+            });
+            if out == "-" {
+                return crate::output::print_json(&document);
+            }
+            std::fs::write(out_path, serde_json::to_string_pretty(&document)?)
+                .with_context(|| format!("Failed to write {}", out_path.display()))?;
+            (pages.len(), Vec::new())
+        }
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "notebookId": notebook.id.to_string(),
+                "notebookName": notebook.name,
+                "format": format!("{:?}", export_format).to_lowercase(),
+                "path": out,
+                "pages": pages,
+                "errors": errors,
+            });
+            crate::output::print_json(&output)?;
+        }
+        OutputFormat::Plain => {
+            println!(
+                "Exported {} pages from \"{}\" to {}",
+                pages,
+                notebook.name,
+                out_path.display()
+            );
+            for error in &errors {
+                eprintln!("  skipped {}", error);
+            }
+        }
+    }
+
+    Ok(())
+}
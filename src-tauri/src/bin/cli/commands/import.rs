@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use nous_lib::evernote::import_evernote_enex;
+use nous_lib::markdown::import_markdown_to_page;
+use nous_lib::notion::import_notion_zip;
+use nous_lib::obsidian::import_obsidian_vault;
+use nous_lib::storage::backup::import_notebook_from_zip;
+
+use crate::app::App;
+use crate::OutputFormat;
+
+/// Kinds of input `nous-cli import` understands
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportKind {
+    /// ZIP written by `nous-cli export --as zip` or the app's backups
+    NousBackup,
+    /// Notion "Markdown & CSV" export ZIP
+    Notion,
+    /// Evernote `.enex` export
+    Evernote,
+    /// Obsidian vault or any folder of Markdown files
+    Folder,
+    /// A single Markdown file, added to an existing notebook
+    Markdown,
+}
+
+impl ImportKind {
+    fn detect(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            return Ok(ImportKind::Folder);
+        }
+        if !path.is_file() {
+            bail!("{} does not exist", path.display());
+        }
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "zip" => {
+                let file = std::fs::File::open(path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?;
+                let archive = zip::ZipArchive::new(file).context("Not a valid ZIP file")?;
+                // Nous backups always have notebook.json at the root
+                if archive.index_for_name("notebook.json").is_some() {
+                    Ok(ImportKind::NousBackup)
+                } else {
+                    Ok(ImportKind::Notion)
+                }
+            }
+            "enex" => Ok(ImportKind::Evernote),
+            "md" | "markdown" => Ok(ImportKind::Markdown),
+            _ => bail!(
+                "Don't know how to import {} (expected a folder, .zip, .enex or .md file)",
+                path.display()
+            ),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ImportKind::NousBackup => "nous-backup",
+            ImportKind::Notion => "notion",
+            ImportKind::Evernote => "evernote",
+            ImportKind::Folder => "markdown-folder",
+            ImportKind::Markdown => "markdown",
+        }
+    }
+}
+
+pub fn run(
+    app: &App,
+    path: &str,
+    name: Option<&str>,
+    notebook_name: Option<&str>,
+    format: &OutputFormat,
+) -> Result<()> {
+    let path = Path::new(path);
+    let kind = ImportKind::detect(path)?;
+    let notebooks_dir = app.storage.notebooks_base_dir();
+    let name = name.map(|n| n.to_string());
+
+    let (notebook, pages) = match kind {
+        ImportKind::NousBackup => {
+            let existing: Vec<_> = app.list_notebooks()?.iter().map(|n| n.id).collect();
+            let notebook = import_notebook_from_zip(path, &notebooks_dir, &existing)
+                .context("Failed to import backup")?;
+            let pages = app.list_pages(notebook.id)?.len();
+            (notebook, pages)
+        }
+        ImportKind::Notion => {
+            let (notebook, pages) = import_notion_zip(path, &notebooks_dir, name)
+                .context("Failed to import Notion export")?;
+            (notebook, pages.len())
+        }
+        ImportKind::Evernote => {
+            let (notebook, pages) = import_evernote_enex(path, &notebooks_dir, name)
+                .context("Failed to import Evernote export")?;
+            (notebook, pages.len())
+        }
+        ImportKind::Folder => {
+            let (notebook, pages) = import_obsidian_vault(path, &notebooks_dir, name)
+                .context("Failed to import folder")?;
+            (notebook, pages.len())
+        }
+        ImportKind::Markdown => {
+            let Some(notebook_name) = notebook_name else {
+                bail!("Importing a single Markdown file needs --notebook <name>");
+            };
+            let notebook = app.find_notebook(notebook_name)?;
+            let markdown = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let fallback_title = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Imported");
+            let page = import_markdown_to_page(&markdown, notebook.id, fallback_title);
+            app.storage
+                .create_page_from(page)
+                .context("Failed to create page")?;
+            (notebook, 1)
+        }
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "source": kind.label(),
+                "notebookId": notebook.id.to_string(),
+                "notebookName": notebook.name,
+                "pages": pages,
+            });
+            crate::output::print_json(&output)?;
+        }
+        OutputFormat::Plain => {
+            println!(
+                "Imported {} pages into \"{}\" ({})",
+                pages,
+                notebook.name,
+                kind.label()
+            );
+            println!("  ID: {}", notebook.id);
+        }
+    }
+
+    Ok(())
+}
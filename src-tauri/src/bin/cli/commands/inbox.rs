@@ -29,7 +29,7 @@ pub fn run_capture(
                 "tags": item.tags,
                 "capturedAt": item.captured_at.to_rfc3339(),
             });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            crate::output::print_json(&output)?;
         }
         OutputFormat::Plain => {
             println!("Captured to inbox: \"{}\"", item.title);
@@ -64,7 +64,7 @@ pub fn run_list(
                     "source": format!("{:?}", item.source),
                 })
             }).collect();
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            crate::output::print_json(&output)?;
         }
         OutputFormat::Plain => {
             if items.is_empty() {
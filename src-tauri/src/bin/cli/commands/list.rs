@@ -37,7 +37,7 @@ pub fn run(app: &App, format: &OutputFormat, _use_color: bool) -> Result<()> {
                     "notebooks": nb_list,
                 }));
             }
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            crate::output::print_json(&output)?;
         }
         OutputFormat::Plain => {
             for lib in &libraries {
@@ -43,7 +43,7 @@ pub fn run(
                     "createdAt": p.created_at.to_rfc3339(),
                 })
             }).collect();
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            crate::output::print_json(&output)?;
         }
         OutputFormat::Plain => {
             if pages.is_empty() {
@@ -0,0 +1,254 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use nous_lib::markdown::{export_page_to_markdown, parse_markdown_to_blocks};
+use nous_lib::storage::{EditorData, Notebook, Page};
+
+use crate::app::App;
+use crate::mcp::{self, Tool, ToolHandler};
+
+/// Serve the library as an MCP server on stdin/stdout until the client
+/// disconnects
+pub fn run(app: &App, read_only: bool) -> Result<()> {
+    log::info!(
+        "MCP server ready for library '{}'{}",
+        app.current_library.name,
+        if read_only { " (read-only)" } else { "" }
+    );
+    let server = LibraryTools { app, read_only };
+    let stdin = std::io::stdin();
+    mcp::serve(&server, stdin.lock(), std::io::stdout())
+}
+
+struct LibraryTools<'a> {
+    app: &'a App,
+    read_only: bool,
+}
+
+impl ToolHandler for LibraryTools<'_> {
+    fn tools(&self) -> Vec<Tool> {
+        let mut tools = vec![
+            Tool {
+                name: "list_notebooks",
+                description: "List the notebooks in the Nous library.",
+                input_schema: json!({ "type": "object", "properties": {} }),
+            },
+            Tool {
+                name: "search_pages",
+                description: "Search pages by title and text. Returns matching pages \
+                              with their notebook, ID and a snippet.",
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "query": { "type": "string", "description": "Words to search for" },
+                        "notebook": { "type": "string", "description": "Limit to a notebook (name or ID)" },
+                        "limit": { "type": "integer", "description": "Maximum results (default 20)" },
+                    },
+                    "required": ["query"],
+                }),
+            },
+            Tool {
+                name: "get_page",
+                description: "Get a page's content as Markdown.",
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "notebook": { "type": "string", "description": "Notebook name or ID" },
+                        "page": { "type": "string", "description": "Page title (prefix match) or ID" },
+                    },
+                    "required": ["notebook", "page"],
+                }),
+            },
+        ];
+        if !self.read_only {
+            tools.push(Tool {
+                name: "create_page",
+                description: "Create a page in a notebook from Markdown content.",
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "notebook": { "type": "string", "description": "Notebook name or ID" },
+                        "title": { "type": "string" },
+                        "content": { "type": "string", "description": "Page body as Markdown" },
+                        "tags": { "type": "array", "items": { "type": "string" } },
+                        "folder": { "type": "string", "description": "Folder name in the notebook" },
+                    },
+                    "required": ["notebook", "title"],
+                }),
+            });
+        }
+        tools
+    }
+
+    fn call(&self, name: &str, arguments: &Value) -> Result<String> {
+        let result = match name {
+            "list_notebooks" => self.list_notebooks()?,
+            "search_pages" => self.search_pages(arguments)?,
+            "get_page" => return self.get_page(arguments),
+            "create_page" => self.create_page(arguments)?,
+            _ => bail!("Unknown tool '{}'", name),
+        };
+        Ok(serde_json::to_string_pretty(&result)?)
+    }
+}
+
+fn str_arg<'v>(arguments: &'v Value, key: &str) -> Result<&'v str> {
+    arguments
+        .get(key)
+        .and_then(|v| v.as_str())
+        .with_context(|| format!("'{}' is required", key))
+}
+
+impl LibraryTools<'_> {
+    fn resolve_notebook(&self, name_or_id: &str) -> Result<Notebook> {
+        match Uuid::parse_str(name_or_id) {
+            Ok(id) => self.app.get_notebook(id),
+            Err(_) => self.app.find_notebook(name_or_id),
+        }
+    }
+
+    fn resolve_page(&self, notebook: &Notebook, title_or_id: &str) -> Result<Page> {
+        match Uuid::parse_str(title_or_id) {
+            Ok(id) => self
+                .app
+                .storage
+                .get_page(notebook.id, id)
+                .context("Page not found"),
+            Err(_) => self.app.find_page(notebook.id, title_or_id),
+        }
+    }
+
+    fn list_notebooks(&self) -> Result<Value> {
+        let notebooks: Vec<Value> = self
+            .app
+            .list_notebooks()?
+            .iter()
+            .filter(|nb| !nb.archived)
+            .map(|nb| {
+                json!({
+                    "id": nb.id.to_string(),
+                    "name": nb.name,
+                    "encrypted": nb.is_encrypted(),
+                })
+            })
+            .collect();
+        Ok(json!(notebooks))
+    }
+
+    /// Word search over titles and Markdown text. The Tantivy index belongs
+    /// to the daemon, so this scans storage directly; every word must match.
+    fn search_pages(&self, arguments: &Value) -> Result<Value> {
+        let query = str_arg(arguments, "query")?.to_lowercase();
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            bail!("'query' is empty");
+        }
+        let limit = arguments
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(20) as usize;
+        let notebooks = match arguments.get("notebook").and_then(|v| v.as_str()) {
+            Some(name) => vec![self.resolve_notebook(name)?],
+            None => self.app.list_notebooks()?,
+        };
+
+        let mut hits = Vec::new();
+        for nb in notebooks.iter().filter(|nb| !nb.archived) {
+            // Locked notebooks can't be read; skip them
+            let Ok(pages) = self.app.list_pages(nb.id) else {
+                continue;
+            };
+            for page in pages.iter().filter(|p| !p.is_archived) {
+                let title = page.title.to_lowercase();
+                let markdown = export_page_to_markdown(page);
+                // Search the body, not the YAML frontmatter
+                let text = markdown
+                    .split_once("---\n\n")
+                    .map_or(markdown.as_str(), |(_, body)| body);
+                let lower = text.to_lowercase();
+                if !words.iter().all(|w| title.contains(w) || lower.contains(w)) {
+                    continue;
+                }
+                let score = words.iter().filter(|w| title.contains(*w)).count() * 10
+                    + words
+                        .iter()
+                        .map(|w| lower.matches(w).count())
+                        .sum::<usize>();
+                let snippet = text
+                    .lines()
+                    .find(|l| {
+                        let l = l.to_lowercase();
+                        words.iter().any(|w| l.contains(w))
+                    })
+                    .unwrap_or_default()
+                    .chars()
+                    .take(200)
+                    .collect::<String>();
+                hits.push((
+                    score,
+                    page.updated_at,
+                    json!({
+                        "notebookId": nb.id.to_string(),
+                        "notebook": nb.name,
+                        "pageId": page.id.to_string(),
+                        "title": page.title,
+                        "tags": page.tags,
+                        "snippet": snippet,
+                    }),
+                ));
+            }
+        }
+
+        hits.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.cmp(&a.1)));
+        Ok(json!(hits
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, hit)| hit)
+            .collect::<Vec<_>>()))
+    }
+
+    fn get_page(&self, arguments: &Value) -> Result<String> {
+        let notebook = self.resolve_notebook(str_arg(arguments, "notebook")?)?;
+        let page = self.resolve_page(&notebook, str_arg(arguments, "page")?)?;
+        Ok(export_page_to_markdown(&page))
+    }
+
+    fn create_page(&self, arguments: &Value) -> Result<Value> {
+        if self.read_only {
+            bail!("This server is read-only");
+        }
+        let notebook = self.resolve_notebook(str_arg(arguments, "notebook")?)?;
+        let title = str_arg(arguments, "title")?.trim();
+        if title.is_empty() {
+            bail!("'title' is empty");
+        }
+
+        let mut page = self.app.create_page(notebook.id, title.to_string())?;
+        if let Some(folder) = arguments.get("folder").and_then(|v| v.as_str()) {
+            page.folder_id = Some(self.app.find_folder(notebook.id, folder)?.id);
+        }
+        if let Some(tags) = arguments.get("tags").and_then(|v| v.as_array()) {
+            page.tags = tags
+                .iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+        }
+        if let Some(content) = arguments.get("content").and_then(|v| v.as_str()) {
+            page.content = EditorData {
+                time: Some(chrono::Utc::now().timestamp_millis()),
+                version: Some("2.28.0".to_string()),
+                blocks: parse_markdown_to_blocks(content),
+            };
+        }
+        self.app.update_page(&page)?;
+
+        Ok(json!({
+            "id": page.id.to_string(),
+            "notebookId": notebook.id.to_string(),
+            "title": page.title,
+        }))
+    }
+}
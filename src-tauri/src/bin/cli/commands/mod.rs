@@ -1,6 +1,13 @@
+pub mod action;
+pub mod automation_script;
+pub mod completions;
+pub mod edit;
+pub mod export;
+pub mod import;
 pub mod inbox;
 pub mod list;
 pub mod ls;
+pub mod mcp_serve;
 pub mod new;
 pub mod search;
 pub mod show;
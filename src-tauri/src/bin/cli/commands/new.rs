@@ -61,7 +61,7 @@ pub fn run(
                 "folderId": page.folder_id.map(|f| f.to_string()),
                 "tags": page.tags,
             });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            crate::output::print_json(&output)?;
         }
         OutputFormat::Plain => {
             println!("Created page \"{}\" in notebook \"{}\"", page.title, notebook.name);
@@ -1,11 +1,46 @@
 use anyhow::Result;
 
+use nous_lib::markdown::export_page_to_markdown;
+use nous_lib::storage::PageType;
+
 use crate::app::App;
 use crate::render::terminal;
+use crate::OutputFormat;
 
-pub fn run(app: &App, notebook_name: &str, page_title: &str, use_color: bool) -> Result<()> {
+pub fn run(
+    app: &App,
+    notebook_name: &str,
+    page_title: &str,
+    format: &OutputFormat,
+    use_color: bool,
+) -> Result<()> {
     let notebook = app.find_notebook(notebook_name)?;
     let page = app.find_page(notebook.id, page_title)?;
+    // Not worth failing `show` over
+    let stats = app.storage.page_stats(notebook.id, page.id).ok().flatten();
+
+    if let OutputFormat::Json = format {
+        let markdown = match page.page_type {
+            PageType::Standard | PageType::Sql => Some(export_page_to_markdown(&page)),
+            PageType::Markdown => app.storage.read_native_file_content(&page).ok(),
+            _ => None,
+        };
+        let output = serde_json::json!({
+            "id": page.id.to_string(),
+            "notebookId": notebook.id.to_string(),
+            "title": page.title,
+            "pageType": format!("{:?}", page.page_type).to_lowercase(),
+            "tags": page.tags,
+            "folderId": page.folder_id.map(|f| f.to_string()),
+            "sourceFile": page.source_file,
+            "createdAt": page.created_at.to_rfc3339(),
+            "updatedAt": page.updated_at.to_rfc3339(),
+            "stats": stats,
+            "markdown": markdown,
+            "blocks": page.content.blocks,
+        });
+        return crate::output::print_json(&output);
+    }
 
     // Print header
     if use_color {
@@ -26,9 +61,24 @@ pub fn run(app: &App, notebook_name: &str, page_title: &str, use_color: bool) ->
         }
     }
 
+    if let Some(stats) = stats {
+        let line = format!(
+            "{} words · {} min read · {} characters · {} revisions",
+            stats.word_count,
+            stats.reading_time_minutes,
+            stats.character_count,
+            stats.revision_count
+        );
+        if use_color {
+            println!("{}{}{}", terminal::Color::DIM, line, terminal::Color::RESET);
+        } else {
+            println!("{}", line);
+        }
+    }
+
     let page_type = format!("{:?}", page.page_type).to_lowercase();
     match page_type.as_str() {
-        "standard" => {
+        "standard" | "sql" => {
             println!();
             let rendered = terminal::render_blocks(&page.content.blocks, use_color);
             println!("{}", rendered);
@@ -27,7 +27,7 @@ pub fn run(
                     "count": count,
                 })
             }).collect();
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            crate::output::print_json(&output)?;
         }
         OutputFormat::Plain => {
             if tags.is_empty() {
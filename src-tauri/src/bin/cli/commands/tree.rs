@@ -1,16 +1,35 @@
 use anyhow::Result;
 
 use crate::app::App;
-use nous_lib::storage::{Folder, Page};
+use crate::OutputFormat;
+use nous_lib::storage::{Folder, Page, PageContainer, PageOrder};
 
-pub fn run(app: &App, notebook_name: &str, _use_color: bool) -> Result<()> {
+pub fn run(app: &App, notebook_name: &str, format: &OutputFormat, _use_color: bool) -> Result<()> {
     let notebook = app.find_notebook(notebook_name)?;
     let folders = app.list_folders(notebook.id)?;
-    let mut pages = app.list_pages(notebook.id)?;
+    let sections = app.list_sections(notebook.id)?;
+    let mut pages = app.list_pages_in_order(notebook.id)?;
+    let order = PageOrder::new(&notebook, &folders, &sections);
 
     // Filter out deleted pages
     pages.retain(|p| p.deleted_at.is_none());
 
+    if let OutputFormat::Json = format {
+        let output = serde_json::json!({
+            "id": notebook.id.to_string(),
+            "name": notebook.name,
+            "folders": folders.iter()
+                .filter(|f| f.parent_id.is_none())
+                .map(|f| folder_json(f, &folders, &pages, &order))
+                .collect::<Vec<_>>(),
+            "pages": pages.iter()
+                .filter(|p| p.folder_id.is_none())
+                .map(|p| page_json(p, &order))
+                .collect::<Vec<_>>(),
+        });
+        return crate::output::print_json(&output);
+    }
+
     println!("{}", notebook.name);
 
     // Build tree: root folders + root pages
@@ -28,7 +47,7 @@ pub fn run(app: &App, notebook_name: &str, _use_color: bool) -> Result<()> {
     for folder in &root_folders {
         item_idx += 1;
         let is_last = item_idx == total_items;
-        print_folder(folder, &folders, &pages, "", is_last);
+        print_folder(folder, &folders, &pages, &order, "", is_last);
     }
 
     // Print root pages
@@ -36,13 +55,20 @@ pub fn run(app: &App, notebook_name: &str, _use_color: bool) -> Result<()> {
         item_idx += 1;
         let is_last = item_idx == total_items;
         let connector = if is_last { "\u{2514}\u{2500}\u{2500} " } else { "\u{251c}\u{2500}\u{2500} " };
-        println!("{}{}", connector, page.title);
+        println!("{}{}", connector, page_label(page, &order));
     }
 
     Ok(())
 }
 
-fn print_folder(folder: &Folder, all_folders: &[Folder], all_pages: &[Page], prefix: &str, is_last: bool) {
+fn print_folder(
+    folder: &Folder,
+    all_folders: &[Folder],
+    all_pages: &[Page],
+    order: &PageOrder,
+    prefix: &str,
+    is_last: bool,
+) {
     let connector = if is_last { "\u{2514}\u{2500}\u{2500} " } else { "\u{251c}\u{2500}\u{2500} " };
     println!("{}{}{}/", prefix, connector, folder.name);
 
@@ -64,13 +90,49 @@ fn print_folder(folder: &Folder, all_folders: &[Folder], all_pages: &[Page], pre
     for child_folder in &child_folders {
         child_idx += 1;
         let child_is_last = child_idx == total_children;
-        print_folder(child_folder, all_folders, all_pages, &child_prefix, child_is_last);
+        print_folder(child_folder, all_folders, all_pages, order, &child_prefix, child_is_last);
     }
 
     for page in &child_pages {
         child_idx += 1;
         let child_is_last = child_idx == total_children;
         let child_connector = if child_is_last { "\u{2514}\u{2500}\u{2500} " } else { "\u{251c}\u{2500}\u{2500} " };
-        println!("{}{}{}", child_prefix, child_connector, page.title);
+        println!("{}{}{}", child_prefix, child_connector, page_label(page, order));
+    }
+}
+
+fn folder_json(folder: &Folder, all_folders: &[Folder], all_pages: &[Page], order: &PageOrder) -> serde_json::Value {
+    serde_json::json!({
+        "id": folder.id.to_string(),
+        "name": folder.name,
+        "pageSortBy": order.sort(PageContainer::Folder(folder.id)).as_str(),
+        "folders": all_folders.iter()
+            .filter(|f| f.parent_id == Some(folder.id))
+            .map(|f| folder_json(f, all_folders, all_pages, order))
+            .collect::<Vec<_>>(),
+        "pages": all_pages.iter()
+            .filter(|p| p.folder_id == Some(folder.id))
+            .map(|p| page_json(p, order))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn page_json(page: &Page, order: &PageOrder) -> serde_json::Value {
+    serde_json::json!({
+        "id": page.id.to_string(),
+        "title": page.title,
+        "pinned": is_pinned(page, order),
+    })
+}
+
+fn is_pinned(page: &Page, order: &PageOrder) -> bool {
+    order.pinned(PageContainer::of(page)).contains(&page.id)
+}
+
+fn page_label(page: &Page, order: &PageOrder) -> String {
+    if is_pinned(page, order) {
+        format!("{} (pinned)", page.title)
+    } else {
+        page.title.clone()
     }
 }
@@ -3,6 +3,8 @@
 //! Provides:
 //! - Action scheduler (daily note creation, etc.)
 //! - WebDAV sync on schedule
+//! - Scheduled Markdown mirrors of libraries
+//! - Two-way Markdown folder sync for folder-backed notebooks
 //! - HTTP API for external processes (MCP server, scripts)
 
 use std::path::PathBuf;
@@ -12,8 +14,10 @@ use anyhow::{bail, Context, Result};
 use tokio::signal;
 
 use nous_lib::actions::{ActionExecutor, ActionScheduler, ActionStorage};
+use nous_lib::clipper::ClipperStorage;
 use nous_lib::commands::{start_backup_scheduler, BackupScheduler};
 use nous_lib::contacts::ContactsStorage;
+use nous_lib::encryption::EncryptionManager;
 use nous_lib::energy::EnergyStorage;
 use nous_lib::goals::GoalsStorage;
 use nous_lib::inbox::InboxStorage;
@@ -24,7 +28,7 @@ use nous_lib::python_bridge::PythonAI;
 use nous_lib::search::{
     self as search_mod, DaemonConfig, RagBackend, RagConfig, SearchIndex, TantivyBackend,
 };
-use nous_lib::storage::FileStorage;
+use nous_lib::storage::{FileStorage, FileStorageWriter, StorageLock};
 use nous_lib::sync::{CrdtStore, LogEmitter, SyncManager};
 use tokio::sync::RwLock;
 
@@ -36,14 +40,19 @@ pub type DaemonEvent = nous_lib::events::AppEvent;
 
 /// Shared state for the daemon (passed to HTTP handlers and schedulers)
 pub struct DaemonState {
-    pub storage: Arc<Mutex<FileStorage>>,
+    pub storage: Arc<StorageLock>,
     pub library_storage: Arc<Mutex<LibraryStorage>>,
     pub inbox_storage: Arc<Mutex<InboxStorage>>,
+    /// Paired browser-extension clients and the clipped-URL registry
+    /// behind /api/clipper/*.
+    pub clipper_storage: Arc<Mutex<ClipperStorage>>,
     pub goals_storage: Arc<Mutex<GoalsStorage>>,
     pub energy_storage: Arc<Mutex<EnergyStorage>>,
     pub contacts_storage: Arc<Mutex<ContactsStorage>>,
     pub sync_manager: Arc<SyncManager>,
     pub action_scheduler: Mutex<ActionScheduler>,
+    /// Runs action steps for handlers, e.g. the daily note rollover
+    pub action_executor: Arc<Mutex<ActionExecutor>>,
     /// Raw Tantivy index. Kept for the few handlers that touch it
     /// directly (POST /api/search/rebuild) — most search now goes
     /// through `tantivy` (the backend wrapper).
@@ -62,10 +71,16 @@ pub struct DaemonState {
     /// /api/ai/* handlers fill omitted request fields from here;
     /// updated via /api/ai/configure.
     pub ai_config: Arc<RwLock<nous_lib::ai_config::AiSection>>,
+    /// Secrets store the `[ai]` keys are kept in once it's unlocked; None
+    /// leaves them in daemon-config.toml.
+    pub secrets: Option<Arc<nous_lib::secrets::SecretStore>>,
     /// Path to the on-disk daemon-config.toml — the configure
     /// endpoint writes back here when it persists changes.
     pub daemon_config_path: PathBuf,
     pub crdt_store: Arc<CrdtStore>,
+    /// Keys of pages unlocked through /api/notebooks/{id}/pages/{id}/unlock.
+    /// Held in memory only, so every encrypted page is locked on restart.
+    pub encryption_manager: Arc<EncryptionManager>,
     pub backup_scheduler: Arc<BackupScheduler>,
     /// Plugin host (Lua VM, capability gating, hook dispatch). Optional
     /// at the type level so the daemon still compiles with the `plugins`
@@ -137,13 +152,15 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     log::info!("Using library: {} at {}", current_library.name, library_path.display());
 
     // Initialize file storage
-    let storage = FileStorage::new(library_path.clone());
+    let storage = FileStorageWriter::new(FileStorage::new(library_path.clone()));
     storage.init().context("Failed to initialize storage")?;
 
     // Initialize Tantivy search index. The daemon owns the writer lock —
     // a collision here means another process (likely a stale daemon or the
     // desktop app from before the migration) is still holding it.
+    let transcript_index_path = library_path.join("transcript_index");
     let mut search_index = SearchIndex::new(library_path.join("search_index"))
+        .and_then(|idx| idx.with_transcripts(transcript_index_path))
         .context(
             "Failed to initialize search index — another process is holding the Tantivy writer lock. \
              Stop any running desktop app or stale daemon and retry.",
@@ -153,7 +170,8 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     // schema migration (SearchIndex::open_or_recreate) — repopulate it from all
     // pages so search works immediately instead of staying blank until each page
     // is next edited.
-    if search_index.num_docs() == 0 {
+    let index_empty = search_index.num_docs() == 0;
+    if index_empty || search_index.transcripts_created() {
         let mut pages = Vec::new();
         if let Ok(notebooks) = storage.list_notebooks() {
             for nb in notebooks {
@@ -167,11 +185,19 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
                 }
             }
         }
-        if !pages.is_empty() {
+        if !pages.is_empty() && index_empty {
+            // rebuild_index also repopulates the attached transcript index.
             match search_index.rebuild_index(&pages) {
                 Ok(()) => log::info!("Populated empty search index with {} pages", pages.len()),
                 Err(e) => log::warn!("Startup search index population failed: {}", e),
             }
+        } else if !pages.is_empty() {
+            // New (or schema-migrated) transcript index: backfill it from the
+            // existing pages so previously transcribed videos are searchable.
+            match search_index.rebuild_transcripts(&pages) {
+                Ok(()) => log::info!("Populated transcript index from {} pages", pages.len()),
+                Err(e) => log::warn!("Startup transcript index population failed: {}", e),
+            }
         }
     }
 
@@ -182,6 +208,8 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     // Initialize storages
     let inbox_storage = InboxStorage::new(library_path.clone())
         .context("Failed to initialize inbox storage")?;
+    let clipper_storage = ClipperStorage::new(library_path.clone())
+        .context("Failed to initialize clipper storage")?;
     let goals_storage = GoalsStorage::new(library_path.clone())
         .context("Failed to initialize goals storage")?;
     let energy_storage = EnergyStorage::new(data_dir.clone())
@@ -197,7 +225,7 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     let python_ai = PythonAI::new(nous_py_path);
 
     // Wrap in Arc<Mutex<>>
-    let storage_arc = Arc::new(Mutex::new(storage));
+    let storage_arc = Arc::new(StorageLock::new(storage));
     let library_storage_arc = Arc::new(Mutex::new(library_storage));
     let inbox_storage_arc = Arc::new(Mutex::new(inbox_storage));
     let goals_storage_arc = Arc::new(Mutex::new(goals_storage));
@@ -232,7 +260,29 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
         );
     }
     let rag_config = Arc::new(RwLock::new(daemon_config.search.rag.clone()));
-    let ai_config = Arc::new(RwLock::new(daemon_config.ai.clone()));
+
+    // Provider keys move from daemon-config.toml into the secrets store;
+    // the live section keeps them filled in memory
+    let secrets = nous_lib::secrets::shared(&data_dir);
+    let mut ai_section = daemon_config.ai.clone();
+    if secrets.is_unlocked() {
+        match nous_lib::secrets::migrate::store_ai_keys(&secrets, &mut ai_section) {
+            Ok(true) => {
+                let to_persist = DaemonConfig {
+                    search: daemon_config.search.clone(),
+                    ai: ai_section.for_disk(),
+                };
+                match search_mod::save(&daemon_config_path, &to_persist) {
+                    Ok(()) => log::info!("Moved AI provider keys into the secrets store"),
+                    Err(e) => log::warn!("Failed to persist daemon config: {}", e),
+                }
+            }
+            Ok(false) => {}
+            Err(e) => log::warn!("Could not move AI provider keys into the secrets store: {}", e),
+        }
+    }
+    nous_lib::secrets::migrate::load_ai_keys(&secrets, &mut ai_section);
+    let ai_config = Arc::new(RwLock::new(ai_section));
     let rag_backend = Arc::new(RagBackend::new(Arc::clone(&rag_config)));
 
     // Create event broadcast channel (capacity 256 — events are small)
@@ -273,6 +323,7 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     action_executor.set_goals_storage(Arc::clone(&goals_storage_arc));
     action_executor.set_energy_storage(Arc::clone(&energy_storage_arc));
     action_executor.set_inbox_storage(Arc::clone(&inbox_storage_arc));
+    action_executor.set_secrets(Arc::clone(&secrets));
     action_executor.set_event_tx(event_tx.clone());
     #[cfg(feature = "plugins")]
     action_executor.set_plugin_host(plugin_host.clone());
@@ -307,6 +358,7 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     let sync_manager_arc = Arc::new(sync_manager);
     sync_manager_arc.set_emitter(Arc::new(LogEmitter));
     sync_manager_arc.set_crdt_store(Arc::clone(&crdt_store));
+    sync_manager_arc.set_secrets(Arc::clone(&secrets));
 
     // Start sync scheduler. The daemon is the sync owner, so it never yields
     // (DL-21) — `None` predicate.
@@ -318,14 +370,22 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
         Arc::clone(&inbox_storage_arc),
         Arc::clone(&contacts_storage_arc),
         Arc::clone(&energy_storage_arc),
+        Arc::clone(&action_storage_arc),
         None,
     );
     log::info!("Sync scheduler started");
 
     // Daemon is the sole owner of periodic backups; Tauri no longer constructs one.
-    let backup_scheduler = Arc::new(start_backup_scheduler(Arc::clone(&storage_arc)));
+    let backup_scheduler = Arc::new(start_backup_scheduler(
+        Arc::clone(&storage_arc),
+        Arc::clone(&library_storage_arc),
+    ));
     log::info!("Backup scheduler started");
 
+    // Markdown mirrors cover every library, not just the one being served
+    let mirror_scheduler =
+        nous_lib::markdown::mirror::start_mirror_scheduler(Arc::clone(&library_storage_arc));
+
     // Web bundle directory for /app (see `just web-deploy`)
     let web_app_dir = std::env::var_os("NOUS_WEB_APP_DIR")
         .map(PathBuf::from)
@@ -344,18 +404,22 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
         storage: storage_arc,
         library_storage: library_storage_arc,
         inbox_storage: inbox_storage_arc,
+        clipper_storage: Arc::new(Mutex::new(clipper_storage)),
         goals_storage: goals_storage_arc,
         energy_storage: energy_storage_arc,
         contacts_storage: contacts_storage_arc,
         sync_manager: sync_manager_arc,
         action_scheduler: Mutex::new(action_scheduler),
+        action_executor: action_executor_arc,
         search_index: search_index_arc,
         tantivy: tantivy_backend,
         rag: rag_backend,
         rag_config,
         ai_config,
+        secrets: Some(secrets),
         daemon_config_path,
         crdt_store,
+        encryption_manager: Arc::new(EncryptionManager::new()),
         backup_scheduler: Arc::clone(&backup_scheduler),
         #[cfg(feature = "plugins")]
         plugin_host,
@@ -365,6 +429,65 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
         event_tx,
     });
 
+    // Encrypted pages unlocked in an earlier run may still be indexed; after
+    // that, drop pages from the indexes as their keys auto-lock.
+    {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            api::unindex_encrypted_pages(&state);
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                ticker.tick().await;
+                api::expire_unlocked_pages(&state);
+            }
+        });
+    }
+
+    // Folder sync passes run on their own thread; their results are applied
+    // (search, RAG, events) back on the runtime.
+    let (report_tx, mut report_rx) =
+        tokio::sync::mpsc::unbounded_channel::<nous_lib::folder_sync::FolderSyncReport>();
+    let folder_sync = Arc::new(nous_lib::folder_sync::start(
+        Arc::clone(&state.storage),
+        move |report| {
+            let _ = report_tx.send(report);
+        },
+    ));
+    {
+        let state = Arc::clone(&state);
+        let folder_sync = Arc::clone(&folder_sync);
+        let mut events = state.event_tx.subscribe();
+        tokio::spawn(async move {
+            use tokio::sync::broadcast::error::RecvError;
+            loop {
+                tokio::select! {
+                    Some(report) = report_rx.recv() => {
+                        api::apply_folder_sync_report(&state, &report);
+                    }
+                    event = events.recv() => match event {
+                        Ok(event) if event.event.starts_with("page.") => {
+                            if let Some(id) = event
+                                .data
+                                .get("notebookId")
+                                .and_then(|v| v.as_str())
+                                .and_then(|s| uuid::Uuid::parse_str(s).ok())
+                            {
+                                folder_sync.notebook_changed(id);
+                            }
+                        }
+                        Ok(event) if event.event == "folder_sync.configured" => {
+                            folder_sync.reload();
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(_)) => folder_sync.reload(),
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+        });
+    }
+    log::info!("Folder sync watcher started");
+
     // Load API keys and configure auth
     let key_path = auth::key_file_path(&data_dir);
     let bind_addr: std::net::IpAddr = bind
@@ -411,6 +534,7 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
     log::info!("Shutting down...");
     sync_scheduler.shutdown();
     state.backup_scheduler.shutdown();
+    mirror_scheduler.shutdown();
     if let Ok(sched) = state.action_scheduler.lock() {
         sched.shutdown();
     }
@@ -421,26 +545,33 @@ pub async fn run(library_name: Option<&str>, port: Option<u16>, bind: Option<&st
 }
 
 /// Get the status of a running daemon
-pub fn status() -> Result<()> {
+pub fn status(format: &crate::OutputFormat) -> Result<()> {
     let data_dir = FileStorage::default_data_dir()
         .context("Failed to get data directory")?;
     let pid_path = data_dir.join(".nous-daemon.pid");
 
-    if !pid_path.exists() {
-        println!("Daemon is not running (no PID file)");
-        return Ok(());
-    }
-
-    let pid_str = std::fs::read_to_string(&pid_path)
-        .context("Failed to read PID file")?;
-    let pid: u32 = pid_str.trim().parse()
-        .context("Invalid PID in file")?;
-
-    if is_process_running(pid) {
-        println!("Daemon is running (PID {})", pid);
+    let (running, pid, message) = if !pid_path.exists() {
+        (false, None, "Daemon is not running (no PID file)".to_string())
     } else {
-        println!("Daemon is not running (stale PID file for PID {})", pid);
-        let _ = std::fs::remove_file(&pid_path);
+        let pid_str = std::fs::read_to_string(&pid_path)
+            .context("Failed to read PID file")?;
+        let pid: u32 = pid_str.trim().parse()
+            .context("Invalid PID in file")?;
+
+        if is_process_running(pid) {
+            (true, Some(pid), format!("Daemon is running (PID {})", pid))
+        } else {
+            let _ = std::fs::remove_file(&pid_path);
+            (false, None, format!("Daemon is not running (stale PID file for PID {})", pid))
+        }
+    };
+
+    match format {
+        crate::OutputFormat::Json => crate::output::print_json(&serde_json::json!({
+            "running": running,
+            "pid": pid,
+        }))?,
+        crate::OutputFormat::Plain => println!("{}", message),
     }
 
     Ok(())
@@ -491,7 +622,7 @@ pub fn uninstall() -> Result<()> {
 
 // ===== Helpers =====
 
-fn find_nous_py_path() -> PathBuf {
+pub fn find_nous_py_path() -> PathBuf {
     // Explicit override for daemons launched outside the repo (systemd).
     if let Ok(p) = std::env::var("NOUS_PY_PATH") {
         let p = PathBuf::from(p);
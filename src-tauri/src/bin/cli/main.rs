@@ -3,6 +3,9 @@ mod app;
 mod auth;
 mod commands;
 mod daemon;
+mod mcp;
+mod output;
+mod query;
 mod render;
 #[cfg(feature = "tui")]
 mod tui;
@@ -10,19 +13,26 @@ mod tui;
 #[cfg(test)]
 mod test_harness;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::engine::ArgValueCandidates;
+
+use commands::completions::{library_candidates, notebook_candidates};
 
 #[derive(Parser)]
 #[command(name = "nous-cli", about = "Nous notebook CLI and TUI", version)]
 struct Cli {
     /// Use a specific library (default: current)
-    #[arg(long, global = true)]
+    #[arg(long, global = true, add = ArgValueCandidates::new(library_candidates))]
     library: Option<String>,
 
     /// Output format
     #[arg(long, global = true, default_value = "plain")]
     format: OutputFormat,
 
+    /// Filter/project JSON output with a JMESPath-like expression (implies --format json)
+    #[arg(long, global = true)]
+    query: Option<String>,
+
     /// Disable ANSI colors
     #[arg(long, global = true)]
     no_color: bool,
@@ -45,6 +55,7 @@ enum Command {
     /// List pages in a notebook
     Ls {
         /// Notebook name (case-insensitive prefix match)
+        #[arg(add = ArgValueCandidates::new(notebook_candidates))]
         notebook: String,
         /// Filter by folder name
         #[arg(long)]
@@ -54,6 +65,16 @@ enum Command {
     /// Show page content
     Show {
         /// Notebook name
+        #[arg(add = ArgValueCandidates::new(notebook_candidates))]
+        notebook: String,
+        /// Page title (case-insensitive prefix match)
+        page: String,
+    },
+
+    /// Edit a page as Markdown in $VISUAL / $EDITOR
+    Edit {
+        /// Notebook name
+        #[arg(add = ArgValueCandidates::new(notebook_candidates))]
         notebook: String,
         /// Page title (case-insensitive prefix match)
         page: String,
@@ -62,12 +83,14 @@ enum Command {
     /// Show folder tree of a notebook
     Tree {
         /// Notebook name
+        #[arg(add = ArgValueCandidates::new(notebook_candidates))]
         notebook: String,
     },
 
     /// Create a new page in a notebook
     New {
         /// Notebook name (case-insensitive prefix match)
+        #[arg(add = ArgValueCandidates::new(notebook_candidates))]
         notebook: String,
         /// Page title (defaults to "Quick Note YYYY-MM-DD HH:MM")
         title: Option<String>,
@@ -82,6 +105,34 @@ enum Command {
         content: Option<String>,
     },
 
+    /// Export a notebook as Markdown files, a ZIP backup or JSON
+    Export {
+        /// Notebook name (case-insensitive prefix match)
+        #[arg(add = ArgValueCandidates::new(notebook_candidates))]
+        notebook: String,
+        /// Output path (a directory for Markdown; "-" writes JSON to stdout)
+        #[arg(long)]
+        out: String,
+        /// What to write (default: guessed from --out, Markdown for directories)
+        #[arg(long = "as", value_enum)]
+        export_as: Option<commands::export::ExportFormat>,
+        /// Include archived pages
+        #[arg(long)]
+        include_archived: bool,
+    },
+
+    /// Import a Nous backup, Notion ZIP, Evernote ENEX, Obsidian vault or Markdown
+    Import {
+        /// File or folder to import (type is detected automatically)
+        path: String,
+        /// Name for the new notebook
+        #[arg(long)]
+        name: Option<String>,
+        /// Notebook to add a single Markdown file to
+        #[arg(long, add = ArgValueCandidates::new(notebook_candidates))]
+        notebook: Option<String>,
+    },
+
     /// Inbox capture and listing
     #[command(subcommand)]
     Inbox(InboxCommand),
@@ -91,7 +142,7 @@ enum Command {
         /// Search query
         query: String,
         /// Filter by notebook name
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(notebook_candidates))]
         notebook: Option<String>,
         /// Maximum results
         #[arg(long, default_value = "20")]
@@ -101,14 +152,47 @@ enum Command {
     /// List tags with counts
     Tags {
         /// Filter by notebook name
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(notebook_candidates))]
         notebook: Option<String>,
     },
 
+    /// List and run actions (automations) without the GUI
+    #[command(subcommand)]
+    Action(ActionCommand),
+
     /// Headless background daemon
     #[command(subcommand)]
     Daemon(DaemonCommand),
 
+    /// Print a shell completion script (e.g. `source <(nous-cli completions bash)`)
+    Completions {
+        #[arg(value_enum)]
+        shell: commands::completions::CompletionShell,
+    },
+
+    /// Print an AppleScript library or PowerShell module for the daemon's automation API
+    AutomationScript {
+        #[arg(value_enum)]
+        language: commands::automation_script::ScriptLanguage,
+        /// Daemon port
+        #[arg(long, default_value = "7667")]
+        port: u16,
+    },
+
+    /// Print the man page, or write one per subcommand with --out-dir
+    Man {
+        /// Directory to write nous-cli.1 and nous-cli-<command>.1 into
+        #[arg(long)]
+        out_dir: Option<String>,
+    },
+
+    /// Serve the library to AI agents as an MCP server over stdio
+    McpServe {
+        /// Only expose read tools (no create_page)
+        #[arg(long)]
+        read_only: bool,
+    },
+
     /// Launch interactive TUI
     #[cfg(feature = "tui")]
     Tui,
@@ -136,6 +220,24 @@ enum InboxCommand {
     },
 }
 
+#[derive(Subcommand)]
+enum ActionCommand {
+    /// List actions in the library
+    List,
+
+    /// Run an action now (exits non-zero if any step fails)
+    Run {
+        /// Action name (case-insensitive, partial match) or ID
+        name: String,
+        /// Set a variable, e.g. --var project=Website (repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// Notebook to run in (default: the action's default notebook)
+        #[arg(long, add = ArgValueCandidates::new(notebook_candidates))]
+        notebook: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum DaemonCommand {
     /// Start the daemon in the foreground
@@ -198,10 +300,19 @@ fn stdin_is_tty() -> bool {
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
+    // Answers the shell when it calls back for completions (COMPLETE=<shell>)
+    clap_complete::CompleteEnv::with_factory(Cli::command).complete();
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
     let use_color = !cli.no_color && atty_check();
 
+    if let Some(expr) = cli.query.as_deref() {
+        let query = query::Query::parse(expr)
+            .map_err(|e| anyhow::anyhow!("Invalid --query: {}", e))?;
+        output::set_query(query);
+        cli.format = OutputFormat::Json;
+    }
+
     match cli.command {
         None => {
             // No subcommand → launch TUI
@@ -226,11 +337,15 @@ fn main() -> anyhow::Result<()> {
         }
         Some(Command::Show { notebook, page }) => {
             let app = app::App::new(cli.library.as_deref())?;
-            commands::show::run(&app, &notebook, &page, use_color)?;
+            commands::show::run(&app, &notebook, &page, &cli.format, use_color)?;
+        }
+        Some(Command::Edit { notebook, page }) => {
+            let app = app::App::new(cli.library.as_deref())?;
+            commands::edit::run(&app, &notebook, &page, &cli.format)?;
         }
         Some(Command::Tree { notebook }) => {
             let app = app::App::new(cli.library.as_deref())?;
-            commands::tree::run(&app, &notebook, use_color)?;
+            commands::tree::run(&app, &notebook, &cli.format, use_color)?;
         }
         Some(Command::New { notebook, title, folder, tags, content }) => {
             let app = app::App::new(cli.library.as_deref())?;
@@ -246,6 +361,14 @@ fn main() -> anyhow::Result<()> {
                 use_color,
             )?;
         }
+        Some(Command::Export { notebook, out, export_as, include_archived }) => {
+            let app = app::App::new(cli.library.as_deref())?;
+            commands::export::run(&app, &notebook, export_as, &out, include_archived, &cli.format)?;
+        }
+        Some(Command::Import { path, name, notebook }) => {
+            let app = app::App::new(cli.library.as_deref())?;
+            commands::import::run(&app, &path, name.as_deref(), notebook.as_deref(), &cli.format)?;
+        }
         Some(Command::Inbox(subcmd)) => {
             let app = app::App::new(cli.library.as_deref())?;
             match subcmd {
@@ -273,6 +396,17 @@ fn main() -> anyhow::Result<()> {
             let app = app::App::new(cli.library.as_deref())?;
             commands::tags::run(&app, notebook.as_deref(), &cli.format, use_color)?;
         }
+        Some(Command::Action(subcmd)) => {
+            let app = app::App::new(cli.library.as_deref())?;
+            match subcmd {
+                ActionCommand::List => {
+                    commands::action::run_list(&app, &cli.format, use_color)?;
+                }
+                ActionCommand::Run { name, vars, notebook } => {
+                    commands::action::run(&app, &name, &vars, notebook.as_deref(), &cli.format)?;
+                }
+            }
+        }
         Some(Command::Daemon(subcmd)) => {
             match subcmd {
                 DaemonCommand::Start { port, bind } => {
@@ -281,7 +415,7 @@ fn main() -> anyhow::Result<()> {
                     rt.block_on(daemon::run(cli.library.as_deref(), port, bind.as_deref()))?;
                 }
                 DaemonCommand::Status => {
-                    daemon::status()?;
+                    daemon::status(&cli.format)?;
                 }
                 DaemonCommand::Install => {
                     daemon::install()?;
@@ -296,8 +430,17 @@ fn main() -> anyhow::Result<()> {
                     let path = auth::key_file_path(&data_dir);
                     let comment = format!("{} key", scope);
                     auth::write_key_to_file(&path, &key, Some(&comment))?;
-                    println!("{}", key);
-                    eprintln!("Key written to {}", path.display());
+                    match cli.format {
+                        OutputFormat::Json => output::print_json(&serde_json::json!({
+                            "key": key,
+                            "scope": scope.to_string(),
+                            "path": path.display().to_string(),
+                        }))?,
+                        OutputFormat::Plain => {
+                            println!("{}", key);
+                            eprintln!("Key written to {}", path.display());
+                        }
+                    }
                 }
                 DaemonCommand::ShowKey => {
                     let data_dir = nous_lib::storage::FileStorage::default_data_dir()?;
@@ -309,7 +452,12 @@ fn main() -> anyhow::Result<()> {
                     }
                     let keys = auth::ApiKeySet::load(&path)?;
                     if let Some(key) = keys.first_rw_key() {
-                        println!("{}", key);
+                        match cli.format {
+                            OutputFormat::Json => {
+                                output::print_json(&serde_json::json!({ "key": key }))?
+                            }
+                            OutputFormat::Plain => println!("{}", key),
+                        }
                     } else {
                         eprintln!("No read-write key found in {}", path.display());
                         std::process::exit(1);
@@ -317,6 +465,19 @@ fn main() -> anyhow::Result<()> {
                 }
             }
         }
+        Some(Command::Completions { shell }) => {
+            commands::completions::run(&shell)?;
+        }
+        Some(Command::AutomationScript { language, port }) => {
+            commands::automation_script::run(&language, port)?;
+        }
+        Some(Command::Man { out_dir }) => {
+            commands::completions::run_man(Cli::command(), out_dir.as_deref())?;
+        }
+        Some(Command::McpServe { read_only }) => {
+            let app = app::App::new(cli.library.as_deref())?;
+            commands::mcp_serve::run(&app, read_only)?;
+        }
         #[cfg(feature = "tui")]
         Some(Command::Tui) => {
             tui::run(cli.library.as_deref())?;
@@ -0,0 +1,243 @@
+//! Minimal MCP (Model Context Protocol) server over stdio.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. Only the `tools` capability
+//! is implemented: `initialize`, `ping`, `tools/list` and `tools/call`. What
+//! the tools do is up to the [`ToolHandler`]; this module only handles
+//! framing and dispatch. Logs must go to stderr — stdout is the protocol
+//! channel.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+/// Protocol revisions we can speak, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// A tool exposed to MCP clients
+pub struct Tool {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema for the tool's arguments
+    pub input_schema: Value,
+}
+
+pub trait ToolHandler {
+    fn tools(&self) -> Vec<Tool>;
+
+    /// Run a tool. The returned text is sent to the client as-is; errors are
+    /// reported as a failed tool result rather than a protocol error, so the
+    /// model can see what went wrong.
+    fn call(&self, name: &str, arguments: &Value) -> Result<String>;
+}
+
+/// Serve requests from `reader` until EOF
+pub fn serve<H: ToolHandler>(
+    handler: &H,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(handler, &line) {
+            writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle one JSON-RPC message; notifications get no response
+pub fn handle_message<H: ToolHandler>(handler: &H, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+        // Stray responses (we never send requests) are ignored
+        return message
+            .get("id")
+            .is_none()
+            .then(|| error(Value::Null, INVALID_REQUEST, "Expected a request"));
+    };
+    let id = message.get("id").cloned()?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list_tools(handler)),
+        "tools/call" => call_tool(handler, &params),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, msg)) => error(id, code, &msg),
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": "nous",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn list_tools<H: ToolHandler>(handler: &H) -> Value {
+    let tools: Vec<Value> = handler
+        .tools()
+        .into_iter()
+        .map(|tool| {
+            json!({
+                "name": tool.name,
+                "description": tool.description,
+                "inputSchema": tool.input_schema,
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+fn call_tool<H: ToolHandler>(handler: &H, params: &Value) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+    if !handler.tools().iter().any(|t| t.name == name) {
+        return Err((INVALID_PARAMS, format!("Unknown tool '{}'", name)));
+    }
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+    Ok(match handler.call(name, &arguments) {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }] }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": format!("{:#}", e) }],
+            "isError": true,
+        }),
+    })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl ToolHandler for Echo {
+        fn tools(&self) -> Vec<Tool> {
+            vec![Tool {
+                name: "echo",
+                description: "Echo the text argument",
+                input_schema: json!({
+                    "type": "object",
+                    "properties": { "text": { "type": "string" } },
+                    "required": ["text"],
+                }),
+            }]
+        }
+
+        fn call(&self, _name: &str, arguments: &Value) -> Result<String> {
+            match arguments.get("text").and_then(|t| t.as_str()) {
+                Some(text) => Ok(text.to_string()),
+                None => anyhow::bail!("text is required"),
+            }
+        }
+    }
+
+    #[test]
+    fn handshake_and_tool_listing() {
+        let init = handle_message(
+            &Echo,
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#,
+        )
+        .unwrap();
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert!(init["result"]["capabilities"]["tools"].is_object());
+
+        // Notifications get no reply
+        let initialized = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert!(handle_message(&Echo, initialized).is_none());
+
+        let list =
+            handle_message(&Echo, r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#).unwrap();
+        assert_eq!(list["id"], "a");
+        assert_eq!(list["result"]["tools"][0]["name"], "echo");
+        assert_eq!(list["result"]["tools"][0]["inputSchema"]["type"], "object");
+    }
+
+    #[test]
+    fn tool_calls_and_errors() {
+        let ok = handle_message(
+            &Echo,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"echo","arguments":{"text":"hi"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(ok["result"]["content"][0]["text"], "hi");
+        assert!(ok["result"].get("isError").is_none());
+
+        let failed = handle_message(
+            &Echo,
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"echo","arguments":{}}}"#,
+        )
+        .unwrap();
+        assert_eq!(failed["result"]["isError"], true);
+
+        let unknown = handle_message(
+            &Echo,
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"nope"}}"#,
+        )
+        .unwrap();
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+
+        let garbage = handle_message(&Echo, "{not json").unwrap();
+        assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn serve_writes_one_line_per_response() {
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"bogus"}"#,
+            "\n",
+        );
+        let mut out = Vec::new();
+        serve(&Echo, input.as_bytes(), &mut out).unwrap();
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["result"], json!({}));
+        assert_eq!(lines[1]["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
@@ -0,0 +1,30 @@
+//! JSON output shared by every subcommand, with `--query` applied.
+
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::query::Query;
+
+static QUERY: OnceLock<Query> = OnceLock::new();
+
+/// Set the `--query` expression applied to all JSON output
+pub fn set_query(query: Query) {
+    let _ = QUERY.set(query);
+}
+
+/// Print a value as pretty JSON, filtered through `--query` if one was given.
+/// A query that selects a single string prints it bare, for use in scripts.
+pub fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    let value = serde_json::to_value(value)?;
+    let value = match QUERY.get() {
+        Some(query) => query.apply(&value),
+        None => value,
+    };
+    match value {
+        serde_json::Value::String(s) if QUERY.get().is_some() => println!("{}", s),
+        other => println!("{}", serde_json::to_string_pretty(&other)?),
+    }
+    Ok(())
+}
@@ -0,0 +1,684 @@
+//! `--query` expressions for filtering and projecting JSON output.
+//!
+//! A practical subset of JMESPath:
+//!
+//! | expression                    | meaning                                   |
+//! |-------------------------------|-------------------------------------------|
+//! | `name`, `a.b`, `"odd key"`    | field access                              |
+//! | `[0]`, `[-1]`                 | array index                               |
+//! | `[*]`, `[]`                   | project over an array (`[]` flattens)     |
+//! | `[?expr]`                     | keep elements where `expr` is truthy      |
+//! | `{id: id, n: pageCount}`      | build an object                           |
+//! | `==` `!=` `<` `<=` `>` `>=`   | comparisons (numbers or strings)          |
+//! | `&&` `\|\|` `!`               | boolean logic                             |
+//! | `a \| b`                      | evaluate `b` on the result of `a`         |
+//! | `'text'`, `` `json` ``, `42`  | literals (`true`/`false`/`null` too)      |
+//! | `@`                           | the current value                         |
+//!
+//! Functions: `length(x)`, `contains(x, y)`, `starts_with(s, p)`,
+//! `ends_with(s, p)`, `keys(o)`.
+//!
+//! ```text
+//! nous-cli ls Work --query "[?contains(tags, 'todo')].title"
+//! nous-cli list --query "[?isCurrent].notebooks[*].{name: name, pages: pageCount}"
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Value};
+
+/// A parsed `--query` expression
+#[derive(Debug, Clone)]
+pub struct Query {
+    root: Node,
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.expression()?;
+        if let Some(tok) = parser.peek() {
+            bail!("unexpected {:?} in query", tok);
+        }
+        Ok(Self { root })
+    }
+
+    pub fn apply(&self, value: &Value) -> Value {
+        eval(&self.root, value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Value),
+    Number(i64),
+    Dot,
+    Star,
+    At,
+    Comma,
+    Colon,
+    Question,
+    Not,
+    And,
+    Or,
+    Pipe,
+    Cmp(CmpOp),
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Current,
+    Literal(Value),
+    Field(String),
+    Path(Box<Node>, Vec<Step>),
+    Hash(Vec<(String, Node)>),
+    Call(String, Vec<Node>),
+    Compare(Box<Node>, CmpOp, Box<Node>),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Pipe(Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Field(String),
+    Index(i64),
+    Hash(Vec<(String, Node)>),
+    Project,
+    Flatten,
+    Filter(Node),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            '@' => Token::At,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '?' => Token::Question,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '&' if next == Some('&') => {
+                i += 1;
+                Token::And
+            }
+            '|' if next == Some('|') => {
+                i += 1;
+                Token::Or
+            }
+            '|' => Token::Pipe,
+            '=' if next == Some('=') => {
+                i += 1;
+                Token::Cmp(CmpOp::Eq)
+            }
+            '!' if next == Some('=') => {
+                i += 1;
+                Token::Cmp(CmpOp::Ne)
+            }
+            '!' => Token::Not,
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                if or_equal {
+                    i += 1;
+                }
+                Token::Cmp(match (c, or_equal) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    ('>', false) => CmpOp::Gt,
+                    _ => CmpOp::Ge,
+                })
+            }
+            '\'' | '"' | '`' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .map(|p| i + 1 + p)
+                    .ok_or_else(|| anyhow!("unterminated {} in query", c))?;
+                let text: String = chars[i + 1..end].iter().collect();
+                i = end;
+                match c {
+                    '\'' => Token::Literal(Value::String(text)),
+                    '"' => Token::Ident(text),
+                    _ => Token::Literal(
+                        serde_json::from_str(&text)
+                            .map_err(|e| anyhow!("invalid JSON literal `{}`: {}", text, e))?,
+                    ),
+                }
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while chars
+                    .get(i)
+                    .is_some_and(|ch| ch.is_ascii_digit() || *ch == '.')
+                {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(match text.parse::<i64>() {
+                    Ok(n) => Token::Number(n),
+                    Err(_) => Token::Literal(
+                        text.parse::<f64>()
+                            .ok()
+                            .and_then(serde_json::Number::from_f64)
+                            .map(Value::Number)
+                            .ok_or_else(|| anyhow!("invalid number '{}' in query", text))?,
+                    ),
+                });
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while chars
+                    .get(i)
+                    .is_some_and(|ch| ch.is_alphanumeric() || *ch == '_')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+                continue;
+            }
+            other => bail!("unexpected '{}' in query", other),
+        };
+        tokens.push(token);
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        match self.next() {
+            Some(t) if t == token => Ok(()),
+            Some(t) => bail!("expected {:?} but found {:?} in query", token, t),
+            None => bail!("expected {:?} but the query ended", token),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Node> {
+        let mut node = self.or()?;
+        while self.eat(&Token::Pipe) {
+            node = Node::Pipe(Box::new(node), Box::new(self.or()?));
+        }
+        Ok(node)
+    }
+
+    fn or(&mut self) -> Result<Node> {
+        let mut node = self.and()?;
+        while self.eat(&Token::Or) {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node> {
+        let mut node = self.unary()?;
+        while self.eat(&Token::And) {
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        if self.eat(&Token::Not) {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        let left = self.path()?;
+        if let Some(Token::Cmp(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.path()?;
+            return Ok(Node::Compare(Box::new(left), op, Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn path(&mut self) -> Result<Node> {
+        let base = match self.peek().cloned() {
+            // A leading bracket applies to the current value
+            Some(Token::LBracket) => Node::Current,
+            _ => self.primary()?,
+        };
+        let mut steps = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Ident(name)) => steps.push(Step::Field(name)),
+                        Some(Token::Star) => steps.push(Step::Project),
+                        Some(Token::LBrace) => steps.push(Step::Hash(self.hash_body()?)),
+                        other => bail!("expected a field name after '.', found {:?}", other),
+                    }
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::RBracket) => steps.push(Step::Flatten),
+                        Some(Token::Star) => {
+                            self.expect(Token::RBracket)?;
+                            steps.push(Step::Project);
+                        }
+                        Some(Token::Number(n)) => {
+                            self.expect(Token::RBracket)?;
+                            steps.push(Step::Index(n));
+                        }
+                        Some(Token::Question) => {
+                            let condition = self.expression()?;
+                            self.expect(Token::RBracket)?;
+                            steps.push(Step::Filter(condition));
+                        }
+                        other => bail!("unexpected {:?} after '['", other),
+                    }
+                }
+                _ => break,
+            }
+        }
+        Ok(if steps.is_empty() {
+            base
+        } else {
+            Node::Path(Box::new(base), steps)
+        })
+    }
+
+    fn primary(&mut self) -> Result<Node> {
+        match self.next() {
+            Some(Token::At) => Ok(Node::Current),
+            Some(Token::Literal(value)) => Ok(Node::Literal(value)),
+            Some(Token::Number(n)) => Ok(Node::Literal(Value::from(n))),
+            Some(Token::LBrace) => Ok(Node::Hash(self.hash_body()?)),
+            Some(Token::LParen) => {
+                let node = self.expression()?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    let mut args = Vec::new();
+                    if !self.eat(&Token::RParen) {
+                        loop {
+                            args.push(self.expression()?);
+                            if self.eat(&Token::RParen) {
+                                break;
+                            }
+                            self.expect(Token::Comma)?;
+                        }
+                    }
+                    check_function(&name, args.len())?;
+                    return Ok(Node::Call(name, args));
+                }
+                Ok(match name.as_str() {
+                    "true" => Node::Literal(Value::Bool(true)),
+                    "false" => Node::Literal(Value::Bool(false)),
+                    "null" => Node::Literal(Value::Null),
+                    _ => Node::Field(name),
+                })
+            }
+            Some(other) => bail!("unexpected {:?} in query", other),
+            None => bail!("the query ended unexpectedly"),
+        }
+    }
+
+    /// `key: expr, …}` (the opening brace is already consumed)
+    fn hash_body(&mut self) -> Result<Vec<(String, Node)>> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match self.next() {
+                Some(Token::Ident(key)) => key,
+                other => bail!("expected a key in {{…}}, found {:?}", other),
+            };
+            // `{title}` is shorthand for `{title: title}`
+            let value = if self.eat(&Token::Colon) {
+                self.expression()?
+            } else {
+                Node::Field(key.clone())
+            };
+            pairs.push((key, value));
+            if self.eat(&Token::RBrace) {
+                return Ok(pairs);
+            }
+            if !self.eat(&Token::Comma) {
+                bail!("expected ',' or '}}' in {{…}}");
+            }
+        }
+    }
+}
+
+fn check_function(name: &str, arity: usize) -> Result<()> {
+    let expected = match name {
+        "length" | "keys" => 1,
+        "contains" | "starts_with" | "ends_with" => 2,
+        _ => bail!("unknown function '{}' in query", name),
+    };
+    if arity != expected {
+        bail!("{}() takes {} argument(s), got {}", name, expected, arity);
+    }
+    Ok(())
+}
+
+fn eval(node: &Node, value: &Value) -> Value {
+    match node {
+        Node::Current => value.clone(),
+        Node::Literal(literal) => literal.clone(),
+        Node::Field(name) => value.get(name).cloned().unwrap_or(Value::Null),
+        Node::Path(base, steps) => apply_steps(eval(base, value), steps),
+        Node::Hash(pairs) => build_hash(pairs, value),
+        Node::Call(name, args) => {
+            let args: Vec<Value> = args.iter().map(|a| eval(a, value)).collect();
+            call(name, &args)
+        }
+        Node::Compare(left, op, right) => compare(&eval(left, value), *op, &eval(right, value)),
+        Node::Not(inner) => Value::Bool(!truthy(&eval(inner, value))),
+        Node::And(left, right) => {
+            let left = eval(left, value);
+            if truthy(&left) {
+                eval(right, value)
+            } else {
+                left
+            }
+        }
+        Node::Or(left, right) => {
+            let left = eval(left, value);
+            if truthy(&left) {
+                left
+            } else {
+                eval(right, value)
+            }
+        }
+        Node::Pipe(left, right) => eval(right, &eval(left, value)),
+    }
+}
+
+fn apply_steps(value: Value, steps: &[Step]) -> Value {
+    let Some((step, rest)) = steps.split_first() else {
+        return value;
+    };
+    match step {
+        Step::Field(name) => {
+            let field = value.get(name).cloned().unwrap_or(Value::Null);
+            apply_steps(field, rest)
+        }
+        Step::Index(index) => {
+            let element = value
+                .as_array()
+                .and_then(|items| {
+                    let index = if *index < 0 {
+                        items.len().checked_sub(index.unsigned_abs() as usize)?
+                    } else {
+                        *index as usize
+                    };
+                    items.get(index).cloned()
+                })
+                .unwrap_or(Value::Null);
+            apply_steps(element, rest)
+        }
+        Step::Hash(pairs) => {
+            if value.is_null() {
+                return Value::Null;
+            }
+            apply_steps(build_hash(pairs, &value), rest)
+        }
+        Step::Project => match value {
+            Value::Array(items) => project(items, rest),
+            // `.*` over an object projects its values
+            Value::Object(map) => project(map.into_iter().map(|(_, v)| v).collect(), rest),
+            _ => Value::Null,
+        },
+        Step::Flatten => match value {
+            Value::Array(items) => {
+                let flat = items
+                    .into_iter()
+                    .flat_map(|item| match item {
+                        Value::Array(inner) => inner,
+                        other => vec![other],
+                    })
+                    .collect();
+                project(flat, rest)
+            }
+            _ => Value::Null,
+        },
+        Step::Filter(condition) => match value {
+            Value::Array(items) => {
+                let kept = items
+                    .into_iter()
+                    .filter(|item| truthy(&eval(condition, item)))
+                    .collect();
+                project(kept, rest)
+            }
+            _ => Value::Null,
+        },
+    }
+}
+
+/// Apply the remaining steps to every element, dropping nulls
+///
+/// A later `[]` ends the projection: it flattens the projected results, as
+/// in `libraries[*].notebooks[].name`.
+fn project(items: Vec<Value>, rest: &[Step]) -> Value {
+    let end = rest
+        .iter()
+        .position(|step| matches!(step, Step::Flatten))
+        .unwrap_or(rest.len());
+    let projected = Value::Array(
+        items
+            .into_iter()
+            .map(|item| apply_steps(item, &rest[..end]))
+            .filter(|item| !item.is_null())
+            .collect(),
+    );
+    apply_steps(projected, &rest[end..])
+}
+
+fn build_hash(pairs: &[(String, Node)], value: &Value) -> Value {
+    let map: Map<String, Value> = pairs
+        .iter()
+        .map(|(key, node)| (key.clone(), eval(node, value)))
+        .collect();
+    Value::Object(map)
+}
+
+fn call(name: &str, args: &[Value]) -> Value {
+    match (name, args) {
+        ("length", [Value::String(s)]) => Value::from(s.chars().count()),
+        ("length", [Value::Array(items)]) => Value::from(items.len()),
+        ("length", [Value::Object(map)]) => Value::from(map.len()),
+        ("keys", [Value::Object(map)]) => {
+            Value::Array(map.keys().cloned().map(Value::String).collect())
+        }
+        ("contains", [Value::String(s), Value::String(needle)]) => {
+            Value::Bool(s.contains(needle.as_str()))
+        }
+        ("contains", [Value::Array(items), needle]) => {
+            Value::Bool(items.iter().any(|item| json_eq(item, needle)))
+        }
+        ("starts_with", [Value::String(s), Value::String(prefix)]) => {
+            Value::Bool(s.starts_with(prefix.as_str()))
+        }
+        ("ends_with", [Value::String(s), Value::String(suffix)]) => {
+            Value::Bool(s.ends_with(suffix.as_str()))
+        }
+        _ => Value::Null,
+    }
+}
+
+fn compare(left: &Value, op: CmpOp, right: &Value) -> Value {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a
+            .as_f64()
+            .zip(b.as_f64())
+            .and_then(|(a, b)| a.partial_cmp(&b)),
+        // ISO dates and times compare correctly as strings
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        CmpOp::Eq => Value::Bool(json_eq(left, right)),
+        CmpOp::Ne => Value::Bool(!json_eq(left, right)),
+        _ => match ordering {
+            Some(ordering) => Value::Bool(match op {
+                CmpOp::Lt => ordering.is_lt(),
+                CmpOp::Le => ordering.is_le(),
+                CmpOp::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            }),
+            None => Value::Null,
+        },
+    }
+}
+
+/// Equality that treats `1` and `1.0` as the same number
+fn json_eq(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => left == right,
+    }
+}
+
+/// JMESPath truthiness: false, null and empty strings/arrays/objects are false
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        Value::Number(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(query: &str, value: &Value) -> Value {
+        Query::parse(query).unwrap().apply(value)
+    }
+
+    fn pages() -> Value {
+        json!([
+            {"title": "Plan", "tags": ["todo", "work"], "words": 120, "folder": {"name": "Projects"}},
+            {"title": "Diary", "tags": [], "words": 40, "folder": null},
+            {"title": "Ideas", "tags": ["todo"], "words": 75.0, "folder": {"name": "Inbox"}}
+        ])
+    }
+
+    #[test]
+    fn fields_indexes_and_projections() {
+        let data =
+            json!({"libraries": [{"name": "Home", "notebooks": [{"name": "A"}, {"name": "B"}]}]});
+        assert_eq!(run("libraries[0].name", &data), json!("Home"));
+        assert_eq!(
+            run("libraries[-1].notebooks[*].name", &data),
+            json!(["A", "B"])
+        );
+        assert_eq!(
+            run("libraries[*].notebooks[].name", &data),
+            json!(["A", "B"])
+        );
+        assert_eq!(run("missing.field", &data), Value::Null);
+        assert_eq!(
+            run("[*].folder.name", &pages()),
+            json!(["Projects", "Inbox"])
+        );
+    }
+
+    #[test]
+    fn filters_and_functions() {
+        assert_eq!(
+            run("[?contains(tags, 'todo')].title", &pages()),
+            json!(["Plan", "Ideas"])
+        );
+        assert_eq!(
+            run("[?words >= `75`].title", &pages()),
+            json!(["Plan", "Ideas"])
+        );
+        assert_eq!(run("[?words == 75].title", &pages()), json!(["Ideas"]));
+        assert_eq!(
+            run("[?!folder || starts_with(title, 'P')].title", &pages()),
+            json!(["Plan", "Diary"])
+        );
+        assert_eq!(run("length(@)", &pages()), json!(3));
+        assert_eq!(run("[?tags] | length(@)", &pages()), json!(2));
+    }
+
+    #[test]
+    fn object_building() {
+        assert_eq!(
+            run("[0].{name: title, n: words, folder: folder.name}", &pages()),
+            json!({"name": "Plan", "n": 120, "folder": "Projects"})
+        );
+        assert_eq!(
+            run("[?words < `50`].{title}", &pages()),
+            json!([{"title": "Diary"}])
+        );
+    }
+
+    #[test]
+    fn bad_queries_are_rejected() {
+        assert!(Query::parse("a.").is_err());
+        assert!(Query::parse("[?a == 'x'").is_err());
+        assert!(Query::parse("nope(a)").is_err());
+        assert!(Query::parse("length(a, b)").is_err());
+        assert!(Query::parse("a b").is_err());
+    }
+}
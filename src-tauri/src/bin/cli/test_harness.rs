@@ -52,6 +52,7 @@ use tower::ServiceExt;
 use uuid::Uuid;
 
 use nous_lib::actions::{ActionExecutor, ActionScheduler, ActionStorage};
+use nous_lib::clipper::ClipperStorage;
 use nous_lib::contacts::ContactsStorage;
 use nous_lib::energy::EnergyStorage;
 use nous_lib::events::AppEvent;
@@ -60,7 +61,9 @@ use nous_lib::inbox::InboxStorage;
 use nous_lib::library::LibraryStorage;
 use nous_lib::python_bridge::PythonAI;
 use nous_lib::search::{RagBackend, RagConfig, SearchIndex, TantivyBackend};
-use nous_lib::storage::{FileStorage, NotebookType};
+use nous_lib::storage::{
+    EditorBlock, FileStorage, FileStorageWriter, NotebookType, StorageLock,
+};
 use nous_lib::sync::{CrdtStore, LogEmitter, SyncManager};
 use tokio::sync::RwLock;
 
@@ -105,11 +108,13 @@ impl TestEnv {
         let tmp = tempfile::tempdir().expect("create tempdir");
         let library_path = tmp.path().to_path_buf();
 
-        let storage = FileStorage::new(library_path.clone());
+        let storage = FileStorageWriter::new(FileStorage::new(library_path.clone()));
         storage.init().expect("storage init");
 
         let library_storage = LibraryStorage::new(library_path.clone());
         let inbox_storage = InboxStorage::new(library_path.clone()).expect("inbox init");
+        let clipper_storage =
+            ClipperStorage::new(library_path.clone()).expect("clipper init");
         let goals_storage = GoalsStorage::new(library_path.clone()).expect("goals init");
         let energy_storage = EnergyStorage::new(library_path.clone()).expect("energy init");
         let contacts_storage = ContactsStorage::new(library_path.clone()).expect("contacts init");
@@ -131,7 +136,7 @@ impl TestEnv {
         let rag_backend = Arc::new(RagBackend::new(Arc::clone(&rag_config)));
         let daemon_config_path = library_path.join("daemon-config.toml");
 
-        let storage_arc = Arc::new(Mutex::new(storage));
+        let storage_arc = Arc::new(StorageLock::new(storage));
         let library_storage_arc = Arc::new(Mutex::new(library_storage));
         let inbox_storage_arc = Arc::new(Mutex::new(inbox_storage));
         let goals_storage_arc = Arc::new(Mutex::new(goals_storage));
@@ -169,18 +174,24 @@ impl TestEnv {
             storage: storage_arc,
             library_storage: library_storage_arc,
             inbox_storage: inbox_storage_arc,
+            clipper_storage: Arc::new(Mutex::new(clipper_storage)),
             goals_storage: goals_storage_arc,
             energy_storage: energy_storage_arc,
             contacts_storage: contacts_storage_arc,
             sync_manager: sync_manager_arc,
             action_scheduler: Mutex::new(action_scheduler),
+            action_executor: action_executor_arc,
             search_index: search_index_arc,
             tantivy: tantivy_backend,
             rag: rag_backend,
             rag_config: Arc::clone(&rag_config),
             ai_config: Arc::new(RwLock::new(Default::default())),
+            secrets: None,
             daemon_config_path,
             crdt_store,
+            encryption_manager: std::sync::Arc::new(
+                nous_lib::encryption::EncryptionManager::new(),
+            ),
             // Plugin host is None in tests by default — keeps construction
             // fast and avoids loading any user-installed Lua plugins from
             // disk into the test process. Tests that exercise plugin
@@ -234,7 +245,7 @@ impl TestEnv {
     /// Create a notebook directly through storage, returning its UUID as
     /// a string. Faster + more deterministic than going via the HTTP API.
     pub fn create_notebook(&self, name: &str) -> String {
-        let storage = self.state.storage.lock().unwrap();
+        let storage = self.state.storage.write().unwrap();
         let nb = storage
             .create_notebook(name.to_string(), NotebookType::Standard)
             .expect("create notebook");
@@ -583,6 +594,151 @@ async fn pages_get_returns_404_for_unknown_page() {
     assert_eq!(status, StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn blocks_move_extract_and_merge_across_pages() {
+    let mut env = TestEnv::new();
+    let nb = env.create_notebook("Blocks");
+    let mut pages = Vec::new();
+    for (title, blocks) in [("Source", ["s1", "s2", "s3"]), ("Target", ["t1", "t2", "t3"])] {
+        let (_, body) = env
+            .post_json(&format!("/api/notebooks/{}/pages", nb), json!({"title": title}))
+            .await;
+        let pg = body["data"]["id"].as_str().unwrap().to_string();
+        let blocks: Vec<Value> = blocks
+            .iter()
+            .map(|id| json!({"id": id, "type": "paragraph", "data": {"text": format!("Text {}", id)}}))
+            .collect();
+        env.put_json(
+            &format!("/api/notebooks/{}/pages/{}", nb, pg),
+            json!({"blocks": blocks}),
+        )
+        .await;
+        pages.push(pg);
+    }
+    let block_ids = |page: &Value| -> Vec<String> {
+        page["content"]["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    env.drain_events();
+
+    let (status, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/move-blocks", nb, pages[0]),
+            json!({"block_ids": ["s2"], "target_page_id": pages[1], "position": 1}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["updated"].as_array().unwrap().len(), 2);
+    let (_, target) = env
+        .get_json(&format!("/api/notebooks/{}/pages/{}", nb, pages[1]))
+        .await;
+    assert_eq!(block_ids(&target["data"]), ["t1", "s2", "t2", "t3"]);
+    let evt = env.try_recv_event().expect("move-blocks emits page.updated");
+    assert_eq!(evt.event, "page.updated");
+    env.drain_events();
+
+    let (status, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/extract-blocks", nb, pages[0]),
+            json!({"block_ids": ["s3"]}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["data"]["created"]["title"], "Text s3");
+    assert_eq!(block_ids(&body["data"]["created"]), ["s3"]);
+    env.drain_events();
+
+    let (status, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/merge-into", nb, pages[0]),
+            json!({"target_page_id": pages[1]}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["trashedPageId"], pages[0]);
+    let (_, target) = env
+        .get_json(&format!("/api/notebooks/{}/pages/{}", nb, pages[1]))
+        .await;
+    let ids = block_ids(&target["data"]);
+    assert_eq!(ids.len(), 6);
+    assert_eq!(ids[..5], ["t1", "s2", "t2", "t3", "s1"]);
+
+    let (status, _) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/merge-into", nb, pages[1]),
+            json!({"target_page_id": pages[1]}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/move-blocks", nb, pages[1]),
+            json!({"block_ids": ["nope"], "target_page_id": pages[1]}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn split_by_headings_creates_subpages_and_contents() {
+    let mut env = TestEnv::new();
+    let nb = env.create_notebook("Split");
+    let (_, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages", nb),
+            json!({"title": "Guide", "blocks": [
+                {"id": "i", "type": "paragraph", "data": {"text": "Intro"}},
+                {"id": "h1", "type": "header", "data": {"text": "Install", "level": 1}},
+                {"id": "p1", "type": "paragraph", "data": {"text": "Download it"}},
+                {"id": "h2", "type": "header", "data": {"text": "Configure", "level": 2}},
+                {"id": "p2", "type": "paragraph", "data": {"text": "Edit the file"}},
+            ]}),
+        )
+        .await;
+    let pg = body["data"]["id"].as_str().unwrap().to_string();
+    env.drain_events();
+
+    let (status, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/split-by-headings", nb, pg),
+            json!({"toc_style": "transclusions"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(body["data"]["created"].is_null());
+    let created = body["data"]["subpages"].as_array().unwrap();
+    assert_eq!(created.len(), 2);
+    assert_eq!(created[0]["title"], "Install");
+    assert_eq!(created[1]["title"], "Configure");
+    assert_eq!(created[1]["parentPageId"], pg);
+
+    let (_, parent) = env
+        .get_json(&format!("/api/notebooks/{}/pages/{}", nb, pg))
+        .await;
+    let blocks = parent["data"]["content"]["blocks"].as_array().unwrap();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[1]["type"], "embed");
+    assert_eq!(blocks[1]["data"]["pageId"], created[0]["id"]);
+
+    let mut events = Vec::new();
+    while let Some(evt) = env.try_recv_event() {
+        events.push(evt.event);
+    }
+    assert_eq!(events.iter().filter(|e| *e == "page.created").count(), 2);
+
+    let (status, _) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/split-by-headings", nb, pg),
+            json!({"max_level": 3}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
 // ===== Version history (snapshots + oplog) =====
 
 #[tokio::test]
@@ -750,6 +906,72 @@ async fn sections_create_emits_event() {
     assert_eq!(evt.event, "section.created");
 }
 
+#[tokio::test]
+async fn folder_sort_override_and_pins_order_listed_pages() {
+    let mut env = TestEnv::new();
+    let nb = env.create_notebook("Pins");
+    let (_, body) = env
+        .post_json(&format!("/api/notebooks/{}/folders", nb), json!({"name": "ideas"}))
+        .await;
+    let folder = body["data"]["id"].as_str().unwrap().to_string();
+
+    let (status, _) = env
+        .put_json(
+            &format!("/api/notebooks/{}/folders/{}", nb, folder),
+            json!({"page_sort_by": "sideways"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, body) = env
+        .put_json(
+            &format!("/api/notebooks/{}/folders/{}", nb, folder),
+            json!({"page_sort_by": "name-asc"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["pageSortBy"], "name-asc");
+
+    let mut ids = Vec::new();
+    for title in ["Charlie", "Alpha", "Bravo"] {
+        let (_, body) = env
+            .post_json(
+                &format!("/api/notebooks/{}/pages", nb),
+                json!({"title": title, "folder_id": folder}),
+            )
+            .await;
+        ids.push(body["data"]["id"].as_str().unwrap().to_string());
+    }
+    env.drain_events();
+
+    let (status, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/pin", nb, ids[0]),
+            json!({"pinned": true}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"], json!({"kind": "folder", "id": folder}));
+    let evt = env.try_recv_event().expect("page.pinned");
+    assert_eq!(evt.event, "page.pinned");
+
+    let (_, body) = env.get_json(&format!("/api/notebooks/{}/pages", nb)).await;
+    let titles: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Charlie", "Alpha", "Bravo"]);
+
+    let (status, _) = env
+        .post_json(
+            &format!("/api/notebooks/{}/pages/{}/pin", nb, Uuid::new_v4()),
+            json!({"pinned": true}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
 // ===== Search =====
 
 #[tokio::test]
@@ -1111,6 +1333,41 @@ async fn markdown_import_falls_back_to_filename_title() {
     assert_eq!(status, StatusCode::BAD_REQUEST);
 }
 
+// ===== Web clipper =====
+
+#[tokio::test]
+async fn clip_with_bad_folder_id_returns_400_and_creates_nothing() {
+    let env = TestEnv::new();
+    let nb = env.create_notebook("Clips");
+
+    let (status, body) = env.post_json("/api/clipper/pairing", json!({})).await;
+    assert_eq!(status, StatusCode::CREATED, "body: {body}");
+    let code = body["data"]["code"].as_str().unwrap().to_string();
+    let (status, body) = env
+        .post_json("/api/clipper/pair", json!({"code": code}))
+        .await;
+    assert_eq!(status, StatusCode::OK, "body: {body}");
+    let token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let (status, _) = env
+        .request_with_token(
+            Method::POST,
+            "/api/clipper/clip",
+            Some(json!({
+                "url": "https://example.com/post",
+                "markdown": "hello",
+                "notebook_id": nb,
+                "folder_id": "nope",
+            })),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = env.get_json(&format!("/api/notebooks/{}/pages", nb)).await;
+    assert_eq!(body["data"].as_array().unwrap().len(), 0);
+}
+
 // ===== AI endpoints =====
 //
 // The harness bridge points at a placeholder Python path, so AI calls fail
@@ -1472,3 +1729,133 @@ async fn publish_notebook_nous_bad_expiry_400() {
         .await;
     assert_eq!(status, StatusCode::BAD_REQUEST);
 }
+
+// ===== Daily notes =====
+
+#[tokio::test]
+async fn daily_note_rollover_carries_unchecked_items() {
+    let env = TestEnv::new();
+    let nb = env.create_notebook("Journal");
+    let nb_id = Uuid::parse_str(&nb).unwrap();
+    let today = chrono::Local::now().date_naive();
+    let yesterday = (today - chrono::Duration::days(1)).format("%Y-%m-%d").to_string();
+    let yesterday_id = {
+        let storage = env.state.storage.write().unwrap();
+        let mut page = storage.create_page(nb_id, "Yesterday".to_string()).unwrap();
+        page.is_daily_note = true;
+        page.daily_note_date = Some(yesterday);
+        page.content.blocks = vec![EditorBlock {
+            id: "todo".to_string(),
+            block_type: "checklist".to_string(),
+            data: json!({"items": [
+                {"text": "Call the bank", "checked": false},
+                {"text": "Water plants", "checked": true},
+            ]}),
+        }];
+        storage.update_page(&page).unwrap();
+        page.id
+    };
+
+    let (status, body) = env
+        .post_json(
+            &format!("/api/notebooks/{}/daily-notes/{}", nb, today.format("%Y-%m-%d")),
+            json!({"rollover": true, "rollover_lookback_days": 3}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let blocks = body["data"]["content"]["blocks"].as_array().unwrap();
+    assert_eq!(blocks[0]["data"]["text"], "Carried Forward");
+    assert_eq!(
+        blocks[1]["data"]["items"],
+        json!([{"text": "Call the bank", "checked": false}])
+    );
+
+    let storage = env.state.storage.read().unwrap();
+    let source = storage.get_page(nb_id, yesterday_id).unwrap();
+    assert_eq!(
+        source.content.blocks[0].data["items"][0],
+        json!({"text": "Call the bank (carried forward)", "checked": true})
+    );
+}
+
+#[tokio::test]
+async fn daily_note_without_rollover_starts_empty() {
+    let env = TestEnv::new();
+    let nb = env.create_notebook("Journal");
+    let (status, body) = env
+        .post_json(&format!("/api/notebooks/{}/daily-notes/2024-01-15", nb), json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["title"], "January 15, 2024");
+    assert_eq!(body["data"]["content"]["blocks"], json!([]));
+}
+
+// ===== Automation verbs =====
+
+#[tokio::test]
+async fn automation_note_is_found_by_name_with_its_link() {
+    let mut env = TestEnv::new();
+    let nb = env.create_notebook("Inbox");
+
+    let (status, body) = env
+        .post_json(
+            "/api/automation/note",
+            json!({"notebook": "inbox", "title": "Call Sam", "content": "About the **offer**"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let page_id = body["data"]["pageId"].as_str().unwrap().to_string();
+    assert_eq!(body["data"]["notebookId"], nb);
+    assert!(body["data"]["url"]
+        .as_str()
+        .unwrap()
+        .starts_with(&format!("nous://page/{}?notebook={}", page_id, nb)));
+    assert_eq!(env.try_recv_event().expect("page.created").event, "page.created");
+
+    let (status, body) = env
+        .get_json("/api/automation/page-url?notebook=Inb&page=call%20sam")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["pageId"], page_id);
+
+    let (status, _) = env
+        .post_json("/api/automation/note", json!({"notebook": "Nope", "title": "x"}))
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn automation_append_reuses_the_days_daily_note() {
+    let env = TestEnv::new();
+    env.create_notebook("Journal");
+
+    let mut page_ids = Vec::new();
+    for text in ["- Shipped the release", "Called the bank"] {
+        let (status, body) = env
+            .post_json(
+                "/api/automation/daily-note/append",
+                json!({"notebook": "Journal", "text": text, "date": "2024-01-15"}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["title"], "January 15, 2024");
+        page_ids.push(body["data"]["pageId"].clone());
+    }
+    assert_eq!(page_ids[0], page_ids[1]);
+
+    let storage = env.state.storage.read().unwrap();
+    let page_id = Uuid::parse_str(page_ids[0].as_str().unwrap()).unwrap();
+    let nb_id = storage.list_notebooks().unwrap()[0].id;
+    let page = storage.get_page(nb_id, page_id).unwrap();
+    assert_eq!(page.content.blocks.len(), 2);
+    assert_eq!(page.content.blocks[1].data["text"], "Called the bank");
+    drop(storage);
+
+    let (status, _) = env
+        .post_json(
+            "/api/automation/daily-note/append",
+            json!({"notebook": "Journal", "text": "x", "date": "15/01/2024"}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
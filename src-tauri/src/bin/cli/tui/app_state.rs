@@ -1,11 +1,23 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use ratatui::prelude::Rect;
 use uuid::Uuid;
 
+use super::fuzzy::{fuzzy_search_pages, load_weights, PageEntry};
+use super::keymap::{Key, Keymap};
+use super::text_editor::TextEditor;
 use crate::app::App;
 use crate::render::terminal as renderer;
-use nous_lib::search::SearchResult;
-use nous_lib::storage::Folder;
+use nous_lib::external_editor::apply_edited_markdown;
+use nous_lib::flashcards::{CardWithState, FlashcardStorage};
+use nous_lib::history::HistoryStorage;
+use nous_lib::markdown::export_page_to_markdown;
+use nous_lib::search::{
+    suggest_queries, RankingContext, RankingWeights, SearchResult, SearchSuggestion,
+    SuggestionKind, SuggestionSources,
+};
+use nous_lib::storage::{Folder, Page, PageType};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Mode {
@@ -14,6 +26,27 @@ pub enum Mode {
     Search,
     CreateNote,
     InboxCapture,
+    Edit,
+    Finder,
+    Review,
+}
+
+/// Unsaved Markdown edits to a page. Drafts outlive edit mode, so several
+/// pages can have pending edits at once; they are marked in the tree.
+pub struct PageDraft {
+    pub notebook_id: Uuid,
+    pub title: String,
+    pub editor: TextEditor,
+}
+
+/// Due flashcards from one notebook, shown one at a time
+pub struct ReviewSession {
+    pub notebook_id: Uuid,
+    pub notebook_name: String,
+    pub cards: Vec<CardWithState>,
+    pub current: usize,
+    /// The back of the current card is showing
+    pub revealed: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -46,14 +79,44 @@ pub struct TuiState {
     pub rendered_lines: Vec<String>,
     pub content_scroll: usize,
     pub content_title: String,
-
-    // Search state
+    /// (notebook, page) shown in the content panel
+    pub current_page: Option<(Uuid, Uuid)>,
+
+    // Editor state: drafts by page ID, and the page being edited
+    pub drafts: HashMap<Uuid, PageDraft>,
+    pub editing: Option<Uuid>,
+    /// Set by `E`; the event loop hands the terminal to $EDITOR
+    pub external_edit_requested: bool,
+    /// `q` was pressed once with unsaved drafts
+    pub confirm_quit: bool,
+
+    // Search state. Suggestions (past searches, titles, tags) are listed
+    // while there are no results; their sources are loaded on open.
     pub search_input: String,
     pub search_results: Vec<SearchResult>,
+    pub search_suggestions: Vec<SearchSuggestion>,
     pub search_selected: usize,
-
-    // Key state for multi-char sequences
-    pub pending_key: Option<char>,
+    search_sources: SuggestionSources,
+    search_history: Option<HistoryStorage>,
+
+    // Ctrl-P finder state: every page in the library, loaded on open, and
+    // the ranked matches for the current input
+    pub finder_input: String,
+    pub finder_entries: Vec<PageEntry>,
+    pub finder_results: Vec<usize>,
+    pub finder_selected: usize,
+    /// Rendered preview of the selected match
+    pub finder_preview: Option<(Uuid, Vec<String>)>,
+    finder_weights: RankingWeights,
+    finder_context: RankingContext,
+
+    // Review state
+    pub review: Option<ReviewSession>,
+
+    /// Bindings for the tree and content panels, from `tui.toml`
+    pub keymap: Keymap,
+    // Keys typed so far of a multi-key binding like `gg`
+    pub pending_keys: Vec<Key>,
 
     // Panel areas for mouse hit-testing (updated each draw)
     pub tree_area: Option<Rect>,
@@ -69,6 +132,18 @@ pub struct TuiState {
 
 impl TuiState {
     pub fn new(app: App) -> Result<Self> {
+        // A broken config shouldn't keep the TUI from starting
+        let (keymap, flash_message) = match Keymap::load() {
+            Ok(keymap) => (keymap, None),
+            Err(e) => (Keymap::default(), Some(format!("{:#}; using vim keys", e))),
+        };
+        let (finder_weights, flash_message) = match load_weights(&app.current_library.path) {
+            Ok(weights) => (weights, flash_message),
+            Err(e) => {
+                let message = format!("{:#}; using default finder ranking", e);
+                (RankingWeights::default(), flash_message.or(Some(message)))
+            }
+        };
         let mut state = Self {
             app,
             mode: Mode::Tree,
@@ -78,14 +153,31 @@ impl TuiState {
             rendered_lines: Vec::new(),
             content_scroll: 0,
             content_title: String::new(),
+            current_page: None,
+            drafts: HashMap::new(),
+            editing: None,
+            external_edit_requested: false,
+            confirm_quit: false,
             search_input: String::new(),
             search_results: Vec::new(),
+            search_suggestions: Vec::new(),
             search_selected: 0,
-            pending_key: None,
+            search_sources: SuggestionSources::default(),
+            search_history: None,
+            finder_input: String::new(),
+            finder_entries: Vec::new(),
+            finder_results: Vec::new(),
+            finder_selected: 0,
+            finder_preview: None,
+            finder_weights,
+            finder_context: RankingContext::default(),
+            review: None,
+            keymap,
+            pending_keys: Vec::new(),
             tree_area: None,
             content_area: None,
             input_text: String::new(),
-            flash_message: None,
+            flash_message,
             show_help: false,
             quit: false,
         };
@@ -313,9 +405,8 @@ impl TuiState {
                 let nb_id = *notebook_id;
                 if let Ok(pages) = self.app.list_pages(nb_id) {
                     if let Some(page) = pages.iter().find(|p| p.id == page_id) {
-                        self.content_title = page.title.clone();
-                        self.rendered_lines = renderer::render_blocks_plain(&page.content.blocks);
-                        self.content_scroll = 0;
+                        self.show_page(page);
+                        self.record_view(nb_id, page_id);
                         self.mode = Mode::Content;
                     }
                 }
@@ -327,12 +418,479 @@ impl TuiState {
         }
     }
 
+    /// Show a page in the content panel
+    pub fn show_page(&mut self, page: &Page) {
+        self.content_title = page.title.clone();
+        self.rendered_lines = renderer::render_blocks_plain(&page.content.blocks);
+        self.content_scroll = 0;
+        self.current_page = Some((page.notebook_id, page.id));
+    }
+
+    /// The page to edit: the selected tree page in tree mode, otherwise the
+    /// page in the content panel
+    fn page_to_edit(&self) -> Option<(Uuid, Uuid)> {
+        if self.mode == Mode::Tree {
+            if let Some(TreeItemKind::Page { id, notebook_id }) =
+                self.tree_items.get(self.tree_selected).map(|item| &item.kind)
+            {
+                return Some((*notebook_id, *id));
+            }
+        }
+        self.current_page
+    }
+
+    /// Open the built-in editor on the selected page, resuming its draft
+    pub fn start_editing(&mut self) {
+        let Some((notebook_id, page_id)) = self.page_to_edit() else {
+            self.flash_message = Some("Select a page to edit".to_string());
+            return;
+        };
+        if !self.drafts.contains_key(&page_id) {
+            let page = match self.app.storage.get_page(notebook_id, page_id) {
+                Ok(page) => page,
+                Err(e) => {
+                    self.flash_message = Some(format!("Error: {}", e));
+                    return;
+                }
+            };
+            if page.page_type != PageType::Standard {
+                self.flash_message = Some("Only standard pages can be edited".to_string());
+                return;
+            }
+            self.show_page(&page);
+            self.drafts.insert(
+                page_id,
+                PageDraft {
+                    notebook_id,
+                    title: page.title.clone(),
+                    editor: TextEditor::new(&export_page_to_markdown(&page)),
+                },
+            );
+        }
+        self.editing = Some(page_id);
+        self.mode = Mode::Edit;
+    }
+
+    pub fn current_draft(&mut self) -> Option<&mut PageDraft> {
+        let page_id = self.editing?;
+        self.drafts.get_mut(&page_id)
+    }
+
+    /// Leave edit mode; unchanged drafts are dropped, changed ones kept
+    pub fn stop_editing(&mut self) {
+        if let Some(page_id) = self.editing.take() {
+            if self.drafts.get(&page_id).is_some_and(|d| !d.editor.is_dirty()) {
+                self.drafts.remove(&page_id);
+            }
+        }
+        self.mode = Mode::Content;
+    }
+
+    /// Throw away the current draft and reload the saved page
+    pub fn revert_draft(&mut self) {
+        if let Some(page_id) = self.editing {
+            self.drafts.remove(&page_id);
+            self.start_editing();
+            self.flash_message = Some("Reverted to saved version".to_string());
+        }
+    }
+
+    /// Save the current draft through the storage layer
+    pub fn save_draft(&mut self) {
+        let Some(page_id) = self.editing else {
+            return;
+        };
+        let Some(draft) = self.drafts.get(&page_id) else {
+            return;
+        };
+        let result = self
+            .app
+            .storage
+            .get_page(draft.notebook_id, page_id)
+            .map_err(anyhow::Error::from)
+            .and_then(|current| {
+                let updated = apply_edited_markdown(&current, &draft.editor.text());
+                self.app.update_page(&updated)?;
+                Ok(updated)
+            });
+        match result {
+            Ok(page) => {
+                // Fresh buffer so the dirty flag resets and headings etc.
+                // show as they were stored
+                self.drafts.insert(
+                    page_id,
+                    PageDraft {
+                        notebook_id: page.notebook_id,
+                        title: page.title.clone(),
+                        editor: TextEditor::new(&export_page_to_markdown(&page)),
+                    },
+                );
+                self.show_page(&page);
+                self.relabel_page(page_id, &page.title);
+                self.flash_message = Some(format!("Saved \"{}\"", page.title));
+            }
+            Err(e) => self.flash_message = Some(format!("Save failed: {}", e)),
+        }
+    }
+
+    /// Hand the selected page to $EDITOR (the event loop suspends the TUI)
+    pub fn request_external_edit(&mut self) {
+        match self.page_to_edit() {
+            Some((_, page_id)) if self.drafts.contains_key(&page_id) => {
+                self.flash_message =
+                    Some("Page has unsaved edits; save or revert them first".to_string());
+            }
+            Some(_) => self.external_edit_requested = true,
+            None => self.flash_message = Some("Select a page to edit".to_string()),
+        }
+    }
+
+    /// Run $EDITOR on the selected page. The terminal must be restored to
+    /// normal mode around this call.
+    pub fn edit_externally(&mut self) {
+        self.external_edit_requested = false;
+        let Some((notebook_id, page_id)) = self.page_to_edit() else {
+            return;
+        };
+        let result = self
+            .app
+            .storage
+            .get_page(notebook_id, page_id)
+            .map_err(anyhow::Error::from)
+            .and_then(|page| crate::commands::edit::edit_page(&self.app, &page));
+        match result {
+            Ok(true) => {
+                if let Ok(page) = self.app.storage.get_page(notebook_id, page_id) {
+                    self.show_page(&page);
+                    self.relabel_page(page_id, &page.title);
+                    self.flash_message = Some(format!("Saved \"{}\"", page.title));
+                }
+            }
+            Ok(false) => self.flash_message = Some("No changes".to_string()),
+            Err(e) => self.flash_message = Some(format!("Error: {}", e)),
+        }
+    }
+
+    /// Update a page's label in the tree after a rename
+    fn relabel_page(&mut self, page_id: Uuid, title: &str) {
+        for item in &mut self.tree_items {
+            if matches!(item.kind, TreeItemKind::Page { id, .. } if id == page_id) {
+                item.label = title.to_string();
+            }
+        }
+    }
+
+    /// Quit, asking for confirmation first if there are unsaved drafts
+    pub fn request_quit(&mut self) {
+        let unsaved = self.drafts.values().filter(|d| d.editor.is_dirty()).count();
+        if unsaved == 0 || self.confirm_quit {
+            self.quit = true;
+        } else {
+            self.confirm_quit = true;
+            self.flash_message = Some(format!(
+                "{} page(s) have unsaved edits. Press q again to discard them",
+                unsaved
+            ));
+        }
+    }
+
+    /// Remember that a page was opened, for the finder's ranking and the
+    /// review feed. Losing a view isn't worth interrupting the user over.
+    fn record_view(&self, notebook_id: Uuid, page_id: Uuid) {
+        if let Ok(mut history) = HistoryStorage::new(self.app.current_library.path.clone()) {
+            let _ = history.record_view(notebook_id, page_id);
+        }
+    }
+
+    /// Open the Ctrl-P finder over every page in the library
+    pub fn open_finder(&mut self) {
+        self.finder_entries.clear();
+        self.finder_context = RankingContext {
+            current_notebook: match self.mode {
+                Mode::Content => self.current_page.map(|(nb, _)| nb),
+                _ => self.selected_context().map(|(nb, _)| nb),
+            },
+            now: None,
+        };
+        let history = HistoryStorage::new(self.app.current_library.path.clone()).ok();
+        let notebooks = match self.app.list_notebooks() {
+            Ok(notebooks) => notebooks,
+            Err(e) => {
+                self.flash_message = Some(format!("Error: {}", e));
+                return;
+            }
+        };
+        for nb in notebooks.iter().filter(|nb| !nb.archived) {
+            // Locked notebooks can't be listed; skip them
+            let Ok(pages) = self.app.list_pages(nb.id) else {
+                continue;
+            };
+            self.finder_entries
+                .extend(pages.into_iter().filter(|p| !p.is_archived).map(|p| {
+                    let view = history.as_ref().and_then(|h| h.get_view(p.id));
+                    PageEntry {
+                        notebook_id: nb.id,
+                        page_id: p.id,
+                        notebook_name: nb.name.clone(),
+                        title: p.title,
+                        updated_at: p.updated_at,
+                        last_viewed_at: view.map(|v| v.last_viewed_at),
+                        view_count: view.map_or(0, |v| v.view_count),
+                    }
+                }));
+        }
+        self.finder_input.clear();
+        self.mode = Mode::Finder;
+        self.update_finder();
+    }
+
+    /// Re-rank matches after the input changed
+    pub fn update_finder(&mut self) {
+        self.finder_results = fuzzy_search_pages(
+            &self.finder_entries,
+            &self.finder_input,
+            200,
+            &self.finder_weights,
+            &self.finder_context,
+        );
+        self.finder_selected = 0;
+        self.load_finder_preview();
+    }
+
+    pub fn finder_move(&mut self, down: bool) {
+        if down {
+            if self.finder_selected + 1 < self.finder_results.len() {
+                self.finder_selected += 1;
+            }
+        } else {
+            self.finder_selected = self.finder_selected.saturating_sub(1);
+        }
+        self.load_finder_preview();
+    }
+
+    fn selected_finder_entry(&self) -> Option<&PageEntry> {
+        self.finder_results
+            .get(self.finder_selected)
+            .map(|&i| &self.finder_entries[i])
+    }
+
+    fn load_finder_preview(&mut self) {
+        let Some(entry) = self.selected_finder_entry() else {
+            self.finder_preview = None;
+            return;
+        };
+        let (notebook_id, page_id) = (entry.notebook_id, entry.page_id);
+        if self.finder_preview.as_ref().is_some_and(|(id, _)| *id == page_id) {
+            return;
+        }
+        let lines = match self.app.storage.get_page(notebook_id, page_id) {
+            Ok(page) => renderer::render_blocks_plain(&page.content.blocks),
+            Err(e) => vec![format!("Failed to load page: {}", e)],
+        };
+        self.finder_preview = Some((page_id, lines));
+    }
+
+    /// Open the selected match in the content panel
+    pub fn open_finder_selection(&mut self) {
+        let Some(entry) = self.selected_finder_entry() else {
+            return;
+        };
+        match self.app.storage.get_page(entry.notebook_id, entry.page_id) {
+            Ok(page) => {
+                self.show_page(&page);
+                self.record_view(page.notebook_id, page.id);
+                self.mode = Mode::Content;
+            }
+            Err(e) => {
+                self.flash_message = Some(format!("Error: {}", e));
+                self.mode = Mode::Tree;
+            }
+        }
+        self.close_finder_state();
+    }
+
+    pub fn close_finder(&mut self) {
+        self.close_finder_state();
+        self.mode = Mode::Tree;
+    }
+
+    fn close_finder_state(&mut self) {
+        self.finder_input.clear();
+        self.finder_entries.clear();
+        self.finder_results.clear();
+        self.finder_preview = None;
+    }
+
+    /// Review the due flashcards of the selected notebook, or of the page
+    /// in the content panel
+    pub fn start_review(&mut self) {
+        let notebook_id = match self.mode {
+            Mode::Content => self.current_page.map(|(nb, _)| nb),
+            _ => self.selected_context().map(|(nb, _)| nb),
+        };
+        let Some(notebook_id) = notebook_id else {
+            self.flash_message = Some("Select a notebook to review".to_string());
+            return;
+        };
+        let notebook_name = self
+            .app
+            .get_notebook(notebook_id)
+            .map(|nb| nb.name)
+            .unwrap_or_default();
+        let flashcards = self.flashcards();
+        match flashcards.get_due_cards(notebook_id, None) {
+            Ok(cards) if cards.is_empty() => {
+                self.flash_message = Some(format!("No flashcards due in {}", notebook_name));
+            }
+            Ok(cards) => {
+                self.review = Some(ReviewSession {
+                    notebook_id,
+                    notebook_name,
+                    cards,
+                    current: 0,
+                    revealed: false,
+                });
+                self.mode = Mode::Review;
+            }
+            Err(e) => self.flash_message = Some(format!("Error: {}", e)),
+        }
+    }
+
+    fn flashcards(&self) -> FlashcardStorage {
+        FlashcardStorage::new(self.app.current_library.path.join("notebooks"))
+    }
+
+    pub fn reveal_card(&mut self) {
+        if let Some(review) = self.review.as_mut() {
+            review.revealed = true;
+        }
+    }
+
+    /// Rate the revealed card (1 = again … 4 = easy) and move to the next
+    pub fn rate_card(&mut self, rating: i32) {
+        let flashcards = self.flashcards();
+        let Some(review) = self.review.as_mut().filter(|r| r.revealed) else {
+            return;
+        };
+        let card_id = review.cards[review.current].card.id;
+        if let Err(e) = flashcards.submit_review(review.notebook_id, card_id, rating) {
+            self.flash_message = Some(format!("Error: {}", e));
+            return;
+        }
+        review.current += 1;
+        review.revealed = false;
+        if review.current >= review.cards.len() {
+            let count = review.cards.len();
+            self.end_review();
+            self.flash_message = Some(format!("Reviewed {} card(s)", count));
+        }
+    }
+
+    pub fn end_review(&mut self) {
+        self.review = None;
+        self.mode = Mode::Tree;
+    }
+
+    /// Open the search bar, loading what suggestions are drawn from
+    pub fn open_search(&mut self) {
+        self.mode = Mode::Search;
+        self.search_input.clear();
+        self.search_results.clear();
+        // Locked notebooks can't be listed; leave all encrypted ones out
+        self.search_sources =
+            match SuggestionSources::collect(&self.app.storage, |nb| !nb.is_encrypted()) {
+                Ok(sources) => sources,
+                Err(e) => {
+                    self.flash_message = Some(format!("Error: {}", e));
+                    SuggestionSources::default()
+                }
+            };
+        self.search_history = HistoryStorage::new(self.app.current_library.path.clone()).ok();
+        self.perform_search();
+    }
+
+    pub fn close_search(&mut self) {
+        self.mode = Mode::Tree;
+        self.search_input.clear();
+        self.search_results.clear();
+        self.search_suggestions.clear();
+        self.search_sources = SuggestionSources::default();
+        self.search_history = None;
+    }
+
     pub fn perform_search(&mut self) {
         // TUI search was backed by the local Tantivy reader. After the daemon
         // took ownership of the writer lock, the read-only path was removed
         // from the CLI App. To re-enable TUI search, plumb a daemon HTTP
         // client (or revive ReadOnlySearchIndex on a non-conflicting path).
         self.search_results.clear();
+
+        let history = self
+            .search_history
+            .as_ref()
+            .map(|h| h.search_history(usize::MAX))
+            .unwrap_or_default();
+        self.search_suggestions =
+            suggest_queries(&self.search_input, &history, &self.search_sources, 10);
+        self.search_selected = 0;
+    }
+
+    /// Rows in the search overlay: results, or suggestions when there are none
+    pub fn search_list_len(&self) -> usize {
+        if self.search_results.is_empty() {
+            self.search_suggestions.len()
+        } else {
+            self.search_results.len()
+        }
+    }
+
+    pub fn search_move(&mut self, down: bool) {
+        if down {
+            if self.search_selected + 1 < self.search_list_len() {
+                self.search_selected += 1;
+            }
+        } else {
+            self.search_selected = self.search_selected.saturating_sub(1);
+        }
+    }
+
+    /// Enter in the search bar: open the selected result or page
+    /// suggestion, or complete the input to a past search or tag
+    pub fn submit_search(&mut self) {
+        if !self.search_results.is_empty() {
+            self.record_search();
+            self.navigate_to_search_result();
+            return;
+        }
+        let Some(suggestion) = self.search_suggestions.get(self.search_selected).cloned() else {
+            self.record_search();
+            return;
+        };
+        match (suggestion.kind, suggestion.notebook_id, suggestion.page_id) {
+            (SuggestionKind::Page, Some(notebook_id), Some(page_id)) => {
+                self.record_search();
+                match self.app.storage.get_page(notebook_id, page_id) {
+                    Ok(page) => {
+                        self.show_page(&page);
+                        self.close_search();
+                        self.mode = Mode::Content;
+                    }
+                    Err(e) => self.flash_message = Some(format!("Error: {}", e)),
+                }
+            }
+            _ => {
+                self.search_input = suggestion.text;
+                self.perform_search();
+            }
+        }
+    }
+
+    fn record_search(&mut self) {
+        if let Some(history) = self.search_history.as_mut() {
+            if let Err(e) = history.record_search(&self.search_input) {
+                self.flash_message = Some(format!("Failed to save search history: {}", e));
+            }
+        }
     }
 
     pub fn navigate_to_search_result(&mut self) {
@@ -346,9 +904,7 @@ impl TuiState {
 
         if let Ok(pages) = self.app.list_pages(nb_id) {
             if let Some(page) = pages.iter().find(|p| p.id == page_id) {
-                self.content_title = page.title.clone();
-                self.rendered_lines = renderer::render_blocks_plain(&page.content.blocks);
-                self.content_scroll = 0;
+                self.show_page(page);
                 self.mode = Mode::Content;
                 self.search_input.clear();
                 self.search_results.clear();
@@ -2,6 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 
 use super::app_state::{Mode, TuiState};
+use super::keymap::Action;
 
 pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
     let is_active = state.mode == Mode::Content;
@@ -30,7 +31,10 @@ pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
             )),
             Line::from(""),
             Line::from(Span::styled(
-                "  Navigate with j/k, open with Enter or l.",
+                match state.keymap.keys_for(Action::Help).first() {
+                    Some(key) => format!("  Press {} to list the keys.", key),
+                    None => "  Navigate with the arrow keys, open with Enter.".to_string(),
+                },
                 Style::default().fg(Color::DarkGray),
             )),
         ];
@@ -0,0 +1,51 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use super::app_state::TuiState;
+
+pub fn draw(f: &mut Frame, area: Rect, state: &mut TuiState) {
+    let Some(draft) = state.current_draft() else {
+        return;
+    };
+
+    let dirty = if draft.editor.is_dirty() { " [+]" } else { "" };
+    let block = Block::default()
+        .title(format!(" Editing: {}{} ", draft.title, dirty))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    let inner = block.inner(area);
+    let editor = &mut draft.editor;
+    editor.scroll_to_cursor(inner.width as usize, inner.height as usize);
+
+    let text: Vec<Line> = editor
+        .lines()
+        .iter()
+        .skip(editor.scroll)
+        .take(inner.height as usize)
+        .map(|line| {
+            let visible: String = line.chars().skip(editor.h_scroll).collect();
+            if line.starts_with('#') {
+                Line::from(Span::styled(
+                    visible,
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ))
+            } else if line.starts_with("```") {
+                Line::from(Span::styled(visible, Style::default().fg(Color::Cyan)))
+            } else {
+                Line::from(visible)
+            }
+        })
+        .collect();
+
+    let (row, col) = editor.cursor();
+    let cursor = Position::new(
+        inner.x + (col - editor.h_scroll) as u16,
+        inner.y + (row - editor.scroll) as u16,
+    );
+
+    f.render_widget(Paragraph::new(text).block(block), area);
+    f.set_cursor_position(cursor);
+}
@@ -0,0 +1,90 @@
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+
+use super::app_state::TuiState;
+
+/// Centered overlay: input line on top, matches on the left, preview on the
+/// right
+pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
+    let width = area.width.saturating_mul(4) / 5;
+    let height = area.height.saturating_mul(4) / 5;
+    let overlay = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, overlay);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(overlay);
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[1]);
+
+    let input_block = Block::default()
+        .title(format!(
+            " Find page ({}/{}) ",
+            state.finder_results.len(),
+            state.finder_entries.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let input_inner = input_block.inner(rows[0]);
+    let input = Paragraph::new(format!("> {}", state.finder_input)).block(input_block);
+    f.render_widget(input, rows[0]);
+
+    let items: Vec<ListItem> = state
+        .finder_results
+        .iter()
+        .map(|&i| {
+            let entry = &state.finder_entries[i];
+            ListItem::new(Line::from(vec![
+                Span::raw(entry.title.clone()),
+                Span::styled(
+                    format!("  {}", entry.notebook_name),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Yellow)),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+    let mut list_state = ListState::default();
+    if !state.finder_results.is_empty() {
+        list_state.select(Some(state.finder_selected));
+    }
+    f.render_stateful_widget(list, panes[0], &mut list_state);
+
+    let preview_lines: Vec<Line> = state
+        .finder_preview
+        .as_ref()
+        .map(|(_, lines)| lines.iter().map(|l| Line::from(l.clone())).collect())
+        .unwrap_or_default();
+    let preview = Paragraph::new(preview_lines)
+        .block(
+            Block::default()
+                .title(" Preview ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray)),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(preview, panes[1]);
+
+    let cursor_x = input_inner.x + 2 + state.finder_input.chars().count() as u16;
+    f.set_cursor_position(Position::new(cursor_x, input_inner.y));
+}
@@ -0,0 +1,282 @@
+//! Fuzzy page matching for the TUI's Ctrl-P finder.
+//!
+//! The daemon owns the Tantivy index, so the finder ranks page titles held in
+//! memory instead. Matching is fzf-style: the query's characters must appear
+//! in order, with bonuses for consecutive runs and word starts.
+//!
+//! On top of the match score, pages get the ranking boosts shared with the
+//! daemon's fuzzy search (see `nous_lib::search::ranking`). Their weights are
+//! the library's, unless the `[finder]` table of `~/.config/nous/tui.toml`
+//! sets its own:
+//!
+//! ```toml
+//! [finder]
+//! exact_prefix = 48
+//! recency = 40
+//! frequency = 32
+//! notebook = 24     # 0 turns a boost off
+//! ```
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use nous_lib::search::{ranking_boost, RankedPage, RankingContext, RankingWeights};
+
+use super::keymap::Keymap;
+
+/// A page the finder can jump to
+pub struct PageEntry {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub notebook_name: String,
+    pub title: String,
+    pub updated_at: DateTime<Utc>,
+    /// From the view history; `None` if never opened
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub view_count: u32,
+}
+
+/// The part of `tui.toml` the finder reads; the rest is the keymap's
+#[derive(Deserialize)]
+struct FinderFile {
+    finder: Option<RankingWeights>,
+}
+
+/// The finder's weights: the `[finder]` table of `tui.toml` if it has one,
+/// else the library's
+pub fn load_weights(library_path: &Path) -> Result<RankingWeights> {
+    let configured = match Keymap::config_path() {
+        Some(path) if path.exists() => {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            weights_from_toml(&text)
+                .with_context(|| format!("Invalid [finder] in {}", path.display()))?
+        }
+        _ => None,
+    };
+    Ok(configured.unwrap_or_else(|| RankingWeights::load(library_path)))
+}
+
+fn weights_from_toml(text: &str) -> Result<Option<RankingWeights>> {
+    let file: FinderFile = toml::from_str(text)?;
+    Ok(file.finder)
+}
+
+const MATCH: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 24;
+const WORD_START_BONUS: i64 = 20;
+const GAP_PENALTY: i64 = 1;
+
+/// Score `candidate` against `query`, or `None` if it doesn't match.
+/// Higher is better. Case-insensitive.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut qi = 0;
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+    for (i, c) in candidate.chars().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if c.to_lowercase().eq(std::iter::once(query[qi])) {
+            score += MATCH;
+            match last_match {
+                Some(last) if last + 1 == i => score += CONSECUTIVE_BONUS,
+                Some(last) => score -= GAP_PENALTY * (i - last - 1) as i64,
+                None => score -= GAP_PENALTY * i as i64,
+            }
+            let word_start = match prev {
+                None => true,
+                Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()),
+            };
+            if word_start {
+                score += WORD_START_BONUS;
+            }
+            last_match = Some(i);
+            qi += 1;
+        }
+        prev = Some(c);
+    }
+
+    (qi == query.len()).then_some(score)
+}
+
+/// The boosts `entry` gets besides its match score
+fn ranking_boost_for(
+    entry: &PageEntry,
+    query: &str,
+    weights: &RankingWeights,
+    context: &RankingContext,
+) -> i64 {
+    let page = RankedPage {
+        notebook_id: entry.notebook_id,
+        title: &entry.title,
+        last_viewed_at: entry.last_viewed_at,
+        view_count: entry.view_count,
+    };
+    ranking_boost(&page, query, weights, context)
+}
+
+/// Rank pages for `query`, returning indices into `entries`, best first.
+/// Titles are matched first; the notebook name is a weaker fallback so
+/// "work meet" can find "Meeting notes" in "Work". Matches are then boosted
+/// by `weights`. An empty query lists pages by their boosts alone, most
+/// recently updated first among equals.
+pub fn fuzzy_search_pages(
+    entries: &[PageEntry],
+    query: &str,
+    limit: usize,
+    weights: &RankingWeights,
+    context: &RankingContext,
+) -> Vec<usize> {
+    let query = query.trim();
+    // Pin the time, so every page's recency is measured from the same moment
+    let context = RankingContext {
+        now: Some(context.now.unwrap_or_else(Utc::now)),
+        ..*context
+    };
+    let mut scored: Vec<(usize, i64)> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let score = if query.is_empty() {
+                0
+            } else {
+                let title = fuzzy_score(query, &entry.title);
+                let qualified =
+                    fuzzy_score(query, &format!("{} {}", entry.notebook_name, entry.title))
+                        .map(|s| s / 2);
+                title.max(qualified)?
+            };
+            Some((
+                i,
+                score + ranking_boost_for(entry, query, weights, &context),
+            ))
+        })
+        .collect();
+
+    scored.sort_by(|(a, sa), (b, sb)| {
+        sb.cmp(sa)
+            .then_with(|| entries[*b].updated_at.cmp(&entries[*a].updated_at))
+    });
+    scored.truncate(limit);
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(notebook: &str, title: &str, age_days: i64) -> PageEntry {
+        PageEntry {
+            notebook_id: Uuid::new_v4(),
+            page_id: Uuid::new_v4(),
+            notebook_name: notebook.to_string(),
+            title: title.to_string(),
+            updated_at: Utc::now() - chrono::Duration::days(age_days),
+            last_viewed_at: None,
+            view_count: 0,
+        }
+    }
+
+    fn search(entries: &[PageEntry], query: &str, limit: usize) -> Vec<usize> {
+        fuzzy_search_pages(
+            entries,
+            query,
+            limit,
+            &RankingWeights::default(),
+            &RankingContext::default(),
+        )
+    }
+
+    #[test]
+    fn subsequence_matching() {
+        assert!(fuzzy_score("mtg", "Meeting notes").is_some());
+        assert!(fuzzy_score("MEET", "meeting").is_some());
+        assert!(fuzzy_score("gtm", "Meeting").is_none());
+        assert!(fuzzy_score("héj", "Héllo Jürgen").is_some());
+    }
+
+    #[test]
+    fn prefers_word_starts_and_runs() {
+        let runs = fuzzy_score("proj", "Project plan").unwrap();
+        let scattered = fuzzy_score("proj", "Apropos of a jar").unwrap();
+        assert!(runs > scattered);
+
+        let initials = fuzzy_score("wn", "Weekly Notes").unwrap();
+        let inner = fuzzy_score("wn", "Unknown").unwrap();
+        assert!(initials > inner);
+    }
+
+    #[test]
+    fn ranks_across_notebooks() {
+        let entries = vec![
+            entry("Work", "Meeting notes", 3),
+            entry("Home", "Groceries", 1),
+            entry("Home", "Meal plan", 0),
+        ];
+        assert_eq!(search(&entries, "meet", 10), vec![0]);
+        assert_eq!(search(&entries, "home gro", 10), vec![1]);
+        // Empty query: most recently updated first
+        assert_eq!(search(&entries, "", 2), vec![2, 1]);
+    }
+
+    #[test]
+    fn boosts_prefixes_history_and_current_notebook() {
+        let mut entries = vec![
+            entry("Work", "Weekly planning", 0),
+            entry("Work", "Plan", 5),
+            entry("Home", "Planets", 10),
+        ];
+        // Titles starting with the query lead
+        assert_eq!(search(&entries, "plan", 10)[..2], [1, 2]);
+
+        // Opened often and lately outweighs a prefix
+        entries[0].last_viewed_at = Some(Utc::now());
+        entries[0].view_count = 40;
+        assert_eq!(search(&entries, "plan", 10)[0], 0);
+        assert_eq!(search(&entries, "", 10)[0], 0);
+
+        // Being in the notebook tips a tie
+        let context = RankingContext {
+            current_notebook: Some(entries[2].notebook_id),
+            now: None,
+        };
+        let no_history = RankingWeights {
+            recency: 0,
+            frequency: 0,
+            ..RankingWeights::default()
+        };
+        assert_eq!(
+            fuzzy_search_pages(&entries, "plan", 10, &no_history, &context)[0],
+            2
+        );
+    }
+
+    #[test]
+    fn reads_weights_from_config() {
+        let weights = weights_from_toml(
+            "profile = \"vim\"\n[bindings]\nsearch = \"/\"\n[finder]\nrecency = 0\n",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(weights.recency, 0);
+        assert_eq!(weights.notebook, RankingWeights::default().notebook);
+        assert!(weights_from_toml("[finder]\nrecncy = 1\n").is_err());
+        // Without a table the library's weights apply
+        assert_eq!(weights_from_toml("").unwrap(), None);
+    }
+}
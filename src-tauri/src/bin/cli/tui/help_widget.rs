@@ -0,0 +1,59 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use super::app_state::TuiState;
+use super::keymap::{Action, Keymap};
+
+/// Centered overlay listing every bound action with its keys, built from the
+/// active keymap so it matches `tui.toml`
+pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
+    let rows: Vec<(String, &str)> = Action::ALL
+        .iter()
+        .filter_map(|&action| {
+            let keys = state.keymap.keys_for(action);
+            (!keys.is_empty()).then(|| (keys.join(", "), action.description()))
+        })
+        .collect();
+    let key_width = rows
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = rows
+        .iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<width$}  ", keys, width = key_width),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(*description),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    let config = Keymap::config_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "tui.toml".to_string());
+    lines.push(Line::from(Span::styled(
+        format!(" Keys can be changed in {}", config),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let width = (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 3).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let overlay = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, overlay);
+
+    let block = Block::default()
+        .title(format!(" Keys ({} profile) ", state.keymap.profile))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    f.render_widget(Paragraph::new(lines).block(block), overlay);
+}
@@ -1,10 +1,20 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
 use super::app_state::{Mode, TuiState};
+use super::keymap::{Action, Key, Lookup};
 
 pub fn handle_key(state: &mut TuiState, key: KeyEvent) {
     // Clear flash message on any keypress
     state.flash_message = None;
+    // Quitting with unsaved drafts needs the quit key twice in a row
+    if state.keymap.lookup(&[Key::from(key)]) != Lookup::Action(Action::Quit) {
+        state.confirm_quit = false;
+    }
+    // Any key closes the help overlay
+    if state.show_help {
+        state.show_help = false;
+        return;
+    }
 
     match state.mode {
         Mode::Tree => handle_tree_key(state, key),
@@ -12,56 +22,76 @@ pub fn handle_key(state: &mut TuiState, key: KeyEvent) {
         Mode::Search => handle_search_key(state, key),
         Mode::CreateNote => handle_input_key(state, key, true),
         Mode::InboxCapture => handle_input_key(state, key, false),
+        Mode::Edit => handle_edit_key(state, key),
+        Mode::Finder => handle_finder_key(state, key),
+        Mode::Review => handle_review_key(state, key),
     }
 }
 
-fn handle_tree_key(state: &mut TuiState, key: KeyEvent) {
-    // Handle pending 'g' key
-    if state.pending_key == Some('g') {
-        state.pending_key = None;
-        if key.code == KeyCode::Char('g') {
-            state.tree_go_top();
-            return;
-        }
-        // Not 'gg', ignore
+/// Map a key in the tree or content panel to an action, waiting for the
+/// rest of multi-key bindings like `gg`
+fn resolve_action(state: &mut TuiState, key: KeyEvent) -> Option<Action> {
+    let key = Key::from(key);
+    let mut typed = std::mem::take(&mut state.pending_keys);
+    typed.push(key);
+    match state.keymap.lookup(&typed) {
+        Lookup::Action(action) => Some(action),
+        Lookup::Pending => {
+            state.pending_keys = typed;
+            None
+        }
+        // Not a full sequence: treat the last key on its own
+        Lookup::None if typed.len() > 1 => match state.keymap.lookup(&[key]) {
+            Lookup::Action(action) => Some(action),
+            Lookup::Pending => {
+                state.pending_keys = vec![key];
+                None
+            }
+            Lookup::None => None,
+        },
+        Lookup::None => None,
     }
+}
 
-    match key.code {
-        KeyCode::Char('q') => state.quit = true,
-        KeyCode::Char('j') | KeyCode::Down => state.tree_move_down(),
-        KeyCode::Char('k') | KeyCode::Up => state.tree_move_up(),
-        KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
-            state.open_selected_page();
-        }
-        KeyCode::Char('h') | KeyCode::Left => {
-            state.collapse_or_parent();
-        }
-        KeyCode::Char('g') => {
-            state.pending_key = Some('g');
-        }
-        KeyCode::Char('G') => state.tree_go_bottom(),
-        KeyCode::Char('/') => {
-            state.mode = Mode::Search;
-            state.search_input.clear();
-            state.search_results.clear();
-        }
-        KeyCode::Tab => {
+/// Actions shared by the tree and content panels
+fn run_common_action(state: &mut TuiState, action: Action) {
+    match action {
+        Action::Finder => state.open_finder(),
+        Action::Search => state.open_search(),
+        Action::Edit => state.start_editing(),
+        Action::ExternalEdit => state.request_external_edit(),
+        Action::Review => state.start_review(),
+        Action::Help => state.show_help = true,
+        Action::Quit => state.request_quit(),
+        _ => {}
+    }
+}
+
+fn handle_tree_key(state: &mut TuiState, key: KeyEvent) {
+    let Some(action) = resolve_action(state, key) else {
+        return;
+    };
+    match action {
+        Action::MoveDown => state.tree_move_down(),
+        Action::MoveUp => state.tree_move_up(),
+        Action::Open => state.open_selected_page(),
+        Action::Collapse => state.collapse_or_parent(),
+        Action::GoTop => state.tree_go_top(),
+        Action::GoBottom => state.tree_go_bottom(),
+        Action::ToggleFocus => {
             if !state.rendered_lines.is_empty() {
                 state.mode = Mode::Content;
             }
         }
-        KeyCode::Char('n') => {
+        Action::NewPage => {
             state.mode = Mode::CreateNote;
             state.input_text.clear();
         }
-        KeyCode::Char('i') => {
+        Action::Inbox => {
             state.mode = Mode::InboxCapture;
             state.input_text.clear();
         }
-        KeyCode::Char('?') => {
-            state.show_help = !state.show_help;
-        }
-        _ => {}
+        other => run_common_action(state, other),
     }
 }
 
@@ -89,34 +119,86 @@ fn handle_input_key(state: &mut TuiState, key: KeyEvent, is_create_note: bool) {
 }
 
 fn handle_content_key(state: &mut TuiState, key: KeyEvent) {
-    // Handle pending 'g' key
-    if state.pending_key == Some('g') {
-        state.pending_key = None;
-        if key.code == KeyCode::Char('g') {
-            state.content_scroll = 0;
-            return;
+    let Some(action) = resolve_action(state, key) else {
+        return;
+    };
+    match action {
+        Action::Back | Action::ToggleFocus | Action::Collapse => {
+            state.mode = Mode::Tree;
+        }
+        Action::MoveDown => state.content_scroll_down(1),
+        Action::MoveUp => state.content_scroll_up(1),
+        Action::HalfPageDown => state.content_scroll_down(15),
+        Action::HalfPageUp => state.content_scroll_up(15),
+        Action::GoTop => state.content_scroll = 0,
+        Action::GoBottom => {
+            state.content_scroll = state.rendered_lines.len().saturating_sub(1);
         }
+        other => run_common_action(state, other),
     }
+}
 
+fn handle_review_key(state: &mut TuiState, key: KeyEvent) {
     match key.code {
-        KeyCode::Char('q') => state.quit = true,
-        KeyCode::Esc | KeyCode::Tab => {
-            state.mode = Mode::Tree;
-        }
-        KeyCode::Char('j') | KeyCode::Down => state.content_scroll_down(1),
-        KeyCode::Char('k') | KeyCode::Up => state.content_scroll_up(1),
-        KeyCode::Char('d') => state.content_scroll_down(15),
-        KeyCode::Char('u') => state.content_scroll_up(15),
-        KeyCode::Char('g') => {
-            state.pending_key = Some('g');
+        KeyCode::Esc | KeyCode::Char('q') => state.end_review(),
+        KeyCode::Char(' ') | KeyCode::Enter => state.reveal_card(),
+        KeyCode::Char(c @ '1'..='4') => state.rate_card(c as i32 - '0' as i32),
+        _ => {}
+    }
+}
+
+fn handle_edit_key(state: &mut TuiState, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('s') if ctrl => state.save_draft(),
+        KeyCode::Char('z') if ctrl => state.revert_draft(),
+        KeyCode::Esc => state.stop_editing(),
+        _ => {
+            let page_height = state
+                .content_area
+                .map(|a| a.height.saturating_sub(2) as usize)
+                .unwrap_or(20)
+                .max(1);
+            let Some(draft) = state.current_draft() else {
+                return;
+            };
+            let editor = &mut draft.editor;
+            match key.code {
+                KeyCode::Left => editor.move_left(),
+                KeyCode::Right => editor.move_right(),
+                KeyCode::Up => editor.move_up(1),
+                KeyCode::Down => editor.move_down(1),
+                KeyCode::PageUp => editor.move_up(page_height),
+                KeyCode::PageDown => editor.move_down(page_height),
+                KeyCode::Home => editor.move_home(),
+                KeyCode::End => editor.move_end(),
+                KeyCode::Enter => editor.insert_newline(),
+                KeyCode::Backspace => editor.backspace(),
+                KeyCode::Delete => editor.delete(),
+                KeyCode::Tab => editor.insert_str("    "),
+                KeyCode::Char(c) if !ctrl => editor.insert_char(c),
+                _ => {}
+            }
         }
-        KeyCode::Char('G') => {
-            state.content_scroll = state.rendered_lines.len().saturating_sub(1);
+    }
+}
+
+fn handle_finder_key(state: &mut TuiState, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Esc => state.close_finder(),
+        KeyCode::Enter => state.open_finder_selection(),
+        KeyCode::Down => state.finder_move(true),
+        KeyCode::Up => state.finder_move(false),
+        KeyCode::Char('n' | 'j') if ctrl => state.finder_move(true),
+        KeyCode::Char('p' | 'k') if ctrl => state.finder_move(false),
+        KeyCode::Backspace => {
+            state.finder_input.pop();
+            state.update_finder();
         }
-        KeyCode::Char('/') => {
-            state.mode = Mode::Search;
-            state.search_input.clear();
-            state.search_results.clear();
+        KeyCode::Char(c) if !ctrl => {
+            state.finder_input.push(c);
+            state.update_finder();
         }
         _ => {}
     }
@@ -124,33 +206,24 @@ fn handle_content_key(state: &mut TuiState, key: KeyEvent) {
 
 fn handle_search_key(state: &mut TuiState, key: KeyEvent) {
     match key.code {
-        KeyCode::Esc => {
-            state.mode = Mode::Tree;
-            state.search_input.clear();
-            state.search_results.clear();
-        }
-        KeyCode::Enter => {
-            state.navigate_to_search_result();
-        }
+        KeyCode::Esc => state.close_search(),
+        KeyCode::Enter => state.submit_search(),
         KeyCode::Backspace => {
             state.search_input.pop();
             state.perform_search();
         }
+        KeyCode::Tab => {
+            // Complete to the selected suggestion without running it
+            if let Some(suggestion) = state.search_suggestions.get(state.search_selected) {
+                state.search_input = suggestion.text.clone();
+                state.perform_search();
+            }
+        }
         KeyCode::Char(c) => {
             if key.modifiers.contains(KeyModifiers::CONTROL) {
                 match c {
-                    'n' => {
-                        if !state.search_results.is_empty()
-                            && state.search_selected < state.search_results.len() - 1
-                        {
-                            state.search_selected += 1;
-                        }
-                    }
-                    'p' => {
-                        if state.search_selected > 0 {
-                            state.search_selected -= 1;
-                        }
-                    }
+                    'n' => state.search_move(true),
+                    'p' => state.search_move(false),
                     _ => {}
                 }
             } else {
@@ -158,23 +231,18 @@ fn handle_search_key(state: &mut TuiState, key: KeyEvent) {
                 state.perform_search();
             }
         }
-        KeyCode::Down => {
-            if !state.search_results.is_empty()
-                && state.search_selected < state.search_results.len() - 1
-            {
-                state.search_selected += 1;
-            }
-        }
-        KeyCode::Up => {
-            if state.search_selected > 0 {
-                state.search_selected -= 1;
-            }
-        }
+        KeyCode::Down => state.search_move(true),
+        KeyCode::Up => state.search_move(false),
         _ => {}
     }
 }
 
 pub fn handle_mouse(state: &mut TuiState, mouse: MouseEvent) {
+    // Clicking away would strand the editor, finder or review; Esc leaves them
+    if matches!(state.mode, Mode::Edit | Mode::Finder | Mode::Review) {
+        return;
+    }
+
     match mouse.kind {
         MouseEventKind::Down(MouseButton::Left) => {
             let col = mouse.column;
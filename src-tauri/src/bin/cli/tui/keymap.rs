@@ -0,0 +1,541 @@
+//! Key bindings for the TUI's tree and content panels.
+//!
+//! Bindings come from a profile, optionally adjusted in
+//! `~/.config/nous/tui.toml`:
+//!
+//! ```toml
+//! profile = "vim"            # or "arrows"
+//!
+//! [bindings]
+//! new_page = "N"
+//! search = ["/", "ctrl-f"]
+//! review = "r"
+//! inbox = []                 # unbind
+//! ```
+//!
+//! A key is a character or a name (`enter`, `esc`, `tab`, `space`, `up`,
+//! `pagedown`, `f1`, ...) with optional `ctrl-`/`alt-`/`shift-` prefixes;
+//! space-separated keys form a sequence such as `"g g"`. A binding replaces
+//! every key the profile gave that action, and takes its keys away from
+//! other actions. Text inputs, the editor and the finder keep fixed keys.
+//! The same file holds the finder's ranking weights (see `fuzzy`).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Something a key can do in the tree or content panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveDown,
+    MoveUp,
+    Open,
+    Collapse,
+    GoTop,
+    GoBottom,
+    HalfPageDown,
+    HalfPageUp,
+    ToggleFocus,
+    Back,
+    Finder,
+    Search,
+    NewPage,
+    Inbox,
+    Edit,
+    ExternalEdit,
+    Review,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 19] = [
+        Action::MoveDown,
+        Action::MoveUp,
+        Action::Open,
+        Action::Collapse,
+        Action::GoTop,
+        Action::GoBottom,
+        Action::HalfPageDown,
+        Action::HalfPageUp,
+        Action::ToggleFocus,
+        Action::Back,
+        Action::Finder,
+        Action::Search,
+        Action::NewPage,
+        Action::Inbox,
+        Action::Edit,
+        Action::ExternalEdit,
+        Action::Review,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name used in `[bindings]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveDown => "move_down",
+            Action::MoveUp => "move_up",
+            Action::Open => "open",
+            Action::Collapse => "collapse",
+            Action::GoTop => "go_top",
+            Action::GoBottom => "go_bottom",
+            Action::HalfPageDown => "half_page_down",
+            Action::HalfPageUp => "half_page_up",
+            Action::ToggleFocus => "toggle_focus",
+            Action::Back => "back",
+            Action::Finder => "finder",
+            Action::Search => "search",
+            Action::NewPage => "new_page",
+            Action::Inbox => "inbox",
+            Action::Edit => "edit",
+            Action::ExternalEdit => "external_edit",
+            Action::Review => "review",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Line in the help overlay
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::MoveDown => "Move down / scroll down",
+            Action::MoveUp => "Move up / scroll up",
+            Action::Open => "Open page or expand",
+            Action::Collapse => "Collapse or go to parent",
+            Action::GoTop => "Go to top",
+            Action::GoBottom => "Go to bottom",
+            Action::HalfPageDown => "Scroll half a page down",
+            Action::HalfPageUp => "Scroll half a page up",
+            Action::ToggleFocus => "Switch between tree and content",
+            Action::Back => "Back to the tree",
+            Action::Finder => "Find page",
+            Action::Search => "Search",
+            Action::NewPage => "New page",
+            Action::Inbox => "Capture to inbox",
+            Action::Edit => "Edit page",
+            Action::ExternalEdit => "Edit page in $EDITOR",
+            Action::Review => "Review due flashcards",
+            Action::Help => "Show this help",
+            Action::Quit => "Quit",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+/// One key press. Shift is folded into the key, so `G` and Shift-Tab are
+/// stored without it whether or not the terminal reports Shift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let shift = modifiers.contains(KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Tab if shift => KeyCode::BackTab,
+            code => code,
+        };
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+
+    #[cfg(test)]
+    fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    #[cfg(test)]
+    fn ctrl(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// Parse one key like `j`, `G`, `ctrl-p` or `pagedown`
+    pub fn parse(s: &str) -> Result<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // A lone `-` or a trailing `-` (as in `ctrl--`) is the key itself
+        while let Some((prefix, tail)) = rest.split_once('-').filter(|(_, t)| !t.is_empty()) {
+            match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" | "c" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "m" => modifiers |= KeyModifiers::ALT,
+                "shift" | "s" => modifiers |= KeyModifiers::SHIFT,
+                _ => break,
+            }
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                let c = if modifiers.contains(KeyModifiers::SHIFT) {
+                    c.to_ascii_uppercase()
+                } else if modifiers.contains(KeyModifiers::CONTROL) {
+                    // Terminals report Ctrl-P as Ctrl+'p'
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                };
+                KeyCode::Char(c)
+            }
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" | "cr" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" | "bs" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!("unknown key \"{}\"", s),
+                },
+            },
+        };
+        Ok(Key::new(code, modifiers))
+    }
+
+    /// Short label for the status bar and help overlay
+    pub fn label(&self) -> String {
+        let base = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                c.to_ascii_uppercase().to_string()
+            }
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "S-Tab".to_string(),
+            KeyCode::Backspace => "Bksp".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Insert => "Ins".to_string(),
+            KeyCode::Up => "Up".to_string(),
+            KeyCode::Down => "Down".to_string(),
+            KeyCode::Left => "Left".to_string(),
+            KeyCode::Right => "Right".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            other => format!("{:?}", other),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push('^');
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("M-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("S-");
+        }
+        label.push_str(&base);
+        label
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        Key::new(event.code, event.modifiers)
+    }
+}
+
+/// A key sequence, usually a single key
+pub type KeySeq = Vec<Key>;
+
+/// Parse a space-separated sequence like `g g`
+pub fn parse_sequence(s: &str) -> Result<KeySeq> {
+    let keys = s
+        .split_whitespace()
+        .map(Key::parse)
+        .collect::<Result<KeySeq>>()?;
+    if keys.is_empty() {
+        bail!("empty key");
+    }
+    Ok(keys)
+}
+
+fn sequence_label(seq: &[Key]) -> String {
+    seq.iter().map(Key::label).collect()
+}
+
+/// What the keys typed so far mean
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Action(Action),
+    /// The start of a longer binding; wait for the next key
+    Pending,
+    None,
+}
+
+/// The active key bindings
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub profile: String,
+    bindings: Vec<(KeySeq, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::profile("vim").expect("vim profile exists")
+    }
+}
+
+/// `~/.config/nous/tui.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeymapFile {
+    profile: Option<String>,
+    #[serde(default)]
+    bindings: toml::Table,
+    /// Ranking weights, read by the finder
+    #[serde(default, rename = "finder")]
+    _finder: toml::Table,
+}
+
+impl Keymap {
+    /// Built-in bindings: `vim` (hjkl, gg/G, d/u, plus arrows) or `arrows`
+    /// (arrows, Home/End, PageUp/PageDown; letters only for commands)
+    pub fn profile(name: &str) -> Result<Keymap> {
+        use Action::*;
+        let common: &[(&str, Action)] = &[
+            ("down", MoveDown),
+            ("up", MoveUp),
+            ("right", Open),
+            ("enter", Open),
+            ("left", Collapse),
+            ("home", GoTop),
+            ("end", GoBottom),
+            ("pagedown", HalfPageDown),
+            ("pageup", HalfPageUp),
+            ("tab", ToggleFocus),
+            ("esc", Back),
+            ("ctrl-p", Finder),
+            ("/", Search),
+            ("n", NewPage),
+            ("i", Inbox),
+            ("e", Edit),
+            ("E", ExternalEdit),
+            ("r", Review),
+            ("?", Help),
+            ("q", Quit),
+        ];
+        let extra: &[(&str, Action)] = match name {
+            "vim" => &[
+                ("j", MoveDown),
+                ("k", MoveUp),
+                ("l", Open),
+                ("h", Collapse),
+                ("g g", GoTop),
+                ("G", GoBottom),
+                ("d", HalfPageDown),
+                ("ctrl-d", HalfPageDown),
+                ("u", HalfPageUp),
+                ("ctrl-u", HalfPageUp),
+            ],
+            "arrows" => &[("ctrl-f", Search)],
+            _ => bail!(
+                "unknown profile \"{}\" (expected \"vim\" or \"arrows\")",
+                name
+            ),
+        };
+        let bindings = extra
+            .iter()
+            .chain(common)
+            .map(|(keys, action)| Ok((parse_sequence(keys)?, *action)))
+            .collect::<Result<_>>()?;
+        Ok(Keymap {
+            profile: name.to_string(),
+            bindings,
+        })
+    }
+
+    /// Where the config file lives
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("nous").join("tui.toml"))
+    }
+
+    /// Load the user's keymap; a missing file gives the vim profile
+    pub fn load() -> Result<Keymap> {
+        match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Keymap::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Keymap> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml(&text).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Keymap> {
+        let file: KeymapFile = toml::from_str(text)?;
+        let mut keymap = Keymap::profile(file.profile.as_deref().unwrap_or("vim"))?;
+        for (name, value) in &file.bindings {
+            let action =
+                Action::from_name(name).ok_or_else(|| anyhow!("unknown action \"{}\"", name))?;
+            let keys = match value {
+                toml::Value::String(s) => vec![parse_sequence(s)?],
+                toml::Value::Array(items) => items
+                    .iter()
+                    .map(|item| match item {
+                        toml::Value::String(s) => parse_sequence(s),
+                        _ => bail!("keys for \"{}\" must be strings", name),
+                    })
+                    .collect::<Result<_>>()?,
+                _ => bail!(
+                    "keys for \"{}\" must be a string or a list of strings",
+                    name
+                ),
+            };
+            keymap.rebind(action, keys);
+        }
+        Ok(keymap)
+    }
+
+    /// Give `action` exactly these keys, taking them from other actions
+    pub fn rebind(&mut self, action: Action, keys: Vec<KeySeq>) {
+        self.bindings
+            .retain(|(seq, bound)| *bound != action && !keys.contains(seq));
+        self.bindings
+            .extend(keys.into_iter().map(|seq| (seq, action)));
+    }
+
+    /// Resolve the keys typed so far
+    pub fn lookup(&self, typed: &[Key]) -> Lookup {
+        if let Some((_, action)) = self
+            .bindings
+            .iter()
+            .find(|(seq, _)| seq.as_slice() == typed)
+        {
+            return Lookup::Action(*action);
+        }
+        if self
+            .bindings
+            .iter()
+            .any(|(seq, _)| seq.len() > typed.len() && seq.starts_with(typed))
+        {
+            return Lookup::Pending;
+        }
+        Lookup::None
+    }
+
+    /// Labels of the keys bound to `action`, in profile order
+    pub fn keys_for(&self, action: Action) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(seq, _)| sequence_label(seq))
+            .collect()
+    }
+
+    /// Status-bar hint like `^P: find`, or `None` when unbound
+    pub fn hint(&self, action: Action, text: &str) -> Option<String> {
+        self.keys_for(action)
+            .into_iter()
+            .next()
+            .map(|key| format!("{}: {}", key, text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        Key::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_keys_and_sequences() {
+        assert_eq!(key("j"), Key::plain(KeyCode::Char('j')));
+        assert_eq!(key("ctrl-P"), Key::ctrl('p'));
+        assert_eq!(key("shift-g"), key("G"));
+        assert_eq!(key("-"), Key::plain(KeyCode::Char('-')));
+        assert_eq!(key("PageDown"), Key::plain(KeyCode::PageDown));
+        assert_eq!(key("f5"), Key::plain(KeyCode::F(5)));
+        assert_eq!(key("shift-tab"), key("backtab"));
+        assert_eq!(parse_sequence("g g").unwrap(), vec![key("g"), key("g")]);
+        assert!(Key::parse("gg").is_err());
+        assert!(parse_sequence("  ").is_err());
+        // Terminals report uppercase letters with Shift held
+        let event = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(Key::from(event), key("G"));
+        assert_eq!(sequence_label(&parse_sequence("g g").unwrap()), "gg");
+        assert_eq!(key("ctrl-p").label(), "^P");
+    }
+
+    #[test]
+    fn vim_profile_resolves_sequences() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.lookup(&[key("j")]), Lookup::Action(Action::MoveDown));
+        assert_eq!(keymap.lookup(&[key("g")]), Lookup::Pending);
+        assert_eq!(
+            keymap.lookup(&[key("g"), key("g")]),
+            Lookup::Action(Action::GoTop)
+        );
+        assert_eq!(keymap.lookup(&[key("g"), key("x")]), Lookup::None);
+        assert_eq!(keymap.lookup(&[key("x")]), Lookup::None);
+
+        let arrows = Keymap::profile("arrows").unwrap();
+        assert_eq!(arrows.lookup(&[key("j")]), Lookup::None);
+        assert_eq!(
+            arrows.lookup(&[key("down")]),
+            Lookup::Action(Action::MoveDown)
+        );
+        assert!(Keymap::profile("emacs").is_err());
+    }
+
+    #[test]
+    fn config_overrides_profile_bindings() {
+        let keymap = Keymap::from_toml(
+            r#"
+            profile = "arrows"
+
+            [bindings]
+            new_page = ["N", "ctrl-n"]
+            review = "n"
+            inbox = []
+            "#,
+        )
+        .unwrap();
+        assert_eq!(keymap.profile, "arrows");
+        assert_eq!(keymap.keys_for(Action::NewPage), ["N", "^N"]);
+        // `n` moved from new_page to review, replacing `r`
+        assert_eq!(keymap.lookup(&[key("n")]), Lookup::Action(Action::Review));
+        assert_eq!(keymap.lookup(&[key("r")]), Lookup::None);
+        assert!(keymap.keys_for(Action::Inbox).is_empty());
+        assert_eq!(keymap.hint(Action::Inbox, "inbox"), None);
+        assert_eq!(
+            keymap.hint(Action::Finder, "find").as_deref(),
+            Some("^P: find")
+        );
+
+        assert!(Keymap::from_toml("[bindings]\nfly = \"f\"").is_err());
+        assert!(Keymap::from_toml("[bindings]\nquit = \"ctrl-nope\"").is_err());
+        assert!(Keymap::from_toml("profil = \"vim\"").is_err());
+    }
+}
@@ -1,8 +1,15 @@
 mod app_state;
 mod content_widget;
+mod editor_widget;
+mod finder_widget;
+mod fuzzy;
+mod help_widget;
 mod key_handler;
+mod keymap;
+mod review_widget;
 mod search_bar;
 mod status_bar;
+mod text_editor;
 mod tree_widget;
 mod ui;
 
@@ -55,6 +62,9 @@ fn run_loop<B: Backend>(terminal: &mut Terminal<B>, state: &mut TuiState) -> Res
             match event::read()? {
                 Event::Key(key) if key.kind == KeyEventKind::Press => {
                     key_handler::handle_key(state, key);
+                    if state.external_edit_requested {
+                        edit_externally(terminal, state)?;
+                    }
                 }
                 Event::Mouse(mouse) => {
                     key_handler::handle_mouse(state, mouse);
@@ -64,3 +74,16 @@ fn run_loop<B: Backend>(terminal: &mut Terminal<B>, state: &mut TuiState) -> Res
         }
     }
 }
+
+/// Hand the terminal to $EDITOR for the selected page, then take it back
+fn edit_externally<B: Backend>(terminal: &mut Terminal<B>, state: &mut TuiState) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+
+    state.edit_externally();
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    Ok(())
+}
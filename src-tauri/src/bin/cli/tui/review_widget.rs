@@ -0,0 +1,58 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+
+use super::app_state::TuiState;
+
+/// The current flashcard in the content panel: the front, then the back once
+/// revealed
+pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
+    let Some(review) = state.review.as_ref() else {
+        return;
+    };
+    let Some(current) = review.cards.get(review.current) else {
+        return;
+    };
+
+    let block = Block::default()
+        .title(format!(
+            " Review: {} ({}/{}) ",
+            review.notebook_name,
+            review.current + 1,
+            review.cards.len()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let mut lines = vec![Line::from("")];
+    lines.extend(current.card.front.lines().map(|l| {
+        Line::from(Span::styled(
+            format!("  {}", l),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    }));
+    lines.push(Line::from(""));
+    if review.revealed {
+        lines.push(Line::from(Span::styled(
+            "  ───",
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(""));
+        lines.extend(
+            current
+                .card
+                .back
+                .lines()
+                .map(|l| Line::from(format!("  {}", l))),
+        );
+    } else {
+        lines.push(Line::from(Span::styled(
+            "  Press Space to show the answer.",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
@@ -1,6 +1,8 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
 
+use nous_lib::search::SuggestionKind;
+
 use super::app_state::TuiState;
 
 pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
@@ -58,6 +60,49 @@ pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
         list_state.select(Some(state.search_selected));
 
         f.render_stateful_widget(results_list, results_area, &mut list_state);
+    } else if !state.search_suggestions.is_empty() {
+        let max_rows = 10.min(state.search_suggestions.len());
+        let height = max_rows as u16 + 2;
+        let suggestions_area = Rect {
+            x: area.x + 1,
+            y: area.y + area.height.saturating_sub(height + 1),
+            width: area.width.saturating_sub(2),
+            height,
+        };
+        f.render_widget(Clear, suggestions_area);
+
+        let items: Vec<ListItem> = state.search_suggestions.iter()
+            .take(max_rows)
+            .map(|s| {
+                let (marker, color) = match s.kind {
+                    SuggestionKind::History => ("↺ ", Color::DarkGray),
+                    SuggestionKind::Page => ("  ", Color::White),
+                    SuggestionKind::Tag => ("# ", Color::Cyan),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(marker, Style::default().fg(color)),
+                    Span::raw(s.text.clone()),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(" Suggestions ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::DarkGray)),
+            )
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("> ");
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.search_selected));
+        f.render_stateful_widget(list, suggestions_area, &mut list_state);
     }
 
     // Set cursor position
@@ -2,6 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
 use super::app_state::{Mode, TuiState};
+use super::keymap::Action;
 
 pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
     // Show flash message if present
@@ -27,14 +28,46 @@ pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
         }
         _ => {
             let hints = match state.mode {
-                Mode::Tree => {
-                    " /: search  n: new page  i: inbox  j/k: navigate  Enter: open  q: quit "
+                Mode::Tree => keymap_hints(
+                    state,
+                    &[
+                        (Action::Finder, "find"),
+                        (Action::Search, "search"),
+                        (Action::NewPage, "new page"),
+                        (Action::Inbox, "inbox"),
+                        (Action::Open, "open"),
+                        (Action::Edit, "edit"),
+                        (Action::ExternalEdit, "$EDITOR"),
+                        (Action::Review, "review"),
+                        (Action::Help, "help"),
+                        (Action::Quit, "quit"),
+                    ],
+                ),
+                Mode::Content => keymap_hints(
+                    state,
+                    &[
+                        (Action::Back, "back"),
+                        (Action::Finder, "find"),
+                        (Action::Search, "search"),
+                        (Action::HalfPageDown, "half-page"),
+                        (Action::GoBottom, "bottom"),
+                        (Action::Edit, "edit"),
+                        (Action::Review, "review"),
+                        (Action::Help, "help"),
+                        (Action::Quit, "quit"),
+                    ],
+                ),
+                Mode::Edit => {
+                    " Ctrl-S: save  Ctrl-Z: revert  Esc: leave editor (keeps draft)  Tab: indent ".to_string()
                 }
-                Mode::Content => {
-                    " Esc: back  /: search  j/k: scroll  d/u: half-page  gg/G: top/bottom  q: quit "
+                Mode::Finder => {
+                    " Type to filter  Up/Down: select  Enter: open  Esc: cancel ".to_string()
                 }
                 Mode::Search => {
-                    " Type to search  Up/Down: select  Enter: open  Esc: cancel "
+                    " Type to search  Up/Down: select  Tab: complete  Enter: open  Esc: cancel ".to_string()
+                }
+                Mode::Review => {
+                    " Space: show answer  1: again  2: hard  3: good  4: easy  Esc: stop ".to_string()
                 }
                 _ => unreachable!(),
             };
@@ -45,3 +78,12 @@ pub fn draw(f: &mut Frame, area: Rect, state: &TuiState) {
         }
     }
 }
+
+/// Hints for the actions that have keys in the active keymap
+fn keymap_hints(state: &TuiState, actions: &[(Action, &str)]) -> String {
+    let hints: Vec<String> = actions
+        .iter()
+        .filter_map(|(action, text)| state.keymap.hint(*action, text))
+        .collect();
+    format!(" {} ", hints.join("  "))
+}
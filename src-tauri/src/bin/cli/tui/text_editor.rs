@@ -0,0 +1,218 @@
+//! Multi-line text buffer behind the TUI's page editor.
+//!
+//! Columns are counted in chars, not bytes, so the cursor never lands inside
+//! a multi-byte character.
+
+pub struct TextEditor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+    /// First visible line and column
+    pub scroll: usize,
+    pub h_scroll: usize,
+    dirty: bool,
+}
+
+impl TextEditor {
+    pub fn new(text: &str) -> Self {
+        let mut lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        Self {
+            lines,
+            row: 0,
+            col: 0,
+            scroll: 0,
+            h_scroll: 0,
+            dirty: false,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    fn byte_index(&self, row: usize, col: usize) -> usize {
+        self.lines[row]
+            .char_indices()
+            .nth(col)
+            .map(|(i, _)| i)
+            .unwrap_or(self.lines[row].len())
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let at = self.byte_index(self.row, self.col);
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.dirty = true;
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        for c in s.chars() {
+            if c == '\n' {
+                self.insert_newline();
+            } else {
+                self.insert_char(c);
+            }
+        }
+    }
+
+    pub fn insert_newline(&mut self) {
+        let at = self.byte_index(self.row, self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+        self.dirty = true;
+    }
+
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = self.byte_index(self.row, self.col);
+            self.lines[self.row].remove(at);
+            self.dirty = true;
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+            self.lines[self.row].push_str(&line);
+            self.dirty = true;
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.col < self.line_len(self.row) {
+            let at = self.byte_index(self.row, self.col);
+            self.lines[self.row].remove(at);
+            self.dirty = true;
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+            self.dirty = true;
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if self.col < self.line_len(self.row) {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    pub fn move_up(&mut self, lines: usize) {
+        self.row = self.row.saturating_sub(lines);
+        self.col = self.col.min(self.line_len(self.row));
+    }
+
+    pub fn move_down(&mut self, lines: usize) {
+        self.row = (self.row + lines).min(self.lines.len() - 1);
+        self.col = self.col.min(self.line_len(self.row));
+    }
+
+    pub fn move_home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.col = self.line_len(self.row);
+    }
+
+    /// Adjust scrolling so the cursor is inside a `width` x `height` view
+    pub fn scroll_to_cursor(&mut self, width: usize, height: usize) {
+        if self.row < self.scroll {
+            self.scroll = self.row;
+        } else if height > 0 && self.row >= self.scroll + height {
+            self.scroll = self.row + 1 - height;
+        }
+        if self.col < self.h_scroll {
+            self.h_scroll = self.col;
+        } else if width > 0 && self.col >= self.h_scroll + width {
+            self.h_scroll = self.col + 1 - width;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typing_and_joining_lines() {
+        let mut editor = TextEditor::new("# Title\n\nbody");
+        assert!(!editor.is_dirty());
+
+        editor.move_down(2);
+        editor.move_end();
+        editor.insert_str(" text\nmore");
+        assert_eq!(editor.text(), "# Title\n\nbody text\nmore\n");
+        assert_eq!(editor.cursor(), (3, 4));
+        assert!(editor.is_dirty());
+
+        editor.move_home();
+        editor.backspace();
+        assert_eq!(editor.text(), "# Title\n\nbody textmore\n");
+        assert_eq!(editor.cursor(), (2, 9));
+
+        editor.move_up(2);
+        editor.move_end();
+        editor.delete();
+        assert_eq!(editor.lines()[0], "# Title");
+        assert_eq!(editor.lines().len(), 2);
+    }
+
+    #[test]
+    fn cursor_is_char_based() {
+        let mut editor = TextEditor::new("héllo");
+        editor.move_right();
+        editor.move_right();
+        editor.backspace();
+        assert_eq!(editor.text(), "hllo\n");
+        editor.insert_char('é');
+        editor.insert_char('ü');
+        assert_eq!(editor.text(), "héüllo\n");
+    }
+
+    #[test]
+    fn vertical_moves_clamp_the_column() {
+        let mut editor = TextEditor::new("a long line\nab\n");
+        editor.move_end();
+        editor.move_down(1);
+        assert_eq!(editor.cursor(), (1, 2));
+        editor.move_down(10);
+        assert_eq!(editor.cursor(), (1, 2));
+
+        editor.scroll_to_cursor(1, 1);
+        assert_eq!((editor.scroll, editor.h_scroll), (1, 2));
+    }
+}
@@ -36,7 +36,16 @@ pub fn draw(f: &mut Frame, area: Rect, state: &mut TuiState) {
             }
         };
 
-        let text = format!("{}{}{}", indent, prefix, item.label);
+        // Mark pages with unsaved edits
+        let dirty = match item.kind {
+            TreeItemKind::Page { id, .. } => {
+                state.drafts.get(&id).is_some_and(|d| d.editor.is_dirty())
+            }
+            _ => false,
+        };
+        let marker = if dirty { " \u{25cf}" } else { "" };
+
+        let text = format!("{}{}{}{}", indent, prefix, item.label, marker);
         ListItem::new(text).style(style)
     }).collect();
 
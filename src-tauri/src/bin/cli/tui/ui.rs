@@ -2,7 +2,10 @@ use ratatui::prelude::*;
 use ratatui::layout::{Constraint, Direction, Layout};
 
 use super::app_state::{Mode, TuiState};
-use super::{content_widget, search_bar, status_bar, tree_widget};
+use super::{
+    content_widget, editor_widget, finder_widget, help_widget, review_widget, search_bar,
+    status_bar, tree_widget,
+};
 
 pub fn draw(f: &mut Frame, state: &mut TuiState) {
     let size = f.area();
@@ -37,7 +40,13 @@ pub fn draw(f: &mut Frame, state: &mut TuiState) {
 
     // Draw panels
     tree_widget::draw(f, tree_area, state);
-    content_widget::draw(f, content_area, state);
+    if state.mode == Mode::Edit {
+        editor_widget::draw(f, content_area, state);
+    } else if state.mode == Mode::Review {
+        review_widget::draw(f, content_area, state);
+    } else {
+        content_widget::draw(f, content_area, state);
+    }
 
     // Draw status bar or search overlay
     if state.mode == Mode::Search {
@@ -45,4 +54,12 @@ pub fn draw(f: &mut Frame, state: &mut TuiState) {
     } else {
         status_bar::draw(f, status_area, state);
     }
+
+    if state.mode == Mode::Finder {
+        finder_widget::draw(f, main_area, state);
+    }
+
+    if state.show_help {
+        help_widget::draw(f, main_area, state);
+    }
 }
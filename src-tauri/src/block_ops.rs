@@ -0,0 +1,1052 @@
+//! Structural page edits: moving blocks to another page, extracting blocks
+//! into a new page, merging a page into another, and splitting a page into
+//! subpages by its headings.
+//!
+//! An edit is planned in memory as a [`BlockEdit`] and then written by
+//! [`BlockEdit::commit`], which puts every page back the way it was if any
+//! write fails. Links follow the content: block refs to moved blocks point at
+//! their new page, and after a merge wiki-links and block refs to the merged
+//! page point at the page it went into, as do its subpages. Links are updated
+//! within the edit's notebook. Comment threads anchored to moved blocks move
+//! with them. Images and attachments stay valid as they are, since assets
+//! belong to the notebook rather than a page.
+//!
+//! Moved blocks keep their ids unless the page they move to already has a
+//! block with the same id. Only unencrypted block pages can be edited.
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::auto_link::{html_escape, wiki_link, LinkTarget};
+use crate::comments::CommentsStorage;
+use crate::storage::html_utils::html_to_searchable_text;
+use crate::storage::{EditorBlock, FileStorage, FileStorageWriter, Page, PageType, StorageError};
+
+/// Longest title taken from the first extracted block
+const MAX_DERIVED_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Error)]
+pub enum BlockOpError {
+    #[error("No blocks given")]
+    NoBlocks,
+
+    #[error("Block not found: {0}")]
+    BlockNotFound(String),
+
+    #[error("A page can't be merged into itself")]
+    SamePage,
+
+    #[error("'{0}' has no headings to split at")]
+    NoHeadings(String),
+
+    #[error("'{0}' isn't a block page")]
+    NotBlockPage(String),
+
+    #[error("'{0}' is encrypted")]
+    Encrypted(String),
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+pub type Result<T> = std::result::Result<T, BlockOpError>;
+
+/// How a split page lists the subpages its sections became
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TocStyle {
+    /// A link to each subpage
+    #[default]
+    Links,
+    /// Each subpage embedded in full
+    Transclusions,
+}
+
+/// The pages one edit writes
+#[derive(Debug, Default)]
+pub struct BlockEdit {
+    /// Changed pages as they were read, in the same order as `updated`
+    originals: Vec<Page>,
+    /// Changed pages; a page the edit moves to trash has `deleted_at` set
+    updated: Vec<Page>,
+    /// Pages the edit creates
+    created: Vec<Page>,
+    /// Comment threads to move along with blocks
+    thread_moves: Vec<ThreadMove>,
+}
+
+/// Threads of `from` anchored to `block_ids` (all of them if `None`) go to `to`
+#[derive(Debug)]
+struct ThreadMove {
+    notebook_id: Uuid,
+    from: Uuid,
+    to: Uuid,
+    block_ids: Option<HashSet<String>>,
+}
+
+impl BlockEdit {
+    pub fn updated(&self) -> &[Page] {
+        &self.updated
+    }
+
+    pub fn created(&self) -> &[Page] {
+        &self.created
+    }
+
+    /// Changed and created pages, for adjusting their content before commit
+    pub fn pages_mut(&mut self) -> impl Iterator<Item = &mut Page> {
+        self.updated.iter_mut().chain(self.created.iter_mut())
+    }
+
+    /// Write every page of the edit. If a write fails, the pages already
+    /// written are restored and created pages are removed again. Comment
+    /// threads move once all pages are written.
+    pub fn commit(&self, storage: &FileStorageWriter) -> Result<()> {
+        for (created, page) in self.created.iter().enumerate() {
+            if let Err(e) = storage.create_page_from(page.clone()) {
+                self.roll_back(storage, created, 0);
+                return Err(e.into());
+            }
+        }
+        for (written, page) in self.updated.iter().enumerate() {
+            if let Err(e) = storage.update_page(page) {
+                self.roll_back(storage, self.created.len(), written);
+                return Err(e.into());
+            }
+        }
+        self.move_threads(storage);
+        Ok(())
+    }
+
+    fn move_threads(&self, storage: &FileStorage) {
+        if self.thread_moves.is_empty() {
+            return;
+        }
+        let comments = CommentsStorage::new(storage.notebooks_dir().join("notebooks"));
+        for thread_move in &self.thread_moves {
+            if let Err(e) = comments.move_threads(
+                thread_move.notebook_id,
+                thread_move.from,
+                thread_move.to,
+                thread_move.block_ids.as_ref(),
+            ) {
+                log::warn!(
+                    "Failed to move comments from page {} to {}: {}",
+                    thread_move.from,
+                    thread_move.to,
+                    e
+                );
+            }
+        }
+    }
+
+    fn roll_back(&self, storage: &FileStorageWriter, created: usize, written: usize) {
+        for original in &self.originals[..written] {
+            if let Err(e) = storage.update_page(original) {
+                log::error!(
+                    "Failed to restore page {} after a failed edit: {}",
+                    original.id,
+                    e
+                );
+            }
+        }
+        for page in &self.created[..created] {
+            if let Err(e) = storage.permanent_delete_page(page.notebook_id, page.id) {
+                log::error!(
+                    "Failed to remove page {} after a failed edit: {}",
+                    page.id,
+                    e
+                );
+            }
+        }
+    }
+
+    fn add(&mut self, original: Page, updated: Page) {
+        self.originals.push(original);
+        self.updated.push(updated);
+    }
+
+    fn move_threads_with(
+        &mut self,
+        notebook_id: Uuid,
+        from: Uuid,
+        to: Uuid,
+        block_ids: Option<HashSet<String>>,
+    ) {
+        self.thread_moves.push(ThreadMove {
+            notebook_id,
+            from,
+            to,
+            block_ids,
+        });
+    }
+
+    fn page_mut(&mut self, page_id: Uuid) -> Option<&mut Page> {
+        self.pages_mut().find(|p| p.id == page_id)
+    }
+}
+
+/// How links change with an edit
+enum LinkChange<'a> {
+    /// Refs to these blocks of `from` now point at `to`
+    BlocksMoved {
+        from: Uuid,
+        to: Uuid,
+        block_ids: &'a HashSet<String>,
+    },
+    /// Links to `from` and its blocks now point at `to`; so do its subpages
+    PageMerged { from: &'a Page, to: &'a LinkTarget },
+}
+
+/// Move blocks of a page to `target_page_id`, inserted at `position` (the
+/// end if `None` or past it). Moving them within their own page reorders
+/// them.
+pub fn move_blocks(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    page_id: Uuid,
+    block_ids: &[String],
+    target_page_id: Uuid,
+    position: Option<usize>,
+) -> Result<BlockEdit> {
+    let original = load_block_page(storage, notebook_id, page_id)?;
+    let mut source = original.clone();
+    let (_, moved) = take_blocks(&mut source.content.blocks, block_ids)?;
+    let mut edit = BlockEdit::default();
+
+    if target_page_id == page_id {
+        insert_blocks(&mut source.content.blocks, moved, position);
+        touch(&mut source);
+        edit.add(original, source);
+        return Ok(edit);
+    }
+
+    let target_original = load_block_page(storage, notebook_id, target_page_id)?;
+    let mut target = target_original.clone();
+    let moved_ids: HashSet<String> = moved.iter().map(|b| b.id.clone()).collect();
+    let moved = with_unique_ids(&target.content.blocks, moved);
+    insert_blocks(&mut target.content.blocks, moved, position);
+    touch(&mut source);
+    touch(&mut target);
+    edit.add(original, source);
+    edit.add(target_original, target);
+
+    retarget_notebook(
+        storage,
+        notebook_id,
+        &[LinkChange::BlocksMoved {
+            from: page_id,
+            to: target_page_id,
+            block_ids: &moved_ids,
+        }],
+        &mut edit,
+    )?;
+    edit.move_threads_with(notebook_id, page_id, target_page_id, Some(moved_ids));
+    Ok(edit)
+}
+
+/// Move blocks of a page into a new page next to it, titled `title` or after
+/// the first block. With `leave_link`, a link to the new page takes the
+/// blocks' place.
+pub fn extract_blocks(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    page_id: Uuid,
+    block_ids: &[String],
+    title: Option<String>,
+    leave_link: bool,
+) -> Result<BlockEdit> {
+    let original = load_block_page(storage, notebook_id, page_id)?;
+    let mut source = original.clone();
+    let (at, blocks) = take_blocks(&mut source.content.blocks, block_ids)?;
+    let moved_ids: HashSet<String> = blocks.iter().map(|b| b.id.clone()).collect();
+
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| derived_title(&blocks));
+    let mut page = Page::new(notebook_id, title);
+    page.folder_id = source.folder_id;
+    page.section_id = source.section_id;
+    page.parent_page_id = source.parent_page_id;
+    page.content.blocks = blocks;
+    page.content.time = Some(Utc::now().timestamp_millis());
+
+    if leave_link {
+        let target = LinkTarget {
+            page_id: page.id,
+            title: page.title.clone(),
+        };
+        source.content.blocks.insert(
+            at,
+            EditorBlock {
+                id: Uuid::new_v4().to_string(),
+                block_type: "paragraph".to_string(),
+                data: json!({ "text": wiki_link(&target, &html_escape(&page.title)) }),
+            },
+        );
+    }
+    touch(&mut source);
+
+    let mut edit = BlockEdit::default();
+    let new_page_id = page.id;
+    edit.add(original, source);
+    edit.created.push(page);
+
+    retarget_notebook(
+        storage,
+        notebook_id,
+        &[LinkChange::BlocksMoved {
+            from: page_id,
+            to: new_page_id,
+            block_ids: &moved_ids,
+        }],
+        &mut edit,
+    )?;
+    edit.move_threads_with(notebook_id, page_id, new_page_id, Some(moved_ids));
+    Ok(edit)
+}
+
+/// Append a page's blocks and tags to `target_page_id` and move the page to
+/// trash. Links to it and its subpages move to the target.
+pub fn merge_page_into(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    page_id: Uuid,
+    target_page_id: Uuid,
+) -> Result<BlockEdit> {
+    if page_id == target_page_id {
+        return Err(BlockOpError::SamePage);
+    }
+    let original = load_block_page(storage, notebook_id, page_id)?;
+    let target_original = load_block_page(storage, notebook_id, target_page_id)?;
+
+    let mut target = target_original.clone();
+    let blocks = with_unique_ids(&target.content.blocks, original.content.blocks.clone());
+    target.content.blocks.extend(blocks);
+    for tag in &original.tags {
+        if !target.tags.contains(tag) {
+            target.tags.push(tag.clone());
+        }
+    }
+    touch(&mut target);
+
+    let mut merged = original.clone();
+    merged.deleted_at = Some(Utc::now());
+    merged.updated_at = Utc::now();
+
+    let link_target = LinkTarget {
+        page_id: target.id,
+        title: target.title.clone(),
+    };
+    let mut edit = BlockEdit::default();
+    edit.add(original.clone(), merged);
+    edit.add(target_original, target);
+
+    retarget_notebook(
+        storage,
+        notebook_id,
+        &[LinkChange::PageMerged {
+            from: &original,
+            to: &link_target,
+        }],
+        &mut edit,
+    )?;
+    edit.move_threads_with(notebook_id, page_id, target_page_id, None);
+    Ok(edit)
+}
+
+/// Split a page at its headings of `max_level` and above (1 or 2): each
+/// section becomes a subpage titled after its heading, and the page keeps
+/// what came before the first heading followed by a table of contents. The
+/// contents entries take over the headings' block ids, so refs to a heading
+/// still land on the page.
+pub fn split_page_by_headings(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    page_id: Uuid,
+    max_level: u8,
+    style: TocStyle,
+) -> Result<BlockEdit> {
+    let original = load_block_page(storage, notebook_id, page_id)?;
+    let is_split_heading = |block: &EditorBlock| {
+        block.block_type == "header"
+            && block
+                .data
+                .get("level")
+                .and_then(|v| v.as_u64())
+                .is_some_and(|level| level <= u64::from(max_level))
+    };
+
+    let mut intro = Vec::new();
+    let mut sections: Vec<(EditorBlock, Vec<EditorBlock>)> = Vec::new();
+    for block in original.content.blocks.iter().cloned() {
+        if is_split_heading(&block) {
+            sections.push((block, Vec::new()));
+        } else if let Some((_, blocks)) = sections.last_mut() {
+            blocks.push(block);
+        } else {
+            intro.push(block);
+        }
+    }
+    if sections.is_empty() {
+        return Err(BlockOpError::NoHeadings(original.title));
+    }
+
+    let mut edit = BlockEdit::default();
+    let mut toc = Vec::new();
+    for (position, (heading, blocks)) in sections.into_iter().enumerate() {
+        let title = heading
+            .data
+            .get("text")
+            .and_then(|v| v.as_str())
+            .map(|html| html_to_searchable_text(html).trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "Untitled".to_string());
+        let mut child = Page::new(notebook_id, title);
+        child.parent_page_id = Some(page_id);
+        child.folder_id = original.folder_id;
+        child.section_id = original.section_id;
+        child.position = position as i32;
+        child.tags = original.tags.clone();
+        child.content.blocks = blocks;
+        child.content.time = Some(Utc::now().timestamp_millis());
+
+        toc.push(EditorBlock {
+            id: heading.id,
+            block_type: match style {
+                TocStyle::Links => "paragraph",
+                TocStyle::Transclusions => "embed",
+            }
+            .to_string(),
+            data: match style {
+                TocStyle::Links => json!({
+                    "text": wiki_link(
+                        &LinkTarget { page_id: child.id, title: child.title.clone() },
+                        &html_escape(&child.title),
+                    ),
+                }),
+                TocStyle::Transclusions => json!({
+                    "embedType": "page",
+                    "pageTitle": child.title,
+                    "pageId": child.id.to_string(),
+                    "url": "",
+                    "isCollapsed": false,
+                    "caption": "",
+                    "displayMode": "embed",
+                }),
+            },
+        });
+        edit.created.push(child);
+    }
+
+    let mut parent = original.clone();
+    parent.content.blocks = intro;
+    parent.content.blocks.extend(toc);
+    touch(&mut parent);
+    edit.add(original, parent);
+
+    let moved: Vec<(Uuid, HashSet<String>)> = edit
+        .created
+        .iter()
+        .map(|child| {
+            let ids = child.content.blocks.iter().map(|b| b.id.clone()).collect();
+            (child.id, ids)
+        })
+        .collect();
+    let changes: Vec<LinkChange> = moved
+        .iter()
+        .map(|(child_id, block_ids)| LinkChange::BlocksMoved {
+            from: page_id,
+            to: *child_id,
+            block_ids,
+        })
+        .collect();
+    retarget_notebook(storage, notebook_id, &changes, &mut edit)?;
+    for (child_id, block_ids) in moved {
+        edit.move_threads_with(notebook_id, page_id, child_id, Some(block_ids));
+    }
+    Ok(edit)
+}
+
+fn load_block_page(storage: &FileStorage, notebook_id: Uuid, page_id: Uuid) -> Result<Page> {
+    let page = storage.get_page(notebook_id, page_id)?;
+    if page.deleted_at.is_some() {
+        return Err(StorageError::PageNotFound(page_id).into());
+    }
+    if page.is_encrypted() {
+        return Err(BlockOpError::Encrypted(page.title));
+    }
+    if !is_block_page(&page) {
+        return Err(BlockOpError::NotBlockPage(page.title));
+    }
+    Ok(page)
+}
+
+fn is_block_page(page: &Page) -> bool {
+    page.page_type == PageType::Standard && page.plugin_page_type.is_none()
+}
+
+fn touch(page: &mut Page) {
+    let now = Utc::now();
+    page.updated_at = now;
+    page.content.time = Some(now.timestamp_millis());
+}
+
+/// Remove the blocks with the given ids; returns where the first of them
+/// was, and the blocks in page order
+fn take_blocks(
+    blocks: &mut Vec<EditorBlock>,
+    block_ids: &[String],
+) -> Result<(usize, Vec<EditorBlock>)> {
+    if block_ids.is_empty() {
+        return Err(BlockOpError::NoBlocks);
+    }
+    if let Some(missing) = block_ids
+        .iter()
+        .find(|id| !blocks.iter().any(|b| &b.id == *id))
+    {
+        return Err(BlockOpError::BlockNotFound(missing.clone()));
+    }
+    let wanted: HashSet<&str> = block_ids.iter().map(String::as_str).collect();
+    let first = blocks
+        .iter()
+        .position(|b| wanted.contains(b.id.as_str()))
+        .unwrap_or(0);
+    let (taken, kept) = std::mem::take(blocks)
+        .into_iter()
+        .partition(|b| wanted.contains(b.id.as_str()));
+    *blocks = kept;
+    Ok((first, taken))
+}
+
+fn insert_blocks(blocks: &mut Vec<EditorBlock>, moved: Vec<EditorBlock>, position: Option<usize>) {
+    let at = position.map_or(blocks.len(), |p| p.min(blocks.len()));
+    blocks.splice(at..at, moved);
+}
+
+/// `moved`, with new ids for blocks whose id is already in `existing`
+fn with_unique_ids(existing: &[EditorBlock], mut moved: Vec<EditorBlock>) -> Vec<EditorBlock> {
+    let taken: HashSet<&str> = existing.iter().map(|b| b.id.as_str()).collect();
+    for block in &mut moved {
+        if taken.contains(block.id.as_str()) {
+            block.id = Uuid::new_v4().to_string();
+        }
+    }
+    moved
+}
+
+/// Title for extracted blocks: the text of the first block that has any
+fn derived_title(blocks: &[EditorBlock]) -> String {
+    blocks
+        .iter()
+        .filter_map(|b| b.data.get("text").and_then(|v| v.as_str()))
+        .map(|html| html_to_searchable_text(html).trim().to_string())
+        .find(|text| !text.is_empty())
+        .map(|text| text.chars().take(MAX_DERIVED_TITLE_CHARS).collect())
+        .unwrap_or_else(|| "Untitled".to_string())
+}
+
+/// Apply `changes` to every block page of the notebook, adding the pages
+/// they change to the edit. Pages already in the edit are changed in place.
+fn retarget_notebook(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    changes: &[LinkChange],
+    edit: &mut BlockEdit,
+) -> Result<()> {
+    for page in storage.list_pages(notebook_id)? {
+        if let Some(edited) = edit.page_mut(page.id) {
+            if edited.deleted_at.is_none() {
+                retarget_page(edited, changes);
+            }
+            continue;
+        }
+        if page.deleted_at.is_some() || page.is_encrypted() || !is_block_page(&page) {
+            continue;
+        }
+        let mut changed = page.clone();
+        if retarget_page(&mut changed, changes) {
+            changed.updated_at = Utc::now();
+            edit.add(page, changed);
+        }
+    }
+    for created in &mut edit.created {
+        retarget_page(created, changes);
+    }
+    Ok(())
+}
+
+fn retarget_page(page: &mut Page, changes: &[LinkChange]) -> bool {
+    let mut changed = false;
+    for change in changes {
+        for block in &mut page.content.blocks {
+            changed |= retarget_embed(block, change);
+            changed |= retarget_value(&mut block.data, change);
+        }
+        changed |= reparent(page, change);
+    }
+    changed
+}
+
+/// Subpages of a merged page move under the page it went into
+fn reparent(page: &mut Page, change: &LinkChange) -> bool {
+    let mut changed = false;
+    if let LinkChange::PageMerged { from, to } = change {
+        if page.parent_page_id == Some(from.id) {
+            // The target itself may have been a subpage of the merged page
+            page.parent_page_id = if page.id == to.page_id {
+                from.parent_page_id
+            } else {
+                Some(to.page_id)
+            };
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Retarget an embed block: `blockEmbed` embeds one block of a page, `embed`
+/// with `embedType` "page" a whole page
+fn retarget_embed(block: &mut EditorBlock, change: &LinkChange) -> bool {
+    let data = &mut block.data;
+    let target_page = data_uuid(data, "targetPageId");
+    let (key, to, title) = match (block.block_type.as_str(), change) {
+        (
+            "blockEmbed",
+            LinkChange::BlocksMoved {
+                from,
+                to,
+                block_ids,
+            },
+        ) if target_page == Some(*from)
+            && data
+                .get("targetBlockId")
+                .and_then(|v| v.as_str())
+                .is_some_and(|id| block_ids.contains(id)) =>
+        {
+            ("targetPageId", *to, None)
+        }
+        ("blockEmbed", LinkChange::PageMerged { from, to }) if target_page == Some(from.id) => {
+            ("targetPageId", to.page_id, None)
+        }
+        ("embed", LinkChange::PageMerged { from, to })
+            if data_uuid(data, "pageId") == Some(from.id) =>
+        {
+            ("pageId", to.page_id, Some(to.title.as_str()))
+        }
+        _ => return false,
+    };
+    data[key] = json!(to.to_string());
+    if let Some(title) = title {
+        data["pageTitle"] = json!(title);
+    }
+    true
+}
+
+fn data_uuid(data: &Value, key: &str) -> Option<Uuid> {
+    data.get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+fn retarget_value(value: &mut Value, change: &LinkChange) -> bool {
+    match value {
+        Value::String(html) => match retarget_html(html, change) {
+            Some(retargeted) => {
+                *html = retargeted;
+                true
+            }
+            None => false,
+        },
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |changed, v| retarget_value(v, change) | changed),
+        Value::Object(map) => map
+            .values_mut()
+            .fold(false, |changed, v| retarget_value(v, change) | changed),
+        _ => false,
+    }
+}
+
+/// `html` with its links retargeted, or `None` if none of them change
+fn retarget_html(html: &str, change: &LinkChange) -> Option<String> {
+    if !html.contains("<wiki-link") && !html.contains("<block-ref") {
+        return None;
+    }
+    let mut out = String::with_capacity(html.len());
+    let mut changed = false;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start..start + len + 1];
+        match retarget_tag(tag, change) {
+            Some(retargeted) => {
+                out.push_str(&retargeted);
+                changed = true;
+            }
+            None => out.push_str(tag),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    changed.then_some(out)
+}
+
+fn retarget_tag(tag: &str, change: &LinkChange) -> Option<String> {
+    let name = tag[1..]
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default();
+    let page_id = attr(tag, "data-page-id").and_then(|id| Uuid::parse_str(id).ok());
+    match (name, change) {
+        (
+            "block-ref",
+            LinkChange::BlocksMoved {
+                from,
+                to,
+                block_ids,
+            },
+        ) => {
+            let moved = page_id == Some(*from)
+                && attr(tag, "data-block-id").is_some_and(|id| block_ids.contains(id));
+            moved.then(|| set_attr(tag, "data-page-id", &to.to_string()))
+        }
+        ("block-ref", LinkChange::PageMerged { from, to }) => (page_id == Some(from.id))
+            .then(|| set_attr(tag, "data-page-id", &to.page_id.to_string())),
+        ("wiki-link", LinkChange::PageMerged { from, to }) => {
+            // Links without an id resolve by title
+            let linked = match page_id {
+                Some(id) => id == from.id,
+                None => attr(tag, "data-page-title")
+                    .is_some_and(|t| t.eq_ignore_ascii_case(&html_escape(&from.title))),
+            };
+            linked.then(|| {
+                let tag = set_attr(tag, "data-page-id", &to.page_id.to_string());
+                set_attr(&tag, "data-page-title", &html_escape(&to.title))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Value of a double-quoted attribute of an opening tag
+pub(crate) fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!(" {}=\"", name);
+    let start = tag.find(&key)? + key.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// `tag` with the attribute set to `value` (already escaped), added if missing
+pub(crate) fn set_attr(tag: &str, name: &str, value: &str) -> String {
+    if let Some(current) = attr(tag, name) {
+        let start = current.as_ptr() as usize - tag.as_ptr() as usize;
+        return format!(
+            "{}{}{}",
+            &tag[..start],
+            value,
+            &tag[start + current.len()..]
+        );
+    }
+    let end = tag.trim_end_matches('>').trim_end_matches('/').len();
+    format!("{} {}=\"{}\"{}", &tag[..end], name, value, &tag[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::NotebookType;
+    use tempfile::TempDir;
+
+    fn paragraph(id: &str, text: &str) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: "paragraph".to_string(),
+            data: json!({ "text": text }),
+        }
+    }
+
+    fn ids(page: &Page) -> Vec<&str> {
+        page.content.blocks.iter().map(|b| b.id.as_str()).collect()
+    }
+
+    fn text(page: &Page, index: usize) -> &str {
+        page.content.blocks[index].data["text"].as_str().unwrap()
+    }
+
+    fn setup() -> (TempDir, FileStorageWriter, Uuid) {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        (temp_dir, storage, notebook.id)
+    }
+
+    fn page_with(
+        storage: &FileStorageWriter,
+        notebook_id: Uuid,
+        title: &str,
+        blocks: Vec<EditorBlock>,
+    ) -> Page {
+        let mut page = storage.create_page(notebook_id, title.to_string()).unwrap();
+        page.content.blocks = blocks;
+        storage.update_page(&page).unwrap();
+        page
+    }
+
+    #[test]
+    fn moved_blocks_take_their_refs_along() {
+        let (_dir, storage, nb) = setup();
+        let source = page_with(
+            &storage,
+            nb,
+            "Source",
+            vec![
+                paragraph("a", "Keep"),
+                paragraph("b", "Move me"),
+                paragraph("c", "Me too"),
+            ],
+        );
+        let target = page_with(&storage, nb, "Target", vec![paragraph("x", "First")]);
+        let referrer = page_with(
+            &storage,
+            nb,
+            "Referrer",
+            vec![paragraph(
+                "r",
+                &format!(
+                    "See <block-ref data-block-id=\"b\" data-page-id=\"{0}\">Move me</block-ref> \
+                     and <block-ref data-block-id=\"a\" data-page-id=\"{0}\">Keep</block-ref>",
+                    source.id
+                ),
+            )],
+        );
+
+        let edit = move_blocks(
+            &storage,
+            nb,
+            source.id,
+            &["c".to_string(), "b".to_string()],
+            target.id,
+            Some(0),
+        )
+        .unwrap();
+        edit.commit(&storage).unwrap();
+
+        assert_eq!(ids(&storage.get_page(nb, source.id).unwrap()), ["a"]);
+        assert_eq!(
+            ids(&storage.get_page(nb, target.id).unwrap()),
+            ["b", "c", "x"]
+        );
+        let referrer = storage.get_page(nb, referrer.id).unwrap();
+        assert!(text(&referrer, 0).contains(&format!(
+            "data-block-id=\"b\" data-page-id=\"{}\"",
+            target.id
+        )));
+        assert!(text(&referrer, 0).contains(&format!(
+            "data-block-id=\"a\" data-page-id=\"{}\"",
+            source.id
+        )));
+    }
+
+    #[test]
+    fn extracting_leaves_a_link_in_place() {
+        let (_dir, storage, nb) = setup();
+        let source = page_with(
+            &storage,
+            nb,
+            "Source",
+            vec![
+                paragraph("a", "Intro"),
+                paragraph("b", "<b>Design</b> notes"),
+                paragraph("c", "More"),
+                paragraph("d", "Outro"),
+            ],
+        );
+
+        let edit = extract_blocks(
+            &storage,
+            nb,
+            source.id,
+            &["b".to_string(), "c".to_string()],
+            None,
+            true,
+        )
+        .unwrap();
+        edit.commit(&storage).unwrap();
+
+        let created = &edit.created()[0];
+        assert_eq!(created.title, "Design notes");
+        assert_eq!(ids(&storage.get_page(nb, created.id).unwrap()), ["b", "c"]);
+        let source = storage.get_page(nb, source.id).unwrap();
+        assert_eq!(source.content.blocks.len(), 3);
+        assert!(text(&source, 1).contains(&format!("data-page-id=\"{}\"", created.id)));
+        assert_eq!(text(&source, 2), "Outro");
+    }
+
+    #[test]
+    fn merging_moves_content_links_and_subpages() {
+        let (_dir, storage, nb) = setup();
+        let mut source = page_with(&storage, nb, "Old Plan", vec![paragraph("a", "Step one")]);
+        source.tags = vec!["plan".to_string()];
+        storage.update_page(&source).unwrap();
+        let target = page_with(&storage, nb, "Plan", vec![paragraph("x", "Goal")]);
+        let mut child = page_with(&storage, nb, "Child", Vec::new());
+        child.parent_page_id = Some(source.id);
+        storage.update_page(&child).unwrap();
+        let linker = page_with(
+            &storage,
+            nb,
+            "Linker",
+            vec![paragraph(
+                "l",
+                "<wiki-link data-page-title=\"old plan\">Old Plan</wiki-link> <i>and</i> \
+                 <wiki-link data-page-title=\"Other\">Other</wiki-link>",
+            )],
+        );
+
+        let edit = merge_page_into(&storage, nb, source.id, target.id).unwrap();
+        edit.commit(&storage).unwrap();
+
+        let target_after = storage.get_page(nb, target.id).unwrap();
+        assert_eq!(ids(&target_after), ["x", "a"]);
+        assert_eq!(target_after.tags, ["plan"]);
+        assert!(storage
+            .get_page(nb, source.id)
+            .unwrap()
+            .deleted_at
+            .is_some());
+        assert_eq!(
+            storage.get_page(nb, child.id).unwrap().parent_page_id,
+            Some(target.id)
+        );
+        assert_eq!(
+            text(&storage.get_page(nb, linker.id).unwrap(), 0),
+            format!(
+                "<wiki-link data-page-title=\"Plan\" data-page-id=\"{}\">Old Plan</wiki-link> <i>and</i> \
+                 <wiki-link data-page-title=\"Other\">Other</wiki-link>",
+                target.id
+            )
+        );
+
+        assert!(matches!(
+            merge_page_into(&storage, nb, target.id, target.id),
+            Err(BlockOpError::SamePage)
+        ));
+    }
+
+    #[test]
+    fn unknown_blocks_change_nothing() {
+        let (_dir, storage, nb) = setup();
+        let source = page_with(&storage, nb, "Source", vec![paragraph("a", "Text")]);
+        let target = page_with(&storage, nb, "Target", Vec::new());
+        let result = move_blocks(
+            &storage,
+            nb,
+            source.id,
+            &["a".to_string(), "missing".to_string()],
+            target.id,
+            None,
+        );
+        assert!(matches!(result, Err(BlockOpError::BlockNotFound(id)) if id == "missing"));
+        assert_eq!(ids(&storage.get_page(nb, source.id).unwrap()), ["a"]);
+    }
+
+    fn heading(id: &str, text: &str, level: u8) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: "header".to_string(),
+            data: json!({ "text": text, "level": level }),
+        }
+    }
+
+    #[test]
+    fn splitting_by_headings_makes_subpages_and_contents() {
+        let (_dir, storage, nb) = setup();
+        let long = page_with(
+            &storage,
+            nb,
+            "Handbook",
+            vec![
+                paragraph("intro", "Read me first"),
+                heading("h1", "Setup", 1),
+                paragraph("a", "Install it"),
+                heading("h3", "Details", 3),
+                heading("h2", "Usage &amp; tips", 2),
+                paragraph("b", "Run it"),
+            ],
+        );
+        let referrer = page_with(
+            &storage,
+            nb,
+            "Referrer",
+            vec![paragraph(
+                "r",
+                &format!(
+                    "<block-ref data-block-id=\"b\" data-page-id=\"{}\">Run it</block-ref>",
+                    long.id
+                ),
+            )],
+        );
+        let comments = CommentsStorage::new(storage.notebooks_dir().join("notebooks"));
+        comments
+            .add_comment(
+                nb,
+                long.id,
+                crate::comments::NewComment {
+                    block_id: Some("a".to_string()),
+                    parent_id: None,
+                    author: None,
+                    body: "Which version?".to_string(),
+                },
+            )
+            .unwrap();
+
+        let edit = split_page_by_headings(&storage, nb, long.id, 2, TocStyle::Links).unwrap();
+        edit.commit(&storage).unwrap();
+
+        let [setup_page, usage] = edit.created() else {
+            panic!("expected two subpages");
+        };
+        assert_eq!(setup_page.title, "Setup");
+        assert_eq!(usage.title, "Usage & tips");
+        assert_eq!(setup_page.parent_page_id, Some(long.id));
+        // Deeper headings stay in their section
+        assert_eq!(ids(setup_page), ["a", "h3"]);
+        assert_eq!(ids(usage), ["b"]);
+
+        let parent = storage.get_page(nb, long.id).unwrap();
+        assert_eq!(ids(&parent), ["intro", "h1", "h2"]);
+        assert!(text(&parent, 1).contains(&setup_page.id.to_string()));
+
+        let referrer = storage.get_page(nb, referrer.id).unwrap();
+        assert!(text(&referrer, 0).contains(&usage.id.to_string()));
+
+        assert_eq!(
+            comments
+                .list_threads(nb, setup_page.id, true)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(comments.list_threads(nb, long.id, true).unwrap().is_empty());
+
+        let flat = page_with(&storage, nb, "Flat", vec![paragraph("p", "No headings")]);
+        assert!(matches!(
+            split_page_by_headings(&storage, nb, flat.id, 2, TocStyle::Transclusions),
+            Err(BlockOpError::NoHeadings(_))
+        ));
+    }
+}
@@ -0,0 +1,932 @@
+//! Chart blocks
+//!
+//! A `chart` block draws data that lives elsewhere: a table block on the same
+//! page or a CSV file in the notebook's assets. On disk it is
+//!
+//! ```json
+//! { "type": "chart", "data": {
+//!     "title": "Sales by region",
+//!     "source": { "type": "table", "blockId": "..." },
+//!     "spec": { "kind": "bar", "x": "Region", "y": ["Total"],
+//!               "aggregate": "sum", "filters": [], "sort": "valueDesc", "limit": 10 } } }
+//! ```
+//!
+//! with `{ "type": "csv", "asset": "sales.csv" }` as the other kind of
+//! source. Filtering, grouping and sorting happen here, so the editor and
+//! publish/print render the same [`ChartData`]; [`render_svg`] draws it.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::storage::html_utils::html_to_searchable_text;
+use crate::storage::{EditorBlock, Page};
+
+/// Block type of chart blocks
+pub const CHART_BLOCK: &str = "chart";
+
+/// Rows read from a CSV source; the rest are ignored
+pub const MAX_SOURCE_ROWS: usize = 100_000;
+
+#[derive(Error, Debug)]
+pub enum ChartError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Chart source block not found: {0}")]
+    SourceNotFound(String),
+    #[error("Block {0} isn't a table")]
+    NotATable(String),
+    #[error("Invalid asset path: {0}")]
+    InvalidAsset(String),
+    #[error("No column named {0:?}")]
+    ColumnNotFound(String),
+    #[error("Invalid chart: {0}")]
+    InvalidSpec(String),
+}
+
+pub type Result<T> = std::result::Result<T, ChartError>;
+
+/// Where a chart's rows come from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ChartSource {
+    /// A table block on the chart's page
+    Table {
+        #[serde(rename = "blockId")]
+        block_id: String,
+    },
+    /// A CSV file, relative to the notebook's assets directory
+    Csv { asset: String },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartKind {
+    #[default]
+    Bar,
+    Line,
+    Area,
+    Pie,
+    Scatter,
+}
+
+/// How rows sharing an x value are combined. `None` plots every row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregate {
+    #[default]
+    None,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChartSort {
+    /// By x value
+    X,
+    /// By the first series, smallest first
+    ValueAsc,
+    /// By the first series, largest first
+    ValueDesc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+}
+
+/// Keep rows whose `column` compares to `value`; numbers compare as numbers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartFilter {
+    pub column: String,
+    pub op: FilterOp,
+    pub value: String,
+}
+
+/// What to draw from the source's rows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChartSpec {
+    pub kind: ChartKind,
+    /// Column along the x axis (the slices of a pie)
+    pub x: String,
+    /// Columns plotted as series; unused when counting
+    pub y: Vec<String>,
+    pub aggregate: Aggregate,
+    pub filters: Vec<ChartFilter>,
+    pub sort: Option<ChartSort>,
+    /// Points kept after sorting
+    pub limit: Option<usize>,
+}
+
+/// Rows of a source, every cell as text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Series {
+    pub name: String,
+    /// One value per label; `None` where a cell isn't a number
+    pub values: Vec<Option<f64>>,
+}
+
+/// A chart ready to draw
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartData {
+    pub kind: ChartKind,
+    /// Every column of the source, for the editor's pickers
+    pub columns: Vec<String>,
+    pub x: String,
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+    /// Source rows left after filtering
+    pub row_count: usize,
+}
+
+/// The source and spec stored on a chart block
+pub fn block_chart(block: &EditorBlock) -> Result<(ChartSource, ChartSpec)> {
+    let source = block
+        .data
+        .get("source")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .ok_or_else(|| ChartError::InvalidSpec("no data source".to_string()))?;
+    let spec = block
+        .data
+        .get("spec")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| ChartError::InvalidSpec(e.to_string()))?
+        .unwrap_or_default();
+    Ok((source, spec))
+}
+
+/// Load a chart's rows. Table sources are looked up on `page`; CSV sources
+/// are read from `assets_dir` and can't point outside it.
+pub fn load_source(page: &Page, source: &ChartSource, assets_dir: &Path) -> Result<Table> {
+    match source {
+        ChartSource::Table { block_id } => {
+            let block = page
+                .content
+                .blocks
+                .iter()
+                .find(|b| &b.id == block_id)
+                .ok_or_else(|| ChartError::SourceNotFound(block_id.clone()))?;
+            table_from_block(block)
+        }
+        ChartSource::Csv { asset } => table_from_csv(&asset_path(asset, assets_dir)?),
+    }
+}
+
+fn asset_path(asset: &str, assets_dir: &Path) -> Result<PathBuf> {
+    let relative = Path::new(asset.trim().trim_start_matches("assets/"));
+    let inside = relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !inside || relative.as_os_str().is_empty() {
+        return Err(ChartError::InvalidAsset(asset.to_string()));
+    }
+    Ok(assets_dir.join(relative))
+}
+
+/// The rows of a table block. Without a heading row the columns are named
+/// "Column 1", "Column 2", ...
+pub fn table_from_block(block: &EditorBlock) -> Result<Table> {
+    if block.block_type != "table" {
+        return Err(ChartError::NotATable(block.id.clone()));
+    }
+    let mut rows: Vec<Vec<String>> = block
+        .data
+        .get("content")
+        .and_then(|v| v.as_array())
+        .map(|rows| {
+            rows.iter()
+                .map(|row| {
+                    row.as_array()
+                        .map(|cells| {
+                            cells
+                                .iter()
+                                .map(|c| html_to_searchable_text(c.as_str().unwrap_or_default()))
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let with_headings = block
+        .data
+        .get("withHeadings")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let columns = if with_headings && !rows.is_empty() {
+        rows.remove(0)
+    } else {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        (1..=width).map(|i| format!("Column {}", i)).collect()
+    };
+    Ok(Table { columns, rows })
+}
+
+/// The rows of a CSV file with a header row (tab-separated for `.tsv`)
+pub fn table_from_csv(path: &Path) -> Result<Table> {
+    let tsv = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("tsv"));
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(if tsv { b'\t' } else { b',' })
+        .flexible(true)
+        .from_path(path)?;
+    let columns = reader
+        .headers()?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    let mut rows = Vec::new();
+    for record in reader.records().take(MAX_SOURCE_ROWS) {
+        rows.push(record?.iter().map(str::to_string).collect());
+    }
+    Ok(Table { columns, rows })
+}
+
+/// A cell as a number: thousands separators, a leading currency sign and a
+/// trailing percent sign are ignored
+pub fn parse_number(cell: &str) -> Option<f64> {
+    let text = cell.trim();
+    let text = text.strip_suffix('%').unwrap_or(text);
+    let text = text.trim_start_matches(['$', '€', '£', '¥']);
+    let text: String = text.chars().filter(|c| *c != ',').collect();
+    text.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+fn column_index(table: &Table, name: &str) -> Result<usize> {
+    table
+        .columns
+        .iter()
+        .position(|c| c == name)
+        .or_else(|| {
+            table
+                .columns
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| ChartError::ColumnNotFound(name.to_string()))
+}
+
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (parse_number(a), parse_number(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+fn matches_filter(cell: &str, filter: &ChartFilter) -> bool {
+    let ordering = compare_cells(cell.trim(), filter.value.trim());
+    // Against a number, text is neither greater nor smaller
+    let ordered = parse_number(&filter.value).is_none() || parse_number(cell).is_some();
+    match filter.op {
+        FilterOp::Gt | FilterOp::Gte | FilterOp::Lt | FilterOp::Lte if !ordered => false,
+        FilterOp::Eq => ordering == Ordering::Equal,
+        FilterOp::Ne => ordering != Ordering::Equal,
+        FilterOp::Gt => ordering == Ordering::Greater,
+        FilterOp::Gte => ordering != Ordering::Less,
+        FilterOp::Lt => ordering == Ordering::Less,
+        FilterOp::Lte => ordering != Ordering::Greater,
+        FilterOp::Contains => cell
+            .to_lowercase()
+            .contains(&filter.value.trim().to_lowercase()),
+    }
+}
+
+fn aggregate(values: &[f64], how: Aggregate) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(match how {
+        Aggregate::Sum => values.iter().sum(),
+        Aggregate::Avg => values.iter().sum::<f64>() / values.len() as f64,
+        Aggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        Aggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        Aggregate::None | Aggregate::Count => values.len() as f64,
+    })
+}
+
+/// Filter, group, sort and cut the table's rows down to what the spec
+/// draws. A spec without its columns picked yet gives no series, but still
+/// the source's columns to pick from.
+pub fn compute(table: &Table, spec: &ChartSpec) -> Result<ChartData> {
+    if spec.x.trim().is_empty() || (spec.y.is_empty() && spec.aggregate != Aggregate::Count) {
+        return Ok(ChartData {
+            kind: spec.kind,
+            columns: table.columns.clone(),
+            x: spec.x.clone(),
+            labels: Vec::new(),
+            series: Vec::new(),
+            row_count: table.rows.len(),
+        });
+    }
+    let x = column_index(table, &spec.x)?;
+    let filters = spec
+        .filters
+        .iter()
+        .map(|f| Ok((column_index(table, &f.column)?, f)))
+        .collect::<Result<Vec<_>>>()?;
+    fn cell(row: &[String], i: usize) -> &str {
+        row.get(i).map(String::as_str).unwrap_or_default()
+    }
+    let rows: Vec<&Vec<String>> = table
+        .rows
+        .iter()
+        .filter(|row| {
+            filters
+                .iter()
+                .all(|(i, f)| matches_filter(cell(row, *i), f))
+        })
+        .collect();
+
+    let (names, columns): (Vec<String>, Vec<usize>) = if spec.aggregate == Aggregate::Count {
+        (vec!["Count".to_string()], vec![x])
+    } else {
+        let columns = spec
+            .y
+            .iter()
+            .map(|y| column_index(table, y))
+            .collect::<Result<Vec<_>>>()?;
+        (
+            columns.iter().map(|&i| table.columns[i].clone()).collect(),
+            columns,
+        )
+    };
+
+    let mut points: Vec<(String, Vec<Option<f64>>)> = if spec.aggregate == Aggregate::None {
+        rows.iter()
+            .map(|row| {
+                let values = columns
+                    .iter()
+                    .map(|&i| parse_number(cell(row, i)))
+                    .collect();
+                (cell(row, x).to_string(), values)
+            })
+            .collect()
+    } else {
+        // Groups in the order their x value first appears
+        let mut groups: Vec<(String, Vec<Vec<f64>>)> = Vec::new();
+        for row in &rows {
+            let label = cell(row, x);
+            let index = match groups.iter().position(|(l, _)| l == label) {
+                Some(index) => index,
+                None => {
+                    groups.push((label.to_string(), vec![Vec::new(); columns.len()]));
+                    groups.len() - 1
+                }
+            };
+            for (values, &i) in groups[index].1.iter_mut().zip(&columns) {
+                if spec.aggregate == Aggregate::Count {
+                    values.push(1.0);
+                } else if let Some(n) = parse_number(cell(row, i)) {
+                    values.push(n);
+                }
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(label, values)| {
+                let values = values
+                    .iter()
+                    .map(|v| aggregate(v, spec.aggregate))
+                    .collect();
+                (label, values)
+            })
+            .collect()
+    };
+
+    match spec.sort {
+        Some(ChartSort::X) => points.sort_by(|a, b| compare_cells(&a.0, &b.0)),
+        Some(order @ (ChartSort::ValueAsc | ChartSort::ValueDesc)) => {
+            points.sort_by(|a, b| {
+                // Points without a value go last either way
+                match (
+                    a.1.first().copied().flatten(),
+                    b.1.first().copied().flatten(),
+                ) {
+                    (Some(a), Some(b)) if order == ChartSort::ValueAsc => {
+                        a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+                    }
+                    (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            })
+        }
+        None => {}
+    }
+    if let Some(limit) = spec.limit {
+        points.truncate(limit);
+    }
+
+    let labels = points.iter().map(|(l, _)| l.clone()).collect();
+    let series = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| Series {
+            name,
+            values: points.iter().map(|(_, v)| v[i]).collect(),
+        })
+        .collect();
+    Ok(ChartData {
+        kind: spec.kind,
+        columns: table.columns.clone(),
+        x: table.columns[x].clone(),
+        labels,
+        series,
+        row_count: rows.len(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// SVG
+// ---------------------------------------------------------------------------
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 360.0;
+const LEFT: f64 = 56.0;
+const RIGHT: f64 = 16.0;
+const TOP: f64 = 16.0;
+const BOTTOM: f64 = 48.0;
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn short_label(text: &str) -> String {
+    if text.chars().count() > 14 {
+        format!("{}…", text.chars().take(13).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+fn format_tick(value: f64) -> String {
+    let abs = value.abs();
+    if abs >= 1e6 {
+        format!("{}M", (value / 1e5).round() / 10.0)
+    } else if abs >= 1e4 {
+        format!("{}k", (value / 1e2).round() / 10.0)
+    } else if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.2}", value)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+/// A "nice" axis range covering `min..max` with about five ticks
+fn axis(min: f64, max: f64) -> (f64, f64, f64) {
+    let (min, max) = if min == max {
+        (min.min(0.0), max.max(min + 1.0))
+    } else {
+        (min, max)
+    };
+    let raw = (max - min) / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 2.5, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|s| *s >= raw)
+        .unwrap_or(raw);
+    (
+        (min / step).floor() * step,
+        (max / step).ceil() * step,
+        step,
+    )
+}
+
+fn open_svg(title: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {w} {h}\" width=\"100%\" \
+         role=\"img\" aria-label=\"{t}\" font-family=\"sans-serif\" font-size=\"11\">\n\
+         <title>{t}</title>\n",
+        w = WIDTH,
+        h = HEIGHT,
+        t = escape(title),
+    )
+}
+
+fn legend(svg: &mut String, names: &[&str]) {
+    if names.len() < 2 {
+        return;
+    }
+    let mut x = LEFT;
+    for (i, name) in names.iter().enumerate() {
+        svg.push_str(&format!(
+            "<rect x=\"{x}\" y=\"{y}\" width=\"10\" height=\"10\" fill=\"{c}\"/>\
+             <text x=\"{tx}\" y=\"{ty}\" fill=\"currentColor\">{n}</text>\n",
+            x = x,
+            y = HEIGHT - 14.0,
+            c = PALETTE[i % PALETTE.len()],
+            tx = x + 14.0,
+            ty = HEIGHT - 5.0,
+            n = escape(name),
+        ));
+        x += 24.0 + 7.0 * name.chars().count().min(20) as f64;
+    }
+}
+
+/// Draw a chart as a standalone SVG element. It uses `currentColor` for
+/// text and axes so it follows the surrounding theme.
+pub fn render_svg(data: &ChartData, title: &str) -> String {
+    let mut svg = open_svg(if title.is_empty() { &data.x } else { title });
+    if data.labels.is_empty() || data.series.is_empty() {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"currentColor\">No data</text>\n</svg>",
+            WIDTH / 2.0,
+            HEIGHT / 2.0
+        ));
+        return svg;
+    }
+    if data.kind == ChartKind::Pie {
+        render_pie(&mut svg, data);
+    } else {
+        render_axes_chart(&mut svg, data);
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn render_pie(svg: &mut String, data: &ChartData) {
+    let values: Vec<f64> = data.series[0]
+        .values
+        .iter()
+        .map(|v| v.unwrap_or(0.0).max(0.0))
+        .collect();
+    let total: f64 = values.iter().sum();
+    if total <= 0.0 {
+        return;
+    }
+    let (cx, cy, r) = (WIDTH / 3.0, HEIGHT / 2.0, HEIGHT / 2.0 - TOP);
+    let mut angle = -std::f64::consts::FRAC_PI_2;
+    for (i, (label, value)) in data.labels.iter().zip(&values).enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        let sweep = value / total * std::f64::consts::TAU;
+        if sweep >= std::f64::consts::TAU - 1e-9 {
+            svg.push_str(&format!(
+                "<circle cx=\"{cx:.1}\" cy=\"{cy:.1}\" r=\"{r:.1}\" fill=\"{color}\"/>\n"
+            ));
+        } else if sweep > 0.0 {
+            let (x1, y1) = (cx + r * angle.cos(), cy + r * angle.sin());
+            let end = angle + sweep;
+            let (x2, y2) = (cx + r * end.cos(), cy + r * end.sin());
+            let large = if sweep > std::f64::consts::PI { 1 } else { 0 };
+            svg.push_str(&format!(
+                "<path d=\"M{cx:.1},{cy:.1} L{x1:.1},{y1:.1} A{r:.1},{r:.1} 0 {large} 1 {x2:.1},{y2:.1} Z\" fill=\"{color}\"><title>{}: {}</title></path>\n",
+                escape(label),
+                format_tick(*value),
+            ));
+        }
+        angle += sweep;
+        // Legend down the right-hand side
+        let ly = TOP + 16.0 * i as f64;
+        if ly < HEIGHT - 16.0 {
+            svg.push_str(&format!(
+                "<rect x=\"{lx:.1}\" y=\"{ly:.1}\" width=\"10\" height=\"10\" fill=\"{color}\"/>\
+                 <text x=\"{tx:.1}\" y=\"{ty:.1}\" fill=\"currentColor\">{} ({:.0}%)</text>\n",
+                escape(&short_label(label)),
+                value / total * 100.0,
+                lx = WIDTH * 2.0 / 3.0 - 40.0,
+                tx = WIDTH * 2.0 / 3.0 - 26.0,
+                ty = ly + 9.0,
+            ));
+        }
+    }
+}
+
+fn render_axes_chart(svg: &mut String, data: &ChartData) {
+    let values = data.series.iter().flat_map(|s| s.values.iter().flatten());
+    let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+    for &v in values {
+        min = min.min(v);
+        max = max.max(v);
+    }
+    if !min.is_finite() {
+        (min, max) = (0.0, 1.0);
+    }
+    // Bars and areas grow from zero
+    if data.kind != ChartKind::Scatter && data.kind != ChartKind::Line {
+        min = min.min(0.0);
+        max = max.max(0.0);
+    }
+    let (lo, hi, step) = axis(min, max);
+    let plot_w = WIDTH - LEFT - RIGHT;
+    let plot_h = HEIGHT - TOP - BOTTOM;
+    let y_of = |v: f64| TOP + plot_h * (1.0 - (v - lo) / (hi - lo));
+
+    // Grid and y ticks
+    let mut tick = lo;
+    while tick <= hi + step / 2.0 {
+        let y = y_of(tick);
+        svg.push_str(&format!(
+            "<line x1=\"{LEFT}\" y1=\"{y:.1}\" x2=\"{x2}\" y2=\"{y:.1}\" stroke=\"currentColor\" stroke-opacity=\"0.15\"/>\
+             <text x=\"{tx}\" y=\"{ty:.1}\" text-anchor=\"end\" fill=\"currentColor\">{}</text>\n",
+            format_tick(tick),
+            x2 = WIDTH - RIGHT,
+            tx = LEFT - 6.0,
+            ty = y + 4.0,
+        ));
+        tick += step;
+    }
+
+    // Scatter plots place points by their numeric x; the others space the
+    // labels evenly
+    let numeric_x: Option<Vec<f64>> = if data.kind == ChartKind::Scatter {
+        data.labels.iter().map(|l| parse_number(l)).collect()
+    } else {
+        None
+    };
+    let n = data.labels.len();
+    let band = plot_w / n as f64;
+    let x_of: Box<dyn Fn(usize) -> f64> = match &numeric_x {
+        Some(xs) => {
+            let (xmin, xmax) = xs
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &x| {
+                    (a.min(x), b.max(x))
+                });
+            let (xlo, xhi, _) = axis(xmin, xmax);
+            let xs = xs.clone();
+            Box::new(move |i| LEFT + plot_w * (xs[i] - xlo) / (xhi - xlo))
+        }
+        None => Box::new(move |i| LEFT + band * (i as f64 + 0.5)),
+    };
+
+    // X labels, thinned out to fit
+    let every = ((n as f64 * 70.0) / plot_w).ceil().max(1.0) as usize;
+    for (i, label) in data.labels.iter().enumerate().step_by(every) {
+        svg.push_str(&format!(
+            "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"middle\" fill=\"currentColor\">{}</text>\n",
+            escape(&short_label(label)),
+            x = x_of(i),
+            y = HEIGHT - BOTTOM + 16.0,
+        ));
+    }
+    svg.push_str(&format!(
+        "<line x1=\"{LEFT}\" y1=\"{y:.1}\" x2=\"{x2}\" y2=\"{y:.1}\" stroke=\"currentColor\" stroke-opacity=\"0.5\"/>\n",
+        y = y_of(lo.max(0.0).min(hi)),
+        x2 = WIDTH - RIGHT,
+    ));
+
+    let count = data.series.len() as f64;
+    for (s, series) in data.series.iter().enumerate() {
+        let color = PALETTE[s % PALETTE.len()];
+        match data.kind {
+            ChartKind::Bar => {
+                let width = band * 0.8 / count;
+                let base = y_of(0f64.max(lo));
+                for (i, value) in series.values.iter().enumerate() {
+                    let Some(value) = value else { continue };
+                    let x = LEFT + band * i as f64 + band * 0.1 + width * s as f64;
+                    let y = y_of(*value);
+                    svg.push_str(&format!(
+                        "<rect x=\"{x:.1}\" y=\"{:.1}\" width=\"{width:.1}\" height=\"{:.1}\" fill=\"{color}\"><title>{}: {}</title></rect>\n",
+                        y.min(base),
+                        (base - y).abs(),
+                        escape(&data.labels[i]),
+                        format_tick(*value),
+                    ));
+                }
+            }
+            ChartKind::Line | ChartKind::Area => {
+                let points: Vec<(f64, f64)> = series
+                    .values
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, v)| v.map(|v| (x_of(i), y_of(v))))
+                    .collect();
+                let path: Vec<String> = points
+                    .iter()
+                    .map(|(x, y)| format!("{x:.1},{y:.1}"))
+                    .collect();
+                if data.kind == ChartKind::Area && points.len() > 1 {
+                    let base = y_of(0f64.max(lo));
+                    svg.push_str(&format!(
+                        "<polygon points=\"{:.1},{base:.1} {} {:.1},{base:.1}\" fill=\"{color}\" fill-opacity=\"0.3\"/>\n",
+                        points[0].0,
+                        path.join(" "),
+                        points[points.len() - 1].0,
+                    ));
+                }
+                svg.push_str(&format!(
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\"/>\n",
+                    path.join(" ")
+                ));
+            }
+            ChartKind::Scatter => {
+                for (i, value) in series.values.iter().enumerate() {
+                    let Some(value) = value else { continue };
+                    svg.push_str(&format!(
+                        "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3.5\" fill=\"{color}\"><title>{}: {}</title></circle>\n",
+                        x_of(i),
+                        y_of(*value),
+                        escape(&data.labels[i]),
+                        format_tick(*value),
+                    ));
+                }
+            }
+            ChartKind::Pie => unreachable!("pies are drawn by render_pie"),
+        }
+    }
+    let names: Vec<&str> = data.series.iter().map(|s| s.name.as_str()).collect();
+    legend(svg, &names);
+}
+
+/// `page` with each chart block's SVG (or the reason it can't be drawn)
+/// stored on the block as `svg` / `chartError`, for publish and print.
+/// Pages without charts are returned as they are.
+pub fn with_rendered_charts<'a>(page: &'a Page, assets_dir: &Path) -> Cow<'a, Page> {
+    if !page
+        .content
+        .blocks
+        .iter()
+        .any(|b| b.block_type == CHART_BLOCK)
+    {
+        return Cow::Borrowed(page);
+    }
+    let mut rendered = page.clone();
+    for block in rendered
+        .content
+        .blocks
+        .iter_mut()
+        .filter(|b| b.block_type == CHART_BLOCK)
+    {
+        let title = block
+            .data
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let result = block_chart(block).and_then(|(source, spec)| {
+            let table = load_source(page, &source, assets_dir)?;
+            compute(&table, &spec)
+        });
+        if let Some(data) = block.data.as_object_mut() {
+            match result {
+                Ok(chart) => data.insert("svg".to_string(), json!(render_svg(&chart, &title))),
+                Err(e) => data.insert("chartError".to_string(), Value::String(e.to_string())),
+            };
+        }
+    }
+    Cow::Owned(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect();
+        Table {
+            columns: vec!["Region".into(), "Month".into(), "Sales".into()],
+            rows: vec![
+                row(&["North", "Jan", "1,200"]),
+                row(&["South", "Jan", "$800"]),
+                row(&["North", "Feb", "1000"]),
+                row(&["East", "Feb", "n/a"]),
+            ],
+        }
+    }
+
+    fn spec(aggregate: Aggregate) -> ChartSpec {
+        ChartSpec {
+            x: "Region".into(),
+            y: vec!["Sales".into()],
+            aggregate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_and_sorts_rows() {
+        let mut spec = spec(Aggregate::Sum);
+        spec.sort = Some(ChartSort::ValueDesc);
+        let data = compute(&table(), &spec).unwrap();
+        assert_eq!(data.labels, ["North", "South", "East"]);
+        assert_eq!(data.series[0].values, [Some(2200.0), Some(800.0), None]);
+
+        spec.aggregate = Aggregate::Count;
+        spec.sort = Some(ChartSort::X);
+        spec.limit = Some(2);
+        let data = compute(&table(), &spec).unwrap();
+        assert_eq!(data.labels, ["East", "North"]);
+        assert_eq!(data.series[0].name, "Count");
+        assert_eq!(data.series[0].values, [Some(1.0), Some(2.0)]);
+
+        // Columns to pick from before any are picked
+        let data = compute(&table(), &ChartSpec::default()).unwrap();
+        assert_eq!(data.columns, ["Region", "Month", "Sales"]);
+        assert!(data.series.is_empty());
+    }
+
+    #[test]
+    fn filters_compare_numbers_as_numbers() {
+        let mut spec = spec(Aggregate::None);
+        spec.filters = vec![ChartFilter {
+            column: "sales".into(),
+            op: FilterOp::Gt,
+            value: "900".into(),
+        }];
+        let data = compute(&table(), &spec).unwrap();
+        assert_eq!(data.labels, ["North", "North"]);
+        assert_eq!(data.row_count, 2);
+
+        spec.filters[0].column = "Profit".into();
+        assert!(matches!(
+            compute(&table(), &spec),
+            Err(ChartError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn table_blocks_and_assets() {
+        let block = EditorBlock {
+            id: "t".into(),
+            block_type: "table".into(),
+            data: json!({ "withHeadings": true, "content": [["<b>Name</b>", "Score"], ["Ann", "3"]] }),
+        };
+        let table = table_from_block(&block).unwrap();
+        assert_eq!(table.columns, ["Name", "Score"]);
+        assert_eq!(table.rows, [["Ann", "3"]]);
+
+        let assets = Path::new("/notebook/assets");
+        assert_eq!(
+            asset_path("assets/data/sales.csv", assets).unwrap(),
+            assets.join("data/sales.csv")
+        );
+        assert!(asset_path("../secrets.csv", assets).is_err());
+        assert!(asset_path("/etc/passwd", assets).is_err());
+    }
+
+    #[test]
+    fn renders_chart_blocks_for_publishing() {
+        let mut page = Page::new(uuid::Uuid::new_v4(), "Charts".to_string());
+        page.content.blocks = vec![
+            EditorBlock {
+                id: "t".into(),
+                block_type: "table".into(),
+                data: json!({ "withHeadings": true, "content": [["Fruit", "Count"], ["Apple", "4"], ["Pear", "2"]] }),
+            },
+            EditorBlock {
+                id: "c".into(),
+                block_type: CHART_BLOCK.into(),
+                data: json!({
+                    "title": "Fruit & veg",
+                    "source": { "type": "table", "blockId": "t" },
+                    "spec": { "kind": "bar", "x": "Fruit", "y": ["Count"] },
+                }),
+            },
+            EditorBlock {
+                id: "d".into(),
+                block_type: CHART_BLOCK.into(),
+                data: json!({ "source": { "type": "table", "blockId": "missing" } }),
+            },
+        ];
+        let rendered = with_rendered_charts(&page, Path::new("/nowhere"));
+        let svg = rendered.content.blocks[1].data["svg"].as_str().unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Fruit &amp; veg"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(rendered.content.blocks[2].data["chartError"]
+            .as_str()
+            .unwrap()
+            .contains("missing"));
+    }
+}
@@ -0,0 +1,210 @@
+//! Simplified-HTML to markdown conversion for clipped content.
+//!
+//! Browser extensions send readability-style "simplified" HTML (article
+//! body or the current selection), not whole documents, so a regex pass
+//! over the common tags is enough. The markdown then feeds the inbox (as
+//! text) or `parse_markdown_to_blocks` (as a page).
+
+use regex::{Captures, Regex};
+
+fn decode(text: &str) -> String {
+    html_escape::decode_html_entities(text).into_owned()
+}
+
+fn strip_tags(text: &str) -> String {
+    let tag_re = Regex::new(r"<[^>]+>").unwrap();
+    tag_re.replace_all(text, "").to_string()
+}
+
+fn attr(tag: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(r#"(?i)\b{}\s*=\s*["']([^"']*)["']"#, name)).ok()?;
+    re.captures(tag).map(|c| decode(&c[1]))
+}
+
+/// Convert simplified HTML (article body or selection) to markdown.
+pub fn simplified_html_to_markdown(html: &str) -> String {
+    let mut text = html.to_string();
+
+    for pattern in [
+        r"(?is)<script[^>]*>.*?</script>",
+        r"(?is)<style[^>]*>.*?</style>",
+        r"(?is)<nav[^>]*>.*?</nav>",
+        r"(?s)<!--.*?-->",
+    ] {
+        text = Regex::new(pattern)
+            .unwrap()
+            .replace_all(&text, "")
+            .to_string();
+    }
+
+    // Pull code blocks out first so later passes don't touch their contents
+    let mut code_blocks: Vec<String> = Vec::new();
+    let pre_re = Regex::new(r"(?is)<pre[^>]*>(.*?)</pre>").unwrap();
+    text = pre_re
+        .replace_all(&text, |caps: &Captures| {
+            code_blocks.push(decode(&strip_tags(&caps[1])).trim_end().to_string());
+            format!("\n\n\u{0}CODE{}\u{0}\n\n", code_blocks.len() - 1)
+        })
+        .to_string();
+
+    // Source line breaks are insignificant in HTML; all structure below
+    // comes from tags
+    let ws_re = Regex::new(r"\s+").unwrap();
+    text = ws_re.replace_all(&text, " ").to_string();
+
+    let img_re = Regex::new(r"(?is)<img\b[^>]*>").unwrap();
+    text = img_re
+        .replace_all(&text, |caps: &Captures| match attr(&caps[0], "src") {
+            Some(src) => format!("![{}]({})", attr(&caps[0], "alt").unwrap_or_default(), src),
+            None => String::new(),
+        })
+        .to_string();
+
+    let link_re = Regex::new(r"(?is)<a\b([^>]*)>(.*?)</a>").unwrap();
+    text = link_re
+        .replace_all(&text, |caps: &Captures| {
+            let label = caps[2].trim().to_string();
+            match attr(&caps[1], "href") {
+                Some(href) if !href.starts_with('#') && !href.starts_with("javascript:") => {
+                    format!("[{}]({})", label, href)
+                }
+                _ => label,
+            }
+        })
+        .to_string();
+
+    for (pattern, wrap) in [
+        (r"(?is)<(?:strong|b)\b[^>]*>(.*?)</(?:strong|b)>", "**"),
+        (r"(?is)<(?:em|i)\b[^>]*>(.*?)</(?:em|i)>", "*"),
+        (r"(?is)<code\b[^>]*>(.*?)</code>", "`"),
+    ] {
+        let re = Regex::new(pattern).unwrap();
+        text = re
+            .replace_all(&text, |caps: &Captures| {
+                let inner = caps[1].trim();
+                if inner.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}{}", wrap, inner, wrap)
+                }
+            })
+            .to_string();
+    }
+
+    let heading_re = Regex::new(r"(?is)<h([1-6])\b[^>]*>(.*?)</h[1-6]>").unwrap();
+    text = heading_re
+        .replace_all(&text, |caps: &Captures| {
+            let level: usize = caps[1].parse().unwrap_or(2);
+            let title = strip_tags(&caps[2])
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("\n\n{} {}\n\n", "#".repeat(level), title)
+        })
+        .to_string();
+
+    // Ordered lists keep "1." on every item; markdown renumbers them
+    let ol_re = Regex::new(r"(?is)<ol\b[^>]*>(.*?)</ol>").unwrap();
+    let li_re = Regex::new(r"(?i)<li\b[^>]*>").unwrap();
+    text = ol_re
+        .replace_all(&text, |caps: &Captures| {
+            format!("\n{}\n", li_re.replace_all(&caps[1], "\n1. "))
+        })
+        .to_string();
+    text = li_re.replace_all(&text, "\n- ").to_string();
+
+    let quote_re = Regex::new(r"(?is)<blockquote\b[^>]*>(.*?)</blockquote>").unwrap();
+    text = quote_re
+        .replace_all(&text, |caps: &Captures| {
+            let block_re = Regex::new(r"(?i)</?(p|div|br)[^>]*>").unwrap();
+            let inner = strip_tags(&block_re.replace_all(&caps[1], "\n"));
+            let quoted: Vec<String> = inner
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(|l| format!("> {}", l))
+                .collect();
+            format!("\n\n{}\n\n", quoted.join("\n"))
+        })
+        .to_string();
+
+    let br_re = Regex::new(r"(?i)<br\s*/?>").unwrap();
+    text = br_re.replace_all(&text, "\n").to_string();
+    let hr_re = Regex::new(r"(?i)<hr\b[^>]*>").unwrap();
+    text = hr_re.replace_all(&text, "\n\n---\n\n").to_string();
+    let block_re = Regex::new(
+        r"(?i)</?(p|div|ul|ol|li|section|article|header|footer|main|aside|figure|figcaption|table|tr)\b[^>]*>",
+    )
+    .unwrap();
+    text = block_re.replace_all(&text, "\n\n").to_string();
+
+    text = decode(&strip_tags(&text));
+
+    // Normalize whitespace line by line; list items may have been split
+    // from their text by the block pass above
+    let space_re = Regex::new(r"[ \t\u{a0}]+").unwrap();
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = space_re.replace_all(line, " ").trim().to_string();
+        if let Some(prev) = lines.last_mut() {
+            if (prev == "-" || prev == "1.") && !line.is_empty() {
+                *prev = format!("{} {}", prev, line);
+                continue;
+            }
+        }
+        if line.is_empty() && lines.last().is_some_and(|l| l == "-" || l == "1.") {
+            continue;
+        }
+        lines.push(line);
+    }
+    let mut text = lines.join("\n");
+    let newline_re = Regex::new(r"\n{3,}").unwrap();
+    text = newline_re.replace_all(&text, "\n\n").to_string();
+
+    for (i, code) in code_blocks.iter().enumerate() {
+        text = text.replace(
+            &format!("\u{0}CODE{}\u{0}", i),
+            &format!("```\n{}\n```", code),
+        );
+    }
+
+    // List items separated only by blank lines from the block pass read
+    // as loose lists; tighten consecutive items
+    let loose_list_re = Regex::new(r"(?m)^((?:-|1\.) .*)\n\n(-|1\.) ").unwrap();
+    while loose_list_re.is_match(&text) {
+        text = loose_list_re.replace_all(&text, "$1\n$2 ").to_string();
+    }
+
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_common_article_markup() {
+        let html = r#"
+            <h1>Title &amp; more</h1>
+            <p>Some <strong>bold</strong> and <em>italic</em> with a
+               <a href="https://example.com/x">link</a>.</p>
+            <ul><li>one</li><li>two</li></ul>
+            <ol><li>first</li><li>second</li></ol>
+            <blockquote><p>Quoted</p></blockquote>
+            <pre><code>let x = 1 &lt; 2;
+    indented</code></pre>
+            <img src="https://example.com/a.png" alt="diagram">
+            <script>alert(1)</script>
+        "#;
+        let md = simplified_html_to_markdown(html);
+
+        assert!(md.starts_with("# Title & more"));
+        assert!(md.contains("Some **bold** and *italic* with a [link](https://example.com/x)."));
+        assert!(md.contains("- one\n- two"));
+        assert!(md.contains("1. first\n1. second"));
+        assert!(md.contains("> Quoted"));
+        assert!(md.contains("```\nlet x = 1 < 2;\n    indented\n```"));
+        assert!(md.contains("![diagram](https://example.com/a.png)"));
+        assert!(!md.contains("alert"));
+    }
+}
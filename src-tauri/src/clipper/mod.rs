@@ -0,0 +1,15 @@
+//! Browser-extension web clipper
+//!
+//! Provides:
+//! - Pairing of browser extensions via a short-lived code, exchanged for a
+//!   clip-only token (separate from daemon API keys)
+//! - A registry of clipped URLs for duplicate detection
+//! - Conversion of the extension's simplified HTML to markdown
+
+mod html;
+mod models;
+mod storage;
+
+pub use html::*;
+pub use models::*;
+pub use storage::*;
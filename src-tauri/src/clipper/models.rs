@@ -0,0 +1,79 @@
+//! Web clipper data models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A paired browser extension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipperClient {
+    pub id: Uuid,
+    /// Name the extension reported when pairing (e.g. "Firefox on laptop")
+    pub name: String,
+    /// SHA-256 of the client token; the token itself is never stored
+    pub token_hash: String,
+    pub paired_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Client details safe to return over the API (no token hash)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipperClientInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub paired_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ClipperClient> for ClipperClientInfo {
+    fn from(client: &ClipperClient) -> Self {
+        Self {
+            id: client.id,
+            name: client.name.clone(),
+            paired_at: client.paired_at,
+            last_used_at: client.last_used_at,
+        }
+    }
+}
+
+/// A pending pairing code shown to the user in Nous
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingCode {
+    pub code: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Result of a successful pairing: the token is returned exactly once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingResult {
+    pub client: ClipperClientInfo,
+    pub token: String,
+}
+
+/// Where a clip ended up
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ClipTarget {
+    /// Captured to the inbox for classification
+    #[serde(rename_all = "camelCase")]
+    Inbox { item_id: Uuid },
+    /// Saved directly as a page
+    #[serde(rename_all = "camelCase")]
+    Page { notebook_id: Uuid, page_id: Uuid },
+}
+
+/// A clipped URL, keyed by its normalized form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipRecord {
+    pub normalized_url: String,
+    pub url: String,
+    pub title: String,
+    pub target: ClipTarget,
+    pub client_id: Option<Uuid>,
+    pub clipped_at: DateTime<Utc>,
+}
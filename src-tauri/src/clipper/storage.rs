@@ -0,0 +1,351 @@
+//! Web clipper storage: paired clients and the clipped-URL registry
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::{atomic, StorageError};
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// How long a pairing code stays valid
+const PAIRING_CODE_TTL_MINUTES: i64 = 5;
+
+/// Wrong guesses allowed before the pending code is discarded
+const MAX_PAIRING_ATTEMPTS: u32 = 5;
+
+/// Prefix on clipper tokens so they're recognisable in logs and can never
+/// be confused with `rw:`/`ro:` daemon API keys
+const TOKEN_PREFIX: &str = "clip_";
+
+/// Query parameters that only track the referrer, dropped before
+/// comparing URLs
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "ref", "ref_src", "igshid",
+];
+
+/// Normalize a URL for duplicate detection: ignore scheme (http/https),
+/// a leading `www.`, the fragment, tracking parameters and a trailing
+/// slash. Unparseable input is returned trimmed.
+pub fn normalize_clip_url(url: &str) -> String {
+    let trimmed = url.trim();
+    let parsed = match reqwest::Url::parse(trimmed) {
+        Ok(u) => u,
+        Err(_) => return trimmed.to_string(),
+    };
+
+    let host = parsed.host_str().unwrap_or("");
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let port = parsed.port().map(|p| format!(":{}", p)).unwrap_or_default();
+
+    let path = parsed.path();
+    let path = if path.len() > 1 {
+        path.trim_end_matches('/')
+    } else {
+        ""
+    };
+
+    let query: Vec<String> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| {
+            if v.is_empty() {
+                k.into_owned()
+            } else {
+                format!("{}={}", k, v)
+            }
+        })
+        .collect();
+
+    let mut normalized = format!("{}{}{}", host, port, path);
+    if !query.is_empty() {
+        normalized.push('?');
+        normalized.push_str(&query.join("&"));
+    }
+    normalized
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Storage for paired clipper clients and clip history
+pub struct ClipperStorage {
+    clipper_dir: PathBuf,
+    clients: Vec<ClipperClient>,
+    clips: HashMap<String, ClipRecord>,
+    /// Only one code is pending at a time; starting a new pairing replaces it
+    pending_code: Option<PairingCode>,
+    failed_attempts: u32,
+}
+
+impl ClipperStorage {
+    /// Create clipper storage, loading existing clients and clips
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        let clipper_dir = data_dir.join("clipper");
+        fs::create_dir_all(&clipper_dir)?;
+
+        let clients_path = clipper_dir.join("clients.json");
+        let clients = if clients_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&clients_path)?)?
+        } else {
+            Vec::new()
+        };
+
+        let clips_path = clipper_dir.join("clips.json");
+        let clips = if clips_path.exists() {
+            let records: Vec<ClipRecord> = serde_json::from_str(&fs::read_to_string(&clips_path)?)?;
+            records
+                .into_iter()
+                .map(|r| (r.normalized_url.clone(), r))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            clipper_dir,
+            clients,
+            clips,
+            pending_code: None,
+            failed_attempts: 0,
+        })
+    }
+
+    fn save_clients(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.clients)?;
+        let path = self.clipper_dir.join("clients.json");
+        atomic::write_str(&path, &json)?;
+
+        // Token hashes aren't secrets, but keep the file private anyway
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    fn save_clips(&self) -> Result<()> {
+        let mut records: Vec<&ClipRecord> = self.clips.values().collect();
+        records.sort_by_key(|r| r.clipped_at);
+        let json = serde_json::to_string_pretty(&records)?;
+        atomic::write_str(&self.clipper_dir.join("clips.json"), &json)?;
+        Ok(())
+    }
+
+    /// Generate a fresh 6-digit pairing code, replacing any pending one
+    pub fn start_pairing(&mut self) -> PairingCode {
+        let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+        let pairing = PairingCode {
+            code,
+            expires_at: Utc::now() + Duration::minutes(PAIRING_CODE_TTL_MINUTES),
+        };
+        self.pending_code = Some(pairing.clone());
+        self.failed_attempts = 0;
+        pairing
+    }
+
+    /// Exchange a pairing code for a client token. The code is single-use
+    /// and is discarded after too many wrong guesses.
+    pub fn complete_pairing(&mut self, code: &str, client_name: &str) -> Result<PairingResult> {
+        let pending = match &self.pending_code {
+            Some(p) if p.expires_at > Utc::now() => p,
+            _ => {
+                self.pending_code = None;
+                return Err(StorageError::InvalidOperation(
+                    "No pairing in progress or the code has expired".to_string(),
+                ));
+            }
+        };
+
+        use subtle::ConstantTimeEq;
+        let matches: bool = pending.code.as_bytes().ct_eq(code.trim().as_bytes()).into();
+        if !matches {
+            self.failed_attempts += 1;
+            if self.failed_attempts >= MAX_PAIRING_ATTEMPTS {
+                self.pending_code = None;
+            }
+            return Err(StorageError::InvalidOperation(
+                "Invalid pairing code".to_string(),
+            ));
+        }
+        self.pending_code = None;
+        self.failed_attempts = 0;
+
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill(&mut bytes);
+        let token = format!("{}{}", TOKEN_PREFIX, URL_SAFE_NO_PAD.encode(bytes));
+
+        let name = client_name.trim();
+        let client = ClipperClient {
+            id: Uuid::new_v4(),
+            name: if name.is_empty() {
+                "Browser extension".to_string()
+            } else {
+                name.to_string()
+            },
+            token_hash: hash_token(&token),
+            paired_at: Utc::now(),
+            last_used_at: None,
+        };
+        self.clients.push(client.clone());
+        self.save_clients()?;
+
+        Ok(PairingResult {
+            client: ClipperClientInfo::from(&client),
+            token,
+        })
+    }
+
+    /// Validate a clipper token, returning the client it belongs to
+    pub fn authenticate(&self, token: &str) -> Option<ClipperClientInfo> {
+        use subtle::ConstantTimeEq;
+        if !token.starts_with(TOKEN_PREFIX) {
+            return None;
+        }
+        let hash = hash_token(token);
+        self.clients
+            .iter()
+            .find(|c| c.token_hash.as_bytes().ct_eq(hash.as_bytes()).into())
+            .map(ClipperClientInfo::from)
+    }
+
+    /// Record that a client was just used
+    pub fn touch_client(&mut self, client_id: Uuid) -> Result<()> {
+        if let Some(client) = self.clients.iter_mut().find(|c| c.id == client_id) {
+            client.last_used_at = Some(Utc::now());
+            self.save_clients()?;
+        }
+        Ok(())
+    }
+
+    /// List paired clients
+    pub fn list_clients(&self) -> Vec<ClipperClientInfo> {
+        self.clients.iter().map(ClipperClientInfo::from).collect()
+    }
+
+    /// Revoke a paired client; its token stops working immediately
+    pub fn revoke_client(&mut self, client_id: Uuid) -> Result<()> {
+        let before = self.clients.len();
+        self.clients.retain(|c| c.id != client_id);
+        if self.clients.len() == before {
+            return Err(StorageError::NotFound(format!(
+                "Clipper client {}",
+                client_id
+            )));
+        }
+        self.save_clients()
+    }
+
+    /// Look up an earlier clip of the same URL
+    pub fn find_clip(&self, url: &str) -> Option<&ClipRecord> {
+        self.clips.get(&normalize_clip_url(url))
+    }
+
+    /// Forget an earlier clip (e.g. its page or inbox item was deleted)
+    pub fn forget_clip(&mut self, url: &str) -> Result<()> {
+        if self.clips.remove(&normalize_clip_url(url)).is_some() {
+            self.save_clips()?;
+        }
+        Ok(())
+    }
+
+    /// Record a clip, replacing any earlier record for the same URL
+    pub fn record_clip(
+        &mut self,
+        url: &str,
+        title: &str,
+        target: ClipTarget,
+        client_id: Option<Uuid>,
+    ) -> Result<ClipRecord> {
+        let record = ClipRecord {
+            normalized_url: normalize_clip_url(url),
+            url: url.to_string(),
+            title: title.to_string(),
+            target,
+            client_id,
+            clipped_at: Utc::now(),
+        };
+        self.clips
+            .insert(record.normalized_url.clone(), record.clone());
+        self.save_clips()?;
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_ignores_scheme_www_fragment_and_tracking() {
+        let a = normalize_clip_url("https://www.example.com/post/1/?utm_source=x&id=7#comments");
+        let b = normalize_clip_url("http://example.com/post/1?id=7&fbclid=abc");
+        assert_eq!(a, b);
+        assert_eq!(a, "example.com/post/1?id=7");
+        assert_ne!(a, normalize_clip_url("https://example.com/post/2?id=7"));
+    }
+
+    #[test]
+    fn pairing_issues_token_once_and_authenticates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = ClipperStorage::new(dir.path().to_path_buf()).unwrap();
+
+        assert!(storage.complete_pairing("123456", "x").is_err());
+
+        let code = storage.start_pairing();
+        let paired = storage.complete_pairing(&code.code, "Firefox").unwrap();
+        assert!(paired.token.starts_with(TOKEN_PREFIX));
+        assert!(
+            storage.complete_pairing(&code.code, "again").is_err(),
+            "single use"
+        );
+
+        // Survives a reload from disk
+        let storage = ClipperStorage::new(dir.path().to_path_buf()).unwrap();
+        let client = storage.authenticate(&paired.token).unwrap();
+        assert_eq!(client.name, "Firefox");
+        assert!(storage.authenticate("clip_bogus").is_none());
+        assert!(storage.authenticate("rw:whatever").is_none());
+    }
+
+    #[test]
+    fn wrong_guesses_discard_the_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = ClipperStorage::new(dir.path().to_path_buf()).unwrap();
+        let code = storage.start_pairing();
+        for _ in 0..MAX_PAIRING_ATTEMPTS {
+            assert!(storage.complete_pairing("not-it", "x").is_err());
+        }
+        assert!(storage.complete_pairing(&code.code, "x").is_err());
+    }
+
+    #[test]
+    fn clip_registry_detects_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = ClipperStorage::new(dir.path().to_path_buf()).unwrap();
+        let item_id = Uuid::new_v4();
+        storage
+            .record_clip(
+                "https://example.com/a",
+                "A",
+                ClipTarget::Inbox { item_id },
+                None,
+            )
+            .unwrap();
+
+        let storage = ClipperStorage::new(dir.path().to_path_buf()).unwrap();
+        let found = storage.find_clip("http://www.example.com/a/#top").unwrap();
+        assert_eq!(found.target, ClipTarget::Inbox { item_id });
+        assert!(storage.find_clip("https://example.com/b").is_none());
+    }
+}
@@ -0,0 +1,418 @@
+//! Running code blocks
+//!
+//! Jupyter pages run Python through nous-py; this module runs the code
+//! blocks of ordinary pages. Each language has a [`Runner`]: shell and
+//! JavaScript go to an interpreter process, SQL to a SQLite file the block
+//! names. A notebook opts in per language through its [`CodeExecConfig`],
+//! which also carries the [`SandboxOptions`] runs get. Results are stored on
+//! the block as Jupyter-style outputs (`stream`, `execute_result`, `error`)
+//! so the editor shows them the way it shows notebook cells.
+
+pub mod runners;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::storage::{EditorBlock, Page};
+
+pub use runners::{NodeRunner, ShellRunner, SqliteRunner};
+
+#[derive(Error, Debug)]
+pub enum CodeExecError {
+    #[error("Running {0} code isn't enabled for this notebook")]
+    NotEnabled(Language),
+    #[error("No runner for code in {0:?}")]
+    UnsupportedLanguage(String),
+    #[error("{0} isn't installed")]
+    Unavailable(String),
+    #[error("SQL blocks need a SQLite database")]
+    NoDatabase,
+    #[error("Database not found: {0}")]
+    DatabaseNotFound(PathBuf),
+    #[error("Network isolation isn't available on this system")]
+    NoNetworkIsolation,
+    #[error("Block not found: {0}")]
+    BlockNotFound(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+pub type Result<T> = std::result::Result<T, CodeExecError>;
+
+/// A language code blocks can be run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Shell,
+    JavaScript,
+    Sql,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::Shell, Language::JavaScript, Language::Sql];
+
+    /// The language of a code block's `language` tag
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag.trim().to_ascii_lowercase().as_str() {
+            "sh" | "shell" | "bash" | "zsh" | "console" => Some(Language::Shell),
+            "js" | "javascript" | "node" | "mjs" => Some(Language::JavaScript),
+            "sql" | "sqlite" => Some(Language::Sql),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Language::Shell => "shell",
+            Language::JavaScript => "JavaScript",
+            Language::Sql => "SQL",
+        })
+    }
+}
+
+/// Limits put on a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SandboxOptions {
+    /// Seconds before the run is stopped
+    pub timeout_secs: u64,
+    /// Output kept per stream; the rest is dropped
+    pub max_output_bytes: usize,
+    /// Start processes with only PATH, HOME, LANG and the temp dir set
+    pub clean_env: bool,
+    /// Run processes in an empty temporary directory rather than the
+    /// notebook's directory
+    pub scratch_dir: bool,
+    /// Cut processes off from the network (Linux, through `unshare`)
+    pub no_network: bool,
+    /// Open SQLite databases read-only
+    pub read_only_database: bool,
+}
+
+impl Default for SandboxOptions {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            max_output_bytes: 1024 * 1024,
+            clean_env: true,
+            scratch_dir: true,
+            no_network: false,
+            read_only_database: true,
+        }
+    }
+}
+
+/// A notebook's code execution settings. Nothing runs until a language is
+/// enabled.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodeExecConfig {
+    pub enabled_languages: Vec<Language>,
+    pub sandbox: SandboxOptions,
+}
+
+impl CodeExecConfig {
+    pub fn is_enabled(&self, language: Language) -> bool {
+        self.enabled_languages.contains(&language)
+    }
+}
+
+/// One run of a block
+pub struct RunRequest<'a> {
+    pub code: &'a str,
+    pub sandbox: &'a SandboxOptions,
+    /// Where processes run when the sandbox doesn't give them a scratch dir
+    pub work_dir: &'a Path,
+    /// The SQLite file SQL runs against
+    pub database: Option<&'a Path>,
+}
+
+/// Runs code in one language
+pub trait Runner: Send + Sync {
+    fn language(&self) -> Language;
+
+    /// Whether the interpreter this runner needs is installed
+    fn available(&self) -> bool;
+
+    /// Run the code, returning its outputs. A failing program isn't an
+    /// `Err`; it's an `error` output and `success: false`.
+    fn run(&self, request: &RunRequest<'_>) -> Result<RunOutput>;
+}
+
+/// Outputs of a run
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunOutput {
+    pub success: bool,
+    /// Outputs in Jupyter format
+    pub outputs: Vec<Value>,
+}
+
+impl RunOutput {
+    pub fn push_stream(&mut self, name: &str, text: &str) {
+        if !text.is_empty() {
+            self.outputs.push(json!({
+                "output_type": "stream",
+                "name": name,
+                "text": text,
+            }));
+        }
+    }
+
+    pub fn push_error(&mut self, ename: &str, evalue: &str) {
+        self.success = false;
+        self.outputs.push(json!({
+            "output_type": "error",
+            "ename": ename,
+            "evalue": evalue,
+            "traceback": [],
+        }));
+    }
+
+    /// A result table; `rows` are JSON values, `truncated` if rows were
+    /// left out. Returns the table's `application/json` data, for callers
+    /// that add to it.
+    pub fn push_table(
+        &mut self,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        truncated: bool,
+    ) -> &mut Value {
+        let mut plain = columns.join("\t");
+        for row in rows {
+            plain.push('\n');
+            let cells: Vec<String> = row
+                .iter()
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    Value::Null => "NULL".to_string(),
+                    v => v.to_string(),
+                })
+                .collect();
+            plain.push_str(&cells.join("\t"));
+        }
+        if truncated {
+            plain.push_str(&format!("\n… showing the first {} rows", rows.len()));
+        }
+        self.outputs.push(json!({
+            "output_type": "execute_result",
+            "data": {
+                "text/plain": plain,
+                "application/json": {
+                    "columns": columns,
+                    "rows": rows,
+                    "truncated": truncated,
+                },
+            },
+            "metadata": {},
+        }));
+        &mut self.outputs.last_mut().expect("just pushed")["data"]["application/json"]
+    }
+}
+
+/// The result of running a block, as stored on it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellResult {
+    pub block_id: String,
+    pub language: Language,
+    pub success: bool,
+    pub outputs: Vec<Value>,
+    pub execution_count: u64,
+    pub duration_ms: u64,
+}
+
+/// Whether a runner for the language is installed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerInfo {
+    pub language: Language,
+    pub available: bool,
+}
+
+/// The runner for a language
+pub fn runner(language: Language) -> Box<dyn Runner> {
+    match language {
+        Language::Shell => Box::new(ShellRunner),
+        Language::JavaScript => Box::new(NodeRunner),
+        Language::Sql => Box::new(SqliteRunner),
+    }
+}
+
+pub fn list_runners() -> Vec<RunnerInfo> {
+    Language::ALL
+        .iter()
+        .map(|&language| RunnerInfo {
+            language,
+            available: runner(language).available(),
+        })
+        .collect()
+}
+
+/// A runnable code block on a page: its language, source and SQL database
+pub struct CodeBlock {
+    pub language: Language,
+    pub code: String,
+    pub database: Option<String>,
+    pub execution_count: u64,
+}
+
+/// Find the code block `block_id` on `page`. `language` overrides the
+/// block's own tag.
+pub fn code_block(page: &Page, block_id: &str, language: Option<&str>) -> Result<CodeBlock> {
+    let block = page
+        .content
+        .blocks
+        .iter()
+        .find(|b| b.id == block_id && b.block_type == "code")
+        .ok_or_else(|| CodeExecError::BlockNotFound(block_id.to_string()))?;
+    let str_field = |key: &str| block.data.get(key).and_then(|v| v.as_str());
+    let tag = language
+        .or_else(|| str_field("language"))
+        .unwrap_or_default();
+    let language = Language::from_tag(tag)
+        .ok_or_else(|| CodeExecError::UnsupportedLanguage(tag.to_string()))?;
+    Ok(CodeBlock {
+        language,
+        code: str_field("code").unwrap_or_default().to_string(),
+        database: str_field("database")
+            .filter(|d| !d.trim().is_empty())
+            .map(str::to_string),
+        execution_count: block
+            .data
+            .get("executionCount")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+    })
+}
+
+/// Resolve a block's database path; relative paths are taken from
+/// `base_dir` (the notebook's directory)
+pub fn resolve_database(database: &str, base_dir: &Path) -> Result<PathBuf> {
+    let path = Path::new(database.trim());
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
+    };
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(CodeExecError::DatabaseNotFound(path))
+    }
+}
+
+/// Store a run's result on its block: `outputs`, `executionCount`, and the
+/// `database` SQL ran against
+pub fn store_result(block: &mut EditorBlock, result: &CellResult, database: Option<&str>) {
+    if let Some(data) = block.data.as_object_mut() {
+        data.insert("outputs".to_string(), Value::Array(result.outputs.clone()));
+        data.insert("executionCount".to_string(), json!(result.execution_count));
+        if let Some(database) = database {
+            data.insert("database".to_string(), json!(database));
+        }
+    }
+}
+
+/// Remove the stored outputs of every code block on the page; true if any
+/// were removed
+pub fn clear_outputs(page: &mut Page) -> bool {
+    let mut changed = false;
+    for block in &mut page.content.blocks {
+        if block.block_type != "code" {
+            continue;
+        }
+        if let Some(data) = block.data.as_object_mut() {
+            changed |= data.remove("outputs").is_some();
+            changed |= data.remove("executionCount").is_some();
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EditorData;
+
+    fn page_with(blocks: Vec<EditorBlock>) -> Page {
+        let mut page = Page::new(uuid::Uuid::new_v4(), "Runs".to_string());
+        page.content = EditorData {
+            time: None,
+            version: None,
+            blocks,
+        };
+        page
+    }
+
+    #[test]
+    fn finds_code_blocks_by_tag() {
+        let page = page_with(vec![
+            EditorBlock {
+                id: "a".to_string(),
+                block_type: "code".to_string(),
+                data: json!({ "code": "echo hi", "language": "Bash", "executionCount": 2 }),
+            },
+            EditorBlock {
+                id: "b".to_string(),
+                block_type: "code".to_string(),
+                data: json!({ "code": "print(1)", "language": "python" }),
+            },
+        ]);
+
+        let block = code_block(&page, "a", None).unwrap();
+        assert_eq!(block.language, Language::Shell);
+        assert_eq!(block.execution_count, 2);
+        assert!(matches!(
+            code_block(&page, "b", None),
+            Err(CodeExecError::UnsupportedLanguage(_))
+        ));
+        assert_eq!(
+            code_block(&page, "b", Some("sql")).unwrap().language,
+            Language::Sql
+        );
+        assert!(matches!(
+            code_block(&page, "c", None),
+            Err(CodeExecError::BlockNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn results_are_stored_and_cleared() {
+        let mut page = page_with(vec![EditorBlock {
+            id: "a".to_string(),
+            block_type: "code".to_string(),
+            data: json!({ "code": "select 1", "language": "sql" }),
+        }]);
+        let mut output = RunOutput {
+            success: true,
+            outputs: Vec::new(),
+        };
+        output.push_table(&["1".to_string()], &[vec![json!(1)]], false);
+        let result = CellResult {
+            block_id: "a".to_string(),
+            language: Language::Sql,
+            success: output.success,
+            outputs: output.outputs,
+            execution_count: 1,
+            duration_ms: 3,
+        };
+
+        store_result(&mut page.content.blocks[0], &result, Some("data.db"));
+        let data = &page.content.blocks[0].data;
+        assert_eq!(data["executionCount"], 1);
+        assert_eq!(data["database"], "data.db");
+        assert_eq!(data["outputs"][0]["data"]["text/plain"], "1\n1");
+
+        assert!(clear_outputs(&mut page));
+        assert!(page.content.blocks[0].data.get("outputs").is_none());
+        assert!(!clear_outputs(&mut page));
+    }
+}
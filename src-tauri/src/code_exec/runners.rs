@@ -0,0 +1,431 @@
+//! The shell, Node and SQLite runners
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{json, Value};
+
+use super::{CodeExecError, Language, Result, RunOutput, RunRequest, Runner};
+
+/// Rows of a query kept in its result table
+pub const MAX_ROWS: usize = 1000;
+
+/// Environment variables kept by `SandboxOptions::clean_env`
+const KEPT_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "LANG",
+    "SYSTEMROOT",
+    "TEMP",
+    "TMP",
+    "TMPDIR",
+];
+
+/// Runs code with `sh` (PowerShell on Windows), fed through stdin
+pub struct ShellRunner;
+
+impl Runner for ShellRunner {
+    fn language(&self) -> Language {
+        Language::Shell
+    }
+
+    fn available(&self) -> bool {
+        on_path(shell_program())
+    }
+
+    fn run(&self, request: &RunRequest<'_>) -> Result<RunOutput> {
+        let mut command = Command::new(shell_program());
+        if cfg!(windows) {
+            command.args(["-NoProfile", "-NonInteractive", "-Command", "-"]);
+        } else {
+            command.arg("-s");
+        }
+        run_process(command, request)
+    }
+}
+
+fn shell_program() -> &'static str {
+    if cfg!(windows) {
+        "powershell"
+    } else {
+        "sh"
+    }
+}
+
+/// Runs JavaScript with `node`, fed through stdin
+pub struct NodeRunner;
+
+impl Runner for NodeRunner {
+    fn language(&self) -> Language {
+        Language::JavaScript
+    }
+
+    fn available(&self) -> bool {
+        on_path("node")
+    }
+
+    fn run(&self, request: &RunRequest<'_>) -> Result<RunOutput> {
+        let mut command = Command::new("node");
+        command.arg("-");
+        run_process(command, request)
+    }
+}
+
+/// Runs SQL against a SQLite file. Each statement that returns rows adds a
+/// result table; the others report how many rows they changed.
+pub struct SqliteRunner;
+
+impl Runner for SqliteRunner {
+    fn language(&self) -> Language {
+        Language::Sql
+    }
+
+    fn available(&self) -> bool {
+        true
+    }
+
+    fn run(&self, request: &RunRequest<'_>) -> Result<RunOutput> {
+        let path = request.database.ok_or(CodeExecError::NoDatabase)?;
+        let flags = if request.sandbox.read_only_database {
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+        } else {
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+        };
+        let conn = Connection::open_with_flags(path, flags | OpenFlags::SQLITE_OPEN_NO_MUTEX)?;
+
+        // Interrupt the query once the timeout passes
+        let interrupt = conn.get_interrupt_handle();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let timeout = Duration::from_secs(request.sandbox.timeout_secs.max(1));
+        let watchdog = thread::spawn(move || {
+            if done_rx.recv_timeout(timeout).is_err() {
+                interrupt.interrupt();
+            }
+        });
+
+        let mut output = RunOutput {
+            success: true,
+            outputs: Vec::new(),
+        };
+        if let Err(e) = run_statements(&conn, request.code, &mut output) {
+            let message = match e {
+                rusqlite::Error::SqliteFailure(err, _)
+                    if err.code == rusqlite::ErrorCode::OperationInterrupted =>
+                {
+                    format!("Stopped after {} seconds", timeout.as_secs())
+                }
+                e => e.to_string(),
+            };
+            output.push_error("SQLError", &message);
+        }
+        let _ = done_tx.send(());
+        let _ = watchdog.join();
+        Ok(output)
+    }
+}
+
+fn run_statements(conn: &Connection, sql: &str, output: &mut RunOutput) -> rusqlite::Result<()> {
+    let mut batch = rusqlite::Batch::new(conn, sql);
+    while let Some(mut stmt) = batch.next()? {
+        let column_count = stmt.column_count();
+        if column_count == 0 {
+            let changed = stmt.execute([])?;
+            output.push_stream("stdout", &format!("{} rows affected\n", changed));
+            continue;
+        }
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        let mut query = stmt.query([])?;
+        while let Some(row) = query.next()? {
+            if rows.len() == MAX_ROWS {
+                truncated = true;
+                break;
+            }
+            let values = (0..column_count)
+                .map(|i| row.get_ref(i).map(sql_value))
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            rows.push(values);
+        }
+        output.push_table(&columns, &rows, truncated);
+    }
+    Ok(())
+}
+
+fn sql_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::String(format!("<{} byte blob>", b.len())),
+    }
+}
+
+/// Whether `program` is on the PATH
+fn on_path(program: &str) -> bool {
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    let names: Vec<String> = if cfg!(windows) {
+        vec![format!("{}.exe", program), format!("{}.cmd", program)]
+    } else {
+        vec![program.to_string()]
+    };
+    std::env::split_paths(&paths).any(|dir| names.iter().any(|name| dir.join(name).is_file()))
+}
+
+/// Run `command` with the code on stdin under the request's sandbox,
+/// collecting stdout and stderr as stream outputs
+fn run_process(mut command: Command, request: &RunRequest<'_>) -> Result<RunOutput> {
+    let sandbox = request.sandbox;
+    if sandbox.no_network {
+        command = isolate_network(command)?;
+    }
+    if sandbox.clean_env {
+        let kept: Vec<(String, std::ffi::OsString)> = KEPT_ENV
+            .iter()
+            .filter_map(|key| std::env::var_os(key).map(|v| (key.to_string(), v)))
+            .collect();
+        command.env_clear().envs(kept);
+    }
+    let scratch = if sandbox.scratch_dir {
+        let dir = std::env::temp_dir().join(format!("nous-run-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        command.current_dir(&dir);
+        Some(ScratchDir(dir))
+    } else {
+        command.current_dir(request.work_dir);
+        None
+    };
+    // Its own process group, so whatever the program starts goes with it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                CodeExecError::Unavailable(command.get_program().to_string_lossy().into_owned())
+            }
+            _ => CodeExecError::Io(e),
+        })?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let code = request.code.to_string();
+    let writer = thread::spawn(move || {
+        // A program that exits without reading its input closes the pipe
+        let _ = stdin.write_all(code.as_bytes());
+    });
+    let limit = sandbox.max_output_bytes;
+    let stdout = capture(child.stdout.take().expect("stdout is piped"), limit);
+    let stderr = capture(child.stderr.take().expect("stderr is piped"), limit);
+
+    let deadline = Instant::now() + Duration::from_secs(sandbox.timeout_secs.max(1));
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            timed_out = true;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    // Background processes left running would hold the pipes open
+    kill_process_group(child.id());
+    let _ = writer.join();
+    let (stdout, stdout_cut) = stdout.join().unwrap_or_default();
+    let (stderr, stderr_cut) = stderr.join().unwrap_or_default();
+    drop(scratch);
+
+    let mut output = RunOutput {
+        success: status.is_some_and(|s| s.success()),
+        outputs: Vec::new(),
+    };
+    output.push_stream("stdout", &stdout);
+    output.push_stream("stderr", &stderr);
+    if stdout_cut || stderr_cut {
+        output.push_stream(
+            "stderr",
+            &format!("\n[output cut off after {} bytes]\n", limit),
+        );
+    }
+    if timed_out {
+        output.push_error(
+            "Timeout",
+            &format!("Stopped after {} seconds", sandbox.timeout_secs.max(1)),
+        );
+    } else if let Some(status) = status.filter(|s| !s.success()) {
+        let evalue = match status.code() {
+            Some(code) => format!("Exited with status {}", code),
+            None => "Killed by a signal".to_string(),
+        };
+        output.push_error("ExitError", &evalue);
+    }
+    Ok(output)
+}
+
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", pid)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: u32) {}
+
+/// Wrap `command` to run in a new network namespace with no interfaces
+fn isolate_network(command: Command) -> Result<Command> {
+    if !cfg!(target_os = "linux") || !on_path("unshare") {
+        return Err(CodeExecError::NoNetworkIsolation);
+    }
+    let mut wrapped = Command::new("unshare");
+    wrapped
+        .args(["--user", "--map-root-user", "--net", "--"])
+        .arg(command.get_program())
+        .args(command.get_args());
+    Ok(wrapped)
+}
+
+/// Read a pipe to the end on a thread, keeping the first `limit` bytes;
+/// returns the text and whether any was dropped
+fn capture<R: Read + Send + 'static>(
+    mut pipe: R,
+    limit: usize,
+) -> thread::JoinHandle<(String, bool)> {
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let mut cut = false;
+        let mut buf = [0u8; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let room = limit.saturating_sub(kept.len());
+                    kept.extend_from_slice(&buf[..n.min(room)]);
+                    cut |= n > room;
+                }
+            }
+        }
+        (String::from_utf8_lossy(&kept).into_owned(), cut)
+    })
+}
+
+/// A temporary working directory, removed when dropped
+struct ScratchDir(PathBuf);
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_exec::SandboxOptions;
+    use std::path::Path;
+
+    fn request<'a>(
+        code: &'a str,
+        sandbox: &'a SandboxOptions,
+        database: Option<&'a Path>,
+    ) -> RunRequest<'a> {
+        RunRequest {
+            code,
+            sandbox,
+            work_dir: Path::new("."),
+            database,
+        }
+    }
+
+    #[test]
+    fn sqlite_runner_returns_tables_and_counts() {
+        let dir = std::env::temp_dir().join(format!("code_exec_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("data.db");
+        Connection::open(&db)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (name TEXT, n INTEGER); INSERT INTO t VALUES ('a', 1), ('b', NULL);")
+            .unwrap();
+
+        let writable = SandboxOptions {
+            read_only_database: false,
+            ..Default::default()
+        };
+        let output = SqliteRunner
+            .run(&request(
+                "UPDATE t SET n = 2 WHERE name = 'b'; SELECT name, n FROM t ORDER BY name;",
+                &writable,
+                Some(&db),
+            ))
+            .unwrap();
+        assert!(output.success);
+        assert_eq!(output.outputs[0]["text"], "1 rows affected\n");
+        assert_eq!(
+            output.outputs[1]["data"]["application/json"]["columns"],
+            json!(["name", "n"])
+        );
+        assert_eq!(
+            output.outputs[1]["data"]["application/json"]["rows"],
+            json!([["a", 1], ["b", 2]])
+        );
+
+        // The default sandbox opens the database read-only
+        let output = SqliteRunner
+            .run(&request(
+                "DELETE FROM t",
+                &SandboxOptions::default(),
+                Some(&db),
+            ))
+            .unwrap();
+        assert!(!output.success);
+        assert_eq!(output.outputs[0]["output_type"], "error");
+
+        assert!(matches!(
+            SqliteRunner.run(&request("SELECT 1", &writable, None)),
+            Err(CodeExecError::NoDatabase)
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shell_runner_captures_streams_and_exit_status() {
+        let sandbox = SandboxOptions::default();
+        let output = ShellRunner
+            .run(&request("echo out; echo err >&2; exit 3", &sandbox, None))
+            .unwrap();
+        assert!(!output.success);
+        assert_eq!(output.outputs[0]["text"], "out\n");
+        assert_eq!(output.outputs[1]["text"], "err\n");
+        assert_eq!(output.outputs[2]["evalue"], "Exited with status 3");
+
+        let small = SandboxOptions {
+            max_output_bytes: 4,
+            timeout_secs: 1,
+            ..Default::default()
+        };
+        let output = ShellRunner
+            .run(&request("echo 0123456789; sleep 5", &small, None))
+            .unwrap();
+        assert_eq!(output.outputs[0]["text"], "0123");
+        assert_eq!(output.outputs.last().unwrap()["ename"], "Timeout");
+    }
+}
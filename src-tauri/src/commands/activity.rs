@@ -0,0 +1,48 @@
+//! Tauri commands for the activity feed of changes across devices
+
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::activity::{ActivityEntry, ActivityFilter};
+use crate::AppState;
+
+type CommandResult<T> = Result<T, String>;
+
+/// Page creations, edits, deletions and imports after `since` (all when
+/// omitted), newest first
+#[tauri::command]
+pub fn get_activity_feed(
+    state: State<AppState>,
+    window: tauri::Window,
+    since: Option<DateTime<Utc>>,
+    filter: Option<ActivityFilter>,
+) -> CommandResult<Vec<ActivityEntry>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
+    Ok(storage.activity().feed(since, &filter.unwrap_or_default()))
+}
+
+/// When the feed was last marked seen
+#[tauri::command]
+pub fn get_activity_last_seen(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Option<DateTime<Utc>>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
+    Ok(storage.activity().last_seen())
+}
+
+/// Mark the feed seen now; returns when it was last seen before
+#[tauri::command]
+pub fn mark_activity_seen(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Option<DateTime<Utc>>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
+    storage
+        .activity()
+        .mark_seen(Utc::now())
+        .map_err(|e| e.to_string())
+}
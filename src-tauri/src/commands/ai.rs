@@ -1,32 +1,55 @@
 //! AI-related Tauri commands.
+//!
+//! Python-bound calls go through the app's [`PythonQueue`], so they run on
+//! its worker pool instead of a command thread. Commands that take a
+//! `task_id` can be cancelled with `cancel_ai_task`.
+//!
+//! [`PythonQueue`]: crate::python_bridge::queue::PythonQueue
+
+use std::time::Duration;
 
 use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::ai_action_log::AiActionRecorder;
+use crate::ai_cache::{AiCache, AiCacheKind};
 
+use crate::python_bridge::queue::{QueueError, TaskOptions};
 use crate::python_bridge::{
     AIConfig, BrowserTaskResult, ChatMessage, ChatResponse, ChatResponseWithActions,
     DiscoveredChatModel, NotebookInfo, PageContext, PageInfo, PageSummaryInput,
     PagesSummaryResult, RelatedPageSuggestion, StreamEvent,
 };
+use crate::secrets;
 use crate::AppState;
 
+use super::ai_permissions::emit_permission;
 use super::notebook::CommandError;
 
+/// Browser automation drives a real browser through many steps
+const BROWSER_TASK_TIMEOUT: Duration = Duration::from_secs(600);
+
+impl From<QueueError> for CommandError {
+    fn from(err: QueueError) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
 /// Send a chat message to the AI
 #[tauri::command]
-pub fn ai_chat(
-    state: State<AppState>,
+pub async fn ai_chat(
+    state: State<'_, AppState>,
     messages: Vec<ChatMessage>,
     provider_type: Option<String>,
     api_key: Option<String>,
     model: Option<String>,
     temperature: Option<f64>,
     max_tokens: Option<i64>,
+    task_id: Option<String>,
 ) -> Result<ChatResponse, CommandError> {
-    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire Python AI lock: {}", e),
-    })?;
-
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -34,16 +57,22 @@ pub fn ai_chat(
         max_tokens,
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    python_ai.chat(messages, config).map_err(|e| CommandError {
-        message: format!("AI chat error: {}", e),
-    })
+    let options = TaskOptions::default().with_task_id(task_id);
+    state
+        .python_queue
+        .run(options, move |python_ai| python_ai.chat(messages, config))
+        .await?
+        .map_err(|e| CommandError {
+            message: format!("AI chat error: {}", e),
+        })
 }
 
 /// Chat with page context
 #[tauri::command]
-pub fn ai_chat_with_context(
-    state: State<AppState>,
+pub async fn ai_chat_with_context(
+    state: State<'_, AppState>,
     user_message: String,
     page_context: Option<PageContext>,
     conversation_history: Option<Vec<ChatMessage>>,
@@ -52,12 +81,9 @@ pub fn ai_chat_with_context(
     model: Option<String>,
     temperature: Option<f64>,
     max_tokens: Option<i64>,
+    task_id: Option<String>,
 ) -> Result<ChatResponse, CommandError> {
-    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire Python AI lock: {}", e),
-    })?;
-
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -65,18 +91,24 @@ pub fn ai_chat_with_context(
         max_tokens,
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    python_ai
-        .chat_with_context(user_message, page_context, conversation_history, config)
+    let options = TaskOptions::default().with_task_id(task_id);
+    state
+        .python_queue
+        .run(options, move |python_ai| {
+            python_ai.chat_with_context(user_message, page_context, conversation_history, config)
+        })
+        .await?
         .map_err(|e| CommandError {
             message: format!("AI chat error: {}", e),
         })
 }
 
-/// Summarize page content
+/// Summarize page content. Summaries are cached by content and model.
 #[tauri::command]
-pub fn ai_summarize_page(
-    state: State<AppState>,
+pub async fn ai_summarize_page(
+    state: State<'_, AppState>,
     content: String,
     title: Option<String>,
     max_length: Option<i64>,
@@ -84,11 +116,7 @@ pub fn ai_summarize_page(
     api_key: Option<String>,
     model: Option<String>,
 ) -> Result<String, CommandError> {
-    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire Python AI lock: {}", e),
-    })?;
-
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -96,29 +124,41 @@ pub fn ai_summarize_page(
         max_tokens: Some(1000),
         ..Default::default()
     };
-
-    python_ai
-        .summarize_page(content, title, max_length, config)
+    state.secrets.fill_ai_config(&mut config);
+
+    let key = AiCache::key(
+        AiCacheKind::Summary,
+        &(AiCache::config_inputs(&config), &content, &title, max_length),
+    );
+    if let Some(summary) = state.ai_cache.get(&key) {
+        return Ok(summary);
+    }
+
+    let summary = state
+        .python_queue
+        .run(TaskOptions::default(), move |python_ai| {
+            python_ai.summarize_page(content, title, max_length, config)
+        })
+        .await?
         .map_err(|e| CommandError {
             message: format!("AI summarization error: {}", e),
-        })
+        })?;
+    state.ai_cache.put(AiCacheKind::Summary, &key, &summary);
+    Ok(summary)
 }
 
-/// Suggest tags for page content
+/// Suggest tags for page content. Suggestions are cached by content,
+/// existing tags and model.
 #[tauri::command]
-pub fn ai_suggest_tags(
-    state: State<AppState>,
+pub async fn ai_suggest_tags(
+    state: State<'_, AppState>,
     content: String,
     existing_tags: Option<Vec<String>>,
     provider_type: Option<String>,
     api_key: Option<String>,
     model: Option<String>,
 ) -> Result<Vec<String>, CommandError> {
-    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire Python AI lock: {}", e),
-    })?;
-
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -126,18 +166,33 @@ pub fn ai_suggest_tags(
         max_tokens: Some(100),
         ..Default::default()
     };
-
-    python_ai
-        .suggest_tags(content, existing_tags, config)
+    state.secrets.fill_ai_config(&mut config);
+
+    let key = AiCache::key(
+        AiCacheKind::Tags,
+        &(AiCache::config_inputs(&config), &content, &existing_tags),
+    );
+    if let Some(tags) = state.ai_cache.get(&key) {
+        return Ok(tags);
+    }
+
+    let tags = state
+        .python_queue
+        .run(TaskOptions::default(), move |python_ai| {
+            python_ai.suggest_tags(content, existing_tags, config)
+        })
+        .await?
         .map_err(|e| CommandError {
             message: format!("AI tag suggestion error: {}", e),
-        })
+        })?;
+    state.ai_cache.put(AiCacheKind::Tags, &key, &tags);
+    Ok(tags)
 }
 
 /// Suggest related pages to link based on content analysis
 #[tauri::command]
-pub fn ai_suggest_related_pages(
-    state: State<AppState>,
+pub async fn ai_suggest_related_pages(
+    state: State<'_, AppState>,
     content: String,
     title: String,
     available_pages: Vec<PageInfo>,
@@ -147,11 +202,7 @@ pub fn ai_suggest_related_pages(
     api_key: Option<String>,
     model: Option<String>,
 ) -> Result<Vec<RelatedPageSuggestion>, CommandError> {
-    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire Python AI lock: {}", e),
-    })?;
-
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -159,18 +210,34 @@ pub fn ai_suggest_related_pages(
         max_tokens: Some(1000),
         ..Default::default()
     };
-
-    python_ai
-        .suggest_related_pages(content, title, available_pages, existing_links, max_suggestions, config)
+    state.secrets.fill_ai_config(&mut config);
+
+    state
+        .python_queue
+        .run(TaskOptions::default(), move |python_ai| {
+            python_ai.suggest_related_pages(
+                content,
+                title,
+                available_pages,
+                existing_links,
+                max_suggestions,
+                config,
+            )
+        })
+        .await?
         .map_err(|e| CommandError {
             message: format!("AI related pages suggestion error: {}", e),
         })
 }
 
-/// Chat with AI using tools for notebook/page creation
+/// Chat with AI using tools for notebook/page creation. Pages the `nous_*`
+/// tools change are recorded in the AI action log under `session_id`; tool
+/// calls the tool permissions stopped are emitted as `ai-tool-permission`.
 #[tauri::command]
 pub async fn ai_chat_with_tools(
+    app: AppHandle,
     state: State<'_, AppState>,
+    window: tauri::Window,
     user_message: String,
     page_context: Option<PageContext>,
     conversation_history: Option<Vec<ChatMessage>>,
@@ -181,10 +248,18 @@ pub async fn ai_chat_with_tools(
     model: Option<String>,
     temperature: Option<f64>,
     max_tokens: Option<i64>,
+    task_id: Option<String>,
+    session_id: Option<String>,
 ) -> Result<ChatResponseWithActions, CommandError> {
-    let python_ai = state.python_ai.clone();
+    let session_id = session_id
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|e| CommandError {
+            message: format!("Invalid session ID: {}", e),
+        })?;
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -192,15 +267,16 @@ pub async fn ai_chat_with_tools(
         max_tokens,
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    // Run the blocking Python call on a separate thread
-    tauri::async_runtime::spawn_blocking(move || {
-        let python_ai = python_ai.lock().map_err(|e| CommandError {
-            message: format!("Failed to acquire Python AI lock: {}", e),
-        })?;
+    let lib = state.library(&window);
+    let recorder = AiActionRecorder::begin(&lib.storage.read().unwrap())?;
 
-        python_ai
-            .chat_with_tools(
+    let options = TaskOptions::default().with_task_id(task_id);
+    let response = state
+        .python_queue
+        .run(options, move |python_ai| {
+            python_ai.chat_with_tools(
                 user_message,
                 page_context,
                 conversation_history,
@@ -208,14 +284,28 @@ pub async fn ai_chat_with_tools(
                 current_notebook_id,
                 config,
             )
-            .map_err(|e| CommandError {
-                message: format!("AI chat with tools error: {}", e),
-            })
-    })
-    .await
-    .map_err(|e| CommandError {
-        message: format!("Task join error: {}", e),
-    })?
+        })
+        .await?
+        .map_err(|e| CommandError {
+            message: format!("AI chat with tools error: {}", e),
+        })?;
+
+    // The reply stands even if logging fails
+    let storage = lib.storage.read().unwrap();
+    let logged = super::ai_action_log::action_log(&storage).and_then(|log| {
+        Ok(recorder.finish(&storage, &log, session_id, &response.actions)?)
+    });
+    match logged {
+        Ok(entries) if !entries.is_empty() => {
+            log::info!("Logged {} AI action(s)", entries.len())
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to log AI actions: {}", e.message),
+    }
+    for check in &response.permission_denials {
+        emit_permission(&app, check);
+    }
+    Ok(response)
 }
 
 /// Chat with AI using tools, streaming the response via events
@@ -239,7 +329,7 @@ pub async fn ai_chat_stream(
 ) -> Result<(), CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         base_url,
@@ -247,6 +337,7 @@ pub async fn ai_chat_stream(
         temperature,
         max_tokens,
     };
+    state.secrets.fill_ai_config(&mut config);
 
     // Get current library path for MCP server access
     let library_path = {
@@ -298,6 +389,10 @@ pub async fn ai_chat_stream(
                 }
             }
 
+            if let StreamEvent::ToolPermission(ref check) = event {
+                emit_permission(&app, check);
+            }
+
             // Emit the event to the frontend
             if let Err(e) = app.emit("ai-stream", &event) {
                 log::error!("Failed to emit AI stream event: {}", e);
@@ -332,10 +427,9 @@ pub async fn ai_summarize_pages(
     provider_type: Option<String>,
     api_key: Option<String>,
     model: Option<String>,
+    task_id: Option<String>,
 ) -> Result<PagesSummaryResult, CommandError> {
-    let python_ai = state.python_ai.clone();
-
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -343,22 +437,18 @@ pub async fn ai_summarize_pages(
         max_tokens: Some(4096),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    tauri::async_runtime::spawn_blocking(move || {
-        let python_ai = python_ai.lock().map_err(|e| CommandError {
-            message: format!("Failed to acquire Python AI lock: {}", e),
-        })?;
-
-        python_ai
-            .summarize_pages(pages, custom_prompt, summary_style, config)
-            .map_err(|e| CommandError {
-                message: format!("AI summarization error: {}", e),
-            })
-    })
-    .await
-    .map_err(|e| CommandError {
-        message: format!("Task join error: {}", e),
-    })?
+    let options = TaskOptions::default().with_task_id(task_id);
+    state
+        .python_queue
+        .run(options, move |python_ai| {
+            python_ai.summarize_pages(pages, custom_prompt, summary_style, config)
+        })
+        .await?
+        .map_err(|e| CommandError {
+            message: format!("AI summarization error: {}", e),
+        })
 }
 
 /// Run a browser automation task using AI
@@ -370,47 +460,62 @@ pub async fn browser_run_task(
     api_key: String,
     model: String,
     capture_screenshot: Option<bool>,
+    task_id: Option<String>,
 ) -> Result<BrowserTaskResult, CommandError> {
-    let python_ai = state.python_ai.clone();
-
-    tauri::async_runtime::spawn_blocking(move || {
-        let python_ai = python_ai.lock().map_err(|e| CommandError {
-            message: format!("Failed to acquire Python AI lock: {}", e),
-        })?;
-
-        python_ai
-            .run_browser_task(
+    let mut stored_key = Some(api_key).filter(|k| !k.is_empty());
+    state
+        .secrets
+        .fill_api_key(&mut stored_key, &secrets::ai_key_id(&provider_type));
+    let api_key = stored_key.unwrap_or_default();
+
+    let options = TaskOptions::default()
+        .with_task_id(task_id)
+        .with_timeout(BROWSER_TASK_TIMEOUT);
+
+    state
+        .python_queue
+        .run(options, move |python_ai| {
+            python_ai.run_browser_task(
                 &task,
                 &provider_type,
                 &api_key,
                 &model,
                 capture_screenshot.unwrap_or(false),
             )
-            .map_err(|e| CommandError {
-                message: format!("Browser automation error: {}", e),
-            })
-    })
-    .await
-    .map_err(|e| CommandError {
-        message: format!("Task join error: {}", e),
-    })?
+        })
+        .await?
+        .map_err(|e| CommandError {
+            message: format!("Browser automation error: {}", e),
+        })
 }
 
 /// Discover available chat models from a local provider
 #[tauri::command]
-pub fn discover_ai_models(
-    state: State<AppState>,
+pub async fn discover_ai_models(
+    state: State<'_, AppState>,
     provider: String,
     base_url: String,
     api_key: Option<String>,
 ) -> Result<Vec<DiscoveredChatModel>, CommandError> {
-    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire Python AI lock: {}", e),
-    })?;
-
-    python_ai
-        .discover_chat_models(&provider, &base_url, api_key.as_deref())
+    let mut api_key = api_key;
+    state
+        .secrets
+        .fill_api_key(&mut api_key, &secrets::ai_key_id(&provider));
+    state
+        .python_queue
+        .run(TaskOptions::default(), move |python_ai| {
+            python_ai.discover_chat_models(&provider, &base_url, api_key.as_deref())
+        })
+        .await?
         .map_err(|e| CommandError {
             message: format!("Failed to discover models: {}", e),
         })
 }
+
+/// Cancel an AI request started with `task_id`. A request still waiting
+/// for a worker never runs; one already running finishes in the background
+/// and its result is dropped. Returns whether the request was found.
+#[tauri::command]
+pub fn cancel_ai_task(state: State<AppState>, task_id: String) -> bool {
+    state.python_queue.cancel(&task_id)
+}
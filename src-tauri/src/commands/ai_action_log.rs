@@ -0,0 +1,132 @@
+//! The AI action log: list what AI tool calls changed, and undo it
+//! (see `ai_action_log`)
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::ai_action_log::{
+    self, AiActionEntry, AiActionLogStorage, AiPageChange, AiPageChangeKind, AiSessionUndo,
+};
+use crate::storage::FileStorage;
+use crate::sync::SyncManager;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+fn parse_uuid(s: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(s).map_err(|e| CommandError {
+        message: format!("Invalid ID '{}': {}", s, e),
+    })
+}
+
+/// The action log of the library `storage` holds
+pub(crate) fn action_log(storage: &FileStorage) -> CommandResult<AiActionLogStorage> {
+    Ok(AiActionLogStorage::new(storage.notebooks_dir().clone())?)
+}
+
+/// Sync what an undo put back or trashed
+fn queue_undone(sync_manager: &SyncManager, changes: &[AiPageChange]) {
+    for change in changes {
+        match change.kind {
+            AiPageChangeKind::Created => {
+                sync_manager.queue_page_delete(change.notebook_id, change.page_id)
+            }
+            AiPageChangeKind::Updated => {
+                sync_manager.queue_page_update(change.notebook_id, change.page_id)
+            }
+        }
+    }
+}
+
+/// AI-applied actions, newest first, optionally only one chat session's
+#[tauri::command]
+pub fn list_ai_actions(
+    state: State<AppState>,
+    window: tauri::Window,
+    session_id: Option<String>,
+    limit: Option<usize>,
+) -> CommandResult<Vec<AiActionEntry>> {
+    let session_id = session_id.as_deref().map(parse_uuid).transpose()?;
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let entries = action_log(&storage)?.list_entries(session_id)?;
+    Ok(entries
+        .iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(AiActionEntry::summary)
+        .collect())
+}
+
+/// Record pages and notebooks the frontend created for an AI tool call
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn record_ai_action(
+    state: State<AppState>,
+    window: tauri::Window,
+    session_id: Option<String>,
+    tool: String,
+    arguments: serde_json::Value,
+    tool_call_id: String,
+    notebook_ids: Option<Vec<String>>,
+    pages: Option<Vec<(String, String)>>,
+) -> CommandResult<AiActionEntry> {
+    let session_id = session_id.as_deref().map(parse_uuid).transpose()?;
+    let notebooks = notebook_ids
+        .unwrap_or_default()
+        .iter()
+        .map(|id| parse_uuid(id))
+        .collect::<CommandResult<Vec<_>>>()?;
+    let pages = pages
+        .unwrap_or_default()
+        .iter()
+        .map(|(notebook_id, page_id)| Ok((parse_uuid(notebook_id)?, parse_uuid(page_id)?)))
+        .collect::<CommandResult<Vec<_>>>()?;
+
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let entry = ai_action_log::record_created(
+        &storage,
+        &action_log(&storage)?,
+        session_id,
+        tool,
+        arguments,
+        tool_call_id,
+        &pages,
+        notebooks,
+    )?;
+    Ok(entry.summary())
+}
+
+/// Undo one AI action
+#[tauri::command]
+pub fn undo_ai_action(
+    state: State<AppState>,
+    window: tauri::Window,
+    id: String,
+) -> CommandResult<AiActionEntry> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    let entry = ai_action_log::undo_entry(&storage, &action_log(&storage)?, parse_uuid(&id)?)?;
+    queue_undone(&state.sync_manager, &entry.changes);
+    Ok(entry.summary())
+}
+
+/// Undo everything a chat session applied, newest first
+#[tauri::command]
+pub fn undo_ai_session(
+    state: State<AppState>,
+    window: tauri::Window,
+    session_id: String,
+) -> CommandResult<AiSessionUndo> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    let mut result =
+        ai_action_log::undo_session(&storage, &action_log(&storage)?, parse_uuid(&session_id)?)?;
+    for entry in &mut result.undone {
+        queue_undone(&state.sync_manager, &entry.changes);
+        *entry = entry.summary();
+    }
+    Ok(result)
+}
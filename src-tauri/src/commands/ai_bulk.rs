@@ -0,0 +1,240 @@
+//! AI bulk operation commands (see `crate::ai_bulk`).
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::ai_bulk::{BulkOperation, BulkPageChange, BulkSkipped, BulkTransformPreview};
+use crate::library::snapshots::{self, LibrarySnapshot};
+use crate::python_bridge::queue::{QueueError, TaskOptions};
+use crate::python_bridge::AIConfig;
+use crate::storage::Page;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Progress of a preview, emitted as `ai-bulk-progress` after each page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkProgress {
+    pub task_id: Option<String>,
+    pub page_id: Uuid,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Result of applying a preview's changes
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkApplyResult {
+    /// Snapshot taken before the changes; roll back to it to undo them
+    pub snapshot: LibrarySnapshot,
+    pub applied: Vec<Uuid>,
+    pub skipped: Vec<BulkSkipped>,
+}
+
+/// Run `operation` over the given pages and return the proposed changes
+/// without saving them. Pages go through the Python queue one at a time, so
+/// a large batch doesn't crowd out other AI requests; `cancel_ai_task` with
+/// `task_id` stops the batch and returns what was done so far.
+#[tauri::command]
+pub async fn ai_bulk_transform(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_ids: Vec<String>,
+    operation: BulkOperation,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    task_id: Option<String>,
+) -> CommandResult<BulkTransformPreview> {
+    operation
+        .validate()
+        .map_err(|message| CommandError { message })?;
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    let mut preview = BulkTransformPreview::default();
+    let pages: Vec<Page> = {
+        let lib = state.library(&window);
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let mut pages = Vec::with_capacity(page_ids.len());
+        for page_id in &page_ids {
+            let id = Uuid::parse_str(page_id).map_err(|e| CommandError {
+                message: format!("Invalid page ID: {}", e),
+            })?;
+            match storage.get_page(nb_id, id) {
+                Ok(page) => pages.push(page),
+                Err(e) => preview.skipped.push(BulkSkipped {
+                    page_id: id,
+                    title: String::new(),
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        pages
+    };
+
+    let mut config = AIConfig {
+        provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
+        api_key,
+        model,
+        temperature: Some(0.3),
+        max_tokens: Some(match operation {
+            BulkOperation::Retitle | BulkOperation::Summarize { .. } => 512,
+            _ => 8192,
+        }),
+        ..Default::default()
+    };
+    state.secrets.fill_ai_config(&mut config);
+
+    let total = pages.len();
+    let mut cancelled = false;
+    for (index, page) in pages.iter().enumerate() {
+        let skip = |reason: String| BulkSkipped {
+            page_id: page.id,
+            title: page.title.clone(),
+            reason,
+        };
+        if cancelled {
+            preview.skipped.push(skip("cancelled".to_string()));
+            continue;
+        }
+
+        match operation.messages(page) {
+            Err(e) => preview.skipped.push(skip(e.to_string())),
+            Ok(messages) => {
+                let config = config.clone();
+                let options = TaskOptions::default().with_task_id(task_id.clone());
+                let reply = state
+                    .python_queue
+                    .run(options, move |python_ai| python_ai.chat(messages, config))
+                    .await;
+                match reply {
+                    Ok(Ok(response)) => match operation.change(page, &response.content) {
+                        Ok(change) => preview.changes.push(change),
+                        Err(e) => preview.skipped.push(skip(e.to_string())),
+                    },
+                    Ok(Err(e)) => preview.skipped.push(skip(format!("AI error: {}", e))),
+                    Err(QueueError::Cancelled) => {
+                        cancelled = true;
+                        preview.skipped.push(skip("cancelled".to_string()));
+                    }
+                    Err(e) => preview.skipped.push(skip(e.to_string())),
+                }
+            }
+        }
+
+        let progress = BulkProgress {
+            task_id: task_id.clone(),
+            page_id: page.id,
+            done: index + 1,
+            total,
+        };
+        if let Err(e) = app.emit("ai-bulk-progress", &progress) {
+            log::warn!("Failed to emit AI bulk progress: {}", e);
+        }
+    }
+
+    log::info!(
+        "AI bulk '{}': {} changes proposed, {} pages skipped",
+        operation.label(),
+        preview.changes.len(),
+        preview.skipped.len()
+    );
+    Ok(preview)
+}
+
+/// Save the accepted `changes` from `ai_bulk_transform`, after taking a
+/// library snapshot to undo them with. Pages edited since the preview are
+/// left alone.
+#[tauri::command]
+pub async fn apply_ai_bulk_transform(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    operation: BulkOperation,
+    changes: Vec<BulkPageChange>,
+) -> CommandResult<BulkApplyResult> {
+    let lib = state.library(&window);
+    let label = operation.label();
+    let notebooks: HashMap<Uuid, Uuid> = changes
+        .iter()
+        .map(|change| (change.page_id, change.notebook_id))
+        .collect();
+
+    let snapshot_label = format!("Before AI bulk edit: {}", label);
+    let result = tokio::task::spawn_blocking(move || -> CommandResult<BulkApplyResult> {
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let snapshot = {
+            let _vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+                message: format!("Lock error: {}", e),
+            })?;
+            snapshots::create_snapshot(storage.notebooks_dir(), &snapshot_label).map_err(|e| {
+                CommandError {
+                    message: format!("Failed to snapshot library: {}", e),
+                }
+            })?
+        };
+
+        let mut applied = Vec::new();
+        let mut skipped = Vec::new();
+        for change in &changes {
+            let skip = |reason: String| BulkSkipped {
+                page_id: change.page_id,
+                title: change.title.clone(),
+                reason,
+            };
+            let mut page = match storage.get_page(change.notebook_id, change.page_id) {
+                Ok(page) => page,
+                Err(e) => {
+                    skipped.push(skip(e.to_string()));
+                    continue;
+                }
+            };
+            if page.updated_at != change.base_updated_at {
+                skipped.push(skip("edited since the preview".to_string()));
+                continue;
+            }
+            change.apply_to(&mut page);
+            match storage.update_page(&page) {
+                Ok(()) => applied.push(page.id),
+                Err(e) => skipped.push(skip(e.to_string())),
+            }
+        }
+
+        Ok(BulkApplyResult {
+            snapshot,
+            applied,
+            skipped,
+        })
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Bulk apply task failed: {}", e),
+    })??;
+
+    for page_id in &result.applied {
+        if let Some(&notebook_id) = notebooks.get(page_id) {
+            state.sync_manager.queue_page_update(notebook_id, *page_id);
+        }
+    }
+
+    log::info!(
+        "AI bulk '{}' applied to {} pages ({} skipped)",
+        label,
+        result.applied.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
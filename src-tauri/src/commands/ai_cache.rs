@@ -0,0 +1,27 @@
+//! AI response cache commands (see `crate::ai_cache`).
+
+use tauri::State;
+
+use crate::ai_cache::{AiCacheKind, AiCacheStats};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Empty the AI cache, or only its entries of `kind`. Returns how many
+/// entries were removed.
+#[tauri::command]
+pub fn clear_ai_cache(state: State<AppState>, kind: Option<AiCacheKind>) -> CommandResult<usize> {
+    state.ai_cache.clear(kind).map_err(|e| CommandError {
+        message: format!("Failed to clear AI cache: {}", e),
+    })
+}
+
+/// Entries, size and hits of the AI cache per kind
+#[tauri::command]
+pub fn get_ai_cache_stats(state: State<AppState>) -> CommandResult<Vec<AiCacheStats>> {
+    state.ai_cache.stats().map_err(|e| CommandError {
+        message: format!("Failed to read AI cache: {}", e),
+    })
+}
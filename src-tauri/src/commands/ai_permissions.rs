@@ -0,0 +1,64 @@
+//! AI tool permission commands (see `crate::ai_permissions`).
+
+use tauri::{AppHandle, Emitter, State};
+
+use crate::ai_permissions::{self, ToolPermissionCheck, ToolPermissions, PERMISSION_EVENT};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Tell the frontend about a tool call that was not allowed
+pub(crate) fn emit_permission(app: &AppHandle, check: &ToolPermissionCheck) {
+    if let Err(e) = app.emit(PERMISSION_EVENT, check) {
+        log::warn!("Failed to emit {} event: {}", PERMISSION_EVENT, e);
+    }
+}
+
+/// The tool permissions of the window's library
+#[tauri::command]
+pub fn get_tool_permissions(state: State<AppState>, window: tauri::Window) -> ToolPermissions {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    ToolPermissions::load(storage.notebooks_dir())
+}
+
+/// Replace the tool permissions of the window's library
+#[tauri::command]
+pub fn save_tool_permissions(
+    state: State<AppState>,
+    window: tauri::Window,
+    permissions: ToolPermissions,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    permissions
+        .save(storage.notebooks_dir())
+        .map_err(|e| CommandError {
+            message: format!("Failed to save tool permissions: {}", e),
+        })
+}
+
+/// Check a tool call before running it. Calls that are not allowed are
+/// also emitted as `ai-tool-permission` events.
+#[tauri::command]
+pub fn check_tool_permission(
+    app: AppHandle,
+    state: State<AppState>,
+    window: tauri::Window,
+    tool: String,
+    arguments: Option<serde_json::Value>,
+    tool_call_id: Option<String>,
+) -> ToolPermissionCheck {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let arguments = arguments.unwrap_or(serde_json::Value::Null);
+    let notebook_id = ai_permissions::target_notebook(&storage, &arguments);
+    let mut check = ToolPermissions::load(storage.notebooks_dir()).check(&tool, notebook_id);
+    check.tool_call_id = tool_call_id;
+    if !check.allowed() {
+        emit_permission(&app, &check);
+    }
+    check
+}
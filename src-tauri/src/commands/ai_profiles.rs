@@ -0,0 +1,55 @@
+//! AI provider profile commands (see `crate::ai_profiles`).
+
+use tauri::State;
+
+use crate::ai_profiles::{self, AiProfile};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// List the AI profiles of the window's library
+#[tauri::command]
+pub fn list_ai_profiles(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<AiProfile>> {
+    Ok(ai_profiles::list_profiles(&state.library_path(&window)?))
+}
+
+/// Create or update an AI profile; an empty id creates a new one
+#[tauri::command]
+pub fn save_ai_profile(
+    state: State<AppState>,
+    window: tauri::Window,
+    profile: AiProfile,
+) -> CommandResult<AiProfile> {
+    if profile.name.trim().is_empty() {
+        return Err(CommandError {
+            message: "Profile name is required".to_string(),
+        });
+    }
+    if profile.provider_type.trim().is_empty() {
+        return Err(CommandError {
+            message: "Profile provider is required".to_string(),
+        });
+    }
+    ai_profiles::save_profile(&state.library_path(&window)?, profile).map_err(|e| CommandError {
+        message: format!("Failed to save AI profile: {}", e),
+    })
+}
+
+/// Delete an AI profile. Returns whether it existed.
+#[tauri::command]
+pub fn delete_ai_profile(
+    state: State<AppState>,
+    window: tauri::Window,
+    profile_id: String,
+) -> CommandResult<bool> {
+    ai_profiles::delete_profile(&state.library_path(&window)?, &profile_id).map_err(|e| {
+        CommandError {
+            message: format!("Failed to delete AI profile: {}", e),
+        }
+    })
+}
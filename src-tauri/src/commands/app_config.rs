@@ -0,0 +1,269 @@
+//! Configuration export/import commands (see `crate::app_config`).
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::app_config::{self, AppConfigBundle, AppConfigCounts, AppConfigError};
+use crate::library::LibrarySettings;
+use crate::secrets;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+impl From<AppConfigError> for CommandError {
+    fn from(err: AppConfigError) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+fn command_error(context: &str, err: impl std::fmt::Display) -> CommandError {
+    CommandError {
+        message: format!("{}: {}", context, err),
+    }
+}
+
+/// Result of an import
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfigImport {
+    pub imported: AppConfigCounts,
+    /// Templates from the bundle, for the frontend to add
+    pub templates: Vec<serde_json::Value>,
+    /// Sync configs imported disabled because this device has no password
+    /// for them yet
+    pub needs_credentials: Vec<String>,
+    /// Parts that couldn't be applied (a notebook that doesn't exist here,
+    /// a backup folder that isn't valid on this machine, ...)
+    pub warnings: Vec<String>,
+}
+
+/// Write the window library's configuration, with the frontend's
+/// `templates`, to a JSON bundle at `path`
+#[tauri::command]
+pub fn export_app_config(
+    state: State<AppState>,
+    window: tauri::Window,
+    path: String,
+    templates: Vec<serde_json::Value>,
+) -> CommandResult<AppConfigCounts> {
+    let library = state.window_library(&window)?;
+    let lib = state.library(&window);
+    let mut bundle = AppConfigBundle::new(&library);
+
+    bundle.settings = LibrarySettings::load(&library.path).entries().clone();
+    {
+        let actions = lib
+            .action_storage
+            .lock()
+            .map_err(|e| command_error("Lock error", e))?;
+        bundle.add_actions(
+            actions
+                .list_actions()
+                .map_err(|e| command_error("Failed to list actions", e))?,
+        );
+    }
+    {
+        let sources = state
+            .external_sources_storage
+            .lock()
+            .map_err(|e| command_error("Lock error", e))?;
+        bundle.add_external_sources(sources.list_sources());
+    }
+    {
+        let storage = lib
+            .storage
+            .read()
+            .map_err(|e| command_error("Storage error", e))?;
+        let notebooks = storage
+            .list_notebooks()
+            .map_err(|e| command_error("Failed to list notebooks", e))?;
+        for notebook in &notebooks {
+            if let Some(config) = &notebook.sync_config {
+                bundle.add_notebook_sync(notebook.id, &notebook.name, config);
+            }
+        }
+    }
+    bundle.mcp_servers = app_config::read_mcp_servers(&library.path)?;
+    bundle.templates = templates;
+
+    std::fs::write(PathBuf::from(&path), bundle.to_json()?)
+        .map_err(|e| command_error("Failed to write export", e))?;
+    log::info!("Exported app configuration to {}", path);
+    Ok(bundle.counts())
+}
+
+/// Apply a bundle written by `export_app_config` to the window's library.
+/// Existing entries with the same id or key are replaced; nothing else is
+/// removed.
+#[tauri::command]
+pub fn import_app_config(
+    state: State<AppState>,
+    window: tauri::Window,
+    path: String,
+) -> CommandResult<AppConfigImport> {
+    let json = std::fs::read_to_string(PathBuf::from(&path))
+        .map_err(|e| command_error("Failed to read export", e))?;
+    let bundle = AppConfigBundle::from_json(&json)?;
+
+    let library = state.window_library(&window)?;
+    let lib = state.library(&window);
+    let mut imported = AppConfigCounts::default();
+    let mut needs_credentials = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Library configuration. Folders from another machine may not exist
+    // here, so each part is applied on its own.
+    {
+        let library_storage = state
+            .library_storage
+            .lock()
+            .map_err(|e| command_error("Lock error", e))?;
+        let config = bundle.library.clone();
+        if config.icon.is_some() || config.color.is_some() {
+            if let Err(e) =
+                library_storage.update_library(library.id, None, config.icon, config.color)
+            {
+                warnings.push(format!("Library appearance: {}", e));
+            }
+        }
+        if let Some(mut sync) = config.sync_config {
+            if sync.enabled
+                && !state
+                    .secrets
+                    .contains(&secrets::library_sync_key_id(library.id))
+            {
+                sync.enabled = false;
+                needs_credentials.push(format!("Library \"{}\"", library.name));
+            }
+            if let Err(e) = library_storage.update_library_sync_config(library.id, Some(sync)) {
+                warnings.push(format!("Library sync: {}", e));
+            }
+        }
+        if let Some(share) = config.share_upload_config {
+            if let Err(e) =
+                library_storage.update_library_share_upload_config(library.id, Some(share))
+            {
+                warnings.push(format!("Share uploads: {}", e));
+            }
+        }
+        if let Some(mirror) = config.markdown_mirror {
+            if let Err(e) = library_storage.update_library_markdown_mirror(library.id, Some(mirror))
+            {
+                warnings.push(format!("Markdown mirror: {}", e));
+            }
+        }
+        if let Some(destination) = config.backup_destination {
+            if let Err(e) =
+                library_storage.update_library_backup_destination(library.id, Some(destination))
+            {
+                warnings.push(format!("Backup destination: {}", e));
+            }
+        }
+    }
+
+    if !bundle.settings.is_empty() {
+        let mut settings = LibrarySettings::load(&library.path);
+        for (key, entry) in &bundle.settings {
+            settings.set(key, entry.value.clone());
+        }
+        settings
+            .save()
+            .map_err(|e| command_error("Failed to save library settings", e))?;
+        imported.settings = bundle.settings.len();
+    }
+
+    {
+        let actions = lib
+            .action_storage
+            .lock()
+            .map_err(|e| command_error("Lock error", e))?;
+        for action in &bundle.actions {
+            match actions.create_action(action.clone()) {
+                Ok(_) => imported.actions += 1,
+                Err(e) => warnings.push(format!("Action \"{}\": {}", action.name, e)),
+            }
+        }
+    }
+
+    {
+        let mut sources = state
+            .external_sources_storage
+            .lock()
+            .map_err(|e| command_error("Lock error", e))?;
+        for source in &bundle.external_sources {
+            match sources.import_source(source.clone()) {
+                Ok(_) => imported.external_sources += 1,
+                Err(e) => warnings.push(format!("External source \"{}\": {}", source.name, e)),
+            }
+        }
+    }
+
+    if !bundle.notebook_sync.is_empty() {
+        let storage = lib
+            .storage
+            .write()
+            .map_err(|e| command_error("Storage error", e))?;
+        let notebooks: Vec<(Uuid, String)> = storage
+            .list_notebooks()
+            .map_err(|e| command_error("Failed to list notebooks", e))?
+            .into_iter()
+            .map(|notebook| (notebook.id, notebook.name))
+            .collect();
+        for entry in &bundle.notebook_sync {
+            let Some(&notebook_id) = app_config::match_notebook(entry, &notebooks) else {
+                warnings.push(format!(
+                    "Sync for notebook \"{}\": no matching notebook in this library",
+                    entry.notebook_name
+                ));
+                continue;
+            };
+            let mut config = entry.config.clone();
+            let managed = config.managed_by_library.unwrap_or(false);
+            let has_credentials = state
+                .secrets
+                .contains(&secrets::notebook_sync_key_id(notebook_id));
+            if config.enabled && !managed && !has_credentials {
+                config.enabled = false;
+                needs_credentials.push(format!("Notebook \"{}\"", entry.notebook_name));
+            }
+            let result = storage.get_notebook(notebook_id).and_then(|mut notebook| {
+                notebook.sync_config = Some(config);
+                storage.update_notebook(&notebook)
+            });
+            match result {
+                Ok(()) => imported.notebook_sync += 1,
+                Err(e) => warnings.push(format!(
+                    "Sync for notebook \"{}\": {}",
+                    entry.notebook_name, e
+                )),
+            }
+        }
+    }
+
+    if let Some(mcp) = &bundle.mcp_servers {
+        match app_config::write_mcp_servers(&library.path, mcp) {
+            Ok(()) => imported.mcp_servers = true,
+            Err(e) => warnings.push(format!("MCP servers: {}", e)),
+        }
+    }
+
+    imported.templates = bundle.templates.len();
+    log::info!(
+        "Imported app configuration from {} ({} warnings)",
+        path,
+        warnings.len()
+    );
+    Ok(AppConfigImport {
+        imported,
+        templates: bundle.templates,
+        needs_credentials,
+        warnings,
+    })
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use tauri::{AppHandle, Manager, State};
 use uuid::Uuid;
 
+use crate::generated_media::GeneratedMediaRegistry;
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -81,9 +82,11 @@ pub fn save_video_asset(
 #[tauri::command]
 pub fn get_notebook_assets_path(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<String> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -110,11 +113,13 @@ pub fn get_notebook_assets_path(
 pub fn save_notebook_asset(
     app: AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     filename: String,
     data: Vec<u8>,
 ) -> CommandResult<String> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -156,21 +161,31 @@ pub struct MediaAssetInfo {
     pub media_type: String, // "video" or "infographic"
     pub size_bytes: u64,
     pub created_at: Option<String>,
+    /// Generated media entry the file belongs to, if it was recorded
+    pub media_id: Option<Uuid>,
 }
 
 /// List media assets (videos and infographics) for a notebook
 #[tauri::command]
 pub fn list_notebook_media_assets(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<Vec<MediaAssetInfo>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
     let assets_path = storage.notebook_assets_dir(nb_id);
+    let registry = GeneratedMediaRegistry::open(assets_path.clone())?;
+    let media_id = |path: &std::path::Path| {
+        registry
+            .relative_path(path)
+            .and_then(|file| registry.find_by_file(&file).map(|m| m.id))
+    };
     let mut media_assets = Vec::new();
 
     // List videos
@@ -200,6 +215,7 @@ pub fn list_notebook_media_assets(
                                 media_type: "video".to_string(),
                                 size_bytes: metadata.len(),
                                 created_at,
+                                media_id: media_id(&path),
                             });
                         }
                     }
@@ -235,6 +251,7 @@ pub fn list_notebook_media_assets(
                                 media_type: "infographic".to_string(),
                                 size_bytes: metadata.len(),
                                 created_at,
+                                media_id: media_id(&path),
                             });
                         }
                     }
@@ -253,10 +270,12 @@ pub fn list_notebook_media_assets(
 #[tauri::command]
 pub fn delete_notebook_media_asset(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     asset_path: String,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -276,5 +295,10 @@ pub fn delete_notebook_media_asset(
         message: format!("Failed to delete asset: {}", e),
     })?;
 
+    let mut registry = GeneratedMediaRegistry::open(assets_path)?;
+    if let Some(file) = registry.relative_path(&file_path) {
+        registry.forget_file(&file)?;
+    }
+
     Ok(())
 }
@@ -6,9 +6,11 @@ use std::fs;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::publish::podcast::{self, EpisodeMeta};
 use crate::python_bridge::{
     AIConfig, AudioGenerationResult, TranscriptionResult, TTSProviderInfo, TTSVoiceInfo,
 };
+use crate::secrets;
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -27,7 +29,7 @@ pub struct TTSConfig {
 
 /// Extract plain text from EditorData blocks for TTS input.
 /// Handles paragraph, header, list, quote, code, and checklist blocks.
-fn extract_text_from_blocks(blocks: &[serde_json::Value]) -> String {
+pub(crate) fn extract_text_from_blocks(blocks: &[serde_json::Value]) -> String {
     let mut parts: Vec<String> = Vec::new();
 
     for block in blocks {
@@ -117,6 +119,7 @@ fn strip_html_tags(input: &str) -> String {
 #[tauri::command]
 pub async fn generate_page_audio(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     mode: String,
@@ -126,6 +129,15 @@ pub async fn generate_page_audio(
     target_length: Option<String>,
     custom_instructions: Option<String>,
 ) -> Result<AudioGenerationResult, CommandError> {
+    let lib = state.library(&window);
+    let mut tts_config = tts_config;
+    state
+        .secrets
+        .fill_api_key(&mut tts_config.api_key, &secrets::tts_key_id(&tts_config.provider));
+    let ai_config = ai_config.map(|mut config| {
+        state.secrets.fill_ai_config(&mut config);
+        config
+    });
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -135,7 +147,7 @@ pub async fn generate_page_audio(
 
     // Get page content and assets directory from storage
     let (content, title, output_dir) = {
-        let storage = state.storage.lock().map_err(|e| CommandError {
+        let storage = lib.storage.read().map_err(|e| CommandError {
             message: format!("Failed to acquire storage lock: {}", e),
         })?;
 
@@ -181,8 +193,14 @@ pub async fn generate_page_audio(
 
     // Clone python_ai Arc for spawn_blocking
     let python_ai = state.python_ai.clone();
+    let episode_title = title.clone();
+    let episode_description = if mode == "podcast" {
+        "Discussion".to_string()
+    } else {
+        "Narration".to_string()
+    };
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -211,7 +229,25 @@ pub async fn generate_page_audio(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+
+    // Titles the episode in podcast feeds
+    let meta = EpisodeMeta {
+        title: episode_title,
+        description: episode_description,
+        page_id: Some(pg_id),
+        duration_seconds: result.duration_seconds,
+        generated_at: chrono::Utc::now(),
+    };
+    if let Err(e) = podcast::write_episode_meta(std::path::Path::new(&result.audio_path), &meta) {
+        log::warn!(
+            "Failed to write episode metadata for {}: {}",
+            result.audio_path,
+            e
+        );
+    }
+
+    Ok(result)
 }
 
 /// List available TTS providers
@@ -234,6 +270,10 @@ pub fn list_tts_voices(
     api_key: Option<String>,
     base_url: Option<String>,
 ) -> Result<Vec<TTSVoiceInfo>, CommandError> {
+    let mut api_key = api_key;
+    state
+        .secrets
+        .fill_api_key(&mut api_key, &secrets::tts_key_id(&provider));
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
@@ -290,16 +330,18 @@ pub async fn transcribe_audio(
 #[tauri::command]
 pub async fn save_audio_recording(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     audio_data_base64: String,
     format: String,
 ) -> Result<SaveAudioResult, CommandError> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
     let audio_dir = {
-        let storage = state.storage.lock().map_err(|e| CommandError {
+        let storage = lib.storage.read().map_err(|e| CommandError {
             message: format!("Failed to acquire storage lock: {}", e),
         })?;
         let assets_dir = storage.notebook_assets_dir(nb_id);
@@ -353,6 +395,10 @@ pub async fn synthesize_text(
             message: "No text to synthesize".to_string(),
         });
     }
+    let mut tts_config = tts_config;
+    state
+        .secrets
+        .fill_api_key(&mut tts_config.api_key, &secrets::tts_key_id(&tts_config.provider));
 
     let python_ai = state.python_ai.clone();
 
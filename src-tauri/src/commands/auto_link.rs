@@ -0,0 +1,130 @@
+//! Applying accepted related-page suggestions as links (see
+//! `crate::auto_link`).
+
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::ai_bulk::BulkSkipped;
+use crate::auto_link::{insert_links, LinkInsertion, PageLinks};
+use crate::library::snapshots::{self, LibrarySnapshot};
+use crate::storage::PageType;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// A page that links were added to
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedPage {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    #[serde(flatten)]
+    pub insertion: LinkInsertion,
+}
+
+/// Result of applying link suggestions
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkApplyResult {
+    /// Snapshot taken before the changes; roll back to it to undo them
+    pub snapshot: LibrarySnapshot,
+    pub linked: Vec<LinkedPage>,
+    pub skipped: Vec<BulkSkipped>,
+}
+
+/// Add wiki-links for the accepted suggestions of many pages at once, after
+/// taking a library snapshot to undo them with. Each target is linked at a
+/// mention of its title, or listed under a "Related" heading.
+#[tauri::command]
+pub async fn apply_link_suggestions(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    pages: Vec<PageLinks>,
+) -> CommandResult<LinkApplyResult> {
+    let lib = state.library(&window);
+    let result = tokio::task::spawn_blocking(move || -> CommandResult<LinkApplyResult> {
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let snapshot = {
+            let _vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+                message: format!("Lock error: {}", e),
+            })?;
+            snapshots::create_snapshot(storage.notebooks_dir(), "Before adding suggested links")
+                .map_err(|e| CommandError {
+                    message: format!("Failed to snapshot library: {}", e),
+                })?
+        };
+
+        let mut linked = Vec::new();
+        let mut skipped = Vec::new();
+        for accepted in &pages {
+            let mut page = match storage.get_page(accepted.notebook_id, accepted.page_id) {
+                Ok(page) => page,
+                Err(e) => {
+                    skipped.push(BulkSkipped {
+                        page_id: accepted.page_id,
+                        title: String::new(),
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let title = page.title.clone();
+            let skip = |reason: &str| BulkSkipped {
+                page_id: accepted.page_id,
+                title: title.clone(),
+                reason: reason.to_string(),
+            };
+            if page.is_encrypted() {
+                skipped.push(skip("page is encrypted"));
+                continue;
+            }
+            if page.page_type != PageType::Standard || page.plugin_page_type.is_some() {
+                skipped.push(skip("not a block page"));
+                continue;
+            }
+
+            let insertion = insert_links(&mut page.content.blocks, page.id, &accepted.targets);
+            if insertion.is_empty() {
+                continue;
+            }
+            match storage.update_page(&page) {
+                Ok(()) => linked.push(LinkedPage {
+                    notebook_id: page.notebook_id,
+                    page_id: page.id,
+                    title,
+                    insertion,
+                }),
+                Err(e) => skipped.push(skip(&e.to_string())),
+            }
+        }
+
+        Ok(LinkApplyResult {
+            snapshot,
+            linked,
+            skipped,
+        })
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Link apply task failed: {}", e),
+    })??;
+
+    for page in &result.linked {
+        state
+            .sync_manager
+            .queue_page_update(page.notebook_id, page.page_id);
+    }
+
+    log::info!(
+        "Added suggested links to {} pages ({} skipped)",
+        result.linked.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
@@ -5,11 +5,18 @@ use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex as TokioMutex;
 use uuid::Uuid;
 
+use crate::library::{Library, LibraryStorage};
 use crate::storage::backup::{
-    calculate_next_backup_time, create_auto_backup, export_notebook_to_zip, get_backup_info,
-    import_notebook_from_zip, is_backup_due, list_auto_backups, load_backup_settings,
-    save_backup_settings, BackupFrequency, BackupMetadata, BackupSettings,
+    calculate_next_backup_time, create_auto_backup, export_notebook_to_encrypted_backup,
+    export_notebook_to_zip, generate_backup_keyfile, get_backup_info, import_notebook_from_backup,
+    is_backup_due, list_auto_backups, load_backup_settings, preview_restore, save_backup_settings,
+    AutoBackup, BackupMetadata, BackupSecret, BackupSettings, RestorePreview,
 };
+use crate::storage::backup_destination::{
+    delete_destination_credentials, get_destination_credentials, push_backups,
+    store_destination_credentials, BackupDestination, DestinationCredentials,
+};
+use crate::storage::integrity::IntegrityReport;
 use crate::AppState;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +28,7 @@ pub struct BackupInfo {
     pub notebook_name: String,
     pub page_count: usize,
     pub asset_count: usize,
+    pub encrypted: bool,
 }
 
 impl From<BackupMetadata> for BackupInfo {
@@ -33,10 +41,68 @@ impl From<BackupMetadata> for BackupInfo {
             notebook_name: m.notebook_name,
             page_count: m.page_count,
             asset_count: m.asset_count,
+            encrypted: m.encrypted,
         }
     }
 }
 
+/// Build the backup secret from a password or keyfile path (password wins
+/// if both are given)
+fn backup_secret(
+    password: Option<String>,
+    keyfile_path: Option<String>,
+) -> Result<Option<BackupSecret>, String> {
+    match (password, keyfile_path) {
+        (Some(password), _) if password.is_empty() => Err("Password cannot be empty".to_string()),
+        (Some(password), _) => Ok(Some(BackupSecret::Password(password))),
+        (None, Some(path)) => Ok(Some(BackupSecret::Keyfile(path.into()))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Secret for scheduled backups, from the keyfile in the settings
+fn scheduled_backup_secret(settings: &BackupSettings) -> Option<BackupSecret> {
+    settings
+        .keyfile_path
+        .as_ref()
+        .map(|path| BackupSecret::Keyfile(path.into()))
+}
+
+/// Copy a run's backups to the current library's backup destination, if it
+/// has one. Failures are logged; the local backups are kept either way.
+async fn push_to_library_destination(
+    library_storage: &Arc<std::sync::Mutex<LibraryStorage>>,
+    data_dir: &std::path::Path,
+    outcomes: &[AutoBackup],
+) {
+    if outcomes.is_empty() {
+        return;
+    }
+    let library = match library_storage.lock() {
+        Ok(storage) => storage.get_current_library().ok(),
+        Err(_) => None,
+    };
+    let Some((library_id, destination)) =
+        library.and_then(|library| library.backup_destination.map(|dest| (library.id, dest)))
+    else {
+        return;
+    };
+
+    let credentials = get_destination_credentials(data_dir, library_id).ok();
+    let created: Vec<_> = outcomes.iter().map(|o| o.path.clone()).collect();
+    let pruned: Vec<_> = outcomes.iter().flat_map(|o| o.pruned.clone()).collect();
+    match push_backups(&destination, credentials.as_ref(), &created, &pruned).await {
+        Ok(()) => log::info!(
+            "Copied {} backups to the library backup destination",
+            created.len()
+        ),
+        Err(e) => log::error!(
+            "Failed to copy backups to the library backup destination: {}",
+            e
+        ),
+    }
+}
+
 #[derive(Clone, Serialize)]
 struct BackupProgress {
     current: usize,
@@ -44,28 +110,78 @@ struct BackupProgress {
     message: String,
 }
 
-/// Export a notebook to a ZIP file
+/// Resolve unfinished page saves and check/repair every notebook in the
+/// current library
 #[tauri::command]
-pub fn export_notebook_zip(
+pub fn verify_storage_integrity(
     state: State<AppState>,
+    window: tauri::Window,
+) -> Result<IntegrityReport, String> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().map_err(|e| e.to_string())?;
+    let report = storage.verify_integrity().map_err(|e| e.to_string())?;
+    if !report.is_clean() {
+        log::warn!(
+            "Storage integrity: {} repairs, {} unresolved problems",
+            report.repairs.len(),
+            report.problems.len()
+        );
+    }
+    Ok(report)
+}
+
+/// Export a notebook to a ZIP file, or to an encrypted backup when a
+/// password or keyfile is given. Runs off the async runtime and reports
+/// `notebook-export-progress` as files are archived.
+#[tauri::command]
+pub async fn export_notebook_zip(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: Uuid,
     output_path: String,
+    password: Option<String>,
+    keyfile_path: Option<String>,
 ) -> Result<BackupInfo, String> {
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
-
-    // Get notebook
-    let notebook = storage
-        .get_notebook(notebook_id)
-        .map_err(|e| e.to_string())?;
-
-    // Get notebook directory
-    let data_dir = crate::storage::FileStorage::default_data_dir().map_err(|e| e.to_string())?;
-    let notebook_dir = data_dir.join("notebooks").join(notebook_id.to_string());
-
-    // Export to ZIP
-    let metadata =
-        export_notebook_to_zip(&notebook_dir, &notebook, std::path::Path::new(&output_path), None)
+    let lib = state.library(&window);
+    let secret = backup_secret(password, keyfile_path)?;
+
+    // Look up the notebook, then release the lock for the export itself
+    let (notebook, notebook_dir) = {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        let notebook = storage
+            .get_notebook(notebook_id)
             .map_err(|e| e.to_string())?;
+        let notebook_dir = storage.notebooks_base_dir().join(notebook_id.to_string());
+        (notebook, notebook_dir)
+    };
+
+    let output = std::path::PathBuf::from(&output_path);
+    let metadata = tokio::task::spawn_blocking(move || {
+        let progress_fn = |current: usize, total: usize, name: &str| {
+            let _ = app.emit(
+                "notebook-export-progress",
+                BackupProgress {
+                    current,
+                    total,
+                    message: format!("Exporting {} ({}/{})", name, current, total),
+                },
+            );
+        };
+        match secret {
+            Some(secret) => export_notebook_to_encrypted_backup(
+                &notebook_dir,
+                &notebook,
+                &output,
+                &secret,
+                Some(&progress_fn),
+            ),
+            None => export_notebook_to_zip(&notebook_dir, &notebook, &output, Some(&progress_fn)),
+        }
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
+    .map_err(|e| e.to_string())?;
 
     let mut info: BackupInfo = metadata.into();
     info.path = output_path;
@@ -73,13 +189,20 @@ pub fn export_notebook_zip(
     Ok(info)
 }
 
-/// Import a notebook from a ZIP file
+/// Import a notebook from a ZIP file or an encrypted backup (which needs
+/// its password or keyfile and is verified before restoring)
 #[tauri::command]
 pub fn import_notebook_zip(
     state: State<AppState>,
+    window: tauri::Window,
     zip_path: String,
+    password: Option<String>,
+    keyfile_path: Option<String>,
 ) -> Result<crate::storage::Notebook, String> {
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let lib = state.library(&window);
+    let secret = backup_secret(password, keyfile_path)?;
+
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
 
     // Get existing notebook IDs
     let existing_notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
@@ -90,9 +213,13 @@ pub fn import_notebook_zip(
     let notebooks_dir = data_dir.join("notebooks");
 
     // Import from ZIP
-    let notebook =
-        import_notebook_from_zip(std::path::Path::new(&zip_path), &notebooks_dir, &existing_ids)
-            .map_err(|e| e.to_string())?;
+    let notebook = import_notebook_from_backup(
+        std::path::Path::new(&zip_path),
+        &notebooks_dir,
+        &existing_ids,
+        secret.as_ref(),
+    )
+    .map_err(|e| e.to_string())?;
 
     // Daemon owns the search index now. After a backup restore, run
     // POST /api/search/rebuild to reindex the restored notebook's pages.
@@ -116,9 +243,11 @@ pub fn get_backup_metadata(zip_path: String) -> Result<BackupInfo, String> {
 #[tauri::command]
 pub fn create_notebook_backup(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: Uuid,
 ) -> Result<BackupInfo, String> {
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
 
     // Get notebook
     let notebook = storage
@@ -129,10 +258,19 @@ pub fn create_notebook_backup(
     let data_dir = crate::storage::FileStorage::default_data_dir().map_err(|e| e.to_string())?;
     let notebook_dir = data_dir.join("notebooks").join(notebook_id.to_string());
 
-    // Create backup (keep max 5 auto-backups per notebook)
-    let backup_path =
-        create_auto_backup(&notebook_dir, &notebook, &data_dir, 5, None)
-            .map_err(|e| e.to_string())?;
+    // Create backup, rotating old ones by the scheduled-backup retention
+    let settings = load_backup_settings(&data_dir).map_err(|e| e.to_string())?;
+    let secret = scheduled_backup_secret(&settings);
+    let backup_path = create_auto_backup(
+        &notebook_dir,
+        &notebook,
+        &data_dir,
+        &settings.retention_policy(),
+        secret.as_ref(),
+        None,
+    )
+    .map_err(|e| e.to_string())?
+    .path;
 
     // Get metadata
     let metadata = get_backup_info(&backup_path).map_err(|e| e.to_string())?;
@@ -145,9 +283,13 @@ pub fn create_notebook_backup(
 
 /// List all auto-backups
 #[tauri::command]
-pub fn list_backups(state: State<AppState>) -> Result<Vec<BackupInfo>, String> {
+pub fn list_backups(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> Result<Vec<BackupInfo>, String> {
+    let lib = state.library(&window);
     // Just need to verify state is accessible
-    let _storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let _storage = lib.storage.read().map_err(|e| e.to_string())?;
 
     let data_dir = crate::storage::FileStorage::default_data_dir().map_err(|e| e.to_string())?;
 
@@ -171,6 +313,70 @@ pub fn delete_backup(backup_path: String) -> Result<(), String> {
     std::fs::remove_file(&backup_path).map_err(|e| e.to_string())
 }
 
+/// Show what restoring a backup would change compared with the current
+/// notebook, without restoring anything
+#[tauri::command]
+pub fn preview_backup_restore(
+    backup_path: String,
+    password: Option<String>,
+    keyfile_path: Option<String>,
+) -> Result<RestorePreview, String> {
+    let secret = backup_secret(password, keyfile_path)?;
+    let data_dir = crate::storage::FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    let notebooks_dir = data_dir.join("notebooks");
+
+    preview_restore(
+        std::path::Path::new(&backup_path),
+        &notebooks_dir,
+        secret.as_ref(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Set or clear the current library's backup destination. `username` and
+/// `secret` are the WebDAV login or the S3 access key pair; they are kept
+/// in the credential store, not in the library settings.
+#[tauri::command]
+pub fn set_library_backup_destination(
+    state: State<AppState>,
+    destination: Option<BackupDestination>,
+    username: Option<String>,
+    secret: Option<String>,
+) -> Result<Library, String> {
+    let data_dir = crate::storage::FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    let library_storage = state.library_storage.lock().map_err(|e| e.to_string())?;
+    let library = library_storage
+        .get_current_library()
+        .map_err(|e| format!("Failed to get library: {}", e))?;
+
+    match (&destination, username, secret) {
+        (Some(dest), Some(username), Some(secret)) if dest.needs_credentials() => {
+            store_destination_credentials(
+                &data_dir,
+                library.id,
+                &DestinationCredentials { username, secret },
+            )?;
+        }
+        (Some(dest), _, _) if dest.needs_credentials() => {
+            if get_destination_credentials(&data_dir, library.id).is_err() {
+                return Err("Credentials are required for this backup destination".to_string());
+            }
+        }
+        (Some(_), _, _) => {}
+        (None, _, _) => delete_destination_credentials(&data_dir, library.id),
+    }
+
+    library_storage
+        .update_library_backup_destination(library.id, destination)
+        .map_err(|e| e.to_string())
+}
+
+/// Create a new random keyfile for encrypted backups
+#[tauri::command]
+pub fn create_backup_keyfile(path: String) -> Result<(), String> {
+    generate_backup_keyfile(std::path::Path::new(&path)).map_err(|e| e.to_string())
+}
+
 // Backup-settings get/update moved to the daemon: GET/POST /api/backup/settings.
 // run_scheduled_backup stays here because it streams Tauri progress events.
 
@@ -180,13 +386,15 @@ pub fn delete_backup(backup_path: String) -> Result<(), String> {
 pub async fn run_scheduled_backup(
     app: AppHandle,
     state: State<'_, AppState>,
+    window: tauri::Window,
 ) -> Result<Vec<BackupInfo>, String> {
+    let lib = state.library(&window);
     let data_dir = crate::storage::FileStorage::default_data_dir().map_err(|e| e.to_string())?;
     let settings = load_backup_settings(&data_dir).map_err(|e| e.to_string())?;
 
     // Gather notebooks while holding the lock, then release it
     let notebooks = {
-        let storage = state.storage.lock().map_err(|e| e.to_string())?;
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
         if settings.notebook_ids.is_empty() {
             storage.list_notebooks().map_err(|e| e.to_string())?
         } else {
@@ -198,13 +406,15 @@ pub async fn run_scheduled_backup(
         }
     };
 
-    let max_backups = settings.max_backups_per_notebook;
+    let retention = settings.retention_policy();
+    let secret = scheduled_backup_secret(&settings);
     let data_dir_clone = data_dir.clone();
     let app_for_backup = app.clone();
 
     // Run the backup in a blocking task to avoid blocking the async runtime
-    let backup_infos: Vec<BackupInfo> = tokio::task::spawn_blocking(move || {
+    let (backup_infos, outcomes) = tokio::task::spawn_blocking(move || {
         let mut backup_infos = Vec::new();
+        let mut outcomes = Vec::new();
         let total_notebooks = notebooks.len();
 
         for (idx, notebook) in notebooks.iter().enumerate() {
@@ -240,15 +450,17 @@ pub async fn run_scheduled_backup(
                 &notebook_dir,
                 notebook,
                 &data_dir_clone,
-                max_backups,
+                &retention,
+                secret.as_ref(),
                 Some(&progress_fn),
             ) {
-                Ok(backup_path) => {
-                    if let Ok(metadata) = get_backup_info(&backup_path) {
+                Ok(outcome) => {
+                    if let Ok(metadata) = get_backup_info(&outcome.path) {
                         let mut info: BackupInfo = metadata.into();
-                        info.path = backup_path.to_string_lossy().to_string();
+                        info.path = outcome.path.to_string_lossy().to_string();
                         backup_infos.push(info);
                     }
+                    outcomes.push(outcome);
                 }
                 Err(e) => {
                     log::error!("Failed to backup notebook '{}': {}", notebook.name, e);
@@ -266,11 +478,13 @@ pub async fn run_scheduled_backup(
             },
         );
 
-        backup_infos
+        (backup_infos, outcomes)
     })
     .await
     .map_err(|e| format!("Backup task failed: {}", e))?;
 
+    push_to_library_destination(&state.library_storage, &data_dir, &outcomes).await;
+
     // Update last backup time
     let mut updated_settings = load_backup_settings(&data_dir).map_err(|e| e.to_string())?;
     updated_settings.last_backup = Some(Utc::now());
@@ -322,12 +536,13 @@ impl BackupScheduler {
 
 /// Start the backup scheduler
 pub fn start_backup_scheduler(
-    storage: Arc<std::sync::Mutex<crate::storage::FileStorage>>,
+    storage: Arc<crate::storage::StorageLock>,
+    library_storage: Arc<std::sync::Mutex<LibraryStorage>>,
 ) -> BackupScheduler {
     let (tx, rx) = tokio::sync::mpsc::channel(32);
 
     tauri::async_runtime::spawn(async move {
-        backup_scheduler_loop(storage, rx).await;
+        backup_scheduler_loop(storage, library_storage, rx).await;
     });
 
     // Trigger initial load
@@ -338,7 +553,8 @@ pub fn start_backup_scheduler(
 
 /// Main backup scheduler loop
 async fn backup_scheduler_loop(
-    storage: Arc<std::sync::Mutex<crate::storage::FileStorage>>,
+    storage: Arc<crate::storage::StorageLock>,
+    library_storage: Arc<std::sync::Mutex<LibraryStorage>>,
     mut receiver: tokio::sync::mpsc::Receiver<BackupSchedulerMessage>,
 ) {
     use std::time::Duration;
@@ -380,7 +596,7 @@ async fn backup_scheduler_loop(
                 // Check if backup is due
                 if settings.enabled && is_backup_due(&settings) {
                     log::info!("Backup scheduler: Running scheduled backup");
-                    run_backup(&storage, &data_dir, &mut settings);
+                    run_backup(&storage, &library_storage, &data_dir, &mut settings).await;
                 }
 
                 // Recalculate next check
@@ -401,7 +617,7 @@ async fn backup_scheduler_loop(
                     }
                     Some(BackupSchedulerMessage::RunNow) => {
                         log::info!("Backup scheduler: Running backup now (manual trigger)");
-                        run_backup(&storage, &data_dir, &mut settings);
+                        run_backup(&storage, &library_storage, &data_dir, &mut settings).await;
                         next_check = settings.next_backup;
                     }
                     Some(BackupSchedulerMessage::Shutdown) | None => {
@@ -415,32 +631,36 @@ async fn backup_scheduler_loop(
 }
 
 /// Run backup for all configured notebooks
-fn run_backup(
-    storage: &Arc<std::sync::Mutex<crate::storage::FileStorage>>,
+async fn run_backup(
+    storage: &Arc<crate::storage::StorageLock>,
+    library_storage: &Arc<std::sync::Mutex<LibraryStorage>>,
     data_dir: &std::path::Path,
     settings: &mut BackupSettings,
 ) {
-    let storage_guard = match storage.lock() {
-        Ok(s) => s,
-        Err(e) => {
-            log::error!("Backup scheduler: Failed to lock storage: {}", e);
-            return;
+    // Get notebooks to backup. The guard isn't `Send`, so it must be gone
+    // before the push below awaits.
+    let notebooks: Vec<crate::storage::Notebook> = {
+        let storage_guard = match storage.read() {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Backup scheduler: Failed to lock storage: {}", e);
+                return;
+            }
+        };
+        if settings.notebook_ids.is_empty() {
+            storage_guard.list_notebooks().unwrap_or_default()
+        } else {
+            settings
+                .notebook_ids
+                .iter()
+                .filter_map(|id| storage_guard.get_notebook(*id).ok())
+                .collect()
         }
     };
 
-    // Get notebooks to backup
-    let notebooks: Vec<crate::storage::Notebook> = if settings.notebook_ids.is_empty() {
-        storage_guard.list_notebooks().unwrap_or_default()
-    } else {
-        settings
-            .notebook_ids
-            .iter()
-            .filter_map(|id| storage_guard.get_notebook(*id).ok())
-            .collect()
-    };
-
-    drop(storage_guard);
-
+    let retention = settings.retention_policy();
+    let secret = scheduled_backup_secret(settings);
+    let mut outcomes = Vec::new();
     let mut success_count = 0;
     let mut error_count = 0;
 
@@ -451,11 +671,13 @@ fn run_backup(
             &notebook_dir,
             &notebook,
             data_dir,
-            settings.max_backups_per_notebook,
+            &retention,
+            secret.as_ref(),
             None,
         ) {
-            Ok(_) => {
+            Ok(outcome) => {
                 log::info!("Backup scheduler: Backed up '{}'", notebook.name);
+                outcomes.push(outcome);
                 success_count += 1;
             }
             Err(e) => {
@@ -475,6 +697,8 @@ fn run_backup(
         error_count
     );
 
+    push_to_library_destination(library_storage, data_dir, &outcomes).await;
+
     // Update settings with new times
     settings.last_backup = Some(Utc::now());
     settings.next_backup = calculate_next_backup_time(settings);
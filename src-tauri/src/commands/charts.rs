@@ -0,0 +1,58 @@
+//! Chart block commands (see `crate::charts`).
+
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::charts::{self, ChartData, ChartError, ChartSource, ChartSpec};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+impl From<ChartError> for CommandError {
+    fn from(e: ChartError) -> Self {
+        CommandError {
+            message: e.to_string(),
+        }
+    }
+}
+
+/// A chart's data and the SVG publish and print use for it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputedChart {
+    #[serde(flatten)]
+    pub data: ChartData,
+    pub svg: String,
+}
+
+/// Compute what a chart block draws. Table sources are read from the saved
+/// page; CSV sources from the notebook's assets.
+#[tauri::command(rename_all = "camelCase")]
+pub fn compute_chart_data(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    source: ChartSource,
+    spec: ChartSpec,
+    title: Option<String>,
+) -> CommandResult<ComputedChart> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let page = storage.get_page(nb_id, pg_id)?;
+    let table = charts::load_source(&page, &source, &storage.notebook_assets_dir(nb_id))?;
+    drop(storage);
+
+    let data = charts::compute(&table, &spec)?;
+    let svg = charts::render_svg(&data, title.as_deref().unwrap_or_default());
+    Ok(ComputedChart { data, svg })
+}
@@ -83,9 +83,11 @@ pub struct MigratedSession {
 #[tauri::command]
 pub fn migrate_chat_sessions_to_pages(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: String,
 ) -> CommandResult<Vec<MigratedSession>> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let f_id = Uuid::parse_str(&folder_id).map_err(|e| format!("Invalid folder ID: {}", e))?;
 
@@ -93,7 +95,7 @@ pub fn migrate_chat_sessions_to_pages(
     let chat_storage = state.chat_session_storage.lock().map_err(|e| e.to_string())?;
     let session_summaries = chat_storage.list_sessions().map_err(|e| e.to_string())?;
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.write().map_err(|e| e.to_string())?;
 
     let mut migrated: Vec<MigratedSession> = Vec::new();
 
@@ -0,0 +1,188 @@
+//! Code block execution commands (see `crate::code_exec`).
+
+use std::time::Instant;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::code_exec::{
+    self, CellResult, CodeExecConfig, CodeExecError, Language, RunRequest, RunnerInfo,
+};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+impl From<CodeExecError> for CommandError {
+    fn from(e: CodeExecError) -> Self {
+        CommandError {
+            message: e.to_string(),
+        }
+    }
+}
+
+fn parse_id(id: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(id).map_err(|e| CommandError {
+        message: format!("Invalid {} ID: {}", what, e),
+    })
+}
+
+/// The languages code blocks can run in, and whether each one's
+/// interpreter is installed
+#[tauri::command]
+pub fn list_code_runners() -> Vec<RunnerInfo> {
+    code_exec::list_runners()
+}
+
+/// A notebook's code execution settings; nothing is enabled by default
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_code_exec_config(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<CodeExecConfig> {
+    let id = parse_id(&notebook_id, "notebook")?;
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    Ok(storage.get_notebook(id)?.code_exec.unwrap_or_default())
+}
+
+/// Set which languages a notebook's code blocks can run in and the sandbox
+/// they run under. Enabling no languages turns execution off.
+#[tauri::command(rename_all = "camelCase")]
+pub fn set_code_exec_config(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    mut config: CodeExecConfig,
+) -> CommandResult<CodeExecConfig> {
+    let id = parse_id(&notebook_id, "notebook")?;
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    let mut notebook = storage.get_notebook(id)?;
+    let mut enabled = Vec::new();
+    for language in config.enabled_languages {
+        if !enabled.contains(&language) {
+            enabled.push(language);
+        }
+    }
+    config.enabled_languages = enabled;
+    notebook.code_exec = if config.enabled_languages.is_empty() {
+        None
+    } else {
+        Some(config.clone())
+    };
+    notebook.updated_at = chrono::Utc::now();
+    storage.update_notebook(&notebook)?;
+    Ok(config)
+}
+
+/// Run a code block and store its outputs on the block. `language`
+/// overrides the block's own tag; `database` is the SQLite file SQL runs
+/// against, relative to the notebook's assets, and is remembered on the
+/// block. Fails if the notebook hasn't enabled the block's language.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn execute_code_block(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    block_id: String,
+    language: Option<String>,
+    database: Option<String>,
+) -> CommandResult<CellResult> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let pg_id = parse_id(&page_id, "page")?;
+
+    let lib = state.library(&window);
+    let result = tokio::task::spawn_blocking(move || -> CommandResult<CellResult> {
+        let (config, block, assets_dir) = {
+            let storage = lib.storage.read().map_err(|e| CommandError {
+                message: format!("Storage error: {}", e),
+            })?;
+            let notebook = storage.get_notebook(nb_id)?;
+            let page = storage.get_page(nb_id, pg_id)?;
+            let block = code_exec::code_block(&page, &block_id, language.as_deref())?;
+            (
+                notebook.code_exec.unwrap_or_default(),
+                block,
+                storage.notebook_assets_dir(nb_id),
+            )
+        };
+        if !config.is_enabled(block.language) {
+            return Err(CodeExecError::NotEnabled(block.language).into());
+        }
+
+        let database = database.or(block.database);
+        let database_path = match (&database, block.language) {
+            (Some(database), Language::Sql) => {
+                Some(code_exec::resolve_database(database, &assets_dir)?)
+            }
+            _ => None,
+        };
+        let runner = code_exec::runner(block.language);
+        let started = Instant::now();
+        let output = runner.run(&RunRequest {
+            code: &block.code,
+            sandbox: &config.sandbox,
+            work_dir: &assets_dir,
+            database: database_path.as_deref(),
+        })?;
+        let result = CellResult {
+            block_id: block_id.clone(),
+            language: block.language,
+            success: output.success,
+            outputs: output.outputs,
+            execution_count: block.execution_count + 1,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+
+        // The page may have been edited while the code ran
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let mut page = storage.get_page(nb_id, pg_id)?;
+        let Some(target) = page.content.blocks.iter_mut().find(|b| b.id == block_id) else {
+            return Err(CodeExecError::BlockNotFound(block_id).into());
+        };
+        let stored_database = database
+            .as_deref()
+            .filter(|_| block.language == Language::Sql);
+        code_exec::store_result(target, &result, stored_database);
+        page.updated_at = chrono::Utc::now();
+        storage.update_page(&page)?;
+        Ok(result)
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Code execution task failed: {}", e),
+    })??;
+
+    state.sync_manager.queue_page_update(nb_id, pg_id);
+    Ok(result)
+}
+
+/// Remove the stored outputs of every code block on a page; returns
+/// whether there were any
+#[tauri::command(rename_all = "camelCase")]
+pub fn clear_code_outputs(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<bool> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let pg_id = parse_id(&page_id, "page")?;
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    let mut page = storage.get_page(nb_id, pg_id)?;
+    if !code_exec::clear_outputs(&mut page) {
+        return Ok(false);
+    }
+    page.updated_at = chrono::Utc::now();
+    storage.update_page(&page)?;
+    drop(storage);
+    state.sync_manager.queue_page_update(nb_id, pg_id);
+    Ok(true)
+}
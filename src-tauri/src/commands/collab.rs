@@ -34,15 +34,17 @@ pub struct StartCollabResponse {
 #[tauri::command]
 pub async fn start_collab_session(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: StartCollabRequest,
 ) -> Result<StartCollabResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let pg_id =
         Uuid::parse_str(&request.page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
     let expiry = CollabExpiry::from_str(&request.expiry)?;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let collab_storage = state.collab_storage.clone();
     let library_storage = state.library_storage.clone();
 
@@ -54,7 +56,7 @@ pub async fn start_collab_session(
             .map_err(|e| format!("Failed to get library: {}", e))?;
         let dir = library.path.clone();
 
-        let store = storage.lock().map_err(|e| e.to_string())?;
+        let store = storage.read().map_err(|e| e.to_string())?;
         let page = store
             .get_page(nb_id, pg_id)
             .map_err(|e| format!("Failed to get page: {}", e))?;
@@ -174,8 +176,10 @@ pub struct StartScopedCollabResponse {
 #[tauri::command]
 pub async fn start_collab_session_scoped(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: StartScopedCollabRequest,
 ) -> Result<StartScopedCollabResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let scope_id =
@@ -186,7 +190,7 @@ pub async fn start_collab_session_scoped(
         return Err(format!("Invalid scope_type: {}. Must be 'section' or 'notebook'", request.scope_type));
     }
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let collab_storage = state.collab_storage.clone();
     let library_storage = state.library_storage.clone();
 
@@ -198,7 +202,7 @@ pub async fn start_collab_session_scoped(
             .map_err(|e| format!("Failed to get library: {}", e))?;
         let dir = library.path.clone();
 
-        let store = storage.lock().map_err(|e| e.to_string())?;
+        let store = storage.read().map_err(|e| e.to_string())?;
         let title = match request.scope_type.as_str() {
             "section" => {
                 let section = store
@@ -319,17 +323,19 @@ pub struct PageSummary {
 #[tauri::command]
 pub async fn list_pages_for_scope(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     scope_type: String,
     scope_id: String,
 ) -> Result<Vec<PageSummary>, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let s_id =
         Uuid::parse_str(&scope_id).map_err(|e| format!("Invalid scope ID: {}", e))?;
 
-    let storage = state.storage.clone();
-    let store = storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.clone();
+    let store = storage.read().map_err(|e| e.to_string())?;
 
     let all_pages = store
         .list_pages(nb_id)
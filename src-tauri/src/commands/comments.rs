@@ -0,0 +1,145 @@
+//! Tauri commands for page comments
+
+use std::collections::HashMap;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::comments::{Comment, CommentStorageError, CommentThread, NewComment};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+impl From<CommentStorageError> for CommandError {
+    fn from(err: CommentStorageError) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+type CommandResult<T> = Result<T, CommandError>;
+
+fn parse_uuid(value: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(value).map_err(|e| CommandError {
+        message: format!("Invalid {} ID: {}", what, e),
+    })
+}
+
+/// List comment threads on a page (open threads only unless
+/// `include_resolved` is set)
+#[tauri::command]
+pub fn list_page_comments(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    include_resolved: Option<bool>,
+) -> CommandResult<Vec<CommentThread>> {
+    let lib = state.library(&window);
+    let nb_id = parse_uuid(&notebook_id, "notebook")?;
+    let pg_id = parse_uuid(&page_id, "page")?;
+    let comments = lib.comments_storage.lock().unwrap();
+    comments
+        .list_threads(nb_id, pg_id, include_resolved.unwrap_or(false))
+        .map_err(Into::into)
+}
+
+/// Start a thread (optionally anchored to a block) or reply to one
+#[tauri::command]
+pub fn add_comment(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    request: NewComment,
+) -> CommandResult<Comment> {
+    let lib = state.library(&window);
+    let nb_id = parse_uuid(&notebook_id, "notebook")?;
+    let pg_id = parse_uuid(&page_id, "page")?;
+
+    // Make sure the page exists before attaching comments to it
+    lib.storage.read().unwrap().get_page(nb_id, pg_id)?;
+
+    let comments = lib.comments_storage.lock().unwrap();
+    comments
+        .add_comment(nb_id, pg_id, request)
+        .map_err(Into::into)
+}
+
+/// Edit a comment's text
+#[tauri::command]
+pub fn update_comment(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    comment_id: String,
+    body: String,
+) -> CommandResult<Comment> {
+    let lib = state.library(&window);
+    let nb_id = parse_uuid(&notebook_id, "notebook")?;
+    let pg_id = parse_uuid(&page_id, "page")?;
+    let c_id = parse_uuid(&comment_id, "comment")?;
+    let comments = lib.comments_storage.lock().unwrap();
+    comments
+        .update_comment(nb_id, pg_id, c_id, body)
+        .map_err(Into::into)
+}
+
+/// Delete a comment (a thread root takes its replies with it)
+#[tauri::command]
+pub fn delete_comment(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    comment_id: String,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let nb_id = parse_uuid(&notebook_id, "notebook")?;
+    let pg_id = parse_uuid(&page_id, "page")?;
+    let c_id = parse_uuid(&comment_id, "comment")?;
+    let comments = lib.comments_storage.lock().unwrap();
+    comments
+        .delete_comment(nb_id, pg_id, c_id)
+        .map_err(Into::into)
+}
+
+/// Resolve or reopen the thread containing a comment
+#[tauri::command]
+pub fn resolve_comment_thread(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    comment_id: String,
+    resolved: bool,
+    resolved_by: Option<String>,
+) -> CommandResult<Comment> {
+    let lib = state.library(&window);
+    let nb_id = parse_uuid(&notebook_id, "notebook")?;
+    let pg_id = parse_uuid(&page_id, "page")?;
+    let c_id = parse_uuid(&comment_id, "comment")?;
+    let comments = lib.comments_storage.lock().unwrap();
+    comments
+        .set_resolved(nb_id, pg_id, c_id, resolved, resolved_by)
+        .map_err(Into::into)
+}
+
+/// Unresolved thread count per page id, for pages that have any
+#[tauri::command]
+pub fn get_unresolved_comment_counts(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<HashMap<String, usize>> {
+    let lib = state.library(&window);
+    let nb_id = parse_uuid(&notebook_id, "notebook")?;
+    let comments = lib.comments_storage.lock().unwrap();
+    Ok(comments
+        .unresolved_counts(nb_id)?
+        .into_iter()
+        .map(|(page_id, count)| (page_id.to_string(), count))
+        .collect())
+}
@@ -1,15 +1,63 @@
-use chrono::Utc;
+use std::sync::Arc;
+
+use chrono::{Local, Utc};
+use serde::Deserialize;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::actions::{ActionExecutor, ActionStep, NotebookTarget, PageSelector};
 use crate::git;
-use crate::storage::{FileStorage, Page};
+use crate::storage::{FileStorage, FileStorageWriter, Page, StorageLock};
 use crate::AppState;
 
 use super::notebook::CommandError;
 
 type CommandResult<T> = Result<T, CommandError>;
 
+/// Days back to look for unfinished items when none is given
+pub const DEFAULT_ROLLOVER_LOOKBACK_DAYS: u32 = 7;
+
+/// Carrying unfinished items into a new daily note
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyNoteRollover {
+    /// How many days back to look for daily notes with unchecked items
+    pub lookback_days: Option<u32>,
+    /// Put the carried items after this section's heading instead of at the
+    /// end of the note
+    pub insert_after_section: Option<String>,
+}
+
+impl DailyNoteRollover {
+    /// The `CarryForwardItems` step that moves the unchecked checklist and
+    /// to-do list items of recent daily notes into today's, marking them
+    /// "(carried forward)" where they came from.
+    pub fn step(&self, template_id: Option<String>) -> ActionStep {
+        let lookback = self
+            .lookback_days
+            .unwrap_or(DEFAULT_ROLLOVER_LOOKBACK_DAYS)
+            .max(1);
+        ActionStep::CarryForwardItems {
+            source_selector: PageSelector {
+                notebook: Some(NotebookTarget::Current),
+                is_daily_note: Some(true),
+                daily_note_date: Some(format!("recent:{}", lookback)),
+                ..Default::default()
+            },
+            destination: NotebookTarget::Current,
+            title_template: "{{date}}".to_string(),
+            template_id,
+            find_existing: Some(PageSelector {
+                notebook: Some(NotebookTarget::Current),
+                is_daily_note: Some(true),
+                daily_note_date: Some("today".to_string()),
+                ..Default::default()
+            }),
+            insert_after_section: self.insert_after_section.clone(),
+        }
+    }
+}
+
 // ===== Standalone core functions (no Tauri dependency) =====
 
 /// Find a daily note for a date in a notebook (standalone).
@@ -32,7 +80,7 @@ pub fn find_daily_note(
 /// Create a daily note for a date (standalone, no sync/search/git side-effects).
 /// Returns the existing note if one already exists for that date.
 pub fn create_daily_note_core(
-    storage: &FileStorage,
+    storage: &FileStorageWriter,
     notebook_id: Uuid,
     date: &str,
     template_id: Option<String>,
@@ -77,6 +125,51 @@ pub fn create_daily_note_core(
     Ok(page)
 }
 
+/// Create a daily note (standalone), carrying the unfinished items of recent
+/// daily notes into it when it's today's note and didn't exist yet.
+/// Takes the storage lock itself, as the carry-forward step locks it too;
+/// `executor` must work on the same storage.
+pub fn create_daily_note_with_rollover(
+    storage: &StorageLock,
+    executor: &ActionExecutor,
+    notebook_id: Uuid,
+    date: &str,
+    template_id: Option<String>,
+    rollover: &DailyNoteRollover,
+) -> Result<Page, CommandError> {
+    let page = {
+        let storage = storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        if let Some(existing) = find_daily_note(&storage, notebook_id, date)? {
+            return Ok(existing);
+        }
+        create_daily_note_core(&storage, notebook_id, date, template_id.clone())?
+    };
+
+    // The step's date selectors are relative to today
+    if date != Local::now().format("%Y-%m-%d").to_string() {
+        return Ok(page);
+    }
+    let context = executor.execute_steps(&[rollover.step(template_id)], Some(notebook_id));
+    for error in &context.errors {
+        log::warn!("Daily note rollover: {}", error);
+    }
+    if !context.modified_pages.contains(&page.id.to_string()) {
+        return Ok(page);
+    }
+
+    log::info!(
+        "Carried unfinished items into daily note {} ({} pages updated)",
+        date,
+        context.modified_pages.len()
+    );
+    let storage = storage.read().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+    Ok(storage.get_page(notebook_id, page.id)?)
+}
+
 /// List daily notes (standalone).
 pub fn list_daily_notes_core(
     storage: &FileStorage,
@@ -125,10 +218,12 @@ pub fn list_daily_notes_core(
 #[tauri::command(rename_all = "camelCase")]
 pub fn get_daily_note(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     date: String, // "YYYY-MM-DD" format
 ) -> CommandResult<Option<Page>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -136,20 +231,44 @@ pub fn get_daily_note(
     find_daily_note(&storage, nb_id, &date)
 }
 
-/// Create a daily note for a specific date
+/// Create a daily note for a specific date. With `rollover`, a new note for
+/// today gets the unchecked items of recent daily notes carried into it.
 #[tauri::command(rename_all = "camelCase")]
 pub fn create_daily_note(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     date: String, // "YYYY-MM-DD" format
     template_id: Option<String>,
+    rollover: Option<DailyNoteRollover>,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let page = create_daily_note_core(&storage, nb_id, &date, template_id)?;
+    let page = match rollover {
+        Some(rollover) => {
+            // Runs the carry-forward step against this window's library
+            let executor = ActionExecutor::new(
+                Arc::clone(&lib.storage),
+                Arc::clone(&lib.action_storage),
+                Arc::clone(&state.python_ai),
+            );
+            create_daily_note_with_rollover(
+                &lib.storage,
+                &executor,
+                nb_id,
+                &date,
+                template_id,
+                &rollover,
+            )?
+        }
+        None => {
+            let storage = lib.storage.write().unwrap();
+            create_daily_note_core(&storage, nb_id, &date, template_id)?
+        }
+    };
 
     // Notify sync manager
     state.sync_manager.queue_page_update(nb_id, page.id);
@@ -157,6 +276,7 @@ pub fn create_daily_note(
     // Search indexing now happens in the daemon's create_page/update_page paths.
 
     // Auto-commit if git is enabled
+    let storage = lib.storage.read().unwrap();
     let notebook_path = storage.get_notebook_path(nb_id);
     if git::is_git_repo(&notebook_path) {
         let commit_message = format!("Create daily note: {}", page.title);
@@ -172,11 +292,13 @@ pub fn create_daily_note(
 #[tauri::command(rename_all = "camelCase")]
 pub fn list_daily_notes(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     start_date: Option<String>, // "YYYY-MM-DD" format
     end_date: Option<String>,   // "YYYY-MM-DD" format
 ) -> CommandResult<Vec<Page>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -193,30 +315,34 @@ pub fn list_daily_notes(
 #[tauri::command(rename_all = "camelCase")]
 pub fn get_or_create_today_daily_note(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     template_id: Option<String>,
+    rollover: Option<DailyNoteRollover>,
 ) -> CommandResult<Page> {
-    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let today = Local::now().format("%Y-%m-%d").to_string();
 
     // First try to get existing
-    let existing = get_daily_note(state.clone(), notebook_id.clone(), today.clone())?;
+    let existing = get_daily_note(state.clone(), window.clone(), notebook_id.clone(), today.clone())?;
     if let Some(page) = existing {
         return Ok(page);
     }
 
     // Create new with optional template
-    create_daily_note(state, notebook_id, today, template_id)
+    create_daily_note(state, window, notebook_id, today, template_id, rollover)
 }
 
 /// Mark an existing page as a daily note
 #[tauri::command(rename_all = "camelCase")]
 pub fn mark_as_daily_note(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     date: String, // "YYYY-MM-DD" format
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -259,10 +385,12 @@ pub fn mark_as_daily_note(
 #[tauri::command(rename_all = "camelCase")]
 pub fn unmark_daily_note(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -0,0 +1,139 @@
+//! Tauri commands for performance diagnostics and error report bundles
+
+use tauri::State;
+
+use crate::diagnostic_bundle::{
+    self, BundleContents, BundleSummary, NotebookSyncCheck, VersionInfo,
+};
+use crate::diagnostics::{self, PerformanceReport};
+use crate::logging;
+use crate::storage::FileStorage;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Timing histograms for the app process (the daemon reports its own at
+/// `GET /api/diagnostics/performance`)
+#[tauri::command]
+pub fn get_performance_report() -> PerformanceReport {
+    diagnostics::report("app")
+}
+
+/// Clear the app's timing histograms
+#[tauri::command]
+pub fn reset_performance_metrics() {
+    diagnostics::reset();
+}
+
+/// Start or stop writing every timing sample to a JSONL file under
+/// `{data_dir}/diagnostics/`. Returns the trace file path (the new one when
+/// starting, the finished one when stopping).
+#[tauri::command]
+pub fn set_performance_trace(enabled: bool) -> CommandResult<Option<String>> {
+    if !enabled {
+        return Ok(diagnostics::stop_trace().map(|p| p.to_string_lossy().to_string()));
+    }
+
+    let dir = FileStorage::default_data_dir()
+        .map_err(|e| CommandError {
+            message: format!("Failed to get data directory: {}", e),
+        })?
+        .join("diagnostics");
+    let path = diagnostics::start_trace(&dir).map_err(|e| CommandError {
+        message: format!("Failed to start trace: {}", e),
+    })?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Package recent logs, the library integrity check, a sync check of every
+/// synced notebook, the Python environment report, performance numbers and
+/// version info into one ZIP for a bug report, with secrets scrubbed.
+/// Written to `output_path`, or `{data_dir}/diagnostics/` by default.
+///
+/// The integrity check is the same as `verify_storage_integrity`, so it
+/// also repairs what it can.
+#[tauri::command]
+pub async fn create_diagnostic_bundle(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    output_path: Option<String>,
+) -> CommandResult<BundleSummary> {
+    let lib = state.library(&window);
+    let data_dir = FileStorage::default_data_dir().map_err(|e| CommandError {
+        message: format!("Failed to get data directory: {}", e),
+    })?;
+    let path = output_path
+        .map(Into::into)
+        .unwrap_or_else(|| diagnostic_bundle::default_bundle_path(&data_dir));
+
+    let (integrity, synced_notebooks) = {
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Failed to lock storage: {}", e),
+        })?;
+        let integrity = storage.verify_integrity().map_err(|e| e.to_string());
+        let synced: Vec<_> = storage
+            .list_notebooks()
+            .map(|notebooks| {
+                notebooks
+                    .into_iter()
+                    .filter(|nb| nb.sync_config.as_ref().is_some_and(|c| c.enabled))
+                    .map(|nb| nb.id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        (integrity, synced)
+    };
+
+    let mut sync = Vec::with_capacity(synced_notebooks.len());
+    for notebook_id in synced_notebooks {
+        let result = state
+            .sync_manager
+            .verify_notebook(notebook_id, &lib.storage)
+            .await;
+        sync.push(NotebookSyncCheck {
+            notebook_id,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            report: result.ok(),
+        });
+    }
+
+    let python = state
+        .python_ai
+        .lock()
+        .map(|python_ai| python_ai.check_environment())
+        .map_err(|e| format!("Failed to acquire Python AI lock: {}", e));
+
+    // Buffered lines have to reach the files before they're copied
+    let log_files = logging::logger()
+        .map(|logger| {
+            logger.flush_file();
+            logger.files()
+        })
+        .unwrap_or_default();
+
+    let contents = BundleContents {
+        version: VersionInfo::current(),
+        integrity,
+        sync,
+        python,
+        performance: diagnostics::report("app"),
+        log_files,
+    };
+    let summary =
+        tokio::task::spawn_blocking(move || diagnostic_bundle::write_bundle(&path, contents))
+            .await
+            .map_err(|e| CommandError {
+                message: format!("Bundle task failed: {}", e),
+            })?
+            .map_err(|e| CommandError {
+                message: format!("Failed to write diagnostic bundle: {}", e),
+            })?;
+    log::info!(
+        "Wrote diagnostic bundle with {} files to {}",
+        summary.files.len(),
+        summary.path
+    );
+    Ok(summary)
+}
@@ -3,6 +3,7 @@ use std::fs;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::library::LibraryContext;
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -24,8 +25,8 @@ pub struct PageAnnotation {
 }
 
 /// Get the annotations directory path for a notebook
-fn get_annotations_dir(state: &State<AppState>, notebook_id: Uuid) -> CommandResult<std::path::PathBuf> {
-    let storage = state.storage.lock().unwrap();
+fn get_annotations_dir(lib: &LibraryContext, notebook_id: Uuid) -> CommandResult<std::path::PathBuf> {
+    let storage = lib.storage.read().unwrap();
     let assets_path = storage.notebook_assets_dir(notebook_id);
     let annotations_path = assets_path.join("annotations");
 
@@ -41,14 +42,16 @@ fn get_annotations_dir(state: &State<AppState>, notebook_id: Uuid) -> CommandRes
 #[tauri::command]
 pub fn get_page_annotation(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<Option<PageAnnotation>> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     if !annotation_path.exists() {
@@ -70,15 +73,17 @@ pub fn get_page_annotation(
 #[tauri::command]
 pub fn save_page_annotation(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     annotation: PageAnnotation,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     let content = serde_json::to_string_pretty(&annotation).map_err(|e| CommandError {
@@ -96,14 +101,16 @@ pub fn save_page_annotation(
 #[tauri::command]
 pub fn delete_page_annotation(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     if annotation_path.exists() {
@@ -6,7 +6,8 @@ use uuid::Uuid;
 
 use crate::encryption::{
     create_verification_hash, derive_key, generate_salt, verify_password, Argon2Params,
-    EncryptionConfig, EncryptionStats, UnlockResult,
+    BiometricSettings, BiometricStatus, EncryptionConfig, EncryptionError, EncryptionStats,
+    UnlockResult,
 };
 use crate::storage::StorageError;
 use crate::AppState;
@@ -47,10 +48,12 @@ type CmdResult<T> = Result<T, CmdError>;
 #[tauri::command]
 pub fn enable_notebook_encryption(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     password: String,
     password_hint: Option<String>,
 ) -> CmdResult<EncryptionConfig> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -71,13 +74,13 @@ pub fn enable_notebook_encryption(
 
     // Encrypt all pages
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         storage.encrypt_all_pages(id, &key)?;
     }
 
     // Update notebook with encryption config
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         let mut notebook = storage.get_notebook(id)?;
         notebook.encryption_config = Some(config.clone());
         notebook.updated_at = chrono::Utc::now();
@@ -100,16 +103,18 @@ pub fn enable_notebook_encryption(
 #[tauri::command]
 pub fn disable_notebook_encryption(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     password: String,
 ) -> CmdResult<()> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
     // Get notebook and verify it's encrypted
     let notebook = {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.read().unwrap();
         storage.get_notebook(id)?
     };
 
@@ -122,21 +127,22 @@ pub fn disable_notebook_encryption(
 
     // Decrypt all pages
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         storage.decrypt_all_pages(id, &key)?;
     }
 
     // Remove encryption config
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         let mut notebook = storage.get_notebook(id)?;
         notebook.encryption_config = None;
         notebook.updated_at = chrono::Utc::now();
         storage.update_notebook(&notebook)?;
     }
 
-    // Remove key from memory
+    // Remove key from memory and the OS keychain
     state.encryption_manager.lock_notebook(id);
+    state.encryption_manager.disable_biometric(id)?;
 
     Ok(())
 }
@@ -145,16 +151,18 @@ pub fn disable_notebook_encryption(
 #[tauri::command]
 pub fn unlock_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     password: String,
 ) -> CmdResult<UnlockResult> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
     // Get notebook and verify it's encrypted
     let notebook = {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.read().unwrap();
         storage.get_notebook(id)?
     };
 
@@ -168,6 +176,7 @@ pub fn unlock_notebook(
     // Verify password and get key
     match verify_password(&password, &config.salt, &config.verification_hash) {
         Ok(key) => {
+            state.encryption_manager.record_password_unlock(id, &key)?;
             state.encryption_manager.unlock_notebook(id, key);
             Ok(UnlockResult::success())
         }
@@ -198,12 +207,17 @@ pub fn is_notebook_unlocked(state: State<AppState>, notebook_id: String) -> CmdR
 
 /// Check if a notebook is encrypted
 #[tauri::command]
-pub fn is_notebook_encrypted(state: State<AppState>, notebook_id: String) -> CmdResult<bool> {
+pub fn is_notebook_encrypted(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CmdResult<bool> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let storage = state.storage.lock().unwrap();
+    let storage = lib.storage.read().unwrap();
     let notebook = storage.get_notebook(id)?;
 
     Ok(notebook.is_encrypted())
@@ -213,13 +227,15 @@ pub fn is_notebook_encrypted(state: State<AppState>, notebook_id: String) -> Cmd
 #[tauri::command]
 pub fn get_notebook_password_hint(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CmdResult<Option<String>> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let storage = state.storage.lock().unwrap();
+    let storage = lib.storage.read().unwrap();
     let notebook = storage.get_notebook(id)?;
 
     Ok(notebook.encryption_hint().map(|s| s.to_string()))
@@ -229,11 +245,13 @@ pub fn get_notebook_password_hint(
 #[tauri::command]
 pub fn change_notebook_password(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     old_password: String,
     new_password: String,
     new_hint: Option<String>,
 ) -> CmdResult<EncryptionConfig> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -246,7 +264,7 @@ pub fn change_notebook_password(
 
     // Get notebook and verify it's encrypted
     let notebook = {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.read().unwrap();
         storage.get_notebook(id)?
     };
 
@@ -264,7 +282,7 @@ pub fn change_notebook_password(
 
     // Re-encrypt all pages with new key
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         storage.reencrypt_all_pages(id, &old_key, &new_key)?;
     }
 
@@ -273,14 +291,17 @@ pub fn change_notebook_password(
 
     // Update notebook
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         let mut notebook = storage.get_notebook(id)?;
         notebook.encryption_config = Some(new_config.clone());
         notebook.updated_at = chrono::Utc::now();
         storage.update_notebook(&notebook)?;
     }
 
-    // Update key in memory
+    // Update key in memory (and in the keychain, if biometric unlock is on)
+    state
+        .encryption_manager
+        .record_password_unlock(id, &new_key)?;
     state.encryption_manager.unlock_notebook(id, new_key);
 
     Ok(new_config)
@@ -293,16 +314,145 @@ pub fn get_unlocked_notebooks(state: State<AppState>) -> CmdResult<Vec<String>>
     Ok(ids.into_iter().map(|id| id.to_string()).collect())
 }
 
+// ===== Biometric Unlock Commands =====
+
+/// Get whether a notebook can be unlocked with TouchID / Windows Hello
+#[tauri::command]
+pub fn get_notebook_biometric_status(
+    state: State<AppState>,
+    notebook_id: String,
+) -> CmdResult<BiometricStatus> {
+    let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    Ok(state.encryption_manager.biometric_status(id))
+}
+
+/// Enable biometric unlock for an encrypted notebook
+///
+/// The password is verified and the derived key is stored in the OS
+/// keychain.
+#[tauri::command]
+pub fn enable_notebook_biometric_unlock(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    password: String,
+) -> CmdResult<BiometricStatus> {
+    let lib = state.library(&window);
+    let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    let notebook = {
+        let storage = lib.storage.read().unwrap();
+        storage.get_notebook(id)?
+    };
+
+    let config = notebook.encryption_config.ok_or_else(|| CmdError {
+        message: "Notebook is not encrypted".to_string(),
+    })?;
+
+    let key = verify_password(&password, &config.salt, &config.verification_hash)?;
+    state.encryption_manager.enable_biometric(id, &key)?;
+
+    Ok(state.encryption_manager.biometric_status(id))
+}
+
+/// Disable biometric unlock for a notebook
+#[tauri::command]
+pub fn disable_notebook_biometric_unlock(
+    state: State<AppState>,
+    notebook_id: String,
+) -> CmdResult<()> {
+    let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    state.encryption_manager.disable_biometric(id)?;
+    Ok(())
+}
+
+/// Unlock an encrypted notebook with TouchID / Windows Hello
+///
+/// Returns a failed `UnlockResult` when the user has to fall back to the
+/// password (cancelled prompt, not enrolled, or the require-password
+/// interval has passed).
+#[tauri::command]
+pub async fn unlock_notebook_biometric(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CmdResult<UnlockResult> {
+    let lib = state.library(&window);
+    let id = Uuid::parse_str(&notebook_id).map_err(|e| CmdError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    let notebook = {
+        let storage = lib.storage.read().unwrap();
+        storage.get_notebook(id)?
+    };
+
+    let config = match notebook.encryption_config {
+        Some(c) if c.enabled => c,
+        _ => {
+            return Ok(UnlockResult::failure("Notebook is not encrypted"));
+        }
+    };
+
+    // The system prompt blocks until the user responds
+    let manager = state.encryption_manager.clone();
+    let reason = format!("unlock \"{}\"", notebook.name);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        manager.unlock_notebook_biometric(id, &config.verification_hash, &reason)
+    })
+    .await
+    .map_err(|e| CmdError {
+        message: format!("Task join error: {}", e),
+    })?;
+
+    match result {
+        Ok(()) => Ok(UnlockResult::success()),
+        Err(EncryptionError::Keychain(e)) => {
+            log::warn!("Biometric unlock of {} failed: {}", id, e);
+            Ok(UnlockResult::failure("Enter your password to unlock"))
+        }
+        Err(e) => Ok(UnlockResult::failure(e.to_string())),
+    }
+}
+
+/// Get biometric unlock settings
+#[tauri::command]
+pub fn get_biometric_settings(state: State<AppState>) -> CmdResult<BiometricSettings> {
+    Ok(state.encryption_manager.biometric_settings())
+}
+
+/// Update biometric unlock settings
+#[tauri::command]
+pub fn update_biometric_settings(
+    state: State<AppState>,
+    settings: BiometricSettings,
+) -> CmdResult<BiometricSettings> {
+    state
+        .encryption_manager
+        .set_biometric_settings(settings.clone())?;
+    Ok(settings)
+}
+
 // ===== Library Encryption Commands =====
 
 /// Enable encryption for a library
 #[tauri::command]
 pub fn enable_library_encryption(
     state: State<AppState>,
+    window: tauri::Window,
     library_id: String,
     password: String,
     password_hint: Option<String>,
 ) -> CmdResult<EncryptionConfig> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&library_id).map_err(|e| CmdError {
         message: format!("Invalid library ID: {}", e),
     })?;
@@ -323,12 +473,12 @@ pub fn enable_library_encryption(
 
     // Get all notebooks in this library and encrypt their pages
     let notebooks = {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.read().unwrap();
         storage.list_notebooks()?
     };
 
     for notebook in &notebooks {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         storage.encrypt_all_pages(notebook.id, &key)?;
     }
 
@@ -352,9 +502,11 @@ pub fn enable_library_encryption(
 #[tauri::command]
 pub fn disable_library_encryption(
     state: State<AppState>,
+    window: tauri::Window,
     library_id: String,
     password: String,
 ) -> CmdResult<()> {
+    let lib = state.library(&window);
     let id = Uuid::parse_str(&library_id).map_err(|e| CmdError {
         message: format!("Invalid library ID: {}", e),
     })?;
@@ -376,12 +528,12 @@ pub fn disable_library_encryption(
 
     // Get all notebooks and decrypt their pages
     let notebooks = {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.read().unwrap();
         storage.list_notebooks()?
     };
 
     for notebook in &notebooks {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         storage.decrypt_all_pages(notebook.id, &key)?;
     }
 
@@ -31,16 +31,18 @@ pub fn preview_evernote_enex_cmd(enex_path: String) -> CommandResult<EvernoteImp
 #[tauri::command]
 pub fn import_evernote_enex_cmd(
     state: State<AppState>,
+    window: tauri::Window,
     enex_path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = Path::new(&enex_path);
 
     if !path.exists() {
         return Err("ENEX file not found".to_string());
     }
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
     let notebooks_dir = storage.notebooks_base_dir();
 
     // Import the ENEX file
@@ -1,15 +1,21 @@
 //! External Editor Commands
 //!
-//! Tauri commands for opening pages in external editors.
+//! Tauri commands for opening pages and whole notebooks in external editors.
+
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use chrono::Utc;
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
-use crate::external_editor::{get_known_editors, EditSession, EditorConfig};
-use crate::markdown::import_markdown_to_page;
-use crate::storage::Page;
+use crate::external_editor::{
+    apply_edited_markdown, available_editors, detect_editors, load_settings, save_settings,
+    ConflictResolution, EditSession, EditorConfig, EditorSettings, NotebookEditSession,
+    NotebookSyncReport,
+};
+use crate::storage::FileStorage;
 use crate::AppState;
 
 /// Error type for external editor commands
@@ -44,27 +50,49 @@ impl From<crate::storage::StorageError> for ExternalEditorCommandError {
 
 type CommandResult<T> = Result<T, ExternalEditorCommandError>;
 
-/// Get list of known external editors
+fn data_dir() -> CommandResult<PathBuf> {
+    FileStorage::default_data_dir().map_err(|e| ExternalEditorCommandError::new(&e.to_string()))
+}
+
+/// Get the editors to offer: the user's own and the installed built-in ones
 #[tauri::command]
 pub fn get_external_editors() -> Vec<EditorConfig> {
-    get_known_editors()
+    match data_dir() {
+        Ok(dir) => available_editors(&dir),
+        Err(_) => detect_editors(),
+    }
+}
+
+/// Get the editors the user added on this device
+#[tauri::command]
+pub fn get_custom_editors() -> CommandResult<Vec<EditorConfig>> {
+    Ok(load_settings(&data_dir()?).custom)
+}
+
+/// Replace the editors the user added on this device
+#[tauri::command]
+pub fn save_custom_editors(editors: Vec<EditorConfig>) -> CommandResult<()> {
+    save_settings(&data_dir()?, &EditorSettings { custom: editors })?;
+    Ok(())
 }
 
 /// Open a page in an external editor
 #[tauri::command]
 pub fn open_page_in_editor(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     editor_config: Option<EditorConfig>,
 ) -> CommandResult<String> {
+    let lib = state.library(&window);
     let notebook_uuid = Uuid::parse_str(&notebook_id)
         .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
     let page_uuid = Uuid::parse_str(&page_id)
         .map_err(|_| ExternalEditorCommandError::new("Invalid page ID"))?;
 
     // Get the page
-    let storage = state.storage.lock()
+    let storage = lib.storage.read()
         .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock storage: {}", e)))?;
 
     let page = storage.get_page(notebook_uuid, page_uuid)?;
@@ -119,9 +147,11 @@ pub fn get_external_file_content(
 #[tauri::command]
 pub fn sync_from_external_editor(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let notebook_uuid = Uuid::parse_str(&notebook_id)
         .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
     let page_uuid = Uuid::parse_str(&page_id)
@@ -135,51 +165,14 @@ pub fn sync_from_external_editor(
     let markdown_content = editor_manager.read_temp_file(page_uuid)?;
 
     // Get storage
-    let mut storage = state.storage.lock()
+    let storage = lib.storage.write()
         .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock storage: {}", e)))?;
 
     // Get existing page to preserve metadata
     let existing_page = storage.get_page(notebook_uuid, page_uuid)?;
 
-    // Import markdown to get updated content
-    let imported_page = import_markdown_to_page(
-        &markdown_content,
-        notebook_uuid,
-        &existing_page.title,
-    );
-
-    // Create updated page with existing metadata but new content
-    let updated_page = Page {
-        id: existing_page.id,
-        notebook_id: existing_page.notebook_id,
-        title: imported_page.title,
-        content: imported_page.content,
-        tags: imported_page.tags,
-        folder_id: existing_page.folder_id,
-        parent_page_id: existing_page.parent_page_id,
-        section_id: existing_page.section_id,
-        is_archived: existing_page.is_archived,
-        is_cover: existing_page.is_cover,
-        position: existing_page.position,
-        system_prompt: existing_page.system_prompt,
-        system_prompt_mode: existing_page.system_prompt_mode,
-        ai_model: existing_page.ai_model,
-        page_type: existing_page.page_type,
-        source_file: existing_page.source_file,
-        storage_mode: existing_page.storage_mode,
-        file_extension: existing_page.file_extension,
-        last_file_sync: existing_page.last_file_sync,
-        template_id: existing_page.template_id,
-        deleted_at: existing_page.deleted_at,
-        is_favorite: existing_page.is_favorite,
-        color: existing_page.color.clone(),
-        is_daily_note: existing_page.is_daily_note,
-        daily_note_date: existing_page.daily_note_date.clone(),
-        plugin_page_type: existing_page.plugin_page_type.clone(),
-        plugin_data: existing_page.plugin_data.clone(),
-        created_at: existing_page.created_at,
-        updated_at: chrono::Utc::now(),
-    };
+    // Apply the new content, keeping the page's metadata
+    let updated_page = apply_edited_markdown(&existing_page, &markdown_content);
 
     // Update the page
     storage.update_page(&updated_page)?;
@@ -249,3 +242,146 @@ pub fn cleanup_external_edit_sessions(
     editor_manager.cleanup_old_sessions()?;
     Ok(())
 }
+
+/// Export a whole notebook to a folder of Markdown files, open the folder in
+/// an editor (VS Code by default) and sync files back as they are saved.
+/// Each sync that changes pages or finds conflicts is emitted as an
+/// `external-notebook-sync` event.
+#[tauri::command]
+pub fn open_notebook_in_editor(
+    app: AppHandle,
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    editor_config: Option<EditorConfig>,
+) -> CommandResult<NotebookEditSession> {
+    let lib = state.library(&window);
+    let notebook_uuid = Uuid::parse_str(&notebook_id)
+        .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
+
+    let editor_manager = state.external_editor.lock()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock editor manager: {}", e)))?;
+
+    let running = editor_manager.get_notebook_session(notebook_uuid).is_some();
+    let session = {
+        let storage = lib.storage.read()
+            .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock storage: {}", e)))?;
+        storage.get_notebook(notebook_uuid)?;
+        editor_manager.start_notebook_session(&storage, notebook_uuid)?
+    };
+
+    if !running {
+        let sync_manager = Arc::clone(&state.sync_manager);
+        editor_manager.watch_notebook_session(
+            notebook_uuid,
+            Arc::clone(&lib.storage),
+            move |report: NotebookSyncReport| {
+                for page_id in &report.pages_updated {
+                    sync_manager.queue_page_update(report.notebook_id, *page_id);
+                }
+                if let Err(e) = app.emit("external-notebook-sync", &report) {
+                    log::warn!("Failed to emit external-notebook-sync: {}", e);
+                }
+            },
+        )?;
+    }
+
+    // Waiting on a folder window would hold the command until it closes
+    let config = EditorConfig {
+        wait: false,
+        ..editor_config.unwrap_or_else(EditorConfig::vs_code)
+    };
+    editor_manager.open_in_editor(&session.dir, &config)?;
+
+    Ok(session)
+}
+
+/// Sync every file of a notebook edit session now
+#[tauri::command]
+pub fn sync_notebook_from_external_editor(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<NotebookSyncReport> {
+    let lib = state.library(&window);
+    let notebook_uuid = Uuid::parse_str(&notebook_id)
+        .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
+
+    let editor_manager = state.external_editor.lock()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock editor manager: {}", e)))?;
+    let storage = lib.storage.write()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock storage: {}", e)))?;
+
+    let report = editor_manager.sync_notebook_session(&storage, notebook_uuid)?;
+    for page_id in &report.pages_updated {
+        state.sync_manager.queue_page_update(notebook_uuid, *page_id);
+    }
+    Ok(report)
+}
+
+/// Settle a conflict in a notebook edit session by keeping the edited file
+/// or the page
+#[tauri::command]
+pub fn resolve_external_edit_conflict(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    path: String,
+    resolution: ConflictResolution,
+) -> CommandResult<NotebookEditSession> {
+    let lib = state.library(&window);
+    let notebook_uuid = Uuid::parse_str(&notebook_id)
+        .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
+
+    let editor_manager = state.external_editor.lock()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock editor manager: {}", e)))?;
+    let storage = lib.storage.write()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock storage: {}", e)))?;
+
+    editor_manager.resolve_notebook_conflict(&storage, notebook_uuid, &path, resolution)?;
+    drop(storage);
+    if resolution == ConflictResolution::KeepFile {
+        if let Some(file) = editor_manager
+            .get_notebook_session(notebook_uuid)
+            .and_then(|s| s.files.get(&path).cloned())
+        {
+            state.sync_manager.queue_page_update(notebook_uuid, file.page_id);
+        }
+    }
+
+    editor_manager
+        .get_notebook_session(notebook_uuid)
+        .ok_or_else(|| ExternalEditorCommandError::new("Notebook edit session ended"))
+}
+
+/// Get the edit session for a notebook, if one is running
+#[tauri::command]
+pub fn get_notebook_edit_session(
+    state: State<AppState>,
+    notebook_id: String,
+) -> CommandResult<Option<NotebookEditSession>> {
+    let notebook_uuid = Uuid::parse_str(&notebook_id)
+        .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
+
+    let editor_manager = state.external_editor.lock()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock editor manager: {}", e)))?;
+
+    Ok(editor_manager.get_notebook_session(notebook_uuid))
+}
+
+/// End a notebook edit session and delete its folder. Sync first to keep
+/// edits made since the last save was picked up.
+#[tauri::command]
+pub fn end_notebook_edit_session(
+    state: State<AppState>,
+    notebook_id: String,
+) -> CommandResult<()> {
+    let notebook_uuid = Uuid::parse_str(&notebook_id)
+        .map_err(|_| ExternalEditorCommandError::new("Invalid notebook ID"))?;
+
+    let editor_manager = state.external_editor.lock()
+        .map_err(|e| ExternalEditorCommandError::new(&format!("Failed to lock editor manager: {}", e)))?;
+
+    editor_manager.end_notebook_session(notebook_uuid)?;
+    Ok(())
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use crate::external_sources::{
     CreateExternalSourceRequest, ExternalFileFormat, ExternalSource, ResolvedFileInfo,
-    UpdateExternalSourceRequest,
+    UpdateExternalSourceRequest, WatchConfig,
 };
 use crate::AppState;
 
@@ -106,6 +106,26 @@ pub fn update_external_source(
         .map_err(|e| ExternalSourcesCommandError::new(&format!("Failed to update source: {}", e)))
 }
 
+/// Turn watch-folder mode on (with `watch`) or off (without) for a source.
+/// The watcher picks up the change on its next rescan.
+#[tauri::command]
+pub fn set_external_source_watch(
+    state: State<AppState>,
+    source_id: String,
+    watch: Option<WatchConfig>,
+) -> CommandResult<ExternalSource> {
+    let mut storage = state.external_sources_storage.lock().map_err(|e| {
+        ExternalSourcesCommandError::new(&format!("Failed to lock external sources storage: {}", e))
+    })?;
+
+    let uuid = Uuid::parse_str(&source_id)
+        .map_err(|_| ExternalSourcesCommandError::new(&format!("Invalid source ID: {}", source_id)))?;
+
+    storage
+        .set_watch(uuid, watch)
+        .map_err(|e| ExternalSourcesCommandError::new(&format!("Failed to update watch mode: {}", e)))
+}
+
 /// Delete an external source
 #[tauri::command]
 pub fn delete_external_source(state: State<AppState>, source_id: String) -> CommandResult<()> {
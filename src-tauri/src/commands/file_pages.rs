@@ -26,13 +26,15 @@ pub struct ImportFileResult {
 #[tauri::command]
 pub fn import_file_as_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     file_path: String,
     storage_mode: String,
     folder_id: Option<String>,
     section_id: Option<String>,
 ) -> CommandResult<ImportFileResult> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -94,10 +96,12 @@ pub fn import_file_as_page(
 pub fn get_file_path(
     app: tauri::AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<String> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -143,10 +147,12 @@ pub struct ReadableHtmlResponse {
 pub fn get_readable_html(
     app: tauri::AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<ReadableHtmlResponse> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -203,10 +209,12 @@ pub fn get_readable_html(
 #[tauri::command]
 pub fn check_linked_file_modified(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<bool> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -235,10 +243,12 @@ pub fn check_linked_file_modified(
 #[tauri::command]
 pub fn mark_linked_file_synced(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -286,10 +296,12 @@ pub fn get_supported_page_extensions() -> Vec<String> {
 #[tauri::command]
 pub fn delete_file_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -350,11 +362,13 @@ pub fn check_python_execution_available(
 #[tauri::command(rename_all = "camelCase")]
 pub fn duplicate_database_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     include_rows: bool,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
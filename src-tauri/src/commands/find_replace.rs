@@ -0,0 +1,114 @@
+//! Find and replace command (see `crate::find_replace`).
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::find_replace::{
+    plan_replacements, ReplaceOptions, ReplacePreview, ReplaceScope, Replacer,
+};
+use crate::library::snapshots::{self, LibrarySnapshot};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Result of `search_and_replace`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchReplaceResult {
+    #[serde(flatten)]
+    pub preview: ReplacePreview,
+    /// Snapshot taken before the changes were written; roll back to it to
+    /// undo them. None for a dry run or when nothing matched.
+    pub snapshot: Option<LibrarySnapshot>,
+}
+
+/// Replace `query` with `replacement` in the text blocks of the pages in
+/// `scope`. A dry run returns the per-page preview without writing;
+/// otherwise a library snapshot is taken first so the whole replacement can
+/// be undone. `page_ids` limits it to pages picked from a preview.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn search_and_replace(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    query: String,
+    replacement: String,
+    scope: ReplaceScope,
+    regex: bool,
+    dry_run: bool,
+    case_sensitive: Option<bool>,
+    include_code: Option<bool>,
+    page_ids: Option<Vec<Uuid>>,
+) -> CommandResult<SearchReplaceResult> {
+    let options = ReplaceOptions {
+        regex,
+        case_sensitive: case_sensitive.unwrap_or(false),
+        include_code: include_code.unwrap_or(false),
+    };
+    let replacer = Replacer::new(&query, &replacement, options).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+    let only_pages: Option<HashSet<Uuid>> = page_ids.map(|ids| ids.into_iter().collect());
+
+    let lib = state.library(&window);
+    let result =
+        tokio::task::spawn_blocking(move || -> CommandResult<SearchReplaceResult> {
+            let storage = lib.storage.write().map_err(|e| CommandError {
+                message: format!("Storage error: {}", e),
+            })?;
+            let plan = plan_replacements(&storage, &scope, &replacer, only_pages.as_ref())
+                .map_err(|e| CommandError {
+                    message: e.to_string(),
+                })?;
+            if dry_run || plan.pages.is_empty() {
+                return Ok(SearchReplaceResult {
+                    preview: plan.preview,
+                    snapshot: None,
+                });
+            }
+
+            let snapshot = {
+                let _vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+                    message: format!("Lock error: {}", e),
+                })?;
+                let label = format!("Before replacing \"{}\"", query);
+                snapshots::create_snapshot(storage.notebooks_dir(), &label).map_err(|e| {
+                    CommandError {
+                        message: format!("Failed to snapshot library: {}", e),
+                    }
+                })?
+            };
+            let now = chrono::Utc::now();
+            for mut page in plan.pages {
+                page.updated_at = now;
+                page.content.time = Some(now.timestamp_millis());
+                storage.update_page(&page)?;
+            }
+            Ok(SearchReplaceResult {
+                preview: plan.preview,
+                snapshot: Some(snapshot),
+            })
+        })
+        .await
+        .map_err(|e| CommandError {
+            message: format!("Replace task failed: {}", e),
+        })??;
+
+    if result.snapshot.is_some() {
+        for page in &result.preview.pages {
+            state
+                .sync_manager
+                .queue_page_update(page.notebook_id, page.page_id);
+        }
+        log::info!(
+            "Replaced {} matches in {} pages",
+            result.preview.total_matches,
+            result.preview.pages.len()
+        );
+    }
+    Ok(result)
+}
@@ -1,11 +1,23 @@
 //! Tauri commands for flashcard operations
 
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
 use tauri::State;
 use uuid::Uuid;
 
+use crate::flashcards::analytics::{DeckAnalytics, LeechCard, DEFAULT_LEECH_THRESHOLD};
+use crate::flashcards::bundle::{self, DeckBundleInfo, DeckImportResult, ImportCollision};
+use crate::flashcards::cloze::{self, ClozeRange};
 use crate::flashcards::{
-    CardState, CardType, CardWithState, Deck, Flashcard, FlashcardStorageError, ReviewStats,
+    BlockCardSync, CardSource, CardState, CardType, CardWithState, Deck, Flashcard,
+    FlashcardStorageError, ReadingItem, ReadingSource, ReviewQueueItem, ReviewStats,
+    DEFAULT_READING_PRIORITY,
 };
+use crate::library::LibraryContext;
+use crate::publish::html::block_plain_text;
+use crate::storage::{EditorBlock, FileStorage};
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -24,8 +36,13 @@ type CommandResult<T> = Result<T, CommandError>;
 
 /// List all decks in a notebook
 #[tauri::command]
-pub fn list_decks(state: State<AppState>, notebook_id: String) -> CommandResult<Vec<Deck>> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+pub fn list_decks(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<Deck>> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -36,10 +53,12 @@ pub fn list_decks(state: State<AppState>, notebook_id: String) -> CommandResult<
 #[tauri::command]
 pub fn get_deck(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: String,
 ) -> CommandResult<Deck> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -53,12 +72,14 @@ pub fn get_deck(
 #[tauri::command]
 pub fn create_deck(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     name: String,
     description: Option<String>,
     color: Option<String>,
 ) -> CommandResult<Deck> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -71,6 +92,7 @@ pub fn create_deck(
 #[tauri::command]
 pub fn update_deck(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: String,
     name: Option<String>,
@@ -79,7 +101,8 @@ pub fn update_deck(
     new_cards_per_day: Option<i32>,
     reviews_per_day: Option<i32>,
 ) -> CommandResult<Deck> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -115,10 +138,12 @@ pub fn update_deck(
 #[tauri::command]
 pub fn delete_deck(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: String,
 ) -> CommandResult<()> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -136,10 +161,12 @@ pub fn delete_deck(
 #[tauri::command]
 pub fn list_cards(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: String,
 ) -> CommandResult<Vec<Flashcard>> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -153,10 +180,12 @@ pub fn list_cards(
 #[tauri::command]
 pub fn get_card(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     card_id: String,
 ) -> CommandResult<Flashcard> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -170,6 +199,7 @@ pub fn get_card(
 #[tauri::command]
 pub fn create_card(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: String,
     front: String,
@@ -177,7 +207,8 @@ pub fn create_card(
     card_type: Option<String>,
     tags: Option<Vec<String>>,
 ) -> CommandResult<Flashcard> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -196,18 +227,52 @@ pub fn create_card(
         .map_err(Into::into)
 }
 
-/// Create a card from an editor block
+/// Text of a block as cards made from it see it
+fn block_text(block: &EditorBlock) -> String {
+    block_plain_text(block).replace("&nbsp;", " ")
+}
+
+/// Current text of every block of a page, by block id; empty when the page
+/// is gone
+fn page_block_texts(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    page_id: Uuid,
+) -> HashMap<String, String> {
+    match storage.get_page(notebook_id, page_id) {
+        Ok(page) => page
+            .content
+            .blocks
+            .iter()
+            .map(|block| (block.id.clone(), block_text(block)))
+            .collect(),
+        Err(e) => {
+            log::info!("Flashcard source page {} unavailable: {}", page_id, e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Create cards from an editor block. Basic and reversible cards take
+/// `front` and `back` as they are. Cloze cards take `front` as the cloze
+/// text: either already marked up with `{{c1::...}}`, or plain with the
+/// `cloze_ranges` (character offsets into it) to hide; one card is made per
+/// cloze, with `back` as extra text on each. The block's current text is
+/// kept so the cards can follow later edits (see `sync_block_cards`).
 #[tauri::command]
 pub fn create_card_from_block(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: String,
     page_id: String,
     block_id: String,
     front: String,
     back: String,
-) -> CommandResult<Flashcard> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    card_type: Option<String>,
+    cloze_ranges: Option<Vec<ClozeRange>>,
+) -> CommandResult<Vec<Flashcard>> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -218,15 +283,114 @@ pub fn create_card_from_block(
         message: format!("Invalid page ID: {}", e),
     })?;
 
+    let source_text = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        page_block_texts(&storage, nb_id, pg_id).remove(&block_id)
+    };
+
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    if card_type.as_deref() != Some("cloze") {
+        let mut card = flashcard_storage.create_card_from_block(
+            nb_id,
+            dk_id,
+            pg_id,
+            block_id,
+            front,
+            back,
+            source_text,
+        )?;
+        if card_type.as_deref() == Some("reversible") {
+            card.card_type = CardType::Reversible;
+            flashcard_storage.update_card(nb_id, &card)?;
+        }
+        return Ok(vec![card]);
+    }
+
+    let cloze_text = match cloze_ranges {
+        Some(ranges) if !ranges.is_empty() => cloze::insert_clozes(&front, &ranges),
+        _ => front,
+    };
+    if cloze::cloze_numbers(&cloze_text).is_empty() {
+        return Err(CommandError {
+            message: "Select some text to hide, or mark it as {{c1::...}}".to_string(),
+        });
+    }
+    let extra = Some(back).filter(|b| !b.trim().is_empty());
     flashcard_storage
-        .create_card_from_block(nb_id, dk_id, pg_id, block_id, front, back)
+        .create_cloze_cards_from_block(
+            nb_id,
+            dk_id,
+            pg_id,
+            block_id,
+            cloze_text,
+            extra,
+            source_text,
+        )
         .map_err(Into::into)
 }
 
+/// Update cards made from blocks after the blocks were edited: cloze cards
+/// follow the new text when their answers are still there, other cards (and
+/// cards whose block was deleted) are flagged `sourceChanged`. Without
+/// `page_id` every page with block cards is checked.
+#[tauri::command]
+pub fn sync_block_cards(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: Option<String>,
+) -> CommandResult<BlockCardSync> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let page_ids: Vec<Uuid> = match page_id {
+        Some(id) => vec![Uuid::parse_str(&id).map_err(|e| CommandError {
+            message: format!("Invalid page ID: {}", e),
+        })?],
+        None => {
+            let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+            let mut ids: Vec<Uuid> = flashcard_storage
+                .list_all_cards(nb_id)?
+                .into_iter()
+                .filter_map(|card| match card.source {
+                    CardSource::BlockRef { page_id, .. } => Some(page_id),
+                    CardSource::Standalone => None,
+                })
+                .collect();
+            ids.sort();
+            ids.dedup();
+            ids
+        }
+    };
+
+    let texts: Vec<(Uuid, HashMap<String, String>)> = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        page_ids
+            .into_iter()
+            .map(|id| (id, page_block_texts(&storage, nb_id, id)))
+            .collect()
+    };
+
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let mut report = BlockCardSync::default();
+    for (page_id, block_texts) in texts {
+        let synced = flashcard_storage.sync_block_cards(nb_id, page_id, &block_texts)?;
+        report.updated.extend(synced.updated);
+        report.flagged.extend(synced.flagged);
+    }
+    Ok(report)
+}
+
 /// Update a card
 #[tauri::command]
 pub fn update_card(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     card_id: String,
     front: Option<String>,
@@ -234,7 +398,8 @@ pub fn update_card(
     card_type: Option<String>,
     tags: Option<Vec<String>>,
 ) -> CommandResult<Flashcard> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -271,10 +436,12 @@ pub fn update_card(
 #[tauri::command]
 pub fn delete_card(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     card_id: String,
 ) -> CommandResult<()> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -292,10 +459,12 @@ pub fn delete_card(
 #[tauri::command]
 pub fn get_due_cards(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: Option<String>,
 ) -> CommandResult<Vec<CardWithState>> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -316,11 +485,13 @@ pub fn get_due_cards(
 #[tauri::command]
 pub fn submit_review(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     card_id: String,
     rating: i32,
 ) -> CommandResult<CardState> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -337,10 +508,12 @@ pub fn submit_review(
 #[tauri::command]
 pub fn get_review_stats(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     deck_id: Option<String>,
 ) -> CommandResult<ReviewStats> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -357,14 +530,81 @@ pub fn get_review_stats(
         .map_err(Into::into)
 }
 
+/// Days of review history in the analytics heatmap by default
+const DEFAULT_HEATMAP_DAYS: i64 = 365;
+
+/// Get review heatmap, retention, ease and workload analytics for a deck
+/// (or all decks)
+#[tauri::command]
+pub fn get_deck_analytics(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    deck_id: Option<String>,
+    days: Option<i64>,
+    leech_threshold: Option<i32>,
+) -> CommandResult<DeckAnalytics> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let dk_id = deck_id
+        .map(|id| {
+            Uuid::parse_str(&id).map_err(|e| CommandError {
+                message: format!("Invalid deck ID: {}", e),
+            })
+        })
+        .transpose()?;
+
+    flashcard_storage
+        .get_deck_analytics(
+            nb_id,
+            dk_id,
+            days.unwrap_or(DEFAULT_HEATMAP_DAYS),
+            leech_threshold.unwrap_or(DEFAULT_LEECH_THRESHOLD),
+        )
+        .map_err(Into::into)
+}
+
+/// List cards forgotten at least `threshold` times (default 8), most
+/// lapses first
+#[tauri::command]
+pub fn list_leech_cards(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    deck_id: Option<String>,
+    threshold: Option<i32>,
+) -> CommandResult<Vec<LeechCard>> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let dk_id = deck_id
+        .map(|id| {
+            Uuid::parse_str(&id).map_err(|e| CommandError {
+                message: format!("Invalid deck ID: {}", e),
+            })
+        })
+        .transpose()?;
+
+    flashcard_storage
+        .list_leech_cards(nb_id, dk_id, threshold.unwrap_or(DEFAULT_LEECH_THRESHOLD))
+        .map_err(Into::into)
+}
+
 /// Get the state for a specific card
 #[tauri::command]
 pub fn get_card_state(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     card_id: String,
 ) -> CommandResult<CardState> {
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -381,12 +621,14 @@ pub fn get_card_state(
 #[tauri::command]
 pub fn preview_review_intervals(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     card_id: String,
 ) -> CommandResult<[i32; 4]> {
+    let lib = state.library(&window);
     use crate::flashcards::algorithm::preview_intervals;
 
-    let flashcard_storage = state.flashcard_storage.lock().unwrap();
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -397,3 +639,342 @@ pub fn preview_review_intervals(
     let card_state = flashcard_storage.get_card_state(nb_id, cd_id)?;
     Ok(preview_intervals(&card_state))
 }
+
+// ==================== Incremental Reading Commands ====================
+
+/// Reading items mixed into a review session after this many cards
+const DEFAULT_CARDS_PER_READING: usize = 5;
+
+/// Schedule a page, or an extract from it, for re-reading. Without `text`
+/// the whole page is scheduled.
+#[tauri::command]
+pub fn add_ir_extract(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    text: Option<String>,
+    block_ids: Option<Vec<String>>,
+    title: Option<String>,
+    priority: Option<u8>,
+) -> CommandResult<ReadingItem> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+
+    let page_title = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        storage.get_page(nb_id, pg_id)?.title
+    };
+
+    let source = match text.filter(|t| !t.trim().is_empty()) {
+        Some(text) => ReadingSource::Extract {
+            page_id: pg_id,
+            block_ids: block_ids.unwrap_or_default(),
+            text,
+        },
+        None => ReadingSource::Page { page_id: pg_id },
+    };
+    let item = ReadingItem::new(
+        title.unwrap_or(page_title),
+        source,
+        priority.unwrap_or(DEFAULT_READING_PRIORITY),
+    );
+
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    flashcard_storage.save_reading_item(nb_id, &item)?;
+    Ok(item)
+}
+
+/// List all incremental reading items in a notebook
+#[tauri::command]
+pub fn list_ir_items(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<ReadingItem>> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    flashcard_storage
+        .list_reading_items(nb_id)
+        .map_err(Into::into)
+}
+
+/// Remove a page or extract from incremental reading
+#[tauri::command]
+pub fn delete_ir_item(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    item_id: String,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let item_id = Uuid::parse_str(&item_id).map_err(|e| CommandError {
+        message: format!("Invalid reading item ID: {}", e),
+    })?;
+    flashcard_storage
+        .delete_reading_item(nb_id, item_id)
+        .map_err(Into::into)
+}
+
+/// Get a review session: due cards mixed with due reading items
+#[tauri::command]
+pub fn get_ir_queue(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    deck_id: Option<String>,
+    cards_per_reading: Option<usize>,
+) -> CommandResult<Vec<ReviewQueueItem>> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let dk_id = deck_id
+        .map(|id| {
+            Uuid::parse_str(&id).map_err(|e| CommandError {
+                message: format!("Invalid deck ID: {}", e),
+            })
+        })
+        .transpose()?;
+
+    flashcard_storage
+        .get_review_queue(
+            nb_id,
+            dk_id,
+            cards_per_reading.unwrap_or(DEFAULT_CARDS_PER_READING),
+        )
+        .map_err(Into::into)
+}
+
+/// Grade a re-reading (1-4: Again, Hard, Good, Easy) and schedule the next.
+/// `priority` changes the item's priority; `done` stops scheduling it.
+#[tauri::command]
+pub fn grade_ir_item(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    item_id: String,
+    rating: i32,
+    priority: Option<u8>,
+    done: Option<bool>,
+) -> CommandResult<ReadingItem> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let item_id = Uuid::parse_str(&item_id).map_err(|e| CommandError {
+        message: format!("Invalid reading item ID: {}", e),
+    })?;
+
+    flashcard_storage
+        .grade_reading_item(nb_id, item_id, rating, priority, done.unwrap_or(false))
+        .map_err(Into::into)
+}
+
+// ==================== Shared Deck Commands ====================
+
+/// The shared decks folder of the window's library. The default folder is
+/// created when missing; a chosen one is not, as it may be on a drive that
+/// isn't there right now.
+fn shared_decks_dir(state: &AppState, window: &tauri::Window) -> CommandResult<PathBuf> {
+    let library = state.window_library(window)?;
+    let dir = library.shared_decks_path();
+    if library.shared_decks_folder.is_none() {
+        fs::create_dir_all(&dir).map_err(|e| CommandError {
+            message: format!("Failed to create shared decks folder: {}", e),
+        })?;
+    }
+    Ok(dir)
+}
+
+fn notebook_assets_dir(lib: &LibraryContext, nb_id: Uuid) -> CommandResult<PathBuf> {
+    let storage = lib.storage.read().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+    Ok(storage.notebook_assets_dir(nb_id))
+}
+
+/// Where to put a bundle of `deck` in `dir`: the bundle already there for
+/// the same deck is replaced, other bundles with the name are kept
+fn shared_bundle_path(dir: &Path, deck: &Deck) -> PathBuf {
+    let file_name = bundle::bundle_file_name(&deck.name);
+    let (stem, _) = file_name.rsplit_once('.').unwrap_or((&file_name, ""));
+    std::iter::once(dir.join(&file_name))
+        .chain((2..).map(|n| dir.join(format!("{}-{}.{}", stem, n, bundle::BUNDLE_EXTENSION))))
+        .find(|path| {
+            !path.exists() || bundle::read_bundle(path).is_ok_and(|b| b.deck.id == deck.id)
+        })
+        .expect("unbounded range")
+}
+
+/// Get the shared decks folder of the current library
+#[tauri::command]
+pub fn get_shared_decks_folder(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<String> {
+    Ok(shared_decks_dir(&state, &window)?
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// Set the shared decks folder of the current library (e.g. a folder synced
+/// with others); `None` goes back to `shared-decks/` in the library
+#[tauri::command]
+pub fn set_shared_decks_folder(
+    state: State<AppState>,
+    window: tauri::Window,
+    path: Option<String>,
+) -> CommandResult<String> {
+    let library = state.window_library(&window)?;
+    let folder = path.filter(|p| !p.trim().is_empty()).map(PathBuf::from);
+    if let Some(folder) = &folder {
+        if !folder.is_dir() {
+            return Err(CommandError {
+                message: format!("Not a folder: {}", folder.display()),
+            });
+        }
+    }
+    state
+        .library_storage
+        .lock()
+        .unwrap()
+        .update_library_shared_decks_folder(library.id, folder)
+        .map_err(|e| CommandError {
+            message: format!("Failed to update library: {}", e),
+        })?;
+    Ok(shared_decks_dir(&state, &window)?
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// Export a deck as a `.nousdeck` bundle to `path`, or into the shared
+/// decks folder when no path is given. Scheduling is left out unless
+/// `include_scheduling` is set.
+#[tauri::command]
+pub fn export_deck_bundle(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    deck_id: String,
+    path: Option<String>,
+    include_scheduling: Option<bool>,
+) -> CommandResult<DeckBundleInfo> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let dk_id = Uuid::parse_str(&deck_id).map_err(|e| CommandError {
+        message: format!("Invalid deck ID: {}", e),
+    })?;
+    let assets_dir = notebook_assets_dir(&lib, nb_id)?;
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let dir = shared_decks_dir(&state, &window)?;
+            let deck = lib.flashcard_storage.lock().unwrap().get_deck(nb_id, dk_id)?;
+            shared_bundle_path(&dir, &deck)
+        }
+    };
+
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    bundle::export_deck(
+        &flashcard_storage,
+        nb_id,
+        dk_id,
+        &assets_dir,
+        &path,
+        include_scheduling.unwrap_or(false),
+    )?;
+    bundle::inspect_bundle(&flashcard_storage, nb_id, &path).map_err(Into::into)
+}
+
+/// Describe a `.nousdeck` bundle and the deck it would collide with
+#[tauri::command]
+pub fn inspect_deck_bundle(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    path: String,
+) -> CommandResult<DeckBundleInfo> {
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    bundle::inspect_bundle(&flashcard_storage, nb_id, Path::new(&path)).map_err(Into::into)
+}
+
+/// List the bundles in the shared decks folder, with what each would
+/// collide with in the notebook. Unreadable files are left out.
+#[tauri::command]
+pub fn list_shared_decks(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<DeckBundleInfo>> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let dir = shared_decks_dir(&state, &window)?;
+    let lib = state.library(&window);
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+
+    Ok(bundle::list_bundle_paths(&dir)
+        .into_iter()
+        .filter_map(|path| match bundle::inspect_bundle(&flashcard_storage, nb_id, &path) {
+            Ok(info) => Some(info),
+            Err(e) => {
+                log::warn!("Shared decks: skipping {:?}: {}", path, e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Import a `.nousdeck` bundle. `collision` says what to do when the deck
+/// is already there (default: merge); `with_scheduling` (default true) keeps
+/// the bundle's scheduling for the cards it adds, when it has any.
+#[tauri::command]
+pub fn import_deck_bundle(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    path: String,
+    collision: Option<ImportCollision>,
+    with_scheduling: Option<bool>,
+) -> CommandResult<DeckImportResult> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let assets_dir = notebook_assets_dir(&lib, nb_id)?;
+
+    let flashcard_storage = lib.flashcard_storage.lock().unwrap();
+    bundle::import_deck(
+        &flashcard_storage,
+        nb_id,
+        &assets_dir,
+        Path::new(&path),
+        collision.unwrap_or_default(),
+        with_scheduling.unwrap_or(true),
+    )
+    .map_err(Into::into)
+}
@@ -4,7 +4,7 @@ use tauri::State;
 use uuid::Uuid;
 
 use crate::git;
-use crate::storage::Folder;
+use crate::storage::{Folder, PageContainer, PageSort};
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -13,8 +13,13 @@ type CommandResult<T> = Result<T, CommandError>;
 
 /// List all folders in a notebook
 #[tauri::command]
-pub fn list_folders(state: State<AppState>, notebook_id: String) -> CommandResult<Vec<Folder>> {
-    let storage = state.storage.lock().unwrap();
+pub fn list_folders(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<Folder>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -25,10 +30,12 @@ pub fn list_folders(state: State<AppState>, notebook_id: String) -> CommandResul
 #[tauri::command]
 pub fn get_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: String,
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -42,12 +49,14 @@ pub fn get_folder(
 #[tauri::command]
 pub fn create_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     name: String,
     parent_id: Option<String>,
     section_id: Option<String>,
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -90,14 +99,17 @@ pub fn create_folder(
 #[tauri::command]
 pub fn update_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: String,
     name: Option<String>,
     parent_id: Option<Option<String>>, // None = don't change, Some(None) = move to root, Some(Some(id)) = move to folder
     color: Option<Option<String>>,     // None = don't change, Some(None) = clear color, Some(Some(c)) = set color
     section_id: Option<Option<String>>, // None = don't change, Some(None) = no section, Some(Some(id)) = set section
+    page_sort_by: Option<Option<String>>, // None = don't change, Some(None) = use the section/notebook sort, Some(Some(sort)) = override
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -149,6 +161,15 @@ pub fn update_folder(
         folder.section_id = new_section_uuid;
     }
 
+    if let Some(new_sort) = page_sort_by {
+        if let Some(sort) = new_sort.as_deref().filter(|s| PageSort::parse(s).is_none()) {
+            return Err(CommandError {
+                message: format!("Unknown page sort: {}", sort),
+            });
+        }
+        folder.page_sort_by = new_sort;
+    }
+
     folder.updated_at = chrono::Utc::now();
     storage.update_folder(&folder)?;
 
@@ -168,11 +189,13 @@ pub fn update_folder(
 #[tauri::command]
 pub fn delete_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: String,
     move_pages_to: Option<String>,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -205,12 +228,14 @@ pub fn delete_folder(
 #[tauri::command]
 pub fn move_page_to_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     folder_id: Option<String>,
     position: Option<i32>,
 ) -> CommandResult<crate::storage::Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -243,10 +268,12 @@ pub fn move_page_to_folder(
 #[tauri::command]
 pub fn archive_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<crate::storage::Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -272,11 +299,13 @@ pub fn archive_page(
 #[tauri::command]
 pub fn unarchive_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     target_folder_id: Option<String>,
 ) -> CommandResult<crate::storage::Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -309,10 +338,12 @@ pub fn unarchive_page(
 #[tauri::command]
 pub fn archive_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: String,
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -338,10 +369,12 @@ pub fn archive_folder(
 #[tauri::command]
 pub fn unarchive_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: String,
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -367,11 +400,13 @@ pub fn unarchive_folder(
 #[tauri::command]
 pub fn reorder_folders(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     parent_id: Option<String>,
     folder_ids: Vec<String>,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -400,11 +435,13 @@ pub fn reorder_folders(
 #[tauri::command]
 pub fn reorder_pages(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     folder_id: Option<String>,
     page_ids: Vec<String>,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -429,16 +466,41 @@ pub fn reorder_pages(
         .map_err(Into::into)
 }
 
+/// Pin a page to the top of its folder, section or notebook root, or unpin it
+#[tauri::command]
+pub fn set_page_pinned(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    pinned: bool,
+) -> CommandResult<PageContainer> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+
+    storage
+        .set_page_pinned(nb_id, pg_id, pinned)
+        .map_err(Into::into)
+}
+
 /// Move a folder (and all descendants + pages) to another notebook
 #[tauri::command]
 pub fn move_folder_to_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     source_notebook_id: String,
     folder_id: String,
     target_notebook_id: String,
     target_parent_folder_id: Option<String>,
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
 
     let src_nb_id = Uuid::parse_str(&source_notebook_id).map_err(|e| CommandError {
         message: format!("Invalid source notebook ID: {}", e),
@@ -484,9 +546,11 @@ pub fn move_folder_to_notebook(
 #[tauri::command]
 pub fn ensure_archive_folder(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<Folder> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -0,0 +1,173 @@
+//! Generated media commands (see `crate::generated_media`).
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+use crate::generated_media::{
+    GeneratedMedia, GeneratedMediaKind, GeneratedMediaRegistry, MediaCleanupReport,
+};
+use crate::AppState;
+
+use super::infographic::{self, InfographicRequest};
+use super::notebook::CommandError;
+use super::video_generate::{self, StudyVideoRequest};
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// A generated media entry as the frontend sees it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedMediaInfo {
+    #[serde(flatten)]
+    pub media: GeneratedMedia,
+    /// Absolute paths of the output files that still exist
+    pub paths: Vec<String>,
+}
+
+fn parse_id(id: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(id).map_err(|e| CommandError {
+        message: format!("Invalid {} ID: {}", what, e),
+    })
+}
+
+/// Source page ids passed along with a generation request
+pub(crate) fn parse_page_ids(page_ids: Option<Vec<String>>) -> CommandResult<Vec<Uuid>> {
+    page_ids
+        .unwrap_or_default()
+        .iter()
+        .map(|id| parse_id(id, "page"))
+        .collect()
+}
+
+fn info(registry: &GeneratedMediaRegistry, media: GeneratedMedia) -> GeneratedMediaInfo {
+    let paths = media
+        .files
+        .iter()
+        .map(|file| registry.file_path(file))
+        .filter(|path| path.exists())
+        .map(|path| path.to_string_lossy().into_owned())
+        .collect();
+    GeneratedMediaInfo { media, paths }
+}
+
+fn open_registry(
+    state: &AppState,
+    window: &tauri::Window,
+    nb_id: Uuid,
+) -> CommandResult<GeneratedMediaRegistry> {
+    let lib = state.library(window);
+    let storage = lib.storage.read().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+    Ok(GeneratedMediaRegistry::open(storage.notebook_assets_dir(nb_id))?)
+}
+
+/// Infographics and study videos generated in a notebook, or only those made
+/// from `page_id`, newest first
+#[tauri::command]
+pub fn list_generated_media(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: Option<String>,
+) -> CommandResult<Vec<GeneratedMediaInfo>> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let page_id = page_id.map(|id| parse_id(&id, "page")).transpose()?;
+    let registry = open_registry(&state, &window, nb_id)?;
+    Ok(registry
+        .list(page_id)
+        .into_iter()
+        .map(|media| info(&registry, media))
+        .collect())
+}
+
+/// Generate a media entry again with the options it was first generated
+/// with. The new output is recorded as regenerated from the old one; with
+/// `replace` the old entry and its files are deleted.
+#[tauri::command]
+pub async fn regenerate_media(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    media_id: String,
+    replace: Option<bool>,
+) -> CommandResult<GeneratedMediaInfo> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let media_id = parse_id(&media_id, "media")?;
+    let lib = state.library(&window);
+    let previous = open_registry(&state, &window, nb_id)?.get(media_id)?;
+
+    let invalid_options = |e: serde_json::Error| CommandError {
+        message: format!("Stored generation options are unreadable: {}", e),
+    };
+    let media = match previous.kind {
+        GeneratedMediaKind::Infographic => {
+            let request: InfographicRequest =
+                serde_json::from_value(previous.options.clone()).map_err(invalid_options)?;
+            infographic::generate_and_record(
+                state.python_ai.clone(),
+                &lib,
+                nb_id,
+                previous.page_ids.clone(),
+                request,
+                Some(media_id),
+            )
+            .await?
+            .1
+        }
+        GeneratedMediaKind::StudyVideo => {
+            let request: StudyVideoRequest =
+                serde_json::from_value(previous.options.clone()).map_err(invalid_options)?;
+            video_generate::generate_and_record(
+                &app_handle,
+                &state,
+                &lib,
+                nb_id,
+                previous.page_ids.clone(),
+                request,
+                Some(media_id),
+            )
+            .await?
+            .1
+        }
+    };
+
+    let storage = lib.storage.write().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+    let mut registry = GeneratedMediaRegistry::open(storage.notebook_assets_dir(nb_id))?;
+    if replace.unwrap_or(false) {
+        registry.remove(media_id)?;
+    }
+    Ok(info(&registry, media))
+}
+
+/// Delete generated media nothing refers to any more: outputs whose source
+/// pages were all deleted, and generator files missing from the registry.
+/// With `dry_run` only reports what would be removed.
+#[tauri::command]
+pub fn cleanup_generated_media(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    dry_run: Option<bool>,
+) -> CommandResult<MediaCleanupReport> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let lib = state.library(&window);
+    let storage = lib.storage.write().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+
+    // Trashed pages still exist and can come back; only gone pages count
+    let existing_pages: HashSet<Uuid> = storage
+        .list_page_summaries(nb_id)?
+        .into_iter()
+        .map(|page| page.id)
+        .collect();
+    let mut registry = GeneratedMediaRegistry::open(storage.notebook_assets_dir(nb_id))?;
+    Ok(registry.cleanup(&existing_pages, dry_run.unwrap_or(false))?)
+}
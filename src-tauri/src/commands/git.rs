@@ -40,7 +40,7 @@ fn get_notebook_path(state: &State<AppState>, notebook_id: &str) -> CommandResul
 
     let storage = state
         .storage
-        .lock()
+        .read()
         .map_err(|e| GitCommandError::new(&format!("Failed to lock storage: {}", e)))?;
 
     Ok(storage.get_notebook_path(uuid))
@@ -14,30 +14,41 @@ type CommandResult<T> = Result<T, String>;
 
 /// List all goals
 #[tauri::command]
-pub fn list_goals(state: State<AppState>) -> CommandResult<Vec<Goal>> {
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+pub fn list_goals(state: State<AppState>, window: tauri::Window) -> CommandResult<Vec<Goal>> {
+    let lib = state.library(&window);
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.list_goals().map_err(|e| e.to_string())
 }
 
 /// List active (non-archived) goals
 #[tauri::command]
-pub fn list_active_goals(state: State<AppState>) -> CommandResult<Vec<Goal>> {
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+pub fn list_active_goals(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<Goal>> {
+    let lib = state.library(&window);
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.list_active_goals().map_err(|e| e.to_string())
 }
 
 /// Get a goal by ID
 #[tauri::command]
-pub fn get_goal(state: State<AppState>, id: String) -> CommandResult<Goal> {
+pub fn get_goal(state: State<AppState>, window: tauri::Window, id: String) -> CommandResult<Goal> {
+    let lib = state.library(&window);
     let goal_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid goal ID: {}", e))?;
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.get_goal(goal_id).map_err(|e| e.to_string())
 }
 
 /// Create a new goal
 #[tauri::command]
-pub fn create_goal(state: State<AppState>, request: CreateGoalRequest) -> CommandResult<Goal> {
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+pub fn create_goal(
+    state: State<AppState>,
+    window: tauri::Window,
+    request: CreateGoalRequest,
+) -> CommandResult<Goal> {
+    let lib = state.library(&window);
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.create_goal(request).map_err(|e| e.to_string())
 }
 
@@ -45,35 +56,48 @@ pub fn create_goal(state: State<AppState>, request: CreateGoalRequest) -> Comman
 #[tauri::command]
 pub fn update_goal(
     state: State<AppState>,
+    window: tauri::Window,
     id: String,
     updates: UpdateGoalRequest,
 ) -> CommandResult<Goal> {
+    let lib = state.library(&window);
     let goal_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid goal ID: {}", e))?;
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.update_goal(goal_id, updates).map_err(|e| e.to_string())
 }
 
 /// Archive a goal
 #[tauri::command]
-pub fn archive_goal(state: State<AppState>, id: String) -> CommandResult<Goal> {
+pub fn archive_goal(
+    state: State<AppState>,
+    window: tauri::Window,
+    id: String,
+) -> CommandResult<Goal> {
+    let lib = state.library(&window);
     let goal_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid goal ID: {}", e))?;
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.archive_goal(goal_id).map_err(|e| e.to_string())
 }
 
 /// Delete a goal
 #[tauri::command]
-pub fn delete_goal(state: State<AppState>, id: String) -> CommandResult<()> {
+pub fn delete_goal(state: State<AppState>, window: tauri::Window, id: String) -> CommandResult<()> {
+    let lib = state.library(&window);
     let goal_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid goal ID: {}", e))?;
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.delete_goal(goal_id).map_err(|e| e.to_string())
 }
 
 /// Get statistics for a goal
 #[tauri::command]
-pub fn get_goal_stats(state: State<AppState>, id: String) -> CommandResult<GoalStats> {
+pub fn get_goal_stats(
+    state: State<AppState>,
+    window: tauri::Window,
+    id: String,
+) -> CommandResult<GoalStats> {
+    let lib = state.library(&window);
     let goal_id = Uuid::parse_str(&id).map_err(|e| format!("Invalid goal ID: {}", e))?;
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.calculate_stats(goal_id).map_err(|e| e.to_string())
 }
 
@@ -81,16 +105,18 @@ pub fn get_goal_stats(state: State<AppState>, id: String) -> CommandResult<GoalS
 #[tauri::command]
 pub fn record_goal_progress(
     state: State<AppState>,
+    window: tauri::Window,
     goal_id: String,
     date: String,
     completed: bool,
 ) -> CommandResult<GoalProgress> {
+    let lib = state.library(&window);
     let gid = Uuid::parse_str(&goal_id).map_err(|e| format!("Invalid goal ID: {}", e))?;
     let parsed_date =
         NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| format!("Invalid date: {}", e))?;
 
     let progress = GoalProgress::new_manual(gid, parsed_date, completed);
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     let result = goals.record_progress(progress).map_err(|e| e.to_string())?;
 
     // Dispatch plugin event
@@ -113,10 +139,12 @@ pub fn record_goal_progress(
 #[tauri::command]
 pub fn get_goal_progress(
     state: State<AppState>,
+    window: tauri::Window,
     goal_id: String,
     start_date: String,
     end_date: String,
 ) -> CommandResult<Vec<GoalProgress>> {
+    let lib = state.library(&window);
     let goal_id = Uuid::parse_str(&goal_id).map_err(|e| format!("Invalid goal ID: {}", e))?;
     let start = NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
         .map_err(|e| format!("Invalid start date: {}", e))?;
@@ -125,14 +153,14 @@ pub fn get_goal_progress(
 
     // Get the goal to check if it's auto-tracked
     let goal = {
-        let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+        let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
         goals.get_goal(goal_id).map_err(|e| e.to_string())?
     };
 
     // For auto-tracked goals, detect and backfill missing progress
     if goal.tracking_type == TrackingType::Auto && goal.auto_detect.is_some() {
         let existing_progress = {
-            let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+            let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
             goals
                 .get_progress_range(goal_id, start, end)
                 .map_err(|e| e.to_string())?
@@ -143,12 +171,12 @@ pub fn get_goal_progress(
             existing_progress.iter().map(|p| p.date).collect();
 
         let detector = {
-            let d = GoalDetector::new(state.storage.clone());
+            let d = GoalDetector::new(lib.storage.clone());
             #[cfg(feature = "plugins")]
             let d = d.with_plugins(state.plugin_host.clone());
             d
         };
-        let goals_storage = state.goals_storage.lock().map_err(|e| e.to_string())?;
+        let goals_storage = lib.goals_storage.lock().map_err(|e| e.to_string())?;
 
         // Check each date in the range
         let mut current_date = start;
@@ -169,7 +197,7 @@ pub fn get_goal_progress(
             .get_progress_range(goal_id, start, end)
             .map_err(|e| e.to_string())
     } else {
-        let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+        let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
         goals
             .get_progress_range(goal_id, start, end)
             .map_err(|e| e.to_string())
@@ -178,16 +206,20 @@ pub fn get_goal_progress(
 
 /// Check auto-detected goals for today
 #[tauri::command]
-pub fn check_auto_goals(state: State<AppState>) -> CommandResult<Vec<GoalProgress>> {
+pub fn check_auto_goals(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<GoalProgress>> {
+    let lib = state.library(&window);
     // Get all active goals
     let active_goals = {
-        let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+        let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
         goals.list_active_goals().map_err(|e| e.to_string())?
     };
 
     // Create detector and check goals
     let detector = {
-        let d = GoalDetector::new(state.storage.clone());
+        let d = GoalDetector::new(lib.storage.clone());
         #[cfg(feature = "plugins")]
         let d = d.with_plugins(state.plugin_host.clone());
         d
@@ -197,7 +229,7 @@ pub fn check_auto_goals(state: State<AppState>) -> CommandResult<Vec<GoalProgres
         .map_err(|e| e.to_string())?;
 
     // Save detected progress
-    let goals_storage = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals_storage = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     let mut saved = Vec::new();
     for progress in detected {
         match goals_storage.record_progress(progress.clone()) {
@@ -211,18 +243,27 @@ pub fn check_auto_goals(state: State<AppState>) -> CommandResult<Vec<GoalProgres
 
 /// Get goals summary
 #[tauri::command]
-pub fn get_goals_summary(state: State<AppState>) -> CommandResult<GoalsSummary> {
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+pub fn get_goals_summary(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<GoalsSummary> {
+    let lib = state.library(&window);
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
     goals.get_summary().map_err(|e| e.to_string())
 }
 
 /// Toggle goal completion for today
 #[tauri::command]
-pub fn toggle_goal_today(state: State<AppState>, goal_id: String) -> CommandResult<GoalProgress> {
+pub fn toggle_goal_today(
+    state: State<AppState>,
+    window: tauri::Window,
+    goal_id: String,
+) -> CommandResult<GoalProgress> {
+    let lib = state.library(&window);
     let gid = Uuid::parse_str(&goal_id).map_err(|e| format!("Invalid goal ID: {}", e))?;
     let today = chrono::Utc::now().date_naive();
 
-    let goals = state.goals_storage.lock().map_err(|e| e.to_string())?;
+    let goals = lib.goals_storage.lock().map_err(|e| e.to_string())?;
 
     // Check current progress for today
     let current_progress = goals.get_progress_range(gid, today, today).map_err(|e| e.to_string())?;
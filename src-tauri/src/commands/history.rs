@@ -0,0 +1,168 @@
+//! Tauri commands for page view history, the review feed and search history
+
+use chrono::{Local, Utc};
+use tauri::State;
+use uuid::Uuid;
+
+use crate::history::{
+    review::review_candidates, review_feed, PageView, ReviewItem, SearchHistoryEntry,
+    SearchHistorySettings,
+};
+use crate::search::{suggest_queries, SearchSuggestion, SuggestionSources};
+use crate::AppState;
+
+type CommandResult<T> = Result<T, String>;
+
+const DEFAULT_FEED_SIZE: usize = 5;
+const DEFAULT_SEARCH_HISTORY_SIZE: usize = 20;
+const DEFAULT_SUGGESTION_COUNT: usize = 8;
+
+fn parse_uuid(s: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(s).map_err(|e| format!("Invalid {} ID: {}", what, e))
+}
+
+/// Record that a page was opened
+#[tauri::command(rename_all = "camelCase")]
+pub fn record_page_view(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<PageView> {
+    let lib = state.library(&window);
+    let notebook_id = parse_uuid(&notebook_id, "notebook")?;
+    let page_id = parse_uuid(&page_id, "page")?;
+    let mut history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    history
+        .record_view(notebook_id, page_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Today's selection of old notes to revisit. The same pages come back all
+/// day and a new selection is made tomorrow. Archived, encrypted and
+/// opted-out notebooks are left out.
+#[tauri::command]
+pub fn get_review_feed(
+    state: State<AppState>,
+    window: tauri::Window,
+    limit: Option<usize>,
+) -> CommandResult<Vec<ReviewItem>> {
+    let lib = state.library(&window);
+    let pages = {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        let notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
+        notebooks
+            .iter()
+            .filter(|nb| !nb.archived && !nb.is_encrypted() && !nb.exclude_from_review)
+            .flat_map(|nb| storage.list_pages(nb.id).unwrap_or_default())
+            .filter(|page| page.deleted_at.is_none() && !page.is_archived)
+            .collect::<Vec<_>>()
+    };
+    let history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    let candidates = review_candidates(pages, |page_id| {
+        history.get_view(page_id).map(|v| v.last_viewed_at)
+    });
+
+    Ok(review_feed(
+        candidates,
+        Local::now().date_naive(),
+        Utc::now(),
+        limit.unwrap_or(DEFAULT_FEED_SIZE),
+    ))
+}
+
+/// Add a query to the search history (a no-op while it's turned off)
+#[tauri::command]
+pub fn record_search(
+    state: State<AppState>,
+    window: tauri::Window,
+    query: String,
+) -> CommandResult<Option<SearchHistoryEntry>> {
+    let lib = state.library(&window);
+    let mut history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    history.record_search(&query).map_err(|e| e.to_string())
+}
+
+/// Past searches, most recent first
+#[tauri::command]
+pub fn get_search_history(
+    state: State<AppState>,
+    window: tauri::Window,
+    limit: Option<usize>,
+) -> CommandResult<Vec<SearchHistoryEntry>> {
+    let lib = state.library(&window);
+    let history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    Ok(history.search_history(limit.unwrap_or(DEFAULT_SEARCH_HISTORY_SIZE)))
+}
+
+/// Forget one past search
+#[tauri::command]
+pub fn remove_search_history_entry(
+    state: State<AppState>,
+    window: tauri::Window,
+    query: String,
+) -> CommandResult<bool> {
+    let lib = state.library(&window);
+    let mut history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    history.remove_search(&query).map_err(|e| e.to_string())
+}
+
+/// Forget all past searches; returns how many there were
+#[tauri::command]
+pub fn clear_search_history(state: State<AppState>, window: tauri::Window) -> CommandResult<usize> {
+    let lib = state.library(&window);
+    let mut history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    history.clear_search_history().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_search_history_settings(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<SearchHistorySettings> {
+    let lib = state.library(&window);
+    let history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    Ok(history.search_history_settings().clone())
+}
+
+/// Change what the search history keeps. Turning it off clears it.
+#[tauri::command]
+pub fn set_search_history_settings(
+    state: State<AppState>,
+    window: tauri::Window,
+    settings: SearchHistorySettings,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let mut history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    history
+        .set_search_history_settings(settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Completions for a partly typed query from past searches, page titles
+/// and tags. Pages of locked notebooks aren't suggested.
+#[tauri::command]
+pub fn get_search_suggestions(
+    state: State<AppState>,
+    window: tauri::Window,
+    prefix: String,
+    limit: Option<usize>,
+) -> CommandResult<Vec<SearchSuggestion>> {
+    let lib = state.library(&window);
+    let sources = if prefix.trim().is_empty() {
+        SuggestionSources::default()
+    } else {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        SuggestionSources::collect(&storage, |nb| {
+            !nb.is_encrypted() || state.encryption_manager.is_notebook_unlocked(nb.id)
+        })
+        .map_err(|e| e.to_string())?
+    };
+    let history = lib.history_storage.lock().map_err(|e| e.to_string())?;
+    Ok(suggest_queries(
+        &prefix,
+        &history.search_history(usize::MAX),
+        &sources,
+        limit.unwrap_or(DEFAULT_SUGGESTION_COUNT),
+    ))
+}
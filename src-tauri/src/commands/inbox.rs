@@ -1,5 +1,6 @@
 //! Tauri commands for inbox operations
 
+use chrono::{DateTime, Utc};
 use tauri::State;
 use uuid::Uuid;
 
@@ -14,8 +15,13 @@ type CommandResult<T> = Result<T, String>;
 
 /// Capture a new item to the inbox
 #[tauri::command]
-pub fn inbox_capture(state: State<AppState>, request: CaptureRequest) -> CommandResult<InboxItem> {
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+pub fn inbox_capture(
+    state: State<AppState>,
+    window: tauri::Window,
+    request: CaptureRequest,
+) -> CommandResult<InboxItem> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
     let item = inbox.capture(request).map_err(|e| e.to_string())?;
 
     // Dispatch plugin event
@@ -35,22 +41,71 @@ pub fn inbox_capture(state: State<AppState>, request: CaptureRequest) -> Command
 
 /// List all inbox items
 #[tauri::command]
-pub fn inbox_list(state: State<AppState>) -> CommandResult<Vec<InboxItem>> {
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+pub fn inbox_list(state: State<AppState>, window: tauri::Window) -> CommandResult<Vec<InboxItem>> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
     inbox.list_items().map_err(|e| e.to_string())
 }
 
 /// List unprocessed inbox items
 #[tauri::command]
-pub fn inbox_list_unprocessed(state: State<AppState>) -> CommandResult<Vec<InboxItem>> {
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+pub fn inbox_list_unprocessed(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<InboxItem>> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
     inbox.list_unprocessed().map_err(|e| e.to_string())
 }
 
+/// List snoozed inbox items, soonest to resurface first
+#[tauri::command]
+pub fn inbox_list_snoozed(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<InboxItem>> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
+    inbox.list_snoozed().map_err(|e| e.to_string())
+}
+
+/// Hide an inbox item until a natural-language time ("monday 9am", "in 3
+/// days"). It resurfaces with a notification when the snooze ends.
+#[tauri::command]
+pub fn snooze_inbox_item(
+    state: State<AppState>,
+    window: tauri::Window,
+    id: Uuid,
+    until: String,
+) -> CommandResult<InboxItem> {
+    let lib = state.library(&window);
+    let until_at: DateTime<Utc> = super::parse_reminder_time(until.clone())?;
+    if until_at <= Utc::now() {
+        return Err(format!("'{}' is in the past", until.trim()));
+    }
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
+    inbox
+        .snooze_item(id, Some(until_at))
+        .map_err(|e| e.to_string())
+}
+
+/// Bring a snoozed inbox item back right away
+#[tauri::command]
+pub fn unsnooze_inbox_item(
+    state: State<AppState>,
+    window: tauri::Window,
+    id: Uuid,
+) -> CommandResult<InboxItem> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
+    inbox.snooze_item(id, None).map_err(|e| e.to_string())
+}
+
 /// Get inbox summary
 #[tauri::command]
-pub fn inbox_summary(state: State<AppState>) -> CommandResult<InboxSummary> {
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+pub fn inbox_summary(state: State<AppState>, window: tauri::Window) -> CommandResult<InboxSummary> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
     inbox.get_summary().map_err(|e| e.to_string())
 }
 
@@ -58,11 +113,13 @@ pub fn inbox_summary(state: State<AppState>) -> CommandResult<InboxSummary> {
 #[tauri::command]
 pub async fn inbox_classify(
     state: State<'_, AppState>,
+    window: tauri::Window,
     item_ids: Option<Vec<Uuid>>,
 ) -> CommandResult<Vec<InboxItem>> {
+    let lib = state.library(&window);
     // Get items to classify
     let items_to_classify: Vec<InboxItem> = {
-        let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+        let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
 
         if let Some(ids) = item_ids {
             ids.iter()
@@ -80,7 +137,7 @@ pub async fn inbox_classify(
 
     // Get notebooks for context
     let notebooks: Vec<(Uuid, String)> = {
-        let storage = state.storage.lock().map_err(|e| e.to_string())?;
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
         storage
             .list_notebooks()
             .map_err(|e| e.to_string())?
@@ -91,7 +148,7 @@ pub async fn inbox_classify(
 
     // Get recent pages for context (up to 50)
     let recent_pages: Vec<(Uuid, Uuid, String)> = {
-        let storage = state.storage.lock().map_err(|e| e.to_string())?;
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
         let mut pages = Vec::new();
         for (notebook_id, notebook_name) in &notebooks {
             if let Ok(notebook_pages) = storage.list_pages(*notebook_id) {
@@ -147,7 +204,7 @@ pub async fn inbox_classify(
 
         match classification_result {
             Ok(classification) => {
-                let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+                let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
                 if let Ok(updated) = inbox.set_classification(item.id, classification) {
                     classified_items.push(updated);
                 }
@@ -165,8 +222,10 @@ pub async fn inbox_classify(
 #[tauri::command]
 pub fn inbox_apply_actions(
     state: State<AppState>,
+    window: tauri::Window,
     request: ApplyActionsRequest,
 ) -> CommandResult<ApplyActionsResult> {
+    let lib = state.library(&window);
     let mut result = ApplyActionsResult {
         processed_count: 0,
         created_pages: Vec::new(),
@@ -175,8 +234,8 @@ pub fn inbox_apply_actions(
         errors: Vec::new(),
     };
 
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.write().map_err(|e| e.to_string())?;
 
     for item_id in &request.item_ids {
         let item = match inbox.get_item(*item_id) {
@@ -302,6 +361,30 @@ pub fn inbox_apply_actions(
                     }
                 }
             }
+            ClassificationAction::AppendToDailyNote {
+                notebook_id,
+                date,
+                ..
+            } => {
+                // Queued from the mobile app: append to that day's note
+                match super::create_daily_note_core(&storage, notebook_id, &date, None) {
+                    Ok(mut page) => {
+                        let new_blocks = crate::markdown::parse_markdown_to_blocks(&item.content);
+                        page.content.blocks.extend(new_blocks);
+
+                        if let Err(e) = storage.update_page(&page) {
+                            result.errors.push(format!("Failed to update page: {}", e));
+                        } else {
+                            result.updated_pages.push(page.id);
+                            let _ = inbox.mark_processed(*item_id);
+                            result.processed_count += 1;
+                        }
+                    }
+                    Err(e) => {
+                        result.errors.push(format!("Failed to get daily note: {}", e.message));
+                    }
+                }
+            }
             ClassificationAction::KeepInInbox { .. } => {
                 // Do nothing, keep in inbox
             }
@@ -313,14 +396,23 @@ pub fn inbox_apply_actions(
 
 /// Delete an inbox item
 #[tauri::command]
-pub fn inbox_delete(state: State<AppState>, item_id: Uuid) -> CommandResult<()> {
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+pub fn inbox_delete(
+    state: State<AppState>,
+    window: tauri::Window,
+    item_id: Uuid,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
     inbox.delete_item(item_id).map_err(|e| e.to_string())
 }
 
 /// Clear all processed items
 #[tauri::command]
-pub fn inbox_clear_processed(state: State<AppState>) -> CommandResult<usize> {
-    let inbox = state.inbox_storage.lock().map_err(|e| e.to_string())?;
+pub fn inbox_clear_processed(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<usize> {
+    let lib = state.library(&window);
+    let inbox = lib.inbox_storage.lock().map_err(|e| e.to_string())?;
     inbox.clear_processed().map_err(|e| e.to_string())
 }
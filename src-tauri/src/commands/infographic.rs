@@ -1,17 +1,23 @@
 //! Infographic generation Tauri commands — visual summaries from study tools content.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::sync::{Arc, Mutex};
 use tauri::State;
 use uuid::Uuid;
 
-use crate::python_bridge::InfographicResult;
+use crate::generated_media::{
+    list_dir_files, GeneratedMedia, GeneratedMediaKind, GeneratedMediaRegistry,
+};
+use crate::library::LibraryContext;
+use crate::python_bridge::{InfographicResult, PythonAI};
 use crate::AppState;
 
+use super::generated_media::parse_page_ids;
 use super::notebook::CommandError;
 
 /// Infographic configuration from the frontend
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InfographicConfig {
     pub template: String, // key_concepts, executive_summary, timeline, concept_map
@@ -37,66 +43,125 @@ fn default_theme() -> String {
     "light".to_string()
 }
 
+/// Everything an infographic was generated from, kept in the generated
+/// media registry so it can be generated again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InfographicRequest {
+    pub template: String,
+    pub data: serde_json::Value,
+    pub config: Option<InfographicConfig>,
+    pub export_png: bool,
+}
+
 /// Generate an infographic from study tools data
 #[tauri::command]
 pub async fn generate_infographic(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     template: String,
     data: serde_json::Value,
     config: Option<InfographicConfig>,
     export_png: Option<bool>,
+    page_ids: Option<Vec<String>>,
 ) -> Result<InfographicResult, CommandError> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
+    let page_ids = parse_page_ids(page_ids)?;
+
+    let request = InfographicRequest {
+        template,
+        data,
+        config,
+        export_png: export_png.unwrap_or(true),
+    };
+    let (result, _) =
+        generate_and_record(state.python_ai.clone(), &lib, nb_id, page_ids, request, None).await?;
+    Ok(result)
+}
 
+/// Generate an infographic into the notebook's `infographics` assets and
+/// record it in the generated media registry
+pub(crate) async fn generate_and_record(
+    python_ai: Arc<Mutex<PythonAI>>,
+    lib: &Arc<LibraryContext>,
+    nb_id: Uuid,
+    page_ids: Vec<Uuid>,
+    request: InfographicRequest,
+    regenerated_from: Option<Uuid>,
+) -> Result<(InfographicResult, GeneratedMedia), CommandError> {
     // Get assets directory from storage
-    let output_dir = {
-        let storage = state.storage.lock().map_err(|e| CommandError {
+    let (assets_dir, infographics_dir) = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
             message: format!("Failed to acquire storage lock: {}", e),
         })?;
 
         // Output directory: {notebook_assets}/infographics/
         let assets_dir = storage.notebook_assets_dir(nb_id);
-        let infographics_dir = assets_dir.join("infographics");
+        let infographics_dir = assets_dir.join(GeneratedMediaKind::Infographic.dir_name());
         fs::create_dir_all(&infographics_dir).map_err(|e| CommandError {
             message: format!("Failed to create infographics directory: {}", e),
         })?;
-
-        infographics_dir
-            .to_str()
-            .map(|s| s.to_string())
-            .ok_or_else(|| CommandError {
-                message: "Invalid path encoding".to_string(),
-            })?
+        (assets_dir, infographics_dir)
     };
+    let output_dir = infographics_dir
+        .to_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| CommandError {
+            message: "Invalid path encoding".to_string(),
+        })?;
 
-    // Clone python_ai Arc for spawn_blocking
-    let python_ai = state.python_ai.clone();
-    let export_png = export_png.unwrap_or(true);
+    let options = serde_json::to_value(&request).map_err(|e| CommandError {
+        message: format!("Failed to record infographic options: {}", e),
+    })?;
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let (result, outputs) = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
 
-        python_ai
+        // The generator names its own files; whatever is new after it ran
+        // (with the lock held, so nothing else is writing) is its output
+        let before = list_dir_files(&infographics_dir);
+        let result = python_ai
             .generate_infographic(
-                &template,
-                data,
+                &request.template,
+                request.data,
                 &output_dir,
-                config.as_ref(),
-                export_png,
+                request.config.as_ref(),
+                request.export_png,
             )
             .map_err(|e| CommandError {
                 message: format!("Infographic generation error: {}", e),
-            })
+            })?;
+        let mut outputs: Vec<_> = list_dir_files(&infographics_dir)
+            .difference(&before)
+            .cloned()
+            .collect();
+        outputs.sort();
+        Ok::<_, CommandError>((result, outputs))
     })
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+
+    let _storage = lib.storage.write().map_err(|e| CommandError {
+        message: format!("Failed to acquire storage lock: {}", e),
+    })?;
+    let mut registry = GeneratedMediaRegistry::open(assets_dir)?;
+    let files = outputs
+        .iter()
+        .filter_map(|path| registry.relative_path(path))
+        .collect();
+    let mut media =
+        GeneratedMedia::new(nb_id, page_ids, GeneratedMediaKind::Infographic, options, files);
+    media.regenerated_from = regenerated_from;
+    let media = registry.add(media)?;
+    Ok((result, media))
 }
 
 /// Check availability of infographic features
@@ -42,16 +42,18 @@ pub fn preview_joplin_import_cmd(path: String) -> CommandResult<JoplinImportPrev
 pub fn import_joplin_cmd(
     app: AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = Path::new(&path);
 
     if !path.exists() {
         return Err("Joplin export not found".to_string());
     }
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
     let notebooks_dir = storage.notebooks_base_dir();
 
     // Create progress callback
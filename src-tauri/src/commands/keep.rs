@@ -0,0 +1,103 @@
+//! Tauri commands for Google Keep (Takeout) import
+
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::keep::{
+    import_keep_with_progress, preview_keep_import, KeepDuplicateStrategy, KeepImportPreview,
+    KeepImportResult,
+};
+use crate::AppState;
+
+/// Error type for command results
+type CommandResult<T> = Result<T, String>;
+
+/// Progress event payload
+#[derive(Clone, Serialize)]
+struct ImportProgress {
+    current: usize,
+    total: usize,
+    message: String,
+}
+
+fn parse_notebook_id(notebook_id: Option<String>) -> CommandResult<Option<Uuid>> {
+    notebook_id
+        .map(|id| Uuid::parse_str(&id).map_err(|e| format!("Invalid notebook ID: {}", e)))
+        .transpose()
+}
+
+/// Preview a Google Keep takeout (zip or extracted folder)
+///
+/// When `notebook_id` is given, notes whose titles already exist in that
+/// notebook are listed as duplicates.
+#[tauri::command]
+pub fn preview_keep_import_cmd(
+    state: State<AppState>,
+    window: tauri::Window,
+    path: String,
+    notebook_id: Option<String>,
+) -> CommandResult<KeepImportPreview> {
+    let lib = state.library(&window);
+    let path = Path::new(&path);
+
+    if !path.exists() {
+        return Err("Google Keep export not found".to_string());
+    }
+
+    let notebook_id = parse_notebook_id(notebook_id)?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
+    preview_keep_import(path, &storage, notebook_id).map_err(|e| e.to_string())
+}
+
+/// Import a Google Keep takeout
+///
+/// Imports into the notebook `notebook_id` when given, otherwise into a new
+/// notebook. `duplicate_strategy` decides what happens to notes whose title
+/// already exists in the target (default: rename).
+#[tauri::command]
+pub fn import_keep_cmd(
+    app: AppHandle,
+    state: State<AppState>,
+    window: tauri::Window,
+    path: String,
+    notebook_id: Option<String>,
+    notebook_name: Option<String>,
+    duplicate_strategy: Option<KeepDuplicateStrategy>,
+) -> CommandResult<KeepImportResult> {
+    let lib = state.library(&window);
+    let path = Path::new(&path);
+
+    if !path.exists() {
+        return Err("Google Keep export not found".to_string());
+    }
+
+    let notebook_id = parse_notebook_id(notebook_id)?;
+    let storage = lib.storage.write().map_err(|e| e.to_string())?;
+
+    let app_clone = app.clone();
+    let progress_callback = move |current: usize, total: usize, message: &str| {
+        let _ = app_clone.emit(
+            "import-progress",
+            ImportProgress {
+                current,
+                total,
+                message: message.to_string(),
+            },
+        );
+    };
+
+    // Search indexing is the daemon's job; POST /api/search/rebuild picks
+    // up the new pages.
+    import_keep_with_progress(
+        path,
+        &storage,
+        notebook_id,
+        notebook_name,
+        duplicate_strategy.unwrap_or_default(),
+        progress_callback,
+    )
+    .map_err(|e| e.to_string())
+}
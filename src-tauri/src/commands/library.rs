@@ -3,11 +3,15 @@
 //! Tauri commands for managing libraries.
 
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tauri::State;
 use uuid::Uuid;
 
-use crate::library::{Library, LibraryStats};
+use crate::library::snapshots::{self, LibrarySnapshot, SnapshotError};
+use crate::library::{Library, LibrarySettings, LibraryStats};
+use crate::markdown::mirror::{mirror_library, MarkdownMirrorConfig, MirrorReport};
+use crate::rag::VectorIndex;
 use crate::AppState;
 
 /// Error type for library commands
@@ -142,16 +146,18 @@ pub async fn switch_library(state: State<'_, AppState>, library_id: String) -> C
 
     // Reinitialize file storage with new library path
     {
-        let mut file_storage = state
-            .storage
-            .lock()
-            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
-
         // Create new storage at library path
-        *file_storage = crate::storage::FileStorage::new(library.path.clone());
+        let file_storage = crate::storage::FileStorageWriter::new(
+            crate::storage::FileStorage::new(library.path.clone()),
+        );
         file_storage
             .init()
             .map_err(|e| LibraryCommandError::new(&format!("Failed to init storage: {}", e)))?;
+
+        state
+            .storage
+            .replace(file_storage)
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
     }
 
     // Search index lives in the daemon now. The daemon needs to be
@@ -169,6 +175,28 @@ pub async fn switch_library(state: State<'_, AppState>, library_id: String) -> C
             .map_err(|e| LibraryCommandError::new(&format!("Failed to init goals storage: {}", e)))?;
     }
 
+    // Reinitialize reminder storage with new library path
+    {
+        let mut reminder_storage = state
+            .reminder_storage
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+
+        *reminder_storage = crate::reminders::ReminderStorage::new(library.path.clone())
+            .map_err(|e| LibraryCommandError::new(&format!("Failed to init reminder storage: {}", e)))?;
+    }
+
+    // Reinitialize view history with new library path
+    {
+        let mut history_storage = state
+            .history_storage
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+
+        *history_storage = crate::history::HistoryStorage::new(library.path.clone())
+            .map_err(|e| LibraryCommandError::new(&format!("Failed to init view history: {}", e)))?;
+    }
+
     // Reinitialize inbox storage with new library path
     {
         let mut inbox_storage = state
@@ -212,6 +240,16 @@ pub async fn switch_library(state: State<'_, AppState>, library_id: String) -> C
         *flashcard_storage = crate::flashcards::FlashcardStorage::new(library.path.join("notebooks"));
     }
 
+    // Reinitialize comments storage with new library path
+    {
+        let mut comments_storage = state
+            .comments_storage
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+
+        *comments_storage = crate::comments::CommentsStorage::new(library.path.join("notebooks"));
+    }
+
     // CRDT store moved to the daemon. The daemon has its own library_path
     // and reads from {library_path}/notebooks/.../sync/.../*.crdt; library
     // swap on the Tauri side no longer needs to retarget a Rust-side store.
@@ -313,3 +351,286 @@ pub fn move_notebook_to_library(
 
     Ok(new_path.to_string_lossy().to_string())
 }
+
+/// Get the current library's settings (synced with the library)
+#[tauri::command]
+pub fn get_library_settings(
+    state: State<AppState>,
+) -> CommandResult<BTreeMap<String, serde_json::Value>> {
+    let library = {
+        let storage = state
+            .library_storage
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+        storage.get_current_library()?
+    };
+
+    Ok(LibrarySettings::load(&library.path)
+        .entries()
+        .iter()
+        .map(|(key, entry)| (key.clone(), entry.value.clone()))
+        .collect())
+}
+
+/// Set one of the current library's settings
+#[tauri::command]
+pub fn set_library_setting(
+    state: State<AppState>,
+    key: String,
+    value: serde_json::Value,
+) -> CommandResult<()> {
+    if key.trim().is_empty() {
+        return Err(LibraryCommandError::new("Setting key is required"));
+    }
+    let library = {
+        let storage = state
+            .library_storage
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+        storage.get_current_library()?
+    };
+
+    let mut settings = LibrarySettings::load(&library.path);
+    if settings.get(&key) != Some(&value) {
+        settings.set(&key, value);
+        settings.save()?;
+    }
+    Ok(())
+}
+
+/// Set or clear a library's scheduled Markdown mirror
+///
+/// The daemon runs the mirror scheduler and re-reads library settings every
+/// minute, so changes take effect without restarting it.
+#[tauri::command]
+pub fn update_library_markdown_mirror(
+    state: State<AppState>,
+    library_id: String,
+    config: Option<MarkdownMirrorConfig>,
+) -> CommandResult<Library> {
+    let id = Uuid::parse_str(&library_id)
+        .map_err(|_| LibraryCommandError::new("Invalid library ID"))?;
+
+    let storage = state
+        .library_storage
+        .lock()
+        .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+
+    Ok(storage.update_library_markdown_mirror(id, config)?)
+}
+
+/// Bring a library's Markdown mirror up to date now
+#[tauri::command]
+pub async fn run_library_markdown_mirror(
+    state: State<'_, AppState>,
+    library_id: String,
+) -> CommandResult<MirrorReport> {
+    let id = Uuid::parse_str(&library_id)
+        .map_err(|_| LibraryCommandError::new("Invalid library ID"))?;
+
+    let library = {
+        let storage = state
+            .library_storage
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+        storage.get_library(id)?
+    };
+
+    if library.is_encrypted() {
+        return Err(LibraryCommandError::new(
+            "Markdown mirrors are not available for encrypted libraries",
+        ));
+    }
+    let config = library
+        .markdown_mirror
+        .clone()
+        .ok_or_else(|| LibraryCommandError::new("No Markdown mirror configured"))?;
+    config
+        .validate(&library.path)
+        .map_err(|e| LibraryCommandError::new(&e))?;
+
+    tokio::task::spawn_blocking(move || {
+        let storage = crate::storage::FileStorage::new(library.path.clone());
+        mirror_library(
+            &storage,
+            std::path::Path::new(config.target_dir.trim()),
+            config.include_archived,
+        )
+    })
+    .await
+    .map_err(|e| LibraryCommandError::new(&format!("Mirror task failed: {}", e)))?
+    .map_err(|e| LibraryCommandError::new(&e.to_string()))
+}
+
+impl From<SnapshotError> for LibraryCommandError {
+    fn from(e: SnapshotError) -> Self {
+        Self {
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Take a snapshot of the window's library: pages, indexes, settings and
+/// everything else under the library folder
+#[tauri::command]
+pub async fn create_library_snapshot(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    label: Option<String>,
+) -> CommandResult<LibrarySnapshot> {
+    let lib = state.library(&window);
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| "Manual snapshot".to_string());
+
+    tokio::task::spawn_blocking(move || {
+        let storage = lib
+            .storage
+            .write()
+            .map_err(|e| LibraryCommandError::new(&format!("Storage error: {}", e)))?;
+        let _vector_index = lib
+            .vector_index
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+        Ok(snapshots::create_snapshot(storage.notebooks_dir(), &label)?)
+    })
+    .await
+    .map_err(|e| LibraryCommandError::new(&format!("Snapshot task failed: {}", e)))?
+}
+
+/// List the window library's snapshots, newest first
+#[tauri::command]
+pub fn list_snapshots(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<LibrarySnapshot>> {
+    let lib = state.library(&window);
+    let storage = lib
+        .storage
+        .read()
+        .map_err(|e| LibraryCommandError::new(&format!("Storage error: {}", e)))?;
+    Ok(snapshots::list_snapshots(storage.notebooks_dir()))
+}
+
+/// Delete one of the window library's snapshots
+#[tauri::command]
+pub fn delete_library_snapshot(
+    state: State<AppState>,
+    window: tauri::Window,
+    snapshot_id: String,
+) -> CommandResult<()> {
+    let id = Uuid::parse_str(&snapshot_id)
+        .map_err(|_| LibraryCommandError::new("Invalid snapshot ID"))?;
+    let lib = state.library(&window);
+    let storage = lib
+        .storage
+        .write()
+        .map_err(|e| LibraryCommandError::new(&format!("Storage error: {}", e)))?;
+    Ok(snapshots::delete_snapshot(storage.notebooks_dir(), id)?)
+}
+
+/// Put the window's library back to the state of a snapshot. The current
+/// state is snapshotted first and returned, so the rollback can itself be
+/// undone. The search index should be rebuilt afterwards.
+#[tauri::command]
+pub async fn rollback_to_snapshot(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    snapshot_id: String,
+) -> CommandResult<LibrarySnapshot> {
+    let id = Uuid::parse_str(&snapshot_id)
+        .map_err(|_| LibraryCommandError::new("Invalid snapshot ID"))?;
+    let lib = state.library(&window);
+
+    tokio::task::spawn_blocking(move || {
+        let storage = lib
+            .storage
+            .write()
+            .map_err(|e| LibraryCommandError::new(&format!("Storage error: {}", e)))?;
+        let path = storage.notebooks_dir().clone();
+        let mut vector_index = lib
+            .vector_index
+            .lock()
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+        let vector_db_path = vector_index.db_path().clone();
+
+        // Close vectors.db so it can be swapped out (Windows won't replace
+        // an open file)
+        *vector_index = VectorIndex::new(PathBuf::from(":memory:"))
+            .map_err(|e| LibraryCommandError::new(&format!("Failed to close vector index: {}", e)))?;
+        let restored = snapshots::rollback_to_snapshot(&path, id);
+        *vector_index = VectorIndex::new(vector_db_path)
+            .map_err(|e| LibraryCommandError::new(&format!("Failed to init vector index: {}", e)))?;
+        drop(vector_index);
+        drop(storage);
+        let safety = restored?;
+
+        // Reopen everything that caches library files in memory
+        let file_storage =
+            crate::storage::FileStorageWriter::new(crate::storage::FileStorage::new(path.clone()));
+        file_storage
+            .init()
+            .map_err(|e| LibraryCommandError::new(&format!("Failed to init storage: {}", e)))?;
+        lib.storage
+            .replace(file_storage)
+            .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+        {
+            let mut action_storage = lib
+                .action_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *action_storage = crate::actions::ActionStorage::new(path.clone())
+                .map_err(|e| LibraryCommandError::new(&format!("Failed to init action storage: {}", e)))?;
+        }
+        {
+            let mut inbox_storage = lib
+                .inbox_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *inbox_storage = crate::inbox::InboxStorage::new(path.clone())
+                .map_err(|e| LibraryCommandError::new(&format!("Failed to init inbox storage: {}", e)))?;
+        }
+        {
+            let mut goals_storage = lib
+                .goals_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *goals_storage = crate::goals::GoalsStorage::new(path.clone())
+                .map_err(|e| LibraryCommandError::new(&format!("Failed to init goals storage: {}", e)))?;
+        }
+        {
+            let mut reminder_storage = lib
+                .reminder_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *reminder_storage = crate::reminders::ReminderStorage::new(path.clone())
+                .map_err(|e| LibraryCommandError::new(&format!("Failed to init reminder storage: {}", e)))?;
+        }
+        {
+            let mut history_storage = lib
+                .history_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *history_storage = crate::history::HistoryStorage::new(path.clone())
+                .map_err(|e| LibraryCommandError::new(&format!("Failed to init view history: {}", e)))?;
+        }
+        {
+            let mut flashcard_storage = lib
+                .flashcard_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *flashcard_storage = crate::flashcards::FlashcardStorage::new(path.join("notebooks"));
+        }
+        {
+            let mut comments_storage = lib
+                .comments_storage
+                .lock()
+                .map_err(|e| LibraryCommandError::new(&format!("Lock error: {}", e)))?;
+            *comments_storage = crate::comments::CommentsStorage::new(path.join("notebooks"));
+        }
+        Ok(safety)
+    })
+    .await
+    .map_err(|e| LibraryCommandError::new(&format!("Rollback task failed: {}", e)))?
+}
@@ -0,0 +1,103 @@
+//! Content lint commands (see `crate::lint`).
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::lint::{self, LintOptions, LintReport};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Result of `fix_lint_diagnostics`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFixResult {
+    /// Ids of the diagnostics that were fixed
+    pub fixed: Vec<String>,
+    pub updated_page_ids: Vec<Uuid>,
+    /// The notebook linted again after the fixes
+    pub report: LintReport,
+}
+
+fn lint_options(max_image_bytes: Option<u64>) -> LintOptions {
+    LintOptions {
+        max_image_bytes: max_image_bytes.unwrap_or(lint::DEFAULT_MAX_IMAGE_BYTES),
+    }
+}
+
+/// Check a notebook's pages for broken links, missing assets, empty pages,
+/// duplicate titles, oversized images and malformed blocks.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn lint_notebook(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    max_image_bytes: Option<u64>,
+) -> CommandResult<LintReport> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let lib = state.library(&window);
+    tokio::task::spawn_blocking(move || -> CommandResult<LintReport> {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        Ok(lint::lint_notebook(
+            &storage,
+            nb_id,
+            lint_options(max_image_bytes),
+        )?)
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Lint task failed: {}", e),
+    })?
+}
+
+/// Apply the fixes offered with the given diagnostics (by id) and lint the
+/// notebook again.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn fix_lint_diagnostics(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    diagnostic_ids: Vec<String>,
+    max_image_bytes: Option<u64>,
+) -> CommandResult<LintFixResult> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let lib = state.library(&window);
+    let result = tokio::task::spawn_blocking(move || -> CommandResult<LintFixResult> {
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let ids: HashSet<String> = diagnostic_ids.into_iter().collect();
+        let options = lint_options(max_image_bytes);
+        let fixes = lint::fix_notebook(&storage, nb_id, &ids, options)?;
+        Ok(LintFixResult {
+            fixed: fixes.fixed,
+            updated_page_ids: fixes.updated.iter().map(|p| p.id).collect(),
+            report: lint::lint_notebook(&storage, nb_id, options)?,
+        })
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Lint fix task failed: {}", e),
+    })??;
+
+    for page_id in &result.updated_page_ids {
+        state.sync_manager.queue_page_update(nb_id, *page_id);
+    }
+    log::info!(
+        "Fixed {} lint diagnostics in {} pages",
+        result.fixed.len(),
+        result.updated_page_ids.len()
+    );
+    Ok(result)
+}
@@ -0,0 +1,65 @@
+//! Tauri commands for the app log (see `crate::logging`)
+
+use serde::Serialize;
+
+use crate::logging::{self, LogEntry, LogLevel, LogSettings};
+use crate::storage::FileStorage;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// How many entries the log viewer gets unless it asks for a number
+const DEFAULT_RECENT_LOGS: usize = 500;
+
+/// Log settings plus where the files are, for the settings panel
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSettingsInfo {
+    pub settings: LogSettings,
+    pub log_dir: String,
+    /// Subsystems to offer level overrides for
+    pub subsystems: Vec<String>,
+}
+
+fn logger() -> CommandResult<&'static logging::FileLogger> {
+    logging::logger().ok_or_else(|| CommandError {
+        message: "Logging is not initialized".to_string(),
+    })
+}
+
+/// The latest log entries, oldest first. `level` is the least severe level
+/// to include (default: everything); `filter` matches targets and messages.
+#[tauri::command]
+pub fn get_recent_logs(
+    limit: Option<usize>,
+    level: Option<LogLevel>,
+    filter: Option<String>,
+) -> CommandResult<Vec<LogEntry>> {
+    Ok(logger()?.recent(
+        limit.unwrap_or(DEFAULT_RECENT_LOGS),
+        level.unwrap_or(LogLevel::Trace),
+        filter.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub fn get_log_settings() -> CommandResult<LogSettingsInfo> {
+    let logger = logger()?;
+    Ok(LogSettingsInfo {
+        settings: logger.settings(),
+        log_dir: logger.dir().to_string_lossy().to_string(),
+        subsystems: logging::SUBSYSTEMS.iter().map(|s| s.to_string()).collect(),
+    })
+}
+
+/// Change log levels and rotation; takes effect immediately
+#[tauri::command]
+pub fn set_log_settings(settings: LogSettings) -> CommandResult<()> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| CommandError {
+        message: format!("Failed to get data directory: {}", e),
+    })?;
+    logging::update_settings(&data_dir, settings).map_err(|e| CommandError {
+        message: format!("Failed to save log settings: {}", e),
+    })
+}
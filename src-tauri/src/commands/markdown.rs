@@ -15,10 +15,12 @@ type CommandResult<T> = Result<T, CommandError>;
 #[tauri::command]
 pub fn export_page_markdown(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<String> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -37,13 +39,15 @@ pub fn export_page_markdown(
 #[tauri::command]
 pub fn import_markdown(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     markdown: String,
     filename: String,
     folder_id: Option<String>,
     section_id: Option<String>,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -89,11 +93,13 @@ pub fn import_markdown(
 #[tauri::command]
 pub fn export_page_to_file(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     path: String,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -116,10 +122,12 @@ pub fn export_page_to_file(
 #[tauri::command]
 pub fn import_markdown_file(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     path: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
 
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
@@ -1,98 +1,83 @@
 //! MCP (Model Context Protocol) server management commands.
 
-use tauri::State;
+use tauri::{AppHandle, State};
 
+use crate::ai_permissions::{self, ToolDecision, ToolPermissions};
 use crate::python_bridge::{MCPServersConfig, MCPTool, MCPToolResult};
 use crate::AppState;
 
+use super::ai_permissions::emit_permission;
 use super::notebook::CommandError;
 
-/// Load MCP server configuration for the current library
+/// Load MCP server configuration for the window's library
 #[tauri::command]
-pub fn mcp_load_config(state: State<AppState>) -> Result<MCPServersConfig, CommandError> {
+pub fn mcp_load_config(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> Result<MCPServersConfig, CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
 
     python_ai
-        .mcp_load_config(&current_library.path.to_string_lossy())
+        .mcp_load_config(&library_path.to_string_lossy())
         .map_err(|e| CommandError {
             message: format!("Failed to load MCP config: {}", e),
         })
 }
 
-/// Save MCP server configuration for the current library
+/// Save MCP server configuration for the window's library
 #[tauri::command]
 pub fn mcp_save_config(
     state: State<AppState>,
+    window: tauri::Window,
     config: MCPServersConfig,
 ) -> Result<(), CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
 
     python_ai
-        .mcp_save_config(&current_library.path.to_string_lossy(), config)
+        .mcp_save_config(&library_path.to_string_lossy(), config)
         .map_err(|e| CommandError {
             message: format!("Failed to save MCP config: {}", e),
         })
 }
 
-/// Start all enabled MCP servers for the current library
+/// Start all enabled MCP servers for the window's library
 #[tauri::command]
-pub fn mcp_start_servers(state: State<AppState>) -> Result<Vec<String>, CommandError> {
+pub fn mcp_start_servers(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> Result<Vec<String>, CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
 
     python_ai
-        .mcp_start_servers(&current_library.path.to_string_lossy())
+        .mcp_start_servers(&library_path.to_string_lossy())
         .map_err(|e| CommandError {
             message: format!("Failed to start MCP servers: {}", e),
         })
 }
 
-/// Stop all MCP servers for the current library
+/// Stop all MCP servers for the window's library
 #[tauri::command]
-pub fn mcp_stop_servers(state: State<AppState>) -> Result<(), CommandError> {
+pub fn mcp_stop_servers(state: State<AppState>, window: tauri::Window) -> Result<(), CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
 
     python_ai
-        .mcp_stop_servers(&current_library.path.to_string_lossy())
+        .mcp_stop_servers(&library_path.to_string_lossy())
         .map_err(|e| CommandError {
             message: format!("Failed to stop MCP servers: {}", e),
         })
@@ -100,21 +85,18 @@ pub fn mcp_stop_servers(state: State<AppState>) -> Result<(), CommandError> {
 
 /// Get all tools from running MCP servers
 #[tauri::command]
-pub fn mcp_get_tools(state: State<AppState>) -> Result<Vec<MCPTool>, CommandError> {
+pub fn mcp_get_tools(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> Result<Vec<MCPTool>, CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
 
     python_ai
-        .mcp_get_tools(&current_library.path.to_string_lossy())
+        .mcp_get_tools(&library_path.to_string_lossy())
         .map_err(|e| CommandError {
             message: format!("Failed to get MCP tools: {}", e),
         })
@@ -122,49 +104,63 @@ pub fn mcp_get_tools(state: State<AppState>) -> Result<Vec<MCPTool>, CommandErro
 
 /// Get list of running MCP server names
 #[tauri::command]
-pub fn mcp_get_running_servers(state: State<AppState>) -> Result<Vec<String>, CommandError> {
+pub fn mcp_get_running_servers(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> Result<Vec<String>, CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
 
     python_ai
-        .mcp_get_running_servers(&current_library.path.to_string_lossy())
+        .mcp_get_running_servers(&library_path.to_string_lossy())
         .map_err(|e| CommandError {
             message: format!("Failed to get running MCP servers: {}", e),
         })
 }
 
-/// Call a tool on an MCP server
+/// Call a tool on an MCP server. The library's tool permissions apply;
+/// `approved` lets a call run that asks for the user's approval.
 #[tauri::command]
 pub fn mcp_call_tool(
+    app: AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     server_name: String,
     tool_name: String,
     arguments: serde_json::Value,
+    approved: Option<bool>,
 ) -> Result<MCPToolResult, CommandError> {
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let library_storage = state.library_storage.lock().map_err(|e| CommandError {
-        message: format!("Failed to acquire library storage lock: {}", e),
-    })?;
-
-    let current_library = library_storage.get_current_library().map_err(|e| CommandError {
-        message: format!("Failed to get current library: {}", e),
-    })?;
+    let library_path = state.library_path(&window)?;
+
+    let notebook_id = {
+        let lib = state.library(&window);
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        ai_permissions::target_notebook(&storage, &arguments)
+    };
+    let check = ToolPermissions::load(&library_path).check(
+        &ai_permissions::mcp_tool_name(&server_name, &tool_name),
+        notebook_id,
+    );
+    let approved = check.decision == ToolDecision::Ask && approved.unwrap_or(false);
+    if !check.allowed() && !approved {
+        emit_permission(&app, &check);
+        return Err(CommandError {
+            message: check.message(),
+        });
+    }
 
     python_ai
         .mcp_call_tool(
-            &current_library.path.to_string_lossy(),
+            &library_path.to_string_lossy(),
             &server_name,
             &tool_name,
             arguments,
@@ -0,0 +1,325 @@
+//! Meeting recording pipeline command (see `crate::meeting_notes`).
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{Local, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::contacts::{ActivityType, ContactActivity, Direction};
+use crate::meeting_notes::{self, ActionItem, MeetingAudio, MeetingError, MEETING_TAG};
+use crate::python_bridge::queue::{QueueError, TaskOptions};
+use crate::python_bridge::AIConfig;
+use crate::storage::Page;
+use crate::AppState;
+
+use super::daily_notes::create_daily_note_core;
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Transcribing a long meeting on the CPU takes a while
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Pipeline stage, emitted as `meeting-progress` when each one starts
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MeetingStage {
+    Transcribing,
+    IdentifyingSpeakers,
+    Summarizing,
+    CreatingPage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingProgress {
+    pub task_id: Option<String>,
+    pub stage: MeetingStage,
+}
+
+/// Result of `process_meeting_recording`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingNotesResult {
+    pub page: Page,
+    /// For the caller to add to the task list
+    pub action_items: Vec<ActionItem>,
+    /// Whether the transcript is split by speaker
+    pub speakers_identified: bool,
+    /// Daily note the meeting was linked from
+    pub daily_note_id: Option<Uuid>,
+}
+
+fn queue_error(e: QueueError) -> CommandError {
+    CommandError {
+        message: match e {
+            QueueError::Cancelled => "Meeting processing cancelled".to_string(),
+            e => e.to_string(),
+        },
+    }
+}
+
+/// Turn a meeting recording into notes: transcribe it, split the transcript
+/// by speaker if pyannote is installed, have the AI summarize it and pull out
+/// decisions and action items, and create a meeting page linked from the
+/// daily note of the recording's day. Attendees (contact ids) are listed on
+/// the page and get a meeting activity pointing at it. The recording is
+/// copied into the notebook's assets unless it's already there.
+///
+/// `cancel_ai_task` with `task_id` stops the pipeline between stages.
+#[tauri::command]
+pub async fn process_meeting_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    audio_path: String,
+    attendee_ids: Option<Vec<String>>,
+    title: Option<String>,
+    model_size: Option<String>,
+    language: Option<String>,
+    link_daily_note: Option<bool>,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    task_id: Option<String>,
+) -> CommandResult<MeetingNotesResult> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let source = Path::new(&audio_path);
+    if !source.is_file() {
+        return Err(CommandError {
+            message: format!("Recording not found: {}", audio_path),
+        });
+    }
+    let recorded_at = std::fs::metadata(source)
+        .and_then(|m| m.modified())
+        .map(chrono::DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+
+    let attendees = {
+        let contacts = state.contacts_storage.lock().map_err(|e| CommandError {
+            message: format!("Failed to lock contacts: {}", e),
+        })?;
+        let mut attendees = Vec::new();
+        for id in attendee_ids.unwrap_or_default() {
+            let contact = Uuid::parse_str(&id)
+                .map_err(|e| e.to_string())
+                .and_then(|id| contacts.get_contact(id).map_err(|e| e.to_string()));
+            match contact {
+                Ok(contact) => attendees.push(contact),
+                Err(e) => log::warn!("Skipping meeting attendee {}: {}", id, e),
+            }
+        }
+        attendees
+    };
+    let attendee_names: Vec<String> = attendees.iter().map(|c| c.name.clone()).collect();
+
+    let lib = state.library(&window);
+    let audio = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let audio_dir = storage.notebook_assets_dir(nb_id).join("audio");
+        let stored = if source.starts_with(storage.notebook_assets_dir(nb_id)) {
+            source.to_path_buf()
+        } else {
+            std::fs::create_dir_all(&audio_dir).map_err(|e| CommandError {
+                message: format!("Failed to create audio directory: {}", e),
+            })?;
+            let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("m4a");
+            let target = audio_dir.join(format!("meeting_{}.{}", Uuid::new_v4(), ext));
+            std::fs::copy(source, &target).map_err(|e| CommandError {
+                message: format!("Failed to copy recording: {}", e),
+            })?;
+            target
+        };
+        MeetingAudio {
+            filename: stored
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: stored.to_string_lossy().to_string(),
+            recorded_at,
+        }
+    };
+
+    let emit = |stage: MeetingStage| {
+        let progress = MeetingProgress {
+            task_id: task_id.clone(),
+            stage,
+        };
+        if let Err(e) = app.emit("meeting-progress", &progress) {
+            log::warn!("Failed to emit meeting progress: {}", e);
+        }
+    };
+
+    emit(MeetingStage::Transcribing);
+    let transcription = {
+        let path = audio.path.clone();
+        let options = TaskOptions::default()
+            .with_task_id(task_id.clone())
+            .with_timeout(TRANSCRIBE_TIMEOUT);
+        state
+            .python_queue
+            .run(options, move |python_ai| {
+                python_ai.transcribe_audio(&path, model_size.as_deref(), language.as_deref())
+            })
+            .await
+            .map_err(queue_error)?
+            .map_err(|e| CommandError {
+                message: format!("Audio transcription error: {}", e),
+            })?
+    };
+    if transcription
+        .segments
+        .iter()
+        .all(|s| s.text.trim().is_empty())
+    {
+        return Err(CommandError {
+            message: MeetingError::NoSpeech.to_string(),
+        });
+    }
+
+    emit(MeetingStage::IdentifyingSpeakers);
+    let diarization = {
+        let path = audio.path.clone();
+        let options = TaskOptions::default()
+            .with_task_id(task_id.clone())
+            .with_timeout(TRANSCRIBE_TIMEOUT);
+        match state
+            .python_queue
+            .run(options, move |python_ai| {
+                python_ai.diarize_audio(&path, None)
+            })
+            .await
+        {
+            Ok(Ok(turns)) => turns,
+            Ok(Err(e)) => {
+                log::info!("Meeting transcript won't be split by speaker: {}", e);
+                Vec::new()
+            }
+            Err(e) => return Err(queue_error(e)),
+        }
+    };
+    let turns = meeting_notes::speaker_turns(&transcription.segments, &diarization);
+    let speakers_identified = turns.iter().any(|t| t.speaker.is_some());
+
+    emit(MeetingStage::Summarizing);
+    let meeting_date = recorded_at.with_timezone(&Local).date_naive();
+    let mut config = AIConfig {
+        provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
+        api_key,
+        model,
+        temperature: Some(0.3),
+        max_tokens: Some(4096),
+        ..Default::default()
+    };
+    state.secrets.fill_ai_config(&mut config);
+    let messages = meeting_notes::summary_messages(
+        &meeting_notes::transcript_text(&turns),
+        &attendee_names,
+        meeting_date,
+    );
+    let response = state
+        .python_queue
+        .run(
+            TaskOptions::default().with_task_id(task_id.clone()),
+            move |python_ai| python_ai.chat(messages, config),
+        )
+        .await
+        .map_err(queue_error)?
+        .map_err(|e| CommandError {
+            message: format!("AI error: {}", e),
+        })?;
+    let summary = meeting_notes::parse_summary(&response.content).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+
+    emit(MeetingStage::CreatingPage);
+    let page_title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| Some(summary.title.clone()).filter(|t| !t.is_empty()))
+        .unwrap_or_else(|| format!("Meeting {}", meeting_date.format("%Y-%m-%d")));
+
+    let (page, daily_note_id) = {
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let mut page = storage.create_page(nb_id, page_title)?;
+        page.tags.push(MEETING_TAG.to_string());
+        page.content.blocks =
+            meeting_notes::meeting_blocks(&summary, &attendee_names, &audio, &turns);
+        page.content.time = Some(Utc::now().timestamp_millis());
+        storage.update_page(&page)?;
+
+        let daily_note_id = if link_daily_note.unwrap_or(true) {
+            let date = meeting_date.format("%Y-%m-%d").to_string();
+            let mut note = create_daily_note_core(&storage, nb_id, &date, None)?;
+            note.content.blocks = meeting_notes::add_meeting_link(
+                std::mem::take(&mut note.content.blocks),
+                page.id,
+                &page.title,
+            );
+            note.content.time = Some(Utc::now().timestamp_millis());
+            storage.update_page(&note)?;
+            Some(note.id)
+        } else {
+            None
+        };
+        (page, daily_note_id)
+    };
+
+    state.sync_manager.queue_page_update(nb_id, page.id);
+    if let Some(note_id) = daily_note_id {
+        state.sync_manager.queue_page_update(nb_id, note_id);
+    }
+
+    if !attendees.is_empty() {
+        let activities: Vec<ContactActivity> = attendees
+            .iter()
+            .map(|contact| ContactActivity {
+                id: Uuid::new_v4(),
+                contact_id: contact.id,
+                activity_type: ActivityType::Meeting,
+                direction: Direction::Outgoing,
+                timestamp: recorded_at,
+                preview: Some(page.title.clone()),
+                duration_seconds: Some(transcription.duration.round() as u64),
+                page_id: Some(page.id),
+                notebook_id: Some(nb_id),
+            })
+            .collect();
+        match state.contacts_storage.lock() {
+            Ok(contacts) => {
+                if let Err(e) = contacts.append_activities(&activities) {
+                    log::warn!("Failed to record meeting for attendees: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to lock contacts: {}", e),
+        }
+    }
+
+    log::info!(
+        "Meeting notes '{}': {} turns, {} action items, speakers {}",
+        page.title,
+        turns.len(),
+        summary.action_items.len(),
+        if speakers_identified {
+            "identified"
+        } else {
+            "not identified"
+        }
+    );
+    Ok(MeetingNotesResult {
+        page,
+        action_items: summary.action_items,
+        speakers_identified,
+        daily_note_id,
+    })
+}
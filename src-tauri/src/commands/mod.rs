@@ -1,13 +1,25 @@
 mod actions;
+mod activity;
 mod ai;
+mod ai_action_log;
+mod ai_cache;
+mod ai_permissions;
+mod ai_bulk;
+mod ai_profiles;
+mod app_config;
 mod assets;
 mod audio;
+mod auto_link;
 mod backup;
+mod charts;
 mod chat_sessions;
+mod code_exec;
 mod collab;
+mod comments;
 mod contacts;
 mod daemon_key;
 mod daily_notes;
+mod diagnostics;
 mod document_convert;
 mod drawing;
 mod encryption;
@@ -16,53 +28,85 @@ mod evernote;
 mod external_editor;
 mod external_sources;
 mod file_pages;
+mod find_replace;
 mod flashcard;
 mod folder;
+mod generated_media;
 mod git;
 mod goals;
+mod history;
 mod inbox;
 mod infographic;
 mod joplin;
+mod keep;
 mod library;
+mod lint;
+mod logging;
 mod markdown;
 mod mcp;
+mod meeting_notes;
 mod monitor;
 mod notebook;
+mod notifications;
 mod organize;
 mod notion;
 mod obsidian;
+mod ollama;
 mod onenote;
 mod orgmode;
 mod page;
 mod pdf_annotations;
 pub(crate) mod plugins;
+mod proofread;
 mod publish;
+mod python_env;
 mod rag;
+mod reading_queue;
+mod reminders;
+mod roam;
 mod scrivener;
+mod secrets;
 mod section;
 // search Tauri commands removed: migrated to daemon HTTP
 // (/api/search, /api/search/rebuild). Frontend calls daemonGet/daemonPost.
 mod share;
+mod spaced_review;
+mod sql_notebook;
 mod study_tools;
+mod scratchpad;
 mod sync;
 mod tags;
+mod translation;
 mod video;
 mod video_generate;
 mod web_clipper;
+mod webdav_server;
 mod web_research;
 mod website_mirror;
 mod window;
 
 pub use actions::*;
+pub use activity::*;
 pub use ai::*;
+pub use ai_action_log::*;
+pub use ai_cache::*;
+pub use ai_permissions::*;
+pub use ai_bulk::*;
+pub use ai_profiles::*;
+pub use app_config::*;
 pub use assets::*;
 pub use audio::*;
+pub use auto_link::*;
 pub use backup::*;
+pub use charts::*;
 pub use chat_sessions::*;
+pub use code_exec::*;
 pub use collab::*;
+pub use comments::*;
 pub use contacts::*;
 pub use daemon_key::*;
 pub use daily_notes::*;
+pub use diagnostics::*;
 pub use document_convert::*;
 pub use drawing::*;
 pub use encryption::*;
@@ -71,37 +115,57 @@ pub use evernote::*;
 pub use external_editor::*;
 pub use external_sources::*;
 pub use file_pages::*;
+pub use find_replace::*;
 pub use flashcard::*;
 pub use folder::*;
+pub use generated_media::*;
 pub use git::*;
 pub use goals::*;
+pub use history::*;
 pub use inbox::*;
 pub use infographic::*;
 pub use joplin::*;
+pub use keep::*;
 pub use library::*;
+pub use lint::*;
+pub use logging::*;
 pub use markdown::*;
 pub use mcp::*;
+pub use meeting_notes::*;
 pub use monitor::*;
 pub use notebook::*;
+pub use notifications::*;
 pub use organize::*;
 pub use notion::*;
 pub use obsidian::*;
+pub use ollama::*;
 pub use onenote::*;
 pub use orgmode::*;
 pub use page::*;
 pub use pdf_annotations::*;
 pub use plugins::*;
+pub use proofread::*;
 pub use publish::*;
+pub use python_env::*;
 pub use rag::*;
+pub use reading_queue::*;
+pub use reminders::*;
+pub use roam::*;
 pub use scrivener::*;
+pub use secrets::*;
 pub use section::*;
 pub use share::*;
+pub use spaced_review::*;
+pub use sql_notebook::*;
 pub use study_tools::*;
+pub use scratchpad::*;
 pub use sync::*;
 pub use tags::*;
+pub use translation::*;
 pub use video::*;
 pub use video_generate::*;
 pub use web_clipper::*;
+pub use webdav_server::*;
 pub use web_research::*;
 pub use website_mirror::*;
 pub use window::*;
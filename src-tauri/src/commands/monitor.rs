@@ -133,7 +133,12 @@ pub fn monitor_dismiss_event(state: State<AppState>, event_id: Uuid) -> CommandR
 
 /// Start the monitor scheduler
 #[tauri::command]
-pub fn monitor_start(state: State<AppState>, app_handle: tauri::AppHandle) -> CommandResult<()> {
+pub fn monitor_start(
+    state: State<AppState>,
+    window: tauri::Window,
+    app_handle: tauri::AppHandle,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
     let mut scheduler = state.monitor_scheduler.lock().map_err(|e| e.to_string())?;
 
     if scheduler.as_ref().map(|s| s.is_running()).unwrap_or(false) {
@@ -143,7 +148,7 @@ pub fn monitor_start(state: State<AppState>, app_handle: tauri::AppHandle) -> Co
     let mut new_scheduler = crate::monitor::scheduler::MonitorScheduler::new();
     new_scheduler.start(
         state.monitor_storage.clone(),
-        state.inbox_storage.clone(),
+        lib.inbox_storage.clone(),
         state.python_ai.clone(),
         app_handle,
     );
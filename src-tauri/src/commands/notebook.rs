@@ -21,14 +21,23 @@ impl From<StorageError> for CommandError {
 type CommandResult<T> = Result<T, CommandError>;
 
 #[tauri::command]
-pub fn list_notebooks(state: State<AppState>) -> CommandResult<Vec<Notebook>> {
-    let storage = state.storage.lock().unwrap();
+pub fn list_notebooks(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<Notebook>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     storage.list_notebooks().map_err(Into::into)
 }
 
 #[tauri::command]
-pub fn get_notebook(state: State<AppState>, notebook_id: String) -> CommandResult<Notebook> {
-    let storage = state.storage.lock().unwrap();
+pub fn get_notebook(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -38,10 +47,12 @@ pub fn get_notebook(state: State<AppState>, notebook_id: String) -> CommandResul
 #[tauri::command]
 pub fn create_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     name: String,
     notebook_type: Option<String>,
 ) -> CommandResult<Notebook> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_type = match notebook_type.as_deref() {
         Some("zettelkasten") => NotebookType::Zettelkasten,
         _ => NotebookType::Standard,
@@ -52,6 +63,7 @@ pub fn create_notebook(
 #[tauri::command]
 pub fn update_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     name: Option<String>,
     icon: Option<String>,
@@ -62,11 +74,14 @@ pub fn update_notebook(
     system_prompt_mode: Option<String>,
     ai_provider: Option<String>,
     ai_model: Option<String>,
+    ai_profile_id: Option<String>,
     is_pinned: Option<bool>,
     page_sort_by: Option<String>,
     cover_image: Option<String>,
+    exclude_from_review: Option<bool>,
 ) -> CommandResult<Notebook> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -107,6 +122,10 @@ pub fn update_notebook(
     if let Some(model) = ai_model {
         notebook.ai_model = if model.is_empty() { None } else { Some(model) };
     }
+    // Allow setting ai_profile_id to None (empty string clears it)
+    if let Some(profile_id) = ai_profile_id {
+        notebook.ai_profile_id = if profile_id.is_empty() { None } else { Some(profile_id) };
+    }
     // Set is_pinned if provided
     if let Some(pinned) = is_pinned {
         notebook.is_pinned = pinned;
@@ -119,6 +138,9 @@ pub fn update_notebook(
     if let Some(img) = cover_image {
         notebook.cover_image = if img.is_empty() { None } else { Some(img) };
     }
+    if let Some(exclude) = exclude_from_review {
+        notebook.exclude_from_review = exclude;
+    }
     notebook.updated_at = chrono::Utc::now();
 
     storage.update_notebook(&notebook)?;
@@ -136,8 +158,13 @@ pub fn update_notebook(
 }
 
 #[tauri::command]
-pub fn delete_notebook(state: State<AppState>, notebook_id: String) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+pub fn delete_notebook(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -147,10 +174,12 @@ pub fn delete_notebook(state: State<AppState>, notebook_id: String) -> CommandRe
 #[tauri::command]
 pub fn merge_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     source_notebook_id: String,
     target_notebook_id: String,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let source_id = Uuid::parse_str(&source_notebook_id).map_err(|e| CommandError {
         message: format!("Invalid source notebook ID: {}", e),
     })?;
@@ -176,9 +205,11 @@ pub fn merge_notebook(
 #[tauri::command]
 pub fn reorder_notebooks(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_ids: Vec<String>,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let ids: Result<Vec<Uuid>, _> = notebook_ids
         .iter()
         .map(|id| {
@@ -0,0 +1,35 @@
+//! Tauri commands for notifications
+
+use tauri::State;
+
+use crate::notifications::{NotificationCenter, NotificationPreferences, PendingNotification};
+use crate::AppState;
+
+type CommandResult<T> = Result<T, String>;
+
+/// Reminders scheduled for today that haven't been shown yet
+#[tauri::command]
+pub fn list_pending_notifications(
+    state: State<AppState>,
+    center: State<NotificationCenter>,
+) -> CommandResult<Vec<PendingNotification>> {
+    Ok(center.pending(&state))
+}
+
+/// Get notification preferences
+#[tauri::command]
+pub fn get_notification_preferences(
+    center: State<NotificationCenter>,
+) -> CommandResult<NotificationPreferences> {
+    Ok(center.preferences())
+}
+
+/// Update notification preferences
+#[tauri::command]
+pub fn update_notification_preferences(
+    center: State<NotificationCenter>,
+    preferences: NotificationPreferences,
+) -> CommandResult<NotificationPreferences> {
+    center.set_preferences(preferences.clone())?;
+    Ok(preferences)
+}
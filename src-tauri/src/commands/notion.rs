@@ -42,9 +42,11 @@ pub fn preview_notion_export(zip_path: String) -> CommandResult<NotionImportPrev
 pub async fn import_notion_export(
     app: AppHandle,
     state: State<'_, AppState>,
+    window: tauri::Window,
     zip_path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = PathBuf::from(&zip_path);
 
     if !path.exists() {
@@ -53,7 +55,7 @@ pub async fn import_notion_export(
 
     // Get notebooks_dir and release the lock immediately
     let notebooks_dir = {
-        let storage = state.storage.lock().map_err(|e| e.to_string())?;
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
         storage.notebooks_base_dir()
     };
 
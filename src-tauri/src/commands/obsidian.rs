@@ -35,9 +35,11 @@ pub fn preview_obsidian_vault_cmd(vault_path: String) -> CommandResult<ObsidianI
 #[tauri::command]
 pub fn import_obsidian_vault_cmd(
     state: State<AppState>,
+    window: tauri::Window,
     vault_path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = Path::new(&vault_path);
 
     if !path.exists() {
@@ -48,7 +50,7 @@ pub fn import_obsidian_vault_cmd(
         return Err("Path is not a directory".to_string());
     }
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
     let notebooks_dir = storage.notebooks_base_dir();
 
     // Import the vault
@@ -0,0 +1,61 @@
+//! Ollama model management commands (see `crate::ollama`).
+
+use tauri::{AppHandle, Emitter};
+
+use crate::ollama::{OllamaClient, OllamaHealth, OllamaModel};
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+fn ollama_error(action: &str, e: crate::ollama::OllamaError) -> CommandError {
+    CommandError {
+        message: format!("Failed to {}: {}", action, e),
+    }
+}
+
+/// Models installed in the Ollama at `base_url` (default localhost)
+#[tauri::command]
+pub async fn ollama_list_models(base_url: Option<String>) -> CommandResult<Vec<OllamaModel>> {
+    OllamaClient::new(base_url.as_deref())
+        .list_models()
+        .await
+        .map_err(|e| ollama_error("list Ollama models", e))
+}
+
+/// Pull a model, emitting `ollama-pull-progress` events as it downloads
+#[tauri::command]
+pub async fn ollama_pull_model(
+    app: AppHandle,
+    model: String,
+    base_url: Option<String>,
+) -> CommandResult<()> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err(CommandError {
+            message: "Model name is required".to_string(),
+        });
+    }
+    OllamaClient::new(base_url.as_deref())
+        .pull_model(model, |progress| {
+            if let Err(e) = app.emit("ollama-pull-progress", &progress) {
+                log::warn!("Failed to emit Ollama pull progress: {}", e);
+            }
+        })
+        .await
+        .map_err(|e| ollama_error(&format!("pull {}", model), e))
+}
+
+#[tauri::command]
+pub async fn ollama_delete_model(model: String, base_url: Option<String>) -> CommandResult<()> {
+    OllamaClient::new(base_url.as_deref())
+        .delete_model(&model)
+        .await
+        .map_err(|e| ollama_error(&format!("delete {}", model), e))
+}
+
+/// Whether Ollama answers, its version and latency
+#[tauri::command]
+pub async fn ollama_health(base_url: Option<String>) -> OllamaHealth {
+    OllamaClient::new(base_url.as_deref()).health().await
+}
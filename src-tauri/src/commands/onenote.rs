@@ -24,16 +24,18 @@ pub fn preview_onenote_cmd(path: String) -> CommandResult<OneNoteImportPreview>
 #[tauri::command]
 pub fn import_onenote_cmd(
     state: State<AppState>,
+    window: tauri::Window,
     path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let source = Path::new(&path);
 
     if !source.exists() {
         return Err("Path does not exist".to_string());
     }
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
     let notebooks_dir = storage.notebooks_base_dir();
     drop(storage);
 
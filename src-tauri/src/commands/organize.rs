@@ -85,10 +85,12 @@ fn extract_text_summary(blocks: &[crate::storage::EditorBlock], max_chars: usize
 #[tauri::command]
 pub async fn smart_organize_suggest(
     state: State<'_, AppState>,
+    window: tauri::Window,
     source_notebook_id: String,
     page_ids: Vec<String>,
     destination_notebook_ids: Vec<String>,
 ) -> CommandResult<Vec<OrganizeSuggestion>> {
+    let lib = state.library(&window);
     let source_nb_id = Uuid::parse_str(&source_notebook_id)
         .map_err(|e| format!("Invalid source notebook ID: {}", e))?;
 
@@ -106,7 +108,7 @@ pub async fn smart_organize_suggest(
 
     // Gather page data and destination context from storage
     let (pages_json, destinations_json) = {
-        let storage = state.storage.lock().map_err(|e| e.to_string())?;
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
 
         // Build page data
         let mut pages_data: Vec<serde_json::Value> = Vec::new();
@@ -242,9 +244,11 @@ pub async fn smart_organize_suggest(
 #[tauri::command]
 pub async fn smart_organize_apply(
     state: State<'_, AppState>,
+    window: tauri::Window,
     source_notebook_id: String,
     moves: Vec<OrganizeMove>,
 ) -> CommandResult<OrganizeApplyResult> {
+    let lib = state.library(&window);
     let source_nb_id = Uuid::parse_str(&source_notebook_id)
         .map_err(|e| format!("Invalid source notebook ID: {}", e))?;
 
@@ -253,7 +257,7 @@ pub async fn smart_organize_apply(
         errors: Vec::new(),
     };
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.write().map_err(|e| e.to_string())?;
 
     for mv in &moves {
         let page_id = match Uuid::parse_str(&mv.page_id) {
@@ -31,16 +31,18 @@ pub fn preview_orgmode_cmd(source_path: String) -> CommandResult<OrgmodeImportPr
 #[tauri::command]
 pub fn import_orgmode_cmd(
     state: State<AppState>,
+    window: tauri::Window,
     source_path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = Path::new(&source_path);
 
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
 
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
     let notebooks_dir = storage.notebooks_base_dir();
 
     let (notebook, pages) = import_orgmode(path, &notebooks_dir, notebook_name)
@@ -5,7 +5,7 @@ use tauri::State;
 use uuid::Uuid;
 
 use crate::git;
-use crate::storage::{EditorData, Page, PageType};
+use crate::storage::{NotebookStats, Page, PageStats, PageType};
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -27,10 +27,12 @@ type CommandResult<T> = Result<T, CommandError>;
 #[tauri::command]
 pub fn list_pages(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     include_archived: Option<bool>,
 ) -> CommandResult<Vec<Page>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -38,7 +40,7 @@ pub fn list_pages(
     // Repair any page/folder section_id mismatches (lightweight, only writes if needed)
     let _ = storage.repair_section_consistency(id);
 
-    let mut pages = storage.list_pages(id)?;
+    let mut pages = storage.list_pages_in_order(id)?;
 
     // Always exclude deleted pages (use list_trash for those)
     pages.retain(|p| p.deleted_at.is_none());
@@ -54,10 +56,12 @@ pub fn list_pages(
 #[tauri::command]
 pub fn get_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -70,6 +74,7 @@ pub fn get_page(
 #[tauri::command(rename_all = "camelCase")]
 pub fn create_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     title: String,
     folder_id: Option<String>,
@@ -79,7 +84,15 @@ pub fn create_page(
     plugin_page_type: Option<String>,
     plugin_data: Option<serde_json::Value>,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    // Taken before the storage lock, for the auto-tagging rules
+    let library_path = state
+        .library_storage
+        .lock()
+        .unwrap()
+        .get_current_library()
+        .map(|library| library.path);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -137,6 +150,13 @@ pub fn create_page(
         storage.update_page(&page)?;
     }
 
+    // Auto-tagging rules
+    if let Ok(library_path) = library_path {
+        if !crate::tags::apply_tag_rules(&storage, &library_path, &mut page).is_empty() {
+            storage.update_page(&page)?;
+        }
+    }
+
     // Notify sync manager of the new page
     state.sync_manager.queue_page_update(nb_id, page.id);
 
@@ -171,10 +191,12 @@ pub fn create_page(
 #[tauri::command]
 pub fn delete_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -223,10 +245,12 @@ pub fn delete_page(
 #[tauri::command]
 pub fn permanent_delete_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -261,10 +285,12 @@ pub fn permanent_delete_page(
 #[tauri::command]
 pub fn restore_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -291,8 +317,13 @@ pub fn restore_page(
 
 /// List all pages in trash for a notebook
 #[tauri::command]
-pub fn list_trash(state: State<AppState>, notebook_id: String) -> CommandResult<Vec<Page>> {
-    let storage = state.storage.lock().unwrap();
+pub fn list_trash(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<Page>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -304,10 +335,12 @@ pub fn list_trash(state: State<AppState>, notebook_id: String) -> CommandResult<
 #[tauri::command]
 pub fn purge_old_trash(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     days: Option<i64>,
 ) -> CommandResult<usize> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -322,12 +355,14 @@ pub fn purge_old_trash(
 #[tauri::command]
 pub fn move_page_to_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     source_notebook_id: String,
     page_id: String,
     target_notebook_id: String,
     target_folder_id: Option<String>,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let source_nb_id = Uuid::parse_str(&source_notebook_id).map_err(|e| CommandError {
         message: format!("Invalid source notebook ID: {}", e),
     })?;
@@ -383,12 +418,14 @@ pub fn move_page_to_notebook(
 #[tauri::command]
 pub fn move_page_to_parent(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     parent_page_id: Option<String>,
     position: Option<i32>,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -459,10 +496,12 @@ pub struct PageContent {
 #[tauri::command]
 pub fn get_page_content(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<PageContent> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -484,11 +523,13 @@ pub fn get_page_content(
 #[tauri::command(rename_all = "camelCase")]
 pub fn get_page_oplog(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     limit: Option<usize>,
 ) -> CommandResult<Vec<crate::storage::oplog::OplogEntry>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -507,14 +548,53 @@ pub fn get_page_oplog(
     Ok(entries)
 }
 
+/// Word count, reading time and revision count of a page; `None` for
+/// encrypted and file-based pages.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_page_stats(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<Option<PageStats>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+
+    Ok(storage.page_stats(nb_id, pg_id)?)
+}
+
+/// Writing statistics summed over a notebook's pages.
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_notebook_stats(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<NotebookStats> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    Ok(storage.notebook_stats(nb_id)?)
+}
+
 /// List available snapshots for a page.
 #[tauri::command(rename_all = "camelCase")]
 pub fn list_page_snapshots(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<Vec<String>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -533,11 +613,13 @@ pub fn list_page_snapshots(
 #[tauri::command(rename_all = "camelCase")]
 pub fn restore_page_snapshot(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     snapshot_name: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -579,10 +661,12 @@ pub fn restore_page_snapshot(
 #[tauri::command(rename_all = "camelCase")]
 pub fn get_block_version_counts(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<HashMap<String, usize>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -611,12 +695,14 @@ pub fn get_block_version_counts(
 #[tauri::command(rename_all = "camelCase")]
 pub fn get_block_history(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     block_id: String,
     limit: Option<usize>,
 ) -> CommandResult<Vec<crate::storage::oplog::BlockHistoryEntry>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -671,12 +757,14 @@ pub fn get_block_history(
 #[tauri::command(rename_all = "camelCase")]
 pub fn revert_block(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     block_id: String,
     snapshot_name: String,
 ) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -739,8 +827,12 @@ pub fn revert_block(
 
 /// Get all favorite pages across all notebooks (lightweight — no page content).
 #[tauri::command]
-pub fn get_all_favorite_pages(state: State<AppState>) -> CommandResult<Vec<FavoritePageEntry>> {
-    let storage = state.storage.lock().unwrap();
+pub fn get_all_favorite_pages(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<FavoritePageEntry>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let notebooks = storage.list_notebooks()?;
 
     let mut entries = Vec::new();
@@ -3,6 +3,8 @@ use std::fs;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::library::LibraryContext;
+use crate::storage::PageType;
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -43,8 +45,8 @@ pub struct PDFPageAnnotations {
 }
 
 /// Get the PDF annotations directory path for a notebook
-fn get_pdf_annotations_dir(state: &State<AppState>, notebook_id: Uuid) -> CommandResult<std::path::PathBuf> {
-    let storage = state.storage.lock().unwrap();
+fn get_pdf_annotations_dir(lib: &LibraryContext, notebook_id: Uuid) -> CommandResult<std::path::PathBuf> {
+    let storage = lib.storage.read().unwrap();
     let assets_path = storage.notebook_assets_dir(notebook_id);
     let pdf_annotations_path = assets_path.join("pdf_annotations");
 
@@ -60,14 +62,16 @@ fn get_pdf_annotations_dir(state: &State<AppState>, notebook_id: Uuid) -> Comman
 #[tauri::command]
 pub fn get_pdf_annotations(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<PDFPageAnnotations> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_pdf_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     if !annotation_path.exists() {
@@ -95,15 +99,17 @@ pub fn get_pdf_annotations(
 #[tauri::command]
 pub fn save_pdf_annotations(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     highlights: Vec<PDFHighlight>,
 ) -> CommandResult<PDFPageAnnotations> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_pdf_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     let annotations = PDFPageAnnotations {
@@ -128,15 +134,17 @@ pub fn save_pdf_annotations(
 #[tauri::command]
 pub fn add_pdf_highlight(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     highlight: PDFHighlight,
 ) -> CommandResult<PDFPageAnnotations> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_pdf_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     // Load existing annotations or create new
@@ -176,17 +184,19 @@ pub fn add_pdf_highlight(
 #[tauri::command]
 pub fn update_pdf_highlight(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     highlight_id: String,
     note: Option<String>,
     color: Option<String>,
 ) -> CommandResult<PDFPageAnnotations> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_pdf_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     if !annotation_path.exists() {
@@ -243,15 +253,17 @@ pub fn update_pdf_highlight(
 #[tauri::command]
 pub fn delete_pdf_highlight(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     highlight_id: String,
 ) -> CommandResult<PDFPageAnnotations> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_pdf_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     if !annotation_path.exists() {
@@ -296,14 +308,16 @@ pub fn delete_pdf_highlight(
 #[tauri::command]
 pub fn delete_pdf_annotations(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let annotations_dir = get_pdf_annotations_dir(&state, nb_id)?;
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
     let annotation_path = annotations_dir.join(format!("{}.json", page_id));
 
     if annotation_path.exists() {
@@ -314,3 +328,282 @@ pub fn delete_pdf_annotations(
 
     Ok(())
 }
+
+/// Result of exporting a PDF with embedded annotations
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotatedPdfExport {
+    pub output_path: String,
+    pub annotation_count: u64,
+    /// Highlights that could not be placed (page out of range, no rects)
+    pub skipped: u64,
+}
+
+/// Result of importing annotations from an externally annotated PDF
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfAnnotationImport {
+    pub annotations: PDFPageAnnotations,
+    pub imported: usize,
+    /// Annotations already present in Nous (e.g. ones Nous exported earlier)
+    pub duplicates: usize,
+}
+
+/// Resolve the on-disk PDF behind a PDF page
+fn get_pdf_source_path(
+    lib: &LibraryContext,
+    nb_id: Uuid,
+    page_id: &str,
+) -> CommandResult<std::path::PathBuf> {
+    let page_uuid = Uuid::parse_str(page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+
+    let storage = lib.storage.read().map_err(|e| CommandError {
+        message: format!("Failed to acquire storage lock: {}", e),
+    })?;
+
+    let page = storage
+        .get_page_any_type(nb_id, page_uuid)
+        .map_err(|e| CommandError {
+            message: format!("Page not found: {}", e),
+        })?;
+
+    if page.page_type != PageType::Pdf {
+        return Err(CommandError {
+            message: "Page is not a PDF page".to_string(),
+        });
+    }
+
+    storage.get_file_path(&page).map_err(|e| CommandError {
+        message: format!("Failed to get file path: {}", e),
+    })
+}
+
+/// Load a page's stored annotations, or an empty container
+fn load_pdf_annotations(
+    annotation_path: &std::path::Path,
+    notebook_id: &str,
+    page_id: &str,
+) -> CommandResult<PDFPageAnnotations> {
+    if !annotation_path.exists() {
+        return Ok(PDFPageAnnotations {
+            page_id: page_id.to_string(),
+            notebook_id: notebook_id.to_string(),
+            highlights: vec![],
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+
+    let content = fs::read_to_string(annotation_path).map_err(|e| CommandError {
+        message: format!("Failed to read PDF annotations: {}", e),
+    })?;
+
+    serde_json::from_str(&content).map_err(|e| CommandError {
+        message: format!("Failed to parse PDF annotations: {}", e),
+    })
+}
+
+/// Identity of a highlight independent of its id: same page, same first
+/// rect (to the nearest point). Catches annotations another tool rewrote
+/// without keeping the /NM name Nous exported.
+fn highlight_position_key(h: &PDFHighlight) -> Option<(i32, i64, i64)> {
+    h.rects
+        .first()
+        .map(|r| (h.page_number, r.x.round() as i64, r.y.round() as i64))
+}
+
+/// Append imported highlights that aren't already present (by id or
+/// position). Returns `(imported, duplicates)`.
+fn merge_imported_highlights(
+    existing: &mut Vec<PDFHighlight>,
+    imported: Vec<PDFHighlight>,
+) -> (usize, usize) {
+    let mut added = 0;
+    let mut duplicates = 0;
+
+    for highlight in imported {
+        let key = highlight_position_key(&highlight);
+        let is_duplicate = existing.iter().any(|h| {
+            h.id == highlight.id || (key.is_some() && highlight_position_key(h) == key)
+        });
+        if is_duplicate {
+            duplicates += 1;
+        } else {
+            existing.push(highlight);
+            added += 1;
+        }
+    }
+
+    (added, duplicates)
+}
+
+/// Export a PDF page to a new PDF file with its highlights and notes
+/// embedded as real annotations (visible in Preview, Acrobat, etc.)
+#[tauri::command]
+pub fn export_annotated_pdf(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    output_path: String,
+) -> CommandResult<AnnotatedPdfExport> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    let source_path = get_pdf_source_path(&lib, nb_id, &page_id)?;
+    if !source_path.exists() {
+        return Err(CommandError {
+            message: format!("PDF file not found: {}", source_path.display()),
+        });
+    }
+    if std::path::Path::new(&output_path) == source_path {
+        return Err(CommandError {
+            message: "Output path must differ from the source PDF".to_string(),
+        });
+    }
+
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
+    let annotations = load_pdf_annotations(
+        &annotations_dir.join(format!("{}.json", page_id)),
+        &notebook_id,
+        &page_id,
+    )?;
+
+    let highlights = serde_json::to_value(&annotations.highlights).map_err(|e| CommandError {
+        message: format!("Failed to serialize PDF annotations: {}", e),
+    })?;
+
+    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
+        message: format!("Failed to acquire Python AI lock: {}", e),
+    })?;
+
+    let result = python_ai
+        .export_annotated_pdf(&source_path.to_string_lossy(), &output_path, highlights)
+        .map_err(|e| CommandError {
+            message: format!("Failed to export annotated PDF: {}", e),
+        })?;
+
+    Ok(AnnotatedPdfExport {
+        output_path: result
+            .get("output_path")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&output_path)
+            .to_string(),
+        annotation_count: result
+            .get("annotation_count")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        skipped: result.get("skipped").and_then(|v| v.as_u64()).unwrap_or(0),
+    })
+}
+
+/// Import annotations from an externally annotated PDF into a PDF page.
+/// Reads the page's own PDF unless `source_path` points at another copy
+/// (e.g. one annotated in Preview and saved elsewhere).
+#[tauri::command]
+pub fn import_pdf_annotations(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    source_path: Option<String>,
+) -> CommandResult<PdfAnnotationImport> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+
+    let source_path = match source_path {
+        Some(p) => std::path::PathBuf::from(p),
+        None => get_pdf_source_path(&lib, nb_id, &page_id)?,
+    };
+    if !source_path.exists() {
+        return Err(CommandError {
+            message: format!("PDF file not found: {}", source_path.display()),
+        });
+    }
+
+    let result = {
+        let python_ai = state.python_ai.lock().map_err(|e| CommandError {
+            message: format!("Failed to acquire Python AI lock: {}", e),
+        })?;
+        python_ai
+            .import_pdf_annotations(&source_path.to_string_lossy())
+            .map_err(|e| CommandError {
+                message: format!("Failed to read PDF annotations: {}", e),
+            })?
+    };
+
+    let imported: Vec<PDFHighlight> = serde_json::from_value(
+        result.get("highlights").cloned().unwrap_or_default(),
+    )
+    .map_err(|e| CommandError {
+        message: format!("Failed to parse imported annotations: {}", e),
+    })?;
+
+    let annotations_dir = get_pdf_annotations_dir(&lib, nb_id)?;
+    let annotation_path = annotations_dir.join(format!("{}.json", page_id));
+    let mut annotations = load_pdf_annotations(&annotation_path, &notebook_id, &page_id)?;
+
+    let (added, duplicates) = merge_imported_highlights(&mut annotations.highlights, imported);
+
+    if added > 0 {
+        annotations.updated_at = chrono::Utc::now().to_rfc3339();
+        let content = serde_json::to_string_pretty(&annotations).map_err(|e| CommandError {
+            message: format!("Failed to serialize PDF annotations: {}", e),
+        })?;
+        fs::write(&annotation_path, content).map_err(|e| CommandError {
+            message: format!("Failed to save PDF annotations: {}", e),
+        })?;
+    }
+
+    Ok(PdfAnnotationImport {
+        annotations,
+        imported: added,
+        duplicates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(id: &str, page: i32, x: f64, y: f64) -> PDFHighlight {
+        PDFHighlight {
+            id: id.to_string(),
+            page_number: page,
+            rects: vec![PDFRect {
+                x,
+                y,
+                width: 100.0,
+                height: 12.0,
+            }],
+            selected_text: String::new(),
+            note: None,
+            color: "#facc15".to_string(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn merge_skips_round_tripped_and_repositioned_duplicates() {
+        let mut existing = vec![highlight("a", 1, 72.0, 100.0)];
+        let imported = vec![
+            // Exported by Nous and read back: same /NM id
+            highlight("a", 1, 72.0, 100.0),
+            // Rewritten by another tool: new id, same spot (float noise)
+            highlight("pdf-1-1", 1, 72.2, 99.8),
+            // Genuinely new
+            highlight("pdf-2-0", 2, 72.0, 100.0),
+        ];
+
+        let (added, duplicates) = merge_imported_highlights(&mut existing, imported);
+        assert_eq!((added, duplicates), (1, 2));
+        assert_eq!(existing.len(), 2);
+        assert_eq!(existing[1].page_number, 2);
+    }
+}
@@ -20,15 +20,17 @@ pub fn set_plugin_ai_config(
     let Some(ref ph) = state.plugin_host else {
         return Ok(());
     };
-    let host = ph.lock().map_err(|e| e.to_string())?;
-    host.set_ai_config(AIConfig {
+    let mut config = AIConfig {
         provider_type,
         api_key,
         base_url,
         model,
         temperature: None,
         max_tokens: None,
-    });
+    };
+    state.secrets.fill_ai_config(&mut config);
+    let host = ph.lock().map_err(|e| e.to_string())?;
+    host.set_ai_config(config);
     Ok(())
 }
 
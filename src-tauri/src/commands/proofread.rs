@@ -0,0 +1,160 @@
+//! Proofreading commands (see `crate::proofread`).
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::proofread::{self, ProofreadReport, SpellChecker};
+use crate::python_bridge::queue::TaskOptions;
+use crate::python_bridge::AIConfig;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Check a page's spelling against the dictionary for `language` (en_US
+/// unless given) and the library's dictionary. With `grammar`, the AI also
+/// looks for grammar mistakes. When no dictionary for the language is
+/// installed the report's `language` is null and no misspellings are found.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn proofread_page(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    language: Option<String>,
+    grammar: Option<bool>,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    task_id: Option<String>,
+) -> CommandResult<ProofreadReport> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+    let language = language
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| proofread::DEFAULT_LANGUAGE.to_string());
+
+    let lib = state.library(&window);
+    let (fields, mut report) = tokio::task::spawn_blocking(move || -> CommandResult<_> {
+        let (page, library_path) = {
+            let storage = lib.storage.read().map_err(|e| CommandError {
+                message: format!("Storage error: {}", e),
+            })?;
+            (
+                storage.get_page(nb_id, pg_id)?,
+                storage.notebooks_dir().clone(),
+            )
+        };
+        if page.is_encrypted() {
+            return Err(CommandError {
+                message: "Encrypted pages can't be proofread".to_string(),
+            });
+        }
+        let fields = proofread::text_fields(&page);
+        let dictionary = proofread::load_dictionary(&language);
+        let misspellings = match &dictionary {
+            Some((_, dictionary)) => {
+                let custom = proofread::custom_words(&library_path);
+                SpellChecker::new(dictionary.clone(), &custom).check(&fields)
+            }
+            None => Vec::new(),
+        };
+        let report = ProofreadReport {
+            page_id: page.id,
+            language: dictionary.map(|(name, _)| name),
+            misspellings,
+            grammar_checked: false,
+            grammar: Vec::new(),
+        };
+        Ok((fields, report))
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Proofreading task failed: {}", e),
+    })??;
+
+    if !grammar.unwrap_or(false) {
+        return Ok(report);
+    }
+    if !fields.is_empty() {
+        let mut config = AIConfig {
+            provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
+            api_key,
+            model,
+            temperature: Some(0.1),
+            max_tokens: Some(4096),
+            ..Default::default()
+        };
+        state.secrets.fill_ai_config(&mut config);
+        let messages = proofread::grammar_messages(
+            &fields,
+            report
+                .language
+                .as_deref()
+                .unwrap_or(proofread::DEFAULT_LANGUAGE),
+        );
+        let response = state
+            .python_queue
+            .run(
+                TaskOptions::default().with_task_id(task_id),
+                move |python_ai| python_ai.chat(messages, config),
+            )
+            .await
+            .map_err(|e| CommandError {
+                message: e.to_string(),
+            })?
+            .map_err(|e| CommandError {
+                message: format!("AI error: {}", e),
+            })?;
+        report.grammar =
+            proofread::parse_grammar(&response.content, &fields).map_err(|e| CommandError {
+                message: e.to_string(),
+            })?;
+    }
+    report.grammar_checked = true;
+    Ok(report)
+}
+
+/// Languages with a spelling dictionary installed
+#[tauri::command]
+pub fn list_spellcheck_languages() -> Vec<String> {
+    proofread::available_languages()
+}
+
+/// Words in the window's library dictionary
+#[tauri::command]
+pub fn list_dictionary_words(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<String>> {
+    Ok(proofread::custom_words(&state.library_path(&window)?))
+}
+
+/// Add a word to the library dictionary; returns the word list
+#[tauri::command]
+pub fn add_dictionary_word(
+    state: State<AppState>,
+    window: tauri::Window,
+    word: String,
+) -> CommandResult<Vec<String>> {
+    proofread::add_custom_word(&state.library_path(&window)?, &word).map_err(|e| CommandError {
+        message: e.to_string(),
+    })
+}
+
+/// Remove a word from the library dictionary; returns the word list
+#[tauri::command]
+pub fn remove_dictionary_word(
+    state: State<AppState>,
+    window: tauri::Window,
+    word: String,
+) -> CommandResult<Vec<String>> {
+    proofread::remove_custom_word(&state.library_path(&window)?, &word).map_err(|e| CommandError {
+        message: e.to_string(),
+    })
+}
@@ -1,10 +1,17 @@
-use serde::Serialize;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
+use crate::charts;
+use crate::publish::podcast::{self, FeedChannel};
+use crate::publish::pptx::{self, GeneratedNotes, NotesSource, PptxOptions, PptxSlide, SlideEdit};
 use crate::publish::presentation::{self, PresentationOptions};
 use crate::publish::print::{self, PrintOptions};
 use crate::publish::site::{self, PublishOptions, PublishResult};
+use crate::python_bridge::queue::TaskOptions;
+use crate::python_bridge::AIConfig;
 use crate::AppState;
 
 #[derive(Clone, Serialize)]
@@ -18,19 +25,21 @@ struct PublishProgress {
 #[tauri::command]
 pub async fn publish_notebook(
     state: State<'_, AppState>,
+    window: tauri::Window,
     app: AppHandle,
     notebook_id: String,
     output_dir: String,
     theme: String,
     options: PublishOptions,
 ) -> Result<PublishResult, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let out = std::path::PathBuf::from(&output_dir);
 
     tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let progress_fn: site::ProgressFn = Box::new(move |current, total, msg| {
             let _ = app.emit(
@@ -53,6 +62,7 @@ pub async fn publish_notebook(
 #[tauri::command]
 pub async fn publish_selected_pages(
     state: State<'_, AppState>,
+    window: tauri::Window,
     app: AppHandle,
     notebook_id: String,
     page_ids: Vec<String>,
@@ -60,17 +70,18 @@ pub async fn publish_selected_pages(
     theme: String,
     options: PublishOptions,
 ) -> Result<PublishResult, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let parsed_ids: Vec<Uuid> = page_ids
         .iter()
         .map(|id| Uuid::parse_str(id).map_err(|e| format!("Invalid page ID {}: {}", id, e)))
         .collect::<Result<Vec<_>, _>>()?;
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let out = std::path::PathBuf::from(&output_dir);
 
     tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let progress_fn: site::ProgressFn = Box::new(move |current, total, msg| {
             let _ = app.emit(
@@ -101,14 +112,16 @@ pub async fn publish_selected_pages(
 #[tauri::command]
 pub fn preview_publish_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     theme: String,
 ) -> Result<String, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let pg_id = Uuid::parse_str(&page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
 
     site::preview_page(&storage, nb_id, pg_id, &theme)
 }
@@ -117,14 +130,16 @@ pub fn preview_publish_page(
 #[tauri::command]
 pub fn generate_presentation(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     options: PresentationOptions,
 ) -> Result<String, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let pg_id = Uuid::parse_str(&page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
 
     let page = storage
         .get_page(nb_id, pg_id)
@@ -136,18 +151,185 @@ pub fn generate_presentation(
     Ok(presentation::render_presentation_html(&page, &all_pages, &options))
 }
 
+/// Slides a page exports to PPTX with, after the manual edits kept for it.
+#[tauri::command]
+pub fn get_presentation_slides(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    options: PptxOptions,
+) -> Result<Vec<PptxSlide>, String> {
+    let lib = state.library(&window);
+    let nb_id =
+        Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
+
+    let page = storage
+        .get_page(nb_id, pg_id)
+        .map_err(|e| format!("Failed to get page: {}", e))?;
+    let sidecar = pptx::load_sidecar(&pptx::sidecar_path(
+        &storage.notebook_assets_dir(nb_id),
+        pg_id,
+    ));
+    Ok(pptx::apply_sidecar(
+        pptx::page_slides(&page),
+        &sidecar,
+        options.notes,
+    ))
+}
+
+/// Replace the manual slide edits kept for a page, keyed by slide. The page
+/// itself is not changed.
+#[tauri::command]
+pub fn save_presentation_slide_edits(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    edits: HashMap<String, SlideEdit>,
+) -> Result<(), String> {
+    let lib = state.library(&window);
+    let nb_id =
+        Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
+    let storage = lib.storage.write().map_err(|e| e.to_string())?;
+
+    let path = pptx::sidecar_path(&storage.notebook_assets_dir(nb_id), pg_id);
+    let mut sidecar = pptx::load_sidecar(&path);
+    sidecar.edits = edits;
+    pptx::save_sidecar(&path, &sidecar).map_err(|e| format!("Failed to save slide edits: {}", e))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PptxExportResult {
+    pub path: String,
+    pub slides: usize,
+    /// Slides exported with manual edits
+    pub edited_slides: usize,
+}
+
+/// Export a page as a PowerPoint deck to `output_path`. With AI notes,
+/// talking points are written for the slides that have none yet or changed
+/// since, and kept with the slide edits for the next export.
+#[tauri::command]
+pub async fn export_presentation_pptx(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    output_path: String,
+    options: PptxOptions,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+) -> Result<PptxExportResult, String> {
+    let lib = state.library(&window);
+    let nb_id =
+        Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
+
+    let (page, sidecar_path) = {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        let page = storage
+            .get_page(nb_id, pg_id)
+            .map_err(|e| format!("Failed to get page: {}", e))?;
+        (
+            page,
+            pptx::sidecar_path(&storage.notebook_assets_dir(nb_id), pg_id),
+        )
+    };
+    let slides = pptx::page_slides(&page);
+
+    if options.notes == NotesSource::Ai {
+        let sidecar = pptx::load_sidecar(&sidecar_path);
+        let pending: Vec<PptxSlide> = pptx::slides_needing_notes(&slides, &sidecar)
+            .into_iter()
+            .filter(|slide| {
+                !sidecar
+                    .edits
+                    .get(&slide.key)
+                    .is_some_and(|e| e.notes.is_some())
+            })
+            .cloned()
+            .collect();
+        if !pending.is_empty() {
+            let mut config = AIConfig {
+                provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
+                api_key,
+                model,
+                temperature: Some(0.5),
+                max_tokens: Some(4096),
+                ..Default::default()
+            };
+            state.secrets.fill_ai_config(&mut config);
+            let messages =
+                pptx::talking_points_messages(&page.title, &pending.iter().collect::<Vec<_>>());
+            let response = state
+                .python_queue
+                .run(TaskOptions::default(), move |python_ai| {
+                    python_ai.chat(messages, config)
+                })
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| format!("AI error: {}", e))?;
+            let points = pptx::parse_talking_points(&response.content, pending.len())
+                .map_err(|e| format!("Could not read the talking points: {}", e))?;
+
+            let _storage = lib.storage.write().map_err(|e| e.to_string())?;
+            let mut sidecar = pptx::load_sidecar(&sidecar_path);
+            for (slide, notes) in pending.iter().zip(points) {
+                sidecar.ai_notes.insert(
+                    slide.key.clone(),
+                    GeneratedNotes {
+                        source_hash: pptx::slide_hash(slide),
+                        notes,
+                    },
+                );
+            }
+            pptx::save_sidecar(&sidecar_path, &sidecar)
+                .map_err(|e| format!("Failed to save talking points: {}", e))?;
+        }
+    }
+
+    let sidecar = pptx::load_sidecar(&sidecar_path);
+    let slides = pptx::apply_sidecar(slides, &sidecar, options.notes);
+    let edited_slides = slides
+        .iter()
+        .filter(|s| s.edit.is_some() && !s.hidden)
+        .count();
+    let written = pptx::write_pptx(
+        std::path::Path::new(&output_path),
+        &page.title,
+        &slides,
+        pptx::theme(&options.theme),
+        options.template,
+    )
+    .map_err(|e| format!("Failed to write presentation: {}", e))?;
+
+    Ok(PptxExportResult {
+        path: output_path,
+        slides: written,
+        edited_slides,
+    })
+}
+
 /// Generate print-friendly HTML for a page.
 #[tauri::command]
 pub fn generate_print_html(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     options: PrintOptions,
 ) -> Result<String, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let pg_id = Uuid::parse_str(&page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
-    let storage = state.storage.lock().map_err(|e| e.to_string())?;
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
 
     let page = storage
         .get_page(nb_id, pg_id)
@@ -155,6 +337,124 @@ pub fn generate_print_html(
     let all_pages = storage
         .list_pages(nb_id)
         .map_err(|e| format!("Failed to list pages: {}", e))?;
+    let page = charts::with_rendered_charts(&page, &storage.notebook_assets_dir(nb_id));
 
     Ok(print::render_print_html(&page, &all_pages, &options))
 }
+
+/// Where `publish_audio_feed` puts the feed.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AudioFeedTarget {
+    /// Served by the local media server until the app quits.
+    Server,
+    /// Written with copies of the audio to `output_dir`, to be hosted at `base_url`.
+    #[serde(rename_all = "camelCase")]
+    Export {
+        output_dir: String,
+        base_url: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioFeedResult {
+    /// URL to subscribe to in a podcast app.
+    pub feed_url: String,
+    /// Where `feed.xml` was written, for exports.
+    pub feed_path: Option<String>,
+    pub episodes: usize,
+}
+
+/// Publish the generated audio of a notebook (or of every notebook) as a
+/// private podcast feed: page narrations and reading queues, newest first.
+#[tauri::command]
+pub async fn publish_audio_feed(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: Option<String>,
+    title: Option<String>,
+    target: AudioFeedTarget,
+) -> Result<AudioFeedResult, String> {
+    let lib = state.library(&window);
+    let nb_id = notebook_id
+        .map(|id| Uuid::parse_str(&id).map_err(|e| format!("Invalid notebook ID: {}", e)))
+        .transpose()?;
+
+    let (mut episodes, default_title) = {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        let notebooks: Vec<_> = storage
+            .list_notebooks()
+            .map_err(|e| format!("Failed to list notebooks: {}", e))?
+            .into_iter()
+            .filter(|nb| nb_id.map_or(true, |id| id == nb.id))
+            .collect();
+        let default_title = match (nb_id, notebooks.first()) {
+            (Some(_), Some(nb)) => format!("{} audio", nb.name),
+            (Some(id), None) => return Err(format!("Notebook not found: {}", id)),
+            (None, _) => "Nous audio".to_string(),
+        };
+        let episodes: Vec<_> = notebooks
+            .iter()
+            .flat_map(|nb| {
+                podcast::collect_episodes(&storage.notebook_assets_dir(nb.id).join("audio"), nb.id)
+            })
+            .collect();
+        (episodes, default_title)
+    };
+    episodes.sort_by_key(|e| std::cmp::Reverse(e.published_at));
+
+    let mut channel = FeedChannel {
+        title: title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or(default_title),
+        description: "Narrations and reading queues generated in Nous".to_string(),
+        link: String::new(),
+    };
+
+    match target {
+        AudioFeedTarget::Server => {
+            let server_guard = state.video_server.lock().await;
+            let server = server_guard.as_ref().ok_or("Media server not running")?;
+            channel.link = server.feed_url();
+            let xml = podcast::build_feed(&channel, &episodes, |episode| {
+                server.stream_url(&episode.path.to_string_lossy())
+            });
+            server.set_feed(xml);
+            Ok(AudioFeedResult {
+                feed_url: server.feed_url(),
+                feed_path: None,
+                episodes: episodes.len(),
+            })
+        }
+        AudioFeedTarget::Export {
+            output_dir,
+            base_url,
+        } => {
+            let base_url = base_url.trim().trim_end_matches('/').to_string();
+            if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+                return Err("Base URL must start with http:// or https://".to_string());
+            }
+            channel.link = format!("{}/feed.xml", base_url);
+            let feed_url = channel.link.clone();
+            let count = episodes.len();
+            let feed_path = tokio::task::spawn_blocking(move || {
+                podcast::export_feed(
+                    &channel,
+                    &episodes,
+                    std::path::Path::new(&output_dir),
+                    &base_url,
+                )
+                .map_err(|e| format!("Failed to export feed: {}", e))
+            })
+            .await
+            .map_err(|e| format!("Task failed: {}", e))??;
+            Ok(AudioFeedResult {
+                feed_url,
+                feed_path: Some(feed_path.to_string_lossy().into_owned()),
+                episodes: count,
+            })
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Python environment health check and repair commands.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::python_bridge::health::{self, PythonHealthReport};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+#[derive(Clone, Serialize)]
+struct RepairOutput {
+    line: String,
+}
+
+/// Check the Python interpreter and the packages the AI features need
+#[tauri::command]
+pub fn check_python_environment(
+    state: State<AppState>,
+) -> Result<PythonHealthReport, CommandError> {
+    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
+        message: format!("Failed to acquire Python AI lock: {}", e),
+    })?;
+
+    Ok(python_ai.check_environment())
+}
+
+/// Install the required packages into the development venv, emitting each
+/// line of installer output as `python-env-repair-output`, and return the
+/// report from a fresh check
+#[tauri::command]
+pub async fn repair_python_environment(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PythonHealthReport, CommandError> {
+    let nous_py_path = {
+        let python_ai = state.python_ai.lock().map_err(|e| CommandError {
+            message: format!("Failed to acquire Python AI lock: {}", e),
+        })?;
+        python_ai.nous_py_path().to_path_buf()
+    };
+
+    tokio::task::spawn_blocking(move || {
+        health::repair(&nous_py_path, &mut |line| {
+            let _ = app.emit(
+                "python-env-repair-output",
+                RepairOutput {
+                    line: line.to_string(),
+                },
+            );
+        })
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Repair task failed: {}", e),
+    })?
+    .map_err(|e| CommandError {
+        message: format!("Python environment repair failed: {}", e),
+    })?;
+
+    let python_ai = state.python_ai.lock().map_err(|e| CommandError {
+        message: format!("Failed to acquire Python AI lock: {}", e),
+    })?;
+    Ok(python_ai.check_environment())
+}
@@ -1,26 +1,44 @@
 //! RAG (Retrieval-Augmented Generation) commands for semantic search.
 
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 
+use serde::Serialize;
 use serde_json;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
-use crate::rag::{chunk_page, chunk_page_with_text, EmbeddingConfig, SemanticSearchResult};
+use crate::ai_cache::{AiCache, AiCacheKind};
+use crate::encryption::EncryptionManager;
+use crate::library::LibraryContext;
+use crate::python_bridge::queue::{QueueError, TaskOptions};
+use crate::python_bridge::PythonAI;
+use crate::rag::{
+    chunk_page, chunk_page_with_text, embedding_config_json, Chunk, ChunkingStrategy,
+    EmbeddingConfig, LibrarySource, RagScope, SemanticSearchResult, VectorIndex,
+};
 use crate::search::SearchResult;
-use crate::storage::PageType;
+use crate::storage::{FileStorage, Page, PageType};
 use crate::AppState;
 
 use super::CommandError;
 
 /// Get the set of notebook IDs that are encrypted but not unlocked (i.e., locked)
-fn get_locked_notebook_ids(state: &State<AppState>) -> Result<HashSet<Uuid>, CommandError> {
-    let storage = state.storage.lock().map_err(|e| CommandError {
+fn get_locked_notebook_ids(
+    state: &State<AppState>,
+    lib: &LibraryContext,
+) -> Result<HashSet<Uuid>, CommandError> {
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
-    let encryption_manager = &state.encryption_manager;
+    locked_notebook_ids(&storage, &state.encryption_manager)
+}
 
+fn locked_notebook_ids(
+    storage: &FileStorage,
+    encryption_manager: &EncryptionManager,
+) -> Result<HashSet<Uuid>, CommandError> {
     let notebooks = storage.list_notebooks().map_err(|e| CommandError {
         message: format!("Failed to list notebooks: {}", e),
     })?;
@@ -35,26 +53,126 @@ fn get_locked_notebook_ids(state: &State<AppState>) -> Result<HashSet<Uuid>, Com
     Ok(locked_ids)
 }
 
+/// The vector indexes of the libraries in `scope`, for retrieval across
+/// libraries. Libraries open in a window (or in the main window) are
+/// searched through their open index; the others are opened for the query.
+/// Encrypted libraries that are locked are left out.
+pub(crate) fn library_sources(
+    state: &State<AppState>,
+    window: &tauri::Window,
+    scope: &RagScope,
+) -> Result<Vec<LibrarySource>, CommandError> {
+    let library_err = |e: crate::library::LibraryError| CommandError {
+        message: format!("Library error: {}", e),
+    };
+    let (libraries, main_id) = {
+        let library_storage = state.library_storage.lock().map_err(|e| CommandError {
+            message: format!("Failed to acquire library storage lock: {}", e),
+        })?;
+        (
+            library_storage.list_libraries().map_err(library_err)?,
+            library_storage
+                .get_current_library_id()
+                .map_err(library_err)?,
+        )
+    };
+    let window_id = window
+        .label()
+        .strip_prefix("library-")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .unwrap_or(main_id);
+
+    let ids: Vec<Uuid> = match scope {
+        RagScope::CurrentLibrary => vec![window_id],
+        RagScope::Libraries { library_ids } => library_ids.clone(),
+        RagScope::AllLibraries => libraries.iter().map(|l| l.id).collect(),
+    };
+
+    let mut sources = Vec::new();
+    for id in ids {
+        let library = libraries
+            .iter()
+            .find(|l| l.id == id)
+            .ok_or_else(|| CommandError {
+                message: format!("Library not found: {}", id),
+            })?;
+        if library.is_encrypted() && !state.encryption_manager.is_library_unlocked(id) {
+            log::info!("Skipping locked library '{}' in retrieval", library.name);
+            continue;
+        }
+
+        let open = if id == window_id {
+            Some(state.library(window))
+        } else if id == main_id {
+            Some(Arc::clone(&state.main_library))
+        } else {
+            state
+                .library_windows
+                .lock()
+                .ok()
+                .and_then(|windows| windows.get(&id).cloned())
+        };
+        let (index, hidden_notebooks) = match open {
+            Some(lib) => (
+                Arc::clone(&lib.vector_index),
+                get_locked_notebook_ids(state, &lib)?,
+            ),
+            None => {
+                let index =
+                    VectorIndex::new(library.vector_db_path()).map_err(|e| CommandError {
+                        message: format!(
+                            "Failed to open vector index of '{}': {}",
+                            library.name, e
+                        ),
+                    })?;
+                let storage = FileStorage::new(library.path.clone());
+                (
+                    Arc::new(Mutex::new(index)),
+                    locked_notebook_ids(&storage, &state.encryption_manager)?,
+                )
+            }
+        };
+        sources.push(LibrarySource {
+            library_id: id,
+            library_name: library.name.clone(),
+            index,
+            hidden_notebooks,
+        });
+    }
+    Ok(sources)
+}
+
 /// Configure the embedding model for RAG.
 #[tauri::command]
 pub fn configure_embeddings(
     state: State<AppState>,
+    window: tauri::Window,
     provider: String,
     model: String,
     dimensions: u32,
     api_key: Option<String>,
     base_url: Option<String>,
+    chunking: Option<ChunkingStrategy>,
 ) -> Result<(), CommandError> {
-    let mut vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    if let Some(chunking) = &chunking {
+        chunking.validate().map_err(|message| CommandError { message })?;
+    }
+    let lib = state.library(&window);
+    let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
+    // Changing the model keeps the chunking unless a new one is given
+    let chunking = chunking
+        .or_else(|| vector_index.get_config().map(|c| c.chunking.clone()))
+        .unwrap_or_default();
     let config = EmbeddingConfig {
         provider,
         model,
         dimensions,
         api_key,
         base_url,
+        chunking,
     };
 
     vector_index.configure(config).map_err(|e| CommandError {
@@ -68,23 +186,76 @@ pub fn configure_embeddings(
 #[tauri::command]
 pub fn get_embedding_config(
     state: State<AppState>,
+    window: tauri::Window,
 ) -> Result<Option<EmbeddingConfig>, CommandError> {
-    let vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
     Ok(vector_index.get_config().cloned())
 }
 
+/// Change how pages are chunked. Pages already indexed keep their old
+/// chunks until they are re-indexed; `rechunk_library` does all of them.
+#[tauri::command]
+pub fn set_chunking_strategy(
+    state: State<AppState>,
+    window: tauri::Window,
+    strategy: ChunkingStrategy,
+) -> Result<(), CommandError> {
+    strategy
+        .validate()
+        .map_err(|message| CommandError { message })?;
+    let lib = state.library(&window);
+    let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+        message: format!("Failed to acquire vector index lock: {}", e),
+    })?;
+
+    let mut config = vector_index.get_config().cloned().ok_or_else(|| CommandError {
+        message: "Embeddings are not configured".to_string(),
+    })?;
+    config.chunking = strategy;
+    vector_index.configure(config).map_err(|e| CommandError {
+        message: format!("Failed to configure embeddings: {}", e),
+    })
+}
+
+/// The chunking strategy of the library's index (the default when
+/// embeddings aren't configured).
+fn configured_chunking(lib: &LibraryContext) -> Result<ChunkingStrategy, CommandError> {
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+        message: format!("Failed to acquire vector index lock: {}", e),
+    })?;
+    Ok(vector_index
+        .get_config()
+        .map(|c| c.chunking.clone())
+        .unwrap_or_default())
+}
+
+/// Chunk a page for the vector index. Html pages are chunked from the text
+/// of their source file.
+fn page_chunks(storage: &FileStorage, page: &Page, strategy: &ChunkingStrategy) -> Vec<Chunk> {
+    if page.page_type == PageType::Html {
+        let raw = storage.read_native_file_content(page).unwrap_or_default();
+        let text = crate::storage::html_utils::html_to_searchable_text(&raw);
+        chunk_page_with_text(page, &text, strategy)
+    } else {
+        chunk_page(page, strategy)
+    }
+}
+
 /// Perform semantic search using a pre-computed query embedding.
 #[tauri::command]
 pub fn semantic_search(
     state: State<AppState>,
+    window: tauri::Window,
     query_embedding: Vec<f32>,
     notebook_id: Option<String>,
     limit: Option<usize>,
 ) -> Result<Vec<SemanticSearchResult>, CommandError> {
-    let vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -96,7 +267,7 @@ pub fn semantic_search(
         })?;
 
     // Get locked notebook IDs to filter out
-    let locked_ids = get_locked_notebook_ids(&state)?;
+    let locked_ids = get_locked_notebook_ids(&state, &lib)?;
 
     let results = vector_index
         .search(&query_embedding, limit.unwrap_or(10), notebook_uuid)
@@ -123,21 +294,23 @@ pub fn semantic_search(
 #[tauri::command]
 pub fn hybrid_search(
     state: State<AppState>,
+    window: tauri::Window,
     query: String,
     query_embedding: Vec<f32>,
     notebook_id: Option<String>,
     limit: Option<usize>,
     semantic_weight: Option<f32>,
 ) -> Result<Vec<SearchResult>, CommandError> {
+    let lib = state.library(&window);
     let limit = limit.unwrap_or(10);
     let semantic_weight = semantic_weight.unwrap_or(0.5).clamp(0.0, 1.0);
     let keyword_weight = 1.0 - semantic_weight;
 
     // Get locked notebook IDs to filter out
-    let locked_ids = get_locked_notebook_ids(&state)?;
+    let locked_ids = get_locked_notebook_ids(&state, &lib)?;
 
     // Perform semantic search
-    let vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -236,11 +409,13 @@ pub fn hybrid_search(
 #[tauri::command]
 pub fn get_rag_context(
     state: State<AppState>,
+    window: tauri::Window,
     query_embedding: Vec<f32>,
     notebook_id: Option<String>,
     max_chunks: Option<usize>,
 ) -> Result<Vec<SemanticSearchResult>, CommandError> {
-    let vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -252,7 +427,7 @@ pub fn get_rag_context(
         })?;
 
     // Get locked notebook IDs to filter out
-    let locked_ids = get_locked_notebook_ids(&state)?;
+    let locked_ids = get_locked_notebook_ids(&state, &lib)?;
 
     let results = vector_index
         .search(&query_embedding, max_chunks.unwrap_or(5), notebook_uuid)
@@ -279,19 +454,22 @@ pub fn get_rag_context(
 #[tauri::command]
 pub fn index_page_embedding(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
     embeddings: Vec<Vec<f32>>,
 ) -> Result<(), CommandError> {
+    let lib = state.library(&window);
     let notebook_uuid = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
     let page_uuid = Uuid::parse_str(&page_id).map_err(|e| CommandError {
         message: format!("Invalid page ID: {}", e),
     })?;
+    let strategy = configured_chunking(&lib)?;
 
     // Get the page from storage
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -299,16 +477,19 @@ pub fn index_page_embedding(
         message: format!("Failed to get page: {}", e),
     })?;
 
-    // For Html pages, read the source file and extract text for chunking
-    let chunks = if page.page_type == PageType::Html {
-        let raw = storage.read_native_file_content(&page).unwrap_or_default();
-        let text = crate::storage::html_utils::html_to_searchable_text(&raw);
-        drop(storage);
-        chunk_page_with_text(&page, &text)
-    } else {
+    // Encrypted pages stay out of the vector index
+    if page.is_encrypted() {
         drop(storage);
-        chunk_page(&page)
-    };
+        let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+            message: format!("Failed to acquire vector index lock: {}", e),
+        })?;
+        return vector_index.remove_page(page_uuid).map_err(|e| CommandError {
+            message: format!("Failed to remove page: {}", e),
+        });
+    }
+
+    let chunks = page_chunks(&storage, &page, &strategy);
+    drop(storage);
 
     if chunks.len() != embeddings.len() {
         return Err(CommandError {
@@ -321,7 +502,7 @@ pub fn index_page_embedding(
     }
 
     // Index the page
-    let mut vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -336,12 +517,17 @@ pub fn index_page_embedding(
 
 /// Remove a page from the vector index.
 #[tauri::command]
-pub fn remove_page_embedding(state: State<AppState>, page_id: String) -> Result<(), CommandError> {
+pub fn remove_page_embedding(
+    state: State<AppState>,
+    window: tauri::Window,
+    page_id: String,
+) -> Result<(), CommandError> {
+    let lib = state.library(&window);
     let page_uuid = Uuid::parse_str(&page_id).map_err(|e| CommandError {
         message: format!("Invalid page ID: {}", e),
     })?;
 
-    let mut vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -356,10 +542,12 @@ pub fn remove_page_embedding(state: State<AppState>, page_id: String) -> Result<
 #[tauri::command]
 pub fn find_similar_pages(
     state: State<AppState>,
+    window: tauri::Window,
     page_id: String,
     notebook_id: Option<String>,
     limit: Option<usize>,
 ) -> Result<Vec<SemanticSearchResult>, CommandError> {
+    let lib = state.library(&window);
     let page_uuid = Uuid::parse_str(&page_id).map_err(|e| CommandError {
         message: format!("Invalid page ID: {}", e),
     })?;
@@ -371,9 +559,9 @@ pub fn find_similar_pages(
             message: format!("Invalid notebook ID: {}", e),
         })?;
 
-    let locked_ids = get_locked_notebook_ids(&state)?;
+    let locked_ids = get_locked_notebook_ids(&state, &lib)?;
 
-    let vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -398,13 +586,27 @@ pub fn find_similar_pages(
     Ok(filtered_results)
 }
 
-/// Get chunks for a page (for embedding generation).
+/// Get chunks for a page (for embedding generation). Passing a `strategy`
+/// previews how it would chunk the page; without one the index's own
+/// strategy is used.
 #[tauri::command]
 pub fn get_page_chunks(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: String,
+    strategy: Option<ChunkingStrategy>,
 ) -> Result<Vec<String>, CommandError> {
+    let lib = state.library(&window);
+    let strategy = match strategy {
+        Some(strategy) => {
+            strategy
+                .validate()
+                .map_err(|message| CommandError { message })?;
+            strategy
+        }
+        None => configured_chunking(&lib)?,
+    };
     let notebook_uuid = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -412,7 +614,7 @@ pub fn get_page_chunks(
         message: format!("Invalid page ID: {}", e),
     })?;
 
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -420,24 +622,24 @@ pub fn get_page_chunks(
         message: format!("Failed to get page: {}", e),
     })?;
 
-    // For Html pages, read the source file and extract text for chunking
-    let chunks = if page.page_type == PageType::Html {
-        let raw = storage.read_native_file_content(&page).unwrap_or_default();
-        let text = crate::storage::html_utils::html_to_searchable_text(&raw);
-        drop(storage);
-        chunk_page_with_text(&page, &text)
-    } else {
-        drop(storage);
-        chunk_page(&page)
-    };
+    if page.is_encrypted() {
+        return Ok(Vec::new());
+    }
+
+    let chunks = page_chunks(&storage, &page, &strategy);
+    drop(storage);
 
     Ok(chunks.into_iter().map(|c| c.content).collect())
 }
 
 /// Rebuild the vector index (clear all embeddings).
 #[tauri::command]
-pub fn rebuild_vector_index(state: State<AppState>) -> Result<(), CommandError> {
-    let mut vector_index = state.vector_index.lock().map_err(|e| CommandError {
+pub fn rebuild_vector_index(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> Result<(), CommandError> {
+    let lib = state.library(&window);
+    let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -452,8 +654,10 @@ pub fn rebuild_vector_index(state: State<AppState>) -> Result<(), CommandError>
 #[tauri::command]
 pub fn get_vector_index_stats(
     state: State<AppState>,
+    window: tauri::Window,
 ) -> Result<serde_json::Value, CommandError> {
-    let vector_index = state.vector_index.lock().map_err(|e| CommandError {
+    let lib = state.library(&window);
+    let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire vector index lock: {}", e),
     })?;
 
@@ -466,7 +670,7 @@ pub fn get_vector_index_stats(
     })
 }
 
-/// Generate embedding for a single text via Python.
+/// Generate embedding for a single text via Python (cached).
 #[tauri::command]
 pub fn generate_embedding(
     state: State<AppState>,
@@ -477,12 +681,57 @@ pub fn generate_embedding(
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    python_ai.generate_embedding(&text, &config).map_err(|e| CommandError {
+    let key = AiCache::embedding_key(&config, &text);
+    if let Some(embedding) = state.ai_cache.get(&key) {
+        return Ok(embedding);
+    }
+
+    let embedding = python_ai.generate_embedding(&text, &config).map_err(|e| CommandError {
         message: format!("Failed to generate embedding: {}", e),
-    })
+    })?;
+    state.ai_cache.put(AiCacheKind::Embedding, &key, &embedding);
+    Ok(embedding)
+}
+
+/// Embed `texts` with the Python helpers, asking only for the ones the
+/// cache doesn't have.
+fn embed_cached(
+    python_ai: &PythonAI,
+    ai_cache: &AiCache,
+    texts: &[String],
+    config: &str,
+) -> Result<Vec<Vec<f64>>, CommandError> {
+    let keys: Vec<String> = texts
+        .iter()
+        .map(|text| AiCache::embedding_key(config, text))
+        .collect();
+    let mut embeddings: Vec<Option<Vec<f64>>> = keys.iter().map(|key| ai_cache.get(key)).collect();
+    let missing: Vec<usize> = (0..texts.len()).filter(|&i| embeddings[i].is_none()).collect();
+
+    if !missing.is_empty() {
+        let text_refs: Vec<&str> = missing.iter().map(|&i| texts[i].as_str()).collect();
+        let generated = python_ai
+            .generate_embeddings_batch(text_refs, config)
+            .map_err(|e| CommandError {
+                message: format!("Failed to generate embeddings: {}", e),
+            })?;
+        for (&i, embedding) in missing.iter().zip(generated) {
+            ai_cache.put(AiCacheKind::Embedding, &keys[i], &embedding);
+            embeddings[i] = Some(embedding);
+        }
+    }
+
+    embeddings
+        .into_iter()
+        .map(|embedding| {
+            embedding.ok_or_else(|| CommandError {
+                message: "Embedding provider returned fewer embeddings than texts".to_string(),
+            })
+        })
+        .collect()
 }
 
-/// Generate embeddings for multiple texts via Python.
+/// Generate embeddings for multiple texts via Python (cached per text).
 #[tauri::command]
 pub fn generate_embeddings_batch(
     state: State<AppState>,
@@ -493,11 +742,156 @@ pub fn generate_embeddings_batch(
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let text_refs: Vec<&str> = texts.iter().map(|s| s.as_str()).collect();
+    embed_cached(&python_ai, &state.ai_cache, &texts, &config)
+}
 
-    python_ai.generate_embeddings_batch(text_refs, &config).map_err(|e| CommandError {
-        message: format!("Failed to generate embeddings: {}", e),
-    })
+/// Progress of `rechunk_library`, emitted as `rag-rechunk-progress` after
+/// each page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RechunkProgress {
+    pub task_id: Option<String>,
+    pub page_id: Uuid,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Outcome of `rechunk_library`
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RechunkSummary {
+    pub pages: usize,
+    pub chunks: usize,
+    /// Pages whose embeddings failed; they keep their old chunks
+    pub failed: Vec<Uuid>,
+    pub cancelled: bool,
+}
+
+/// Re-chunk and re-embed every page of the library with the index's
+/// current chunking strategy. Pages go through the Python queue one at a
+/// time and each page's chunks are replaced only once its embeddings are
+/// in, so the index stays searchable throughout; `cancel_ai_task` with
+/// `task_id` stops after the current page. Embeddings of chunks whose text
+/// didn't change come from the AI cache.
+#[tauri::command]
+pub async fn rechunk_library(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    task_id: Option<String>,
+) -> Result<RechunkSummary, CommandError> {
+    let lib = state.library(&window);
+    let config = {
+        let vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+            message: format!("Failed to acquire vector index lock: {}", e),
+        })?;
+        vector_index.get_config().cloned().ok_or_else(|| CommandError {
+            message: "Embeddings are not configured".to_string(),
+        })?
+    };
+    let config_json = embedding_config_json(&config);
+
+    // Chunk everything up front so the storage lock isn't held while embedding
+    let pages: Vec<(Page, Vec<Chunk>)> = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Failed to acquire storage lock: {}", e),
+        })?;
+        let locked_ids = locked_notebook_ids(&storage, &state.encryption_manager)?;
+        let notebooks = storage.list_notebooks().map_err(|e| CommandError {
+            message: format!("Failed to list notebooks: {}", e),
+        })?;
+        let mut pages = Vec::new();
+        for notebook in notebooks {
+            if notebook.archived || locked_ids.contains(&notebook.id) {
+                continue;
+            }
+            let notebook_pages = storage.list_pages(notebook.id).map_err(|e| CommandError {
+                message: format!("Failed to list pages: {}", e),
+            })?;
+            for page in notebook_pages {
+                if page.deleted_at.is_some() || page.is_archived || page.is_encrypted() {
+                    continue;
+                }
+                let chunks = page_chunks(&storage, &page, &config.chunking);
+                pages.push((page, chunks));
+            }
+        }
+        pages
+    };
+
+    let mut summary = RechunkSummary::default();
+    let total = pages.len();
+    for (index, (page, chunks)) in pages.into_iter().enumerate() {
+        if chunks.is_empty() {
+            let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+                message: format!("Failed to acquire vector index lock: {}", e),
+            })?;
+            vector_index.remove_page(page.id).map_err(|e| CommandError {
+                message: format!("Failed to remove page: {}", e),
+            })?;
+        } else {
+            let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+            let ai_cache = state.ai_cache.clone();
+            let config_json = config_json.clone();
+            let options = TaskOptions::default().with_task_id(task_id.clone());
+            let embedded = state
+                .python_queue
+                .run(options, move |python_ai| {
+                    embed_cached(python_ai, &ai_cache, &texts, &config_json)
+                })
+                .await;
+            match embedded {
+                Ok(Ok(embeddings)) => {
+                    let embeddings: Vec<Vec<f32>> = embeddings
+                        .into_iter()
+                        .map(|e| e.into_iter().map(|x| x as f32).collect())
+                        .collect();
+                    let mut vector_index = lib.vector_index.lock().map_err(|e| CommandError {
+                        message: format!("Failed to acquire vector index lock: {}", e),
+                    })?;
+                    match vector_index.index_page(page.id, &page.title, &chunks, &embeddings) {
+                        Ok(()) => summary.chunks += chunks.len(),
+                        Err(e) => {
+                            log::warn!("Failed to re-index page {}: {}", page.id, e);
+                            summary.failed.push(page.id);
+                        }
+                    }
+                }
+                Ok(Err(e)) => {
+                    log::warn!("Failed to embed page {}: {}", page.id, e.message);
+                    summary.failed.push(page.id);
+                }
+                Err(QueueError::Cancelled) => {
+                    summary.cancelled = true;
+                    break;
+                }
+                Err(e) => {
+                    log::warn!("Failed to embed page {}: {}", page.id, e);
+                    summary.failed.push(page.id);
+                }
+            }
+        }
+        summary.pages += 1;
+
+        let progress = RechunkProgress {
+            task_id: task_id.clone(),
+            page_id: page.id,
+            done: index + 1,
+            total,
+        };
+        if let Err(e) = app.emit("rag-rechunk-progress", &progress) {
+            log::warn!("Failed to emit re-chunk progress: {}", e);
+        }
+    }
+
+    log::info!(
+        "Re-chunked {} pages into {} chunks ({} failed{})",
+        summary.pages,
+        summary.chunks,
+        summary.failed.len(),
+        if summary.cancelled { ", cancelled" } else { "" }
+    );
+    Ok(summary)
 }
 
 /// Discover available embedding models from a provider.
@@ -0,0 +1,393 @@
+//! Reading queue commands (see `crate::reading_queue`).
+
+use std::fs;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+use crate::library::LibraryContext;
+use crate::publish::podcast::{self, EpisodeMeta};
+use crate::reading_queue::{
+    id3, PlaybackPosition, PlaybackPositions, QueueAudio, QueueChapter, ReadingQueue,
+    ReadingQueueStorage, MAX_QUEUE_PAGES,
+};
+use crate::secrets;
+use crate::storage::{FileStorage, StorageWriteGuard};
+use crate::AppState;
+
+use super::audio::{extract_text_from_blocks, TTSConfig};
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// A queue as the frontend sees it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueueInfo {
+    #[serde(flatten)]
+    pub queue: ReadingQueue,
+    /// Absolute path of the narration, if generated
+    pub audio_path: Option<String>,
+    /// False once pages were added, removed or reordered after narrating
+    pub audio_current: bool,
+    /// Where this user stopped listening
+    pub position: Option<PlaybackPosition>,
+}
+
+fn parse_id(id: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(id).map_err(|e| CommandError {
+        message: format!("Invalid {} ID: {}", what, e),
+    })
+}
+
+fn parse_page_ids(page_ids: &[String]) -> CommandResult<Vec<Uuid>> {
+    if page_ids.len() > MAX_QUEUE_PAGES {
+        return Err(CommandError {
+            message: format!("A reading queue holds at most {} pages", MAX_QUEUE_PAGES),
+        });
+    }
+    page_ids.iter().map(|id| parse_id(id, "page")).collect()
+}
+
+fn open_queues(storage: &FileStorage) -> CommandResult<ReadingQueueStorage> {
+    Ok(ReadingQueueStorage::new(storage.notebooks_dir().clone())?)
+}
+
+fn open_positions() -> CommandResult<PlaybackPositions> {
+    Ok(PlaybackPositions::new(FileStorage::default_data_dir()?)?)
+}
+
+fn info(
+    storage: &FileStorage,
+    positions: &PlaybackPositions,
+    queue: ReadingQueue,
+) -> ReadingQueueInfo {
+    let audio_path = queue.audio.as_ref().map(|audio| {
+        storage
+            .notebook_assets_dir(queue.notebook_id)
+            .join("audio")
+            .join(&audio.file_name)
+            .to_string_lossy()
+            .into_owned()
+    });
+    ReadingQueueInfo {
+        audio_path,
+        audio_current: queue.audio_is_current(),
+        position: positions.get(&queue),
+        queue,
+    }
+}
+
+fn library_storage(lib: &Arc<LibraryContext>) -> CommandResult<StorageWriteGuard<'_>> {
+    lib.storage.write().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })
+}
+
+/// List the reading queues of a notebook, or of the whole library
+#[tauri::command]
+pub fn list_reading_queues(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: Option<String>,
+) -> CommandResult<Vec<ReadingQueueInfo>> {
+    let notebook_id = notebook_id
+        .map(|id| parse_id(&id, "notebook"))
+        .transpose()?;
+    let lib = state.library(&window);
+    let storage = lib.storage.read().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+    let positions = open_positions()?;
+    Ok(open_queues(&storage)?
+        .list(notebook_id)
+        .into_iter()
+        .map(|queue| info(&storage, &positions, queue))
+        .collect())
+}
+
+/// Create a reading queue from pages of a notebook, in reading order
+#[tauri::command]
+pub fn create_reading_queue(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    name: String,
+    page_ids: Vec<String>,
+) -> CommandResult<ReadingQueueInfo> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let page_ids = parse_page_ids(&page_ids)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(CommandError {
+            message: "Reading queue name is required".to_string(),
+        });
+    }
+
+    let lib = state.library(&window);
+    let storage = library_storage(&lib)?;
+    let queue =
+        open_queues(&storage)?.create(ReadingQueue::new(nb_id, name.to_string(), page_ids))?;
+    Ok(info(&storage, &open_positions()?, queue))
+}
+
+/// Rename a reading queue or change its pages. The narration is kept until
+/// it is regenerated; `audioCurrent` tells whether it still matches.
+#[tauri::command]
+pub fn update_reading_queue(
+    state: State<AppState>,
+    window: tauri::Window,
+    queue_id: String,
+    name: Option<String>,
+    page_ids: Option<Vec<String>>,
+) -> CommandResult<ReadingQueueInfo> {
+    let queue_id = parse_id(&queue_id, "reading queue")?;
+    let lib = state.library(&window);
+    let storage = library_storage(&lib)?;
+    let mut queues = open_queues(&storage)?;
+
+    let mut queue = queues.get(queue_id)?;
+    if let Some(name) = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+        queue.name = name;
+    }
+    if let Some(page_ids) = page_ids {
+        queue.page_ids = parse_page_ids(&page_ids)?;
+    }
+    let queue = queues.update(queue)?;
+    Ok(info(&storage, &open_positions()?, queue))
+}
+
+/// Delete a reading queue and its narration
+#[tauri::command]
+pub fn delete_reading_queue(
+    state: State<AppState>,
+    window: tauri::Window,
+    queue_id: String,
+) -> CommandResult<()> {
+    let queue_id = parse_id(&queue_id, "reading queue")?;
+    let lib = state.library(&window);
+    let storage = library_storage(&lib)?;
+    let queue = open_queues(&storage)?.delete(queue_id)?;
+
+    if let Some(audio) = &queue.audio {
+        let path = storage
+            .notebook_assets_dir(queue.notebook_id)
+            .join("audio")
+            .join(&audio.file_name);
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("Failed to remove reading queue audio {:?}: {}", path, e);
+        }
+        let _ = fs::remove_file(path.with_extension("json"));
+    }
+    open_positions()?.remove(queue_id)?;
+    Ok(())
+}
+
+/// Narrate the queue's pages into one MP3 with a chapter per page, stored in
+/// the notebook's audio assets. Pages that are gone, encrypted or have no
+/// text are left out. Replaces any earlier narration and resets the
+/// playback position.
+#[tauri::command]
+pub async fn generate_reading_queue_audio(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    queue_id: String,
+    tts_config: TTSConfig,
+) -> CommandResult<ReadingQueueInfo> {
+    let queue_id = parse_id(&queue_id, "reading queue")?;
+    let lib = state.library(&window);
+    let mut tts_config = tts_config;
+    state.secrets.fill_api_key(
+        &mut tts_config.api_key,
+        &secrets::tts_key_id(&tts_config.provider),
+    );
+
+    let (queue, chapters, audio_dir) = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let queue = open_queues(&storage)?.get(queue_id)?;
+
+        let mut chapters: Vec<(Uuid, String, String)> = Vec::new();
+        for page_id in &queue.page_ids {
+            let page = match storage.get_page(queue.notebook_id, *page_id) {
+                Ok(page) if page.deleted_at.is_none() && !page.is_encrypted() => page,
+                Ok(_) => continue,
+                Err(e) => {
+                    log::warn!(
+                        "Reading queue {}: skipping page {}: {}",
+                        queue.id,
+                        page_id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            let blocks: Vec<serde_json::Value> = page
+                .content
+                .blocks
+                .iter()
+                .map(|b| serde_json::json!({ "type": b.block_type, "data": b.data }))
+                .collect();
+            let text = extract_text_from_blocks(&blocks);
+            if !text.trim().is_empty() {
+                chapters.push((page.id, page.title, text));
+            }
+        }
+        if chapters.is_empty() {
+            return Err(CommandError {
+                message: "None of the queue's pages have text to narrate".to_string(),
+            });
+        }
+
+        let audio_dir = storage.notebook_assets_dir(queue.notebook_id).join("audio");
+        fs::create_dir_all(&audio_dir).map_err(|e| CommandError {
+            message: format!("Failed to create audio directory: {}", e),
+        })?;
+        (queue, chapters, audio_dir)
+    };
+
+    let file_name = format!("reading_queue_{}.mp3", queue.id);
+    let final_path = audio_dir.join(&file_name);
+    // Narrate next to the old file so a failed run leaves it playable
+    let partial_path = audio_dir.join(format!("reading_queue_{}.partial.mp3", queue.id));
+    let partial_str = partial_path
+        .to_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| CommandError {
+            message: "Invalid path encoding".to_string(),
+        })?;
+
+    let python_ai = state.python_ai.clone();
+    let texts: Vec<(String, String)> = chapters
+        .iter()
+        .map(|(_, title, text)| (title.clone(), text.clone()))
+        .collect();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let python_ai = python_ai.lock().map_err(|e| CommandError {
+            message: format!("Failed to acquire Python AI lock: {}", e),
+        })?;
+        python_ai
+            .generate_reading_queue_audio(
+                &texts,
+                &partial_str,
+                &tts_config.provider,
+                &tts_config.voice,
+                tts_config.api_key.as_deref(),
+                tts_config.base_url.as_deref(),
+                tts_config.model.as_deref(),
+                tts_config.speed,
+            )
+            .map_err(|e| CommandError {
+                message: format!("Audio generation error: {}", e),
+            })
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Task join error: {}", e),
+    })??;
+
+    if result.chapters.len() != chapters.len() {
+        let _ = fs::remove_file(&partial_path);
+        return Err(CommandError {
+            message: format!(
+                "Narration returned {} chapters for {} pages",
+                result.chapters.len(),
+                chapters.len()
+            ),
+        });
+    }
+    let chapters: Vec<QueueChapter> = chapters
+        .into_iter()
+        .zip(result.chapters)
+        .map(|((page_id, title, _), marker)| QueueChapter {
+            page_id,
+            title,
+            start_seconds: marker.start_seconds,
+            end_seconds: marker.end_seconds,
+        })
+        .collect();
+
+    let finish = || -> std::io::Result<u64> {
+        id3::write_chapters(&partial_path, &queue.name, &chapters)?;
+        fs::rename(&partial_path, &final_path)?;
+        Ok(fs::metadata(&final_path)?.len())
+    };
+    let file_size = finish().map_err(|e| {
+        let _ = fs::remove_file(&partial_path);
+        CommandError {
+            message: format!("Failed to write chapter markers: {}", e),
+        }
+    })?;
+
+    let storage = library_storage(&lib)?;
+    let mut queues = open_queues(&storage)?;
+    // Re-read in case the queue was renamed while narrating
+    let mut queue = queues.get(queue_id)?;
+    let generated_at = Utc::now();
+    let meta = EpisodeMeta {
+        title: queue.name.clone(),
+        description: chapters
+            .iter()
+            .map(|c| c.title.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        page_id: None,
+        duration_seconds: result.duration_seconds,
+        generated_at,
+    };
+    if let Err(e) = podcast::write_episode_meta(&final_path, &meta) {
+        log::warn!(
+            "Failed to write episode metadata for {:?}: {}",
+            final_path,
+            e
+        );
+    }
+    queue.audio = Some(QueueAudio {
+        file_name,
+        duration_seconds: result.duration_seconds,
+        file_size_bytes: file_size as i64,
+        chapters,
+        generated_at,
+    });
+    let queue = queues.update(queue)?;
+
+    let mut positions = open_positions()?;
+    positions.remove(queue.id)?;
+    log::info!(
+        "Narrated reading queue '{}': {} chapters, {:.0}s in {:.1}s",
+        queue.name,
+        queue.audio.as_ref().map(|a| a.chapters.len()).unwrap_or(0),
+        result.duration_seconds,
+        result.generation_time_seconds
+    );
+    Ok(info(&storage, &positions, queue))
+}
+
+/// Remember where the user is in a queue's narration
+#[tauri::command]
+pub fn set_reading_position(
+    state: State<AppState>,
+    window: tauri::Window,
+    queue_id: String,
+    position_seconds: f64,
+) -> CommandResult<PlaybackPosition> {
+    let queue_id = parse_id(&queue_id, "reading queue")?;
+    let generated_at = {
+        let lib = state.library(&window);
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let queue = open_queues(&storage)?.get(queue_id)?;
+        queue
+            .audio
+            .map(|audio| audio.generated_at)
+            .ok_or_else(|| CommandError {
+                message: "Reading queue has no narration yet".to_string(),
+            })?
+    };
+    Ok(open_positions()?.set(queue_id, generated_at, position_seconds)?)
+}
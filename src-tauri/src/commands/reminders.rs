@@ -0,0 +1,154 @@
+//! Tauri commands for page reminders
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use tauri::State;
+use uuid::Uuid;
+
+use crate::library::LibraryContext;
+use crate::reminders::{parse_when, PageReminder};
+use crate::AppState;
+
+type CommandResult<T> = Result<T, String>;
+
+fn parse_uuid(s: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(s).map_err(|e| format!("Invalid {} ID: {}", what, e))
+}
+
+fn local_to_utc(local: NaiveDateTime) -> CommandResult<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| format!("{} doesn't exist in the local time zone", local))
+}
+
+/// Resolve a natural-language time ("next tuesday 9am") without saving
+/// anything, so the UI can preview it
+#[tauri::command]
+pub fn parse_reminder_time(when: String) -> CommandResult<DateTime<Utc>> {
+    local_to_utc(parse_when(&when, Local::now().naive_local())?)
+}
+
+/// Set a reminder on a page
+#[tauri::command(rename_all = "camelCase")]
+pub fn set_page_reminder(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    when: String,
+    note: Option<String>,
+) -> CommandResult<PageReminder> {
+    let lib = state.library(&window);
+    create_reminder(&lib, &notebook_id, &page_id, &when, note, false)
+}
+
+/// Snooze a page: resurface it with a notification at a later time ("in 3
+/// months"), for revisiting old notes
+#[tauri::command(rename_all = "camelCase")]
+pub fn snooze_page(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    until: String,
+) -> CommandResult<PageReminder> {
+    let lib = state.library(&window);
+    create_reminder(&lib, &notebook_id, &page_id, &until, None, true)
+}
+
+fn create_reminder(
+    lib: &LibraryContext,
+    notebook_id: &str,
+    page_id: &str,
+    when: &str,
+    note: Option<String>,
+    resurface: bool,
+) -> CommandResult<PageReminder> {
+    let notebook_id = parse_uuid(notebook_id, "notebook")?;
+    let page_id = parse_uuid(page_id, "page")?;
+    let remind_at = parse_reminder_time(when.to_string())?;
+    if remind_at <= Utc::now() {
+        return Err(format!("'{}' is in the past", when.trim()));
+    }
+
+    let title = {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        storage
+            .get_page(notebook_id, page_id)
+            .map_err(|e| e.to_string())?
+            .title
+    };
+    let mut reminder = PageReminder::new(notebook_id, page_id, title, remind_at);
+    if let Some(note) = note.filter(|n| !n.trim().is_empty()) {
+        reminder = reminder.with_note(note.trim().to_string());
+    }
+    if resurface {
+        reminder = reminder.resurfacing();
+    }
+
+    let reminders = lib.reminder_storage.lock().map_err(|e| e.to_string())?;
+    reminders
+        .save_reminder(&reminder)
+        .map_err(|e| e.to_string())?;
+    Ok(reminder)
+}
+
+/// List reminders, optionally for one page. Fired reminders are left out
+/// unless `include_fired` is set.
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_page_reminders(
+    state: State<AppState>,
+    window: tauri::Window,
+    page_id: Option<String>,
+    include_fired: Option<bool>,
+) -> CommandResult<Vec<PageReminder>> {
+    let lib = state.library(&window);
+    let reminders = lib.reminder_storage.lock().map_err(|e| e.to_string())?;
+    let list = match page_id {
+        Some(id) => reminders.list_for_page(parse_uuid(&id, "page")?),
+        None => reminders.list_reminders(),
+    }
+    .map_err(|e| e.to_string())?;
+    let include_fired = include_fired.unwrap_or(false);
+    Ok(list
+        .into_iter()
+        .filter(|r| include_fired || r.is_pending())
+        .collect())
+}
+
+/// Delete a reminder
+#[tauri::command]
+pub fn delete_page_reminder(
+    state: State<AppState>,
+    window: tauri::Window,
+    id: String,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let id = parse_uuid(&id, "reminder")?;
+    let reminders = lib.reminder_storage.lock().map_err(|e| e.to_string())?;
+    reminders.delete_reminder(id).map_err(|e| e.to_string())
+}
+
+/// Reminders falling on a day (local time), for the daily note agenda
+#[tauri::command]
+pub fn get_daily_agenda(
+    state: State<AppState>,
+    window: tauri::Window,
+    date: String, // "YYYY-MM-DD" format
+) -> CommandResult<Vec<PageReminder>> {
+    let lib = state.library(&window);
+    let day = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date '{}': {}", date, e))?;
+    let start = local_to_utc(day.and_hms_opt(0, 0, 0).unwrap_or_default())?;
+    let end = local_to_utc(
+        (day + Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default(),
+    )?;
+
+    let reminders = lib.reminder_storage.lock().map_err(|e| e.to_string())?;
+    reminders
+        .list_between(start, end)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,80 @@
+//! Tauri commands for Roam Research / Logseq import
+
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::roam::{import_roam_with_progress, preview_roam_import, RoamImportPreview};
+use crate::storage::Notebook;
+use crate::AppState;
+
+/// Error type for command results
+type CommandResult<T> = Result<T, String>;
+
+/// Progress event payload
+#[derive(Clone, Serialize)]
+struct ImportProgress {
+    current: usize,
+    total: usize,
+    message: String,
+}
+
+/// Preview a Roam / Logseq export
+///
+/// Accepts a Roam JSON export, a Logseq JSON or EDN export, or a Logseq
+/// graph directory. Returns metadata about the import without importing.
+#[tauri::command]
+pub fn preview_roam_import_cmd(path: String) -> CommandResult<RoamImportPreview> {
+    let path = Path::new(&path);
+
+    if !path.exists() {
+        return Err("Roam / Logseq export not found".to_string());
+    }
+
+    preview_roam_import(path).map_err(|e| e.to_string())
+}
+
+/// Import a Roam / Logseq export as a new notebook
+///
+/// Bullet outlines become nested list blocks, `((block refs))` become
+/// transclusions (or links to the containing page) and daily pages become
+/// daily notes.
+#[tauri::command]
+pub fn import_roam_cmd(
+    app: AppHandle,
+    state: State<AppState>,
+    window: tauri::Window,
+    path: String,
+    notebook_name: Option<String>,
+) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
+    let path = Path::new(&path);
+
+    if !path.exists() {
+        return Err("Roam / Logseq export not found".to_string());
+    }
+
+    let storage = lib.storage.read().map_err(|e| e.to_string())?;
+    let notebooks_dir = storage.notebooks_base_dir();
+
+    let app_clone = app.clone();
+    let progress_callback = move |current: usize, total: usize, message: &str| {
+        let _ = app_clone.emit(
+            "import-progress",
+            ImportProgress {
+                current,
+                total,
+                message: message.to_string(),
+            },
+        );
+    };
+
+    let (notebook, _pages) =
+        import_roam_with_progress(path, &notebooks_dir, notebook_name, progress_callback)
+            .map_err(|e| e.to_string())?;
+
+    // Search indexing is the daemon's job; POST /api/search/rebuild picks
+    // up the new pages.
+    Ok(notebook)
+}
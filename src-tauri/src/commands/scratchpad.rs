@@ -0,0 +1,69 @@
+//! Tauri commands for the library scratchpad (see `sync::scratchpad`)
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::sync::scratchpad::{self, ScratchpadContent};
+use crate::sync::{SyncError, SyncManager};
+use crate::AppState;
+
+type CommandResult<T> = Result<T, String>;
+
+/// Path of the library and whether it syncs
+fn library_info(state: &AppState, library_id: &str) -> CommandResult<(Uuid, PathBuf, bool)> {
+    let library_id = Uuid::parse_str(library_id).map_err(|e| format!("Invalid UUID: {}", e))?;
+    let lib_storage = state.library_storage.lock().map_err(|e| e.to_string())?;
+    let library = lib_storage
+        .get_library(library_id)
+        .map_err(|e| e.to_string())?;
+    Ok((library_id, library.path, library.sync_config.is_some()))
+}
+
+/// Sync the scratchpad in the background; pulled edits arrive as a
+/// `sync-scratchpad-updated` event
+fn spawn_sync(state: &AppState, library_id: Uuid) {
+    let sync_manager: Arc<SyncManager> = Arc::clone(&state.sync_manager);
+    let library_storage = Arc::clone(&state.library_storage);
+    tauri::async_runtime::spawn(async move {
+        match sync_manager
+            .sync_scratchpad(library_id, &library_storage)
+            .await
+        {
+            Ok(_) | Err(SyncError::Paused) => {}
+            Err(e) => log::warn!("Scratchpad sync failed: {}", e),
+        }
+    });
+}
+
+/// The library's scratchpad. Also starts a sync so edits from other
+/// devices show up while it's open.
+#[tauri::command]
+pub fn get_scratchpad(
+    state: State<'_, AppState>,
+    library_id: String,
+) -> CommandResult<ScratchpadContent> {
+    let (library_id, path, synced) = library_info(&state, &library_id)?;
+    let content = scratchpad::read(&path).map_err(|e| e.to_string())?;
+    if synced {
+        spawn_sync(&state, library_id);
+    }
+    Ok(content)
+}
+
+/// Replace the scratchpad text and push it right away when the library syncs
+#[tauri::command]
+pub fn update_scratchpad(
+    state: State<'_, AppState>,
+    library_id: String,
+    text: String,
+) -> CommandResult<ScratchpadContent> {
+    let (library_id, path, synced) = library_info(&state, &library_id)?;
+    let content = scratchpad::write(&path, &text).map_err(|e| e.to_string())?;
+    if synced && content.pending_push {
+        spawn_sync(&state, library_id);
+    }
+    Ok(content)
+}
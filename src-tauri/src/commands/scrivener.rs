@@ -35,9 +35,11 @@ pub fn preview_scrivener_project_cmd(scriv_path: String) -> CommandResult<Scrive
 #[tauri::command]
 pub fn import_scrivener_project_cmd(
     state: State<AppState>,
+    window: tauri::Window,
     scriv_path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = Path::new(&scriv_path);
 
     if !path.exists() {
@@ -49,7 +51,7 @@ pub fn import_scrivener_project_cmd(
     }
 
     let notebooks_dir = {
-        let storage = state.storage.lock().map_err(|e| e.to_string())?;
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
         storage.notebooks_base_dir()
     };
 
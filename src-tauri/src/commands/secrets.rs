@@ -0,0 +1,101 @@
+//! Secrets store commands (see `crate::secrets`). Values go in but never
+//! come back out: the frontend only sees ids, hints and timestamps.
+
+use std::collections::HashMap;
+
+use tauri::State;
+
+use crate::secrets::{self, migrate, SecretError, SecretInfo, SecretsStatus};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+impl From<SecretError> for CommandError {
+    fn from(err: SecretError) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Protection mode, lock state and number of stored secrets
+#[tauri::command]
+pub fn get_secrets_status(state: State<AppState>) -> SecretsStatus {
+    state.secrets.status()
+}
+
+/// Stored secrets, without their values
+#[tauri::command]
+pub fn list_secrets(state: State<AppState>) -> Vec<SecretInfo> {
+    state.secrets.list()
+}
+
+/// Store a secret, replacing any previous value
+#[tauri::command]
+pub fn set_secret(state: State<AppState>, id: String, value: String) -> CommandResult<SecretInfo> {
+    if value.trim().is_empty() {
+        return Err(CommandError {
+            message: "Secret value is required".to_string(),
+        });
+    }
+    Ok(state.secrets.set(&id, value.trim())?)
+}
+
+/// Replace the value of an existing secret
+#[tauri::command]
+pub fn rotate_secret(
+    state: State<AppState>,
+    id: String,
+    value: String,
+) -> CommandResult<SecretInfo> {
+    if value.trim().is_empty() {
+        return Err(CommandError {
+            message: "Secret value is required".to_string(),
+        });
+    }
+    Ok(state.secrets.rotate(&id, value.trim())?)
+}
+
+/// Delete a secret. Returns whether it existed.
+#[tauri::command]
+pub fn delete_secret(state: State<AppState>, id: String) -> CommandResult<bool> {
+    Ok(state.secrets.delete(&id)?)
+}
+
+/// Unlock a passphrase-protected store for this session
+#[tauri::command]
+pub fn unlock_secrets(state: State<AppState>, passphrase: String) -> CommandResult<()> {
+    Ok(state.secrets.unlock(&passphrase)?)
+}
+
+/// Protect the store with a passphrase, or with the OS keyring when
+/// `passphrase` is None
+#[tauri::command]
+pub fn set_secrets_protection(
+    state: State<AppState>,
+    passphrase: Option<String>,
+) -> CommandResult<SecretsStatus> {
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => state.secrets.use_passphrase(&passphrase)?,
+        None => state.secrets.use_keyring()?,
+    }
+    Ok(state.secrets.status())
+}
+
+/// Move plaintext keys the frontend still holds into the store. Returns
+/// the ids now stored, whose local copies the caller can drop.
+#[tauri::command]
+pub fn import_plaintext_secrets(
+    state: State<AppState>,
+    keys: HashMap<String, String>,
+) -> CommandResult<Vec<String>> {
+    if !state.secrets.is_unlocked() {
+        return Ok(Vec::new());
+    }
+    let keys = keys
+        .into_iter()
+        .filter(|(id, _)| secrets::validate_id(id).is_ok());
+    Ok(migrate::import_plaintext(&state.secrets, keys)?)
+}
@@ -13,8 +13,13 @@ type CommandResult<T> = Result<T, CommandError>;
 
 /// List all sections in a notebook
 #[tauri::command]
-pub fn list_sections(state: State<AppState>, notebook_id: String) -> CommandResult<Vec<Section>> {
-    let storage = state.storage.lock().unwrap();
+pub fn list_sections(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<Section>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -25,10 +30,12 @@ pub fn list_sections(state: State<AppState>, notebook_id: String) -> CommandResu
 #[tauri::command]
 pub fn get_section(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     section_id: String,
 ) -> CommandResult<Section> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -42,11 +49,13 @@ pub fn get_section(
 #[tauri::command]
 pub fn create_section(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     name: String,
     color: Option<String>,
 ) -> CommandResult<Section> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -69,6 +78,7 @@ pub fn create_section(
 #[tauri::command]
 pub fn update_section(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     section_id: String,
     name: Option<String>,
@@ -78,7 +88,8 @@ pub fn update_section(
     system_prompt_mode: Option<String>, // None = don't change, Some("override") or Some("concatenate")
     page_sort_by: Option<String>,      // None = don't change, Some("") = clear, Some(sort) = set
 ) -> CommandResult<Section> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -134,11 +145,13 @@ pub fn update_section(
 #[tauri::command]
 pub fn delete_section(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     section_id: String,
     move_items_to: Option<String>,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -171,10 +184,12 @@ pub fn delete_section(
 #[tauri::command]
 pub fn reorder_sections(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     section_ids: Vec<String>,
 ) -> CommandResult<()> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -206,8 +221,13 @@ pub fn reorder_sections(
 
 /// Get the cover page for a notebook
 #[tauri::command]
-pub fn get_cover_page(state: State<AppState>, notebook_id: String) -> CommandResult<Option<Page>> {
-    let storage = state.storage.lock().unwrap();
+pub fn get_cover_page(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Option<Page>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -216,8 +236,13 @@ pub fn get_cover_page(state: State<AppState>, notebook_id: String) -> CommandRes
 
 /// Create a cover page for a notebook
 #[tauri::command]
-pub fn create_cover_page(state: State<AppState>, notebook_id: String) -> CommandResult<Page> {
-    let storage = state.storage.lock().unwrap();
+pub fn create_cover_page(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Page> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -228,10 +253,12 @@ pub fn create_cover_page(state: State<AppState>, notebook_id: String) -> Command
 #[tauri::command]
 pub fn set_cover_page(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     page_id: Option<String>,
 ) -> CommandResult<Option<Page>> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -251,9 +278,11 @@ pub fn set_cover_page(
 #[tauri::command]
 pub fn repair_orphaned_sections(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<usize> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -278,11 +307,13 @@ pub fn repair_orphaned_sections(
 #[tauri::command]
 pub fn move_section_to_notebook(
     state: State<AppState>,
+    window: tauri::Window,
     source_notebook_id: String,
     section_id: String,
     target_notebook_id: String,
 ) -> CommandResult<Section> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
 
     let src_nb_id = Uuid::parse_str(&source_notebook_id).map_err(|e| CommandError {
         message: format!("Invalid source notebook ID: {}", e),
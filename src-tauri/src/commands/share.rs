@@ -1,14 +1,19 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use uuid::Uuid;
 
 use crate::share::credentials;
+use crate::share::encrypted::{self, SharedPageBundle};
 use crate::share::html_gen::{generate_share_site, render_share_html};
 use crate::share::publish;
 use crate::share::storage::{
     build_multi_share_record, build_share_record, ShareExpiry, ShareRecord, ShareType,
 };
 use crate::share::upload::{self, ShareUploadConfig};
+use crate::storage::{Page, PageType};
 use crate::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -32,8 +37,10 @@ pub struct SharePageResponse {
 #[tauri::command]
 pub async fn share_page(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: SharePageRequest,
 ) -> Result<SharePageResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let pg_id =
@@ -41,7 +48,7 @@ pub async fn share_page(
     let expiry = ShareExpiry::from_str(&request.expiry)?;
     let upload_external = request.upload_external;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let library_storage = state.library_storage.clone();
     let theme = request.theme.clone();
@@ -61,7 +68,7 @@ pub async fn share_page(
     };
 
     let (html, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let page = storage
             .get_page(nb_id, pg_id)
@@ -110,6 +117,178 @@ pub async fn share_page(
     })
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedShareRequest {
+    pub notebook_id: String,
+    pub page_id: String,
+    pub password: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub theme: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedShareResponse {
+    pub output_path: String,
+    pub asset_count: usize,
+    pub size_bytes: u64,
+}
+
+/// Write a page as one password-protected HTML file (assets inlined) that can
+/// be emailed and opened in any browser, or imported with
+/// `import_shared_page`. Unlike `share_page` nothing is recorded or served.
+#[tauri::command]
+pub async fn share_page_encrypted(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    request: EncryptedShareRequest,
+) -> Result<EncryptedShareResponse, String> {
+    let lib = state.library(&window);
+    let nb_id =
+        Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
+    let pg_id =
+        Uuid::parse_str(&request.page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
+    let theme = request.theme.unwrap_or_else(|| "minimal".to_string());
+    let storage = lib.storage.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let (bundle, asset_count) = {
+            let storage = storage.read().map_err(|e| e.to_string())?;
+            let page = storage
+                .get_page(nb_id, pg_id)
+                .map_err(|e| format!("Failed to get page: {}", e))?;
+            if page.page_type != PageType::Standard {
+                return Err("Only standard pages can be shared as encrypted files".to_string());
+            }
+            let all_pages = storage
+                .list_pages(nb_id)
+                .map_err(|e| format!("Failed to list pages: {}", e))?;
+
+            let html = render_share_html(&storage, nb_id, &page, &all_pages, &theme)?;
+            let assets_dir = storage.notebook_assets_dir(nb_id);
+            let mut bundle = SharedPageBundle::new(page, html);
+            for rel in encrypted::referenced_assets(&bundle.page) {
+                // Missing files (deleted assets, other notebooks) just stay
+                // as dangling references, same as in the rendered HTML.
+                if let Ok(data) = std::fs::read(assets_dir.join(&rel)) {
+                    bundle.assets.insert(rel, BASE64.encode(data));
+                }
+            }
+            let count = bundle.assets.len();
+            (bundle, count)
+        };
+
+        let sealed = encrypted::seal_shared_page(&bundle, &request.password)?;
+        std::fs::write(&request.output_path, &sealed)
+            .map_err(|e| format!("Failed to write {}: {}", request.output_path, e))?;
+
+        Ok(EncryptedShareResponse {
+            output_path: request.output_path,
+            asset_count,
+            size_bytes: sealed.len() as u64,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Decrypt a file made by `share_page_encrypted` and add it to `notebook_id`
+/// as a new page. Bundled assets are written to the notebook's assets dir
+/// (renamed if a different file already has the name) and the page's
+/// references are pointed at them.
+#[tauri::command]
+pub async fn import_shared_page(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    path: String,
+    password: String,
+    notebook_id: String,
+) -> Result<Page, String> {
+    let lib = state.library(&window);
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
+    let storage = lib.storage.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let file_html = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let bundle = encrypted::open_shared_page(&file_html, &password)?;
+
+        let storage = storage.write().map_err(|e| e.to_string())?;
+        storage
+            .get_notebook(nb_id)
+            .map_err(|e| format!("Notebook not found: {}", e))?;
+        let assets_dir = storage.notebook_assets_dir(nb_id);
+
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        for (rel, b64) in &bundle.assets {
+            // The bundle is untrusted input: only accept paths that stay
+            // inside the assets dir.
+            let Some(safe) = encrypted::asset_relative_path(&format!("assets/{}", rel)) else {
+                continue;
+            };
+            let data = BASE64
+                .decode(b64)
+                .map_err(|e| format!("Corrupt asset {}: {}", rel, e))?;
+
+            let mut target = safe.clone();
+            let existing = assets_dir.join(&target);
+            if existing.exists() && std::fs::read(&existing).ok().as_deref() != Some(&data[..]) {
+                let (dir, name) = match safe.rsplit_once('/') {
+                    Some((dir, name)) => (format!("{}/", dir), name),
+                    None => (String::new(), safe.as_str()),
+                };
+                target = format!(
+                    "{}{}-{}",
+                    dir,
+                    &Uuid::new_v4().simple().to_string()[..8],
+                    name
+                );
+            }
+            let dest = assets_dir.join(&target);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create assets dir: {}", e))?;
+            }
+            std::fs::write(&dest, &data).map_err(|e| format!("Failed to write asset: {}", e))?;
+            renamed.insert(rel.clone(), target);
+        }
+
+        let now = chrono::Utc::now();
+        let mut page = Page {
+            id: Uuid::new_v4(),
+            notebook_id: nb_id,
+            folder_id: None,
+            parent_page_id: None,
+            section_id: None,
+            is_archived: false,
+            is_cover: false,
+            position: 0,
+            deleted_at: None,
+            is_favorite: false,
+            is_daily_note: false,
+            daily_note_date: None,
+            updated_at: now,
+            ..bundle.page
+        };
+        encrypted::rewrite_block_strings(&mut page, &mut |s| {
+            let rel = encrypted::asset_relative_path(s)?;
+            renamed
+                .get(&rel)
+                .map(|target| format!("asset://{}/{}", nb_id, target))
+        });
+
+        storage
+            .create_page_from(page.clone())
+            .map_err(|e| format!("Failed to create page: {}", e))?;
+
+        Ok(page)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PublishToNousRequest {
@@ -133,15 +312,17 @@ pub struct PublishToNousResponse {
 #[tauri::command]
 pub async fn publish_share_to_nous(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: PublishToNousRequest,
 ) -> Result<PublishToNousResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let pg_id =
         Uuid::parse_str(&request.page_id).map_err(|e| format!("Invalid page ID: {}", e))?;
     let expiry = ShareExpiry::from_str(&request.expiry)?;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let library_storage = state.library_storage.clone();
     let theme = request.theme.clone();
@@ -157,7 +338,7 @@ pub async fn publish_share_to_nous(
 
     // Render + build the record (needs the storage lock) off the async thread.
     let (html, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
         let page = storage
             .get_page(nb_id, pg_id)
             .map_err(|e| format!("Failed to get page: {}", e))?;
@@ -268,8 +449,10 @@ pub struct ShareSectionRequest {
 #[tauri::command]
 pub async fn share_folder(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: ShareFolderRequest,
 ) -> Result<SharePageResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let folder_id =
@@ -277,7 +460,7 @@ pub async fn share_folder(
     let expiry = ShareExpiry::from_str(&request.expiry)?;
     let upload_external = request.upload_external;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let library_storage = state.library_storage.clone();
     let theme = request.theme.clone();
@@ -298,7 +481,7 @@ pub async fn share_folder(
     // Generate site in a blocking task
     let allowlist = publish::parse_page_allowlist(&request.page_ids);
     let (site_dir, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let all_pages = storage.list_pages(nb_id).map_err(|e| format!("{}", e))?;
         let all_folders = storage.list_folders(nb_id).map_err(|e| format!("{}", e))?;
@@ -386,8 +569,10 @@ pub async fn share_folder(
 #[tauri::command]
 pub async fn share_section(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: ShareSectionRequest,
 ) -> Result<SharePageResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let section_id =
@@ -395,7 +580,7 @@ pub async fn share_section(
     let expiry = ShareExpiry::from_str(&request.expiry)?;
     let upload_external = request.upload_external;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let library_storage = state.library_storage.clone();
     let theme = request.theme.clone();
@@ -416,7 +601,7 @@ pub async fn share_section(
     // Generate site in a blocking task
     let allowlist = publish::parse_page_allowlist(&request.page_ids);
     let (site_dir, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let all_pages = storage.list_pages(nb_id).map_err(|e| format!("{}", e))?;
         let all_folders = storage.list_folders(nb_id).map_err(|e| format!("{}", e))?;
@@ -515,14 +700,16 @@ pub struct ShareNotebookRequest {
 #[tauri::command]
 pub async fn share_notebook(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: ShareNotebookRequest,
 ) -> Result<SharePageResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let expiry = ShareExpiry::from_str(&request.expiry)?;
     let upload_external = request.upload_external;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let library_storage = state.library_storage.clone();
     let theme = request.theme.clone();
@@ -543,7 +730,7 @@ pub async fn share_notebook(
     // Generate site in a blocking task
     let allowlist = publish::parse_page_allowlist(&request.page_ids);
     let (site_dir, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let notebook = storage
             .get_notebook(nb_id)
@@ -636,15 +823,17 @@ fn publish_identity(state: &AppState) -> Result<(std::path::PathBuf, String), St
 #[tauri::command]
 pub async fn publish_folder_to_nous(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: ShareFolderRequest,
 ) -> Result<PublishToNousResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let folder_id =
         Uuid::parse_str(&request.folder_id).map_err(|e| format!("Invalid folder ID: {}", e))?;
     let expiry = ShareExpiry::from_str(&request.expiry)?;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let theme = request.theme.clone();
     let site_title_opt = request.site_title.clone();
@@ -654,7 +843,7 @@ pub async fn publish_folder_to_nous(
     // Render the folder mini-site under the storage lock, off the async thread.
     let allowlist = publish::parse_page_allowlist(&request.page_ids);
     let (site_dir, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let all_pages = storage.list_pages(nb_id).map_err(|e| format!("{}", e))?;
         let all_folders = storage.list_folders(nb_id).map_err(|e| format!("{}", e))?;
@@ -701,15 +890,17 @@ pub async fn publish_folder_to_nous(
 #[tauri::command]
 pub async fn publish_section_to_nous(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: ShareSectionRequest,
 ) -> Result<PublishToNousResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let section_id =
         Uuid::parse_str(&request.section_id).map_err(|e| format!("Invalid section ID: {}", e))?;
     let expiry = ShareExpiry::from_str(&request.expiry)?;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let theme = request.theme.clone();
     let site_title_opt = request.site_title.clone();
@@ -718,7 +909,7 @@ pub async fn publish_section_to_nous(
 
     let allowlist = publish::parse_page_allowlist(&request.page_ids);
     let (site_dir, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let all_pages = storage.list_pages(nb_id).map_err(|e| format!("{}", e))?;
         let all_folders = storage.list_folders(nb_id).map_err(|e| format!("{}", e))?;
@@ -765,13 +956,15 @@ pub async fn publish_section_to_nous(
 #[tauri::command]
 pub async fn publish_notebook_to_nous(
     state: State<'_, AppState>,
+    window: tauri::Window,
     request: ShareNotebookRequest,
 ) -> Result<PublishToNousResponse, String> {
+    let lib = state.library(&window);
     let nb_id =
         Uuid::parse_str(&request.notebook_id).map_err(|e| format!("Invalid notebook ID: {}", e))?;
     let expiry = ShareExpiry::from_str(&request.expiry)?;
 
-    let storage = state.storage.clone();
+    let storage = lib.storage.clone();
     let share_storage = state.share_storage.clone();
     let theme = request.theme.clone();
     let site_title_opt = request.site_title.clone();
@@ -780,7 +973,7 @@ pub async fn publish_notebook_to_nous(
 
     let allowlist = publish::parse_page_allowlist(&request.page_ids);
     let (site_dir, record) = tokio::task::spawn_blocking(move || {
-        let storage = storage.lock().map_err(|e| e.to_string())?;
+        let storage = storage.read().map_err(|e| e.to_string())?;
 
         let notebook = storage
             .get_notebook(nb_id)
@@ -0,0 +1,52 @@
+//! Spaced-writing review settings commands (see `crate::spaced_review`).
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::spaced_review::{NotebookReviewSettings, SpacedReviewStorage};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+fn open_review_storage(
+    state: &State<AppState>,
+    window: &tauri::Window,
+) -> CommandResult<SpacedReviewStorage> {
+    let lib = state.library(window);
+    let storage = lib.storage.read().map_err(|e| CommandError {
+        message: format!("Storage error: {}", e),
+    })?;
+    Ok(SpacedReviewStorage::new(storage.notebooks_dir().clone())?)
+}
+
+fn parse_notebook_id(notebook_id: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })
+}
+
+/// A notebook's spaced review settings (review is off until turned on)
+#[tauri::command]
+pub fn get_spaced_review_settings(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<NotebookReviewSettings> {
+    let notebook_id = parse_notebook_id(&notebook_id)?;
+    Ok(open_review_storage(&state, &window)?.settings(notebook_id))
+}
+
+/// Save a notebook's spaced review settings, returning them with counts
+/// brought into range
+#[tauri::command]
+pub fn update_spaced_review_settings(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    settings: NotebookReviewSettings,
+) -> CommandResult<NotebookReviewSettings> {
+    let notebook_id = parse_notebook_id(&notebook_id)?;
+    Ok(open_review_storage(&state, &window)?.set_settings(notebook_id, settings)?)
+}
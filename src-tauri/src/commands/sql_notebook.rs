@@ -0,0 +1,153 @@
+//! SQL notebook commands (see `crate::sql_notebook`).
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use tauri::State;
+use uuid::Uuid;
+
+use crate::code_exec::{self, CellResult, CodeExecError, Language};
+use crate::sql_notebook::{self, Dataset, SqlNotebookError};
+use crate::storage::PageType;
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+impl From<SqlNotebookError> for CommandError {
+    fn from(e: SqlNotebookError) -> Self {
+        CommandError {
+            message: e.to_string(),
+        }
+    }
+}
+
+fn parse_id(id: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(id).map_err(|e| CommandError {
+        message: format!("Invalid {} ID: {}", what, e),
+    })
+}
+
+/// Run a SQL cell of a SQL notebook page over the notebook's datasets and
+/// store its result tables on the block. The notebook's code execution
+/// timeout applies; SQL cells don't need SQL enabled for code blocks.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn execute_sql_cell(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    block_id: String,
+) -> CommandResult<CellResult> {
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let pg_id = parse_id(&page_id, "page")?;
+
+    let lib = state.library(&window);
+    let result = tokio::task::spawn_blocking(move || -> CommandResult<CellResult> {
+        let (timeout, block, dir) = {
+            let storage = lib.storage.read().map_err(|e| CommandError {
+                message: format!("Storage error: {}", e),
+            })?;
+            let notebook = storage.get_notebook(nb_id)?;
+            let page = storage.get_page(nb_id, pg_id)?;
+            if page.page_type != PageType::Sql {
+                return Err(CommandError {
+                    message: "Not a SQL notebook page".to_string(),
+                });
+            }
+            let block = code_exec::code_block(&page, &block_id, Some("sql"))?;
+            (
+                notebook.code_exec.unwrap_or_default().sandbox.timeout_secs,
+                block,
+                sql_notebook::datasets_dir(&storage.notebook_assets_dir(nb_id)),
+            )
+        };
+
+        let datasets = sql_notebook::list_datasets(&dir)?;
+        let started = Instant::now();
+        let output =
+            sql_notebook::execute_cell(&dir, &datasets, &block.code, Duration::from_secs(timeout))?;
+        let result = CellResult {
+            block_id: block_id.clone(),
+            language: Language::Sql,
+            success: output.success,
+            outputs: output.outputs,
+            execution_count: block.execution_count + 1,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+
+        // The page may have been edited while the query ran
+        let storage = lib.storage.write().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let mut page = storage.get_page(nb_id, pg_id)?;
+        let Some(target) = page.content.blocks.iter_mut().find(|b| b.id == block_id) else {
+            return Err(CodeExecError::BlockNotFound(block_id).into());
+        };
+        code_exec::store_result(target, &result, None);
+        page.updated_at = chrono::Utc::now();
+        storage.update_page(&page)?;
+        Ok(result)
+    })
+    .await
+    .map_err(|e| CommandError {
+        message: format!("SQL execution task failed: {}", e),
+    })??;
+
+    state.sync_manager.queue_page_update(nb_id, pg_id);
+    Ok(result)
+}
+
+/// The datasets SQL notebook pages in a notebook can query
+#[tauri::command(rename_all = "camelCase")]
+pub fn list_datasets(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<Dataset>> {
+    let id = parse_id(&notebook_id, "notebook")?;
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    let dir = sql_notebook::datasets_dir(&storage.notebook_assets_dir(id));
+    Ok(sql_notebook::list_datasets(&dir)?)
+}
+
+/// Copy a CSV, TSV or Parquet file into the notebook as a dataset. `name`
+/// is the table SQL reads it as, by default the file's name.
+#[tauri::command(rename_all = "camelCase")]
+pub fn add_dataset(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    path: String,
+    name: Option<String>,
+) -> CommandResult<Dataset> {
+    let id = parse_id(&notebook_id, "notebook")?;
+    let source = PathBuf::from(&path);
+    if !source.is_file() {
+        return Err(CommandError {
+            message: format!("File not found: {}", path),
+        });
+    }
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    storage.get_notebook(id)?;
+    let dir = sql_notebook::datasets_dir(&storage.notebook_assets_dir(id));
+    Ok(sql_notebook::add_dataset(&dir, &source, name.as_deref())?)
+}
+
+/// Remove a dataset and its file from the notebook
+#[tauri::command(rename_all = "camelCase")]
+pub fn remove_dataset(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    name: String,
+) -> CommandResult<()> {
+    let id = parse_id(&notebook_id, "notebook")?;
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
+    let dir = sql_notebook::datasets_dir(&storage.notebook_assets_dir(id));
+    Ok(sql_notebook::remove_dataset(&dir, &name)?)
+}
@@ -1,14 +1,23 @@
-//! Study tools Tauri commands for generating educational content.
+//! Study tools Tauri commands for generating educational content. Results
+//! are cached by their inputs (see `crate::ai_cache`).
+
+use std::collections::HashSet;
 
 use tauri::State;
 
+use crate::ai_cache::{AiCache, AiCacheKind};
 use crate::python_bridge::{
     AIConfig, BriefingDocument, CitedResponse, ConceptGraph, FAQ, FlashcardGenerationResult,
     RAGChunk, StudyGuide, StudyGuideOptions, StudyPageContent, Timeline,
 };
+use crate::rag::{embedding_config_json, search_libraries, RagScope};
 use crate::AppState;
 
 use super::notebook::CommandError;
+use super::rag::library_sources;
+
+/// Sources the AI sees when the caller doesn't say (as in `nous_ai.study_tools`)
+const DEFAULT_MAX_CITATIONS: i32 = 5;
 
 /// Generate a study guide from selected pages
 #[tauri::command]
@@ -26,7 +35,7 @@ pub async fn generate_study_guide(
 ) -> Result<StudyGuide, CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -34,6 +43,7 @@ pub async fn generate_study_guide(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
     let options = if depth.is_some() || focus_areas.is_some() || num_practice_questions.is_some() {
         Some(StudyGuideOptions {
@@ -45,7 +55,15 @@ pub async fn generate_study_guide(
         None
     };
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let key = AiCache::key(
+        AiCacheKind::StudyTool,
+        &("study_guide", AiCache::config_inputs(&config), &pages, &options),
+    );
+    if let Some(cached) = state.ai_cache.get(&key) {
+        return Ok(cached);
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -59,7 +77,9 @@ pub async fn generate_study_guide(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+    state.ai_cache.put(AiCacheKind::StudyTool, &key, &result);
+    Ok(result)
 }
 
 /// Generate FAQ from selected pages
@@ -76,7 +96,7 @@ pub async fn generate_faq(
 ) -> Result<FAQ, CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -84,8 +104,17 @@ pub async fn generate_faq(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let key = AiCache::key(
+        AiCacheKind::StudyTool,
+        &("faq", AiCache::config_inputs(&config), &pages, num_questions),
+    );
+    if let Some(cached) = state.ai_cache.get(&key) {
+        return Ok(cached);
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -99,7 +128,9 @@ pub async fn generate_faq(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+    state.ai_cache.put(AiCacheKind::StudyTool, &key, &result);
+    Ok(result)
 }
 
 /// Generate flashcards from selected pages
@@ -117,7 +148,7 @@ pub async fn ai_generate_flashcards(
 ) -> Result<FlashcardGenerationResult, CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -125,8 +156,17 @@ pub async fn ai_generate_flashcards(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let key = AiCache::key(
+        AiCacheKind::StudyTool,
+        &("flashcards", AiCache::config_inputs(&config), &pages, (num_cards, &card_types)),
+    );
+    if let Some(cached) = state.ai_cache.get(&key) {
+        return Ok(cached);
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -140,7 +180,9 @@ pub async fn ai_generate_flashcards(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+    state.ai_cache.put(AiCacheKind::StudyTool, &key, &result);
+    Ok(result)
 }
 
 /// Generate briefing document from selected pages
@@ -157,7 +199,7 @@ pub async fn generate_briefing(
 ) -> Result<BriefingDocument, CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -165,8 +207,17 @@ pub async fn generate_briefing(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let key = AiCache::key(
+        AiCacheKind::StudyTool,
+        &("briefing", AiCache::config_inputs(&config), &pages, include_action_items),
+    );
+    if let Some(cached) = state.ai_cache.get(&key) {
+        return Ok(cached);
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -180,7 +231,9 @@ pub async fn generate_briefing(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+    state.ai_cache.put(AiCacheKind::StudyTool, &key, &result);
+    Ok(result)
 }
 
 /// Extract timeline from selected pages
@@ -196,7 +249,7 @@ pub async fn extract_timeline(
 ) -> Result<Timeline, CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -204,8 +257,17 @@ pub async fn extract_timeline(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let key = AiCache::key(
+        AiCacheKind::StudyTool,
+        &("timeline", AiCache::config_inputs(&config), &pages, ()),
+    );
+    if let Some(cached) = state.ai_cache.get(&key) {
+        return Ok(cached);
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -219,7 +281,9 @@ pub async fn extract_timeline(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+    state.ai_cache.put(AiCacheKind::StudyTool, &key, &result);
+    Ok(result)
 }
 
 /// Extract concept graph from selected pages
@@ -236,7 +300,7 @@ pub async fn extract_concepts(
 ) -> Result<ConceptGraph, CommandError> {
     let python_ai = state.python_ai.clone();
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -244,8 +308,17 @@ pub async fn extract_concepts(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let key = AiCache::key(
+        AiCacheKind::StudyTool,
+        &("concepts", AiCache::config_inputs(&config), &pages, max_nodes),
+    );
+    if let Some(cached) = state.ai_cache.get(&key) {
+        return Ok(cached);
+    }
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -259,16 +332,25 @@ pub async fn extract_concepts(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+    state.ai_cache.put(AiCacheKind::StudyTool, &key, &result);
+    Ok(result)
 }
 
-/// Chat with RAG context and return response with source citations
+/// Chat with RAG context and return response with source citations.
+///
+/// Without a `scope`, the answer draws only on `context_chunks`. With one,
+/// the chunks most relevant to the query are also retrieved from every
+/// library in the scope and added after them; citations then name the
+/// library each source came from.
 #[tauri::command]
 pub async fn chat_with_citations(
     state: State<'_, AppState>,
+    window: tauri::Window,
     query: String,
     context_chunks: Vec<RAGChunk>,
     max_citations: Option<i32>,
+    scope: Option<RagScope>,
     provider_type: Option<String>,
     api_key: Option<String>,
     model: Option<String>,
@@ -276,8 +358,13 @@ pub async fn chat_with_citations(
     max_tokens: Option<i64>,
 ) -> Result<CitedResponse, CommandError> {
     let python_ai = state.python_ai.clone();
+    let ai_cache = state.ai_cache.clone();
+    let sources = match &scope {
+        Some(scope) => library_sources(&state, &window, scope)?,
+        None => Vec::new(),
+    };
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -285,8 +372,55 @@ pub async fn chat_with_citations(
         max_tokens: max_tokens.or(Some(4096)),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
     tauri::async_runtime::spawn_blocking(move || {
+        let mut context_chunks = context_chunks;
+        if scope.is_some() {
+            let limit = max_citations.unwrap_or(DEFAULT_MAX_CITATIONS).max(1) as usize;
+            let retrieved = search_libraries(
+                &sources,
+                |embedding_config| {
+                    let python_ai = python_ai
+                        .lock()
+                        .map_err(|e| format!("Failed to acquire Python AI lock: {}", e))?;
+                    let config_json = embedding_config_json(embedding_config);
+                    let key = AiCache::embedding_key(&config_json, &query);
+                    let embedding: Vec<f64> = match ai_cache.get(&key) {
+                        Some(embedding) => embedding,
+                        None => {
+                            let embedding = python_ai
+                                .generate_embedding(&query, &config_json)
+                                .map_err(|e| e.to_string())?;
+                            ai_cache.put(AiCacheKind::Embedding, &key, &embedding);
+                            embedding
+                        }
+                    };
+                    Ok(embedding.into_iter().map(|x| x as f32).collect())
+                },
+                limit,
+            )
+            .map_err(|message| CommandError { message })?;
+
+            let mut seen: HashSet<String> =
+                context_chunks.iter().map(|c| c.chunk_id.clone()).collect();
+            context_chunks.extend(
+                retrieved
+                    .into_iter()
+                    .filter(|hit| seen.insert(hit.result.chunk_id.clone()))
+                    .map(|hit| RAGChunk {
+                        chunk_id: hit.result.chunk_id,
+                        page_id: hit.result.page_id,
+                        notebook_id: hit.result.notebook_id,
+                        title: hit.result.title,
+                        content: hit.result.content,
+                        score: hit.result.score,
+                        library_id: Some(hit.library_id.to_string()),
+                        library_name: Some(hit.library_name),
+                    }),
+            );
+        }
+
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
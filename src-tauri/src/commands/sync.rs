@@ -1,7 +1,14 @@
+use serde::Serialize;
 use tauri::State;
 use uuid::Uuid;
 
-use crate::sync::{LibrarySyncConfigInput, QueueItem, SyncConfigInput, SyncResult, SyncStatus};
+use crate::sync::{
+    AssetSyncPolicy, LibrarySyncConfigInput, NotebookQueueDetails, PageLockInfo, QueueItem,
+    RemoteNotebook, RemoteNotebookListing, RemotePage, RemotePageVersion, SyncActivity,
+    SyncBandwidth, SyncCompression, SyncConfigInput, SyncDeviceInfo, SyncRepair, SyncResult,
+    SyncStatus, SyncVerifyReport,
+};
+use crate::storage::{EditorData, Page};
 use crate::AppState;
 
 type CommandResult<T> = Result<T, String>;
@@ -28,13 +35,15 @@ pub async fn sync_test_connection(
 #[tauri::command]
 pub async fn sync_configure(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     config: SyncConfigInput,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&notebook_id)?;
 
     state.sync_manager
-        .configure(uuid, &state.storage, config)
+        .configure(uuid, &lib.storage, config)
         .await
         .map_err(|e| e.to_string())
 }
@@ -43,13 +52,15 @@ pub async fn sync_configure(
 #[tauri::command]
 pub async fn sync_status(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<SyncStatus> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&notebook_id)?;
 
     // Short lock just to get the sync_config
     let sync_config = {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.read().unwrap();
         let notebook = storage
             .get_notebook(uuid)
             .map_err(|e| e.to_string())?;
@@ -63,16 +74,116 @@ pub async fn sync_status(
 #[tauri::command]
 pub async fn sync_now(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<SyncResult> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&notebook_id)?;
 
     state.sync_manager
-        .sync_notebook(uuid, &state.storage)
+        .sync_notebook(uuid, &lib.storage)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Check a notebook for drift between local state, the remote manifest and
+/// the remote page files
+#[tauri::command]
+pub async fn sync_verify(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<SyncVerifyReport> {
+    let lib = state.library(&window);
+    let uuid = parse_uuid(&notebook_id)?;
+
+    state.sync_manager
+        .verify_notebook(uuid, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Apply repairs from a `sync_verify` report and sync the notebook
+#[tauri::command]
+pub async fn sync_repair(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    repairs: Vec<SyncRepair>,
+) -> CommandResult<SyncResult> {
+    let lib = state.library(&window);
+    let uuid = parse_uuid(&notebook_id)?;
+
+    state.sync_manager
+        .repair_notebook(uuid, &lib.storage, &repairs)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Set which remote assets this notebook downloads during sync
+#[tauri::command]
+pub fn sync_set_asset_policy(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    policy: AssetSyncPolicy,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let uuid = parse_uuid(&notebook_id)?;
+
+    let storage = lib.storage.write().unwrap();
+    let mut notebook = storage.get_notebook(uuid).map_err(|e| e.to_string())?;
+    let config = notebook
+        .sync_config
+        .as_mut()
+        .ok_or_else(|| "Sync not configured for this notebook".to_string())?;
+    config.asset_policy = policy;
+    storage.update_notebook(&notebook).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnsuredAsset {
+    pub path: String,
+    /// False when the asset was already on this device
+    pub downloaded: bool,
+}
+
+/// Download an asset skipped by the notebook's asset policy. Called when a
+/// page that references the asset is opened.
+#[tauri::command]
+pub async fn ensure_asset_local(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    asset_path: String,
+) -> CommandResult<EnsuredAsset> {
+    let lib = state.library(&window);
+    let uuid = parse_uuid(&notebook_id)?;
+
+    let (path, downloaded) = state.sync_manager
+        .ensure_asset_local(uuid, &lib.storage, &asset_path)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(EnsuredAsset {
+        path: path.to_string_lossy().to_string(),
+        downloaded,
+    })
+}
+
+/// Overall sync state (idle/syncing/error/paused)
+#[tauri::command]
+pub fn sync_get_activity(state: State<'_, AppState>) -> SyncActivity {
+    state.sync_manager.activity()
+}
+
+/// Pause or resume all syncing in this app
+#[tauri::command]
+pub fn sync_set_paused(state: State<'_, AppState>, paused: bool) -> SyncActivity {
+    state.sync_manager.set_paused(paused);
+    state.sync_manager.activity()
+}
+
 /// Get pending sync queue items for a notebook
 #[tauri::command]
 pub async fn sync_queue_status(
@@ -83,16 +194,51 @@ pub async fn sync_queue_status(
     Ok(state.sync_manager.get_queue_items(uuid))
 }
 
+/// Everything waiting to sync, for showing what's pending while offline
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncQueueDetails {
+    /// Whether the current connection is metered (None = unknown)
+    pub metered: Option<bool>,
+    pub notebooks: Vec<NotebookQueueDetails>,
+}
+
+/// List queued operations per notebook with their ages
+#[tauri::command]
+pub fn get_sync_queue_details(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+) -> CommandResult<SyncQueueDetails> {
+    let lib = state.library(&window);
+    let mut notebooks = state.sync_manager.get_queue_details();
+    {
+        let storage = lib.storage.read().map_err(|e| e.to_string())?;
+        for details in &mut notebooks {
+            details.notebook_name = storage
+                .get_notebook(details.notebook_id)
+                .ok()
+                .map(|notebook| notebook.name);
+        }
+    }
+
+    Ok(SyncQueueDetails {
+        metered: crate::sync::bandwidth::is_metered_connection(),
+        notebooks,
+    })
+}
+
 /// Disable sync for a notebook
 #[tauri::command]
 pub async fn sync_disable(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&notebook_id)?;
 
     state.sync_manager
-        .disable_sync(uuid, &state.storage)
+        .disable_sync(uuid, &lib.storage)
         .map_err(|e| e.to_string())
 }
 
@@ -100,13 +246,15 @@ pub async fn sync_disable(
 #[tauri::command]
 pub async fn library_sync_configure(
     state: State<'_, AppState>,
+    window: tauri::Window,
     library_id: String,
     config: LibrarySyncConfigInput,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&library_id)?;
 
     state.sync_manager
-        .configure_library_sync(uuid, &state.library_storage, &state.storage, config)
+        .configure_library_sync(uuid, &state.library_storage, &lib.storage, config)
         .await
         .map_err(|e| e.to_string())
 }
@@ -115,12 +263,14 @@ pub async fn library_sync_configure(
 #[tauri::command]
 pub async fn library_sync_disable(
     state: State<'_, AppState>,
+    window: tauri::Window,
     library_id: String,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&library_id)?;
 
     state.sync_manager
-        .disable_library_sync(uuid, &state.library_storage, &state.storage)
+        .disable_library_sync(uuid, &state.library_storage, &lib.storage)
         .map_err(|e| e.to_string())
 }
 
@@ -128,12 +278,14 @@ pub async fn library_sync_disable(
 #[tauri::command]
 pub async fn library_sync_now(
     state: State<'_, AppState>,
+    window: tauri::Window,
     library_id: String,
 ) -> CommandResult<SyncResult> {
+    let lib = state.library(&window);
     let library_uuid = parse_uuid(&library_id)?;
 
     state.sync_manager
-        .sync_library(library_uuid, &state.library_storage, &state.storage, &state.goals_storage, &state.inbox_storage, &state.contacts_storage, &state.energy_storage)
+        .sync_library(library_uuid, &state.library_storage, &lib.storage, &lib.goals_storage, &lib.inbox_storage, &state.contacts_storage, &state.energy_storage, &lib.action_storage)
         .await
         .map_err(|e| e.to_string())
 }
@@ -142,9 +294,11 @@ pub async fn library_sync_now(
 #[tauri::command]
 pub async fn library_sync_configure_notebook(
     state: State<'_, AppState>,
+    window: tauri::Window,
     library_id: String,
     notebook_id: String,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let lib_uuid = parse_uuid(&library_id)?;
     let nb_uuid = parse_uuid(&notebook_id)?;
 
@@ -160,7 +314,7 @@ pub async fn library_sync_configure_notebook(
     };
 
     state.sync_manager
-        .apply_library_sync_to_notebook(lib_uuid, nb_uuid, &library_config, &state.storage)
+        .apply_library_sync_to_notebook(lib_uuid, nb_uuid, &library_config, &lib.storage)
         .await
         .map_err(|e| e.to_string())
 }
@@ -169,13 +323,15 @@ pub async fn library_sync_configure_notebook(
 #[tauri::command]
 pub async fn sync_update_config(
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     sync_mode: String,
     sync_interval: Option<u64>,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let uuid = parse_uuid(&notebook_id)?;
 
-    let storage = state.storage.lock().unwrap();
+    let storage = lib.storage.write().unwrap();
     let mut notebook = storage.get_notebook(uuid).map_err(|e| e.to_string())?;
 
     let config = notebook
@@ -189,7 +345,7 @@ pub async fn sync_update_config(
     storage.update_notebook(&notebook).map_err(|e| e.to_string())?;
 
     // Notify sync scheduler of config change
-    if let Ok(scheduler) = state.sync_scheduler.try_lock() {
+    if let Ok(scheduler) = lib.sync_scheduler.try_lock() {
         if let Some(ref s) = *scheduler {
             s.reload();
         }
@@ -202,10 +358,12 @@ pub async fn sync_update_config(
 #[tauri::command]
 pub async fn library_sync_update_config(
     state: State<'_, AppState>,
+    window: tauri::Window,
     library_id: String,
     sync_mode: String,
     sync_interval: Option<u64>,
 ) -> CommandResult<()> {
+    let lib = state.library(&window);
     let lib_uuid = parse_uuid(&library_id)?;
     let parsed_mode: crate::sync::SyncMode =
         serde_json::from_value(serde_json::Value::String(sync_mode))
@@ -227,7 +385,7 @@ pub async fn library_sync_update_config(
 
     // Propagate to all managed notebooks
     {
-        let storage = state.storage.lock().unwrap();
+        let storage = lib.storage.write().unwrap();
         let notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
         for mut notebook in notebooks {
             if let Some(ref mut config) = notebook.sync_config {
@@ -241,7 +399,7 @@ pub async fn library_sync_update_config(
     }
 
     // Notify sync scheduler of config change
-    if let Ok(scheduler) = state.sync_scheduler.try_lock() {
+    if let Ok(scheduler) = lib.sync_scheduler.try_lock() {
         if let Some(ref s) = *scheduler {
             s.reload();
         }
@@ -249,3 +407,311 @@ pub async fn library_sync_update_config(
 
     Ok(())
 }
+
+/// Update bandwidth limits for a library and all its managed notebooks
+#[tauri::command]
+pub async fn library_sync_update_bandwidth(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    library_id: String,
+    bandwidth: SyncBandwidth,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let lib_uuid = parse_uuid(&library_id)?;
+    if bandwidth.max_upload_kbps == Some(0) || bandwidth.max_download_kbps == Some(0) {
+        return Err("Bandwidth limits must be greater than zero".to_string());
+    }
+
+    {
+        let lib_storage = state.library_storage.lock().unwrap();
+        let library = lib_storage.get_library(lib_uuid).map_err(|e| e.to_string())?;
+        let mut config = library
+            .sync_config
+            .ok_or_else(|| "Sync not configured for this library".to_string())?;
+        config.bandwidth = bandwidth.clone();
+        lib_storage
+            .update_library_sync_config(lib_uuid, Some(config))
+            .map_err(|e| e.to_string())?;
+    }
+
+    {
+        let storage = lib.storage.write().unwrap();
+        let notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
+        for mut notebook in notebooks {
+            if let Some(ref mut config) = notebook.sync_config {
+                if config.managed_by_library == Some(true) {
+                    config.bandwidth = bandwidth.clone();
+                    let _ = storage.update_notebook(&notebook);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Set upload compression for a library and all its managed notebooks
+#[tauri::command]
+pub async fn library_sync_set_compression(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    library_id: String,
+    compression: SyncCompression,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let lib_uuid = parse_uuid(&library_id)?;
+
+    {
+        let lib_storage = state.library_storage.lock().unwrap();
+        let library = lib_storage.get_library(lib_uuid).map_err(|e| e.to_string())?;
+        let mut config = library
+            .sync_config
+            .ok_or_else(|| "Sync not configured for this library".to_string())?;
+        config.compression = compression;
+        lib_storage
+            .update_library_sync_config(lib_uuid, Some(config))
+            .map_err(|e| e.to_string())?;
+    }
+
+    {
+        let storage = lib.storage.write().unwrap();
+        let notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
+        for mut notebook in notebooks {
+            if let Some(ref mut config) = notebook.sync_config {
+                if config.managed_by_library == Some(true) {
+                    config.compression = compression;
+                    let _ = storage.update_notebook(&notebook);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Turn CRDT delta sync on or off for a library and all its managed notebooks
+#[tauri::command]
+pub async fn library_sync_set_delta_sync(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    library_id: String,
+    enabled: bool,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let lib_uuid = parse_uuid(&library_id)?;
+
+    {
+        let lib_storage = state.library_storage.lock().unwrap();
+        let library = lib_storage.get_library(lib_uuid).map_err(|e| e.to_string())?;
+        let mut config = library
+            .sync_config
+            .ok_or_else(|| "Sync not configured for this library".to_string())?;
+        config.delta_sync = enabled;
+        lib_storage
+            .update_library_sync_config(lib_uuid, Some(config))
+            .map_err(|e| e.to_string())?;
+    }
+
+    {
+        let storage = lib.storage.write().unwrap();
+        let notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
+        for mut notebook in notebooks {
+            if let Some(ref mut config) = notebook.sync_config {
+                if config.managed_by_library == Some(true) {
+                    config.delta_sync = enabled;
+                    let _ = storage.update_notebook(&notebook);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// List the notebooks on a library's sync server without syncing
+#[tauri::command]
+pub async fn browse_remote_library(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    library_id: String,
+) -> CommandResult<Vec<RemoteNotebook>> {
+    let lib = state.library(&window);
+    let lib_uuid = parse_uuid(&library_id)?;
+
+    state.sync_manager
+        .browse_remote_library(lib_uuid, &state.library_storage, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List the pages, folders and sections of a notebook on the server
+#[tauri::command]
+pub async fn browse_remote_notebook(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    library_id: String,
+    notebook_id: String,
+) -> CommandResult<RemoteNotebookListing> {
+    let lib = state.library(&window);
+    let lib_uuid = parse_uuid(&library_id)?;
+    let nb_uuid = parse_uuid(&notebook_id)?;
+
+    state.sync_manager
+        .browse_remote_notebook(lib_uuid, nb_uuid, &state.library_storage, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fetch one page's content from the server without storing it
+#[tauri::command]
+pub async fn fetch_remote_page(
+    state: State<'_, AppState>,
+    library_id: String,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<RemotePage> {
+    let lib_uuid = parse_uuid(&library_id)?;
+    let nb_uuid = parse_uuid(&notebook_id)?;
+    let page_uuid = parse_uuid(&page_id)?;
+
+    state.sync_manager
+        .fetch_remote_page(lib_uuid, nb_uuid, page_uuid, &state.library_storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Claim a page for editing on this device, or extend the claim. Fails
+/// with the holder's device name if another device has it.
+#[tauri::command]
+pub async fn lock_page_for_editing(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<PageLockInfo> {
+    let lib = state.library(&window);
+    let nb_uuid = parse_uuid(&notebook_id)?;
+    let page_uuid = parse_uuid(&page_id)?;
+
+    state.sync_manager
+        .lock_page(nb_uuid, page_uuid, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Release this device's lock on a page
+#[tauri::command]
+pub async fn release_page_lock(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let nb_uuid = parse_uuid(&notebook_id)?;
+    let page_uuid = parse_uuid(&page_id)?;
+
+    state.sync_manager
+        .release_page_lock(nb_uuid, page_uuid, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Page locks in a notebook as of the last sync, from any device
+#[tauri::command]
+pub fn get_page_locks(
+    state: State<'_, AppState>,
+    notebook_id: String,
+) -> CommandResult<Vec<PageLockInfo>> {
+    let uuid = parse_uuid(&notebook_id)?;
+    Ok(state.sync_manager.page_locks(uuid))
+}
+
+/// Devices that have synced a notebook, from the server's registry
+#[tauri::command]
+pub async fn list_sync_devices(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<SyncDeviceInfo>> {
+    let lib = state.library(&window);
+    let uuid = parse_uuid(&notebook_id)?;
+
+    state.sync_manager
+        .list_sync_devices(uuid, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stop another device from syncing a notebook
+#[tauri::command]
+pub async fn revoke_sync_device(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    client_id: String,
+) -> CommandResult<()> {
+    let lib = state.library(&window);
+    let uuid = parse_uuid(&notebook_id)?;
+
+    state.sync_manager
+        .revoke_sync_device(uuid, &client_id, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Versions of a page kept on the sync server, newest first
+#[tauri::command]
+pub async fn list_remote_page_versions(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<Vec<RemotePageVersion>> {
+    let lib = state.library(&window);
+    let nb_uuid = parse_uuid(&notebook_id)?;
+    let page_uuid = parse_uuid(&page_id)?;
+
+    state.sync_manager
+        .list_remote_page_versions(nb_uuid, page_uuid, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Content of a page version on the sync server, for a preview
+#[tauri::command]
+pub async fn get_remote_page_version(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    version: String,
+) -> CommandResult<EditorData> {
+    let lib = state.library(&window);
+    let nb_uuid = parse_uuid(&notebook_id)?;
+    let page_uuid = parse_uuid(&page_id)?;
+
+    state.sync_manager
+        .fetch_remote_page_version(nb_uuid, page_uuid, &version, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Put a page back to a version from the sync server
+#[tauri::command]
+pub async fn restore_remote_page_version(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    version: String,
+) -> CommandResult<Page> {
+    let lib = state.library(&window);
+    let nb_uuid = parse_uuid(&notebook_id)?;
+    let page_uuid = parse_uuid(&page_id)?;
+
+    state.sync_manager
+        .restore_remote_page_version(nb_uuid, page_uuid, &version, &lib.storage)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -4,6 +4,10 @@ use serde::Serialize;
 use tauri::State;
 use uuid::Uuid;
 
+use crate::tags::{
+    library_tag_tree, pages_with_tag, test_rule, RuleMatch, TagDefinition, TagNode, TagRegistry,
+    TagRule, TagRuleSet, TaggedPage,
+};
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -18,8 +22,9 @@ pub struct TagInfo {
 
 /// Get all tags across all notebooks
 #[tauri::command]
-pub fn get_all_tags(state: State<AppState>) -> CommandResult<Vec<TagInfo>> {
-    let storage = state.storage.lock().unwrap();
+pub fn get_all_tags(state: State<AppState>, window: tauri::Window) -> CommandResult<Vec<TagInfo>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let tags = storage.get_all_tags().map_err(|e| CommandError {
         message: format!("Failed to get tags: {}", e),
     })?;
@@ -32,8 +37,13 @@ pub fn get_all_tags(state: State<AppState>) -> CommandResult<Vec<TagInfo>> {
 
 /// Get tags for a specific notebook
 #[tauri::command]
-pub fn get_notebook_tags(state: State<AppState>, notebook_id: String) -> CommandResult<Vec<TagInfo>> {
-    let storage = state.storage.lock().unwrap();
+pub fn get_notebook_tags(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+) -> CommandResult<Vec<TagInfo>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -52,11 +62,13 @@ pub fn get_notebook_tags(state: State<AppState>, notebook_id: String) -> Command
 #[tauri::command]
 pub fn rename_tag(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     old_tag: String,
     new_tag: String,
 ) -> CommandResult<usize> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -72,11 +84,13 @@ pub fn rename_tag(
 #[tauri::command]
 pub fn merge_tags(
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
     tags_to_merge: Vec<String>,
     target_tag: String,
 ) -> CommandResult<usize> {
-    let storage = state.storage.lock().unwrap();
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -90,8 +104,14 @@ pub fn merge_tags(
 
 /// Delete a tag from all pages in a notebook
 #[tauri::command]
-pub fn delete_tag(state: State<AppState>, notebook_id: String, tag: String) -> CommandResult<usize> {
-    let storage = state.storage.lock().unwrap();
+pub fn delete_tag(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    tag: String,
+) -> CommandResult<usize> {
+    let lib = state.library(&window);
+    let storage = lib.storage.write().unwrap();
     let id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
@@ -100,3 +120,154 @@ pub fn delete_tag(state: State<AppState>, notebook_id: String, tag: String) -> C
         message: format!("Failed to delete tag: {}", e),
     })
 }
+
+fn load_registry(
+    state: &AppState,
+    window: &tauri::Window,
+) -> CommandResult<(std::path::PathBuf, TagRegistry)> {
+    let path = state.library_path(window)?;
+    let registry = TagRegistry::load(&path).map_err(|e| CommandError {
+        message: format!("Failed to load tag registry: {}", e),
+    })?;
+    Ok((path, registry))
+}
+
+/// Get the library's tag registry (descriptions, colors, aliases)
+#[tauri::command]
+pub fn get_tag_registry(
+    state: State<AppState>,
+    window: tauri::Window,
+) -> CommandResult<Vec<TagDefinition>> {
+    Ok(load_registry(&state, &window)?.1.tags)
+}
+
+/// Add or update a tag definition
+#[tauri::command]
+pub fn save_tag_definition(
+    state: State<AppState>,
+    window: tauri::Window,
+    definition: TagDefinition,
+) -> CommandResult<TagDefinition> {
+    let (path, mut registry) = load_registry(&state, &window)?;
+    let saved = registry.upsert(definition).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+    registry.save(&path).map_err(|e| CommandError {
+        message: format!("Failed to save tag registry: {}", e),
+    })?;
+    Ok(saved)
+}
+
+/// Remove a tag definition. Pages keep the tag itself.
+#[tauri::command]
+pub fn delete_tag_definition(
+    state: State<AppState>,
+    window: tauri::Window,
+    name: String,
+) -> CommandResult<()> {
+    let (path, mut registry) = load_registry(&state, &window)?;
+    registry.remove(&name).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+    registry.save(&path).map_err(|e| CommandError {
+        message: format!("Failed to save tag registry: {}", e),
+    })
+}
+
+/// Tag hierarchy with counts rolled up from child tags
+#[tauri::command]
+pub fn get_tag_tree(state: State<AppState>, window: tauri::Window) -> CommandResult<Vec<TagNode>> {
+    let lib = state.library(&window);
+    let (_, registry) = load_registry(&state, &window)?;
+    let storage = lib.storage.read().unwrap();
+    library_tag_tree(&storage, &registry).map_err(|e| CommandError {
+        message: format!("Failed to build tag tree: {}", e),
+    })
+}
+
+/// Pages with a tag or one of its aliases; child tags are included unless
+/// `include_children` is false
+#[tauri::command(rename_all = "camelCase")]
+pub fn get_pages_by_tag(
+    state: State<AppState>,
+    window: tauri::Window,
+    tag: String,
+    include_children: Option<bool>,
+) -> CommandResult<Vec<TaggedPage>> {
+    let lib = state.library(&window);
+    let (_, registry) = load_registry(&state, &window)?;
+    let storage = lib.storage.read().unwrap();
+    pages_with_tag(&storage, &registry, &tag, include_children.unwrap_or(true)).map_err(|e| {
+        CommandError {
+            message: format!("Failed to find pages: {}", e),
+        }
+    })
+}
+
+fn load_rules(
+    state: &AppState,
+    window: &tauri::Window,
+) -> CommandResult<(std::path::PathBuf, TagRuleSet)> {
+    let path = state.library_path(window)?;
+    let rules = TagRuleSet::load(&path).map_err(|e| CommandError {
+        message: format!("Failed to load tag rules: {}", e),
+    })?;
+    Ok((path, rules))
+}
+
+/// List the library's auto-tagging rules
+#[tauri::command]
+pub fn list_tag_rules(state: State<AppState>, window: tauri::Window) -> CommandResult<Vec<TagRule>> {
+    Ok(load_rules(&state, &window)?.1.rules)
+}
+
+/// Add or update an auto-tagging rule
+#[tauri::command]
+pub fn save_tag_rule(
+    state: State<AppState>,
+    window: tauri::Window,
+    rule: TagRule,
+) -> CommandResult<TagRule> {
+    let (path, mut rules) = load_rules(&state, &window)?;
+    let saved = rules.upsert(rule).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+    rules.save(&path).map_err(|e| CommandError {
+        message: format!("Failed to save tag rules: {}", e),
+    })?;
+    Ok(saved)
+}
+
+/// Delete an auto-tagging rule. Tags it already added stay.
+#[tauri::command]
+pub fn delete_tag_rule(
+    state: State<AppState>,
+    window: tauri::Window,
+    rule_id: String,
+) -> CommandResult<()> {
+    let id = Uuid::parse_str(&rule_id).map_err(|e| CommandError {
+        message: format!("Invalid rule ID: {}", e),
+    })?;
+    let (path, mut rules) = load_rules(&state, &window)?;
+    rules.remove(id).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+    rules.save(&path).map_err(|e| CommandError {
+        message: format!("Failed to save tag rules: {}", e),
+    })
+}
+
+/// Dry run: which pages a rule would tag, without changing any
+#[tauri::command]
+pub fn test_tag_rule(
+    state: State<AppState>,
+    window: tauri::Window,
+    rule: TagRule,
+    limit: Option<usize>,
+) -> CommandResult<Vec<RuleMatch>> {
+    let lib = state.library(&window);
+    let storage = lib.storage.read().unwrap();
+    test_rule(&storage, &rule, limit.unwrap_or(100)).map_err(|e| CommandError {
+        message: e.to_string(),
+    })
+}
@@ -0,0 +1,386 @@
+//! Page translation commands (see `crate::translation`).
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::library::LibraryContext;
+use crate::python_bridge::queue::{QueueError, TaskOptions};
+use crate::python_bridge::AIConfig;
+use crate::storage::Page;
+use crate::translation::{self, TranslationError, TranslationMode};
+use crate::AppState;
+
+use super::notebook::CommandError;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Outcome of translating one page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageTranslation {
+    pub page_id: Uuid,
+    pub title: String,
+    pub source_language: String,
+    pub target_language: String,
+    pub mode: TranslationMode,
+    /// The page holding the translation: the page itself when replacing,
+    /// the new copy otherwise
+    pub translated_page_id: Uuid,
+    pub segments: usize,
+}
+
+/// A page a folder translation left alone, and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationSkipped {
+    pub page_id: Uuid,
+    pub title: String,
+    pub reason: String,
+}
+
+/// Result of `translate_folder`
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderTranslation {
+    pub translated: Vec<PageTranslation>,
+    pub skipped: Vec<TranslationSkipped>,
+}
+
+/// Progress of a folder translation, emitted as `translation-progress` after
+/// each page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationProgress {
+    pub task_id: Option<String>,
+    pub page_id: Uuid,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Why a single page wasn't translated
+enum Failure {
+    Cancelled,
+    Failed(String),
+}
+
+impl From<TranslationError> for Failure {
+    fn from(e: TranslationError) -> Self {
+        Failure::Failed(e.to_string())
+    }
+}
+
+impl From<Failure> for CommandError {
+    fn from(failure: Failure) -> Self {
+        let message = match failure {
+            Failure::Cancelled => "Translation cancelled".to_string(),
+            Failure::Failed(message) => message,
+        };
+        CommandError { message }
+    }
+}
+
+/// Translate a page into `target_language`, detecting the language it is
+/// written in. In `replace` mode the page is overwritten (its previous
+/// content is kept in page history); in `copy` mode a translated copy is
+/// created next to it, linked to the original through page properties.
+#[tauri::command]
+pub async fn translate_page(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+    target_language: String,
+    mode: TranslationMode,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    task_id: Option<String>,
+) -> CommandResult<PageTranslation> {
+    let target_language = parse_language(&target_language)?;
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let pg_id = parse_id(&page_id, "page")?;
+
+    let lib = state.library(&window);
+    let page = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        storage.get_page(nb_id, pg_id)?
+    };
+
+    let config = ai_config(&state, provider_type, api_key, model);
+    let result = translate_one(
+        &state,
+        &lib,
+        page,
+        &target_language,
+        mode,
+        &config,
+        &task_id,
+    )
+    .await?;
+    log::info!(
+        "Translated page {} from {} to {} ({} segments)",
+        result.page_id,
+        result.source_language,
+        result.target_language,
+        result.segments
+    );
+    Ok(result)
+}
+
+/// Translate every page in a folder and its subfolders, one page at a time
+/// through the Python queue. `cancel_ai_task` with `task_id` stops the batch;
+/// pages already translated stay translated.
+#[tauri::command]
+pub async fn translate_folder(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    folder_id: String,
+    target_language: String,
+    mode: TranslationMode,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+    task_id: Option<String>,
+) -> CommandResult<FolderTranslation> {
+    let target_language = parse_language(&target_language)?;
+    let nb_id = parse_id(&notebook_id, "notebook")?;
+    let folder_id = parse_id(&folder_id, "folder")?;
+
+    let lib = state.library(&window);
+    let pages: Vec<Page> = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })?;
+        let folders = storage.list_folders(nb_id)?;
+        let mut folder_ids = vec![folder_id];
+        let mut i = 0;
+        while i < folder_ids.len() {
+            let parent = folder_ids[i];
+            folder_ids.extend(
+                folders
+                    .iter()
+                    .filter(|f| f.parent_id == Some(parent))
+                    .map(|f| f.id),
+            );
+            i += 1;
+        }
+        let mut pages: Vec<Page> = storage
+            .list_pages(nb_id)?
+            .into_iter()
+            .filter(|p| p.deleted_at.is_none())
+            .filter(|p| p.folder_id.is_some_and(|id| folder_ids.contains(&id)))
+            // Copies made by an earlier run are already translated
+            .filter(|p| {
+                !p.properties
+                    .contains_key(translation::TRANSLATION_OF_PROPERTY)
+            })
+            .collect();
+        pages.sort_by_key(|p| p.position);
+        pages
+    };
+
+    let config = ai_config(&state, provider_type, api_key, model);
+    let total = pages.len();
+    let mut result = FolderTranslation::default();
+    let mut cancelled = false;
+    for (index, page) in pages.into_iter().enumerate() {
+        let (page_id, title) = (page.id, page.title.clone());
+        let skip = |reason: String| TranslationSkipped {
+            page_id,
+            title: title.clone(),
+            reason,
+        };
+        if cancelled {
+            result.skipped.push(skip("cancelled".to_string()));
+            continue;
+        }
+
+        match translate_one(
+            &state,
+            &lib,
+            page,
+            &target_language,
+            mode,
+            &config,
+            &task_id,
+        )
+        .await
+        {
+            Ok(translated) => result.translated.push(translated),
+            Err(Failure::Cancelled) => {
+                cancelled = true;
+                result.skipped.push(skip("cancelled".to_string()));
+            }
+            Err(Failure::Failed(reason)) => result.skipped.push(skip(reason)),
+        }
+
+        let progress = TranslationProgress {
+            task_id: task_id.clone(),
+            page_id,
+            done: index + 1,
+            total,
+        };
+        if let Err(e) = app.emit("translation-progress", &progress) {
+            log::warn!("Failed to emit translation progress: {}", e);
+        }
+    }
+
+    log::info!(
+        "Translated folder {} to {}: {} pages translated, {} skipped",
+        folder_id,
+        target_language,
+        result.translated.len(),
+        result.skipped.len()
+    );
+    Ok(result)
+}
+
+fn parse_id(id: &str, what: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(id).map_err(|e| CommandError {
+        message: format!("Invalid {} ID: {}", what, e),
+    })
+}
+
+fn parse_language(language: &str) -> CommandResult<String> {
+    let language = language.trim();
+    if language.is_empty() {
+        return Err(CommandError {
+            message: "Target language is required".to_string(),
+        });
+    }
+    Ok(language.to_string())
+}
+
+fn ai_config(
+    state: &AppState,
+    provider_type: Option<String>,
+    api_key: Option<String>,
+    model: Option<String>,
+) -> AIConfig {
+    let mut config = AIConfig {
+        provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
+        api_key,
+        model,
+        temperature: Some(0.2),
+        max_tokens: Some(8192),
+        ..Default::default()
+    };
+    state.secrets.fill_ai_config(&mut config);
+    config
+}
+
+/// Translate `page` chunk by chunk and save the result
+async fn translate_one(
+    state: &AppState,
+    lib: &LibraryContext,
+    page: Page,
+    target_language: &str,
+    mode: TranslationMode,
+    config: &AIConfig,
+    task_id: &Option<String>,
+) -> Result<PageTranslation, Failure> {
+    translation::check_page(&page)?;
+    let segments = translation::collect_segments(&page);
+    if segments.iter().all(|s| s.block.is_none()) {
+        return Err(TranslationError::NothingToTranslate.into());
+    }
+
+    let mut source_language = String::new();
+    let mut translations = Vec::with_capacity(segments.len());
+    for range in translation::chunk_segments(&segments) {
+        let chunk = &segments[range];
+        let messages = translation::translation_messages(chunk, target_language);
+        let config = config.clone();
+        let options = TaskOptions::default().with_task_id(task_id.clone());
+        let response = match state
+            .python_queue
+            .run(options, move |python_ai| python_ai.chat(messages, config))
+            .await
+        {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => return Err(Failure::Failed(format!("AI error: {}", e))),
+            Err(QueueError::Cancelled) => return Err(Failure::Cancelled),
+            Err(e) => return Err(Failure::Failed(e.to_string())),
+        };
+        let reply = translation::parse_reply(&response.content, chunk.len())?;
+
+        if source_language.is_empty() {
+            source_language = reply.source_language.trim().to_string();
+            // Detected from the first chunk; don't spend requests on the rest
+            if translation::same_language(&source_language, target_language) {
+                return Err(Failure::Failed(format!("already in {}", target_language)));
+            }
+        }
+        translations.extend(reply.translations);
+    }
+
+    let mut translated = page.clone();
+    translation::apply_translations(&mut translated, &segments, &translations);
+    translated.updated_at = chrono::Utc::now();
+
+    let storage = lib
+        .storage
+        .write()
+        .map_err(|e| Failure::Failed(format!("Storage error: {}", e)))?;
+    let translated_page_id = match mode {
+        TranslationMode::Replace => {
+            let pages_dir = storage.get_notebook_path(page.notebook_id).join("pages");
+            if let Err(e) = crate::storage::snapshots::take_snapshot(&pages_dir, &page) {
+                log::warn!(
+                    "translate_page: failed to snapshot page {} before translating: {}",
+                    page.id,
+                    e
+                );
+            }
+            translated.properties.insert(
+                translation::LANGUAGE_PROPERTY.to_string(),
+                target_language.to_string(),
+            );
+            storage
+                .update_page(&translated)
+                .map_err(|e| Failure::Failed(e.to_string()))?;
+            state
+                .sync_manager
+                .queue_page_update(page.notebook_id, page.id);
+            page.id
+        }
+        TranslationMode::Copy => {
+            let copy = translation::translated_copy(&page, &translated, target_language);
+            let copy = storage
+                .create_page_from(copy)
+                .map_err(|e| Failure::Failed(e.to_string()))?;
+            state
+                .sync_manager
+                .queue_page_update(page.notebook_id, copy.id);
+
+            let mut original = page.clone();
+            translation::link_translation(&mut original, copy.id, target_language);
+            match storage.update_page(&original) {
+                Ok(()) => state
+                    .sync_manager
+                    .queue_page_update(page.notebook_id, page.id),
+                Err(e) => log::warn!(
+                    "translate_page: failed to link page {} to its translation: {}",
+                    page.id,
+                    e
+                ),
+            }
+            copy.id
+        }
+    };
+
+    Ok(PageTranslation {
+        page_id: page.id,
+        title: page.title,
+        source_language,
+        target_language: target_language.to_string(),
+        mode,
+        translated_page_id,
+        segments: segments.len(),
+    })
+}
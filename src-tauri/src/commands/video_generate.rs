@@ -2,13 +2,18 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::sync::mpsc;
+use std::path::Path;
+use std::sync::{mpsc, Arc};
 use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
+use crate::generated_media::{GeneratedMedia, GeneratedMediaKind, GeneratedMediaRegistry};
+use crate::library::LibraryContext;
 use crate::python_bridge::{SlideContent, VideoGenerationResult};
+use crate::secrets;
 use crate::AppState;
 
+use super::generated_media::parse_page_ids;
 use super::notebook::CommandError;
 
 /// Progress event payload for video generation
@@ -22,11 +27,14 @@ pub struct VideoProgressPayload {
 }
 
 /// TTS configuration for video narration
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VideoTTSConfig {
     pub provider: String,
     pub voice: String,
+    /// Never written to the generated media registry; filled from the
+    /// keychain again on regeneration
+    #[serde(skip_serializing)]
     pub api_key: Option<String>,
     pub base_url: Option<String>,
     pub model: Option<String>,
@@ -34,7 +42,7 @@ pub struct VideoTTSConfig {
 }
 
 /// Video configuration from the frontend
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VideoConfig {
     #[serde(default = "default_video_width")]
@@ -65,61 +73,116 @@ fn default_transition() -> String {
     "cut".to_string()
 }
 
+/// Everything a study video was generated from, kept in the generated media
+/// registry so it can be generated again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StudyVideoRequest {
+    pub slides: Vec<SlideContent>,
+    pub tts_config: VideoTTSConfig,
+    pub video_config: VideoConfig,
+}
+
 /// Generate a narrated video from study content slides
 #[tauri::command]
 pub async fn generate_study_video(
     app_handle: AppHandle,
     state: State<'_, AppState>,
+    window: tauri::Window,
     notebook_id: String,
     slides: Vec<SlideContent>,
     tts_config: VideoTTSConfig,
     video_config: Option<VideoConfig>,
+    page_ids: Option<Vec<String>>,
 ) -> Result<VideoGenerationResult, CommandError> {
+    let lib = state.library(&window);
     let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
+    let page_ids = parse_page_ids(page_ids)?;
+
+    let request = StudyVideoRequest {
+        slides,
+        tts_config,
+        video_config: video_config.unwrap_or(VideoConfig {
+            width: default_video_width(),
+            height: default_video_height(),
+            theme: default_theme(),
+            transition: default_transition(),
+            title: None,
+            accent_color: None,
+        }),
+    };
+    let (result, _) = generate_and_record(
+        &app_handle,
+        &state,
+        &lib,
+        nb_id,
+        page_ids,
+        request,
+        None,
+    )
+    .await?;
+    Ok(result)
+}
 
-    if slides.is_empty() {
+/// Generate a study video into the notebook's `videos` assets and record it
+/// in the generated media registry
+pub(crate) async fn generate_and_record(
+    app_handle: &AppHandle,
+    state: &AppState,
+    lib: &Arc<LibraryContext>,
+    nb_id: Uuid,
+    page_ids: Vec<Uuid>,
+    request: StudyVideoRequest,
+    regenerated_from: Option<Uuid>,
+) -> Result<(VideoGenerationResult, GeneratedMedia), CommandError> {
+    if request.slides.is_empty() {
         return Err(CommandError {
             message: "No slides provided for video generation".to_string(),
         });
     }
+    let options = serde_json::to_value(&request).map_err(|e| CommandError {
+        message: format!("Failed to record video options: {}", e),
+    })?;
+
+    let StudyVideoRequest {
+        slides,
+        mut tts_config,
+        video_config: config,
+    } = request;
+    state
+        .secrets
+        .fill_api_key(&mut tts_config.api_key, &secrets::tts_key_id(&tts_config.provider));
 
     // Get assets directory from storage
-    let output_dir = {
-        let storage = state.storage.lock().map_err(|e| CommandError {
+    let (assets_dir, output_dir) = {
+        let storage = lib.storage.read().map_err(|e| CommandError {
             message: format!("Failed to acquire storage lock: {}", e),
         })?;
 
         // Output directory: {notebook_assets}/videos/
         let assets_dir = storage.notebook_assets_dir(nb_id);
-        let videos_dir = assets_dir.join("videos");
+        let videos_dir = assets_dir.join(GeneratedMediaKind::StudyVideo.dir_name());
         fs::create_dir_all(&videos_dir).map_err(|e| CommandError {
             message: format!("Failed to create videos directory: {}", e),
         })?;
 
-        videos_dir
+        let output_dir = videos_dir
             .to_str()
             .map(|s| s.to_string())
             .ok_or_else(|| CommandError {
                 message: "Invalid path encoding".to_string(),
-            })?
+            })?;
+        (assets_dir, output_dir)
     };
 
     // Clone python_ai Arc for spawn_blocking
     let python_ai = state.python_ai.clone();
-    let config = video_config.unwrap_or(VideoConfig {
-        width: default_video_width(),
-        height: default_video_height(),
-        theme: default_theme(),
-        transition: default_transition(),
-        title: None,
-        accent_color: None,
-    });
 
     // Create a channel for progress updates
     let (progress_tx, progress_rx) = mpsc::channel::<(i32, i32, String)>();
-    let notebook_id_clone = notebook_id.clone();
+    let notebook_id = nb_id.to_string();
 
     // Spawn a task to emit progress events
     let app_handle_clone = app_handle.clone();
@@ -129,13 +192,13 @@ pub async fn generate_study_video(
                 current_slide: current,
                 total_slides: total,
                 status,
-                notebook_id: notebook_id_clone.clone(),
+                notebook_id: notebook_id.clone(),
             };
             let _ = app_handle_clone.emit("video-generation-progress", payload);
         }
     });
 
-    tauri::async_runtime::spawn_blocking(move || {
+    let result = tauri::async_runtime::spawn_blocking(move || {
         let python_ai = python_ai.lock().map_err(|e| CommandError {
             message: format!("Failed to acquire Python AI lock: {}", e),
         })?;
@@ -164,7 +227,21 @@ pub async fn generate_study_video(
     .await
     .map_err(|e| CommandError {
         message: format!("Task join error: {}", e),
-    })?
+    })??;
+
+    let _storage = lib.storage.write().map_err(|e| CommandError {
+        message: format!("Failed to acquire storage lock: {}", e),
+    })?;
+    let mut registry = GeneratedMediaRegistry::open(assets_dir)?;
+    let files = registry
+        .relative_path(Path::new(&result.video_path))
+        .into_iter()
+        .collect();
+    let mut media =
+        GeneratedMedia::new(nb_id, page_ids, GeneratedMediaKind::StudyVideo, options, files);
+    media.regenerated_from = regenerated_from;
+    let media = registry.add(media)?;
+    Ok((result, media))
 }
 
 /// Check availability of video generation features
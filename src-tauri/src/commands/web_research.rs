@@ -5,6 +5,7 @@ use serde::Serialize;
 use tauri::State;
 
 use crate::python_bridge::{AIConfig, ResearchSummary, ScrapedContent, SearchResponse};
+use crate::secrets;
 use crate::AppState;
 
 use super::notebook::CommandError;
@@ -202,16 +203,23 @@ pub async fn fetch_link_metadata(url: String) -> Result<Option<LinkMetadata>, Co
     }))
 }
 
-/// Search the web using Tavily API
+/// Search the web using Tavily API. Without `api_key`, the stored Tavily
+/// key is used.
 #[tauri::command]
 pub fn web_search(
     state: State<AppState>,
     query: String,
-    api_key: String,
+    api_key: Option<String>,
     max_results: Option<i64>,
     search_depth: Option<String>,
     include_answer: Option<bool>,
 ) -> Result<SearchResponse, CommandError> {
+    let mut api_key = api_key;
+    state.secrets.fill_api_key(&mut api_key, secrets::TAVILY_KEY_ID);
+    let api_key = api_key.ok_or_else(|| CommandError {
+        message: "No Tavily API key configured".to_string(),
+    })?;
+
     let python_ai = state.python_ai.lock().map_err(|e| CommandError {
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
@@ -255,7 +263,7 @@ pub fn summarize_research(
         message: format!("Failed to acquire Python AI lock: {}", e),
     })?;
 
-    let config = AIConfig {
+    let mut config = AIConfig {
         provider_type: provider_type.unwrap_or_else(|| "openai".to_string()),
         api_key,
         model,
@@ -263,6 +271,7 @@ pub fn summarize_research(
         max_tokens: Some(2000),
         ..Default::default()
     };
+    state.secrets.fill_ai_config(&mut config);
 
     python_ai
         .summarize_research(contents, query, config)
@@ -0,0 +1,69 @@
+//! Start and stop the WebDAV share of the library (see `webdav_server`)
+
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::commands::CommandError;
+use crate::webdav_server::{self, WebDavConfig, WebDavStatus};
+use crate::AppState;
+
+type CommandResult<T> = Result<T, CommandError>;
+
+/// Share the window's library over WebDAV, restarting the share if one is
+/// running. Clients sign in with the returned user name and password.
+#[tauri::command]
+pub async fn webdav_server_start(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    config: Option<WebDavConfig>,
+) -> CommandResult<WebDavStatus> {
+    let token = {
+        let video_server = state.video_server.lock().await;
+        let server = video_server.as_ref().ok_or_else(|| CommandError {
+            message: "Video streaming server not running".to_string(),
+        })?;
+        server.token.clone()
+    };
+
+    let mut webdav = state.webdav_server.lock().await;
+    if let Some(mut running) = webdav.take() {
+        running.stop();
+    }
+
+    let server = webdav_server::start_server(
+        config.unwrap_or_default(),
+        token,
+        state.library(&window).storage.clone(),
+        Arc::clone(&state.sync_manager),
+    )
+    .await
+    .map_err(|e| CommandError {
+        message: format!("Failed to start WebDAV server: {}", e),
+    })?;
+    let status = server.status();
+    *webdav = Some(server);
+    Ok(status)
+}
+
+/// Stop sharing the library
+#[tauri::command]
+pub async fn webdav_server_stop(state: State<'_, AppState>) -> CommandResult<()> {
+    if let Some(mut server) = state.webdav_server.lock().await.take() {
+        server.stop();
+    }
+    Ok(())
+}
+
+/// The running share, if any
+#[tauri::command]
+pub async fn webdav_server_status(
+    state: State<'_, AppState>,
+) -> CommandResult<Option<WebDavStatus>> {
+    Ok(state
+        .webdav_server
+        .lock()
+        .await
+        .as_ref()
+        .map(|server| server.status()))
+}
@@ -197,9 +197,11 @@ pub fn preview_website_mirror_cmd(mirror_path: String) -> CommandResult<WebsiteM
 pub fn import_website_mirror_cmd(
     app: AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     mirror_path: String,
     notebook_name: Option<String>,
 ) -> CommandResult<Notebook> {
+    let lib = state.library(&window);
     let path = Path::new(&mirror_path);
 
     if !path.exists() {
@@ -214,7 +216,7 @@ pub fn import_website_mirror_cmd(
         });
     }
 
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let storage = lib.storage.read().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -284,14 +286,18 @@ pub fn import_website_mirror_cmd(
         system_prompt_mode: SystemPromptMode::default(),
         ai_provider: None,
         ai_model: None,
+        ai_profile_id: None,
         sync_config: None,
         encryption_config: None,
         is_pinned: false,
         position: 0,
         page_sort_by: None,
+        pinned_page_ids: Vec::new(),
         daily_notes_config: None,
         cover_image: None,
         mirror_path: Some(mirror_path.clone()),
+        exclude_from_review: false,
+        code_exec: None,
         created_at: now,
         updated_at: now,
     };
@@ -428,6 +434,9 @@ pub fn import_website_mirror_cmd(
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: now,
             updated_at: now,
         };
@@ -464,13 +473,15 @@ pub fn import_website_mirror_cmd(
 pub fn rescan_website_mirror_cmd(
     app: AppHandle,
     state: State<AppState>,
+    window: tauri::Window,
     notebook_id: String,
 ) -> CommandResult<RescanSummary> {
+    let lib = state.library(&window);
     let notebook_uuid = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
         message: format!("Invalid notebook ID: {}", e),
     })?;
 
-    let storage = state.storage.lock().map_err(|e| CommandError {
+    let storage = lib.storage.write().map_err(|e| CommandError {
         message: format!("Failed to acquire storage lock: {}", e),
     })?;
 
@@ -701,6 +712,9 @@ pub fn rescan_website_mirror_cmd(
                 daily_note_date: None,
                 plugin_page_type: None,
                 plugin_data: None,
+                properties: Default::default(),
+                encryption: None,
+                stats: None,
                 created_at: now,
                 updated_at: now,
             };
@@ -1,11 +1,24 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use std::sync::Arc;
+
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use uuid::Uuid;
+
+use crate::library::LibraryContext;
+use crate::AppState;
 
 /// Open a library in a new window
 ///
 /// If a window for this library is already open, focus it instead of creating a duplicate.
+/// The window gets its own stores, vector index and sync scheduler, which are
+/// dropped again when it closes.
 #[tauri::command]
-pub async fn open_library_window(app: AppHandle, library_id: String) -> Result<String, String> {
-    let label = format!("library-{}", library_id);
+pub async fn open_library_window(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    library_id: String,
+) -> Result<String, String> {
+    let id = Uuid::parse_str(&library_id).map_err(|e| e.to_string())?;
+    let label = format!("library-{}", id);
 
     // Focus existing window if already open
     if let Some(window) = app.get_webview_window(&label) {
@@ -13,20 +26,83 @@ pub async fn open_library_window(app: AppHandle, library_id: String) -> Result<S
         return Ok(label);
     }
 
+    let library = state
+        .library_storage
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get_library(id)
+        .map_err(|e| e.to_string())?;
+    let context = Arc::new(LibraryContext::open(&library)?);
+
+    // The daemon only syncs the library it was started on, so this window
+    // always runs its own scheduler
+    let scheduler = crate::sync::scheduler::start_sync_scheduler(
+        Arc::clone(&state.sync_manager),
+        Arc::clone(&context.storage),
+        Arc::clone(&state.library_storage),
+        Arc::clone(&context.goals_storage),
+        Arc::clone(&context.inbox_storage),
+        Arc::clone(&state.contacts_storage),
+        Arc::clone(&state.energy_storage),
+        Arc::clone(&context.action_storage),
+        None,
+    );
+    *context.sync_scheduler.lock().await = Some(scheduler);
+
+    if let Some(ref server) = *state.video_server.lock().await {
+        server.add_allowed_dir(library.path.clone());
+    }
+
+    state
+        .library_windows
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(id, context);
+
     // Create new window with library context via URL query parameter
-    let url = format!("/?library={}", library_id);
+    let url = format!("/?library={}", id);
 
-    WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+    let window = match WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
         .title("Nous")
         .inner_size(1200.0, 800.0)
         .min_inner_size(800.0, 600.0)
         .center()
         .build()
-        .map_err(|e| e.to_string())?;
+    {
+        Ok(window) => window,
+        Err(e) => {
+            release_library_context(&app, id);
+            return Err(e.to_string());
+        }
+    };
+
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            release_library_context(&handle, id);
+        }
+    });
 
     Ok(label)
 }
 
+/// Drop a library window's context and stop its sync scheduler
+fn release_library_context(app: &AppHandle, library_id: Uuid) {
+    let state = app.state::<AppState>();
+    let context = match state.library_windows.lock() {
+        Ok(mut windows) => windows.remove(&library_id),
+        Err(_) => None,
+    };
+    if let Some(context) = context {
+        if let Ok(scheduler) = context.sync_scheduler.try_lock() {
+            if let Some(ref scheduler) = *scheduler {
+                scheduler.shutdown();
+            }
+        }
+        log::info!("Closed library window {}", library_id);
+    }
+}
+
 /// Close a library window by its ID
 #[tauri::command]
 pub async fn close_library_window(app: AppHandle, library_id: String) -> Result<(), String> {
@@ -0,0 +1,12 @@
+//! Comments module for threaded, block-anchored page comments
+//!
+//! Provides:
+//! - Threads of comments anchored to a page block (or the page itself)
+//! - Resolve/reopen per thread and unresolved counts for page lists
+//! - Timestamp-based merging so comment files sync like other JSON data
+
+mod models;
+mod storage;
+
+pub use models::*;
+pub use storage::*;
@@ -0,0 +1,81 @@
+//! Data models for page comments
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A single comment. Thread roots have no `parent_id`; replies point at
+/// their thread's root and inherit its block anchor.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: Uuid,
+    pub page_id: Uuid,
+    /// Block the thread is anchored to; None for page-level comments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// Thread root this comment replies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    pub author: String,
+    pub body: String,
+    /// Only meaningful on thread roots
+    #[serde(default)]
+    pub resolved: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Tombstone: deleted comments are kept (with an empty body) so the
+    /// deletion wins when merged with a copy from another device
+    #[serde(default)]
+    pub deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Comment {
+    pub fn new(page_id: Uuid, author: String, body: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            page_id,
+            block_id: None,
+            parent_id: None,
+            author,
+            body,
+            resolved: false,
+            resolved_by: None,
+            resolved_at: None,
+            deleted: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.parent_id.is_none()
+    }
+}
+
+/// A thread root with its replies (oldest first), as shown in the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThread {
+    pub root: Comment,
+    pub replies: Vec<Comment>,
+}
+
+/// Request to add a comment or reply
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewComment {
+    #[serde(default)]
+    pub block_id: Option<String>,
+    /// Reply to this comment's thread
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub body: String,
+}
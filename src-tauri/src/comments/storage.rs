@@ -0,0 +1,527 @@
+//! Storage operations for page comments
+//!
+//! Directory structure per notebook:
+//! ```text
+//! notebooks/{notebook-id}/comments/
+//! └── {page-id}.json   # Array of every comment on the page (incl. tombstones)
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::atomic;
+
+#[derive(Error, Debug)]
+pub enum CommentStorageError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Comment not found: {0}")]
+    CommentNotFound(Uuid),
+
+    #[error("Comment body cannot be empty")]
+    EmptyBody,
+}
+
+pub type Result<T> = std::result::Result<T, CommentStorageError>;
+
+/// Storage manager for comment operations
+pub struct CommentsStorage {
+    /// Base path for notebooks (e.g., ~/.local/share/nous/notebooks)
+    notebooks_path: PathBuf,
+}
+
+impl CommentsStorage {
+    pub fn new(notebooks_path: PathBuf) -> Self {
+        Self { notebooks_path }
+    }
+
+    /// Get the comments directory for a notebook
+    fn comments_dir(&self, notebook_id: Uuid) -> PathBuf {
+        self.notebooks_path
+            .join(notebook_id.to_string())
+            .join("comments")
+    }
+
+    fn page_file(&self, notebook_id: Uuid, page_id: Uuid) -> PathBuf {
+        self.comments_dir(notebook_id)
+            .join(format!("{}.json", page_id))
+    }
+
+    // ==================== Raw access (used by sync) ====================
+
+    /// All comments on a page, including tombstones, oldest first
+    pub fn load_comments(&self, notebook_id: Uuid, page_id: Uuid) -> Result<Vec<Comment>> {
+        let path = self.page_file(notebook_id, page_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Overwrite a page's comment file
+    pub fn replace_comments(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        comments: &[Comment],
+    ) -> Result<()> {
+        fs::create_dir_all(self.comments_dir(notebook_id))?;
+        let json = serde_json::to_string_pretty(comments)?;
+        atomic::write_str(&self.page_file(notebook_id, page_id), &json)?;
+        Ok(())
+    }
+
+    /// Pages in a notebook that have a comment file
+    pub fn list_commented_pages(&self, notebook_id: Uuid) -> Result<Vec<Uuid>> {
+        let dir = self.comments_dir(notebook_id);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut pages = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().map(|e| e == "json").unwrap_or(false) {
+                if let Some(id) = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| Uuid::parse_str(s).ok())
+                {
+                    pages.push(id);
+                }
+            }
+        }
+        Ok(pages)
+    }
+
+    // ==================== Threads ====================
+
+    /// Threads on a page, oldest first. Deleted comments are hidden, and
+    /// resolved threads only included when `include_resolved` is set.
+    pub fn list_threads(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        include_resolved: bool,
+    ) -> Result<Vec<CommentThread>> {
+        let comments = self.load_comments(notebook_id, page_id)?;
+        Ok(build_threads(comments, include_resolved))
+    }
+
+    /// Add a thread root, or a reply when `parent_id` is set. Replying to a
+    /// reply attaches to the same thread; replying to a resolved thread
+    /// reopens it.
+    pub fn add_comment(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        request: NewComment,
+    ) -> Result<Comment> {
+        let body = request.body.trim().to_string();
+        if body.is_empty() {
+            return Err(CommentStorageError::EmptyBody);
+        }
+        let author = request
+            .author
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .unwrap_or_else(crate::storage::oplog::get_client_id);
+
+        let mut comments = self.load_comments(notebook_id, page_id)?;
+        let mut comment = Comment::new(page_id, author, body);
+
+        match request.parent_id {
+            Some(parent_id) => {
+                let parent = comments
+                    .iter()
+                    .find(|c| c.id == parent_id && !c.deleted)
+                    .ok_or(CommentStorageError::CommentNotFound(parent_id))?;
+                let root_id = parent.parent_id.unwrap_or(parent.id);
+                let root = comments
+                    .iter_mut()
+                    .find(|c| c.id == root_id && !c.deleted)
+                    .ok_or(CommentStorageError::CommentNotFound(root_id))?;
+
+                comment.parent_id = Some(root.id);
+                comment.block_id = root.block_id.clone();
+                if root.resolved {
+                    root.resolved = false;
+                    root.resolved_by = None;
+                    root.resolved_at = None;
+                    root.updated_at = comment.created_at;
+                }
+            }
+            None => comment.block_id = request.block_id,
+        }
+
+        comments.push(comment.clone());
+        self.replace_comments(notebook_id, page_id, &comments)?;
+        Ok(comment)
+    }
+
+    /// Edit a comment's text
+    pub fn update_comment(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        comment_id: Uuid,
+        body: String,
+    ) -> Result<Comment> {
+        let body = body.trim().to_string();
+        if body.is_empty() {
+            return Err(CommentStorageError::EmptyBody);
+        }
+
+        let mut comments = self.load_comments(notebook_id, page_id)?;
+        let comment = comments
+            .iter_mut()
+            .find(|c| c.id == comment_id && !c.deleted)
+            .ok_or(CommentStorageError::CommentNotFound(comment_id))?;
+        comment.body = body;
+        comment.updated_at = Utc::now();
+        let updated = comment.clone();
+
+        self.replace_comments(notebook_id, page_id, &comments)?;
+        Ok(updated)
+    }
+
+    /// Delete a comment. Deleting a thread root deletes the whole thread.
+    pub fn delete_comment(&self, notebook_id: Uuid, page_id: Uuid, comment_id: Uuid) -> Result<()> {
+        let mut comments = self.load_comments(notebook_id, page_id)?;
+        if !comments.iter().any(|c| c.id == comment_id && !c.deleted) {
+            return Err(CommentStorageError::CommentNotFound(comment_id));
+        }
+
+        let now = Utc::now();
+        for c in comments
+            .iter_mut()
+            .filter(|c| c.id == comment_id || c.parent_id == Some(comment_id))
+        {
+            c.deleted = true;
+            c.body.clear();
+            c.updated_at = now;
+        }
+
+        self.replace_comments(notebook_id, page_id, &comments)
+    }
+
+    /// Resolve or reopen the thread containing `comment_id`
+    pub fn set_resolved(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        comment_id: Uuid,
+        resolved: bool,
+        by: Option<String>,
+    ) -> Result<Comment> {
+        let mut comments = self.load_comments(notebook_id, page_id)?;
+        let root_id = comments
+            .iter()
+            .find(|c| c.id == comment_id && !c.deleted)
+            .map(|c| c.parent_id.unwrap_or(c.id))
+            .ok_or(CommentStorageError::CommentNotFound(comment_id))?;
+        let root = comments
+            .iter_mut()
+            .find(|c| c.id == root_id && !c.deleted)
+            .ok_or(CommentStorageError::CommentNotFound(root_id))?;
+
+        let now = Utc::now();
+        root.resolved = resolved;
+        root.resolved_by =
+            resolved.then(|| by.unwrap_or_else(crate::storage::oplog::get_client_id));
+        root.resolved_at = resolved.then_some(now);
+        root.updated_at = now;
+        let updated = root.clone();
+
+        self.replace_comments(notebook_id, page_id, &comments)?;
+        Ok(updated)
+    }
+
+    /// Unresolved thread count per page, for pages that have any
+    pub fn unresolved_counts(&self, notebook_id: Uuid) -> Result<HashMap<Uuid, usize>> {
+        let mut counts = HashMap::new();
+        for page_id in self.list_commented_pages(notebook_id)? {
+            let open = self
+                .load_comments(notebook_id, page_id)?
+                .iter()
+                .filter(|c| c.is_root() && !c.deleted && !c.resolved)
+                .count();
+            if open > 0 {
+                counts.insert(page_id, open);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Move the threads anchored to `block_ids` (all threads if `None`) from
+    /// one page to another, for blocks that moved there. The copies get new
+    /// ids and the originals become tombstones, so the move survives a merge
+    /// with a copy of either page from another device. Returns the number of
+    /// comments moved.
+    pub fn move_threads(
+        &self,
+        notebook_id: Uuid,
+        from_page: Uuid,
+        to_page: Uuid,
+        block_ids: Option<&HashSet<String>>,
+    ) -> Result<usize> {
+        let mut source = self.load_comments(notebook_id, from_page)?;
+        let moves = |c: &Comment| {
+            !c.deleted
+                && block_ids.map_or(true, |ids| {
+                    c.block_id.as_ref().is_some_and(|id| ids.contains(id))
+                })
+        };
+        let new_ids: HashMap<Uuid, Uuid> = source
+            .iter()
+            .filter(|c| moves(c))
+            .map(|c| (c.id, Uuid::new_v4()))
+            .collect();
+        if new_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let mut target = self.load_comments(notebook_id, to_page)?;
+        for c in source.iter_mut().filter(|c| new_ids.contains_key(&c.id)) {
+            let mut moved = c.clone();
+            moved.id = new_ids[&c.id];
+            moved.page_id = to_page;
+            moved.parent_id = c.parent_id.map(|id| new_ids.get(&id).copied().unwrap_or(id));
+            moved.updated_at = now;
+            target.push(moved);
+
+            c.deleted = true;
+            c.body.clear();
+            c.updated_at = now;
+        }
+
+        self.replace_comments(notebook_id, to_page, &target)?;
+        self.replace_comments(notebook_id, from_page, &source)?;
+        Ok(new_ids.len())
+    }
+
+    /// Drop a page's comments (page permanently deleted)
+    pub fn delete_page_comments(&self, notebook_id: Uuid, page_id: Uuid) -> Result<()> {
+        let path = self.page_file(notebook_id, page_id);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn build_threads(comments: Vec<Comment>, include_resolved: bool) -> Vec<CommentThread> {
+    let mut replies: HashMap<Uuid, Vec<Comment>> = HashMap::new();
+    let mut roots = Vec::new();
+    for c in comments.into_iter().filter(|c| !c.deleted) {
+        match c.parent_id {
+            Some(parent) => replies.entry(parent).or_default().push(c),
+            None => roots.push(c),
+        }
+    }
+
+    roots.sort_by_key(|c| c.created_at);
+    roots
+        .into_iter()
+        .filter(|root| include_resolved || !root.resolved)
+        .map(|root| {
+            let mut thread_replies = replies.remove(&root.id).unwrap_or_default();
+            thread_replies.sort_by_key(|c| c.created_at);
+            CommentThread {
+                root,
+                replies: thread_replies,
+            }
+        })
+        .collect()
+}
+
+/// Merge two copies of a page's comments (local and remote). Comments are
+/// matched by id and the most recently updated copy wins; exact timestamp
+/// ties go to the lexicographically greater author so every device picks
+/// the same winner.
+pub fn merge_comments(local: &[Comment], remote: &[Comment]) -> Vec<Comment> {
+    let mut merged: HashMap<Uuid, Comment> = local.iter().map(|c| (c.id, c.clone())).collect();
+    for theirs in remote {
+        match merged.get(&theirs.id) {
+            Some(ours)
+                if (ours.updated_at, &ours.author) >= (theirs.updated_at, &theirs.author) => {}
+            _ => {
+                merged.insert(theirs.id, theirs.clone());
+            }
+        }
+    }
+
+    let mut merged: Vec<Comment> = merged.into_values().collect();
+    merged.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    merged
+}
+
+/// Order-independent content hash of a page's comments, used by sync to
+/// tell whether either side changed since the last sync
+pub fn comments_hash(comments: &[Comment]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut sorted: Vec<&Comment> = comments.iter().collect();
+    sorted.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    let json = serde_json::to_vec(&sorted).unwrap_or_default();
+    hex::encode(Sha256::digest(&json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_storage() -> (CommentsStorage, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("comments_test_{}", Uuid::new_v4()));
+        (CommentsStorage::new(dir.clone()), dir)
+    }
+
+    fn new_comment(body: &str, block_id: Option<&str>, parent_id: Option<Uuid>) -> NewComment {
+        NewComment {
+            block_id: block_id.map(str::to_string),
+            parent_id,
+            author: Some("ana".to_string()),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn threads_replies_and_resolution() {
+        let (storage, dir) = temp_storage();
+        let (nb, page) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let root = storage
+            .add_comment(nb, page, new_comment("Is this right?", Some("b1"), None))
+            .unwrap();
+        let reply = storage
+            .add_comment(nb, page, new_comment("Yes", None, Some(root.id)))
+            .unwrap();
+        assert_eq!(
+            reply.block_id.as_deref(),
+            Some("b1"),
+            "replies inherit the anchor"
+        );
+
+        // Replying to a reply lands in the same thread
+        let nested = storage
+            .add_comment(nb, page, new_comment("Thanks", None, Some(reply.id)))
+            .unwrap();
+        assert_eq!(nested.parent_id, Some(root.id));
+
+        storage
+            .add_comment(nb, page, new_comment("Page note", None, None))
+            .unwrap();
+        assert_eq!(storage.unresolved_counts(nb).unwrap()[&page], 2);
+
+        let resolved = storage
+            .set_resolved(nb, page, reply.id, true, Some("bo".to_string()))
+            .unwrap();
+        assert_eq!(resolved.id, root.id);
+        assert_eq!(storage.unresolved_counts(nb).unwrap()[&page], 1);
+        assert_eq!(storage.list_threads(nb, page, false).unwrap().len(), 1);
+
+        let threads = storage.list_threads(nb, page, true).unwrap();
+        assert_eq!(threads[0].replies.len(), 2);
+
+        // A new reply reopens the thread
+        storage
+            .add_comment(nb, page, new_comment("Wait", None, Some(root.id)))
+            .unwrap();
+        assert_eq!(storage.unresolved_counts(nb).unwrap()[&page], 2);
+
+        // Deleting the root removes the whole thread but keeps tombstones
+        storage.delete_comment(nb, page, root.id).unwrap();
+        assert_eq!(storage.list_threads(nb, page, true).unwrap().len(), 1);
+        assert_eq!(storage.load_comments(nb, page).unwrap().len(), 5);
+        assert!(storage
+            .add_comment(nb, page, new_comment("late", None, Some(root.id)))
+            .is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn moving_threads_follows_their_blocks() {
+        let (storage, dir) = temp_storage();
+        let (nb, from, to) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let moved = storage
+            .add_comment(nb, from, new_comment("Source?", Some("b1"), None))
+            .unwrap();
+        storage
+            .add_comment(nb, from, new_comment("Added", None, Some(moved.id)))
+            .unwrap();
+        storage
+            .add_comment(nb, from, new_comment("Stays", Some("b2"), None))
+            .unwrap();
+
+        let ids: HashSet<String> = ["b1".to_string()].into();
+        assert_eq!(storage.move_threads(nb, from, to, Some(&ids)).unwrap(), 2);
+
+        let threads = storage.list_threads(nb, to, true).unwrap();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].root.body, "Source?");
+        assert_eq!(threads[0].root.page_id, to);
+        assert_eq!(threads[0].replies.len(), 1);
+        assert_ne!(threads[0].root.id, moved.id);
+
+        let left = storage.list_threads(nb, from, true).unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].root.body, "Stays");
+        // The originals are kept as tombstones
+        assert_eq!(storage.load_comments(nb, from).unwrap().len(), 3);
+
+        assert_eq!(storage.move_threads(nb, from, to, None).unwrap(), 1);
+        assert!(storage.list_threads(nb, from, true).unwrap().is_empty());
+        assert_eq!(storage.list_threads(nb, to, true).unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merge_prefers_newer_copy_and_keeps_both_sides() {
+        let page = Uuid::new_v4();
+        let shared = Comment::new(page, "ana".to_string(), "draft".to_string());
+
+        let mut local_edit = shared.clone();
+        local_edit.body = "local edit".to_string();
+        local_edit.updated_at = shared.updated_at + chrono::Duration::seconds(5);
+
+        let mut remote_delete = shared.clone();
+        remote_delete.deleted = true;
+        remote_delete.body.clear();
+        remote_delete.updated_at = shared.updated_at + chrono::Duration::seconds(10);
+
+        let local_only = Comment::new(page, "ana".to_string(), "mine".to_string());
+        let remote_only = Comment::new(page, "bo".to_string(), "theirs".to_string());
+
+        let merged = merge_comments(
+            &[local_edit, local_only.clone()],
+            &[remote_delete, remote_only.clone()],
+        );
+        assert_eq!(merged.len(), 3);
+        assert!(merged.iter().find(|c| c.id == shared.id).unwrap().deleted);
+        assert!(merged.iter().any(|c| c.id == local_only.id));
+        assert!(merged.iter().any(|c| c.id == remote_only.id));
+
+        // Merging is symmetric
+        let a = merge_comments(&merged, &[]);
+        let b = merge_comments(&[], &merged);
+        assert_eq!(a, b);
+
+        let reversed: Vec<Comment> = merged.iter().rev().cloned().collect();
+        assert_eq!(comments_hash(&merged), comments_hash(&reversed));
+    }
+}
@@ -319,6 +319,8 @@ mod macos {
                     timestamp,
                     preview,
                     duration_seconds: None,
+                    page_id: None,
+                    notebook_id: None,
                 });
             }
 
@@ -432,6 +434,8 @@ mod macos {
                     timestamp,
                     preview: None,
                     duration_seconds: duration.map(|d| d as u64),
+                    page_id: None,
+                    notebook_id: None,
                 });
             }
 
@@ -13,6 +13,8 @@ pub enum ActivityType {
     FaceTimeAudio,
     FaceTimeVideo,
     MissedCall,
+    /// A recorded meeting the contact attended
+    Meeting,
 }
 
 /// Direction of communication
@@ -70,6 +72,11 @@ pub struct ContactActivity {
     pub timestamp: DateTime<Utc>,
     pub preview: Option<String>,
     pub duration_seconds: Option<u64>,
+    /// Page about the activity, such as a meeting's notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notebook_id: Option<Uuid>,
 }
 
 /// State for incremental harvesting
@@ -0,0 +1,388 @@
+//! `nous://` deep links.
+//!
+//! Links are registered as a custom URI scheme through the deep-link
+//! plugin (single-instance forwards them to the running app on Linux and
+//! Windows). Each incoming URL is parsed here, queued, and announced to the
+//! frontend with a "deep-link" event; the frontend drains the queue with
+//! `take_pending_deep_links`, so links that arrive before the webview is
+//! listening (e.g. the one the app was launched with) aren't lost.
+//!
+//! Supported forms:
+//! - `nous://page/{page_id}?notebook={id}&library={id}`
+//! - `nous://search?q=...&notebook={id}`
+//! - `nous://capture?text=...&title=...&url=...&tags=a,b`
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::commands::CommandError;
+use crate::storage::FileStorage;
+use crate::AppState;
+
+pub const SCHEME: &str = "nous";
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum DeepLinkError {
+    #[error("Not a nous:// link: {0}")]
+    WrongScheme(String),
+    #[error("Invalid deep link: {0}")]
+    Invalid(String),
+}
+
+/// A parsed `nous://` link.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum DeepLink {
+    #[serde(rename_all = "camelCase")]
+    Page {
+        page_id: Uuid,
+        notebook_id: Option<Uuid>,
+        library_id: Option<Uuid>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Search {
+        query: String,
+        notebook_id: Option<Uuid>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Capture {
+        title: Option<String>,
+        text: String,
+        url: Option<String>,
+        tags: Vec<String>,
+    },
+}
+
+fn optional_uuid(value: Option<&String>, name: &str) -> Result<Option<Uuid>, DeepLinkError> {
+    value
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            Uuid::parse_str(v)
+                .map_err(|_| DeepLinkError::Invalid(format!("{} is not a valid ID", name)))
+        })
+        .transpose()
+}
+
+/// Parse a `nous://` URL.
+pub fn parse_deep_link(url: &str) -> Result<DeepLink, DeepLinkError> {
+    let parsed =
+        reqwest::Url::parse(url.trim()).map_err(|e| DeepLinkError::Invalid(e.to_string()))?;
+    if parsed.scheme() != SCHEME {
+        return Err(DeepLinkError::WrongScheme(url.to_string()));
+    }
+
+    // `nous://page/ID` puts the action in the host; `nous:page/ID` and
+    // `nous:///page/ID` put it in the path. Accept all three.
+    let mut segments: Vec<&str> = parsed
+        .host_str()
+        .into_iter()
+        .filter(|h| !h.is_empty())
+        .collect();
+    segments.extend(parsed.path().split('/').filter(|s| !s.is_empty()));
+
+    let params: std::collections::HashMap<String, String> =
+        parsed.query_pairs().into_owned().collect();
+
+    match segments.first().copied() {
+        Some("page") => {
+            let id = segments
+                .get(1)
+                .ok_or_else(|| DeepLinkError::Invalid("missing page ID".to_string()))?;
+            let page_id = Uuid::parse_str(id)
+                .map_err(|_| DeepLinkError::Invalid("page is not a valid ID".to_string()))?;
+            Ok(DeepLink::Page {
+                page_id,
+                notebook_id: optional_uuid(params.get("notebook"), "notebook")?,
+                library_id: optional_uuid(params.get("library"), "library")?,
+            })
+        }
+        Some("search") => {
+            let query = params
+                .get("q")
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty())
+                .ok_or_else(|| DeepLinkError::Invalid("missing search query".to_string()))?;
+            Ok(DeepLink::Search {
+                query,
+                notebook_id: optional_uuid(params.get("notebook"), "notebook")?,
+            })
+        }
+        Some("capture") => {
+            let text = params.get("text").cloned().unwrap_or_default();
+            let url = params.get("url").cloned().filter(|u| !u.is_empty());
+            if text.trim().is_empty() && url.is_none() {
+                return Err(DeepLinkError::Invalid(
+                    "capture needs text or url".to_string(),
+                ));
+            }
+            Ok(DeepLink::Capture {
+                title: params.get("title").cloned().filter(|t| !t.is_empty()),
+                text,
+                url,
+                tags: params
+                    .get("tags")
+                    .map(|t| {
+                        t.split(',')
+                            .map(str::trim)
+                            .filter(|s| !s.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+        }
+        Some(other) => Err(DeepLinkError::Invalid(format!(
+            "unknown action '{}'",
+            other
+        ))),
+        None => Err(DeepLinkError::Invalid("missing action".to_string())),
+    }
+}
+
+/// Build the shareable link for a page.
+pub fn page_deep_link(page_id: Uuid, notebook_id: Uuid, library_id: Option<Uuid>) -> String {
+    let mut link = format!("{}://page/{}?notebook={}", SCHEME, page_id, notebook_id);
+    if let Some(lib) = library_id {
+        link.push_str(&format!("&library={}", lib));
+    }
+    link
+}
+
+/// Find which notebook of a library holds `page_id`, checking the hinted
+/// notebook first.
+fn find_page_notebook(
+    storage: &FileStorage,
+    page_id: Uuid,
+    notebook_hint: Option<Uuid>,
+) -> Option<Uuid> {
+    if let Some(nb) = notebook_hint {
+        if storage.get_page(nb, page_id).is_ok() {
+            return Some(nb);
+        }
+    }
+    storage
+        .list_notebooks()
+        .ok()?
+        .into_iter()
+        .map(|nb| nb.id)
+        .filter(|id| Some(*id) != notebook_hint)
+        .find(|id| storage.get_page(*id, page_id).is_ok())
+}
+
+/// Links received but not yet handled by the frontend.
+#[derive(Default)]
+pub struct PendingDeepLinks(Mutex<Vec<DeepLink>>);
+
+/// Parse an incoming URL, queue it and ping the frontend.
+pub fn dispatch(app: &AppHandle, url: &str) {
+    match parse_deep_link(url) {
+        Ok(link) => {
+            log::info!("Deep link received: {}", url);
+            if let Some(pending) = app.try_state::<PendingDeepLinks>() {
+                pending.0.lock().unwrap().push(link);
+            }
+            let _ = app.emit("deep-link", ());
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }
+        Err(e) => log::warn!("Ignoring deep link {}: {}", url, e),
+    }
+}
+
+/// Page link resolved to the library and notebook that hold the page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedPageLink {
+    pub library_id: Uuid,
+    pub library_name: String,
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    /// False when the page isn't in the window's library, so the app must
+    /// switch libraries to open it
+    pub is_current_library: bool,
+}
+
+/// Drain the queue of links received since the last call.
+#[tauri::command]
+pub fn take_pending_deep_links(pending: State<'_, PendingDeepLinks>) -> Vec<DeepLink> {
+    std::mem::take(&mut *pending.0.lock().unwrap())
+}
+
+/// Re-queue a link, e.g. before switching libraries reloads the window.
+#[tauri::command]
+pub fn queue_deep_link(
+    pending: State<'_, PendingDeepLinks>,
+    url: String,
+) -> Result<(), CommandError> {
+    let link = parse_deep_link(&url).map_err(|e| CommandError {
+        message: e.to_string(),
+    })?;
+    pending.0.lock().unwrap().push(link);
+    Ok(())
+}
+
+/// Shareable `nous://` link for a page in the window's library.
+#[tauri::command]
+pub fn get_page_deep_link(
+    state: State<AppState>,
+    window: tauri::Window,
+    notebook_id: String,
+    page_id: String,
+) -> Result<String, CommandError> {
+    let nb_id = Uuid::parse_str(&notebook_id).map_err(|e| CommandError {
+        message: format!("Invalid notebook ID: {}", e),
+    })?;
+    let pg_id = Uuid::parse_str(&page_id).map_err(|e| CommandError {
+        message: format!("Invalid page ID: {}", e),
+    })?;
+
+    state
+        .library(&window)
+        .storage
+        .read()
+        .unwrap()
+        .get_page(nb_id, pg_id)?;
+    let library_id = state.window_library(&window).ok().map(|library| library.id);
+
+    Ok(page_deep_link(pg_id, nb_id, library_id))
+}
+
+/// Locate the page behind a `nous://page/...` link. The library named in
+/// the link is tried first, then the window's library, then every other
+/// library, so links keep working after a page moves between libraries.
+#[tauri::command]
+pub fn resolve_page_deep_link(
+    state: State<AppState>,
+    window: tauri::Window,
+    url: String,
+) -> Result<ResolvedPageLink, CommandError> {
+    let (page_id, notebook_hint, library_hint) = match parse_deep_link(&url) {
+        Ok(DeepLink::Page {
+            page_id,
+            notebook_id,
+            library_id,
+        }) => (page_id, notebook_id, library_id),
+        Ok(_) => {
+            return Err(CommandError {
+                message: "Not a page link".to_string(),
+            })
+        }
+        Err(e) => {
+            return Err(CommandError {
+                message: e.to_string(),
+            })
+        }
+    };
+
+    let libraries = state
+        .library_storage
+        .lock()
+        .unwrap()
+        .list_libraries()
+        .map_err(|e| CommandError {
+            message: e.to_string(),
+        })?;
+    let current_id = state.window_library(&window).ok().map(|library| library.id);
+
+    let mut ordered: Vec<_> = libraries.iter().collect();
+    ordered.sort_by_key(|lib| {
+        if Some(lib.id) == library_hint {
+            0
+        } else if Some(lib.id) == current_id {
+            1
+        } else {
+            2
+        }
+    });
+
+    for library in ordered {
+        let is_current = Some(library.id) == current_id;
+        let found = if is_current {
+            let lib = state.library(&window);
+            let storage = lib.storage.read().unwrap();
+            find_page_notebook(&storage, page_id, notebook_hint)
+        } else {
+            find_page_notebook(
+                &FileStorage::new(library.path.clone()),
+                page_id,
+                notebook_hint,
+            )
+        };
+        if let Some(notebook_id) = found {
+            return Ok(ResolvedPageLink {
+                library_id: library.id,
+                library_name: library.name.clone(),
+                notebook_id,
+                page_id,
+                is_current_library: is_current,
+            });
+        }
+    }
+
+    Err(CommandError {
+        message: format!("Page {} not found in any library", page_id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_page_links_in_all_forms() {
+        let page = Uuid::new_v4();
+        let nb = Uuid::new_v4();
+        let expected = DeepLink::Page {
+            page_id: page,
+            notebook_id: Some(nb),
+            library_id: None,
+        };
+        for url in [
+            format!("nous://page/{}?notebook={}", page, nb),
+            format!("nous:///page/{}?notebook={}", page, nb),
+            format!("nous:page/{}?notebook={}", page, nb),
+        ] {
+            assert_eq!(parse_deep_link(&url), Ok(expected.clone()), "{}", url);
+        }
+
+        let link = page_deep_link(page, nb, None);
+        assert_eq!(parse_deep_link(&link), Ok(expected));
+    }
+
+    #[test]
+    fn parses_search_and_capture() {
+        assert_eq!(
+            parse_deep_link("nous://search?q=entropy%20notes"),
+            Ok(DeepLink::Search {
+                query: "entropy notes".to_string(),
+                notebook_id: None,
+            })
+        );
+        assert_eq!(
+            parse_deep_link("nous://capture?text=hello&url=https%3A%2F%2Fexample.com&tags=a,%20b"),
+            Ok(DeepLink::Capture {
+                title: None,
+                text: "hello".to_string(),
+                url: Some("https://example.com".to_string()),
+                tags: vec!["a".to_string(), "b".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_bad_links() {
+        assert!(matches!(
+            parse_deep_link("https://example.com/page/1"),
+            Err(DeepLinkError::WrongScheme(_))
+        ));
+        assert!(parse_deep_link("nous://page/not-a-uuid").is_err());
+        assert!(parse_deep_link("nous://search?q=").is_err());
+        assert!(parse_deep_link("nous://capture").is_err());
+        assert!(parse_deep_link("nous://delete/everything").is_err());
+    }
+}
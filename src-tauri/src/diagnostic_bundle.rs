@@ -0,0 +1,334 @@
+//! Local error report bundles
+//!
+//! Packs what a maintainer needs to look into a bug report into one ZIP the
+//! user attaches themselves; nothing is sent anywhere. The commands layer
+//! gathers the reports (library integrity, sync health, Python environment,
+//! performance); this module writes them next to the recent log files and a
+//! version summary.
+//!
+//! Every text file goes through [`crate::logging::redact`] and has the
+//! user's home directory replaced with `~`. Page titles are left out of
+//! sync reports; pages are referred to by id only.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::diagnostics::PerformanceReport;
+use crate::logging;
+use crate::python_bridge::health::PythonHealthReport;
+use crate::storage::integrity::IntegrityReport;
+use crate::sync::verify::SyncVerifyReport;
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("ZIP error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, BundleError>;
+
+/// Where and what the bundle came from
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub app_version: String,
+    pub tauri_version: String,
+    pub os: String,
+    pub arch: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl VersionInfo {
+    pub fn current() -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            tauri_version: tauri::VERSION.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// The sync check of one notebook, or why it couldn't run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookSyncCheck {
+    pub notebook_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<SyncVerifyReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Everything that goes into a bundle. A report that couldn't be made is
+/// recorded as its error instead.
+pub struct BundleContents {
+    pub version: VersionInfo,
+    pub integrity: std::result::Result<IntegrityReport, String>,
+    pub sync: Vec<NotebookSyncCheck>,
+    pub python: std::result::Result<PythonHealthReport, String>,
+    pub performance: PerformanceReport,
+    /// Newest first
+    pub log_files: Vec<PathBuf>,
+}
+
+/// What ended up in a written bundle
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSummary {
+    pub path: String,
+    pub files: Vec<String>,
+    pub size_bytes: u64,
+    /// Reports that couldn't be made, by file
+    pub errors: Vec<String>,
+}
+
+/// Scrubs text before it goes into a bundle
+struct Scrubber {
+    home: Option<String>,
+}
+
+impl Scrubber {
+    fn new(home: Option<&Path>) -> Self {
+        Self {
+            home: home
+                .map(|h| {
+                    h.to_string_lossy()
+                        .trim_end_matches(['/', '\\'])
+                        .to_string()
+                })
+                .filter(|h| h.len() > 1),
+        }
+    }
+
+    fn scrub(&self, text: &str) -> String {
+        let text = text
+            .lines()
+            .map(logging::redact)
+            .collect::<Vec<_>>()
+            .join("\n");
+        match &self.home {
+            Some(home) => text.replace(home.as_str(), "~"),
+            None => text,
+        }
+    }
+}
+
+fn to_json<T: Serialize>(
+    value: &std::result::Result<T, String>,
+    errors: &mut Vec<String>,
+    name: &str,
+) -> Result<String> {
+    Ok(match value {
+        Ok(value) => serde_json::to_string_pretty(value)?,
+        Err(e) => {
+            errors.push(format!("{}: {}", name, e));
+            serde_json::to_string_pretty(&serde_json::json!({ "error": e }))?
+        }
+    })
+}
+
+/// Write `contents` as a ZIP at `path`
+pub fn write_bundle(path: &Path, contents: BundleContents) -> Result<BundleSummary> {
+    write_bundle_with_home(path, contents, dirs::home_dir().as_deref())
+}
+
+fn write_bundle_with_home(
+    path: &Path,
+    mut contents: BundleContents,
+    home: Option<&Path>,
+) -> Result<BundleSummary> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let scrubber = Scrubber::new(home);
+    let mut errors = Vec::new();
+
+    // Titles are user content and never needed to diagnose sync
+    for check in &mut contents.sync {
+        if let Some(report) = check.report.as_mut() {
+            for discrepancy in &mut report.discrepancies {
+                discrepancy.title = None;
+            }
+        }
+        if let Some(e) = &check.error {
+            errors.push(format!("sync/{}.json: {}", check.notebook_id, e));
+        }
+    }
+
+    let mut files: Vec<(String, String)> = vec![
+        (
+            "version.json".to_string(),
+            serde_json::to_string_pretty(&contents.version)?,
+        ),
+        (
+            "integrity.json".to_string(),
+            to_json(&contents.integrity, &mut errors, "integrity.json")?,
+        ),
+        (
+            "python.json".to_string(),
+            to_json(&contents.python, &mut errors, "python.json")?,
+        ),
+        (
+            "performance.json".to_string(),
+            serde_json::to_string_pretty(&contents.performance)?,
+        ),
+    ];
+    for check in &contents.sync {
+        files.push((
+            format!("sync/{}.json", check.notebook_id),
+            serde_json::to_string_pretty(check)?,
+        ));
+    }
+    for log_file in &contents.log_files {
+        let Some(name) = log_file.file_name() else {
+            continue;
+        };
+        match fs::read(log_file) {
+            Ok(bytes) => files.push((
+                format!("logs/{}", name.to_string_lossy()),
+                String::from_utf8_lossy(&bytes).into_owned(),
+            )),
+            Err(e) => errors.push(format!("logs/{}: {}", name.to_string_lossy(), e)),
+        }
+    }
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut names = Vec::with_capacity(files.len() + 1);
+    for (name, text) in &files {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(scrubber.scrub(text).as_bytes())?;
+        names.push(name.clone());
+    }
+    zip.start_file("README.txt", options)?;
+    zip.write_all(readme(&errors).as_bytes())?;
+    names.push("README.txt".to_string());
+    zip.finish()?;
+
+    Ok(BundleSummary {
+        path: path.to_string_lossy().to_string(),
+        files: names,
+        size_bytes: fs::metadata(path)?.len(),
+        errors,
+    })
+}
+
+fn readme(errors: &[String]) -> String {
+    let mut text = String::from(
+        "Nous diagnostic bundle\n\
+         \n\
+         Created on this device for a bug report; nothing was uploaded.\n\
+         API keys, tokens, passwords and your home directory have been\n\
+         scrubbed from every file, and the sync reports leave out page\n\
+         titles. Page contents are not included, but log lines may name\n\
+         pages: look through the files before sharing.\n",
+    );
+    if !errors.is_empty() {
+        text.push_str("\nReports that could not be created:\n");
+        for e in errors {
+            text.push_str(&format!("- {}\n", e));
+        }
+    }
+    text
+}
+
+/// Default location for a new bundle: `{data_dir}/diagnostics/`
+pub fn default_bundle_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("diagnostics").join(format!(
+        "nous-diagnostics-{}.zip",
+        Utc::now().format("%Y%m%dT%H%M%S")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    use crate::sync::verify::{DiscrepancyKind, RepairAction, SyncDiscrepancy};
+
+    fn read_entry(path: &Path, name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn writes_scrubbed_reports_and_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home").join("alice");
+        let log = dir.path().join("nous.log");
+        fs::write(
+            &log,
+            format!(
+                "{{\"message\":\"opened {}/notes with api_key=abc123\"}}\n",
+                home.display()
+            ),
+        )
+        .unwrap();
+
+        let notebook_id = Uuid::new_v4();
+        let contents = BundleContents {
+            version: VersionInfo::current(),
+            integrity: Err("library is locked".to_string()),
+            sync: vec![NotebookSyncCheck {
+                notebook_id,
+                report: Some(SyncVerifyReport {
+                    notebook_id,
+                    checked_at: Utc::now(),
+                    local_pages: 1,
+                    remote_files: 0,
+                    manifest_pages: 0,
+                    manifest_missing: true,
+                    discrepancies: vec![SyncDiscrepancy {
+                        page_id: Uuid::new_v4(),
+                        title: Some("Diary".to_string()),
+                        kind: DiscrepancyKind::MissingRemote,
+                        repair: RepairAction::Push,
+                    }],
+                }),
+                error: None,
+            }],
+            python: Err("Python is not installed".to_string()),
+            performance: crate::diagnostics::report("test"),
+            log_files: vec![log, dir.path().join("nous.1.log")],
+        };
+
+        let path = dir.path().join("out").join("bundle.zip");
+        let summary = write_bundle_with_home(&path, contents, Some(&home)).unwrap();
+        assert!(summary.files.contains(&"logs/nous.log".to_string()));
+        assert!(summary
+            .files
+            .contains(&format!("sync/{}.json", notebook_id)));
+        // The missing rotated log and both failed reports
+        assert_eq!(summary.errors.len(), 3);
+
+        let log = read_entry(&path, "logs/nous.log");
+        assert!(log.contains("opened ~/notes"));
+        assert!(!log.contains("abc123"));
+        assert!(!read_entry(&path, &format!("sync/{}.json", notebook_id)).contains("Diary"));
+        assert!(read_entry(&path, "integrity.json").contains("library is locked"));
+        assert!(read_entry(&path, "README.txt").contains("python.json"));
+    }
+}
@@ -0,0 +1,352 @@
+//! In-process performance metrics.
+//!
+//! Storage operations, sync phases, search queries and Python bridge calls
+//! record their wall-clock time here. Samples are aggregated into fixed
+//! log-scale histograms per (category, operation), so memory stays constant
+//! no matter how long the process runs. `report()` snapshots them for the
+//! `get_performance_report` command / `GET /api/diagnostics/performance`,
+//! which users can attach to bug reports.
+//!
+//! Tracing can optionally append every raw sample to a JSONL file for
+//! reproducing a specific slow interaction.
+//!
+//! The registry is process-global: the app and the daemon each report their
+//! own numbers.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Subsystem an operation belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Storage,
+    Sync,
+    Search,
+    Python,
+}
+
+/// Histogram bucket upper bounds in milliseconds. The last bucket is
+/// open-ended.
+const BUCKET_BOUNDS_MS: [f64; 14] = [
+    1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1_000.0, 2_500.0, 5_000.0, 10_000.0,
+    30_000.0,
+];
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    count: u64,
+    total_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+    buckets: [u64; BUCKET_BOUNDS_MS.len() + 1],
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            total_ms: 0.0,
+            min_ms: f64::INFINITY,
+            max_ms: 0.0,
+            buckets: [0; BUCKET_BOUNDS_MS.len() + 1],
+        }
+    }
+}
+
+impl Histogram {
+    fn record(&mut self, ms: f64) {
+        self.count += 1;
+        self.total_ms += ms;
+        self.min_ms = self.min_ms.min(ms);
+        self.max_ms = self.max_ms.max(ms);
+        let idx = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[idx] += 1;
+    }
+
+    /// Upper bound of the bucket holding the `q` quantile, capped at the
+    /// observed max (so a single 3 ms sample doesn't report p99 = 5 ms).
+    fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let target = ((self.count as f64) * q).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                let bound = BUCKET_BOUNDS_MS.get(i).copied().unwrap_or(self.max_ms);
+                return bound.min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+}
+
+/// Aggregated timings for one operation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStats {
+    pub category: Category,
+    pub operation: String,
+    pub count: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    /// Non-empty histogram buckets; `le_ms` is None for the overflow bucket
+    pub buckets: Vec<BucketCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketCount {
+    pub le_ms: Option<f64>,
+    pub count: u64,
+}
+
+/// Snapshot of every recorded operation plus enough environment detail to
+/// make a bug report actionable
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceReport {
+    pub generated_at: DateTime<Utc>,
+    pub collecting_since: DateTime<Utc>,
+    pub process: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    /// Sorted by total time spent, most expensive first
+    pub operations: Vec<OperationStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_file: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TraceLine<'a> {
+    ts: DateTime<Utc>,
+    category: Category,
+    op: &'a str,
+    ms: f64,
+}
+
+struct Registry {
+    since: DateTime<Utc>,
+    histograms: HashMap<(Category, String), Histogram>,
+    trace: Option<(PathBuf, BufWriter<File>)>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        Mutex::new(Registry {
+            since: Utc::now(),
+            histograms: HashMap::new(),
+            trace: None,
+        })
+    })
+}
+
+/// Record one timed operation
+pub fn record(category: Category, operation: &str, elapsed: Duration) {
+    let ms = elapsed.as_secs_f64() * 1000.0;
+    let Ok(mut reg) = registry().lock() else {
+        return;
+    };
+    reg.histograms
+        .entry((category, operation.to_string()))
+        .or_default()
+        .record(ms);
+
+    if let Some((_, writer)) = reg.trace.as_mut() {
+        let line = TraceLine {
+            ts: Utc::now(),
+            category,
+            op: operation,
+            ms,
+        };
+        if let Ok(json) = serde_json::to_string(&line) {
+            let _ = writeln!(writer, "{}", json);
+        }
+    }
+}
+
+/// Times a scope; the elapsed time is recorded when the guard drops
+pub struct Timer {
+    category: Category,
+    operation: &'static str,
+    start: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        record(self.category, self.operation, self.start.elapsed());
+    }
+}
+
+/// Start timing `operation`; hold the returned guard for the scope to time
+pub fn time(category: Category, operation: &'static str) -> Timer {
+    Timer {
+        category,
+        operation,
+        start: Instant::now(),
+    }
+}
+
+/// Snapshot the current metrics
+pub fn report(process: &str) -> PerformanceReport {
+    let mut reg = registry().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, writer)) = reg.trace.as_mut() {
+        let _ = writer.flush();
+    }
+
+    let mut operations: Vec<OperationStats> = reg
+        .histograms
+        .iter()
+        .map(|((category, operation), h)| OperationStats {
+            category: *category,
+            operation: operation.clone(),
+            count: h.count,
+            total_ms: h.total_ms,
+            mean_ms: if h.count > 0 {
+                h.total_ms / h.count as f64
+            } else {
+                0.0
+            },
+            min_ms: if h.count > 0 { h.min_ms } else { 0.0 },
+            max_ms: h.max_ms,
+            p50_ms: h.quantile(0.50),
+            p95_ms: h.quantile(0.95),
+            p99_ms: h.quantile(0.99),
+            buckets: h
+                .buckets
+                .iter()
+                .enumerate()
+                .filter(|(_, &n)| n > 0)
+                .map(|(i, &count)| BucketCount {
+                    le_ms: BUCKET_BOUNDS_MS.get(i).copied(),
+                    count,
+                })
+                .collect(),
+        })
+        .collect();
+    operations.sort_by(|a, b| {
+        b.total_ms
+            .partial_cmp(&a.total_ms)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    PerformanceReport {
+        generated_at: Utc::now(),
+        collecting_since: reg.since,
+        process: process.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        operations,
+        trace_file: reg
+            .trace
+            .as_ref()
+            .map(|(path, _)| path.to_string_lossy().to_string()),
+    }
+}
+
+/// Clear all histograms (tracing, if on, keeps going)
+pub fn reset() {
+    if let Ok(mut reg) = registry().lock() {
+        reg.histograms.clear();
+        reg.since = Utc::now();
+    }
+}
+
+/// Start appending every sample to a new JSONL file in `dir`, returning its
+/// path. Replaces any trace already running.
+pub fn start_trace(dir: &Path) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "trace-{}-{}.jsonl",
+        Utc::now().format("%Y%m%dT%H%M%S"),
+        std::process::id()
+    ));
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+    let mut reg = registry().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, mut old)) = reg.trace.take() {
+        let _ = old.flush();
+    }
+    reg.trace = Some((path.clone(), BufWriter::new(file)));
+    Ok(path)
+}
+
+/// Stop tracing, returning the finished file's path
+pub fn stop_trace() -> Option<PathBuf> {
+    let mut reg = registry().lock().unwrap_or_else(|e| e.into_inner());
+    reg.trace.take().map(|(path, mut writer)| {
+        let _ = writer.flush();
+        path
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_quantiles_follow_buckets() {
+        let mut h = Histogram::default();
+        for _ in 0..90 {
+            h.record(3.0);
+        }
+        for _ in 0..10 {
+            h.record(400.0);
+        }
+        assert_eq!(h.count, 100);
+        assert_eq!(h.quantile(0.5), 5.0);
+        assert_eq!(h.quantile(0.95), 400.0, "capped at the observed max");
+        assert_eq!(h.min_ms, 3.0);
+
+        let mut overflow = Histogram::default();
+        overflow.record(60_000.0);
+        assert_eq!(overflow.buckets[BUCKET_BOUNDS_MS.len()], 1);
+        assert_eq!(overflow.quantile(0.99), 60_000.0);
+    }
+
+    #[test]
+    fn timers_land_in_the_report_and_trace() {
+        let dir = std::env::temp_dir().join(format!("diagnostics_test_{}", uuid::Uuid::new_v4()));
+        let trace = start_trace(&dir).unwrap();
+
+        record(Category::Search, "test_query", Duration::from_millis(12));
+        {
+            let _t = time(Category::Search, "test_query");
+        }
+
+        let report = report("test");
+        let stats = report
+            .operations
+            .iter()
+            .find(|s| s.category == Category::Search && s.operation == "test_query")
+            .unwrap();
+        assert!(stats.count >= 2);
+        assert!(stats.max_ms >= 12.0);
+
+        assert_eq!(stop_trace(), Some(trace.clone()));
+        let lines = std::fs::read_to_string(&trace).unwrap();
+        assert!(lines.lines().filter(|l| l.contains("test_query")).count() >= 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,178 @@
+//! Biometric unlock enrollments
+//!
+//! Records which notebooks can be unlocked with TouchID / Windows Hello and
+//! when each last had its password typed. The keys themselves live in the
+//! OS keychain (see `keychain`); this file only holds timestamps.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::errors::EncryptionResult;
+use super::models::BiometricSettings;
+
+/// A notebook set up for biometric unlock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricEnrollment {
+    pub enrolled_at: DateTime<Utc>,
+    /// Last time the notebook was unlocked with its password
+    pub last_password_at: DateTime<Utc>,
+}
+
+impl BiometricEnrollment {
+    /// Whether the password has to be typed again before biometric unlock
+    /// is allowed
+    pub fn password_required(&self, settings: &BiometricSettings, now: DateTime<Utc>) -> bool {
+        let hours = settings.require_password_after_hours;
+        hours > 0 && now - self.last_password_at >= Duration::hours(hours.into())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BiometricFile {
+    #[serde(default)]
+    settings: BiometricSettings,
+    #[serde(default)]
+    enrollments: HashMap<Uuid, BiometricEnrollment>,
+}
+
+/// Enrollments and settings, kept in `biometric_unlock.json` in the app
+/// data directory
+pub struct BiometricStore {
+    path: PathBuf,
+    file: BiometricFile,
+}
+
+impl BiometricStore {
+    /// Load from `path`; a missing or unreadable file starts empty
+    pub fn load(path: PathBuf) -> Self {
+        let file = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {}: {}", path.display(), e);
+                BiometricFile::default()
+            }),
+            Err(_) => BiometricFile::default(),
+        };
+        Self { path, file }
+    }
+
+    fn save(&self) -> EncryptionResult<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.file)?)?;
+        Ok(())
+    }
+
+    pub fn settings(&self) -> &BiometricSettings {
+        &self.file.settings
+    }
+
+    pub fn set_settings(&mut self, settings: BiometricSettings) -> EncryptionResult<()> {
+        self.file.settings = settings;
+        self.save()
+    }
+
+    pub fn enrollment(&self, scope_id: Uuid) -> Option<&BiometricEnrollment> {
+        self.file.enrollments.get(&scope_id)
+    }
+
+    pub fn enroll(&mut self, scope_id: Uuid, now: DateTime<Utc>) -> EncryptionResult<()> {
+        self.file.enrollments.insert(
+            scope_id,
+            BiometricEnrollment {
+                enrolled_at: now,
+                last_password_at: now,
+            },
+        );
+        self.save()
+    }
+
+    /// Restart the require-password clock. Returns whether `scope_id` is
+    /// enrolled at all.
+    pub fn record_password(
+        &mut self,
+        scope_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> EncryptionResult<bool> {
+        let Some(enrollment) = self.file.enrollments.get_mut(&scope_id) else {
+            return Ok(false);
+        };
+        enrollment.last_password_at = now;
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, scope_id: Uuid) -> EncryptionResult<()> {
+        if self.file.enrollments.remove(&scope_id).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn password_required_after_configured_hours() {
+        let now = Utc::now();
+        let enrollment = BiometricEnrollment {
+            enrolled_at: now - Duration::hours(100),
+            last_password_at: now - Duration::hours(10),
+        };
+        let within = BiometricSettings {
+            require_password_after_hours: 12,
+        };
+        let past = BiometricSettings {
+            require_password_after_hours: 8,
+        };
+        let never = BiometricSettings {
+            require_password_after_hours: 0,
+        };
+
+        assert!(!enrollment.password_required(&within, now));
+        assert!(enrollment.password_required(&past, now));
+        assert!(!enrollment.password_required(&never, now));
+    }
+
+    #[test]
+    fn store_persists_enrollments_and_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("biometric_unlock.json");
+        let id = Uuid::new_v4();
+        let enrolled_at = Utc::now() - Duration::hours(5);
+
+        let mut store = BiometricStore::load(path.clone());
+        assert!(!store.record_password(id, Utc::now()).unwrap());
+        store.enroll(id, enrolled_at).unwrap();
+        store
+            .set_settings(BiometricSettings {
+                require_password_after_hours: 4,
+            })
+            .unwrap();
+
+        let mut store = BiometricStore::load(path.clone());
+        assert_eq!(store.settings().require_password_after_hours, 4);
+        let enrollment = store.enrollment(id).unwrap();
+        assert!(enrollment.password_required(store.settings(), Utc::now()));
+
+        assert!(store.record_password(id, Utc::now()).unwrap());
+        let store_settings = store.settings().clone();
+        assert!(!store
+            .enrollment(id)
+            .unwrap()
+            .password_required(&store_settings, Utc::now()));
+
+        store.remove(id).unwrap();
+        assert!(BiometricStore::load(path).enrollment(id).is_none());
+    }
+}
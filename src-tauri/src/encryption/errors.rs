@@ -14,12 +14,27 @@ pub enum EncryptionError {
     #[error("Library is locked. Please unlock with password first.")]
     LibraryLocked,
 
+    #[error("Page is locked. Please unlock with password first.")]
+    PageLocked,
+
     #[error("Invalid password")]
     InvalidPassword,
 
     #[error("Password is required")]
     PasswordRequired,
 
+    #[error("Biometric unlock is not available on this device")]
+    BiometricUnavailable,
+
+    #[error("Biometric unlock is not set up for this notebook")]
+    BiometricNotEnrolled,
+
+    #[error("Biometric verification failed: {0}")]
+    BiometricFailed(String),
+
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+
     #[error("Key derivation failed: {0}")]
     KeyDerivationFailed(String),
 
@@ -0,0 +1,157 @@
+//! OS keychain-wrapped keys and the TouchID / Windows Hello prompt
+//!
+//! A notebook enrolled for biometric unlock has its derived key stored in
+//! the OS keychain (via `keyring`). Reading it back is gated on a user
+//! presence check: LocalAuthentication on macOS, Windows Hello on Windows.
+//! Other platforms report biometric unlock as unavailable, so the password
+//! is always required there.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use uuid::Uuid;
+use zeroize::Zeroize;
+
+use super::errors::{EncryptionError, EncryptionResult};
+use super::models::EncryptionKey;
+
+const SERVICE_NAME: &str = "nous-encryption-keys";
+
+fn entry(scope_id: Uuid) -> EncryptionResult<keyring::Entry> {
+    keyring::Entry::new(SERVICE_NAME, &scope_id.to_string())
+        .map_err(|e| EncryptionError::Keychain(e.to_string()))
+}
+
+/// Store a key in the OS keychain
+pub fn store_key(scope_id: Uuid, key: &EncryptionKey) -> EncryptionResult<()> {
+    let mut encoded = BASE64.encode(key.as_bytes());
+    let result = entry(scope_id)?
+        .set_password(&encoded)
+        .map_err(|e| EncryptionError::Keychain(e.to_string()));
+    encoded.zeroize();
+    result
+}
+
+/// Read a key back from the OS keychain
+pub fn load_key(scope_id: Uuid) -> EncryptionResult<EncryptionKey> {
+    let mut encoded = entry(scope_id)?
+        .get_password()
+        .map_err(|e| EncryptionError::Keychain(e.to_string()))?;
+    let mut bytes = BASE64.decode(encoded.trim())?;
+    encoded.zeroize();
+    let key: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| EncryptionError::InvalidFormat)?;
+    bytes.zeroize();
+    Ok(EncryptionKey::new(key))
+}
+
+/// Remove a key from the OS keychain (best-effort)
+pub fn delete_key(scope_id: Uuid) {
+    if let Ok(entry) = entry(scope_id) {
+        let _ = entry.delete_credential();
+    }
+}
+
+/// Whether this device can verify the user with biometrics
+pub fn biometric_available() -> bool {
+    platform::available()
+}
+
+/// Show the system prompt and wait for the user to pass it. `reason` ends
+/// up in the dialog ("Nous is trying to {reason}" on macOS).
+pub fn verify_user(reason: &str) -> EncryptionResult<()> {
+    platform::verify(reason)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::sync::mpsc;
+
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSError, NSString};
+    use objc2_local_authentication::{LAContext, LAPolicy};
+
+    use super::{EncryptionError, EncryptionResult};
+
+    const POLICY: LAPolicy = LAPolicy::DeviceOwnerAuthenticationWithBiometrics;
+
+    pub fn available() -> bool {
+        let context = unsafe { LAContext::new() };
+        unsafe { context.canEvaluatePolicy_error(POLICY) }.is_ok()
+    }
+
+    pub fn verify(reason: &str) -> EncryptionResult<()> {
+        if !available() {
+            return Err(EncryptionError::BiometricUnavailable);
+        }
+        let context = unsafe { LAContext::new() };
+        let (tx, rx) = mpsc::channel();
+        let reply = RcBlock::new(move |success: Bool, error: *mut NSError| {
+            let message = if success.as_bool() || error.is_null() {
+                None
+            } else {
+                Some(unsafe { (*error).localizedDescription() }.to_string())
+            };
+            let _ = tx.send((success.as_bool(), message));
+        });
+        unsafe {
+            context.evaluatePolicy_localizedReason_reply(
+                POLICY,
+                &NSString::from_str(reason),
+                &reply,
+            )
+        };
+        match rx.recv() {
+            Ok((true, _)) => Ok(()),
+            Ok((false, message)) => Err(EncryptionError::BiometricFailed(
+                message.unwrap_or_else(|| "Cancelled".to_string()),
+            )),
+            Err(_) => Err(EncryptionError::BiometricFailed("No response".to_string())),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{
+        UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+    };
+
+    use super::{EncryptionError, EncryptionResult};
+
+    pub fn available() -> bool {
+        UserConsentVerifier::CheckAvailabilityAsync()
+            .and_then(|op| op.get())
+            .map(|availability| availability == UserConsentVerifierAvailability::Available)
+            .unwrap_or(false)
+    }
+
+    pub fn verify(reason: &str) -> EncryptionResult<()> {
+        if !available() {
+            return Err(EncryptionError::BiometricUnavailable);
+        }
+        let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason))
+            .and_then(|op| op.get())
+            .map_err(|e| EncryptionError::BiometricFailed(e.message().to_string()))?;
+        if result == UserConsentVerificationResult::Verified {
+            Ok(())
+        } else {
+            Err(EncryptionError::BiometricFailed(format!("{:?}", result)))
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::{EncryptionError, EncryptionResult};
+
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn verify(_reason: &str) -> EncryptionResult<()> {
+        Err(EncryptionError::BiometricUnavailable)
+    }
+}
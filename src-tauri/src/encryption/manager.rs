@@ -1,12 +1,16 @@
 //! Encryption manager for handling unlock state
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use super::biometric::BiometricStore;
+use super::crypto::create_verification_hash;
 use super::errors::{EncryptionError, EncryptionResult};
-use super::models::EncryptionKey;
+use super::keychain;
+use super::models::{BiometricSettings, BiometricStatus, EncryptionKey};
 
 /// Default auto-lock timeout (1 hour)
 const DEFAULT_AUTO_LOCK_TIMEOUT: Duration = Duration::from_secs(3600);
@@ -37,14 +41,18 @@ impl UnlockedEntry {
     }
 }
 
-/// Manages encryption keys for unlocked notebooks and libraries
+/// Manages encryption keys for unlocked notebooks, libraries and pages
 pub struct EncryptionManager {
     /// Keys for unlocked notebooks (notebook_id -> key)
     unlocked_notebooks: RwLock<HashMap<Uuid, UnlockedEntry>>,
     /// Keys for unlocked libraries (library_id -> key)
     unlocked_libraries: RwLock<HashMap<Uuid, UnlockedEntry>>,
+    /// Keys for unlocked pages (page_id -> key)
+    unlocked_pages: RwLock<HashMap<Uuid, UnlockedEntry>>,
     /// Auto-lock timeout duration
     auto_lock_timeout: Duration,
+    /// Biometric unlock enrollments (None until a store path is given)
+    biometric: RwLock<Option<BiometricStore>>,
 }
 
 impl Default for EncryptionManager {
@@ -59,7 +67,9 @@ impl EncryptionManager {
         Self {
             unlocked_notebooks: RwLock::new(HashMap::new()),
             unlocked_libraries: RwLock::new(HashMap::new()),
+            unlocked_pages: RwLock::new(HashMap::new()),
             auto_lock_timeout: DEFAULT_AUTO_LOCK_TIMEOUT,
+            biometric: RwLock::new(None),
         }
     }
 
@@ -68,10 +78,18 @@ impl EncryptionManager {
         Self {
             unlocked_notebooks: RwLock::new(HashMap::new()),
             unlocked_libraries: RwLock::new(HashMap::new()),
+            unlocked_pages: RwLock::new(HashMap::new()),
             auto_lock_timeout: timeout,
+            biometric: RwLock::new(None),
         }
     }
 
+    /// Keep biometric unlock enrollments in the JSON file at `path`
+    pub fn with_biometric_store(self, path: PathBuf) -> Self {
+        *self.biometric.write().unwrap() = Some(BiometricStore::load(path));
+        self
+    }
+
     /// Set the auto-lock timeout
     pub fn set_auto_lock_timeout(&mut self, timeout: Duration) {
         self.auto_lock_timeout = timeout;
@@ -186,11 +204,181 @@ impl EncryptionManager {
             .collect()
     }
 
+    // ========================
+    // Page operations
+    // ========================
+
+    /// Store a key for an unlocked page
+    pub fn unlock_page(&self, page_id: Uuid, key: EncryptionKey) {
+        let mut pages = self.unlocked_pages.write().unwrap();
+        pages.insert(page_id, UnlockedEntry::new(key));
+    }
+
+    /// Lock a page (remove key from memory)
+    pub fn lock_page(&self, page_id: Uuid) {
+        let mut pages = self.unlocked_pages.write().unwrap();
+        pages.remove(&page_id);
+    }
+
+    /// Check if a page is unlocked
+    pub fn is_page_unlocked(&self, page_id: Uuid) -> bool {
+        let pages = self.unlocked_pages.read().unwrap();
+        if let Some(entry) = pages.get(&page_id) {
+            !entry.is_expired(self.auto_lock_timeout)
+        } else {
+            false
+        }
+    }
+
+    /// Get the key for an unlocked page
+    pub fn get_page_key(&self, page_id: Uuid) -> EncryptionResult<EncryptionKey> {
+        let mut pages = self.unlocked_pages.write().unwrap();
+
+        if let Some(entry) = pages.get_mut(&page_id) {
+            if entry.is_expired(self.auto_lock_timeout) {
+                pages.remove(&page_id);
+                return Err(EncryptionError::PageLocked);
+            }
+            entry.touch();
+            Ok(entry.key.clone())
+        } else {
+            Err(EncryptionError::PageLocked)
+        }
+    }
+
+    /// Get all unlocked page IDs
+    pub fn unlocked_page_ids(&self) -> Vec<Uuid> {
+        let pages = self.unlocked_pages.read().unwrap();
+        pages
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(self.auto_lock_timeout))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Remove expired page keys, returning the pages that were auto-locked
+    /// so their search entries can be dropped
+    pub fn expire_pages(&self) -> Vec<Uuid> {
+        let mut pages = self.unlocked_pages.write().unwrap();
+        let expired: Vec<Uuid> = pages
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(self.auto_lock_timeout))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &expired {
+            pages.remove(id);
+        }
+        expired
+    }
+
+    // ========================
+    // Biometric unlock
+    // ========================
+
+    /// Put a notebook's key in the OS keychain so it can be unlocked with
+    /// TouchID / Windows Hello
+    pub fn enable_biometric(&self, notebook_id: Uuid, key: &EncryptionKey) -> EncryptionResult<()> {
+        if !keychain::biometric_available() {
+            return Err(EncryptionError::BiometricUnavailable);
+        }
+        let mut biometric = self.biometric.write().unwrap();
+        let store = biometric.as_mut().ok_or(EncryptionError::BiometricUnavailable)?;
+        keychain::store_key(notebook_id, key)?;
+        store.enroll(notebook_id, chrono::Utc::now())
+    }
+
+    /// Remove a notebook's key from the OS keychain
+    pub fn disable_biometric(&self, notebook_id: Uuid) -> EncryptionResult<()> {
+        keychain::delete_key(notebook_id);
+        let mut biometric = self.biometric.write().unwrap();
+        match biometric.as_mut() {
+            Some(store) => store.remove(notebook_id),
+            None => Ok(()),
+        }
+    }
+
+    /// Note that the password was just typed. For enrolled notebooks this
+    /// restarts the require-password clock and refreshes the keychain copy
+    /// of the key (which changes with the password).
+    pub fn record_password_unlock(&self, notebook_id: Uuid, key: &EncryptionKey) -> EncryptionResult<()> {
+        let mut biometric = self.biometric.write().unwrap();
+        let Some(store) = biometric.as_mut() else {
+            return Ok(());
+        };
+        if store.record_password(notebook_id, chrono::Utc::now())? {
+            keychain::store_key(notebook_id, key)?;
+        }
+        Ok(())
+    }
+
+    /// Biometric unlock state of a notebook
+    pub fn biometric_status(&self, notebook_id: Uuid) -> BiometricStatus {
+        let available = keychain::biometric_available();
+        let biometric = self.biometric.read().unwrap();
+        let (enrolled, password_required) = match biometric.as_ref() {
+            Some(store) => match store.enrollment(notebook_id) {
+                Some(enrollment) => (true, enrollment.password_required(store.settings(), chrono::Utc::now())),
+                None => (false, true),
+            },
+            None => (false, true),
+        };
+        BiometricStatus {
+            available,
+            enrolled,
+            password_required: password_required || !available,
+        }
+    }
+
+    /// Unlock a notebook with TouchID / Windows Hello. Blocks while the
+    /// system prompt is shown. Fails with `PasswordRequired` when the
+    /// password has to be typed instead.
+    pub fn unlock_notebook_biometric(&self, notebook_id: Uuid, verification_hash: &str, reason: &str) -> EncryptionResult<()> {
+        {
+            let biometric = self.biometric.read().unwrap();
+            let store = biometric.as_ref().ok_or(EncryptionError::BiometricNotEnrolled)?;
+            let enrollment = store.enrollment(notebook_id).ok_or(EncryptionError::BiometricNotEnrolled)?;
+            if !keychain::biometric_available() {
+                return Err(EncryptionError::BiometricUnavailable);
+            }
+            if enrollment.password_required(store.settings(), chrono::Utc::now()) {
+                return Err(EncryptionError::PasswordRequired);
+            }
+        }
+
+        keychain::verify_user(reason)?;
+        let key = keychain::load_key(notebook_id)?;
+
+        // The password changed somewhere we didn't see (e.g. on another
+        // device): the stored key is stale, fall back to the password
+        if create_verification_hash(&key) != verification_hash {
+            self.disable_biometric(notebook_id)?;
+            return Err(EncryptionError::PasswordRequired);
+        }
+
+        self.unlock_notebook(notebook_id, key);
+        Ok(())
+    }
+
+    /// Current biometric unlock settings
+    pub fn biometric_settings(&self) -> BiometricSettings {
+        let biometric = self.biometric.read().unwrap();
+        biometric.as_ref().map(|store| store.settings().clone()).unwrap_or_default()
+    }
+
+    /// Update biometric unlock settings
+    pub fn set_biometric_settings(&self, settings: BiometricSettings) -> EncryptionResult<()> {
+        let mut biometric = self.biometric.write().unwrap();
+        match biometric.as_mut() {
+            Some(store) => store.set_settings(settings),
+            None => Err(EncryptionError::BiometricUnavailable),
+        }
+    }
+
     // ========================
     // Maintenance operations
     // ========================
 
-    /// Lock all notebooks and libraries
+    /// Lock all notebooks, libraries and pages
     pub fn lock_all(&self) {
         {
             let mut notebooks = self.unlocked_notebooks.write().unwrap();
@@ -200,6 +388,10 @@ impl EncryptionManager {
             let mut libraries = self.unlocked_libraries.write().unwrap();
             libraries.clear();
         }
+        {
+            let mut pages = self.unlocked_pages.write().unwrap();
+            pages.clear();
+        }
     }
 
     /// Remove expired entries (auto-lock check)
@@ -212,16 +404,22 @@ impl EncryptionManager {
             let mut libraries = self.unlocked_libraries.write().unwrap();
             libraries.retain(|_, entry| !entry.is_expired(self.auto_lock_timeout));
         }
+        {
+            let mut pages = self.unlocked_pages.write().unwrap();
+            pages.retain(|_, entry| !entry.is_expired(self.auto_lock_timeout));
+        }
     }
 
     /// Get statistics about unlocked items
     pub fn stats(&self) -> EncryptionStats {
         let notebooks = self.unlocked_notebooks.read().unwrap();
         let libraries = self.unlocked_libraries.read().unwrap();
+        let pages = self.unlocked_pages.read().unwrap();
 
         EncryptionStats {
             unlocked_notebooks: notebooks.len(),
             unlocked_libraries: libraries.len(),
+            unlocked_pages: pages.len(),
             auto_lock_timeout_secs: self.auto_lock_timeout.as_secs(),
         }
     }
@@ -232,6 +430,7 @@ impl EncryptionManager {
 pub struct EncryptionStats {
     pub unlocked_notebooks: usize,
     pub unlocked_libraries: usize,
+    pub unlocked_pages: usize,
     pub auto_lock_timeout_secs: u64,
 }
 
@@ -287,6 +486,41 @@ mod tests {
         assert!(manager.get_library_key(library_id).is_err());
     }
 
+    #[test]
+    fn test_page_unlock_lock() {
+        let manager = EncryptionManager::new();
+        let page_id = Uuid::new_v4();
+
+        assert!(!manager.is_page_unlocked(page_id));
+        assert!(matches!(
+            manager.get_page_key(page_id),
+            Err(EncryptionError::PageLocked)
+        ));
+
+        manager.unlock_page(page_id, test_key());
+        assert!(manager.is_page_unlocked(page_id));
+        assert_eq!(manager.unlocked_page_ids(), vec![page_id]);
+        assert_eq!(manager.stats().unlocked_pages, 1);
+
+        manager.lock_page(page_id);
+        assert!(!manager.is_page_unlocked(page_id));
+        assert!(manager.get_page_key(page_id).is_err());
+    }
+
+    #[test]
+    fn test_expire_pages_reports_auto_locked() {
+        let manager = EncryptionManager::with_timeout(Duration::from_millis(50));
+        let page_id = Uuid::new_v4();
+
+        manager.unlock_page(page_id, test_key());
+        assert!(manager.expire_pages().is_empty());
+
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(manager.expire_pages(), vec![page_id]);
+        assert_eq!(manager.stats().unlocked_pages, 0);
+    }
+
     #[test]
     fn test_auto_lock_timeout() {
         // Use a very short timeout for testing
@@ -1,15 +1,20 @@
-//! Encryption module for notebook and library password protection
+//! Encryption module for notebook, library and page password protection
 //!
 //! This module provides:
 //! - ChaCha20-Poly1305 authenticated encryption
 //! - Argon2id password-based key derivation
 //! - In-memory key management for unlocked notebooks/libraries
+//! - OS keychain-wrapped keys for TouchID / Windows Hello unlock
 //! - Encrypted file container format
+//! - Sealing the content of individual pages
 
+pub mod biometric;
 pub mod crypto;
 pub mod errors;
+pub mod keychain;
 pub mod manager;
 pub mod models;
+pub mod page;
 
 // Re-export commonly used types
 pub use crypto::{
@@ -20,6 +25,7 @@ pub use crypto::{
 pub use errors::{EncryptionError, EncryptionResult};
 pub use manager::{EncryptionManager, EncryptionStats};
 pub use models::{
-    Argon2Params, EncryptedContainer, EncryptionConfig, EncryptionKey, EncryptionLevel,
+    Argon2Params, BiometricSettings, BiometricStatus, EncryptedContainer, EncryptionConfig, EncryptionKey, EncryptionLevel,
     EncryptionMetadata, UnlockResult, ALGORITHM_VERSION, ENCRYPTED_MAGIC,
 };
+pub use page::{decrypt_page, decrypt_page_content, encrypt_page, reseal_page, unlocked_view};
@@ -10,7 +10,7 @@ pub const ENCRYPTED_MAGIC: &str = "KATT_ENC";
 /// Current encryption algorithm version
 pub const ALGORITHM_VERSION: u32 = 1;
 
-/// Encryption level for notebooks/libraries
+/// Encryption level for notebooks/libraries/pages
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum EncryptionLevel {
@@ -21,6 +21,8 @@ pub enum EncryptionLevel {
     Notebook,
     /// Entire library encrypted
     Library,
+    /// A single page inside an otherwise unencrypted notebook
+    Page,
 }
 
 /// Configuration stored with encrypted notebooks/libraries
@@ -62,6 +64,13 @@ impl EncryptionConfig {
         config.level = EncryptionLevel::Library;
         config
     }
+
+    /// Create a page-level encryption config
+    pub fn new_page(salt: String, verification_hash: String, password_hint: Option<String>) -> Self {
+        let mut config = Self::new(salt, verification_hash, password_hint);
+        config.level = EncryptionLevel::Page;
+        config
+    }
 }
 
 /// Metadata stored with each encrypted file
@@ -176,6 +185,39 @@ impl Default for Argon2Params {
     }
 }
 
+/// Default for `BiometricSettings::require_password_after_hours`
+const DEFAULT_REQUIRE_PASSWORD_AFTER_HOURS: u32 = 48;
+
+/// Settings for unlocking with TouchID / Windows Hello
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BiometricSettings {
+    /// Ask for the password again once it hasn't been typed for this many
+    /// hours (0 = never)
+    pub require_password_after_hours: u32,
+}
+
+impl Default for BiometricSettings {
+    fn default() -> Self {
+        Self {
+            require_password_after_hours: DEFAULT_REQUIRE_PASSWORD_AFTER_HOURS,
+        }
+    }
+}
+
+/// Biometric unlock state of a notebook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BiometricStatus {
+    /// This device can verify the user with biometrics
+    pub available: bool,
+    /// The notebook's key is in the OS keychain
+    pub enrolled: bool,
+    /// The password must be typed instead (not enrolled, or too long since
+    /// it was last entered)
+    pub password_required: bool,
+}
+
 /// Response for unlock operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnlockResult {
@@ -0,0 +1,136 @@
+//! Per-page encryption
+//!
+//! A page can be encrypted on its own inside an unencrypted notebook. Its
+//! metadata stays as plain JSON so listings and the page tree keep working;
+//! the `EditorData` is sealed into `Page::encryption` and `content` is left
+//! empty. Edits to an unlocked page are resealed before they are written.
+
+use crate::storage::{EditorData, Page, PageEncryption};
+
+use super::crypto::{decrypt_json, encrypt_json};
+use super::errors::{EncryptionError, EncryptionResult};
+use super::models::{EncryptionConfig, EncryptionKey};
+
+/// Seal a page's current content with `key`
+pub fn encrypt_page(
+    page: &mut Page,
+    key: &EncryptionKey,
+    config: EncryptionConfig,
+) -> EncryptionResult<()> {
+    let sealed_content = encrypt_json(&page.content, key)?;
+    page.encryption = Some(PageEncryption {
+        config,
+        sealed_content,
+    });
+    page.content = EditorData::default();
+    Ok(())
+}
+
+/// Decrypt the sealed content of an encrypted page
+pub fn decrypt_page_content(page: &Page, key: &EncryptionKey) -> EncryptionResult<EditorData> {
+    let encryption = page
+        .encryption
+        .as_ref()
+        .ok_or(EncryptionError::NotEncrypted)?;
+    decrypt_json(&encryption.sealed_content, key)
+}
+
+/// Permanently remove a page's encryption, restoring its content
+pub fn decrypt_page(page: &mut Page, key: &EncryptionKey) -> EncryptionResult<()> {
+    page.content = decrypt_page_content(page, key)?;
+    page.encryption = None;
+    Ok(())
+}
+
+/// Re-encrypt an encrypted page after its `content` was replaced with new
+/// plaintext, leaving `content` empty again
+pub fn reseal_page(page: &mut Page, key: &EncryptionKey) -> EncryptionResult<()> {
+    let sealed_content = encrypt_json(&page.content, key)?;
+    let encryption = page
+        .encryption
+        .as_mut()
+        .ok_or(EncryptionError::NotEncrypted)?;
+    encryption.sealed_content = sealed_content;
+    page.content = EditorData::default();
+    Ok(())
+}
+
+/// A copy of an encrypted page with its content decrypted, for returning to
+/// a client that unlocked it. The copy is still marked as encrypted.
+pub fn unlocked_view(page: &Page, key: &EncryptionKey) -> EncryptionResult<Page> {
+    let mut view = page.clone();
+    view.content = decrypt_page_content(page, key)?;
+    Ok(view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EditorBlock;
+    use uuid::Uuid;
+
+    fn key(byte: u8) -> EncryptionKey {
+        EncryptionKey::new([byte; 32])
+    }
+
+    fn page_with_text(text: &str) -> Page {
+        let mut page = Page::new(Uuid::new_v4(), "Secret".to_string());
+        page.content.blocks.push(EditorBlock {
+            id: "b1".to_string(),
+            block_type: "paragraph".to_string(),
+            data: serde_json::json!({ "text": text }),
+        });
+        page
+    }
+
+    fn config() -> EncryptionConfig {
+        EncryptionConfig::new_page("salt".to_string(), "hash".to_string(), None)
+    }
+
+    #[test]
+    fn encrypt_clears_content_and_roundtrips() {
+        let mut page = page_with_text("bank pin 1234");
+        encrypt_page(&mut page, &key(7), config()).unwrap();
+
+        assert!(page.is_encrypted());
+        assert!(page.content.blocks.is_empty());
+        assert!(!serde_json::to_string(&page).unwrap().contains("bank pin"));
+
+        let view = unlocked_view(&page, &key(7)).unwrap();
+        assert!(view.is_encrypted());
+        assert_eq!(view.content.blocks[0].data["text"], "bank pin 1234");
+
+        decrypt_page(&mut page, &key(7)).unwrap();
+        assert!(!page.is_encrypted());
+        assert_eq!(page.content.blocks[0].data["text"], "bank pin 1234");
+    }
+
+    #[test]
+    fn wrong_key_fails() {
+        let mut page = page_with_text("hidden");
+        encrypt_page(&mut page, &key(7), config()).unwrap();
+        assert!(decrypt_page_content(&page, &key(8)).is_err());
+    }
+
+    #[test]
+    fn reseal_replaces_sealed_content() {
+        let mut page = page_with_text("old");
+        encrypt_page(&mut page, &key(1), config()).unwrap();
+
+        page.content = page_with_text("new").content;
+        reseal_page(&mut page, &key(1)).unwrap();
+
+        assert!(page.content.blocks.is_empty());
+        let content = decrypt_page_content(&page, &key(1)).unwrap();
+        assert_eq!(content.blocks[0].data["text"], "new");
+    }
+
+    #[test]
+    fn plain_page_is_not_encrypted() {
+        let mut page = page_with_text("open");
+        assert!(matches!(
+            reseal_page(&mut page, &key(1)),
+            Err(EncryptionError::NotEncrypted)
+        ));
+    }
+}
@@ -457,14 +457,18 @@ pub fn import_evernote_enex(
         system_prompt_mode: crate::storage::SystemPromptMode::default(),
         ai_provider: None,
         ai_model: None,
+        ai_profile_id: None,
         sync_config: None,
         encryption_config: None,
         is_pinned: false,
         position: 0,
         page_sort_by: None,
+        pinned_page_ids: Vec::new(),
         daily_notes_config: None,
         cover_image: None,
         mirror_path: None,
+        exclude_from_review: false,
+        code_exec: None,
         created_at: now,
         updated_at: now,
     };
@@ -577,6 +581,9 @@ pub fn import_evernote_enex(
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: note.created.unwrap_or(now),
             updated_at: note.updated.unwrap_or(now),
         };
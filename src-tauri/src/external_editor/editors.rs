@@ -0,0 +1,416 @@
+//! Which external editors can be offered
+//!
+//! The built-in list covers the common editors on macOS, Windows and Linux;
+//! [`detect_editors`] keeps the ones installed on this machine, looking on
+//! `PATH` (with `PATHEXT` on Windows) and in the usual install locations.
+//! Users can add their own entries, kept per device in
+//! `external_editors.json` in the data directory since commands and paths
+//! differ between machines.
+//!
+//! Arguments may use `{file}` and `{line}` placeholders (see
+//! [`EditorConfig::args_for`]).
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{EditorConfig, ExternalEditorError, Result};
+
+/// User editor settings file in the data directory
+pub const EDITORS_FILE: &str = "external_editors.json";
+
+/// Editors the user added on this device
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EditorSettings {
+    pub custom: Vec<EditorConfig>,
+}
+
+/// A built-in editor
+struct KnownEditor {
+    name: &'static str,
+    /// Executable to look for
+    binary: &'static str,
+    args: &'static [&'static str],
+    wait: bool,
+    /// Runs in a terminal, so it is started in a new terminal window
+    terminal: bool,
+    /// Install locations used when the executable isn't on `PATH`: an
+    /// environment variable naming a base directory and a path under it
+    install_paths: &'static [(&'static str, &'static str)],
+}
+
+const KNOWN_EDITORS: &[KnownEditor] = &[
+    KnownEditor {
+        name: "VS Code",
+        binary: "code",
+        args: &["--wait"],
+        wait: true,
+        terminal: false,
+        install_paths: &[
+            ("LOCALAPPDATA", "Programs/Microsoft VS Code/bin/code.cmd"),
+            ("ProgramFiles", "Microsoft VS Code/bin/code.cmd"),
+            (
+                "HOME",
+                "Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            ),
+            (
+                "",
+                "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            ),
+        ],
+    },
+    KnownEditor {
+        name: "VS Code (no wait)",
+        binary: "code",
+        args: &[],
+        wait: false,
+        terminal: false,
+        install_paths: &[
+            ("LOCALAPPDATA", "Programs/Microsoft VS Code/bin/code.cmd"),
+            ("ProgramFiles", "Microsoft VS Code/bin/code.cmd"),
+            (
+                "HOME",
+                "Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            ),
+            (
+                "",
+                "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code",
+            ),
+        ],
+    },
+    KnownEditor {
+        name: "Vim (Terminal)",
+        binary: "vim",
+        args: &["+{line}", "{file}"],
+        wait: false,
+        terminal: true,
+        install_paths: &[],
+    },
+    KnownEditor {
+        name: "Neovim (Terminal)",
+        binary: "nvim",
+        args: &["+{line}", "{file}"],
+        wait: false,
+        terminal: true,
+        install_paths: &[],
+    },
+    KnownEditor {
+        name: "Sublime Text",
+        binary: "subl",
+        args: &["--wait", "{file}:{line}"],
+        wait: true,
+        terminal: false,
+        install_paths: &[
+            ("ProgramFiles", "Sublime Text/subl.exe"),
+            (
+                "",
+                "/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl",
+            ),
+        ],
+    },
+    KnownEditor {
+        name: "Atom",
+        binary: "atom",
+        args: &["--wait"],
+        wait: true,
+        terminal: false,
+        install_paths: &[],
+    },
+    KnownEditor {
+        name: "Emacs",
+        binary: "emacs",
+        args: &["+{line}", "{file}"],
+        wait: false,
+        terminal: false,
+        install_paths: &[],
+    },
+    KnownEditor {
+        name: "TextMate",
+        binary: "mate",
+        args: &["--wait", "--line", "{line}", "{file}"],
+        wait: true,
+        terminal: false,
+        install_paths: &[],
+    },
+    KnownEditor {
+        name: "Zed",
+        binary: "zed",
+        args: &["--wait", "{file}:{line}"],
+        wait: true,
+        terminal: false,
+        install_paths: &[
+            ("LOCALAPPDATA", "Programs/Zed/Zed.exe"),
+            ("", "/Applications/Zed.app/Contents/MacOS/cli"),
+        ],
+    },
+    KnownEditor {
+        name: "Notepad++",
+        binary: "notepad++",
+        args: &["-n{line}", "{file}"],
+        wait: false,
+        terminal: false,
+        install_paths: &[
+            ("ProgramFiles", "Notepad++/notepad++.exe"),
+            ("ProgramFiles(x86)", "Notepad++/notepad++.exe"),
+        ],
+    },
+    KnownEditor {
+        name: "Notepad",
+        binary: "notepad",
+        args: &[],
+        wait: true,
+        terminal: false,
+        install_paths: &[("SystemRoot", "notepad.exe")],
+    },
+];
+
+fn system_default() -> EditorConfig {
+    EditorConfig {
+        name: "System Default".to_string(),
+        command: String::new(),
+        args: vec![],
+        wait: false,
+    }
+}
+
+impl KnownEditor {
+    /// The editor as launched on this OS
+    fn config(&self) -> EditorConfig {
+        let mut args: Vec<String> = self.args.iter().map(|a| a.to_string()).collect();
+        let command = if self.terminal {
+            // The terminal launcher returns at once, so the editor can't be
+            // waited on
+            let (launcher, mut launch_args) = terminal_launcher();
+            launch_args.push(self.binary.to_string());
+            launch_args.append(&mut args);
+            args = launch_args;
+            launcher.to_string()
+        } else {
+            self.binary.to_string()
+        };
+        EditorConfig {
+            name: self.name.to_string(),
+            command,
+            args,
+            wait: self.wait && !self.terminal,
+        }
+    }
+
+    /// Where the editor is installed, if it is
+    fn locate(&self) -> Option<PathBuf> {
+        find_executable(self.binary).or_else(|| {
+            self.install_paths
+                .iter()
+                .filter_map(|(var, relative)| match *var {
+                    "" => Some(PathBuf::from(relative)),
+                    var => std::env::var_os(var).map(|base| Path::new(&base).join(relative)),
+                })
+                .find(|path| path.is_file())
+        })
+    }
+}
+
+/// Command and arguments that open a new terminal window running the rest
+/// of the arguments
+fn terminal_launcher() -> (&'static str, Vec<String>) {
+    let args: &[&str] = if cfg!(target_os = "macos") {
+        &["-a", "Terminal", "--args"]
+    } else if cfg!(windows) {
+        // `start` opens a new console window; its first quoted argument is
+        // the window title
+        &["/C", "start", ""]
+    } else {
+        &["-e"]
+    };
+    let launcher = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "cmd"
+    } else {
+        "x-terminal-emulator"
+    };
+    (launcher, args.iter().map(|a| a.to_string()).collect())
+}
+
+/// Every built-in editor, installed or not, as launched on this OS
+pub fn get_known_editors() -> Vec<EditorConfig> {
+    std::iter::once(system_default())
+        .chain(KNOWN_EDITORS.iter().map(KnownEditor::config))
+        .collect()
+}
+
+/// The built-in editors installed on this machine. Editors found outside
+/// `PATH` are launched by their full path.
+pub fn detect_editors() -> Vec<EditorConfig> {
+    let mut editors = vec![system_default()];
+    for known in KNOWN_EDITORS {
+        let Some(path) = known.locate() else {
+            continue;
+        };
+        let mut config = known.config();
+        if !known.terminal && find_executable(known.binary).is_none() {
+            config.command = path.to_string_lossy().to_string();
+        }
+        editors.push(config);
+    }
+    editors
+}
+
+/// Editors to offer: the user's own, then the installed built-in ones
+pub fn available_editors(data_dir: &Path) -> Vec<EditorConfig> {
+    let mut editors = detect_editors();
+    // After "System Default", so a custom editor is easy to find
+    let custom = load_settings(data_dir).custom;
+    editors.splice(1..1, custom);
+    editors
+}
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(EDITORS_FILE)
+}
+
+/// The user's editor settings; missing or unreadable settings are empty
+pub fn load_settings(data_dir: &Path) -> EditorSettings {
+    std::fs::read_to_string(settings_path(data_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save the user's editors. Every editor needs a name and a command, and
+/// names must be unique.
+pub fn save_settings(data_dir: &Path, settings: &EditorSettings) -> Result<()> {
+    let mut names = std::collections::HashSet::new();
+    for editor in &settings.custom {
+        if editor.name.trim().is_empty() || editor.command.trim().is_empty() {
+            return Err(ExternalEditorError::InvalidEditor(
+                "An editor needs a name and a command".to_string(),
+            ));
+        }
+        if !names.insert(editor.name.trim().to_lowercase()) {
+            return Err(ExternalEditorError::InvalidEditor(format!(
+                "Two editors are named \"{}\"",
+                editor.name
+            )));
+        }
+    }
+    std::fs::create_dir_all(data_dir)?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| ExternalEditorError::InvalidEditor(e.to_string()))?;
+    std::fs::write(settings_path(data_dir), content)?;
+    Ok(())
+}
+
+/// Find an executable on `PATH`, trying `PATHEXT` extensions on Windows.
+/// A command with a directory part is checked as given.
+pub fn find_executable(command: &str) -> Option<PathBuf> {
+    if command.is_empty() {
+        return None;
+    }
+    let extensions: Vec<OsString> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|e| !e.is_empty())
+            .map(OsString::from)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    if Path::new(command).components().count() > 1 {
+        return find_in(&[PathBuf::new()], command, &extensions);
+    }
+    let dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    find_in(&dirs, command, &extensions)
+}
+
+/// `name` in the first of `dirs` that has it, as is or with one of
+/// `extensions`
+fn find_in(dirs: &[PathBuf], name: &str, extensions: &[OsString]) -> Option<PathBuf> {
+    dirs.iter().find_map(|dir| {
+        let plain = dir.join(name);
+        if is_executable(&plain) {
+            return Some(plain);
+        }
+        extensions.iter().find_map(|ext| {
+            let mut file = plain.clone().into_os_string();
+            file.push(ext);
+            let file = PathBuf::from(file);
+            is_executable(&file).then_some(file)
+        })
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn terminal_editors_run_in_a_terminal_on_every_os() {
+        let vim = get_known_editors()
+            .into_iter()
+            .find(|e| e.name == "Vim (Terminal)")
+            .unwrap();
+        assert_ne!(vim.command, "vim");
+        assert!(vim.args.iter().any(|a| a == "vim"));
+        assert!(!vim.wait);
+    }
+
+    #[test]
+    fn finds_executables_with_extensions() {
+        let dir = TempDir::new().unwrap();
+        let dirs = vec![PathBuf::from("/nonexistent"), dir.path().to_path_buf()];
+        let script = dir.path().join("edit.cmd");
+        std::fs::write(&script, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        assert_eq!(
+            find_in(&dirs, "edit", &[OsString::from(".cmd")]),
+            Some(script)
+        );
+        assert_eq!(find_in(&dirs, "edit", &[]), None);
+    }
+
+    #[test]
+    fn custom_editors_round_trip_and_are_validated() {
+        let dir = TempDir::new().unwrap();
+        let helix = EditorConfig {
+            name: "Helix".to_string(),
+            command: "hx".to_string(),
+            args: vec!["{file}:{line}".to_string()],
+            wait: true,
+        };
+        let settings = EditorSettings {
+            custom: vec![helix.clone()],
+        };
+        save_settings(dir.path(), &settings).unwrap();
+        assert_eq!(load_settings(dir.path()), settings);
+        assert_eq!(available_editors(dir.path())[1], helix);
+
+        let duplicate = EditorSettings {
+            custom: vec![helix.clone(), helix],
+        };
+        assert!(save_settings(dir.path(), &duplicate).is_err());
+    }
+}
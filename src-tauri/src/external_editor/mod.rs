@@ -1,10 +1,15 @@
 //! External Editor Support
 //!
 //! Allows opening pages in external editors (VS Code, Vim, etc.)
-//! with file watching for automatic reimport on save.
+//! with file watching for automatic reimport on save. Whole notebooks can
+//! be opened as a folder of Markdown files (see [`notebook`]).
 
-use std::collections::HashMap;
+mod editors;
+mod notebook;
+
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
@@ -12,11 +17,26 @@ use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::markdown::export_page_to_markdown;
-use crate::storage::Page;
+use crate::markdown::{export_page_to_markdown, import_markdown_to_page};
+use crate::storage::{FileStorage, FileStorageWriter, Page, StorageError, StorageLock};
+
+pub use editors::{
+    available_editors, detect_editors, find_executable, get_known_editors, load_settings,
+    save_settings, EditorSettings,
+};
+pub use notebook::{
+    ConflictResolution, FileSyncOutcome, NotebookEditConflict, NotebookEditSession,
+    NotebookSyncReport, SessionFile,
+};
+
+/// How long a watched folder must be quiet before its changes are reported
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
 
 /// Known external editors with their launch commands
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `args` may contain `{file}` and `{line}` placeholders; without `{file}`
+/// the path is passed after the arguments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EditorConfig {
     pub name: String,
@@ -36,86 +56,61 @@ impl Default for EditorConfig {
     }
 }
 
-/// Predefined editor configurations
-pub fn get_known_editors() -> Vec<EditorConfig> {
-    vec![
-        EditorConfig {
-            name: "System Default".to_string(),
-            command: String::new(),
-            args: vec![],
-            wait: false,
-        },
-        EditorConfig {
-            name: "VS Code".to_string(),
-            command: "code".to_string(),
-            args: vec!["--wait".to_string()],
+impl EditorConfig {
+    /// The user's terminal editor from `$VISUAL` or `$EDITOR`, falling back
+    /// to `vi` (`notepad` on Windows). Always waits for the editor to exit.
+    pub fn from_env() -> Self {
+        let command_line = ["VISUAL", "EDITOR"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+        Self::from_command_line(&command_line)
+    }
+
+    /// Parse an editor command line such as `code --wait` or `nvim -u NONE`
+    pub fn from_command_line(command_line: &str) -> Self {
+        let mut parts = command_line.split_whitespace().map(|s| s.to_string());
+        let command = parts.next().unwrap_or_default();
+        Self {
+            name: command.clone(),
+            command,
+            args: parts.collect(),
             wait: true,
-        },
-        EditorConfig {
-            name: "VS Code (no wait)".to_string(),
+        }
+    }
+
+    /// Arguments for opening `path`, with placeholders filled in. Without a
+    /// line, `{file}:{line}` becomes just the path and other `{line}`s
+    /// become 1.
+    pub fn args_for(&self, path: &Path, line: Option<u32>) -> Vec<String> {
+        let file = path.to_string_lossy();
+        let mut args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| {
+                let arg = match line {
+                    Some(line) => arg.replace("{line}", &line.to_string()),
+                    None => arg.replace(":{line}", "").replace("{line}", "1"),
+                };
+                arg.replace("{file}", &file)
+            })
+            .collect();
+        if !self.args.iter().any(|arg| arg.contains("{file}")) {
+            args.push(file.to_string());
+        }
+        args
+    }
+
+    /// VS Code, not waiting for the window to close
+    pub fn vs_code() -> Self {
+        Self {
+            name: "VS Code".to_string(),
             command: "code".to_string(),
             args: vec![],
             wait: false,
-        },
-        EditorConfig {
-            name: "Vim (Terminal)".to_string(),
-            command: if cfg!(target_os = "macos") {
-                "open".to_string()
-            } else {
-                "x-terminal-emulator".to_string()
-            },
-            args: if cfg!(target_os = "macos") {
-                vec!["-a".to_string(), "Terminal".to_string(), "--args".to_string(), "vim".to_string()]
-            } else {
-                vec!["-e".to_string(), "vim".to_string()]
-            },
-            wait: false,
-        },
-        EditorConfig {
-            name: "Neovim (Terminal)".to_string(),
-            command: if cfg!(target_os = "macos") {
-                "open".to_string()
-            } else {
-                "x-terminal-emulator".to_string()
-            },
-            args: if cfg!(target_os = "macos") {
-                vec!["-a".to_string(), "Terminal".to_string(), "--args".to_string(), "nvim".to_string()]
-            } else {
-                vec!["-e".to_string(), "nvim".to_string()]
-            },
-            wait: false,
-        },
-        EditorConfig {
-            name: "Sublime Text".to_string(),
-            command: "subl".to_string(),
-            args: vec!["--wait".to_string()],
-            wait: true,
-        },
-        EditorConfig {
-            name: "Atom".to_string(),
-            command: "atom".to_string(),
-            args: vec!["--wait".to_string()],
-            wait: true,
-        },
-        EditorConfig {
-            name: "Emacs".to_string(),
-            command: "emacs".to_string(),
-            args: vec![],
-            wait: false,
-        },
-        EditorConfig {
-            name: "TextMate".to_string(),
-            command: "mate".to_string(),
-            args: vec!["--wait".to_string()],
-            wait: true,
-        },
-        EditorConfig {
-            name: "Zed".to_string(),
-            command: "zed".to_string(),
-            args: vec!["--wait".to_string()],
-            wait: true,
-        },
-    ]
+        }
+    }
 }
 
 /// Information about an active external edit session
@@ -140,6 +135,14 @@ pub enum ExternalEditorError {
     SessionNotFound(String),
     #[error("Watch error: {0}")]
     WatchError(String),
+    #[error("No notebook edit session for notebook: {0}")]
+    NotebookSessionNotFound(String),
+    #[error("Not a file of the edit session: {0}")]
+    FileNotTracked(String),
+    #[error("Invalid editor: {0}")]
+    InvalidEditor(String),
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
 }
 
 pub type Result<T> = std::result::Result<T, ExternalEditorError>;
@@ -147,6 +150,9 @@ pub type Result<T> = std::result::Result<T, ExternalEditorError>;
 /// Manages external editor sessions
 pub struct ExternalEditorManager {
     sessions: Arc<Mutex<HashMap<Uuid, EditSession>>>,
+    /// Notebook sessions by notebook; shared with their watchers
+    notebook_sessions: Arc<Mutex<HashMap<Uuid, NotebookEditSession>>>,
+    notebook_watchers: Mutex<HashMap<Uuid, RecommendedWatcher>>,
     temp_dir: PathBuf,
     #[allow(dead_code)]
     watcher: Option<RecommendedWatcher>,
@@ -160,6 +166,8 @@ impl ExternalEditorManager {
 
         Ok(Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            notebook_sessions: Arc::new(Mutex::new(HashMap::new())),
+            notebook_watchers: Mutex::new(HashMap::new()),
             temp_dir,
             watcher: None,
         })
@@ -225,12 +233,12 @@ impl ExternalEditorManager {
                     .map_err(|e| ExternalEditorError::Io(e))?;
             }
         } else {
-            // Use specified editor
-            let mut cmd = Command::new(&config.command);
-            for arg in &config.args {
-                cmd.arg(arg);
-            }
-            cmd.arg(path);
+            // Use specified editor. Resolving the path first lets Windows
+            // run `.cmd` launchers such as VS Code's `code`.
+            let program = find_executable(&config.command)
+                .unwrap_or_else(|| PathBuf::from(&config.command));
+            let mut cmd = Command::new(program);
+            cmd.args(config.args_for(path, None));
 
             if config.wait {
                 cmd.status()
@@ -322,6 +330,111 @@ impl ExternalEditorManager {
 
         Ok(())
     }
+
+    /// Export a notebook to a folder for editing, or return its running
+    /// session (re-exporting would throw away edits not synced yet)
+    pub fn start_notebook_session(
+        &self,
+        storage: &FileStorage,
+        notebook_id: Uuid,
+    ) -> Result<NotebookEditSession> {
+        let mut sessions = self.notebook_sessions.lock().unwrap();
+        if let Some(session) = sessions.get(&notebook_id) {
+            return Ok(session.clone());
+        }
+        let dir = self.temp_dir.join(format!("notebook-{}", notebook_id));
+        let session = NotebookEditSession::export(storage, notebook_id, dir)?;
+        sessions.insert(notebook_id, session.clone());
+        Ok(session)
+    }
+
+    /// Sync a notebook session's files as they change on disk. `on_sync` is
+    /// called with each sync that updated pages or found conflicts.
+    pub fn watch_notebook_session<F>(
+        &self,
+        notebook_id: Uuid,
+        storage: Arc<StorageLock>,
+        on_sync: F,
+    ) -> Result<()>
+    where
+        F: Fn(NotebookSyncReport) + Send + 'static,
+    {
+        let dir = self
+            .get_notebook_session(notebook_id)
+            .ok_or_else(|| ExternalEditorError::NotebookSessionNotFound(notebook_id.to_string()))?
+            .dir;
+        let sessions = Arc::clone(&self.notebook_sessions);
+        let watcher = watch_folder(&dir, move |paths| {
+            // Storage before sessions, the order commands take them in
+            let Ok(storage) = storage.write() else {
+                return;
+            };
+            let mut sessions = sessions.lock().unwrap();
+            let Some(session) = sessions.get_mut(&notebook_id) else {
+                return;
+            };
+            let report = session.sync_paths(&storage, &paths);
+            for error in &report.errors {
+                log::warn!("External edit of notebook {}: {}", notebook_id, error);
+            }
+            if !report.is_empty() {
+                on_sync(report);
+            }
+        })?;
+        self.notebook_watchers
+            .lock()
+            .unwrap()
+            .insert(notebook_id, watcher);
+        Ok(())
+    }
+
+    /// Sync every file of a notebook session now
+    pub fn sync_notebook_session(
+        &self,
+        storage: &FileStorageWriter,
+        notebook_id: Uuid,
+    ) -> Result<NotebookSyncReport> {
+        let mut sessions = self.notebook_sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(&notebook_id)
+            .ok_or_else(|| ExternalEditorError::NotebookSessionNotFound(notebook_id.to_string()))?;
+        Ok(session.sync_all(storage))
+    }
+
+    /// Settle a conflict in a notebook session
+    pub fn resolve_notebook_conflict(
+        &self,
+        storage: &FileStorageWriter,
+        notebook_id: Uuid,
+        path: &str,
+        resolution: ConflictResolution,
+    ) -> Result<()> {
+        let mut sessions = self.notebook_sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(&notebook_id)
+            .ok_or_else(|| ExternalEditorError::NotebookSessionNotFound(notebook_id.to_string()))?;
+        session.resolve_conflict(storage, path, resolution)
+    }
+
+    /// Get the running session for a notebook
+    pub fn get_notebook_session(&self, notebook_id: Uuid) -> Option<NotebookEditSession> {
+        self.notebook_sessions
+            .lock()
+            .unwrap()
+            .get(&notebook_id)
+            .cloned()
+    }
+
+    /// Stop watching a notebook session and delete its folder. Edits not
+    /// synced by then are lost, so sync first.
+    pub fn end_notebook_session(&self, notebook_id: Uuid) -> Result<()> {
+        self.notebook_watchers.lock().unwrap().remove(&notebook_id);
+        if let Some(session) = self.notebook_sessions.lock().unwrap().remove(&notebook_id) {
+            let _ = std::fs::remove_dir_all(&session.dir);
+            log::info!("Ended external edit session for notebook {}", notebook_id);
+        }
+        Ok(())
+    }
 }
 
 impl Default for ExternalEditorManager {
@@ -330,6 +443,19 @@ impl Default for ExternalEditorManager {
     }
 }
 
+/// Apply Markdown edited outside Nous to a page, keeping its metadata
+pub fn apply_edited_markdown(page: &Page, markdown: &str) -> Page {
+    let imported = import_markdown_to_page(markdown, page.notebook_id, &page.title);
+    Page {
+        title: imported.title,
+        content: imported.content,
+        tags: imported.tags,
+        properties: imported.properties,
+        updated_at: chrono::Utc::now(),
+        ..page.clone()
+    }
+}
+
 /// Sanitize a filename to remove invalid characters
 fn sanitize_filename(name: &str) -> String {
     name.chars()
@@ -379,3 +505,93 @@ where
 
     Ok(watcher)
 }
+
+/// Watch a folder and everything under it. `callback` gets the changed
+/// paths once the folder has been quiet for [`WATCH_DEBOUNCE`], since editors
+/// often save in several steps. Dropping the watcher stops the thread.
+pub fn watch_folder<F>(dir: &Path, callback: F) -> Result<RecommendedWatcher>
+where
+    F: Fn(Vec<PathBuf>) + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let mut watcher = RecommendedWatcher::new(
+        move |res: std::result::Result<notify::Event, notify::Error>| {
+            if let Ok(event) = res {
+                if event.kind.is_modify() || event.kind.is_create() {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+            }
+        },
+        Config::default(),
+    )
+    .map_err(|e| ExternalEditorError::WatchError(e.to_string()))?;
+
+    watcher
+        .watch(dir, RecursiveMode::Recursive)
+        .map_err(|e| ExternalEditorError::WatchError(e.to_string()))?;
+
+    std::thread::spawn(move || {
+        let mut pending = HashSet::new();
+        loop {
+            match rx.recv_timeout(WATCH_DEBOUNCE) {
+                Ok(path) => {
+                    pending.insert(path);
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if !pending.is_empty() {
+                        callback(pending.drain().collect());
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editor_command_line_is_split_into_args() {
+        let config = EditorConfig::from_command_line("code --wait --new-window");
+        assert_eq!(config.command, "code");
+        assert_eq!(config.args, vec!["--wait", "--new-window"]);
+        assert!(config.wait);
+
+        let config = EditorConfig::from_command_line("  nvim ");
+        assert_eq!(config.command, "nvim");
+        assert!(config.args.is_empty());
+    }
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let config = EditorConfig::from_command_line("subl --wait {file}:{line}");
+        let path = Path::new("/tmp/Notes.md");
+        assert_eq!(config.args_for(path, Some(12)), vec!["--wait", "/tmp/Notes.md:12"]);
+        assert_eq!(config.args_for(path, None), vec!["--wait", "/tmp/Notes.md"]);
+
+        let config = EditorConfig::from_command_line("vim +{line}");
+        assert_eq!(config.args_for(path, None), vec!["+1", "/tmp/Notes.md"]);
+    }
+
+    #[test]
+    fn edited_markdown_keeps_page_metadata() {
+        let mut page = Page::new(Uuid::new_v4(), "Notes".to_string());
+        page.is_favorite = true;
+        let folder_id = Uuid::new_v4();
+        page.folder_id = Some(folder_id);
+
+        let updated = apply_edited_markdown(&page, "# Renamed\n\nNew body text\n");
+        assert_eq!(updated.id, page.id);
+        assert_eq!(updated.folder_id, Some(folder_id));
+        assert!(updated.is_favorite);
+        assert_eq!(updated.title, "Renamed");
+        assert!(!updated.content.blocks.is_empty());
+    }
+}
@@ -0,0 +1,442 @@
+//! Notebook-wide external edit sessions
+//!
+//! A notebook session writes every standard page of a notebook into a
+//! folder of Markdown files, laid out like the Markdown mirror (folders
+//! become directories), so the whole notebook can be opened in an editor
+//! such as VS Code. Edited files are brought back into their pages.
+//!
+//! Each file remembers the hash of what was exported and of the page it was
+//! exported from. A file edited after its page also changed in Nous is a
+//! conflict: neither side is overwritten until the user picks one with
+//! [`NotebookEditSession::resolve_conflict`]. Files added in the folder are
+//! ignored, and deleting a file never deletes its page.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{apply_edited_markdown, ExternalEditorError, Result};
+use crate::markdown::export_page_to_markdown;
+use crate::markdown::mirror::{folder_chain, resolve, sanitize_name};
+use crate::storage::{FileStorage, FileStorageWriter, Folder, Page, PageType};
+
+fn hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// An exported notebook being edited outside Nous
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookEditSession {
+    pub notebook_id: Uuid,
+    /// Folder the notebook was exported to
+    pub dir: PathBuf,
+    pub started_at: SystemTime,
+    /// Exported files by path relative to `dir`, `/`-separated
+    pub files: BTreeMap<String, SessionFile>,
+    /// Files edited on both sides, waiting to be resolved
+    pub conflicts: Vec<NotebookEditConflict>,
+}
+
+/// Last-synced state of one exported file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFile {
+    pub page_id: Uuid,
+    /// SHA-256 of the file as last written or read back
+    pub file_hash: String,
+    /// SHA-256 of the page rendered as Markdown at that point
+    pub page_hash: String,
+}
+
+/// A file edited outside Nous whose page was also edited in Nous
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookEditConflict {
+    pub path: String,
+    pub page_id: Uuid,
+    pub title: String,
+}
+
+/// Which side of a conflict to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    /// Apply the edited file to the page
+    KeepFile,
+    /// Overwrite the file with the page
+    KeepPage,
+}
+
+/// What syncing one file did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileSyncOutcome {
+    Unchanged,
+    Updated(Uuid),
+    Conflict(NotebookEditConflict),
+}
+
+/// What syncing a session's files did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookSyncReport {
+    pub notebook_id: Uuid,
+    pub pages_updated: Vec<Uuid>,
+    /// Conflicts found by this sync
+    pub conflicts: Vec<NotebookEditConflict>,
+    /// Files that could not be synced
+    pub errors: Vec<String>,
+}
+
+impl NotebookSyncReport {
+    /// Whether the sync changed pages or found conflicts
+    pub fn is_empty(&self) -> bool {
+        self.pages_updated.is_empty() && self.conflicts.is_empty() && self.errors.is_empty()
+    }
+}
+
+impl NotebookEditSession {
+    /// Export a notebook's standard pages into `dir`, replacing anything
+    /// already there
+    pub fn export(storage: &FileStorage, notebook_id: Uuid, dir: PathBuf) -> Result<Self> {
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+
+        let folders = storage
+            .list_folders(notebook_id)?
+            .into_iter()
+            .map(|f| (f.id, f))
+            .collect();
+        let mut pages: Vec<Page> = storage
+            .list_pages(notebook_id)?
+            .into_iter()
+            .filter(|p| p.page_type == PageType::Standard && p.deleted_at.is_none())
+            .collect();
+        // Oldest first, so the oldest of two same-titled pages keeps the plain name
+        pages.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        let mut claimed = HashSet::new();
+        let mut files = BTreeMap::new();
+        for page in &pages {
+            let path = allocate_path(page, &folders, &mut claimed);
+            let markdown = export_page_to_markdown(page);
+            write_file(&dir, &path, &markdown)?;
+            let page_hash = hash(&markdown);
+            files.insert(
+                path,
+                SessionFile {
+                    page_id: page.id,
+                    file_hash: page_hash.clone(),
+                    page_hash,
+                },
+            );
+        }
+
+        log::info!(
+            "Exported {} pages of notebook {} to {:?} for external editing",
+            files.len(),
+            notebook_id,
+            dir
+        );
+        Ok(Self {
+            notebook_id,
+            dir,
+            started_at: SystemTime::now(),
+            files,
+            conflicts: Vec::new(),
+        })
+    }
+
+    /// The session path of a file under the session folder, if it is one
+    /// of the exported files
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.dir).ok()?;
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?
+            .join("/");
+        self.files.contains_key(&relative).then_some(relative)
+    }
+
+    /// Bring one edited file back into its page
+    pub fn sync_file(
+        &mut self,
+        storage: &FileStorageWriter,
+        path: &str,
+    ) -> Result<FileSyncOutcome> {
+        let entry = self
+            .files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| ExternalEditorError::FileNotTracked(path.to_string()))?;
+        let content = match fs::read_to_string(self.dir.join(path)) {
+            Ok(content) => content,
+            // Deleted or mid-save; a deleted file leaves its page alone
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(FileSyncOutcome::Unchanged)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let file_hash = hash(&content);
+        if file_hash == entry.file_hash {
+            return Ok(FileSyncOutcome::Unchanged);
+        }
+
+        let page = storage.get_page(self.notebook_id, entry.page_id)?;
+        if hash(&export_page_to_markdown(&page)) != entry.page_hash {
+            let conflict = NotebookEditConflict {
+                path: path.to_string(),
+                page_id: page.id,
+                title: page.title,
+            };
+            if !self.conflicts.iter().any(|c| c.path == path) {
+                self.conflicts.push(conflict.clone());
+            }
+            return Ok(FileSyncOutcome::Conflict(conflict));
+        }
+
+        let page = self.apply(storage, &page, &content)?;
+        self.files.insert(
+            path.to_string(),
+            SessionFile {
+                page_id: page.id,
+                file_hash,
+                page_hash: hash(&export_page_to_markdown(&page)),
+            },
+        );
+        Ok(FileSyncOutcome::Updated(page.id))
+    }
+
+    /// Sync the exported files among `paths`, as reported by a file watcher
+    pub fn sync_paths(
+        &mut self,
+        storage: &FileStorageWriter,
+        paths: &[PathBuf],
+    ) -> NotebookSyncReport {
+        let relative: Vec<String> = paths
+            .iter()
+            .filter_map(|p| self.relative_path(p))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        self.sync_files(storage, relative)
+    }
+
+    /// Sync every exported file
+    pub fn sync_all(&mut self, storage: &FileStorageWriter) -> NotebookSyncReport {
+        let paths = self.files.keys().cloned().collect();
+        self.sync_files(storage, paths)
+    }
+
+    fn sync_files(
+        &mut self,
+        storage: &FileStorageWriter,
+        paths: Vec<String>,
+    ) -> NotebookSyncReport {
+        let mut report = NotebookSyncReport {
+            notebook_id: self.notebook_id,
+            ..Default::default()
+        };
+        for path in paths {
+            match self.sync_file(storage, &path) {
+                Ok(FileSyncOutcome::Unchanged) => {}
+                Ok(FileSyncOutcome::Updated(page_id)) => report.pages_updated.push(page_id),
+                Ok(FileSyncOutcome::Conflict(conflict)) => report.conflicts.push(conflict),
+                Err(e) => report.errors.push(format!("{}: {}", path, e)),
+            }
+        }
+        report
+    }
+
+    /// Settle a conflict by keeping the edited file or the page
+    pub fn resolve_conflict(
+        &mut self,
+        storage: &FileStorageWriter,
+        path: &str,
+        resolution: ConflictResolution,
+    ) -> Result<()> {
+        let entry = self
+            .files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| ExternalEditorError::FileNotTracked(path.to_string()))?;
+        let page = storage.get_page(self.notebook_id, entry.page_id)?;
+
+        let (file_hash, page) = match resolution {
+            ConflictResolution::KeepFile => {
+                let content = fs::read_to_string(self.dir.join(path))?;
+                (hash(&content), self.apply(storage, &page, &content)?)
+            }
+            ConflictResolution::KeepPage => {
+                let markdown = export_page_to_markdown(&page);
+                write_file(&self.dir, path, &markdown)?;
+                (hash(&markdown), page)
+            }
+        };
+        self.files.insert(
+            path.to_string(),
+            SessionFile {
+                page_id: page.id,
+                file_hash,
+                page_hash: hash(&export_page_to_markdown(&page)),
+            },
+        );
+        self.conflicts.retain(|c| c.path != path);
+        Ok(())
+    }
+
+    /// Save edited Markdown to a page, returning the page as stored
+    fn apply(&self, storage: &FileStorageWriter, page: &Page, markdown: &str) -> Result<Page> {
+        storage.update_page(&apply_edited_markdown(page, markdown))?;
+        // Saving can normalize the content (BlockNote pages are converted),
+        // so hash what was stored rather than what was passed in
+        Ok(storage.get_page(self.notebook_id, page.id)?)
+    }
+}
+
+/// An unused path for a page, from its folder and title
+fn allocate_path(
+    page: &Page,
+    folders: &HashMap<Uuid, Folder>,
+    claimed: &mut HashSet<String>,
+) -> String {
+    let mut dir: Vec<String> = folder_chain(page, folders)
+        .iter()
+        .map(|name| sanitize_name(name))
+        .collect();
+    let name = sanitize_name(&page.title);
+    dir.push(String::new());
+    let prefix = dir.join("/");
+
+    let mut candidate = format!("{}{}.md", prefix, name);
+    let mut n = 2;
+    while !claimed.insert(candidate.to_lowercase()) {
+        candidate = format!("{}{} ({}).md", prefix, name, n);
+        n += 1;
+    }
+    candidate
+}
+
+fn write_file(root: &Path, relative: &str, content: &str) -> Result<()> {
+    let path = resolve(root, relative)
+        .ok_or_else(|| ExternalEditorError::FileNotTracked(relative.to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::NotebookType;
+
+    struct Fixture {
+        _library: tempfile::TempDir,
+        export: tempfile::TempDir,
+        storage: FileStorageWriter,
+        notebook_id: Uuid,
+    }
+
+    fn fixture() -> Fixture {
+        let library = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(library.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        Fixture {
+            _library: library,
+            export: tempfile::tempdir().unwrap(),
+            storage,
+            notebook_id: notebook.id,
+        }
+    }
+
+    fn edit(session: &NotebookEditSession, path: &str, from: &str, to: &str) {
+        let file = session.dir.join(path);
+        let content = fs::read_to_string(&file).unwrap();
+        fs::write(&file, content.replace(from, to)).unwrap();
+    }
+
+    #[test]
+    fn exports_folders_and_syncs_edits_back() {
+        let f = fixture();
+        let folder = f
+            .storage
+            .create_folder(f.notebook_id, "Projects".to_string(), None)
+            .unwrap();
+        let mut page = f
+            .storage
+            .create_page(f.notebook_id, "Launch".to_string())
+            .unwrap();
+        page.folder_id = Some(folder.id);
+        f.storage.update_page(&page).unwrap();
+
+        let dir = f.export.path().join("session");
+        let mut session = NotebookEditSession::export(&f.storage, f.notebook_id, dir).unwrap();
+        assert!(session.files.contains_key("Projects/Launch.md"));
+        assert_eq!(
+            session.relative_path(&session.dir.join("Projects").join("Launch.md")),
+            Some("Projects/Launch.md".to_string())
+        );
+
+        edit(
+            &session,
+            "Projects/Launch.md",
+            "title: \"Launch\"",
+            "title: \"Liftoff\"",
+        );
+        let report = session.sync_all(&f.storage);
+        assert_eq!(report.pages_updated, vec![page.id]);
+        assert_eq!(
+            f.storage.get_page(f.notebook_id, page.id).unwrap().title,
+            "Liftoff"
+        );
+        // Nothing left to bring back
+        assert!(session.sync_all(&f.storage).is_empty());
+    }
+
+    #[test]
+    fn edits_on_both_sides_wait_for_a_resolution() {
+        let f = fixture();
+        let page = f
+            .storage
+            .create_page(f.notebook_id, "Notes".to_string())
+            .unwrap();
+        let dir = f.export.path().join("session");
+        let mut session = NotebookEditSession::export(&f.storage, f.notebook_id, dir).unwrap();
+
+        let mut edited = f.storage.get_page(f.notebook_id, page.id).unwrap();
+        edited.tags = vec!["nous".to_string()];
+        f.storage.update_page(&edited).unwrap();
+        edit(&session, "Notes.md", "title: \"Notes\"", "title: \"Disk\"");
+
+        let outcome = session.sync_file(&f.storage, "Notes.md").unwrap();
+        assert!(matches!(outcome, FileSyncOutcome::Conflict(_)));
+        assert_eq!(session.conflicts.len(), 1);
+        let stored = f.storage.get_page(f.notebook_id, page.id).unwrap();
+        assert_eq!(stored.title, "Notes");
+
+        session
+            .resolve_conflict(&f.storage, "Notes.md", ConflictResolution::KeepPage)
+            .unwrap();
+        assert!(session.conflicts.is_empty());
+        let file = fs::read_to_string(session.dir.join("Notes.md")).unwrap();
+        assert!(file.contains("title: \"Notes\""));
+        assert!(file.contains("- \"nous\""));
+        assert_eq!(
+            session.sync_file(&f.storage, "Notes.md").unwrap(),
+            FileSyncOutcome::Unchanged
+        );
+    }
+}
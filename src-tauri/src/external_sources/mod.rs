@@ -10,6 +10,7 @@
 //! - **Format detection**: Automatically detect JSON, Markdown, and plain text
 //! - **Incremental processing**: Skip already-processed files when enabled
 //! - **AI summarization**: Generate summaries via the ProcessExternalSource action step
+//! - **Watch folders**: Auto-import files dropped into a watched directory as pages
 //!
 //! # Example
 //!
@@ -29,7 +30,9 @@
 
 pub mod models;
 pub mod storage;
+pub mod watch;
 
 // Re-export commonly used types
 pub use models::*;
 pub use storage::{read_file_content, ExternalSourcesError, ExternalSourcesStorage};
+pub use watch::{WatchFolders, WatchImportEvent};
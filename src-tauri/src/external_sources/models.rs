@@ -65,6 +65,10 @@ pub struct ExternalSource {
     pub last_processed: Option<DateTime<Utc>>,
     #[serde(default)]
     pub processed_files: Vec<ProcessedFileInfo>,
+    /// Watch-folder mode: when set, files dropped into the source's
+    /// directory are imported as pages automatically
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch: Option<WatchConfig>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -80,6 +84,7 @@ impl ExternalSource {
             enabled: true,
             last_processed: None,
             processed_files: Vec::new(),
+            watch: None,
             created_at: now,
             updated_at: now,
         }
@@ -91,6 +96,27 @@ impl ExternalSource {
     }
 }
 
+/// Watch-folder settings for an external source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchConfig {
+    /// Notebook that imported pages are created in
+    pub notebook_id: Uuid,
+    /// Folder within the notebook (None = notebook root)
+    #[serde(default)]
+    pub folder_id: Option<Uuid>,
+    /// Tags added to every imported page
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Subfolder processed files are moved to
+    #[serde(default = "default_done_folder")]
+    pub done_folder: String,
+}
+
+fn default_done_folder() -> String {
+    "done".to_string()
+}
+
 /// Request to create a new external source
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
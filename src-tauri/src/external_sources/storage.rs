@@ -7,7 +7,7 @@ use uuid::Uuid;
 
 use super::models::{
     CreateExternalSourceRequest, ExternalFileFormat, ExternalSource, ProcessedFileInfo,
-    ResolvedFileInfo, UpdateExternalSourceRequest,
+    ResolvedFileInfo, UpdateExternalSourceRequest, WatchConfig,
 };
 
 /// Error type for external sources operations
@@ -133,6 +133,62 @@ impl ExternalSourcesStorage {
         Ok(updated)
     }
 
+    /// Add a source from a configuration export, replacing any source with
+    /// the same ID
+    pub fn import_source(
+        &mut self,
+        source: ExternalSource,
+    ) -> Result<ExternalSource, ExternalSourcesError> {
+        match self.data.sources.iter_mut().find(|s| s.id == source.id) {
+            Some(existing) => *existing = source.clone(),
+            None => self.data.sources.push(source.clone()),
+        }
+        self.save()?;
+        Ok(source)
+    }
+
+    /// Enable (Some) or disable (None) watch-folder mode for a source
+    pub fn set_watch(
+        &mut self,
+        id: Uuid,
+        watch: Option<WatchConfig>,
+    ) -> Result<ExternalSource, ExternalSourcesError> {
+        let source = self
+            .data
+            .sources
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or(ExternalSourcesError::NotFound(id))?;
+
+        if let Some(config) = &watch {
+            if config.done_folder.is_empty()
+                || config.done_folder.contains(['/', '\\'])
+                || config.done_folder == ".."
+            {
+                return Err(ExternalSourcesError::InvalidPattern(format!(
+                    "Done folder must be a plain folder name: {}",
+                    config.done_folder
+                )));
+            }
+        }
+        source.watch = watch;
+        source.updated_at = Utc::now();
+
+        let updated = source.clone();
+        self.save()?;
+        Ok(updated)
+    }
+
+    /// Enabled sources with watch-folder mode on
+    pub fn watched_sources(&self) -> Vec<ExternalSource> {
+        self.data
+            .sources
+            .iter()
+            .filter(|s| s.enabled && s.watch.is_some())
+            .cloned()
+            .collect()
+    }
+
     /// Delete an external source
     pub fn delete_source(&mut self, id: Uuid) -> Result<(), ExternalSourcesError> {
         let idx = self
@@ -300,6 +356,26 @@ fn expand_home_dir(path: &str) -> String {
     path.to_string()
 }
 
+/// Directory watched for a watch-folder source: the pattern itself when it
+/// names a directory, otherwise the directory part before any glob syntax
+/// (`~/inbox/*.md` watches `~/inbox`)
+pub fn watch_directory(path_pattern: &str) -> PathBuf {
+    let expanded = expand_home_dir(path_pattern);
+    let literal_end = expanded
+        .find(['*', '?', '[', '{'])
+        .unwrap_or(expanded.len());
+    let literal = Path::new(&expanded[..literal_end]);
+    if literal_end == expanded.len() {
+        return literal.to_path_buf();
+    }
+    // Drop the partial component the glob starts in ("~/inbox/note*.md")
+    if expanded[..literal_end].ends_with(['/', '\\']) {
+        literal.to_path_buf()
+    } else {
+        literal.parent().map(Path::to_path_buf).unwrap_or_default()
+    }
+}
+
 /// Read file content based on format
 pub fn read_file_content(path: &Path, format: &ExternalFileFormat) -> Result<String, std::io::Error> {
     let raw = fs::read_to_string(path)?;
@@ -348,4 +424,12 @@ mod tests {
         );
         assert_eq!(ExternalFileFormat::from_extension("unknown"), None);
     }
+
+    #[test]
+    fn test_watch_directory() {
+        assert_eq!(watch_directory("/data/inbox"), PathBuf::from("/data/inbox"));
+        assert_eq!(watch_directory("/data/inbox/*.md"), PathBuf::from("/data/inbox/"));
+        assert_eq!(watch_directory("/data/inbox/note*.md"), PathBuf::from("/data/inbox"));
+        assert!(!watch_directory("~/inbox").starts_with("~"));
+    }
 }
@@ -0,0 +1,454 @@
+//! Watch-folder auto-import.
+//!
+//! Sources with a [`WatchConfig`] name a directory. A background thread
+//! watches those directories (via `notify`) and imports every new file as a
+//! page in the configured notebook, then moves the file into the `done/`
+//! subfolder (or `failed/` when it can't be imported) so each file is
+//! handled exactly once.
+//!
+//! Markdown and plain text are imported directly; HTML, docx, PDF and the
+//! other formats markitdown understands go through the Python converter
+//! first. Files are only picked up once their size has stopped changing, so
+//! a large file still being copied in isn't imported half-written. A full
+//! directory rescan every [`RESCAN_INTERVAL`] catches anything dropped while
+//! the app was closed or missed by the OS watcher, and picks up changes to
+//! the source configuration.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::models::{ExternalSource, WatchConfig};
+use super::storage::{watch_directory, ExternalSourcesStorage};
+use crate::markdown::import_markdown_to_page;
+use crate::python_bridge::PythonAI;
+use crate::storage::StorageLock;
+
+/// How often watched directories are rescanned and the config reloaded
+const RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a file's size must stay unchanged before it's imported
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+const FAILED_FOLDER: &str = "failed";
+
+/// Emitted (as `watch-folder-import`) for every file the watcher handles
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchImportEvent {
+    pub source_id: Uuid,
+    pub file: String,
+    pub notebook_id: Uuid,
+    pub page_id: Option<Uuid>,
+    pub page_title: Option<String>,
+    /// Where the file was moved to
+    pub moved_to: Option<String>,
+    pub error: Option<String>,
+}
+
+/// How a file is turned into page content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    /// Markdown / plain text, imported as-is
+    Text,
+    /// Converted to Markdown by markitdown first
+    Document,
+}
+
+fn file_kind(path: &Path) -> Option<FileKind> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "md" | "markdown" | "txt" | "text" => Some(FileKind::Text),
+        "html" | "htm" | "docx" | "doc" | "pdf" | "pptx" | "xlsx" | "xls" | "epub" | "rtf"
+        | "odt" | "csv" => Some(FileKind::Document),
+        _ => None,
+    }
+}
+
+/// Hidden files, editor swap files and partial downloads
+fn is_ignored(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return true;
+    };
+    name.starts_with('.')
+        || name.starts_with("~$")
+        || name.ends_with('~')
+        || [".tmp", ".part", ".crdownload", ".download", ".swp"]
+            .iter()
+            .any(|suffix| name.to_lowercase().ends_with(suffix))
+}
+
+/// `dir/name`, or `dir/name (2).ext` etc. if that's taken
+fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !p.exists())
+        .expect("unbounded range")
+}
+
+fn move_into(file: &Path, subfolder: &str) -> std::io::Result<PathBuf> {
+    let dir = file
+        .parent()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "no parent dir"))?
+        .join(subfolder);
+    fs::create_dir_all(&dir)?;
+    let name = file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = unique_destination(&dir, &name);
+    fs::rename(file, &target)?;
+    Ok(target)
+}
+
+/// Turn a file into Markdown plus a fallback title
+fn file_to_markdown(
+    path: &Path,
+    kind: FileKind,
+    python_ai: &Mutex<PythonAI>,
+) -> Result<(String, Option<String>), String> {
+    match kind {
+        FileKind::Text => fs::read_to_string(path)
+            .map(|content| (content, None))
+            .map_err(|e| e.to_string()),
+        FileKind::Document => {
+            let python_ai = python_ai
+                .lock()
+                .map_err(|e| format!("Failed to acquire Python AI lock: {}", e))?;
+            let result = python_ai
+                .convert_document(path.to_string_lossy().to_string())
+                .map_err(|e| format!("Document conversion error: {}", e))?;
+            match result.error {
+                Some(error) => Err(error),
+                None => Ok((result.content, result.title)),
+            }
+        }
+    }
+}
+
+/// Import one file into the source's notebook and move it out of the way
+fn import_file(
+    source_id: Uuid,
+    config: &WatchConfig,
+    path: &Path,
+    kind: FileKind,
+    storage: &StorageLock,
+    python_ai: &Mutex<PythonAI>,
+) -> WatchImportEvent {
+    let mut event = WatchImportEvent {
+        source_id,
+        file: path.to_string_lossy().to_string(),
+        notebook_id: config.notebook_id,
+        page_id: None,
+        page_title: None,
+        moved_to: None,
+        error: None,
+    };
+
+    let result = file_to_markdown(path, kind, python_ai).and_then(|(markdown, title)| {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Imported file".to_string());
+        let mut page = import_markdown_to_page(
+            &markdown,
+            config.notebook_id,
+            title.as_deref().unwrap_or(&stem),
+        );
+        page.folder_id = config.folder_id;
+        for tag in &config.tags {
+            if !page.tags.contains(tag) {
+                page.tags.push(tag.clone());
+            }
+        }
+        let storage = storage.write().map_err(|e| e.to_string())?;
+        storage.create_page_from(page).map_err(|e| e.to_string())
+    });
+
+    let subfolder = match result {
+        Ok(page) => {
+            event.page_id = Some(page.id);
+            event.page_title = Some(page.title);
+            config.done_folder.as_str()
+        }
+        Err(e) => {
+            log::warn!("Watch folder: failed to import {:?}: {}", path, e);
+            event.error = Some(e);
+            FAILED_FOLDER
+        }
+    };
+
+    match move_into(path, subfolder) {
+        Ok(target) => event.moved_to = Some(target.to_string_lossy().to_string()),
+        Err(e) => {
+            log::warn!(
+                "Watch folder: failed to move {:?} to {}/: {}",
+                path,
+                subfolder,
+                e
+            );
+        }
+    }
+    event
+}
+
+/// A file seen in a watched directory, waiting for its size to settle
+struct Pending {
+    source_id: Uuid,
+    size: u64,
+    stable_since: Instant,
+}
+
+/// Handle to the watch-folder thread; dropping it stops the thread
+pub struct WatchFolders {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for WatchFolders {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Start watching every enabled watch-folder source. `on_import` is called
+/// for each file imported (or that failed to import).
+pub fn start<F>(
+    sources: Arc<Mutex<ExternalSourcesStorage>>,
+    storage: Arc<StorageLock>,
+    python_ai: Arc<Mutex<PythonAI>>,
+    on_import: F,
+) -> WatchFolders
+where
+    F: Fn(WatchImportEvent) + Send + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+
+    let thread = std::thread::Builder::new()
+        .name("watch-folders".to_string())
+        .spawn(move || {
+            let (tx, rx) = mpsc::channel::<PathBuf>();
+            let mut watcher = match RecommendedWatcher::new(
+                move |res: Result<notify::Event, notify::Error>| {
+                    if let Ok(event) = res {
+                        if event.kind.is_create() || event.kind.is_modify() {
+                            for path in event.paths {
+                                let _ = tx.send(path);
+                            }
+                        }
+                    }
+                },
+                Config::default(),
+            ) {
+                Ok(w) => Some(w),
+                Err(e) => {
+                    // Still works via periodic rescans, just with more latency
+                    log::warn!("Watch folder: OS file watcher unavailable: {}", e);
+                    None
+                }
+            };
+
+            // watched directory → source
+            let mut watched: HashMap<PathBuf, ExternalSource> = HashMap::new();
+            let mut pending: HashMap<PathBuf, Pending> = HashMap::new();
+            let mut last_rescan: Option<Instant> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(path) => {
+                        if let Some(source) = path.parent().and_then(|dir| watched.get(dir)) {
+                            let source_id = source.id;
+                            track(&mut pending, path, source_id);
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                if !matches!(last_rescan, Some(t) if t.elapsed() < RESCAN_INTERVAL) {
+                    last_rescan = Some(Instant::now());
+                    let current: HashMap<PathBuf, ExternalSource> = match sources.lock() {
+                        Ok(s) => s
+                            .watched_sources()
+                            .into_iter()
+                            .map(|s| (watch_directory(&s.path_pattern), s))
+                            .filter(|(dir, _)| dir.is_dir())
+                            .collect(),
+                        Err(_) => continue,
+                    };
+                    if let Some(watcher) = watcher.as_mut() {
+                        for dir in watched.keys().filter(|d| !current.contains_key(*d)) {
+                            let _ = watcher.unwatch(dir);
+                        }
+                        for dir in current.keys().filter(|d| !watched.contains_key(*d)) {
+                            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                                log::warn!("Watch folder: cannot watch {:?}: {}", dir, e);
+                            }
+                        }
+                    }
+                    watched = current;
+                    pending.retain(|path, _| {
+                        path.parent().is_some_and(|dir| watched.contains_key(dir))
+                    });
+
+                    for (dir, source) in &watched {
+                        let Ok(entries) = fs::read_dir(dir) else {
+                            continue;
+                        };
+                        for entry in entries.flatten() {
+                            track(&mut pending, entry.path(), source.id);
+                        }
+                    }
+                }
+
+                // Import files whose size has settled
+                let ready: Vec<(PathBuf, Uuid)> = pending
+                    .iter()
+                    .filter(|(_, p)| p.stable_since.elapsed() >= SETTLE_TIME)
+                    .map(|(path, p)| (path.clone(), p.source_id))
+                    .collect();
+                for (path, source_id) in ready {
+                    let size = fs::metadata(&path).map(|m| m.len()).ok();
+                    let entry = pending.get_mut(&path).expect("collected from pending");
+                    match size {
+                        None => {
+                            // Gone (moved away or deleted) before we got to it
+                            pending.remove(&path);
+                            continue;
+                        }
+                        Some(size) if size != entry.size => {
+                            entry.size = size;
+                            entry.stable_since = Instant::now();
+                            continue;
+                        }
+                        Some(_) => {}
+                    }
+                    pending.remove(&path);
+
+                    let Some(config) = watched
+                        .values()
+                        .find(|s| s.id == source_id)
+                        .and_then(|s| s.watch.clone())
+                    else {
+                        continue;
+                    };
+                    let Some(kind) = file_kind(&path) else {
+                        continue;
+                    };
+                    let modified_at = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .map(DateTime::<Utc>::from)
+                        .unwrap_or_else(|_| Utc::now());
+                    // Imported before but couldn't be moved out of the way
+                    let already_imported = sources.lock().is_ok_and(|s| {
+                        !s.needs_processing(source_id, &path.to_string_lossy(), modified_at)
+                            .unwrap_or(true)
+                    });
+                    if already_imported {
+                        continue;
+                    }
+
+                    let event = import_file(source_id, &config, &path, kind, &storage, &python_ai);
+                    if let Ok(mut s) = sources.lock() {
+                        if let Err(e) =
+                            s.mark_processed(source_id, &event.file, modified_at, event.page_id)
+                        {
+                            log::warn!("Watch folder: failed to record {:?}: {}", path, e);
+                        }
+                    }
+                    on_import(event);
+                }
+            }
+
+            drop(watcher);
+        })
+        .expect("failed to spawn watch-folder thread");
+
+    WatchFolders {
+        stop,
+        thread: Some(thread),
+    }
+}
+
+/// Start (or refresh) tracking a candidate file
+fn track(pending: &mut HashMap<PathBuf, Pending>, path: PathBuf, source_id: Uuid) {
+    if pending.contains_key(&path) || !path.is_file() || is_ignored(&path) {
+        return;
+    }
+    if file_kind(&path).is_none() {
+        return;
+    }
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    pending.insert(
+        path,
+        Pending {
+            source_id,
+            size,
+            stable_since: Instant::now(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_up_only_importable_files() {
+        assert_eq!(file_kind(Path::new("/in/notes.MD")), Some(FileKind::Text));
+        assert_eq!(
+            file_kind(Path::new("/in/report.pdf")),
+            Some(FileKind::Document)
+        );
+        assert_eq!(file_kind(Path::new("/in/photo.jpg")), None);
+        assert!(is_ignored(Path::new("/in/.DS_Store")));
+        assert!(is_ignored(Path::new("/in/~$report.docx")));
+        assert!(is_ignored(Path::new("/in/big.pdf.crdownload")));
+        assert!(!is_ignored(Path::new("/in/report.pdf")));
+    }
+
+    #[test]
+    fn processed_files_never_overwrite_each_other() {
+        let dir = std::env::temp_dir().join(format!("nous_watch_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        for _ in 0..3 {
+            let file = dir.join("note.md");
+            fs::write(&file, "# Hi").unwrap();
+            move_into(&file, "done").unwrap();
+        }
+        let done = dir.join("done");
+        assert!(done.join("note.md").exists());
+        assert!(done.join("note (2).md").exists());
+        assert!(done.join("note (3).md").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,480 @@
+//! Find and replace across pages.
+//!
+//! Replacement works on the text of text blocks (paragraphs, headings,
+//! quotes, callouts, lists, checklists and tables), one text run at a time:
+//! tags are never touched, nor the labels of wiki-links and block refs, and
+//! a match can't span formatting (`foo <b>bar</b>` has no "foo bar"). Code
+//! blocks and inline code are left alone unless asked for. Entities are
+//! decoded before matching, so "a & b" matches `a &amp; b`.
+//!
+//! [`plan_replacements`] computes the changed pages and a per-page preview
+//! without writing anything; the caller writes the pages (after taking an
+//! undo snapshot) or just shows the preview.
+
+use std::collections::HashSet;
+
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::storage::html_utils::html_to_searchable_text;
+use crate::storage::{EditorBlock, FileStorage, Page, PageType, StorageError};
+
+#[derive(Debug, Error)]
+pub enum ReplaceError {
+    #[error("Search text is empty")]
+    EmptyQuery,
+
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+
+    #[error("Pattern matches empty text")]
+    MatchesEmpty,
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// Which pages to replace in
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReplaceScope {
+    /// Every notebook of the library
+    #[default]
+    Library,
+    #[serde(rename_all = "camelCase")]
+    Notebook { notebook_id: Uuid },
+    #[serde(rename_all = "camelCase")]
+    Section { notebook_id: Uuid, section_id: Uuid },
+    #[serde(rename_all = "camelCase")]
+    Page { notebook_id: Uuid, page_id: Uuid },
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplaceOptions {
+    /// Treat the query as a regular expression; the replacement can then use
+    /// `$1`, `${name}` for groups
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Also replace in code blocks and inline code
+    pub include_code: bool,
+}
+
+/// One changed text, as plain text before and after
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextChange {
+    pub block_id: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// What a replacement changes in one page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageReplacement {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    pub matches: usize,
+    pub changes: Vec<TextChange>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreview {
+    pub pages: Vec<PageReplacement>,
+    pub total_matches: usize,
+    /// Encrypted pages in scope, which can't be searched
+    pub encrypted_pages_skipped: usize,
+}
+
+/// The changed pages, ready to write, and their preview
+#[derive(Debug, Default)]
+pub struct ReplacePlan {
+    pub pages: Vec<Page>,
+    pub preview: ReplacePreview,
+}
+
+/// A compiled find-and-replace
+pub struct Replacer {
+    pattern: Regex,
+    replacement: String,
+    expand_groups: bool,
+    include_code: bool,
+}
+
+impl Replacer {
+    pub fn new(
+        query: &str,
+        replacement: &str,
+        options: ReplaceOptions,
+    ) -> Result<Self, ReplaceError> {
+        if query.is_empty() {
+            return Err(ReplaceError::EmptyQuery);
+        }
+        let source = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| ReplaceError::InvalidPattern(e.to_string()))?;
+        if pattern.is_match("") {
+            return Err(ReplaceError::MatchesEmpty);
+        }
+        Ok(Self {
+            pattern,
+            replacement: replacement.to_string(),
+            expand_groups: options.regex,
+            include_code: options.include_code,
+        })
+    }
+
+    /// Replace in a page's text blocks. Returns what changed, if anything.
+    pub fn replace_in_page(&self, page: &mut Page) -> Option<PageReplacement> {
+        let mut matches = 0;
+        let mut changes = Vec::new();
+        for block in &mut page.content.blocks {
+            matches += self.replace_in_block(block, &mut changes);
+        }
+        (matches > 0).then(|| PageReplacement {
+            notebook_id: page.notebook_id,
+            page_id: page.id,
+            title: page.title.clone(),
+            matches,
+            changes,
+        })
+    }
+
+    fn replace_in_block(&self, block: &mut EditorBlock, changes: &mut Vec<TextChange>) -> usize {
+        let block_id = block.id.clone();
+        let data = &mut block.data;
+        let mut matches = 0;
+        let mut html = |value: Option<&mut serde_json::Value>, changes: &mut Vec<TextChange>| {
+            let Some(serde_json::Value::String(text)) = value else {
+                return;
+            };
+            if let Some((replaced, n)) = self.replace_in_html(text) {
+                changes.push(TextChange {
+                    block_id: block_id.clone(),
+                    before: html_to_searchable_text(text),
+                    after: html_to_searchable_text(&replaced),
+                });
+                *text = replaced;
+                matches += n;
+            }
+        };
+        match block.block_type.as_str() {
+            "paragraph" | "header" | "quote" | "callout" => {
+                for key in ["text", "title", "caption"] {
+                    html(data.get_mut(key), changes);
+                }
+            }
+            "list" | "checklist" => {
+                let mut items = vec![data.get_mut("items")];
+                while let Some(next) = items.pop() {
+                    let Some(serde_json::Value::Array(list)) = next else {
+                        continue;
+                    };
+                    for item in list {
+                        if item.is_string() {
+                            html(Some(item), changes);
+                            continue;
+                        }
+                        let Some(item) = item.as_object_mut() else {
+                            continue;
+                        };
+                        for (key, value) in item.iter_mut() {
+                            match key.as_str() {
+                                "text" | "content" => html(Some(value), changes),
+                                "items" => items.push(Some(value)),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            "table" => {
+                if let Some(serde_json::Value::Array(rows)) = data.get_mut("content") {
+                    for row in rows.iter_mut().filter_map(|r| r.as_array_mut()) {
+                        for cell in row {
+                            html(Some(cell), changes);
+                        }
+                    }
+                }
+            }
+            "code" if self.include_code => {
+                if let Some(serde_json::Value::String(code)) = data.get_mut("code") {
+                    let (replaced, n) = self.replace_text(code);
+                    if n > 0 {
+                        changes.push(TextChange {
+                            block_id: block_id.clone(),
+                            before: code.clone(),
+                            after: replaced.clone(),
+                        });
+                        *code = replaced;
+                        matches += n;
+                    }
+                }
+            }
+            _ => {}
+        }
+        matches
+    }
+
+    /// Replace in the text runs of inline HTML
+    fn replace_in_html(&self, html: &str) -> Option<(String, usize)> {
+        let mut out = String::with_capacity(html.len());
+        let mut count = 0;
+        // Open elements whose text is left alone
+        let mut protected: Vec<String> = Vec::new();
+        let mut rest = html;
+        loop {
+            let next = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..next];
+            let (replaced, n) = if protected.is_empty() && !text.is_empty() {
+                self.replace_text(&decode_entities(text))
+            } else {
+                (String::new(), 0)
+            };
+            if n > 0 {
+                out.push_str(&escape_text(&replaced));
+                count += n;
+            } else {
+                out.push_str(text);
+            }
+            rest = &rest[next..];
+            if rest.is_empty() {
+                break;
+            }
+            let Some(end) = rest.find('>') else {
+                out.push_str(rest);
+                break;
+            };
+            let tag = &rest[..=end];
+            let name = tag
+                .trim_start_matches(['<', '/'])
+                .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            if self.protects(&name) && !tag.ends_with("/>") {
+                if tag.starts_with("</") {
+                    if let Some(open) = protected.iter().rposition(|n| *n == name) {
+                        protected.truncate(open);
+                    }
+                } else {
+                    protected.push(name);
+                }
+            }
+            out.push_str(tag);
+            rest = &rest[end + 1..];
+        }
+        (count > 0).then_some((out, count))
+    }
+
+    fn protects(&self, element: &str) -> bool {
+        match element {
+            "wiki-link" | "block-ref" => true,
+            "code" => !self.include_code,
+            _ => false,
+        }
+    }
+
+    fn replace_text(&self, text: &str) -> (String, usize) {
+        let count = self.pattern.find_iter(text).count();
+        if count == 0 {
+            return (String::new(), 0);
+        }
+        let replaced = if self.expand_groups {
+            self.pattern.replace_all(text, self.replacement.as_str())
+        } else {
+            self.pattern.replace_all(text, NoExpand(&self.replacement))
+        };
+        (replaced.into_owned(), count)
+    }
+}
+
+/// Work out a replacement over the pages in `scope` without writing
+/// anything. `only_pages` narrows it to pages picked from a preview.
+pub fn plan_replacements(
+    storage: &FileStorage,
+    scope: &ReplaceScope,
+    replacer: &Replacer,
+    only_pages: Option<&HashSet<Uuid>>,
+) -> Result<ReplacePlan, ReplaceError> {
+    let mut plan = ReplacePlan::default();
+    for mut page in pages_in_scope(storage, scope)? {
+        if page.deleted_at.is_some()
+            || page.page_type != PageType::Standard
+            || page.plugin_page_type.is_some()
+            || only_pages.is_some_and(|ids| !ids.contains(&page.id))
+        {
+            continue;
+        }
+        if page.is_encrypted() {
+            plan.preview.encrypted_pages_skipped += 1;
+            continue;
+        }
+        if let Some(replacement) = replacer.replace_in_page(&mut page) {
+            plan.preview.total_matches += replacement.matches;
+            plan.preview.pages.push(replacement);
+            plan.pages.push(page);
+        }
+    }
+    Ok(plan)
+}
+
+fn pages_in_scope(storage: &FileStorage, scope: &ReplaceScope) -> Result<Vec<Page>, StorageError> {
+    match scope {
+        ReplaceScope::Library => {
+            let mut pages = Vec::new();
+            for notebook in storage.list_notebooks()? {
+                pages.extend(storage.list_pages(notebook.id)?);
+            }
+            Ok(pages)
+        }
+        ReplaceScope::Notebook { notebook_id } => storage.list_pages(*notebook_id),
+        ReplaceScope::Section {
+            notebook_id,
+            section_id,
+        } => Ok(storage
+            .list_pages(*notebook_id)?
+            .into_iter()
+            .filter(|page| page.section_id == Some(*section_id))
+            .collect()),
+        ReplaceScope::Page {
+            notebook_id,
+            page_id,
+        } => Ok(vec![storage.get_page(*notebook_id, *page_id)?]),
+    }
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&nbsp;", "\u{a0}")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Text as it's stored in block HTML; non-breaking spaces stay entities
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\u{a0}', "&nbsp;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn block(block_type: &str, data: serde_json::Value) -> EditorBlock {
+        EditorBlock {
+            id: format!("{}-1", block_type),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn replacer(query: &str, replacement: &str, options: ReplaceOptions) -> Replacer {
+        Replacer::new(query, replacement, options).unwrap()
+    }
+
+    #[test]
+    fn replaces_text_but_not_tags_links_or_code() {
+        let mut page = Page::new(Uuid::new_v4(), "Notes".to_string());
+        page.content.blocks = vec![
+            block(
+                "paragraph",
+                json!({"text": "Link to <wiki-link data-page-title=\"Link\">Link</wiki-link>, \
+                                <a href=\"https://link.example\">a link</a> and <code>link()</code> &amp; LINK"}),
+            ),
+            block(
+                "list",
+                json!({"items": [{"content": "link", "items": [{"content": "<b>link</b>", "items": []}]}]}),
+            ),
+            block(
+                "checklist",
+                json!({"items": [{"text": "check link", "checked": false}]}),
+            ),
+            block("table", json!({"content": [["link", "other"]]})),
+            block("code", json!({"code": "let link = 1;"})),
+        ];
+
+        let changes = replacer("link", "ref", ReplaceOptions::default())
+            .replace_in_page(&mut page)
+            .unwrap();
+        // Text, anchor text, "LINK", two list items, the checklist and a cell
+        assert_eq!(changes.matches, 7);
+        assert_eq!(
+            page.content.blocks[0].data["text"],
+            "ref to <wiki-link data-page-title=\"Link\">Link</wiki-link>, \
+             <a href=\"https://link.example\">a ref</a> and <code>link()</code> &amp; ref"
+        );
+        assert_eq!(
+            page.content.blocks[1].data["items"][0]["items"][0]["content"],
+            "<b>ref</b>"
+        );
+        assert_eq!(page.content.blocks[4].data["code"], "let link = 1;");
+        assert_eq!(
+            changes.changes[0].after,
+            "ref to Link, a ref and link() & ref"
+        );
+
+        let with_code = ReplaceOptions {
+            include_code: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let changes = replacer("link", "ref", with_code)
+            .replace_in_page(&mut page)
+            .unwrap();
+        assert_eq!(changes.matches, 2);
+        assert_eq!(page.content.blocks[4].data["code"], "let ref = 1;");
+    }
+
+    #[test]
+    fn regex_replacements_expand_groups() {
+        let mut page = Page::new(Uuid::new_v4(), "Dates".to_string());
+        page.content.blocks = vec![block(
+            "paragraph",
+            json!({"text": "Due 2024-03-01, a&nbsp;$1 fee &lt;tbd&gt;"}),
+        )];
+        let options = ReplaceOptions {
+            regex: true,
+            ..Default::default()
+        };
+        replacer(r"(\d{4})-(\d{2})-(\d{2})", "$3.$2.$1", options).replace_in_page(&mut page);
+        replacer("<tbd>", "<none>", ReplaceOptions::default()).replace_in_page(&mut page);
+        // Without regex, "$1" in the replacement is literal
+        replacer("fee", "$1", ReplaceOptions::default()).replace_in_page(&mut page);
+        assert_eq!(
+            page.content.blocks[0].data["text"],
+            "Due 01.03.2024, a&nbsp;$1 $1 &lt;none&gt;"
+        );
+
+        assert!(matches!(
+            Replacer::new("a*", "b", options),
+            Err(ReplaceError::MatchesEmpty)
+        ));
+        assert!(matches!(
+            Replacer::new("(", "b", options),
+            Err(ReplaceError::InvalidPattern(_))
+        ));
+        assert!(replacer("absent", "x", options)
+            .replace_in_page(&mut page)
+            .is_none());
+    }
+}
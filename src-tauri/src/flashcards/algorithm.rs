@@ -13,7 +13,7 @@
 
 use chrono::{DateTime, Duration, Utc};
 
-use super::models::{CardState, CardStatus};
+use super::models::{CardState, CardStatus, CardWithState, ReadingItem, ReviewQueueItem};
 
 /// Minimum ease factor allowed
 const MIN_EASE_FACTOR: f32 = 1.3;
@@ -118,6 +118,61 @@ pub fn ui_rating_to_quality(rating: i32) -> i32 {
     }
 }
 
+/// How much a reading priority (1 highest to 5 lowest) stretches intervals
+fn priority_factor(priority: u8) -> f32 {
+    match priority {
+        0 | 1 => 0.5,
+        2 => 0.75,
+        3 => 1.0,
+        4 => 1.5,
+        _ => 2.0,
+    }
+}
+
+/// Calculate the next re-reading of an incremental reading item
+///
+/// The item is scheduled like a card rated with the UI rating (1-4), then
+/// the due date is moved by its priority: high-priority items come back
+/// sooner. The returned interval is the unscaled one, so changing the
+/// priority later doesn't compound.
+pub fn calculate_next_reading(state: &CardState, rating: i32, priority: u8) -> ReviewResult {
+    let mut result = calculate_next_review(state, ui_rating_to_quality(rating));
+    let days = (result.interval as f32 * priority_factor(priority))
+        .round()
+        .max(1.0) as i64;
+    result.due_date = Utc::now() + Duration::days(days);
+    result
+}
+
+/// Mix due reading items into due cards: one reading item after every
+/// `cards_per_reading` cards, highest priority first, and whatever is left
+/// of either at the end
+pub fn mix_review_queue(
+    cards: Vec<CardWithState>,
+    mut reading: Vec<ReadingItem>,
+    cards_per_reading: usize,
+) -> Vec<ReviewQueueItem> {
+    reading.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then(a.state.due_date.cmp(&b.state.due_date))
+    });
+    let cards_per_reading = cards_per_reading.max(1);
+    let mut queue = Vec::with_capacity(cards.len() + reading.len());
+    let mut reading = reading.into_iter();
+
+    for (i, card) in cards.into_iter().enumerate() {
+        queue.push(ReviewQueueItem::Card(card));
+        if (i + 1) % cards_per_reading == 0 {
+            if let Some(item) = reading.next() {
+                queue.push(ReviewQueueItem::Reading(item));
+            }
+        }
+    }
+    queue.extend(reading.map(ReviewQueueItem::Reading));
+    queue
+}
+
 /// Format an interval in days to a human-readable string
 #[allow(dead_code)]
 pub fn format_interval(days: i32) -> String {
@@ -225,6 +280,56 @@ mod tests {
         assert!(result2.ease_factor >= MIN_EASE_FACTOR);
     }
 
+    #[test]
+    fn test_reading_priority_moves_due_date() {
+        let mut state = new_card_state();
+        state.review_count = 1;
+        state.interval = 1;
+
+        let urgent = calculate_next_reading(&state, 3, 1);
+        let normal = calculate_next_reading(&state, 3, 3);
+        let later = calculate_next_reading(&state, 3, 5);
+
+        // The stored interval stays the card interval
+        assert_eq!(urgent.interval, 6);
+        assert_eq!(later.interval, 6);
+        let days = |r: &ReviewResult| {
+            ((r.due_date - Utc::now()).num_minutes() as f64 / 1440.0).round() as i64
+        };
+        assert_eq!(days(&urgent), 3);
+        assert_eq!(days(&normal), 6);
+        assert_eq!(days(&later), 12);
+    }
+
+    #[test]
+    fn test_mix_review_queue() {
+        use crate::flashcards::models::{Flashcard, ReadingSource};
+
+        let cards: Vec<CardWithState> = (0..5)
+            .map(|_| {
+                let card = Flashcard::new(Uuid::new_v4(), "Q".into(), "A".into());
+                let state = CardState::new(card.id);
+                CardWithState { card, state }
+            })
+            .collect();
+        let page = ReadingSource::Page {
+            page_id: Uuid::new_v4(),
+        };
+        let low = ReadingItem::new("Low".into(), page.clone(), 5);
+        let high = ReadingItem::new("High".into(), page.clone(), 1);
+        let mid = ReadingItem::new("Mid".into(), page, 3);
+
+        let queue = mix_review_queue(cards, vec![low, high, mid], 2);
+        let kinds: Vec<String> = queue
+            .iter()
+            .map(|item| match item {
+                ReviewQueueItem::Card(_) => "c".to_string(),
+                ReviewQueueItem::Reading(r) => r.title.clone(),
+            })
+            .collect();
+        assert_eq!(kinds, vec!["c", "c", "High", "c", "c", "Mid", "c", "Low"]);
+    }
+
     #[test]
     fn test_format_interval() {
         assert_eq!(format_interval(0), "now");
@@ -0,0 +1,353 @@
+//! Deck analytics computed from the review log
+//!
+//! Everything here is derived from cards, their current states and the
+//! review records in `reviews.jsonl`; nothing is stored. Days are local
+//! calendar days, like the "today" of a review session.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::models::{CardState, CardStatus, CardWithState, Flashcard, ReviewRecord};
+
+/// Lapses after which a card counts as a leech
+pub const DEFAULT_LEECH_THRESHOLD: i32 = 8;
+
+/// Days of workload projected ahead
+const WORKLOAD_DAYS: i64 = 30;
+
+/// Reviews done on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyReviews {
+    pub date: NaiveDate,
+    pub reviews: usize,
+    pub correct: usize,
+}
+
+/// Share of correct answers for cards of an age
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionBucket {
+    pub label: String,
+    /// Card age at review, in days: `min_age_days <= age < max_age_days`
+    pub min_age_days: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<i64>,
+    pub reviews: usize,
+    pub correct: usize,
+    /// `correct / reviews`, `None` without reviews
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f32>,
+}
+
+/// Cards whose ease factor is in `[min, min + 0.2)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EaseBucket {
+    pub min: f32,
+    pub cards: usize,
+}
+
+/// Reviews falling due on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyWorkload {
+    pub date: NaiveDate,
+    pub due: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckAnalytics {
+    /// One entry per day of the requested range, oldest first
+    pub heatmap: Vec<DailyReviews>,
+    pub retention_by_age: Vec<RetentionBucket>,
+    /// Cards past their first review, by ease factor
+    pub ease_distribution: Vec<EaseBucket>,
+    /// The next 30 days, today first; overdue cards count for today
+    pub workload: Vec<DailyWorkload>,
+    pub leech_count: usize,
+}
+
+/// A card with many lapses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeechCard {
+    #[serde(flatten)]
+    pub card: CardWithState,
+    pub lapses: i32,
+    /// When it was last forgotten
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_lapse: Option<DateTime<Utc>>,
+}
+
+fn local_date(at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(&Local).date_naive()
+}
+
+/// Whether a review counted as remembered (SM-2 quality 3 or more)
+fn is_correct(record: &ReviewRecord) -> bool {
+    record.quality >= 3
+}
+
+/// Reviews per day for the `days` days up to `today`
+pub fn review_heatmap(records: &[ReviewRecord], today: NaiveDate, days: i64) -> Vec<DailyReviews> {
+    let first = today - Duration::days(days.max(1) - 1);
+    let mut by_day: BTreeMap<NaiveDate, (usize, usize)> = BTreeMap::new();
+    for record in records {
+        let date = local_date(record.reviewed_at);
+        if date >= first && date <= today {
+            let entry = by_day.entry(date).or_default();
+            entry.0 += 1;
+            if is_correct(record) {
+                entry.1 += 1;
+            }
+        }
+    }
+    first
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| {
+            let (reviews, correct) = by_day.get(&date).copied().unwrap_or_default();
+            DailyReviews {
+                date,
+                reviews,
+                correct,
+            }
+        })
+        .collect()
+}
+
+/// Retention by how old the card was when it was reviewed
+pub fn retention_by_age(
+    records: &[ReviewRecord],
+    cards: &HashMap<Uuid, &Flashcard>,
+) -> Vec<RetentionBucket> {
+    let bounds: [(&str, i64, Option<i64>); 6] = [
+        ("Under a week", 0, Some(7)),
+        ("1–4 weeks", 7, Some(28)),
+        ("1–3 months", 28, Some(90)),
+        ("3–6 months", 90, Some(180)),
+        ("6–12 months", 180, Some(365)),
+        ("Over a year", 365, None),
+    ];
+    let mut buckets: Vec<RetentionBucket> = bounds
+        .iter()
+        .map(|(label, min, max)| RetentionBucket {
+            label: label.to_string(),
+            min_age_days: *min,
+            max_age_days: *max,
+            reviews: 0,
+            correct: 0,
+            rate: None,
+        })
+        .collect();
+
+    for record in records {
+        let Some(card) = cards.get(&record.card_id) else {
+            continue;
+        };
+        let age = (record.reviewed_at - card.created_at).num_days().max(0);
+        if let Some(bucket) = buckets
+            .iter_mut()
+            .find(|b| age >= b.min_age_days && !matches!(b.max_age_days, Some(max) if age >= max))
+        {
+            bucket.reviews += 1;
+            if is_correct(record) {
+                bucket.correct += 1;
+            }
+        }
+    }
+    for bucket in &mut buckets {
+        if bucket.reviews > 0 {
+            bucket.rate = Some(bucket.correct as f32 / bucket.reviews as f32);
+        }
+    }
+    buckets
+}
+
+/// Ease factors of reviewed cards in steps of 0.2 from the SM-2 minimum;
+/// the last bucket takes everything from 2.9 up
+pub fn ease_distribution(states: &[&CardState]) -> Vec<EaseBucket> {
+    let mut buckets: Vec<EaseBucket> = (0..9)
+        .map(|i| EaseBucket {
+            min: ((1.3 + i as f32 * 0.2) * 10.0).round() / 10.0,
+            cards: 0,
+        })
+        .collect();
+    for state in states.iter().filter(|s| s.status != CardStatus::New) {
+        // The epsilon keeps e.g. 2.5 out of the bucket below it
+        let step = ((state.ease_factor - 1.3) / 0.2 + 1e-3).floor().max(0.0) as usize;
+        let index = step.min(buckets.len() - 1);
+        buckets[index].cards += 1;
+    }
+    buckets
+}
+
+/// Cards falling due on each of the next 30 days
+pub fn projected_workload(states: &[&CardState], today: NaiveDate) -> Vec<DailyWorkload> {
+    let mut workload: Vec<DailyWorkload> = (0..WORKLOAD_DAYS)
+        .map(|i| DailyWorkload {
+            date: today + Duration::days(i),
+            due: 0,
+        })
+        .collect();
+    for state in states {
+        let offset = (local_date(state.due_date) - today).num_days().max(0);
+        if let Some(day) = workload.get_mut(offset as usize) {
+            day.due += 1;
+        }
+    }
+    workload
+}
+
+/// When each card was last forgotten
+pub fn last_lapses(records: &[ReviewRecord]) -> HashMap<Uuid, DateTime<Utc>> {
+    let mut last = HashMap::new();
+    for record in records.iter().filter(|r| !is_correct(r)) {
+        last.entry(record.card_id)
+            .and_modify(|at: &mut DateTime<Utc>| *at = (*at).max(record.reviewed_at))
+            .or_insert(record.reviewed_at);
+    }
+    last
+}
+
+/// Cards with at least `threshold` lapses, most lapses first
+pub fn find_leeches(
+    cards: Vec<CardWithState>,
+    records: &[ReviewRecord],
+    threshold: i32,
+) -> Vec<LeechCard> {
+    let last = last_lapses(records);
+    let mut leeches: Vec<LeechCard> = cards
+        .into_iter()
+        .filter(|c| c.state.lapses >= threshold.max(1))
+        .map(|card| LeechCard {
+            lapses: card.state.lapses,
+            last_lapse: last.get(&card.card.id).copied(),
+            card,
+        })
+        .collect();
+    leeches.sort_by(|a, b| {
+        b.lapses
+            .cmp(&a.lapses)
+            .then(b.last_lapse.cmp(&a.last_lapse))
+    });
+    leeches
+}
+
+/// Consecutive days with reviews, ending today (or yesterday, when today
+/// has none yet)
+pub fn streak_days(records: &[ReviewRecord], today: NaiveDate) -> i32 {
+    let days: HashSet<NaiveDate> = records.iter().map(|r| local_date(r.reviewed_at)).collect();
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(card_id: Uuid, quality: i32, days_ago: i64) -> ReviewRecord {
+        let mut record = ReviewRecord::new(card_id, quality, 1, 2.5);
+        record.reviewed_at = Utc::now() - Duration::days(days_ago);
+        record
+    }
+
+    #[test]
+    fn test_heatmap_streak_and_retention() {
+        let today = local_date(Utc::now());
+        let mut card = Flashcard::new(Uuid::new_v4(), "Q".into(), "A".into());
+        card.created_at = Utc::now() - Duration::days(40);
+        let records = vec![
+            record(card.id, 4, 0),
+            record(card.id, 1, 0),
+            record(card.id, 5, 1),
+            record(card.id, 4, 3),
+            record(card.id, 4, 38),
+        ];
+
+        let heatmap = review_heatmap(&records, today, 7);
+        assert_eq!(heatmap.len(), 7);
+        assert_eq!(heatmap[6].date, today);
+        assert_eq!((heatmap[6].reviews, heatmap[6].correct), (2, 1));
+        assert_eq!(heatmap[5].reviews, 1);
+        assert_eq!(heatmap[4].reviews, 0);
+
+        assert_eq!(streak_days(&records, today), 2);
+
+        let cards: HashMap<Uuid, &Flashcard> = [(card.id, &card)].into();
+        let retention = retention_by_age(&records, &cards);
+        assert_eq!(retention[0].reviews, 1);
+        assert_eq!(retention[2].reviews, 4);
+        assert_eq!(retention[2].rate, Some(0.75));
+        assert_eq!(retention[5].rate, None);
+    }
+
+    #[test]
+    fn test_ease_workload_and_leeches() {
+        let today = local_date(Utc::now());
+        let mut states: Vec<CardState> = (0..4).map(|_| CardState::new(Uuid::new_v4())).collect();
+        states[0].status = CardStatus::Review;
+        states[0].ease_factor = 1.3;
+        states[1].status = CardStatus::Review;
+        states[1].ease_factor = 2.5;
+        states[1].due_date = Utc::now() + Duration::days(3);
+        states[2].status = CardStatus::Review;
+        states[2].ease_factor = 3.4;
+        states[2].due_date = Utc::now() + Duration::days(45);
+        states[3].due_date = Utc::now() - Duration::days(2);
+
+        let refs: Vec<&CardState> = states.iter().collect();
+        let ease = ease_distribution(&refs);
+        assert_eq!(ease[0].cards, 1);
+        assert_eq!(ease[6].min, 2.5);
+        assert_eq!(ease[6].cards, 1);
+        assert_eq!(ease[8].cards, 1);
+
+        let workload = projected_workload(&refs, today);
+        assert_eq!(workload.len(), 30);
+        // The overdue card and the one due now both count for today
+        assert_eq!(workload[0].due, 2);
+        assert_eq!(workload[3].due, 1);
+        assert_eq!(workload.iter().map(|d| d.due).sum::<usize>(), 3);
+
+        let card = Flashcard::new(Uuid::new_v4(), "Q".into(), "A".into());
+        let mut state = CardState::new(card.id);
+        state.lapses = 9;
+        let records = vec![
+            record(card.id, 1, 5),
+            record(card.id, 1, 2),
+            record(card.id, 4, 1),
+        ];
+        let leeches = find_leeches(
+            vec![
+                CardWithState {
+                    card: card.clone(),
+                    state,
+                },
+                CardWithState {
+                    card: Flashcard::new(Uuid::new_v4(), "Q".into(), "A".into()),
+                    state: CardState::new(Uuid::new_v4()),
+                },
+            ],
+            &records,
+            DEFAULT_LEECH_THRESHOLD,
+        );
+        assert_eq!(leeches.len(), 1);
+        assert_eq!(leeches[0].lapses, 9);
+        assert_eq!(leeches[0].last_lapse, Some(records[1].reviewed_at));
+    }
+}
@@ -0,0 +1,694 @@
+//! Portable deck bundles (`.nousdeck`)
+//!
+//! A bundle is a zip archive with a `deck.json` manifest — the deck, its
+//! cards and, optionally, their scheduling — and the media the cards show
+//! under `media/`. Inside a bundle, card text refers to media as
+//! `media://{path}`; importing copies the files into the notebook's assets
+//! and turns those back into `asset://{notebook-id}/{path}` references.
+//!
+//! Importing a deck that is already there (same id, or failing that the
+//! same name) is resolved by an [`ImportCollision`].
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::models::{CardSource, CardState, Deck, Flashcard};
+use super::storage::{FlashcardStorage, FlashcardStorageError, Result};
+use crate::markdown::mirror::resolve;
+use crate::share::encrypted::{asset_relative_path, sanitize_relative};
+use crate::storage::atomic;
+
+/// File extension of deck bundles
+pub const BUNDLE_EXTENSION: &str = "nousdeck";
+
+/// Manifest format written by this version
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "deck.json";
+const MEDIA_DIR: &str = "media/";
+const MEDIA_SCHEME: &str = "media://";
+
+/// The `deck.json` manifest of a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub deck: Deck,
+    pub cards: Vec<Flashcard>,
+    /// Scheduling state of the cards; `None` when exported without it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub states: Option<Vec<CardState>>,
+    /// Media paths, relative to `media/`
+    #[serde(default)]
+    pub media: Vec<String>,
+}
+
+/// How to import a deck that is already in the notebook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportCollision {
+    /// Add the bundle's new cards to the existing deck and update the ones
+    /// it has newer versions of; local scheduling is kept
+    #[default]
+    Merge,
+    /// Import next to the existing deck, as a new deck with new ids
+    Copy,
+    /// Delete the existing deck and import the bundle in its place
+    Replace,
+    /// Leave the existing deck alone
+    Skip,
+}
+
+/// A deck in the notebook that a bundle collides with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistingDeck {
+    pub id: Uuid,
+    pub name: String,
+    pub card_count: usize,
+    /// Same deck (an earlier import or export of it); otherwise only the
+    /// names match
+    pub same_deck: bool,
+}
+
+/// What a bundle holds, without importing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckBundleInfo {
+    pub path: String,
+    pub file_name: String,
+    pub deck_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    pub card_count: usize,
+    pub has_scheduling: bool,
+    pub media_count: usize,
+    pub exported_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub existing_deck: Option<ExistingDeck>,
+}
+
+/// What an import did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeckImportResult {
+    /// The imported (or merged into) deck; `None` when skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deck: Option<Deck>,
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub media_copied: usize,
+    /// Media files renamed because a different file had the same name
+    pub media_renamed: usize,
+}
+
+fn invalid(message: impl Into<String>) -> FlashcardStorageError {
+    FlashcardStorageError::InvalidBundle(message.into())
+}
+
+/// Local media references in card text, in any of the forms page content
+/// stores (see [`asset_relative_path`])
+fn media_ref_regex() -> Regex {
+    Regex::new(r#"(?:asset://|https?://asset\.localhost/|/)[^\s"'()<>\]]+"#).unwrap()
+}
+
+fn bundled_media_regex() -> Regex {
+    Regex::new(r#"media://([^\s"'()<>\]]+)"#).unwrap()
+}
+
+/// The text fields of a card that can show media
+fn card_texts(card: &mut Flashcard) -> Vec<&mut String> {
+    let mut texts = vec![&mut card.front, &mut card.back];
+    if let Some(cloze) = card.cloze.as_mut() {
+        texts.push(&mut cloze.text);
+        if let Some(extra) = cloze.extra.as_mut() {
+            texts.push(extra);
+        }
+    }
+    texts
+}
+
+/// Point local media references of `card` at `media://`, collecting the
+/// files that exist under `assets_dir`
+fn bundle_card_media(card: &mut Flashcard, assets_dir: &Path, media: &mut Vec<String>) {
+    let re = media_ref_regex();
+    for text in card_texts(card) {
+        let rewritten = re.replace_all(text, |c: &regex::Captures| {
+            match asset_relative_path(&c[0]).filter(|rel| assets_dir.join(rel).is_file()) {
+                Some(rel) => {
+                    if !media.contains(&rel) {
+                        media.push(rel.clone());
+                    }
+                    format!("{}{}", MEDIA_SCHEME, rel)
+                }
+                None => c[0].to_string(),
+            }
+        });
+        if let std::borrow::Cow::Owned(new) = rewritten {
+            *text = new;
+        }
+    }
+}
+
+/// Write `deck` of `notebook_id` to a bundle at `path`. Cards made from
+/// blocks become standalone cards, as the pages don't travel with them.
+pub fn export_deck(
+    storage: &FlashcardStorage,
+    notebook_id: Uuid,
+    deck_id: Uuid,
+    assets_dir: &Path,
+    path: &Path,
+    include_scheduling: bool,
+) -> Result<DeckBundle> {
+    let deck = storage.get_deck(notebook_id, deck_id)?;
+    let mut cards = storage.list_cards(notebook_id, deck_id)?;
+    let mut media = Vec::new();
+    for card in &mut cards {
+        card.source = CardSource::Standalone;
+        card.source_changed = false;
+        bundle_card_media(card, assets_dir, &mut media);
+    }
+    let states = if include_scheduling {
+        Some(
+            cards
+                .iter()
+                .map(|card| storage.get_card_state(notebook_id, card.id))
+                .collect::<Result<Vec<_>>>()?,
+        )
+    } else {
+        None
+    };
+
+    let bundle = DeckBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        deck,
+        cards,
+        states,
+        media,
+    };
+    write_bundle(&bundle, assets_dir, path)?;
+    Ok(bundle)
+}
+
+fn write_bundle(bundle: &DeckBundle, assets_dir: &Path, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Written next to the target first, so a watcher of the shared decks
+    // folder never sees half a bundle
+    let partial = path.with_extension(format!("{}.part", BUNDLE_EXTENSION));
+    let mut zip = ZipWriter::new(File::create(&partial)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(MANIFEST_NAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(bundle)?.as_bytes())?;
+    for rel in &bundle.media {
+        zip.start_file(format!("{}{}", MEDIA_DIR, rel), options)?;
+        zip.write_all(&fs::read(assets_dir.join(rel))?)?;
+    }
+    zip.finish()?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// Read the manifest of the bundle at `path`
+pub fn read_bundle(path: &Path) -> Result<DeckBundle> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut content = String::new();
+    archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|_| invalid(format!("{} is missing", MANIFEST_NAME)))?
+        .read_to_string(&mut content)?;
+    let bundle: DeckBundle = serde_json::from_str(&content)?;
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(invalid(format!(
+            "format version {} is newer than this version of Nous supports",
+            bundle.format_version
+        )));
+    }
+    Ok(bundle)
+}
+
+/// The deck of `notebook_id` that `bundle` would collide with: the same
+/// deck first, else one with the same name
+fn find_existing(decks: &[Deck], bundle: &DeckBundle) -> Option<ExistingDeck> {
+    let same = |deck: &Deck, same_deck| ExistingDeck {
+        id: deck.id,
+        name: deck.name.clone(),
+        card_count: deck.card_count,
+        same_deck,
+    };
+    decks
+        .iter()
+        .find(|d| d.id == bundle.deck.id)
+        .map(|d| same(d, true))
+        .or_else(|| {
+            decks
+                .iter()
+                .find(|d| d.name.eq_ignore_ascii_case(&bundle.deck.name))
+                .map(|d| same(d, false))
+        })
+}
+
+/// Describe the bundle at `path` and what it collides with in `notebook_id`
+pub fn inspect_bundle(
+    storage: &FlashcardStorage,
+    notebook_id: Uuid,
+    path: &Path,
+) -> Result<DeckBundleInfo> {
+    let bundle = read_bundle(path)?;
+    let decks = storage.list_decks(notebook_id)?;
+    Ok(DeckBundleInfo {
+        path: path.to_string_lossy().into_owned(),
+        file_name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        existing_deck: find_existing(&decks, &bundle),
+        deck_id: bundle.deck.id,
+        name: bundle.deck.name,
+        description: bundle.deck.description,
+        color: bundle.deck.color,
+        card_count: bundle.cards.len(),
+        has_scheduling: bundle.states.is_some(),
+        media_count: bundle.media.len(),
+        exported_at: bundle.exported_at,
+    })
+}
+
+/// Where media file `rel` goes under `assets_dir`: `rel` itself, or
+/// `name-2.ext` etc. beside it when a different file already has that
+/// name (no spaces, so references to it stay one token). The flag is false when the same file is already there.
+fn media_destination(assets_dir: &Path, rel: &str, bytes: &[u8]) -> (String, bool) {
+    // Some(true): free, Some(false): same file there, None: taken
+    let free = |rel: &str| -> Option<bool> {
+        match fs::read(assets_dir.join(rel)) {
+            Err(_) => Some(true),
+            Ok(existing) if existing == bytes => Some(false),
+            Ok(_) => None,
+        }
+    };
+    if let Some(write) = free(rel) {
+        return (rel.to_string(), write);
+    }
+
+    let (dir, name) = match rel.rsplit_once('/') {
+        Some((dir, name)) => (format!("{}/", dir), name),
+        None => (String::new(), rel),
+    };
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (name, String::new()),
+    };
+    (2..)
+        .find_map(|n| {
+            let candidate = format!("{}{}-{}{}", dir, stem, n, ext);
+            free(&candidate).map(|write| (candidate, write))
+        })
+        .expect("unbounded range")
+}
+
+/// Copy the bundle's media into `assets_dir`; returns bundle path →
+/// assets path, plus how many files were copied and renamed
+fn import_media(
+    archive: &mut ZipArchive<File>,
+    media: &[String],
+    assets_dir: &Path,
+) -> Result<(HashMap<String, String>, usize, usize)> {
+    let mut mapping = HashMap::new();
+    let (mut copied, mut renamed) = (0, 0);
+    for rel in media {
+        let Some(safe) = sanitize_relative(rel) else {
+            continue;
+        };
+        let Ok(mut entry) = archive.by_name(&format!("{}{}", MEDIA_DIR, safe)) else {
+            continue;
+        };
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+
+        let (target, write) = media_destination(assets_dir, &safe, &bytes);
+        if write {
+            let Some(path) = resolve(assets_dir, &target) else {
+                continue;
+            };
+            atomic::write(&path, &bytes)?;
+            copied += 1;
+            if target != safe {
+                renamed += 1;
+            }
+        }
+        mapping.insert(rel.clone(), target);
+    }
+    Ok((mapping, copied, renamed))
+}
+
+/// Point `media://` references of `card` at the imported files
+fn localize_card_media(card: &mut Flashcard, notebook_id: Uuid, mapping: &HashMap<String, String>) {
+    let re = bundled_media_regex();
+    for text in card_texts(card) {
+        let rewritten = re.replace_all(text, |c: &regex::Captures| match mapping.get(&c[1]) {
+            Some(target) => format!("asset://{}/{}", notebook_id, target),
+            None => c[0].to_string(),
+        });
+        if let std::borrow::Cow::Owned(new) = rewritten {
+            *text = new;
+        }
+    }
+}
+
+/// `name`, or `name (2)` etc. if a deck already has it
+fn unique_deck_name(decks: &[Deck], name: &str) -> String {
+    let taken = |candidate: &str| decks.iter().any(|d| d.name.eq_ignore_ascii_case(candidate));
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !taken(candidate))
+        .expect("unbounded range")
+}
+
+/// Whether two cards ask the same thing
+fn same_content(a: &Flashcard, b: &Flashcard) -> bool {
+    a.front.trim() == b.front.trim() && a.back.trim() == b.back.trim()
+}
+
+/// Import the bundle at `path` into `notebook_id`. `with_scheduling` uses
+/// the bundle's scheduling (when it has any) for the cards it adds.
+pub fn import_deck(
+    storage: &FlashcardStorage,
+    notebook_id: Uuid,
+    assets_dir: &Path,
+    path: &Path,
+    collision: ImportCollision,
+    with_scheduling: bool,
+) -> Result<DeckImportResult> {
+    let bundle = read_bundle(path)?;
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    storage.init(notebook_id)?;
+
+    let decks = storage.list_decks(notebook_id)?;
+    let existing = find_existing(&decks, &bundle);
+    let collision = if existing.is_some() {
+        collision
+    } else {
+        // Nothing to collide with: import the deck as it is
+        ImportCollision::Replace
+    };
+    if collision == ImportCollision::Skip {
+        return Ok(DeckImportResult::default());
+    }
+
+    let (mapping, media_copied, media_renamed) =
+        import_media(&mut archive, &bundle.media, assets_dir)?;
+    let mut result = DeckImportResult {
+        media_copied,
+        media_renamed,
+        ..Default::default()
+    };
+    let states: HashMap<Uuid, CardState> = bundle
+        .states
+        .clone()
+        .filter(|_| with_scheduling)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| (s.card_id, s))
+        .collect();
+
+    // The deck the cards go into, and the cards already in it
+    let now = Utc::now();
+    let new_deck = |id: Uuid, name: String| Deck {
+        id,
+        notebook_id,
+        name,
+        card_count: 0,
+        created_at: now,
+        updated_at: now,
+        ..bundle.deck.clone()
+    };
+    let (deck, mut local_cards) = match (collision, existing) {
+        (ImportCollision::Merge, Some(existing)) => (
+            storage.get_deck(notebook_id, existing.id)?,
+            storage.list_cards(notebook_id, existing.id)?,
+        ),
+        (ImportCollision::Copy, _) => {
+            let deck = new_deck(Uuid::new_v4(), unique_deck_name(&decks, &bundle.deck.name));
+            storage.put_deck(notebook_id, &deck)?;
+            (deck, Vec::new())
+        }
+        (_, existing) => {
+            // An existing deck matched by name only doesn't have the
+            // bundle's id, so that stays free after deleting it
+            if let Some(existing) = existing {
+                storage.delete_deck(notebook_id, existing.id)?;
+            }
+            let deck = new_deck(bundle.deck.id, bundle.deck.name.clone());
+            storage.put_deck(notebook_id, &deck)?;
+            (deck, Vec::new())
+        }
+    };
+
+    let taken_ids: HashSet<Uuid> = storage
+        .list_all_cards(notebook_id)?
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    let mut next_position = local_cards
+        .iter()
+        .map(|c| c.position + 1)
+        .max()
+        .unwrap_or(0);
+
+    for mut card in bundle.cards {
+        localize_card_media(&mut card, notebook_id, &mapping);
+        let bundle_id = card.id;
+
+        let local = local_cards
+            .iter()
+            .position(|c| c.id == card.id)
+            .or_else(|| local_cards.iter().position(|c| same_content(c, &card)));
+        if let Some(index) = local {
+            let local = &mut local_cards[index];
+            if local.id == card.id && card.updated_at > local.updated_at {
+                local.front = card.front;
+                local.back = card.back;
+                local.card_type = card.card_type;
+                local.tags = card.tags;
+                local.cloze = card.cloze;
+                local.updated_at = card.updated_at;
+                storage.update_card(notebook_id, local)?;
+                result.updated += 1;
+            } else {
+                result.unchanged += 1;
+            }
+            continue;
+        }
+
+        if collision == ImportCollision::Copy || taken_ids.contains(&card.id) {
+            card.id = Uuid::new_v4();
+        }
+        card.deck_id = deck.id;
+        if collision == ImportCollision::Merge {
+            card.position = next_position;
+            next_position += 1;
+        }
+        let state = match states.get(&bundle_id) {
+            Some(state) => CardState {
+                card_id: card.id,
+                ..state.clone()
+            },
+            None => CardState::new(card.id),
+        };
+        storage.put_card(notebook_id, &card, &state)?;
+        local_cards.push(card);
+        result.added += 1;
+    }
+
+    storage.update_deck_card_count(notebook_id, deck.id)?;
+    result.deck = Some(storage.get_deck(notebook_id, deck.id)?);
+    Ok(result)
+}
+
+/// File name for a bundle of the deck called `name`
+pub fn bundle_file_name(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let stem = stem.trim();
+    format!(
+        "{}.{}",
+        if stem.is_empty() { "deck" } else { stem },
+        BUNDLE_EXTENSION
+    )
+}
+
+/// Bundles directly inside `dir`, by file name
+pub fn list_bundle_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file() && path.extension().is_some_and(|ext| ext == BUNDLE_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_and_import_with_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FlashcardStorage::new(dir.path().join("notebooks"));
+        let (source_nb, target_nb) = (Uuid::new_v4(), Uuid::new_v4());
+        let source_assets = dir.path().join("source-assets");
+        let target_assets = dir.path().join("target-assets");
+        fs::create_dir_all(source_assets.join("images")).unwrap();
+        fs::write(source_assets.join("images/cell.png"), b"cell").unwrap();
+        fs::create_dir_all(target_assets.join("images")).unwrap();
+        fs::write(target_assets.join("images/cell.png"), b"other").unwrap();
+
+        let deck = storage
+            .create_deck(source_nb, "Biology".into(), None, None)
+            .unwrap();
+        let card = storage
+            .create_card(
+                source_nb,
+                deck.id,
+                format!(
+                    "![cell](asset://{}/images/cell.png) What is this?",
+                    source_nb
+                ),
+                "A cell".into(),
+                None,
+                None,
+            )
+            .unwrap();
+        storage.submit_review(source_nb, card.id, 3).unwrap();
+        storage
+            .create_card(source_nb, deck.id, "Q2".into(), "A2".into(), None, None)
+            .unwrap();
+
+        let path = dir.path().join(bundle_file_name(&deck.name));
+        let bundle =
+            export_deck(&storage, source_nb, deck.id, &source_assets, &path, true).unwrap();
+        assert_eq!(bundle.media, vec!["images/cell.png".to_string()]);
+        assert!(bundle.cards[0]
+            .front
+            .starts_with("![cell](media://images/cell.png)"));
+
+        // A new notebook: imported as is, the clashing image renamed
+        let result = import_deck(
+            &storage,
+            target_nb,
+            &target_assets,
+            &path,
+            ImportCollision::Merge,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            (result.added, result.media_copied, result.media_renamed),
+            (2, 1, 1)
+        );
+        assert_eq!(result.deck.as_ref().unwrap().id, deck.id);
+        assert_eq!(result.deck.as_ref().unwrap().card_count, 2);
+        let imported = storage.get_card(target_nb, card.id).unwrap();
+        assert!(imported
+            .front
+            .starts_with(&format!("![cell](asset://{}/images/cell-2.png)", target_nb)));
+        assert_eq!(
+            fs::read(target_assets.join("images/cell-2.png")).unwrap(),
+            b"cell"
+        );
+        assert_eq!(
+            storage
+                .get_card_state(target_nb, card.id)
+                .unwrap()
+                .review_count,
+            1
+        );
+
+        // The same deck again: merging changes nothing, copying makes a
+        // second deck, skipping does nothing
+        let info = inspect_bundle(&storage, target_nb, &path).unwrap();
+        assert!(info.existing_deck.as_ref().unwrap().same_deck);
+        let merged = import_deck(
+            &storage,
+            target_nb,
+            &target_assets,
+            &path,
+            ImportCollision::Merge,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            (merged.added, merged.unchanged, merged.media_copied),
+            (0, 2, 0)
+        );
+
+        let copied = import_deck(
+            &storage,
+            target_nb,
+            &target_assets,
+            &path,
+            ImportCollision::Copy,
+            false,
+        )
+        .unwrap();
+        let copy = copied.deck.unwrap();
+        assert_eq!(copy.name, "Biology (2)");
+        assert_ne!(copy.id, deck.id);
+        assert_eq!(copied.added, 2);
+        let copied_cards = storage.list_cards(target_nb, copy.id).unwrap();
+        assert!(copied_cards.iter().all(|c| c.id != card.id));
+        assert_eq!(
+            storage
+                .get_card_state(target_nb, copied_cards[0].id)
+                .unwrap()
+                .review_count,
+            0
+        );
+
+        let skipped = import_deck(
+            &storage,
+            target_nb,
+            &target_assets,
+            &path,
+            ImportCollision::Skip,
+            true,
+        )
+        .unwrap();
+        assert!(skipped.deck.is_none());
+        assert_eq!(storage.list_decks(target_nb).unwrap().len(), 2);
+    }
+}
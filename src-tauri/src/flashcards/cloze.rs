@@ -0,0 +1,167 @@
+//! Cloze deletions
+//!
+//! Cloze text marks the parts to hide as `{{c1::answer}}` or
+//! `{{c1::answer::hint}}`, as Anki does. Every cloze number becomes one
+//! card: its front hides that number's answers and shows the others, its
+//! back shows the whole text.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::models::Cloze;
+
+fn cloze_regex() -> Regex {
+    Regex::new(r"\{\{c(\d+)::(.*?)(?:::(.*?))?\}\}").unwrap()
+}
+
+/// A selected range of the block text, in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClozeRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Wrap each of `ranges` of `text` in its own cloze, numbered in text order.
+/// Overlapping or out-of-bounds ranges are skipped.
+pub fn insert_clozes(text: &str, ranges: &[ClozeRange]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut ranges: Vec<ClozeRange> = ranges
+        .iter()
+        .copied()
+        .filter(|r| r.start < r.end && r.end <= chars.len())
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+
+    let mut out = String::with_capacity(text.len() + ranges.len() * 10);
+    let mut cursor = 0;
+    let mut number = 0;
+    for range in ranges {
+        if range.start < cursor {
+            continue;
+        }
+        number += 1;
+        out.extend(&chars[cursor..range.start]);
+        out.push_str(&format!("{{{{c{}::", number));
+        out.extend(&chars[range.start..range.end]);
+        out.push_str("}}");
+        cursor = range.end;
+    }
+    out.extend(&chars[cursor..]);
+    out
+}
+
+/// The distinct cloze numbers in `text`, ascending
+pub fn cloze_numbers(text: &str) -> Vec<u32> {
+    let mut numbers: Vec<u32> = cloze_regex()
+        .captures_iter(text)
+        .filter_map(|c| c[1].parse().ok())
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    numbers
+}
+
+/// Front and back of the card for cloze `number`
+pub fn render_cloze(text: &str, number: u32) -> (String, String) {
+    let front = cloze_regex()
+        .replace_all(text, |c: &regex::Captures| {
+            if c[1].parse::<u32>().ok() == Some(number) {
+                match c.get(3) {
+                    Some(hint) => format!("[{}]", hint.as_str()),
+                    None => "[...]".to_string(),
+                }
+            } else {
+                c[2].to_string()
+            }
+        })
+        .into_owned();
+    (front, strip_clozes(text))
+}
+
+/// Front and back of a cloze card
+pub fn card_sides(cloze: &Cloze) -> (String, String) {
+    let (front, back) = render_cloze(&cloze.text, cloze.number);
+    match cloze.extra.as_deref().filter(|e| !e.trim().is_empty()) {
+        Some(extra) => (front, format!("{}\n\n{}", back, extra)),
+        None => (front, back),
+    }
+}
+
+/// `text` with the cloze markup removed
+pub fn strip_clozes(text: &str) -> String {
+    cloze_regex().replace_all(text, "$2").into_owned()
+}
+
+/// Text reduced to its words, for telling real edits from formatting ones
+fn normalize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Whether `new` says something different from `old`; changes to case,
+/// whitespace or punctuation don't count
+pub fn changed_materially(old: &str, new: &str) -> bool {
+    normalize(old) != normalize(new)
+}
+
+/// Put the clozes of `cloze_text` back into the edited block text `new`,
+/// by finding each answer again in order. `None` when an answer is gone.
+pub fn rebase_clozes(cloze_text: &str, new: &str) -> Option<String> {
+    let mut out = String::with_capacity(new.len() + 16);
+    let mut cursor = 0;
+    for c in cloze_regex().captures_iter(cloze_text) {
+        let answer = &c[2];
+        let found = cursor + new[cursor..].find(answer)?;
+        out.push_str(&new[cursor..found]);
+        out.push_str(&c[0]);
+        cursor = found + answer.len();
+    }
+    out.push_str(&new[cursor..]);
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloze_cards_from_ranges() {
+        let text = "Paris is the capital of France.";
+        let marked = insert_clozes(
+            text,
+            &[
+                ClozeRange { start: 24, end: 30 },
+                ClozeRange { start: 0, end: 5 },
+                ClozeRange { start: 2, end: 4 },
+            ],
+        );
+        assert_eq!(marked, "{{c1::Paris}} is the capital of {{c2::France}}.");
+        assert_eq!(cloze_numbers(&marked), vec![1, 2]);
+
+        let (front, back) = render_cloze(&marked, 2);
+        assert_eq!(front, "Paris is the capital of [...].");
+        assert_eq!(back, text);
+
+        let (front, _) = render_cloze("{{c1::H2O::formula}} is water", 1);
+        assert_eq!(front, "[formula] is water");
+    }
+
+    #[test]
+    fn test_rebase_after_edit() {
+        let marked = "{{c1::Paris}} is the capital of {{c2::France}}.";
+        assert!(!changed_materially(
+            "Paris is the capital of France.",
+            "paris  is the Capital of France"
+        ));
+
+        let edited = "Since 508, Paris has been the capital of France.";
+        assert!(changed_materially("Paris is the capital of France.", edited));
+        assert_eq!(
+            rebase_clozes(marked, edited).unwrap(),
+            "Since 508, {{c1::Paris}} has been the capital of {{c2::France}}."
+        );
+        assert_eq!(rebase_clozes(marked, "Lyon is in France."), None);
+    }
+}
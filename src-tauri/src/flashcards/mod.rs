@@ -4,10 +4,17 @@
 //! - Deck management (per-notebook flashcard collections)
 //! - Flashcard CRUD (standalone or linked to editor blocks)
 //! - SM-2 spaced repetition algorithm
-//! - Review state tracking
+//! - Review state tracking and the review log behind deck analytics
+//! - Incremental reading: pages and extracts scheduled for re-reading
+//! - Portable `.nousdeck` bundles, and a watched shared decks folder for
+//!   trading them
 
 pub mod algorithm;
+pub mod analytics;
+pub mod bundle;
+pub mod cloze;
 pub mod models;
+pub mod shared_folder;
 pub mod storage;
 
 pub use models::*;
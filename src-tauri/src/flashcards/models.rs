@@ -79,6 +79,9 @@ pub enum CardSource {
     BlockRef {
         page_id: Uuid,
         block_id: String,
+        /// The block's text when the card was made or last updated from it
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source_text: Option<String>,
     },
 }
 
@@ -88,6 +91,20 @@ impl Default for CardSource {
     }
 }
 
+/// The cloze a cloze card asks for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cloze {
+    /// The full text with its `{{cN::...}}` markup, shared by the cards
+    /// made from it
+    pub text: String,
+    /// Which cloze this card hides
+    pub number: u32,
+    /// Shown on the back below the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<String>,
+}
+
 /// A flashcard with question (front) and answer (back)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -104,6 +121,13 @@ pub struct Flashcard {
     pub source: CardSource,
     #[serde(default)]
     pub position: i32,
+    /// Set for cloze cards; `front` and `back` are rendered from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloze: Option<Cloze>,
+    /// The source block changed in a way the card couldn't follow, or is
+    /// gone; the card needs a look
+    #[serde(default)]
+    pub source_changed: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -120,6 +144,8 @@ impl Flashcard {
             tags: Vec::new(),
             source: CardSource::default(),
             position: 0,
+            cloze: None,
+            source_changed: false,
             created_at: now,
             updated_at: now,
         }
@@ -127,7 +153,11 @@ impl Flashcard {
 
     pub fn from_block(deck_id: Uuid, page_id: Uuid, block_id: String, front: String, back: String) -> Self {
         let mut card = Self::new(deck_id, front, back);
-        card.source = CardSource::BlockRef { page_id, block_id };
+        card.source = CardSource::BlockRef {
+            page_id,
+            block_id,
+            source_text: None,
+        };
         card
     }
 }
@@ -171,6 +201,9 @@ pub struct CardState {
     /// Number of correct responses
     #[serde(default)]
     pub correct_count: i32,
+    /// Times the card was forgotten after it had been learned
+    #[serde(default)]
+    pub lapses: i32,
     /// Current status in the learning process
     #[serde(default)]
     pub status: CardStatus,
@@ -193,6 +226,7 @@ impl CardState {
             due_date: Utc::now(),
             review_count: 0,
             correct_count: 0,
+            lapses: 0,
             status: CardStatus::New,
         }
     }
@@ -226,7 +260,6 @@ pub struct ReviewRecord {
 }
 
 impl ReviewRecord {
-    #[allow(dead_code)]
     pub fn new(card_id: Uuid, quality: i32, interval: i32, ease_factor: f32) -> Self {
         Self {
             id: Uuid::new_v4(),
@@ -275,3 +308,89 @@ pub struct CardWithState {
     pub card: Flashcard,
     pub state: CardState,
 }
+
+/// What an incremental reading item points at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReadingSource {
+    /// A whole page, re-read as it is at the time
+    Page { page_id: Uuid },
+    /// A passage copied out of a page
+    Extract {
+        page_id: Uuid,
+        #[serde(default)]
+        block_ids: Vec<String>,
+        text: String,
+    },
+}
+
+impl ReadingSource {
+    pub fn page_id(&self) -> Uuid {
+        match self {
+            Self::Page { page_id } | Self::Extract { page_id, .. } => *page_id,
+        }
+    }
+}
+
+/// Reading priority, 1 (highest) to 5 (lowest)
+pub const DEFAULT_READING_PRIORITY: u8 = 3;
+
+/// A page or extract scheduled for re-reading ("incremental reading")
+///
+/// Items are scheduled with the same state and algorithm as flashcards;
+/// their priority stretches or shrinks the intervals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingItem {
+    pub id: Uuid,
+    pub title: String,
+    pub source: ReadingSource,
+    #[serde(default = "default_reading_priority")]
+    pub priority: u8,
+    /// Scheduling state; `card_id` is the item id
+    pub state: CardState,
+    /// Finished with, no longer scheduled
+    #[serde(default)]
+    pub done: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_reading_priority() -> u8 {
+    DEFAULT_READING_PRIORITY
+}
+
+impl ReadingItem {
+    pub fn new(title: String, source: ReadingSource, priority: u8) -> Self {
+        let id = Uuid::new_v4();
+        let now = Utc::now();
+        Self {
+            id,
+            title,
+            source,
+            priority: priority.clamp(1, 5),
+            state: CardState::new(id),
+            done: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// An entry in a mixed review session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ReviewQueueItem {
+    Card(CardWithState),
+    Reading(ReadingItem),
+}
+
+/// Cards updated or flagged after their source blocks changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCardSync {
+    /// Cloze cards moved over to the new block text
+    pub updated: Vec<Uuid>,
+    /// Cards marked `source_changed`
+    pub flagged: Vec<Uuid>,
+}
@@ -0,0 +1,163 @@
+//! Watching the shared decks folder
+//!
+//! A background thread watches the current library's shared decks folder
+//! (see `Library::shared_decks_path`) and reports whenever the `.nousdeck`
+//! bundles in it change, so the shared decks browser can refresh. Bundles
+//! are never imported on their own: which to import, and how to resolve
+//! collisions with decks already in the notebook, is up to the user.
+//!
+//! The folder is looked up again every [`RESCAN_INTERVAL`], which picks up
+//! a changed setting or library and catches changes the OS watcher misses
+//! (common on network and synced drives).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+
+use super::bundle::list_bundle_paths;
+
+/// How often the folder is looked up and rescanned
+const RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the folder must be quiet before changes are reported
+const SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// Emitted (as `shared-decks-changed`) when bundles are added, changed or
+/// removed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDecksChanged {
+    pub folder: String,
+    /// File names of the bundles now in the folder
+    pub files: Vec<String>,
+}
+
+/// Bundle file name → (size, modified)
+type Snapshot = BTreeMap<String, (u64, Option<SystemTime>)>;
+
+fn snapshot(folder: &Path) -> Snapshot {
+    list_bundle_paths(folder)
+        .into_iter()
+        .filter_map(|path| {
+            let meta = fs::metadata(&path).ok()?;
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            Some((name, (meta.len(), meta.modified().ok())))
+        })
+        .collect()
+}
+
+/// Handle to the shared decks watcher thread; dropping it stops the thread
+pub struct SharedDecksWatcher {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for SharedDecksWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Start watching the folder `folder` returns (looked up again on every
+/// rescan). `on_change` is called when the bundles in it change.
+pub fn start<P, F>(folder: P, on_change: F) -> SharedDecksWatcher
+where
+    P: Fn() -> Option<PathBuf> + Send + 'static,
+    F: Fn(SharedDecksChanged) + Send + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+
+    let thread = std::thread::Builder::new()
+        .name("shared-decks".to_string())
+        .spawn(move || {
+            let (tx, rx) = mpsc::channel::<()>();
+            let mut watcher = match RecommendedWatcher::new(
+                move |res: Result<notify::Event, notify::Error>| {
+                    if res.is_ok() {
+                        let _ = tx.send(());
+                    }
+                },
+                Config::default(),
+            ) {
+                Ok(w) => Some(w),
+                Err(e) => {
+                    // Still works via periodic rescans, just with more latency
+                    log::warn!("Shared decks: OS file watcher unavailable: {}", e);
+                    None
+                }
+            };
+
+            let mut watched: Option<PathBuf> = None;
+            let mut last: Option<Snapshot> = None;
+            let mut last_rescan: Option<Instant> = None;
+            let mut changed_at: Option<Instant> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(()) => changed_at = Some(Instant::now()),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                if !matches!(last_rescan, Some(t) if t.elapsed() < RESCAN_INTERVAL) {
+                    last_rescan = Some(Instant::now());
+                    let current = folder().filter(|dir| dir.is_dir());
+                    if current != watched {
+                        if let Some(watcher) = watcher.as_mut() {
+                            if let Some(old) = &watched {
+                                let _ = watcher.unwatch(old);
+                            }
+                            if let Some(dir) = &current {
+                                if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                                    log::warn!("Shared decks: cannot watch {:?}: {}", dir, e);
+                                }
+                            }
+                        }
+                        // A different folder: its contents are the baseline
+                        watched = current;
+                        last = watched.as_deref().map(snapshot);
+                        changed_at = None;
+                        continue;
+                    }
+                    changed_at.get_or_insert_with(Instant::now);
+                }
+
+                let Some(dir) = &watched else {
+                    continue;
+                };
+                if !matches!(changed_at, Some(t) if t.elapsed() >= SETTLE_TIME) {
+                    continue;
+                }
+                changed_at = None;
+
+                let current = snapshot(dir);
+                if last.as_ref() != Some(&current) {
+                    on_change(SharedDecksChanged {
+                        folder: dir.to_string_lossy().into_owned(),
+                        files: current.keys().cloned().collect(),
+                    });
+                    last = Some(current);
+                }
+            }
+
+            drop(watcher);
+        })
+        .expect("failed to spawn shared decks thread");
+
+    SharedDecksWatcher {
+        stop,
+        thread: Some(thread),
+    }
+}
@@ -6,19 +6,30 @@
 //! ├── decks.json           # Array of all decks
 //! ├── cards/
 //! │   └── {card-id}.json   # Individual card files
-//! └── states/
-//!     └── {card-id}.json   # Card spaced repetition state
+//! ├── states/
+//! │   └── {card-id}.json   # Card spaced repetition state
+//! ├── reviews.jsonl        # Review log, one record per line
+//! └── reading/
+//!     └── {item-id}.json   # Incremental reading item with its state
 //! ```
 
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
 use std::path::PathBuf;
 
-use chrono::Utc;
+use chrono::{Local, Utc};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::algorithm::{calculate_next_review, ui_rating_to_quality, ReviewResult};
+use super::algorithm::{
+    calculate_next_reading, calculate_next_review, mix_review_queue, ui_rating_to_quality,
+    ReviewResult,
+};
+use super::analytics::{self, DeckAnalytics, LeechCard};
+use super::cloze;
 use super::models::*;
+use crate::storage::atomic;
 
 #[derive(Error, Debug)]
 pub enum FlashcardStorageError {
@@ -34,6 +45,15 @@ pub enum FlashcardStorageError {
     #[error("Card not found: {0}")]
     CardNotFound(Uuid),
 
+    #[error("Reading item not found: {0}")]
+    ReadingItemNotFound(Uuid),
+
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Invalid deck bundle: {0}")]
+    InvalidBundle(String),
+
     #[error("Invalid notebook path")]
     InvalidNotebookPath,
 }
@@ -68,6 +88,22 @@ impl FlashcardStorage {
         self.flashcards_dir(notebook_id).join("states")
     }
 
+    /// Get the incremental reading directory for a notebook
+    fn reading_dir(&self, notebook_id: Uuid) -> PathBuf {
+        self.flashcards_dir(notebook_id).join("reading")
+    }
+
+    /// Get the path for an incremental reading item
+    fn reading_path(&self, notebook_id: Uuid, item_id: Uuid) -> PathBuf {
+        self.reading_dir(notebook_id)
+            .join(format!("{}.json", item_id))
+    }
+
+    /// Get the review log path for a notebook
+    fn reviews_path(&self, notebook_id: Uuid) -> PathBuf {
+        self.flashcards_dir(notebook_id).join("reviews.jsonl")
+    }
+
     /// Get the decks.json path for a notebook
     fn decks_path(&self, notebook_id: Uuid) -> PathBuf {
         self.flashcards_dir(notebook_id).join("decks.json")
@@ -176,8 +212,24 @@ impl FlashcardStorage {
         Ok(())
     }
 
+    /// Add a deck as it is, or overwrite the deck with its id (e.g. when
+    /// importing one)
+    pub fn put_deck(&self, notebook_id: Uuid, deck: &Deck) -> Result<()> {
+        self.init(notebook_id)?;
+        let mut decks = self.list_decks(notebook_id)?;
+        match decks.iter().position(|d| d.id == deck.id) {
+            Some(pos) => decks[pos] = deck.clone(),
+            None => decks.push(deck.clone()),
+        }
+
+        let decks_path = self.decks_path(notebook_id);
+        atomic::write_str(&decks_path, &serde_json::to_string_pretty(&decks)?)?;
+
+        Ok(())
+    }
+
     /// Update the card count for a deck
-    fn update_deck_card_count(&self, notebook_id: Uuid, deck_id: Uuid) -> Result<()> {
+    pub(crate) fn update_deck_card_count(&self, notebook_id: Uuid, deck_id: Uuid) -> Result<()> {
         let cards = self.list_cards(notebook_id, deck_id)?;
         let mut deck = self.get_deck(notebook_id, deck_id)?;
         deck.card_count = cards.len();
@@ -284,7 +336,9 @@ impl FlashcardStorage {
         Ok(card)
     }
 
-    /// Create a card from an editor block
+    /// Create a card from an editor block. `source_text` is the block's
+    /// text, kept to notice when the block changes.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_card_from_block(
         &self,
         notebook_id: Uuid,
@@ -293,6 +347,7 @@ impl FlashcardStorage {
         block_id: String,
         front: String,
         back: String,
+        source_text: Option<String>,
     ) -> Result<Flashcard> {
         self.init(notebook_id)?;
 
@@ -301,6 +356,9 @@ impl FlashcardStorage {
 
         let mut card = Flashcard::from_block(deck_id, page_id, block_id, front, back);
         card.position = position;
+        if let CardSource::BlockRef { source_text: text, .. } = &mut card.source {
+            *text = source_text;
+        }
 
         let card_path = self.card_path(notebook_id, card.id);
         fs::write(&card_path, serde_json::to_string_pretty(&card)?)?;
@@ -316,6 +374,138 @@ impl FlashcardStorage {
         Ok(card)
     }
 
+    /// Create one cloze card per cloze number in `cloze_text`, all linked to
+    /// the block. `extra` goes on the back of each.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_cloze_cards_from_block(
+        &self,
+        notebook_id: Uuid,
+        deck_id: Uuid,
+        page_id: Uuid,
+        block_id: String,
+        cloze_text: String,
+        extra: Option<String>,
+        source_text: Option<String>,
+    ) -> Result<Vec<Flashcard>> {
+        self.init(notebook_id)?;
+
+        let first_position = self.list_cards(notebook_id, deck_id)?.len() as i32;
+        let mut cards = Vec::new();
+        for (number, position) in cloze::cloze_numbers(&cloze_text)
+            .into_iter()
+            .zip(first_position..)
+        {
+            let cloze = Cloze {
+                text: cloze_text.clone(),
+                number,
+                extra: extra.clone(),
+            };
+            let (front, back) = cloze::card_sides(&cloze);
+            let mut card = Flashcard::from_block(deck_id, page_id, block_id.clone(), front, back);
+            card.card_type = CardType::Cloze;
+            card.cloze = Some(cloze);
+            card.position = position;
+            if let CardSource::BlockRef { source_text: text, .. } = &mut card.source {
+                *text = source_text.clone();
+            }
+
+            let card_path = self.card_path(notebook_id, card.id);
+            fs::write(&card_path, serde_json::to_string_pretty(&card)?)?;
+            let state = CardState::new(card.id);
+            let state_path = self.state_path(notebook_id, card.id);
+            fs::write(&state_path, serde_json::to_string_pretty(&state)?)?;
+            cards.push(card);
+        }
+
+        self.update_deck_card_count(notebook_id, deck_id)?;
+        Ok(cards)
+    }
+
+    /// Bring the cards made from blocks of a page up to date with the
+    /// blocks' current text (`block_texts`, by block id). Cards whose block
+    /// changed materially are updated when they are cloze cards whose
+    /// answers can still be found, and flagged `source_changed` otherwise.
+    pub fn sync_block_cards(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        block_texts: &HashMap<String, String>,
+    ) -> Result<BlockCardSync> {
+        let mut report = BlockCardSync::default();
+
+        for mut card in self.list_all_cards(notebook_id)? {
+            let CardSource::BlockRef {
+                page_id: source_page,
+                block_id,
+                source_text,
+            } = &mut card.source
+            else {
+                continue;
+            };
+            if *source_page != page_id {
+                continue;
+            }
+
+            let Some(new_text) = block_texts.get(block_id.as_str()) else {
+                if !card.source_changed {
+                    card.source_changed = true;
+                    card.updated_at = Utc::now();
+                    self.update_card(notebook_id, &card)?;
+                    report.flagged.push(card.id);
+                }
+                continue;
+            };
+            let Some(old_text) = source_text.as_deref() else {
+                // Made before the block text was kept; start tracking now
+                *source_text = Some(new_text.clone());
+                self.update_card(notebook_id, &card)?;
+                continue;
+            };
+            if !cloze::changed_materially(old_text, new_text) {
+                continue;
+            }
+
+            *source_text = Some(new_text.clone());
+            let rebased = card
+                .cloze
+                .as_ref()
+                .and_then(|c| cloze::rebase_clozes(&c.text, new_text));
+            match (rebased, card.cloze.as_mut()) {
+                (Some(text), Some(c)) => {
+                    c.text = text;
+                    let (front, back) = cloze::card_sides(c);
+                    card.front = front;
+                    card.back = back;
+                    card.source_changed = false;
+                    report.updated.push(card.id);
+                }
+                _ => {
+                    card.source_changed = true;
+                    report.flagged.push(card.id);
+                }
+            }
+            card.updated_at = Utc::now();
+            self.update_card(notebook_id, &card)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Write a card and its state as they are, without touching the deck's
+    /// card count (e.g. when importing a deck)
+    pub fn put_card(&self, notebook_id: Uuid, card: &Flashcard, state: &CardState) -> Result<()> {
+        self.init(notebook_id)?;
+        atomic::write_str(
+            &self.card_path(notebook_id, card.id),
+            &serde_json::to_string_pretty(card)?,
+        )?;
+        atomic::write_str(
+            &self.state_path(notebook_id, card.id),
+            &serde_json::to_string_pretty(state)?,
+        )?;
+        Ok(())
+    }
+
     /// Update a card
     pub fn update_card(&self, notebook_id: Uuid, card: &Flashcard) -> Result<()> {
         let card_path = self.card_path(notebook_id, card.id);
@@ -401,6 +591,7 @@ impl FlashcardStorage {
 
         // Convert UI rating (1-4) to SM-2 quality (0-5)
         let quality = ui_rating_to_quality(rating);
+        let record = ReviewRecord::new(card_id, quality, state.interval, state.ease_factor);
 
         // Calculate next review
         let ReviewResult {
@@ -418,14 +609,53 @@ impl FlashcardStorage {
         state.review_count += 1;
         if quality >= 3 {
             state.correct_count += 1;
+        } else if status == CardStatus::Relearning {
+            state.lapses += 1;
         }
 
         // Save state
         self.update_card_state(notebook_id, &state)?;
+        self.append_review_record(notebook_id, &record)?;
 
         Ok(state)
     }
 
+    /// Append a review to the notebook's review log
+    fn append_review_record(&self, notebook_id: Uuid, record: &ReviewRecord) -> Result<()> {
+        fs::create_dir_all(self.flashcards_dir(notebook_id))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.reviews_path(notebook_id))?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+
+    /// All logged reviews in a notebook, oldest first. Lines that don't
+    /// parse (e.g. a write cut short) are skipped.
+    pub fn list_review_records(&self, notebook_id: Uuid) -> Result<Vec<ReviewRecord>> {
+        let path = self.reviews_path(notebook_id);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Logged reviews of the given cards
+    fn review_records_for(&self, notebook_id: Uuid, cards: &[Flashcard]) -> Result<Vec<ReviewRecord>> {
+        let ids: HashSet<Uuid> = cards.iter().map(|c| c.id).collect();
+        Ok(self
+            .list_review_records(notebook_id)?
+            .into_iter()
+            .filter(|r| ids.contains(&r.card_id))
+            .collect())
+    }
+
     /// Get review statistics for a notebook (optionally filtered by deck)
     pub fn get_review_stats(&self, notebook_id: Uuid, deck_id: Option<Uuid>) -> Result<ReviewStats> {
         let cards = match deck_id {
@@ -452,6 +682,178 @@ impl FlashcardStorage {
             }
         }
 
+        let records = self.review_records_for(notebook_id, &cards)?;
+        let today = Local::now().date_naive();
+        if let Some(day) = analytics::review_heatmap(&records, today, 1).pop() {
+            stats.reviews_today = day.reviews;
+            stats.correct_today = day.correct;
+        }
+        stats.streak_days = analytics::streak_days(&records, today);
+
         Ok(stats)
     }
+
+    /// Heatmap over the last `days` days, retention by card age, ease
+    /// distribution, 30-day workload and leech count for a deck (or all decks)
+    pub fn get_deck_analytics(
+        &self,
+        notebook_id: Uuid,
+        deck_id: Option<Uuid>,
+        days: i64,
+        leech_threshold: i32,
+    ) -> Result<DeckAnalytics> {
+        let cards = match deck_id {
+            Some(did) => self.list_cards(notebook_id, did)?,
+            None => self.list_all_cards(notebook_id)?,
+        };
+        let states = cards
+            .iter()
+            .map(|card| self.get_card_state(notebook_id, card.id))
+            .collect::<Result<Vec<_>>>()?;
+        let records = self.review_records_for(notebook_id, &cards)?;
+
+        let today = Local::now().date_naive();
+        let by_id: HashMap<Uuid, &Flashcard> = cards.iter().map(|c| (c.id, c)).collect();
+        let state_refs: Vec<&CardState> = states.iter().collect();
+        Ok(DeckAnalytics {
+            heatmap: analytics::review_heatmap(&records, today, days),
+            retention_by_age: analytics::retention_by_age(&records, &by_id),
+            ease_distribution: analytics::ease_distribution(&state_refs),
+            workload: analytics::projected_workload(&state_refs, today),
+            leech_count: states
+                .iter()
+                .filter(|s| s.lapses >= leech_threshold.max(1))
+                .count(),
+        })
+    }
+
+    /// Cards of a deck (or all decks) forgotten at least `threshold` times
+    pub fn list_leech_cards(
+        &self,
+        notebook_id: Uuid,
+        deck_id: Option<Uuid>,
+        threshold: i32,
+    ) -> Result<Vec<LeechCard>> {
+        let cards = match deck_id {
+            Some(did) => self.list_cards(notebook_id, did)?,
+            None => self.list_all_cards(notebook_id)?,
+        };
+        let records = self.review_records_for(notebook_id, &cards)?;
+        let with_state = cards
+            .into_iter()
+            .map(|card| {
+                let state = self.get_card_state(notebook_id, card.id)?;
+                Ok(CardWithState { card, state })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(analytics::find_leeches(with_state, &records, threshold))
+    }
+
+    // ==================== Incremental Reading ====================
+
+    /// List all incremental reading items in a notebook
+    pub fn list_reading_items(&self, notebook_id: Uuid) -> Result<Vec<ReadingItem>> {
+        let reading_dir = self.reading_dir(notebook_id);
+        if !reading_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut items = Vec::new();
+        for entry in fs::read_dir(&reading_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().map_or(false, |ext| ext == "json") {
+                let content = fs::read_to_string(&path)?;
+                let item: ReadingItem = serde_json::from_str(&content)?;
+                items.push(item);
+            }
+        }
+
+        items.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(items)
+    }
+
+    /// Get a specific reading item
+    pub fn get_reading_item(&self, notebook_id: Uuid, item_id: Uuid) -> Result<ReadingItem> {
+        let path = self.reading_path(notebook_id, item_id);
+        if !path.exists() {
+            return Err(FlashcardStorageError::ReadingItemNotFound(item_id));
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let item: ReadingItem = serde_json::from_str(&content)?;
+        Ok(item)
+    }
+
+    /// Save a reading item, creating it if it is new
+    pub fn save_reading_item(&self, notebook_id: Uuid, item: &ReadingItem) -> Result<()> {
+        fs::create_dir_all(self.reading_dir(notebook_id))?;
+        let path = self.reading_path(notebook_id, item.id);
+        fs::write(&path, serde_json::to_string_pretty(item)?)?;
+        Ok(())
+    }
+
+    /// Delete a reading item
+    pub fn delete_reading_item(&self, notebook_id: Uuid, item_id: Uuid) -> Result<()> {
+        let path = self.reading_path(notebook_id, item_id);
+        if !path.exists() {
+            return Err(FlashcardStorageError::ReadingItemNotFound(item_id));
+        }
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// Grade a re-reading with a UI rating (1-4) and schedule the next one.
+    /// A new `priority` applies from this grading on; `done` retires the item.
+    pub fn grade_reading_item(
+        &self,
+        notebook_id: Uuid,
+        item_id: Uuid,
+        rating: i32,
+        priority: Option<u8>,
+        done: bool,
+    ) -> Result<ReadingItem> {
+        let mut item = self.get_reading_item(notebook_id, item_id)?;
+        if let Some(priority) = priority {
+            item.priority = priority.clamp(1, 5);
+        }
+
+        let ReviewResult {
+            interval,
+            ease_factor,
+            due_date,
+            status,
+        } = calculate_next_reading(&item.state, rating, item.priority);
+
+        item.state.interval = interval;
+        item.state.ease_factor = ease_factor;
+        item.state.due_date = due_date;
+        item.state.status = status;
+        item.state.review_count += 1;
+        if ui_rating_to_quality(rating) >= 3 {
+            item.state.correct_count += 1;
+        }
+        item.done = done;
+        item.updated_at = Utc::now();
+
+        self.save_reading_item(notebook_id, &item)?;
+        Ok(item)
+    }
+
+    /// Due cards (optionally of one deck) mixed with due reading items, one
+    /// reading item after every `cards_per_reading` cards
+    pub fn get_review_queue(
+        &self,
+        notebook_id: Uuid,
+        deck_id: Option<Uuid>,
+        cards_per_reading: usize,
+    ) -> Result<Vec<ReviewQueueItem>> {
+        let cards = self.get_due_cards(notebook_id, deck_id)?;
+        let reading = self
+            .list_reading_items(notebook_id)?
+            .into_iter()
+            .filter(|item| !item.done && item.state.is_due())
+            .collect();
+        Ok(mix_review_queue(cards, reading, cards_per_reading))
+    }
 }
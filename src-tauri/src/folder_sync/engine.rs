@@ -0,0 +1,729 @@
+//! One sync pass between a notebook and its folder.
+//!
+//! Each page/file pair is compared against the hashes recorded at the last
+//! sync, which tells which side changed:
+//!
+//! | page      | file      | result                                          |
+//! |-----------|-----------|-------------------------------------------------|
+//! | unchanged | unchanged | nothing                                         |
+//! | changed   | unchanged | file rewritten (moved if renamed in Nous)       |
+//! | unchanged | changed   | page re-imported from the file                  |
+//! | changed   | changed   | conflict: file edits saved as a conflict copy,  |
+//! |           |           | which becomes a new page; Nous version is kept  |
+//! | unchanged | deleted   | page moved to trash                             |
+//! | changed   | deleted   | file recreated                                  |
+//! | trashed   | unchanged | file deleted                                    |
+//! | trashed   | changed   | file kept and imported again as a new page      |
+//!
+//! A file that disappears while an untracked file with exactly its last
+//! synced content shows up is treated as a rename or move on disk: the page
+//! follows it into the matching folder. New files become new pages (creating
+//! folders for subdirectories) and new pages become new files. Hidden files
+//! and directories (`.obsidian`, `.git`, …) are ignored.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::models::{
+    FolderSyncConflict, FolderSyncReport, FolderSyncState, FolderSyncStatus, SyncEntry,
+};
+use crate::markdown::mirror::{folder_chain, prune_empty_dirs, resolve, sanitize_name};
+use crate::markdown::{export_page_to_markdown, import_markdown_to_page};
+use crate::storage::{
+    atomic, FileStorage, FileStorageWriter, Folder, Page, PageType, StorageError,
+};
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+fn hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Run one sync pass for a notebook with folder sync set up
+pub fn sync_notebook_folder(
+    storage: &FileStorageWriter,
+    notebook_id: Uuid,
+) -> Result<FolderSyncReport> {
+    let notebook_dir = storage.get_notebook_path(notebook_id);
+    let mut state = FolderSyncState::load(&notebook_dir).ok_or_else(|| {
+        StorageError::NotFound(format!(
+            "Folder sync is not set up for notebook {}",
+            notebook_id
+        ))
+    })?;
+    if !state.enabled {
+        return Err(StorageError::InvalidOperation(
+            "Folder sync is turned off for this notebook".to_string(),
+        ));
+    }
+    let root = state.folder();
+    fs::create_dir_all(&root)?;
+
+    let mut pass = Pass {
+        storage,
+        notebook_id,
+        root: &root,
+        files: scan_folder(&root)?,
+        folders: storage
+            .list_folders(notebook_id)?
+            .into_iter()
+            .map(|f| (f.id, f))
+            .collect(),
+        claimed: HashSet::new(),
+        report: FolderSyncReport {
+            notebook_id,
+            ..Default::default()
+        },
+    };
+
+    let mut pages: HashMap<Uuid, Page> = storage
+        .list_pages(notebook_id)?
+        .into_iter()
+        .filter(|p| p.page_type == PageType::Standard)
+        .map(|p| (p.id, p))
+        .collect();
+
+    let previous = std::mem::take(&mut state.entries);
+    pass.claimed
+        .extend(pass.files.keys().map(|p| p.to_lowercase()));
+    pass.claimed
+        .extend(previous.values().map(|e| e.path.to_lowercase()));
+
+    let moves = detect_disk_moves(&previous, &pass.files);
+    let mut entries = HashMap::new();
+
+    // 1. Pages that already have a file
+    for (page_id, mut entry) in previous {
+        if let Some(new_path) = moves.get(&page_id) {
+            entry.path = new_path.clone();
+        }
+        let file = pass.files.get(&entry.path).cloned();
+        let result = match pages.remove(&page_id) {
+            Some(page) if page.deleted_at.is_none() => {
+                pass.sync_pair(page, entry, file, moves.contains_key(&page_id))
+            }
+            _ => pass.page_removed(entry, file),
+        };
+        match result {
+            Ok(Some(entry)) => {
+                entries.insert(page_id, entry);
+            }
+            Ok(None) => {}
+            Err((entry, e)) => {
+                pass.report.errors.push(format!("{}: {}", entry.path, e));
+                entries.insert(page_id, entry);
+            }
+        }
+    }
+
+    // 2. Pages without a file yet
+    let mut new_pages: Vec<Page> = pages
+        .into_values()
+        .filter(|p| p.deleted_at.is_none())
+        .collect();
+    // Oldest first, so the oldest of two same-titled pages keeps the plain name
+    new_pages.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    for page in new_pages {
+        let path = pass.allocate_path(&page);
+        match pass.write_file(&path, &export_page_to_markdown(&page)) {
+            Ok(file_hash) => {
+                entries.insert(page.id, entry_for(&page, path, file_hash));
+            }
+            Err(e) => pass.report.errors.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    // 3. Files without a page yet
+    let tracked: HashSet<String> = entries.values().map(|e| e.path.clone()).collect();
+    let mut new_files: Vec<(String, String)> = pass
+        .files
+        .iter()
+        .filter(|(path, _)| !tracked.contains(*path))
+        .map(|(path, content)| (path.clone(), content.clone()))
+        .collect();
+    new_files.sort();
+    for (path, content) in new_files {
+        match pass.import_new_file(&path, &content) {
+            Ok(page) => {
+                pass.report.pages_created.push(page.id);
+                entries.insert(page.id, entry_for(&page, path, hash(&content)));
+            }
+            Err(e) => pass.report.errors.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    state.entries = entries;
+    state.last_sync = Some(Utc::now());
+    state.save(&notebook_dir)?;
+
+    Ok(pass.report)
+}
+
+/// Back a notebook with a folder of Markdown files and run the first pass
+///
+/// The first pass merges both sides: existing files become pages and
+/// existing pages become files. The folder must be absolute, outside the
+/// library, and not used by another notebook.
+pub fn enable_folder_sync(
+    storage: &FileStorageWriter,
+    notebook_id: Uuid,
+    path: &str,
+) -> Result<FolderSyncReport> {
+    let folder = PathBuf::from(path.trim());
+    let invalid = |message: &str| Err(StorageError::InvalidOperation(message.to_string()));
+    if !folder.is_absolute() {
+        return invalid("Sync folder must be an absolute path");
+    }
+    let library_dir = storage.notebooks_base_dir();
+    let library_dir = library_dir.parent().unwrap_or(&library_dir);
+    if folder.starts_with(library_dir) || library_dir.starts_with(&folder) {
+        return invalid("Sync folder must be outside the library folder");
+    }
+    for notebook in storage.list_notebooks()? {
+        if notebook.id == notebook_id {
+            continue;
+        }
+        if let Some(other) = FolderSyncState::load(&storage.get_notebook_path(notebook.id)) {
+            let other = other.folder();
+            if folder.starts_with(&other) || other.starts_with(&folder) {
+                return invalid(&format!(
+                    "Folder is already synced with notebook '{}'",
+                    notebook.name
+                ));
+            }
+        }
+    }
+
+    let notebook_dir = storage.get_notebook_path(notebook_id);
+    if !notebook_dir.exists() {
+        return Err(StorageError::NotebookNotFound(notebook_id));
+    }
+    let state = match FolderSyncState::load(&notebook_dir) {
+        // Same folder again: keep the sync history
+        Some(existing) if existing.folder() == folder => FolderSyncState {
+            enabled: true,
+            ..existing
+        },
+        _ => FolderSyncState::new(folder.to_string_lossy().to_string()),
+    };
+    state.save(&notebook_dir)?;
+
+    sync_notebook_folder(storage, notebook_id)
+}
+
+/// Stop syncing a notebook with its folder; pages and files are both kept
+///
+/// The sync history is kept too, so enabling the same folder again picks up
+/// where it left off instead of importing every file as a duplicate.
+pub fn disable_folder_sync(storage: &FileStorageWriter, notebook_id: Uuid) -> Result<()> {
+    let notebook_dir = storage.get_notebook_path(notebook_id);
+    match FolderSyncState::load(&notebook_dir) {
+        Some(state) => FolderSyncState {
+            enabled: false,
+            ..state
+        }
+        .save(&notebook_dir),
+        None => Ok(()),
+    }
+}
+
+/// Folder sync status for a notebook, or `None` if it isn't folder-backed
+pub fn folder_sync_status(storage: &FileStorage, notebook_id: Uuid) -> Option<FolderSyncStatus> {
+    FolderSyncState::load(&storage.get_notebook_path(notebook_id))
+        .map(|state| FolderSyncStatus::new(notebook_id, &state))
+}
+
+/// Sync entry for a page whose file was just written or imported
+fn entry_for(page: &Page, path: String, file_hash: String) -> SyncEntry {
+    SyncEntry {
+        path,
+        page_hash: hash(&export_page_to_markdown(page)),
+        file_hash,
+        title: page.title.clone(),
+        folder_id: page.folder_id,
+    }
+}
+
+/// Tracked files that vanished while an untracked file with the same content
+/// appeared: page id → new path
+fn detect_disk_moves(
+    entries: &HashMap<Uuid, SyncEntry>,
+    files: &HashMap<String, String>,
+) -> HashMap<Uuid, String> {
+    let tracked: HashSet<&str> = entries.values().map(|e| e.path.as_str()).collect();
+    let mut untracked: HashMap<String, Vec<&str>> = HashMap::new();
+    for (path, content) in files {
+        if !tracked.contains(path.as_str()) {
+            untracked.entry(hash(content)).or_default().push(path);
+        }
+    }
+
+    let mut moves = HashMap::new();
+    for (page_id, entry) in entries {
+        if files.contains_key(&entry.path) {
+            continue;
+        }
+        if let Some(candidates) = untracked.get_mut(&entry.file_hash) {
+            // Several identical new files: the rename is ambiguous, treat
+            // them all as new files instead
+            if candidates.len() == 1 {
+                moves.insert(*page_id, candidates.remove(0).to_string());
+            }
+        }
+    }
+    moves
+}
+
+type PairResult = std::result::Result<Option<SyncEntry>, (SyncEntry, StorageError)>;
+
+struct Pass<'a> {
+    storage: &'a FileStorageWriter,
+    notebook_id: Uuid,
+    root: &'a Path,
+    /// Markdown files in the folder: relative path → contents
+    files: HashMap<String, String>,
+    folders: HashMap<Uuid, Folder>,
+    /// Lowercased paths in use, for allocating new file names
+    claimed: HashSet<String>,
+    report: FolderSyncReport,
+}
+
+impl Pass<'_> {
+    /// Reconcile a live page with its (possibly missing) file
+    fn sync_pair(
+        &mut self,
+        mut page: Page,
+        entry: SyncEntry,
+        file: Option<String>,
+        moved_on_disk: bool,
+    ) -> PairResult {
+        let page_changed = hash(&export_page_to_markdown(&page)) != entry.page_hash;
+        let relocated_in_nous = page.title != entry.title || page.folder_id != entry.folder_id;
+
+        let Some(content) = file else {
+            if !page_changed && !relocated_in_nous {
+                // Deleted on disk
+                self.storage
+                    .delete_page(self.notebook_id, page.id)
+                    .map_err(|e| (entry.clone(), e))?;
+                self.report.pages_trashed.push(page.id);
+                return Ok(None);
+            }
+            // Deleted on disk but edited in Nous: the edits win
+            self.claimed.remove(&entry.path.to_lowercase());
+            let path = self.allocate_path(&page);
+            let file_hash = self
+                .write_file(&path, &export_page_to_markdown(&page))
+                .map_err(|e| (entry.clone(), e))?;
+            return Ok(Some(entry_for(&page, path, file_hash)));
+        };
+
+        let file_hash = hash(&content);
+        let file_changed = file_hash != entry.file_hash;
+
+        if moved_on_disk {
+            // Follow a rename/move made on disk into the matching folder
+            let folder_id = self
+                .folder_for_path(&entry.path)
+                .map_err(|e| (entry.clone(), e))?;
+            if page.folder_id != folder_id {
+                self.storage
+                    .move_page_to_folder(self.notebook_id, page.id, folder_id, None)
+                    .and_then(|moved| self.storage.get_page(self.notebook_id, moved.id))
+                    .map(|moved| page = moved)
+                    .map_err(|e| (entry.clone(), e))?;
+            }
+            // Keep a pending Nous edit or rename pending; only the folder
+            // is settled by the move
+            let page_hash = if page_changed {
+                entry.page_hash.clone()
+            } else {
+                hash(&export_page_to_markdown(&page))
+            };
+            let entry = SyncEntry {
+                page_hash,
+                folder_id: page.folder_id,
+                ..entry
+            };
+            return self.sync_pair(page, entry, Some(content), false);
+        }
+
+        match (page_changed, file_changed) {
+            (false, false) if !relocated_in_nous => Ok(Some(entry)),
+            (_, false) => {
+                let path = if relocated_in_nous {
+                    self.relocate(&entry.path, &page)
+                } else {
+                    entry.path.clone()
+                };
+                let file_hash = self
+                    .write_file(&path, &export_page_to_markdown(&page))
+                    .map_err(|e| (entry.clone(), e))?;
+                Ok(Some(entry_for(&page, path, file_hash)))
+            }
+            (false, true) => {
+                apply_file(&mut page, &content, &file_stem(&entry.path));
+                // Hash what was stored, not what was passed in: saving can
+                // normalize the content (BlockNote pages are converted)
+                let page = self
+                    .storage
+                    .update_page(&page)
+                    .and_then(|()| self.storage.get_page(self.notebook_id, page.id))
+                    .map_err(|e| (entry.clone(), e))?;
+                self.report.pages_updated.push(page.id);
+                // The file stays where it is; its title now follows the file
+                Ok(Some(entry_for(&page, entry.path.clone(), file_hash)))
+            }
+            (true, true) => {
+                let conflict = self.conflict_path(&entry.path);
+                self.write_file(&conflict, &content)
+                    .map_err(|e| (entry.clone(), e))?;
+                // Picked up as a new file (and so a new page) by this pass
+                self.files.insert(conflict.clone(), content);
+                self.report.conflicts.push(FolderSyncConflict {
+                    page_id: page.id,
+                    title: page.title.clone(),
+                    conflict_file: conflict,
+                });
+
+                let path = if relocated_in_nous {
+                    self.relocate(&entry.path, &page)
+                } else {
+                    entry.path.clone()
+                };
+                let file_hash = self
+                    .write_file(&path, &export_page_to_markdown(&page))
+                    .map_err(|e| (entry.clone(), e))?;
+                Ok(Some(entry_for(&page, path, file_hash)))
+            }
+        }
+    }
+
+    /// A tracked page was trashed or deleted in Nous
+    fn page_removed(&mut self, entry: SyncEntry, file: Option<String>) -> PairResult {
+        match file {
+            None => Ok(None),
+            Some(content) if hash(&content) == entry.file_hash => {
+                self.remove_file(&entry.path)
+                    .map_err(|e| (entry.clone(), e))?;
+                self.report.files_deleted += 1;
+                Ok(None)
+            }
+            // Edited on disk after the page was deleted: keep the edits by
+            // leaving the file untracked, so it is imported as a new page
+            Some(_) => Ok(None),
+        }
+    }
+
+    /// Create a page for a file that has none
+    fn import_new_file(&mut self, path: &str, content: &str) -> Result<Page> {
+        let mut page = import_markdown_to_page(content, self.notebook_id, &file_stem(path));
+        page.folder_id = self.folder_for_path(path)?;
+        if let Some(folder) = page.folder_id.and_then(|id| self.folders.get(&id)) {
+            page.section_id = folder.section_id;
+        }
+        let page = self.storage.create_page_from(page)?;
+        self.storage.get_page(self.notebook_id, page.id)
+    }
+
+    /// The folder matching a file's directory, created if it doesn't exist
+    fn folder_for_path(&mut self, path: &str) -> Result<Option<Uuid>> {
+        let mut parent: Option<Uuid> = None;
+        let dirs: Vec<&str> = path.split('/').collect();
+        for name in &dirs[..dirs.len().saturating_sub(1)] {
+            let existing = self
+                .folders
+                .values()
+                .filter(|f| f.parent_id == parent && !f.is_archived)
+                .find(|f| sanitize_name(&f.name).eq_ignore_ascii_case(name))
+                .map(|f| f.id);
+            parent = Some(match existing {
+                Some(id) => id,
+                None => {
+                    let folder =
+                        self.storage
+                            .create_folder(self.notebook_id, name.to_string(), parent)?;
+                    let id = folder.id;
+                    self.folders.insert(id, folder);
+                    id
+                }
+            });
+        }
+        Ok(parent)
+    }
+
+    /// Pick an unused path for a page, from its folder and title
+    fn allocate_path(&mut self, page: &Page) -> String {
+        let mut dir: Vec<String> = folder_chain(page, &self.folders)
+            .iter()
+            .map(|name| sanitize_name(name))
+            .collect();
+        let name = sanitize_name(&page.title);
+        dir.push(String::new());
+        let prefix = dir.join("/");
+
+        let mut candidate = format!("{}{}.md", prefix, name);
+        let mut n = 2;
+        while !self.claimed.insert(candidate.to_lowercase()) {
+            candidate = format!("{}{} ({}).md", prefix, name, n);
+            n += 1;
+        }
+        candidate
+    }
+
+    /// Move a page's file to match a rename or move made in Nous
+    fn relocate(&mut self, old_path: &str, page: &Page) -> String {
+        self.claimed.remove(&old_path.to_lowercase());
+        let path = self.allocate_path(page);
+        if path != old_path {
+            if let Err(e) = self.remove_file(old_path) {
+                self.report.errors.push(format!("{}: {}", old_path, e));
+            }
+        }
+        path
+    }
+
+    /// A free name next to `path` for the on-disk side of a conflict
+    fn conflict_path(&mut self, path: &str) -> String {
+        let stamp = Utc::now().format("%Y-%m-%d %H%M%S");
+        let (dir, stem) = match path.rfind('/') {
+            Some(i) => (&path[..=i], file_stem(&path[i + 1..])),
+            None => ("", file_stem(path)),
+        };
+        let mut candidate = format!("{}{} (conflict {}).md", dir, stem, stamp);
+        let mut n = 2;
+        while !self.claimed.insert(candidate.to_lowercase()) {
+            candidate = format!("{}{} (conflict {} {}).md", dir, stem, stamp, n);
+            n += 1;
+        }
+        candidate
+    }
+
+    /// Write a file in the folder, returning the hash of what was written
+    fn write_file(&mut self, relative: &str, content: &str) -> Result<String> {
+        let path = resolve(self.root, relative).ok_or_else(|| {
+            StorageError::InvalidOperation(format!("Path escapes the sync folder: {}", relative))
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic::write_str(&path, content)?;
+        self.report.files_written += 1;
+        Ok(hash(content))
+    }
+
+    fn remove_file(&mut self, relative: &str) -> Result<()> {
+        let Some(path) = resolve(self.root, relative) else {
+            return Ok(());
+        };
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        self.files.remove(relative);
+        self.claimed.remove(&relative.to_lowercase());
+        prune_empty_dirs(&path, self.root);
+        Ok(())
+    }
+}
+
+/// Replace a page's title, tags, properties and content with a file's
+fn apply_file(page: &mut Page, content: &str, fallback_title: &str) {
+    let parsed = import_markdown_to_page(content, page.notebook_id, fallback_title);
+    page.title = parsed.title;
+    page.tags = parsed.tags;
+    page.properties = parsed.properties;
+    // Keep the page's content version so BlockNote pages stay BlockNote
+    page.content.time = parsed.content.time;
+    page.content.blocks = parsed.content.blocks;
+    page.updated_at = Utc::now();
+}
+
+/// File name without directory or `.md` extension
+fn file_stem(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.len().checked_sub(3) {
+        Some(i) if name.is_char_boundary(i) && name[i..].eq_ignore_ascii_case(".md") => {
+            name[..i].to_string()
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Read every Markdown file under `root`, keyed by `/`-separated relative path
+fn scan_folder(root: &Path) -> Result<HashMap<String, String>> {
+    let mut files = HashMap::new();
+    let mut stack = vec![(root.to_path_buf(), String::new())];
+    while let Some((dir, prefix)) = stack.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            let relative = format!("{}{}", prefix, name);
+            if file_type.is_dir() {
+                stack.push((entry.path(), format!("{}/", relative)));
+            } else if file_type.is_file() && file_stem(&name) != name {
+                match fs::read_to_string(entry.path()) {
+                    Ok(content) => {
+                        files.insert(relative, content);
+                    }
+                    Err(e) => {
+                        log::warn!("Folder sync: skipping unreadable {:?}: {}", entry.path(), e)
+                    }
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::NotebookType;
+
+    struct Fixture {
+        _library: tempfile::TempDir,
+        folder: tempfile::TempDir,
+        storage: FileStorageWriter,
+        notebook_id: Uuid,
+    }
+
+    fn fixture() -> Fixture {
+        let library = tempfile::tempdir().unwrap();
+        let folder = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(library.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Vault".to_string(), NotebookType::Standard)
+            .unwrap();
+        FolderSyncState::new(folder.path().to_string_lossy().to_string())
+            .save(&storage.get_notebook_path(notebook.id))
+            .unwrap();
+        Fixture {
+            _library: library,
+            folder,
+            storage,
+            notebook_id: notebook.id,
+        }
+    }
+
+    #[test]
+    fn edits_flow_both_ways() {
+        let f = fixture();
+        let page = f
+            .storage
+            .create_page(f.notebook_id, "Ideas".to_string())
+            .unwrap();
+        fs::create_dir_all(f.folder.path().join("Projects")).unwrap();
+        fs::write(
+            f.folder.path().join("Projects/Launch.md"),
+            "# Launch\n\nShip it.\n",
+        )
+        .unwrap();
+
+        let report = sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+        assert_eq!(report.files_written, 1);
+        assert_eq!(report.pages_created.len(), 1);
+        assert!(f.folder.path().join("Ideas.md").is_file());
+        let launch = f
+            .storage
+            .get_page(f.notebook_id, report.pages_created[0])
+            .unwrap();
+        let folder = f
+            .storage
+            .get_folder(f.notebook_id, launch.folder_id.unwrap())
+            .unwrap();
+        assert_eq!(folder.name, "Projects");
+
+        // Nothing changed: nothing to do
+        let report = sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+        assert_eq!(report.files_written, 0);
+        assert!(!report.changed_pages());
+
+        // External edit is re-imported into the same page
+        fs::write(
+            f.folder.path().join("Ideas.md"),
+            "---\ntitle: \"Ideas\"\n---\n\nEdited outside.\n",
+        )
+        .unwrap();
+        let report = sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+        assert_eq!(report.pages_updated, vec![page.id]);
+        let updated = f.storage.get_page(f.notebook_id, page.id).unwrap();
+        assert!(export_page_to_markdown(&updated).contains("Edited outside."));
+
+        // Deleting the file trashes the page
+        fs::remove_file(f.folder.path().join("Ideas.md")).unwrap();
+        let report = sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+        assert_eq!(report.pages_trashed, vec![page.id]);
+    }
+
+    #[test]
+    fn edits_on_both_sides_keep_both_versions() {
+        let f = fixture();
+        let page = f
+            .storage
+            .create_page(f.notebook_id, "Draft".to_string())
+            .unwrap();
+        sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+
+        fs::write(f.folder.path().join("Draft.md"), "Disk version\n").unwrap();
+        let mut edited = f.storage.get_page(f.notebook_id, page.id).unwrap();
+        edited.tags.push("nous".to_string());
+        edited.updated_at = Utc::now();
+        f.storage.update_page(&edited).unwrap();
+
+        let report = sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.pages_created.len(), 1);
+        let on_disk = fs::read_to_string(f.folder.path().join("Draft.md")).unwrap();
+        assert!(on_disk.contains("nous"));
+        let conflict = f.folder.path().join(&report.conflicts[0].conflict_file);
+        assert_eq!(fs::read_to_string(conflict).unwrap(), "Disk version\n");
+    }
+
+    #[test]
+    fn renames_on_disk_are_followed() {
+        let f = fixture();
+        let page = f
+            .storage
+            .create_page(f.notebook_id, "Note".to_string())
+            .unwrap();
+        sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+
+        fs::create_dir_all(f.folder.path().join("Archive")).unwrap();
+        fs::rename(
+            f.folder.path().join("Note.md"),
+            f.folder.path().join("Archive/Note.md"),
+        )
+        .unwrap();
+
+        let report = sync_notebook_folder(&f.storage, f.notebook_id).unwrap();
+        assert!(report.pages_created.is_empty());
+        assert!(report.pages_trashed.is_empty());
+        let moved = f.storage.get_page(f.notebook_id, page.id).unwrap();
+        let folder = f
+            .storage
+            .get_folder(f.notebook_id, moved.folder_id.unwrap())
+            .unwrap();
+        assert_eq!(folder.name, "Archive");
+        assert!(!f.folder.path().join("Note.md").exists());
+    }
+
+    #[test]
+    fn file_stems() {
+        assert_eq!(file_stem("a/b/Notes.md"), "Notes");
+        assert_eq!(file_stem("README.MD"), "README");
+        assert_eq!(file_stem("photo.png"), "photo.png");
+        assert_eq!(file_stem("é.md"), "é");
+    }
+}
@@ -0,0 +1,30 @@
+//! Folder Sync Module
+//!
+//! Backs a notebook with a folder of Markdown files, Obsidian-style: pages
+//! edited in Nous are written out as `.md` files, and files edited, added,
+//! moved or deleted on disk are brought back into the notebook. Edits made
+//! on both sides since the last sync are a conflict; the Nous version is
+//! kept and the on-disk version is saved next to it as a conflict copy, so
+//! nothing is lost.
+//!
+//! Subdirectories map to folders. Only standard pages are synced; PDFs,
+//! canvases and other page types stay in the library only. Sync state lives
+//! in `folder-sync.json` in the notebook directory.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let report = folder_sync::enable_folder_sync(&storage, notebook_id, "/home/me/vault")?;
+//! // later, from the background watcher or on demand
+//! let report = folder_sync::sync_notebook_folder(&storage, notebook_id)?;
+//! ```
+
+mod engine;
+mod models;
+mod watcher;
+
+pub use engine::{
+    disable_folder_sync, enable_folder_sync, folder_sync_status, sync_notebook_folder,
+};
+pub use models::{FolderSyncConflict, FolderSyncReport, FolderSyncStatus};
+pub use watcher::{start, FolderSyncWatcher};
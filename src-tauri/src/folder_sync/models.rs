@@ -0,0 +1,151 @@
+//! Folder sync data models
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::{atomic, StorageError};
+
+/// State file kept in the notebook directory
+pub const STATE_FILE: &str = "folder-sync.json";
+
+/// Per-notebook folder sync settings and bookkeeping
+///
+/// `entries` records, for every page that has a file on disk, where the file
+/// is and the hashes of both sides as of the last sync. A side whose current
+/// hash differs from its recorded hash has changed since then.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncState {
+    /// Folder of Markdown files backing the notebook
+    pub path: String,
+    /// Whether the watcher keeps the folder in sync
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// When the last sync pass finished
+    #[serde(default)]
+    pub last_sync: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub entries: HashMap<Uuid, SyncEntry>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Last-synced state of one page/file pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEntry {
+    /// File path relative to the folder, `/`-separated
+    pub path: String,
+    /// SHA-256 of the page rendered as Markdown
+    pub page_hash: String,
+    /// SHA-256 of the file contents
+    pub file_hash: String,
+    /// Page title and folder when the file was last placed, so renames and
+    /// moves made in Nous can be told apart from ones made on disk
+    pub title: String,
+    #[serde(default)]
+    pub folder_id: Option<Uuid>,
+}
+
+impl FolderSyncState {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    /// The backing folder
+    pub fn folder(&self) -> PathBuf {
+        PathBuf::from(&self.path)
+    }
+
+    /// Load the state for a notebook, if folder sync is set up for it
+    pub fn load(notebook_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(notebook_dir.join(STATE_FILE)).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                log::warn!("Folder sync: unreadable state in {:?}: {}", notebook_dir, e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, notebook_dir: &Path) -> Result<(), StorageError> {
+        let content = serde_json::to_string_pretty(self)?;
+        atomic::write_str(&notebook_dir.join(STATE_FILE), &content)?;
+        Ok(())
+    }
+}
+
+/// A page edited on both sides since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncConflict {
+    pub page_id: Uuid,
+    pub title: String,
+    /// The file the on-disk edits were saved to (relative to the folder)
+    pub conflict_file: String,
+}
+
+/// What one sync pass did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncReport {
+    pub notebook_id: Uuid,
+    /// Files written from pages edited in Nous
+    pub files_written: usize,
+    /// Pages updated from files edited on disk
+    pub pages_updated: Vec<Uuid>,
+    /// Pages created from new files
+    pub pages_created: Vec<Uuid>,
+    /// Pages moved to trash because their file was deleted
+    pub pages_trashed: Vec<Uuid>,
+    /// Files deleted because their page was deleted
+    pub files_deleted: usize,
+    pub conflicts: Vec<FolderSyncConflict>,
+    /// Files or pages that could not be synced
+    pub errors: Vec<String>,
+}
+
+impl FolderSyncReport {
+    /// Whether the pass changed any pages (so search etc. need refreshing)
+    pub fn changed_pages(&self) -> bool {
+        !self.pages_updated.is_empty()
+            || !self.pages_created.is_empty()
+            || !self.pages_trashed.is_empty()
+    }
+}
+
+/// Folder sync status for one notebook, as returned to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncStatus {
+    pub notebook_id: Uuid,
+    pub path: String,
+    pub enabled: bool,
+    pub last_sync: Option<DateTime<Utc>>,
+    /// Number of pages with a backing file
+    pub tracked_pages: usize,
+}
+
+impl FolderSyncStatus {
+    pub fn new(notebook_id: Uuid, state: &FolderSyncState) -> Self {
+        Self {
+            notebook_id,
+            path: state.path.clone(),
+            enabled: state.enabled,
+            last_sync: state.last_sync,
+            tracked_pages: state.entries.len(),
+        }
+    }
+}
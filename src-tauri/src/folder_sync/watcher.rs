@@ -0,0 +1,214 @@
+//! Background thread that keeps folder-backed notebooks in sync.
+//!
+//! Edits on disk are picked up through an OS file watcher (`notify`); edits
+//! made in Nous arrive through [`FolderSyncWatcher::notebook_changed`]. Either
+//! marks the notebook dirty, and a sync pass runs once the notebook has been
+//! quiet for [`DEBOUNCE`] so a burst of saves (or an editor writing a file in
+//! several steps) is handled in one pass. Every [`RESCAN_INTERVAL`] the set
+//! of folder-backed notebooks is reloaded and each one gets a pass anyway,
+//! which catches anything the OS watcher missed.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use uuid::Uuid;
+
+use super::engine::sync_notebook_folder;
+use super::models::{FolderSyncReport, FolderSyncState};
+use crate::storage::{FileStorage, StorageLock};
+
+/// How long a notebook must be quiet before it is synced
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// How often folder-backed notebooks are reloaded and fully re-synced
+const RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+enum Signal {
+    /// Something changed under a watched folder
+    Path(PathBuf),
+    /// A page in this notebook was changed in Nous
+    Notebook(Uuid),
+    /// Folder sync was turned on or off somewhere
+    Reload,
+}
+
+/// Handle to the folder sync thread; dropping it stops the thread
+pub struct FolderSyncWatcher {
+    stop: Arc<AtomicBool>,
+    signals: mpsc::Sender<Signal>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FolderSyncWatcher {
+    /// Tell the watcher a page in `notebook_id` was created, edited or deleted
+    pub fn notebook_changed(&self, notebook_id: Uuid) {
+        let _ = self.signals.send(Signal::Notebook(notebook_id));
+    }
+
+    /// Reload which notebooks are folder-backed (after enabling or disabling)
+    pub fn reload(&self) {
+        let _ = self.signals.send(Signal::Reload);
+    }
+}
+
+impl Drop for FolderSyncWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Start syncing every folder-backed notebook. `on_sync` is called with the
+/// report of each pass that changed something.
+pub fn start<F>(storage: Arc<StorageLock>, on_sync: F) -> FolderSyncWatcher
+where
+    F: Fn(FolderSyncReport) + Send + 'static,
+{
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = Arc::clone(&stop);
+    let (tx, rx) = mpsc::channel::<Signal>();
+    let fs_tx = tx.clone();
+
+    let thread = std::thread::Builder::new()
+        .name("folder-sync".to_string())
+        .spawn(move || {
+            let mut watcher = match RecommendedWatcher::new(
+                move |res: Result<notify::Event, notify::Error>| {
+                    if let Ok(event) = res {
+                        if !event.kind.is_access() {
+                            for path in event.paths {
+                                let _ = fs_tx.send(Signal::Path(path));
+                            }
+                        }
+                    }
+                },
+                Config::default(),
+            ) {
+                Ok(w) => Some(w),
+                Err(e) => {
+                    // Still works via periodic rescans, just with more latency
+                    log::warn!("Folder sync: OS file watcher unavailable: {}", e);
+                    None
+                }
+            };
+
+            // notebook → backing folder
+            let mut watched: HashMap<Uuid, PathBuf> = HashMap::new();
+            // notebook → last change seen
+            let mut dirty: HashMap<Uuid, Instant> = HashMap::new();
+            let mut last_rescan: Option<Instant> = None;
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                match rx.recv_timeout(Duration::from_millis(250)) {
+                    Ok(Signal::Path(path)) => {
+                        if let Some((id, _)) = watched.iter().find(|(_, dir)| path.starts_with(dir))
+                        {
+                            dirty.insert(*id, Instant::now());
+                        }
+                    }
+                    Ok(Signal::Notebook(id)) => {
+                        if watched.contains_key(&id) {
+                            dirty.insert(id, Instant::now());
+                        }
+                    }
+                    Ok(Signal::Reload) => last_rescan = None,
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                if !matches!(last_rescan, Some(t) if t.elapsed() < RESCAN_INTERVAL) {
+                    last_rescan = Some(Instant::now());
+                    let current = match storage.read() {
+                        Ok(s) => folder_backed_notebooks(&s),
+                        Err(_) => continue,
+                    };
+                    if let Some(watcher) = watcher.as_mut() {
+                        for (id, dir) in &watched {
+                            if current.get(id) != Some(dir) {
+                                let _ = watcher.unwatch(dir);
+                            }
+                        }
+                        for (id, dir) in &current {
+                            if watched.get(id) != Some(dir) {
+                                let _ = std::fs::create_dir_all(dir);
+                                if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                                    log::warn!("Folder sync: cannot watch {:?}: {}", dir, e);
+                                }
+                            }
+                        }
+                    }
+                    watched = current;
+                    dirty.retain(|id, _| watched.contains_key(id));
+                    for id in watched.keys() {
+                        dirty.entry(*id).or_insert_with(Instant::now);
+                    }
+                }
+
+                let ready: Vec<Uuid> = dirty
+                    .iter()
+                    .filter(|(_, since)| since.elapsed() >= DEBOUNCE)
+                    .map(|(id, _)| *id)
+                    .collect();
+                for notebook_id in ready {
+                    dirty.remove(&notebook_id);
+                    let result = match storage.write() {
+                        Ok(s) => sync_notebook_folder(&s, notebook_id),
+                        Err(_) => continue,
+                    };
+                    match result {
+                        Ok(report) => {
+                            for error in &report.errors {
+                                log::warn!("Folder sync for {}: {}", notebook_id, error);
+                            }
+                            if report.changed_pages()
+                                || report.files_written > 0
+                                || report.files_deleted > 0
+                            {
+                                log::info!(
+                                    "Folder sync for {}: {} written, {} updated, {} created, {} trashed, {} conflicts",
+                                    notebook_id,
+                                    report.files_written,
+                                    report.pages_updated.len(),
+                                    report.pages_created.len(),
+                                    report.pages_trashed.len(),
+                                    report.conflicts.len()
+                                );
+                                on_sync(report);
+                            }
+                        }
+                        Err(e) => log::warn!("Folder sync for {} failed: {}", notebook_id, e),
+                    }
+                }
+            }
+
+            drop(watcher);
+        })
+        .expect("failed to spawn folder-sync thread");
+
+    FolderSyncWatcher {
+        stop,
+        signals: tx,
+        thread: Some(thread),
+    }
+}
+
+/// Every notebook with folder sync turned on, with its folder
+fn folder_backed_notebooks(storage: &FileStorage) -> HashMap<Uuid, PathBuf> {
+    storage
+        .list_notebooks()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|nb| {
+            let state = FolderSyncState::load(&storage.get_notebook_path(nb.id))?;
+            state.enabled.then(|| (nb.id, state.folder()))
+        })
+        .collect()
+}
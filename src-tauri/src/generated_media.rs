@@ -0,0 +1,359 @@
+//! Registry of generated media
+//!
+//! Infographics and study videos are written to a notebook's `infographics/`
+//! and `videos/` asset directories. Each generation is recorded in
+//! `assets/generated_media.json` with the pages it was made from and the
+//! options it was made with, so the media can be listed per page and
+//! generated again later with the same inputs.
+//!
+//! Outputs nobody can reach any more (generator files the registry doesn't
+//! know, and media whose source pages were all deleted) are removed by
+//! [`GeneratedMediaRegistry::cleanup`].
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::StorageError;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+const REGISTRY_FILE: &str = "generated_media.json";
+
+/// What kind of media a generation produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratedMediaKind {
+    Infographic,
+    StudyVideo,
+}
+
+impl GeneratedMediaKind {
+    /// Asset subdirectory the generator writes to
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::Infographic => "infographics",
+            Self::StudyVideo => "videos",
+        }
+    }
+
+    /// Whether `file_name` looks like one of this generator's outputs
+    fn is_output_name(self, file_name: &str) -> bool {
+        match self {
+            Self::Infographic => {
+                file_name.starts_with("infographic_")
+                    && (file_name.ends_with(".svg") || file_name.ends_with(".png"))
+            }
+            Self::StudyVideo => file_name.starts_with("presentation_") && file_name.ends_with(".mp4"),
+        }
+    }
+}
+
+/// One generation of an infographic or study video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedMedia {
+    pub id: Uuid,
+    pub notebook_id: Uuid,
+    /// Pages the content was generated from; empty when not known
+    #[serde(default)]
+    pub page_ids: Vec<Uuid>,
+    pub kind: GeneratedMediaKind,
+    /// Inputs of the generation (template and data, or slides and voice),
+    /// enough to run it again. API keys are never stored.
+    pub options: serde_json::Value,
+    /// Output files, relative to the notebook's assets directory
+    pub files: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    /// The media this one was regenerated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regenerated_from: Option<Uuid>,
+}
+
+impl GeneratedMedia {
+    pub fn new(
+        notebook_id: Uuid,
+        page_ids: Vec<Uuid>,
+        kind: GeneratedMediaKind,
+        options: serde_json::Value,
+        files: Vec<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            notebook_id,
+            page_ids,
+            kind,
+            options,
+            files,
+            created_at: Utc::now(),
+            regenerated_from: None,
+        }
+    }
+}
+
+/// What a cleanup removed (or would remove, on a dry run)
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaCleanupReport {
+    /// Registry entries dropped because their pages or files are gone
+    pub removed_entries: Vec<Uuid>,
+    /// Files deleted, relative to the assets directory
+    pub removed_files: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// The generated media of one notebook
+pub struct GeneratedMediaRegistry {
+    assets_dir: PathBuf,
+    entries: Vec<GeneratedMedia>,
+}
+
+impl GeneratedMediaRegistry {
+    /// Open the registry in the notebook assets directory `assets_dir`
+    pub fn open(assets_dir: PathBuf) -> Result<Self> {
+        let path = assets_dir.join(REGISTRY_FILE);
+        let entries = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable generated media registry: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        Ok(Self { assets_dir, entries })
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::create_dir_all(&self.assets_dir)?;
+        fs::write(
+            self.assets_dir.join(REGISTRY_FILE),
+            serde_json::to_string_pretty(&self.entries)?,
+        )?;
+        Ok(())
+    }
+
+    /// Absolute path of a file recorded relative to the assets directory
+    pub fn file_path(&self, file: &str) -> PathBuf {
+        self.assets_dir.join(file)
+    }
+
+    /// `path` relative to the assets directory, if it is inside it
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        path.strip_prefix(&self.assets_dir)
+            .ok()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Media of the notebook, or only that made from `page_id`, newest first
+    pub fn list(&self, page_id: Option<Uuid>) -> Vec<GeneratedMedia> {
+        let mut list: Vec<GeneratedMedia> = self
+            .entries
+            .iter()
+            .filter(|m| page_id.map_or(true, |id| m.page_ids.contains(&id)))
+            .cloned()
+            .collect();
+        list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        list
+    }
+
+    pub fn get(&self, media_id: Uuid) -> Result<GeneratedMedia> {
+        self.entries
+            .iter()
+            .find(|m| m.id == media_id)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(format!("Generated media {}", media_id)))
+    }
+
+    /// The media an output file belongs to
+    pub fn find_by_file(&self, file: &str) -> Option<&GeneratedMedia> {
+        self.entries.iter().find(|m| m.files.iter().any(|f| f == file))
+    }
+
+    pub fn add(&mut self, media: GeneratedMedia) -> Result<GeneratedMedia> {
+        self.entries.push(media.clone());
+        self.save()?;
+        Ok(media)
+    }
+
+    /// Remove an entry and delete its files
+    pub fn remove(&mut self, media_id: Uuid) -> Result<GeneratedMedia> {
+        let index = self
+            .entries
+            .iter()
+            .position(|m| m.id == media_id)
+            .ok_or_else(|| StorageError::NotFound(format!("Generated media {}", media_id)))?;
+        let media = self.entries.remove(index);
+        for file in &media.files {
+            let path = self.file_path(file);
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Failed to remove generated media {:?}: {}", path, e);
+                }
+            }
+        }
+        self.save()?;
+        Ok(media)
+    }
+
+    /// Forget a file deleted from outside the registry; the entry goes too
+    /// once none of its files are left
+    pub fn forget_file(&mut self, file: &str) -> Result<()> {
+        if self.find_by_file(file).is_none() {
+            return Ok(());
+        }
+        for media in &mut self.entries {
+            media.files.retain(|f| f != file);
+        }
+        self.entries.retain(|m| !m.files.is_empty());
+        self.save()
+    }
+
+    /// Remove media whose source pages no longer exist (`existing_pages`),
+    /// entries whose files are all gone, and generator outputs the registry
+    /// has no entry for. With `dry_run` nothing is deleted.
+    pub fn cleanup(
+        &mut self,
+        existing_pages: &HashSet<Uuid>,
+        dry_run: bool,
+    ) -> Result<MediaCleanupReport> {
+        let mut report = MediaCleanupReport::default();
+
+        for media in &self.entries {
+            let pages_gone = !media.page_ids.is_empty()
+                && media.page_ids.iter().all(|id| !existing_pages.contains(id));
+            let files_gone = media.files.iter().all(|f| !self.file_path(f).exists());
+            if pages_gone || files_gone {
+                report.removed_entries.push(media.id);
+                report.removed_files.extend(
+                    media
+                        .files
+                        .iter()
+                        .filter(|f| self.file_path(f).exists())
+                        .cloned(),
+                );
+            }
+        }
+
+        let known: HashSet<&str> = self
+            .entries
+            .iter()
+            .filter(|m| !report.removed_entries.contains(&m.id))
+            .flat_map(|m| m.files.iter().map(String::as_str))
+            .collect();
+        let mut unknown = Vec::new();
+        for kind in [GeneratedMediaKind::Infographic, GeneratedMediaKind::StudyVideo] {
+            let Ok(dir) = fs::read_dir(self.assets_dir.join(kind.dir_name())) else {
+                continue;
+            };
+            for entry in dir.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let file = format!("{}/{}", kind.dir_name(), name);
+                if kind.is_output_name(&name) && !known.contains(file.as_str()) {
+                    unknown.push(file);
+                }
+            }
+        }
+        for file in unknown {
+            if !report.removed_files.contains(&file) {
+                report.removed_files.push(file);
+            }
+        }
+
+        for file in &report.removed_files {
+            let path = self.file_path(file);
+            report.freed_bytes += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if !dry_run {
+                if let Err(e) = fs::remove_file(&path) {
+                    log::warn!("Failed to remove orphaned media {:?}: {}", path, e);
+                }
+            }
+        }
+
+        if !dry_run && !report.removed_entries.is_empty() {
+            self.entries.retain(|m| !report.removed_entries.contains(&m.id));
+            self.save()?;
+        }
+        Ok(report)
+    }
+}
+
+/// Files in `dir` (non-recursive)
+pub fn list_dir_files(dir: &Path) -> HashSet<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, file: &str) {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"data").unwrap();
+    }
+
+    #[test]
+    fn cleanup_removes_orphans_and_keeps_live_media() {
+        let tmp = TempDir::new().unwrap();
+        let assets = tmp.path().to_path_buf();
+        let nb = Uuid::new_v4();
+        let (live_page, gone_page) = (Uuid::new_v4(), Uuid::new_v4());
+
+        write(&assets, "infographics/infographic_timeline_1.svg");
+        write(&assets, "infographics/infographic_timeline_2.svg");
+        write(&assets, "videos/presentation_3.mp4");
+        write(&assets, "videos/lecture.mp4");
+
+        let mut registry = GeneratedMediaRegistry::open(assets.clone()).unwrap();
+        let live = registry
+            .add(GeneratedMedia::new(
+                nb,
+                vec![live_page],
+                GeneratedMediaKind::Infographic,
+                serde_json::json!({ "template": "timeline" }),
+                vec!["infographics/infographic_timeline_1.svg".into()],
+            ))
+            .unwrap();
+        let dead = registry
+            .add(GeneratedMedia::new(
+                nb,
+                vec![gone_page],
+                GeneratedMediaKind::StudyVideo,
+                serde_json::json!({}),
+                vec!["videos/presentation_3.mp4".into()],
+            ))
+            .unwrap();
+        assert_eq!(registry.list(Some(live_page)).len(), 1);
+
+        let pages: HashSet<Uuid> = [live_page].into();
+        let preview = registry.cleanup(&pages, true).unwrap();
+        assert_eq!(preview.removed_entries, vec![dead.id]);
+        assert_eq!(preview.removed_files.len(), 2);
+        assert!(assets.join("videos/presentation_3.mp4").exists());
+        assert_eq!(registry.list(None).len(), 2);
+
+        registry.cleanup(&pages, false).unwrap();
+        assert!(!assets.join("videos/presentation_3.mp4").exists());
+        assert!(!assets.join("infographics/infographic_timeline_2.svg").exists());
+        // Not a generator output
+        assert!(assets.join("videos/lecture.mp4").exists());
+
+        let reopened = GeneratedMediaRegistry::open(assets).unwrap();
+        assert_eq!(reopened.list(None).len(), 1);
+        assert_eq!(reopened.get(live.id).unwrap().files.len(), 1);
+    }
+}
@@ -11,20 +11,20 @@ use quick_xml::Reader;
 use uuid::Uuid;
 
 use super::models::*;
-use crate::storage::{FileStorage, StorageError};
+use crate::storage::{StorageError, StorageLock};
 
 type Result<T> = std::result::Result<T, StorageError>;
 
 /// Detector for automatic goal progress tracking
 pub struct GoalDetector {
-    storage: Arc<Mutex<FileStorage>>,
+    storage: Arc<StorageLock>,
     #[cfg(feature = "plugins")]
     plugin_host: Option<Arc<Mutex<crate::plugins::PluginHost>>>,
 }
 
 impl GoalDetector {
     /// Create a new goal detector
-    pub fn new(storage: Arc<Mutex<FileStorage>>) -> Self {
+    pub fn new(storage: Arc<StorageLock>) -> Self {
         Self {
             storage,
             #[cfg(feature = "plugins")]
@@ -356,7 +356,7 @@ impl GoalDetector {
 
     /// Detect page edits on a given date
     fn detect_page_edits(&self, scope: &AutoDetectScope, date: NaiveDate) -> Result<(bool, u32)> {
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             StorageError::InvalidOperation(format!("Failed to lock storage: {}", e))
         })?;
 
@@ -427,7 +427,7 @@ impl GoalDetector {
 
     /// Detect page creates on a given date
     fn detect_page_creates(&self, scope: &AutoDetectScope, date: NaiveDate) -> Result<(bool, u32)> {
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             StorageError::InvalidOperation(format!("Failed to lock storage: {}", e))
         })?;
 
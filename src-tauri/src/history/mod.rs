@@ -0,0 +1,12 @@
+//! Page view history and the daily review feed
+//!
+//! Records when each page was last opened, which the review feed uses to
+//! bring back old notes that haven't been looked at in a while.
+
+mod models;
+pub mod review;
+mod storage;
+
+pub use models::*;
+pub use review::review_feed;
+pub use storage::*;
@@ -0,0 +1,72 @@
+//! View history data models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// When a page was last opened, and how often
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageView {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub last_viewed_at: DateTime<Utc>,
+    pub view_count: u32,
+}
+
+/// A page considered for the review feed
+#[derive(Debug, Clone)]
+pub struct ReviewCandidate {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    pub updated_at: DateTime<Utc>,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    /// Wiki links pointing at this page from other pages
+    pub inbound_links: usize,
+}
+
+/// A page picked for today's review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewItem {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    pub updated_at: DateTime<Utc>,
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub inbound_links: usize,
+    /// Why it was picked ("Not opened in 8 months")
+    pub reason: String,
+}
+
+/// A query from the search history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub last_searched_at: DateTime<Utc>,
+    pub search_count: u32,
+}
+
+/// What the search history keeps, and for how long
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchHistorySettings {
+    /// Record searches at all. Turning this off also forgets past searches.
+    pub enabled: bool,
+    /// Most queries kept; the least recently searched go first
+    pub max_entries: usize,
+    /// Forget queries not searched for in this many days; `None` keeps them
+    pub retention_days: Option<u32>,
+}
+
+impl Default for SearchHistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 200,
+            retention_days: Some(90),
+        }
+    }
+}
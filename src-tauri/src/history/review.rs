@@ -0,0 +1,263 @@
+//! Daily review feed selection
+//!
+//! Candidates are old pages (not edited for `MIN_AGE_DAYS`) that haven't
+//! been opened recently. Each gets a weight from its age, inbound links and
+//! time since it was last viewed, and the feed is a weighted sample that is
+//! stable for a whole day and changes the next.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use super::models::{ReviewCandidate, ReviewItem};
+use crate::storage::Page;
+
+/// Pages edited more recently than this are still fresh in mind
+pub const MIN_AGE_DAYS: i64 = 30;
+/// Pages opened more recently than this are skipped
+pub const RECENTLY_VIEWED_DAYS: i64 = 14;
+
+fn collect_strings<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// How many other pages link to each title (lowercased) with a wiki link
+pub fn inbound_link_counts(pages: &[Page]) -> HashMap<String, usize> {
+    let wiki_re = regex::Regex::new(r#"<wiki-link[^>]*data-page-title="([^"]*)"[^>]*>"#).unwrap();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for page in pages {
+        let own_title = page.title.to_lowercase();
+        let mut texts = Vec::new();
+        for block in &page.content.blocks {
+            collect_strings(&block.data, &mut texts);
+        }
+        let targets: HashSet<String> = texts
+            .iter()
+            .flat_map(|text| wiki_re.captures_iter(text))
+            .map(|cap| cap[1].to_lowercase())
+            .filter(|target| *target != own_title)
+            .collect();
+        for target in targets {
+            *counts.entry(target).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn days_between(earlier: DateTime<Utc>, later: DateTime<Utc>) -> i64 {
+    (later - earlier).num_days().max(0)
+}
+
+/// Relative chance of a candidate being picked, or None if it isn't eligible
+pub fn weight(candidate: &ReviewCandidate, now: DateTime<Utc>) -> Option<f64> {
+    let age = days_between(candidate.updated_at, now);
+    if age < MIN_AGE_DAYS {
+        return None;
+    }
+    let unseen = match candidate.last_viewed_at {
+        Some(viewed) => days_between(viewed, now),
+        None => age,
+    };
+    if unseen < RECENTLY_VIEWED_DAYS {
+        return None;
+    }
+
+    let age_weight = (1.0 + age as f64 / 30.0).ln();
+    let link_weight = 1.0 + (1.0 + candidate.inbound_links as f64).ln();
+    let unseen_weight = (1.0 + unseen as f64 / 30.0).ln();
+    Some(age_weight * link_weight * unseen_weight)
+}
+
+/// A uniform value in (0, 1) that depends only on the day and the page, so
+/// the feed doesn't change when it's fetched again the same day
+fn daily_unit(day: NaiveDate, page_id: &Uuid) -> f64 {
+    // FNV-1a over the day and the page ID, then a splitmix64 finalizer
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in day.to_string().as_bytes().iter().chain(page_id.as_bytes()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^= hash >> 31;
+    ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+}
+
+fn describe(months: i64, what: &str) -> String {
+    match months {
+        0 => format!("{} over a month ago", what),
+        1 => format!("{} a month ago", what),
+        m if m < 24 => format!("{} {} months ago", what, m),
+        m => format!("{} {} years ago", what, m / 12),
+    }
+}
+
+fn reason(candidate: &ReviewCandidate, now: DateTime<Utc>) -> String {
+    match candidate.last_viewed_at {
+        Some(viewed) => describe(days_between(viewed, now) / 30, "Last opened"),
+        None => describe(days_between(candidate.updated_at, now) / 30, "Last edited"),
+    }
+}
+
+/// Review candidates among `pages`, with inbound links counted between them
+/// and the last view of each page from `last_viewed`. Cover pages are left out.
+pub fn review_candidates(
+    pages: Vec<Page>,
+    last_viewed: impl Fn(Uuid) -> Option<DateTime<Utc>>,
+) -> Vec<ReviewCandidate> {
+    let links = inbound_link_counts(&pages);
+    pages
+        .into_iter()
+        .filter(|page| !page.is_cover)
+        .map(|page| ReviewCandidate {
+            notebook_id: page.notebook_id,
+            page_id: page.id,
+            inbound_links: links.get(&page.title.to_lowercase()).copied().unwrap_or(0),
+            last_viewed_at: last_viewed(page.id),
+            updated_at: page.updated_at,
+            title: page.title,
+        })
+        .collect()
+}
+
+/// Pick up to `limit` pages for `day`'s review, most strongly weighted first
+pub fn review_feed(
+    candidates: Vec<ReviewCandidate>,
+    day: NaiveDate,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<ReviewItem> {
+    // Weighted sampling without replacement: the largest u^(1/w) win
+    let mut keyed: Vec<(f64, ReviewCandidate)> = candidates
+        .into_iter()
+        .filter_map(|c| {
+            let weight = weight(&c, now)?;
+            let key = daily_unit(day, &c.page_id).powf(1.0 / weight);
+            Some((key, c))
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed
+        .into_iter()
+        .take(limit)
+        .map(|(_, c)| ReviewItem {
+            reason: reason(&c, now),
+            notebook_id: c.notebook_id,
+            page_id: c.page_id,
+            title: c.title,
+            updated_at: c.updated_at,
+            last_viewed_at: c.last_viewed_at,
+            inbound_links: c.inbound_links,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 14, 12, 0, 0).unwrap()
+    }
+
+    fn candidate(age_days: i64, viewed_days_ago: Option<i64>, links: usize) -> ReviewCandidate {
+        ReviewCandidate {
+            notebook_id: Uuid::new_v4(),
+            page_id: Uuid::new_v4(),
+            title: format!("{} days old", age_days),
+            updated_at: now() - Duration::days(age_days),
+            last_viewed_at: viewed_days_ago.map(|d| now() - Duration::days(d)),
+            inbound_links: links,
+        }
+    }
+
+    #[test]
+    fn fresh_and_recently_viewed_pages_are_skipped() {
+        assert!(weight(&candidate(10, None, 0), now()).is_none());
+        assert!(weight(&candidate(400, Some(3), 0), now()).is_none());
+        assert!(weight(&candidate(400, Some(60), 0), now()).is_some());
+    }
+
+    #[test]
+    fn older_linked_unseen_pages_weigh_more() {
+        let base = weight(&candidate(90, Some(60), 0), now()).unwrap();
+        assert!(weight(&candidate(365, Some(60), 0), now()).unwrap() > base);
+        assert!(weight(&candidate(90, Some(60), 5), now()).unwrap() > base);
+        assert!(weight(&candidate(90, None, 0), now()).unwrap() > base);
+    }
+
+    #[test]
+    fn feed_is_stable_within_a_day_and_rotates() {
+        let candidates: Vec<_> = (0..50)
+            .map(|i| candidate(60 + i * 10, None, (i % 4) as usize))
+            .collect();
+        let day = now().date_naive();
+        let ids = |day: NaiveDate| -> Vec<Uuid> {
+            review_feed(candidates.clone(), day, now(), 5)
+                .iter()
+                .map(|item| item.page_id)
+                .collect()
+        };
+
+        assert_eq!(ids(day).len(), 5);
+        assert_eq!(ids(day), ids(day));
+        assert_ne!(ids(day), ids(day + Duration::days(1)));
+    }
+
+    #[test]
+    fn inbound_links_count_each_source_once() {
+        let notebook_id = Uuid::new_v4();
+        let link = |title: &str| {
+            format!(
+                "See <wiki-link data-page-title=\"{0}\">{0}</wiki-link>",
+                title
+            )
+        };
+        let page_with = |title: &str, texts: Vec<String>| {
+            let mut page = Page::new(notebook_id, title.to_string());
+            page.content.blocks = texts
+                .into_iter()
+                .enumerate()
+                .map(|(i, text)| crate::storage::EditorBlock {
+                    id: i.to_string(),
+                    block_type: "paragraph".to_string(),
+                    data: serde_json::json!({ "text": text }),
+                })
+                .collect();
+            page
+        };
+        let pages = [
+            page_with("A", vec![link("Target"), link("target")]),
+            page_with("B", vec![link("Target"), link("B")]),
+            page_with("Target", vec![]),
+        ];
+
+        let counts = inbound_link_counts(&pages);
+        assert_eq!(counts.get("target"), Some(&2));
+        assert_eq!(counts.get("b"), None);
+    }
+
+    #[test]
+    fn reasons_are_readable() {
+        let now = now();
+        assert_eq!(
+            reason(&candidate(400, None, 0), now),
+            "Last edited 13 months ago"
+        );
+        assert_eq!(
+            reason(&candidate(900, Some(45), 0), now),
+            "Last opened a month ago"
+        );
+        assert_eq!(
+            reason(&candidate(900, Some(800), 0), now),
+            "Last opened 2 years ago"
+        );
+    }
+}
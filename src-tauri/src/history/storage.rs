@@ -0,0 +1,261 @@
+//! View history storage implementation
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::StorageError;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Queries shorter than this aren't worth suggesting again
+const MIN_QUERY_CHARS: usize = 2;
+
+/// The search history file: its settings and the queries, most recent first
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchHistoryFile {
+    #[serde(default)]
+    settings: SearchHistorySettings,
+    #[serde(default)]
+    entries: Vec<SearchHistoryEntry>,
+}
+
+/// Last-viewed times for the pages of a library, in a single JSON file,
+/// and the library's search history in another
+pub struct HistoryStorage {
+    path: PathBuf,
+    views: HashMap<Uuid, PageView>,
+    searches_path: PathBuf,
+    searches: SearchHistoryFile,
+}
+
+impl HistoryStorage {
+    /// Create a new history storage, loading any saved views
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&data_dir)?;
+        let path = data_dir.join("view_history.json");
+        let views = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let list: Vec<PageView> = serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable view history: {}", e);
+                Vec::new()
+            });
+            list.into_iter().map(|v| (v.page_id, v)).collect()
+        } else {
+            HashMap::new()
+        };
+
+        let searches_path = data_dir.join("search_history.json");
+        let searches = if searches_path.exists() {
+            let content = fs::read_to_string(&searches_path)?;
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable search history: {}", e);
+                SearchHistoryFile::default()
+            })
+        } else {
+            SearchHistoryFile::default()
+        };
+
+        Ok(Self {
+            path,
+            views,
+            searches_path,
+            searches,
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        let list: Vec<&PageView> = self.views.values().collect();
+        fs::write(&self.path, serde_json::to_string(&list)?)?;
+        Ok(())
+    }
+
+    /// Record that a page was opened now
+    pub fn record_view(&mut self, notebook_id: Uuid, page_id: Uuid) -> Result<PageView> {
+        let now = Utc::now();
+        let view = self
+            .views
+            .entry(page_id)
+            .and_modify(|v| {
+                v.notebook_id = notebook_id;
+                v.last_viewed_at = now;
+                v.view_count += 1;
+            })
+            .or_insert(PageView {
+                notebook_id,
+                page_id,
+                last_viewed_at: now,
+                view_count: 1,
+            })
+            .clone();
+        self.save()?;
+        Ok(view)
+    }
+
+    /// Get the view record for a page
+    pub fn get_view(&self, page_id: Uuid) -> Option<&PageView> {
+        self.views.get(&page_id)
+    }
+
+    fn save_searches(&self) -> Result<()> {
+        fs::write(&self.searches_path, serde_json::to_string(&self.searches)?)?;
+        Ok(())
+    }
+
+    /// Drop queries past the retention period or the size limit
+    fn prune_searches(&mut self) {
+        let settings = &self.searches.settings;
+        if let Some(days) = settings.retention_days {
+            let cutoff = Utc::now() - Duration::days(days as i64);
+            self.searches
+                .entries
+                .retain(|e| e.last_searched_at > cutoff);
+        }
+        self.searches.entries.truncate(settings.max_entries);
+    }
+
+    /// Record that `query` was searched for now. Returns `None` when the
+    /// history is turned off or the query is too short to keep.
+    pub fn record_search(&mut self, query: &str) -> Result<Option<SearchHistoryEntry>> {
+        let query = normalize_query(query);
+        if !self.searches.settings.enabled || query.chars().count() < MIN_QUERY_CHARS {
+            return Ok(None);
+        }
+
+        let entries = &mut self.searches.entries;
+        let previous = entries
+            .iter()
+            .position(|e| e.query.to_lowercase() == query.to_lowercase())
+            .map(|i| entries.remove(i));
+        let entry = SearchHistoryEntry {
+            query,
+            last_searched_at: Utc::now(),
+            search_count: previous.map_or(1, |e| e.search_count + 1),
+        };
+        entries.insert(0, entry.clone());
+        self.prune_searches();
+        self.save_searches()?;
+        Ok(Some(entry))
+    }
+
+    /// Past searches, most recent first
+    pub fn search_history(&self, limit: usize) -> Vec<SearchHistoryEntry> {
+        let cutoff = self
+            .searches
+            .settings
+            .retention_days
+            .map(|days| Utc::now() - Duration::days(days as i64));
+        self.searches
+            .entries
+            .iter()
+            .filter(|e| cutoff.map_or(true, |cutoff| e.last_searched_at > cutoff))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Forget one query. Returns whether it was in the history.
+    pub fn remove_search(&mut self, query: &str) -> Result<bool> {
+        let query = normalize_query(query).to_lowercase();
+        let before = self.searches.entries.len();
+        self.searches
+            .entries
+            .retain(|e| e.query.to_lowercase() != query);
+        let removed = self.searches.entries.len() != before;
+        if removed {
+            self.save_searches()?;
+        }
+        Ok(removed)
+    }
+
+    /// Forget every query. Returns how many there were.
+    pub fn clear_search_history(&mut self) -> Result<usize> {
+        let cleared = self.searches.entries.len();
+        self.searches.entries.clear();
+        self.save_searches()?;
+        Ok(cleared)
+    }
+
+    pub fn search_history_settings(&self) -> &SearchHistorySettings {
+        &self.searches.settings
+    }
+
+    /// Change what the history keeps. Turning it off forgets past searches;
+    /// tighter limits apply to them right away.
+    pub fn set_search_history_settings(&mut self, settings: SearchHistorySettings) -> Result<()> {
+        if !settings.enabled {
+            self.searches.entries.clear();
+        }
+        self.searches.settings = settings;
+        self.prune_searches();
+        self.save_searches()
+    }
+}
+
+/// Trim a query and collapse its inner whitespace
+fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_history_dedupes_and_orders_by_recency() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+
+        history.record_search("rust  traits").unwrap();
+        history.record_search("tokio").unwrap();
+        let again = history.record_search("Rust traits ").unwrap().unwrap();
+        assert_eq!(again.search_count, 2);
+        assert!(history.record_search("a").unwrap().is_none());
+
+        let queries: Vec<String> = history
+            .search_history(10)
+            .into_iter()
+            .map(|e| e.query)
+            .collect();
+        assert_eq!(queries, vec!["Rust traits", "tokio"]);
+
+        // Survives a reload
+        let reloaded = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.search_history(1)[0].query, "Rust traits");
+
+        assert!(history.remove_search("TOKIO").unwrap());
+        assert_eq!(history.clear_search_history().unwrap(), 1);
+    }
+
+    #[test]
+    fn search_history_privacy_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut history = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        for query in ["one", "two", "three"] {
+            history.record_search(query).unwrap();
+        }
+
+        history
+            .set_search_history_settings(SearchHistorySettings {
+                max_entries: 2,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(history.search_history(10).len(), 2);
+
+        history
+            .set_search_history_settings(SearchHistorySettings {
+                enabled: false,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(history.search_history(10).is_empty());
+        assert!(history.record_search("secret").unwrap().is_none());
+    }
+}
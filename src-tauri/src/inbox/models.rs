@@ -32,6 +32,9 @@ pub struct InboxItem {
     pub classification: Option<InboxClassification>,
     /// Whether this item has been processed
     pub is_processed: bool,
+    /// Hidden from the inbox until this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime<Utc>>,
 }
 
 impl InboxItem {
@@ -47,9 +50,15 @@ impl InboxItem {
             source: CaptureSource::QuickCapture,
             classification: None,
             is_processed: false,
+            snoozed_until: None,
         }
     }
 
+    /// Whether the item is hidden by a snooze at `now`
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until.is_some_and(|until| until > now)
+    }
+
     pub fn with_tags(mut self, tags: Vec<String>) -> Self {
         self.tags = tags;
         self
@@ -80,6 +89,11 @@ pub enum CaptureSource {
         target_name: String,
         target_id: String,
     },
+    /// Captured in the mobile app or its share sheet
+    Mobile {
+        platform: String,
+        url: Option<String>,
+    },
 }
 
 /// AI classification result for an inbox item
@@ -123,6 +137,14 @@ pub enum ClassificationAction {
         suggested_name: String,
         suggested_icon: Option<String>,
     },
+    /// Append to the notebook's daily note for a date (YYYY-MM-DD). Queued
+    /// by the mobile app, which doesn't have the notebook.
+    #[serde(rename_all = "camelCase")]
+    AppendToDailyNote {
+        notebook_id: Uuid,
+        notebook_name: String,
+        date: String,
+    },
     /// Keep in inbox (unclear classification)
     KeepInInbox {
         reason: String,
@@ -178,4 +200,5 @@ pub struct InboxSummary {
     pub unclassified_count: usize,
     pub classified_count: usize,
     pub processed_count: usize,
+    pub snoozed_count: usize,
 }
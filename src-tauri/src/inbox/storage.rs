@@ -92,21 +92,51 @@ impl InboxStorage {
         Ok(items)
     }
 
-    /// List unprocessed inbox items
+    /// List unprocessed inbox items, leaving out snoozed ones
     pub fn list_unprocessed(&self) -> Result<Vec<InboxItem>> {
         let items = self.list_items()?;
-        Ok(items.into_iter().filter(|i| !i.is_processed).collect())
+        let now = chrono::Utc::now();
+        Ok(items
+            .into_iter()
+            .filter(|i| !i.is_processed && !i.is_snoozed(now))
+            .collect())
     }
 
     /// List items pending classification
     pub fn list_unclassified(&self) -> Result<Vec<InboxItem>> {
         let items = self.list_items()?;
+        let now = chrono::Utc::now();
         Ok(items
             .into_iter()
-            .filter(|i| !i.is_processed && i.classification.is_none())
+            .filter(|i| !i.is_processed && !i.is_snoozed(now) && i.classification.is_none())
             .collect())
     }
 
+    /// List unprocessed items that are snoozed or whose snooze has ended
+    /// but hasn't been cleared yet, soonest first
+    pub fn list_snoozed(&self) -> Result<Vec<InboxItem>> {
+        let items = self.list_items()?;
+        let mut snoozed: Vec<InboxItem> = items
+            .into_iter()
+            .filter(|i| !i.is_processed && i.snoozed_until.is_some())
+            .collect();
+        snoozed.sort_by_key(|i| i.snoozed_until);
+        Ok(snoozed)
+    }
+
+    /// Hide an item until `until`, or bring it back now with `None`
+    pub fn snooze_item(
+        &self,
+        id: Uuid,
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<InboxItem> {
+        let mut item = self.get_item(id)?;
+        item.snoozed_until = until;
+        item.updated_at = chrono::Utc::now();
+        self.save_item(&item)?;
+        Ok(item)
+    }
+
     /// Update classification for an item
     pub fn set_classification(
         &self,
@@ -157,19 +187,25 @@ impl InboxStorage {
     pub fn get_summary(&self) -> Result<InboxSummary> {
         let items = self.list_items()?;
 
+        let now = chrono::Utc::now();
         let total_items = items.len();
         let processed_count = items.iter().filter(|i| i.is_processed).count();
+        let snoozed_count = items
+            .iter()
+            .filter(|i| !i.is_processed && i.is_snoozed(now))
+            .count();
         let classified_count = items
             .iter()
-            .filter(|i| !i.is_processed && i.classification.is_some())
+            .filter(|i| !i.is_processed && !i.is_snoozed(now) && i.classification.is_some())
             .count();
-        let unclassified_count = total_items - processed_count - classified_count;
+        let unclassified_count = total_items - processed_count - snoozed_count - classified_count;
 
         Ok(InboxSummary {
             total_items,
             unclassified_count,
             classified_count,
             processed_count,
+            snoozed_count,
         })
     }
 
@@ -0,0 +1,984 @@
+//! Google Keep (Takeout) import implementation
+//!
+//! Google Takeout exports Keep as a flat `Takeout/Keep/` folder holding, per
+//! note, a `.json` file (newer takeouts) and an `.html` rendering, plus the
+//! note's attachments. The JSON is preferred; notes that only have HTML are
+//! parsed best-effort from the markup.
+//!
+//! Notes can be imported into a new notebook or an existing one. When the
+//! target already has a page with the same title, [`KeepDuplicateStrategy`]
+//! decides whether the note is skipped, renamed or replaces the page.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::storage::{
+    EditorBlock, EditorData, FileStorage, FileStorageWriter, Notebook, NotebookType, Page,
+    StorageError,
+};
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// What to do with a note whose title matches an existing page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeepDuplicateStrategy {
+    /// Leave the existing page alone and don't import the note
+    Skip,
+    /// Import the note as "Title (2)"
+    #[default]
+    Rename,
+    /// Overwrite the existing page's content, keeping its id
+    Replace,
+}
+
+/// Preview metadata for a Keep import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeepImportPreview {
+    /// Number of notes that will be imported (trashed notes excluded)
+    pub note_count: usize,
+    /// Number of checklist notes
+    pub checklist_count: usize,
+    /// Number of archived notes (imported as archived pages)
+    pub archived_count: usize,
+    /// Number of trashed notes that will be skipped
+    pub trashed_count: usize,
+    /// Number of attachments referenced by notes
+    pub attachment_count: usize,
+    /// Distinct labels (imported as tags)
+    pub labels: Vec<String>,
+    /// Titles that already exist in the target notebook
+    pub duplicate_titles: Vec<String>,
+    /// Sample notes for preview (first 10)
+    pub notes: Vec<KeepNotePreview>,
+    /// Suggested notebook name
+    pub suggested_name: String,
+    /// Warnings during preview
+    pub warnings: Vec<String>,
+}
+
+/// Preview info for a single note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeepNotePreview {
+    pub title: String,
+    pub labels: Vec<String>,
+    pub is_checklist: bool,
+    pub color: Option<String>,
+    pub attachment_count: usize,
+}
+
+/// Outcome of a Keep import
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeepImportResult {
+    pub notebook: Notebook,
+    /// Pages created for new notes
+    pub imported: usize,
+    /// Existing pages overwritten (`Replace` strategy)
+    pub replaced: usize,
+    /// Notes skipped as duplicates (`Skip` strategy)
+    pub skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Keep note as exported in Takeout JSON
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct KeepJsonNote {
+    title: String,
+    text_content: String,
+    list_content: Vec<KeepJsonListItem>,
+    labels: Vec<KeepJsonLabel>,
+    color: Option<String>,
+    is_pinned: bool,
+    is_archived: bool,
+    is_trashed: bool,
+    attachments: Vec<KeepJsonAttachment>,
+    annotations: Vec<KeepJsonAnnotation>,
+    created_timestamp_usec: Option<i64>,
+    user_edited_timestamp_usec: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct KeepJsonListItem {
+    text: String,
+    is_checked: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KeepJsonLabel {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct KeepJsonAttachment {
+    file_path: String,
+    mimetype: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KeepJsonAnnotation {
+    title: String,
+    url: String,
+}
+
+/// A note normalized from either the JSON or the HTML export
+#[derive(Debug, Clone, Default)]
+struct KeepNote {
+    title: String,
+    text: String,
+    items: Vec<(String, bool)>,
+    labels: Vec<String>,
+    /// Keep color name (RED, TEAL, ...); None for DEFAULT
+    color: Option<String>,
+    pinned: bool,
+    archived: bool,
+    trashed: bool,
+    created: Option<DateTime<Utc>>,
+    updated: Option<DateTime<Utc>>,
+    attachments: Vec<KeepAttachment>,
+    links: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+enum KeepAttachment {
+    /// A file next to the note in the Keep folder
+    File { name: String, mimetype: String },
+    /// An image inlined as a data: URI (some HTML-only exports)
+    Inline { name: String, data: Vec<u8> },
+}
+
+impl KeepAttachment {
+    fn is_image(&self) -> bool {
+        match self {
+            KeepAttachment::File { mimetype, name } => {
+                mimetype.starts_with("image/") || is_image_name(name)
+            }
+            KeepAttachment::Inline { .. } => true,
+        }
+    }
+}
+
+fn is_image_name(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif", ".webp"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+}
+
+// ===== Reading the export =====
+
+/// The Keep folder of a Takeout, either extracted or still zipped
+enum KeepSource {
+    Dir(PathBuf),
+    Zip {
+        archive: ZipArchive<File>,
+        /// file name → entry index
+        entries: HashMap<String, usize>,
+    },
+}
+
+impl KeepSource {
+    fn open(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            // Accept the Takeout root, the Keep folder itself, or a folder
+            // containing Takeout/
+            for candidate in [
+                path.join("Takeout").join("Keep"),
+                path.join("Keep"),
+                path.to_path_buf(),
+            ] {
+                if candidate.is_dir() && dir_has_notes(&candidate) {
+                    return Ok(KeepSource::Dir(candidate));
+                }
+            }
+            return Err(StorageError::InvalidOperation(
+                "No Google Keep notes found in directory".to_string(),
+            ));
+        }
+
+        let file = File::open(path)?;
+        let mut archive = ZipArchive::new(file)?;
+        let mut entries = HashMap::new();
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let entry_path = Path::new(entry.name());
+            let in_keep = entry_path
+                .parent()
+                .and_then(|p| p.file_name())
+                .is_some_and(|p| p == "Keep");
+            if let (true, Some(name)) = (in_keep, entry_path.file_name()) {
+                entries.insert(name.to_string_lossy().to_string(), i);
+            }
+        }
+        if entries.is_empty() {
+            return Err(StorageError::InvalidOperation(
+                "Archive has no Keep folder (expected Takeout/Keep/)".to_string(),
+            ));
+        }
+        Ok(KeepSource::Zip { archive, entries })
+    }
+
+    fn names(&self) -> Vec<String> {
+        match self {
+            KeepSource::Dir(dir) => fs::read_dir(dir)
+                .map(|rd| {
+                    rd.flatten()
+                        .filter(|e| e.path().is_file())
+                        .map(|e| e.file_name().to_string_lossy().to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            KeepSource::Zip { entries, .. } => entries.keys().cloned().collect(),
+        }
+    }
+
+    fn read(&mut self, name: &str) -> Option<Vec<u8>> {
+        match self {
+            KeepSource::Dir(dir) => fs::read(dir.join(name)).ok(),
+            KeepSource::Zip { archive, entries } => {
+                let index = *entries.get(name)?;
+                let mut entry = archive.by_index(index).ok()?;
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data).ok()?;
+                Some(data)
+            }
+        }
+    }
+
+    /// Read an attachment. Keep's JSON sometimes says `.jpeg` for a file
+    /// stored as `.jpg` (and vice versa).
+    fn read_attachment(&mut self, name: &str) -> Option<(String, Vec<u8>)> {
+        if let Some(data) = self.read(name) {
+            return Some((name.to_string(), data));
+        }
+        let alternate = if let Some(stem) = name.strip_suffix(".jpeg") {
+            format!("{}.jpg", stem)
+        } else if let Some(stem) = name.strip_suffix(".jpg") {
+            format!("{}.jpeg", stem)
+        } else {
+            return None;
+        };
+        self.read(&alternate).map(|data| (alternate, data))
+    }
+}
+
+fn dir_has_notes(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|rd| {
+            rd.flatten().any(|e| {
+                let name = e.file_name().to_string_lossy().to_lowercase();
+                name.ends_with(".json") || name.ends_with(".html")
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn usec(value: Option<i64>) -> Option<DateTime<Utc>> {
+    value.and_then(|us| Utc.timestamp_micros(us).single())
+}
+
+fn parse_json_note(content: &str) -> Option<KeepNote> {
+    let note: KeepJsonNote = serde_json::from_str(content).ok()?;
+    Some(KeepNote {
+        title: note.title.trim().to_string(),
+        text: note.text_content,
+        items: note
+            .list_content
+            .into_iter()
+            .map(|i| (i.text, i.is_checked))
+            .collect(),
+        labels: note
+            .labels
+            .into_iter()
+            .map(|l| l.name)
+            .filter(|n| !n.is_empty())
+            .collect(),
+        color: note.color.filter(|c| c != "DEFAULT"),
+        pinned: note.is_pinned,
+        archived: note.is_archived,
+        trashed: note.is_trashed,
+        created: usec(note.created_timestamp_usec),
+        updated: usec(note.user_edited_timestamp_usec),
+        attachments: note
+            .attachments
+            .into_iter()
+            .map(|a| KeepAttachment::File {
+                name: a.file_path,
+                mimetype: a.mimetype,
+            })
+            .collect(),
+        links: note
+            .annotations
+            .into_iter()
+            .filter(|a| !a.url.is_empty())
+            .map(|a| (a.title, a.url))
+            .collect(),
+    })
+}
+
+/// Strip tags from an HTML fragment, keeping `<br>` as newlines
+fn html_to_text(html: &str) -> String {
+    let br = Regex::new(r"(?i)<br\s*/?>").unwrap();
+    let tags = Regex::new(r"<[^>]+>").unwrap();
+    let text = br.replace_all(html, "\n");
+    let text = tags.replace_all(&text, "");
+    html_escape::decode_html_entities(&text).trim().to_string()
+}
+
+/// Best-effort parse of a Keep HTML note (older takeouts without JSON)
+fn parse_html_note(html: &str, file_stem: &str) -> KeepNote {
+    let capture = |pattern: &str| -> Option<String> {
+        Regex::new(pattern)
+            .unwrap()
+            .captures(html)
+            .map(|c| c[1].to_string())
+    };
+
+    let mut note = KeepNote {
+        title: capture(r#"(?s)<div class="title">(.*?)</div>"#)
+            .map(|t| html_to_text(&t))
+            .unwrap_or_default(),
+        color: capture(r#"<div class="note ([A-Z_]+)""#).filter(|c| c != "DEFAULT"),
+        archived: html.contains(r#"class="archived""#),
+        trashed: html.contains(r#"class="trashed""#),
+        ..Default::default()
+    };
+
+    // "Jan 3, 2019, 10:26:47 AM" in the heading
+    note.updated = capture(
+        r#"(?s)<div class="heading">(?:<div class="meta-icons">.*?</div>)?\s*([^<]+?)\s*</div>"#,
+    )
+    .and_then(|d| NaiveDateTime::parse_from_str(&d, "%b %d, %Y, %I:%M:%S %p").ok())
+    .map(|d| d.and_utc());
+    note.created = note.updated;
+
+    // Checklist items: <li class="listitem checked">…<span class="text">…</span>
+    // in newer exports, <div class="listitem"><div class="bullet">☑</div>
+    // <div class="text">…</div> in older ones
+    let item_re = Regex::new(
+        r#"(?s)class="listitem( checked)?"[^>]*>.*?class="bullet">([^<]*)<.*?class="text">(.*?)</(?:span|div)>"#,
+    )
+    .unwrap();
+    for c in item_re.captures_iter(html) {
+        let bullet = html_escape::decode_html_entities(&c[2]).to_string();
+        let checked = c.get(1).is_some() || bullet.contains('☑');
+        note.items.push((html_to_text(&c[3]), checked));
+    }
+    if note.items.is_empty() {
+        note.text = capture(r#"(?s)<div class="content">(.*?)</div>"#)
+            .map(|t| html_to_text(&t))
+            .unwrap_or_default();
+    }
+
+    let label_re = Regex::new(r#"<span class="label-name">(.*?)</span>"#).unwrap();
+    note.labels = label_re
+        .captures_iter(html)
+        .map(|c| html_to_text(&c[1]))
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let img_re = Regex::new(r#"<img[^>]*\ssrc="([^"]+)""#).unwrap();
+    for (i, c) in img_re.captures_iter(html).enumerate() {
+        let src = &c[1];
+        if let Some(rest) = src.strip_prefix("data:") {
+            let Some((meta, data)) = rest.split_once(',') else {
+                continue;
+            };
+            let ext = meta
+                .split(';')
+                .next()
+                .and_then(|m| m.strip_prefix("image/"))
+                .unwrap_or("png");
+            if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) {
+                note.attachments.push(KeepAttachment::Inline {
+                    name: format!("{}-{}.{}", file_stem, i + 1, ext),
+                    data: bytes,
+                });
+            }
+        } else if !src.contains("://") {
+            note.attachments.push(KeepAttachment::File {
+                name: src.to_string(),
+                mimetype: String::new(),
+            });
+        }
+    }
+
+    note
+}
+
+/// Read every note in the export, in file-name order
+fn read_notes(source: &mut KeepSource, warnings: &mut Vec<String>) -> Vec<KeepNote> {
+    let names: BTreeSet<String> = source.names().into_iter().collect();
+    let mut notes = Vec::new();
+
+    for name in &names {
+        let (stem, is_json) = if let Some(stem) = strip_extension(name, ".json") {
+            (stem, true)
+        } else if let Some(stem) = strip_extension(name, ".html") {
+            (stem, false)
+        } else {
+            continue;
+        };
+        // Prefer the JSON when both exist
+        if !is_json && names.contains(&format!("{}.json", stem)) {
+            continue;
+        }
+
+        let Some(bytes) = source.read(name) else {
+            warnings.push(format!("Could not read {}", name));
+            continue;
+        };
+        let content = String::from_utf8_lossy(&bytes);
+        let mut note = if is_json {
+            // Anything that isn't shaped like a note is skipped quietly
+            let Some(note) = parse_json_note(&content) else {
+                continue;
+            };
+            note
+        } else {
+            parse_html_note(&content, stem)
+        };
+
+        if note.title.is_empty() {
+            note.title = derive_title(&note, stem);
+        }
+        notes.push(note);
+    }
+
+    notes
+}
+
+/// Case-insensitive `strip_suffix` for a file extension
+fn strip_extension<'a>(name: &'a str, extension: &str) -> Option<&'a str> {
+    let split = name.len().checked_sub(extension.len())?;
+    (name.is_char_boundary(split) && name[split..].eq_ignore_ascii_case(extension))
+        .then(|| &name[..split])
+}
+
+/// Keep notes often have no title; use the first line of text or the
+/// first checklist item, falling back to the export file name
+fn derive_title(note: &KeepNote, file_stem: &str) -> String {
+    let first_line = note
+        .text
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .or_else(|| note.items.first().map(|(t, _)| t.trim()))
+        .filter(|l| !l.is_empty());
+    match first_line {
+        Some(line) if line.chars().count() > 60 => {
+            format!("{}…", line.chars().take(60).collect::<String>().trim_end())
+        }
+        Some(line) => line.to_string(),
+        None => file_stem.to_string(),
+    }
+}
+
+// ===== Conversion =====
+
+/// Keep's note palette (light theme), keyed by Takeout color name
+fn keep_color_css(color: &str) -> Option<&'static str> {
+    Some(match color {
+        "RED" => "#f28b82",
+        "ORANGE" => "#fbbc04",
+        "YELLOW" => "#fff475",
+        "GREEN" => "#ccff90",
+        "TEAL" => "#a7ffeb",
+        "BLUE" => "#cbf0f8",
+        "CERULEAN" | "DARK_BLUE" => "#aecbfa",
+        "PURPLE" => "#d7aefb",
+        "PINK" => "#fdcfe8",
+        "BROWN" => "#e6c9a8",
+        "GRAY" => "#e8eaed",
+        _ => return None,
+    })
+}
+
+/// Generate a unique block ID
+fn generate_block_id() -> String {
+    Uuid::new_v4().simple().to_string()[..10].to_string()
+}
+
+fn escape(text: &str) -> String {
+    html_escape::encode_text(text).into_owned()
+}
+
+/// Convert a note to blocks. `asset_urls` maps attachment names to the
+/// asset:// URL they were copied to.
+fn note_to_blocks(note: &KeepNote, asset_urls: &HashMap<String, String>) -> Vec<EditorBlock> {
+    let mut blocks = Vec::new();
+
+    // Keep separates paragraphs with blank lines; single newlines are
+    // line breaks within a paragraph
+    fn flush(paragraph: &mut Vec<&str>, blocks: &mut Vec<EditorBlock>) {
+        if !paragraph.is_empty() {
+            let html = paragraph
+                .iter()
+                .map(|l| escape(l))
+                .collect::<Vec<_>>()
+                .join("<br>");
+            blocks.push(EditorBlock {
+                id: generate_block_id(),
+                block_type: "paragraph".to_string(),
+                data: json!({ "text": html }),
+            });
+            paragraph.clear();
+        }
+    }
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in note.text.lines() {
+        if line.trim().is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    if !note.items.is_empty() {
+        blocks.push(EditorBlock {
+            id: generate_block_id(),
+            block_type: "checklist".to_string(),
+            data: json!({
+                "items": note
+                    .items
+                    .iter()
+                    .map(|(text, checked)| json!({ "text": escape(text), "checked": checked }))
+                    .collect::<Vec<_>>(),
+            }),
+        });
+    }
+
+    for attachment in &note.attachments {
+        let name = match attachment {
+            KeepAttachment::File { name, .. } | KeepAttachment::Inline { name, .. } => name,
+        };
+        let Some(url) = asset_urls.get(name) else {
+            continue;
+        };
+        if attachment.is_image() {
+            blocks.push(EditorBlock {
+                id: generate_block_id(),
+                block_type: "image".to_string(),
+                data: json!({
+                    "file": { "url": url },
+                    "caption": "",
+                    "withBorder": false,
+                    "withBackground": false,
+                    "stretched": false,
+                }),
+            });
+        } else {
+            // Voice memos and other files
+            blocks.push(EditorBlock {
+                id: generate_block_id(),
+                block_type: "paragraph".to_string(),
+                data: json!({
+                    "text": format!("📎 <a href=\"{}\">{}</a>", escape(url), escape(name)),
+                }),
+            });
+        }
+    }
+
+    if !note.links.is_empty() {
+        blocks.push(EditorBlock {
+            id: generate_block_id(),
+            block_type: "list".to_string(),
+            data: json!({
+                "style": "unordered",
+                "items": note
+                    .links
+                    .iter()
+                    .map(|(title, url)| {
+                        let label = if title.is_empty() { url } else { title };
+                        json!({
+                            "content": format!("<a href=\"{}\">{}</a>", escape(url), escape(label)),
+                            "items": [],
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        });
+    }
+
+    blocks
+}
+
+/// What to do with one note after duplicate resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Placement {
+    Create(String),
+    Replace(Uuid),
+    Skip,
+}
+
+/// Decide where each note goes. `existing` maps lower-cased titles of pages
+/// already in the target notebook to their ids. Two notes in the same
+/// export with the same title are always renamed, whatever the strategy.
+fn plan_placements(
+    titles: &[String],
+    existing: &HashMap<String, Uuid>,
+    strategy: KeepDuplicateStrategy,
+) -> Vec<Placement> {
+    let mut taken: HashSet<String> = existing.keys().cloned().collect();
+    let mut replaced = HashSet::new();
+
+    titles
+        .iter()
+        .map(|title| {
+            let key = title.to_lowercase();
+            if let Some(&page_id) = existing.get(&key) {
+                match strategy {
+                    KeepDuplicateStrategy::Skip => return Placement::Skip,
+                    KeepDuplicateStrategy::Replace if replaced.insert(page_id) => {
+                        return Placement::Replace(page_id)
+                    }
+                    _ => {}
+                }
+            }
+            if taken.insert(key) {
+                return Placement::Create(title.clone());
+            }
+            let mut n = 2;
+            loop {
+                let candidate = format!("{} ({})", title, n);
+                if taken.insert(candidate.to_lowercase()) {
+                    return Placement::Create(candidate);
+                }
+                n += 1;
+            }
+        })
+        .collect()
+}
+
+fn existing_titles(storage: &FileStorage, notebook_id: Uuid) -> Result<HashMap<String, Uuid>> {
+    Ok(storage
+        .list_pages(notebook_id)?
+        .into_iter()
+        .filter(|p| p.deleted_at.is_none())
+        .map(|p| (p.title.to_lowercase(), p.id))
+        .collect())
+}
+
+// ===== Public API =====
+
+/// Preview a Google Keep takeout (zip or extracted folder). When
+/// `target_notebook` is given, titles already present there are reported
+/// as duplicates.
+pub fn preview_keep_import(
+    path: &Path,
+    storage: &FileStorage,
+    target_notebook: Option<Uuid>,
+) -> Result<KeepImportPreview> {
+    let mut source = KeepSource::open(path)?;
+    let mut warnings = Vec::new();
+    let all_notes = read_notes(&mut source, &mut warnings);
+    let trashed_count = all_notes.iter().filter(|n| n.trashed).count();
+    let notes: Vec<KeepNote> = all_notes.into_iter().filter(|n| !n.trashed).collect();
+
+    let existing = match target_notebook {
+        Some(id) => existing_titles(storage, id)?,
+        None => HashMap::new(),
+    };
+    let mut duplicate_titles: Vec<String> = notes
+        .iter()
+        .filter(|n| existing.contains_key(&n.title.to_lowercase()))
+        .map(|n| n.title.clone())
+        .collect();
+    duplicate_titles.dedup();
+
+    let mut labels: Vec<String> = notes.iter().flat_map(|n| n.labels.clone()).collect();
+    labels.sort();
+    labels.dedup();
+
+    if notes.is_empty() {
+        warnings.push("No notes found in the export".to_string());
+    }
+
+    Ok(KeepImportPreview {
+        note_count: notes.len(),
+        checklist_count: notes.iter().filter(|n| !n.items.is_empty()).count(),
+        archived_count: notes.iter().filter(|n| n.archived).count(),
+        trashed_count,
+        attachment_count: notes.iter().map(|n| n.attachments.len()).sum(),
+        labels,
+        duplicate_titles,
+        notes: notes
+            .iter()
+            .take(10)
+            .map(|n| KeepNotePreview {
+                title: n.title.clone(),
+                labels: n.labels.clone(),
+                is_checklist: !n.items.is_empty(),
+                color: n.color.clone(),
+                attachment_count: n.attachments.len(),
+            })
+            .collect(),
+        suggested_name: "Google Keep".to_string(),
+        warnings,
+    })
+}
+
+/// Import a Google Keep takeout into `target_notebook`, or into a new
+/// notebook named `notebook_name` when no target is given
+pub fn import_keep_with_progress<F>(
+    path: &Path,
+    storage: &FileStorageWriter,
+    target_notebook: Option<Uuid>,
+    notebook_name: Option<String>,
+    duplicates: KeepDuplicateStrategy,
+    progress: F,
+) -> Result<KeepImportResult>
+where
+    F: Fn(usize, usize, &str),
+{
+    progress(0, 100, "Reading Keep export...");
+    let mut source = KeepSource::open(path)?;
+    let mut warnings = Vec::new();
+    let notes: Vec<KeepNote> = read_notes(&mut source, &mut warnings)
+        .into_iter()
+        .filter(|n| !n.trashed)
+        .collect();
+
+    let notebook = match target_notebook {
+        Some(id) => storage.get_notebook(id)?,
+        None => {
+            let mut notebook = storage.create_notebook(
+                notebook_name.unwrap_or_else(|| "Google Keep".to_string()),
+                NotebookType::Standard,
+            )?;
+            notebook.icon = Some("💡".to_string());
+            storage.update_notebook(&notebook)?;
+            notebook
+        }
+    };
+    let existing = existing_titles(storage, notebook.id)?;
+    let titles: Vec<String> = notes.iter().map(|n| n.title.clone()).collect();
+    let placements = plan_placements(&titles, &existing, duplicates);
+
+    let assets_dir = storage.notebook_assets_dir(notebook.id);
+    fs::create_dir_all(&assets_dir)?;
+
+    let total = notes.len();
+    let mut result = KeepImportResult {
+        notebook: notebook.clone(),
+        imported: 0,
+        replaced: 0,
+        skipped: 0,
+        warnings: Vec::new(),
+    };
+
+    for (i, (note, placement)) in notes.iter().zip(placements).enumerate() {
+        progress(i, total, &format!("Importing {}", note.title));
+
+        if placement == Placement::Skip {
+            result.skipped += 1;
+            continue;
+        }
+
+        // Copy attachments
+        let mut asset_urls = HashMap::new();
+        for attachment in &note.attachments {
+            let (name, data) = match attachment {
+                KeepAttachment::File { name, .. } => match source.read_attachment(name) {
+                    Some((_, data)) => (name.clone(), data),
+                    None => {
+                        warnings.push(format!(
+                            "Attachment {} of \"{}\" is missing from the export",
+                            name, note.title
+                        ));
+                        continue;
+                    }
+                },
+                KeepAttachment::Inline { name, data } => (name.clone(), data.clone()),
+            };
+            let file_name = Path::new(&name)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| name.clone());
+            fs::write(assets_dir.join(&file_name), data)?;
+            asset_urls.insert(name, format!("asset://{}/{}", notebook.id, file_name));
+        }
+
+        let blocks = note_to_blocks(note, &asset_urls);
+        let content = EditorData {
+            time: Some(Utc::now().timestamp_millis()),
+            version: Some("2.28.0".to_string()),
+            blocks,
+        };
+
+        match placement {
+            Placement::Replace(page_id) => {
+                let mut page = storage.get_page(notebook.id, page_id)?;
+                page.content = content;
+                for label in &note.labels {
+                    if !page.tags.contains(label) {
+                        page.tags.push(label.clone());
+                    }
+                }
+                page.color = note
+                    .color
+                    .as_deref()
+                    .and_then(keep_color_css)
+                    .map(String::from);
+                page.is_favorite = note.pinned;
+                page.is_archived = note.archived;
+                page.updated_at = Utc::now();
+                storage.update_page(&page)?;
+                result.replaced += 1;
+            }
+            Placement::Create(title) => {
+                let mut page = Page::new(notebook.id, title);
+                page.content = content;
+                page.tags = note.labels.clone();
+                page.color = note
+                    .color
+                    .as_deref()
+                    .and_then(keep_color_css)
+                    .map(String::from);
+                page.is_favorite = note.pinned;
+                page.is_archived = note.archived;
+                if let Some(created) = note.created {
+                    page.created_at = created;
+                }
+                if let Some(updated) = note.updated {
+                    page.updated_at = updated;
+                }
+                storage.create_page_from(page)?;
+                result.imported += 1;
+            }
+            Placement::Skip => unreachable!("skipped above"),
+        }
+    }
+
+    progress(total, total, "Import complete");
+    result.warnings = warnings;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_json_checklist_note() {
+        let json = r#"{
+            "color": "TEAL", "isTrashed": false, "isPinned": true, "isArchived": false,
+            "title": "",
+            "listContent": [
+                {"text": "Milk & eggs", "isChecked": false},
+                {"text": "Bread", "isChecked": true}
+            ],
+            "labels": [{"name": "Shopping"}],
+            "attachments": [{"filePath": "abc.jpeg", "mimetype": "image/jpeg"}],
+            "annotations": [{"source": "WEBLINK", "title": "", "url": "https://example.com"}],
+            "userEditedTimestampUsec": 1700000000000000,
+            "createdTimestampUsec": 1690000000000000
+        }"#;
+        let mut note = parse_json_note(json).unwrap();
+        note.title = derive_title(&note, "Untitled");
+        assert_eq!(note.title, "Milk & eggs");
+        assert_eq!(note.color.as_deref(), Some("TEAL"));
+        assert_eq!(keep_color_css("TEAL"), Some("#a7ffeb"));
+        assert!(note.pinned);
+        assert_eq!(note.created.unwrap().timestamp(), 1_690_000_000);
+
+        let mut urls = HashMap::new();
+        urls.insert("abc.jpeg".to_string(), "asset://nb/abc.jpg".to_string());
+        let blocks = note_to_blocks(&note, &urls);
+        let types: Vec<&str> = blocks.iter().map(|b| b.block_type.as_str()).collect();
+        assert_eq!(types, vec!["checklist", "image", "list"]);
+        assert_eq!(blocks[0].data["items"][0]["text"], "Milk &amp; eggs");
+        assert_eq!(blocks[0].data["items"][1]["checked"], true);
+        assert_eq!(blocks[1].data["file"]["url"], "asset://nb/abc.jpg");
+    }
+
+    #[test]
+    fn parses_html_only_note() {
+        let html = r#"<html><body><div class="note RED"><div class="heading">Jan 3, 2019, 10:26:47 AM</div>
+<div class="title">Ideas</div>
+<div class="content">First line<br>second line<br><br>New &amp; paragraph</div>
+<div class="chips"><span class="chip label"><span class="label-name">Work</span></span></div>
+<div class="attachments"><ul><li><img alt="" src="data:image/png;base64,iVBORw0KGgo=" /></li></ul></div>
+</div></body></html>"#;
+        let note = parse_html_note(html, "Ideas");
+        assert_eq!(note.title, "Ideas");
+        assert_eq!(note.color.as_deref(), Some("RED"));
+        assert_eq!(note.labels, vec!["Work".to_string()]);
+        assert_eq!(note.text, "First line\nsecond line\n\nNew & paragraph");
+        assert!(note.updated.is_some());
+        assert!(matches!(
+            &note.attachments[0],
+            KeepAttachment::Inline { name, .. } if name == "Ideas-1.png"
+        ));
+
+        let blocks = note_to_blocks(&note, &HashMap::new());
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].data["text"], "First line<br>second line");
+        assert_eq!(blocks[1].data["text"], "New &amp; paragraph");
+
+        let list = r#"<div class="content"><ul class="list"><li class="listitem checked"><span class="bullet">&#9745;</span><span class="text">Done</span></li><li class="listitem"><span class="bullet">&#9744;</span><span class="text">Todo</span></li></ul></div>"#;
+        let note = parse_html_note(list, "x");
+        assert_eq!(
+            note.items,
+            vec![("Done".to_string(), true), ("Todo".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn duplicate_titles_follow_strategy() {
+        let existing_id = Uuid::new_v4();
+        let mut existing = HashMap::new();
+        existing.insert("groceries".to_string(), existing_id);
+        let titles = vec![
+            "Groceries".to_string(),
+            "Ideas".to_string(),
+            "Ideas".to_string(),
+        ];
+
+        assert_eq!(
+            plan_placements(&titles, &existing, KeepDuplicateStrategy::Rename),
+            vec![
+                Placement::Create("Groceries (2)".to_string()),
+                Placement::Create("Ideas".to_string()),
+                Placement::Create("Ideas (2)".to_string()),
+            ]
+        );
+        assert_eq!(
+            plan_placements(&titles, &existing, KeepDuplicateStrategy::Skip)[0],
+            Placement::Skip
+        );
+        let replace = plan_placements(
+            &["Groceries".to_string(), "Groceries".to_string()],
+            &existing,
+            KeepDuplicateStrategy::Replace,
+        );
+        assert_eq!(
+            replace,
+            vec![
+                Placement::Replace(existing_id),
+                Placement::Create("Groceries (2)".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,8 @@
+//! Google Keep import module
+//!
+//! Handles importing Google Takeout Keep exports into Nous notebooks.
+//! Supports: Takeout zip archives or extracted folders, JSON and HTML notes,
+//! checklists, labels (as tags), note colors, and image attachments
+
+mod import;
+pub use import::*;
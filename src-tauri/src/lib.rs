@@ -1,47 +1,93 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use tauri::Manager;
+use uuid::Uuid;
 
+pub mod ai_action_log;
+pub mod ai_cache;
+pub mod ai_bulk;
 pub mod ai_config;
+pub mod ai_permissions;
+pub mod ai_profiles;
+pub mod app_config;
+mod auto_link;
 pub mod actions;
+pub mod activity;
+pub mod block_ops;
+pub mod charts;
 mod chat_sessions;
+pub mod clipper;
+pub mod code_exec;
 pub mod collab;
+pub mod comments;
+pub mod deep_link;
 pub mod events;
 pub mod commands;
+pub mod diagnostic_bundle;
+pub mod diagnostics;
 pub mod contacts;
 pub mod energy;
 mod freeze_watchdog;
 pub mod encryption;
-mod evernote;
-mod external_editor;
+pub mod evernote;
+pub mod external_editor;
 pub mod external_sources;
-mod flashcards;
+pub mod find_replace;
+pub mod flashcards;
+pub mod folder_sync;
+pub mod generated_media;
 pub mod git;
 pub mod goals;
+pub mod history;
 pub mod inbox;
 mod joplin;
+mod keep;
 mod monitor;
 pub mod library;
+pub mod lint;
+pub mod logging;
 pub mod markdown;
-mod notion;
-mod obsidian;
+mod meeting_notes;
+pub mod mobile;
+mod notifications;
+pub mod notion;
+pub mod obsidian;
+pub mod ollama;
 mod onenote;
 mod orgmode;
+mod os_search;
+pub mod proofread;
 mod publish;
 pub mod python_bridge;
+mod quick_capture;
+mod reminders;
 pub mod share;
 mod rag;
+pub mod reading_queue;
+mod roam;
 mod scrivener;
 pub mod search;
+pub mod secrets;
+mod spaced_review;
+pub mod sql_notebook;
+mod startup;
 pub mod storage;
 pub mod sync;
+pub mod tags;
+pub mod translation;
+mod tray;
 mod video_server;
+mod webdav_server;
 #[cfg(feature = "plugins")]
 pub mod plugins;
 
+use ai_cache::AiCache;
 use actions::{ActionExecutor, ActionScheduler, ActionStorage};
 use chat_sessions::ChatSessionStorage;
+use commands::CommandError;
+use comments::CommentsStorage;
 use contacts::ContactsStorage;
 use encryption::EncryptionManager;
 use external_editor::ExternalEditorManager;
@@ -50,41 +96,65 @@ use flashcards::FlashcardStorage;
 use energy::EnergyStorage;
 use goals::GoalsStorage;
 use inbox::InboxStorage;
-use library::LibraryStorage;
+use library::{Library, LibraryContext, LibraryStorage};
 use monitor::MonitorStorage;
+use python_bridge::queue::PythonQueue;
 use python_bridge::PythonAI;
 use rag::VectorIndex;
-use storage::FileStorage;
-use sync::{SyncManager, SyncScheduler};
+use secrets::SecretStore;
+use history::HistoryStorage;
+use reminders::ReminderStorage;
+use storage::{FileStorage, FileStorageWriter, StorageLock};
+use sync::SyncManager;
 use collab::storage::CollabStorage;
 use share::storage::ShareStorage;
 use video_server::VideoServer;
+use webdav_server::WebDavServer;
 
 pub struct AppState {
     pub library_storage: Arc<Mutex<LibraryStorage>>,
-    pub storage: Arc<Mutex<FileStorage>>,
+    pub storage: Arc<StorageLock>,
     // CRDT store now lives in the daemon. Frontend pane lifecycle goes
     // through the daemon's /api/events WS; updatePage routes through the
     // daemon's update_page handler which calls apply_save.
-    pub vector_index: Mutex<VectorIndex>,
+    pub vector_index: Arc<Mutex<VectorIndex>>,
     pub python_ai: Arc<Mutex<PythonAI>>,
+    /// Worker pool for Python calls from commands (see `python_bridge::queue`)
+    pub python_queue: Arc<PythonQueue>,
     pub action_storage: Arc<Mutex<ActionStorage>>,
     pub action_executor: Arc<Mutex<ActionExecutor>>,
     pub action_scheduler: Mutex<ActionScheduler>,
     pub inbox_storage: Arc<Mutex<InboxStorage>>,
-    pub flashcard_storage: Mutex<FlashcardStorage>,
+    pub flashcard_storage: Arc<Mutex<FlashcardStorage>>,
+    pub comments_storage: Arc<Mutex<CommentsStorage>>,
     pub goals_storage: Arc<Mutex<GoalsStorage>>,
+    pub reminder_storage: Arc<Mutex<ReminderStorage>>,
+    pub history_storage: Arc<Mutex<HistoryStorage>>,
     pub energy_storage: Arc<Mutex<EnergyStorage>>,
     pub contacts_storage: Arc<Mutex<ContactsStorage>>,
     pub sync_manager: Arc<SyncManager>,
     pub external_editor: Mutex<ExternalEditorManager>,
     pub external_sources_storage: Arc<Mutex<ExternalSourcesStorage>>,
-    pub sync_scheduler: Arc<tokio::sync::Mutex<Option<SyncScheduler>>>,
+    /// The main window's library. Shares its stores with the fields above,
+    /// so `switch_library` retargets both.
+    pub main_library: Arc<LibraryContext>,
+    /// Libraries opened in their own window (`open_library_window`)
+    pub library_windows: Mutex<HashMap<Uuid, Arc<LibraryContext>>>,
     pub video_server: Arc<tokio::sync::Mutex<Option<VideoServer>>>,
+    /// WebDAV share of the library, when turned on
+    pub webdav_server: Arc<tokio::sync::Mutex<Option<WebDavServer>>>,
     pub chat_session_storage: Arc<Mutex<ChatSessionStorage>>,
     pub encryption_manager: Arc<EncryptionManager>,
+    /// Provider API keys and service passwords
+    pub secrets: Arc<SecretStore>,
+    /// Cached AI responses (see `ai_cache`)
+    pub ai_cache: Arc<AiCache>,
     pub monitor_storage: Arc<Mutex<MonitorStorage>>,
     pub monitor_scheduler: Mutex<Option<monitor::scheduler::MonitorScheduler>>,
+    /// Watch-folder auto-import thread (started in the setup hook)
+    pub watch_folders: Mutex<Option<external_sources::WatchFolders>>,
+    /// Shared decks folder watcher (started in the setup hook)
+    pub shared_decks_watcher: Mutex<Option<flashcards::shared_folder::SharedDecksWatcher>>,
     pub share_storage: Arc<Mutex<ShareStorage>>,
     pub collab_storage: Arc<Mutex<CollabStorage>>,
     /// Keeps the MCP file watcher alive for the app's lifetime.
@@ -93,6 +163,44 @@ pub struct AppState {
     pub plugin_host: Option<Arc<Mutex<plugins::PluginHost>>>,
 }
 
+impl AppState {
+    /// The library `window` shows: its own context for a `library-{id}`
+    /// window, the main library otherwise
+    pub fn library(&self, window: &tauri::Window) -> Arc<LibraryContext> {
+        window
+            .label()
+            .strip_prefix("library-")
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .and_then(|id| self.library_windows.lock().ok()?.get(&id).cloned())
+            .unwrap_or_else(|| Arc::clone(&self.main_library))
+    }
+
+    /// Root directory of the library `window` shows
+    pub fn library_path(&self, window: &tauri::Window) -> Result<PathBuf, CommandError> {
+        self.library(window).path().map_err(|e| CommandError {
+            message: format!("Storage error: {}", e),
+        })
+    }
+
+    /// The library list entry of the library `window` shows
+    pub fn window_library(&self, window: &tauri::Window) -> Result<Library, CommandError> {
+        let path = self.library_path(window)?;
+        let library_storage = self.library_storage.lock().map_err(|e| CommandError {
+            message: format!("Lock error: {}", e),
+        })?;
+        library_storage
+            .list_libraries()
+            .map_err(|e| CommandError {
+                message: format!("Failed to list libraries: {}", e),
+            })?
+            .into_iter()
+            .find(|library| library.path == path)
+            .ok_or_else(|| CommandError {
+                message: "No library is open in this window".to_string(),
+            })
+    }
+}
+
 /// Check whether the Nous daemon is running by reading its PID file and
 /// verifying the process is alive.  When the daemon is active it already
 /// runs the `ActionScheduler`, so the Tauri app should skip starting its
@@ -155,11 +263,65 @@ fn find_python_bundle() -> Option<PathBuf> {
     None
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
+/// Start the action and sync schedulers, unless the daemon is running them.
+/// Both processes hitting WebDAV against the same library wastes
+/// round-trips and opens a small race window when something has actually
+/// changed since the last sync; running actions twice is worse.
+#[cfg(desktop)]
+fn start_schedulers(app: &tauri::AppHandle, daemon_pid_path: PathBuf) -> Result<(), String> {
+    let state: tauri::State<AppState> = app.state();
+    if is_daemon_running(&daemon_pid_path) {
+        log::info!(
+            "Daemon detected (PID file {:?}); skipping local action and sync schedulers",
+            daemon_pid_path
+        );
+        return Ok(());
+    }
+
+    state
+        .action_scheduler
+        .lock()
+        .map_err(|e| format!("Failed to lock action scheduler: {}", e))?
+        .start();
+    log::info!("Action scheduler started");
+
+    // DL-21: the app may have launched before the daemon. If the daemon
+    // starts later, this predicate makes the app's scheduler yield periodic
+    // sync to it (re-checked every tick, not just at startup).
+    let should_yield: sync::scheduler::ShouldYield =
+        Arc::new(move || is_daemon_running(&daemon_pid_path));
+    let sync_scheduler = sync::scheduler::start_sync_scheduler(
+        Arc::clone(&state.sync_manager),
+        Arc::clone(&state.storage),
+        Arc::clone(&state.library_storage),
+        Arc::clone(&state.goals_storage),
+        Arc::clone(&state.inbox_storage),
+        Arc::clone(&state.contacts_storage),
+        Arc::clone(&state.energy_storage),
+        Arc::clone(&state.action_storage),
+        Some(should_yield),
+    );
+    *state.main_library.sync_scheduler.blocking_lock() = Some(sync_scheduler);
+    Ok(())
+}
+
+/// Mobile builds run the capture-and-read companion instead (see `mobile`)
+#[cfg(mobile)]
+#[tauri::mobile_entry_point]
+pub fn run() {
+    mobile::run();
+}
+
+#[cfg(desktop)]
 pub fn run() {
     // Get base data directory
     let data_dir = FileStorage::default_data_dir().expect("Failed to get data directory");
 
+    // Start logging before anything has something to say
+    if let Err(e) = logging::init(&data_dir) {
+        eprintln!("Failed to start logging: {}", e);
+    }
+
     // Initialize library storage first - this handles default library creation
     let library_storage = LibraryStorage::new(data_dir.clone());
     let current_library = library_storage.init().expect("Failed to initialize library storage");
@@ -168,16 +330,17 @@ pub fn run() {
     let library_path = current_library.path.clone();
 
     // Initialize file storage at the library path
-    let storage = FileStorage::new(library_path.clone());
+    let storage = FileStorageWriter::new(FileStorage::new(library_path.clone()));
     storage.init().expect("Failed to initialize storage");
 
     // Search index now lives in the daemon (single-writer; daemon owns the
     // Tantivy lock). Tauri-side commands that touched the index have been
     // stubbed; frontend queries go through daemon HTTP.
 
-    // Initialize vector index for RAG at the library path
+    // Vector index for RAG at the library path. Opened in the background
+    // once the window is up (see `startup`), or by whatever needs it first.
     let vector_db_path = current_library.path.join(".nous").join("vectors.db");
-    let vector_index = VectorIndex::new(vector_db_path).expect("Failed to initialize vector index");
+    let vector_index = VectorIndex::lazy(vector_db_path);
 
     // Initialize Python AI bridge
     // Check for bundled Python first (release builds), then fall back to dev layout
@@ -231,11 +394,23 @@ pub fn run() {
     // Initialize flashcard storage (library-scoped)
     let flashcard_storage = FlashcardStorage::new(library_path.join("notebooks"));
 
+    // Initialize comments storage (library-scoped, per notebook)
+    let comments_storage = CommentsStorage::new(library_path.join("notebooks"));
+
     // Initialize goals storage (library-scoped)
     let goals_storage = GoalsStorage::new(library_path.clone())
         .expect("Failed to initialize goals storage");
     let goals_storage_arc = Arc::new(Mutex::new(goals_storage));
 
+    // Initialize page reminder storage (library-scoped)
+    let reminder_storage = ReminderStorage::new(library_path.clone())
+        .expect("Failed to initialize reminder storage");
+
+    // Initialize page view history (library-scoped)
+    let history_storage_arc = Arc::new(Mutex::new(
+        HistoryStorage::new(library_path.clone()).expect("Failed to initialize view history"),
+    ));
+
     // Initialize energy storage
     let energy_storage = EnergyStorage::new(data_dir.clone())
         .expect("Failed to initialize energy storage");
@@ -251,9 +426,20 @@ pub fn run() {
         .expect("Failed to initialize chat session storage");
     let chat_session_storage_arc = Arc::new(Mutex::new(chat_session_storage));
 
+    // Open the secrets store (API keys, sync passwords)
+    let secrets = secrets::shared(&data_dir);
+
+    // Open the AI response cache, dropping what has expired
+    let ai_cache = AiCache::open(&data_dir.join("ai_cache.db"))
+        .expect("Failed to initialize AI cache");
+    if let Err(e) = ai_cache.prune_expired() {
+        log::warn!("Failed to prune AI cache: {}", e);
+    }
+
     // Initialize sync manager
     let sync_manager = SyncManager::new(data_dir.clone());
     let sync_manager_arc = Arc::new(sync_manager);
+    sync_manager_arc.set_secrets(Arc::clone(&secrets));
 
     // CRDT store moved to the daemon (Daemon API migration). Tauri no
     // longer constructs one — multi-pane merge happens via daemon HTTP/WS.
@@ -278,8 +464,9 @@ pub fn run() {
     let external_sources_storage_arc = Arc::new(Mutex::new(external_sources_storage));
 
     // Wrap storage in Arc<Mutex<>> for sharing with executor
-    let storage_arc = Arc::new(Mutex::new(storage));
+    let storage_arc = Arc::new(StorageLock::new(storage));
     let action_storage_arc = Arc::new(Mutex::new(action_storage));
+    let python_queue = Arc::new(PythonQueue::new(python_ai.clone()));
     let python_ai_arc = Arc::new(Mutex::new(python_ai));
 
     // Initialize action executor (needs references to storage, action_storage, and python_ai)
@@ -293,6 +480,8 @@ pub fn run() {
     action_executor.set_goals_storage(Arc::clone(&goals_storage_arc));
     action_executor.set_energy_storage(Arc::clone(&energy_storage_arc));
     action_executor.set_inbox_storage(Arc::clone(&inbox_storage_arc));
+    action_executor.set_history_storage(Arc::clone(&history_storage_arc));
+    action_executor.set_secrets(Arc::clone(&secrets));
 
     // Initialize plugin host (optional, behind "plugins" feature)
     #[cfg(feature = "plugins")]
@@ -345,66 +534,67 @@ pub fn run() {
 
     // Backup scheduler now lives in the daemon (see `bin/cli/daemon.rs::run`) and settings flow through `POST /api/backup/settings`.
 
-    // Start sync scheduler for periodic syncs — but skip if the daemon is
-    // already running one. Both processes hitting WebDAV against the same
-    // library wastes round-trips and opens a small race window when
-    // something has actually changed since the last sync. Mirrors the
-    // action-scheduler PID-file guard below in the setup hook.
-    let daemon_pid_path = data_dir.join(".nous-daemon.pid");
-    let sync_scheduler_arc = if is_daemon_running(&daemon_pid_path) {
-        log::info!(
-            "Daemon detected (PID file {:?}); skipping local sync scheduler — daemon owns sync",
-            daemon_pid_path
-        );
-        Arc::new(tokio::sync::Mutex::new(None))
-    } else {
-        // DL-21: the app may have launched before the daemon. If the daemon
-        // starts later, this predicate makes the app's scheduler yield periodic
-        // sync to it (re-checked every tick, not just at startup).
-        let yield_pid_path = daemon_pid_path.clone();
-        let should_yield: sync::scheduler::ShouldYield =
-            Arc::new(move || is_daemon_running(&yield_pid_path));
-        let sync_scheduler = sync::scheduler::start_sync_scheduler(
-            Arc::clone(&sync_manager_arc),
-            Arc::clone(&storage_arc),
-            Arc::clone(&library_storage_arc),
-            Arc::clone(&goals_storage_arc),
-            Arc::clone(&inbox_storage_arc),
-            Arc::clone(&contacts_storage_arc),
-            Arc::clone(&energy_storage_arc),
-            Some(should_yield),
-        );
-        Arc::new(tokio::sync::Mutex::new(Some(sync_scheduler)))
-    };
+    // The sync scheduler is started in the background from the setup hook
+    // (see `startup`), unless the daemon owns sync
+    let sync_scheduler_arc = Arc::new(tokio::sync::Mutex::new(None));
 
     // Video server will be started in setup hook
     let video_server_arc = Arc::new(tokio::sync::Mutex::new(None));
 
     // Initialize encryption manager
-    let encryption_manager = Arc::new(EncryptionManager::new());
+    let encryption_manager = Arc::new(
+        EncryptionManager::new().with_biometric_store(data_dir.join("biometric_unlock.json")),
+    );
+
+    let vector_index_arc = Arc::new(Mutex::new(vector_index));
+    let flashcard_storage_arc = Arc::new(Mutex::new(flashcard_storage));
+    let comments_storage_arc = Arc::new(Mutex::new(comments_storage));
+    let reminder_storage_arc = Arc::new(Mutex::new(reminder_storage));
+    let main_library = Arc::new(LibraryContext {
+        storage: Arc::clone(&storage_arc),
+        vector_index: Arc::clone(&vector_index_arc),
+        action_storage: Arc::clone(&action_storage_arc),
+        inbox_storage: Arc::clone(&inbox_storage_arc),
+        flashcard_storage: Arc::clone(&flashcard_storage_arc),
+        comments_storage: Arc::clone(&comments_storage_arc),
+        goals_storage: Arc::clone(&goals_storage_arc),
+        reminder_storage: Arc::clone(&reminder_storage_arc),
+        history_storage: Arc::clone(&history_storage_arc),
+        sync_scheduler: sync_scheduler_arc,
+    });
 
     let state = AppState {
         library_storage: library_storage_arc,
         storage: storage_arc,
-        vector_index: Mutex::new(vector_index),
+        vector_index: vector_index_arc,
         python_ai: python_ai_arc,
+        python_queue,
         action_storage: action_storage_arc,
         action_executor: action_executor_arc,
         action_scheduler: Mutex::new(action_scheduler),
         inbox_storage: inbox_storage_arc,
-        flashcard_storage: Mutex::new(flashcard_storage),
+        flashcard_storage: flashcard_storage_arc,
+        comments_storage: comments_storage_arc,
         goals_storage: goals_storage_arc,
+        reminder_storage: reminder_storage_arc,
+        history_storage: history_storage_arc,
         energy_storage: energy_storage_arc,
         contacts_storage: contacts_storage_arc,
         sync_manager: sync_manager_arc,
         external_editor: Mutex::new(external_editor),
         external_sources_storage: external_sources_storage_arc,
         chat_session_storage: chat_session_storage_arc,
-        sync_scheduler: sync_scheduler_arc,
+        main_library,
+        library_windows: Mutex::new(HashMap::new()),
         video_server: video_server_arc,
+        webdav_server: Arc::new(tokio::sync::Mutex::new(None)),
         encryption_manager,
+        secrets,
+        ai_cache: Arc::new(ai_cache),
         monitor_storage: monitor_storage_arc,
         monitor_scheduler: Mutex::new(None),
+        watch_folders: Mutex::new(None),
+        shared_decks_watcher: Mutex::new(None),
         share_storage: share_storage_arc,
         collab_storage: collab_storage_arc,
         #[cfg(feature = "plugins")]
@@ -416,17 +606,28 @@ pub fn run() {
     tauri::Builder::default()
         .manage(state)
         .manage(Arc::clone(&watchdog_state))
+        .manage(startup::StartupStatus::new())
+        .manage(deep_link::PendingDeepLinks::default())
+        .manage(quick_capture::RegisteredShortcut::default())
+        // Must be the first plugin: a second launch (e.g. the OS opening a
+        // nous:// link on Linux/Windows) hands its URL to this instance
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            // The main window may be hidden or closed while the app stays
+            // resident for quick capture
+            quick_capture::show_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    quick_capture::on_shortcut(app, shortcut, event.state())
+                })
+                .build(),
+        )
         .setup(move |app| {
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
-
             // Register the data directory with the asset protocol scope
             // This allows all assets (images, videos, etc.) to be loaded via convertFileSrc
             if let Ok(data_dir) = storage::FileStorage::default_data_dir() {
@@ -474,17 +675,31 @@ pub fn run() {
                 }
             }
 
-            // Start the action scheduler (skip if the daemon is already running one)
             let state: tauri::State<AppState> = app.handle().state();
-            let daemon_pid_path = data_dir.join(".nous-daemon.pid");
-            if is_daemon_running(&daemon_pid_path) {
-                log::info!(
-                    "Daemon detected (PID file {:?}); skipping local action scheduler",
-                    daemon_pid_path
-                );
-            } else if let Ok(mut scheduler) = state.action_scheduler.lock() {
-                scheduler.start();
-                log::info!("Action scheduler started");
+
+            // Heavyweight subsystems start in the background so they don't
+            // hold up the first frame
+            {
+                let vector_index = Arc::clone(&state.vector_index);
+                startup::start(app.handle(), startup::Subsystem::VectorIndex, move || {
+                    let index = vector_index.lock().map_err(|e| e.to_string())?;
+                    index.load().map_err(|e| e.to_string())
+                });
+
+                let python_ai = state
+                    .python_ai
+                    .lock()
+                    .map(|python_ai| python_ai.clone())
+                    .map_err(|e| e.to_string());
+                startup::start(app.handle(), startup::Subsystem::Python, move || {
+                    python_ai?.warm_up().map_err(|e| e.to_string())
+                });
+
+                let handle = app.handle().clone();
+                let daemon_pid_path = data_dir.join(".nous-daemon.pid");
+                startup::start(app.handle(), startup::Subsystem::Schedulers, move || {
+                    start_schedulers(&handle, daemon_pid_path)
+                });
             }
 
             // Give the sync manager the Tauri emitter so scheduler-triggered syncs
@@ -500,12 +715,91 @@ pub fn run() {
             // Give the sync manager the collab storage so it can skip pages with active sessions
             state.sync_manager.set_collab_storage(Arc::clone(&state.collab_storage));
 
+            // nous:// deep links. Linux (and Windows dev builds) register the
+            // scheme at runtime; bundles declare it in tauri.conf.json.
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+
+                #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("Failed to register nous:// scheme: {}", e);
+                }
+
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    for url in urls {
+                        deep_link::dispatch(app.handle(), url.as_str());
+                    }
+                }
+
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        deep_link::dispatch(&handle, url.as_str());
+                    }
+                });
+            }
+
+            // Global shortcut for the quick capture window
+            quick_capture::init(app.handle());
+
+            // Tray icon with sync status, capture and recent pages
+            if let Err(e) = tray::init(app.handle()) {
+                log::warn!("Failed to create tray icon: {}", e);
+            }
+
+            // OS notifications for due cards, goal check-ins, action runs
+            // and sync errors
+            if let Ok(data_dir) = FileStorage::default_data_dir() {
+                notifications::center::init(app.handle(), data_dir);
+            }
+
+            // Keep page stubs in the OS search index current (when enabled)
+            os_search::init(app.handle());
+
             // Start the freeze watchdog (Rust-side ping/pong to detect frontend freezes)
             freeze_watchdog::start_watchdog(
                 app.handle().clone(),
                 Arc::clone(&watchdog_state),
             );
 
+            // Watch-folder auto-import for external sources in watch mode
+            {
+                use tauri::Emitter;
+
+                let handle = app.handle().clone();
+                let watch_folders = external_sources::watch::start(
+                    Arc::clone(&state.external_sources_storage),
+                    Arc::clone(&state.storage),
+                    Arc::clone(&state.python_ai),
+                    move |event| {
+                        let _ = handle.emit("watch-folder-import", event);
+                    },
+                );
+                if let Ok(mut slot) = state.watch_folders.lock() {
+                    *slot = Some(watch_folders);
+                }
+            }
+
+            // Refresh the shared decks browser when bundles come and go
+            {
+                use tauri::Emitter;
+
+                let handle = app.handle().clone();
+                let library_storage = Arc::clone(&state.library_storage);
+                let watcher = flashcards::shared_folder::start(
+                    move || {
+                        let library = library_storage.lock().ok()?.get_current_library().ok()?;
+                        Some(library.shared_decks_path())
+                    },
+                    move |event| {
+                        let _ = handle.emit("shared-decks-changed", event);
+                    },
+                );
+                if let Ok(mut slot) = state.shared_decks_watcher.lock() {
+                    *slot = Some(watcher);
+                }
+            }
+
             // MCP file watcher removed: the daemon's WS event stream
             // (page.created/updated/deleted, folder.*, section.*, ...) now
             // drives live UI refresh. External file edits (git pull, manual
@@ -573,6 +867,8 @@ pub fn run() {
             commands::get_all_favorite_pages,
             // Page history commands
             commands::get_page_oplog,
+            commands::get_page_stats,
+            commands::get_notebook_stats,
             commands::list_page_snapshots,
             commands::restore_page_snapshot,
             // Block-level history commands
@@ -588,10 +884,29 @@ pub fn run() {
             commands::ai_suggest_tags,
             commands::ai_suggest_related_pages,
             commands::ai_chat_with_tools,
+            commands::list_ai_actions,
+            commands::record_ai_action,
+            commands::undo_ai_action,
+            commands::undo_ai_session,
+            commands::get_tool_permissions,
+            commands::save_tool_permissions,
+            commands::check_tool_permission,
             commands::ai_chat_stream,
             commands::ai_summarize_pages,
             commands::browser_run_task,
             commands::discover_ai_models,
+            commands::ollama_list_models,
+            commands::ollama_pull_model,
+            commands::ollama_delete_model,
+            commands::ollama_health,
+            commands::cancel_ai_task,
+            commands::clear_ai_cache,
+            commands::get_ai_cache_stats,
+            commands::ai_bulk_transform,
+            commands::apply_ai_bulk_transform,
+            commands::apply_link_suggestions,
+            commands::translate_page,
+            commands::translate_folder,
             // Study tools commands
             commands::generate_study_guide,
             commands::generate_faq,
@@ -627,6 +942,15 @@ pub fn run() {
             commands::rename_tag,
             commands::merge_tags,
             commands::delete_tag,
+            commands::get_tag_registry,
+            commands::save_tag_definition,
+            commands::delete_tag_definition,
+            commands::get_tag_tree,
+            commands::get_pages_by_tag,
+            commands::list_tag_rules,
+            commands::save_tag_rule,
+            commands::delete_tag_rule,
+            commands::test_tag_rule,
             // Backup commands
             commands::export_notebook_zip,
             commands::import_notebook_zip,
@@ -634,7 +958,35 @@ pub fn run() {
             commands::create_notebook_backup,
             commands::list_backups,
             commands::delete_backup,
+            commands::create_backup_keyfile,
+            commands::preview_backup_restore,
+            commands::set_library_backup_destination,
             commands::run_scheduled_backup,
+            commands::verify_storage_integrity,
+            // Content lint commands
+            commands::lint_notebook,
+            commands::fix_lint_diagnostics,
+            // Find and replace
+            commands::search_and_replace,
+            // Proofreading commands
+            commands::proofread_page,
+            commands::list_spellcheck_languages,
+            commands::list_dictionary_words,
+            commands::add_dictionary_word,
+            commands::remove_dictionary_word,
+            // Code block execution commands
+            commands::list_code_runners,
+            commands::get_code_exec_config,
+            commands::set_code_exec_config,
+            commands::execute_code_block,
+            commands::clear_code_outputs,
+            // Chart block commands
+            commands::compute_chart_data,
+            // SQL notebook commands
+            commands::execute_sql_cell,
+            commands::list_datasets,
+            commands::add_dataset,
+            commands::remove_dataset,
             // Notion import commands
             commands::preview_notion_export,
             commands::import_notion_export,
@@ -650,6 +1002,12 @@ pub fn run() {
             // Joplin import commands
             commands::preview_joplin_import_cmd,
             commands::import_joplin_cmd,
+            // Google Keep import commands
+            commands::preview_keep_import_cmd,
+            commands::import_keep_cmd,
+            // Roam / Logseq import commands
+            commands::preview_roam_import_cmd,
+            commands::import_roam_cmd,
             // OneNote import commands
             commands::preview_onenote_cmd,
             commands::import_onenote_cmd,
@@ -671,6 +1029,7 @@ pub fn run() {
             commands::unarchive_page,
             commands::reorder_folders,
             commands::reorder_pages,
+            commands::set_page_pinned,
             commands::ensure_archive_folder,
             commands::move_folder_to_notebook,
             commands::archive_folder,
@@ -705,6 +1064,7 @@ pub fn run() {
             commands::get_external_source,
             commands::create_external_source,
             commands::update_external_source,
+            commands::set_external_source_watch,
             commands::delete_external_source,
             commands::preview_external_source_files,
             commands::preview_path_pattern_files,
@@ -712,6 +1072,9 @@ pub fn run() {
             commands::inbox_capture,
             commands::inbox_list,
             commands::inbox_list_unprocessed,
+            commands::inbox_list_snoozed,
+            commands::snooze_inbox_item,
+            commands::unsnooze_inbox_item,
             commands::inbox_summary,
             commands::inbox_classify,
             commands::inbox_apply_actions,
@@ -792,6 +1155,8 @@ pub fn run() {
             commands::git_abort_merge,
             // External editor commands
             commands::get_external_editors,
+            commands::get_custom_editors,
+            commands::save_custom_editors,
             commands::open_page_in_editor,
             commands::check_external_changes,
             commands::get_external_file_content,
@@ -800,6 +1165,18 @@ pub fn run() {
             commands::get_external_edit_session,
             commands::get_all_external_edit_sessions,
             commands::cleanup_external_edit_sessions,
+            commands::open_notebook_in_editor,
+            commands::sync_notebook_from_external_editor,
+            commands::resolve_external_edit_conflict,
+            commands::get_notebook_edit_session,
+            commands::end_notebook_edit_session,
+            // Comment commands
+            commands::list_page_comments,
+            commands::add_comment,
+            commands::update_comment,
+            commands::delete_comment,
+            commands::resolve_comment_thread,
+            commands::get_unresolved_comment_counts,
             // Flashcard commands
             commands::list_decks,
             commands::get_deck,
@@ -810,27 +1187,68 @@ pub fn run() {
             commands::get_card,
             commands::create_card,
             commands::create_card_from_block,
+            commands::sync_block_cards,
             commands::update_card,
             commands::delete_card,
             commands::get_due_cards,
             commands::submit_review,
             commands::get_review_stats,
+            commands::get_deck_analytics,
+            commands::list_leech_cards,
             commands::get_card_state,
             commands::preview_review_intervals,
+            commands::add_ir_extract,
+            commands::list_ir_items,
+            commands::delete_ir_item,
+            commands::get_ir_queue,
+            commands::grade_ir_item,
+            commands::get_shared_decks_folder,
+            commands::set_shared_decks_folder,
+            commands::export_deck_bundle,
+            commands::inspect_deck_bundle,
+            commands::list_shared_decks,
+            commands::import_deck_bundle,
             // Sync commands
             commands::sync_test_connection,
             commands::sync_configure,
             commands::sync_status,
             commands::sync_now,
             commands::sync_queue_status,
+            commands::get_sync_queue_details,
+            commands::sync_verify,
+            commands::sync_repair,
+            commands::sync_set_asset_policy,
+            commands::ensure_asset_local,
+            commands::sync_get_activity,
+            commands::sync_set_paused,
             commands::sync_disable,
             // Library sync commands
             commands::library_sync_configure,
             commands::library_sync_disable,
             commands::library_sync_now,
+            commands::get_scratchpad,
+            commands::update_scratchpad,
             commands::library_sync_configure_notebook,
             commands::sync_update_config,
             commands::library_sync_update_config,
+            commands::library_sync_update_bandwidth,
+            commands::library_sync_set_compression,
+            commands::library_sync_set_delta_sync,
+            commands::browse_remote_library,
+            commands::browse_remote_notebook,
+            commands::fetch_remote_page,
+            commands::lock_page_for_editing,
+            commands::release_page_lock,
+            commands::get_page_locks,
+            commands::list_sync_devices,
+            commands::revoke_sync_device,
+            commands::list_remote_page_versions,
+            commands::get_remote_page_version,
+            commands::restore_remote_page_version,
+            // Activity feed commands
+            commands::get_activity_feed,
+            commands::get_activity_last_seen,
+            commands::mark_activity_seen,
             // Document conversion commands (markitdown)
             commands::convert_document,
             commands::convert_documents_batch,
@@ -849,6 +1267,9 @@ pub fn run() {
             commands::read_video_chunk,
             commands::open_video_with_system_player,
             commands::get_video_stream_url,
+            commands::webdav_server_start,
+            commands::webdav_server_stop,
+            commands::webdav_server_status,
             // Drawing/annotation commands
             commands::get_page_annotation,
             commands::save_page_annotation,
@@ -860,6 +1281,8 @@ pub fn run() {
             commands::update_pdf_highlight,
             commands::delete_pdf_highlight,
             commands::delete_pdf_annotations,
+            commands::export_annotated_pdf,
+            commands::import_pdf_annotations,
             // File-based page commands
             commands::import_file_as_page,
             commands::duplicate_database_page,
@@ -871,6 +1294,8 @@ pub fn run() {
             commands::delete_file_page,
             commands::execute_jupyter_cell,
             commands::check_python_execution_available,
+            commands::check_python_environment,
+            commands::repair_python_environment,
             // Library commands
             commands::list_libraries,
             commands::get_library,
@@ -883,6 +1308,17 @@ pub fn run() {
             commands::validate_library_path,
             commands::pick_library_folder,
             commands::move_notebook_to_library,
+            commands::get_library_settings,
+            commands::list_ai_profiles,
+            commands::save_ai_profile,
+            commands::delete_ai_profile,
+            commands::set_library_setting,
+            commands::update_library_markdown_mirror,
+            commands::run_library_markdown_mirror,
+            commands::create_library_snapshot,
+            commands::list_snapshots,
+            commands::delete_library_snapshot,
+            commands::rollback_to_snapshot,
             // MCP server commands
             commands::mcp_load_config,
             commands::mcp_save_config,
@@ -902,6 +1338,8 @@ pub fn run() {
             commands::find_similar_pages,
             commands::get_page_chunks,
             commands::rebuild_vector_index,
+            commands::rechunk_library,
+            commands::set_chunking_strategy,
             commands::get_vector_index_stats,
             commands::generate_embedding,
             commands::generate_embeddings_batch,
@@ -928,22 +1366,51 @@ pub fn run() {
             commands::is_library_encrypted,
             commands::get_library_password_hint,
             commands::lock_all,
+            commands::get_notebook_biometric_status,
+            commands::enable_notebook_biometric_unlock,
+            commands::disable_notebook_biometric_unlock,
+            commands::unlock_notebook_biometric,
+            commands::get_biometric_settings,
+            commands::update_biometric_settings,
             commands::get_encryption_stats,
             commands::cleanup_expired_sessions,
+            // Secrets store commands
+            commands::get_secrets_status,
+            commands::list_secrets,
+            commands::set_secret,
+            commands::rotate_secret,
+            commands::delete_secret,
+            commands::unlock_secrets,
+            commands::set_secrets_protection,
+            commands::import_plaintext_secrets,
+            commands::export_app_config,
+            commands::import_app_config,
             // Audio generation commands
             commands::generate_page_audio,
             commands::get_tts_providers,
             commands::list_tts_voices,
             // Audio recording & transcription commands
             commands::transcribe_audio,
+            commands::process_meeting_recording,
             commands::save_audio_recording,
             commands::synthesize_text,
+            // Reading queue commands
+            commands::list_reading_queues,
+            commands::create_reading_queue,
+            commands::update_reading_queue,
+            commands::delete_reading_queue,
+            commands::generate_reading_queue_audio,
+            commands::set_reading_position,
             // Infographic generation commands
             commands::generate_infographic,
             commands::check_infographic_availability,
             // Video generation commands
             commands::generate_study_video,
             commands::check_video_generation_availability,
+            // Generated media commands
+            commands::list_generated_media,
+            commands::regenerate_media,
+            commands::cleanup_generated_media,
             // Smart organize commands
             commands::smart_organize_suggest,
             commands::smart_organize_apply,
@@ -956,6 +1423,8 @@ pub fn run() {
             commands::get_collab_config,
             // Share commands
             commands::share_page,
+            commands::share_page_encrypted,
+            commands::import_shared_page,
             commands::share_folder,
             commands::share_section,
             commands::share_notebook,
@@ -973,7 +1442,11 @@ pub fn run() {
             commands::publish_notebook,
             commands::publish_selected_pages,
             commands::preview_publish_page,
+            commands::publish_audio_feed,
             commands::generate_presentation,
+            commands::get_presentation_slides,
+            commands::save_presentation_slide_edits,
+            commands::export_presentation_pptx,
             commands::generate_print_html,
             // Daily notes commands
             commands::get_daily_note,
@@ -1006,6 +1479,51 @@ pub fn run() {
             commands::set_plugin_ai_config,
             // Freeze watchdog
             freeze_watchdog::freeze_pong,
+            // Background startup
+            startup::get_startup_status,
+            // Diagnostics commands
+            commands::get_performance_report,
+            commands::reset_performance_metrics,
+            commands::set_performance_trace,
+            commands::create_diagnostic_bundle,
+            commands::get_recent_logs,
+            commands::get_log_settings,
+            commands::set_log_settings,
+            // Deep links (nous://)
+            deep_link::take_pending_deep_links,
+            deep_link::queue_deep_link,
+            deep_link::get_page_deep_link,
+            deep_link::resolve_page_deep_link,
+            // Quick capture window
+            quick_capture::capture_quick_note,
+            quick_capture::hide_quick_capture,
+            quick_capture::open_main_window,
+            quick_capture::get_quick_capture_settings,
+            quick_capture::update_quick_capture_settings,
+            tray::refresh_tray_menu,
+            commands::list_pending_notifications,
+            commands::get_notification_preferences,
+            commands::update_notification_preferences,
+            commands::parse_reminder_time,
+            commands::set_page_reminder,
+            commands::snooze_page,
+            commands::list_page_reminders,
+            commands::delete_page_reminder,
+            commands::get_daily_agenda,
+            commands::record_page_view,
+            commands::get_review_feed,
+            commands::record_search,
+            commands::get_search_history,
+            commands::remove_search_history_entry,
+            commands::clear_search_history,
+            commands::get_search_history_settings,
+            commands::set_search_history_settings,
+            commands::get_search_suggestions,
+            commands::get_spaced_review_settings,
+            commands::update_spaced_review_settings,
+            os_search::get_os_search_settings,
+            os_search::update_os_search_settings,
+            os_search::export_os_search_index,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,81 @@
+//! Per-library state
+//!
+//! Everything the app keeps for one open library: page storage, the vector
+//! index and the library-scoped stores, plus that library's sync scheduler.
+//! The main window's context is built from `AppState`'s own handles (and is
+//! retargeted in place by `switch_library`); each window opened with
+//! `open_library_window` gets a context of its own, so two libraries can be
+//! open side by side without sharing indexes or sync.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::Library;
+use crate::actions::ActionStorage;
+use crate::comments::CommentsStorage;
+use crate::flashcards::FlashcardStorage;
+use crate::goals::GoalsStorage;
+use crate::history::HistoryStorage;
+use crate::inbox::InboxStorage;
+use crate::rag::VectorIndex;
+use crate::reminders::ReminderStorage;
+use crate::storage::{FileStorage, FileStorageWriter, StorageLock, StoragePoisoned};
+use crate::sync::SyncScheduler;
+
+pub struct LibraryContext {
+    pub storage: Arc<StorageLock>,
+    pub vector_index: Arc<Mutex<VectorIndex>>,
+    pub action_storage: Arc<Mutex<ActionStorage>>,
+    pub inbox_storage: Arc<Mutex<InboxStorage>>,
+    pub flashcard_storage: Arc<Mutex<FlashcardStorage>>,
+    pub comments_storage: Arc<Mutex<CommentsStorage>>,
+    pub goals_storage: Arc<Mutex<GoalsStorage>>,
+    pub reminder_storage: Arc<Mutex<ReminderStorage>>,
+    pub history_storage: Arc<Mutex<HistoryStorage>>,
+    /// Periodic sync for this library; `None` until started (or when the
+    /// daemon owns sync)
+    pub sync_scheduler: Arc<tokio::sync::Mutex<Option<SyncScheduler>>>,
+}
+
+impl LibraryContext {
+    /// Open the stores of `library`. The sync scheduler is left to the caller.
+    pub fn open(library: &Library) -> Result<Self, String> {
+        let path = &library.path;
+
+        let storage = FileStorageWriter::new(FileStorage::new(path.clone()));
+        storage
+            .init()
+            .map_err(|e| format!("Failed to init storage: {}", e))?;
+        let vector_index = VectorIndex::new(library.vector_db_path())
+            .map_err(|e| format!("Failed to init vector index: {}", e))?;
+        let action_storage = ActionStorage::new(path.clone())
+            .map_err(|e| format!("Failed to init action storage: {}", e))?;
+        let inbox_storage = InboxStorage::new(path.clone())
+            .map_err(|e| format!("Failed to init inbox storage: {}", e))?;
+        let goals_storage = GoalsStorage::new(path.clone())
+            .map_err(|e| format!("Failed to init goals storage: {}", e))?;
+        let reminder_storage = ReminderStorage::new(path.clone())
+            .map_err(|e| format!("Failed to init reminder storage: {}", e))?;
+        let history_storage = HistoryStorage::new(path.clone())
+            .map_err(|e| format!("Failed to init view history: {}", e))?;
+
+        Ok(Self {
+            storage: Arc::new(StorageLock::new(storage)),
+            vector_index: Arc::new(Mutex::new(vector_index)),
+            action_storage: Arc::new(Mutex::new(action_storage)),
+            inbox_storage: Arc::new(Mutex::new(inbox_storage)),
+            flashcard_storage: Arc::new(Mutex::new(FlashcardStorage::new(path.join("notebooks")))),
+            comments_storage: Arc::new(Mutex::new(CommentsStorage::new(path.join("notebooks")))),
+            goals_storage: Arc::new(Mutex::new(goals_storage)),
+            reminder_storage: Arc::new(Mutex::new(reminder_storage)),
+            history_storage: Arc::new(Mutex::new(history_storage)),
+            sync_scheduler: Arc::new(tokio::sync::Mutex::new(None)),
+        })
+    }
+
+    /// The library's root directory. Read from the storage, as the main
+    /// window's context is retargeted by `switch_library`.
+    pub fn path(&self) -> Result<PathBuf, StoragePoisoned> {
+        Ok(self.storage.read()?.notebooks_dir().clone())
+    }
+}
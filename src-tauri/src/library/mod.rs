@@ -2,8 +2,13 @@
 //!
 //! Provides the concept of "Libraries" - collections of notebooks stored at different paths.
 
+mod context;
 mod models;
+mod settings;
+pub mod snapshots;
 mod storage;
 
+pub use context::LibraryContext;
 pub use models::{Library, LibraryStats};
+pub use settings::{LibrarySettings, SettingEntry};
 pub use storage::{LibraryError, LibraryStorage};
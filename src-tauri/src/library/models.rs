@@ -8,7 +8,9 @@ use std::path::PathBuf;
 use uuid::Uuid;
 
 use crate::encryption::EncryptionConfig;
+use crate::markdown::mirror::MarkdownMirrorConfig;
 use crate::share::upload::ShareUploadConfig;
+use crate::storage::backup_destination::BackupDestination;
 use crate::sync::config::LibrarySyncConfig;
 
 /// A library is a collection of notebooks stored at a specific path
@@ -49,6 +51,19 @@ pub struct Library {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub share_upload_config: Option<ShareUploadConfig>,
 
+    /// Scheduled one-way Markdown mirror of this library
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markdown_mirror: Option<MarkdownMirrorConfig>,
+
+    /// Where auto-backups of this library's notebooks are copied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_destination: Option<BackupDestination>,
+
+    /// Folder of `.nousdeck` bundles shared with others (e.g. a synced
+    /// folder); `shared-decks/` in the library when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_decks_folder: Option<PathBuf>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
@@ -70,6 +85,9 @@ impl Library {
             encryption_config: None,
             sync_config: None,
             share_upload_config: None,
+            markdown_mirror: None,
+            backup_destination: None,
+            shared_decks_folder: None,
             created_at: now,
             updated_at: now,
         }
@@ -88,6 +106,9 @@ impl Library {
             encryption_config: None,
             sync_config: None,
             share_upload_config: None,
+            markdown_mirror: None,
+            backup_destination: None,
+            shared_decks_folder: None,
             created_at: now,
             updated_at: now,
         }
@@ -138,6 +159,13 @@ impl Library {
         self.path.join("actions")
     }
 
+    /// Get the shared decks folder for this library
+    pub fn shared_decks_path(&self) -> PathBuf {
+        self.shared_decks_folder
+            .clone()
+            .unwrap_or_else(|| self.path.join("shared-decks"))
+    }
+
     /// Get the vector database path for this library
     pub fn vector_db_path(&self) -> PathBuf {
         self.path.join(".nous").join("vectors.db")
@@ -0,0 +1,92 @@
+//! Per-library settings that follow the library across devices
+//!
+//! A flat key → JSON value map in `{library}/settings.json`. Each key keeps
+//! its own `updated_at` so library sync can merge settings one key at a time.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::storage::LibraryError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingEntry {
+    pub value: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Settings for one library
+#[derive(Debug, Default)]
+pub struct LibrarySettings {
+    path: PathBuf,
+    entries: BTreeMap<String, SettingEntry>,
+}
+
+impl LibrarySettings {
+    /// Load the settings of the library at `library_path`; a missing or
+    /// unreadable file starts empty
+    pub fn load(library_path: &Path) -> Self {
+        let path = library_path.join("settings.json");
+        let entries = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {}: {}", path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self { path, entries }
+    }
+
+    pub fn save(&self) -> Result<(), LibraryError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &BTreeMap<String, SettingEntry> {
+        &self.entries
+    }
+
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    /// Set a value, stamping it with the current time
+    pub fn set(&mut self, key: &str, value: serde_json::Value) {
+        self.put(key, value, Utc::now());
+    }
+
+    /// Set a value with an explicit timestamp (used when applying synced
+    /// settings, which keep the time they were changed on the other device)
+    pub fn put(&mut self, key: &str, value: serde_json::Value, updated_at: DateTime<Utc>) {
+        self.entries
+            .insert(key.to_string(), SettingEntry { value, updated_at });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn settings_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let mut settings = LibrarySettings::load(dir.path());
+        assert!(settings.get("editor.spellcheck").is_none());
+
+        settings.set("editor.spellcheck", json!(false));
+        settings.save().unwrap();
+
+        let settings = LibrarySettings::load(dir.path());
+        assert_eq!(settings.get("editor.spellcheck"), Some(&json!(false)));
+        assert_eq!(settings.entries().len(), 1);
+    }
+}
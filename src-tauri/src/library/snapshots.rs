@@ -0,0 +1,466 @@
+//! Point-in-time snapshots of a whole library
+//!
+//! A snapshot is a copy of everything in the library directory (pages,
+//! assets, search and vector indexes, settings, actions, ...) kept under
+//! `{library}/.nous/snapshots/{id}/`, taken before something big like an
+//! import or an AI bulk edit so it can be undone in one step.
+//!
+//! Copies are kept cheap where the filesystem allows: a file unchanged
+//! since the previous snapshot is hard-linked to that snapshot's copy
+//! (snapshot files are never modified, so sharing them is safe), and other
+//! files are cloned copy-on-write on filesystems that support it (APFS,
+//! Btrfs, XFS). Live files are never hard-linked, since some stores
+//! rewrite their files in place. A snapshot is built under
+//! `{id}.partial` and renamed into place when complete, so a crash never
+//! leaves a half-written snapshot in the list.
+//!
+//! Rolling back first takes a snapshot of the current state, then swaps
+//! the library's contents for the snapshot's, entry by entry.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::storage::atomic;
+
+/// Snapshots kept per library; the oldest are pruned past this
+const MAX_SNAPSHOTS: usize = 20;
+
+/// Metadata file in each snapshot directory
+const META_FILE: &str = "snapshot.json";
+
+/// Snapshot contents, next to the metadata
+const DATA_DIR: &str = "data";
+
+/// Suffix of a snapshot still being written
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// Where a rollback stages the snapshot's files before swapping them in
+const STAGING_DIR: &str = ".restore";
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("Snapshot not found: {0}")]
+    NotFound(Uuid),
+
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type SnapshotResult<T> = Result<T, SnapshotError>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySnapshot {
+    pub id: Uuid,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    /// Bytes shared with the previous snapshot or cloned copy-on-write
+    /// instead of copied
+    pub shared_bytes: u64,
+}
+
+/// Snapshots directory of the library at `library_path`
+pub fn snapshots_dir(library_path: &Path) -> PathBuf {
+    library_path.join(".nous").join("snapshots")
+}
+
+/// Snapshot the library at `library_path`. The caller holds the library's
+/// write locks so nothing changes while files are copied.
+pub fn create_snapshot(library_path: &Path, label: &str) -> SnapshotResult<LibrarySnapshot> {
+    let root = snapshots_dir(library_path);
+    fs::create_dir_all(&root)?;
+    remove_partials(&root);
+
+    let previous = list_snapshots(library_path).into_iter().next();
+    let mut snapshot = LibrarySnapshot {
+        id: Uuid::new_v4(),
+        label: label.to_string(),
+        created_at: Utc::now(),
+        file_count: 0,
+        total_bytes: 0,
+        shared_bytes: 0,
+    };
+
+    let partial = root.join(format!("{}{}", snapshot.id, PARTIAL_SUFFIX));
+    let data = partial.join(DATA_DIR);
+    fs::create_dir_all(&data)?;
+    let previous_data = previous
+        .as_ref()
+        .map(|p| (root.join(p.id.to_string()).join(DATA_DIR), p.created_at));
+    let mut can_clone = true;
+
+    let copied = (|| -> SnapshotResult<()> {
+        for relative in members(library_path)? {
+            let source = library_path.join(&relative);
+            if let Some(parent) = data.join(&relative).parent() {
+                fs::create_dir_all(parent)?;
+            }
+            for entry in WalkDir::new(&source).follow_links(false) {
+                let entry = entry.map_err(io::Error::from)?;
+                let path = entry.path();
+                let rel = path.strip_prefix(library_path).unwrap_or(path);
+                let target = data.join(rel);
+                let file_type = entry.file_type();
+                if file_type.is_dir() {
+                    fs::create_dir_all(&target)?;
+                    continue;
+                }
+                if !file_type.is_file() || is_transient(path) {
+                    continue;
+                }
+                let metadata = entry.metadata().map_err(io::Error::from)?;
+                let unchanged = previous_data.as_ref().and_then(|(dir, taken_at)| {
+                    let modified: DateTime<Utc> = metadata.modified().ok()?.into();
+                    let earlier = dir.join(rel);
+                    let same_len = fs::metadata(&earlier).ok()?.len() == metadata.len();
+                    (same_len && modified < *taken_at).then_some(earlier)
+                });
+
+                let shared = match unchanged {
+                    Some(earlier) if fs::hard_link(&earlier, &target).is_ok() => true,
+                    _ => copy_file(path, &target, &mut can_clone)?,
+                };
+                snapshot.file_count += 1;
+                snapshot.total_bytes += metadata.len();
+                if shared {
+                    snapshot.shared_bytes += metadata.len();
+                }
+            }
+        }
+        atomic::write_str(
+            &partial.join(META_FILE),
+            &serde_json::to_string_pretty(&snapshot)?,
+        )?;
+        Ok(())
+    })();
+
+    if let Err(e) = copied {
+        let _ = fs::remove_dir_all(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, root.join(snapshot.id.to_string()))?;
+    prune(library_path);
+
+    log::info!(
+        "Snapshot '{}' of {}: {} files, {} of {} bytes shared",
+        snapshot.label,
+        library_path.display(),
+        snapshot.file_count,
+        snapshot.shared_bytes,
+        snapshot.total_bytes
+    );
+    Ok(snapshot)
+}
+
+/// Complete snapshots of the library, newest first
+pub fn list_snapshots(library_path: &Path) -> Vec<LibrarySnapshot> {
+    let Ok(entries) = fs::read_dir(snapshots_dir(library_path)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<LibrarySnapshot> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let json = fs::read_to_string(entry.path().join(META_FILE)).ok()?;
+            serde_json::from_str(&json).ok()
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    snapshots
+}
+
+pub fn delete_snapshot(library_path: &Path, id: Uuid) -> SnapshotResult<()> {
+    let dir = snapshots_dir(library_path).join(id.to_string());
+    if !dir.join(META_FILE).exists() {
+        return Err(SnapshotError::NotFound(id));
+    }
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+/// Replace the library's contents with snapshot `id`. Returns the snapshot
+/// taken of the state being replaced. The caller holds the library's write
+/// locks and reopens its stores afterwards.
+pub fn rollback_to_snapshot(library_path: &Path, id: Uuid) -> SnapshotResult<LibrarySnapshot> {
+    let root = snapshots_dir(library_path);
+    let snapshot_dir = root.join(id.to_string());
+    let meta: LibrarySnapshot = match fs::read_to_string(snapshot_dir.join(META_FILE)) {
+        Ok(json) => serde_json::from_str(&json)?,
+        Err(_) => return Err(SnapshotError::NotFound(id)),
+    };
+
+    let safety = create_snapshot(
+        library_path,
+        &format!("Before rollback to \"{}\"", meta.label),
+    )?;
+
+    // Stage the snapshot's files next to the library first, so the swap
+    // below is a series of renames rather than copies
+    let staging = root.join(STAGING_DIR);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let data = snapshot_dir.join(DATA_DIR);
+    let mut can_clone = true;
+    for entry in WalkDir::new(&data) {
+        let entry = entry.map_err(io::Error::from)?;
+        let rel = entry.path().strip_prefix(&data).unwrap_or(entry.path());
+        let target = staging.join(rel);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else {
+            copy_file(entry.path(), &target, &mut can_clone)?;
+        }
+    }
+
+    for relative in members(library_path)? {
+        remove_path(&library_path.join(relative))?;
+    }
+    for relative in members(&staging)? {
+        let target = library_path.join(&relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(staging.join(&relative), target)?;
+    }
+    fs::remove_dir_all(&staging)?;
+
+    log::info!(
+        "Rolled back {} to snapshot '{}' ({})",
+        library_path.display(),
+        meta.label,
+        meta.created_at
+    );
+    Ok(safety)
+}
+
+/// The entries a snapshot covers, relative to `root`: everything at the
+/// top level, with `.nous` descended into so the snapshots directory
+/// itself is left out
+fn members(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut members = Vec::new();
+    if !root.exists() {
+        return Ok(members);
+    }
+    for entry in fs::read_dir(root)? {
+        let name = entry?.file_name();
+        if name == ".nous" {
+            let nous = root.join(".nous");
+            if nous.is_dir() {
+                for inner in fs::read_dir(&nous)? {
+                    let inner = inner?.file_name();
+                    if inner != "snapshots" {
+                        members.push(Path::new(".nous").join(inner));
+                    }
+                }
+            }
+        } else {
+            members.push(PathBuf::from(name));
+        }
+    }
+    members.sort();
+    Ok(members)
+}
+
+/// Temp files of in-flight atomic writes and lock files aren't state
+fn is_transient(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    name.ends_with(".nous-tmp") || name.ends_with(".lock")
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+fn remove_partials(root: &Path) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .ends_with(PARTIAL_SUFFIX)
+        {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Drop the oldest snapshots past [`MAX_SNAPSHOTS`]
+fn prune(library_path: &Path) {
+    for old in list_snapshots(library_path).into_iter().skip(MAX_SNAPSHOTS) {
+        if let Err(e) = delete_snapshot(library_path, old.id) {
+            log::warn!("Failed to prune snapshot {}: {}", old.id, e);
+        }
+    }
+}
+
+/// Copy `source` to `target`, cloning copy-on-write while the filesystem
+/// supports it. Returns whether the data is shared with `source`.
+fn copy_file(source: &Path, target: &Path, can_clone: &mut bool) -> io::Result<bool> {
+    if *can_clone {
+        match clone_file(source, target) {
+            Ok(()) => return Ok(true),
+            // Not supported here (or across devices): stop trying for this run
+            Err(_) => *can_clone = false,
+        }
+    }
+    fs::copy(source, target)?;
+    Ok(false)
+}
+
+#[cfg(target_os = "linux")]
+fn clone_file(source: &Path, target: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    /// `_IOW(0x94, 9, int)`
+    const FICLONE: std::ffi::c_ulong = 0x4004_9409;
+    extern "C" {
+        fn ioctl(fd: std::ffi::c_int, request: std::ffi::c_ulong, ...) -> std::ffi::c_int;
+    }
+
+    let src = fs::File::open(source)?;
+    let dst = fs::File::create(target)?;
+    if unsafe { ioctl(dst.as_raw_fd(), FICLONE, src.as_raw_fd()) } == 0 {
+        return Ok(());
+    }
+    let err = io::Error::last_os_error();
+    drop(dst);
+    let _ = fs::remove_file(target);
+    Err(err)
+}
+
+#[cfg(target_os = "macos")]
+fn clone_file(source: &Path, target: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    extern "C" {
+        fn clonefile(
+            src: *const std::ffi::c_char,
+            dst: *const std::ffi::c_char,
+            flags: u32,
+        ) -> std::ffi::c_int;
+    }
+
+    let src = CString::new(source.as_os_str().as_bytes())?;
+    let dst = CString::new(target.as_os_str().as_bytes())?;
+    if unsafe { clonefile(src.as_ptr(), dst.as_ptr(), 0) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn clone_file(_source: &Path, _target: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "copy-on-write clones are not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn library() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let notebook = dir.path().join("notebooks").join("nb1").join("pages");
+        fs::create_dir_all(&notebook).unwrap();
+        fs::write(notebook.join("page.json"), "original").unwrap();
+        fs::write(dir.path().join("settings.json"), "{}").unwrap();
+        fs::create_dir_all(dir.path().join(".nous")).unwrap();
+        fs::write(dir.path().join(".nous").join("vectors.db"), "vectors").unwrap();
+        dir
+    }
+
+    fn read(dir: &TempDir, rel: &str) -> String {
+        fs::read_to_string(dir.path().join(rel)).unwrap()
+    }
+
+    #[test]
+    fn snapshot_leaves_out_itself_and_transient_files() {
+        let dir = library();
+        fs::write(dir.path().join("settings.json.1.0.nous-tmp"), "partial").unwrap();
+
+        let snapshot = create_snapshot(dir.path(), "Before import").unwrap();
+        assert_eq!(snapshot.file_count, 3);
+
+        let second = create_snapshot(dir.path(), "Again").unwrap();
+        assert_eq!(second.file_count, 3);
+        assert_eq!(second.shared_bytes, second.total_bytes);
+
+        let listed = list_snapshots(dir.path());
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, second.id);
+    }
+
+    #[test]
+    fn rollback_restores_contents_and_keeps_a_safety_snapshot() {
+        let dir = library();
+        let snapshot = create_snapshot(dir.path(), "Before bulk edit").unwrap();
+
+        fs::write(
+            dir.path().join("notebooks/nb1/pages/page.json"),
+            "edited by AI",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("notebooks/nb2")).unwrap();
+        fs::write(dir.path().join("notebooks/nb2/notebook.json"), "new").unwrap();
+        fs::remove_file(dir.path().join(".nous/vectors.db")).unwrap();
+
+        let safety = rollback_to_snapshot(dir.path(), snapshot.id).unwrap();
+
+        assert_eq!(read(&dir, "notebooks/nb1/pages/page.json"), "original");
+        assert_eq!(read(&dir, ".nous/vectors.db"), "vectors");
+        assert!(!dir.path().join("notebooks/nb2").exists());
+        assert!(!snapshots_dir(dir.path()).join(STAGING_DIR).exists());
+
+        // The state that was replaced can be brought back
+        rollback_to_snapshot(dir.path(), safety.id).unwrap();
+        assert_eq!(read(&dir, "notebooks/nb1/pages/page.json"), "edited by AI");
+        assert_eq!(read(&dir, "notebooks/nb2/notebook.json"), "new");
+    }
+
+    #[test]
+    fn snapshot_files_are_not_linked_to_live_files() {
+        let dir = library();
+        let snapshot = create_snapshot(dir.path(), "Before").unwrap();
+
+        // An in-place rewrite of a live file must not reach the snapshot
+        fs::write(dir.path().join("settings.json"), r#"{"changed":true}"#).unwrap();
+        let copy = snapshots_dir(dir.path())
+            .join(snapshot.id.to_string())
+            .join(DATA_DIR)
+            .join("settings.json");
+        assert_eq!(fs::read_to_string(copy).unwrap(), "{}");
+    }
+
+    #[test]
+    fn unknown_snapshot_is_not_found() {
+        let dir = library();
+        assert!(matches!(
+            rollback_to_snapshot(dir.path(), Uuid::new_v4()),
+            Err(SnapshotError::NotFound(_))
+        ));
+    }
+}
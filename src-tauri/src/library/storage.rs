@@ -452,6 +452,93 @@ impl LibraryStorage {
         Ok(updated)
     }
 
+    /// Update a library's Markdown mirror configuration
+    pub fn update_library_markdown_mirror(
+        &self,
+        id: Uuid,
+        markdown_mirror: Option<crate::markdown::mirror::MarkdownMirrorConfig>,
+    ) -> Result<Library, LibraryError> {
+        let mut libraries = self.list_libraries()?;
+
+        let lib = libraries
+            .iter_mut()
+            .find(|lib| lib.id == id)
+            .ok_or(LibraryError::NotFound(id))?;
+
+        if let Some(config) = &markdown_mirror {
+            config
+                .validate(&lib.path)
+                .map_err(LibraryError::InvalidPath)?;
+        }
+
+        lib.markdown_mirror = markdown_mirror;
+        lib.updated_at = chrono::Utc::now();
+
+        let updated = lib.clone();
+        self.save_libraries(&libraries)?;
+
+        log::info!("Updated library Markdown mirror for '{}'", updated.name);
+        Ok(updated)
+    }
+
+    /// Update a library's auto-backup destination
+    pub fn update_library_backup_destination(
+        &self,
+        id: Uuid,
+        backup_destination: Option<crate::storage::backup_destination::BackupDestination>,
+    ) -> Result<Library, LibraryError> {
+        let mut libraries = self.list_libraries()?;
+
+        let lib = libraries
+            .iter_mut()
+            .find(|lib| lib.id == id)
+            .ok_or(LibraryError::NotFound(id))?;
+
+        if let Some(destination) = &backup_destination {
+            destination.validate().map_err(LibraryError::InvalidPath)?;
+        }
+
+        lib.backup_destination = backup_destination;
+        lib.updated_at = chrono::Utc::now();
+
+        let updated = lib.clone();
+        self.save_libraries(&libraries)?;
+
+        log::info!("Updated library backup destination for '{}'", updated.name);
+        Ok(updated)
+    }
+
+    /// Set or clear a library's shared decks folder
+    pub fn update_library_shared_decks_folder(
+        &self,
+        id: Uuid,
+        folder: Option<PathBuf>,
+    ) -> Result<Library, LibraryError> {
+        let mut libraries = self.list_libraries()?;
+
+        let lib = libraries
+            .iter_mut()
+            .find(|lib| lib.id == id)
+            .ok_or(LibraryError::NotFound(id))?;
+
+        if let Some(folder) = &folder {
+            if !folder.is_absolute() {
+                return Err(LibraryError::InvalidPath(
+                    "Shared decks folder must be an absolute path".to_string(),
+                ));
+            }
+        }
+
+        lib.shared_decks_folder = folder;
+        lib.updated_at = chrono::Utc::now();
+
+        let updated = lib.clone();
+        self.save_libraries(&libraries)?;
+
+        log::info!("Updated library shared decks folder for '{}'", updated.name);
+        Ok(updated)
+    }
+
     /// Save libraries to file
     fn save_libraries(&self, libraries: &[Library]) -> Result<(), LibraryError> {
         let content = serde_json::to_string_pretty(libraries)?;
@@ -0,0 +1,923 @@
+//! Content linting: problems in a notebook's pages that don't stop them from
+//! loading but that a reader trips over.
+//!
+//! [`lint_notebook`] checks the notebook's block pages for links whose target
+//! is gone, assets that aren't on disk, empty pages, titles several pages
+//! share, oversized images and blocks the editor can't render properly.
+//! Diagnostics in the safe categories carry a [`LintFix`]. [`fix_notebook`]
+//! lints again and applies the fixes of the given diagnostics in the same
+//! pass, so a fix is only applied while its problem is still there.
+//!
+//! Wiki-links resolve by title within the notebook, as the editor does, so a
+//! link whose page was renamed is broken even though its page id is valid.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::auto_link::html_escape;
+use crate::block_ops::{attr, set_attr};
+use crate::share::encrypted::asset_relative_path;
+use crate::storage::html_utils::html_to_searchable_text;
+use crate::storage::{EditorBlock, FileStorage, FileStorageWriter, Page, PageType, StorageError};
+
+/// Images larger than this are reported unless the caller sets a limit
+pub const DEFAULT_MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintKind {
+    BrokenLink,
+    MissingAsset,
+    EmptyPage,
+    DuplicateTitle,
+    OversizedImage,
+    MalformedBlock,
+}
+
+impl LintKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            LintKind::BrokenLink => "broken-link",
+            LintKind::MissingAsset => "missing-asset",
+            LintKind::EmptyPage => "empty-page",
+            LintKind::DuplicateTitle => "duplicate-title",
+            LintKind::OversizedImage => "oversized-image",
+            LintKind::MalformedBlock => "malformed-block",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A change that resolves a diagnostic without losing content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum LintFix {
+    /// Point the link or embed at this page
+    #[serde(rename_all = "camelCase")]
+    Relink { page_id: Uuid, title: String },
+    /// Replace the link with its text
+    Unlink,
+    /// Give the block a new id
+    NewBlockId,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintDiagnostic {
+    /// Stays the same between runs while the problem is unchanged
+    pub id: String,
+    pub kind: LintKind,
+    pub severity: LintSeverity,
+    pub page_id: Uuid,
+    pub page_title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<LintFix>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub notebook_id: Uuid,
+    pub checked_at: DateTime<Utc>,
+    pub pages_checked: usize,
+    /// Encrypted pages, whose content can't be checked
+    pub pages_skipped: usize,
+    pub diagnostics: Vec<LintDiagnostic>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LintOptions {
+    pub max_image_bytes: u64,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            max_image_bytes: DEFAULT_MAX_IMAGE_BYTES,
+        }
+    }
+}
+
+/// Result of [`fix_notebook`]
+#[derive(Debug, Default)]
+pub struct LintFixes {
+    /// Ids of the diagnostics that were fixed
+    pub fixed: Vec<String>,
+    /// Pages written
+    pub updated: Vec<Page>,
+}
+
+/// Check every block page of a notebook
+pub fn lint_notebook(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    options: LintOptions,
+) -> Result<LintReport, StorageError> {
+    let pages = storage.list_pages(notebook_id)?;
+    let linter = Linter::new(storage, notebook_id, options, &pages)?;
+    let mut report = LintReport {
+        notebook_id,
+        checked_at: Utc::now(),
+        pages_checked: 0,
+        pages_skipped: 0,
+        diagnostics: Vec::new(),
+    };
+    let no_fixes = HashSet::new();
+    for mut page in pages {
+        if page.deleted_at.is_some() {
+            continue;
+        }
+        let mut lint = PageLint::new(&page, &no_fixes);
+        linter.lint_page(&mut lint, &mut page, &mut report);
+        report.diagnostics.append(&mut lint.diagnostics);
+    }
+    Ok(report)
+}
+
+/// Apply the fixes of the diagnostics with the given ids and write the pages
+/// they change. Diagnostics without a fix, or whose problem is gone, are
+/// left alone.
+pub fn fix_notebook(
+    storage: &FileStorageWriter,
+    notebook_id: Uuid,
+    diagnostic_ids: &HashSet<String>,
+    options: LintOptions,
+) -> Result<LintFixes, StorageError> {
+    let pages = storage.list_pages(notebook_id)?;
+    let linter = Linter::new(storage, notebook_id, options, &pages)?;
+    let mut report = LintReport {
+        notebook_id,
+        checked_at: Utc::now(),
+        pages_checked: 0,
+        pages_skipped: 0,
+        diagnostics: Vec::new(),
+    };
+    let mut fixes = LintFixes::default();
+    for mut page in pages {
+        if page.deleted_at.is_some() {
+            continue;
+        }
+        let mut lint = PageLint::new(&page, diagnostic_ids);
+        if linter.lint_page(&mut lint, &mut page, &mut report) {
+            let now = Utc::now();
+            page.updated_at = now;
+            page.content.time = Some(now.timestamp_millis());
+            storage.update_page(&page)?;
+            fixes.fixed.append(&mut lint.fixed);
+            fixes.updated.push(page);
+        }
+    }
+    Ok(fixes)
+}
+
+/// What links need to know about a page
+struct PageInfo {
+    notebook_id: Uuid,
+    title: String,
+    live: bool,
+    encrypted: bool,
+}
+
+struct Linter<'a> {
+    storage: &'a FileStorage,
+    notebook_id: Uuid,
+    options: LintOptions,
+    /// Every page of the library, trashed ones included
+    pages: HashMap<Uuid, PageInfo>,
+    /// Live pages of the notebook by lowercased title
+    titles: HashMap<String, Vec<Uuid>>,
+    /// Live pages each block id is on
+    blocks: HashMap<String, Vec<Uuid>>,
+    /// Pages with live subpages
+    parents: HashSet<Uuid>,
+    /// A `<wiki-link>` or `<block-ref>` element: name, attributes, label
+    element: Regex,
+}
+
+impl<'a> Linter<'a> {
+    fn new(
+        storage: &'a FileStorage,
+        notebook_id: Uuid,
+        options: LintOptions,
+        pages: &[Page],
+    ) -> Result<Self, StorageError> {
+        let mut linter = Self {
+            storage,
+            notebook_id,
+            options,
+            pages: HashMap::new(),
+            titles: HashMap::new(),
+            blocks: HashMap::new(),
+            parents: HashSet::new(),
+            element: Regex::new(
+                r"(?s)<(wiki-link|block-ref)\b([^>]*)>(.*?)</(?:wiki-link|block-ref)>",
+            )
+            .expect("valid element pattern"),
+        };
+        for page in pages {
+            linter.add(page);
+        }
+        // Links and refs may point into other notebooks
+        for notebook in storage.list_notebooks()? {
+            if notebook.id == notebook_id {
+                continue;
+            }
+            match storage.list_pages(notebook.id) {
+                Ok(pages) => pages.iter().for_each(|page| linter.add(page)),
+                Err(e) => log::warn!("Lint: skipping notebook {}: {}", notebook.id, e),
+            }
+        }
+        Ok(linter)
+    }
+
+    fn add(&mut self, page: &Page) {
+        let live = page.deleted_at.is_none();
+        self.pages.insert(
+            page.id,
+            PageInfo {
+                notebook_id: page.notebook_id,
+                title: page.title.clone(),
+                live,
+                encrypted: page.is_encrypted(),
+            },
+        );
+        if !live {
+            return;
+        }
+        if page.notebook_id == self.notebook_id {
+            self.titles
+                .entry(title_key(&page.title))
+                .or_default()
+                .push(page.id);
+            if let Some(parent_id) = page.parent_page_id {
+                self.parents.insert(parent_id);
+            }
+        }
+        for block in &page.content.blocks {
+            self.blocks
+                .entry(block.id.clone())
+                .or_default()
+                .push(page.id);
+        }
+    }
+
+    /// Lint one live page; returns whether fixes changed it
+    fn lint_page(&self, lint: &mut PageLint, page: &mut Page, report: &mut LintReport) -> bool {
+        if let Some(others) = self
+            .titles
+            .get(&title_key(&page.title))
+            .filter(|ids| ids.len() > 1)
+        {
+            lint.report(
+                LintKind::DuplicateTitle,
+                LintSeverity::Warning,
+                None,
+                &title_key(&page.title),
+                format!(
+                    "{} other page(s) are titled '{}'; wiki-links to it may open the wrong one",
+                    others.len() - 1,
+                    page.title
+                ),
+                None,
+            );
+        }
+
+        if page.page_type != PageType::Standard || page.plugin_page_type.is_some() {
+            return false;
+        }
+        if page.is_encrypted() {
+            report.pages_skipped += 1;
+            return false;
+        }
+        report.pages_checked += 1;
+
+        if !self.parents.contains(&page.id) && is_empty(&page.content.blocks) {
+            lint.report(
+                LintKind::EmptyPage,
+                LintSeverity::Info,
+                None,
+                "",
+                "Page has no content".to_string(),
+                None,
+            );
+        }
+
+        let mut changed = false;
+        let mut seen_ids = HashSet::new();
+        for (index, block) in page.content.blocks.iter_mut().enumerate() {
+            changed |= self.lint_block(lint, index, block, &mut seen_ids);
+        }
+        changed
+    }
+
+    fn lint_block(
+        &self,
+        lint: &mut PageLint,
+        index: usize,
+        block: &mut EditorBlock,
+        seen_ids: &mut HashSet<String>,
+    ) -> bool {
+        let mut changed = false;
+        let missing_id = block.id.trim().is_empty();
+        if missing_id || seen_ids.contains(&block.id) {
+            let message = if missing_id {
+                "Block has no id".to_string()
+            } else {
+                format!("Block id '{}' is used by an earlier block", block.id)
+            };
+            if lint.report(
+                LintKind::MalformedBlock,
+                LintSeverity::Error,
+                Some((index, &block.id)),
+                "id",
+                message,
+                Some(LintFix::NewBlockId),
+            ) {
+                block.id = Uuid::new_v4().to_string();
+                changed = true;
+            }
+        }
+        seen_ids.insert(block.id.clone());
+
+        if let Some(problem) = malformed(block) {
+            lint.report(
+                LintKind::MalformedBlock,
+                LintSeverity::Error,
+                Some((index, &block.id)),
+                "structure",
+                problem,
+                None,
+            );
+            return changed;
+        }
+
+        let block_id = block.id.clone();
+        changed |= self.lint_embed(lint, index, block);
+        let is_image = block.block_type == "image";
+        changed |= walk_strings(&mut block.data, &mut |s| {
+            self.lint_string(lint, (index, &block_id), is_image, s)
+        });
+        changed
+    }
+
+    /// Embeds of a block (`blockEmbed`) or a whole page (`embed` with
+    /// `embedType` "page")
+    fn lint_embed(&self, lint: &mut PageLint, index: usize, block: &mut EditorBlock) -> bool {
+        let data = &block.data;
+        let problem = match block.block_type.as_str() {
+            "blockEmbed" => {
+                let Some(target) = data.get("targetBlockId").and_then(|v| v.as_str()) else {
+                    return false;
+                };
+                let page_id = data_uuid(data, "targetPageId");
+                self.check_block_target(page_id, target)
+                    .map(|(message, fix)| (target.to_string(), message, fix))
+            }
+            "embed" if data.get("embedType").and_then(|v| v.as_str()) == Some("page") => {
+                let title = data.get("pageTitle").and_then(|v| v.as_str()).unwrap_or("");
+                self.check_page_embed(data_uuid(data, "pageId"), title)
+                    .map(|(message, fix)| (title.to_string(), message, fix))
+            }
+            _ => None,
+        };
+        let Some((target, message, fix)) = problem else {
+            return false;
+        };
+        let apply = lint.report(
+            LintKind::BrokenLink,
+            LintSeverity::Error,
+            Some((index, &block.id)),
+            &target,
+            message,
+            fix.clone(),
+        );
+        let (true, Some(LintFix::Relink { page_id, title })) = (apply, fix) else {
+            return false;
+        };
+        if block.block_type == "blockEmbed" {
+            block.data["targetPageId"] = json!(page_id.to_string());
+        } else {
+            block.data["pageId"] = json!(page_id.to_string());
+            block.data["pageTitle"] = json!(title);
+        }
+        true
+    }
+
+    /// Check one string of block data for asset references and links;
+    /// returns it with fixes applied if any were
+    fn lint_string(
+        &self,
+        lint: &mut PageLint,
+        block: (usize, &str),
+        is_image: bool,
+        s: &str,
+    ) -> Option<String> {
+        if let Some((relative, path)) = self.asset_path(s) {
+            match std::fs::metadata(&path) {
+                Err(_) => {
+                    lint.report(
+                        LintKind::MissingAsset,
+                        LintSeverity::Error,
+                        Some(block),
+                        &relative,
+                        format!("Asset '{}' is missing", relative),
+                        None,
+                    );
+                }
+                Ok(meta) if is_image && meta.len() > self.options.max_image_bytes => {
+                    lint.report(
+                        LintKind::OversizedImage,
+                        LintSeverity::Warning,
+                        Some(block),
+                        &relative,
+                        format!(
+                            "Image '{}' is {:.1} MB",
+                            relative,
+                            meta.len() as f64 / (1024.0 * 1024.0)
+                        ),
+                        None,
+                    );
+                }
+                Ok(_) => {}
+            }
+            return None;
+        }
+
+        if !s.contains("<wiki-link") && !s.contains("<block-ref") {
+            return None;
+        }
+        let mut changed = false;
+        let fixed = self.element.replace_all(s, |caps: &Captures| {
+            let (whole, name, label) = (&caps[0], &caps[1], &caps[3]);
+            let tag = format!("<{}{}>", name, &caps[2]);
+            let page_id = attr(&tag, "data-page-id").and_then(|id| Uuid::parse_str(id).ok());
+            let problem = if name == "wiki-link" {
+                let title = html_to_searchable_text(attr(&tag, "data-page-title").unwrap_or(label));
+                self.check_wiki_link(page_id, &title)
+                    .map(|(severity, message, fix)| (title, severity, message, fix))
+            } else {
+                let target = attr(&tag, "data-block-id").unwrap_or_default();
+                self.check_block_target(page_id, target)
+                    .map(|(message, fix)| (target.to_string(), LintSeverity::Error, message, fix))
+            };
+            let Some((target, severity, message, fix)) = problem else {
+                return whole.to_string();
+            };
+            if !lint.report(
+                LintKind::BrokenLink,
+                severity,
+                Some(block),
+                &target,
+                message,
+                fix.clone(),
+            ) {
+                return whole.to_string();
+            }
+            changed = true;
+            match fix {
+                Some(LintFix::Relink { page_id, title }) => {
+                    let mut tag = set_attr(&tag, "data-page-id", &page_id.to_string());
+                    if name == "wiki-link" {
+                        tag = set_attr(&tag, "data-page-title", &html_escape(&title));
+                    }
+                    format!("{}{}</{}>", tag, label, name)
+                }
+                _ => label.to_string(),
+            }
+        });
+        changed.then(|| fixed.into_owned())
+    }
+
+    /// Problem with a wiki-link to `title`; `page_id` is the page it was
+    /// made for, if it records one
+    fn check_wiki_link(
+        &self,
+        page_id: Option<Uuid>,
+        title: &str,
+    ) -> Option<(LintSeverity, String, Option<LintFix>)> {
+        if self.titles.contains_key(&title_key(title)) {
+            return None;
+        }
+        match page_id.and_then(|id| self.pages.get(&id).map(|info| (id, info))) {
+            Some((id, info)) if info.live && info.notebook_id == self.notebook_id => Some((
+                LintSeverity::Error,
+                format!(
+                    "Link to '{}' is stale; the page is now '{}'",
+                    title, info.title
+                ),
+                Some(LintFix::Relink {
+                    page_id: id,
+                    title: info.title.clone(),
+                }),
+            )),
+            // A page in another notebook
+            Some((_, info)) if info.live => None,
+            Some(_) => Some((
+                LintSeverity::Error,
+                format!("Link to '{}' points to a page in the trash", title),
+                Some(LintFix::Unlink),
+            )),
+            None if page_id.is_some() => Some((
+                LintSeverity::Error,
+                format!("Link to '{}' points to a deleted page", title),
+                Some(LintFix::Unlink),
+            )),
+            // Following the link creates the page
+            None => Some((
+                LintSeverity::Info,
+                format!("Link to '{}', which doesn't exist yet", title),
+                None,
+            )),
+        }
+    }
+
+    /// Problem with a reference to block `block_id` on `page_id`. When the
+    /// block is found on exactly one other page, the fix points there.
+    fn check_block_target(
+        &self,
+        page_id: Option<Uuid>,
+        block_id: &str,
+    ) -> Option<(String, Option<LintFix>)> {
+        let found = self
+            .blocks
+            .get(block_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let info = page_id.and_then(|id| self.pages.get(&id));
+        let resolves = match (page_id, info) {
+            (Some(id), Some(info)) => info.live && (info.encrypted || found.contains(&id)),
+            (None, _) => !found.is_empty(),
+            (Some(_), None) => false,
+        };
+        if resolves {
+            return None;
+        }
+
+        let message = match info {
+            Some(info) if !info.live => format!(
+                "Referenced block is on '{}', which is in the trash",
+                info.title
+            ),
+            Some(info) => format!("Referenced block is no longer on '{}'", info.title),
+            None => "Referenced block no longer exists".to_string(),
+        };
+        let fix = match found {
+            [only] => Some(LintFix::Relink {
+                page_id: *only,
+                title: self.pages[only].title.clone(),
+            }),
+            [] => Some(LintFix::Unlink),
+            _ => None,
+        };
+        Some((message, fix))
+    }
+
+    /// Problem with an embed of page `page_id`, titled `title` when embedded.
+    /// A live page with the same title is offered instead.
+    fn check_page_embed(
+        &self,
+        page_id: Option<Uuid>,
+        title: &str,
+    ) -> Option<(String, Option<LintFix>)> {
+        if page_id
+            .and_then(|id| self.pages.get(&id))
+            .is_some_and(|info| info.live)
+        {
+            return None;
+        }
+        let fix = match self.titles.get(&title_key(title)).map(Vec::as_slice) {
+            Some([only]) => Some(LintFix::Relink {
+                page_id: *only,
+                title: self.pages[only].title.clone(),
+            }),
+            _ => None,
+        };
+        Some((format!("Embedded page '{}' no longer exists", title), fix))
+    }
+
+    /// Path relative to the assets dir and on disk of a local asset URL.
+    /// `asset://{notebook-id}/...` URLs may point into another notebook.
+    fn asset_path(&self, url: &str) -> Option<(String, PathBuf)> {
+        let relative = asset_relative_path(url)?;
+        let notebook_id = url
+            .strip_prefix("asset://")
+            .and_then(|rest| rest.split('/').next())
+            .and_then(|host| Uuid::parse_str(host).ok())
+            .unwrap_or(self.notebook_id);
+        let path = self
+            .storage
+            .notebook_assets_dir(notebook_id)
+            .join(&relative);
+        Some((relative, path))
+    }
+}
+
+/// Diagnostics of one page, and which of them to fix
+struct PageLint<'f> {
+    page_id: Uuid,
+    page_title: String,
+    fix: &'f HashSet<String>,
+    diagnostics: Vec<LintDiagnostic>,
+    fixed: Vec<String>,
+}
+
+impl<'f> PageLint<'f> {
+    fn new(page: &Page, fix: &'f HashSet<String>) -> Self {
+        Self {
+            page_id: page.id,
+            page_title: page.title.clone(),
+            fix,
+            diagnostics: Vec::new(),
+            fixed: Vec::new(),
+        }
+    }
+
+    /// Record a problem at a block (index and id) or the page; returns
+    /// whether its fix should be applied
+    fn report(
+        &mut self,
+        kind: LintKind,
+        severity: LintSeverity,
+        block: Option<(usize, &str)>,
+        target: &str,
+        message: String,
+        fix: Option<LintFix>,
+    ) -> bool {
+        let at = block.map_or_else(|| "page".to_string(), |(index, _)| index.to_string());
+        let id = format!("{}:{}:{}:{}", kind.as_str(), self.page_id, at, target);
+        if fix.is_some() && self.fix.contains(&id) {
+            if !self.fixed.contains(&id) {
+                self.fixed.push(id);
+            }
+            return true;
+        }
+        if !self.diagnostics.iter().any(|d| d.id == id) {
+            self.diagnostics.push(LintDiagnostic {
+                id,
+                kind,
+                severity,
+                page_id: self.page_id,
+                page_title: self.page_title.clone(),
+                block_id: block.map(|(_, id)| id.to_string()),
+                message,
+                fix,
+            });
+        }
+        false
+    }
+}
+
+fn title_key(title: &str) -> String {
+    title.trim().to_lowercase()
+}
+
+fn data_uuid(data: &Value, key: &str) -> Option<Uuid> {
+    data.get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+/// No blocks, or only text blocks without text
+fn is_empty(blocks: &[EditorBlock]) -> bool {
+    blocks.iter().all(|block| {
+        matches!(block.block_type.as_str(), "paragraph" | "header")
+            && block
+                .data
+                .get("text")
+                .and_then(|v| v.as_str())
+                .map_or(true, |html| html_to_searchable_text(html).trim().is_empty())
+    })
+}
+
+/// What's wrong with a block's structure, if anything
+fn malformed(block: &EditorBlock) -> Option<String> {
+    let data = &block.data;
+    if block.block_type.trim().is_empty() {
+        return Some("Block has no type".to_string());
+    }
+    if !data.is_object() {
+        return Some(format!("{} block has no data", block.block_type));
+    }
+    match block.block_type.as_str() {
+        "paragraph" | "header" if !data.get("text").is_some_and(Value::is_string) => {
+            Some(format!("{} block has no text", block.block_type))
+        }
+        "header"
+            if !data
+                .get("level")
+                .and_then(|v| v.as_u64())
+                .is_some_and(|level| (1..=6).contains(&level)) =>
+        {
+            Some("Heading has no valid level".to_string())
+        }
+        "list" | "checklist" if !data.get("items").is_some_and(Value::is_array) => {
+            Some(format!("{} block has no items", block.block_type))
+        }
+        _ => None,
+    }
+}
+
+/// Visit every string in `value`, replacing it when `f` returns `Some`;
+/// returns whether any was replaced
+fn walk_strings(value: &mut Value, f: &mut dyn FnMut(&str) -> Option<String>) -> bool {
+    match value {
+        Value::String(s) => match f(s) {
+            Some(new) => {
+                *s = new;
+                true
+            }
+            None => false,
+        },
+        Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |changed, v| walk_strings(v, f) | changed),
+        Value::Object(map) => map
+            .values_mut()
+            .fold(false, |changed, v| walk_strings(v, f) | changed),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::NotebookType;
+    use tempfile::TempDir;
+
+    fn block(id: &str, block_type: &str, data: Value) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn paragraph(id: &str, text: &str) -> EditorBlock {
+        block(id, "paragraph", json!({ "text": text }))
+    }
+
+    fn setup() -> (TempDir, FileStorageWriter, Uuid) {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        (temp_dir, storage, notebook.id)
+    }
+
+    fn page_with(
+        storage: &FileStorageWriter,
+        notebook_id: Uuid,
+        title: &str,
+        blocks: Vec<EditorBlock>,
+    ) -> Page {
+        let mut page = storage.create_page(notebook_id, title.to_string()).unwrap();
+        page.content.blocks = blocks;
+        storage.update_page(&page).unwrap();
+        page
+    }
+
+    fn kinds(report: &LintReport, page_id: Uuid) -> Vec<LintKind> {
+        report
+            .diagnostics
+            .iter()
+            .filter(|d| d.page_id == page_id)
+            .map(|d| d.kind)
+            .collect()
+    }
+
+    #[test]
+    fn finds_each_kind_of_problem() {
+        let (_dir, storage, nb) = setup();
+        let assets = storage.notebook_assets_dir(nb).join("images");
+        std::fs::create_dir_all(&assets).unwrap();
+        std::fs::write(assets.join("big.png"), vec![0u8; 2048]).unwrap();
+
+        let target = page_with(&storage, nb, "Target", vec![paragraph("t1", "Hello")]);
+        let page = page_with(
+            &storage,
+            nb,
+            "Notes",
+            vec![
+                paragraph(
+                    "a",
+                    &format!(
+                        "<wiki-link data-page-title=\"Target\" data-page-id=\"{0}\">Target</wiki-link> \
+                         <block-ref data-block-id=\"t1\" data-page-id=\"{0}\">Hello</block-ref> \
+                         <block-ref data-block-id=\"gone\" data-page-id=\"{0}\">Bye</block-ref>",
+                        target.id
+                    ),
+                ),
+                block("b", "image", json!({"file": {"url": format!("asset://{}/images/big.png", nb)}})),
+                block("c", "image", json!({"file": {"url": format!("asset://{}/images/none.png", nb)}})),
+                block("d", "header", json!({"text": "No level"})),
+                paragraph("d", "Same id"),
+            ],
+        );
+        let empty = page_with(&storage, nb, "notes ", Vec::new());
+
+        let report = lint_notebook(
+            &storage,
+            nb,
+            LintOptions {
+                max_image_bytes: 1024,
+            },
+        )
+        .unwrap();
+        assert_eq!(report.pages_checked, 3);
+        assert!(kinds(&report, target.id).is_empty());
+        assert_eq!(
+            kinds(&report, page.id),
+            [
+                LintKind::DuplicateTitle,
+                LintKind::BrokenLink,
+                LintKind::OversizedImage,
+                LintKind::MissingAsset,
+                LintKind::MalformedBlock,
+                LintKind::MalformedBlock,
+            ]
+        );
+        assert_eq!(
+            kinds(&report, empty.id),
+            [LintKind::DuplicateTitle, LintKind::EmptyPage]
+        );
+        let broken = report
+            .diagnostics
+            .iter()
+            .find(|d| d.kind == LintKind::BrokenLink)
+            .unwrap();
+        assert_eq!(broken.fix, Some(LintFix::Unlink));
+    }
+
+    #[test]
+    fn fixes_stale_links_and_duplicate_ids() {
+        let (_dir, storage, nb) = setup();
+        let mut renamed = page_with(&storage, nb, "Plan", vec![paragraph("p1", "Step")]);
+        renamed.title = "Roadmap".to_string();
+        storage.update_page(&renamed).unwrap();
+        let other = page_with(&storage, nb, "Other", vec![paragraph("moved", "Here now")]);
+        let page = page_with(
+            &storage,
+            nb,
+            "Notes",
+            vec![
+                paragraph(
+                    "a",
+                    &format!(
+                        "<wiki-link data-page-title=\"Plan\" data-page-id=\"{}\">the plan</wiki-link> \
+                         <block-ref data-block-id=\"moved\" data-page-id=\"{}\">Here</block-ref>",
+                        renamed.id,
+                        Uuid::new_v4()
+                    ),
+                ),
+                paragraph("a", "Copy"),
+                paragraph("b", "<wiki-link data-page-title=\"Someday\">Someday</wiki-link>"),
+            ],
+        );
+
+        let report = lint_notebook(&storage, nb, LintOptions::default()).unwrap();
+        let fixable: HashSet<String> = report
+            .diagnostics
+            .iter()
+            .filter(|d| d.fix.is_some())
+            .map(|d| d.id.clone())
+            .collect();
+        assert_eq!(fixable.len(), 3);
+        // The link to a page that doesn't exist yet is left alone
+        assert!(report
+            .diagnostics
+            .iter()
+            .any(|d| d.severity == LintSeverity::Info && d.fix.is_none()));
+
+        let fixes = fix_notebook(&storage, nb, &fixable, LintOptions::default()).unwrap();
+        assert_eq!(fixes.fixed.len(), 3);
+        assert_eq!(fixes.updated.len(), 1);
+
+        let page = storage.get_page(nb, page.id).unwrap();
+        let text = page.content.blocks[0].data["text"].as_str().unwrap();
+        assert!(text.contains("data-page-title=\"Roadmap\""));
+        assert!(text.contains("the plan</wiki-link>"));
+        assert!(text.contains(&other.id.to_string()));
+        assert_ne!(page.content.blocks[1].id, "a");
+
+        let report = lint_notebook(&storage, nb, LintOptions::default()).unwrap();
+        assert!(report.diagnostics.iter().all(|d| d.fix.is_none()));
+    }
+}
@@ -1,27 +1,25 @@
 use crate::storage::{EditorBlock, Page};
 use regex::Regex;
 
+use super::frontmatter::Frontmatter;
+
 /// Export a Page to Markdown format with YAML frontmatter
 pub fn export_page_to_markdown(page: &Page) -> String {
     let mut output = String::new();
 
     // Generate YAML frontmatter
-    output.push_str("---\n");
-    output.push_str(&format!("title: \"{}\"\n", escape_yaml_string(&page.title)));
+    output.push_str(&Frontmatter::from_page(page).render());
+    output.push('\n');
 
-    if !page.tags.is_empty() {
-        output.push_str("tags:\n");
-        for tag in &page.tags {
-            output.push_str(&format!("  - \"{}\"\n", escape_yaml_string(tag)));
-        }
-    }
+    output.push_str(&export_blocks_to_markdown(&page.content.blocks));
+    output.trim_end().to_string() + "\n"
+}
 
-    output.push_str(&format!("created: {}\n", page.created_at.to_rfc3339()));
-    output.push_str(&format!("updated: {}\n", page.updated_at.to_rfc3339()));
-    output.push_str("---\n\n");
+/// Convert blocks to Markdown, without frontmatter
+pub fn export_blocks_to_markdown(blocks: &[EditorBlock]) -> String {
+    let mut output = String::new();
 
-    // Convert blocks to markdown
-    for block in &page.content.blocks {
+    for block in blocks {
         let block_md = convert_block_to_markdown(block);
         if !block_md.is_empty() {
             output.push_str(&block_md);
@@ -43,6 +41,7 @@ fn convert_block_to_markdown(block: &EditorBlock) -> String {
         "code" => convert_code(block),
         "mermaid" => convert_mermaid(block),
         "animation" => convert_animation(block),
+        "math" => convert_math(block),
         "quote" => convert_quote(block),
         "delimiter" => "---".to_string(),
         "table" => convert_table(block),
@@ -158,6 +157,19 @@ fn convert_animation(block: &EditorBlock) -> String {
     format!("```animation\n{}\n```", html)
 }
 
+/// A display formula round-trips to a `$$` block; inline `$...$` formulas
+/// are left in the text as they are.
+fn convert_math(block: &EditorBlock) -> String {
+    let latex = block.data.get("latex")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+
+    if latex.trim().is_empty() {
+        return String::new();
+    }
+    format!("$$\n{}\n$$", latex.trim())
+}
+
 fn convert_quote(block: &EditorBlock) -> String {
     let text = block.data.get("text")
         .and_then(|v| v.as_str())
@@ -338,12 +350,6 @@ fn convert_inline_html_to_markdown(text: &str) -> String {
     result
 }
 
-/// Escape special characters for YAML strings
-fn escape_yaml_string(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('"', "\\\"")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -384,6 +390,9 @@ mod tests {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -470,6 +479,18 @@ mod tests {
         assert!(result.ends_with("```"));
     }
 
+    #[test]
+    fn test_convert_math_block() {
+        let block = EditorBlock {
+            id: "1".to_string(),
+            block_type: "math".to_string(),
+            data: serde_json::json!({ "latex": "\\frac{a}{b}" }),
+        };
+
+        let result = convert_block_to_markdown(&block);
+        assert_eq!(result, "$$\n\\frac{a}{b}\n$$");
+    }
+
     #[test]
     fn test_convert_animation_block() {
         let block = EditorBlock {
@@ -648,13 +669,6 @@ mod tests {
         assert_eq!(result, "Visit [Example](https://example.com)");
     }
 
-    #[test]
-    fn test_escape_yaml_string() {
-        assert_eq!(escape_yaml_string("simple"), "simple");
-        assert_eq!(escape_yaml_string("with \"quotes\""), "with \\\"quotes\\\"");
-        assert_eq!(escape_yaml_string("back\\slash"), "back\\\\slash");
-    }
-
     #[test]
     fn test_export_page_with_no_tags() {
         let page = create_test_page(
@@ -689,6 +703,31 @@ mod tests {
         assert!(markdown.contains("\\\\backslash"));
     }
 
+    #[test]
+    fn page_metadata_survives_an_export_import_round_trip() {
+        let mut page = create_test_page(
+            "Metadata",
+            vec![EditorBlock {
+                id: "1".to_string(),
+                block_type: "paragraph".to_string(),
+                data: serde_json::json!({ "text": "Body" }),
+            }],
+            vec!["work".to_string()],
+        );
+        page.properties.insert("status".to_string(), "draft: \"v2\"".to_string());
+        page.properties.insert("aliases".to_string(), "Meta\nMD".to_string());
+
+        let markdown = export_page_to_markdown(&page);
+        assert!(markdown.contains("aliases:\n  - \"Meta\"\n  - \"MD\"\n"));
+        let back = crate::markdown::import_markdown_to_page(&markdown, page.notebook_id, "x");
+
+        assert_eq!(back.title, page.title);
+        assert_eq!(back.tags, page.tags);
+        assert_eq!(back.properties, page.properties);
+        assert_eq!(back.created_at, page.created_at);
+        assert_eq!(back.updated_at, page.updated_at);
+    }
+
     #[test]
     fn test_header_levels() {
         for level in 1..=6 {
@@ -0,0 +1,346 @@
+//! YAML frontmatter of Markdown pages
+//!
+//! Exported pages start with their metadata, and importing reads it back:
+//!
+//! ```yaml
+//! ---
+//! title: "Quarterly plan"
+//! tags:
+//!   - "work"
+//! aliases:
+//!   - "Q3 plan"
+//! created: 2024-07-01T09:00:00+00:00
+//! updated: 2024-07-03T17:30:00+00:00
+//! properties:
+//!   "status": "draft"
+//!   "summary": "Goals and owners for Q3"
+//! ---
+//! ```
+//!
+//! Every key is optional. `tags` and `aliases` may also be a single
+//! comma-separated string, and tags may start with `#`. `created`/`updated`
+//! are RFC 3339 timestamps or plain dates. `properties` is the page's
+//! key/value metadata; any other top-level key an editor adds (Obsidian's
+//! `cssclasses`, say) is kept as a property too, so nothing is dropped on
+//! import. Aliases are stored as the `aliases` property, one per line.
+//!
+//! Frontmatter that isn't valid YAML is read line by line for the title,
+//! tags and dates, as older exports were.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_yaml::Value;
+
+use crate::storage::Page;
+
+/// Page property holding the page's aliases, one per line
+pub const ALIASES_PROPERTY: &str = "aliases";
+
+/// Metadata carried in a page's frontmatter
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Frontmatter {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+    pub created: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    /// Page properties other than aliases
+    pub properties: BTreeMap<String, String>,
+}
+
+impl Frontmatter {
+    pub fn from_page(page: &Page) -> Self {
+        let mut properties = page.properties.clone();
+        let aliases = properties
+            .remove(ALIASES_PROPERTY)
+            .map(|a| split_aliases(&a))
+            .unwrap_or_default();
+        Self {
+            title: Some(page.title.clone()),
+            tags: page.tags.clone(),
+            aliases,
+            created: Some(page.created_at),
+            updated: Some(page.updated_at),
+            properties,
+        }
+    }
+
+    /// Page properties for this frontmatter, aliases included
+    pub fn page_properties(&self) -> BTreeMap<String, String> {
+        let mut properties = self.properties.clone();
+        if !self.aliases.is_empty() {
+            properties.insert(ALIASES_PROPERTY.to_string(), self.aliases.join("\n"));
+        }
+        properties
+    }
+
+    /// The frontmatter block, delimiters included
+    pub fn render(&self) -> String {
+        let mut out = String::from("---\n");
+        if let Some(title) = &self.title {
+            out.push_str(&format!("title: \"{}\"\n", escape_yaml_string(title)));
+        }
+        push_list(&mut out, "tags", &self.tags);
+        push_list(&mut out, "aliases", &self.aliases);
+        if let Some(created) = self.created {
+            out.push_str(&format!("created: {}\n", created.to_rfc3339()));
+        }
+        if let Some(updated) = self.updated {
+            out.push_str(&format!("updated: {}\n", updated.to_rfc3339()));
+        }
+        if !self.properties.is_empty() {
+            out.push_str("properties:\n");
+            for (key, value) in &self.properties {
+                out.push_str(&format!(
+                    "  \"{}\": \"{}\"\n",
+                    escape_yaml_string(key),
+                    escape_yaml_string(value)
+                ));
+            }
+        }
+        out.push_str("---\n");
+        out
+    }
+}
+
+fn push_list(out: &mut String, key: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(key);
+    out.push_str(":\n");
+    for item in items {
+        out.push_str(&format!("  - \"{}\"\n", escape_yaml_string(item)));
+    }
+}
+
+/// The aliases stored in an `aliases` property
+pub fn split_aliases(value: &str) -> Vec<String> {
+    value
+        .lines()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Escape a string for a double-quoted YAML scalar
+pub fn escape_yaml_string(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// Split Markdown into its frontmatter and body. Without a frontmatter
+/// block the frontmatter is empty and the body is the whole text.
+pub fn parse(markdown: &str) -> (Frontmatter, String) {
+    if !markdown.starts_with("---") {
+        return (Frontmatter::default(), markdown.to_string());
+    }
+    let content_after_first = &markdown[3..];
+    let Some(end_pos) = content_after_first.find("\n---") else {
+        return (Frontmatter::default(), markdown.to_string());
+    };
+    let yaml = content_after_first[..end_pos].trim();
+    let body = content_after_first[end_pos + 4..].trim_start();
+
+    let frontmatter = match serde_yaml::from_str::<Value>(yaml) {
+        Ok(Value::Mapping(map)) => from_mapping(map),
+        Ok(Value::Null) => Frontmatter::default(),
+        _ => parse_lenient(yaml),
+    };
+    (frontmatter, body.to_string())
+}
+
+fn from_mapping(map: serde_yaml::Mapping) -> Frontmatter {
+    let mut frontmatter = Frontmatter::default();
+    for (key, value) in map {
+        let Some(key) = key.as_str().map(str::to_string) else {
+            continue;
+        };
+        match key.as_str() {
+            "title" => frontmatter.title = scalar_string(&value).filter(|t| !t.is_empty()),
+            "tags" | "tag" => {
+                frontmatter.tags = string_list(&value)
+                    .into_iter()
+                    .map(|t| t.trim_start_matches('#').to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            }
+            "aliases" | "alias" => frontmatter.aliases = string_list(&value),
+            "created" => {
+                frontmatter.created = scalar_string(&value).and_then(|s| parse_datetime(&s))
+            }
+            "updated" => {
+                frontmatter.updated = scalar_string(&value).and_then(|s| parse_datetime(&s))
+            }
+            "properties" => {
+                if let Value::Mapping(properties) = value {
+                    for (k, v) in properties {
+                        if let Some(k) = scalar_string(&k) {
+                            frontmatter.properties.insert(k, property_value(&v));
+                        }
+                    }
+                }
+            }
+            _ => {
+                frontmatter.properties.insert(key, property_value(&value));
+            }
+        }
+    }
+    frontmatter
+}
+
+/// The old line-based reading, for frontmatter serde_yaml rejects
+fn parse_lenient(yaml: &str) -> Frontmatter {
+    let mut frontmatter = Frontmatter::default();
+    for line in yaml.lines() {
+        let line = line.trim();
+        if let Some(title) = line.strip_prefix("title:") {
+            frontmatter.title = Some(parse_yaml_string(title));
+        } else if let Some(created) = line.strip_prefix("created:") {
+            frontmatter.created = parse_datetime(created);
+        } else if let Some(updated) = line.strip_prefix("updated:") {
+            frontmatter.updated = parse_datetime(updated);
+        } else if let Some(tag) = line.strip_prefix("- ") {
+            // Tag item (assumes tags come after title)
+            let tag = parse_yaml_string(tag);
+            if frontmatter.title.is_some() && !tag.is_empty() {
+                frontmatter.tags.push(tag);
+            }
+        }
+    }
+    frontmatter
+}
+
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A list of strings from a sequence or a comma-separated string
+fn string_list(value: &Value) -> Vec<String> {
+    let items: Vec<String> = match value {
+        Value::Sequence(items) => items.iter().filter_map(scalar_string).collect(),
+        other => scalar_string(other)
+            .map(|s| s.split(',').map(|i| i.trim().to_string()).collect())
+            .unwrap_or_default(),
+    };
+    items.into_iter().filter(|i| !i.is_empty()).collect()
+}
+
+/// A property value as text; lists are comma-separated
+fn property_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Sequence(items) => items
+            .iter()
+            .map(property_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Tagged(tagged) => property_value(&tagged.value),
+        Value::Mapping(_) => serde_yaml::to_string(value)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default(),
+        other => scalar_string(other).unwrap_or_default(),
+    }
+}
+
+/// Parse a YAML string value (handles quoted and unquoted)
+fn parse_yaml_string(s: &str) -> String {
+    let s = s.trim();
+    if s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+    {
+        s[1..s.len() - 1].replace("\\\"", "\"").replace("\\'", "'")
+    } else {
+        s.to_string()
+    }
+}
+
+/// An RFC 3339 timestamp, or a date taken as midnight UTC
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim().trim_matches(|c| c == '"' || c == '\'');
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_escape_yaml_string() {
+        assert_eq!(escape_yaml_string("simple"), "simple");
+        assert_eq!(escape_yaml_string("with \"quotes\""), "with \\\"quotes\\\"");
+        assert_eq!(escape_yaml_string("back\\slash"), "back\\\\slash");
+        assert_eq!(escape_yaml_string("two\nlines"), "two\\nlines");
+    }
+
+    #[test]
+    fn test_parse_yaml_string_quoted() {
+        assert_eq!(parse_yaml_string("\"quoted value\""), "quoted value");
+        assert_eq!(parse_yaml_string("'single quoted'"), "single quoted");
+        assert_eq!(parse_yaml_string("unquoted"), "unquoted");
+    }
+
+    #[test]
+    fn test_metadata_round_trips() {
+        let mut page = Page::new(Uuid::new_v4(), "Plan: \"Q3\"".to_string());
+        page.tags = vec!["work".to_string(), "planning".to_string()];
+        page.properties
+            .insert("summary".to_string(), "Goals,\nowners \\ dates".to_string());
+        page.properties.insert(
+            ALIASES_PROPERTY.to_string(),
+            "Q3 plan\nThird quarter".to_string(),
+        );
+
+        let rendered = Frontmatter::from_page(&page).render();
+        let (frontmatter, body) = parse(&format!("{}\nBody", rendered));
+        assert_eq!(body, "Body");
+        assert_eq!(frontmatter.title.as_deref(), Some("Plan: \"Q3\""));
+        assert_eq!(frontmatter.tags, page.tags);
+        assert_eq!(frontmatter.aliases, ["Q3 plan", "Third quarter"]);
+        assert_eq!(frontmatter.created, Some(page.created_at));
+        assert_eq!(frontmatter.page_properties(), page.properties);
+    }
+
+    #[test]
+    fn test_external_frontmatter() {
+        let markdown = "---\ntitle: Notes\ntags: \"#a, b\"\naliases: [N]\ncreated: 2024-03-01\n\
+                        cssclasses:\n  - wide\n  - dark\nrating: 4\n---\nText";
+        let (frontmatter, body) = parse(markdown);
+        assert_eq!(body, "Text");
+        assert_eq!(frontmatter.tags, ["a", "b"]);
+        assert_eq!(frontmatter.aliases, ["N"]);
+        assert_eq!(
+            frontmatter.created.map(|d| d.to_rfc3339()).as_deref(),
+            Some("2024-03-01T00:00:00+00:00")
+        );
+        assert_eq!(frontmatter.properties["cssclasses"], "wide, dark");
+        assert_eq!(frontmatter.properties["rating"], "4");
+    }
+
+    #[test]
+    fn test_invalid_yaml_falls_back_to_lines() {
+        let markdown = "---\ntitle: \"A: b\" c\ntags:\n  - \"x\"\n  bad: [\n---\nBody";
+        let (frontmatter, _) = parse(markdown);
+        assert_eq!(frontmatter.title.as_deref(), Some("\"A: b\" c"));
+        assert_eq!(frontmatter.tags, ["x"]);
+    }
+}
@@ -1,17 +1,10 @@
 use crate::storage::{EditorBlock, EditorData, Page};
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use regex::Regex;
 use uuid::Uuid;
 
-/// Frontmatter extracted from markdown
-#[derive(Default)]
-struct Frontmatter {
-    title: Option<String>,
-    tags: Vec<String>,
-    created: Option<DateTime<Utc>>,
-    updated: Option<DateTime<Utc>>,
-}
+use super::frontmatter::parse as parse_frontmatter;
 
 /// Import markdown content and create a new Page
 pub fn import_markdown_to_page(markdown: &str, notebook_id: Uuid, fallback_title: &str) -> Page {
@@ -20,6 +13,7 @@ pub fn import_markdown_to_page(markdown: &str, notebook_id: Uuid, fallback_title
     let blocks = parse_markdown_to_blocks(&body);
 
     let now = Utc::now();
+    let properties = frontmatter.page_properties();
     let title = frontmatter.title.unwrap_or_else(|| {
         // Try to extract title from first heading
         extract_title_from_blocks(&blocks).unwrap_or_else(|| fallback_title.to_string())
@@ -57,68 +51,14 @@ pub fn import_markdown_to_page(markdown: &str, notebook_id: Uuid, fallback_title
         daily_note_date: None,
         plugin_page_type: None,
         plugin_data: None,
+        properties,
+        encryption: None,
+        stats: None,
         created_at: frontmatter.created.unwrap_or(now),
         updated_at: frontmatter.updated.unwrap_or(now),
     }
 }
 
-/// Parse YAML frontmatter from markdown content
-fn parse_frontmatter(markdown: &str) -> (Frontmatter, String) {
-    let mut frontmatter = Frontmatter::default();
-
-    // Check if content starts with frontmatter delimiter
-    if !markdown.starts_with("---") {
-        return (frontmatter, markdown.to_string());
-    }
-
-    // Find the closing delimiter
-    let content_after_first = &markdown[3..];
-    if let Some(end_pos) = content_after_first.find("\n---") {
-        let yaml_content = &content_after_first[..end_pos].trim();
-        let body = &content_after_first[end_pos + 4..].trim_start();
-
-        // Parse YAML-like frontmatter (simple key-value parsing)
-        for line in yaml_content.lines() {
-            let line = line.trim();
-            if line.starts_with("title:") {
-                frontmatter.title = Some(parse_yaml_string(&line[6..]));
-            } else if line.starts_with("created:") {
-                frontmatter.created = parse_datetime(&line[8..]);
-            } else if line.starts_with("updated:") {
-                frontmatter.updated = parse_datetime(&line[8..]);
-            } else if line.starts_with("- ") && frontmatter.title.is_some() {
-                // Tag item (assumes tags come after title)
-                let tag = parse_yaml_string(&line[2..]);
-                if !tag.is_empty() {
-                    frontmatter.tags.push(tag);
-                }
-            }
-        }
-
-        return (frontmatter, body.to_string());
-    }
-
-    (frontmatter, markdown.to_string())
-}
-
-/// Parse a YAML string value (handles quoted and unquoted)
-fn parse_yaml_string(s: &str) -> String {
-    let s = s.trim();
-    if (s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')) {
-        s[1..s.len() - 1].replace("\\\"", "\"").replace("\\'", "'")
-    } else {
-        s.to_string()
-    }
-}
-
-/// Parse a datetime string
-fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
-    let s = s.trim();
-    DateTime::parse_from_rfc3339(s)
-        .ok()
-        .map(|dt| dt.with_timezone(&Utc))
-}
-
 /// Parse markdown body into Editor.js blocks
 pub fn parse_markdown_to_blocks(markdown: &str) -> Vec<EditorBlock> {
     // Enable tables + GFM task lists. Without ENABLE_TASKLISTS, `- [ ]` items
@@ -128,7 +68,8 @@ pub fn parse_markdown_to_blocks(markdown: &str) -> Vec<EditorBlock> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_TASKLISTS);
-    let parser = Parser::new_ext(markdown, options);
+    let protected = super::math::protect(markdown);
+    let parser = Parser::new_ext(&protected.markdown, options);
     let mut blocks: Vec<EditorBlock> = Vec::new();
     let mut current_text = String::new();
     let mut in_code_block = false;
@@ -469,7 +410,7 @@ pub fn parse_markdown_to_blocks(markdown: &str) -> Vec<EditorBlock> {
     // Flush any remaining content
     flush_paragraph(&mut blocks, &mut current_text);
 
-    blocks
+    protected.restore(blocks)
 }
 
 /// Convert heading level enum to integer
@@ -582,13 +523,6 @@ updated: 2024-01-02T00:00:00Z
         assert_eq!(body, markdown);
     }
 
-    #[test]
-    fn test_parse_yaml_string_quoted() {
-        assert_eq!(parse_yaml_string("\"quoted value\""), "quoted value");
-        assert_eq!(parse_yaml_string("'single quoted'"), "single quoted");
-        assert_eq!(parse_yaml_string("unquoted"), "unquoted");
-    }
-
     #[test]
     fn test_import_simple_markdown() {
         let markdown = r#"# Hello World
@@ -843,6 +777,23 @@ Content
         }
     }
 
+    #[test]
+    fn test_import_math() {
+        let markdown = "Mass $E = mc^2$ costs $5 and $10.\n\n$$\n\\frac{a_1}{b_1}\n$$\n";
+        let page = import_markdown_to_page(markdown, Uuid::new_v4(), "Untitled");
+
+        let paragraph = page.content.blocks.iter()
+            .find(|b| b.block_type == "paragraph")
+            .unwrap();
+        let text = paragraph.data.get("text").unwrap().as_str().unwrap();
+        assert_eq!(text, "Mass $E = mc^2$ costs $5 and $10.");
+
+        let math = page.content.blocks.iter()
+            .find(|b| b.block_type == "math")
+            .expect("display formula becomes a math block");
+        assert_eq!(math.data.get("latex").unwrap().as_str().unwrap(), "\\frac{a_1}{b_1}");
+    }
+
     #[test]
     fn test_import_link() {
         let markdown = "Check out [this link](https://example.com).";
@@ -0,0 +1,402 @@
+//! LaTeX math in markdown and page text
+//!
+//! A display formula is a `math` block holding its source in `data.latex`,
+//! and round-trips through markdown as a `$$ ... $$` block. Inline formulas
+//! stay in the text as `$...$`, on one line, and are rendered by KaTeX
+//! wherever a page is shown as HTML. A `$` only opens a formula when it's
+//! followed by a non-space and closes one when preceded by a non-space and
+//! not followed by a digit, so prices ("$5 and $10") stay text.
+//!
+//! pulldown-cmark would read the `\{`, `*` and `_` of a formula as markdown,
+//! so [`protect`] swaps formulas for placeholders before parsing and
+//! [`Protected::restore`] puts them back in the parsed blocks.
+
+use std::ops::Range;
+
+use serde_json::Value;
+
+use crate::storage::EditorBlock;
+
+/// Block type of a display formula
+pub const MATH_BLOCK: &str = "math";
+
+/// Placeholder delimiters; private-use characters, which markdown leaves alone
+const INLINE_OPEN: char = '\u{E000}';
+const INLINE_CLOSE: char = '\u{E001}';
+const DISPLAY_OPEN: char = '\u{E002}';
+const DISPLAY_CLOSE: char = '\u{E003}';
+
+/// LaTeX commands that only lay a formula out, left out of its search text
+const LAYOUT_COMMANDS: &[&str] = &[
+    "begin",
+    "end",
+    "left",
+    "right",
+    "big",
+    "Big",
+    "bigg",
+    "Bigg",
+    "displaystyle",
+    "textstyle",
+    "mathrm",
+    "mathbf",
+    "mathit",
+    "mathcal",
+    "mathbb",
+    "boldsymbol",
+    "operatorname",
+    "text",
+    "quad",
+    "qquad",
+];
+
+/// Byte ranges of the inline `$...$` formulas in `text`, delimiters included
+pub fn inline_math_spans(text: &str) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                i += 2;
+                continue;
+            }
+            b'$' if bytes.get(i + 1) == Some(&b'$') => {
+                i += 2;
+                continue;
+            }
+            b'$' => {}
+            _ => {
+                i += 1;
+                continue;
+            }
+        }
+        let open = i;
+        i += 1;
+        if bytes.get(i).map_or(true, u8::is_ascii_whitespace) {
+            continue;
+        }
+        let mut j = i;
+        while j < bytes.len() {
+            match bytes[j] {
+                b'\\' => j += 2,
+                b'\n' => break,
+                b'$' => {
+                    let closes = !bytes[j - 1].is_ascii_whitespace()
+                        && !bytes.get(j + 1).is_some_and(u8::is_ascii_digit);
+                    if closes {
+                        spans.push(open..j + 1);
+                        i = j + 1;
+                    }
+                    break;
+                }
+                _ => j += 1,
+            }
+        }
+    }
+    spans
+}
+
+/// The words and symbols of a formula, for search: commands become their
+/// names (`\alpha` → `alpha`), layout commands and grouping are dropped
+pub fn formula_text(latex: &str) -> String {
+    let mut out = String::with_capacity(latex.len());
+    let mut chars = latex.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !next.is_ascii_alphabetic() {
+                        break;
+                    }
+                    name.push(next);
+                    chars.next();
+                }
+                if name.is_empty() {
+                    // An escaped symbol (`\{`, `\%`) or spacing (`\,`, `\\`)
+                    match chars.next() {
+                        Some(symbol) if !matches!(symbol, ',' | ';' | ':' | '!' | '\\') => {
+                            out.push(symbol)
+                        }
+                        _ => out.push(' '),
+                    }
+                } else if !LAYOUT_COMMANDS.contains(&name.as_str()) {
+                    out.push(' ');
+                    out.push_str(&name);
+                    out.push(' ');
+                }
+            }
+            '{' | '}' | '&' | '~' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Markdown with its formulas swapped for placeholders
+pub struct Protected {
+    pub markdown: String,
+    formulas: Vec<String>,
+}
+
+/// Swap the formulas in `markdown` for placeholders. Code blocks and code
+/// spans are left as they are.
+pub fn protect(markdown: &str) -> Protected {
+    let mut out = Protected {
+        markdown: String::with_capacity(markdown.len()),
+        formulas: Vec::new(),
+    };
+    let mut fence: Option<String> = None;
+    let mut lines = markdown.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+
+        if let Some(marker) = &fence {
+            if trimmed.starts_with(marker.as_str()) {
+                fence = None;
+            }
+            push_line(&mut out.markdown, line);
+            continue;
+        }
+        if indent.len() < 4 && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            let marker: String = trimmed
+                .chars()
+                .take_while(|c| *c == trimmed.chars().next().unwrap_or('`'))
+                .collect();
+            fence = Some(marker);
+            push_line(&mut out.markdown, line);
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("$$") {
+            // `$$ x $$` on one line, or `$$` up to a line ending in `$$`
+            let formula = match rest.trim_end().strip_suffix("$$") {
+                Some(inner) => Some(inner.to_string()),
+                None => {
+                    let mut body = vec![rest.to_string()];
+                    let mut closed = None;
+                    let mut ahead = lines.clone();
+                    while let Some(next) = ahead.next() {
+                        if let Some(last) = next.trim_end().strip_suffix("$$") {
+                            body.push(last.to_string());
+                            closed = Some(ahead);
+                            break;
+                        }
+                        body.push(next.to_string());
+                    }
+                    closed.map(|rest_of_doc| {
+                        lines = rest_of_doc;
+                        body.join("\n")
+                    })
+                }
+            };
+            if let Some(formula) = formula {
+                let placeholder = out.placeholder(formula.trim(), true);
+                if indent.is_empty() {
+                    out.markdown.push('\n');
+                    push_line(&mut out.markdown, &placeholder);
+                    out.markdown.push('\n');
+                } else {
+                    push_line(&mut out.markdown, &format!("{}{}", indent, placeholder));
+                }
+                continue;
+            }
+        }
+
+        let code = code_spans(line);
+        let mut protected = String::with_capacity(line.len());
+        let mut last = 0;
+        for span in inline_math_spans(line) {
+            if code
+                .iter()
+                .any(|c| c.start < span.end && span.start < c.end)
+            {
+                continue;
+            }
+            protected.push_str(&line[last..span.start]);
+            let formula = &line[span.start + 1..span.end - 1];
+            protected.push_str(&out.placeholder(formula, false));
+            last = span.end;
+        }
+        protected.push_str(&line[last..]);
+        push_line(&mut out.markdown, &protected);
+    }
+    out
+}
+
+impl Protected {
+    fn placeholder(&mut self, formula: &str, display: bool) -> String {
+        self.formulas.push(formula.to_string());
+        let (open, close) = if display {
+            (DISPLAY_OPEN, DISPLAY_CLOSE)
+        } else {
+            (INLINE_OPEN, INLINE_CLOSE)
+        };
+        format!("{}{}{}", open, self.formulas.len() - 1, close)
+    }
+
+    /// Put the formulas back into blocks parsed from the protected markdown:
+    /// a paragraph that's just a display formula becomes a math block, and
+    /// formulas in text go back as escaped `$...$` / `$$...$$`
+    pub fn restore(&self, blocks: Vec<EditorBlock>) -> Vec<EditorBlock> {
+        if self.formulas.is_empty() {
+            return blocks;
+        }
+        blocks
+            .into_iter()
+            .map(|mut block| {
+                let text = block.data.get("text").and_then(|v| v.as_str());
+                if block.block_type == "paragraph" {
+                    if let Some(latex) = text.and_then(|t| self.display_formula(t.trim())) {
+                        block.block_type = MATH_BLOCK.to_string();
+                        block.data = serde_json::json!({ "latex": latex });
+                        return block;
+                    }
+                }
+                self.restore_value(&mut block.data);
+                block
+            })
+            .collect()
+    }
+
+    fn display_formula(&self, text: &str) -> Option<&str> {
+        let index = text
+            .strip_prefix(DISPLAY_OPEN)?
+            .strip_suffix(DISPLAY_CLOSE)?
+            .parse::<usize>()
+            .ok()?;
+        self.formulas.get(index).map(String::as_str)
+    }
+
+    fn restore_value(&self, value: &mut Value) {
+        match value {
+            Value::String(s) if s.contains([INLINE_OPEN, DISPLAY_OPEN]) => {
+                *s = self.restore_text(s);
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.restore_value(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.restore_value(v)),
+            _ => {}
+        }
+    }
+
+    fn restore_text(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find([INLINE_OPEN, DISPLAY_OPEN]) {
+            out.push_str(&rest[..start]);
+            let display = rest[start..].starts_with(DISPLAY_OPEN);
+            let close = if display { DISPLAY_CLOSE } else { INLINE_CLOSE };
+            let after = &rest[start + INLINE_OPEN.len_utf8()..];
+            let formula = after.find(close).and_then(|end| {
+                let index = after[..end].parse::<usize>().ok()?;
+                Some((self.formulas.get(index)?, end))
+            });
+            match formula {
+                Some((formula, end)) => {
+                    let delimiter = if display { "$$" } else { "$" };
+                    out.push_str(delimiter);
+                    out.push_str(&escape(formula));
+                    out.push_str(delimiter);
+                    rest = &after[end + close.len_utf8()..];
+                }
+                None => {
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+fn push_line(out: &mut String, line: &str) {
+    out.push_str(line);
+    out.push('\n');
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Byte ranges of the code spans in a line: a run of backticks up to the
+/// next run of the same length
+fn code_spans(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let run_at = |i: usize| bytes[i..].iter().take_while(|b| **b == b'`').count();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let len = run_at(i);
+        let mut j = i + len;
+        let mut end = None;
+        while j < bytes.len() {
+            if bytes[j] == b'`' {
+                let close = run_at(j);
+                if close == len {
+                    end = Some(j + close);
+                    break;
+                }
+                j += close;
+            } else {
+                j += 1;
+            }
+        }
+        match end {
+            Some(end) => {
+                spans.push(i..end);
+                i = end;
+            }
+            None => i += len,
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(text: &str) -> Vec<&str> {
+        inline_math_spans(text)
+            .into_iter()
+            .map(|r| &text[r])
+            .collect()
+    }
+
+    #[test]
+    fn finds_inline_formulas_but_not_prices() {
+        assert_eq!(
+            spans("Energy $E = mc^2$ and $\\$5$ but not $5 and $10, nor $ x $."),
+            ["$E = mc^2$", "$\\$5$"]
+        );
+        assert_eq!(spans("$$x$$ and \\$y$ and $a$5"), Vec::<&str>::new());
+        assert_eq!(spans("é $\\alpha_1$ ü"), ["$\\alpha_1$"]);
+    }
+
+    #[test]
+    fn formula_text_keeps_names_and_symbols() {
+        assert_eq!(
+            formula_text(r"\frac{\alpha}{2} + \left( x_1 \right) \, \mathrm{d}x"),
+            "frac alpha 2 + ( x_1 ) d x"
+        );
+        assert_eq!(formula_text(r"50\% \text{off}"), "50% off");
+    }
+
+    #[test]
+    fn protects_formulas_but_not_code() {
+        let markdown = "Inline $a_1 * b_1$ and `$x$`.\n\n$$\n\\int_0^1 f\n$$\n\n```\n$$\n```\n";
+        let protected = protect(markdown);
+        assert_eq!(protected.formulas, ["a_1 * b_1", "\\int_0^1 f"]);
+        assert!(protected.markdown.contains("`$x$`"));
+        assert!(protected.markdown.contains("```\n$$\n```"));
+        assert!(!protected.markdown.contains("a_1"));
+    }
+}
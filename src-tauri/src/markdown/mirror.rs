@@ -0,0 +1,758 @@
+//! Scheduled one-way Markdown mirror of a library.
+//!
+//! The mirror writes every page of a library as a `.md` file under a
+//! configured folder, laid out as `<notebook>/<section>/<folder>/…/<title>.md`,
+//! so the library can be grepped or opened in any editor. It is strictly
+//! one-way: edits made inside the mirror are overwritten on the next run.
+//!
+//! A manifest (`.nous-mirror.json`) in the mirror root records where each page
+//! was written and a hash of what was written, so a run only rewrites pages
+//! whose Markdown changed, moves files for renamed pages and deletes files for
+//! pages that were removed. Files the manifest doesn't know about are never
+//! touched.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::library::LibraryStorage;
+use crate::storage::{
+    atomic, FileStorage, Folder, FolderType, Notebook, Page, PageType, StorageError,
+};
+
+use super::export_page_to_markdown;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Manifest file kept in the mirror root
+pub const MANIFEST_FILE: &str = ".nous-mirror.json";
+
+/// Longest file or directory name the mirror writes (before the extension)
+const MAX_NAME_LEN: usize = 100;
+
+/// Per-library Markdown mirror settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownMirrorConfig {
+    /// Whether the scheduler keeps the mirror up to date
+    pub enabled: bool,
+    /// Folder the Markdown files are written to
+    pub target_dir: String,
+    /// Minutes between scheduled runs
+    #[serde(default = "default_interval_minutes")]
+    pub interval_minutes: u32,
+    /// Also mirror archived pages and notebooks
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+fn default_interval_minutes() -> u32 {
+    15
+}
+
+impl MarkdownMirrorConfig {
+    /// Check the target folder is usable for a library rooted at `library_path`
+    ///
+    /// The target must be absolute and must not overlap the library itself,
+    /// otherwise sync and backups would pick the mirror up (or the mirror
+    /// would overwrite library data).
+    pub fn validate(&self, library_path: &Path) -> std::result::Result<(), String> {
+        let target = PathBuf::from(self.target_dir.trim());
+        if self.target_dir.trim().is_empty() {
+            return Err("Mirror folder is required".to_string());
+        }
+        if !target.is_absolute() {
+            return Err("Mirror folder must be an absolute path".to_string());
+        }
+        if target.starts_with(library_path) || library_path.starts_with(&target) {
+            return Err("Mirror folder must be outside the library folder".to_string());
+        }
+        Ok(())
+    }
+
+    /// Interval between scheduled runs (at least one minute)
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(u64::from(self.interval_minutes.max(1)) * 60)
+    }
+}
+
+/// Outcome of one mirror run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorReport {
+    /// Pages written because they were new or changed
+    pub written: usize,
+    /// Pages skipped because the mirror copy is current
+    pub unchanged: usize,
+    /// Files deleted for pages that were removed, trashed or renamed
+    pub removed: usize,
+    /// Pages or notebooks that could not be mirrored
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MirrorManifest {
+    #[serde(default)]
+    generated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pages: HashMap<Uuid, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    /// Path relative to the mirror root, `/`-separated
+    path: String,
+    /// SHA-256 of the Markdown last written
+    hash: String,
+}
+
+/// A page rendered for the mirror
+pub(crate) struct MirroredPage {
+    pub id: Uuid,
+    pub notebook_id: Uuid,
+    pub path: String,
+    pub markdown: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The mirror layout of a library: each notebook's directory and its pages
+#[derive(Default)]
+pub(crate) struct MirrorLayout {
+    pub notebooks: Vec<(Uuid, String)>,
+    pub pages: Vec<MirroredPage>,
+}
+
+/// Bring the mirror at `target_dir` up to date with every page in `storage`
+///
+/// Encrypted notebooks are skipped so plaintext never leaves the library,
+/// as are trashed pages and page types with no Markdown form (PDFs, canvases…).
+pub fn mirror_library(
+    storage: &FileStorage,
+    target_dir: &Path,
+    include_archived: bool,
+) -> Result<MirrorReport> {
+    fs::create_dir_all(target_dir)?;
+    let manifest_path = target_dir.join(MANIFEST_FILE);
+    let previous = load_manifest(&manifest_path);
+    let mut report = MirrorReport::default();
+
+    let pages = collect_pages(storage, include_archived, &mut report)?.pages;
+    // A notebook or page that failed to read must not look deleted
+    let complete = report.errors.is_empty();
+
+    // Delete files no page wants any more before writing, so a page that
+    // takes over another page's old path isn't deleted right after writing.
+    let wanted: HashSet<&str> = pages.iter().map(|p| p.path.as_str()).collect();
+    for entry in previous.pages.values() {
+        if !complete || wanted.contains(entry.path.as_str()) {
+            continue;
+        }
+        let Some(path) = resolve(target_dir, &entry.path) else {
+            continue;
+        };
+        if path.is_file() {
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    report.removed += 1;
+                    prune_empty_dirs(&path, target_dir);
+                }
+                Err(e) => report.errors.push(format!("{}: {}", entry.path, e)),
+            }
+        }
+    }
+
+    let mut manifest = MirrorManifest::default();
+    for page in pages {
+        let Some(path) = resolve(target_dir, &page.path) else {
+            continue;
+        };
+        let hash = hex::encode(Sha256::digest(page.markdown.as_bytes()));
+        let current = previous
+            .pages
+            .get(&page.id)
+            .is_some_and(|e| e.path == page.path && e.hash == hash)
+            && path.is_file();
+
+        if current {
+            report.unchanged += 1;
+        } else {
+            let written = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| atomic::write_str(&path, &page.markdown));
+            if let Err(e) = written {
+                report.errors.push(format!("{}: {}", page.path, e));
+                continue;
+            }
+            report.written += 1;
+        }
+
+        manifest.pages.insert(
+            page.id,
+            ManifestEntry {
+                path: page.path,
+                hash,
+            },
+        );
+    }
+
+    if !complete {
+        for (id, entry) in previous.pages {
+            if !manifest.pages.contains_key(&id)
+                && !manifest.pages.values().any(|e| e.path == entry.path)
+            {
+                manifest.pages.insert(id, entry);
+            }
+        }
+    }
+
+    manifest.generated_at = Some(Utc::now());
+    atomic::write_str(&manifest_path, &serde_json::to_string_pretty(&manifest)?)?;
+
+    Ok(report)
+}
+
+/// Write one notebook's pages as Markdown files directly under `target_dir`
+///
+/// Uses the mirror's layout (`<section>/<folder>/…/<title>.md`) but is a
+/// one-off export: no manifest is kept and existing files with the same
+/// names are overwritten.
+pub fn export_notebook_markdown(
+    storage: &FileStorage,
+    notebook_id: Uuid,
+    target_dir: &Path,
+    include_archived: bool,
+) -> Result<MirrorReport> {
+    let notebook = storage.get_notebook(notebook_id)?;
+    if notebook
+        .encryption_config
+        .as_ref()
+        .is_some_and(|c| c.enabled)
+    {
+        return Err(StorageError::InvalidOperation(
+            "Encrypted notebooks cannot be exported as Markdown".to_string(),
+        ));
+    }
+
+    fs::create_dir_all(target_dir)?;
+    let mut report = MirrorReport::default();
+    let mut pages = Vec::new();
+    collect_notebook_pages(
+        storage,
+        &notebook,
+        "",
+        include_archived,
+        &mut HashSet::new(),
+        &mut report,
+        &mut pages,
+    );
+
+    for page in pages {
+        let Some(path) = resolve(target_dir, &page.path) else {
+            continue;
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| atomic::write_str(&path, &page.markdown));
+        match written {
+            Ok(()) => report.written += 1,
+            Err(e) => report.errors.push(format!("{}: {}", page.path, e)),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Render every mirrored page and assign it a unique relative path
+pub(crate) fn collect_pages(
+    storage: &FileStorage,
+    include_archived: bool,
+    report: &mut MirrorReport,
+) -> Result<MirrorLayout> {
+    let mut notebooks = storage.list_notebooks()?;
+    // Oldest first, so the oldest of two same-named items keeps the plain name
+    notebooks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+    let mut used_paths = HashSet::new();
+    let mut layout = MirrorLayout::default();
+
+    for notebook in &notebooks {
+        if notebook
+            .encryption_config
+            .as_ref()
+            .is_some_and(|c| c.enabled)
+        {
+            continue;
+        }
+        if notebook.archived && !include_archived {
+            continue;
+        }
+
+        let notebook_dir = unique_path(&mut used_paths, "", &sanitize_name(&notebook.name), "");
+        collect_notebook_pages(
+            storage,
+            notebook,
+            &notebook_dir,
+            include_archived,
+            &mut used_paths,
+            report,
+            &mut layout.pages,
+        );
+        layout.notebooks.push((notebook.id, notebook_dir));
+    }
+
+    Ok(layout)
+}
+
+/// Render the pages of one notebook under `notebook_dir` (relative to the
+/// mirror root; empty for the root itself)
+fn collect_notebook_pages(
+    storage: &FileStorage,
+    notebook: &Notebook,
+    notebook_dir: &str,
+    include_archived: bool,
+    used_paths: &mut HashSet<String>,
+    report: &mut MirrorReport,
+    mirrored: &mut Vec<MirroredPage>,
+) {
+    let mut pages = match storage.list_all_pages(notebook.id) {
+        Ok(pages) => pages,
+        Err(e) => {
+            report.errors.push(format!("{}: {}", notebook.name, e));
+            return;
+        }
+    };
+    pages.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+    let folders: HashMap<Uuid, Folder> = storage
+        .list_folders(notebook.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|f| (f.id, f))
+        .collect();
+    let sections: HashMap<Uuid, String> = storage
+        .list_sections(notebook.id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| (s.id, s.name))
+        .collect();
+
+    for page in &pages {
+        if page.deleted_at.is_some() {
+            continue;
+        }
+        let in_archive = page.is_archived
+            || page
+                .folder_id
+                .and_then(|id| folders.get(&id))
+                .is_some_and(|f| f.is_archived || f.folder_type == FolderType::Archive);
+        if in_archive && !include_archived {
+            continue;
+        }
+
+        let markdown = match page.page_type {
+            PageType::Standard | PageType::Sql => export_page_to_markdown(page),
+            PageType::Markdown => match storage.read_native_file_content(page) {
+                Ok(content) => content,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", page.title, e));
+                    continue;
+                }
+            },
+            _ => continue,
+        };
+
+        let mut dir = notebook_dir.to_string();
+        if notebook.sections_enabled {
+            if let Some(section) = page.section_id.and_then(|id| sections.get(&id)) {
+                dir = join_dir(&dir, &sanitize_name(section));
+            }
+        }
+        for name in folder_chain(page, &folders) {
+            dir = join_dir(&dir, &sanitize_name(&name));
+        }
+
+        let path = unique_path(used_paths, &dir, &sanitize_name(&page.title), ".md");
+        mirrored.push(MirroredPage {
+            id: page.id,
+            notebook_id: notebook.id,
+            path,
+            markdown,
+            updated_at: page.updated_at,
+        });
+    }
+}
+
+fn join_dir(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// Folder names from the notebook root down to the page's folder
+pub(crate) fn folder_chain(page: &Page, folders: &HashMap<Uuid, Folder>) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut next = page.folder_id;
+    // Bounded walk: a corrupt parent cycle must not hang the mirror
+    while let Some(folder) = next.and_then(|id| folders.get(&id)) {
+        if names.len() >= 32 {
+            break;
+        }
+        names.push(folder.name.clone());
+        next = folder.parent_id;
+    }
+    names.reverse();
+    names
+}
+
+/// Make a file or directory name safe on every platform
+pub(crate) fn sanitize_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(MAX_NAME_LEN)
+        .collect();
+    // No hidden files, no `..`, and Windows rejects trailing dots and spaces
+    let cleaned = cleaned
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' '])
+        .to_string();
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Join `dir` and `name` + `ext`, numbering the name if the path is taken
+///
+/// Comparison is case-insensitive because the mirror may live on a
+/// case-insensitive filesystem.
+fn unique_path(used: &mut HashSet<String>, dir: &str, name: &str, ext: &str) -> String {
+    let join = |name: &str| join_dir(dir, &format!("{}{}", name, ext));
+
+    let mut candidate = join(name);
+    let mut n = 2;
+    while !used.insert(candidate.to_lowercase()) {
+        candidate = join(&format!("{} ({})", name, n));
+        n += 1;
+    }
+    candidate
+}
+
+/// Resolve a manifest path under the mirror root, rejecting anything that
+/// could escape it
+pub(crate) fn resolve(root: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        Some(root.join(relative))
+    } else {
+        None
+    }
+}
+
+/// Remove directories left empty by a deletion, up to (not including) `root`
+pub(crate) fn prune_empty_dirs(removed_file: &Path, root: &Path) {
+    let mut dir = removed_file.parent();
+    while let Some(d) = dir {
+        if d == root || !d.starts_with(root) || fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+fn load_manifest(path: &Path) -> MirrorManifest {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// ===== Mirror Scheduler =====
+
+/// Message types for mirror scheduler communication
+#[derive(Debug)]
+pub enum MirrorSchedulerMessage {
+    /// Mirror settings changed; run any newly enabled mirrors
+    Reload,
+    /// Run every enabled mirror now
+    RunNow,
+    /// Shutdown
+    Shutdown,
+}
+
+/// Markdown mirror scheduler handle
+pub struct MirrorScheduler {
+    sender: tokio::sync::mpsc::Sender<MirrorSchedulerMessage>,
+}
+
+impl MirrorScheduler {
+    pub fn reload(&self) {
+        let _ = self.sender.try_send(MirrorSchedulerMessage::Reload);
+    }
+
+    pub fn run_now(&self) {
+        let _ = self.sender.try_send(MirrorSchedulerMessage::RunNow);
+    }
+
+    pub fn shutdown(&self) {
+        let _ = self.sender.try_send(MirrorSchedulerMessage::Shutdown);
+    }
+}
+
+/// How often the scheduler re-reads library settings and checks for due mirrors
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Start the Markdown mirror scheduler
+///
+/// Mirrors every library whose `markdown_mirror` setting is enabled, each on
+/// its own interval. Settings are re-read on every poll, so changes made by
+/// another process (the desktop app) are picked up within a minute.
+pub fn start_mirror_scheduler(library_storage: Arc<Mutex<LibraryStorage>>) -> MirrorScheduler {
+    let (tx, rx) = tokio::sync::mpsc::channel(32);
+
+    tauri::async_runtime::spawn(async move {
+        mirror_scheduler_loop(library_storage, rx).await;
+    });
+
+    MirrorScheduler { sender: tx }
+}
+
+/// Main mirror scheduler loop
+async fn mirror_scheduler_loop(
+    library_storage: Arc<Mutex<LibraryStorage>>,
+    mut receiver: tokio::sync::mpsc::Receiver<MirrorSchedulerMessage>,
+) {
+    let mut last_run: HashMap<Uuid, std::time::Instant> = HashMap::new();
+
+    log::info!("Markdown mirror scheduler started");
+
+    // First pass right away so a restart doesn't leave the mirror stale
+    run_due_mirrors(&library_storage, &mut last_run, false).await;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {
+                run_due_mirrors(&library_storage, &mut last_run, false).await;
+            }
+
+            msg = receiver.recv() => {
+                match msg {
+                    Some(MirrorSchedulerMessage::Reload) => {
+                        run_due_mirrors(&library_storage, &mut last_run, false).await;
+                    }
+                    Some(MirrorSchedulerMessage::RunNow) => {
+                        run_due_mirrors(&library_storage, &mut last_run, true).await;
+                    }
+                    Some(MirrorSchedulerMessage::Shutdown) | None => {
+                        log::info!("Markdown mirror scheduler: shutting down");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Run each enabled mirror whose interval has elapsed (or all of them if `force`)
+async fn run_due_mirrors(
+    library_storage: &Arc<Mutex<LibraryStorage>>,
+    last_run: &mut HashMap<Uuid, std::time::Instant>,
+    force: bool,
+) {
+    let libraries = match library_storage.lock() {
+        Ok(store) => store.list_libraries().unwrap_or_default(),
+        Err(_) => return,
+    };
+
+    for library in libraries {
+        let Some(config) = library.markdown_mirror.clone().filter(|c| c.enabled) else {
+            last_run.remove(&library.id);
+            continue;
+        };
+        let due = force
+            || !matches!(last_run.get(&library.id), Some(t) if t.elapsed() < config.interval());
+        if !due {
+            continue;
+        }
+        if library.is_encrypted() {
+            log::warn!(
+                "Markdown mirror: skipping encrypted library '{}'",
+                library.name
+            );
+            continue;
+        }
+        if let Err(e) = config.validate(&library.path) {
+            log::warn!("Markdown mirror for '{}': {}", library.name, e);
+            continue;
+        }
+
+        last_run.insert(library.id, std::time::Instant::now());
+        let name = library.name.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let storage = FileStorage::new(library.path.clone());
+            mirror_library(
+                &storage,
+                Path::new(config.target_dir.trim()),
+                config.include_archived,
+            )
+        })
+        .await;
+
+        match result {
+            Ok(Ok(report)) => {
+                log::info!(
+                    "Markdown mirror for '{}': {} written, {} unchanged, {} removed",
+                    name,
+                    report.written,
+                    report.unchanged,
+                    report.removed
+                );
+                for error in &report.errors {
+                    log::warn!("Markdown mirror for '{}': {}", name, error);
+                }
+            }
+            Ok(Err(e)) => log::error!("Markdown mirror for '{}' failed: {}", name, e),
+            Err(e) => log::error!("Markdown mirror task for '{}' panicked: {}", name, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{EditorBlock, FileStorageWriter, NotebookType};
+
+    fn paragraph(text: &str) -> EditorBlock {
+        EditorBlock {
+            id: Uuid::new_v4().to_string(),
+            block_type: "paragraph".to_string(),
+            data: serde_json::json!({ "text": text }),
+        }
+    }
+
+    #[test]
+    fn only_changed_pages_are_rewritten() {
+        let library = tempfile::tempdir().unwrap();
+        let mirror = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(library.path().to_path_buf()));
+        storage.init().unwrap();
+
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let mut first = storage
+            .create_page(notebook.id, "Plans".to_string())
+            .unwrap();
+        first.content.blocks.push(paragraph("ship it"));
+        storage.update_page(&first).unwrap();
+        let mut second = storage
+            .create_page(notebook.id, "Plans".to_string())
+            .unwrap();
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+        storage.update_page(&second).unwrap();
+
+        let report = mirror_library(&storage, mirror.path(), false).unwrap();
+        assert_eq!(report.written, 2);
+        let plans = mirror.path().join("Work/Plans.md");
+        assert!(fs::read_to_string(&plans).unwrap().contains("ship it"));
+        assert!(mirror.path().join("Work/Plans (2).md").is_file());
+
+        let report = mirror_library(&storage, mirror.path(), false).unwrap();
+        assert_eq!((report.written, report.unchanged), (0, 2));
+
+        // Renaming moves the file; trashing removes it
+        let mut renamed = storage.get_page(notebook.id, first.id).unwrap();
+        renamed.title = "Roadmap".to_string();
+        storage.update_page(&renamed).unwrap();
+        storage.delete_page(notebook.id, second.id).unwrap();
+
+        let report = mirror_library(&storage, mirror.path(), false).unwrap();
+        assert_eq!((report.written, report.removed), (1, 2));
+        assert!(mirror.path().join("Work/Roadmap.md").is_file());
+        assert!(!plans.exists());
+        assert!(!mirror.path().join("Work/Plans (2).md").exists());
+    }
+
+    #[test]
+    fn notebook_export_writes_folders_without_a_manifest() {
+        let library = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(library.path().to_path_buf()));
+        storage.init().unwrap();
+
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let folder = storage
+            .create_folder(notebook.id, "Projects".to_string(), None)
+            .unwrap();
+        let mut page = storage
+            .create_page(notebook.id, "Launch".to_string())
+            .unwrap();
+        page.folder_id = Some(folder.id);
+        page.content.blocks.push(paragraph("go live"));
+        storage.update_page(&page).unwrap();
+
+        let report = export_notebook_markdown(&storage, notebook.id, out.path(), false).unwrap();
+        assert_eq!(report.written, 1);
+        let file = out.path().join("Projects/Launch.md");
+        assert!(fs::read_to_string(file).unwrap().contains("go live"));
+        assert!(!out.path().join(MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn names_are_sanitized_and_manifest_paths_stay_inside() {
+        assert_eq!(sanitize_name("a/b: c?"), "a_b_ c_");
+        assert_eq!(sanitize_name(".."), "Untitled");
+        assert_eq!(sanitize_name("../x"), "_x");
+        assert_eq!(sanitize_name(" notes. "), "notes");
+
+        let mut used = HashSet::new();
+        assert_eq!(unique_path(&mut used, "Nb", "Todo", ".md"), "Nb/Todo.md");
+        assert_eq!(
+            unique_path(&mut used, "Nb", "TODO", ".md"),
+            "Nb/TODO (2).md"
+        );
+
+        let root = Path::new("/mirror");
+        assert!(resolve(root, "Nb/Todo.md").is_some());
+        assert!(resolve(root, "../etc/passwd").is_none());
+        assert!(resolve(root, "/etc/passwd").is_none());
+    }
+
+    #[test]
+    fn target_must_be_outside_the_library() {
+        let config = |dir: &str| MarkdownMirrorConfig {
+            enabled: true,
+            target_dir: dir.to_string(),
+            interval_minutes: 15,
+            include_archived: false,
+        };
+        let library = Path::new("/data/nous");
+        assert!(config("/home/me/mirror").validate(library).is_ok());
+        assert!(config("/data/nous/mirror").validate(library).is_err());
+        assert!(config("/data").validate(library).is_err());
+        assert!(config("mirror").validate(library).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 mod export;
+pub mod frontmatter;
 mod import;
+pub mod math;
+pub mod mirror;
 
-pub use export::export_page_to_markdown;
+pub use export::{export_blocks_to_markdown, export_page_to_markdown};
 pub use import::import_markdown_to_page;
 pub use import::parse_markdown_to_blocks;
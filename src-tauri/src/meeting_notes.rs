@@ -0,0 +1,582 @@
+//! Meeting notes from a recording
+//!
+//! `process_meeting_recording` runs a recording through a pipeline:
+//! transcription (faster-whisper), speaker diarization when pyannote is
+//! installed, then one AI request for a title, summary, decisions and action
+//! items. The result becomes a meeting page with the recording, attendees,
+//! summary, a checklist of action items and the transcript, linked from the
+//! daily note of the day it was recorded.
+//!
+//! This module holds the parts that don't need Python or storage:
+//! attributing transcript segments to speakers, the AI request and reply,
+//! and the blocks written to the pages.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::python_bridge::{ChatMessage, SpeakerTurn, TranscriptSegment};
+use crate::storage::EditorBlock;
+
+/// Tag on every meeting page
+pub const MEETING_TAG: &str = "meeting";
+/// Heading in the daily note the meeting links go under
+pub const MEETINGS_SECTION: &str = "Meetings";
+
+/// Transcript characters sent to the AI; a longer meeting is cut short
+const MAX_TRANSCRIPT_CHARS: usize = 80_000;
+
+/// Speakerless transcript text is split into paragraphs of about this size
+const PARAGRAPH_CHARS: usize = 800;
+
+#[derive(Error, Debug)]
+pub enum MeetingError {
+    #[error("no speech was found in the recording")]
+    NoSpeech,
+    #[error("AI reply is not valid meeting summary JSON: {0}")]
+    BadReply(String),
+}
+
+/// A run of transcript by one speaker
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptTurn {
+    /// "Speaker 1", "Speaker 2", ... in order of first appearance, or `None`
+    /// without diarization
+    pub speaker: Option<String>,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// A follow-up the AI found in the meeting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionItem {
+    pub text: String,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// `YYYY-MM-DD`, when a deadline was mentioned
+    #[serde(default)]
+    pub due_date: Option<String>,
+}
+
+/// The AI's account of the meeting
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingSummary {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    #[serde(default)]
+    pub action_items: Vec<ActionItem>,
+}
+
+/// The recording as stored in the notebook's assets
+#[derive(Debug, Clone)]
+pub struct MeetingAudio {
+    pub path: String,
+    pub filename: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn block(block_type: &str, data: serde_json::Value) -> EditorBlock {
+    EditorBlock {
+        id: Uuid::new_v4().to_string(),
+        block_type: block_type.to_string(),
+        data,
+    }
+}
+
+fn header(text: &str) -> EditorBlock {
+    block("header", serde_json::json!({ "text": text, "level": 2 }))
+}
+
+/// `m:ss`, or `h:mm:ss` past the first hour
+pub fn timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, (total % 3600) / 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+/// Attribute each transcript segment to the speaker whose turns overlap it
+/// most, and join consecutive segments of the same speaker. Without
+/// diarization the segments are joined into paragraphs instead.
+pub fn speaker_turns(
+    segments: &[TranscriptSegment],
+    diarization: &[SpeakerTurn],
+) -> Vec<TranscriptTurn> {
+    let mut labels: Vec<&str> = Vec::new();
+    let mut turns: Vec<TranscriptTurn> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let best = diarization
+            .iter()
+            .map(|turn| {
+                let overlap = segment.end.min(turn.end) - segment.start.max(turn.start);
+                (turn, overlap)
+            })
+            .filter(|(_, overlap)| *overlap > 0.0)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(turn, _)| turn.speaker.as_str());
+        let speaker = best.map(|label| {
+            let index = match labels.iter().position(|l| *l == label) {
+                Some(index) => index,
+                None => {
+                    labels.push(label);
+                    labels.len() - 1
+                }
+            };
+            format!("Speaker {}", index + 1)
+        });
+
+        match turns.last_mut() {
+            Some(last)
+                if last.speaker == speaker
+                    && (speaker.is_some() || last.text.len() < PARAGRAPH_CHARS) =>
+            {
+                last.text.push(' ');
+                last.text.push_str(text);
+                last.end = segment.end;
+            }
+            _ => turns.push(TranscriptTurn {
+                speaker,
+                start: segment.start,
+                end: segment.end,
+                text: text.to_string(),
+            }),
+        }
+    }
+    turns
+}
+
+/// The transcript as plain text, one turn per line
+pub fn transcript_text(turns: &[TranscriptTurn]) -> String {
+    turns
+        .iter()
+        .map(|turn| match &turn.speaker {
+            Some(speaker) => format!("[{}] {}: {}", timestamp(turn.start), speaker, turn.text),
+            None => format!("[{}] {}", timestamp(turn.start), turn.text),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The chat request summarizing `transcript`
+pub fn summary_messages(
+    transcript: &str,
+    attendees: &[String],
+    date: NaiveDate,
+) -> Vec<ChatMessage> {
+    let mut content: String = transcript.chars().take(MAX_TRANSCRIPT_CHARS).collect();
+    if content.len() < transcript.len() {
+        content.push_str("\n[transcript cut short]");
+    }
+    let attendees = if attendees.is_empty() {
+        "not given".to_string()
+    } else {
+        attendees.join(", ")
+    };
+    let system = format!(
+        "You write meeting notes from a transcript of a meeting held on {}. \
+         Speakers may be labelled \"Speaker 1\", \"Speaker 2\" and so on; the \
+         attendees were: {}. Reply with JSON only, in the form {{\"title\": \
+         \"<short title naming what the meeting was about>\", \"summary\": \
+         \"<one or two paragraphs>\", \"decisions\": [\"...\"], \
+         \"actionItems\": [{{\"text\": \"<what needs doing>\", \"assignee\": \
+         \"<attendee name, or null>\", \"dueDate\": \"<YYYY-MM-DD, or null>\"}}]}}. \
+         Only list decisions and action items the meeting actually agreed on, \
+         and work out relative deadlines (\"by Friday\") from the meeting date.",
+        date.format("%A, %Y-%m-%d"),
+        attendees
+    );
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: system,
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content,
+        },
+    ]
+}
+
+/// Parse the AI's reply. Empty action items are dropped, and due dates that
+/// aren't `YYYY-MM-DD` are cleared.
+pub fn parse_summary(reply: &str) -> Result<MeetingSummary, MeetingError> {
+    // Models wrap JSON in fences or add a sentence around it; take the
+    // outermost object
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err(MeetingError::BadReply("no JSON object".to_string())),
+    };
+    let mut summary: MeetingSummary =
+        serde_json::from_str(json).map_err(|e| MeetingError::BadReply(e.to_string()))?;
+
+    summary.title = summary.title.trim().to_string();
+    summary.decisions.retain(|d| !d.trim().is_empty());
+    summary
+        .action_items
+        .retain(|item| !item.text.trim().is_empty());
+    for item in &mut summary.action_items {
+        item.assignee = item.assignee.take().filter(|a| !a.trim().is_empty());
+        item.due_date = item
+            .due_date
+            .take()
+            .filter(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok());
+    }
+    Ok(summary)
+}
+
+fn action_item_text(item: &ActionItem) -> String {
+    let mut text = html_escape(item.text.trim());
+    if let Some(assignee) = &item.assignee {
+        text.push_str(&format!(" <i>({})</i>", html_escape(assignee)));
+    }
+    if let Some(due) = &item.due_date {
+        text.push_str(&format!(" <i>due {}</i>", due));
+    }
+    text
+}
+
+/// The meeting page: the recording, attendees, summary, decisions, action
+/// items as a checklist, and the transcript
+pub fn meeting_blocks(
+    summary: &MeetingSummary,
+    attendees: &[String],
+    audio: &MeetingAudio,
+    turns: &[TranscriptTurn],
+) -> Vec<EditorBlock> {
+    let mut blocks = vec![block(
+        "audio",
+        serde_json::json!({
+            "filename": audio.filename,
+            "url": audio.path,
+            "caption": "Recording",
+            "transcription": transcript_text(turns),
+            "transcriptionStatus": "done",
+            "showTranscript": false,
+            "recordedAt": audio.recorded_at.to_rfc3339(),
+        }),
+    )];
+
+    if !attendees.is_empty() {
+        blocks.push(header("Attendees"));
+        let items: Vec<String> = attendees.iter().map(|a| html_escape(a)).collect();
+        blocks.push(block(
+            "list",
+            serde_json::json!({ "style": "unordered", "items": items }),
+        ));
+    }
+
+    if !summary.summary.trim().is_empty() {
+        blocks.push(header("Summary"));
+        for paragraph in summary
+            .summary
+            .split("\n\n")
+            .filter(|p| !p.trim().is_empty())
+        {
+            blocks.push(block(
+                "paragraph",
+                serde_json::json!({ "text": html_escape(paragraph.trim()) }),
+            ));
+        }
+    }
+
+    if !summary.decisions.is_empty() {
+        blocks.push(header("Decisions"));
+        let items: Vec<String> = summary
+            .decisions
+            .iter()
+            .map(|d| html_escape(d.trim()))
+            .collect();
+        blocks.push(block(
+            "list",
+            serde_json::json!({ "style": "unordered", "items": items }),
+        ));
+    }
+
+    if !summary.action_items.is_empty() {
+        blocks.push(header("Action Items"));
+        let items: Vec<serde_json::Value> = summary
+            .action_items
+            .iter()
+            .map(|item| serde_json::json!({ "text": action_item_text(item), "checked": false }))
+            .collect();
+        blocks.push(block("checklist", serde_json::json!({ "items": items })));
+    }
+
+    if !turns.is_empty() {
+        blocks.push(header("Transcript"));
+        for turn in turns {
+            let stamp = format!("<i>[{}]</i>", timestamp(turn.start));
+            let text = match &turn.speaker {
+                Some(speaker) => format!(
+                    "{} <b>{}:</b> {}",
+                    stamp,
+                    html_escape(speaker),
+                    html_escape(&turn.text)
+                ),
+                None => format!("{} {}", stamp, html_escape(&turn.text)),
+            };
+            blocks.push(block("paragraph", serde_json::json!({ "text": text })));
+        }
+    }
+    blocks
+}
+
+fn header_level(block: &EditorBlock) -> Option<u64> {
+    if block.block_type != "header" {
+        return None;
+    }
+    Some(
+        block
+            .data
+            .get("level")
+            .and_then(|l| l.as_u64())
+            .unwrap_or(2),
+    )
+}
+
+/// Add a link to the meeting page at the end of the daily note's "Meetings"
+/// section, creating the section if missing. A note that already links to
+/// the page is returned unchanged.
+pub fn add_meeting_link(
+    mut blocks: Vec<EditorBlock>,
+    page_id: Uuid,
+    title: &str,
+) -> Vec<EditorBlock> {
+    let marker = format!("data-page-id=\"{}\"", page_id);
+    let linked = blocks.iter().any(|b| {
+        b.data
+            .get("text")
+            .and_then(|t| t.as_str())
+            .is_some_and(|t| t.contains(&marker))
+    });
+    if linked {
+        return blocks;
+    }
+
+    let escaped = html_escape(title);
+    let link = block(
+        "paragraph",
+        serde_json::json!({
+            "text": format!(
+                "<wiki-link data-page-title=\"{}\" data-page-id=\"{}\">{}</wiki-link>",
+                escaped, page_id, escaped
+            )
+        }),
+    );
+    let section = blocks.iter().position(|b| {
+        header_level(b).is_some()
+            && b.data
+                .get("text")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(MEETINGS_SECTION))
+    });
+    let Some(start) = section else {
+        blocks.push(header(MEETINGS_SECTION));
+        blocks.push(link);
+        return blocks;
+    };
+    let level = header_level(&blocks[start]).unwrap_or(2);
+    let end = blocks[start + 1..]
+        .iter()
+        .position(|b| header_level(b).is_some_and(|l| l <= level))
+        .map_or(blocks.len(), |i| start + 1 + i);
+    blocks.insert(end, link);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f64, end: f64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            id: 0,
+            start,
+            end,
+            text: text.to_string(),
+            words: Vec::new(),
+        }
+    }
+
+    fn turn(start: f64, end: f64, speaker: &str) -> SpeakerTurn {
+        SpeakerTurn {
+            start,
+            end,
+            speaker: speaker.to_string(),
+        }
+    }
+
+    #[test]
+    fn segments_go_to_the_most_overlapping_speaker() {
+        let segments = [
+            segment(0.0, 4.0, "Shall we start?"),
+            segment(4.0, 8.0, "Yes."),
+            segment(8.0, 12.0, "The budget is done."),
+            segment(12.0, 15.0, "Great."),
+        ];
+        let diarization = [
+            turn(0.0, 4.5, "SPEAKER_03"),
+            turn(4.5, 12.5, "SPEAKER_00"),
+            turn(12.5, 15.0, "SPEAKER_03"),
+        ];
+        let turns = speaker_turns(&segments, &diarization);
+
+        let summary: Vec<(Option<&str>, &str)> = turns
+            .iter()
+            .map(|t| (t.speaker.as_deref(), t.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("Speaker 1"), "Shall we start?"),
+                (Some("Speaker 2"), "Yes. The budget is done."),
+                (Some("Speaker 1"), "Great."),
+            ]
+        );
+        assert_eq!(turns[1].end, 12.0);
+        assert_eq!(
+            transcript_text(&turns[..1]),
+            "[0:00] Speaker 1: Shall we start?"
+        );
+    }
+
+    #[test]
+    fn without_diarization_segments_become_paragraphs() {
+        let long = "word ".repeat(200);
+        let segments = [
+            segment(0.0, 60.0, &long),
+            segment(60.0, 70.0, "next"),
+            segment(3700.0, 3705.0, ""),
+        ];
+        let turns = speaker_turns(&segments, &[]);
+        assert_eq!(turns.len(), 2);
+        assert!(turns.iter().all(|t| t.speaker.is_none()));
+        assert_eq!(timestamp(3725.0), "1:02:05");
+    }
+
+    #[test]
+    fn reply_parsing_cleans_up_the_summary() {
+        let reply = "```json\n{\"title\": \" Budget review \", \"summary\": \"We met.\", \
+            \"decisions\": [\"Ship it\", \"\"], \"actionItems\": [\
+            {\"text\": \"Send the report\", \"assignee\": \"Ana\", \"dueDate\": \"2026-10-20\"}, \
+            {\"text\": \"Book a room\", \"assignee\": \"\", \"dueDate\": \"next week\"}, \
+            {\"text\": \" \"}]}\n```";
+        let summary = parse_summary(reply).unwrap();
+        assert_eq!(summary.title, "Budget review");
+        assert_eq!(summary.decisions, vec!["Ship it"]);
+        assert_eq!(summary.action_items.len(), 2);
+        assert_eq!(
+            summary.action_items[0].due_date.as_deref(),
+            Some("2026-10-20")
+        );
+        assert_eq!(summary.action_items[1].assignee, None);
+        assert_eq!(summary.action_items[1].due_date, None);
+
+        assert!(matches!(
+            parse_summary("I can't help with that."),
+            Err(MeetingError::BadReply(_))
+        ));
+    }
+
+    #[test]
+    fn meeting_page_has_each_section() {
+        let summary = MeetingSummary {
+            title: "Budget".to_string(),
+            summary: "First.\n\nSecond.".to_string(),
+            decisions: vec!["Ship <it>".to_string()],
+            action_items: vec![ActionItem {
+                text: "Send report".to_string(),
+                assignee: Some("Ana".to_string()),
+                due_date: None,
+            }],
+        };
+        let audio = MeetingAudio {
+            path: "/notebook/assets/audio/meeting.m4a".to_string(),
+            filename: "meeting.m4a".to_string(),
+            recorded_at: Utc::now(),
+        };
+        let turns = [TranscriptTurn {
+            speaker: Some("Speaker 1".to_string()),
+            start: 1.0,
+            end: 2.0,
+            text: "Hello".to_string(),
+        }];
+        let blocks = meeting_blocks(&summary, &["Ana".to_string()], &audio, &turns);
+
+        let types: Vec<&str> = blocks.iter().map(|b| b.block_type.as_str()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "audio",
+                "header",
+                "list",
+                "header",
+                "paragraph",
+                "paragraph",
+                "header",
+                "list",
+                "header",
+                "checklist",
+                "header",
+                "paragraph"
+            ]
+        );
+        assert_eq!(blocks[7].data["items"][0], "Ship &lt;it&gt;");
+        assert_eq!(
+            blocks[9].data["items"][0]["text"],
+            "Send report <i>(Ana)</i>"
+        );
+        assert_eq!(
+            blocks[11].data["text"],
+            "<i>[0:01]</i> <b>Speaker 1:</b> Hello"
+        );
+    }
+
+    #[test]
+    fn daily_note_links_each_meeting_once() {
+        let page_id = Uuid::new_v4();
+        let blocks = vec![
+            header(MEETINGS_SECTION),
+            block("paragraph", serde_json::json!({ "text": "Standup" })),
+            header("Notes"),
+        ];
+        let blocks = add_meeting_link(blocks, page_id, "Budget");
+        assert_eq!(blocks.len(), 4);
+        assert!(blocks[2].data["text"]
+            .as_str()
+            .unwrap()
+            .contains(&page_id.to_string()));
+
+        let again = add_meeting_link(blocks, page_id, "Budget");
+        assert_eq!(again.len(), 4);
+
+        let fresh = add_meeting_link(Vec::new(), page_id, "Budget");
+        assert_eq!(fresh.len(), 2);
+        assert_eq!(fresh[0].data["text"], MEETINGS_SECTION);
+    }
+}
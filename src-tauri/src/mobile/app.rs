@@ -0,0 +1,286 @@
+//! Tauri app for the mobile build: state, commands and the share-sheet
+//! deep link handler
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{Local, NaiveDate};
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
+
+use crate::commands::CommandError;
+use crate::deep_link::{self, DeepLink, PendingDeepLinks};
+use crate::inbox::{InboxItem, InboxStorage};
+use crate::library::LibraryStorage;
+use crate::storage::{FileStorage, FileStorageWriter, StorageLock};
+use crate::sync::{
+    LibrarySyncConfigInput, RemoteNotebook, RemoteNotebookListing, RemotePage, SyncManager,
+    TauriEmitter,
+};
+
+type CommandResult<T> = Result<T, CommandError>;
+
+const PLATFORM: &str = if cfg!(target_os = "ios") {
+    "ios"
+} else {
+    "android"
+};
+
+fn command_error(e: impl std::fmt::Display) -> CommandError {
+    CommandError {
+        message: e.to_string(),
+    }
+}
+
+fn parse_uuid(s: &str) -> CommandResult<Uuid> {
+    Uuid::parse_str(s).map_err(|e| command_error(format!("Invalid ID '{}': {}", s, e)))
+}
+
+/// What the mobile app keeps: the library, its inbox and a sync manager
+#[derive(Clone)]
+pub struct MobileState {
+    library_id: Uuid,
+    library_storage: Arc<Mutex<LibraryStorage>>,
+    /// Never holds notebooks; browsing uses it to flag local copies
+    storage: Arc<StorageLock>,
+    inbox_storage: Arc<Mutex<InboxStorage>>,
+    sync_manager: Arc<SyncManager>,
+}
+
+impl MobileState {
+    fn new(data_dir: std::path::PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
+        let library_storage = LibraryStorage::new(data_dir.clone());
+        let library = library_storage.init()?;
+        let storage = FileStorageWriter::new(FileStorage::new(library.path.clone()));
+        storage.init()?;
+        let inbox_storage = InboxStorage::new(library.path.clone())?;
+
+        let sync_manager = SyncManager::new(data_dir.clone());
+        sync_manager.set_secrets(crate::secrets::shared(&data_dir));
+
+        Ok(Self {
+            library_id: library.id,
+            library_storage: Arc::new(Mutex::new(library_storage)),
+            storage: Arc::new(StorageLock::new(storage)),
+            inbox_storage: Arc::new(Mutex::new(inbox_storage)),
+            sync_manager: Arc::new(sync_manager),
+        })
+    }
+
+    fn save(&self, item: &InboxItem) -> CommandResult<()> {
+        self.inbox_storage.lock().unwrap().save_item(item)?;
+        self.sync_in_background();
+        Ok(())
+    }
+
+    async fn sync_inbox(&self) -> CommandResult<bool> {
+        self.sync_manager
+            .sync_library_inbox(self.library_id, &self.library_storage, &self.inbox_storage)
+            .await
+            .map_err(command_error)
+    }
+
+    /// Push a capture right away; offline, it waits for the next sync
+    fn sync_in_background(&self) {
+        let state = self.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = state.sync_inbox().await {
+                log::info!("Inbox sync deferred: {}", e.message);
+            }
+        });
+    }
+}
+
+/// Capture shared text or a link to the inbox. The share extension opens
+/// `nous://capture?...`; other links go to the frontend as on desktop.
+fn on_deep_link(app: &AppHandle, url: &str) {
+    match deep_link::parse_deep_link(url) {
+        Ok(DeepLink::Capture {
+            title,
+            text,
+            url,
+            tags,
+        }) => {
+            let state = app.state::<MobileState>();
+            let request = super::share_capture(PLATFORM, title, &text, url, tags);
+            let captured = state.inbox_storage.lock().unwrap().capture(request);
+            match captured {
+                Ok(item) => {
+                    state.sync_in_background();
+                    let _ = app.emit("inbox-captured", &item);
+                }
+                Err(e) => log::error!("Share capture failed: {}", e),
+            }
+        }
+        _ => deep_link::dispatch(app, url),
+    }
+}
+
+/// Connect the library to a sync server. Only the inbox is synced; pages
+/// are read from the server when opened.
+#[tauri::command]
+pub async fn mobile_configure_sync(
+    state: State<'_, MobileState>,
+    config: LibrarySyncConfigInput,
+) -> CommandResult<()> {
+    state
+        .sync_manager
+        .configure_library_sync(
+            state.library_id,
+            &state.library_storage,
+            &state.storage,
+            config,
+        )
+        .await
+        .map_err(command_error)
+}
+
+/// Capture a note to the inbox
+#[tauri::command]
+pub fn mobile_capture(
+    state: State<'_, MobileState>,
+    title: Option<String>,
+    text: String,
+    url: Option<String>,
+    tags: Option<Vec<String>>,
+) -> CommandResult<InboxItem> {
+    let request = super::share_capture(PLATFORM, title, &text, url, tags.unwrap_or_default());
+    let item = state.inbox_storage.lock().unwrap().capture(request)?;
+    state.sync_in_background();
+    Ok(item)
+}
+
+/// Queue Markdown for a notebook's daily note (today unless `date` is
+/// given). A desktop applies it on its next sync.
+#[tauri::command]
+pub fn mobile_append_daily_note(
+    state: State<'_, MobileState>,
+    notebook_id: String,
+    notebook_name: String,
+    text: String,
+    date: Option<String>,
+) -> CommandResult<InboxItem> {
+    if text.trim().is_empty() {
+        return Err(command_error("Nothing to add"));
+    }
+    let date = match date {
+        Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .map_err(|_| command_error(format!("Invalid date '{}'", date)))?,
+        None => Local::now().date_naive(),
+    };
+    let item = super::daily_append_item(
+        PLATFORM,
+        parse_uuid(&notebook_id)?,
+        &notebook_name,
+        date,
+        &text,
+    );
+    state.save(&item)?;
+    Ok(item)
+}
+
+/// Captures and appends no desktop has processed yet
+#[tauri::command]
+pub fn mobile_pending_items(state: State<'_, MobileState>) -> CommandResult<Vec<InboxItem>> {
+    Ok(state.inbox_storage.lock().unwrap().list_unprocessed()?)
+}
+
+/// Sync the inbox now. Returns whether anything came back from the server.
+#[tauri::command]
+pub async fn mobile_sync(state: State<'_, MobileState>) -> CommandResult<bool> {
+    state.sync_inbox().await
+}
+
+/// Notebooks on the sync server
+#[tauri::command]
+pub async fn mobile_list_notebooks(
+    state: State<'_, MobileState>,
+) -> CommandResult<Vec<RemoteNotebook>> {
+    state
+        .sync_manager
+        .browse_remote_library(state.library_id, &state.library_storage, &state.storage)
+        .await
+        .map_err(command_error)
+}
+
+/// Pages, folders and sections of a notebook on the sync server
+#[tauri::command]
+pub async fn mobile_list_pages(
+    state: State<'_, MobileState>,
+    notebook_id: String,
+) -> CommandResult<RemoteNotebookListing> {
+    state
+        .sync_manager
+        .browse_remote_notebook(
+            state.library_id,
+            parse_uuid(&notebook_id)?,
+            &state.library_storage,
+            &state.storage,
+        )
+        .await
+        .map_err(command_error)
+}
+
+/// A page as the sync server has it, read-only
+#[tauri::command]
+pub async fn mobile_get_page(
+    state: State<'_, MobileState>,
+    notebook_id: String,
+    page_id: String,
+) -> CommandResult<RemotePage> {
+    state
+        .sync_manager
+        .fetch_remote_page(
+            state.library_id,
+            parse_uuid(&notebook_id)?,
+            parse_uuid(&page_id)?,
+            &state.library_storage,
+        )
+        .await
+        .map_err(command_error)
+}
+
+pub fn run() {
+    tauri::Builder::default()
+        .manage(PendingDeepLinks::default())
+        .plugin(tauri_plugin_deep_link::init())
+        .setup(|app| {
+            // The app sandbox's data directory; `dirs` doesn't know it
+            let data_dir = app.path().app_data_dir()?;
+            std::fs::create_dir_all(&data_dir)?;
+            let state = MobileState::new(data_dir)?;
+            state
+                .sync_manager
+                .set_emitter(Arc::new(TauriEmitter::new(app.handle().clone())));
+            app.manage(state);
+
+            use tauri_plugin_deep_link::DeepLinkExt;
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    on_deep_link(app.handle(), url.as_str());
+                }
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    on_deep_link(&handle, url.as_str());
+                }
+            });
+
+            // Pull what desktops marked processed
+            app.state::<MobileState>().sync_in_background();
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            mobile_configure_sync,
+            mobile_capture,
+            mobile_append_daily_note,
+            mobile_pending_items,
+            mobile_sync,
+            mobile_list_notebooks,
+            mobile_list_pages,
+            mobile_get_page,
+            deep_link::take_pending_deep_links,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running mobile app");
+}
@@ -0,0 +1,227 @@
+//! Mobile companion (iOS/Android)
+//!
+//! The mobile build is a capture-and-read client. It keeps no notebooks,
+//! search index or Python bridge; it has:
+//! - capture to the library inbox, from the app or the OS share sheet
+//!   (which opens `nous://capture?...`)
+//! - daily note appends
+//! - read-only page viewing straight from the sync server
+//!   (`SyncManager::browse_remote_*`, `fetch_remote_page`)
+//!
+//! Only the inbox syncs (`SyncManager::sync_library_inbox`). Daily note
+//! appends can't be written without the notebook, so they travel as inbox
+//! items classified `AppendToDailyNote`. The next desktop library sync that
+//! has the notebook applies them with [`apply_daily_appends`], and the page
+//! is indexed and pushed there like any other edit.
+
+#[cfg(mobile)]
+mod app;
+
+#[cfg(mobile)]
+pub use app::run;
+
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::commands::{create_daily_note_core, format_daily_note_title};
+use crate::inbox::{
+    CaptureRequest, CaptureSource, ClassificationAction, InboxClassification, InboxItem,
+    InboxStorage,
+};
+use crate::markdown::parse_markdown_to_blocks;
+use crate::storage::{FileStorageWriter, Page, StorageError};
+
+/// Longest title taken from the first line of shared text
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Build the inbox capture for something shared to the app. Without a
+/// title, the first line of the text (or the URL) is used. The URL is added
+/// to the content unless the text already has it.
+pub fn share_capture(
+    platform: &str,
+    title: Option<String>,
+    text: &str,
+    url: Option<String>,
+    tags: Vec<String>,
+) -> CaptureRequest {
+    let text = text.trim();
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| {
+            text.lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(|l| l.chars().take(MAX_TITLE_CHARS).collect())
+        })
+        .or_else(|| url.clone())
+        .unwrap_or_else(|| "Shared note".to_string());
+
+    let content = match &url {
+        Some(url) if !text.contains(url.as_str()) => {
+            if text.is_empty() {
+                url.clone()
+            } else {
+                format!("{}\n\n{}", text, url)
+            }
+        }
+        _ => text.to_string(),
+    };
+
+    CaptureRequest {
+        title,
+        content,
+        tags: Some(tags),
+        source: Some(CaptureSource::Mobile {
+            platform: platform.to_string(),
+            url,
+        }),
+        auto_classify: Some(false),
+    }
+}
+
+/// An inbox item that appends `text` (Markdown) to a notebook's daily note
+/// once a desktop applies it
+pub fn daily_append_item(
+    platform: &str,
+    notebook_id: Uuid,
+    notebook_name: &str,
+    date: NaiveDate,
+    text: &str,
+) -> InboxItem {
+    let date = date.format("%Y-%m-%d").to_string();
+    let mut item = InboxItem::new(format_daily_note_title(&date), text.trim().to_string())
+        .with_source(CaptureSource::Mobile {
+            platform: platform.to_string(),
+            url: None,
+        });
+    item.classification = Some(InboxClassification {
+        action: ClassificationAction::AppendToDailyNote {
+            notebook_id,
+            notebook_name: notebook_name.to_string(),
+            date,
+        },
+        confidence: 1.0,
+        reasoning: "Added to the daily note from the mobile app".to_string(),
+        classified_at: Utc::now(),
+    });
+    item
+}
+
+/// Apply the pending daily note appends whose notebook is in `storage`,
+/// oldest first, creating daily notes as needed. Applied items are marked
+/// processed; the rest stay queued for a device that has their notebook.
+/// Returns the pages that changed.
+pub fn apply_daily_appends(
+    storage: &FileStorageWriter,
+    inbox: &InboxStorage,
+) -> Result<Vec<Page>, StorageError> {
+    let mut pending: Vec<(InboxItem, Uuid, String)> = inbox
+        .list_unprocessed()?
+        .into_iter()
+        .filter_map(|item| match &item.classification {
+            Some(InboxClassification {
+                action:
+                    ClassificationAction::AppendToDailyNote {
+                        notebook_id, date, ..
+                    },
+                ..
+            }) => {
+                let (notebook_id, date) = (*notebook_id, date.clone());
+                Some((item, notebook_id, date))
+            }
+            _ => None,
+        })
+        .filter(|(_, notebook_id, _)| storage.get_notebook(*notebook_id).is_ok())
+        .collect();
+    pending.sort_by_key(|(item, _, _)| item.captured_at);
+
+    let mut changed: HashMap<Uuid, Page> = HashMap::new();
+    for (item, notebook_id, date) in pending {
+        // create_daily_note_core returns the note if it exists already
+        let mut page = match create_daily_note_core(storage, notebook_id, &date, None) {
+            Ok(page) => page,
+            Err(e) => {
+                log::warn!("Mobile daily note append {}: {}", item.id, e.message);
+                continue;
+            }
+        };
+        page.content
+            .blocks
+            .extend(parse_markdown_to_blocks(&item.content));
+        page.updated_at = Utc::now();
+        storage.update_page(&page)?;
+        inbox.mark_processed(item.id)?;
+        changed.insert(page.id, page);
+    }
+
+    Ok(changed.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{FileStorage, NotebookType};
+
+    #[test]
+    fn share_capture_titles_from_text_and_keeps_the_url() {
+        let request = share_capture(
+            "ios",
+            None,
+            "  Great read on CRDTs\nmore text ",
+            Some("https://example.com/crdt".to_string()),
+            vec!["reading".to_string()],
+        );
+        assert_eq!(request.title, "Great read on CRDTs");
+        assert_eq!(
+            request.content,
+            "Great read on CRDTs\nmore text\n\nhttps://example.com/crdt"
+        );
+        assert!(matches!(
+            request.source,
+            Some(CaptureSource::Mobile { ref platform, url: Some(_) }) if platform == "ios"
+        ));
+
+        let request = share_capture(
+            "android",
+            None,
+            "",
+            Some("https://example.com".into()),
+            vec![],
+        );
+        assert_eq!(request.title, "https://example.com");
+        assert_eq!(request.content, "https://example.com");
+    }
+
+    #[test]
+    fn daily_appends_wait_for_a_device_with_the_notebook() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let inbox = InboxStorage::new(dir.path().to_path_buf()).unwrap();
+        let notebook = storage
+            .create_notebook("Journal".to_string(), NotebookType::Standard)
+            .unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        let elsewhere = daily_append_item("ios", Uuid::new_v4(), "Elsewhere", date, "x");
+        inbox.save_item(&elsewhere).unwrap();
+        for text in ["- Shipped the release", "Called the bank"] {
+            let item = daily_append_item("ios", notebook.id, "Journal", date, text);
+            inbox.save_item(&item).unwrap();
+        }
+
+        let pages = apply_daily_appends(&storage, &inbox).unwrap();
+        assert_eq!(pages.len(), 1);
+        let page = storage.get_page(notebook.id, pages[0].id).unwrap();
+        assert_eq!(page.daily_note_date.as_deref(), Some("2024-01-15"));
+        assert_eq!(page.content.blocks.len(), 2);
+
+        let pending = inbox.list_unprocessed().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, elsewhere.id);
+        assert!(apply_daily_appends(&storage, &inbox).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,538 @@
+//! Notification scheduling and delivery
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use uuid::Uuid;
+
+use super::models::*;
+use crate::actions::{Action, ActionTrigger};
+use crate::goals::Goal;
+use crate::inbox::InboxItem;
+use crate::reminders::PageReminder;
+use crate::AppState;
+
+const PREFERENCES_FILE: &str = "notifications.json";
+const LOG_FILE: &str = "notifications_log.json";
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long delivered IDs are remembered. Everything we schedule is keyed
+/// by day at most, so two days is enough to never repeat one.
+fn retention() -> chrono::Duration {
+    chrono::Duration::days(2)
+}
+
+/// `HH:MM` local time on `day`, in UTC
+fn local_time_on(day: NaiveDate, time: &str) -> Option<DateTime<Utc>> {
+    let time = NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()?;
+    Local
+        .from_local_datetime(&day.and_time(time))
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// One reminder a day while cards are due
+pub fn flashcard_reminder(
+    due_cards: usize,
+    reminder_time: &str,
+    today: NaiveDate,
+) -> Option<PendingNotification> {
+    if due_cards == 0 {
+        return None;
+    }
+    Some(PendingNotification {
+        id: format!("flashcards:{}", today),
+        category: NotificationCategory::FlashcardsDue,
+        title: "Flashcards due".to_string(),
+        body: if due_cards == 1 {
+            "1 card is ready for review".to_string()
+        } else {
+            format!("{} cards are ready for review", due_cards)
+        },
+        due_at: local_time_on(today, reminder_time)?,
+    })
+}
+
+/// Check-ins for active goals with a reminder that aren't done today
+pub fn goal_check_ins(
+    goals: &[Goal],
+    done_today: &HashSet<Uuid>,
+    today: NaiveDate,
+) -> Vec<PendingNotification> {
+    goals
+        .iter()
+        .filter(|goal| !goal.is_archived() && !done_today.contains(&goal.id))
+        .filter_map(|goal| {
+            let reminder = goal.reminder.as_ref().filter(|r| r.enabled)?;
+            Some(PendingNotification {
+                id: format!("goal:{}:{}", goal.id, today),
+                category: NotificationCategory::GoalCheckIn,
+                title: format!("Goal check-in: {}", goal.name),
+                body: goal
+                    .description
+                    .clone()
+                    .filter(|d| !d.trim().is_empty())
+                    .unwrap_or_else(|| "Not done yet today".to_string()),
+                due_at: local_time_on(today, &reminder.time)?,
+            })
+        })
+        .collect()
+}
+
+/// Scheduled actions that ran after `since`
+pub fn action_completions(actions: &[Action], since: DateTime<Utc>) -> Vec<PendingNotification> {
+    actions
+        .iter()
+        .filter(|action| {
+            action
+                .triggers
+                .iter()
+                .any(|t| matches!(t, ActionTrigger::Scheduled { .. }))
+        })
+        .filter_map(|action| {
+            let last_run = action.last_run.filter(|run| *run > since)?;
+            Some(PendingNotification {
+                id: format!("action:{}:{}", action.id, last_run.timestamp()),
+                category: NotificationCategory::ActionCompleted,
+                title: format!("{} finished", action.name),
+                body: format!("Ran at {}", last_run.with_timezone(&Local).format("%H:%M")),
+                due_at: last_run,
+            })
+        })
+        .collect()
+}
+
+const REMINDER_PREFIX: &str = "reminder:";
+
+/// Page reminders that haven't fired yet
+pub fn page_reminders(reminders: &[PageReminder]) -> Vec<PendingNotification> {
+    reminders
+        .iter()
+        .filter(|r| r.is_pending())
+        .map(|r| PendingNotification {
+            id: format!("{}{}", REMINDER_PREFIX, r.id),
+            category: NotificationCategory::PageReminder,
+            title: if r.resurface {
+                format!("Resurfaced: {}", r.page_title)
+            } else {
+                r.page_title.clone()
+            },
+            body: r.note.clone().unwrap_or_else(|| {
+                if r.resurface {
+                    "A note you snoozed is back".to_string()
+                } else {
+                    "Reminder".to_string()
+                }
+            }),
+            due_at: r.remind_at,
+        })
+        .collect()
+}
+
+const SNOOZE_PREFIX: &str = "snooze:";
+
+/// Unprocessed inbox items whose snooze ends. Keyed by the end time, so
+/// snoozing the same item again notifies again.
+pub fn snooze_endings(items: &[InboxItem]) -> Vec<PendingNotification> {
+    items
+        .iter()
+        .filter(|item| !item.is_processed)
+        .filter_map(|item| {
+            let until = item.snoozed_until?;
+            Some(PendingNotification {
+                id: format!("{}{}:{}", SNOOZE_PREFIX, item.id, until.timestamp()),
+                category: NotificationCategory::SnoozeEnded,
+                title: format!("Back in your inbox: {}", item.title),
+                body: item.content.chars().take(200).collect(),
+                due_at: until,
+            })
+        })
+        .collect()
+}
+
+/// At most one sync error notification an hour
+pub fn sync_error(message: &str, now: DateTime<Utc>) -> PendingNotification {
+    PendingNotification {
+        id: format!("sync-error:{}", now.format("%Y-%m-%dT%H")),
+        category: NotificationCategory::SyncError,
+        title: "Sync failed".to_string(),
+        body: message.chars().take(200).collect(),
+        due_at: now,
+    }
+}
+
+/// What has been shown, persisted so restarts don't repeat reminders
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeliveryLog {
+    delivered: HashMap<String, DateTime<Utc>>,
+}
+
+fn load_json<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> T {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Collects reminders from flashcards, goals, actions and sync, and shows
+/// them as OS notifications when they fall due. Managed as Tauri state.
+pub struct NotificationCenter {
+    data_dir: PathBuf,
+    preferences: Mutex<NotificationPreferences>,
+    log: Mutex<DeliveryLog>,
+    /// Event-driven notifications (sync errors) waiting for the next tick
+    queued: Mutex<Vec<PendingNotification>>,
+    started_at: DateTime<Utc>,
+}
+
+impl NotificationCenter {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let preferences = load_json(&data_dir.join(PREFERENCES_FILE));
+        let log = load_json(&data_dir.join(LOG_FILE));
+        Self {
+            data_dir,
+            preferences: Mutex::new(preferences),
+            log: Mutex::new(log),
+            queued: Mutex::new(Vec::new()),
+            started_at: Utc::now(),
+        }
+    }
+
+    pub fn preferences(&self) -> NotificationPreferences {
+        self.preferences.lock().unwrap().clone()
+    }
+
+    pub fn set_preferences(&self, preferences: NotificationPreferences) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&preferences).map_err(|e| e.to_string())?;
+        std::fs::write(self.data_dir.join(PREFERENCES_FILE), content).map_err(|e| e.to_string())?;
+        *self.preferences.lock().unwrap() = preferences;
+        Ok(())
+    }
+
+    pub fn queue(&self, notification: PendingNotification) {
+        if !self.preferences().allows(notification.category) {
+            return;
+        }
+        let mut queued = self.queued.lock().unwrap();
+        if !queued.iter().any(|n| n.id == notification.id) {
+            queued.push(notification);
+        }
+    }
+
+    /// Everything scheduled for today that hasn't been shown, oldest first
+    pub fn pending(&self, state: &AppState) -> Vec<PendingNotification> {
+        let preferences = self.preferences();
+        if !preferences.enabled {
+            return Vec::new();
+        }
+        let now = Utc::now();
+        let today = Local::now().date_naive();
+        // Timed items up to the end of today, including ones missed while
+        // the app was closed
+        let end_of_today = (today + chrono::Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| Local.from_local_datetime(&t).earliest())
+            .map_or(now, |t| t.with_timezone(&Utc));
+        let mut pending = self.queued.lock().unwrap().clone();
+
+        if preferences.flashcards_due {
+            pending.extend(flashcard_reminder(
+                due_flashcards(state),
+                &preferences.flashcard_reminder_time,
+                today,
+            ));
+        }
+        if preferences.goal_check_ins {
+            let goals = state.goals_storage.lock().unwrap();
+            let active = goals.list_active_goals().unwrap_or_default();
+            let done_today: HashSet<Uuid> = active
+                .iter()
+                .filter(|goal| {
+                    goals
+                        .get_progress_range(goal.id, today, today)
+                        .unwrap_or_default()
+                        .iter()
+                        .any(|p| p.completed)
+                })
+                .map(|goal| goal.id)
+                .collect();
+            pending.extend(goal_check_ins(&active, &done_today, today));
+        }
+        if preferences.page_reminders {
+            let reminders = state
+                .reminder_storage
+                .lock()
+                .unwrap()
+                .list_due(end_of_today)
+                .unwrap_or_default();
+            pending.extend(page_reminders(&reminders));
+        }
+        if preferences.snoozed_items {
+            let snoozed: Vec<InboxItem> = state
+                .inbox_storage
+                .lock()
+                .unwrap()
+                .list_snoozed()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|item| item.snoozed_until.is_some_and(|t| t <= end_of_today))
+                .collect();
+            pending.extend(snooze_endings(&snoozed));
+        }
+        if preferences.action_completions {
+            let actions = state
+                .action_storage
+                .lock()
+                .unwrap()
+                .list_actions()
+                .unwrap_or_default();
+            // Runs from before startup were the user's to see already
+            let since = self.started_at.max(now - retention());
+            pending.extend(action_completions(&actions, since));
+        }
+
+        let log = self.log.lock().unwrap();
+        pending.retain(|n| preferences.allows(n.category) && !log.delivered.contains_key(&n.id));
+        pending.sort_by_key(|n| n.due_at);
+        pending
+    }
+
+    /// Show whatever has fallen due
+    pub fn tick(&self, app: &AppHandle) {
+        let now = Utc::now();
+        let due: Vec<_> = self
+            .pending(&app.state::<AppState>())
+            .into_iter()
+            .filter(|n| n.due_at <= now)
+            .collect();
+
+        let mut delivery_log = self.log.lock().unwrap();
+        let before = delivery_log.delivered.len();
+        delivery_log
+            .delivered
+            .retain(|_, shown| now - *shown < retention());
+        if due.is_empty() && delivery_log.delivered.len() == before {
+            return;
+        }
+
+        for notification in &due {
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title(&notification.title)
+                .body(&notification.body)
+                .show()
+            {
+                log::warn!("Failed to show notification '{}': {}", notification.id, e);
+            }
+            delivery_log.delivered.insert(notification.id.clone(), now);
+            let _ = app.emit("notification-shown", notification);
+        }
+        // Fired page reminders stay fired even once the log forgets them
+        let fired = due
+            .iter()
+            .filter_map(|n| n.id.strip_prefix(REMINDER_PREFIX))
+            .filter_map(|id| Uuid::parse_str(id).ok());
+        let state = app.state::<AppState>();
+        let reminders = state.reminder_storage.lock().unwrap();
+        for id in fired {
+            if let Err(e) = reminders.mark_fired(id) {
+                log::warn!("Failed to mark reminder {} fired: {}", id, e);
+            }
+        }
+        drop(reminders);
+
+        // Resurfaced inbox items lose their snooze
+        let resurfaced = due
+            .iter()
+            .filter_map(|n| n.id.strip_prefix(SNOOZE_PREFIX))
+            .filter_map(|rest| rest.split_once(':'))
+            .filter_map(|(id, _)| Uuid::parse_str(id).ok());
+        let inbox = state.inbox_storage.lock().unwrap();
+        for id in resurfaced {
+            if let Err(e) = inbox.snooze_item(id, None) {
+                log::warn!("Failed to unsnooze inbox item {}: {}", id, e);
+            }
+        }
+        drop(inbox);
+
+        let ids: HashSet<&str> = due.iter().map(|n| n.id.as_str()).collect();
+        self.queued
+            .lock()
+            .unwrap()
+            .retain(|n| !ids.contains(n.id.as_str()));
+
+        match serde_json::to_string(&*delivery_log) {
+            Ok(content) => {
+                if let Err(e) = std::fs::write(self.data_dir.join(LOG_FILE), content) {
+                    log::warn!("Failed to save notification log: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to save notification log: {}", e),
+        }
+    }
+}
+
+/// Cards due across the current library's notebooks
+fn due_flashcards(state: &AppState) -> usize {
+    let notebooks = state
+        .storage
+        .read()
+        .unwrap()
+        .list_notebooks()
+        .unwrap_or_default();
+    let flashcards = state.flashcard_storage.lock().unwrap();
+    notebooks
+        .iter()
+        .filter(|nb| !nb.archived && !nb.is_encrypted())
+        .map(|nb| {
+            flashcards
+                .get_due_cards(nb.id, None)
+                .map(|cards| cards.len())
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Manage the center and start the delivery loop; called once from `setup`
+pub fn init(app: &AppHandle, data_dir: PathBuf) {
+    app.manage(NotificationCenter::new(data_dir));
+    let handle = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TICK_INTERVAL);
+        handle.state::<NotificationCenter>().tick(&handle);
+    });
+}
+
+/// Queue a sync error notification; called from the sync event emitter
+pub fn notify_sync_error(app: &AppHandle, message: &str) {
+    if let Some(center) = app.try_state::<NotificationCenter>() {
+        center.queue(sync_error(message, Utc::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::Schedule;
+    use crate::goals::{Frequency, ReminderConfig};
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 14).unwrap()
+    }
+
+    #[test]
+    fn flashcard_reminder_only_when_cards_are_due() {
+        assert!(flashcard_reminder(0, "09:00", day()).is_none());
+        assert!(flashcard_reminder(3, "9am", day()).is_none());
+
+        let reminder = flashcard_reminder(3, "09:30", day()).unwrap();
+        assert_eq!(reminder.id, "flashcards:2025-03-14");
+        assert_eq!(reminder.body, "3 cards are ready for review");
+        let local = reminder.due_at.with_timezone(&Local);
+        assert_eq!(
+            local.format("%Y-%m-%d %H:%M").to_string(),
+            "2025-03-14 09:30"
+        );
+    }
+
+    #[test]
+    fn goal_check_ins_skip_done_and_unreminded_goals() {
+        let reminded = Goal::new_manual("Write".to_string(), Frequency::Daily)
+            .with_reminder("20:00".to_string());
+        let done = Goal::new_manual("Run".to_string(), Frequency::Daily)
+            .with_reminder("07:00".to_string());
+        let mut disabled = Goal::new_manual("Read".to_string(), Frequency::Daily);
+        disabled.reminder = Some(ReminderConfig {
+            enabled: false,
+            time: "08:00".to_string(),
+        });
+        let quiet = Goal::new_manual("Stretch".to_string(), Frequency::Daily);
+
+        let done_today = HashSet::from([done.id]);
+        let goals = [reminded.clone(), done, disabled, quiet];
+        let check_ins = goal_check_ins(&goals, &done_today, day());
+        assert_eq!(check_ins.len(), 1);
+        assert_eq!(check_ins[0].id, format!("goal:{}:2025-03-14", reminded.id));
+        assert_eq!(check_ins[0].title, "Goal check-in: Write");
+    }
+
+    #[test]
+    fn only_new_scheduled_runs_are_reported() {
+        let since = Utc::now();
+        let mut scheduled = Action::new("Daily digest".to_string(), String::new());
+        scheduled.triggers = vec![ActionTrigger::Scheduled {
+            schedule: Schedule::Daily {
+                time: "08:00".to_string(),
+                skip_weekends: false,
+            },
+        }];
+        scheduled.last_run = Some(since + chrono::Duration::minutes(5));
+        let mut stale = scheduled.clone();
+        stale.last_run = Some(since - chrono::Duration::minutes(5));
+        let mut manual = Action::new("Manual".to_string(), String::new());
+        manual.last_run = scheduled.last_run;
+
+        let completions = action_completions(&[scheduled, stale, manual], since);
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].title, "Daily digest finished");
+    }
+
+    #[test]
+    fn fired_page_reminders_are_skipped() {
+        let at = Utc.with_ymd_and_hms(2025, 3, 14, 9, 0, 0).unwrap();
+        let pending = PageReminder::new(Uuid::new_v4(), Uuid::new_v4(), "Draft".to_string(), at)
+            .with_note("Send it".to_string());
+        let mut fired = pending.clone();
+        fired.fired_at = Some(at);
+
+        let notifications = page_reminders(&[pending.clone(), fired]);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].id, format!("reminder:{}", pending.id));
+        assert_eq!(notifications[0].title, "Draft");
+        assert_eq!(notifications[0].body, "Send it");
+    }
+
+    #[test]
+    fn snoozed_pages_and_inbox_items_resurface() {
+        let at = Utc.with_ymd_and_hms(2025, 6, 14, 9, 0, 0).unwrap();
+        let page = PageReminder::new(Uuid::new_v4(), Uuid::new_v4(), "Old idea".to_string(), at)
+            .resurfacing();
+        let notifications = page_reminders(&[page]);
+        assert_eq!(notifications[0].title, "Resurfaced: Old idea");
+
+        let mut snoozed = InboxItem::new("Call back".to_string(), "About the quote".to_string());
+        snoozed.snoozed_until = Some(at);
+        let mut processed = snoozed.clone();
+        processed.is_processed = true;
+        let awake = InboxItem::new("Now".to_string(), String::new());
+
+        let endings = snooze_endings(&[snoozed.clone(), processed, awake]);
+        assert_eq!(endings.len(), 1);
+        assert_eq!(
+            endings[0].id,
+            format!("snooze:{}:{}", snoozed.id, at.timestamp())
+        );
+        assert_eq!(endings[0].due_at, at);
+        assert!(snoozed.is_snoozed(at - chrono::Duration::minutes(1)));
+        assert!(!snoozed.is_snoozed(at));
+    }
+
+    #[test]
+    fn sync_errors_collapse_per_hour() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 14, 10, 5, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2025, 3, 14, 10, 55, 0).unwrap();
+        assert_eq!(sync_error("a", now).id, sync_error("b", later).id);
+        assert_ne!(
+            sync_error("a", now).id,
+            sync_error("a", later + chrono::Duration::hours(1)).id
+        );
+    }
+}
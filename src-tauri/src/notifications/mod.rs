@@ -0,0 +1,13 @@
+//! OS notifications for reminders and reviews
+//!
+//! Reminders are derived on each tick rather than stored: due flashcards
+//! (once a day at the configured time), goal check-ins (at each goal's
+//! reminder time until it's done), scheduled action runs, and sync errors
+//! (queued by the sync event emitter). A small log of delivered IDs keeps
+//! anything from being shown twice, across restarts too.
+
+pub mod center;
+pub mod models;
+
+pub use center::{notify_sync_error, NotificationCenter};
+pub use models::*;
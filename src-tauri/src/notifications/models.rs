@@ -0,0 +1,80 @@
+//! Notification data models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a notification is about; each category can be turned off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationCategory {
+    /// Daily nudge when flashcards are due for review
+    FlashcardsDue,
+    /// A goal's reminder time passed and it isn't done today
+    GoalCheckIn,
+    /// A scheduled action finished running
+    ActionCompleted,
+    /// A sync failed
+    SyncError,
+    /// A reminder set on a page
+    PageReminder,
+    /// A snoozed inbox item is back
+    SnoozeEnded,
+}
+
+/// User preferences for notifications
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationPreferences {
+    /// Master switch
+    pub enabled: bool,
+    pub flashcards_due: bool,
+    /// Time of day for the flashcard reminder (HH:MM, local time)
+    pub flashcard_reminder_time: String,
+    pub goal_check_ins: bool,
+    pub action_completions: bool,
+    pub sync_errors: bool,
+    pub page_reminders: bool,
+    pub snoozed_items: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            flashcards_due: true,
+            flashcard_reminder_time: "09:00".to_string(),
+            goal_check_ins: true,
+            action_completions: true,
+            sync_errors: true,
+            page_reminders: true,
+            snoozed_items: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    pub fn allows(&self, category: NotificationCategory) -> bool {
+        self.enabled
+            && match category {
+                NotificationCategory::FlashcardsDue => self.flashcards_due,
+                NotificationCategory::GoalCheckIn => self.goal_check_ins,
+                NotificationCategory::ActionCompleted => self.action_completions,
+                NotificationCategory::SyncError => self.sync_errors,
+                NotificationCategory::PageReminder => self.page_reminders,
+                NotificationCategory::SnoozeEnded => self.snoozed_items,
+            }
+    }
+}
+
+/// A notification that is scheduled but not yet shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingNotification {
+    /// Stable key, so the same reminder is never shown twice
+    pub id: String,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    /// When it should be shown; may be in the past (shown on the next tick)
+    pub due_at: DateTime<Utc>,
+}
@@ -859,14 +859,18 @@ pub fn import_notion_zip(
         system_prompt_mode: crate::storage::SystemPromptMode::default(),
         ai_provider: None,
         ai_model: None,
+        ai_profile_id: None,
         sync_config: None,
         encryption_config: None,
         is_pinned: false,
         position: 0,
         page_sort_by: None,
+        pinned_page_ids: Vec::new(),
         daily_notes_config: None,
         cover_image: None,
         mirror_path: None,
+        exclude_from_review: false,
+        code_exec: None,
         created_at: now,
         updated_at: now,
     };
@@ -1205,14 +1209,18 @@ where
         system_prompt_mode: crate::storage::SystemPromptMode::default(),
         ai_provider: None,
         ai_model: None,
+        ai_profile_id: None,
         sync_config: None,
         encryption_config: None,
         is_pinned: false,
         position: 0,
         page_sort_by: None,
+        pinned_page_ids: Vec::new(),
         daily_notes_config: None,
         cover_image: None,
         mirror_path: None,
+        exclude_from_review: false,
+        code_exec: None,
         created_at: now,
         updated_at: now,
     };
@@ -0,0 +1,398 @@
+//! Local model management over the Ollama HTTP API
+//!
+//! Lists installed models, pulls new ones (streaming progress), deletes
+//! them and checks that the server is up, so local AI can be managed from
+//! the settings instead of the `ollama` CLI. Chat itself still goes through
+//! the Python providers; this only talks to the server's management
+//! endpoints:
+//!
+//! - `GET /api/tags` — installed models
+//! - `POST /api/pull` — newline-delimited JSON progress, ending in
+//!   `{"status": "success"}` or an `{"error": ...}` line
+//! - `DELETE /api/delete`
+//! - `GET /api/version` — used for the health check
+
+use std::time::{Duration, Instant};
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+/// Where Ollama listens unless configured otherwise
+pub const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/// Quick calls; pulls stream for as long as the download takes
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum OllamaError {
+    #[error("Ollama is not reachable at {url}: {message}")]
+    Unreachable { url: String, message: String },
+
+    #[error("Model not found: {0}")]
+    ModelNotFound(String),
+
+    #[error("Ollama error: {0}")]
+    Server(String),
+
+    #[error("Unexpected response from Ollama: {0}")]
+    Decode(String),
+}
+
+pub type Result<T> = std::result::Result<T, OllamaError>;
+
+/// An installed model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaModel {
+    pub name: String,
+    /// Size on disk in bytes
+    pub size: u64,
+    pub digest: String,
+    pub modified_at: Option<String>,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization_level: Option<String>,
+}
+
+/// One step of a pull
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullProgress {
+    pub model: String,
+    /// Ollama's status line, e.g. "pulling manifest" or "verifying sha256 digest"
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+    /// Percent of the current layer, when Ollama reports sizes
+    pub percent: Option<f64>,
+    pub done: bool,
+}
+
+/// Result of a health check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaHealth {
+    pub base_url: String,
+    pub reachable: bool,
+    pub version: Option<String>,
+    /// Round trip of the version request
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+pub struct OllamaClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl OllamaClient {
+    /// A client for the server at `base_url` (the default when empty)
+    pub fn new(base_url: Option<&str>) -> Self {
+        let base_url = base_url
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/')
+            // Users often paste the OpenAI-compatible endpoint
+            .trim_end_matches("/v1")
+            .to_string();
+        Self {
+            base_url,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn unreachable(&self, e: reqwest::Error) -> OllamaError {
+        OllamaError::Unreachable {
+            url: self.base_url.clone(),
+            message: e.to_string(),
+        }
+    }
+
+    /// The error of a failed response; Ollama puts it in `{"error": ...}`
+    async fn server_error(response: reqwest::Response) -> OllamaError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("error")?.as_str().map(str::to_string))
+            .unwrap_or(body);
+        OllamaError::Server(format!("{} ({})", message.trim(), status))
+    }
+
+    /// Installed models, largest first
+    pub async fn list_models(&self) -> Result<Vec<OllamaModel>> {
+        let response = self
+            .http
+            .get(self.url("/api/tags"))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+        if !response.status().is_success() {
+            return Err(Self::server_error(response).await);
+        }
+        let tags: TagsResponse = response
+            .json()
+            .await
+            .map_err(|e| OllamaError::Decode(e.to_string()))?;
+        let mut models: Vec<OllamaModel> = tags.models.into_iter().map(Into::into).collect();
+        models.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+        Ok(models)
+    }
+
+    /// Pull `model`, calling `on_progress` for every status line
+    pub async fn pull_model(
+        &self,
+        model: &str,
+        mut on_progress: impl FnMut(PullProgress),
+    ) -> Result<()> {
+        let response = self
+            .http
+            .post(self.url("/api/pull"))
+            .json(&json!({ "model": model, "stream": true }))
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+        if !response.status().is_success() {
+            return Err(Self::server_error(response).await);
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut buffer = Vec::new();
+        let mut finished = false;
+        while let Some(chunk) = stream.next().await {
+            buffer.extend_from_slice(&chunk.map_err(|e| self.unreachable(e))?);
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if let Some(progress) = parse_pull_line(model, &line)? {
+                    finished |= progress.done;
+                    on_progress(progress);
+                }
+            }
+        }
+        if let Some(progress) = parse_pull_line(model, &buffer)? {
+            finished |= progress.done;
+            on_progress(progress);
+        }
+
+        if finished {
+            Ok(())
+        } else {
+            Err(OllamaError::Server(format!(
+                "pull of {} ended before it finished",
+                model
+            )))
+        }
+    }
+
+    pub async fn delete_model(&self, model: &str) -> Result<()> {
+        let response = self
+            .http
+            .delete(self.url("/api/delete"))
+            .json(&json!({ "model": model }))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| self.unreachable(e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(OllamaError::ModelNotFound(model.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(Self::server_error(response).await);
+        }
+        Ok(())
+    }
+
+    /// Whether the server answers, its version and how long it took. Never
+    /// fails: an unreachable server is reported in the result.
+    pub async fn health(&self) -> OllamaHealth {
+        let started = Instant::now();
+        let result = async {
+            let response = self
+                .http
+                .get(self.url("/api/version"))
+                .timeout(REQUEST_TIMEOUT)
+                .send()
+                .await
+                .map_err(|e| self.unreachable(e))?;
+            if !response.status().is_success() {
+                return Err(Self::server_error(response).await);
+            }
+            let version: VersionResponse = response
+                .json()
+                .await
+                .map_err(|e| OllamaError::Decode(e.to_string()))?;
+            Ok(version.version)
+        }
+        .await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(version) => OllamaHealth {
+                base_url: self.base_url.clone(),
+                reachable: true,
+                version: Some(version),
+                latency_ms: Some(latency_ms),
+                error: None,
+            },
+            Err(e) => OllamaHealth {
+                base_url: self.base_url.clone(),
+                reachable: false,
+                version: None,
+                latency_ms: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagsModel>,
+}
+
+#[derive(Deserialize)]
+struct TagsModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    digest: String,
+    modified_at: Option<String>,
+    #[serde(default)]
+    details: TagsModelDetails,
+}
+
+#[derive(Deserialize, Default)]
+struct TagsModelDetails {
+    family: Option<String>,
+    parameter_size: Option<String>,
+    quantization_level: Option<String>,
+}
+
+impl From<TagsModel> for OllamaModel {
+    fn from(model: TagsModel) -> Self {
+        Self {
+            name: model.name,
+            size: model.size,
+            digest: model.digest,
+            modified_at: model.modified_at,
+            family: model.details.family,
+            parameter_size: model.details.parameter_size,
+            quantization_level: model.details.quantization_level,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct PullLine {
+    #[serde(default)]
+    status: String,
+    digest: Option<String>,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+/// Parse one line of a pull's progress stream; blank lines give `None`
+fn parse_pull_line(model: &str, line: &[u8]) -> Result<Option<PullProgress>> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let parsed: PullLine =
+        serde_json::from_str(line).map_err(|e| OllamaError::Decode(e.to_string()))?;
+    if let Some(error) = parsed.error {
+        return Err(OllamaError::Server(error));
+    }
+    let percent = match (parsed.completed, parsed.total) {
+        (Some(completed), Some(total)) if total > 0 => {
+            Some((completed as f64 / total as f64 * 100.0).min(100.0))
+        }
+        _ => None,
+    };
+    Ok(Some(PullProgress {
+        model: model.to_string(),
+        done: parsed.status == "success",
+        status: parsed.status,
+        digest: parsed.digest,
+        total: parsed.total,
+        completed: parsed.completed,
+        percent,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_base_url() {
+        assert_eq!(OllamaClient::new(None).base_url(), DEFAULT_BASE_URL);
+        assert_eq!(OllamaClient::new(Some("  ")).base_url(), DEFAULT_BASE_URL);
+        assert_eq!(
+            OllamaClient::new(Some("http://gpu-box:11434/v1/")).base_url(),
+            "http://gpu-box:11434"
+        );
+    }
+
+    #[test]
+    fn parses_pull_progress_lines() {
+        let progress = parse_pull_line(
+            "llama3.2",
+            br#"{"status":"pulling 6a0746a1ec1a","digest":"sha256:6a07","total":2000,"completed":500}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(progress.percent, Some(25.0));
+        assert!(!progress.done);
+
+        let done = parse_pull_line("llama3.2", b"{\"status\":\"success\"}\n")
+            .unwrap()
+            .unwrap();
+        assert!(done.done);
+        assert_eq!(done.percent, None);
+
+        assert!(parse_pull_line("llama3.2", b"  \n").unwrap().is_none());
+        assert!(matches!(
+            parse_pull_line(
+                "nope",
+                br#"{"error":"pull model manifest: file does not exist"}"#
+            ),
+            Err(OllamaError::Server(_))
+        ));
+    }
+
+    #[test]
+    fn converts_tags_with_details() {
+        let tags: TagsResponse = serde_json::from_str(
+            r#"{"models":[{"name":"qwen2.5:7b","size":4683087332,"digest":"845dbda0",
+                "modified_at":"2026-09-01T10:00:00Z",
+                "details":{"family":"qwen2","parameter_size":"7.6B","quantization_level":"Q4_K_M"}},
+               {"name":"nomic-embed-text:latest","size":274302450,"digest":"0a109f42"}]}"#,
+        )
+        .unwrap();
+        let models: Vec<OllamaModel> = tags.models.into_iter().map(Into::into).collect();
+        assert_eq!(models[0].parameter_size.as_deref(), Some("7.6B"));
+        assert_eq!(models[1].family, None);
+    }
+}
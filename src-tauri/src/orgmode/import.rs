@@ -666,6 +666,9 @@ fn import_org_file(content: &str, notebook_id: Uuid, fallback_title: &str) -> Pa
         daily_note_date: None,
         plugin_page_type: None,
         plugin_data: None,
+        properties: Default::default(),
+        encryption: None,
+        stats: None,
         created_at,
         updated_at: now,
     }
@@ -970,14 +973,18 @@ pub fn import_orgmode(
         system_prompt_mode: crate::storage::SystemPromptMode::default(),
         ai_provider: None,
         ai_model: None,
+        ai_profile_id: None,
         sync_config: None,
         encryption_config: None,
         is_pinned: false,
         position: 0,
         page_sort_by: None,
+        pinned_page_ids: Vec::new(),
         daily_notes_config: None,
         cover_image: None,
         mirror_path: None,
+        exclude_from_review: false,
+        code_exec: None,
         created_at: now,
         updated_at: now,
     };
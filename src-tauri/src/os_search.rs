@@ -0,0 +1,435 @@
+//! Export pages to the OS search index (Spotlight, Windows Search).
+//!
+//! Rather than talking to Core Spotlight or the Windows indexer directly,
+//! this writes one small stub file per page into a folder the platform
+//! indexer already crawls; opening a stub hands a nous:// link back to the
+//! app.
+//!
+//! - macOS: HTML stubs in `~/Library/Caches/Metadata/Nous`. Spotlight's HTML
+//!   importer picks up the title, keywords and body text, and the stub
+//!   redirects to the page's deep link.
+//! - Windows: Internet Shortcut (`.url`) files in a Start Menu folder, so
+//!   page titles show up in Start search.
+//! - Elsewhere: HTML stubs in the data directory, for desktop indexers
+//!   (Tracker, Baloo) that are pointed at it.
+//!
+//! Only the current library (the main window's, `AppState::storage`) is
+//! exported, whatever library windows are open, and encrypted notebooks are
+//! always skipped, so nothing locked leaks into the OS index. A manifest
+//! next to the stubs records what was written so re-exports only touch
+//! pages that changed and remove stubs for deleted pages.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+use crate::deep_link::page_deep_link;
+use crate::markdown::export_page_to_markdown;
+use crate::storage::{FileStorage, Page};
+use crate::AppState;
+
+const MANIFEST_FILE: &str = ".nous-index.json";
+/// Body text kept in HTML stubs; enough for search, not a full copy
+const MAX_BODY_CHARS: usize = 4000;
+const EXPORT_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OsSearchSettings {
+    pub enabled: bool,
+    /// Override for the stub folder; the platform default when unset
+    pub directory: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StubFormat {
+    Html,
+    InternetShortcut,
+}
+
+impl StubFormat {
+    fn current() -> Self {
+        if cfg!(windows) {
+            StubFormat::InternetShortcut
+        } else {
+            StubFormat::Html
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            StubFormat::Html => "html",
+            StubFormat::InternetShortcut => "url",
+        }
+    }
+}
+
+/// What a stub is rendered from
+struct PageStub<'a> {
+    page: &'a Page,
+    notebook_name: &'a str,
+    link: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestEntry {
+    file: String,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    library_id: Option<Uuid>,
+    pages: HashMap<Uuid, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OsSearchExportResult {
+    pub directory: PathBuf,
+    pub written: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("os_search.json")
+}
+
+pub fn load_settings(data_dir: &Path) -> OsSearchSettings {
+    std::fs::read_to_string(settings_path(data_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(data_dir: &Path, settings: &OsSearchSettings) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(settings_path(data_dir), content).map_err(|e| e.to_string())
+}
+
+/// Where stubs go when the settings don't say
+fn default_directory(data_dir: &Path) -> PathBuf {
+    if cfg!(target_os = "macos") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join("Library/Caches/Metadata/Nous");
+        }
+    }
+    if cfg!(windows) {
+        if let Some(roaming) = dirs::data_dir() {
+            return roaming.join(r"Microsoft\Windows\Start Menu\Programs\Nous Pages");
+        }
+    }
+    data_dir.join("os-search")
+}
+
+fn stub_directory(data_dir: &Path, settings: &OsSearchSettings) -> PathBuf {
+    settings
+        .directory
+        .clone()
+        .unwrap_or_else(|| default_directory(data_dir))
+}
+
+/// File name for a page's stub. The short ID keeps same-titled pages apart
+/// and makes renames replace rather than duplicate.
+fn stub_file_name(title: &str, page_id: Uuid, format: StubFormat) -> String {
+    let title: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(60)
+        .collect();
+    let title = title.trim().trim_end_matches('.');
+    let title = if title.is_empty() { "Untitled" } else { title };
+    let short_id = &page_id.simple().to_string()[..8];
+    format!("{} ({}).{}", title, short_id, format.extension())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Page text without the YAML frontmatter, trimmed to `MAX_BODY_CHARS`
+fn body_text(page: &Page) -> String {
+    let markdown = export_page_to_markdown(page);
+    let body = markdown
+        .split_once("---\n\n")
+        .map_or(markdown.as_str(), |(_, body)| body);
+    body.chars().take(MAX_BODY_CHARS).collect()
+}
+
+fn render_stub(stub: &PageStub, format: StubFormat) -> String {
+    match format {
+        StubFormat::Html => {
+            let body = body_text(stub.page);
+            let description: String = body
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(200)
+                .collect();
+            let link = escape_html(&stub.link);
+            format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                 <title>{title}</title>\n\
+                 <meta name=\"keywords\" content=\"{keywords}\">\n\
+                 <meta name=\"description\" content=\"{description}\">\n\
+                 <meta name=\"author\" content=\"Nous — {notebook}\">\n\
+                 <meta http-equiv=\"refresh\" content=\"0; url={link}\">\n\
+                 </head>\n<body>\n<h1><a href=\"{link}\">{title}</a></h1>\n\
+                 <p>{notebook}</p>\n<pre>{body}</pre>\n</body>\n</html>\n",
+                title = escape_html(&stub.page.title),
+                keywords = escape_html(&stub.page.tags.join(", ")),
+                description = escape_html(&description),
+                notebook = escape_html(stub.notebook_name),
+                link = link,
+                body = escape_html(&body),
+            )
+        }
+        StubFormat::InternetShortcut => {
+            format!("[InternetShortcut]\r\nURL={}\r\n", stub.link)
+        }
+    }
+}
+
+fn load_manifest(dir: &Path) -> Manifest {
+    std::fs::read_to_string(dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(dir: &Path, manifest: &Manifest) -> Result<(), String> {
+    let content = serde_json::to_string(manifest).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(MANIFEST_FILE), content).map_err(|e| e.to_string())
+}
+
+/// Bring the stub folder in line with the current library
+fn export(
+    storage: &FileStorage,
+    library_id: Uuid,
+    dir: &Path,
+) -> Result<OsSearchExportResult, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let format = StubFormat::current();
+    let mut manifest = load_manifest(dir);
+    if manifest.library_id != Some(library_id) {
+        // Switched libraries: everything from the old one goes
+        remove_stubs(dir, &manifest);
+        manifest = Manifest {
+            library_id: Some(library_id),
+            pages: HashMap::new(),
+        };
+    }
+
+    let mut result = OsSearchExportResult {
+        directory: dir.to_path_buf(),
+        ..Default::default()
+    };
+    let mut seen = HashMap::new();
+    let notebooks = storage.list_notebooks().map_err(|e| e.to_string())?;
+    for notebook in notebooks
+        .iter()
+        .filter(|nb| !nb.archived && !nb.is_encrypted())
+    {
+        let Ok(pages) = storage.list_pages(notebook.id) else {
+            continue;
+        };
+        for page in pages
+            .iter()
+            .filter(|p| !p.is_archived && p.deleted_at.is_none())
+        {
+            let file = stub_file_name(&page.title, page.id, format);
+            let up_to_date = manifest
+                .pages
+                .get(&page.id)
+                .is_some_and(|e| e.file == file && e.updated_at == page.updated_at)
+                && dir.join(&file).exists();
+            if up_to_date {
+                result.unchanged += 1;
+            } else {
+                let stub = PageStub {
+                    page,
+                    notebook_name: &notebook.name,
+                    link: page_deep_link(page.id, notebook.id, Some(library_id)),
+                };
+                std::fs::write(dir.join(&file), render_stub(&stub, format))
+                    .map_err(|e| format!("Failed to write stub for '{}': {}", page.title, e))?;
+                result.written += 1;
+            }
+            seen.insert(
+                page.id,
+                ManifestEntry {
+                    file,
+                    updated_at: page.updated_at,
+                },
+            );
+        }
+    }
+
+    // Pages that were deleted, archived, encrypted or renamed
+    for (page_id, entry) in &manifest.pages {
+        let renamed = seen.get(page_id).is_some_and(|e| e.file != entry.file);
+        if !seen.contains_key(page_id) || renamed {
+            let _ = std::fs::remove_file(dir.join(&entry.file));
+            result.removed += 1;
+        }
+    }
+
+    manifest.pages = seen;
+    save_manifest(dir, &manifest)?;
+    Ok(result)
+}
+
+fn remove_stubs(dir: &Path, manifest: &Manifest) {
+    for entry in manifest.pages.values() {
+        let _ = std::fs::remove_file(dir.join(&entry.file));
+    }
+}
+
+/// Remove everything we exported, e.g. when the feature is turned off
+fn clear(dir: &Path) {
+    remove_stubs(dir, &load_manifest(dir));
+    let _ = std::fs::remove_file(dir.join(MANIFEST_FILE));
+    // Only succeeds if nothing else lives there
+    let _ = std::fs::remove_dir(dir);
+}
+
+fn export_current_library(state: &AppState) -> Result<OsSearchExportResult, String> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    let settings = load_settings(&data_dir);
+    let library_id = state
+        .library_storage
+        .lock()
+        .unwrap()
+        .get_current_library_id()
+        .map_err(|e| e.to_string())?;
+    let storage = state.storage.read().unwrap();
+    export(&storage, library_id, &stub_directory(&data_dir, &settings))
+}
+
+/// Re-export periodically while enabled; called once from `setup`
+pub fn init(app: &AppHandle) {
+    let handle = app.clone();
+    std::thread::spawn(move || loop {
+        let enabled = FileStorage::default_data_dir()
+            .map(|dir| load_settings(&dir).enabled)
+            .unwrap_or(false);
+        if enabled {
+            match export_current_library(&handle.state::<AppState>()) {
+                Ok(result) if result.written + result.removed > 0 => log::info!(
+                    "OS search export: {} written, {} removed",
+                    result.written,
+                    result.removed
+                ),
+                Ok(_) => {}
+                Err(e) => log::warn!("OS search export failed: {}", e),
+            }
+        }
+        std::thread::sleep(EXPORT_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub fn get_os_search_settings() -> Result<OsSearchSettings, String> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    Ok(load_settings(&data_dir))
+}
+
+/// Save settings. Turning the export off, or moving it, removes the stubs
+/// from the old folder.
+#[tauri::command]
+pub fn update_os_search_settings(
+    state: State<'_, AppState>,
+    settings: OsSearchSettings,
+) -> Result<OsSearchSettings, String> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    let previous = load_settings(&data_dir);
+    let old_dir = stub_directory(&data_dir, &previous);
+    if previous.enabled && (!settings.enabled || stub_directory(&data_dir, &settings) != old_dir) {
+        clear(&old_dir);
+    }
+    save_settings(&data_dir, &settings)?;
+    if settings.enabled {
+        export_current_library(&state)?;
+    }
+    Ok(settings)
+}
+
+/// Export now, regardless of the periodic schedule
+#[tauri::command]
+pub fn export_os_search_index(state: State<'_, AppState>) -> Result<OsSearchExportResult, String> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    if !load_settings(&data_dir).enabled {
+        return Err("OS search integration is turned off".to_string());
+    }
+    export_current_library(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{EditorBlock, EditorData};
+
+    fn page(title: &str) -> Page {
+        let mut page = Page::new(Uuid::new_v4(), title.to_string());
+        page.tags = vec!["work".to_string(), "a&b".to_string()];
+        page.content = EditorData {
+            time: None,
+            version: None,
+            blocks: vec![EditorBlock {
+                id: "b1".to_string(),
+                block_type: "paragraph".to_string(),
+                data: serde_json::json!({ "text": "Quarterly <b>numbers</b>" }),
+            }],
+        };
+        page
+    }
+
+    #[test]
+    fn file_names_are_safe_and_unique() {
+        let id = Uuid::parse_str("0123abcd-0000-0000-0000-000000000000").unwrap();
+        assert_eq!(
+            stub_file_name("Q3: plan/review?", id, StubFormat::Html),
+            "Q3_ plan_review_ (0123abcd).html"
+        );
+        assert_eq!(
+            stub_file_name("  ...", id, StubFormat::InternetShortcut),
+            "Untitled (0123abcd).url"
+        );
+    }
+
+    #[test]
+    fn stubs_link_back_to_the_page() {
+        let page = page("Plans <2025>");
+        let stub = PageStub {
+            page: &page,
+            notebook_name: "Work",
+            link: page_deep_link(page.id, page.notebook_id, None),
+        };
+
+        let html = render_stub(&stub, StubFormat::Html);
+        assert!(html.contains("<title>Plans &lt;2025&gt;</title>"));
+        assert!(html.contains("content=\"work, a&amp;b\""));
+        assert!(html.contains(&format!("url=nous://page/{}", page.id)));
+        assert!(html.contains("Quarterly"));
+
+        let url = render_stub(&stub, StubFormat::InternetShortcut);
+        assert!(url.starts_with("[InternetShortcut]\r\nURL=nous://page/"));
+    }
+}
@@ -15,7 +15,7 @@ use crate::goals::GoalsStorage;
 use crate::inbox::InboxStorage;
 use crate::python_bridge::{AIConfig, ChatMessage, PythonAI};
 use crate::search::SearchIndex;
-use crate::storage::{FileStorage, FileStorageMode, PageType};
+use crate::storage::{FileStorageMode, PageType, StorageLock};
 
 /// Maximum timeout for HTTP requests (60 seconds).
 const MAX_TIMEOUT_SECS: u64 = 60;
@@ -25,7 +25,7 @@ const MAX_RESPONSE_BYTES: usize = 10 * 1024 * 1024;
 /// Host API available to all plugin runtimes.
 /// Holds Arc references to the app's storage layers.
 pub struct HostApi {
-    pub(crate) storage: Arc<Mutex<FileStorage>>,
+    pub(crate) storage: Arc<StorageLock>,
     pub(crate) goals_storage: Arc<Mutex<GoalsStorage>>,
     pub(crate) inbox_storage: Arc<Mutex<InboxStorage>>,
     pub(crate) search_index: Option<Arc<Mutex<SearchIndex>>>,
@@ -37,7 +37,7 @@ pub struct HostApi {
 
 impl HostApi {
     pub fn new(
-        storage: Arc<Mutex<FileStorage>>,
+        storage: Arc<StorageLock>,
         goals_storage: Arc<Mutex<GoalsStorage>>,
         inbox_storage: Arc<Mutex<InboxStorage>>,
     ) -> Self {
@@ -110,7 +110,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_READ, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let pages: Vec<_> = storage
@@ -134,7 +134,7 @@ impl HostApi {
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = storage
@@ -285,7 +285,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::DATABASE_READ, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let pages = storage
@@ -336,7 +336,7 @@ impl HostApi {
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = storage
@@ -415,7 +415,7 @@ impl HostApi {
             "views": [default_view],
         });
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
 
@@ -473,7 +473,7 @@ impl HostApi {
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = storage
@@ -559,7 +559,7 @@ impl HostApi {
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = storage
@@ -672,7 +672,7 @@ impl HostApi {
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = storage
@@ -863,7 +863,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_WRITE, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = storage
@@ -892,7 +892,7 @@ impl HostApi {
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let mut page = storage
@@ -936,7 +936,7 @@ impl HostApi {
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let mut page = storage
@@ -971,7 +971,7 @@ impl HostApi {
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         storage
@@ -1000,7 +1000,7 @@ impl HostApi {
             .transpose()
             .map_err(|e| PluginError::CallFailed(format!("invalid folder_id: {e}")))?;
 
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let mut page = storage
@@ -1040,7 +1040,7 @@ impl HostApi {
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
         let pid = Uuid::parse_str(page_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid page_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let mut page = storage
@@ -1082,7 +1082,7 @@ impl HostApi {
         plugin_id: &str,
     ) -> Result<serde_json::Value, PluginError> {
         Self::require(caps, CapabilitySet::PAGE_READ, plugin_id)?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let notebooks = storage
@@ -1101,7 +1101,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_READ, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let sections = storage
@@ -1120,7 +1120,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_READ, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let folders = storage
@@ -1145,7 +1145,7 @@ impl HostApi {
             .map(|p| Uuid::parse_str(p))
             .transpose()
             .map_err(|e| PluginError::CallFailed(format!("invalid parent_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let folder = storage
@@ -1170,7 +1170,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_READ, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let mut pages = crate::commands::list_daily_notes_core(&storage, nid, None, None)
@@ -1192,7 +1192,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_READ, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.read().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let page = crate::commands::find_daily_note(&storage, nid, date)
@@ -1215,7 +1215,7 @@ impl HostApi {
         Self::require(caps, CapabilitySet::PAGE_WRITE, plugin_id)?;
         let nid = Uuid::parse_str(notebook_id)
             .map_err(|e| PluginError::CallFailed(format!("invalid notebook_id: {e}")))?;
-        let storage = self.storage.lock().map_err(|e| {
+        let storage = self.storage.write().map_err(|e| {
             PluginError::CallFailed(format!("storage lock: {e}"))
         })?;
         let mut page = crate::commands::create_daily_note_core(&storage, nid, date, None)
@@ -8,7 +8,7 @@ use uuid::Uuid;
 use crate::energy::EnergyStorage;
 use crate::goals::GoalsStorage;
 use crate::inbox::InboxStorage;
-use crate::storage::FileStorage;
+use crate::storage::{FileStorage, FileStorageWriter, StorageLock};
 
 use super::api::HostApi;
 use super::error::PluginError;
@@ -24,9 +24,9 @@ fn create_test_env() -> (Arc<HostApi>, TempDir, Uuid) {
     let tmp = TempDir::new().expect("create temp dir");
     let base = tmp.path().to_path_buf();
 
-    let storage = FileStorage::new(base.clone());
+    let storage = FileStorageWriter::new(FileStorage::new(base.clone()));
     storage.init().expect("init file storage");
-    let storage = Arc::new(Mutex::new(storage));
+    let storage = Arc::new(StorageLock::new(storage));
 
     let goals = GoalsStorage::new(base.clone()).expect("init goals storage");
     let goals = Arc::new(Mutex::new(goals));
@@ -44,7 +44,7 @@ fn create_test_env() -> (Arc<HostApi>, TempDir, Uuid) {
 
     // Create a test notebook
     let notebook_id = {
-        let s = storage.lock().unwrap();
+        let s = storage.write().unwrap();
         let nb = s
             .create_notebook(
                 "Test Notebook".to_string(),
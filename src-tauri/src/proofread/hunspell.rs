@@ -0,0 +1,493 @@
+//! Reading Hunspell dictionaries
+//!
+//! A dictionary is a `.dic` word list whose words carry affix flags, and an
+//! `.aff` file with the prefix and suffix rules those flags stand for. The
+//! word list is expanded into every form the rules allow when it's loaded,
+//! so checking a word is a set lookup.
+//!
+//! This covers what most spelling dictionaries use: `SET`, `FLAG`, `TRY`,
+//! `PFX`/`SFX` with cross products and one level of continuation flags,
+//! `NEEDAFFIX` and `FORBIDDENWORD`. Compounding isn't supported, so
+//! languages that build words by compounding (German, Dutch, Hungarian)
+//! will flag compounds that aren't in the word list.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Most suggestions offered for a misspelling
+pub const MAX_SUGGESTIONS: usize = 5;
+
+/// Letters tried when the `.aff` has no `TRY` line
+const DEFAULT_TRY: &str = "esianrtolcdugmphbyfvkwz'";
+
+/// One position of an affix condition
+#[derive(Debug, Clone)]
+enum CondChar {
+    Any,
+    Char(char),
+    Set { chars: Vec<char>, negated: bool },
+}
+
+impl CondChar {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            CondChar::Any => true,
+            CondChar::Char(c) => *c == ch,
+            CondChar::Set { chars, negated } => chars.contains(&ch) != *negated,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    /// Flags of the affixes that may follow this one
+    continuation: Vec<String>,
+    condition: Vec<CondChar>,
+}
+
+#[derive(Debug, Clone)]
+struct AffixClass {
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagType {
+    /// One character per flag
+    Short,
+    /// Two characters per flag
+    Long,
+    /// Comma-separated numbers
+    Num,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Short => flags.chars().map(String::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagType::Num => flags
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// The affix rules of an `.aff` file
+struct Affixes {
+    flag_type: FlagType,
+    try_chars: Vec<char>,
+    prefixes: HashMap<String, AffixClass>,
+    suffixes: HashMap<String, AffixClass>,
+    need_affix: Option<String>,
+    forbidden: Option<String>,
+}
+
+impl Affixes {
+    fn parse(aff: &str) -> Self {
+        let mut affixes = Affixes {
+            flag_type: FlagType::Short,
+            try_chars: Vec::new(),
+            prefixes: HashMap::new(),
+            suffixes: HashMap::new(),
+            need_affix: None,
+            forbidden: None,
+        };
+        // FLAG comes before the rules that use it, but scan for it first in
+        // case a dictionary doesn't keep to that
+        for line in aff.lines() {
+            let mut fields = line.split_whitespace();
+            if fields.next() == Some("FLAG") {
+                affixes.flag_type = match fields.next() {
+                    Some("long") => FlagType::Long,
+                    Some("num") => FlagType::Num,
+                    _ => FlagType::Short,
+                };
+            }
+        }
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["TRY", chars, ..] => affixes.try_chars = chars.chars().collect(),
+                ["NEEDAFFIX" | "PSEUDOROOT", flag, ..] => {
+                    affixes.need_affix = Some(flag.to_string())
+                }
+                ["FORBIDDENWORD", flag, ..] => affixes.forbidden = Some(flag.to_string()),
+                [kind @ ("PFX" | "SFX"), flag, cross, count]
+                    if count.parse::<usize>().is_ok() && matches!(*cross, "Y" | "N") =>
+                {
+                    let classes = if *kind == "PFX" {
+                        &mut affixes.prefixes
+                    } else {
+                        &mut affixes.suffixes
+                    };
+                    classes.insert(
+                        flag.to_string(),
+                        AffixClass {
+                            cross_product: *cross == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let (add, continuation) = match add.split_once('/') {
+                        Some((add, flags)) => (add, affixes.flag_type.split(flags)),
+                        None => (*add, Vec::new()),
+                    };
+                    let rule = AffixRule {
+                        strip: empty_if_zero(strip),
+                        add: empty_if_zero(add),
+                        continuation,
+                        condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                    };
+                    let classes = if *kind == "PFX" {
+                        &mut affixes.prefixes
+                    } else {
+                        &mut affixes.suffixes
+                    };
+                    if let Some(class) = classes.get_mut(*flag) {
+                        class.rules.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        affixes
+    }
+
+    fn has(flags: &[String], flag: &Option<String>) -> bool {
+        flag.as_ref().is_some_and(|flag| flags.contains(flag))
+    }
+
+    /// Every form of `stem` its flags allow, the stem itself included
+    /// unless it needs an affix
+    fn expand(&self, stem: &str, flags: &[String], out: &mut Vec<String>) {
+        if !Self::has(flags, &self.need_affix) {
+            out.push(stem.to_string());
+        }
+
+        // Suffixed forms, with the suffixes' own continuation suffixes, and
+        // whether each may also take a prefix
+        let mut suffixed: Vec<(String, bool)> = Vec::new();
+        for flag in flags {
+            let Some(class) = self.suffixes.get(flag) else {
+                continue;
+            };
+            for rule in &class.rules {
+                let Some(form) = apply_suffix(stem, rule) else {
+                    continue;
+                };
+                for cont in &rule.continuation {
+                    let Some(next) = self.suffixes.get(cont) else {
+                        continue;
+                    };
+                    for next_rule in &next.rules {
+                        if let Some(twofold) = apply_suffix(&form, next_rule) {
+                            suffixed.push((twofold, false));
+                        }
+                    }
+                }
+                if !Self::has(&rule.continuation, &self.need_affix) {
+                    suffixed.push((form, class.cross_product));
+                }
+            }
+        }
+
+        for flag in flags {
+            let Some(class) = self.prefixes.get(flag) else {
+                continue;
+            };
+            for rule in &class.rules {
+                if let Some(form) = apply_prefix(stem, rule) {
+                    out.push(form);
+                }
+                if !class.cross_product {
+                    continue;
+                }
+                for (form, cross) in &suffixed {
+                    if *cross {
+                        if let Some(form) = apply_prefix(form, rule) {
+                            out.push(form);
+                        }
+                    }
+                }
+            }
+        }
+        out.extend(suffixed.into_iter().map(|(form, _)| form));
+    }
+}
+
+/// A loaded spelling dictionary
+pub struct Dictionary {
+    words: HashSet<String>,
+    forbidden: HashSet<String>,
+    try_chars: Vec<char>,
+}
+
+impl Dictionary {
+    /// Load the dictionary from its `.aff` and `.dic` files, decoding them
+    /// by the `.aff`'s `SET` (UTF-8 or a single-byte encoding)
+    pub fn load(aff_path: &Path, dic_path: &Path) -> std::io::Result<Self> {
+        let aff = fs::read(aff_path)?;
+        let dic = fs::read(dic_path)?;
+        let utf8 = String::from_utf8_lossy(&aff)
+            .lines()
+            .find_map(|line| line.strip_prefix("SET "))
+            .map_or(true, |set| set.trim().eq_ignore_ascii_case("UTF-8"));
+        let decode = |bytes: &[u8]| {
+            if utf8 {
+                String::from_utf8_lossy(bytes).into_owned()
+            } else {
+                // Latin-1 maps bytes straight to code points; other
+                // single-byte sets mostly agree on letters
+                bytes.iter().map(|&b| b as char).collect()
+            }
+        };
+        Ok(Self::parse(&decode(&aff), &decode(&dic)))
+    }
+
+    pub fn parse(aff: &str, dic: &str) -> Self {
+        let affixes = Affixes::parse(aff);
+        let mut words = HashSet::new();
+        let mut forbidden = HashSet::new();
+        let mut forms = Vec::new();
+        // The first line is the word count
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, affixes.flag_type.split(flags)),
+                None => (entry, Vec::new()),
+            };
+            if word.is_empty() {
+                continue;
+            }
+            if Affixes::has(&flags, &affixes.forbidden) {
+                forbidden.insert(word.to_string());
+                continue;
+            }
+            forms.clear();
+            affixes.expand(word, &flags, &mut forms);
+            words.extend(forms.drain(..));
+        }
+        let try_chars = if affixes.try_chars.is_empty() {
+            DEFAULT_TRY.chars().collect()
+        } else {
+            affixes.try_chars
+        };
+        Self {
+            words,
+            forbidden,
+            try_chars,
+        }
+    }
+
+    /// Whether `word` is in the dictionary exactly as written
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word) && !self.forbidden.contains(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Words one edit away from `word` that `known` accepts, and splits of
+    /// it into two known words, most likely first
+    pub fn suggest(&self, word: &str, known: &dyn Fn(&str) -> bool) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut candidates: Vec<String> = Vec::new();
+        let collect = |chars: Vec<char>| chars.into_iter().collect::<String>();
+
+        for i in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(i, i + 1);
+            candidates.push(collect(swapped));
+        }
+        for i in 0..chars.len() {
+            for &c in &self.try_chars {
+                if c != chars[i] {
+                    let mut replaced = chars.clone();
+                    replaced[i] = c;
+                    candidates.push(collect(replaced));
+                }
+            }
+        }
+        for i in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(i);
+            candidates.push(collect(deleted));
+        }
+        for i in 0..=chars.len() {
+            for &c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(i, c);
+                candidates.push(collect(inserted));
+            }
+        }
+        for i in 1..chars.len() {
+            let (left, right) = chars.split_at(i);
+            let (left, right) = (collect(left.to_vec()), collect(right.to_vec()));
+            if known(&left) && known(&right) {
+                candidates.push(format!("{} {}", left, right));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        candidates
+            .into_iter()
+            .filter(|c| c.contains(' ') || known(c.as_str()))
+            .filter(|c| seen.insert(c.clone()))
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+}
+
+fn empty_if_zero(value: &str) -> String {
+    if value == "0" {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<CondChar> {
+    let mut out = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => out.push(CondChar::Any),
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                for (i, c) in chars.by_ref().enumerate() {
+                    match c {
+                        ']' => break,
+                        '^' if i == 0 => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                out.push(CondChar::Set {
+                    chars: set,
+                    negated,
+                });
+            }
+            c => out.push(CondChar::Char(c)),
+        }
+    }
+    if matches!(out.as_slice(), [CondChar::Any]) {
+        out.clear();
+    }
+    out
+}
+
+fn apply_suffix(stem: &str, rule: &AffixRule) -> Option<String> {
+    let base = stem.strip_suffix(rule.strip.as_str())?;
+    let tail: Vec<char> = stem.chars().rev().take(rule.condition.len()).collect();
+    if tail.len() < rule.condition.len()
+        || !rule
+            .condition
+            .iter()
+            .rev()
+            .zip(&tail)
+            .all(|(cond, &c)| cond.matches(c))
+    {
+        return None;
+    }
+    Some(format!("{}{}", base, rule.add))
+}
+
+fn apply_prefix(stem: &str, rule: &AffixRule) -> Option<String> {
+    let base = stem.strip_prefix(rule.strip.as_str())?;
+    let head: Vec<char> = stem.chars().take(rule.condition.len()).collect();
+    if head.len() < rule.condition.len()
+        || !rule
+            .condition
+            .iter()
+            .zip(&head)
+            .all(|(cond, &c)| cond.matches(c))
+    {
+        return None;
+    }
+    Some(format!("{}{}", rule.add, base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 3
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     s          [^y]
+
+SFX D Y 2
+SFX D   0     d          e
+SFX D   0     ed         [^e]
+
+SFX R N 2
+SFX R   0     r/S        e
+SFX R   0     er/S       [^e]
+
+NEEDAFFIX X
+FORBIDDENWORD F
+";
+
+    const DIC: &str = "7
+city/S
+day/S
+lock/UD
+bake/DR
+teach/RX
+alot/F
+paper
+";
+
+    #[test]
+    fn expands_affixes_by_their_conditions() {
+        let dictionary = Dictionary::parse(AFF, DIC);
+        for word in [
+            "city", "cities", "day", "days", "lock", "locked", "unlock", "unlocked", "bake",
+            "baked", "baker", "bakers", "teacher", "teachers", "paper",
+        ] {
+            assert!(dictionary.contains(word), "{} should be known", word);
+        }
+        for word in [
+            "citys", "daies", "bakeed", "unbake", "teach", "alot", "papers",
+        ] {
+            assert!(!dictionary.contains(word), "{} should be unknown", word);
+        }
+    }
+
+    #[test]
+    fn suggests_nearby_words_and_splits() {
+        let dictionary = Dictionary::parse(AFF, DIC);
+        let known = |w: &str| dictionary.contains(w);
+        assert_eq!(dictionary.suggest("ctiy", &known)[0], "city");
+        assert_eq!(dictionary.suggest("lokced", &known)[0], "locked");
+        assert!(dictionary.suggest("qqqq", &known).is_empty());
+        assert!(dictionary
+            .suggest("bakeer", &known)
+            .contains(&"baker".to_string()));
+        assert_eq!(dictionary.suggest("citypaper", &known), ["city paper"]);
+    }
+}
@@ -0,0 +1,760 @@
+//! Spelling and grammar checks for pages
+//!
+//! Spelling is checked locally against a Hunspell dictionary (see
+//! [`hunspell`]) plus the library's own dictionary; grammar is an optional
+//! AI pass. Both come back as spans the editor can underline: the block,
+//! the text field in it (`text`, `items/0/content`, `content/1/2`) and start
+//! and end offsets into the field's text content, in UTF-16 code units as
+//! the DOM counts them.
+//!
+//! Inline code, wiki-links and block refs aren't checked, nor words in
+//! capitals or with capitals inside them (acronyms, identifiers) or in
+//! anything that looks like a URL, address or path.
+//!
+//! Dictionaries are looked for in the app's `dictionaries` folder and where
+//! the system keeps Hunspell dictionaries. Words added to the library's
+//! dictionary are stored as the `proofread.dictionary` library setting, so
+//! they sync with the library.
+
+pub mod hunspell;
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::find_replace::decode_entities;
+use crate::library::{LibraryError, LibrarySettings};
+use crate::python_bridge::ChatMessage;
+use crate::storage::{FileStorage, Page};
+
+pub use hunspell::Dictionary;
+
+/// Library setting holding the library's own dictionary words
+pub const DICTIONARY_SETTING: &str = "proofread.dictionary";
+
+/// Dictionary used when none is asked for
+pub const DEFAULT_LANGUAGE: &str = "en_US";
+
+/// Text sent for the grammar pass; fields past it aren't checked
+const MAX_GRAMMAR_CHARS: usize = 40_000;
+
+/// Longest word the library dictionary takes
+const MAX_WORD_LEN: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum ProofreadError {
+    #[error("Invalid dictionary word: {0:?}")]
+    InvalidWord(String),
+    #[error("AI reply is not valid proofreading JSON: {0}")]
+    BadReply(String),
+    #[error(transparent)]
+    Library(#[from] LibraryError),
+}
+
+/// Where in a page a problem is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextSpan {
+    pub block_id: String,
+    /// Path of the text field in the block's data
+    pub field: String,
+    /// UTF-16 offsets into the field's text content
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Misspelling {
+    pub span: TextSpan,
+    pub word: String,
+    /// Most likely first
+    pub suggestions: Vec<String>,
+}
+
+/// A grammar problem the AI found
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrammarSuggestion {
+    pub span: TextSpan,
+    /// The text with the problem
+    pub text: String,
+    pub issue: String,
+    pub replacement: String,
+}
+
+/// Result of proofreading a page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofreadReport {
+    pub page_id: Uuid,
+    /// Dictionary spelling was checked with; `None` when none is installed
+    /// for the language asked for
+    pub language: Option<String>,
+    pub misspellings: Vec<Misspelling>,
+    /// Whether the AI grammar pass ran
+    pub grammar_checked: bool,
+    pub grammar: Vec<GrammarSuggestion>,
+}
+
+/// The text content of one text field of a block
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextField {
+    pub block_id: String,
+    pub field: String,
+    pub text: String,
+    /// Byte ranges of `text` that aren't prose (code, links to pages)
+    skipped: Vec<Range<usize>>,
+    /// Byte offsets of line breaks, which have no text of their own
+    breaks: Vec<usize>,
+}
+
+impl TextField {
+    fn from_html(block_id: &str, field: String, html: &str) -> Option<Self> {
+        let mut out = TextField {
+            block_id: block_id.to_string(),
+            field,
+            text: String::new(),
+            skipped: Vec::new(),
+            breaks: Vec::new(),
+        };
+        // Open elements whose text isn't checked, and where the outermost
+        // one started
+        let mut skipping: Vec<String> = Vec::new();
+        let mut skip_start = 0;
+        let mut rest = html;
+        loop {
+            let next = rest.find('<').unwrap_or(rest.len());
+            out.text.push_str(&decode_entities(&rest[..next]));
+            rest = &rest[next..];
+            if rest.is_empty() {
+                break;
+            }
+            let Some(end) = rest.find('>') else {
+                out.text.push_str(&decode_entities(rest));
+                break;
+            };
+            let tag = &rest[..=end];
+            let name = tag
+                .trim_start_matches(['<', '/'])
+                .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            match name.as_str() {
+                "br" | "p" | "div" | "li" => out.breaks.push(out.text.len()),
+                "code" | "wiki-link" | "block-ref" if !tag.ends_with("/>") => {
+                    if !tag.starts_with("</") {
+                        if skipping.is_empty() {
+                            skip_start = out.text.len();
+                        }
+                        skipping.push(name);
+                    } else if let Some(open) = skipping.iter().rposition(|n| *n == name) {
+                        skipping.truncate(open);
+                        if skipping.is_empty() {
+                            out.skipped.push(skip_start..out.text.len());
+                        }
+                    }
+                }
+                _ => {}
+            }
+            rest = &rest[end + 1..];
+        }
+        if !skipping.is_empty() {
+            out.skipped.push(skip_start..out.text.len());
+        }
+        (!out.text.trim().is_empty()).then_some(out)
+    }
+
+    /// A span of this field from a byte range of its text
+    fn span(&self, range: &Range<usize>) -> TextSpan {
+        let utf16 = |byte: usize| self.text[..byte].encode_utf16().count();
+        TextSpan {
+            block_id: self.block_id.clone(),
+            field: self.field.clone(),
+            start: utf16(range.start),
+            end: utf16(range.end),
+        }
+    }
+
+    /// Byte ranges of the words to spell check
+    fn words(&self) -> Vec<Range<usize>> {
+        let mut chunks = Vec::new();
+        let mut start = None;
+        for (i, c) in self.text.char_indices() {
+            if self.breaks.contains(&i) {
+                if let Some(s) = start.take() {
+                    chunks.push(s..i);
+                }
+            }
+            if c.is_whitespace() {
+                if let Some(s) = start.take() {
+                    chunks.push(s..i);
+                }
+            } else if start.is_none() {
+                start = Some(i);
+            }
+        }
+        if let Some(s) = start {
+            chunks.push(s..self.text.len());
+        }
+
+        let is_word_char = |c: char| c.is_alphabetic() || c == '\'' || c == '\u{2019}';
+        let mut words = Vec::new();
+        for chunk in chunks {
+            let text = &self.text[chunk.clone()];
+            if text.contains("://")
+                || text.starts_with('#')
+                || text
+                    .chars()
+                    .any(|c| c.is_ascii_digit() || matches!(c, '@' | '_' | '/' | '\\'))
+            {
+                continue;
+            }
+            let mut run: Option<usize> = None;
+            for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+                if is_word_char(c) {
+                    run.get_or_insert(i);
+                    continue;
+                }
+                let Some(s) = run.take() else {
+                    continue;
+                };
+                let word = text[s..i].trim_matches(['\'', '\u{2019}']);
+                if word.is_empty() {
+                    continue;
+                }
+                let offset = chunk.start + s + text[s..i].find(word).unwrap_or(0);
+                words.push(offset..offset + word.len());
+            }
+        }
+        words.retain(|word| {
+            let text = &self.text[word.clone()];
+            text.chars().count() > 1
+                && !text.chars().skip(1).any(char::is_uppercase)
+                && !self
+                    .skipped
+                    .iter()
+                    .any(|s| s.start < word.end && word.start < s.end)
+        });
+        words
+    }
+}
+
+/// The text fields of a page's text blocks, in page order
+pub fn text_fields(page: &Page) -> Vec<TextField> {
+    let mut fields = Vec::new();
+    for block in &page.content.blocks {
+        let data = &block.data;
+        match block.block_type.as_str() {
+            "paragraph" | "header" | "quote" | "callout" => {
+                for key in ["text", "title", "caption"] {
+                    push_field(&mut fields, &block.id, key.to_string(), data.get(key));
+                }
+            }
+            "list" | "checklist" => {
+                if let Some(Value::Array(items)) = data.get("items") {
+                    list_fields(&mut fields, &block.id, "items", items);
+                }
+            }
+            "table" => {
+                let rows = data.get("content").and_then(|v| v.as_array());
+                for (r, row) in rows.into_iter().flatten().enumerate() {
+                    let cells = row.as_array().into_iter().flatten();
+                    for (c, cell) in cells.enumerate() {
+                        let path = format!("content/{}/{}", r, c);
+                        push_field(&mut fields, &block.id, path, Some(cell));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    fields
+}
+
+fn push_field(fields: &mut Vec<TextField>, block_id: &str, path: String, value: Option<&Value>) {
+    if let Some(field) = value
+        .and_then(|v| v.as_str())
+        .and_then(|html| TextField::from_html(block_id, path, html))
+    {
+        fields.push(field);
+    }
+}
+
+/// Items are strings, `{text}` (checklist) or `{content, items}` (nested list)
+fn list_fields(fields: &mut Vec<TextField>, block_id: &str, path: &str, items: &[Value]) {
+    for (i, item) in items.iter().enumerate() {
+        let item_path = format!("{}/{}", path, i);
+        if item.is_string() {
+            push_field(fields, block_id, item_path, Some(item));
+            continue;
+        }
+        for key in ["text", "content"] {
+            let path = format!("{}/{}", item_path, key);
+            push_field(fields, block_id, path, item.get(key));
+        }
+        if let Some(Value::Array(nested)) = item.get("items") {
+            list_fields(fields, block_id, &format!("{}/items", item_path), nested);
+        }
+    }
+}
+
+/// A dictionary together with the library's own words
+pub struct SpellChecker {
+    dictionary: Arc<Dictionary>,
+    custom: HashSet<String>,
+}
+
+impl SpellChecker {
+    pub fn new(dictionary: Arc<Dictionary>, custom_words: &[String]) -> Self {
+        Self {
+            dictionary,
+            custom: custom_words.iter().cloned().collect(),
+        }
+    }
+
+    fn known(&self, word: &str) -> bool {
+        self.dictionary.contains(word) || self.custom.contains(word)
+    }
+
+    /// Whether `word` is spelled right. A capitalised word is also right
+    /// when its lowercase form is, as at the start of a sentence.
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.replace('\u{2019}', "'");
+        let word = word.strip_suffix("'s").unwrap_or(&word);
+        self.known(word) || (starts_uppercase(word) && self.known(&word.to_lowercase()))
+    }
+
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let word = word.replace('\u{2019}', "'");
+        let known = |w: &str| self.known(w);
+        if !starts_uppercase(&word) {
+            return self.dictionary.suggest(&word, &known);
+        }
+        let mut suggestions = self.dictionary.suggest(&word, &known);
+        for lower in self.dictionary.suggest(&word.to_lowercase(), &known) {
+            let capitalised = capitalise(&lower);
+            if !suggestions.contains(&capitalised) {
+                suggestions.push(capitalised);
+            }
+        }
+        suggestions.truncate(hunspell::MAX_SUGGESTIONS);
+        suggestions
+    }
+
+    /// The misspelled words of `fields`
+    pub fn check(&self, fields: &[TextField]) -> Vec<Misspelling> {
+        let mut suggestions: HashMap<String, Vec<String>> = HashMap::new();
+        let mut misspellings = Vec::new();
+        for field in fields {
+            for range in field.words() {
+                let word = &field.text[range.clone()];
+                if self.is_correct(word) {
+                    continue;
+                }
+                let suggestions = suggestions
+                    .entry(word.to_string())
+                    .or_insert_with(|| self.suggest(word));
+                misspellings.push(Misspelling {
+                    span: field.span(&range),
+                    word: word.to_string(),
+                    suggestions: suggestions.clone(),
+                });
+            }
+        }
+        misspellings
+    }
+}
+
+fn starts_uppercase(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+}
+
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Folders dictionaries are looked for in, the app's own first
+pub fn dictionary_dirs() -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    if let Ok(data_dir) = FileStorage::default_data_dir() {
+        found.push(data_dir.join("dictionaries"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        found.push(home.join("Library/Spelling"));
+        found.push(home.join(".local/share/hunspell"));
+    }
+    for dir in [
+        "/Library/Spelling",
+        "/usr/share/hunspell",
+        "/usr/local/share/hunspell",
+        "/usr/share/myspell",
+        "/usr/share/myspell/dicts",
+    ] {
+        found.push(PathBuf::from(dir));
+    }
+    found
+}
+
+/// Languages with an installed dictionary (`en_US`, `de_DE`, ...)
+pub fn available_languages() -> Vec<String> {
+    let mut languages: Vec<String> = dictionary_dirs()
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "dic" || !path.with_extension("aff").is_file() {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().to_string())
+        })
+        .collect();
+    languages.sort();
+    languages.dedup();
+    languages
+}
+
+/// The dictionary for `language` (`en_US`, `en-US`, or just `en` for the
+/// first English one), loaded on first use and kept for the process
+pub fn load_dictionary(language: &str) -> Option<(String, Arc<Dictionary>)> {
+    static LOADED: OnceLock<Mutex<HashMap<PathBuf, Arc<Dictionary>>>> = OnceLock::new();
+
+    let language = language.trim().replace('-', "_");
+    let dic_path = dictionary_dirs().into_iter().find_map(|dir| {
+        let exact = dir.join(format!("{}.dic", language));
+        if exact.is_file() {
+            return Some(exact);
+        }
+        if language.contains('_') {
+            return None;
+        }
+        let prefix = format!("{}_", language);
+        let mut regional: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "dic")
+                    && path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+            })
+            .collect();
+        regional.sort();
+        regional.into_iter().next()
+    })?;
+    let name = dic_path.file_stem()?.to_string_lossy().to_string();
+
+    let mut loaded = LOADED.get_or_init(Default::default).lock().unwrap();
+    if let Some(dictionary) = loaded.get(&dic_path) {
+        return Some((name, Arc::clone(dictionary)));
+    }
+    match Dictionary::load(&dic_path.with_extension("aff"), &dic_path) {
+        Ok(dictionary) => {
+            log::info!(
+                "Loaded {} dictionary: {} words from {}",
+                name,
+                dictionary.len(),
+                dic_path.display()
+            );
+            let dictionary = Arc::new(dictionary);
+            loaded.insert(dic_path, Arc::clone(&dictionary));
+            Some((name, dictionary))
+        }
+        Err(e) => {
+            log::warn!("Failed to load dictionary {}: {}", dic_path.display(), e);
+            None
+        }
+    }
+}
+
+/// The words of the library at `library_path`'s dictionary, sorted. An
+/// unreadable setting is logged and treated as empty.
+pub fn custom_words(library_path: &Path) -> Vec<String> {
+    let settings = LibrarySettings::load(library_path);
+    match settings.get(DICTIONARY_SETTING) {
+        Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable {} setting: {}", DICTIONARY_SETTING, e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    }
+}
+
+/// Add a word to the library's dictionary; returns the new word list
+pub fn add_custom_word(library_path: &Path, word: &str) -> Result<Vec<String>, ProofreadError> {
+    let word = word.trim();
+    if word.is_empty() || word.len() > MAX_WORD_LEN || word.contains(char::is_whitespace) {
+        return Err(ProofreadError::InvalidWord(word.to_string()));
+    }
+    let mut words = custom_words(library_path);
+    if let Err(at) = words.binary_search_by(|w| w.as_str().cmp(word)) {
+        words.insert(at, word.to_string());
+        write_custom_words(library_path, &words)?;
+    }
+    Ok(words)
+}
+
+/// Remove a word from the library's dictionary; returns the new word list
+pub fn remove_custom_word(library_path: &Path, word: &str) -> Result<Vec<String>, ProofreadError> {
+    let mut words = custom_words(library_path);
+    let before = words.len();
+    words.retain(|w| w != word.trim());
+    if words.len() != before {
+        write_custom_words(library_path, &words)?;
+    }
+    Ok(words)
+}
+
+fn write_custom_words(library_path: &Path, words: &[String]) -> Result<(), LibraryError> {
+    let mut settings = LibrarySettings::load(library_path);
+    settings.set(DICTIONARY_SETTING, serde_json::to_value(words)?);
+    settings.save()
+}
+
+/// The AI request for a grammar pass over `fields`, each sent as a
+/// numbered line
+pub fn grammar_messages(fields: &[TextField], language: &str) -> Vec<ChatMessage> {
+    let mut content = String::new();
+    for (i, field) in fields.iter().enumerate() {
+        if content.len() + field.text.len() > MAX_GRAMMAR_CHARS {
+            break;
+        }
+        content.push_str(&format!("[{}] {}\n", i, field.text.replace('\n', " ")));
+    }
+    let system = format!(
+        "You proofread text written in {}. Each numbered line is a separate \
+         piece of text. Find mistakes of grammar, punctuation and word choice; \
+         spelling is checked separately, so leave it out unless a real word is \
+         used wrongly (\"their\" for \"there\"). Reply with JSON only, in the \
+         form {{\"suggestions\": [{{\"field\": <line number>, \"text\": \
+         \"<the words with the mistake, copied exactly from the line>\", \
+         \"issue\": \"<short explanation>\", \"replacement\": \"<the corrected \
+         words>\"}}]}}. Keep each text as short as possible, and reply \
+         {{\"suggestions\": []}} when there's nothing to fix.",
+        language
+    );
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: system,
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content,
+        },
+    ]
+}
+
+#[derive(Deserialize)]
+struct GrammarReply {
+    #[serde(default)]
+    suggestions: Vec<ReplySuggestion>,
+}
+
+#[derive(Deserialize)]
+struct ReplySuggestion {
+    field: usize,
+    text: String,
+    #[serde(default)]
+    issue: String,
+    #[serde(default)]
+    replacement: String,
+}
+
+/// Read the AI's grammar suggestions, placing each where its text is in
+/// the field it names. Suggestions whose text isn't there are dropped.
+pub fn parse_grammar(
+    reply: &str,
+    fields: &[TextField],
+) -> Result<Vec<GrammarSuggestion>, ProofreadError> {
+    // Models wrap JSON in fences or add a sentence around it; take the
+    // outermost object
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err(ProofreadError::BadReply("no JSON object".to_string())),
+    };
+    let reply: GrammarReply =
+        serde_json::from_str(json).map_err(|e| ProofreadError::BadReply(e.to_string()))?;
+
+    let mut placed: HashSet<(usize, usize)> = HashSet::new();
+    let mut suggestions = Vec::new();
+    for suggestion in reply.suggestions {
+        let Some(field) = fields.get(suggestion.field) else {
+            continue;
+        };
+        if suggestion.text.is_empty() || suggestion.text == suggestion.replacement {
+            continue;
+        }
+        let Some(start) = field
+            .text
+            .match_indices(&suggestion.text)
+            .map(|(start, _)| start)
+            .find(|start| !placed.contains(&(suggestion.field, *start)))
+        else {
+            continue;
+        };
+        placed.insert((suggestion.field, start));
+        suggestions.push(GrammarSuggestion {
+            span: field.span(&(start..start + suggestion.text.len())),
+            text: suggestion.text,
+            issue: suggestion.issue.trim().to_string(),
+            replacement: suggestion.replacement,
+        });
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EditorBlock;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn block(id: &str, block_type: &str, data: Value) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn checker(custom: &[&str]) -> SpellChecker {
+        let dic = "10\nsee\nthe\nquick\nbrown\nfox\njumps\nover\nlazy\ndog\nnotes\n";
+        let custom: Vec<String> = custom.iter().map(|w| w.to_string()).collect();
+        SpellChecker::new(Arc::new(Dictionary::parse("", dic)), &custom)
+    }
+
+    #[test]
+    fn misspellings_are_placed_in_their_fields() {
+        let mut page = Page::new(Uuid::new_v4(), "Draft".to_string());
+        page.content.blocks = vec![
+            block(
+                "p1",
+                "paragraph",
+                json!({"text": "🦊 Teh <b>quick</b>&nbsp;brwn fox &amp; <code>fn jmups</code> \
+                                <wiki-link data-page-title=\"Ovr\">Ovr</wiki-link> NASA iPhone"}),
+            ),
+            block(
+                "l1",
+                "list",
+                json!({"items": [
+                    {"content": "the dog", "items": [{"content": "lazzy dog", "items": []}]},
+                ]}),
+            ),
+            block(
+                "t1",
+                "table",
+                json!({"content": [["see https://exmple.com", "notes<br>dogg"]]}),
+            ),
+            block("c1", "code", json!({"code": "lettt x = 1"})),
+        ];
+
+        let fields = text_fields(&page);
+        let paths: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "text",
+                "items/0/content",
+                "items/0/items/0/content",
+                "content/0/0",
+                "content/0/1"
+            ]
+        );
+
+        let misspellings = checker(&[]).check(&fields);
+        let found: Vec<(&str, &str, usize, usize)> = misspellings
+            .iter()
+            .map(|m| {
+                (
+                    m.span.field.as_str(),
+                    m.word.as_str(),
+                    m.span.start,
+                    m.span.end,
+                )
+            })
+            .collect();
+        // The fox is two UTF-16 units; "Teh" is capitalised but unknown
+        // either way
+        assert_eq!(
+            found,
+            [
+                ("text", "Teh", 3, 6),
+                ("text", "brwn", 13, 17),
+                ("items/0/items/0/content", "lazzy", 0, 5),
+                ("content/0/1", "dogg", 5, 9),
+            ]
+        );
+        assert_eq!(misspellings[1].suggestions, ["brown"]);
+        assert_eq!(misspellings[0].suggestions, ["The"]);
+    }
+
+    #[test]
+    fn library_words_are_known_and_stored_sorted() {
+        let dir = TempDir::new().unwrap();
+        assert!(custom_words(dir.path()).is_empty());
+        add_custom_word(dir.path(), "Nous").unwrap();
+        add_custom_word(dir.path(), " backlinks ").unwrap();
+        let words = add_custom_word(dir.path(), "Nous").unwrap();
+        assert_eq!(words, ["Nous", "backlinks"]);
+        assert!(add_custom_word(dir.path(), "two words").is_err());
+
+        let checker = checker(&["Nous", "backlinks"]);
+        assert!(checker.is_correct("Backlinks"));
+        assert!(checker.is_correct("Nous's"));
+        assert!(!checker.is_correct("nous"));
+
+        let words = remove_custom_word(dir.path(), "Nous").unwrap();
+        assert_eq!(words, ["backlinks"]);
+        assert_eq!(custom_words(dir.path()), ["backlinks"]);
+    }
+
+    #[test]
+    fn grammar_suggestions_are_placed_by_their_text() {
+        let fields = vec![TextField::from_html(
+            "p1",
+            "text".to_string(),
+            "It’s <i>they're</i> car. Its fine",
+        )
+        .unwrap()];
+        let reply = r#"Here you go:
+```json
+{"suggestions": [
+  {"field": 0, "text": "they're car", "issue": "Wrong word", "replacement": "their car"},
+  {"field": 0, "text": "Its fine", "issue": "Missing apostrophe", "replacement": "It's fine"},
+  {"field": 0, "text": "not in the text", "issue": "?", "replacement": "x"},
+  {"field": 3, "text": "car", "issue": "?", "replacement": "x"}
+]}
+```"#;
+        let suggestions = parse_grammar(reply, &fields).unwrap();
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(
+            suggestions[0].span,
+            TextSpan {
+                block_id: "p1".to_string(),
+                field: "text".to_string(),
+                start: 5,
+                end: 16,
+            }
+        );
+        assert_eq!(suggestions[1].replacement, "It's fine");
+        assert_eq!(
+            (suggestions[1].span.start, suggestions[1].span.end),
+            (18, 26)
+        );
+        assert!(parse_grammar("no idea", &fields).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use regex::Regex;
 use std::collections::HashMap;
 
+use crate::charts::CHART_BLOCK;
+use crate::markdown::math::{self, MATH_BLOCK};
 use crate::storage::{EditorBlock, Page};
 
 /// Convert a page to an HTML content fragment (no wrapping html/body tags).
@@ -41,6 +43,8 @@ pub fn render_block(
         "code" => render_code(block),
         "mermaid" => render_mermaid(block),
         "animation" => render_animation(block),
+        MATH_BLOCK => render_math(block),
+        CHART_BLOCK => render_chart(block),
         "quote" => render_quote(block, page_slugs, block_texts),
         "delimiter" => "<hr>".to_string(),
         "table" => render_table(block, page_slugs, block_texts),
@@ -201,6 +205,109 @@ pub fn blocks_have_mermaid(blocks: &[EditorBlock]) -> bool {
     blocks.iter().any(block_is_mermaid)
 }
 
+fn math_source(block: &EditorBlock) -> &str {
+    block
+        .data
+        .get("latex")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .trim()
+}
+
+/// Render a display formula as escaped LaTeX between `\[ \]`; a theme that
+/// opts in injects KaTeX, which typesets it. Without KaTeX the source shows.
+fn render_math(block: &EditorBlock) -> String {
+    let latex = math_source(block);
+    if latex.is_empty() {
+        return String::new();
+    }
+    format!("<div class=\"math\">\\[{}\\]</div>", html_escape(latex))
+}
+
+/// Render a chart block from the SVG `charts::with_rendered_charts` stored
+/// on it, or the reason it couldn't be drawn. Pages that weren't passed
+/// through it get nothing.
+fn render_chart(block: &EditorBlock) -> String {
+    let str_field = |key: &str| {
+        block
+            .data
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    };
+    let caption = match str_field("title") {
+        "" => String::new(),
+        title => format!("<figcaption>{}</figcaption>", html_escape(title)),
+    };
+    match (str_field("svg"), str_field("chartError")) {
+        ("", "") => String::new(),
+        ("", error) => format!(
+            "<p class=\"chart-error\">Chart unavailable: {}</p>",
+            html_escape(error)
+        ),
+        (svg, _) => format!("<figure class=\"chart\">{}{}</figure>", svg, caption),
+    }
+}
+
+/// Wrap the inline `$...$` formulas in the text of an HTML fragment as
+/// `\( \)` spans for KaTeX. Tags and the text of `<code>` are left alone.
+fn render_inline_math(html: &str) -> String {
+    fn push_text(out: &mut String, text: &str, in_code: bool) {
+        if in_code {
+            out.push_str(text);
+            return;
+        }
+        let mut from = 0;
+        for span in math::inline_math_spans(text) {
+            out.push_str(&text[from..span.start]);
+            out.push_str("<span class=\"math\">\\(");
+            out.push_str(&text[span.start + 1..span.end - 1]);
+            out.push_str("\\)</span>");
+            from = span.end;
+        }
+        out.push_str(&text[from..]);
+    }
+
+    if !html.contains('$') {
+        return html.to_string();
+    }
+    let tag_re = Regex::new(r"<[^>]*>").unwrap();
+    let mut out = String::with_capacity(html.len());
+    let mut in_code = 0usize;
+    let mut last = 0;
+    for tag in tag_re.find_iter(html) {
+        push_text(&mut out, &html[last..tag.start()], in_code > 0);
+        let name = tag.as_str().to_ascii_lowercase();
+        if name.starts_with("<code") || name.starts_with("<pre") {
+            in_code += 1;
+        } else if name.starts_with("</code") || name.starts_with("</pre") {
+            in_code = in_code.saturating_sub(1);
+        }
+        out.push_str(tag.as_str());
+        last = tag.end();
+    }
+    push_text(&mut out, &html[last..], in_code > 0);
+    out
+}
+
+/// True if any block on the page has math — a display formula or an inline
+/// `$...$` in its text — so the page needs KaTeX injected into its head.
+pub fn blocks_have_math(blocks: &[EditorBlock]) -> bool {
+    fn has_inline(value: &serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::String(s) => !math::inline_math_spans(s).is_empty(),
+            serde_json::Value::Array(items) => items.iter().any(has_inline),
+            serde_json::Value::Object(map) => map.values().any(has_inline),
+            _ => false,
+        }
+    }
+    blocks.iter().any(|block| match block.block_type.as_str() {
+        MATH_BLOCK => !math_source(block).is_empty(),
+        "code" | "mermaid" | "animation" | CHART_BLOCK => false,
+        _ => has_inline(&block.data),
+    })
+}
+
 /// CSP for the sandboxed animation document. `default-src 'none'` +
 /// `connect-src 'none'` kill fetch/XHR/WebSocket; assets must be inlined as
 /// `data:`/`blob:` URIs. Only inline `<style>`/`<script>` may execute.
@@ -581,7 +688,7 @@ fn resolve_custom_elements(
         })
         .to_string();
 
-    result
+    render_inline_math(&result)
 }
 
 /// Extract the original filename from an asset URL and return an assets/-relative path.
@@ -672,6 +779,7 @@ pub fn block_plain_text(block: &EditorBlock) -> String {
             .and_then(|v| v.as_str())
             .unwrap_or_default()
             .to_string(),
+        MATH_BLOCK => math_source(block).to_string(),
         _ => String::new(),
     };
     strip_html_tags(&raw)
@@ -890,4 +998,50 @@ mod tests {
         assert!(!blocks_have_animation(&[block("animation", json!({}))]));
         assert!(!blocks_have_animation(&[block("paragraph", json!({ "text": "hi" }))]));
     }
+
+    #[test]
+    fn math_block_renders_escaped_display_latex() {
+        let html = render(&block("math", json!({ "latex": "a < b" })));
+        assert_eq!(html, "<div class=\"math\">\\[a &lt; b\\]</div>");
+        assert_eq!(render(&block("math", json!({ "latex": " " }))), "");
+    }
+
+    #[test]
+    fn inline_math_is_wrapped_outside_code() {
+        let html = render(&block(
+            "paragraph",
+            json!({ "text": "Area $\\pi r^2$ for $5, not <code>$x$</code>" }),
+        ));
+        assert!(
+            html.contains("Area <span class=\"math\">\\(\\pi r^2\\)</span> for $5"),
+            "got: {html}"
+        );
+        assert!(html.contains("<code>$x$</code>"), "got: {html}");
+    }
+
+    #[test]
+    fn blocks_have_math_detects_blocks_and_inline_formulas() {
+        assert!(blocks_have_math(&[block("math", json!({ "latex": "x^2" }))]));
+        assert!(blocks_have_math(&[block("paragraph", json!({ "text": "so $x^2$" }))]));
+        assert!(!blocks_have_math(&[block("paragraph", json!({ "text": "$5 and $10" }))]));
+        assert!(!blocks_have_math(&[block("code", json!({ "code": "echo $a$" }))]));
+    }
+
+    #[test]
+    fn chart_block_renders_stored_svg_or_error() {
+        let html = render(&block(
+            "chart",
+            json!({ "title": "Q&A", "svg": "<svg></svg>" }),
+        ));
+        assert_eq!(
+            html,
+            "<figure class=\"chart\"><svg></svg><figcaption>Q&amp;A</figcaption></figure>"
+        );
+        let html = render(&block(
+            "chart",
+            json!({ "chartError": "No column named \"x\"" }),
+        ));
+        assert!(html.starts_with("<p class=\"chart-error\">"), "got: {html}");
+        assert_eq!(render(&block("chart", json!({}))), "");
+    }
 }
@@ -1,4 +1,6 @@
 pub mod html;
+pub mod podcast;
+pub mod pptx;
 pub mod presentation;
 pub mod print;
 pub mod site;
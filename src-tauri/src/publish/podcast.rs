@@ -0,0 +1,330 @@
+//! Private podcast feeds of generated audio
+//!
+//! Page narrations and reading queues are written to each notebook's
+//! `assets/audio` directory. Each generator leaves a small JSON sidecar next
+//! to the MP3 with the episode's title; files without one (narrations made
+//! before sidecars existed) are titled after their file name. `build_feed`
+//! turns the episodes into an RSS 2.0 feed with the iTunes tags podcast apps
+//! expect, marked `itunes:block` so directories never list it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::storage::atomic;
+
+/// What a generator knows about an audio file, stored next to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeMeta {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub page_id: Option<Uuid>,
+    pub duration_seconds: f64,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A generated audio file in a feed
+#[derive(Debug, Clone)]
+pub struct Episode {
+    pub notebook_id: Uuid,
+    pub path: PathBuf,
+    pub file_name: String,
+    pub title: String,
+    pub description: String,
+    pub size: u64,
+    pub duration_seconds: Option<f64>,
+    pub published_at: DateTime<Utc>,
+}
+
+impl Episode {
+    /// Stable across feed rebuilds, so apps don't download episodes twice
+    pub fn guid(&self) -> String {
+        format!("{}/{}", self.notebook_id, self.file_name)
+    }
+}
+
+/// Channel-level details of a feed
+#[derive(Debug, Clone)]
+pub struct FeedChannel {
+    pub title: String,
+    pub description: String,
+    pub link: String,
+}
+
+fn meta_path(audio_path: &Path) -> PathBuf {
+    audio_path.with_extension("json")
+}
+
+/// Record `meta` for the audio file at `audio_path`
+pub fn write_episode_meta(audio_path: &Path, meta: &EpisodeMeta) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(meta).map_err(io::Error::other)?;
+    atomic::write_str(&meta_path(audio_path), &json)
+}
+
+fn read_episode_meta(audio_path: &Path) -> Option<EpisodeMeta> {
+    let content = fs::read_to_string(meta_path(audio_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Whether `file_name` is audio the app generated: a page narration or a
+/// reading queue, not a recording or an unfinished file
+fn is_generated_audio(file_name: &str) -> bool {
+    file_name.ends_with(".mp3")
+        && !file_name.ends_with(".partial.mp3")
+        && (file_name.starts_with("audio_") || file_name.starts_with("reading_queue_"))
+}
+
+/// The generated audio in a notebook's `audio_dir`, newest first
+pub fn collect_episodes(audio_dir: &Path, notebook_id: Uuid) -> Vec<Episode> {
+    let Ok(entries) = fs::read_dir(audio_dir) else {
+        return Vec::new();
+    };
+    let mut episodes: Vec<Episode> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if !is_generated_audio(&file_name) {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let path = entry.path();
+            let modified: DateTime<Utc> = metadata.modified().ok()?.into();
+            let episode = match read_episode_meta(&path) {
+                Some(meta) => Episode {
+                    notebook_id,
+                    file_name,
+                    title: meta.title,
+                    description: meta.description,
+                    size: metadata.len(),
+                    duration_seconds: Some(meta.duration_seconds).filter(|d| *d > 0.0),
+                    published_at: meta.generated_at,
+                    path,
+                },
+                None => Episode {
+                    notebook_id,
+                    title: format!("Narration {}", modified.format("%Y-%m-%d %H:%M")),
+                    file_name,
+                    description: String::new(),
+                    size: metadata.len(),
+                    duration_seconds: None,
+                    published_at: modified,
+                    path,
+                },
+            };
+            Some(episode)
+        })
+        .collect();
+    episodes.sort_by_key(|e| std::cmp::Reverse(e.published_at));
+    episodes
+}
+
+/// An RSS feed of `episodes`, with enclosure URLs from `enclosure_url`
+pub fn build_feed(
+    channel: &FeedChannel,
+    episodes: &[Episode],
+    enclosure_url: impl Fn(&Episode) -> String,
+) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <rss version=\"2.0\" xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\">\n\
+         <channel>\n",
+    );
+    xml.push_str(&format!("<title>{}</title>\n", xml_escape(&channel.title)));
+    xml.push_str(&format!(
+        "<description>{}</description>\n",
+        xml_escape(&channel.description)
+    ));
+    xml.push_str(&format!("<link>{}</link>\n", xml_escape(&channel.link)));
+    xml.push_str("<itunes:block>Yes</itunes:block>\n");
+    if let Some(latest) = episodes.iter().map(|e| e.published_at).max() {
+        xml.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>\n",
+            latest.to_rfc2822()
+        ));
+    }
+
+    for episode in episodes {
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&episode.title)));
+        if !episode.description.is_empty() {
+            xml.push_str(&format!(
+                "<description>{}</description>\n",
+                xml_escape(&episode.description)
+            ));
+        }
+        xml.push_str(&format!(
+            "<guid isPermaLink=\"false\">{}</guid>\n",
+            xml_escape(&episode.guid())
+        ));
+        xml.push_str(&format!(
+            "<pubDate>{}</pubDate>\n",
+            episode.published_at.to_rfc2822()
+        ));
+        xml.push_str(&format!(
+            "<enclosure url=\"{}\" length=\"{}\" type=\"audio/mpeg\"/>\n",
+            xml_escape(&enclosure_url(episode)),
+            episode.size
+        ));
+        if let Some(duration) = episode.duration_seconds {
+            xml.push_str(&format!(
+                "<itunes:duration>{}</itunes:duration>\n",
+                duration.round() as u64
+            ));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+/// Write `feed.xml` and copies of the episodes (under `episodes/`) to
+/// `out_dir`, for uploading to `base_url`. Episodes already copied are left
+/// alone. Returns the path of the feed.
+pub fn export_feed(
+    channel: &FeedChannel,
+    episodes: &[Episode],
+    out_dir: &Path,
+    base_url: &str,
+) -> io::Result<PathBuf> {
+    let episodes_dir = out_dir.join("episodes");
+    fs::create_dir_all(&episodes_dir)?;
+    for episode in episodes {
+        let dest = episodes_dir.join(export_name(episode));
+        let copied = fs::metadata(&dest).is_ok_and(|m| m.len() == episode.size);
+        if !copied {
+            fs::copy(&episode.path, &dest)?;
+        }
+    }
+
+    let base_url = base_url.trim_end_matches('/');
+    let xml = build_feed(channel, episodes, |episode| {
+        format!("{}/episodes/{}", base_url, export_name(episode))
+    });
+    let feed_path = out_dir.join("feed.xml");
+    atomic::write_str(&feed_path, &xml)?;
+    Ok(feed_path)
+}
+
+/// Narration file names are only unique within a notebook
+fn export_name(episode: &Episode) -> String {
+    format!("{}_{}", episode.notebook_id, episode.file_name)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn collects_generated_audio_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path();
+        fs::write(audio.join("audio_1.mp3"), b"narration").unwrap();
+        fs::write(audio.join("reading_queue_q.mp3"), b"queue").unwrap();
+        fs::write(audio.join("reading_queue_q.partial.mp3"), b"half").unwrap();
+        fs::write(audio.join("recording_1.webm"), b"mic").unwrap();
+        write_episode_meta(
+            &audio.join("reading_queue_q.mp3"),
+            &EpisodeMeta {
+                title: "Morning queue".to_string(),
+                description: "Intro\nPlan".to_string(),
+                page_id: None,
+                duration_seconds: 95.0,
+                generated_at: Utc::now() + chrono::Duration::hours(1),
+            },
+        )
+        .unwrap();
+
+        let notebook_id = Uuid::new_v4();
+        let episodes = collect_episodes(audio, notebook_id);
+        let names: Vec<&str> = episodes.iter().map(|e| e.file_name.as_str()).collect();
+        assert_eq!(names, vec!["reading_queue_q.mp3", "audio_1.mp3"]);
+        assert_eq!(episodes[0].title, "Morning queue");
+        assert_eq!(episodes[0].duration_seconds, Some(95.0));
+        assert!(episodes[1].title.starts_with("Narration "));
+        assert_eq!(episodes[1].size, 9);
+        assert!(collect_episodes(&audio.join("missing"), notebook_id).is_empty());
+    }
+
+    #[test]
+    fn feed_escapes_text_and_lists_enclosures() {
+        let episode = Episode {
+            notebook_id: Uuid::nil(),
+            path: PathBuf::from("/lib/audio_1.mp3"),
+            file_name: "audio_1.mp3".to_string(),
+            title: "Q&A <draft>".to_string(),
+            description: String::new(),
+            size: 1234,
+            duration_seconds: Some(61.6),
+            published_at: Utc.with_ymd_and_hms(2026, 3, 1, 9, 30, 0).unwrap(),
+        };
+        let channel = FeedChannel {
+            title: "Nous audio".to_string(),
+            description: "Generated narrations".to_string(),
+            link: "http://127.0.0.1:1234/feed.xml".to_string(),
+        };
+        let xml = build_feed(&channel, &[episode], |e| {
+            format!("http://host/audio?path={}&token=t", e.file_name)
+        });
+
+        assert!(xml.contains("<title>Q&amp;A &lt;draft&gt;</title>"));
+        assert!(xml.contains(
+            "<enclosure url=\"http://host/audio?path=audio_1.mp3&amp;token=t\" length=\"1234\" type=\"audio/mpeg\"/>"
+        ));
+        assert!(xml.contains("<itunes:duration>62</itunes:duration>"));
+        assert!(xml.contains("<pubDate>Sun, 1 Mar 2026 09:30:00 +0000</pubDate>"));
+        assert!(xml.contains(&format!(
+            "<guid isPermaLink=\"false\">{}/audio_1.mp3</guid>",
+            Uuid::nil()
+        )));
+        assert!(xml.contains("<itunes:block>Yes</itunes:block>"));
+    }
+
+    #[test]
+    fn export_copies_episodes_next_to_feed() {
+        let library = tempfile::tempdir().unwrap();
+        fs::write(library.path().join("audio_1.mp3"), b"narration").unwrap();
+        let notebook_id = Uuid::new_v4();
+        let episodes = collect_episodes(library.path(), notebook_id);
+        let channel = FeedChannel {
+            title: "Nous audio".to_string(),
+            description: String::new(),
+            link: "https://example.com/audio/feed.xml".to_string(),
+        };
+
+        let out = tempfile::tempdir().unwrap();
+        let feed_path = export_feed(
+            &channel,
+            &episodes,
+            out.path(),
+            "https://example.com/audio/",
+        )
+        .unwrap();
+        let name = format!("{}_audio_1.mp3", notebook_id);
+        assert_eq!(
+            fs::read(out.path().join("episodes").join(&name)).unwrap(),
+            b"narration"
+        );
+        let xml = fs::read_to_string(feed_path).unwrap();
+        assert!(xml.contains(&format!(
+            "url=\"https://example.com/audio/episodes/{}\"",
+            name
+        )));
+    }
+}
@@ -0,0 +1,1025 @@
+//! PowerPoint export of a page presentation
+//!
+//! Slides are split like the Reveal.js presentation: every H1/H2 starts a
+//! new slide and its text becomes the slide title. The other blocks become
+//! bullets, and the speaker notes hold either the slide's full text or
+//! AI-written talking points.
+//!
+//! Changes made to slides in the export dialog are not written back to the
+//! page. They are kept in a sidecar file next to the notebook's assets,
+//! keyed by the block that starts each slide, so exporting again after the
+//! page changed keeps them on the slides that still exist.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::python_bridge::ChatMessage;
+use crate::storage::{EditorBlock, Page};
+
+use super::html::block_plain_text;
+use super::presentation::split_into_slides;
+
+/// 16:9 slide, in EMU
+const SLIDE_WIDTH: i64 = 12_192_000;
+const SLIDE_HEIGHT: i64 = 6_858_000;
+
+/// Colors and font of an exported deck. The names match the Reveal.js
+/// themes offered for the HTML presentation.
+#[derive(Debug, Clone, Copy)]
+pub struct PptxTheme {
+    pub name: &'static str,
+    pub background: &'static str,
+    pub title: &'static str,
+    pub text: &'static str,
+    pub accent: &'static str,
+    pub font: &'static str,
+}
+
+pub const PPTX_THEMES: &[PptxTheme] = &[
+    PptxTheme {
+        name: "white",
+        background: "FFFFFF",
+        title: "222222",
+        text: "333333",
+        accent: "2A76DD",
+        font: "Calibri",
+    },
+    PptxTheme {
+        name: "black",
+        background: "191919",
+        title: "FFFFFF",
+        text: "EEEEEE",
+        accent: "42AFFA",
+        font: "Calibri",
+    },
+    PptxTheme {
+        name: "moon",
+        background: "002B36",
+        title: "EEE8D5",
+        text: "93A1A1",
+        accent: "268BD2",
+        font: "Calibri",
+    },
+    PptxTheme {
+        name: "solarized",
+        background: "FDF6E3",
+        title: "586E75",
+        text: "657B83",
+        accent: "268BD2",
+        font: "Calibri",
+    },
+    PptxTheme {
+        name: "dracula",
+        background: "282A36",
+        title: "BD93F9",
+        text: "F8F8F2",
+        accent: "FF79C6",
+        font: "Calibri",
+    },
+];
+
+/// The theme called `name`, or the first one
+pub fn theme(name: &str) -> &'static PptxTheme {
+    PPTX_THEMES
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+        .unwrap_or(&PPTX_THEMES[0])
+}
+
+/// Slide layout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PptxTemplate {
+    /// Title above a bulleted body
+    #[default]
+    Standard,
+    /// Like standard, with an accent bar down the left edge
+    AccentBar,
+    /// Large centered titles; slides without bullets become section dividers
+    Sections,
+}
+
+/// Where speaker notes come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotesSource {
+    None,
+    /// The full text of the slide's blocks
+    #[default]
+    Content,
+    /// Talking points written by the AI
+    Ai,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PptxOptions {
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default)]
+    pub template: PptxTemplate,
+    #[serde(default)]
+    pub notes: NotesSource,
+}
+
+fn default_theme() -> String {
+    "white".to_string()
+}
+
+/// A slide as it will be exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PptxSlide {
+    /// Id of the block that starts the slide, which identifies it across
+    /// exports
+    pub key: String,
+    pub title: String,
+    pub bullets: Vec<String>,
+    pub notes: String,
+    /// Left out of the export
+    #[serde(default)]
+    pub hidden: bool,
+    /// The manual change applied to the slide, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit: Option<SlideEdit>,
+}
+
+/// A manual change to one slide; unset fields keep the page's version
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideEdit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bullets: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// AI talking points for a slide, with a hash of the text they were written
+/// from so they are written again once the slide changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedNotes {
+    pub source_hash: String,
+    pub notes: String,
+}
+
+/// Per-page sidecar with manual slide edits and cached talking points
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlideSidecar {
+    #[serde(default)]
+    pub edits: HashMap<String, SlideEdit>,
+    #[serde(default)]
+    pub ai_notes: HashMap<String, GeneratedNotes>,
+}
+
+/// `{assets}/presentations/{page_id}.pptx.json`
+pub fn sidecar_path(assets_dir: &Path, page_id: uuid::Uuid) -> PathBuf {
+    assets_dir
+        .join("presentations")
+        .join(format!("{}.pptx.json", page_id))
+}
+
+pub fn load_sidecar(path: &Path) -> SlideSidecar {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| {
+            serde_json::from_str(&content)
+                .map_err(|e| log::warn!("Ignoring unreadable slide edits {:?}: {}", path, e))
+                .ok()
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_sidecar(path: &Path, sidecar: &SlideSidecar) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(sidecar)?)
+}
+
+/// Bullet lines of a block shown on a slide
+fn block_bullets(block: &EditorBlock) -> Vec<String> {
+    match block.block_type.as_str() {
+        "list" | "checklist" => {
+            let mut out = Vec::new();
+            collect_items(block.data.get("items"), &mut out);
+            out
+        }
+        "paragraph" | "quote" | "callout" => {
+            let text = clean(&block_plain_text(block));
+            if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![text]
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// List item texts, nested items included
+fn collect_items(items: Option<&serde_json::Value>, out: &mut Vec<String>) {
+    for item in items.and_then(|v| v.as_array()).into_iter().flatten() {
+        let text = match item {
+            serde_json::Value::String(s) => s.clone(),
+            _ => item
+                .get("content")
+                .or_else(|| item.get("text"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        };
+        let text = clean(&strip_tags(&text));
+        if !text.is_empty() {
+            out.push(text);
+        }
+        collect_items(item.get("items"), out);
+    }
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Decode the entities the editor writes and collapse whitespace
+fn clean(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The page's slides, before any manual edits, with its text as notes
+pub fn page_slides(page: &Page) -> Vec<PptxSlide> {
+    split_into_slides(&page.content.blocks, &page.title)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (title, blocks))| {
+            let key = blocks
+                .first()
+                .map(|b| b.id.clone())
+                .unwrap_or_else(|| format!("slide-{}", index));
+            // The header that starts a slide is its title, not a bullet
+            let skip = usize::from(blocks.first().is_some_and(|b| b.block_type == "header"));
+            let body = &blocks[skip..];
+            let bullets = body.iter().flat_map(|b| block_bullets(b)).collect();
+            let notes = body
+                .iter()
+                .map(|b| {
+                    let bullets = block_bullets(b);
+                    if bullets.is_empty() {
+                        clean(&block_plain_text(b))
+                    } else {
+                        bullets.join("\n")
+                    }
+                })
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            PptxSlide {
+                key,
+                title: clean(&strip_tags(&title.unwrap_or_else(|| page.title.clone()))),
+                bullets,
+                notes,
+                hidden: false,
+                edit: None,
+            }
+        })
+        .collect()
+}
+
+/// Hash of what a slide says, for telling whether its talking points are stale
+pub fn slide_hash(slide: &PptxSlide) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(slide.title.as_bytes());
+    for bullet in &slide.bullets {
+        hasher.update(b"\n");
+        hasher.update(bullet.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Slides still missing current talking points
+pub fn slides_needing_notes<'a>(
+    slides: &'a [PptxSlide],
+    sidecar: &SlideSidecar,
+) -> Vec<&'a PptxSlide> {
+    slides
+        .iter()
+        .filter(|slide| {
+            sidecar
+                .ai_notes
+                .get(&slide.key)
+                .map_or(true, |cached| cached.source_hash != slide_hash(slide))
+        })
+        .collect()
+}
+
+/// Apply the notes source and the manual edits in `sidecar` to the page's
+/// slides. Talking points not generated yet leave the notes empty.
+pub fn apply_sidecar(
+    mut slides: Vec<PptxSlide>,
+    sidecar: &SlideSidecar,
+    notes: NotesSource,
+) -> Vec<PptxSlide> {
+    for slide in &mut slides {
+        match notes {
+            NotesSource::None => slide.notes.clear(),
+            NotesSource::Content => {}
+            NotesSource::Ai => {
+                slide.notes = sidecar
+                    .ai_notes
+                    .get(&slide.key)
+                    .filter(|cached| cached.source_hash == slide_hash(slide))
+                    .map(|cached| cached.notes.clone())
+                    .unwrap_or_default();
+            }
+        }
+        if let Some(edit) = sidecar.edits.get(&slide.key) {
+            if let Some(title) = &edit.title {
+                slide.title = title.clone();
+            }
+            if let Some(bullets) = &edit.bullets {
+                slide.bullets = bullets.clone();
+            }
+            if let Some(notes) = &edit.notes {
+                slide.notes = notes.clone();
+            }
+            slide.hidden = edit.hidden;
+            slide.edit = Some(edit.clone());
+        }
+    }
+    slides
+}
+
+/// Prompt for talking points for `slides`, answered with one JSON array of
+/// strings per slide
+pub fn talking_points_messages(page_title: &str, slides: &[&PptxSlide]) -> Vec<ChatMessage> {
+    let mut outline = String::new();
+    for (i, slide) in slides.iter().enumerate() {
+        outline.push_str(&format!("Slide {}: {}\n", i + 1, slide.title));
+        for bullet in &slide.bullets {
+            outline.push_str(&format!("- {}\n", bullet));
+        }
+        outline.push('\n');
+    }
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: "You write speaker notes for presentations. For every slide, write \
+                      3 to 5 short talking points the presenter can say aloud, expanding on \
+                      the slide rather than repeating it. Reply with only a JSON array that \
+                      has one string per slide, in order, with the talking points of a slide \
+                      separated by newlines."
+                .to_string(),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: format!("Presentation: {}\n\n{}", page_title, outline),
+        },
+    ]
+}
+
+/// Talking points per slide from the model's reply
+pub fn parse_talking_points(reply: &str, slides: usize) -> Result<Vec<String>, String> {
+    // Models wrap JSON in fences or add a sentence around it; take the
+    // outermost array
+    let json = match (reply.find('['), reply.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err("no JSON array in the reply".to_string()),
+    };
+    let points: Vec<String> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if points.len() != slides {
+        return Err(format!(
+            "expected talking points for {} slides, got {}",
+            slides,
+            points.len()
+        ));
+    }
+    Ok(points.into_iter().map(|p| p.trim().to_string()).collect())
+}
+
+// ===== OOXML writer =====
+
+const NS: &str = r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main""#;
+const REL: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const CT: &str = "application/vnd.openxmlformats-officedocument";
+const XML_DECL: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n";
+
+fn esc(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control() || *c == '\t')
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn rels(entries: &[(String, &str, String)]) -> String {
+    let mut xml = format!(
+        "{}<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+        XML_DECL
+    );
+    for (id, kind, target) in entries {
+        xml.push_str(&format!(
+            "<Relationship Id=\"{}\" Type=\"{}/{}\" Target=\"{}\"/>",
+            id, REL, kind, target
+        ));
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+const GROUP_PROPS: &str = r#"<p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr>"#;
+
+fn background(color: &str) -> String {
+    format!(
+        r#"<p:bg><p:bgPr><a:solidFill><a:srgbClr val="{}"/></a:solidFill><a:effectLst/></p:bgPr></p:bg>"#,
+        color
+    )
+}
+
+fn run(text: &str, size: u32, bold: bool, color: &str, font: &str) -> String {
+    format!(
+        r#"<a:r><a:rPr lang="en-US" sz="{}" b="{}" dirty="0"><a:solidFill><a:srgbClr val="{}"/></a:solidFill><a:latin typeface="{}"/></a:rPr><a:t>{}</a:t></a:r>"#,
+        size,
+        if bold { 1 } else { 0 },
+        color,
+        esc(font),
+        esc(text)
+    )
+}
+
+struct Frame {
+    x: i64,
+    y: i64,
+    cx: i64,
+    cy: i64,
+}
+
+fn text_box(id: u32, name: &str, frame: &Frame, anchor: &str, paragraphs: &str) -> String {
+    format!(
+        r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="{name}"/><p:cNvSpPr txBox="1"/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="{x}" y="{y}"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:noFill/></p:spPr><p:txBody><a:bodyPr wrap="square" anchor="{anchor}"><a:normAutofit/></a:bodyPr><a:lstStyle/>{paragraphs}</p:txBody></p:sp>"#,
+        id = id,
+        name = name,
+        x = frame.x,
+        y = frame.y,
+        cx = frame.cx,
+        cy = frame.cy,
+        anchor = anchor,
+        paragraphs = paragraphs,
+    )
+}
+
+fn slide_xml(slide: &PptxSlide, theme: &PptxTheme, template: PptxTemplate) -> String {
+    let margin = 609_600;
+    let mut shapes = String::new();
+
+    if template == PptxTemplate::AccentBar {
+        shapes.push_str(&format!(
+            r#"<p:sp><p:nvSpPr><p:cNvPr id="4" name="Accent"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="182880" cy="{}"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:solidFill><a:srgbClr val="{}"/></a:solidFill><a:ln><a:noFill/></a:ln></p:spPr></p:sp>"#,
+            SLIDE_HEIGHT, theme.accent
+        ));
+    }
+
+    let divider = template == PptxTemplate::Sections && slide.bullets.is_empty();
+    if divider {
+        let title = format!(
+            r#"<a:p><a:pPr algn="ctr"/>{}</a:p>"#,
+            run(&slide.title, 4400, true, theme.title, theme.font)
+        );
+        let frame = Frame {
+            x: margin,
+            y: SLIDE_HEIGHT / 3,
+            cx: SLIDE_WIDTH - 2 * margin,
+            cy: SLIDE_HEIGHT / 3,
+        };
+        shapes.push_str(&text_box(2, "Title", &frame, "ctr", &title));
+    } else {
+        let centered = template == PptxTemplate::Sections;
+        let title = format!(
+            r#"<a:p>{}{}</a:p>"#,
+            if centered {
+                r#"<a:pPr algn="ctr"/>"#
+            } else {
+                ""
+            },
+            run(&slide.title, 3200, true, theme.title, theme.font)
+        );
+        let title_frame = Frame {
+            x: margin,
+            y: 365_760,
+            cx: SLIDE_WIDTH - 2 * margin,
+            cy: 1_143_000,
+        };
+        shapes.push_str(&text_box(2, "Title", &title_frame, "b", &title));
+
+        let size = match slide.bullets.len() {
+            0..=5 => 2400,
+            6..=8 => 2000,
+            _ => 1600,
+        };
+        let body: String = slide
+            .bullets
+            .iter()
+            .filter(|bullet| !bullet.trim().is_empty())
+            .map(|bullet| {
+                format!(
+                    r#"<a:p><a:pPr marL="342900" indent="-342900"><a:spcBef><a:spcPts val="600"/></a:spcBef><a:buClr><a:srgbClr val="{}"/></a:buClr><a:buFont typeface="Arial"/><a:buChar char="&#8226;"/></a:pPr>{}</a:p>"#,
+                    theme.accent,
+                    run(bullet, size, false, theme.text, theme.font)
+                )
+            })
+            .collect();
+        let body_frame = Frame {
+            x: margin,
+            y: 1_645_920,
+            cx: SLIDE_WIDTH - 2 * margin,
+            cy: SLIDE_HEIGHT - 1_645_920 - 457_200,
+        };
+        shapes.push_str(&text_box(3, "Body", &body_frame, "t", &body));
+    }
+
+    format!(
+        r#"{}<p:sld {}><p:cSld>{}<p:spTree>{}{}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sld>"#,
+        XML_DECL,
+        NS,
+        background(theme.background),
+        GROUP_PROPS,
+        shapes
+    )
+}
+
+fn notes_xml(notes: &str) -> String {
+    let paragraphs: String = if notes.trim().is_empty() {
+        r#"<a:p><a:endParaRPr lang="en-US"/></a:p>"#.to_string()
+    } else {
+        notes
+            .lines()
+            .map(|line| {
+                format!(
+                    r#"<a:p><a:r><a:rPr lang="en-US" dirty="0"/><a:t>{}</a:t></a:r></a:p>"#,
+                    esc(line)
+                )
+            })
+            .collect()
+    };
+    format!(
+        r#"{}<p:notes {}><p:cSld><p:spTree>{}<p:sp><p:nvSpPr><p:cNvPr id="2" name="Slide Image Placeholder 1"/><p:cNvSpPr><a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/></p:cNvSpPr><p:nvPr><p:ph type="sldImg"/></p:nvPr></p:nvSpPr><p:spPr/></p:sp><p:sp><p:nvSpPr><p:cNvPr id="3" name="Notes Placeholder 2"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="body" idx="1"/></p:nvPr></p:nvSpPr><p:spPr/><p:txBody><a:bodyPr/><a:lstStyle/>{}</p:txBody></p:sp></p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:notes>"#,
+        XML_DECL, NS, GROUP_PROPS, paragraphs
+    )
+}
+
+const CLR_MAP: &str = r#"<p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/>"#;
+
+fn theme_xml(background: &str, text: &str, accent: &str, font: &str) -> String {
+    let color =
+        |name: &str, val: &str| format!(r#"<a:{0}><a:srgbClr val="{1}"/></a:{0}>"#, name, val);
+    let font_set = format!(
+        r#"<a:latin typeface="{}"/><a:ea typeface=""/><a:cs typeface=""/>"#,
+        esc(font)
+    );
+    let fill = r#"<a:solidFill><a:schemeClr val="phClr"/></a:solidFill>"#;
+    let line = r#"<a:ln w="6350"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln>"#;
+    let effect = "<a:effectStyle><a:effectLst/></a:effectStyle>";
+    format!(
+        r#"{decl}<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="Nous"><a:themeElements><a:clrScheme name="Nous">{dk1}{lt1}{dk2}{lt2}{a1}{a2}{a3}{a4}{a5}{a6}{hl}{fhl}</a:clrScheme><a:fontScheme name="Nous"><a:majorFont>{font}</a:majorFont><a:minorFont>{font}</a:minorFont></a:fontScheme><a:fmtScheme name="Nous"><a:fillStyleLst>{fill}{fill}{fill}</a:fillStyleLst><a:lnStyleLst>{line}{line}{line}</a:lnStyleLst><a:effectStyleLst>{effect}{effect}{effect}</a:effectStyleLst><a:bgFillStyleLst>{fill}{fill}{fill}</a:bgFillStyleLst></a:fmtScheme></a:themeElements></a:theme>"#,
+        decl = XML_DECL,
+        dk1 = color("dk1", text),
+        lt1 = color("lt1", background),
+        dk2 = color("dk2", text),
+        lt2 = color("lt2", background),
+        a1 = color("accent1", accent),
+        a2 = color("accent2", accent),
+        a3 = color("accent3", accent),
+        a4 = color("accent4", accent),
+        a5 = color("accent5", accent),
+        a6 = color("accent6", accent),
+        hl = color("hlink", accent),
+        fhl = color("folHlink", accent),
+        font = font_set,
+        fill = fill,
+        line = line,
+        effect = effect,
+    )
+}
+
+/// Write the visible `slides` to a `.pptx` at `path`
+pub fn write_pptx(
+    path: &Path,
+    title: &str,
+    slides: &[PptxSlide],
+    theme: &PptxTheme,
+    template: PptxTemplate,
+) -> zip::result::ZipResult<usize> {
+    let slides: Vec<&PptxSlide> = slides.iter().filter(|s| !s.hidden).collect();
+    let mut parts: Vec<(String, String)> = Vec::new();
+
+    let mut types = format!(
+        r#"{}<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/>"#,
+        XML_DECL
+    );
+    let mut override_part = |part: &str, content_type: &str| {
+        types.push_str(&format!(
+            r#"<Override PartName="/{}" ContentType="{}"/>"#,
+            part, content_type
+        ));
+    };
+    override_part(
+        "ppt/presentation.xml",
+        &format!("{}.presentationml.presentation.main+xml", CT),
+    );
+    override_part(
+        "ppt/slideMasters/slideMaster1.xml",
+        &format!("{}.presentationml.slideMaster+xml", CT),
+    );
+    override_part(
+        "ppt/slideLayouts/slideLayout1.xml",
+        &format!("{}.presentationml.slideLayout+xml", CT),
+    );
+    override_part(
+        "ppt/notesMasters/notesMaster1.xml",
+        &format!("{}.presentationml.notesMaster+xml", CT),
+    );
+    override_part("ppt/theme/theme1.xml", &format!("{}.theme+xml", CT));
+    override_part("ppt/theme/theme2.xml", &format!("{}.theme+xml", CT));
+    override_part(
+        "ppt/presProps.xml",
+        &format!("{}.presentationml.presProps+xml", CT),
+    );
+    override_part(
+        "ppt/viewProps.xml",
+        &format!("{}.presentationml.viewProps+xml", CT),
+    );
+    override_part(
+        "ppt/tableStyles.xml",
+        &format!("{}.presentationml.tableStyles+xml", CT),
+    );
+    override_part(
+        "docProps/core.xml",
+        "application/vnd.openxmlformats-package.core-properties+xml",
+    );
+    override_part(
+        "docProps/app.xml",
+        &format!("{}.extended-properties+xml", CT),
+    );
+    for n in 1..=slides.len() {
+        override_part(
+            &format!("ppt/slides/slide{}.xml", n),
+            &format!("{}.presentationml.slide+xml", CT),
+        );
+        override_part(
+            &format!("ppt/notesSlides/notesSlide{}.xml", n),
+            &format!("{}.presentationml.notesSlide+xml", CT),
+        );
+    }
+    types.push_str("</Types>");
+    parts.push(("[Content_Types].xml".to_string(), types));
+
+    parts.push((
+        "_rels/.rels".to_string(),
+        format!(
+            "{}<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\"><Relationship Id=\"rId1\" Type=\"{}/officeDocument\" Target=\"ppt/presentation.xml\"/><Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" Target=\"docProps/core.xml\"/><Relationship Id=\"rId3\" Type=\"{}/extended-properties\" Target=\"docProps/app.xml\"/></Relationships>",
+            XML_DECL, REL, REL
+        ),
+    ));
+    parts.push((
+        "docProps/core.xml".to_string(),
+        format!(
+            r#"{}<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><dc:title>{}</dc:title><dc:creator>Nous</dc:creator><dcterms:created xsi:type="dcterms:W3CDTF">{}</dcterms:created></cp:coreProperties>"#,
+            XML_DECL,
+            esc(title),
+            Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+        ),
+    ));
+    parts.push((
+        "docProps/app.xml".to_string(),
+        format!(
+            r#"{}<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties"><Application>Nous</Application><Slides>{}</Slides><Notes>{}</Notes></Properties>"#,
+            XML_DECL,
+            slides.len(),
+            slides.len()
+        ),
+    ));
+
+    // Presentation
+    let slide_ids: String = (1..=slides.len())
+        .map(|n| format!(r#"<p:sldId id="{}" r:id="rId{}"/>"#, 255 + n, 9 + n))
+        .collect();
+    parts.push((
+        "ppt/presentation.xml".to_string(),
+        format!(
+            r#"{}<p:presentation {} saveSubsetFonts="1"><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst><p:notesMasterIdLst><p:notesMasterId r:id="rId2"/></p:notesMasterIdLst><p:sldIdLst>{}</p:sldIdLst><p:sldSz cx="{}" cy="{}"/><p:notesSz cx="6858000" cy="9144000"/></p:presentation>"#,
+            XML_DECL, NS, slide_ids, SLIDE_WIDTH, SLIDE_HEIGHT
+        ),
+    ));
+    let mut presentation_rels = vec![
+        (
+            "rId1".to_string(),
+            "slideMaster",
+            "slideMasters/slideMaster1.xml".to_string(),
+        ),
+        (
+            "rId2".to_string(),
+            "notesMaster",
+            "notesMasters/notesMaster1.xml".to_string(),
+        ),
+        ("rId3".to_string(), "theme", "theme/theme1.xml".to_string()),
+        ("rId4".to_string(), "presProps", "presProps.xml".to_string()),
+        ("rId5".to_string(), "viewProps", "viewProps.xml".to_string()),
+        (
+            "rId6".to_string(),
+            "tableStyles",
+            "tableStyles.xml".to_string(),
+        ),
+    ];
+    for n in 1..=slides.len() {
+        presentation_rels.push((
+            format!("rId{}", 9 + n),
+            "slide",
+            format!("slides/slide{}.xml", n),
+        ));
+    }
+    parts.push((
+        "ppt/_rels/presentation.xml.rels".to_string(),
+        rels(&presentation_rels),
+    ));
+    parts.push((
+        "ppt/presProps.xml".to_string(),
+        format!("{}<p:presentationPr {}/>", XML_DECL, NS),
+    ));
+    parts.push((
+        "ppt/viewProps.xml".to_string(),
+        format!("{}<p:viewPr {}/>", XML_DECL, NS),
+    ));
+    parts.push((
+        "ppt/tableStyles.xml".to_string(),
+        format!(
+            r#"{}<a:tblStyleLst xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" def="{{5C22544A-7EE6-4342-B048-85BDC9FD1C3A}}"/>"#,
+            XML_DECL
+        ),
+    ));
+
+    // Masters, layout and themes
+    parts.push((
+        "ppt/theme/theme1.xml".to_string(),
+        theme_xml(theme.background, theme.text, theme.accent, theme.font),
+    ));
+    // The notes pages are printed; keep them black on white
+    parts.push((
+        "ppt/theme/theme2.xml".to_string(),
+        theme_xml("FFFFFF", "000000", theme.accent, theme.font),
+    ));
+    parts.push((
+        "ppt/slideMasters/slideMaster1.xml".to_string(),
+        format!(
+            r#"{}<p:sldMaster {}><p:cSld>{}<p:spTree>{}</p:spTree></p:cSld>{}<p:sldLayoutIdLst><p:sldLayoutId id="2147483649" r:id="rId1"/></p:sldLayoutIdLst></p:sldMaster>"#,
+            XML_DECL,
+            NS,
+            background(theme.background),
+            GROUP_PROPS,
+            CLR_MAP
+        ),
+    ));
+    parts.push((
+        "ppt/slideMasters/_rels/slideMaster1.xml.rels".to_string(),
+        rels(&[
+            (
+                "rId1".to_string(),
+                "slideLayout",
+                "../slideLayouts/slideLayout1.xml".to_string(),
+            ),
+            (
+                "rId2".to_string(),
+                "theme",
+                "../theme/theme1.xml".to_string(),
+            ),
+        ]),
+    ));
+    parts.push((
+        "ppt/slideLayouts/slideLayout1.xml".to_string(),
+        format!(
+            r#"{}<p:sldLayout {} type="blank" preserve="1"><p:cSld name="Blank"><p:spTree>{}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>"#,
+            XML_DECL, NS, GROUP_PROPS
+        ),
+    ));
+    parts.push((
+        "ppt/slideLayouts/_rels/slideLayout1.xml.rels".to_string(),
+        rels(&[(
+            "rId1".to_string(),
+            "slideMaster",
+            "../slideMasters/slideMaster1.xml".to_string(),
+        )]),
+    ));
+    parts.push((
+        "ppt/notesMasters/notesMaster1.xml".to_string(),
+        format!(
+            r#"{}<p:notesMaster {}><p:cSld><p:bg><p:bgRef idx="1001"><a:schemeClr val="bg1"/></p:bgRef></p:bg><p:spTree>{}<p:sp><p:nvSpPr><p:cNvPr id="2" name="Slide Image Placeholder 1"/><p:cNvSpPr><a:spLocks noGrp="1" noRot="1" noChangeAspect="1"/></p:cNvSpPr><p:nvPr><p:ph type="sldImg" idx="2"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="381000" y="685800"/><a:ext cx="6096000" cy="3429000"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:noFill/><a:ln w="12700"><a:solidFill><a:prstClr val="black"/></a:solidFill></a:ln></p:spPr></p:sp><p:sp><p:nvSpPr><p:cNvPr id="3" name="Notes Placeholder 2"/><p:cNvSpPr><a:spLocks noGrp="1"/></p:cNvSpPr><p:nvPr><p:ph type="body" sz="quarter" idx="3"/></p:nvPr></p:nvSpPr><p:spPr><a:xfrm><a:off x="685800" y="4400550"/><a:ext cx="5486400" cy="3600450"/></a:xfrm><a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr><p:txBody><a:bodyPr/><a:lstStyle/><a:p><a:endParaRPr lang="en-US"/></a:p></p:txBody></p:sp></p:spTree></p:cSld>{}</p:notesMaster>"#,
+            XML_DECL, NS, GROUP_PROPS, CLR_MAP
+        ),
+    ));
+    parts.push((
+        "ppt/notesMasters/_rels/notesMaster1.xml.rels".to_string(),
+        rels(&[(
+            "rId1".to_string(),
+            "theme",
+            "../theme/theme2.xml".to_string(),
+        )]),
+    ));
+
+    // Slides and their notes
+    for (i, slide) in slides.iter().enumerate() {
+        let n = i + 1;
+        parts.push((
+            format!("ppt/slides/slide{}.xml", n),
+            slide_xml(slide, theme, template),
+        ));
+        parts.push((
+            format!("ppt/slides/_rels/slide{}.xml.rels", n),
+            rels(&[
+                (
+                    "rId1".to_string(),
+                    "slideLayout",
+                    "../slideLayouts/slideLayout1.xml".to_string(),
+                ),
+                (
+                    "rId2".to_string(),
+                    "notesSlide",
+                    format!("../notesSlides/notesSlide{}.xml", n),
+                ),
+            ]),
+        ));
+        parts.push((
+            format!("ppt/notesSlides/notesSlide{}.xml", n),
+            notes_xml(&slide.notes),
+        ));
+        parts.push((
+            format!("ppt/notesSlides/_rels/notesSlide{}.xml.rels", n),
+            rels(&[
+                (
+                    "rId1".to_string(),
+                    "notesMaster",
+                    "../notesMasters/notesMaster1.xml".to_string(),
+                ),
+                (
+                    "rId2".to_string(),
+                    "slide",
+                    format!("../slides/slide{}.xml", n),
+                ),
+            ]),
+        ));
+    }
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in parts {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(slides.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EditorData;
+    use serde_json::json;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn block(id: &str, block_type: &str, data: serde_json::Value) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn page() -> Page {
+        let mut page = Page::new(uuid::Uuid::new_v4(), "Deck".to_string());
+        page.content = EditorData {
+            blocks: vec![
+                block("h1", "header", json!({ "text": "Intro", "level": 2 })),
+                block(
+                    "p1",
+                    "paragraph",
+                    json!({ "text": "Why <b>this</b> matters" }),
+                ),
+                block(
+                    "l1",
+                    "list",
+                    json!({ "style": "unordered", "items": ["One", "Two &amp; three"] }),
+                ),
+                block("h2", "header", json!({ "text": "Details", "level": 2 })),
+                block("c1", "code", json!({ "code": "let x = 1;" })),
+            ],
+            time: None,
+            version: None,
+        };
+        page
+    }
+
+    #[test]
+    fn slides_keep_manual_edits_across_exports() {
+        let slides = page_slides(&page());
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].key, "h1");
+        assert_eq!(slides[0].title, "Intro");
+        assert_eq!(
+            slides[0].bullets,
+            vec!["Why this matters", "One", "Two & three"]
+        );
+        assert!(slides[1].bullets.is_empty());
+        assert_eq!(slides[1].notes, "let x = 1;");
+
+        let mut sidecar = SlideSidecar::default();
+        sidecar.edits.insert(
+            "h1".to_string(),
+            SlideEdit {
+                title: Some("Opening".to_string()),
+                ..Default::default()
+            },
+        );
+        sidecar.edits.insert(
+            "h2".to_string(),
+            SlideEdit {
+                hidden: true,
+                ..Default::default()
+            },
+        );
+        let edited = apply_sidecar(slides.clone(), &sidecar, NotesSource::Content);
+        assert_eq!(edited[0].title, "Opening");
+        assert_eq!(edited[0].bullets, slides[0].bullets);
+        assert!(edited[0].edit.is_some() && edited[1].hidden);
+
+        // Talking points go stale once the slide changes
+        sidecar.ai_notes.insert(
+            "h1".to_string(),
+            GeneratedNotes {
+                source_hash: slide_hash(&slides[0]),
+                notes: "Say hello".to_string(),
+            },
+        );
+        assert_eq!(slides_needing_notes(&slides, &sidecar).len(), 1);
+        let with_ai = apply_sidecar(slides.clone(), &sidecar, NotesSource::Ai);
+        assert_eq!(with_ai[0].notes, "Say hello");
+        assert_eq!(with_ai[1].notes, "");
+
+        assert_eq!(
+            parse_talking_points("Sure:\n```json\n[\"a\", \"b\"]\n```", 2).unwrap(),
+            vec!["a", "b"]
+        );
+        assert!(parse_talking_points("[\"a\"]", 2).is_err());
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("deck.pptx");
+        let written = write_pptx(
+            &path,
+            "Deck",
+            &edited,
+            theme("moon"),
+            PptxTemplate::AccentBar,
+        )
+        .unwrap();
+        assert_eq!(written, 1);
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut slide = String::new();
+        archive
+            .by_name("ppt/slides/slide1.xml")
+            .unwrap()
+            .read_to_string(&mut slide)
+            .unwrap();
+        assert!(slide.contains("Opening") && slide.contains("Two &amp; three"));
+        assert!(archive.by_name("ppt/slides/slide2.xml").is_err());
+        assert!(archive.by_name("ppt/notesSlides/notesSlide1.xml").is_ok());
+    }
+}
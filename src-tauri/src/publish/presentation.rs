@@ -14,7 +14,7 @@ pub struct PresentationOptions {
 
 /// Split blocks into slides. H1/H2 headers start new slides.
 /// Content before the first header becomes the title slide.
-fn split_into_slides<'a>(blocks: &'a [EditorBlock], page_title: &str) -> Vec<(Option<String>, Vec<&'a EditorBlock>)> {
+pub(super) fn split_into_slides<'a>(blocks: &'a [EditorBlock], page_title: &str) -> Vec<(Option<String>, Vec<&'a EditorBlock>)> {
     let mut slides: Vec<(Option<String>, Vec<&EditorBlock>)> = Vec::new();
     let mut current_blocks: Vec<&EditorBlock> = Vec::new();
     let mut current_title: Option<String> = None;
@@ -3,7 +3,8 @@ use std::collections::HashMap;
 
 use crate::storage::Page;
 
-use super::html::{block_plain_text, render_page_html, slugify};
+use super::html::{block_plain_text, blocks_have_math, render_page_html, slugify};
+use super::themes::KATEX_HEAD;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -92,6 +93,13 @@ pub fn render_print_html(
     let (page_slugs, block_texts) = build_lookup_maps(all_pages);
     let content_html = render_page_html(page, &page_slugs, &block_texts);
 
+    // KaTeX typesets formulas once the page loads, before it is printed
+    let math_head = if blocks_have_math(&page.content.blocks) {
+        KATEX_HEAD
+    } else {
+        ""
+    };
+
     let toc_html = if options.include_toc {
         generate_toc(page)
     } else {
@@ -285,6 +293,7 @@ pub fn render_print_html(
       }}
     }}
   </style>
+{math_head}
 </head>
 <body>
   <h1>{title}</h1>
@@ -299,5 +308,6 @@ pub fn render_print_html(
         metadata = metadata_html,
         toc = toc_html,
         content = content_html,
+        math_head = math_head,
     )
 }
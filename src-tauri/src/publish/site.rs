@@ -5,11 +5,12 @@ use std::fs;
 use std::path::Path;
 use uuid::Uuid;
 
+use crate::charts::with_rendered_charts;
 use crate::storage::{FileStorage, Folder, Page, Section};
 
 use super::html::{
-    block_plain_text, blocks_have_animation, blocks_have_mermaid, render_page_html,
-    rewrite_asset_url, slugify,
+    block_plain_text, blocks_have_animation, blocks_have_math, blocks_have_mermaid,
+    render_page_html, rewrite_asset_url, slugify,
 };
 use super::themes::{get_theme, page_head_extra};
 
@@ -172,6 +173,7 @@ pub fn preview_page(
     let (page_slugs, block_texts) = build_lookup_maps(&all_pages);
     let theme = get_theme(theme_name);
 
+    let page = with_rendered_charts(&page, &storage.notebook_assets_dir(notebook_id));
     let content_html = render_page_html(&page, &page_slugs, &block_texts);
     let date = page.updated_at.format("%B %d, %Y").to_string();
 
@@ -188,6 +190,7 @@ pub fn preview_page(
         theme.name,
         blocks_have_mermaid(&page.content.blocks),
         blocks_have_animation(&page.content.blocks),
+        blocks_have_math(&page.content.blocks),
     );
 
     let html = theme
@@ -348,7 +351,8 @@ pub fn generate_site(
             cb(current, total, &format!("Rendering {}", page.title));
         }
 
-        let content_html = render_page_html(page, &page_slugs, &block_texts);
+        let charted = with_rendered_charts(page, &storage.notebook_assets_dir(notebook_id));
+        let content_html = render_page_html(&charted, &page_slugs, &block_texts);
         let date = page.updated_at.format("%B %d, %Y").to_string();
 
         // Build backlinks section
@@ -388,6 +392,7 @@ pub fn generate_site(
             theme.name,
             blocks_have_mermaid(&page.content.blocks),
             blocks_have_animation(&page.content.blocks),
+            blocks_have_math(&page.content.blocks),
         );
 
         let page_html = theme
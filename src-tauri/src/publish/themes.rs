@@ -59,6 +59,26 @@ pub fn animation_head(theme_name: &str) -> Option<&'static str> {
     }
 }
 
+/// KaTeX, injected into a page's `<head>` when the page has math. Formulas are
+/// rendered to `\( \)` / `\[ \]` by `publish::html`, and auto-render only
+/// looks for those delimiters, so a stray `$` in prose is never typeset. The
+/// rendered glyphs inherit `color`, so they follow every theme's palette.
+pub fn math_head(theme_name: &str) -> Option<&'static str> {
+    match theme_name {
+        "academic" | "minimal" | "documentation" | "blog" | "docs" => Some(KATEX_HEAD),
+        _ => None,
+    }
+}
+
+/// Pinned CDN build of KaTeX (matches the app's katex ^0.16.27).
+pub const KATEX_HEAD: &str = r#"<link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/katex@0.16.27/dist/katex.min.css" crossorigin="anonymous">
+<style>
+div.math { margin: 1.25rem 0; overflow-x: auto; overflow-y: hidden; }
+</style>
+<script defer src="https://cdn.jsdelivr.net/npm/katex@0.16.27/dist/katex.min.js" crossorigin="anonymous"></script>
+<script defer src="https://cdn.jsdelivr.net/npm/katex@0.16.27/dist/contrib/auto-render.min.js" crossorigin="anonymous"
+  onload="renderMathInElement(document.body, { delimiters: [{ left: '\\[', right: '\\]', display: true }, { left: '\\(', right: '\\)', display: false }], throwOnError: false })"></script>"#;
+
 const ANIMATION_HEAD: &str = r#"<script type="module">
 // Reduced motion: swap each animation that ships a poster for its still frame
 // and drop the live iframe entirely, so motion-sensitive readers get no motion.
@@ -105,9 +125,14 @@ document.querySelectorAll('.theme-toggle').forEach(function (b) {
 });
 </script>"#;
 
-/// Combined `{{head_extra}}` for a page: the Mermaid runtime and/or the
-/// animation theme bridge, in that order, for whatever the page contains.
-pub fn page_head_extra(theme_name: &str, has_mermaid: bool, has_animation: bool) -> String {
+/// Combined `{{head_extra}}` for a page: the Mermaid runtime, the animation
+/// theme bridge and KaTeX, in that order, for whatever the page contains.
+pub fn page_head_extra(
+    theme_name: &str,
+    has_mermaid: bool,
+    has_animation: bool,
+    has_math: bool,
+) -> String {
     let mut out = String::new();
     if has_mermaid {
         out.push_str(mermaid_head(theme_name).unwrap_or(""));
@@ -115,6 +140,9 @@ pub fn page_head_extra(theme_name: &str, has_mermaid: bool, has_animation: bool)
     if has_animation {
         out.push_str(animation_head(theme_name).unwrap_or(""));
     }
+    if has_math {
+        out.push_str(math_head(theme_name).unwrap_or(""));
+    }
     out
 }
 
@@ -1551,21 +1579,26 @@ mod tests {
     #[test]
     fn page_head_extra_concatenates_only_what_the_page_needs() {
         // Nothing needed → empty (clean head).
-        assert_eq!(page_head_extra("academic", false, false), "");
+        assert_eq!(page_head_extra("academic", false, false, false), "");
         // Mermaid only.
-        let m = page_head_extra("academic", true, false);
+        let m = page_head_extra("academic", true, false, false);
         assert!(m.contains("mermaid@11.16.0") && !m.contains("iframe.nous-animation"));
         // Animation only.
-        let a = page_head_extra("academic", false, true);
+        let a = page_head_extra("academic", false, true, false);
         assert!(a.contains("iframe.nous-animation") && !a.contains("mermaid@11.16.0"));
         // Both, in order.
-        let both = page_head_extra("academic", true, true);
+        let both = page_head_extra("academic", true, true, false);
         assert!(both.find("mermaid@11.16.0").unwrap() < both.find("iframe.nous-animation").unwrap());
         // A non-academic theme now gets both runtimes too (they all ship tokens).
-        let mini = page_head_extra("minimal", true, true);
+        let mini = page_head_extra("minimal", true, true, false);
         assert!(mini.contains("mermaid@11.16.0") && mini.contains("iframe.nous-animation"));
+        // Math only, and KaTeX last when everything is needed.
+        let k = page_head_extra("blog", false, false, true);
+        assert!(k.contains("katex@0.16.27") && !k.contains("mermaid@11.16.0"));
+        let all = page_head_extra("docs", true, true, true);
+        assert!(all.find("iframe.nous-animation").unwrap() < all.find("katex@0.16.27").unwrap());
         // An unknown theme name still gets nothing.
-        assert_eq!(page_head_extra("nonesuch", true, true), "");
+        assert_eq!(page_head_extra("nonesuch", true, true, true), "");
     }
 
     #[test]
@@ -0,0 +1,463 @@
+//! Python environment health check and repair
+//!
+//! Compares the packages the embedded interpreter can see against the
+//! requirements in nous-py's `pyproject.toml`, so a missing dependency shows
+//! up as a report entry instead of a `ModuleNotFoundError` in the middle of
+//! an AI call. The dependencies (and the optional groups, which are reported
+//! but not required) are checked with `importlib.metadata`, which only needs
+//! the standard library. A development venv can be repaired by installing
+//! the requirements into it; the bundled interpreter can't be changed.
+
+use std::cmp::Ordering;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{configure_python_path, PythonError, PythonMode, Result};
+
+/// Optional dependency groups that aren't used by the app
+const SKIPPED_GROUPS: &[&str] = &["dev", "mcp-server"];
+
+/// Which Python the app runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PythonInstall {
+    /// Shipped with a release build (`PYTHONHOME` points at the bundle)
+    Bundled,
+    /// nous-py's `.venv`, in development
+    Venv,
+    /// Whatever interpreter PyO3 linked against, without a venv
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PackageState {
+    Ok,
+    Missing,
+    /// Installed, but not a version the requirement allows
+    Outdated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageStatus {
+    pub name: String,
+    /// The requirement as written in `pyproject.toml`
+    pub requirement: String,
+    /// Optional dependency group, `None` for required packages
+    pub group: Option<String>,
+    pub installed: Option<String>,
+    pub state: PackageState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PythonHealthReport {
+    /// The interpreter loaded and every required package is usable
+    pub healthy: bool,
+    pub install: PythonInstall,
+    pub mode: PythonMode,
+    pub python_version: String,
+    pub nous_py_path: String,
+    /// Venv a repair would install into (development only)
+    pub venv_path: Option<String>,
+    pub packages: Vec<PackageStatus>,
+    pub problems: Vec<String>,
+}
+
+/// Check the embedded interpreter against nous-py's requirements
+pub fn check(nous_py_path: &Path, mode: PythonMode) -> PythonHealthReport {
+    let install = install_kind(nous_py_path);
+    let venv = venv_dir(nous_py_path);
+    let mut problems = Vec::new();
+
+    if !nous_py_path.join("nous_ai").is_dir() {
+        problems.push(format!(
+            "nous-py source not found at {}",
+            nous_py_path.display()
+        ));
+    }
+    let requirements = match read_requirements(nous_py_path) {
+        Ok(requirements) => requirements,
+        Err(e) => {
+            problems.push(e.to_string());
+            Vec::new()
+        }
+    };
+
+    let names: Vec<String> = requirements.iter().map(|(r, _)| r.name.clone()).collect();
+    let (python_version, installed) = match probe(nous_py_path, &names) {
+        Ok(found) => found,
+        Err(e) => {
+            problems.push(format!("Python interpreter unavailable: {}", e));
+            (String::new(), vec![None; names.len()])
+        }
+    };
+
+    let packages: Vec<PackageStatus> = requirements
+        .into_iter()
+        .zip(installed)
+        .map(|((requirement, group), installed)| {
+            let state = match &installed {
+                None => PackageState::Missing,
+                Some(version) if requirement.allows(version) => PackageState::Ok,
+                Some(_) => PackageState::Outdated,
+            };
+            PackageStatus {
+                name: requirement.name,
+                requirement: requirement.text,
+                group,
+                installed,
+                state,
+            }
+        })
+        .collect();
+    for package in packages.iter().filter(|p| p.group.is_none()) {
+        match (&package.state, &package.installed) {
+            (PackageState::Missing, _) => problems.push(format!(
+                "Required package {} is not installed",
+                package.name
+            )),
+            (PackageState::Outdated, Some(version)) => problems.push(format!(
+                "{} {} doesn't satisfy {}",
+                package.name, version, package.requirement
+            )),
+            _ => {}
+        }
+    }
+
+    PythonHealthReport {
+        healthy: problems.is_empty(),
+        install,
+        mode,
+        python_version,
+        nous_py_path: nous_py_path.to_string_lossy().to_string(),
+        venv_path: (install != PythonInstall::Bundled).then(|| venv.to_string_lossy().to_string()),
+        packages,
+        problems,
+    }
+}
+
+/// Install nous-py's required packages into its venv (creating the venv if
+/// needed), passing every line of installer output to `on_output`. Uses `uv`
+/// when it's on the PATH, else the venv's pip.
+pub fn repair(nous_py_path: &Path, on_output: &mut dyn FnMut(&str)) -> Result<()> {
+    if install_kind(nous_py_path) == PythonInstall::Bundled {
+        return Err(PythonError::Environment(
+            "The bundled Python can't be repaired in place; reinstall the app".to_string(),
+        ));
+    }
+    let requirements: Vec<String> = read_requirements(nous_py_path)?
+        .into_iter()
+        .filter(|(_, group)| group.is_none())
+        .map(|(requirement, _)| requirement.text)
+        .collect();
+
+    let venv = venv_dir(nous_py_path);
+    let uv = uv_available();
+    if !venv_python(&venv).exists() {
+        on_output(&format!(
+            "Creating virtual environment at {}",
+            venv.display()
+        ));
+        let mut command = if uv {
+            let mut command = Command::new("uv");
+            command.arg("venv").arg(&venv);
+            command
+        } else {
+            let mut command = Command::new("python3");
+            command.args(["-m", "venv"]).arg(&venv);
+            command
+        };
+        run_streamed(&mut command, on_output)?;
+    }
+
+    on_output(&format!("Installing {}", requirements.join(", ")));
+    let mut command = if uv {
+        let mut command = Command::new("uv");
+        command
+            .args(["pip", "install", "--python"])
+            .arg(venv_python(&venv));
+        command
+    } else {
+        let mut command = Command::new(venv_python(&venv));
+        command.args(["-m", "pip", "install"]);
+        command
+    };
+    command.args(&requirements);
+    run_streamed(&mut command, on_output)
+}
+
+fn uv_available() -> bool {
+    Command::new("uv")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Run a command to completion, streaming stdout and stderr line by line
+fn run_streamed(command: &mut Command, on_output: &mut dyn FnMut(&str)) -> Result<()> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| PythonError::Environment(format!("Failed to run {:?}: {}", command, e)))?;
+
+    // Both pipes feed one channel so neither can fill up and stall the child
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+    let readers = [
+        spawn_line_reader(Box::new(stdout), tx.clone()),
+        spawn_line_reader(Box::new(stderr), tx),
+    ];
+    for line in rx {
+        on_output(&line);
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| PythonError::Environment(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(PythonError::Environment(format!(
+            "{:?} failed ({})",
+            command.get_program(),
+            status
+        )))
+    }
+}
+
+fn spawn_line_reader(
+    pipe: Box<dyn std::io::Read + Send>,
+    tx: std::sync::mpsc::Sender<String>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(std::io::Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Python version and the installed version of each package, as seen by the
+/// embedded interpreter with nous-py's paths configured
+fn probe(nous_py_path: &Path, names: &[String]) -> Result<(String, Vec<Option<String>>)> {
+    Python::attach(|py| {
+        configure_python_path(py, nous_py_path)?;
+        // Pick up packages installed since the interpreter started
+        py.import("importlib")?.call_method0("invalidate_caches")?;
+
+        let sys = py.import("sys")?;
+        let version: String = sys.getattr("version")?.extract()?;
+        let metadata = py.import("importlib.metadata")?;
+        let installed = names
+            .iter()
+            .map(|name| {
+                metadata
+                    .call_method1("version", (name,))
+                    .and_then(|v| v.extract::<String>())
+                    .ok()
+            })
+            .collect();
+        let version = version.split_whitespace().next().unwrap_or_default();
+        Ok((version.to_string(), installed))
+    })
+}
+
+fn install_kind(nous_py_path: &Path) -> PythonInstall {
+    if std::env::var_os("PYTHONHOME").is_some() {
+        PythonInstall::Bundled
+    } else if venv_dir(nous_py_path).is_dir() {
+        PythonInstall::Venv
+    } else {
+        PythonInstall::System
+    }
+}
+
+fn venv_dir(nous_py_path: &Path) -> PathBuf {
+    nous_py_path.join(".venv")
+}
+
+fn venv_python(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts/python.exe")
+    } else {
+        venv.join("bin/python")
+    }
+}
+
+/// `[project]` dependencies, then the optional groups the app uses
+fn read_requirements(nous_py_path: &Path) -> Result<Vec<(Requirement, Option<String>)>> {
+    let path = nous_py_path.join("pyproject.toml");
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| PythonError::Environment(format!("Can't read {}: {}", path.display(), e)))?;
+    let pyproject: toml::Value = toml::from_str(&text)
+        .map_err(|e| PythonError::Environment(format!("Can't parse {}: {}", path.display(), e)))?;
+    let project = pyproject.get("project");
+    let list = |value: Option<&toml::Value>| -> Vec<Requirement> {
+        value
+            .and_then(toml::Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(toml::Value::as_str)
+                    .filter_map(Requirement::parse)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut requirements: Vec<_> = list(project.and_then(|p| p.get("dependencies")))
+        .into_iter()
+        .map(|r| (r, None))
+        .collect();
+    if let Some(groups) = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(toml::Value::as_table)
+    {
+        for (group, deps) in groups {
+            if SKIPPED_GROUPS.contains(&group.as_str()) {
+                continue;
+            }
+            requirements.extend(
+                list(Some(deps))
+                    .into_iter()
+                    .map(|r| (r, Some(group.clone()))),
+            );
+        }
+    }
+    Ok(requirements)
+}
+
+/// A dependency specifier such as `httpx>=0.27` or `foo[bar]>=1,<2`.
+/// Extras and environment markers are ignored.
+#[derive(Debug, Clone, PartialEq)]
+struct Requirement {
+    text: String,
+    name: String,
+    constraints: Vec<(String, String)>,
+}
+
+impl Requirement {
+    fn parse(text: &str) -> Option<Self> {
+        let spec = text.split(';').next().unwrap_or_default().trim();
+        let name_end = spec
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            .unwrap_or(spec.len());
+        let name = &spec[..name_end];
+        if name.is_empty() {
+            return None;
+        }
+        let rest = spec[name_end..].trim_start();
+        let rest = match rest.strip_prefix('[') {
+            Some(extras) => extras.split_once(']').map_or("", |(_, r)| r),
+            None => rest,
+        };
+        let constraints = rest
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .filter_map(|c| {
+                let op_end = c.find(|ch: char| !"<>=!~".contains(ch))?;
+                Some((c[..op_end].to_string(), c[op_end..].trim().to_string()))
+            })
+            .collect();
+        Some(Self {
+            text: text.trim().to_string(),
+            name: name.to_string(),
+            constraints,
+        })
+    }
+
+    fn allows(&self, installed: &str) -> bool {
+        self.constraints.iter().all(|(op, version)| {
+            let order = compare_versions(installed, version);
+            match op.as_str() {
+                ">=" => order != Ordering::Less,
+                ">" => order == Ordering::Greater,
+                "<=" => order != Ordering::Greater,
+                "<" => order == Ordering::Less,
+                "==" => order == Ordering::Equal,
+                "!=" => order != Ordering::Equal,
+                // ~=1.4 means >=1.4 and ==1.*
+                "~=" => {
+                    let release = release_numbers(version);
+                    let prefix = &release[..release.len().saturating_sub(1)];
+                    order != Ordering::Less && release_numbers(installed).starts_with(prefix)
+                }
+                _ => true,
+            }
+        })
+    }
+}
+
+/// Compare the release parts of two versions (`2.10.1rc1` counts as 2.10.1)
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (release_numbers(a), release_numbers(b));
+    let len = a.len().max(b.len());
+    let part = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| part(&a, i).cmp(&part(&b, i)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn release_numbers(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map_while(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_requirements() {
+        let r = Requirement::parse("browser-use[cli]>=0.2, <1 ; python_version >= '3.11'").unwrap();
+        assert_eq!(r.name, "browser-use");
+        assert_eq!(
+            r.constraints,
+            [
+                (">=".to_string(), "0.2".to_string()),
+                ("<".to_string(), "1".to_string())
+            ]
+        );
+        assert!(Requirement::parse("pydantic")
+            .unwrap()
+            .constraints
+            .is_empty());
+    }
+
+    #[test]
+    fn checks_installed_versions() {
+        let r = Requirement::parse("httpx>=0.27").unwrap();
+        assert!(r.allows("0.27"));
+        assert!(r.allows("0.28.1"));
+        assert!(!r.allows("0.9"));
+
+        let compatible = Requirement::parse("mcp~=1.25").unwrap();
+        assert!(compatible.allows("1.30.0"));
+        assert!(!compatible.allows("2.0"));
+        assert!(Requirement::parse("openai>=1.0")
+            .unwrap()
+            .allows("1.0.0rc2"));
+    }
+}
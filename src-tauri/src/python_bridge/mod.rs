@@ -1,13 +1,30 @@
 //! Python bridge module for AI operations via PyO3.
-
+//!
+//! By default the `nous_ai` functions run in the embedded interpreter. With
+//! `NOUS_PYTHON_ISOLATION=subprocess` they run in a worker process instead
+//! (see [`worker`]), so a crash in a native dependency can't take the app
+//! down; the embedded interpreter then only marshals arguments and results.
+//! Commands submit their calls through [`queue::PythonQueue`] rather than
+//! calling [`PythonAI`] on their own thread.
+
+pub mod health;
+pub mod queue;
+mod worker;
+
+use pyo3::exceptions::{PyBaseException, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::{PyCFunction, PyDict, PyList, PyTuple};
+use pyo3::types::{PyCFunction, PyDict, PyList, PyTuple, PyType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::mpsc;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
 use thiserror::Error;
 
+use crate::ai_permissions::ToolPermissionCheck;
+use crate::diagnostics::{self, Category};
+use health::PythonHealthReport;
+use worker::{PythonWorker, WorkerError, CALLBACK_KEY};
+
 #[derive(Error, Debug)]
 pub enum PythonError {
     #[error("Python error: {0}")]
@@ -21,6 +38,9 @@ pub enum PythonError {
 
     #[error("Type conversion error: {0}")]
     TypeConversion(String),
+
+    #[error("Python environment error: {0}")]
+    Environment(String),
 }
 
 pub type Result<T> = std::result::Result<T, PythonError>;
@@ -212,6 +232,9 @@ pub struct ChatResponseWithActions {
     pub finish_reason: Option<String>,
     pub actions: Vec<AIAction>,
     pub thinking: Option<String>,
+    /// Tool calls the chat loop did not run because of tool permissions
+    #[serde(default)]
+    pub permission_denials: Vec<ToolPermissionCheck>,
 }
 
 /// Streaming event from AI chat
@@ -237,6 +260,9 @@ pub enum StreamEvent {
     },
     #[serde(rename = "error")]
     Error { message: String },
+    /// A tool call that was denied or needs the user's approval
+    #[serde(rename = "toolPermission")]
+    ToolPermission(ToolPermissionCheck),
 }
 
 /// Result from browser automation task
@@ -273,6 +299,16 @@ pub struct TranscriptSegment {
     pub words: Vec<TranscriptWord>,
 }
 
+/// A stretch of a recording attributed to one speaker by diarization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerTurn {
+    pub start: f64,
+    pub end: f64,
+    /// Label such as `SPEAKER_00`, stable within one recording
+    pub speaker: String,
+}
+
 /// Result from video transcription
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -544,6 +580,11 @@ pub struct RAGChunk {
     pub title: String,
     pub content: String,
     pub score: f32,
+    /// Library the chunk was retrieved from, when retrieving across libraries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_name: Option<String>,
 }
 
 /// A citation reference in a response
@@ -555,6 +596,11 @@ pub struct Citation {
     pub page_title: String,
     pub excerpt: String,
     pub relevance_score: f32,
+    /// Library of the cited page, when the chat retrieved across libraries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_name: Option<String>,
 }
 
 /// Response with inline citations
@@ -595,6 +641,18 @@ pub struct MCPToolResult {
     pub error: Option<String>,
 }
 
+/// Convert a Python value to `T` by way of JSON
+fn py_to_serde<T: serde::de::DeserializeOwned>(py: Python<'_>, value: &Py<PyAny>) -> Option<T> {
+    let text: String = py
+        .import("json")
+        .ok()?
+        .call_method1("dumps", (value,))
+        .ok()?
+        .extract()
+        .ok()?;
+    serde_json::from_str(&text).ok()
+}
+
 /// Configure sys.path for the given nous-py directory.
 /// Handles both dev mode (venv at nous-py/.venv) and bundled release mode
 /// (PYTHONHOME set, site-packages inside the bundle).
@@ -663,15 +721,207 @@ fn configure_python_path(py: Python<'_>, nous_py_path: &std::path::Path) -> Resu
     Ok(())
 }
 
-/// Python AI bridge for calling Python functions
+/// Where the bridge runs `nous_ai` code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PythonMode {
+    /// In the embedded interpreter
+    InProcess,
+    /// In a long-lived worker process, restarted if it dies
+    Subprocess,
+}
+
+impl PythonMode {
+    /// `NOUS_PYTHON_ISOLATION=subprocess` selects the worker process
+    pub fn from_env() -> Self {
+        match std::env::var("NOUS_PYTHON_ISOLATION").as_deref() {
+            Ok("subprocess") => Self::Subprocess,
+            _ => Self::InProcess,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Backend {
+    InProcess,
+    Worker(Arc<PythonWorker>),
+}
+
+impl Backend {
+    /// Look up `module.function`: the function itself in process, or a
+    /// stand-in that forwards the call to the worker
+    fn function<'py>(
+        &self,
+        py: Python<'py>,
+        module: &str,
+        function: &str,
+    ) -> Result<Bound<'py, PyAny>> {
+        match self {
+            Self::InProcess => Ok(py.import(module)?.getattr(function)?),
+            Self::Worker(worker) => {
+                Ok(remote_function(py, Arc::clone(worker), module, function)?.into_any())
+            }
+        }
+    }
+}
+
+/// A Python callable that runs `module.function` in the worker
+fn remote_function<'py>(
+    py: Python<'py>,
+    worker: Arc<PythonWorker>,
+    module: &str,
+    function: &str,
+) -> PyResult<Bound<'py, PyCFunction>> {
+    let module = module.to_string();
+    let function = function.to_string();
+    PyCFunction::new_closure(
+        py,
+        None,
+        None,
+        move |args: &Bound<'_, PyTuple>, kwargs: Option<&Bound<'_, PyDict>>| {
+            call_remote(&worker, &module, &function, args, kwargs)
+        },
+    )
+}
+
+/// Forward a call to the worker. Arguments and the result travel as JSON;
+/// callables passed as top-level arguments (progress and stream callbacks)
+/// are called back as the worker reports.
+fn call_remote(
+    worker: &PythonWorker,
+    module: &str,
+    function: &str,
+    args: &Bound<'_, PyTuple>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Py<PyAny>> {
+    let py = args.py();
+    let json_module = py.import("json")?;
+    let mut callbacks: Vec<Py<PyAny>> = Vec::new();
+    let mut encode = |value: Bound<'_, PyAny>| -> PyResult<serde_json::Value> {
+        if value.is_callable() {
+            callbacks.push(value.unbind());
+            return Ok(serde_json::json!({ CALLBACK_KEY: callbacks.len() - 1 }));
+        }
+        let text: String = json_module.call_method1("dumps", (value,))?.extract()?;
+        serde_json::from_str(&text).map_err(|e| PyValueError::new_err(e.to_string()))
+    };
+
+    let call_args = args.iter().map(&mut encode).collect::<PyResult<Vec<_>>>()?;
+    let mut call_kwargs = serde_json::Map::new();
+    if let Some(kwargs) = kwargs {
+        for (key, value) in kwargs.iter() {
+            call_kwargs.insert(key.extract::<String>()?, encode(value)?);
+        }
+    }
+
+    let cancel = worker::task_token();
+    let result = py.detach(|| {
+        let mut on_callback = |index: usize, args: Vec<serde_json::Value>| {
+            if let Some(callback) = callbacks.get(index) {
+                Python::attach(|py| {
+                    if let Err(e) = call_back(py, callback, args) {
+                        log::warn!("Python worker callback failed: {}", e);
+                    }
+                });
+            }
+        };
+        worker.call(
+            module,
+            function,
+            call_args,
+            call_kwargs,
+            &cancel,
+            &mut on_callback,
+        )
+    });
+
+    match result {
+        Ok(value) => {
+            let text =
+                serde_json::to_string(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            Ok(json_module.call_method1("loads", (text,))?.unbind())
+        }
+        Err(WorkerError::Remote { kind, message }) => Err(remote_exception(py, &kind, message)),
+        Err(e) => Err(PyRuntimeError::new_err(e.to_string())),
+    }
+}
+
+/// Call a local callback with the arguments the worker reported
+fn call_back(py: Python<'_>, callback: &Py<PyAny>, args: Vec<serde_json::Value>) -> PyResult<()> {
+    let text = serde_json::to_string(&args).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let args = py.import("json")?.call_method1("loads", (text,))?;
+    callback.call1(py, args.cast_into::<PyList>()?.to_tuple())?;
+    Ok(())
+}
+
+/// Re-raise a worker exception as the builtin exception of the same name,
+/// or `RuntimeError` for anything else
+fn remote_exception(py: Python<'_>, kind: &str, message: String) -> PyErr {
+    let builtin = py
+        .import("builtins")
+        .and_then(|builtins| builtins.getattr(kind))
+        .ok()
+        .and_then(|ty| ty.cast_into::<PyType>().ok())
+        .filter(|ty| ty.is_subclass_of::<PyBaseException>().unwrap_or(false));
+    match builtin {
+        Some(ty) => PyErr::from_type(ty, message),
+        None => PyRuntimeError::new_err(format!("{}: {}", kind, message)),
+    }
+}
+
+/// Python AI bridge for calling Python functions. Clones share the worker
+/// process in subprocess mode.
+#[derive(Clone)]
 pub struct PythonAI {
     nous_py_path: PathBuf,
+    backend: Backend,
 }
 
 impl PythonAI {
-    /// Create a new PythonAI instance
+    /// Create a new PythonAI instance, in the mode picked by
+    /// [`PythonMode::from_env`]
     pub fn new(nous_py_path: PathBuf) -> Self {
-        Self { nous_py_path }
+        Self::with_mode(nous_py_path, PythonMode::from_env())
+    }
+
+    pub fn with_mode(nous_py_path: PathBuf, mode: PythonMode) -> Self {
+        let backend = match mode {
+            PythonMode::InProcess => Backend::InProcess,
+            PythonMode::Subprocess => {
+                log::info!("Running Python AI calls in a worker process");
+                Backend::Worker(Arc::new(PythonWorker::new(nous_py_path.clone())))
+            }
+        };
+        Self {
+            nous_py_path,
+            backend,
+        }
+    }
+
+    pub fn mode(&self) -> PythonMode {
+        match self.backend {
+            Backend::InProcess => PythonMode::InProcess,
+            Backend::Worker(_) => PythonMode::Subprocess,
+        }
+    }
+
+    /// The nous-py directory the bridge imports from
+    pub fn nous_py_path(&self) -> &Path {
+        &self.nous_py_path
+    }
+
+    /// Check the interpreter and the packages nous-py requires
+    pub fn check_environment(&self) -> PythonHealthReport {
+        let _timer = diagnostics::time(Category::Python, "check_environment");
+        health::check(&self.nous_py_path, self.mode())
+    }
+
+    /// Start the interpreter (or worker process) and import nous-py, so the
+    /// first real call doesn't pay for it. Importing any `nous_ai` module
+    /// loads the whole package.
+    pub fn warm_up(&self) -> Result<()> {
+        let _timer = diagnostics::time(Category::Python, "warm_up");
+        self.get_supported_extensions().map(|_| ())
     }
 
     /// Initialize Python path to include nous-py and its venv/bundled site-packages.
@@ -679,13 +929,23 @@ impl PythonAI {
         configure_python_path(py, &self.nous_py_path)
     }
 
+    /// `module.function` from nous-py, wherever this bridge runs it
+    fn function<'py>(
+        &self,
+        py: Python<'py>,
+        module: &str,
+        function: &str,
+    ) -> Result<Bound<'py, PyAny>> {
+        self.backend.function(py, module, function)
+    }
+
     /// Send a chat request to the AI provider
     pub fn chat(&self, messages: Vec<ChatMessage>, config: AIConfig) -> Result<ChatResponse> {
+        let _timer = diagnostics::time(Category::Python, "chat");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let chat_fn = chat_module.getattr("chat_sync")?;
+            let chat_fn = self.function(py, "nous_ai.chat", "chat_sync")?;
 
             // Convert messages to Python list of dicts
             let py_messages = PyList::empty(py);
@@ -751,11 +1011,11 @@ impl PythonAI {
         conversation_history: Option<Vec<ChatMessage>>,
         config: AIConfig,
     ) -> Result<ChatResponse> {
+        let _timer = diagnostics::time(Category::Python, "chat_with_context");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let chat_fn = chat_module.getattr("chat_with_context_sync")?;
+            let chat_fn = self.function(py, "nous_ai.chat", "chat_with_context_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("user_message", user_message)?;
@@ -838,11 +1098,11 @@ impl PythonAI {
         current_notebook_id: Option<String>,
         config: AIConfig,
     ) -> Result<ChatResponseWithActions> {
+        let _timer = diagnostics::time(Category::Python, "chat_with_tools");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let chat_fn = chat_module.getattr("chat_with_tools_sync")?;
+            let chat_fn = self.function(py, "nous_ai.chat", "chat_with_tools_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("user_message", user_message)?;
@@ -978,6 +1238,10 @@ impl PythonAI {
                     .get("thinking")
                     .and_then(|v| v.extract::<String>(py).ok())
                     .filter(|s| !s.is_empty()),
+                permission_denials: result_dict
+                    .get("permission_denials")
+                    .and_then(|v| py_to_serde(py, v))
+                    .unwrap_or_default(),
             })
         })
     }
@@ -995,8 +1259,10 @@ impl PythonAI {
         system_prompt: Option<String>,
         library_path: Option<String>,
     ) -> Result<mpsc::Receiver<StreamEvent>> {
+        let _timer = diagnostics::time(Category::Python, "chat_with_tools_stream");
         let (tx, rx) = mpsc::channel();
         let nous_py_path = self.nous_py_path.clone();
+        let backend = self.backend.clone();
 
         // Spawn a thread to run the Python code
         std::thread::spawn(move || {
@@ -1005,8 +1271,7 @@ impl PythonAI {
                 configure_python_path(py, &nous_py_path)?;
 
                 log::info!("Python bridge: attempting to import nous_ai.chat");
-                let chat_module = py.import("nous_ai.chat")?;
-                let chat_fn = chat_module.getattr("chat_with_tools_stream_sync")?;
+                let chat_fn = backend.function(py, "nous_ai.chat", "chat_with_tools_stream_sync")?;
 
                 // Create a Python callback that sends to our channel
                 let tx_clone = tx.clone();
@@ -1075,6 +1340,16 @@ impl PythonAI {
                                             .unwrap_or_else(|| "Unknown error".to_string());
                                         StreamEvent::Error { message }
                                     }
+                                    "tool_permission" => {
+                                        let value = event_dict.get("check");
+                                        match value.and_then(|v| py_to_serde(py, v)) {
+                                            Some(check) => StreamEvent::ToolPermission(check),
+                                            None => {
+                                                log::warn!("Unreadable tool permission event");
+                                                return;
+                                            }
+                                        }
+                                    }
                                     other => {
                                         log::warn!("Unknown AI stream event type: {}", other);
                                         return;
@@ -1179,11 +1454,11 @@ impl PythonAI {
         max_length: Option<i64>,
         config: AIConfig,
     ) -> Result<String> {
+        let _timer = diagnostics::time(Category::Python, "summarize_page");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let summarize_fn = chat_module.getattr("summarize_page_sync")?;
+            let summarize_fn = self.function(py, "nous_ai.chat", "summarize_page_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("content", content)?;
@@ -1220,11 +1495,11 @@ impl PythonAI {
         summary_style: Option<String>,
         config: AIConfig,
     ) -> Result<PagesSummaryResult> {
+        let _timer = diagnostics::time(Category::Python, "summarize_pages");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let summarize_fn = chat_module.getattr("summarize_pages_sync")?;
+            let summarize_fn = self.function(py, "nous_ai.chat", "summarize_pages_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -1295,11 +1570,11 @@ impl PythonAI {
         existing_tags: Option<Vec<String>>,
         config: AIConfig,
     ) -> Result<Vec<String>> {
+        let _timer = diagnostics::time(Category::Python, "suggest_tags");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let suggest_fn = chat_module.getattr("suggest_page_tags_sync")?;
+            let suggest_fn = self.function(py, "nous_ai.chat", "suggest_page_tags_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("content", content)?;
@@ -1332,11 +1607,11 @@ impl PythonAI {
         max_suggestions: Option<i64>,
         config: AIConfig,
     ) -> Result<Vec<RelatedPageSuggestion>> {
+        let _timer = diagnostics::time(Category::Python, "suggest_related_pages");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let suggest_fn = chat_module.getattr("suggest_related_pages_sync")?;
+            let suggest_fn = self.function(py, "nous_ai.chat", "suggest_related_pages_sync")?;
 
             // Convert available_pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -1396,11 +1671,11 @@ impl PythonAI {
         search_depth: String,
         include_answer: bool,
     ) -> Result<SearchResponse> {
+        let _timer = diagnostics::time(Category::Python, "web_search");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let web_module = py.import("nous_ai.web_research")?;
-            let search_fn = web_module.getattr("web_search_sync")?;
+            let search_fn = self.function(py, "nous_ai.web_research", "web_search_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("query", query)?;
@@ -1448,11 +1723,11 @@ impl PythonAI {
 
     /// Scrape content from a URL
     pub fn scrape_url(&self, url: String) -> Result<ScrapedContent> {
+        let _timer = diagnostics::time(Category::Python, "scrape_url");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let web_module = py.import("nous_ai.web_research")?;
-            let scrape_fn = web_module.getattr("scrape_url_sync")?;
+            let scrape_fn = self.function(py, "nous_ai.web_research", "scrape_url_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("url", url)?;
@@ -1478,11 +1753,11 @@ impl PythonAI {
         query: String,
         config: AIConfig,
     ) -> Result<ResearchSummary> {
+        let _timer = diagnostics::time(Category::Python, "summarize_research");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let web_module = py.import("nous_ai.web_research")?;
-            let summarize_fn = web_module.getattr("summarize_research_sync")?;
+            let summarize_fn = self.function(py, "nous_ai.web_research", "summarize_research_sync")?;
 
             // Convert contents to Python list of dicts
             let py_contents = PyList::empty(py);
@@ -1551,11 +1826,11 @@ impl PythonAI {
         notebooks: &[serde_json::Value],
         pages: &[serde_json::Value],
     ) -> Result<crate::inbox::InboxClassification> {
+        let _timer = diagnostics::time(Category::Python, "classify_inbox_item");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let inbox_module = py.import("nous_ai.inbox")?;
-            let classify_fn = inbox_module.getattr("classify_inbox_item_sync")?;
+            let classify_fn = self.function(py, "nous_ai.inbox", "classify_inbox_item_sync")?;
 
             // Convert notebooks to Python list
             let py_notebooks = PyList::empty(py);
@@ -1702,11 +1977,11 @@ impl PythonAI {
         pages: &[serde_json::Value],
         destinations: &[serde_json::Value],
     ) -> Result<Vec<serde_json::Value>> {
+        let _timer = diagnostics::time(Category::Python, "smart_organize");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let organize_module = py.import("nous_ai.organize")?;
-            let suggest_fn = organize_module.getattr("suggest_organization_sync")?;
+            let suggest_fn = self.function(py, "nous_ai.organize", "suggest_organization_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -1819,11 +2094,11 @@ impl PythonAI {
 
     /// Convert a document to Markdown using markitdown
     pub fn convert_document(&self, file_path: String) -> Result<DocumentConversionResult> {
+        let _timer = diagnostics::time(Category::Python, "convert_document");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let doc_module = py.import("nous_ai.document_convert")?;
-            let convert_fn = doc_module.getattr("convert_document_sync")?;
+            let convert_fn = self.function(py, "nous_ai.document_convert", "convert_document_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("file_path", file_path)?;
@@ -1863,11 +2138,11 @@ impl PythonAI {
         &self,
         file_paths: Vec<String>,
     ) -> Result<Vec<DocumentConversionResult>> {
+        let _timer = diagnostics::time(Category::Python, "convert_documents_batch");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let doc_module = py.import("nous_ai.document_convert")?;
-            let convert_fn = doc_module.getattr("convert_documents_batch_sync")?;
+            let convert_fn = self.function(py, "nous_ai.document_convert", "convert_documents_batch_sync")?;
 
             // Convert file paths to Python list
             let py_paths = PyList::empty(py);
@@ -1915,11 +2190,11 @@ impl PythonAI {
 
     /// Get list of supported file extensions for document conversion
     pub fn get_supported_extensions(&self) -> Result<Vec<String>> {
+        let _timer = diagnostics::time(Category::Python, "get_supported_extensions");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let doc_module = py.import("nous_ai.document_convert")?;
-            let get_ext_fn = doc_module.getattr("get_supported_extensions_sync")?;
+            let get_ext_fn = self.function(py, "nous_ai.document_convert", "get_supported_extensions_sync")?;
 
             let result = get_ext_fn.call0()?;
             let extensions: Vec<String> = result.extract()?;
@@ -1930,11 +2205,11 @@ impl PythonAI {
 
     /// Check if a file type is supported for conversion
     pub fn is_supported_file(&self, file_path: String) -> Result<bool> {
+        let _timer = diagnostics::time(Category::Python, "is_supported_file");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let doc_module = py.import("nous_ai.document_convert")?;
-            let is_supported_fn = doc_module.getattr("is_supported_file_sync")?;
+            let is_supported_fn = self.function(py, "nous_ai.document_convert", "is_supported_file_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("file_path", file_path)?;
@@ -1957,11 +2232,11 @@ impl PythonAI {
         model: &str,
         capture_screenshot: bool,
     ) -> Result<BrowserTaskResult> {
+        let _timer = diagnostics::time(Category::Python, "run_browser_task");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let browser_module = py.import("nous_ai.browser_automation")?;
-            let run_fn = browser_module.getattr("run_browser_task_sync")?;
+            let run_fn = self.function(py, "nous_ai.browser_automation", "run_browser_task_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("task", task)?;
@@ -2012,11 +2287,11 @@ impl PythonAI {
         model_size: Option<&str>,
         language: Option<&str>,
     ) -> Result<TranscriptionResult> {
+        let _timer = diagnostics::time(Category::Python, "transcribe_video");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let video_module = py.import("nous_ai.video_transcribe")?;
-            let transcribe_fn = video_module.getattr("transcribe_video_sync")?;
+            let transcribe_fn = self.function(py, "nous_ai.video_transcribe", "transcribe_video_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("video_path", video_path)?;
@@ -2104,6 +2379,42 @@ impl PythonAI {
         })
     }
 
+    /// Find who spoke when in an audio file using pyannote.audio. Fails if
+    /// pyannote isn't installed or its model can't be downloaded.
+    pub fn diarize_audio(
+        &self,
+        audio_path: &str,
+        num_speakers: Option<usize>,
+    ) -> Result<Vec<SpeakerTurn>> {
+        let _timer = diagnostics::time(Category::Python, "diarize_audio");
+        Python::attach(|py| {
+            self.setup_python_path(py)?;
+
+            let diarize_fn = self.function(py, "nous_ai.video_transcribe", "diarize_audio_sync")?;
+
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("audio_path", audio_path)?;
+            if let Some(n) = num_speakers {
+                kwargs.set_item("num_speakers", n)?;
+            }
+
+            let result = diarize_fn.call((), Some(&kwargs))?;
+            let turns: Vec<HashMap<String, Py<PyAny>>> = result.extract()?;
+
+            Ok(turns
+                .into_iter()
+                .map(|t| SpeakerTurn {
+                    start: t.get("start").and_then(|v| v.extract::<f64>(py).ok()).unwrap_or(0.0),
+                    end: t.get("end").and_then(|v| v.extract::<f64>(py).ok()).unwrap_or(0.0),
+                    speaker: t
+                        .get("speaker")
+                        .and_then(|v| v.extract::<String>(py).ok())
+                        .unwrap_or_default(),
+                })
+                .collect())
+        })
+    }
+
     /// Transcribe an audio file using faster-whisper
     pub fn transcribe_audio(
         &self,
@@ -2111,11 +2422,11 @@ impl PythonAI {
         model_size: Option<&str>,
         language: Option<&str>,
     ) -> Result<TranscriptionResult> {
+        let _timer = diagnostics::time(Category::Python, "transcribe_audio");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let video_module = py.import("nous_ai.video_transcribe")?;
-            let transcribe_fn = video_module.getattr("transcribe_audio_sync")?;
+            let transcribe_fn = self.function(py, "nous_ai.video_transcribe", "transcribe_audio_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("audio_path", audio_path)?;
@@ -2214,11 +2525,11 @@ impl PythonAI {
         tts_model: Option<&str>,
         tts_speed: Option<f64>,
     ) -> Result<AudioGenerationResult> {
+        let _timer = diagnostics::time(Category::Python, "synthesize_text");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.audio_generate")?;
-            let func = module.getattr("generate_page_audio_sync")?;
+            let func = self.function(py, "nous_ai.audio_generate", "generate_page_audio_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("content", text)?;
@@ -2305,11 +2616,11 @@ impl PythonAI {
 
     /// Get video duration in seconds
     pub fn get_video_duration(&self, video_path: &str) -> Result<f64> {
+        let _timer = diagnostics::time(Category::Python, "get_video_duration");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let video_module = py.import("nous_ai.video_transcribe")?;
-            let duration_fn = video_module.getattr("get_video_duration_sync")?;
+            let duration_fn = self.function(py, "nous_ai.video_transcribe", "get_video_duration_sync")?;
 
             let result = duration_fn.call1((video_path,))?;
             let duration: f64 = result.extract()?;
@@ -2320,11 +2631,11 @@ impl PythonAI {
 
     /// Check if a file is a supported video format
     pub fn is_supported_video(&self, file_path: &str) -> Result<bool> {
+        let _timer = diagnostics::time(Category::Python, "is_supported_video");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let video_module = py.import("nous_ai.video_transcribe")?;
-            let check_fn = video_module.getattr("is_supported_video_sync")?;
+            let check_fn = self.function(py, "nous_ai.video_transcribe", "is_supported_video_sync")?;
 
             let result = check_fn.call1((file_path,))?;
             let is_supported: bool = result.extract()?;
@@ -2335,11 +2646,11 @@ impl PythonAI {
 
     /// Get list of supported video extensions
     pub fn get_supported_video_extensions(&self) -> Result<Vec<String>> {
+        let _timer = diagnostics::time(Category::Python, "get_supported_video_extensions");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let video_module = py.import("nous_ai.video_transcribe")?;
-            let get_ext_fn = video_module.getattr("get_supported_extensions_sync")?;
+            let get_ext_fn = self.function(py, "nous_ai.video_transcribe", "get_supported_extensions_sync")?;
 
             let result = get_ext_fn.call0()?;
             let extensions: Vec<String> = result.extract()?;
@@ -2356,11 +2667,11 @@ impl PythonAI {
         timestamp_seconds: Option<f64>,
         width: Option<i32>,
     ) -> Result<String> {
+        let _timer = diagnostics::time(Category::Python, "extract_video_thumbnail");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let video_module = py.import("nous_ai.video_transcribe")?;
-            let extract_fn = video_module.getattr("extract_thumbnail_sync")?;
+            let extract_fn = self.function(py, "nous_ai.video_transcribe", "extract_thumbnail_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("video_path", video_path)?;
@@ -2385,11 +2696,11 @@ impl PythonAI {
 
     /// Execute a Jupyter notebook code cell
     pub fn execute_jupyter_cell(&self, code: String, cell_index: usize) -> Result<JupyterCellOutput> {
+        let _timer = diagnostics::time(Category::Python, "execute_jupyter_cell");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let jupyter_module = py.import("nous_ai.jupyter_execute")?;
-            let execute_fn = jupyter_module.getattr("execute_cell")?;
+            let execute_fn = self.function(py, "nous_ai.jupyter_execute", "execute_cell")?;
 
             let result = execute_fn.call1((code, cell_index))?;
             let result_dict: HashMap<String, Py<PyAny>> = result.extract()?;
@@ -2432,11 +2743,11 @@ impl PythonAI {
 
     /// Check if Python execution is available
     pub fn check_python_available(&self) -> Result<PythonEnvironmentInfo> {
+        let _timer = diagnostics::time(Category::Python, "check_python_available");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let jupyter_module = py.import("nous_ai.jupyter_execute")?;
-            let check_fn = jupyter_module.getattr("check_python_available")?;
+            let check_fn = self.function(py, "nous_ai.jupyter_execute", "check_python_available")?;
 
             let result = check_fn.call0()?;
             let result_dict: HashMap<String, Py<PyAny>> = result.extract()?;
@@ -2462,11 +2773,11 @@ impl PythonAI {
 
     /// Load MCP server configuration for a library
     pub fn mcp_load_config(&self, library_path: &str) -> Result<MCPServersConfig> {
+        let _timer = diagnostics::time(Category::Python, "mcp_load_config");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let load_fn = mcp_module.getattr("mcp_load_config_sync")?;
+            let load_fn = self.function(py, "nous_ai.mcp_client", "mcp_load_config_sync")?;
 
             let result = load_fn.call1((library_path,))?;
             let result_dict: HashMap<String, Py<PyAny>> = result.extract()?;
@@ -2494,11 +2805,11 @@ impl PythonAI {
 
     /// Save MCP server configuration for a library
     pub fn mcp_save_config(&self, library_path: &str, config: MCPServersConfig) -> Result<()> {
+        let _timer = diagnostics::time(Category::Python, "mcp_save_config");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let save_fn = mcp_module.getattr("mcp_save_config_sync")?;
+            let save_fn = self.function(py, "nous_ai.mcp_client", "mcp_save_config_sync")?;
 
             // Convert config to Python dict
             let config_dict = PyDict::new(py);
@@ -2529,11 +2840,11 @@ impl PythonAI {
 
     /// Start all enabled MCP servers for a library
     pub fn mcp_start_servers(&self, library_path: &str) -> Result<Vec<String>> {
+        let _timer = diagnostics::time(Category::Python, "mcp_start_servers");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let start_fn = mcp_module.getattr("mcp_start_servers_sync")?;
+            let start_fn = self.function(py, "nous_ai.mcp_client", "mcp_start_servers_sync")?;
 
             let result = start_fn.call1((library_path,))?;
             let started: Vec<String> = result.extract()?;
@@ -2544,11 +2855,11 @@ impl PythonAI {
 
     /// Stop all MCP servers for a library
     pub fn mcp_stop_servers(&self, library_path: &str) -> Result<()> {
+        let _timer = diagnostics::time(Category::Python, "mcp_stop_servers");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let stop_fn = mcp_module.getattr("mcp_stop_servers_sync")?;
+            let stop_fn = self.function(py, "nous_ai.mcp_client", "mcp_stop_servers_sync")?;
 
             stop_fn.call1((library_path,))?;
             Ok(())
@@ -2557,11 +2868,11 @@ impl PythonAI {
 
     /// Get all tools from running MCP servers
     pub fn mcp_get_tools(&self, library_path: &str) -> Result<Vec<MCPTool>> {
+        let _timer = diagnostics::time(Category::Python, "mcp_get_tools");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let get_tools_fn = mcp_module.getattr("mcp_get_tools_sync")?;
+            let get_tools_fn = self.function(py, "nous_ai.mcp_client", "mcp_get_tools_sync")?;
 
             let result = get_tools_fn.call1((library_path,))?;
             let tools_list: Vec<HashMap<String, Py<PyAny>>> = result.extract()?;
@@ -2606,11 +2917,11 @@ impl PythonAI {
         tool_name: &str,
         arguments: serde_json::Value,
     ) -> Result<MCPToolResult> {
+        let _timer = diagnostics::time(Category::Python, "mcp_call_tool");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let call_fn = mcp_module.getattr("mcp_call_tool_sync")?;
+            let call_fn = self.function(py, "nous_ai.mcp_client", "mcp_call_tool_sync")?;
 
             // Convert arguments to Python dict
             let json_module = py.import("json")?;
@@ -2650,11 +2961,11 @@ impl PythonAI {
 
     /// Get list of running MCP server names
     pub fn mcp_get_running_servers(&self, library_path: &str) -> Result<Vec<String>> {
+        let _timer = diagnostics::time(Category::Python, "mcp_get_running_servers");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let mcp_module = py.import("nous_ai.mcp_client")?;
-            let get_fn = mcp_module.getattr("mcp_get_running_servers_sync")?;
+            let get_fn = self.function(py, "nous_ai.mcp_client", "mcp_get_running_servers_sync")?;
 
             let result = get_fn.call1((library_path,))?;
             let servers: Vec<String> = result.extract()?;
@@ -2667,11 +2978,11 @@ impl PythonAI {
 
     /// Generate embedding for a single text
     pub fn generate_embedding(&self, text: &str, config: &str) -> Result<Vec<f64>> {
+        let _timer = diagnostics::time(Category::Python, "generate_embedding");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let embed_module = py.import("nous_ai.embeddings")?;
-            let embed_fn = embed_module.getattr("generate_embedding_sync")?;
+            let embed_fn = self.function(py, "nous_ai.embeddings", "generate_embedding_sync")?;
 
             // Parse config JSON to Python dict
             let config_dict: serde_json::Value = serde_json::from_str(config)?;
@@ -2703,11 +3014,11 @@ impl PythonAI {
 
     /// Generate embeddings for multiple texts
     pub fn generate_embeddings_batch(&self, texts: Vec<&str>, config: &str) -> Result<Vec<Vec<f64>>> {
+        let _timer = diagnostics::time(Category::Python, "generate_embeddings_batch");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let embed_module = py.import("nous_ai.embeddings")?;
-            let embed_fn = embed_module.getattr("generate_embeddings_batch_sync")?;
+            let embed_fn = self.function(py, "nous_ai.embeddings", "generate_embeddings_batch_sync")?;
 
             // Convert texts to Python list
             let py_texts = PyList::empty(py);
@@ -2745,11 +3056,11 @@ impl PythonAI {
 
     /// Discover available embedding models from a provider
     pub fn discover_embedding_models(&self, provider: &str, base_url: Option<&str>) -> Result<Vec<DiscoveredModel>> {
+        let _timer = diagnostics::time(Category::Python, "discover_embedding_models");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let embed_module = py.import("nous_ai.embeddings")?;
-            let discover_fn = embed_module.getattr("discover_models_sync")?;
+            let discover_fn = self.function(py, "nous_ai.embeddings", "discover_models_sync")?;
 
             let result = discover_fn.call1((provider, base_url))?;
             let models_list: Vec<HashMap<String, Py<PyAny>>> = result.extract()?;
@@ -2798,11 +3109,11 @@ impl PythonAI {
         target_length: Option<&str>,
         custom_instructions: Option<&str>,
     ) -> Result<AudioGenerationResult> {
+        let _timer = diagnostics::time(Category::Python, "generate_page_audio");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.audio_generate")?;
-            let func = module.getattr("generate_page_audio_sync")?;
+            let func = self.function(py, "nous_ai.audio_generate", "generate_page_audio_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("content", content)?;
@@ -2917,6 +3228,105 @@ impl PythonAI {
         })
     }
 
+    /// Narrate `chapters` (title, plain text) into one MP3 at `output_path`,
+    /// returning where each chapter starts and ends
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate_reading_queue_audio(
+        &self,
+        chapters: &[(String, String)],
+        output_path: &str,
+        tts_provider: &str,
+        tts_voice: &str,
+        tts_api_key: Option<&str>,
+        tts_base_url: Option<&str>,
+        tts_model: Option<&str>,
+        tts_speed: Option<f64>,
+    ) -> Result<ReadingQueueAudioResult> {
+        let _timer = diagnostics::time(Category::Python, "generate_reading_queue_audio");
+        Python::attach(|py| {
+            self.setup_python_path(py)?;
+
+            let func = self.function(
+                py,
+                "nous_ai.audio_generate",
+                "generate_reading_queue_audio_sync",
+            )?;
+
+            let chapter_list = PyList::empty(py);
+            for (title, content) in chapters {
+                let chapter = PyDict::new(py);
+                chapter.set_item("title", title)?;
+                chapter.set_item("content", content)?;
+                chapter_list.append(chapter)?;
+            }
+
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("chapters", chapter_list)?;
+            kwargs.set_item("output_path", output_path)?;
+
+            let tts_config = PyDict::new(py);
+            tts_config.set_item("provider", tts_provider)?;
+            tts_config.set_item("voice", tts_voice)?;
+            if let Some(key) = tts_api_key {
+                tts_config.set_item("api_key", key)?;
+            }
+            if let Some(url) = tts_base_url {
+                tts_config.set_item("base_url", url)?;
+            }
+            if let Some(model) = tts_model {
+                tts_config.set_item("model", model)?;
+            }
+            if let Some(speed) = tts_speed {
+                tts_config.set_item("speed", speed)?;
+            }
+            kwargs.set_item("tts_config", tts_config)?;
+
+            let result = func.call((), Some(&kwargs))?;
+            let result_dict: HashMap<String, Py<PyAny>> = result.extract()?;
+
+            let chapters = result_dict
+                .get("chapters")
+                .and_then(|v| v.extract::<Vec<HashMap<String, Py<PyAny>>>>(py).ok())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|chapter| AudioChapter {
+                    title: chapter
+                        .get("title")
+                        .and_then(|v| v.extract::<String>(py).ok())
+                        .unwrap_or_default(),
+                    start_seconds: chapter
+                        .get("start_seconds")
+                        .and_then(|v| v.extract::<f64>(py).ok())
+                        .unwrap_or(0.0),
+                    end_seconds: chapter
+                        .get("end_seconds")
+                        .and_then(|v| v.extract::<f64>(py).ok())
+                        .unwrap_or(0.0),
+                })
+                .collect();
+
+            Ok(ReadingQueueAudioResult {
+                audio_path: result_dict
+                    .get("audio_path")
+                    .and_then(|v| v.extract::<String>(py).ok())
+                    .unwrap_or_default(),
+                duration_seconds: result_dict
+                    .get("duration_seconds")
+                    .and_then(|v| v.extract::<f64>(py).ok())
+                    .unwrap_or(0.0),
+                file_size_bytes: result_dict
+                    .get("file_size_bytes")
+                    .and_then(|v| v.extract::<i64>(py).ok())
+                    .unwrap_or(0),
+                generation_time_seconds: result_dict
+                    .get("generation_time_seconds")
+                    .and_then(|v| v.extract::<f64>(py).ok())
+                    .unwrap_or(0.0),
+                chapters,
+            })
+        })
+    }
+
     /// List available voices for a TTS provider
     pub fn list_tts_voices(
         &self,
@@ -2924,11 +3334,11 @@ impl PythonAI {
         api_key: Option<&str>,
         base_url: Option<&str>,
     ) -> Result<Vec<TTSVoiceInfo>> {
+        let _timer = diagnostics::time(Category::Python, "list_tts_voices");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.audio_generate")?;
-            let func = module.getattr("list_tts_voices_sync")?;
+            let func = self.function(py, "nous_ai.audio_generate", "list_tts_voices_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("provider", provider)?;
@@ -2970,11 +3380,11 @@ impl PythonAI {
 
     /// Get available TTS providers and their status
     pub fn get_tts_providers(&self) -> Result<Vec<TTSProviderInfo>> {
+        let _timer = diagnostics::time(Category::Python, "get_tts_providers");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.audio_generate")?;
-            let func = module.getattr("get_tts_providers_sync")?;
+            let func = self.function(py, "nous_ai.audio_generate", "get_tts_providers_sync")?;
 
             let result = func.call0()?;
             let providers_list: Vec<HashMap<String, Py<PyAny>>> = result.extract()?;
@@ -3002,11 +3412,11 @@ impl PythonAI {
     }
 
     pub fn discover_chat_models(&self, provider: &str, base_url: &str, api_key: Option<&str>) -> Result<Vec<DiscoveredChatModel>> {
+        let _timer = diagnostics::time(Category::Python, "discover_chat_models");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let chat_module = py.import("nous_ai.chat")?;
-            let discover_fn = chat_module.getattr("discover_chat_models_sync")?;
+            let discover_fn = self.function(py, "nous_ai.chat", "discover_chat_models_sync")?;
 
             let result = discover_fn.call1((provider, base_url, api_key.unwrap_or("")))?;
             let models_list: Vec<HashMap<String, Py<PyAny>>> = result.extract()?;
@@ -3041,11 +3451,11 @@ impl PythonAI {
         config: AIConfig,
         options: Option<StudyGuideOptions>,
     ) -> Result<StudyGuide> {
+        let _timer = diagnostics::time(Category::Python, "generate_study_guide");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let generate_fn = study_module.getattr("generate_study_guide_sync")?;
+            let generate_fn = self.function(py, "nous_ai.study_tools", "generate_study_guide_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -3107,11 +3517,11 @@ impl PythonAI {
         config: AIConfig,
         num_questions: Option<i32>,
     ) -> Result<FAQ> {
+        let _timer = diagnostics::time(Category::Python, "generate_faq");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let generate_fn = study_module.getattr("generate_faq_sync")?;
+            let generate_fn = self.function(py, "nous_ai.study_tools", "generate_faq_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -3167,11 +3577,11 @@ impl PythonAI {
         num_cards: Option<i32>,
         card_types: Option<Vec<String>>,
     ) -> Result<FlashcardGenerationResult> {
+        let _timer = diagnostics::time(Category::Python, "generate_flashcards");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let generate_fn = study_module.getattr("generate_flashcards_sync")?;
+            let generate_fn = self.function(py, "nous_ai.study_tools", "generate_flashcards_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -3229,11 +3639,11 @@ impl PythonAI {
         config: AIConfig,
         include_action_items: Option<bool>,
     ) -> Result<BriefingDocument> {
+        let _timer = diagnostics::time(Category::Python, "generate_briefing");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let generate_fn = study_module.getattr("generate_briefing_sync")?;
+            let generate_fn = self.function(py, "nous_ai.study_tools", "generate_briefing_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -3287,11 +3697,11 @@ impl PythonAI {
         pages: Vec<StudyPageContent>,
         config: AIConfig,
     ) -> Result<Timeline> {
+        let _timer = diagnostics::time(Category::Python, "extract_timeline");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let extract_fn = study_module.getattr("extract_timeline_sync")?;
+            let extract_fn = self.function(py, "nous_ai.study_tools", "extract_timeline_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -3343,11 +3753,11 @@ impl PythonAI {
         config: AIConfig,
         max_nodes: Option<i32>,
     ) -> Result<ConceptGraph> {
+        let _timer = diagnostics::time(Category::Python, "extract_concepts");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let extract_fn = study_module.getattr("extract_concepts_sync")?;
+            let extract_fn = self.function(py, "nous_ai.study_tools", "extract_concepts_sync")?;
 
             // Convert pages to Python list of dicts
             let py_pages = PyList::empty(py);
@@ -3403,11 +3813,11 @@ impl PythonAI {
         config: AIConfig,
         max_citations: Option<i32>,
     ) -> Result<CitedResponse> {
+        let _timer = diagnostics::time(Category::Python, "chat_with_citations");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let study_module = py.import("nous_ai.study_tools")?;
-            let chat_fn = study_module.getattr("chat_with_citations_sync")?;
+            let chat_fn = self.function(py, "nous_ai.study_tools", "chat_with_citations_sync")?;
 
             // Convert chunks to Python list of dicts
             let py_chunks = PyList::empty(py);
@@ -3419,6 +3829,8 @@ impl PythonAI {
                 dict.set_item("title", chunk.title)?;
                 dict.set_item("content", chunk.content)?;
                 dict.set_item("score", chunk.score)?;
+                dict.set_item("library_id", chunk.library_id)?;
+                dict.set_item("library_name", chunk.library_name)?;
                 py_chunks.append(dict)?;
             }
 
@@ -3469,11 +3881,11 @@ impl PythonAI {
         config: Option<&crate::commands::InfographicConfig>,
         export_png: bool,
     ) -> Result<InfographicResult> {
+        let _timer = diagnostics::time(Category::Python, "generate_infographic");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.infographic_generate")?;
-            let func = module.getattr("generate_infographic_sync")?;
+            let func = self.function(py, "nous_ai.infographic_generate", "generate_infographic_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("template", template)?;
@@ -3529,11 +3941,11 @@ impl PythonAI {
 
     /// Check availability of infographic features
     pub fn check_infographic_availability(&self) -> Result<serde_json::Value> {
+        let _timer = diagnostics::time(Category::Python, "check_infographic_availability");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.infographic_generate")?;
-            let func = module.getattr("check_infographic_availability")?;
+            let func = self.function(py, "nous_ai.infographic_generate", "check_infographic_availability")?;
 
             let result = func.call0()?;
 
@@ -3547,6 +3959,64 @@ impl PythonAI {
         })
     }
 
+    // ===== PDF Annotation Methods =====
+
+    /// Write a copy of `source_path` to `output_path` with the given Nous
+    /// highlights (PDFHighlight JSON) embedded as real PDF annotations.
+    /// Returns `{output_path, annotation_count, skipped}`.
+    pub fn export_annotated_pdf(
+        &self,
+        source_path: &str,
+        output_path: &str,
+        highlights: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let _timer = diagnostics::time(Category::Python, "export_annotated_pdf");
+        Python::attach(|py| {
+            self.setup_python_path(py)?;
+
+            let func = self.function(py, "nous_ai.pdf_annotations", "export_annotated_pdf_sync")?;
+
+            let json_module = py.import("json")?;
+            let loads = json_module.getattr("loads")?;
+            let highlights_str =
+                serde_json::to_string(&highlights).map_err(PythonError::Serialization)?;
+            let py_highlights = loads.call1((highlights_str,))?;
+
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("source_path", source_path)?;
+            kwargs.set_item("output_path", output_path)?;
+            kwargs.set_item("highlights", py_highlights)?;
+
+            let result = func.call((), Some(&kwargs))?;
+
+            let dumps = json_module.getattr("dumps")?;
+            let json_str: String = dumps.call1((result,))?.extract()?;
+            let value: serde_json::Value = serde_json::from_str(&json_str)?;
+
+            Ok(value)
+        })
+    }
+
+    /// Read highlight/markup/note annotations out of an externally annotated
+    /// PDF. Returns `{highlights: [PDFHighlight JSON...]}`.
+    pub fn import_pdf_annotations(&self, source_path: &str) -> Result<serde_json::Value> {
+        let _timer = diagnostics::time(Category::Python, "import_pdf_annotations");
+        Python::attach(|py| {
+            self.setup_python_path(py)?;
+
+            let func = self.function(py, "nous_ai.pdf_annotations", "import_pdf_annotations_sync")?;
+
+            let result = func.call1((source_path,))?;
+
+            let json_module = py.import("json")?;
+            let dumps = json_module.getattr("dumps")?;
+            let json_str: String = dumps.call1((result,))?.extract()?;
+            let value: serde_json::Value = serde_json::from_str(&json_str)?;
+
+            Ok(value)
+        })
+    }
+
     // ===== Video Generation Methods =====
 
     /// Generate a narrated video from study content
@@ -3566,11 +4036,11 @@ impl PythonAI {
         transition: &str,
         title: Option<&str>,
     ) -> Result<VideoGenerationResult> {
+        let _timer = diagnostics::time(Category::Python, "generate_video");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.video_generate")?;
-            let func = module.getattr("generate_video_sync")?;
+            let func = self.function(py, "nous_ai.video_generate", "generate_video_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("output_dir", output_dir)?;
@@ -3657,11 +4127,11 @@ impl PythonAI {
         title: Option<&str>,
         progress_tx: std::sync::mpsc::Sender<(i32, i32, String)>,
     ) -> Result<VideoGenerationResult> {
+        let _timer = diagnostics::time(Category::Python, "generate_video_with_progress");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.video_generate")?;
-            let func = module.getattr("generate_video_sync")?;
+            let func = self.function(py, "nous_ai.video_generate", "generate_video_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("output_dir", output_dir)?;
@@ -3753,11 +4223,11 @@ impl PythonAI {
 
     /// Check availability of video generation features
     pub fn check_video_generation_availability(&self) -> Result<serde_json::Value> {
+        let _timer = diagnostics::time(Category::Python, "check_video_generation_availability");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let module = py.import("nous_ai.video_generate")?;
-            let func = module.getattr("check_video_availability")?;
+            let func = self.function(py, "nous_ai.video_generate", "check_video_availability")?;
 
             let result = func.call0()?;
 
@@ -3777,11 +4247,11 @@ impl PythonAI {
         image_path: &str,
         watch_instructions: Option<&str>,
     ) -> Result<serde_json::Value> {
+        let _timer = diagnostics::time(Category::Python, "analyze_screenshot");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let vision_module = py.import("nous_ai.vision")?;
-            let analyze_fn = vision_module.getattr("analyze_screenshot_sync")?;
+            let analyze_fn = self.function(py, "nous_ai.vision", "analyze_screenshot_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("image_path", image_path)?;
@@ -3801,11 +4271,11 @@ impl PythonAI {
 
     /// Scrape accessibility tree from a window
     pub fn scrape_accessibility(&self, window_name: &str) -> Result<serde_json::Value> {
+        let _timer = diagnostics::time(Category::Python, "scrape_accessibility");
         Python::attach(|py| {
             self.setup_python_path(py)?;
 
-            let a11y_module = py.import("nous_ai.accessibility")?;
-            let scrape_fn = a11y_module.getattr("scrape_window_atspi_sync")?;
+            let scrape_fn = self.function(py, "nous_ai.accessibility", "scrape_window_atspi_sync")?;
 
             let kwargs = PyDict::new(py);
             kwargs.set_item("window_name", window_name)?;
@@ -3835,6 +4305,26 @@ pub struct AudioGenerationResult {
     pub transcript: Option<Vec<PodcastLine>>,
 }
 
+/// Result from narrating a reading queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueueAudioResult {
+    pub audio_path: String,
+    pub duration_seconds: f64,
+    pub file_size_bytes: i64,
+    pub generation_time_seconds: f64,
+    pub chapters: Vec<AudioChapter>,
+}
+
+/// One chapter of a narrated reading queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioChapter {
+    pub title: String,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
 /// A single line of podcast dialogue
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
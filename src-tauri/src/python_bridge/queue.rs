@@ -0,0 +1,288 @@
+//! Worker pool for Python-bound work
+//!
+//! Commands used to call [`PythonAI`] on their own thread behind a mutex, so
+//! AI calls ran one at a time and a slow one held a Tauri command thread for
+//! its whole length. The queue runs them on a fixed set of worker threads
+//! instead: callers await the result, the queue is bounded so a burst of
+//! requests fails fast rather than piling up, and every task has a timeout
+//! and a cancellation token. The workers still take turns on the GIL while
+//! running Python code, but the network-bound calls that dominate (LLM and
+//! embedding requests) release it while they wait.
+//!
+//! A cancelled or timed-out task that hasn't started is skipped. One that is
+//! already running can't be interrupted; it finishes on its worker and the
+//! result is dropped. The exception is a call into the Python worker process
+//! (subprocess mode), which gives up and kills the process.
+
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use super::{worker, PythonAI};
+
+/// Worker threads running Python calls
+pub const DEFAULT_WORKERS: usize = 4;
+
+/// Tasks that can wait for a worker before submissions are refused
+pub const DEFAULT_CAPACITY: usize = 32;
+
+/// How long a task may queue and run before its caller gives up
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Error, Debug)]
+pub enum QueueError {
+    #[error("Too many AI requests in progress ({0} waiting); try again shortly")]
+    Full(usize),
+
+    #[error("AI request timed out after {} seconds", .0.as_secs())]
+    Timeout(Duration),
+
+    #[error("AI request was cancelled")]
+    Cancelled,
+
+    #[error("AI request failed unexpectedly")]
+    Panicked,
+
+    #[error("Python task queue has shut down")]
+    Closed,
+}
+
+/// How to run one task
+#[derive(Debug, Clone)]
+pub struct TaskOptions {
+    /// Id the task can be cancelled by (see [`PythonQueue::cancel`])
+    pub task_id: Option<String>,
+    pub timeout: Duration,
+}
+
+impl Default for TaskOptions {
+    fn default() -> Self {
+        Self {
+            task_id: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl TaskOptions {
+    pub fn with_task_id(mut self, task_id: Option<String>) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+type Job = Box<dyn FnOnce(&PythonAI) + Send>;
+
+type TaskMap = Arc<Mutex<HashMap<String, CancellationToken>>>;
+
+pub struct PythonQueue {
+    sender: SyncSender<Job>,
+    capacity: usize,
+    /// Tokens of running or waiting tasks submitted with an id
+    tasks: TaskMap,
+}
+
+impl PythonQueue {
+    pub fn new(python_ai: PythonAI) -> Self {
+        Self::with_limits(python_ai, DEFAULT_WORKERS, DEFAULT_CAPACITY)
+    }
+
+    pub fn with_limits(python_ai: PythonAI, workers: usize, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<Job>(capacity);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let python_ai = Arc::new(python_ai);
+
+        for index in 0..workers.max(1) {
+            let receiver = Arc::clone(&receiver);
+            let python_ai = Arc::clone(&python_ai);
+            std::thread::Builder::new()
+                .name(format!("python-worker-{}", index))
+                .spawn(move || run_worker(&receiver, &python_ai))
+                .expect("failed to spawn Python worker thread");
+        }
+
+        Self {
+            sender,
+            capacity,
+            tasks: TaskMap::default(),
+        }
+    }
+
+    /// Run `job` on a worker and wait for its result, within the options'
+    /// timeout
+    pub async fn run<T, F>(&self, options: TaskOptions, job: F) -> Result<T, QueueError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&PythonAI) -> T + Send + 'static,
+    {
+        let token = CancellationToken::new();
+        let _registration = options
+            .task_id
+            .map(|id| TaskRegistration::new(&self.tasks, id, token.clone()));
+
+        let (tx, rx) = oneshot::channel();
+        let job_token = token.clone();
+        let job: Job = Box::new(move |python_ai| {
+            if job_token.is_cancelled() {
+                return;
+            }
+            let _ = tx.send(worker::with_cancellation(&job_token, || job(python_ai)));
+        });
+        match self.sender.try_send(job) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => return Err(QueueError::Full(self.capacity)),
+            Err(TrySendError::Disconnected(_)) => return Err(QueueError::Closed),
+        }
+
+        tokio::select! {
+            result = rx => result.map_err(|_| {
+                if token.is_cancelled() {
+                    QueueError::Cancelled
+                } else {
+                    QueueError::Panicked
+                }
+            }),
+            _ = token.cancelled() => Err(QueueError::Cancelled),
+            _ = tokio::time::sleep(options.timeout) => {
+                token.cancel();
+                Err(QueueError::Timeout(options.timeout))
+            }
+        }
+    }
+
+    /// Cancel the task submitted with `task_id`. Returns whether one was
+    /// waiting or running.
+    pub fn cancel(&self, task_id: &str) -> bool {
+        match self.tasks.lock().unwrap().get(task_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn run_worker(receiver: &Mutex<Receiver<Job>>, python_ai: &PythonAI) {
+    loop {
+        // Hold the lock only while taking a job, so the other workers can
+        // pick up the next one
+        let job = match receiver.lock().unwrap().recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        // A panicking job drops its result sender, which the caller reports
+        if panic::catch_unwind(AssertUnwindSafe(|| job(python_ai))).is_err() {
+            log::error!("Python task panicked");
+        }
+    }
+}
+
+/// Keeps a task's token in the map while its caller waits
+struct TaskRegistration {
+    tasks: TaskMap,
+    id: String,
+}
+
+impl TaskRegistration {
+    fn new(tasks: &TaskMap, id: String, token: CancellationToken) -> Self {
+        tasks.lock().unwrap().insert(id.clone(), token);
+        Self {
+            tasks: Arc::clone(tasks),
+            id,
+        }
+    }
+}
+
+impl Drop for TaskRegistration {
+    fn drop(&mut self) {
+        self.tasks.lock().unwrap().remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::python_bridge::PythonMode;
+    use std::path::PathBuf;
+    use std::sync::mpsc::channel;
+
+    fn queue(workers: usize, capacity: usize) -> PythonQueue {
+        let python_ai = PythonAI::with_mode(PathBuf::from("nous-py"), PythonMode::InProcess);
+        PythonQueue::with_limits(python_ai, workers, capacity)
+    }
+
+    #[tokio::test]
+    async fn runs_tasks_concurrently() {
+        let queue = queue(2, 4);
+        let (tx, rx) = channel();
+
+        // The first task only finishes once the second has run
+        let waiting = queue.run(TaskOptions::default(), move |_| {
+            rx.recv_timeout(Duration::from_secs(5)).is_ok()
+        });
+        let signalling = queue.run(TaskOptions::default(), move |_| tx.send(()).is_ok());
+        let (waited, signalled) = tokio::join!(waiting, signalling);
+
+        assert!(signalled.unwrap());
+        assert!(waited.unwrap());
+    }
+
+    #[tokio::test]
+    async fn refuses_tasks_when_full() {
+        let queue = queue(1, 1);
+        let (release, blocked) = channel::<()>();
+        let options = TaskOptions::default().with_timeout(Duration::from_millis(50));
+
+        // Occupies the worker; the timeout leaves it running
+        let started = queue.run(options.clone(), move |_| {
+            let _ = blocked.recv();
+        });
+        assert!(matches!(started.await, Err(QueueError::Timeout(_))));
+
+        let queued = queue.run(options.clone(), |_| ());
+        assert!(matches!(queued.await, Err(QueueError::Timeout(_))));
+        assert!(matches!(
+            queue.run(options, |_| ()).await,
+            Err(QueueError::Full(1))
+        ));
+        drop(release);
+    }
+
+    #[tokio::test]
+    async fn cancels_by_task_id() {
+        let queue = Arc::new(queue(1, 4));
+        let (release, blocked) = channel::<()>();
+
+        let busy = queue.run(TaskOptions::default(), move |_| {
+            let _ = blocked.recv();
+        });
+        let options = TaskOptions::default().with_task_id(Some("chat-1".to_string()));
+        let cancelled = queue.run(options, |_| panic!("cancelled task ran"));
+
+        let canceller = {
+            let queue = Arc::clone(&queue);
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                assert!(queue.cancel("chat-1"));
+                drop(release);
+            }
+        };
+        let (busy, cancelled, ()) = tokio::join!(busy, cancelled, canceller);
+
+        assert!(busy.is_ok());
+        assert!(matches!(cancelled, Err(QueueError::Cancelled)));
+        assert!(!queue.cancel("chat-1"));
+    }
+}
@@ -0,0 +1,492 @@
+//! Out-of-process Python worker
+//!
+//! Runs `nous_ai` functions in a long-lived `python -m nous_ai.bridge_worker`
+//! subprocess instead of the embedded interpreter, so a crash in a native
+//! dependency (faster-whisper, onnxruntime, ...) fails the call rather than
+//! taking the app down. Requests and responses are newline-delimited JSON on
+//! the worker's stdin/stdout (see `nous_ai/bridge_worker.py` for the
+//! protocol); a reader thread routes responses to the waiting call by id, so
+//! calls can overlap. When the process dies, calls in flight fail and the
+//! next call starts a fresh worker.
+//!
+//! A call that runs past its deadline, or whose queue task is cancelled, has
+//! no way to stop just its own function, so it kills the worker: the other
+//! calls in flight fail with it and the next call starts a fresh one.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+/// Marker the worker replaces with a function reporting back to the caller
+pub const CALLBACK_KEY: &str = "__nous_callback__";
+
+/// How long a call may run before the worker is killed, the same as a
+/// queue task's default timeout
+pub const DEFAULT_CALL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often a waiting call checks whether it was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+thread_local! {
+    /// Token of the queue task running on this thread, if any
+    static TASK_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with `token` cancelling the worker calls it makes on this thread
+pub fn with_cancellation<T>(token: &CancellationToken, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<CancellationToken>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            TASK_TOKEN.set(self.0.take());
+        }
+    }
+    let _restore = Restore(TASK_TOKEN.replace(Some(token.clone())));
+    f()
+}
+
+/// The token set by [`with_cancellation`], or one that is never cancelled
+pub fn task_token() -> CancellationToken {
+    TASK_TOKEN.with_borrow(Clone::clone).unwrap_or_default()
+}
+
+#[derive(Error, Debug)]
+pub enum WorkerError {
+    #[error("Failed to start Python worker: {0}")]
+    Spawn(std::io::Error),
+
+    #[error("Python worker I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Python worker exited during the call ({0})")]
+    Exited(String),
+
+    #[error("Python worker call timed out after {} seconds", .0.as_secs())]
+    Timeout(Duration),
+
+    #[error("Python worker call was cancelled")]
+    Cancelled,
+
+    /// The function raised; `kind` is the Python exception class name
+    #[error("{kind}: {message}")]
+    Remote { kind: String, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, WorkerError>;
+
+pub struct PythonWorker {
+    nous_py_path: PathBuf,
+    process: Mutex<Option<Arc<WorkerProcess>>>,
+    next_id: AtomicU64,
+    timeout: Duration,
+}
+
+struct WorkerProcess {
+    child: Mutex<Child>,
+    stdin: Mutex<ChildStdin>,
+    /// Calls waiting for messages, by request id
+    pending: Arc<Mutex<HashMap<u64, Sender<Value>>>>,
+    alive: Arc<AtomicBool>,
+}
+
+impl PythonWorker {
+    /// The process is started on the first call
+    pub fn new(nous_py_path: PathBuf) -> Self {
+        Self {
+            nous_py_path,
+            process: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+            timeout: DEFAULT_CALL_TIMEOUT,
+        }
+    }
+
+    /// Call `module.function(*args, **kwargs)` in the worker and return its
+    /// result. Arguments equal to `{CALLBACK_KEY: n}` become functions in the
+    /// worker; each call of one is passed to `on_callback` as `(n, args)`.
+    /// Past the timeout, or once `cancel` fires, the worker is killed.
+    pub fn call(
+        &self,
+        module: &str,
+        function: &str,
+        args: Vec<Value>,
+        kwargs: Map<String, Value>,
+        cancel: &CancellationToken,
+        on_callback: &mut dyn FnMut(usize, Vec<Value>),
+    ) -> Result<Value> {
+        let process = self.process()?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel();
+        process.pending.lock().unwrap().insert(id, tx);
+
+        let request = json!({
+            "id": id,
+            "module": module,
+            "function": function,
+            "args": args,
+            "kwargs": kwargs,
+        });
+        // A worker that died before we registered never answers
+        if !process.alive.load(Ordering::SeqCst) {
+            process.pending.lock().unwrap().remove(&id);
+            return Err(WorkerError::Exited(process.exit_status()));
+        }
+        if let Err(e) = process.send(&request) {
+            process.pending.lock().unwrap().remove(&id);
+            return Err(e.into());
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let abandon = if cancel.is_cancelled() {
+                Some(WorkerError::Cancelled)
+            } else if Instant::now() >= deadline {
+                Some(WorkerError::Timeout(self.timeout))
+            } else {
+                None
+            };
+            if let Some(error) = abandon {
+                process.pending.lock().unwrap().remove(&id);
+                log::warn!("{}; stopping the Python worker", error);
+                process.kill();
+                return Err(error);
+            }
+
+            let wait = deadline
+                .saturating_duration_since(Instant::now())
+                .min(CANCEL_POLL_INTERVAL);
+            let mut message = match rx.recv_timeout(wait) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(WorkerError::Exited(process.exit_status()));
+                }
+            };
+            if let Some(index) = message.get("callback").and_then(Value::as_u64) {
+                let args = match message["args"].take() {
+                    Value::Array(args) => args,
+                    _ => Vec::new(),
+                };
+                on_callback(index as usize, args);
+                continue;
+            }
+
+            process.pending.lock().unwrap().remove(&id);
+            if let Some(error) = message.get("error") {
+                let field = |name: &str| {
+                    error
+                        .get(name)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                return Err(WorkerError::Remote {
+                    kind: field("type"),
+                    message: field("message"),
+                });
+            }
+            return Ok(message["result"].take());
+        }
+    }
+
+    /// The running worker, started (or restarted) as needed
+    fn process(&self) -> Result<Arc<WorkerProcess>> {
+        let mut slot = self.process.lock().unwrap();
+        if let Some(process) = slot.as_ref() {
+            if process.alive.load(Ordering::SeqCst) {
+                return Ok(Arc::clone(process));
+            }
+            log::warn!(
+                "Python worker exited ({}); restarting",
+                process.exit_status()
+            );
+        }
+        let process = Arc::new(WorkerProcess::spawn(&self.nous_py_path)?);
+        *slot = Some(Arc::clone(&process));
+        Ok(process)
+    }
+}
+
+impl WorkerProcess {
+    fn spawn(nous_py_path: &Path) -> Result<Self> {
+        let python = python_executable(nous_py_path);
+        let mut python_path = vec![nous_py_path.to_path_buf()];
+        if let Some(existing) = std::env::var_os("PYTHONPATH") {
+            python_path.extend(std::env::split_paths(&existing));
+        }
+        let python_path = std::env::join_paths(python_path)
+            .map_err(|e| WorkerError::Spawn(std::io::Error::other(e)))?;
+
+        log::info!("Starting Python worker with {}", python.display());
+        let mut child = Command::new(&python)
+            .args(["-u", "-m", "nous_ai.bridge_worker"])
+            .env("PYTHONPATH", python_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(WorkerError::Spawn)?;
+        let stdin = child.stdin.take().expect("worker stdin is piped");
+        let stdout = child.stdout.take().expect("worker stdout is piped");
+
+        let pending: Arc<Mutex<HashMap<u64, Sender<Value>>>> = Arc::default();
+        let alive = Arc::new(AtomicBool::new(true));
+        {
+            let pending = Arc::clone(&pending);
+            let alive = Arc::clone(&alive);
+            std::thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else { break };
+                    let message: Value = match serde_json::from_str(&line) {
+                        Ok(message) => message,
+                        Err(e) => {
+                            log::warn!("Unreadable message from Python worker: {}", e);
+                            continue;
+                        }
+                    };
+                    let Some(id) = message.get("id").and_then(Value::as_u64) else {
+                        continue;
+                    };
+                    if let Some(tx) = pending.lock().unwrap().get(&id) {
+                        let _ = tx.send(message);
+                    }
+                }
+                // Mark dead before failing the waiting calls, so a call
+                // registering now sees it
+                alive.store(false, Ordering::SeqCst);
+                pending.lock().unwrap().clear();
+            });
+        }
+
+        Ok(Self {
+            child: Mutex::new(child),
+            stdin: Mutex::new(stdin),
+            pending,
+            alive,
+        })
+    }
+
+    fn send(&self, request: &Value) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        let mut stdin = self.stdin.lock().unwrap();
+        stdin.write_all(&line)?;
+        stdin.flush()
+    }
+
+    /// Stop the process. It counts as dead from here on, so the next call
+    /// starts a new one without waiting for the reader to notice.
+    fn kill(&self) {
+        self.alive.store(false, Ordering::SeqCst);
+        let _ = self.child.lock().unwrap().kill();
+    }
+
+    fn exit_status(&self) -> String {
+        match self.child.lock().unwrap().try_wait() {
+            Ok(Some(status)) => status.to_string(),
+            _ => "no exit status".to_string(),
+        }
+    }
+}
+
+impl Drop for WorkerProcess {
+    fn drop(&mut self) {
+        if let Ok(child) = self.child.get_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Interpreter for the worker: the nous-py venv in development, the bundled
+/// one under `PYTHONHOME` in release builds, else `PYO3_PYTHON` or `python3`
+fn python_executable(nous_py_path: &Path) -> PathBuf {
+    let mut candidates = vec![
+        nous_py_path.join(".venv/bin/python"),
+        nous_py_path.join(".venv/Scripts/python.exe"),
+    ];
+    if let Some(home) = std::env::var_os("PYTHONHOME") {
+        candidates.push(PathBuf::from(home).join("bin/python3"));
+    }
+    candidates
+        .into_iter()
+        .find(|p| p.exists())
+        .or_else(|| std::env::var_os("PYO3_PYTHON").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("python3"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for `nous_ai.bridge_worker`, speaking the same protocol.
+    /// The first `pair` call is held until a second arrives and answered
+    /// after it, so responses come back out of order.
+    const STUB_WORKER: &str = r#"
+import json, os, sys
+
+def send(message):
+    sys.stdout.write(json.dumps(message) + "\n")
+    sys.stdout.flush()
+
+held = None
+for line in sys.stdin:
+    request = json.loads(line)
+    id, function, args = request["id"], request["function"], request["args"]
+    if function == "pair":
+        if held is None:
+            held = request
+            continue
+        send({"id": id, "result": args})
+        send({"id": held["id"], "result": held["args"]})
+        held = None
+    elif function == "progress":
+        index = args[0]["__nous_callback__"]
+        for step in range(3):
+            send({"id": id, "callback": index, "args": [step]})
+        send({"id": id, "result": "done"})
+    elif function == "pid":
+        send({"id": id, "result": os.getpid()})
+    elif function == "fail":
+        send({"id": id, "error": {"type": "ValueError", "message": "bad input"}})
+    elif function == "crash":
+        os._exit(3)
+    # "hang" never answers
+"#;
+
+    fn stub_worker() -> (tempfile::TempDir, PythonWorker) {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("nous_ai");
+        std::fs::create_dir(&package).unwrap();
+        std::fs::write(package.join("__init__.py"), "").unwrap();
+        std::fs::write(package.join("bridge_worker.py"), STUB_WORKER).unwrap();
+        let worker = PythonWorker::new(dir.path().to_path_buf());
+        (dir, worker)
+    }
+
+    fn call(worker: &PythonWorker, function: &str, args: Vec<Value>) -> Result<Value> {
+        call_with(worker, function, args, &CancellationToken::new())
+    }
+
+    fn call_with(
+        worker: &PythonWorker,
+        function: &str,
+        args: Vec<Value>,
+        cancel: &CancellationToken,
+    ) -> Result<Value> {
+        worker.call("stub", function, args, Map::new(), cancel, &mut |_, _| {})
+    }
+
+    #[test]
+    fn routes_responses_by_id() {
+        let (_dir, worker) = stub_worker();
+
+        let (first, second) = std::thread::scope(|s| {
+            let first = s.spawn(|| call(&worker, "pair", vec![json!("first")]));
+            let second = s.spawn(|| call(&worker, "pair", vec![json!("second")]));
+            (first.join().unwrap(), second.join().unwrap())
+        });
+
+        assert_eq!(first.unwrap(), json!(["first"]));
+        assert_eq!(second.unwrap(), json!(["second"]));
+    }
+
+    #[test]
+    fn passes_callbacks_to_the_caller() {
+        let (_dir, worker) = stub_worker();
+        let mut calls = Vec::new();
+
+        let result = worker.call(
+            "stub",
+            "progress",
+            vec![json!({ CALLBACK_KEY: 0 })],
+            Map::new(),
+            &CancellationToken::new(),
+            &mut |index, args| calls.push((index, args)),
+        );
+
+        assert_eq!(result.unwrap(), json!("done"));
+        assert_eq!(
+            calls,
+            vec![
+                (0, vec![json!(0)]),
+                (0, vec![json!(1)]),
+                (0, vec![json!(2)])
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_remote_exceptions() {
+        let (_dir, worker) = stub_worker();
+
+        match call(&worker, "fail", Vec::new()) {
+            Err(WorkerError::Remote { kind, message }) => {
+                assert_eq!(kind, "ValueError");
+                assert_eq!(message, "bad input");
+            }
+            other => panic!("expected a remote error, got {:?}", other),
+        }
+        // The worker survives an exception
+        assert!(call(&worker, "pid", Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn restarts_after_a_crash() {
+        let (_dir, worker) = stub_worker();
+        let pid = call(&worker, "pid", Vec::new()).unwrap();
+
+        assert!(matches!(
+            call(&worker, "crash", Vec::new()),
+            Err(WorkerError::Exited(_))
+        ));
+
+        let restarted = call(&worker, "pid", Vec::new()).unwrap();
+        assert_ne!(restarted, pid);
+    }
+
+    #[test]
+    fn kills_the_worker_after_the_timeout() {
+        let (_dir, mut worker) = stub_worker();
+        let pid = call(&worker, "pid", Vec::new()).unwrap();
+        worker.timeout = Duration::from_millis(200);
+
+        assert!(matches!(
+            call(&worker, "hang", Vec::new()),
+            Err(WorkerError::Timeout(_))
+        ));
+
+        worker.timeout = DEFAULT_CALL_TIMEOUT;
+        let restarted = call(&worker, "pid", Vec::new()).unwrap();
+        assert_ne!(restarted, pid);
+    }
+
+    #[test]
+    fn kills_the_worker_when_cancelled() {
+        let (_dir, worker) = stub_worker();
+        let pid = call(&worker, "pid", Vec::new()).unwrap();
+        let cancel = CancellationToken::new();
+
+        let result = std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                cancel.cancel();
+            });
+            with_cancellation(&cancel, || {
+                call_with(&worker, "hang", Vec::new(), &task_token())
+            })
+        });
+
+        assert!(matches!(result, Err(WorkerError::Cancelled)));
+        let restarted = call(&worker, "pid", Vec::new()).unwrap();
+        assert_ne!(restarted, pid);
+    }
+}
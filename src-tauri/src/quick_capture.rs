@@ -0,0 +1,311 @@
+//! Quick capture: a global shortcut that pops up a small always-on-top
+//! window for jotting a note into the inbox.
+//!
+//! The capture window is created hidden at startup and only shown/hidden
+//! afterwards, so the shortcut responds instantly. Saving goes through
+//! `capture_quick_note`, which writes straight to an `InboxStorage` over the
+//! data directory instead of going through `AppState` — captures don't wait
+//! on locks held by the main window's work. Because the hidden window stays
+//! alive, closing the main window doesn't quit the app while quick capture
+//! is enabled; a second launch (or `open_main_window`) brings it back.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::inbox::{CaptureRequest, CaptureSource, InboxItem, InboxStorage};
+use crate::storage::FileStorage;
+
+pub const WINDOW_LABEL: &str = "quick-capture";
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+
+/// Titles derived from the note body are cut to this many characters
+const MAX_DERIVED_TITLE: usize = 80;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuickCaptureSettings {
+    pub enabled: bool,
+    /// Accelerator string, e.g. "CommandOrControl+Shift+Space"
+    pub shortcut: String,
+}
+
+impl Default for QuickCaptureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shortcut: DEFAULT_SHORTCUT.to_string(),
+        }
+    }
+}
+
+fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("quick_capture.json")
+}
+
+pub fn load_settings(data_dir: &Path) -> QuickCaptureSettings {
+    std::fs::read_to_string(settings_path(data_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(data_dir: &Path, settings: &QuickCaptureSettings) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(settings_path(data_dir), content).map_err(|e| e.to_string())
+}
+
+/// The shortcut currently registered, so it can be swapped out
+#[derive(Default)]
+pub struct RegisteredShortcut(Mutex<Option<Shortcut>>);
+
+/// Build the inbox request for a capture. An empty title falls back to the
+/// first line of the note.
+fn capture_request(
+    title: &str,
+    content: &str,
+    tags: Vec<String>,
+) -> Result<CaptureRequest, String> {
+    let content = content.trim_end().to_string();
+    let title = match title.trim() {
+        "" => content
+            .lines()
+            .map(|l| l.trim().trim_start_matches('#').trim())
+            .find(|l| !l.is_empty())
+            .map(|l| l.chars().take(MAX_DERIVED_TITLE).collect::<String>())
+            .ok_or_else(|| "Nothing to capture".to_string())?,
+        title => title.to_string(),
+    };
+    let tags: Vec<String> = tags
+        .into_iter()
+        .map(|t| t.trim().trim_start_matches('#').to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    Ok(CaptureRequest {
+        title,
+        content,
+        tags: (!tags.is_empty()).then_some(tags),
+        source: Some(CaptureSource::QuickCapture),
+        auto_classify: None,
+    })
+}
+
+/// Register `settings.shortcut` (replacing any previous one), or just
+/// unregister when quick capture is disabled.
+pub fn apply_settings(app: &AppHandle, settings: &QuickCaptureSettings) -> Result<(), String> {
+    let registered = app.state::<RegisteredShortcut>();
+    let mut current = registered.0.lock().unwrap();
+    if let Some(old) = current.take() {
+        let _ = app.global_shortcut().unregister(old);
+    }
+    if !settings.enabled {
+        if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+            let _ = window.destroy();
+        }
+        return Ok(());
+    }
+
+    let shortcut: Shortcut = settings
+        .shortcut
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", settings.shortcut, e))?;
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("Failed to register '{}': {}", settings.shortcut, e))?;
+    *current = Some(shortcut);
+    drop(current);
+
+    // Pre-create the window so the first press doesn't pay for a webview
+    if app.get_webview_window(WINDOW_LABEL).is_none() {
+        build_window(app, false).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Load settings and register the shortcut; called once from `setup`
+pub fn init(app: &AppHandle) {
+    let Ok(data_dir) = FileStorage::default_data_dir() else {
+        return;
+    };
+    let settings = load_settings(&data_dir);
+    if let Err(e) = apply_settings(app, &settings) {
+        log::warn!("Quick capture unavailable: {}", e);
+    }
+}
+
+/// Global shortcut handler: toggle the capture window
+pub fn on_shortcut(app: &AppHandle, _shortcut: &Shortcut, state: ShortcutState) {
+    if state != ShortcutState::Pressed {
+        return;
+    }
+    match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            let _ = window.hide();
+        }
+        _ => show_window(app),
+    }
+}
+
+/// Show and focus the capture window, creating it if needed
+pub fn show_window(app: &AppHandle) {
+    match app.get_webview_window(WINDOW_LABEL) {
+        Some(window) => {
+            let _ = window.center();
+            let _ = window.show();
+            let _ = window.set_focus();
+            let _ = window.emit("quick-capture-shown", ());
+        }
+        None => {
+            if let Err(e) = build_window(app, true) {
+                log::error!("Failed to open quick capture window: {}", e);
+            }
+        }
+    }
+}
+
+fn build_window(app: &AppHandle, visible: bool) -> tauri::Result<()> {
+    let window = WebviewWindowBuilder::new(
+        app,
+        WINDOW_LABEL,
+        WebviewUrl::App("/?window=quick-capture".into()),
+    )
+    .title("Quick Capture")
+    .inner_size(560.0, 220.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .visible(visible)
+    .build()?;
+
+    // Dismiss on focus loss, and hide rather than close so the next press
+    // is instant
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Focused(false) => {
+            let _ = handle.hide();
+        }
+        WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            let _ = handle.hide();
+        }
+        _ => {}
+    });
+    Ok(())
+}
+
+/// Show the main window, recreating it if it was closed while the app kept
+/// running for quick capture.
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let Some(config) = app.config().app.windows.iter().find(|w| w.label == "main") else {
+        return;
+    };
+    if let Err(e) = WebviewWindowBuilder::from_config(app, config).and_then(|b| b.build()) {
+        log::error!("Failed to reopen main window: {}", e);
+    }
+}
+
+/// Save a note from the capture window into the inbox
+#[tauri::command]
+pub fn capture_quick_note(
+    app: AppHandle,
+    title: String,
+    content: String,
+    tags: Option<Vec<String>>,
+) -> Result<InboxItem, String> {
+    let request = capture_request(&title, &content, tags.unwrap_or_default())?;
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    let item = InboxStorage::new(data_dir)
+        .and_then(|inbox| inbox.capture(request))
+        .map_err(|e| e.to_string())?;
+
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+    // Lets an open main window refresh its inbox count
+    let _ = app.emit("inbox-captured", &item);
+    Ok(item)
+}
+
+#[tauri::command]
+pub fn hide_quick_capture(app: AppHandle) {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+#[tauri::command]
+pub fn open_main_window(app: AppHandle) {
+    show_main_window(&app);
+}
+
+#[tauri::command]
+pub fn get_quick_capture_settings() -> Result<QuickCaptureSettings, String> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    Ok(load_settings(&data_dir))
+}
+
+/// Save settings and re-register the shortcut. Settings are only written
+/// once the new shortcut registered successfully.
+#[tauri::command]
+pub fn update_quick_capture_settings(
+    app: AppHandle,
+    settings: QuickCaptureSettings,
+) -> Result<QuickCaptureSettings, String> {
+    let data_dir = FileStorage::default_data_dir().map_err(|e| e.to_string())?;
+    if let Err(e) = apply_settings(&app, &settings) {
+        // Put the previous shortcut back
+        let _ = apply_settings(&app, &load_settings(&data_dir));
+        return Err(e);
+    }
+    save_settings(&data_dir, &settings)?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_falls_back_to_first_line() {
+        let request = capture_request("  ", "\n# Call the bank\nabout the card", vec![]).unwrap();
+        assert_eq!(request.title, "Call the bank");
+        assert_eq!(request.content, "\n# Call the bank\nabout the card");
+        assert!(request.tags.is_none());
+        assert!(matches!(request.source, Some(CaptureSource::QuickCapture)));
+
+        let long = "x".repeat(200);
+        let request = capture_request("", &long, vec![]).unwrap();
+        assert_eq!(request.title.chars().count(), MAX_DERIVED_TITLE);
+
+        assert!(capture_request("", "  \n ", vec![]).is_err());
+    }
+
+    #[test]
+    fn tags_are_normalized() {
+        let tags = vec!["#todo".to_string(), " ".to_string(), " work ".to_string()];
+        let request = capture_request("Title", "", tags).unwrap();
+        assert_eq!(
+            request.tags,
+            Some(vec!["todo".to_string(), "work".to_string()])
+        );
+    }
+
+    #[test]
+    fn settings_fill_in_missing_fields() {
+        let settings: QuickCaptureSettings = serde_json::from_str(r#"{"enabled":false}"#).unwrap();
+        assert!(!settings.enabled);
+        assert_eq!(settings.shortcut, DEFAULT_SHORTCUT);
+    }
+}
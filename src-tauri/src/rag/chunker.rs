@@ -7,37 +7,272 @@ use uuid::Uuid;
 
 use crate::storage::{EditorBlock, Page};
 
-use super::models::{Chunk, ChunkMetadata};
-
-/// Maximum number of tokens per chunk (approximate).
-const MAX_CHUNK_TOKENS: usize = 512;
-
-/// Number of tokens to overlap between chunks for context continuity.
-const OVERLAP_TOKENS: usize = 50;
+use super::models::{Chunk, ChunkMetadata, ChunkingStrategy};
 
 /// Approximate characters per token (rough estimate for English text).
 const CHARS_PER_TOKEN: usize = 4;
 
-/// Maximum characters per chunk.
-const MAX_CHUNK_CHARS: usize = MAX_CHUNK_TOKENS * CHARS_PER_TOKEN;
+/// Maximum characters per chunk under `strategy`.
+fn max_chunk_chars(strategy: &ChunkingStrategy) -> usize {
+    strategy.chunk_size * CHARS_PER_TOKEN
+}
 
-/// Overlap characters between chunks.
-const OVERLAP_CHARS: usize = OVERLAP_TOKENS * CHARS_PER_TOKEN;
+/// Overlap characters between chunks under `strategy`.
+fn overlap_chars(strategy: &ChunkingStrategy) -> usize {
+    strategy.overlap * CHARS_PER_TOKEN
+}
 
 /// Chunk a page into embedding-ready text chunks.
-pub fn chunk_page(page: &Page) -> Vec<Chunk> {
-    let text = extract_text_from_blocks(&page.content.blocks);
-    chunk_text_with_ids(&text, page.id, page.notebook_id)
+///
+/// With neither structural option set, the page text is cut into
+/// overlapping windows regardless of blocks. Heading-aware and code-aware
+/// strategies chunk block by block instead (see `chunk_blocks`).
+///
+/// Video transcripts are chunked separately from the page body: segments are
+/// grouped in order and each chunk records the block id and the start/end
+/// seconds it covers, so semantic hits can seek the player.
+pub fn chunk_page(page: &Page, strategy: &ChunkingStrategy) -> Vec<Chunk> {
+    let mut chunks = if strategy.heading_aware || strategy.code_aware {
+        chunk_blocks(page, strategy)
+    } else {
+        let text = extract_text_from_blocks(&page.content.blocks);
+        chunk_text_with_ids(&text, page.id, page.notebook_id, strategy)
+    };
+    let next_index = chunks.len() as u32;
+    chunks.extend(chunk_transcripts(page, next_index, strategy));
+    chunks
+}
+
+/// A piece of a page before it becomes a [`Chunk`]: text, offsets in the
+/// page text, block type and the heading it falls under.
+struct Piece {
+    content: String,
+    start: usize,
+    end: usize,
+    block_type: &'static str,
+    heading: Option<String>,
+}
+
+/// Chunk a page's blocks section by section.
+///
+/// Prose accumulates into a section that is cut into overlapping windows
+/// when it ends. Heading-aware strategies end the section at every header
+/// and prefix the section's later chunks with the heading, so each chunk
+/// carries its topic. Code-aware strategies end the section at code blocks
+/// and emit the code as chunks of its own, split between lines only.
+/// Offsets are positions in the text `extract_text_from_blocks` produces.
+fn chunk_blocks(page: &Page, strategy: &ChunkingStrategy) -> Vec<Chunk> {
+    let max_chars = max_chunk_chars(strategy);
+    let overlap = overlap_chars(strategy);
+    let mut pieces: Vec<Piece> = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut section: Vec<String> = Vec::new();
+    let mut section_start = 0;
+    // Whether the running section opens with its header block
+    let mut led_by_heading = false;
+    let mut position = 0;
+
+    let flush = |pieces: &mut Vec<Piece>,
+                 section: &mut Vec<String>,
+                 start: usize,
+                 led_by_heading: bool,
+                 heading: &Option<String>| {
+        if section.is_empty() {
+            return;
+        }
+        let text = section.join("\n\n");
+        section.clear();
+        for (i, (content, s, e)) in sliding_window(&text, max_chars, overlap)
+            .into_iter()
+            .enumerate()
+        {
+            let content = match heading {
+                Some(heading) if i > 0 || !led_by_heading => {
+                    format!("{}\n\n{}", heading, content)
+                }
+                _ => content,
+            };
+            pieces.push(Piece {
+                content,
+                start: start + s,
+                end: start + e,
+                block_type: "text",
+                heading: heading.clone(),
+            });
+        }
+    };
+
+    for block in &page.content.blocks {
+        let text = extract_text_from_block(block);
+        if text.is_empty() {
+            continue;
+        }
+        let block_start = position;
+        position += text.len() + 2;
+
+        let is_header = strategy.heading_aware && block.block_type == "header";
+        if is_header || (strategy.code_aware && block.block_type == "code") {
+            flush(
+                &mut pieces,
+                &mut section,
+                section_start,
+                led_by_heading,
+                &heading,
+            );
+        }
+        if is_header {
+            heading = Some(text.clone());
+        } else if strategy.code_aware && block.block_type == "code" {
+            for (content, s, e) in split_between_lines(&text, max_chars) {
+                let content = match &heading {
+                    Some(heading) if strategy.heading_aware => {
+                        format!("{}\n\n{}", heading, content)
+                    }
+                    _ => content,
+                };
+                pieces.push(Piece {
+                    content,
+                    start: block_start + s,
+                    end: block_start + e,
+                    block_type: "code",
+                    heading: heading.clone(),
+                });
+            }
+            continue;
+        }
+
+        if section.is_empty() {
+            section_start = block_start;
+            led_by_heading = is_header;
+        }
+        section.push(text);
+    }
+    flush(
+        &mut pieces,
+        &mut section,
+        section_start,
+        led_by_heading,
+        &heading,
+    );
+
+    pieces
+        .into_iter()
+        .enumerate()
+        .map(|(index, piece)| {
+            Chunk::new(
+                page.id,
+                page.notebook_id,
+                index as u32,
+                piece.content,
+                Some(ChunkMetadata {
+                    block_types: vec![piece.block_type.to_string()],
+                    start_offset: piece.start,
+                    end_offset: piece.end,
+                    media_block_id: None,
+                    media_start: None,
+                    media_end: None,
+                    heading: piece.heading,
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Split text into pieces of at most `max_chars` at line breaks. A single
+/// line longer than that stays whole. Returns (text, start, end) tuples.
+fn split_between_lines(text: &str, max_chars: usize) -> Vec<(String, usize, usize)> {
+    let mut pieces = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let content = text[start..end].trim_end();
+        if !content.trim().is_empty() {
+            pieces.push((content.to_string(), start, end));
+        }
+    };
+
+    let (mut start, mut end) = (0, 0);
+    for line in text.split_inclusive('\n') {
+        if end > start && end - start + line.len() > max_chars {
+            push(start, end);
+            start = end;
+        }
+        end += line.len();
+    }
+    if end > start {
+        push(start, end);
+    }
+    pieces
+}
+
+/// Group a page's transcript segments into timestamped chunks, numbering
+/// them from `first_index`. Transcript chunks are kept at half the chunk
+/// size so a semantic hit points at a narrow span of the video.
+fn chunk_transcripts(page: &Page, first_index: u32, strategy: &ChunkingStrategy) -> Vec<Chunk> {
+    let max_chars = max_chunk_chars(strategy) / 2;
+    let segments = crate::search::extract_transcript_segments(page);
+    let mut chunks = Vec::new();
+    let mut current: Option<(String, f64, f64, String)> = None;
+
+    let flush = |chunks: &mut Vec<Chunk>, (block_id, start, end, text): (String, f64, f64, String)| {
+        let index = first_index + chunks.len() as u32;
+        let len = text.len();
+        chunks.push(Chunk::new(
+            page.id,
+            page.notebook_id,
+            index,
+            text,
+            Some(ChunkMetadata {
+                block_types: vec!["transcript".to_string()],
+                start_offset: 0,
+                end_offset: len,
+                media_block_id: Some(block_id),
+                media_start: Some(start),
+                media_end: Some(end),
+                heading: None,
+            }),
+        ));
+    };
+
+    for seg in segments {
+        let extends_current = matches!(
+            &current,
+            Some((block_id, _, _, text))
+                if *block_id == seg.block_id
+                    && text.len() + seg.text.len() < max_chars
+        );
+        if extends_current {
+            if let Some((_, _, end, text)) = current.as_mut() {
+                text.push(' ');
+                text.push_str(&seg.text);
+                *end = seg.end;
+            }
+        } else {
+            if let Some(done) = current.take() {
+                flush(&mut chunks, done);
+            }
+            current = Some((seg.block_id, seg.start, seg.end, seg.text));
+        }
+    }
+    if let Some(done) = current.take() {
+        flush(&mut chunks, done);
+    }
+
+    chunks
 }
 
 /// Chunk a page using pre-extracted text content (for Html, PDF, etc.).
-pub fn chunk_page_with_text(page: &Page, text: &str) -> Vec<Chunk> {
-    chunk_text_with_ids(text, page.id, page.notebook_id)
+/// The text has no block structure, so only the sizes of `strategy` apply.
+pub fn chunk_page_with_text(page: &Page, text: &str, strategy: &ChunkingStrategy) -> Vec<Chunk> {
+    chunk_text_with_ids(text, page.id, page.notebook_id, strategy)
 }
 
 /// Chunk raw text into chunks with page/notebook IDs.
-fn chunk_text_with_ids(text: &str, page_id: Uuid, notebook_id: Uuid) -> Vec<Chunk> {
-    let text_chunks = sliding_window(text, MAX_CHUNK_CHARS, OVERLAP_CHARS);
+fn chunk_text_with_ids(
+    text: &str,
+    page_id: Uuid,
+    notebook_id: Uuid,
+    strategy: &ChunkingStrategy,
+) -> Vec<Chunk> {
+    let text_chunks = sliding_window(text, max_chunk_chars(strategy), overlap_chars(strategy));
 
     text_chunks
         .into_iter()
@@ -52,6 +287,10 @@ fn chunk_text_with_ids(text: &str, page_id: Uuid, notebook_id: Uuid) -> Vec<Chun
                     block_types: vec!["text".to_string()],
                     start_offset: start,
                     end_offset: end,
+                    media_block_id: None,
+                    media_start: None,
+                    media_end: None,
+                    heading: None,
                 }),
             )
         })
@@ -60,7 +299,8 @@ fn chunk_text_with_ids(text: &str, page_id: Uuid, notebook_id: Uuid) -> Vec<Chun
 
 /// Chunk raw text into string chunks (for external use).
 pub fn chunk_text(text: &str) -> Vec<String> {
-    sliding_window(text, MAX_CHUNK_CHARS, OVERLAP_CHARS)
+    let strategy = ChunkingStrategy::default();
+    sliding_window(text, max_chunk_chars(&strategy), overlap_chars(&strategy))
         .into_iter()
         .map(|(content, _, _)| content)
         .collect()
@@ -178,19 +418,8 @@ fn extract_text_from_block(block: &EditorBlock) -> String {
                 }
             }
 
-            // Extract transcript from transcription.segments
-            if let Some(transcription) = block.data.get("transcription") {
-                if let Some(segments) = transcription.get("segments").and_then(|v| v.as_array()) {
-                    let transcript_text: String = segments
-                        .iter()
-                        .filter_map(|seg| seg.get("text").and_then(|v| v.as_str()))
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    if !transcript_text.is_empty() {
-                        parts.push(format!("Transcript: {}", transcript_text));
-                    }
-                }
-            }
+            // Transcript segments are chunked separately with their
+            // timestamps (see `chunk_transcripts`).
 
             parts.join("\n\n")
         }
@@ -409,6 +638,124 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chunk_page_emits_timestamped_transcript_chunks() {
+        use crate::storage::EditorData;
+        use serde_json::json;
+
+        let mut page = Page::new(Uuid::new_v4(), "Talk".to_string());
+        page.content = EditorData {
+            time: None,
+            version: None,
+            blocks: vec![EditorBlock {
+                id: "v1".to_string(),
+                block_type: "video".to_string(),
+                data: json!({
+                    "caption": "Keynote",
+                    "transcription": { "segments": [
+                        { "start": 1.0, "end": 3.0, "text": "Hello there." },
+                        { "start": 3.0, "end": 6.5, "text": "Today: compilers." }
+                    ]}
+                }),
+            }],
+        };
+
+        let chunks = chunk_page(&page, &ChunkingStrategy::default());
+        assert_eq!(chunks.len(), 2, "caption chunk + one grouped transcript chunk");
+        assert!(!chunks[0].content.contains("compilers"));
+        let meta = chunks[1].metadata.as_ref().unwrap();
+        assert_eq!(chunks[1].chunk_index, 1);
+        assert_eq!(chunks[1].content, "Hello there. Today: compilers.");
+        assert_eq!(meta.media_block_id.as_deref(), Some("v1"));
+        assert_eq!(meta.media_start, Some(1.0));
+        assert_eq!(meta.media_end, Some(6.5));
+    }
+
+    #[test]
+    fn test_heading_and_code_aware_chunking() {
+        use crate::storage::EditorData;
+        use serde_json::json;
+
+        let block = |id: &str, block_type: &str, data: serde_json::Value| EditorBlock {
+            id: id.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        };
+        let mut page = Page::new(Uuid::new_v4(), "Guide".to_string());
+        page.content = EditorData {
+            time: None,
+            version: None,
+            blocks: vec![
+                block("h1", "header", json!({ "text": "Setup", "level": 2 })),
+                block(
+                    "p1",
+                    "paragraph",
+                    json!({ "text": "Install the tools. ".repeat(30) }),
+                ),
+                block(
+                    "c1",
+                    "code",
+                    json!({ "code": "let x = 1;\nlet y = 2;\n".repeat(40) }),
+                ),
+                block("h2", "header", json!({ "text": "Usage", "level": 2 })),
+                block("p2", "paragraph", json!({ "text": "Run it." })),
+            ],
+        };
+
+        // The default strategy ignores structure
+        let plain = chunk_page(&page, &ChunkingStrategy::default());
+        assert!(plain
+            .iter()
+            .all(|c| c.metadata.as_ref().unwrap().heading.is_none()));
+
+        let strategy = ChunkingStrategy {
+            chunk_size: 64,
+            overlap: 8,
+            heading_aware: true,
+            code_aware: true,
+        };
+        let chunks = chunk_page(&page, &strategy);
+        let meta = |c: &Chunk| c.metadata.clone().unwrap();
+
+        // Every chunk is labelled with its section and starts with the heading
+        assert!(chunks.iter().all(|c| {
+            let heading = meta(c).heading.unwrap();
+            c.content.starts_with(&heading)
+        }));
+        // Code is kept apart from prose and split between lines only
+        let code: Vec<&Chunk> = chunks
+            .iter()
+            .filter(|c| meta(c).block_types == ["code"])
+            .collect();
+        assert!(code.len() > 1);
+        assert!(code.iter().all(|c| c
+            .content
+            .lines()
+            .skip(2)
+            .all(|line| line == "let x = 1;" || line == "let y = 2;")));
+        assert!(chunks
+            .iter()
+            .filter(|c| meta(c).block_types == ["text"])
+            .all(|c| !c.content.contains("let x")));
+        // The last section stands alone
+        let last = chunks.last().unwrap();
+        assert_eq!(last.content, "Usage\n\nRun it.");
+        assert_eq!(last.chunk_index as usize, chunks.len() - 1);
+    }
+
+    #[test]
+    fn test_chunking_strategy_validation() {
+        assert!(ChunkingStrategy::default().validate().is_ok());
+        let mut strategy = ChunkingStrategy {
+            chunk_size: 16,
+            ..Default::default()
+        };
+        assert!(strategy.validate().is_err());
+        strategy.chunk_size = 256;
+        strategy.overlap = 128;
+        assert!(strategy.validate().is_err());
+    }
+
     #[test]
     fn test_chunk_text() {
         let text = "Short text";
@@ -0,0 +1,380 @@
+//! Retrieval across several libraries
+//!
+//! Every library has its own vector index, and each may have been built with
+//! a different embedding model. The query is embedded once per distinct
+//! model, every index is searched with the matching embedding (in parallel),
+//! and the hits are merged by rank with reciprocal rank fusion: cosine
+//! scores from different models aren't comparable, ranks are.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::index::VectorIndex;
+use super::models::{EmbeddingConfig, SemanticSearchResult};
+
+/// Reciprocal rank fusion constant, as in hybrid search
+const RRF_K: f32 = 60.0;
+
+/// Which libraries a chat retrieves from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RagScope {
+    /// The library of the window the chat is in
+    #[default]
+    CurrentLibrary,
+    /// These libraries
+    #[serde(rename_all = "camelCase")]
+    Libraries { library_ids: Vec<Uuid> },
+    /// Every library
+    AllLibraries,
+}
+
+/// A library's vector index, ready to search
+pub struct LibrarySource {
+    pub library_id: Uuid,
+    pub library_name: String,
+    pub index: Arc<Mutex<VectorIndex>>,
+    /// Notebooks whose chunks must not be returned (encrypted and locked)
+    pub hidden_notebooks: HashSet<Uuid>,
+}
+
+/// One library's hits, best first
+#[derive(Debug, Clone)]
+pub struct LibraryHits {
+    pub library_id: Uuid,
+    pub library_name: String,
+    pub results: Vec<SemanticSearchResult>,
+}
+
+/// A hit with the library it came from
+#[derive(Debug, Clone)]
+pub struct ScopedResult {
+    pub library_id: Uuid,
+    pub library_name: String,
+    pub result: SemanticSearchResult,
+    /// Fused rank score the merged list is ordered by
+    pub fused_score: f32,
+}
+
+/// Indexes built with the same model can share a query embedding
+fn model_key(config: &EmbeddingConfig) -> (String, String, Option<String>) {
+    (
+        config.provider.clone(),
+        config.model.clone(),
+        config.base_url.clone(),
+    )
+}
+
+/// The embedding settings in the form the Python embedding helpers take
+pub fn embedding_config_json(config: &EmbeddingConfig) -> String {
+    serde_json::json!({
+        "provider": config.provider,
+        "model": config.model,
+        "api_key": config.api_key,
+        "base_url": config.base_url,
+    })
+    .to_string()
+}
+
+/// Merge per-library hits into one list of at most `limit`, by rank. Hits
+/// with the same fused score keep the order of their raw similarity.
+pub fn merge_library_hits(hits: Vec<LibraryHits>, limit: usize) -> Vec<ScopedResult> {
+    let mut merged: Vec<ScopedResult> = hits
+        .into_iter()
+        .flat_map(|library| {
+            let LibraryHits {
+                library_id,
+                library_name,
+                results,
+            } = library;
+            results
+                .into_iter()
+                .enumerate()
+                .map(move |(rank, result)| ScopedResult {
+                    library_id,
+                    library_name: library_name.clone(),
+                    result,
+                    fused_score: 1.0 / (RRF_K + rank as f32 + 1.0),
+                })
+        })
+        .collect();
+    merged.sort_by(|a, b| {
+        b.fused_score
+            .total_cmp(&a.fused_score)
+            .then(b.result.score.total_cmp(&a.result.score))
+    });
+    merged.truncate(limit);
+    merged
+}
+
+/// Search `sources` for the query and merge the hits. `embed` turns the query
+/// into a vector for an embedding model; it is called once per model.
+/// Libraries without a configured index, or whose model fails to embed the
+/// query, are skipped; an error is returned only if no library could be
+/// searched.
+pub fn search_libraries(
+    sources: &[LibrarySource],
+    embed: impl Fn(&EmbeddingConfig) -> Result<Vec<f32>, String>,
+    limit: usize,
+) -> Result<Vec<ScopedResult>, String> {
+    let mut configs = Vec::new();
+    for source in sources {
+        let index = source
+            .index
+            .lock()
+            .map_err(|e| format!("Failed to acquire vector index lock: {}", e))?;
+        configs.push(index.get_config().cloned());
+    }
+
+    let mut embeddings: HashMap<(String, String, Option<String>), Vec<f32>> = HashMap::new();
+    let mut last_error = None;
+    for config in configs.iter().flatten() {
+        let key = model_key(config);
+        if embeddings.contains_key(&key) {
+            continue;
+        }
+        match embed(config) {
+            Ok(embedding) => {
+                embeddings.insert(key, embedding);
+            }
+            Err(e) => {
+                log::warn!("Failed to embed query with {}: {}", config.model, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let searches: Vec<(&LibrarySource, &Vec<f32>)> = sources
+        .iter()
+        .zip(&configs)
+        .filter_map(|(source, config)| {
+            let embedding = embeddings.get(&model_key(config.as_ref()?))?;
+            Some((source, embedding))
+        })
+        .collect();
+    if searches.is_empty() {
+        return match last_error {
+            Some(e) => Err(format!("Failed to embed query: {}", e)),
+            None => Ok(Vec::new()),
+        };
+    }
+
+    let hits: Vec<LibraryHits> = std::thread::scope(|scope| {
+        let handles: Vec<_> = searches
+            .into_iter()
+            .map(|(source, embedding)| {
+                scope.spawn(move || -> Result<LibraryHits, String> {
+                    let index = source
+                        .index
+                        .lock()
+                        .map_err(|e| format!("Failed to acquire vector index lock: {}", e))?;
+                    // Every chunk is scored either way, so with hidden notebooks
+                    // take all hits and filter, rather than come up short
+                    let fetch = if source.hidden_notebooks.is_empty() {
+                        limit
+                    } else {
+                        usize::MAX
+                    };
+                    let results = index
+                        .search(embedding, fetch, None)
+                        .map_err(|e| e.to_string())?
+                        .into_iter()
+                        .filter(|r| {
+                            Uuid::parse_str(&r.notebook_id)
+                                .map_or(true, |id| !source.hidden_notebooks.contains(&id))
+                        })
+                        .take(limit)
+                        .collect();
+                    Ok(LibraryHits {
+                        library_id: source.library_id,
+                        library_name: source.library_name.clone(),
+                        results,
+                    })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| match handle.join() {
+                Ok(Ok(hits)) => Some(hits),
+                Ok(Err(e)) => {
+                    log::warn!("Library search failed: {}", e);
+                    None
+                }
+                Err(_) => {
+                    log::warn!("Library search panicked");
+                    None
+                }
+            })
+            .collect()
+    });
+
+    Ok(merge_library_hits(hits, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(chunk_id: &str, score: f32) -> SemanticSearchResult {
+        SemanticSearchResult {
+            chunk_id: chunk_id.to_string(),
+            page_id: format!("page-{}", chunk_id),
+            notebook_id: Uuid::nil().to_string(),
+            title: chunk_id.to_string(),
+            content: String::new(),
+            score,
+            media_block_id: None,
+            media_start: None,
+        }
+    }
+
+    fn library(name: &str, results: Vec<SemanticSearchResult>) -> LibraryHits {
+        LibraryHits {
+            library_id: Uuid::new_v4(),
+            library_name: name.to_string(),
+            results,
+        }
+    }
+
+    #[test]
+    fn merge_interleaves_libraries_by_rank() {
+        // Library B's model scores everything low, but its best hit still
+        // ranks alongside library A's best
+        let merged = merge_library_hits(
+            vec![
+                library("A", vec![hit("a1", 0.9), hit("a2", 0.85), hit("a3", 0.8)]),
+                library("B", vec![hit("b1", 0.4), hit("b2", 0.3)]),
+            ],
+            4,
+        );
+        let ids: Vec<&str> = merged.iter().map(|r| r.result.chunk_id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "b1", "a2", "b2"]);
+        assert_eq!(merged[1].library_name, "B");
+    }
+
+    #[test]
+    fn single_library_keeps_its_order() {
+        let merged = merge_library_hits(vec![library("A", vec![hit("x", 0.9), hit("y", 0.5)])], 10);
+        let ids: Vec<&str> = merged.iter().map(|r| r.result.chunk_id.as_str()).collect();
+        assert_eq!(ids, vec!["x", "y"]);
+    }
+
+    fn index_with(
+        dir: &std::path::Path,
+        model: &str,
+        notebook_id: Uuid,
+        vectors: &[Vec<f32>],
+    ) -> Arc<Mutex<VectorIndex>> {
+        let mut index = VectorIndex::new(dir.join(format!("{}.db", Uuid::new_v4()))).unwrap();
+        index
+            .configure(EmbeddingConfig {
+                model: model.to_string(),
+                dimensions: 2,
+                ..Default::default()
+            })
+            .unwrap();
+        for (i, vector) in vectors.iter().enumerate() {
+            let page_id = Uuid::new_v4();
+            let chunk = super::super::models::Chunk::new(
+                page_id,
+                notebook_id,
+                0,
+                format!("{} chunk {}", model, i),
+                None,
+            );
+            index
+                .index_page(
+                    page_id,
+                    &format!("{} page {}", model, i),
+                    &[chunk],
+                    std::slice::from_ref(vector),
+                )
+                .unwrap();
+        }
+        Arc::new(Mutex::new(index))
+    }
+
+    #[test]
+    fn searches_each_library_with_its_own_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let hidden = Uuid::new_v4();
+        let source = |name: &str, index, hidden_notebooks| LibrarySource {
+            library_id: Uuid::new_v4(),
+            library_name: name.to_string(),
+            index,
+            hidden_notebooks,
+        };
+        let sources = vec![
+            source(
+                "Work",
+                index_with(
+                    dir.path(),
+                    "m1",
+                    Uuid::new_v4(),
+                    &[vec![1.0, 0.0], vec![0.0, 1.0]],
+                ),
+                HashSet::new(),
+            ),
+            source(
+                "Journal",
+                index_with(dir.path(), "m2", hidden, &[vec![0.0, 1.0]]),
+                HashSet::from([hidden]),
+            ),
+            source(
+                "Reading",
+                index_with(dir.path(), "m1", Uuid::new_v4(), &[vec![0.6, 0.8]]),
+                HashSet::new(),
+            ),
+        ];
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let merged = search_libraries(
+            &sources,
+            |config| {
+                calls.borrow_mut().push(config.model.clone());
+                Ok(vec![1.0, 0.0])
+            },
+            5,
+        )
+        .unwrap();
+
+        // One embedding per model, not per library
+        assert_eq!(calls.borrow().len(), 2);
+        let names: Vec<(&str, &str)> = merged
+            .iter()
+            .map(|r| (r.library_name.as_str(), r.result.title.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Work", "m1 page 0"),
+                ("Reading", "m1 page 0"),
+                ("Work", "m1 page 1")
+            ]
+        );
+    }
+
+    #[test]
+    fn scope_serializes_with_a_type_tag() {
+        let id = Uuid::nil();
+        let scope: RagScope = serde_json::from_value(serde_json::json!({
+            "type": "libraries",
+            "libraryIds": [id],
+        }))
+        .unwrap();
+        assert_eq!(
+            scope,
+            RagScope::Libraries {
+                library_ids: vec![id]
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(RagScope::default()).unwrap(),
+            serde_json::json!({ "type": "currentLibrary" })
+        );
+    }
+}
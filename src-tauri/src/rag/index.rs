@@ -1,14 +1,15 @@
 //! Vector index for semantic search using SQLite with vec0 extension.
 
+use std::cell::OnceCell;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use rusqlite::{params, Connection};
 use serde_json;
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::models::{Chunk, EmbeddingConfig, SemanticSearchResult};
+use super::models::{Chunk, ChunkMetadata, EmbeddingConfig, SemanticSearchResult};
 
 #[derive(Error, Debug)]
 pub enum VectorIndexError {
@@ -34,21 +35,27 @@ pub type Result<T> = std::result::Result<T, VectorIndexError>;
 ///
 /// Uses SQLite with a custom virtual table for vector similarity search.
 /// When sqlite-vec is not available, falls back to brute-force search.
+///
+/// The database is opened on first use (or by [`VectorIndex::load`]), so a
+/// [`VectorIndex::lazy`] index costs nothing until something needs it.
 pub struct VectorIndex {
+    opened: OnceCell<OpenedIndex>,
+    db_path: PathBuf,
+}
+
+struct OpenedIndex {
     conn: Connection,
     config: Option<EmbeddingConfig>,
-    db_path: PathBuf,
 }
 
-impl VectorIndex {
-    /// Create a new vector index at the given path.
-    pub fn new(db_path: PathBuf) -> Result<Self> {
+impl OpenedIndex {
+    fn open(db_path: &Path) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(&db_path)?;
+        let conn = Connection::open(db_path)?;
 
         // Create tables
         conn.execute_batch(
@@ -103,34 +110,75 @@ impl VectorIndex {
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok());
 
-        Ok(Self {
-            conn,
-            config,
+        Ok(Self { conn, config })
+    }
+}
+
+impl VectorIndex {
+    /// Create a new vector index at the given path.
+    pub fn new(db_path: PathBuf) -> Result<Self> {
+        let index = Self::lazy(db_path);
+        index.load()?;
+        Ok(index)
+    }
+
+    /// A vector index at the given path that opens its database on first use.
+    pub fn lazy(db_path: PathBuf) -> Self {
+        Self {
+            opened: OnceCell::new(),
             db_path,
-        })
+        }
+    }
+
+    /// Open the database now rather than on first use.
+    pub fn load(&self) -> Result<()> {
+        self.opened().map(|_| ())
+    }
+
+    /// Whether the database has been opened.
+    pub fn is_loaded(&self) -> bool {
+        self.opened.get().is_some()
+    }
+
+    fn opened(&self) -> Result<&OpenedIndex> {
+        if self.opened.get().is_none() {
+            let _ = self.opened.set(OpenedIndex::open(&self.db_path)?);
+        }
+        Ok(self.opened.get().expect("vector index was just opened"))
+    }
+
+    fn opened_mut(&mut self) -> Result<&mut OpenedIndex> {
+        self.opened()?;
+        Ok(self.opened.get_mut().expect("vector index was just opened"))
+    }
+
+    fn conn(&self) -> Result<&Connection> {
+        Ok(&self.opened()?.conn)
     }
 
     /// Configure the embedding model to use.
     pub fn configure(&mut self, config: EmbeddingConfig) -> Result<()> {
         let config_json = serde_json::to_string(&config)?;
 
-        self.conn.execute(
+        let opened = self.opened_mut()?;
+        opened.conn.execute(
             "INSERT OR REPLACE INTO config (key, value) VALUES ('embedding_config', ?1)",
             params![config_json],
         )?;
 
-        self.config = Some(config);
+        opened.config = Some(config);
         Ok(())
     }
 
-    /// Get the current embedding configuration.
+    /// Get the current embedding configuration. `None` as well when the
+    /// database can't be opened; the next read or write reports why.
     pub fn get_config(&self) -> Option<&EmbeddingConfig> {
-        self.config.as_ref()
+        self.opened().ok()?.config.as_ref()
     }
 
     /// Check if the index is configured.
     pub fn is_configured(&self) -> bool {
-        self.config.is_some()
+        self.get_config().is_some()
     }
 
     /// Index a page with pre-computed chunks and embeddings.
@@ -150,7 +198,7 @@ impl VectorIndex {
         }
 
         // Verify embedding dimensions match config
-        if let Some(config) = &self.config {
+        if let Some(config) = self.get_config() {
             for (i, emb) in embeddings.iter().enumerate() {
                 if emb.len() as u32 != config.dimensions {
                     return Err(VectorIndexError::DimensionMismatch {
@@ -162,7 +210,7 @@ impl VectorIndex {
         }
 
         // Start transaction
-        let tx = self.conn.transaction()?;
+        let tx = self.opened_mut()?.conn.transaction()?;
 
         // Remove existing chunks and embeddings for this page
         tx.execute(
@@ -222,7 +270,7 @@ impl VectorIndex {
 
     /// Remove a page from the index.
     pub fn remove_page(&mut self, page_id: Uuid) -> Result<()> {
-        let tx = self.conn.transaction()?;
+        let tx = self.opened_mut()?.conn.transaction()?;
 
         tx.execute(
             "DELETE FROM embeddings WHERE chunk_id IN (SELECT id FROM chunks WHERE page_id = ?1)",
@@ -256,7 +304,7 @@ impl VectorIndex {
         // Build query based on notebook filter
         let sql = if notebook_id.is_some() {
             r#"
-            SELECT c.id, c.page_id, c.notebook_id, c.content, e.embedding, p.title
+            SELECT c.id, c.page_id, c.notebook_id, c.content, e.embedding, p.title, c.metadata
             FROM chunks c
             JOIN embeddings e ON c.id = e.chunk_id
             LEFT JOIN page_titles p ON c.page_id = p.page_id
@@ -264,17 +312,18 @@ impl VectorIndex {
             "#
         } else {
             r#"
-            SELECT c.id, c.page_id, c.notebook_id, c.content, e.embedding, p.title
+            SELECT c.id, c.page_id, c.notebook_id, c.content, e.embedding, p.title, c.metadata
             FROM chunks c
             JOIN embeddings e ON c.id = e.chunk_id
             LEFT JOIN page_titles p ON c.page_id = p.page_id
             "#
         };
 
-        let mut stmt = self.conn.prepare(sql)?;
+        let mut stmt = self.conn()?.prepare(sql)?;
 
         // Collect all rows first to avoid lifetime issues
-        let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<(String, String, String, String, Vec<u8>, Option<String>)> {
+        #[allow(clippy::type_complexity)]
+        let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<(String, String, String, String, Vec<u8>, Option<String>, Option<String>)> {
             Ok((
                 row.get::<_, String>(0)?, // chunk_id
                 row.get::<_, String>(1)?, // page_id
@@ -282,6 +331,7 @@ impl VectorIndex {
                 row.get::<_, String>(3)?, // content
                 row.get::<_, Vec<u8>>(4)?, // embedding
                 row.get::<_, Option<String>>(5)?, // title
+                row.get::<_, Option<String>>(6)?, // metadata
             ))
         };
 
@@ -295,7 +345,7 @@ impl VectorIndex {
 
         let mut scored_results: Vec<(f32, SemanticSearchResult)> = Vec::new();
 
-        for (chunk_id, page_id, notebook_id, content, embedding_bytes, title) in collected_rows {
+        for (chunk_id, page_id, notebook_id, content, embedding_bytes, title, metadata) in collected_rows {
 
             // Deserialize embedding
             let embedding = deserialize_embedding(&embedding_bytes);
@@ -303,6 +353,13 @@ impl VectorIndex {
             // Calculate cosine similarity
             let score = cosine_similarity(query_embedding, &embedding);
 
+            // Transcript chunks carry their video timestamps in metadata
+            let metadata: Option<ChunkMetadata> =
+                metadata.and_then(|m| serde_json::from_str(&m).ok());
+            let (media_block_id, media_start) = metadata
+                .map(|m| (m.media_block_id, m.media_start))
+                .unwrap_or((None, None));
+
             scored_results.push((
                 score,
                 SemanticSearchResult {
@@ -312,6 +369,8 @@ impl VectorIndex {
                     title: title.unwrap_or_default(),
                     content,
                     score,
+                    media_block_id,
+                    media_start,
                 },
             ));
         }
@@ -333,7 +392,7 @@ impl VectorIndex {
         notebook_id: Option<Uuid>,
     ) -> Result<Vec<SemanticSearchResult>> {
         // Get all embeddings for the source page
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn()?.prepare(
             r#"
             SELECT e.embedding
             FROM embeddings e
@@ -397,7 +456,7 @@ impl VectorIndex {
 
     /// Rebuild the index (clear and prepare for re-indexing).
     pub fn rebuild(&mut self) -> Result<()> {
-        let tx = self.conn.transaction()?;
+        let tx = self.opened_mut()?.conn.transaction()?;
 
         tx.execute("DELETE FROM embeddings", [])?;
         tx.execute("DELETE FROM chunks", [])?;
@@ -409,17 +468,17 @@ impl VectorIndex {
 
     /// Get statistics about the index.
     pub fn stats(&self) -> Result<IndexStats> {
-        let chunk_count: i64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))?;
+        let conn = self.conn()?;
+        let chunk_count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))?;
 
-        let page_count: i64 = self.conn.query_row(
+        let page_count: i64 = conn.query_row(
             "SELECT COUNT(DISTINCT page_id) FROM chunks",
             [],
             |row| row.get(0),
         )?;
 
-        let notebook_count: i64 = self.conn.query_row(
+        let notebook_count: i64 = conn.query_row(
             "SELECT COUNT(DISTINCT notebook_id) FROM chunks",
             [],
             |row| row.get(0),
@@ -429,7 +488,7 @@ impl VectorIndex {
             chunk_count: chunk_count as u64,
             page_count: page_count as u64,
             notebook_count: notebook_count as u64,
-            dimensions: self.config.as_ref().map(|c| c.dimensions).unwrap_or(0),
+            dimensions: self.get_config().map(|c| c.dimensions).unwrap_or(0),
         })
     }
 
@@ -515,6 +574,19 @@ mod tests {
         assert!((cosine_similarity(&a, &d) + 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_lazy_index_opens_on_first_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".nous").join("vectors.db");
+        let index = VectorIndex::lazy(path.clone());
+        assert!(!index.is_loaded());
+        assert!(!path.exists());
+
+        assert_eq!(index.stats().unwrap().chunk_count, 0);
+        assert!(index.is_loaded());
+        assert!(path.exists());
+    }
+
     #[test]
     fn test_deserialize_embedding() {
         let values = vec![1.0f32, 2.0, 3.0];
@@ -1,9 +1,11 @@
 //! RAG (Retrieval-Augmented Generation) module for semantic search and AI context.
 
 mod chunker;
+mod federated;
 mod index;
 mod models;
 
 pub use chunker::{chunk_page, chunk_page_with_text};
+pub use federated::{embedding_config_json, search_libraries, LibrarySource, RagScope};
 pub use index::VectorIndex;
-pub use models::{EmbeddingConfig, SemanticSearchResult};
+pub use models::{Chunk, ChunkingStrategy, EmbeddingConfig, SemanticSearchResult};
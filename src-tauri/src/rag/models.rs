@@ -13,6 +13,18 @@ pub struct ChunkMetadata {
     pub start_offset: usize,
     /// End position in the original content
     pub end_offset: usize,
+    /// For transcript chunks: video block the text came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_block_id: Option<String>,
+    /// For transcript chunks: start of the covered span, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_start: Option<f64>,
+    /// For transcript chunks: end of the covered span, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_end: Option<f64>,
+    /// For heading-aware chunks: the heading of the section the text is under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
 }
 
 /// A chunk of content with its embedding-ready text.
@@ -69,6 +81,9 @@ pub struct EmbeddingConfig {
     /// Optional base URL (for Ollama/LM Studio)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_url: Option<String>,
+    /// How pages are split into chunks before embedding
+    #[serde(default)]
+    pub chunking: ChunkingStrategy,
 }
 
 impl Default for EmbeddingConfig {
@@ -79,6 +94,59 @@ impl Default for EmbeddingConfig {
             dimensions: 1536,
             api_key: None,
             base_url: None,
+            chunking: ChunkingStrategy::default(),
+        }
+    }
+}
+
+/// How page text is split into chunks. Sizes are in approximate tokens.
+///
+/// The defaults reproduce the original fixed strategy (512-token windows
+/// overlapping by 50, ignoring page structure), so existing indexes stay
+/// valid until the user changes them and re-chunks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChunkingStrategy {
+    /// Maximum tokens per chunk
+    pub chunk_size: usize,
+    /// Tokens repeated from the end of one chunk at the start of the next
+    pub overlap: usize,
+    /// Start a new chunk at every heading and prefix each chunk with the
+    /// heading it falls under
+    pub heading_aware: bool,
+    /// Keep code blocks out of prose chunks and never split them mid-line
+    pub code_aware: bool,
+}
+
+impl ChunkingStrategy {
+    /// Smallest chunk size accepted; anything below fragments sentences.
+    pub const MIN_CHUNK_SIZE: usize = 64;
+    /// Largest chunk size accepted; most embedding models truncate past it.
+    pub const MAX_CHUNK_SIZE: usize = 8192;
+
+    /// Check the sizes are usable.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(Self::MIN_CHUNK_SIZE..=Self::MAX_CHUNK_SIZE).contains(&self.chunk_size) {
+            return Err(format!(
+                "Chunk size must be between {} and {} tokens",
+                Self::MIN_CHUNK_SIZE,
+                Self::MAX_CHUNK_SIZE
+            ));
+        }
+        if self.overlap >= self.chunk_size / 2 {
+            return Err("Chunk overlap must be less than half the chunk size".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for ChunkingStrategy {
+    fn default() -> Self {
+        Self {
+            chunk_size: 512,
+            overlap: 50,
+            heading_aware: false,
+            code_aware: false,
         }
     }
 }
@@ -99,6 +167,12 @@ pub struct SemanticSearchResult {
     pub content: String,
     /// Similarity score (higher is more similar)
     pub score: f32,
+    /// Video block for transcript chunks (seek target)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_block_id: Option<String>,
+    /// Timestamp (seconds) where a transcript chunk starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_start: Option<f64>,
 }
 
 /// Combined result for hybrid search (semantic + keyword).
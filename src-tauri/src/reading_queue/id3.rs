@@ -0,0 +1,185 @@
+//! ID3v2.3 chapter tags
+//!
+//! Writes a tag with the queue's title, a `CTOC` table of contents and one
+//! `CHAP` frame per chapter (ID3v2 Chapter Frame Addendum). Version 2.3 is
+//! used rather than 2.4 because more players read chapters from it. Any
+//! ID3v2 tag the encoder left at the start of the file is replaced.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::models::QueueChapter;
+
+/// Replace the ID3v2 tag of the MP3 at `path` with one holding `title` and
+/// `chapters`
+pub fn write_chapters(path: &Path, title: &str, chapters: &[QueueChapter]) -> io::Result<()> {
+    let data = fs::read(path)?;
+    let mut out = build_tag(title, chapters);
+    out.extend_from_slice(strip_tag(&data));
+
+    let tmp = path.with_extension("mp3.tmp");
+    fs::write(&tmp, &out)?;
+    fs::rename(&tmp, path)
+}
+
+/// An ID3v2.3 tag for `title` and `chapters`
+pub fn build_tag(title: &str, chapters: &[QueueChapter]) -> Vec<u8> {
+    let mut frames = frame(b"TIT2", &text_payload(title));
+
+    let ids: Vec<String> = (0..chapters.len()).map(|i| format!("chp{}", i)).collect();
+    let mut toc = b"toc\0".to_vec();
+    // Top-level, ordered
+    toc.push(0x03);
+    toc.push(chapters.len().min(u8::MAX as usize) as u8);
+    for id in ids.iter().take(u8::MAX as usize) {
+        toc.extend_from_slice(id.as_bytes());
+        toc.push(0);
+    }
+    frames.extend(frame(b"CTOC", &toc));
+
+    for (id, chapter) in ids.iter().zip(chapters) {
+        let mut chap = id.as_bytes().to_vec();
+        chap.push(0);
+        chap.extend_from_slice(&millis(chapter.start_seconds).to_be_bytes());
+        chap.extend_from_slice(&millis(chapter.end_seconds).to_be_bytes());
+        // Byte offsets unused; players go by time
+        chap.extend_from_slice(&u32::MAX.to_be_bytes());
+        chap.extend_from_slice(&u32::MAX.to_be_bytes());
+        chap.extend(frame(b"TIT2", &text_payload(&chapter.title)));
+        frames.extend(frame(b"CHAP", &chap));
+    }
+
+    let mut tag = b"ID3\x03\x00\x00".to_vec();
+    tag.extend_from_slice(&syncsafe(frames.len() as u32));
+    tag.extend(frames);
+    tag
+}
+
+/// `data` without a leading ID3v2 tag
+pub fn strip_tag(data: &[u8]) -> &[u8] {
+    if data.len() < 10 || &data[..3] != b"ID3" {
+        return data;
+    }
+    let size = data[6..10]
+        .iter()
+        .fold(0usize, |acc, b| (acc << 7) | (*b & 0x7f) as usize);
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    &data[(10 + size + footer).min(data.len())..]
+}
+
+fn frame(id: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut out = id.to_vec();
+    out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    out.extend_from_slice(&[0, 0]);
+    out.extend_from_slice(payload);
+    out
+}
+
+/// A text frame body: ISO-8859-1 when the text fits, UTF-16 otherwise
+fn text_payload(text: &str) -> Vec<u8> {
+    if text.chars().all(|c| (c as u32) <= 0xff) {
+        let mut out = vec![0x00];
+        out.extend(text.chars().map(|c| c as u8));
+        out
+    } else {
+        let mut out = vec![0x01, 0xff, 0xfe];
+        for unit in text.encode_utf16() {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+        out
+    }
+}
+
+fn millis(seconds: f64) -> u32 {
+    (seconds.max(0.0) * 1000.0).round().min(u32::MAX as f64) as u32
+}
+
+fn syncsafe(size: u32) -> [u8; 4] {
+    [
+        ((size >> 21) & 0x7f) as u8,
+        ((size >> 14) & 0x7f) as u8,
+        ((size >> 7) & 0x7f) as u8,
+        (size & 0x7f) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn chapter(title: &str, start: f64, end: f64) -> QueueChapter {
+        QueueChapter {
+            page_id: Uuid::new_v4(),
+            title: title.to_string(),
+            start_seconds: start,
+            end_seconds: end,
+        }
+    }
+
+    /// (id, payload) of each top-level frame in `tag`
+    fn frames(tag: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut out = Vec::new();
+        let mut rest = &tag[10..];
+        while rest.len() >= 10 {
+            let size = u32::from_be_bytes(rest[4..8].try_into().unwrap()) as usize;
+            out.push((
+                String::from_utf8(rest[..4].to_vec()).unwrap(),
+                rest[10..10 + size].to_vec(),
+            ));
+            rest = &rest[10 + size..];
+        }
+        out
+    }
+
+    #[test]
+    fn tag_lists_chapters_in_order() {
+        let tag = build_tag(
+            "Morning reading",
+            &[
+                chapter("Intro", 0.0, 12.5),
+                chapter("Café notes", 13.7, 40.0),
+            ],
+        );
+        assert_eq!(&tag[..5], b"ID3\x03\x00");
+        assert_eq!(strip_tag(&tag).len(), 0);
+
+        let frames = frames(&tag);
+        let ids: Vec<&str> = frames.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["TIT2", "CTOC", "CHAP", "CHAP"]);
+        assert_eq!(frames[0].1, b"\x00Morning reading");
+        assert_eq!(frames[1].1, b"toc\0\x03\x02chp0\0chp1\0");
+
+        let second = &frames[3].1;
+        assert_eq!(&second[..5], b"chp1\0");
+        assert_eq!(u32::from_be_bytes(second[5..9].try_into().unwrap()), 13_700);
+        assert_eq!(
+            u32::from_be_bytes(second[9..13].try_into().unwrap()),
+            40_000
+        );
+        // "é" fits ISO-8859-1
+        assert_eq!(&second[31..], b"\x00Caf\xe9 notes");
+    }
+
+    #[test]
+    fn titles_outside_latin1_use_utf16() {
+        assert_eq!(text_payload("→"), vec![0x01, 0xff, 0xfe, 0x92, 0x21]);
+    }
+
+    #[test]
+    fn existing_tag_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.mp3");
+        let audio = b"\xff\xfb\x90\x00audio";
+        let mut old = b"ID3\x04\x00\x00\x00\x00\x00\x05".to_vec();
+        old.extend_from_slice(b"xxxxx");
+        old.extend_from_slice(audio);
+        fs::write(&path, &old).unwrap();
+
+        write_chapters(&path, "Queue", &[chapter("One", 0.0, 1.0)]).unwrap();
+        let written = fs::read(&path).unwrap();
+        assert_eq!(&written[..4], b"ID3\x03");
+        assert_eq!(strip_tag(&written), audio);
+    }
+}
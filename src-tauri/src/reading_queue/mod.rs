@@ -0,0 +1,17 @@
+//! Text-to-speech reading queues
+//!
+//! A reading queue is an ordered list of pages narrated into a single MP3,
+//! one chapter per page. The chapter markers are written as ID3 `CHAP`/`CTOC`
+//! frames, which podcast players and most audio apps show as a chapter list.
+//! The audio is stored as a notebook asset next to the per-page narrations.
+//!
+//! Queues live in the library, but the playback position is kept in the
+//! user's local data directory, so everyone listening to a shared library
+//! resumes from their own place.
+
+pub mod id3;
+mod models;
+mod storage;
+
+pub use models::*;
+pub use storage::*;
@@ -0,0 +1,82 @@
+//! Reading queue data models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Chapters an ID3 table of contents can list
+pub const MAX_QUEUE_PAGES: usize = 255;
+
+/// An ordered list of pages to narrate into one audio file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueue {
+    pub id: Uuid,
+    pub notebook_id: Uuid,
+    pub name: String,
+    pub page_ids: Vec<Uuid>,
+    /// The last narration, if any
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub audio: Option<QueueAudio>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReadingQueue {
+    pub fn new(notebook_id: Uuid, name: String, page_ids: Vec<Uuid>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            notebook_id,
+            name,
+            page_ids,
+            audio: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether the narration still matches the queue's pages and order
+    pub fn audio_is_current(&self) -> bool {
+        self.audio.as_ref().is_some_and(|audio| {
+            audio
+                .chapters
+                .iter()
+                .map(|c| c.page_id)
+                .eq(self.page_ids.iter().copied())
+        })
+    }
+}
+
+/// A narrated reading queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueAudio {
+    /// File name in the notebook's `assets/audio` directory
+    pub file_name: String,
+    pub duration_seconds: f64,
+    pub file_size_bytes: i64,
+    pub chapters: Vec<QueueChapter>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A page's chapter in a narrated queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueChapter {
+    pub page_id: Uuid,
+    pub title: String,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+/// Where the user stopped listening to a queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackPosition {
+    pub queue_id: Uuid,
+    pub position_seconds: f64,
+    /// The narration the position is in; a regenerated queue starts over
+    pub audio_generated_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,158 @@
+//! Reading queue storage implementation
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::StorageError;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// The reading queues of a library, in a single JSON file
+pub struct ReadingQueueStorage {
+    path: PathBuf,
+    queues: Vec<ReadingQueue>,
+}
+
+impl ReadingQueueStorage {
+    /// Open the queues of the library at `data_dir`
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&data_dir)?;
+        let path = data_dir.join("reading_queues.json");
+        let queues = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable reading queues: {}", e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { path, queues })
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.queues)?)?;
+        Ok(())
+    }
+
+    /// Queues of one notebook, or of the whole library
+    pub fn list(&self, notebook_id: Option<Uuid>) -> Vec<ReadingQueue> {
+        self.queues
+            .iter()
+            .filter(|q| notebook_id.map_or(true, |id| q.notebook_id == id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, queue_id: Uuid) -> Result<ReadingQueue> {
+        self.queues
+            .iter()
+            .find(|q| q.id == queue_id)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(format!("Reading queue {}", queue_id)))
+    }
+
+    pub fn create(&mut self, queue: ReadingQueue) -> Result<ReadingQueue> {
+        self.queues.push(queue.clone());
+        self.save()?;
+        Ok(queue)
+    }
+
+    /// Replace a stored queue, bumping its `updated_at`
+    pub fn update(&mut self, mut queue: ReadingQueue) -> Result<ReadingQueue> {
+        let slot = self
+            .queues
+            .iter_mut()
+            .find(|q| q.id == queue.id)
+            .ok_or_else(|| StorageError::NotFound(format!("Reading queue {}", queue.id)))?;
+        queue.updated_at = Utc::now();
+        *slot = queue.clone();
+        self.save()?;
+        Ok(queue)
+    }
+
+    /// Remove a queue, returning it so its audio can be cleaned up
+    pub fn delete(&mut self, queue_id: Uuid) -> Result<ReadingQueue> {
+        let index = self
+            .queues
+            .iter()
+            .position(|q| q.id == queue_id)
+            .ok_or_else(|| StorageError::NotFound(format!("Reading queue {}", queue_id)))?;
+        let queue = self.queues.remove(index);
+        self.save()?;
+        Ok(queue)
+    }
+}
+
+/// The user's playback positions, kept outside the library
+pub struct PlaybackPositions {
+    path: PathBuf,
+    positions: HashMap<Uuid, PlaybackPosition>,
+}
+
+impl PlaybackPositions {
+    /// Open the positions saved in the user's data directory `data_dir`
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&data_dir)?;
+        let path = data_dir.join("reading_positions.json");
+        let positions = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let list: Vec<PlaybackPosition> = serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable reading positions: {}", e);
+                Vec::new()
+            });
+            list.into_iter().map(|p| (p.queue_id, p)).collect()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { path, positions })
+    }
+
+    fn save(&self) -> Result<()> {
+        let list: Vec<&PlaybackPosition> = self.positions.values().collect();
+        fs::write(&self.path, serde_json::to_string(&list)?)?;
+        Ok(())
+    }
+
+    /// The position in `queue`'s current narration, if the user has started it
+    pub fn get(&self, queue: &ReadingQueue) -> Option<PlaybackPosition> {
+        let audio = queue.audio.as_ref()?;
+        self.positions
+            .get(&queue.id)
+            .filter(|p| p.audio_generated_at == audio.generated_at)
+            .cloned()
+    }
+
+    /// Remember where the user is in the narration generated at
+    /// `audio_generated_at`
+    pub fn set(
+        &mut self,
+        queue_id: Uuid,
+        audio_generated_at: DateTime<Utc>,
+        position_seconds: f64,
+    ) -> Result<PlaybackPosition> {
+        let position = PlaybackPosition {
+            queue_id,
+            position_seconds: position_seconds.max(0.0),
+            audio_generated_at,
+            updated_at: Utc::now(),
+        };
+        self.positions.insert(queue_id, position.clone());
+        self.save()?;
+        Ok(position)
+    }
+
+    pub fn remove(&mut self, queue_id: Uuid) -> Result<()> {
+        if self.positions.remove(&queue_id).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+//! Page reminders
+//!
+//! Provides:
+//! - Reminders on pages, set with natural-language times
+//! - Storage of reminders per library
+//! - Delivery through the notifications module, and the daily agenda
+
+mod models;
+pub mod parse;
+mod storage;
+
+pub use models::*;
+pub use parse::parse_when;
+pub use storage::*;
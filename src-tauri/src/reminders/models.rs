@@ -0,0 +1,61 @@
+//! Reminder data models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A reminder to revisit a page at a given time. Snoozed pages are
+/// reminders with `resurface` set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageReminder {
+    pub id: Uuid,
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    /// Page title when the reminder was set, for the notification text
+    pub page_title: String,
+    pub remind_at: DateTime<Utc>,
+    /// Optional note shown with the reminder
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// When the notification was shown (None = still pending)
+    pub fired_at: Option<DateTime<Utc>>,
+    /// Set by "resurface this note later" rather than an explicit reminder
+    #[serde(default)]
+    pub resurface: bool,
+}
+
+impl PageReminder {
+    pub fn new(
+        notebook_id: Uuid,
+        page_id: Uuid,
+        page_title: String,
+        remind_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            notebook_id,
+            page_id,
+            page_title,
+            remind_at,
+            note: None,
+            created_at: Utc::now(),
+            fired_at: None,
+            resurface: false,
+        }
+    }
+
+    pub fn resurfacing(mut self) -> Self {
+        self.resurface = true;
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.note = Some(note);
+        self
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.fired_at.is_none()
+    }
+}
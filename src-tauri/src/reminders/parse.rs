@@ -0,0 +1,331 @@
+//! Natural-language reminder times ("next tuesday 9am", "in 3 days")
+//!
+//! Deliberately small: relative offsets, today/tomorrow/tonight, weekdays,
+//! "next week/month/year", month-day dates, ISO dates and clock times. A
+//! date without a time means 09:00; a time without a date means the next
+//! time the clock shows it.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("monday", Weekday::Mon),
+    ("tuesday", Weekday::Tue),
+    ("wednesday", Weekday::Wed),
+    ("thursday", Weekday::Thu),
+    ("friday", Weekday::Fri),
+    ("saturday", Weekday::Sat),
+    ("sunday", Weekday::Sun),
+];
+
+fn default_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// Parse `input` relative to `now` (local time)
+pub fn parse_when(input: &str, now: NaiveDateTime) -> Result<NaiveDateTime, String> {
+    let normalized = input.trim().to_lowercase().replace(',', " ");
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    let unknown = || format!("Couldn't understand '{}'", input.trim());
+    if tokens.is_empty() {
+        return Err("No time given".to_string());
+    }
+
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dt%H:%M", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(&normalized, format) {
+            return Ok(dt);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&normalized, "%Y-%m-%d") {
+        return Ok(date.and_time(default_time()));
+    }
+
+    if tokens[0] == "in" {
+        return parse_relative(&tokens[1..], now).ok_or_else(unknown);
+    }
+
+    let (time, date_tokens, tonight) = split_time(&tokens).ok_or_else(unknown)?;
+    let today = now.date();
+    let date = if tonight {
+        Some(today)
+    } else {
+        parse_date(&date_tokens, today).map_err(|_| unknown())?
+    };
+
+    match (date, time) {
+        (Some(date), time) => Ok(date.and_time(time.unwrap_or_else(default_time))),
+        (None, Some(time)) => {
+            let at = today.and_time(time);
+            Ok(if at <= now {
+                at + Duration::days(1)
+            } else {
+                at
+            })
+        }
+        (None, None) => Err(unknown()),
+    }
+}
+
+/// "3 days", "an hour", "2 weeks at 5pm"
+fn parse_relative(tokens: &[&str], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let amount: u32 = match *tokens.first()? {
+        "a" | "an" | "one" => 1,
+        n => n.parse().ok()?,
+    };
+    let unit = tokens.get(1)?.trim_end_matches('s');
+    let (time, rest, _) = split_time(tokens.get(2..).unwrap_or_default())?;
+    if !rest.is_empty() {
+        return None;
+    }
+
+    let date = now.date();
+    let shifted = match unit {
+        "min" | "minute" => return Some(now + Duration::minutes(amount.into())),
+        "h" | "hr" | "hour" => return Some(now + Duration::hours(amount.into())),
+        "day" => date + Duration::days(amount.into()),
+        "week" => date + Duration::weeks(amount.into()),
+        "month" => date.checked_add_months(Months::new(amount))?,
+        "year" => date.checked_add_months(Months::new(amount.checked_mul(12)?))?,
+        _ => return None,
+    };
+    Some(shifted.and_time(time.unwrap_or(now.time())))
+}
+
+/// Pull time-of-day words out of `tokens`. Returns the time, the remaining
+/// (date) tokens, and whether "tonight" was used.
+fn split_time<'a>(tokens: &[&'a str]) -> Option<(Option<NaiveTime>, Vec<&'a str>, bool)> {
+    let mut time = None;
+    let mut rest = Vec::new();
+    let mut tonight = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let after_at = i > 0 && tokens[i - 1] == "at";
+        let named = match token {
+            "noon" | "midday" => Some(hm(12, 0)),
+            "midnight" => Some(hm(0, 0)),
+            "morning" => Some(hm(9, 0)),
+            "afternoon" => Some(hm(14, 0)),
+            "evening" => Some(hm(18, 0)),
+            "night" => Some(hm(20, 0)),
+            "tonight" => {
+                tonight = true;
+                Some(hm(20, 0))
+            }
+            _ => None,
+        };
+        if let Some(named) = named {
+            // An explicit clock time ("tonight at 9pm") wins
+            time = time.or(Some(named));
+            i += 1;
+            continue;
+        }
+
+        let meridiem = tokens
+            .get(i + 1)
+            .copied()
+            .filter(|next| matches!(*next, "am" | "pm" | "a.m." | "p.m."));
+        if let Some(clock) = parse_clock(token, meridiem, after_at) {
+            time = Some(clock);
+            i += if meridiem.is_some() { 2 } else { 1 };
+            continue;
+        }
+
+        if !matches!(token, "at" | "on" | "the" | "this" | "of") {
+            rest.push(token);
+        }
+        i += 1;
+    }
+    Some((time, rest, tonight))
+}
+
+/// "9am", "9:30pm", "14:30", "9" + "am"; a bare hour only after "at"
+fn parse_clock(token: &str, meridiem: Option<&str>, after_at: bool) -> Option<NaiveTime> {
+    let (body, pm) = if let Some(body) = token
+        .strip_suffix("am")
+        .or_else(|| token.strip_suffix("a.m."))
+    {
+        (body, Some(false))
+    } else if let Some(body) = token
+        .strip_suffix("pm")
+        .or_else(|| token.strip_suffix("p.m."))
+    {
+        (body, Some(true))
+    } else {
+        (token, meridiem.map(|m| m.starts_with('p')))
+    };
+    if body.is_empty() || !body.chars().all(|c| c.is_ascii_digit() || c == ':') {
+        return None;
+    }
+
+    let (hour, minute): (u32, u32) = match body.split_once(':') {
+        Some((h, m)) if m.len() == 2 => (h.parse().ok()?, m.parse().ok()?),
+        Some(_) => return None,
+        None if pm.is_some() || after_at => (body.parse().ok()?, 0),
+        // A bare number is a day of the month
+        None => return None,
+    };
+    let hour = match pm {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn month_number(token: &str) -> Option<u32> {
+    if token.len() < 3 {
+        return None;
+    }
+    let token = token.trim_end_matches('.');
+    MONTHS
+        .iter()
+        .position(|m| m.starts_with(token))
+        .map(|i| i as u32 + 1)
+}
+
+fn weekday(token: &str) -> Option<Weekday> {
+    if token.len() < 3 {
+        return None;
+    }
+    WEEKDAYS
+        .iter()
+        .find(|(name, _)| name.starts_with(token))
+        .map(|(_, day)| *day)
+}
+
+fn day_of_month(token: &str) -> Option<u32> {
+    let digits = token
+        .trim_end_matches("st")
+        .trim_end_matches("nd")
+        .trim_end_matches("rd")
+        .trim_end_matches("th");
+    digits.parse().ok().filter(|d| (1..=31).contains(d))
+}
+
+/// The next `day` strictly after `from`
+fn next_weekday(from: NaiveDate, day: Weekday) -> NaiveDate {
+    let ahead = (day.num_days_from_monday() + 7 - from.weekday().num_days_from_monday()) % 7;
+    from + Duration::days(if ahead == 0 { 7 } else { ahead.into() })
+}
+
+/// Ok(None) when there are no date words
+fn parse_date(tokens: &[&str], today: NaiveDate) -> Result<Option<NaiveDate>, ()> {
+    let date = match tokens {
+        [] => return Ok(None),
+        ["today"] => today,
+        ["tomorrow" | "tmrw" | "tmr"] => today + Duration::days(1),
+        ["next", "week"] => next_weekday(today, Weekday::Mon),
+        ["next", "month"] => today
+            .checked_add_months(Months::new(1))
+            .and_then(|d| d.with_day(1))
+            .ok_or(())?,
+        ["next", "year"] => NaiveDate::from_ymd_opt(today.year() + 1, 1, 1).ok_or(())?,
+        ["weekend"] | ["next", "weekend"] => next_weekday(today, Weekday::Sat),
+        [day] | ["next", day] if weekday(day).is_some() => {
+            next_weekday(today, weekday(day).ok_or(())?)
+        }
+        [a, b, year @ ..] if year.len() <= 1 => {
+            let (month, day) = match (month_number(a), month_number(b)) {
+                (Some(month), None) => (month, day_of_month(b).ok_or(())?),
+                (None, Some(month)) => (month, day_of_month(a).ok_or(())?),
+                _ => return Err(()),
+            };
+            match year.first() {
+                Some(year) => {
+                    let year = year.parse().map_err(|_| ())?;
+                    NaiveDate::from_ymd_opt(year, month, day).ok_or(())?
+                }
+                None => {
+                    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day).ok_or(())?;
+                    if this_year < today {
+                        NaiveDate::from_ymd_opt(today.year() + 1, month, day).ok_or(())?
+                    } else {
+                        this_year
+                    }
+                }
+            }
+        }
+        _ => return Err(()),
+    };
+    Ok(Some(date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 12 March 2025, 15:00
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 12)
+            .unwrap()
+            .and_hms_opt(15, 0, 0)
+            .unwrap()
+    }
+
+    fn at(input: &str) -> String {
+        parse_when(input, now())
+            .unwrap_or_else(|e| panic!("{}: {}", input, e))
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    }
+
+    #[test]
+    fn weekdays_and_relative_days() {
+        assert_eq!(at("next tuesday 9am"), "2025-03-18 09:00");
+        assert_eq!(at("Tuesday at 14:30"), "2025-03-18 14:30");
+        assert_eq!(at("wed"), "2025-03-19 09:00");
+        assert_eq!(at("tomorrow"), "2025-03-13 09:00");
+        assert_eq!(at("tomorrow 9 pm"), "2025-03-13 21:00");
+        assert_eq!(at("tonight"), "2025-03-12 20:00");
+        assert_eq!(at("next week"), "2025-03-17 09:00");
+        assert_eq!(at("next month"), "2025-04-01 09:00");
+        assert_eq!(at("this weekend"), "2025-03-15 09:00");
+    }
+
+    #[test]
+    fn offsets_and_clock_times() {
+        assert_eq!(at("in 2 hours"), "2025-03-12 17:00");
+        assert_eq!(at("in 90 minutes"), "2025-03-12 16:30");
+        assert_eq!(at("in 3 days at 5pm"), "2025-03-15 17:00");
+        assert_eq!(at("in a month"), "2025-04-12 15:00");
+        assert_eq!(at("5pm"), "2025-03-12 17:00");
+        // Already past today, so tomorrow
+        assert_eq!(at("noon"), "2025-03-13 12:00");
+        assert_eq!(at("at 8"), "2025-03-13 08:00");
+    }
+
+    #[test]
+    fn calendar_dates() {
+        assert_eq!(at("March 20"), "2025-03-20 09:00");
+        assert_eq!(at("1 march"), "2026-03-01 09:00");
+        assert_eq!(at("Jan 5th, 2027 8:15am"), "2027-01-05 08:15");
+        assert_eq!(at("2025-04-01"), "2025-04-01 09:00");
+        assert_eq!(at("2025-04-01 10:30"), "2025-04-01 10:30");
+    }
+
+    #[test]
+    fn rejects_nonsense() {
+        for input in ["", "someday", "feb 30", "in 3 fortnights", "13pm", "march"] {
+            assert!(parse_when(input, now()).is_err(), "{}", input);
+        }
+    }
+}
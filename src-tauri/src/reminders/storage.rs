@@ -0,0 +1,116 @@
+//! Reminder storage implementation
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::StorageError;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Storage for page reminders, one JSON file per reminder
+pub struct ReminderStorage {
+    reminders_dir: PathBuf,
+}
+
+impl ReminderStorage {
+    /// Create a new reminder storage
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        let reminders_dir = data_dir.join("reminders");
+        fs::create_dir_all(&reminders_dir)?;
+
+        Ok(Self { reminders_dir })
+    }
+
+    fn reminder_path(&self, id: Uuid) -> PathBuf {
+        self.reminders_dir.join(format!("{}.json", id))
+    }
+
+    /// Save a reminder
+    pub fn save_reminder(&self, reminder: &PageReminder) -> Result<()> {
+        let json = serde_json::to_string_pretty(reminder)?;
+        fs::write(self.reminder_path(reminder.id), json)?;
+        Ok(())
+    }
+
+    /// Get a reminder by ID
+    pub fn get_reminder(&self, id: Uuid) -> Result<PageReminder> {
+        let path = self.reminder_path(id);
+        if !path.exists() {
+            return Err(StorageError::PageNotFound(id));
+        }
+
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// List all reminders, soonest first
+    pub fn list_reminders(&self) -> Result<Vec<PageReminder>> {
+        let mut reminders = Vec::new();
+
+        for entry in fs::read_dir(&self.reminders_dir)? {
+            let path = entry?.path();
+            if path.extension().map(|e| e == "json").unwrap_or(false) {
+                if let Ok(content) = fs::read_to_string(&path) {
+                    if let Ok(reminder) = serde_json::from_str::<PageReminder>(&content) {
+                        reminders.push(reminder);
+                    }
+                }
+            }
+        }
+
+        reminders.sort_by_key(|r| r.remind_at);
+        Ok(reminders)
+    }
+
+    /// List reminders for a page
+    pub fn list_for_page(&self, page_id: Uuid) -> Result<Vec<PageReminder>> {
+        let reminders = self.list_reminders()?;
+        Ok(reminders
+            .into_iter()
+            .filter(|r| r.page_id == page_id)
+            .collect())
+    }
+
+    /// List reminders in `[start, end)`, fired or not
+    pub fn list_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<PageReminder>> {
+        let reminders = self.list_reminders()?;
+        Ok(reminders
+            .into_iter()
+            .filter(|r| r.remind_at >= start && r.remind_at < end)
+            .collect())
+    }
+
+    /// List unfired reminders due by `until`
+    pub fn list_due(&self, until: DateTime<Utc>) -> Result<Vec<PageReminder>> {
+        let reminders = self.list_reminders()?;
+        Ok(reminders
+            .into_iter()
+            .filter(|r| r.is_pending() && r.remind_at <= until)
+            .collect())
+    }
+
+    /// Record that a reminder's notification was shown
+    pub fn mark_fired(&self, id: Uuid) -> Result<PageReminder> {
+        let mut reminder = self.get_reminder(id)?;
+        reminder.fired_at = Some(Utc::now());
+        self.save_reminder(&reminder)?;
+        Ok(reminder)
+    }
+
+    /// Delete a reminder
+    pub fn delete_reminder(&self, id: Uuid) -> Result<()> {
+        let path = self.reminder_path(id);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
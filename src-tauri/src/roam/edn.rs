@@ -0,0 +1,280 @@
+//! Minimal EDN reader for Logseq graph exports.
+//!
+//! Reads EDN into `serde_json::Value` so the EDN and JSON exports share one
+//! code path. Only what Logseq emits is supported: maps, vectors, lists,
+//! sets, strings, numbers, booleans, nil, keywords, symbols and tagged
+//! literals (`#uuid "…"`, `#inst "…"`). Keywords and symbols become strings
+//! without the leading colon (`:block/content` → `"block/content"`), tagged
+//! literals become their inner value, and lists/sets become arrays.
+
+use serde_json::{Map, Number, Value};
+
+#[derive(Debug, thiserror::Error)]
+#[error("EDN parse error at byte {pos}: {message}")]
+pub struct EdnError {
+    pub pos: usize,
+    pub message: String,
+}
+
+/// Parse a single EDN value (trailing whitespace/comments allowed)
+pub fn parse(input: &str) -> Result<Value, EdnError> {
+    let mut reader = Reader {
+        chars: input.char_indices().collect(),
+        idx: 0,
+    };
+    let value = reader.read_value()?;
+    reader.skip_ws();
+    if reader.idx < reader.chars.len() {
+        return Err(reader.error("unexpected trailing input"));
+    }
+    Ok(value)
+}
+
+struct Reader {
+    chars: Vec<(usize, char)>,
+    idx: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.idx).map(|(_, c)| *c)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.idx += 1;
+        c
+    }
+
+    fn error(&self, message: &str) -> EdnError {
+        EdnError {
+            pos: self
+                .chars
+                .get(self.idx)
+                .map(|(p, _)| *p)
+                .unwrap_or_else(|| self.chars.last().map(|(p, _)| p + 1).unwrap_or(0)),
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == ',' {
+                self.idx += 1;
+            } else if c == ';' {
+                while let Some(c) = self.next() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Value, EdnError> {
+        self.skip_ws();
+        match self.peek() {
+            None => Err(self.error("unexpected end of input")),
+            Some('{') => {
+                self.idx += 1;
+                self.read_map()
+            }
+            Some('[') => {
+                self.idx += 1;
+                self.read_seq(']')
+            }
+            Some('(') => {
+                self.idx += 1;
+                self.read_seq(')')
+            }
+            Some('"') => {
+                self.idx += 1;
+                self.read_string().map(Value::String)
+            }
+            Some('#') => {
+                self.idx += 1;
+                match self.peek() {
+                    Some('{') => {
+                        self.idx += 1;
+                        self.read_seq('}')
+                    }
+                    Some('_') => {
+                        // Discard the next form
+                        self.idx += 1;
+                        self.read_value()?;
+                        self.read_value()
+                    }
+                    _ => {
+                        // Tagged literal: drop the tag, keep the value
+                        self.read_token();
+                        self.read_value()
+                    }
+                }
+            }
+            Some('\\') => {
+                self.idx += 1;
+                let token = self.read_token();
+                let c = match token.as_str() {
+                    "newline" => "\n".to_string(),
+                    "space" => " ".to_string(),
+                    "tab" => "\t".to_string(),
+                    "return" => "\r".to_string(),
+                    other => other.to_string(),
+                };
+                Ok(Value::String(c))
+            }
+            Some('}' | ']' | ')') => Err(self.error("unbalanced delimiter")),
+            Some(':') => {
+                self.idx += 1;
+                Ok(Value::String(self.read_token()))
+            }
+            Some(_) => {
+                let token = self.read_token();
+                Ok(atom(&token))
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> String {
+        let mut token = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, ',' | '{' | '}' | '[' | ']' | '(' | ')' | '"' | ';')
+            {
+                break;
+            }
+            token.push(c);
+            self.idx += 1;
+        }
+        token
+    }
+
+    fn read_string(&mut self) -> Result<String, EdnError> {
+        let mut out = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(out),
+                Some('\\') => match self.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('u') => {
+                        let hex: String = (0..4).filter_map(|_| self.next()).collect();
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("invalid \\u escape"))?;
+                        out.push(c);
+                    }
+                    Some(c) => out.push(c),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn read_seq(&mut self, close: char) -> Result<Value, EdnError> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => return Err(self.error("unterminated collection")),
+                Some(c) if c == close => {
+                    self.idx += 1;
+                    return Ok(Value::Array(items));
+                }
+                Some(_) => items.push(self.read_value()?),
+            }
+        }
+    }
+
+    fn read_map(&mut self) -> Result<Value, EdnError> {
+        let mut map = Map::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => return Err(self.error("unterminated map")),
+                Some('}') => {
+                    self.idx += 1;
+                    return Ok(Value::Object(map));
+                }
+                Some(_) => {
+                    let key = match self.read_value()? {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    self.skip_ws();
+                    if self.peek() == Some('}') {
+                        return Err(self.error("map has an odd number of forms"));
+                    }
+                    let value = self.read_value()?;
+                    map.insert(key, value);
+                }
+            }
+        }
+    }
+}
+
+/// Interpret a bare token: nil/booleans/numbers, otherwise a symbol
+fn atom(token: &str) -> Value {
+    match token {
+        "nil" => return Value::Null,
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+    let numeric = token.trim_end_matches(['N', 'M']);
+    if let Ok(i) = numeric.parse::<i64>() {
+        return Value::Number(i.into());
+    }
+    if let Some(n) = numeric.parse::<f64>().ok().and_then(Number::from_f64) {
+        return Value::Number(n);
+    }
+    Value::String(token.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_logseq_export_shape() {
+        let edn = r#"
+            ;; exported graph
+            {:version 1,
+             :blocks
+             ({:block/id #uuid "6543a1b2-0000-4000-8000-000000000001",
+               :block/page-name "contents",
+               :block/properties {:tags #{"a" "b"}},
+               :block/format :markdown,
+               :block/children
+               [{:block/id #uuid "6543a1b2-0000-4000-8000-000000000002",
+                 :block/content "Hello \"world\"\nline two",
+                 :block/collapsed? false,
+                 :block/children []}]})}
+        "#;
+        let value = parse(edn).unwrap();
+        assert_eq!(value["version"], 1);
+        let page = &value["blocks"][0];
+        assert_eq!(page["block/page-name"], "contents");
+        assert_eq!(page["block/format"], "markdown");
+        assert_eq!(
+            page["block/properties"]["tags"].as_array().unwrap().len(),
+            2
+        );
+        let child = &page["block/children"][0];
+        assert_eq!(child["block/id"], "6543a1b2-0000-4000-8000-000000000002");
+        assert_eq!(child["block/content"], "Hello \"world\"\nline two");
+        assert_eq!(child["block/collapsed?"], false);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse("{:a 1").is_err());
+        assert!(parse("[1 2]]").is_err());
+        assert!(parse("{:a}").is_err());
+    }
+}
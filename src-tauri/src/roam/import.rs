@@ -0,0 +1,1433 @@
+//! Roam Research / Logseq import implementation
+//!
+//! Both tools store notes as outlines: pages made of nested bullet blocks,
+//! where any block can be referenced from elsewhere by its uid as
+//! `((uid))`. Supported sources:
+//!
+//! - Roam JSON export (`[{title, uid, children: [{string, uid, children}]}]`)
+//! - Logseq JSON / EDN export (`{version, blocks: [{page-name, children}]}`)
+//! - Logseq graph directory (`pages/*.md`, `journals/*.md`, `assets/`)
+//!
+//! Every source is normalized into [`OutlinePage`]s first. Each top-level
+//! block becomes one Nous block (a list block carrying its children as
+//! nested items), so references to top-level blocks become `<block-ref>`
+//! transclusions; references to deeper blocks, which Nous can't address,
+//! become the quoted text plus a link to the page that contains it. Daily
+//! pages become daily notes.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::storage::{EditorBlock, EditorData, Notebook, NotebookType, Page, StorageError};
+
+use super::edn;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Which export format a path contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutlineSource {
+    RoamJson,
+    LogseqJson,
+    LogseqEdn,
+    LogseqGraph,
+}
+
+/// Preview metadata for a Roam / Logseq import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoamImportPreview {
+    /// Detected export format
+    pub source: OutlineSource,
+    /// Number of pages that will be imported
+    pub page_count: usize,
+    /// Number of pages that will become daily notes
+    pub daily_note_count: usize,
+    /// Total number of blocks across all pages
+    pub block_count: usize,
+    /// Number of `((block ref))` / embed occurrences
+    pub block_ref_count: usize,
+    /// Number of asset files (Logseq graphs only)
+    pub asset_count: usize,
+    /// Sample pages for preview (first 10)
+    pub pages: Vec<RoamPagePreview>,
+    /// Suggested notebook name
+    pub suggested_name: String,
+    /// Warnings during preview
+    pub warnings: Vec<String>,
+}
+
+/// Preview info for a single page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoamPagePreview {
+    pub title: String,
+    pub block_count: usize,
+    /// YYYY-MM-DD when the page is a daily note
+    pub daily_note_date: Option<String>,
+}
+
+/// A page in the normalized outline model
+#[derive(Debug, Clone, Default)]
+struct OutlinePage {
+    title: String,
+    created: Option<DateTime<Utc>>,
+    updated: Option<DateTime<Utc>>,
+    daily_date: Option<NaiveDate>,
+    tags: Vec<String>,
+    blocks: Vec<OutlineBlock>,
+}
+
+/// A block in the normalized outline model
+#[derive(Debug, Clone, Default)]
+struct OutlineBlock {
+    uid: Option<String>,
+    text: String,
+    heading: Option<u8>,
+    /// Logseq `key:: value` properties, keys lower-cased
+    properties: HashMap<String, String>,
+    children: Vec<OutlineBlock>,
+}
+
+impl OutlineBlock {
+    fn count(&self) -> usize {
+        1 + self.children.iter().map(OutlineBlock::count).sum::<usize>()
+    }
+}
+
+/// Everything read from an export before conversion
+struct ParsedExport {
+    source: OutlineSource,
+    pages: Vec<OutlinePage>,
+    /// (source file, file name) for Logseq graph assets
+    assets: Vec<(PathBuf, String)>,
+    warnings: Vec<String>,
+}
+
+// ===== Source detection and parsing =====
+
+fn invalid(message: impl Into<String>) -> StorageError {
+    StorageError::InvalidOperation(message.into())
+}
+
+fn parse_export(path: &Path) -> Result<ParsedExport> {
+    if path.is_dir() {
+        return parse_logseq_graph(path);
+    }
+
+    let content = fs::read_to_string(path)?;
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let (source, value) = match extension.as_str() {
+        "edn" => (
+            OutlineSource::LogseqEdn,
+            edn::parse(&content).map_err(|e| invalid(e.to_string()))?,
+        ),
+        "json" => {
+            let value: Value = serde_json::from_str(&content)?;
+            let source = if value.is_array() {
+                OutlineSource::RoamJson
+            } else if value.get("blocks").is_some() {
+                OutlineSource::LogseqJson
+            } else {
+                return Err(invalid("JSON file is not a Roam or Logseq export"));
+            };
+            (source, value)
+        }
+        other => return Err(StorageError::UnsupportedFileType(other.to_string())),
+    };
+
+    let pages = match source {
+        OutlineSource::RoamJson => parse_roam_pages(&value),
+        _ => parse_logseq_export(&value),
+    };
+
+    Ok(ParsedExport {
+        source,
+        pages,
+        assets: Vec::new(),
+        warnings: Vec::new(),
+    })
+}
+
+fn millis(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|v| v.as_i64())
+        .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+}
+
+fn parse_roam_pages(value: &Value) -> Vec<OutlinePage> {
+    fn block(v: &Value) -> OutlineBlock {
+        OutlineBlock {
+            uid: v.get("uid").and_then(|u| u.as_str()).map(str::to_string),
+            text: v
+                .get("string")
+                .and_then(|s| s.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            heading: v
+                .get("heading")
+                .and_then(|h| h.as_u64())
+                .map(|h| h.clamp(1, 3) as u8),
+            children: children(v),
+            ..Default::default()
+        }
+    }
+    fn children(v: &Value) -> Vec<OutlineBlock> {
+        v.get("children")
+            .and_then(|c| c.as_array())
+            .map(|c| c.iter().map(block).collect())
+            .unwrap_or_default()
+    }
+
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            let title = p.get("title")?.as_str()?.to_string();
+            let daily_date = p
+                .get("uid")
+                .and_then(|u| u.as_str())
+                .and_then(|u| NaiveDate::parse_from_str(u, "%m-%d-%Y").ok())
+                .or_else(|| parse_daily_title(&title));
+            Some(OutlinePage {
+                created: millis(p.get("create-time")),
+                updated: millis(p.get("edit-time")),
+                daily_date,
+                blocks: children(p),
+                title,
+                tags: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Look up a Logseq export field, which the EDN export namespaces
+/// (`block/content`) and the JSON export doesn't (`content`)
+fn field<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    value
+        .get(key)
+        .or_else(|| value.get(format!("block/{}", key).as_str()))
+}
+
+fn parse_logseq_export(value: &Value) -> Vec<OutlinePage> {
+    fn block(v: &Value) -> OutlineBlock {
+        let content = field(v, "content")
+            .and_then(|c| c.as_str())
+            .unwrap_or_default();
+        let mut parsed = logseq_block(content);
+        if parsed.uid.is_none() {
+            parsed.uid = field(v, "id")
+                .and_then(|id| id.as_str())
+                .map(str::to_string);
+        }
+        parsed.children = field(v, "children")
+            .and_then(|c| c.as_array())
+            .map(|c| c.iter().map(block).collect())
+            .unwrap_or_default();
+        parsed
+    }
+
+    field(value, "blocks")
+        .and_then(|b| b.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            let name = field(p, "page-name")?.as_str()?.to_string();
+            let properties = field(p, "properties");
+            let title = properties
+                .and_then(|props| props.get("title"))
+                .and_then(|t| t.as_str())
+                .map(str::to_string)
+                .unwrap_or(name);
+            let tags = properties
+                .and_then(|props| props.get("tags"))
+                .map(property_list)
+                .unwrap_or_default();
+            let blocks: Vec<OutlineBlock> = field(p, "children")
+                .and_then(|c| c.as_array())
+                .map(|c| c.iter().map(block).collect())
+                .unwrap_or_default();
+            let (page_props, rest) = take_page_properties(blocks);
+            Some(OutlinePage {
+                daily_date: parse_daily_title(&title),
+                title: page_props.get("title").cloned().unwrap_or(title),
+                tags: merge_tags(tags, &page_props),
+                blocks: rest,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// A property value that is either a list or a comma-separated string
+fn property_list(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect(),
+        Value::String(s) => split_property_list(s),
+        _ => Vec::new(),
+    }
+}
+
+fn split_property_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(|t| t.trim().trim_start_matches('#'))
+        .map(|t| {
+            t.trim_start_matches("[[")
+                .trim_end_matches("]]")
+                .to_string()
+        })
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn merge_tags(mut tags: Vec<String>, page_props: &HashMap<String, String>) -> Vec<String> {
+    for tag in page_props
+        .get("tags")
+        .map(|t| split_property_list(t))
+        .unwrap_or_default()
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Parse a `key:: value` property line
+fn property_line(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.trim().split_once(":: ").or_else(|| {
+        let trimmed = line.trim();
+        trimmed.strip_suffix("::").map(|k| (k, ""))
+    })?;
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+    {
+        return None;
+    }
+    Some((key.to_lowercase(), value.trim().to_string()))
+}
+
+/// Build a block from Logseq content: strips `key:: value` property lines
+/// (picking up `id::` as the block uid) and a leading markdown heading
+fn logseq_block(content: &str) -> OutlineBlock {
+    let mut block = OutlineBlock::default();
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence {
+            if let Some((key, value)) = property_line(line) {
+                match key.as_str() {
+                    "id" => block.uid = Some(value.clone()),
+                    "heading" => {
+                        if let Ok(level) = value.parse::<u8>() {
+                            block.heading = Some(level.clamp(1, 3));
+                        } else if value == "true" {
+                            block.heading = block.heading.or(Some(2));
+                        }
+                    }
+                    _ => {}
+                }
+                block.properties.insert(key, value);
+                continue;
+            }
+        }
+        lines.push(line);
+    }
+
+    let mut text = lines.join("\n").trim().to_string();
+    let hashes = text.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && text[hashes..].starts_with(' ') {
+        block.heading = Some((hashes as u8).min(3));
+        text = text[hashes..].trim_start().to_string();
+    }
+    block.text = text;
+    block
+}
+
+/// Logseq keeps page properties in a leading block made only of
+/// `key:: value` lines. Split them off.
+fn take_page_properties(
+    mut blocks: Vec<OutlineBlock>,
+) -> (HashMap<String, String>, Vec<OutlineBlock>) {
+    let is_property_block = blocks
+        .first()
+        .is_some_and(|b| b.text.is_empty() && b.children.is_empty() && !b.properties.is_empty());
+    if !is_property_block {
+        return (HashMap::new(), blocks);
+    }
+    let first = blocks.remove(0);
+    (first.properties, blocks)
+}
+
+fn parse_logseq_graph(dir: &Path) -> Result<ParsedExport> {
+    let pages_dir = dir.join("pages");
+    let journals_dir = dir.join("journals");
+    if !pages_dir.is_dir() && !journals_dir.is_dir() {
+        return Err(invalid(
+            "Directory is not a Logseq graph (no pages/ or journals/ folder)",
+        ));
+    }
+
+    let mut pages = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (folder, is_journal) in [(&pages_dir, false), (&journals_dir, true)] {
+        let Ok(entries) = fs::read_dir(folder) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            let extension = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if extension == "org" {
+                warnings.push(format!(
+                    "Skipped org-mode page {} (use the org-mode importer)",
+                    path.display()
+                ));
+                continue;
+            }
+            if extension != "md" || !path.is_file() {
+                continue;
+            }
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let content = match fs::read_to_string(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    warnings.push(format!("Could not read {}: {}", path.display(), e));
+                    continue;
+                }
+            };
+
+            let (page_props, blocks) = take_page_properties(parse_logseq_markdown(&content));
+            let file_title = logseq_title_from_file_name(&stem);
+            let daily_date = if is_journal {
+                NaiveDate::parse_from_str(&stem, "%Y_%m_%d")
+                    .ok()
+                    .or_else(|| parse_daily_title(&stem))
+            } else {
+                None
+            };
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from);
+
+            pages.push(OutlinePage {
+                title: page_props.get("title").cloned().unwrap_or(file_title),
+                created: None,
+                updated: modified,
+                daily_date,
+                tags: merge_tags(Vec::new(), &page_props),
+                blocks,
+            });
+        }
+    }
+
+    let mut assets = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir.join("assets")) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                assets.push((path, name));
+            }
+        }
+    }
+
+    Ok(ParsedExport {
+        source: OutlineSource::LogseqGraph,
+        pages,
+        assets,
+        warnings,
+    })
+}
+
+/// Logseq encodes namespace slashes in file names as `___` (or `%2F` in
+/// older graphs) and percent-escapes other unsafe characters
+fn logseq_title_from_file_name(stem: &str) -> String {
+    let title = stem.replace("___", "/");
+    urlencoding::decode(&title)
+        .map(|t| t.into_owned())
+        .unwrap_or(title)
+}
+
+/// Parse a Logseq markdown page into its block tree
+fn parse_logseq_markdown(content: &str) -> Vec<OutlineBlock> {
+    fn indent_of(line: &str) -> usize {
+        line.chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum()
+    }
+    fn attach(stack: &mut Vec<(usize, OutlineBlock)>, roots: &mut Vec<OutlineBlock>) {
+        if let Some((_, block)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(block),
+                None => roots.push(block),
+            }
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<(usize, OutlineBlock)> = Vec::new();
+    let mut preamble: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let is_bullet = !in_fence && (trimmed.starts_with("- ") || trimmed == "-");
+
+        if is_bullet {
+            let indent = indent_of(line);
+            while stack.last().is_some_and(|(i, _)| *i >= indent) {
+                attach(&mut stack, &mut roots);
+            }
+            let text = trimmed[1..].trim_start();
+            stack.push((
+                indent,
+                OutlineBlock {
+                    text: text.to_string(),
+                    ..Default::default()
+                },
+            ));
+            if text.matches("```").count() % 2 == 1 {
+                in_fence = true;
+            }
+            continue;
+        }
+
+        if trimmed.matches("```").count() % 2 == 1 {
+            in_fence = !in_fence;
+        }
+        match stack.last_mut() {
+            Some((_, block)) => {
+                block.text.push('\n');
+                block.text.push_str(trimmed);
+            }
+            None => preamble.push(line),
+        }
+    }
+    while !stack.is_empty() {
+        attach(&mut stack, &mut roots);
+    }
+
+    // Text before the first bullet (usually page properties) becomes a
+    // leading block of its own
+    let preamble = preamble.join("\n");
+    if !preamble.trim().is_empty() {
+        roots.insert(
+            0,
+            OutlineBlock {
+                text: preamble.trim().to_string(),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn finish(block: OutlineBlock) -> OutlineBlock {
+        let mut parsed = logseq_block(&block.text);
+        parsed.children = block.children.into_iter().map(finish).collect();
+        parsed
+    }
+    roots.into_iter().map(finish).collect()
+}
+
+/// Recognise daily-page titles: "January 15th, 2024", "Jan 15th, 2024",
+/// "2024-01-15", "2024_01_15"
+fn parse_daily_title(title: &str) -> Option<NaiveDate> {
+    let title = title.trim();
+    for format in ["%Y-%m-%d", "%Y_%m_%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(title, format) {
+            return Some(date);
+        }
+    }
+
+    let (month, rest) = title.split_once(' ')?;
+    let (day, year) = rest.split_once(", ")?;
+    let day: u32 = day
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+        .parse()
+        .ok()?;
+    let year: i32 = year.trim().parse().ok()?;
+    let month_prefix = month.get(..3)?.to_lowercase();
+    let month = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ]
+    .iter()
+    .position(|m| *m == month_prefix)? as u32
+        + 1;
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// A todo marker at the start of a block: Roam `{{[[TODO]]}}`, Logseq
+/// `TODO`/`LATER`/`NOW`/`DOING`/`DONE`. Returns (checked, remaining text).
+fn todo_marker(text: &str) -> Option<(bool, &str)> {
+    for (marker, checked) in [
+        ("{{[[TODO]]}}", false),
+        ("{{[[DONE]]}}", true),
+        ("{{TODO}}", false),
+        ("{{DONE}}", true),
+        ("TODO ", false),
+        ("LATER ", false),
+        ("NOW ", false),
+        ("DOING ", false),
+        ("WAITING ", false),
+        ("DONE ", true),
+        ("CANCELED ", true),
+        ("CANCELLED ", true),
+    ] {
+        if let Some(rest) = text.strip_prefix(marker) {
+            return Some((checked, rest.trim_start()));
+        }
+    }
+    None
+}
+
+// ===== Conversion to Nous blocks =====
+
+/// Where a `((uid))` reference points
+struct BlockTarget {
+    page_id: Uuid,
+    page_title: String,
+    /// Set for top-level blocks, which map to an addressable Nous block
+    block_id: Option<String>,
+    text: String,
+}
+
+/// Regexes compiled once per import
+struct Patterns {
+    code_span: Regex,
+    block_embed: Regex,
+    page_embed: Regex,
+    block_ref: Regex,
+    hash_bracket_tag: Regex,
+    page_link: Regex,
+    image: Regex,
+    link: Regex,
+    hash_tag: Regex,
+    bold: Regex,
+    italic_roam: Regex,
+    italic: Regex,
+    highlight: Regex,
+    strike: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        let re = |p: &str| Regex::new(p).expect("valid regex");
+        Self {
+            code_span: re(r"`([^`\n]+)`"),
+            block_embed: re(r"\{\{(?:\[\[embed\]\]:|embed:?)\s*\(\(([\w-]+)\)\)\s*\}\}"),
+            page_embed: re(r"\{\{(?:\[\[embed\]\]:|embed:?)\s*\[\[([^\]]+)\]\]\s*\}\}"),
+            block_ref: re(r"\(\(([\w-]{6,})\)\)"),
+            hash_bracket_tag: re(r"#\[\[([^\]]+)\]\]"),
+            page_link: re(r"\[\[([^\[\]]+)\]\]"),
+            image: re(r#"!\[([^\]]*)\]\(([^)\s]+)(?:\s+"[^"]*")?\)(?:\{[^}]*\})?"#),
+            link: re(r"\[([^\]]+)\]\(([^)\s]+)\)"),
+            hash_tag: re(r"(^|\s)#([\w/-]+)"),
+            bold: re(r"\*\*(.+?)\*\*"),
+            italic_roam: re(r"__(.+?)__"),
+            italic: re(r"\*([^*\s][^*]*?)\*"),
+            highlight: re(r"\^\^(.+?)\^\^"),
+            strike: re(r"~~(.+?)~~"),
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    html_escape::encode_text(text).into_owned()
+}
+
+fn escape_attr(text: &str) -> String {
+    html_escape::encode_double_quoted_attribute(text).into_owned()
+}
+
+/// Placeholder for already-rendered HTML so later inline passes (bold,
+/// italics, tags) can't reach into it
+fn stash(stashed: &mut Vec<String>, html: String) -> String {
+    stashed.push(html);
+    format!("\u{E000}{}\u{E001}", stashed.len() - 1)
+}
+
+fn unstash(text: &str, stashed: &[String]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('\u{E000}') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + '\u{E000}'.len_utf8()..];
+        let Some(end) = after.find('\u{E001}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        match after[..end]
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| stashed.get(i))
+        {
+            Some(html) => out.push_str(&unstash(html, stashed)),
+            None => out.push_str(&after[..end]),
+        }
+        rest = &after[end + '\u{E001}'.len_utf8()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+struct RenderContext {
+    notebook_id: Uuid,
+    /// lower-cased original title → (page id, final title)
+    pages_by_title: HashMap<String, (Uuid, String)>,
+    blocks_by_uid: HashMap<String, BlockTarget>,
+    /// asset file name → asset:// URL
+    asset_urls: HashMap<String, String>,
+    patterns: Patterns,
+}
+
+impl RenderContext {
+    fn wiki_link(&self, title: &str) -> String {
+        let (page_id, title) = match self.pages_by_title.get(&title.to_lowercase()) {
+            Some((id, final_title)) => (id.to_string(), final_title.as_str()),
+            None => (String::new(), title),
+        };
+        format!(
+            "<wiki-link data-page-title=\"{}\" data-page-id=\"{}\">{}</wiki-link>",
+            escape_attr(title),
+            page_id,
+            escape(title)
+        )
+    }
+
+    fn block_ref(&self, uid: &str) -> String {
+        let Some(target) = self.blocks_by_uid.get(uid) else {
+            return escape(&format!("(({}))", uid));
+        };
+        let text = plain_text(&target.text);
+        match &target.block_id {
+            Some(block_id) => format!(
+                "<block-ref data-block-id=\"{}\" data-page-id=\"{}\">{}</block-ref>",
+                escape_attr(block_id),
+                target.page_id,
+                escape(&text)
+            ),
+            None => format!(
+                "“{}” ({})",
+                escape(&text),
+                self.wiki_link(&target.page_title)
+            ),
+        }
+    }
+
+    fn resolve_url(&self, url: &str) -> String {
+        let name = url
+            .strip_prefix("../assets/")
+            .or_else(|| url.strip_prefix("assets/"));
+        name.and_then(|n| self.asset_urls.get(n))
+            .cloned()
+            .unwrap_or_else(|| url.to_string())
+    }
+
+    /// Convert Roam/Logseq inline markup to Nous inline HTML, collecting
+    /// `#tags` along the way
+    fn render_inline(&self, text: &str, tags: &mut Vec<String>) -> String {
+        let p = &self.patterns;
+        let mut stashed = Vec::new();
+        let mut add_tag = |tag: &str| {
+            let tag = tag.trim().to_string();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        };
+
+        let mut out = p
+            .code_span
+            .replace_all(text, |c: &Captures| {
+                stash(&mut stashed, format!("<code>{}</code>", escape(&c[1])))
+            })
+            .into_owned();
+        out = p
+            .block_embed
+            .replace_all(&out, |c: &Captures| {
+                stash(&mut stashed, self.block_ref(&c[1]))
+            })
+            .into_owned();
+        out = p
+            .page_embed
+            .replace_all(&out, |c: &Captures| {
+                stash(&mut stashed, self.wiki_link(&c[1]))
+            })
+            .into_owned();
+        out = p
+            .block_ref
+            .replace_all(&out, |c: &Captures| {
+                stash(&mut stashed, self.block_ref(&c[1]))
+            })
+            .into_owned();
+        out = p
+            .hash_bracket_tag
+            .replace_all(&out, |c: &Captures| {
+                add_tag(&c[1]);
+                stash(&mut stashed, escape(&format!("#{}", &c[1])))
+            })
+            .into_owned();
+        out = p
+            .page_link
+            .replace_all(&out, |c: &Captures| {
+                stash(&mut stashed, self.wiki_link(&c[1]))
+            })
+            .into_owned();
+        out = p
+            .image
+            .replace_all(&out, |c: &Captures| {
+                let label = if c[1].is_empty() { "image" } else { &c[1] };
+                stash(
+                    &mut stashed,
+                    format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_attr(&self.resolve_url(&c[2])),
+                        escape(label)
+                    ),
+                )
+            })
+            .into_owned();
+        out = p
+            .link
+            .replace_all(&out, |c: &Captures| {
+                stash(
+                    &mut stashed,
+                    format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_attr(&self.resolve_url(&c[2])),
+                        escape(&c[1])
+                    ),
+                )
+            })
+            .into_owned();
+        for c in p.hash_tag.captures_iter(&out) {
+            add_tag(&c[2]);
+        }
+
+        // Everything left is plain text: escape, then apply emphasis
+        let mut html = escape(&out);
+        html = p.bold.replace_all(&html, "<b>$1</b>").into_owned();
+        html = p.italic_roam.replace_all(&html, "<i>$1</i>").into_owned();
+        html = p.italic.replace_all(&html, "<i>$1</i>").into_owned();
+        html = p
+            .highlight
+            .replace_all(&html, "<mark>$1</mark>")
+            .into_owned();
+        html = p.strike.replace_all(&html, "<s>$1</s>").into_owned();
+        unstash(&html.replace('\n', "<br>"), &stashed)
+    }
+
+    fn list_item(&self, block: &OutlineBlock, tags: &mut Vec<String>) -> Value {
+        let text = match todo_marker(&block.text) {
+            Some((checked, rest)) => format!("{} {}", if checked { "☑" } else { "☐" }, rest),
+            None => block.text.clone(),
+        };
+        json!({
+            "content": self.render_inline(&text, tags),
+            "items": block
+                .children
+                .iter()
+                .map(|c| self.list_item(c, tags))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn checklist_item(&self, block: &OutlineBlock, tags: &mut Vec<String>) -> Value {
+        let (checked, text) = todo_marker(&block.text).unwrap_or((false, block.text.as_str()));
+        let mut item = json!({
+            "text": self.render_inline(text, tags),
+            "checked": checked,
+        });
+        if !block.children.is_empty() {
+            item["items"] = Value::Array(
+                block
+                    .children
+                    .iter()
+                    .map(|c| self.checklist_item(c, tags))
+                    .collect(),
+            );
+        }
+        item
+    }
+
+    /// Convert one top-level outline block (with its subtree) to Nous blocks.
+    /// The first emitted block takes `id`, so references resolve to it.
+    fn convert_block(
+        &self,
+        block: &OutlineBlock,
+        id: String,
+        tags: &mut Vec<String>,
+    ) -> Vec<EditorBlock> {
+        let text = block.text.trim();
+        let children_list = |tags: &mut Vec<String>| -> Option<EditorBlock> {
+            (!block.children.is_empty()).then(|| EditorBlock {
+                id: generate_block_id(),
+                block_type: "list".to_string(),
+                data: json!({
+                    "style": "unordered",
+                    "items": block
+                        .children
+                        .iter()
+                        .map(|c| self.list_item(c, tags))
+                        .collect::<Vec<_>>(),
+                }),
+            })
+        };
+
+        // Fenced code block
+        if let Some(body) = text.strip_prefix("```") {
+            let (language, code) = body.split_once('\n').unwrap_or(("", body));
+            let code = code.trim_end().trim_end_matches("```").trim_end();
+            let mut out = vec![EditorBlock {
+                id,
+                block_type: "code".to_string(),
+                data: json!({ "code": code, "language": language.trim() }),
+            }];
+            out.extend(children_list(tags));
+            return out;
+        }
+
+        // A block that is only an image
+        if let Some(c) = self.patterns.image.captures(text) {
+            if c.get(0).map(|m| m.as_str().len()) == Some(text.len()) {
+                let mut out = vec![EditorBlock {
+                    id,
+                    block_type: "image".to_string(),
+                    data: json!({
+                        "file": { "url": self.resolve_url(&c[2]) },
+                        "caption": &c[1],
+                        "withBorder": false,
+                        "withBackground": false,
+                        "stretched": false,
+                    }),
+                }];
+                out.extend(children_list(tags));
+                return out;
+            }
+        }
+
+        // A block that is only a page embed
+        if let Some(c) = self.patterns.page_embed.captures(text) {
+            if c.get(0).map(|m| m.as_str().len()) == Some(text.len()) {
+                let (page_id, title) = self
+                    .pages_by_title
+                    .get(&c[1].to_lowercase())
+                    .map(|(id, t)| (id.to_string(), t.clone()))
+                    .unwrap_or_else(|| (String::new(), c[1].to_string()));
+                let mut out = vec![EditorBlock {
+                    id,
+                    block_type: "embed".to_string(),
+                    data: json!({
+                        "embedType": "page",
+                        "pageTitle": title,
+                        "pageId": page_id,
+                        "url": "",
+                        "isCollapsed": false,
+                        "caption": "",
+                        "displayMode": "embed",
+                    }),
+                }];
+                out.extend(children_list(tags));
+                return out;
+            }
+        }
+
+        if let Some(level) = block.heading {
+            let mut out = vec![EditorBlock {
+                id,
+                block_type: "header".to_string(),
+                data: json!({
+                    "text": self.render_inline(text, tags),
+                    "level": level,
+                }),
+            }];
+            out.extend(children_list(tags));
+            return out;
+        }
+
+        if todo_marker(text).is_some() {
+            return vec![EditorBlock {
+                id,
+                block_type: "checklist".to_string(),
+                data: json!({ "items": [self.checklist_item(block, tags)] }),
+            }];
+        }
+
+        vec![EditorBlock {
+            id,
+            block_type: "list".to_string(),
+            data: json!({
+                "style": "unordered",
+                "items": [self.list_item(block, tags)],
+            }),
+        }]
+    }
+}
+
+/// Strip link/ref syntax for the display text of a block reference
+fn plain_text(text: &str) -> String {
+    let first_line = text.lines().next().unwrap_or_default();
+    let without_marker = todo_marker(first_line)
+        .map(|(_, rest)| rest)
+        .unwrap_or(first_line);
+    without_marker
+        .replace("[[", "")
+        .replace("]]", "")
+        .replace("**", "")
+        .replace("__", "")
+        .replace("^^", "")
+}
+
+/// Record `block` and its descendants as `((uid))` targets. Only top-level
+/// blocks get a `block_id`; deeper blocks live inside a list item.
+fn index_block(
+    block: &OutlineBlock,
+    block_id: Option<&String>,
+    page_id: Uuid,
+    page_title: &str,
+    out: &mut HashMap<String, BlockTarget>,
+) {
+    if let Some(uid) = &block.uid {
+        out.insert(
+            uid.clone(),
+            BlockTarget {
+                page_id,
+                page_title: page_title.to_string(),
+                block_id: block_id.cloned(),
+                text: block.text.clone(),
+            },
+        );
+    }
+    for child in &block.children {
+        index_block(child, None, page_id, page_title, out);
+    }
+}
+
+/// Generate a unique block ID
+fn generate_block_id() -> String {
+    // Random, not clock-based — matches markdown/import.rs
+    Uuid::new_v4().simple().to_string()[..10].to_string()
+}
+
+/// Pages worth importing: Roam auto-creates an empty page for every day
+/// the app was opened, so empty daily pages are dropped
+fn importable_pages(pages: Vec<OutlinePage>) -> (Vec<OutlinePage>, usize) {
+    let before = pages.len();
+    let kept: Vec<OutlinePage> = pages
+        .into_iter()
+        .filter(|p| {
+            let empty = p
+                .blocks
+                .iter()
+                .all(|b| b.text.trim().is_empty() && b.children.is_empty());
+            !(empty && p.daily_date.is_some())
+        })
+        .collect();
+    let skipped = before - kept.len();
+    (kept, skipped)
+}
+
+fn count_refs(block: &OutlineBlock, re: &Regex) -> usize {
+    re.find_iter(&block.text).count()
+        + block
+            .children
+            .iter()
+            .map(|c| count_refs(c, re))
+            .sum::<usize>()
+}
+
+fn suggested_name(path: &Path, source: OutlineSource) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .filter(|s| !s.is_empty());
+    match source {
+        OutlineSource::RoamJson => stem.unwrap_or_else(|| "Roam Import".to_string()),
+        _ => stem.unwrap_or_else(|| "Logseq Import".to_string()),
+    }
+}
+
+// ===== Public API =====
+
+/// Preview a Roam / Logseq export without importing anything
+pub fn preview_roam_import(path: &Path) -> Result<RoamImportPreview> {
+    let parsed = parse_export(path)?;
+    let (pages, skipped) = importable_pages(parsed.pages);
+    let ref_re = Patterns::new().block_ref;
+
+    let mut warnings = parsed.warnings;
+    if skipped > 0 {
+        warnings.push(format!("{} empty daily pages will be skipped", skipped));
+    }
+
+    Ok(RoamImportPreview {
+        source: parsed.source,
+        page_count: pages.len(),
+        daily_note_count: pages.iter().filter(|p| p.daily_date.is_some()).count(),
+        block_count: pages
+            .iter()
+            .flat_map(|p| &p.blocks)
+            .map(OutlineBlock::count)
+            .sum(),
+        block_ref_count: pages
+            .iter()
+            .flat_map(|p| &p.blocks)
+            .map(|b| count_refs(b, &ref_re))
+            .sum(),
+        asset_count: parsed.assets.len(),
+        pages: pages
+            .iter()
+            .take(10)
+            .map(|p| RoamPagePreview {
+                title: p.title.clone(),
+                block_count: p.blocks.iter().map(OutlineBlock::count).sum(),
+                daily_note_date: p.daily_date.map(|d| d.format("%Y-%m-%d").to_string()),
+            })
+            .collect(),
+        suggested_name: suggested_name(path, parsed.source),
+        warnings,
+    })
+}
+
+/// Import a Roam / Logseq export as a new notebook
+pub fn import_roam_with_progress<F>(
+    path: &Path,
+    notebooks_dir: &Path,
+    notebook_name: Option<String>,
+    progress: F,
+) -> Result<(Notebook, Vec<Page>)>
+where
+    F: Fn(usize, usize, &str),
+{
+    progress(0, 100, "Reading export...");
+    let parsed = parse_export(path)?;
+    let source = parsed.source;
+    let (outline_pages, _) = importable_pages(parsed.pages);
+
+    let mut notebook = Notebook::new(
+        notebook_name.unwrap_or_else(|| suggested_name(path, source)),
+        NotebookType::Standard,
+    );
+    notebook.icon = Some(match source {
+        OutlineSource::RoamJson => "🧠".to_string(),
+        _ => "🪵".to_string(),
+    });
+    let notebook_id = notebook.id;
+
+    let notebook_dir = notebooks_dir.join(notebook_id.to_string());
+    let pages_dir = notebook_dir.join("pages");
+    let assets_dir = notebook_dir.join("assets");
+    fs::create_dir_all(&pages_dir)?;
+    fs::create_dir_all(&assets_dir)?;
+    crate::storage::atomic::write_str(
+        &notebook_dir.join("notebook.json"),
+        &serde_json::to_string_pretty(&notebook)?,
+    )?;
+
+    // Copy assets
+    let mut asset_urls = HashMap::new();
+    for (source_path, name) in &parsed.assets {
+        fs::copy(source_path, assets_dir.join(name))?;
+        asset_urls.insert(name.clone(), format!("asset://{}/{}", notebook_id, name));
+    }
+
+    // First pass: assign page ids/titles and top-level block ids so links
+    // and block references can be resolved in any order
+    let mut pages_by_title = HashMap::new();
+    let mut blocks_by_uid = HashMap::new();
+    let mut planned: Vec<(Uuid, String, Vec<String>)> = Vec::new();
+    let mut used_dates = HashSet::new();
+
+    for outline in &outline_pages {
+        let page_id = Uuid::new_v4();
+        let title = match outline.daily_date {
+            Some(date) => {
+                crate::commands::format_daily_note_title(&date.format("%Y-%m-%d").to_string())
+            }
+            None => outline.title.clone(),
+        };
+        pages_by_title.insert(outline.title.to_lowercase(), (page_id, title.clone()));
+
+        let top_ids: Vec<String> = outline.blocks.iter().map(|_| generate_block_id()).collect();
+        for (block, id) in outline.blocks.iter().zip(&top_ids) {
+            index_block(block, Some(id), page_id, &outline.title, &mut blocks_by_uid);
+        }
+        planned.push((page_id, title, top_ids));
+    }
+
+    let ctx = RenderContext {
+        notebook_id,
+        pages_by_title,
+        blocks_by_uid,
+        asset_urls,
+        patterns: Patterns::new(),
+    };
+
+    // Second pass: convert and write pages
+    let total = outline_pages.len();
+    let mut pages = Vec::with_capacity(total);
+    for (i, (outline, (page_id, title, top_ids))) in outline_pages.iter().zip(planned).enumerate() {
+        if i % 25 == 0 {
+            progress(i, total, &format!("Converting {}", title));
+        }
+
+        let mut tags = outline.tags.clone();
+        let blocks: Vec<EditorBlock> = outline
+            .blocks
+            .iter()
+            .zip(top_ids)
+            .filter(|(b, _)| !(b.text.trim().is_empty() && b.children.is_empty()))
+            .flat_map(|(b, id)| ctx.convert_block(b, id, &mut tags))
+            .collect();
+
+        let mut page = Page::new(ctx.notebook_id, title);
+        page.id = page_id;
+        page.tags = tags;
+        page.content = EditorData {
+            time: Some(Utc::now().timestamp_millis()),
+            version: Some("2.28.0".to_string()),
+            blocks,
+        };
+        if let Some(created) = outline.created {
+            page.created_at = created;
+        }
+        if let Some(updated) = outline.updated {
+            page.updated_at = updated;
+        }
+        // Two sources can map to the same day (e.g. a journal file plus a
+        // page titled with the date); only the first becomes the daily note
+        if let Some(date) = outline.daily_date {
+            let date = date.format("%Y-%m-%d").to_string();
+            if used_dates.insert(date.clone()) {
+                page.is_daily_note = true;
+                page.daily_note_date = Some(date);
+            }
+        }
+
+        let page_json = crate::storage::content_format::page_to_disk_json(&page)?;
+        crate::storage::atomic::write_str(
+            &pages_dir.join(format!("{}.json", page.id)),
+            &page_json,
+        )?;
+        pages.push(page);
+    }
+
+    progress(total, total, "Import complete");
+    Ok((notebook, pages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(pages: &[OutlinePage]) -> (RenderContext, Vec<Vec<String>>) {
+        let mut pages_by_title = HashMap::new();
+        let mut blocks_by_uid = HashMap::new();
+        let mut ids = Vec::new();
+        for page in pages {
+            let page_id = Uuid::new_v4();
+            pages_by_title.insert(page.title.to_lowercase(), (page_id, page.title.clone()));
+            let top: Vec<String> = page.blocks.iter().map(|_| generate_block_id()).collect();
+            for (block, id) in page.blocks.iter().zip(&top) {
+                index_block(block, Some(id), page_id, &page.title, &mut blocks_by_uid);
+            }
+            ids.push(top);
+        }
+        let ctx = RenderContext {
+            notebook_id: Uuid::new_v4(),
+            pages_by_title,
+            blocks_by_uid,
+            asset_urls: HashMap::new(),
+            patterns: Patterns::new(),
+        };
+        (ctx, ids)
+    }
+
+    #[test]
+    fn parses_roam_json_with_daily_pages() {
+        let json = serde_json::json!([
+            {
+                "title": "January 15th, 2024",
+                "uid": "01-15-2024",
+                "create-time": 1705312800000i64,
+                "children": [
+                    {"string": "Met with **Ana** about [[Project X]]", "uid": "abc123def",
+                     "children": [{"string": "follow up", "uid": "child0001"}]}
+                ]
+            },
+            {"title": "Project X", "children": [{"string": "Goals", "heading": 2}]}
+        ]);
+        let pages = parse_roam_pages(&json);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].daily_date, NaiveDate::from_ymd_opt(2024, 1, 15));
+        assert_eq!(
+            pages[0].blocks[0].children[0].uid.as_deref(),
+            Some("child0001")
+        );
+        assert_eq!(pages[1].blocks[0].heading, Some(2));
+        assert_eq!(
+            parse_daily_title("Mar 3rd, 2023"),
+            NaiveDate::from_ymd_opt(2023, 3, 3)
+        );
+        assert_eq!(parse_daily_title("Project X"), None);
+    }
+
+    #[test]
+    fn parses_logseq_markdown_outline() {
+        let md = "title:: My Page\ntags:: alpha, [[beta]]\n\n- TODO first\n  id:: 6543a1b2-0000-4000-8000-000000000001\n\t- child\n\t  continued\n\t\t- grandchild\n- ## Heading\n- ```rust\n  fn main() {}\n  - not a bullet\n  ```\n";
+        let (props, blocks) = take_page_properties(parse_logseq_markdown(md));
+        assert_eq!(props.get("title").map(String::as_str), Some("My Page"));
+        assert_eq!(
+            merge_tags(Vec::new(), &props),
+            vec!["alpha".to_string(), "beta".to_string()]
+        );
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            blocks[0].uid.as_deref(),
+            Some("6543a1b2-0000-4000-8000-000000000001")
+        );
+        assert_eq!(blocks[0].text, "TODO first");
+        assert_eq!(blocks[0].children[0].text, "child\ncontinued");
+        assert_eq!(blocks[0].children[0].children[0].text, "grandchild");
+        assert_eq!(blocks[1].heading, Some(2));
+        assert_eq!(blocks[1].text, "Heading");
+        assert!(blocks[2].text.contains("- not a bullet"));
+        assert!(blocks[2].children.is_empty());
+    }
+
+    #[test]
+    fn renders_links_refs_and_markup() {
+        let pages = vec![OutlinePage {
+            title: "Target".to_string(),
+            blocks: vec![OutlineBlock {
+                uid: Some("toplevel1".to_string()),
+                text: "Top **idea**".to_string(),
+                children: vec![OutlineBlock {
+                    uid: Some("nested001".to_string()),
+                    text: "Deep thought".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+        let (ctx, ids) = context(&pages);
+        let mut tags = Vec::new();
+        let html = ctx.render_inline(
+            "See ((toplevel1)) and ((nested001)) in [[Target]], **bold** ^^hl^^ `a*b*c` #todo #[[big idea]] <x>",
+            &mut tags,
+        );
+
+        assert!(html.contains(&format!("<block-ref data-block-id=\"{}\"", ids[0][0])));
+        assert!(html.contains(">Top idea</block-ref>"));
+        assert!(html.contains("“Deep thought” (<wiki-link data-page-title=\"Target\""));
+        assert!(html.contains("<b>bold</b>"));
+        assert!(html.contains("<mark>hl</mark>"));
+        assert!(
+            html.contains("<code>a*b*c</code>"),
+            "code spans are left alone: {}",
+            html
+        );
+        assert!(html.contains("&lt;x&gt;"));
+        assert_eq!(tags, vec!["big idea".to_string(), "todo".to_string()]);
+
+        let todo = OutlineBlock {
+            text: "{{[[DONE]]}} ship it".to_string(),
+            ..Default::default()
+        };
+        let blocks = ctx.convert_block(&todo, "b1".to_string(), &mut tags);
+        assert_eq!(blocks[0].block_type, "checklist");
+        assert_eq!(blocks[0].data["items"][0]["checked"], true);
+        assert_eq!(blocks[0].data["items"][0]["text"], "ship it");
+    }
+
+    #[test]
+    fn imports_logseq_graph_into_notebook() {
+        let root = std::env::temp_dir().join(format!("nous_logseq_{}", Uuid::new_v4()));
+        let graph = root.join("graph");
+        fs::create_dir_all(graph.join("pages")).unwrap();
+        fs::create_dir_all(graph.join("journals")).unwrap();
+        fs::create_dir_all(graph.join("assets")).unwrap();
+        fs::write(graph.join("assets").join("pic.png"), b"png").unwrap();
+        fs::write(
+            graph.join("pages").join("Books___Dune.md"),
+            "- A classic\n  id:: 6543a1b2-0000-4000-8000-00000000000a\n- ![cover](../assets/pic.png)\n",
+        )
+        .unwrap();
+        fs::write(
+            graph.join("journals").join("2024_02_01.md"),
+            "- Reread ((6543a1b2-0000-4000-8000-00000000000a)) from [[Books/Dune]]\n",
+        )
+        .unwrap();
+        fs::write(graph.join("journals").join("2024_02_02.md"), "-\n").unwrap();
+
+        let preview = preview_roam_import(&graph).unwrap();
+        assert_eq!(preview.source, OutlineSource::LogseqGraph);
+        assert_eq!(preview.page_count, 2);
+        assert_eq!(preview.daily_note_count, 1);
+        assert_eq!(preview.block_ref_count, 1);
+
+        let notebooks = root.join("notebooks");
+        let (notebook, pages) =
+            import_roam_with_progress(&graph, &notebooks, None, |_, _, _| {}).unwrap();
+        assert_eq!(notebook.name, "graph");
+
+        let dune = pages.iter().find(|p| p.title == "Books/Dune").unwrap();
+        let journal = pages.iter().find(|p| p.is_daily_note).unwrap();
+        assert_eq!(journal.daily_note_date.as_deref(), Some("2024-02-01"));
+        assert_eq!(journal.title, "February 1, 2024");
+
+        let image = &dune.content.blocks[1];
+        assert_eq!(image.block_type, "image");
+        assert_eq!(
+            image.data["file"]["url"],
+            format!("asset://{}/pic.png", notebook.id)
+        );
+        assert!(notebooks
+            .join(notebook.id.to_string())
+            .join("assets")
+            .join("pic.png")
+            .exists());
+
+        let item = journal.content.blocks[0].data["items"][0]["content"]
+            .as_str()
+            .unwrap();
+        assert!(item.contains(&format!(
+            "data-block-id=\"{}\" data-page-id=\"{}\"",
+            dune.content.blocks[0].id, dune.id
+        )));
+        assert!(item.contains(&format!("data-page-id=\"{}\"", dune.id)));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,11 @@
+//! Roam Research / Logseq import module
+//!
+//! Imports outliner exports into Nous notebooks:
+//! - Roam Research JSON exports
+//! - Logseq JSON and EDN exports
+//! - Logseq graph directories (`pages/`, `journals/`, `assets/`)
+
+mod edn;
+mod import;
+
+pub use import::*;
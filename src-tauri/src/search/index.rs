@@ -9,6 +9,8 @@ use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocumen
 use thiserror::Error;
 use uuid::Uuid;
 
+use super::transcripts::{TranscriptHit, TranscriptIndex};
+use crate::diagnostics::{self, Category};
 use crate::storage::{EditorBlock, Page, PageType};
 
 /// Convert PageType to string for indexing
@@ -24,6 +26,7 @@ fn page_type_to_str(page_type: &PageType) -> &'static str {
         PageType::Canvas => "canvas",
         PageType::Database => "database",
         PageType::Html => "html",
+        PageType::Sql => "sql",
     }
 }
 
@@ -69,6 +72,8 @@ pub struct SearchIndex {
     fields: SearchFields,
     #[allow(dead_code)]
     schema: Schema,
+    /// Segment-level transcript index, attached via `with_transcripts`.
+    transcripts: Option<TranscriptIndex>,
 }
 
 impl SearchIndex {
@@ -123,9 +128,48 @@ impl SearchIndex {
             writer,
             fields,
             schema,
+            transcripts: None,
         })
     }
 
+    /// Attach a transcript index at `transcript_path`. Once attached, every
+    /// page upsert/delete/rebuild also refreshes that page's timestamped
+    /// transcript segments, so `search_transcripts` never lags the page index.
+    pub fn with_transcripts(mut self, transcript_path: PathBuf) -> Result<Self> {
+        self.transcripts = Some(TranscriptIndex::new(transcript_path)?);
+        Ok(self)
+    }
+
+    /// Re-populate only the transcript index (e.g. the first time it is
+    /// attached to an already-populated page index).
+    pub fn rebuild_transcripts(&mut self, pages: &[Page]) -> Result<()> {
+        if let Some(transcripts) = self.transcripts.as_mut() {
+            transcripts.rebuild(pages)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the attached transcript index started out empty: it was new,
+    /// or was recreated for a schema change. The caller then fills it with
+    /// [`Self::rebuild_transcripts`].
+    pub fn transcripts_created(&self) -> bool {
+        self.transcripts.as_ref().is_some_and(TranscriptIndex::created)
+    }
+
+    /// Search video transcripts, returning the page plus the segment's
+    /// timestamp. Empty when no transcript index is attached.
+    pub fn search_transcripts(
+        &self,
+        query_str: &str,
+        limit: usize,
+        notebook_id: Option<Uuid>,
+    ) -> Result<Vec<TranscriptHit>> {
+        match &self.transcripts {
+            Some(t) => t.search(query_str, limit, notebook_id),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Open the index at `index_path`, reusing it only if its stored schema
     /// matches `schema`. If the schemas differ (a schema migration) — or no
     /// usable index exists — the directory is cleared and a fresh index is
@@ -134,7 +178,7 @@ impl SearchIndex {
     /// This is the root-cause fix for the Tantivy fastfield commit panic: it
     /// guarantees the writer's schema always matches the documents' field ids,
     /// so `fast_field_names[field_id]` can never go out of bounds.
-    fn open_or_recreate(index_path: &Path, schema: &Schema) -> Result<Index> {
+    pub(super) fn open_or_recreate(index_path: &Path, schema: &Schema) -> Result<Index> {
         if let Ok(existing) = Index::open_in_dir(index_path) {
             if Self::schema_matches(&existing.schema(), schema) {
                 return Ok(existing);
@@ -157,7 +201,7 @@ impl SearchIndex {
     /// (e.g. making `page_id` indexed so deletes work) also requires a rebuild,
     /// since the on-disk index was built without that capability. `FieldEntry`
     /// compares all of this via `PartialEq`.
-    fn schema_matches(existing: &Schema, desired: &Schema) -> bool {
+    pub(super) fn schema_matches(existing: &Schema, desired: &Schema) -> bool {
         let existing_fields: Vec<&FieldEntry> = existing.fields().map(|(_, e)| e).collect();
         let desired_fields: Vec<&FieldEntry> = desired.fields().map(|(_, e)| e).collect();
         existing_fields == desired_fields
@@ -232,6 +276,14 @@ impl SearchIndex {
                         text_parts.push(code.to_string());
                     }
                 }
+                "math" => {
+                    if let Some(latex) = block.data.get("latex").and_then(|v| v.as_str()) {
+                        let formula = crate::markdown::math::formula_text(latex);
+                        if !formula.is_empty() {
+                            text_parts.push(formula);
+                        }
+                    }
+                }
                 "quote" => {
                     if let Some(text) = block.data.get("text").and_then(|v| v.as_str()) {
                         let plain_text = Self::strip_html_tags(text);
@@ -376,8 +428,14 @@ impl SearchIndex {
         text_parts.join("\n")
     }
 
-    /// Index a page (for standard Editor.js pages)
+    /// Index a page (for standard Editor.js pages). Encrypted pages are
+    /// removed instead; an unlocked page is indexed through a decrypted copy
+    /// with `encryption` cleared.
     pub fn index_page(&mut self, page: &Page) -> Result<()> {
+        let _timer = diagnostics::time(Category::Search, "index_page");
+        if page.is_encrypted() {
+            return self.remove_page(page.id);
+        }
         // Upsert in a SINGLE commit: stage the delete of any existing document
         // for this page id, then the add, then commit once. (Deleting via a
         // separately-committed call and then committing the add doubled the
@@ -400,11 +458,16 @@ impl SearchIndex {
 
         self.writer.commit()?;
 
+        if let Some(transcripts) = self.transcripts.as_mut() {
+            transcripts.index_page(page)?;
+        }
+
         Ok(())
     }
 
     /// Index a page with file content (for Jupyter, Markdown, etc.)
     pub fn index_page_with_content(&mut self, page: &Page, file_content: &str) -> Result<()> {
+        let _timer = diagnostics::time(Category::Search, "index_page_with_content");
         // Upsert in a single commit (see `index_page`): stage the delete, then
         // the add, then commit once.
         let term = Term::from_field_text(self.fields.page_id, &page.id.to_string());
@@ -604,11 +667,15 @@ impl SearchIndex {
         let term = Term::from_field_text(self.fields.page_id, &page_id.to_string());
         self.writer.delete_term(term);
         self.writer.commit()?;
+        if let Some(transcripts) = self.transcripts.as_mut() {
+            transcripts.remove_page(page_id)?;
+        }
         Ok(())
     }
 
     /// Search pages with a query string
     pub fn search(&self, query_str: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let _timer = diagnostics::time(Category::Search, "search");
         if query_str.trim().is_empty() {
             return Ok(Vec::new());
         }
@@ -743,11 +810,12 @@ impl SearchIndex {
     /// manual rebuild. (The daemon's incremental workaround for the Tantivy bulk
     /// panic is tracked separately.)
     pub fn rebuild_index(&mut self, pages: &[Page]) -> Result<()> {
+        let _timer = diagnostics::time(Category::Search, "rebuild_index");
         // Stage the clear; do NOT commit yet — commit once, after re-indexing.
         self.writer.delete_all_documents()?;
 
         // Re-index all pages
-        for page in pages {
+        for page in pages.iter().filter(|p| !p.is_encrypted()) {
             let content = Self::extract_text_from_blocks(&page.content.blocks);
             let tags = page.tags.join(" ");
             let page_type_str = page_type_to_str(&page.page_type);
@@ -764,6 +832,10 @@ impl SearchIndex {
 
         self.writer.commit()?;
 
+        if let Some(transcripts) = self.transcripts.as_mut() {
+            transcripts.rebuild(pages)?;
+        }
+
         Ok(())
     }
 }
@@ -817,6 +889,7 @@ impl ReadOnlySearchIndex {
 
     /// Search pages with a query string
     pub fn search(&self, query_str: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let _timer = diagnostics::time(Category::Search, "search");
         if query_str.trim().is_empty() {
             return Ok(Vec::new());
         }
@@ -990,11 +1063,27 @@ mod tests {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
     }
 
+    #[test]
+    fn math_blocks_index_formula_text() {
+        let blocks = vec![EditorBlock {
+            id: "m1".to_string(),
+            block_type: "math".to_string(),
+            data: json!({ "latex": "\\sum_{i=1}^n \\mathrm{Var}(X_i)" }),
+        }];
+        assert_eq!(
+            SearchIndex::extract_text_from_blocks(&blocks),
+            "sum _ i=1 ^n Var (X_i)"
+        );
+    }
+
     /// The 2026-05-01 incident: an on-disk index built with an older (smaller)
     /// schema is reopened, then adding a document with a now-out-of-range field
     /// id panics in Tantivy's fastfield writer on commit. `open_or_recreate`
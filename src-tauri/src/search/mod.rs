@@ -2,7 +2,10 @@ mod backend;
 mod index;
 mod rag_backend;
 mod rag_config;
+mod ranking;
+mod suggestions;
 mod tantivy_backend;
+mod transcripts;
 
 pub use backend::{BackendError, BackendResult, PageRef, SearchBackend, SearchHit, SearchMode};
 pub use index::{ReadOnlySearchIndex, SearchIndex, SearchResult};
@@ -10,4 +13,11 @@ pub use rag_backend::RagBackend;
 pub use rag_config::{
     config_path, load_or_default, save, DaemonConfig, RagConfig, SearchSection,
 };
+pub use ranking::{
+    rank_hits, ranking_boost, RankedPage, RankingContext, RankingWeights, RANKING_SETTING,
+};
+pub use suggestions::{
+    suggest_queries, SearchSuggestion, SuggestionKind, SuggestionSources, TitleSource,
+};
 pub use tantivy_backend::TantivyBackend;
+pub use transcripts::{extract_transcript_segments, TranscriptHit, TranscriptIndex};
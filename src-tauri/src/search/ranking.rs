@@ -0,0 +1,236 @@
+//! Ranking boosts for page title matches
+//!
+//! A title match alone ranks a page the user opens every day level with one
+//! they have never seen. On top of the match score, pages get boosts for
+//! titles starting with the query, for being opened recently and often (from
+//! the library's view history), and for living in the notebook the user is
+//! in. The TUI finder adds them to its fzf-style score, `/api/search?fuzzy=1`
+//! to Tantivy's.
+//!
+//! The size of each boost is a library setting ([`RANKING_SETTING`]), so it
+//! follows the library across devices:
+//!
+//! ```json
+//! {"exact_prefix": 48, "recency": 40, "frequency": 32, "notebook": 24}
+//! ```
+//!
+//! Missing weights keep their defaults; 0 turns a boost off.
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::backend::SearchHit;
+use crate::history::HistoryStorage;
+use crate::library::{LibraryError, LibrarySettings};
+
+/// Library setting holding the [`RankingWeights`]
+pub const RANKING_SETTING: &str = "search.fuzzyRanking";
+
+/// Boost points worth 1.0 of a Tantivy score: a matched character in the
+/// finder's scoring
+const POINTS_PER_SCORE: f32 = 16.0;
+
+/// Points the ranking signals add on top of the match score. Each is the
+/// most a signal can add: a word of the query is worth about 16 points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RankingWeights {
+    /// The title starts with the query
+    pub exact_prefix: i64,
+    /// Opened just now; halves after a week
+    pub recency: i64,
+    /// Opened 50 times or more; fewer opens add less
+    pub frequency: i64,
+    /// In the notebook the user is in
+    pub notebook: i64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            exact_prefix: 48,
+            recency: 40,
+            frequency: 32,
+            notebook: 24,
+        }
+    }
+}
+
+impl RankingWeights {
+    /// The library's weights; an unreadable setting is logged and the
+    /// defaults used
+    pub fn load(library_path: &Path) -> Self {
+        let settings = LibrarySettings::load(library_path);
+        match settings.get(RANKING_SETTING) {
+            Some(value) => serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {} setting: {}", RANKING_SETTING, e);
+                Self::default()
+            }),
+            None => Self::default(),
+        }
+    }
+
+    pub fn save(&self, library_path: &Path) -> Result<(), LibraryError> {
+        let mut settings = LibrarySettings::load(library_path);
+        settings.set(RANKING_SETTING, serde_json::to_value(self)?);
+        settings.save()
+    }
+}
+
+/// Where the user is when searching
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RankingContext {
+    pub current_notebook: Option<Uuid>,
+    /// Defaults to the current time
+    pub now: Option<DateTime<Utc>>,
+}
+
+/// What the boosts look at for one page
+pub struct RankedPage<'a> {
+    pub notebook_id: Uuid,
+    pub title: &'a str,
+    /// From the view history; `None` if never opened
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub view_count: u32,
+}
+
+/// The points `page` gets besides its match score
+pub fn ranking_boost(
+    page: &RankedPage<'_>,
+    query: &str,
+    weights: &RankingWeights,
+    context: &RankingContext,
+) -> i64 {
+    let query = query.trim().to_lowercase();
+    let now = context.now.unwrap_or_else(Utc::now);
+    let mut boost = 0.0;
+    if !query.is_empty() && page.title.to_lowercase().starts_with(&query) {
+        boost += weights.exact_prefix as f64;
+    }
+    if let Some(viewed) = page.last_viewed_at {
+        let days = (now - viewed).num_minutes().max(0) as f64 / (24.0 * 60.0);
+        boost += weights.recency as f64 * 0.5f64.powf(days / 7.0);
+    }
+    if page.view_count > 0 {
+        let opens = (page.view_count as f64).min(50.0);
+        boost += weights.frequency as f64 * (1.0 + opens).ln() / 51f64.ln();
+    }
+    if context.current_notebook == Some(page.notebook_id) {
+        boost += weights.notebook as f64;
+    }
+    boost.round() as i64
+}
+
+/// Add the boosts to the scores of search hits and re-sort them, best
+/// first. Hits whose ids don't parse keep their score.
+pub fn rank_hits(
+    mut hits: Vec<SearchHit>,
+    query: &str,
+    weights: &RankingWeights,
+    history: Option<&HistoryStorage>,
+    context: &RankingContext,
+) -> Vec<SearchHit> {
+    for hit in &mut hits {
+        let (Ok(page_id), Ok(notebook_id)) = (
+            Uuid::parse_str(&hit.page_id),
+            Uuid::parse_str(&hit.notebook_id),
+        ) else {
+            continue;
+        };
+        let view = history.and_then(|h| h.get_view(page_id));
+        let page = RankedPage {
+            notebook_id,
+            title: &hit.title,
+            last_viewed_at: view.map(|v| v.last_viewed_at),
+            view_count: view.map_or(0, |v| v.view_count),
+        };
+        hit.score += ranking_boost(&page, query, weights, context) as f32 / POINTS_PER_SCORE;
+    }
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn hit(notebook_id: Uuid, title: &str) -> SearchHit {
+        SearchHit {
+            page_id: Uuid::new_v4().to_string(),
+            notebook_id: notebook_id.to_string(),
+            title: title.to_string(),
+            snippet: String::new(),
+            score: 1.0,
+            page_type: "standard".to_string(),
+        }
+    }
+
+    fn titles(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.title.as_str()).collect()
+    }
+
+    #[test]
+    fn weights_round_trip_through_library_settings() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(RankingWeights::load(dir.path()), RankingWeights::default());
+
+        let weights = RankingWeights {
+            recency: 0,
+            ..RankingWeights::default()
+        };
+        weights.save(dir.path()).unwrap();
+        assert_eq!(RankingWeights::load(dir.path()), weights);
+
+        // Missing weights keep their defaults, a typo is ignored as a whole
+        let mut settings = LibrarySettings::load(dir.path());
+        settings.set(RANKING_SETTING, json!({"notebook": 0}));
+        settings.save().unwrap();
+        let loaded = RankingWeights::load(dir.path());
+        assert_eq!(loaded.notebook, 0);
+        assert_eq!(loaded.recency, RankingWeights::default().recency);
+        settings.set(RANKING_SETTING, json!({"recncy": 0}));
+        settings.save().unwrap();
+        assert_eq!(RankingWeights::load(dir.path()), RankingWeights::default());
+    }
+
+    #[test]
+    fn ranks_hits_by_prefix_history_and_notebook() {
+        let dir = TempDir::new().unwrap();
+        let work = Uuid::new_v4();
+        let home = Uuid::new_v4();
+        let hits = vec![
+            hit(work, "Weekly planning"),
+            hit(home, "Planets"),
+            hit(work, "Plan"),
+        ];
+        let weights = RankingWeights::default();
+        let context = RankingContext::default();
+
+        // Tantivy's fuzzy matches all score the same; prefixes lead
+        let ranked = rank_hits(hits.clone(), "plan", &weights, None, &context);
+        assert_eq!(titles(&ranked)[2], "Weekly planning");
+        assert!(ranked[0].score > 1.0);
+
+        // Opened often and lately outweighs a prefix
+        let mut history = HistoryStorage::new(dir.path().to_path_buf()).unwrap();
+        let weekly = Uuid::parse_str(&hits[0].page_id).unwrap();
+        for _ in 0..40 {
+            history.record_view(work, weekly).unwrap();
+        }
+        let ranked = rank_hits(hits.clone(), "plan", &weights, Some(&history), &context);
+        assert_eq!(titles(&ranked)[0], "Weekly planning");
+
+        // Being in the notebook tips a tie
+        let context = RankingContext {
+            current_notebook: Some(home),
+            now: None,
+        };
+        let ranked = rank_hits(hits, "plan", &weights, None, &context);
+        assert_eq!(titles(&ranked)[0], "Planets");
+    }
+}
@@ -0,0 +1,276 @@
+//! Query suggestions for the search bar
+//!
+//! Completes what the user has typed so far from three sources: their past
+//! searches, page titles and tags. Matches at the start of a suggestion
+//! rank above matches at a later word, and within that past searches come
+//! first (most recent first), then titles (shortest first), then tags (most
+//! used first). The same text is only suggested once.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::history::SearchHistoryEntry;
+use crate::storage::{FileStorage, Notebook, StorageError};
+
+/// Where a suggestion came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SuggestionKind {
+    History,
+    Page,
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSuggestion {
+    pub text: String,
+    pub kind: SuggestionKind,
+    /// For page suggestions: the page to open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_id: Option<Uuid>,
+}
+
+/// A page title suggestions can complete to
+#[derive(Debug, Clone)]
+pub struct TitleSource {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+}
+
+/// Page titles and tags of a library, gathered once and reused while the
+/// user types
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionSources {
+    pub titles: Vec<TitleSource>,
+    /// Tags with their page counts
+    pub tags: Vec<(String, usize)>,
+}
+
+impl SuggestionSources {
+    /// Collect titles and tags from the notebooks `include` accepts.
+    /// Archived and deleted pages are left out.
+    pub fn collect(
+        storage: &FileStorage,
+        include: impl Fn(&Notebook) -> bool,
+    ) -> Result<Self, StorageError> {
+        let mut titles = Vec::new();
+        let mut tag_counts: HashMap<String, usize> = HashMap::new();
+        for notebook in storage.list_notebooks()? {
+            if notebook.archived || !include(&notebook) {
+                continue;
+            }
+            let pages = storage.list_page_summaries(notebook.id)?;
+            for page in pages {
+                if page.is_archived || page.deleted_at.is_some() {
+                    continue;
+                }
+                for tag in &page.tags {
+                    let tag = tag.trim().to_lowercase();
+                    if !tag.is_empty() {
+                        *tag_counts.entry(tag).or_insert(0) += 1;
+                    }
+                }
+                titles.push(TitleSource {
+                    notebook_id: notebook.id,
+                    page_id: page.id,
+                    title: page.title,
+                });
+            }
+        }
+
+        let mut tags: Vec<(String, usize)> = tag_counts.into_iter().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(Self { titles, tags })
+    }
+}
+
+/// Where `prefix` matches in `text`: 0 at the start, 1 at a later word,
+/// `None` if nowhere. Both are lowercase.
+fn match_rank(text: &str, prefix: &str) -> Option<u8> {
+    if text.starts_with(prefix) {
+        return Some(0);
+    }
+    let mut prev_alphanumeric = true;
+    for (i, c) in text.char_indices() {
+        if !prev_alphanumeric && c.is_alphanumeric() && text[i..].starts_with(prefix) {
+            return Some(1);
+        }
+        prev_alphanumeric = c.is_alphanumeric();
+    }
+    None
+}
+
+/// Suggestions completing `prefix`, best first. An empty prefix suggests
+/// recent searches only. `history` is expected most recent first.
+pub fn suggest_queries(
+    prefix: &str,
+    history: &[SearchHistoryEntry],
+    sources: &SuggestionSources,
+    limit: usize,
+) -> Vec<SearchSuggestion> {
+    let prefix = prefix.trim().to_lowercase();
+    let tag_prefix = prefix.trim_start_matches('#');
+
+    // (match rank, source order, position within source, suggestion)
+    let mut candidates: Vec<(u8, u8, usize, SearchSuggestion)> = Vec::new();
+    for (i, entry) in history.iter().enumerate() {
+        let query = entry.query.to_lowercase();
+        // Suggesting exactly what was typed completes nothing
+        if query == prefix {
+            continue;
+        }
+        if let Some(rank) = match_rank(&query, &prefix) {
+            candidates.push((
+                rank,
+                0,
+                i,
+                SearchSuggestion {
+                    text: entry.query.clone(),
+                    kind: SuggestionKind::History,
+                    notebook_id: None,
+                    page_id: None,
+                },
+            ));
+        }
+    }
+    if !prefix.is_empty() {
+        for source in &sources.titles {
+            if let Some(rank) = match_rank(&source.title.to_lowercase(), &prefix) {
+                candidates.push((
+                    rank,
+                    1,
+                    source.title.chars().count(),
+                    SearchSuggestion {
+                        text: source.title.clone(),
+                        kind: SuggestionKind::Page,
+                        notebook_id: Some(source.notebook_id),
+                        page_id: Some(source.page_id),
+                    },
+                ));
+            }
+        }
+        for (i, (tag, _)) in sources.tags.iter().enumerate() {
+            if tag_prefix.is_empty() || *tag == tag_prefix {
+                continue;
+            }
+            if let Some(rank) = match_rank(tag, tag_prefix) {
+                candidates.push((
+                    rank,
+                    2,
+                    i,
+                    SearchSuggestion {
+                        text: tag.clone(),
+                        kind: SuggestionKind::Tag,
+                        notebook_id: None,
+                        page_id: None,
+                    },
+                ));
+            }
+        }
+    }
+
+    // Stable, so pages with equally long titles keep their order
+    candidates.sort_by_key(|(rank, source, position, _)| (*rank, *source, *position));
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .map(|(_, _, _, suggestion)| suggestion)
+        .filter(|s| seen.insert(s.text.to_lowercase()))
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn history(queries: &[&str]) -> Vec<SearchHistoryEntry> {
+        queries
+            .iter()
+            .map(|q| SearchHistoryEntry {
+                query: q.to_string(),
+                last_searched_at: Utc::now(),
+                search_count: 1,
+            })
+            .collect()
+    }
+
+    fn sources(titles: &[&str], tags: &[&str]) -> SuggestionSources {
+        SuggestionSources {
+            titles: titles
+                .iter()
+                .map(|t| TitleSource {
+                    notebook_id: Uuid::new_v4(),
+                    page_id: Uuid::new_v4(),
+                    title: t.to_string(),
+                })
+                .collect(),
+            tags: tags.iter().map(|t| (t.to_string(), 1)).collect(),
+        }
+    }
+
+    fn texts(suggestions: &[SearchSuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn combines_sources_by_match_position() {
+        let history = history(&["project budget", "meeting"]);
+        let sources = sources(
+            &["Project plan", "Weekly project sync", "Proj"],
+            &["projects", "personal"],
+        );
+
+        let suggestions = suggest_queries("Proj", &history, &sources, 10);
+        assert_eq!(
+            texts(&suggestions),
+            vec![
+                "project budget",
+                "Proj",
+                "Project plan",
+                "projects",
+                "Weekly project sync"
+            ]
+        );
+        assert_eq!(suggestions[1].kind, SuggestionKind::Page);
+        assert!(suggestions[1].page_id.is_some());
+        assert_eq!(suggestions[3].kind, SuggestionKind::Tag);
+
+        // Tags can be asked for with a leading '#'
+        assert_eq!(
+            texts(&suggest_queries("#pers", &[], &sources, 10)),
+            vec!["personal"]
+        );
+    }
+
+    #[test]
+    fn empty_prefix_lists_recent_searches() {
+        let history = history(&["tokio", "serde"]);
+        let sources = sources(&["Tokio notes"], &["rust"]);
+        assert_eq!(
+            texts(&suggest_queries("", &history, &sources, 10)),
+            vec!["tokio", "serde"]
+        );
+        assert_eq!(
+            texts(&suggest_queries("  ", &history, &sources, 1)),
+            vec!["tokio"]
+        );
+    }
+
+    #[test]
+    fn dedupes_and_skips_exact_history() {
+        let history = history(&["tokio", "tokio runtime"]);
+        let sources = sources(&["Tokio runtime"], &[]);
+        assert_eq!(
+            texts(&suggest_queries("tokio", &history, &sources, 10)),
+            vec!["tokio runtime"]
+        );
+    }
+}
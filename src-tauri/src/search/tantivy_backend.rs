@@ -59,6 +59,9 @@ impl TantivyBackend {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
@@ -0,0 +1,403 @@
+//! Timestamped transcript search.
+//!
+//! Video blocks carry their faster-whisper transcription (segments with
+//! start/end seconds). The page-level index flattens that text into the
+//! page's content, which is enough to find the page but not the moment.
+//! This index stores one document per transcript segment so a hit can
+//! seek the player straight to `start`.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tantivy::collector::TopDocs;
+use tantivy::query::{BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, TermQuery};
+use tantivy::schema::{Field, IndexRecordOption, Schema, Value, STORED, STRING, TEXT};
+use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+use uuid::Uuid;
+
+use super::index::{Result, SearchIndex};
+use crate::storage::{EditorBlock, Page};
+
+/// One transcript segment lifted out of a video block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptSegmentDoc {
+    pub block_id: String,
+    /// Display name for the video (original filename, caption, or asset name)
+    pub video_name: String,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// A transcript search hit: the page holding the video plus the moment
+/// the matching segment starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptHit {
+    pub page_id: String,
+    pub notebook_id: String,
+    pub page_title: String,
+    pub block_id: String,
+    pub video_name: String,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    pub score: f32,
+}
+
+/// Collect every timestamped transcript segment from the page's video blocks.
+/// Blocks without a structured `transcription.segments` array (untranscribed
+/// videos, legacy string transcripts) contribute nothing.
+pub fn extract_transcript_segments(page: &Page) -> Vec<TranscriptSegmentDoc> {
+    page.content
+        .blocks
+        .iter()
+        .filter(|b| b.block_type == "video")
+        .flat_map(segments_for_block)
+        .collect()
+}
+
+fn segments_for_block(block: &EditorBlock) -> Vec<TranscriptSegmentDoc> {
+    let segments = match block
+        .data
+        .get("transcription")
+        .and_then(|t| t.get("segments"))
+        .and_then(|s| s.as_array())
+    {
+        Some(s) => s,
+        None => return Vec::new(),
+    };
+
+    let video_name = ["originalName", "caption", "filename"]
+        .iter()
+        .filter_map(|key| block.data.get(*key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .unwrap_or("Video")
+        .to_string();
+
+    segments
+        .iter()
+        .filter_map(|seg| {
+            let text = seg.get("text").and_then(|v| v.as_str())?.trim();
+            if text.is_empty() {
+                return None;
+            }
+            let start = seg.get("start").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let end = seg.get("end").and_then(|v| v.as_f64()).unwrap_or(start);
+            Some(TranscriptSegmentDoc {
+                block_id: block.id.clone(),
+                video_name: video_name.clone(),
+                start,
+                end,
+                text: text.to_string(),
+            })
+        })
+        .collect()
+}
+
+struct TranscriptFields {
+    page_id: Field,
+    notebook_id: Field,
+    page_title: Field,
+    block_id: Field,
+    video_name: Field,
+    start: Field,
+    end: Field,
+    text: Field,
+}
+
+/// Segment-level Tantivy index living next to the page index. Owned by
+/// [`SearchIndex`] so every page upsert/delete keeps it in step.
+pub struct TranscriptIndex {
+    index: Index,
+    reader: IndexReader,
+    writer: IndexWriter,
+    fields: TranscriptFields,
+    /// The index started out empty: it was new, or was recreated for a
+    /// schema change, and needs filling from the pages
+    created: bool,
+}
+
+impl TranscriptIndex {
+    pub fn new(index_path: PathBuf) -> Result<Self> {
+        std::fs::create_dir_all(&index_path)?;
+
+        let mut schema_builder = Schema::builder();
+        // page_id is STRING so `delete_term` can drop every segment of a page,
+        // notebook_id so a search can be limited to one notebook.
+        let page_id = schema_builder.add_text_field("page_id", STRING | STORED);
+        let notebook_id = schema_builder.add_text_field("notebook_id", STRING | STORED);
+        let page_title = schema_builder.add_text_field("page_title", STORED);
+        let block_id = schema_builder.add_text_field("block_id", STORED);
+        let video_name = schema_builder.add_text_field("video_name", TEXT | STORED);
+        let start = schema_builder.add_f64_field("start", STORED);
+        let end = schema_builder.add_f64_field("end", STORED);
+        let text = schema_builder.add_text_field("text", TEXT | STORED);
+        let schema = schema_builder.build();
+
+        let created = !Index::open_in_dir(&index_path)
+            .is_ok_and(|existing| SearchIndex::schema_matches(&existing.schema(), &schema));
+        let index = SearchIndex::open_or_recreate(&index_path, &schema)?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommitWithDelay)
+            .try_into()?;
+        // Transcripts are small; a modest writer heap is plenty.
+        let writer = index.writer(15_000_000)?;
+
+        Ok(Self {
+            index,
+            reader,
+            writer,
+            fields: TranscriptFields {
+                page_id,
+                notebook_id,
+                page_title,
+                block_id,
+                video_name,
+                start,
+                end,
+                text,
+            },
+            created,
+        })
+    }
+
+    /// Whether the index started out empty (see [`SearchIndex::transcripts_created`])
+    pub fn created(&self) -> bool {
+        self.created
+    }
+
+    /// Stage the page's segments (delete + re-add) without committing.
+    fn stage_page(&mut self, page: &Page) -> Result<()> {
+        let term = Term::from_field_text(self.fields.page_id, &page.id.to_string());
+        self.writer.delete_term(term);
+
+        for seg in extract_transcript_segments(page) {
+            self.writer.add_document(doc!(
+                self.fields.page_id => page.id.to_string(),
+                self.fields.notebook_id => page.notebook_id.to_string(),
+                self.fields.page_title => page.title.clone(),
+                self.fields.block_id => seg.block_id,
+                self.fields.video_name => seg.video_name,
+                self.fields.start => seg.start,
+                self.fields.end => seg.end,
+                self.fields.text => seg.text
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Upsert all transcript segments of a page in a single commit.
+    pub fn index_page(&mut self, page: &Page) -> Result<()> {
+        self.stage_page(page)?;
+        self.writer.commit()?;
+        Ok(())
+    }
+
+    pub fn remove_page(&mut self, page_id: Uuid) -> Result<()> {
+        let term = Term::from_field_text(self.fields.page_id, &page_id.to_string());
+        self.writer.delete_term(term);
+        self.writer.commit()?;
+        Ok(())
+    }
+
+    /// Clear and re-index every page in one commit (mirrors
+    /// `SearchIndex::rebuild_index`).
+    pub fn rebuild(&mut self, pages: &[Page]) -> Result<()> {
+        self.writer.delete_all_documents()?;
+        for page in pages {
+            self.stage_page(page)?;
+        }
+        self.writer.commit()?;
+        Ok(())
+    }
+
+    /// Search transcript text (and video names), within one notebook if
+    /// `notebook_id` is given.
+    pub fn search(
+        &self,
+        query_str: &str,
+        limit: usize,
+        notebook_id: Option<Uuid>,
+    ) -> Result<Vec<TranscriptHit>> {
+        if query_str.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let searcher = self.reader.searcher();
+        let query_parser =
+            QueryParser::for_index(&self.index, vec![self.fields.text, self.fields.video_name]);
+        let mut query = query_parser.parse_query(query_str)?;
+        if let Some(notebook_id) = notebook_id {
+            // Scored zero, so the filter leaves the ranking alone
+            let term = Term::from_field_text(self.fields.notebook_id, &notebook_id.to_string());
+            let in_notebook: Box<dyn Query> = Box::new(ConstScoreQuery::new(
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+                0.0,
+            ));
+            query = Box::new(BooleanQuery::new(vec![
+                (Occur::Must, query),
+                (Occur::Must, in_notebook),
+            ]));
+        }
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+
+        let mut hits = Vec::new();
+        for (score, doc_address) in top_docs {
+            let doc: TantivyDocument = searcher.doc(doc_address)?;
+            let text_of = |field: Field| {
+                doc.get_first(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            let num_of =
+                |field: Field| doc.get_first(field).and_then(|v| v.as_f64()).unwrap_or(0.0);
+
+            hits.push(TranscriptHit {
+                page_id: text_of(self.fields.page_id),
+                notebook_id: text_of(self.fields.notebook_id),
+                page_title: text_of(self.fields.page_title),
+                block_id: text_of(self.fields.block_id),
+                video_name: text_of(self.fields.video_name),
+                start: num_of(self.fields.start),
+                end: num_of(self.fields.end),
+                text: text_of(self.fields.text),
+                score,
+            });
+        }
+
+        Ok(hits)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn reload(&self) {
+        self.reader.reload().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EditorData;
+    use serde_json::json;
+
+    fn video_page(notebook_id: Uuid) -> Page {
+        let mut page = Page::new(notebook_id, "Lecture 3".to_string());
+        page.content = EditorData {
+            time: None,
+            version: None,
+            blocks: vec![
+                EditorBlock {
+                    id: "p1".to_string(),
+                    block_type: "paragraph".to_string(),
+                    data: json!({ "text": "notes" }),
+                },
+                EditorBlock {
+                    id: "v1".to_string(),
+                    block_type: "video".to_string(),
+                    data: json!({
+                        "filename": "123-abc.mp4",
+                        "originalName": "thermo.mp4",
+                        "transcription": {
+                            "segments": [
+                                { "id": 0, "start": 0.0, "end": 4.5, "text": " Welcome back." },
+                                { "id": 1, "start": 4.5, "end": 9.0, "text": " Entropy always increases." },
+                                { "id": 2, "start": 9.0, "end": 9.5, "text": "  " }
+                            ]
+                        }
+                    }),
+                },
+            ],
+        };
+        page
+    }
+
+    #[test]
+    fn extracts_segments_from_video_blocks_only() {
+        let segs = extract_transcript_segments(&video_page(Uuid::new_v4()));
+        assert_eq!(segs.len(), 2, "blank segments are skipped");
+        assert_eq!(segs[1].block_id, "v1");
+        assert_eq!(segs[1].video_name, "thermo.mp4");
+        assert_eq!(segs[1].start, 4.5);
+        assert_eq!(segs[1].text, "Entropy always increases.");
+    }
+
+    #[test]
+    fn search_returns_timestamped_hits_and_upserts() {
+        let dir = std::env::temp_dir().join(format!("transcript_idx_test_{}", Uuid::new_v4()));
+        let nb = Uuid::new_v4();
+        let mut page = video_page(nb);
+        let mut idx = TranscriptIndex::new(dir.clone()).unwrap();
+
+        idx.index_page(&page).unwrap();
+        idx.index_page(&page).unwrap();
+        idx.reload();
+
+        let hits = idx.search("entropy", 10, None).unwrap();
+        assert_eq!(hits.len(), 1, "re-indexing must not duplicate segments");
+        assert_eq!(hits[0].page_id, page.id.to_string());
+        assert_eq!(hits[0].start, 4.5);
+        assert!(idx
+            .search("entropy", 10, Some(Uuid::new_v4()))
+            .unwrap()
+            .is_empty());
+
+        page.content.blocks.truncate(1);
+        idx.index_page(&page).unwrap();
+        idx.reload();
+        assert!(idx.search("entropy", 10, None).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn notebook_filter_is_part_of_the_query() {
+        let dir = std::env::temp_dir().join(format!("transcript_idx_test_{}", Uuid::new_v4()));
+        let mut idx = TranscriptIndex::new(dir.clone()).unwrap();
+        assert!(idx.created());
+
+        // Enough better-ranked hits elsewhere to crowd out a post-filtered
+        // notebook
+        let other = Uuid::new_v4();
+        for _ in 0..8 {
+            idx.index_page(&video_page(other)).unwrap();
+        }
+        let nb = Uuid::new_v4();
+        let mut page = video_page(nb);
+        page.content.blocks[1].data["transcription"]["segments"][1]["text"] =
+            json!("Entropy, in a long aside about steam engines and heat.");
+        idx.index_page(&page).unwrap();
+        idx.reload();
+
+        let hits = idx.search("entropy", 1, Some(nb)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].page_id, page.id.to_string());
+        assert_eq!(idx.search("entropy", 20, None).unwrap().len(), 9);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn recreates_an_index_with_the_old_schema() {
+        let dir = std::env::temp_dir().join(format!("transcript_idx_test_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        {
+            // notebook_id used to be stored only
+            let mut schema_builder = Schema::builder();
+            schema_builder.add_text_field("page_id", STRING | STORED);
+            schema_builder.add_text_field("notebook_id", STORED);
+            Index::create_in_dir(&dir, schema_builder.build()).unwrap();
+        }
+
+        let idx = TranscriptIndex::new(dir.clone()).unwrap();
+        assert!(idx.created(), "an outdated index is recreated");
+        drop(idx);
+        let idx = TranscriptIndex::new(dir.clone()).unwrap();
+        assert!(!idx.created(), "a current index is kept");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,127 @@
+//! Moving plaintext keys into the store
+//!
+//! Each old location is migrated where it's read, so there is no separate
+//! migration step to run: the daemon moves `[ai.providers.*] api_key` out
+//! of `daemon-config.toml` when it loads the file, the sync manager moves
+//! `.credentials/` files when it first needs them, and the frontend hands
+//! over its localStorage keys through `import_plaintext_secrets` on
+//! startup. A locked store leaves everything where it was.
+
+use crate::ai_config::AiSection;
+
+use super::{ai_key_id, validate_id, SecretResult, SecretStore};
+
+/// Store each non-empty `(id, value)` pair that isn't stored yet. Returns
+/// the ids now held by the store, so the caller can drop its own copies.
+pub fn import_plaintext(
+    store: &SecretStore,
+    keys: impl IntoIterator<Item = (String, String)>,
+) -> SecretResult<Vec<String>> {
+    let mut imported = Vec::new();
+    for (id, value) in keys {
+        if value.is_empty() {
+            continue;
+        }
+        validate_id(&id)?;
+        // A value already in the store was set later than the copy
+        // being migrated
+        if !store.contains(&id) {
+            store.set(&id, &value)?;
+        }
+        imported.push(id);
+    }
+    Ok(imported)
+}
+
+/// Move the keys in `section` into the store and point each provider at
+/// its secret. The keys stay filled in `section` for use; write
+/// [`AiSection::for_disk`] to persist it. Returns whether anything moved.
+pub fn store_ai_keys(store: &SecretStore, section: &mut AiSection) -> SecretResult<bool> {
+    let mut moved = false;
+    for (name, provider) in section.providers.iter_mut() {
+        let Some(key) = provider.api_key.as_deref().filter(|k| !k.is_empty()) else {
+            continue;
+        };
+        let id = ai_key_id(name);
+        if store.get(&id)?.as_deref() != Some(key) {
+            store.set(&id, key)?;
+            moved = true;
+        }
+        if provider.api_key_secret.as_deref() != Some(id.as_str()) {
+            provider.api_key_secret = Some(id);
+            moved = true;
+        }
+    }
+    Ok(moved)
+}
+
+/// Fill the keys of providers that point at a secret
+pub fn load_ai_keys(store: &SecretStore, section: &mut AiSection) {
+    for provider in section.providers.values_mut() {
+        if let Some(id) = provider.api_key_secret.clone() {
+            store.fill_api_key(&mut provider.api_key, &id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_config::AiProviderConfig;
+    use tempfile::TempDir;
+
+    fn store(dir: &TempDir) -> SecretStore {
+        let store = SecretStore::load(dir.path());
+        store.use_passphrase("passphrase").unwrap();
+        store
+    }
+
+    #[test]
+    fn imports_without_overwriting() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        store.set("ai.openai", "newer").unwrap();
+
+        let imported = import_plaintext(
+            &store,
+            vec![
+                ("ai.openai".to_string(), "older".to_string()),
+                ("search.tavily".to_string(), "tvly".to_string()),
+                ("tts.openai".to_string(), String::new()),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(imported, vec!["ai.openai", "search.tavily"]);
+        assert_eq!(store.get("ai.openai").unwrap().as_deref(), Some("newer"));
+        assert!(!store.contains("tts.openai"));
+    }
+
+    #[test]
+    fn ai_keys_move_out_of_daemon_config() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        let mut section = AiSection::default();
+        section.providers.insert(
+            "openai".to_string(),
+            AiProviderConfig {
+                api_key: Some("sk-daemon".to_string()),
+                ..Default::default()
+            },
+        );
+
+        assert!(store_ai_keys(&store, &mut section).unwrap());
+        assert!(!store_ai_keys(&store, &mut section).unwrap());
+
+        let on_disk = section.for_disk();
+        assert_eq!(on_disk.providers["openai"].api_key, None);
+        assert_eq!(
+            on_disk.providers["openai"].api_key_secret.as_deref(),
+            Some("ai.openai")
+        );
+
+        let mut reloaded = on_disk;
+        load_ai_keys(&store, &mut reloaded);
+        assert_eq!(reloaded, section);
+    }
+}
@@ -0,0 +1,127 @@
+//! Encrypted store for provider API keys and service passwords
+//!
+//! AI provider keys, the Tavily search key, TTS keys and sync passwords
+//! used to live in plaintext: in the frontend's localStorage, in
+//! `daemon-config.toml` and in `.credentials/` files. They now live in
+//! `{data_dir}/secrets.json`, each value sealed with ChaCha20-Poly1305
+//! under a master key that is either kept in the OS keyring or derived
+//! from a passphrase the user chooses. Everything else refers to a key by
+//! its id (`ai.openai`, `search.tavily`, `sync.notebook.<uuid>`, ...) and
+//! asks the store for the value when it makes the call.
+//!
+//! Plaintext keys found in the old places are moved in as they're read;
+//! see [`migrate`].
+
+pub mod migrate;
+pub mod store;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::encryption::EncryptionError;
+
+pub use store::{SecretInfo, SecretProtection, SecretStore, SecretsStatus};
+
+#[derive(Error, Debug)]
+pub enum SecretError {
+    #[error("Secrets are locked. Unlock them with your passphrase first.")]
+    Locked,
+
+    #[error("No secret stored for {0}")]
+    NotFound(String),
+
+    #[error("Invalid secret id: {0}")]
+    InvalidId(String),
+
+    #[error("Invalid passphrase")]
+    InvalidPassphrase,
+
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type SecretResult<T> = Result<T, SecretError>;
+
+/// Namespaces a secret id may start with
+const NAMESPACES: &[&str] = &["ai", "search", "tts", "sync"];
+
+/// Id of an AI provider's API key ("openai", "anthropic", ...)
+pub fn ai_key_id(provider: &str) -> String {
+    format!("ai.{}", provider)
+}
+
+/// Id of a TTS provider's API key
+pub fn tts_key_id(provider: &str) -> String {
+    format!("tts.{}", provider)
+}
+
+/// Id of the Tavily web search key
+pub const TAVILY_KEY_ID: &str = "search.tavily";
+
+/// Id of a notebook's WebDAV credentials
+pub fn notebook_sync_key_id(notebook_id: Uuid) -> String {
+    format!("sync.notebook.{}", notebook_id)
+}
+
+/// Id of a library's WebDAV credentials
+pub fn library_sync_key_id(library_id: Uuid) -> String {
+    format!("sync.library.{}", library_id)
+}
+
+/// Check that `id` is `<namespace>.<name>` with a known namespace and only
+/// lowercase letters, digits, `.`, `-` and `_`
+pub fn validate_id(id: &str) -> SecretResult<()> {
+    let valid_chars = id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-' | '_'));
+    let valid_namespace = id
+        .split_once('.')
+        .is_some_and(|(ns, name)| NAMESPACES.contains(&ns) && !name.is_empty());
+    if valid_chars && valid_namespace {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidId(id.to_string()))
+    }
+}
+
+/// The store for `data_dir`, opened on first use and shared by everything
+/// in the process (the app state, the sync manager, the daemon)
+pub fn shared(data_dir: &Path) -> Arc<SecretStore> {
+    static STORES: OnceLock<Mutex<HashMap<PathBuf, Arc<SecretStore>>>> = OnceLock::new();
+    let mut stores = STORES.get_or_init(Default::default).lock().unwrap();
+    Arc::clone(
+        stores
+            .entry(data_dir.to_path_buf())
+            .or_insert_with(|| Arc::new(SecretStore::open(data_dir))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_ids() {
+        assert!(validate_id("ai.openai").is_ok());
+        assert!(validate_id(TAVILY_KEY_ID).is_ok());
+        assert!(validate_id(&notebook_sync_key_id(Uuid::new_v4())).is_ok());
+
+        assert!(validate_id("openai").is_err());
+        assert!(validate_id("ai.").is_err());
+        assert!(validate_id("other.key").is_err());
+        assert!(validate_id("ai.Open AI").is_err());
+    }
+}
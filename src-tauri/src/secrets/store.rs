@@ -0,0 +1,464 @@
+//! The `secrets.json` file and its master key
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::encryption::{
+    self, create_verification_hash, decrypt_from_container, derive_key, encrypt_to_container,
+    generate_salt, verify_password, Argon2Params, EncryptedContainer, EncryptionError,
+    EncryptionKey,
+};
+use crate::python_bridge::AIConfig;
+use crate::storage::atomic;
+
+use super::{ai_key_id, validate_id, SecretError, SecretResult};
+
+const FILE_NAME: &str = "secrets.json";
+const FORMAT_VERSION: u32 = 1;
+
+/// Keys shorter than this don't get a hint, so it can't give most of them
+/// away
+const HINT_MIN_LENGTH: usize = 12;
+
+/// Where the master key comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretProtection {
+    /// Random key kept in the OS keyring; unlocked automatically
+    Keyring,
+    /// Derived from a passphrase, which has to be entered once per session
+    Passphrase,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretInfo {
+    pub id: String,
+    /// Last characters of the value ("…a1b2"), empty for short values
+    pub hint: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsStatus {
+    pub protection: SecretProtection,
+    pub unlocked: bool,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretsFile {
+    version: u32,
+    /// Keyring account the master key is stored under
+    store_id: Uuid,
+    protection: SecretProtection,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verification_hash: Option<String>,
+    #[serde(default)]
+    entries: BTreeMap<String, StoredSecret>,
+}
+
+impl Default for SecretsFile {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            store_id: Uuid::new_v4(),
+            protection: SecretProtection::Keyring,
+            salt: None,
+            verification_hash: None,
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredSecret {
+    value: EncryptedContainer,
+    hint: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+struct Inner {
+    file: SecretsFile,
+    /// Master key, once unlocked
+    key: Option<EncryptionKey>,
+}
+
+pub struct SecretStore {
+    path: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+impl SecretStore {
+    /// Load the store in `data_dir` and, for keyring protection, unlock it.
+    /// A keyring that can't be reached leaves the store locked; callers
+    /// then keep using whatever plaintext source they had.
+    pub fn open(data_dir: &Path) -> Self {
+        let store = Self::load(data_dir);
+        if store.status().protection == SecretProtection::Keyring {
+            if let Err(e) = store.unlock_with_keyring() {
+                log::warn!("Secrets store locked, keyring unavailable: {}", e);
+            }
+        }
+        store
+    }
+
+    /// Load the store without unlocking it
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(FILE_NAME);
+        let file = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                // Keep the unreadable file rather than overwriting it on the
+                // next save
+                log::error!("Unreadable {}: {}; starting a new store", path.display(), e);
+                let _ = std::fs::rename(&path, path.with_extension("json.unreadable"));
+                SecretsFile::default()
+            }),
+            Err(_) => SecretsFile::default(),
+        };
+        Self {
+            path,
+            inner: Mutex::new(Inner { file, key: None }),
+        }
+    }
+
+    pub fn status(&self) -> SecretsStatus {
+        let inner = self.inner.lock().unwrap();
+        SecretsStatus {
+            protection: inner.file.protection,
+            unlocked: inner.key.is_some(),
+            count: inner.file.entries.len(),
+        }
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.inner.lock().unwrap().key.is_some()
+    }
+
+    /// Unlock with the master key from the keyring, creating one for a new
+    /// store
+    fn unlock_with_keyring(&self) -> SecretResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let store_id = inner.file.store_id;
+        match encryption::keychain::load_key(store_id) {
+            Ok(key) => inner.key = Some(key),
+            Err(e) if !inner.file.entries.is_empty() => {
+                return Err(SecretError::Keyring(e.to_string()))
+            }
+            Err(_) => {
+                let key = random_key();
+                encryption::keychain::store_key(store_id, &key)
+                    .map_err(|e| SecretError::Keyring(e.to_string()))?;
+                inner.key = Some(key);
+                self.save(&inner.file)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Unlock a passphrase-protected store for this session
+    pub fn unlock(&self, passphrase: &str) -> SecretResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let (Some(salt), Some(hash)) = (&inner.file.salt, &inner.file.verification_hash) else {
+            return Err(SecretError::InvalidPassphrase);
+        };
+        let key = verify_password(passphrase, salt, hash).map_err(|e| match e {
+            EncryptionError::InvalidPassword => SecretError::InvalidPassphrase,
+            other => other.into(),
+        })?;
+        inner.key = Some(key);
+        Ok(())
+    }
+
+    /// Forget the master key of a passphrase-protected store
+    pub fn lock(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.file.protection == SecretProtection::Passphrase {
+            inner.key = None;
+        }
+    }
+
+    /// Protect the store with `passphrase` instead of its current key,
+    /// re-encrypting every secret
+    pub fn use_passphrase(&self, passphrase: &str) -> SecretResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let had_keyring_key =
+            inner.file.protection == SecretProtection::Keyring && inner.key.is_some();
+        let salt = generate_salt();
+        let key = derive_key(passphrase, &salt, &Argon2Params::default())?;
+        let hash = create_verification_hash(&key);
+        rekey(&mut inner, key)?;
+
+        inner.file.protection = SecretProtection::Passphrase;
+        inner.file.salt = Some(salt);
+        inner.file.verification_hash = Some(hash);
+        self.save(&inner.file)?;
+        if had_keyring_key {
+            encryption::keychain::delete_key(inner.file.store_id);
+        }
+        Ok(())
+    }
+
+    /// Keep the master key in the OS keyring instead of deriving it from a
+    /// passphrase, re-encrypting every secret
+    pub fn use_keyring(&self) -> SecretResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        let key = random_key();
+        encryption::keychain::store_key(inner.file.store_id, &key)
+            .map_err(|e| SecretError::Keyring(e.to_string()))?;
+        rekey(&mut inner, key)?;
+
+        inner.file.protection = SecretProtection::Keyring;
+        inner.file.salt = None;
+        inner.file.verification_hash = None;
+        self.save(&inner.file)
+    }
+
+    /// Store `value` under `id`, replacing any previous value
+    pub fn set(&self, id: &str, value: &str) -> SecretResult<SecretInfo> {
+        validate_id(id)?;
+        let mut inner = self.inner.lock().unwrap();
+        let key = inner.key.as_ref().ok_or(SecretError::Locked)?;
+        let sealed = encrypt_to_container(value.as_bytes(), key, "text/plain")?;
+
+        let now = Utc::now();
+        let created_at = inner
+            .file
+            .entries
+            .get(id)
+            .map_or(now, |existing| existing.created_at);
+        let stored = StoredSecret {
+            value: sealed,
+            hint: hint(value),
+            created_at,
+            updated_at: now,
+        };
+        let info = info(id, &stored);
+        inner.file.entries.insert(id.to_string(), stored);
+        self.save(&inner.file)?;
+        Ok(info)
+    }
+
+    /// Replace the value of an existing secret
+    pub fn rotate(&self, id: &str, value: &str) -> SecretResult<SecretInfo> {
+        if !self.contains(id) {
+            return Err(SecretError::NotFound(id.to_string()));
+        }
+        self.set(id, value)
+    }
+
+    /// The value stored under `id`, if any
+    pub fn get(&self, id: &str) -> SecretResult<Option<String>> {
+        let inner = self.inner.lock().unwrap();
+        let Some(stored) = inner.file.entries.get(id) else {
+            return Ok(None);
+        };
+        let key = inner.key.as_ref().ok_or(SecretError::Locked)?;
+        let bytes = decrypt_from_container(&stored.value, key)?;
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// Remove a secret. Returns whether it existed.
+    pub fn delete(&self, id: &str) -> SecretResult<bool> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.file.entries.remove(id).is_none() {
+            return Ok(false);
+        }
+        self.save(&inner.file)?;
+        Ok(true)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.inner.lock().unwrap().file.entries.contains_key(id)
+    }
+
+    pub fn list(&self) -> Vec<SecretInfo> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .file
+            .entries
+            .iter()
+            .map(|(id, stored)| info(id, stored))
+            .collect()
+    }
+
+    /// Fill an empty `api_key` from the secret `id`. Callers that were
+    /// handed a key keep it; a locked store leaves the key empty.
+    pub fn fill_api_key(&self, api_key: &mut Option<String>, id: &str) {
+        if api_key.as_deref().is_some_and(|k| !k.is_empty()) {
+            return;
+        }
+        match self.get(id) {
+            Ok(value) => *api_key = value,
+            Err(e) => log::warn!("Could not read secret {}: {}", id, e),
+        }
+    }
+
+    /// Fill the API key of an AI call from its provider's secret
+    pub fn fill_ai_config(&self, config: &mut AIConfig) {
+        let id = ai_key_id(&config.provider_type);
+        self.fill_api_key(&mut config.api_key, &id);
+    }
+
+    fn save(&self, file: &SecretsFile) -> SecretResult<()> {
+        let content = serde_json::to_string_pretty(file)?;
+        atomic::write_str(&self.path, &content)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600));
+        }
+        Ok(())
+    }
+}
+
+/// Re-encrypt every secret under `new_key`. An empty store can be rekeyed
+/// while locked.
+fn rekey(inner: &mut Inner, new_key: EncryptionKey) -> SecretResult<()> {
+    if !inner.file.entries.is_empty() {
+        let old_key = inner.key.as_ref().ok_or(SecretError::Locked)?;
+        for stored in inner.file.entries.values_mut() {
+            let plaintext = decrypt_from_container(&stored.value, old_key)?;
+            stored.value = encrypt_to_container(&plaintext, &new_key, "text/plain")?;
+        }
+    }
+    inner.key = Some(new_key);
+    Ok(())
+}
+
+fn random_key() -> EncryptionKey {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    EncryptionKey::new(bytes)
+}
+
+fn hint(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < HINT_MIN_LENGTH {
+        return String::new();
+    }
+    chars[chars.len() - 4..].iter().collect()
+}
+
+fn info(id: &str, stored: &StoredSecret) -> SecretInfo {
+    SecretInfo {
+        id: id.to_string(),
+        hint: stored.hint.clone(),
+        created_at: stored.created_at,
+        updated_at: stored.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A passphrase-protected store, so tests never touch the real keyring
+    fn store(dir: &TempDir) -> SecretStore {
+        let store = SecretStore::load(dir.path());
+        store.use_passphrase("correct horse").unwrap();
+        store
+    }
+
+    #[test]
+    fn values_are_encrypted_on_disk() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+
+        let info = store.set("ai.openai", "sk-test-0123456789abcd").unwrap();
+        assert_eq!(info.hint, "abcd");
+        assert_eq!(
+            store.get("ai.openai").unwrap().as_deref(),
+            Some("sk-test-0123456789abcd")
+        );
+
+        let on_disk = std::fs::read_to_string(dir.path().join(FILE_NAME)).unwrap();
+        assert!(!on_disk.contains("sk-test"));
+    }
+
+    #[test]
+    fn passphrase_store_reopens_locked() {
+        let dir = TempDir::new().unwrap();
+        store(&dir).set("search.tavily", "tvly-key").unwrap();
+
+        let reopened = SecretStore::load(dir.path());
+        assert_eq!(reopened.status().protection, SecretProtection::Passphrase);
+        assert!(matches!(
+            reopened.get("search.tavily"),
+            Err(SecretError::Locked)
+        ));
+        assert!(matches!(
+            reopened.unlock("wrong"),
+            Err(SecretError::InvalidPassphrase)
+        ));
+
+        reopened.unlock("correct horse").unwrap();
+        assert_eq!(
+            reopened.get("search.tavily").unwrap().as_deref(),
+            Some("tvly-key")
+        );
+    }
+
+    #[test]
+    fn rotate_and_delete() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+
+        assert!(matches!(
+            store.rotate("tts.openai", "new"),
+            Err(SecretError::NotFound(_))
+        ));
+        let created = store.set("tts.openai", "old").unwrap();
+        let rotated = store.rotate("tts.openai", "new").unwrap();
+        assert_eq!(rotated.created_at, created.created_at);
+        assert_eq!(store.get("tts.openai").unwrap().as_deref(), Some("new"));
+
+        assert!(store.delete("tts.openai").unwrap());
+        assert!(!store.delete("tts.openai").unwrap());
+        assert!(store.list().is_empty());
+    }
+
+    #[test]
+    fn changing_passphrase_keeps_values() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        store.set("ai.anthropic", "sk-ant").unwrap();
+
+        store.use_passphrase("new passphrase").unwrap();
+        let reopened = SecretStore::load(dir.path());
+        reopened.unlock("new passphrase").unwrap();
+        assert_eq!(
+            reopened.get("ai.anthropic").unwrap().as_deref(),
+            Some("sk-ant")
+        );
+    }
+
+    #[test]
+    fn fills_only_missing_keys() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+        store.set("ai.openai", "stored").unwrap();
+
+        let mut config = AIConfig::default();
+        store.fill_ai_config(&mut config);
+        assert_eq!(config.api_key.as_deref(), Some("stored"));
+
+        let mut given = Some("given".to_string());
+        store.fill_api_key(&mut given, "ai.openai");
+        assert_eq!(given.as_deref(), Some("given"));
+    }
+}
@@ -0,0 +1,387 @@
+//! Password-protected, single-file page shares.
+//!
+//! A shared page is one HTML file that can be emailed around. The page (its
+//! JSON, its rendered HTML with images inlined, and the raw asset files it
+//! references) is packed into a JSON bundle, encrypted with AES-256-GCM under
+//! a PBKDF2-SHA256 key, and embedded in a small unlock page. Both primitives
+//! are in WebCrypto, so the recipient can open the file in any browser
+//! without Nous; another Nous install can decrypt the same file and import
+//! the page with its assets.
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use uuid::Uuid;
+use zeroize::Zeroize;
+
+use crate::storage::Page;
+
+/// Current bundle/envelope format version.
+const FORMAT_VERSION: u32 = 1;
+
+/// PBKDF2 rounds for new shares. Stored in the envelope, so this can be
+/// raised later without breaking old files.
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+
+/// Minimum password length accepted when sealing a share.
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+const PAYLOAD_ELEMENT_ID: &str = "nous-shared-page";
+
+/// Everything needed to view or re-import a shared page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedPageBundle {
+    pub version: u32,
+    /// Self-contained themed render shown after unlocking in a browser.
+    pub html: String,
+    pub page: Page,
+    /// Asset files referenced by the page, keyed by their path relative to
+    /// the notebook's assets dir, base64-encoded.
+    #[serde(default)]
+    pub assets: BTreeMap<String, String>,
+    pub shared_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SharedPageBundle {
+    pub fn new(page: Page, html: String) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            html,
+            page,
+            assets: BTreeMap::new(),
+            shared_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// The encrypted envelope embedded in the unlock page.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedEnvelope {
+    v: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    iv: String,
+    ciphertext: String,
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+/// Encrypt `bundle` under `password` and wrap it in a standalone unlock page.
+pub fn seal_shared_page(bundle: &SharedPageBundle, password: &str) -> Result<String, String> {
+    seal_with_iterations(bundle, password, PBKDF2_ITERATIONS)
+}
+
+fn seal_with_iterations(
+    bundle: &SharedPageBundle,
+    password: &str,
+    iterations: u32,
+) -> Result<String, String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LEN
+        ));
+    }
+
+    let plaintext = serde_json::to_vec(bundle).map_err(|e| e.to_string())?;
+
+    let mut salt = [0u8; SALT_SIZE];
+    let mut nonce = [0u8; NONCE_SIZE];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut key = derive_key(password, &salt, iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string());
+    key.zeroize();
+    let ciphertext = cipher?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| "Encryption failed".to_string())?;
+
+    let envelope = EncryptedEnvelope {
+        v: FORMAT_VERSION,
+        kdf: "PBKDF2-SHA256".to_string(),
+        iterations,
+        salt: BASE64.encode(salt),
+        iv: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    let payload = serde_json::to_string(&envelope).map_err(|e| e.to_string())?;
+
+    Ok(UNLOCK_PAGE
+        .replace("{{ELEMENT_ID}}", PAYLOAD_ELEMENT_ID)
+        .replace("{{PAYLOAD}}", &payload))
+}
+
+/// Decrypt a file produced by [`seal_shared_page`].
+pub fn open_shared_page(file_html: &str, password: &str) -> Result<SharedPageBundle, String> {
+    let re = regex::Regex::new(&format!(
+        r#"(?s)<script type="application/json" id="{}">(.*?)</script>"#,
+        PAYLOAD_ELEMENT_ID
+    ))
+    .unwrap();
+    let payload = re
+        .captures(file_html)
+        .map(|c| c[1].trim().to_string())
+        .ok_or_else(|| "Not a Nous shared page".to_string())?;
+
+    let envelope: EncryptedEnvelope =
+        serde_json::from_str(&payload).map_err(|e| format!("Corrupt shared page: {}", e))?;
+    if envelope.v > FORMAT_VERSION {
+        return Err(format!(
+            "Shared page format v{} is newer than this version of Nous supports",
+            envelope.v
+        ));
+    }
+    if envelope.kdf != "PBKDF2-SHA256" {
+        return Err(format!("Unsupported key derivation: {}", envelope.kdf));
+    }
+
+    let decode = |s: &str| {
+        BASE64
+            .decode(s)
+            .map_err(|e| format!("Corrupt shared page: {}", e))
+    };
+    let salt = decode(&envelope.salt)?;
+    let nonce = decode(&envelope.iv)?;
+    let ciphertext = decode(&envelope.ciphertext)?;
+    if nonce.len() != NONCE_SIZE {
+        return Err("Corrupt shared page: bad nonce".to_string());
+    }
+
+    let mut key = derive_key(password, &salt, envelope.iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string());
+    key.zeroize();
+    let plaintext = cipher?
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Wrong password, or the file is damaged".to_string())?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Corrupt shared page: {}", e))
+}
+
+/// Path of a local asset relative to its notebook's assets dir, for any of
+/// the forms page content stores: `asset://{notebook-id}/{path}`, Tauri
+/// `convertFileSrc` URLs, raw absolute paths under `.../assets/`, and
+/// relative `assets/...` paths. External URLs return `None`.
+pub fn asset_relative_path(url: &str) -> Option<String> {
+    if let Some(rest) = url.strip_prefix("asset://") {
+        if let Some((host, path)) = rest.split_once('/') {
+            if Uuid::parse_str(host).is_ok() {
+                return sanitize_relative(path);
+            }
+        }
+    }
+
+    let fs_path = if let Some(rest) = url.strip_prefix("asset://localhost/") {
+        urlencoding::decode(rest).ok()?.into_owned()
+    } else if let Some(rest) = url
+        .strip_prefix("https://asset.localhost/")
+        .or_else(|| url.strip_prefix("http://asset.localhost/"))
+    {
+        format!("/{}", urlencoding::decode(rest).ok()?)
+    } else if let Some(rest) = url.strip_prefix("assets/") {
+        return sanitize_relative(rest);
+    } else if url.starts_with('/') {
+        url.to_string()
+    } else {
+        return None;
+    };
+
+    let pos = fs_path.rfind("/assets/")?;
+    sanitize_relative(&fs_path[pos + "/assets/".len()..])
+}
+
+/// Reject anything that could escape the assets dir on import. `:` is
+/// rejected too: on Windows `C:evil.png` is a drive prefix, and joining it
+/// replaces the assets dir.
+pub(crate) fn sanitize_relative(path: &str) -> Option<String> {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    if path.is_empty()
+        || path.contains(['\\', ':'])
+        || path.split('/').any(|seg| seg.is_empty() || seg == ".")
+        || !Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(path.to_string())
+}
+
+/// Visit every string in the page's block data, replacing it when `f`
+/// returns `Some`.
+pub fn rewrite_block_strings(page: &mut Page, f: &mut impl FnMut(&str) -> Option<String>) {
+    fn walk(value: &mut Value, f: &mut impl FnMut(&str) -> Option<String>) {
+        match value {
+            Value::String(s) => {
+                if let Some(new) = f(s) {
+                    *s = new;
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| walk(v, f)),
+            Value::Object(map) => map.values_mut().for_each(|v| walk(v, f)),
+            _ => {}
+        }
+    }
+    for block in &mut page.content.blocks {
+        walk(&mut block.data, f);
+    }
+}
+
+/// Relative paths of every local asset the page's blocks reference.
+pub fn referenced_assets(page: &Page) -> Vec<String> {
+    let mut page = page.clone();
+    let mut paths = Vec::new();
+    rewrite_block_strings(&mut page, &mut |s| {
+        if let Some(rel) = asset_relative_path(s) {
+            if !paths.contains(&rel) {
+                paths.push(rel);
+            }
+        }
+        None
+    });
+    paths
+}
+
+const UNLOCK_PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>Protected page</title>
+<style>
+body { font-family: system-ui, -apple-system, sans-serif; background: #f5f5f4; color: #1c1917; display: flex; align-items: center; justify-content: center; min-height: 100vh; margin: 0; }
+form { background: #fff; padding: 2rem; border-radius: 12px; box-shadow: 0 4px 24px rgba(0,0,0,0.08); width: min(90vw, 360px); }
+h1 { font-size: 1.1rem; margin: 0 0 0.5rem; }
+p { font-size: 0.9rem; color: #57534e; margin: 0 0 1rem; }
+input, button { width: 100%; box-sizing: border-box; padding: 0.6rem 0.75rem; font-size: 0.95rem; border-radius: 8px; }
+input { border: 1px solid #d6d3d1; margin-bottom: 0.75rem; }
+button { border: 0; background: #1c1917; color: #fff; cursor: pointer; }
+#error { color: #b91c1c; min-height: 1.2em; margin: 0.75rem 0 0; }
+</style>
+</head>
+<body>
+<form id="unlock">
+<h1>This page is password protected</h1>
+<p>Shared from Nous. Enter the password you were given to view it.</p>
+<input id="password" type="password" autocomplete="off" autofocus required>
+<button type="submit">Unlock</button>
+<p id="error"></p>
+</form>
+<script type="application/json" id="{{ELEMENT_ID}}">{{PAYLOAD}}</script>
+<script>
+(function () {
+  var payload = JSON.parse(document.getElementById("{{ELEMENT_ID}}").textContent);
+  function bytes(b64) {
+    var bin = atob(b64);
+    var out = new Uint8Array(bin.length);
+    for (var i = 0; i < bin.length; i++) out[i] = bin.charCodeAt(i);
+    return out;
+  }
+  document.getElementById("unlock").addEventListener("submit", async function (e) {
+    e.preventDefault();
+    var error = document.getElementById("error");
+    error.textContent = "";
+    try {
+      var password = new TextEncoder().encode(document.getElementById("password").value);
+      var material = await crypto.subtle.importKey("raw", password, "PBKDF2", false, ["deriveKey"]);
+      var key = await crypto.subtle.deriveKey(
+        { name: "PBKDF2", salt: bytes(payload.salt), iterations: payload.iterations, hash: "SHA-256" },
+        material,
+        { name: "AES-GCM", length: 256 },
+        false,
+        ["decrypt"]
+      );
+      var plain = await crypto.subtle.decrypt({ name: "AES-GCM", iv: bytes(payload.iv) }, key, bytes(payload.ciphertext));
+      var bundle = JSON.parse(new TextDecoder().decode(plain));
+      document.open();
+      document.write(bundle.html);
+      document.close();
+    } catch (err) {
+      error.textContent = "Wrong password, or the file is damaged.";
+    }
+  });
+})();
+</script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> SharedPageBundle {
+        let mut page = Page::new(Uuid::new_v4(), "Trip notes".to_string());
+        page.content.blocks.push(crate::storage::EditorBlock {
+            id: "img".to_string(),
+            block_type: "image".to_string(),
+            data: serde_json::json!({
+                "file": { "url": "asset://localhost/%2Fhome%2Fu%2Fnous%2Fnotebooks%2Fnb%2Fassets%2Fimages%2Fmap.png" },
+                "caption": "https://example.com/map.png"
+            }),
+        });
+        let mut bundle = SharedPageBundle::new(page, "<html><body>hi</body></html>".to_string());
+        bundle
+            .assets
+            .insert("images/map.png".to_string(), BASE64.encode(b"png"));
+        bundle
+    }
+
+    #[test]
+    fn seal_and_open_roundtrip() {
+        let sealed = seal_with_iterations(&bundle(), "correct horse", 1_000).unwrap();
+        assert!(sealed.contains(r#"id="nous-shared-page""#));
+        assert!(!sealed.contains("Trip notes"), "title must not leak");
+
+        let opened = open_shared_page(&sealed, "correct horse").unwrap();
+        assert_eq!(opened.page.title, "Trip notes");
+        assert_eq!(opened.assets.len(), 1);
+
+        let err = open_shared_page(&sealed, "wrong password").unwrap_err();
+        assert!(err.contains("Wrong password"));
+        assert!(seal_with_iterations(&bundle(), "short", 1_000).is_err());
+        assert!(open_shared_page("<html></html>", "correct horse").is_err());
+    }
+
+    #[test]
+    fn asset_paths_are_relative_and_safe() {
+        let nb = Uuid::new_v4();
+        assert_eq!(
+            asset_relative_path(&format!("asset://{}/images/a.png", nb)).as_deref(),
+            Some("images/a.png")
+        );
+        assert_eq!(
+            asset_relative_path("https://asset.localhost/C:/nous/notebooks/x/assets/v.mp4")
+                .as_deref(),
+            Some("v.mp4")
+        );
+        assert_eq!(
+            asset_relative_path("assets/a.png?t=1").as_deref(),
+            Some("a.png")
+        );
+        assert_eq!(asset_relative_path("https://example.com/a.png"), None);
+        assert_eq!(asset_relative_path("assets/../../etc/passwd"), None);
+        assert_eq!(asset_relative_path("assets/C:evil.png"), None);
+        assert_eq!(sanitize_relative("/etc/passwd"), None);
+        assert_eq!(sanitize_relative("images/C:/evil.png"), None);
+
+        assert_eq!(referenced_assets(&bundle().page), vec!["images/map.png"]);
+    }
+}
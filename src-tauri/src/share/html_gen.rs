@@ -1,7 +1,10 @@
 use std::fs;
 use std::path::PathBuf;
 
-use crate::publish::html::{blocks_have_animation, blocks_have_mermaid, render_page_html};
+use crate::charts::with_rendered_charts;
+use crate::publish::html::{
+    blocks_have_animation, blocks_have_math, blocks_have_mermaid, render_page_html,
+};
 use crate::publish::site::{build_lookup_maps, generate_site, PublishOptions};
 use crate::publish::themes::{get_theme, page_head_extra};
 use crate::storage::{FileStorage, Folder, Page};
@@ -21,13 +24,15 @@ pub fn render_share_html(
     let (page_slugs, block_texts) = build_lookup_maps(all_pages);
     let theme = get_theme(theme_name);
 
-    let content_html = render_page_html(page, &page_slugs, &block_texts);
+    let charted = with_rendered_charts(page, &storage.notebook_assets_dir(notebook_id));
+    let content_html = render_page_html(&charted, &page_slugs, &block_texts);
     let date = page.updated_at.format("%B %d, %Y").to_string();
 
     let head_extra = page_head_extra(
         theme.name,
         blocks_have_mermaid(&page.content.blocks),
         blocks_have_animation(&page.content.blocks),
+        blocks_have_math(&page.content.blocks),
     );
 
     let html = theme
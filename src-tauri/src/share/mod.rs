@@ -1,4 +1,5 @@
 pub mod credentials;
+pub mod encrypted;
 pub mod html_gen;
 pub mod publish;
 pub mod publish_token;
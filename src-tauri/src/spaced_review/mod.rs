@@ -0,0 +1,253 @@
+//! Spaced-writing review of old notes
+//!
+//! Each run of the "Spaced Writing Review" action takes the pages the review
+//! feed (`crate::history::review`) would bring back in every notebook with
+//! review turned on, asks the study tools for a few recall questions about
+//! each, and files them as flashcards or under a "Review" heading in today's
+//! daily note. A page that got questions isn't picked again for
+//! `REVIEW_INTERVAL_DAYS`, so the same note doesn't come back every morning.
+
+mod models;
+mod storage;
+
+pub use models::*;
+pub use storage::*;
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::history::ReviewItem;
+use crate::storage::EditorBlock;
+
+/// Days before a page can get new questions
+pub const REVIEW_INTERVAL_DAYS: i64 = 30;
+/// Heading of the daily note section questions are filed under
+pub const REVIEW_SECTION: &str = "Review";
+/// Tag on flashcards made from review questions
+pub const REVIEW_TAG: &str = "spaced-review";
+
+/// The feed items without questions in the last `REVIEW_INTERVAL_DAYS`,
+/// up to `limit`, keeping the feed's order
+pub fn due_pages(
+    feed: Vec<ReviewItem>,
+    reviewed: &HashMap<Uuid, DateTime<Utc>>,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<ReviewItem> {
+    feed.into_iter()
+        .filter(|item| {
+            reviewed
+                .get(&item.page_id)
+                .map_or(true, |at| (now - *at).num_days() >= REVIEW_INTERVAL_DAYS)
+        })
+        .take(limit)
+        .collect()
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn block(block_type: &str, data: serde_json::Value) -> EditorBlock {
+    EditorBlock {
+        id: Uuid::new_v4().to_string(),
+        block_type: block_type.to_string(),
+        data,
+    }
+}
+
+/// Blocks listing the questions: a link to each page, then its questions as
+/// a checklist to tick off once answered from memory
+pub fn review_blocks(pages: &[PageQuestions]) -> Vec<EditorBlock> {
+    let mut blocks = Vec::new();
+    for page in pages.iter().filter(|p| !p.questions.is_empty()) {
+        let title = html_escape(&page.title);
+        blocks.push(block(
+            "paragraph",
+            serde_json::json!({
+                "text": format!(
+                    "<wiki-link data-page-title=\"{}\" data-page-id=\"{}\">{}</wiki-link>",
+                    title, page.page_id, title
+                )
+            }),
+        ));
+        let items: Vec<serde_json::Value> = page
+            .questions
+            .iter()
+            .map(|(question, _)| {
+                serde_json::json!({
+                    "text": html_escape(question),
+                    "checked": false
+                })
+            })
+            .collect();
+        blocks.push(block("checklist", serde_json::json!({ "items": items })));
+    }
+    blocks
+}
+
+fn header_level(block: &EditorBlock) -> Option<u64> {
+    if block.block_type != "header" {
+        return None;
+    }
+    Some(block.data.get("level").and_then(|l| l.as_u64()).unwrap_or(2))
+}
+
+/// `blocks` with `review` added at the end of the "Review" section, which is
+/// started at the end of the page if there isn't one yet
+pub fn add_to_review_section(
+    mut blocks: Vec<EditorBlock>,
+    review: Vec<EditorBlock>,
+) -> Vec<EditorBlock> {
+    let section = blocks.iter().position(|b| {
+        b.block_type == "header"
+            && b.data
+                .get("text")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case(REVIEW_SECTION))
+    });
+    let Some(start) = section else {
+        blocks.push(block(
+            "header",
+            serde_json::json!({ "text": REVIEW_SECTION, "level": 2 }),
+        ));
+        blocks.extend(review);
+        return blocks;
+    };
+
+    let level = header_level(&blocks[start]).unwrap_or(2);
+    let end = blocks[start + 1..]
+        .iter()
+        .position(|b| header_level(b).is_some_and(|l| l <= level))
+        .map_or(blocks.len(), |i| start + 1 + i);
+    blocks.splice(end..end, review);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn item(title: &str) -> ReviewItem {
+        ReviewItem {
+            notebook_id: Uuid::nil(),
+            page_id: Uuid::new_v4(),
+            title: title.to_string(),
+            updated_at: Utc::now() - Duration::days(200),
+            last_viewed_at: None,
+            inbound_links: 0,
+            reason: String::new(),
+        }
+    }
+
+    fn text(block: &EditorBlock) -> &str {
+        block.data.get("text").and_then(|t| t.as_str()).unwrap_or("")
+    }
+
+    #[test]
+    fn recently_reviewed_pages_are_not_due() {
+        let now = Utc::now();
+        let feed = vec![item("a"), item("b"), item("c"), item("d")];
+        let mut reviewed = HashMap::new();
+        reviewed.insert(feed[0].page_id, now - Duration::days(3));
+        reviewed.insert(feed[1].page_id, now - Duration::days(REVIEW_INTERVAL_DAYS));
+
+        let titles: Vec<String> = due_pages(feed, &reviewed, now, 2)
+            .into_iter()
+            .map(|i| i.title)
+            .collect();
+        assert_eq!(titles, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn review_section_is_created_once() {
+        let questions = |title: &str| PageQuestions {
+            page_id: Uuid::nil(),
+            title: title.to_string(),
+            questions: vec![("Why <this>?".to_string(), "Because".to_string())],
+        };
+        let note = vec![
+            block("header", serde_json::json!({ "text": "Tasks", "level": 2 })),
+            block("paragraph", serde_json::json!({ "text": "Ship it" })),
+        ];
+
+        let note = add_to_review_section(note, review_blocks(&[questions("Old & wise")]));
+        assert_eq!(note.len(), 5);
+        assert_eq!(text(&note[2]), REVIEW_SECTION);
+        assert!(text(&note[3]).contains("data-page-title=\"Old &amp; wise\""));
+        assert_eq!(note[4].data["items"][0]["text"], "Why &lt;this&gt;?");
+
+        let mut note = note;
+        note.push(block("header", serde_json::json!({ "text": "Log", "level": 2 })));
+        let note = add_to_review_section(note, review_blocks(&[questions("Second")]));
+        let headers: Vec<&str> = note
+            .iter()
+            .filter(|b| b.block_type == "header")
+            .map(text)
+            .collect();
+        assert_eq!(headers, vec!["Tasks", REVIEW_SECTION, "Log"]);
+        assert!(text(&note[5]).contains("Second"));
+        assert_eq!(note[7].block_type, "header");
+    }
+
+    #[test]
+    fn pages_without_questions_are_left_out() {
+        let empty = PageQuestions {
+            page_id: Uuid::nil(),
+            title: "Empty".to_string(),
+            questions: Vec::new(),
+        };
+        assert!(review_blocks(&[empty]).is_empty());
+    }
+
+    #[test]
+    fn settings_are_clamped() {
+        let settings = NotebookReviewSettings {
+            enabled: true,
+            destination: ReviewDestination::Flashcards {
+                deck_name: "  ".to_string(),
+            },
+            questions_per_page: 40,
+            pages_per_run: 0,
+        }
+        .clamped();
+        assert_eq!(settings.questions_per_page, MAX_QUESTIONS);
+        assert_eq!(settings.pages_per_run, 1);
+        assert_eq!(
+            settings.destination,
+            ReviewDestination::Flashcards {
+                deck_name: DEFAULT_DECK_NAME.to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn storage_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let notebook_id = Uuid::new_v4();
+        let page_id = Uuid::new_v4();
+        {
+            let mut storage = SpacedReviewStorage::new(dir.path().to_path_buf()).unwrap();
+            assert!(!storage.settings(notebook_id).enabled);
+            storage
+                .set_settings(
+                    notebook_id,
+                    NotebookReviewSettings {
+                        enabled: true,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+            storage.mark_reviewed(&[page_id], Utc::now()).unwrap();
+        }
+        let storage = SpacedReviewStorage::new(dir.path().to_path_buf()).unwrap();
+        assert_eq!(storage.enabled_notebooks().len(), 1);
+        assert!(storage.reviewed().contains_key(&page_id));
+    }
+}
@@ -0,0 +1,97 @@
+//! Spaced-writing review data models
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Fewest and most questions asked about one page
+pub const MIN_QUESTIONS: u32 = 1;
+pub const MAX_QUESTIONS: u32 = 5;
+/// Most pages reviewed in one notebook per run
+pub const MAX_PAGES_PER_RUN: u32 = 10;
+/// Deck used when none is named
+pub const DEFAULT_DECK_NAME: &str = "Spaced Review";
+
+/// Where a notebook's review questions are filed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReviewDestination {
+    /// As flashcards in the notebook's deck of this name, created if missing
+    #[serde(rename_all = "camelCase")]
+    Flashcards { deck_name: String },
+    /// Under a "Review" heading in today's daily note
+    #[default]
+    DailyNote,
+}
+
+/// How a notebook takes part in spaced-writing review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookReviewSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub destination: ReviewDestination,
+    #[serde(default = "default_questions_per_page")]
+    pub questions_per_page: u32,
+    #[serde(default = "default_pages_per_run")]
+    pub pages_per_run: u32,
+}
+
+fn default_questions_per_page() -> u32 {
+    3
+}
+
+fn default_pages_per_run() -> u32 {
+    3
+}
+
+impl Default for NotebookReviewSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            destination: ReviewDestination::default(),
+            questions_per_page: default_questions_per_page(),
+            pages_per_run: default_pages_per_run(),
+        }
+    }
+}
+
+impl NotebookReviewSettings {
+    /// The settings with counts brought into range
+    pub fn clamped(mut self) -> Self {
+        self.questions_per_page = self.questions_per_page.clamp(MIN_QUESTIONS, MAX_QUESTIONS);
+        self.pages_per_run = self.pages_per_run.clamp(1, MAX_PAGES_PER_RUN);
+        if let ReviewDestination::Flashcards { deck_name } = &mut self.destination {
+            let trimmed = deck_name.trim();
+            *deck_name = if trimmed.is_empty() {
+                DEFAULT_DECK_NAME.to_string()
+            } else {
+                trimmed.to_string()
+            };
+        }
+        self
+    }
+}
+
+/// Everything stored for a library
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpacedReviewData {
+    #[serde(default)]
+    pub notebooks: HashMap<Uuid, NotebookReviewSettings>,
+    /// When questions were last generated for each page
+    #[serde(default)]
+    pub reviewed: HashMap<Uuid, DateTime<Utc>>,
+}
+
+/// Recall questions about one page
+#[derive(Debug, Clone)]
+pub struct PageQuestions {
+    pub page_id: Uuid,
+    pub title: String,
+    /// Question and answer pairs
+    pub questions: Vec<(String, String)>,
+}
@@ -0,0 +1,89 @@
+//! Spaced-writing review storage implementation
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::models::*;
+use crate::storage::StorageError;
+
+type Result<T> = std::result::Result<T, StorageError>;
+
+/// Per-notebook review settings and review history of a library, in a
+/// single JSON file
+pub struct SpacedReviewStorage {
+    path: PathBuf,
+    data: SpacedReviewData,
+}
+
+impl SpacedReviewStorage {
+    /// Open the review data of the library at `data_dir`
+    pub fn new(data_dir: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&data_dir)?;
+        let path = data_dir.join("spaced_review.json");
+        let data = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable spaced review data: {}", e);
+                SpacedReviewData::default()
+            })
+        } else {
+            SpacedReviewData::default()
+        };
+
+        Ok(Self { path, data })
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.data)?)?;
+        Ok(())
+    }
+
+    /// A notebook's settings; review is off for notebooks never configured
+    pub fn settings(&self, notebook_id: Uuid) -> NotebookReviewSettings {
+        self.data
+            .notebooks
+            .get(&notebook_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set_settings(
+        &mut self,
+        notebook_id: Uuid,
+        settings: NotebookReviewSettings,
+    ) -> Result<NotebookReviewSettings> {
+        let settings = settings.clamped();
+        self.data.notebooks.insert(notebook_id, settings.clone());
+        self.save()?;
+        Ok(settings)
+    }
+
+    /// Notebooks with review turned on, and their settings
+    pub fn enabled_notebooks(&self) -> Vec<(Uuid, NotebookReviewSettings)> {
+        self.data
+            .notebooks
+            .iter()
+            .filter(|(_, settings)| settings.enabled)
+            .map(|(id, settings)| (*id, settings.clone()))
+            .collect()
+    }
+
+    /// When questions were last generated for each page
+    pub fn reviewed(&self) -> &std::collections::HashMap<Uuid, DateTime<Utc>> {
+        &self.data.reviewed
+    }
+
+    /// Record that questions were generated for `page_ids` at `at`
+    pub fn mark_reviewed(&mut self, page_ids: &[Uuid], at: DateTime<Utc>) -> Result<()> {
+        if page_ids.is_empty() {
+            return Ok(());
+        }
+        for page_id in page_ids {
+            self.data.reviewed.insert(*page_id, at);
+        }
+        self.save()
+    }
+}
@@ -0,0 +1,555 @@
+//! SQL notebook pages
+//!
+//! A page of type `sql` is a block page whose code blocks are SQL, run by an
+//! embedded DuckDB over the notebook's datasets: CSV and Parquet files copied
+//! into `assets/datasets/` and listed in `datasets.json` there. Each dataset
+//! is a view named after it, so a cell can `SELECT * FROM sales`. DuckDB may
+//! read the datasets directory and nothing else; the configuration is locked
+//! before the cell's SQL runs.
+//!
+//! Results are stored on the block like other code block runs (see
+//! `code_exec`): an `execute_result` per statement returning rows, whose
+//! table data also carries column types and, when the shape suits one, a
+//! chart suggestion.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use duckdb::types::{TimeUnit, ValueRef};
+use duckdb::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::code_exec::RunOutput;
+use crate::storage::atomic;
+
+/// Rows of a query kept in its result table
+pub const MAX_ROWS: usize = 5000;
+
+/// Directory under a notebook's assets holding its datasets
+const DATASETS_DIR: &str = "datasets";
+const REGISTRY_FILE: &str = "datasets.json";
+
+#[derive(Error, Debug)]
+pub enum SqlNotebookError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("DuckDB error: {0}")]
+    DuckDb(#[from] duckdb::Error),
+    #[error("Only CSV, TSV and Parquet files can be datasets, not {0}")]
+    UnsupportedFormat(String),
+    #[error("Invalid dataset name: {0:?}")]
+    InvalidName(String),
+    #[error("A dataset named {0} already exists")]
+    DatasetExists(String),
+    #[error("Dataset not found: {0}")]
+    DatasetNotFound(String),
+}
+
+pub type Result<T> = std::result::Result<T, SqlNotebookError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatasetFormat {
+    Csv,
+    Parquet,
+}
+
+impl DatasetFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" | "tsv" => Ok(DatasetFormat::Csv),
+            "parquet" | "pq" => Ok(DatasetFormat::Parquet),
+            _ => Err(SqlNotebookError::UnsupportedFormat(if ext.is_empty() {
+                "files without an extension".to_string()
+            } else {
+                format!(".{} files", ext)
+            })),
+        }
+    }
+
+    fn reader(self) -> &'static str {
+        match self {
+            DatasetFormat::Csv => "read_csv_auto",
+            DatasetFormat::Parquet => "read_parquet",
+        }
+    }
+}
+
+/// A file registered as a dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dataset {
+    /// Name of the view SQL reads it through
+    pub name: String,
+    /// File name in the datasets directory
+    pub file: String,
+    pub format: DatasetFormat,
+    pub size: u64,
+    pub added_at: DateTime<Utc>,
+}
+
+/// The datasets directory under a notebook's assets directory
+pub fn datasets_dir(assets_dir: &Path) -> PathBuf {
+    assets_dir.join(DATASETS_DIR)
+}
+
+pub fn list_datasets(dir: &Path) -> Result<Vec<Dataset>> {
+    let path = dir.join(REGISTRY_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn save_datasets(dir: &Path, datasets: &[Dataset]) -> Result<()> {
+    atomic::write_str(
+        &dir.join(REGISTRY_FILE),
+        &serde_json::to_string_pretty(datasets)?,
+    )?;
+    Ok(())
+}
+
+/// A view name made from `text`: lowercase letters, digits and underscores,
+/// not starting with a digit
+pub fn table_name(text: &str) -> String {
+    let mut name = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c.to_ascii_lowercase());
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_matches('_').to_string();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("t_{}", name),
+        _ => name,
+    }
+}
+
+/// Copy `source` into the datasets directory and register it as `name`
+/// (by default the file's name)
+pub fn add_dataset(dir: &Path, source: &Path, name: Option<&str>) -> Result<Dataset> {
+    let format = DatasetFormat::from_path(source)?;
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let requested = name.filter(|n| !n.trim().is_empty()).unwrap_or(stem);
+    let name = table_name(requested);
+    if name.is_empty() {
+        return Err(SqlNotebookError::InvalidName(requested.to_string()));
+    }
+
+    let mut datasets = list_datasets(dir)?;
+    if datasets.iter().any(|d| d.name == name) {
+        return Err(SqlNotebookError::DatasetExists(name));
+    }
+    let ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let file = format!("{}.{}", name, ext);
+    fs::create_dir_all(dir)?;
+    let path = dir.join(&file);
+    let size = fs::copy(source, &path)?;
+
+    let dataset = Dataset {
+        name,
+        file,
+        format,
+        size,
+        added_at: Utc::now(),
+    };
+    datasets.push(dataset.clone());
+    if let Err(e) = save_datasets(dir, &datasets) {
+        // Don't leave an unregistered copy behind
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+    Ok(dataset)
+}
+
+/// Unregister a dataset and delete its file
+pub fn remove_dataset(dir: &Path, name: &str) -> Result<()> {
+    let mut datasets = list_datasets(dir)?;
+    let index = datasets
+        .iter()
+        .position(|d| d.name == name)
+        .ok_or_else(|| SqlNotebookError::DatasetNotFound(name.to_string()))?;
+    let dataset = datasets.remove(index);
+    let path = dir.join(&dataset.file);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    save_datasets(dir, &datasets)
+}
+
+/// Split SQL into statements on the semicolons outside strings, quoted
+/// identifiers and comments; empty statements are dropped
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"') => {
+                i += 1;
+                while i < bytes.len() {
+                    if bytes[i] == quote {
+                        // A doubled quote is an escaped one
+                        if bytes.get(i + 1) == Some(&quote) {
+                            i += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    i += 1;
+                }
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            b';' => {
+                statements.push(&sql[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    statements.push(&sql[start.min(sql.len())..]);
+    statements
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !is_blank_sql(s))
+        .collect()
+}
+
+/// True if the statement is only whitespace and comments
+fn is_blank_sql(statement: &str) -> bool {
+    let mut rest = statement.trim_start();
+    loop {
+        if let Some(after) = rest.strip_prefix("--") {
+            rest = after.split_once('\n').map_or("", |(_, r)| r).trim_start();
+        } else if let Some(after) = rest.strip_prefix("/*") {
+            rest = after.split_once("*/").map_or("", |(_, r)| r).trim_start();
+        } else {
+            return rest.is_empty();
+        }
+    }
+}
+
+fn quote_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn quote_identifier(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// An in-memory DuckDB with a view per dataset that can read the datasets
+/// directory and nothing else
+fn open_sandboxed(dir: &Path, datasets: &[Dataset]) -> Result<Connection> {
+    let conn = Connection::open_in_memory()?;
+    let mut setup = String::new();
+    for dataset in datasets {
+        let path = dir.join(&dataset.file);
+        setup.push_str(&format!(
+            "CREATE VIEW {} AS SELECT * FROM {}({});\n",
+            quote_identifier(&dataset.name),
+            dataset.format.reader(),
+            quote_literal(&path.to_string_lossy()),
+        ));
+    }
+    setup.push_str(&format!(
+        "SET autoinstall_known_extensions = false;
+SET autoload_known_extensions = false;
+SET allowed_directories = [{}];
+SET enable_external_access = false;
+SET lock_configuration = true;",
+        quote_literal(&dir.to_string_lossy())
+    ));
+    conn.execute_batch(&setup)?;
+    Ok(conn)
+}
+
+/// Run a cell's SQL over the datasets. Each statement returning rows adds a
+/// result table; a failing statement adds an error and stops the cell.
+pub fn execute_cell(
+    dir: &Path,
+    datasets: &[Dataset],
+    sql: &str,
+    timeout: Duration,
+) -> Result<RunOutput> {
+    let conn = open_sandboxed(dir, datasets)?;
+
+    // Interrupt the query once the timeout passes
+    let interrupt = conn.interrupt_handle();
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    let watchdog = std::thread::spawn(move || {
+        if done_rx.recv_timeout(timeout).is_err() {
+            interrupt.interrupt();
+        }
+    });
+
+    let mut output = RunOutput {
+        success: true,
+        outputs: Vec::new(),
+    };
+    for statement in split_statements(sql) {
+        if let Err(e) = run_statement(&conn, statement, &mut output) {
+            let message = if done_tx.send(()).is_err() {
+                format!("Stopped after {} seconds", timeout.as_secs())
+            } else {
+                e.to_string()
+            };
+            output.push_error("SQLError", &message);
+            break;
+        }
+    }
+    let _ = done_tx.send(());
+    let _ = watchdog.join();
+    Ok(output)
+}
+
+fn run_statement(conn: &Connection, sql: &str, output: &mut RunOutput) -> duckdb::Result<()> {
+    let mut stmt = conn.prepare(sql)?;
+    let mut query = stmt.query([])?;
+    let columns: Vec<String> = query.as_ref().map(|s| s.column_names()).unwrap_or_default();
+    let mut rows = Vec::new();
+    let mut truncated = false;
+    while let Some(row) = query.next()? {
+        if rows.len() == MAX_ROWS {
+            truncated = true;
+            break;
+        }
+        let values = (0..columns.len())
+            .map(|i| row.get_ref(i).map(sql_value))
+            .collect::<duckdb::Result<Vec<_>>>()?;
+        rows.push(values);
+    }
+    if columns.is_empty() {
+        return Ok(());
+    }
+
+    let types = column_types(columns.len(), &rows);
+    let chart = chart_hint(&columns, &types);
+    let data = output.push_table(&columns, &rows, truncated);
+    data["columnTypes"] = json!(types);
+    if let Some(chart) = chart {
+        data["chart"] = chart;
+    }
+    Ok(())
+}
+
+fn micros(unit: TimeUnit, value: i64) -> i64 {
+    match unit {
+        TimeUnit::Second => value.saturating_mul(1_000_000),
+        TimeUnit::Millisecond => value.saturating_mul(1_000),
+        TimeUnit::Microsecond => value,
+        TimeUnit::Nanosecond => value / 1_000,
+    }
+}
+
+fn sql_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Boolean(b) => json!(b),
+        ValueRef::TinyInt(i) => json!(i),
+        ValueRef::SmallInt(i) => json!(i),
+        ValueRef::Int(i) => json!(i),
+        ValueRef::BigInt(i) => json!(i),
+        ValueRef::HugeInt(i) => json!(i.to_string()),
+        ValueRef::UTinyInt(i) => json!(i),
+        ValueRef::USmallInt(i) => json!(i),
+        ValueRef::UInt(i) => json!(i),
+        ValueRef::UBigInt(i) => json!(i),
+        ValueRef::Float(f) => json!(f),
+        ValueRef::Double(f) => json!(f),
+        ValueRef::Decimal(d) => {
+            let text = d.to_string();
+            text.parse::<f64>().map(|f| json!(f)).unwrap_or(json!(text))
+        }
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::String(format!("<{} byte blob>", b.len())),
+        ValueRef::Date32(days) => NaiveDate::from_num_days_from_ce_opt(days + 719_163)
+            .map_or(Value::Null, |d| json!(d.format("%Y-%m-%d").to_string())),
+        ValueRef::Timestamp(unit, value) => DateTime::from_timestamp_micros(micros(unit, value))
+            .map_or(Value::Null, |t| {
+                json!(t.format("%Y-%m-%d %H:%M:%S").to_string())
+            }),
+        ValueRef::Time64(unit, value) => {
+            let micros = micros(unit, value);
+            NaiveTime::from_num_seconds_from_midnight_opt(
+                (micros / 1_000_000) as u32,
+                (micros % 1_000_000) as u32 * 1_000,
+            )
+            .map_or(Value::Null, |t| json!(t.format("%H:%M:%S").to_string()))
+        }
+        other => Value::String(format!("{:?}", other)),
+    }
+}
+
+/// `number`, `boolean` or `text` per column, from its non-null values
+fn column_types(count: usize, rows: &[Vec<Value>]) -> Vec<&'static str> {
+    (0..count)
+        .map(|i| {
+            let mut values = rows
+                .iter()
+                .map(|r| &r[i])
+                .filter(|v| !v.is_null())
+                .peekable();
+            if values.peek().is_none() {
+                "text"
+            } else if values.clone().all(Value::is_number) {
+                "number"
+            } else if values.all(Value::is_boolean) {
+                "boolean"
+            } else {
+                "text"
+            }
+        })
+        .collect()
+}
+
+/// A chart for a result: the first text column along the x axis and the
+/// numeric columns as series. None unless there's at least one of each.
+fn chart_hint(columns: &[String], types: &[&str]) -> Option<Value> {
+    let x = types.iter().position(|t| *t == "text")?;
+    let series: Vec<&String> = columns
+        .iter()
+        .zip(types)
+        .filter(|(_, t)| **t == "number")
+        .map(|(c, _)| c)
+        .collect();
+    if series.is_empty() {
+        return None;
+    }
+    let kind = if series.len() == 1 { "bar" } else { "line" };
+    Some(json!({
+        "type": kind,
+        "x": columns[x],
+        "series": series,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("sql_notebook_test_{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn splits_statements_outside_quotes_and_comments() {
+        let sql = "select 'a;b' as \"x;y\"; -- note; here\nselect 2 /* ; */;\n\n-- trailing;\n";
+        assert_eq!(
+            split_statements(sql),
+            ["select 'a;b' as \"x;y\"", "-- note; here\nselect 2 /* ; */"]
+        );
+        assert_eq!(split_statements("select 'it''s;'"), ["select 'it''s;'"]);
+        assert!(split_statements("  ;; ").is_empty());
+    }
+
+    #[test]
+    fn table_names_are_identifiers() {
+        assert_eq!(table_name("Sales 2024 (Q1)"), "sales_2024_q1");
+        assert_eq!(table_name("2024-data"), "t_2024_data");
+        assert_eq!(table_name("  --  "), "");
+    }
+
+    #[test]
+    fn datasets_are_copied_registered_and_removed() {
+        let root = temp_dir();
+        let source = root.join("Monthly Sales.csv");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&source, "month,total\nJan,10\nFeb,12\n").unwrap();
+        let dir = datasets_dir(&root.join("assets"));
+
+        let dataset = add_dataset(&dir, &source, None).unwrap();
+        assert_eq!(dataset.name, "monthly_sales");
+        assert_eq!(dataset.format, DatasetFormat::Csv);
+        assert!(dir.join("monthly_sales.csv").is_file());
+        assert!(matches!(
+            add_dataset(&dir, &source, Some("Monthly-Sales")),
+            Err(SqlNotebookError::DatasetExists(_))
+        ));
+        assert!(matches!(
+            add_dataset(&dir, &root.join("notes.txt"), None),
+            Err(SqlNotebookError::UnsupportedFormat(_))
+        ));
+
+        let output = execute_cell(
+            &dir,
+            &list_datasets(&dir).unwrap(),
+            "SELECT month, total FROM monthly_sales ORDER BY total",
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(output.success, "{:?}", output.outputs);
+        let table = &output.outputs[0]["data"]["application/json"];
+        assert_eq!(table["rows"], json!([["Jan", 10], ["Feb", 12]]));
+        assert_eq!(table["columnTypes"], json!(["text", "number"]));
+        assert_eq!(table["chart"]["x"], "month");
+
+        // Files outside the datasets directory can't be read
+        let output = execute_cell(
+            &dir,
+            &[],
+            &format!(
+                "SELECT * FROM read_csv_auto({})",
+                quote_literal(&source.to_string_lossy())
+            ),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(!output.success);
+
+        remove_dataset(&dir, "monthly_sales").unwrap();
+        assert!(list_datasets(&dir).unwrap().is_empty());
+        assert!(!dir.join("monthly_sales.csv").exists());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn charts_need_a_label_and_a_number() {
+        let columns = vec!["city".to_string(), "temp".to_string(), "rain".to_string()];
+        let chart = chart_hint(&columns, &["text", "number", "number"]).unwrap();
+        assert_eq!(
+            chart,
+            json!({ "type": "line", "x": "city", "series": ["temp", "rain"] })
+        );
+        assert!(chart_hint(&columns[1..], &["number", "number"]).is_none());
+        assert_eq!(
+            column_types(
+                2,
+                &[vec![json!(1), Value::Null], vec![json!(2.5), json!(true)]]
+            ),
+            ["number", "boolean"]
+        );
+    }
+}
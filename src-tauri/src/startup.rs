@@ -0,0 +1,167 @@
+//! Background startup of heavyweight subsystems.
+//!
+//! `run()` only builds what the first frame needs. Loading the vector
+//! index, warming up Python and starting the schedulers happen on blocking
+//! threads spawned from the setup hook, so a big library doesn't hold up the
+//! window. Each subsystem reports through a `subsystem-ready` event when it
+//! finishes; the frontend calls `get_startup_status` on mount for anything
+//! that finished before it was listening.
+//!
+//! Nothing waits on readiness to be correct: the vector index opens on first
+//! use and Python starts on the first call, whichever comes first.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted with a [`SubsystemStatus`] when a subsystem finishes
+pub const READY_EVENT: &str = "subsystem-ready";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    VectorIndex,
+    Python,
+    Schedulers,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 3] = [Self::VectorIndex, Self::Python, Self::Schedulers];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Readiness {
+    Pending,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemStatus {
+    pub subsystem: Subsystem,
+    pub readiness: Readiness,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// How long initialization took, once finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// Readiness of every subsystem, managed as Tauri state
+pub struct StartupStatus {
+    statuses: Mutex<BTreeMap<Subsystem, SubsystemStatus>>,
+}
+
+impl StartupStatus {
+    /// Every subsystem pending
+    pub fn new() -> Self {
+        let statuses = Subsystem::ALL
+            .into_iter()
+            .map(|subsystem| {
+                (
+                    subsystem,
+                    SubsystemStatus {
+                        subsystem,
+                        readiness: Readiness::Pending,
+                        error: None,
+                        elapsed_ms: None,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            statuses: Mutex::new(statuses),
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<SubsystemStatus> {
+        let statuses = self.statuses.lock().unwrap_or_else(|e| e.into_inner());
+        statuses.values().cloned().collect()
+    }
+
+    fn finish(
+        &self,
+        subsystem: Subsystem,
+        result: Result<(), String>,
+        elapsed_ms: u64,
+    ) -> SubsystemStatus {
+        let (readiness, error) = match result {
+            Ok(()) => (Readiness::Ready, None),
+            Err(e) => (Readiness::Failed, Some(e)),
+        };
+        let status = SubsystemStatus {
+            subsystem,
+            readiness,
+            error,
+            elapsed_ms: Some(elapsed_ms),
+        };
+        let mut statuses = self.statuses.lock().unwrap_or_else(|e| e.into_inner());
+        statuses.insert(subsystem, status.clone());
+        status
+    }
+}
+
+impl Default for StartupStatus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run `init` for `subsystem` on a blocking thread, then record the outcome
+/// and emit [`READY_EVENT`]. Needs [`StartupStatus`] in the app's state.
+pub fn start<F>(app: &AppHandle, subsystem: Subsystem, init: F)
+where
+    F: FnOnce() -> Result<(), String> + Send + 'static,
+{
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let started = Instant::now();
+        let result = init();
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match &result {
+            Ok(()) => log::info!("{:?} ready after {} ms", subsystem, elapsed_ms),
+            Err(e) => log::warn!("{:?} failed to start: {}", subsystem, e),
+        }
+        let status = handle
+            .state::<StartupStatus>()
+            .finish(subsystem, result, elapsed_ms);
+        let _ = handle.emit(READY_EVENT, status);
+    });
+}
+
+/// Readiness of the background subsystems, for the frontend to catch up on
+/// events it missed
+#[tauri::command]
+pub fn get_startup_status(state: tauri::State<'_, StartupStatus>) -> Vec<SubsystemStatus> {
+    state.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_outcomes_per_subsystem() {
+        let status = StartupStatus::new();
+        assert!(status
+            .snapshot()
+            .iter()
+            .all(|s| s.readiness == Readiness::Pending));
+
+        status.finish(Subsystem::VectorIndex, Ok(()), 12);
+        let failed = status.finish(Subsystem::Python, Err("no interpreter".into()), 3);
+        assert_eq!(failed.readiness, Readiness::Failed);
+
+        let snapshot = status.snapshot();
+        assert_eq!(snapshot.len(), Subsystem::ALL.len());
+        assert_eq!(snapshot[0].readiness, Readiness::Ready);
+        assert_eq!(snapshot[0].elapsed_ms, Some(12));
+        assert_eq!(snapshot[1].error.as_deref(), Some("no interpreter"));
+        assert_eq!(snapshot[2].readiness, Readiness::Pending);
+    }
+}
@@ -1,9 +1,13 @@
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{BufWriter, Cursor, Read, Seek, Write};
 use std::path::{Path, PathBuf};
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::{DateTime, Utc};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
@@ -11,6 +15,32 @@ use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
 use super::file_storage::{Result, StorageError};
 use super::models::{Notebook, Page};
+use crate::encryption::{crypto, Argon2Params, EncryptionError, EncryptionKey};
+
+/// Magic bytes at the start of an encrypted backup
+pub const ENCRYPTED_BACKUP_MAGIC: &[u8; 8] = b"NOUS_EBK";
+
+/// Current encrypted backup format version
+const ENCRYPTED_BACKUP_VERSION: u32 = 1;
+
+/// File extension for encrypted backups (plain backups are `.nous.zip`)
+pub const ENCRYPTED_BACKUP_EXTENSION: &str = "nousbak";
+
+/// Minimum size of a backup keyfile
+const MIN_KEYFILE_SIZE: usize = 32;
+
+/// Upper bound on the plaintext header, so a damaged length can't make us
+/// allocate the whole file
+const MAX_BACKUP_HEADER_SIZE: usize = 1024 * 1024;
+
+/// Entries at least this large are written with ZIP64 headers
+const ZIP64_ENTRY_THRESHOLD: u64 = u32::MAX as u64;
+
+/// Already-compressed media, stored as-is rather than deflated again
+const STORED_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "mov", "mkv", "webm", "avi", "mp3", "m4a", "aac", "ogg", "opus", "jpg", "jpeg",
+    "png", "gif", "webp", "avif", "heic", "zip", "gz", "7z",
+];
 
 /// Backup schedule frequency
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -41,14 +71,21 @@ pub struct BackupSettings {
     pub day_of_week: Option<u8>,
     /// Day of month for monthly backups (1-28)
     pub day_of_month: Option<u8>,
-    /// Maximum number of backups to keep per notebook
+    /// Maximum number of backups to keep per notebook (ignored when
+    /// `retention` is set)
     pub max_backups_per_notebook: usize,
+    /// Grandfather-father-son retention
+    #[serde(default)]
+    pub retention: Option<BackupRetention>,
     /// Notebooks to backup (empty = all)
     pub notebook_ids: Vec<Uuid>,
     /// Last backup time
     pub last_backup: Option<DateTime<Utc>>,
     /// Next scheduled backup time
     pub next_backup: Option<DateTime<Utc>>,
+    /// Encrypt scheduled backups with this keyfile (None = plain ZIP)
+    #[serde(default)]
+    pub keyfile_path: Option<String>,
 }
 
 impl Default for BackupSettings {
@@ -63,10 +100,89 @@ impl Default for BackupSettings {
             notebook_ids: Vec::new(), // Empty = all notebooks
             last_backup: None,
             next_backup: None,
+            keyfile_path: None,
+            retention: None,
         }
     }
 }
 
+impl BackupSettings {
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        match &self.retention {
+            Some(retention) => RetentionPolicy::Rotating(retention.clone()),
+            None => RetentionPolicy::KeepLast(self.max_backups_per_notebook),
+        }
+    }
+}
+
+/// Grandfather-father-son retention: keep the newest backup of each of the
+/// last N days, M weeks and K months
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRetention {
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub keep_monthly: usize,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            keep_daily: 7,
+            keep_weekly: 4,
+            keep_monthly: 12,
+        }
+    }
+}
+
+/// Which auto-backups of a notebook survive cleanup
+#[derive(Debug, Clone, PartialEq)]
+pub enum RetentionPolicy {
+    /// Keep the most recent N
+    KeepLast(usize),
+    Rotating(BackupRetention),
+}
+
+impl RetentionPolicy {
+    /// Indices into `created` (backup creation times, any order) of the
+    /// backups to keep. The newest backup is always kept.
+    pub fn select(&self, created: &[DateTime<Utc>]) -> HashSet<usize> {
+        use chrono::Datelike;
+
+        let mut order: Vec<usize> = (0..created.len()).collect();
+        order.sort_by(|a, b| created[*b].cmp(&created[*a]));
+
+        let mut keep: HashSet<usize> = order.first().copied().into_iter().collect();
+        match self {
+            RetentionPolicy::KeepLast(n) => keep.extend(order.iter().take(*n)),
+            RetentionPolicy::Rotating(retention) => {
+                let tiers: [(usize, fn(&DateTime<Utc>) -> (i32, u32)); 3] = [
+                    (retention.keep_daily, |t| (t.year(), t.ordinal())),
+                    (retention.keep_weekly, |t| {
+                        let week = t.iso_week();
+                        (week.year(), week.week())
+                    }),
+                    (retention.keep_monthly, |t| (t.year(), t.month())),
+                ];
+                for (limit, period_of) in tiers {
+                    // Newest first, so the first backup seen in each period is
+                    // the one that represents it
+                    let mut periods = HashSet::new();
+                    for &i in &order {
+                        if periods.len() >= limit {
+                            break;
+                        }
+                        if periods.insert(period_of(&created[i])) {
+                            keep.insert(i);
+                        }
+                    }
+                }
+            }
+        }
+        keep
+    }
+}
+
 /// Get the backup settings file path
 pub fn get_backup_settings_path(data_dir: &Path) -> PathBuf {
     data_dir.join("backup_settings.json")
@@ -102,15 +218,132 @@ pub struct BackupMetadata {
     pub notebook_name: String,
     pub page_count: usize,
     pub asset_count: usize,
+    /// Whether the backup is an encrypted container
+    #[serde(default)]
+    pub encrypted: bool,
+}
+
+/// Secret used to encrypt or decrypt a backup
+#[derive(Debug, Clone)]
+pub enum BackupSecret {
+    Password(String),
+    /// Path to a keyfile of at least 32 random bytes
+    Keyfile(PathBuf),
+}
+
+/// How the key of an encrypted backup is derived
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum BackupKeySource {
+    Password,
+    Keyfile,
+}
+
+/// Plaintext header of an encrypted backup, after the magic and its length
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedBackupHeader {
+    format_version: u32,
+    /// Readable without the secret so backups can be listed and rotated
+    metadata: BackupMetadata,
+    key_source: BackupKeySource,
+    /// Argon2 salt (base64), for password backups
+    salt: Option<String>,
+    /// ChaCha20-Poly1305 nonce (base64)
+    nonce: String,
+    /// SHA-256 of the plaintext ZIP (hex), checked on restore
+    sha256: String,
 }
 
 /// Export a notebook to a ZIP file
+///
+/// The archive is streamed to a `.part` file next to `output_path` one entry
+/// at a time, so memory use doesn't grow with the notebook, and renamed into
+/// place once complete. Entries and archives past 4 GB use ZIP64.
 pub fn export_notebook_to_zip(
     notebook_dir: &Path,
     notebook: &Notebook,
     output_path: &Path,
     progress_fn: Option<&dyn Fn(usize, usize, &str)>,
 ) -> Result<BackupMetadata> {
+    let mut part_name = output_path.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+
+    let written = File::create(&part_path)
+        .map_err(StorageError::from)
+        .and_then(|file| {
+            let (metadata, writer) =
+                write_notebook_zip(notebook_dir, notebook, BufWriter::new(file), progress_fn)?;
+            let file = writer.into_inner().map_err(|e| e.into_error())?;
+            file.sync_all()?;
+            Ok(metadata)
+        });
+    match written {
+        Ok(metadata) => {
+            fs::rename(&part_path, output_path)?;
+            Ok(metadata)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&part_path);
+            Err(e)
+        }
+    }
+}
+
+/// Export a notebook to an encrypted backup container
+///
+/// Unlike [`export_notebook_to_zip`] this holds the whole archive in memory:
+/// the container is sealed as a single AEAD message.
+pub fn export_notebook_to_encrypted_backup(
+    notebook_dir: &Path,
+    notebook: &Notebook,
+    output_path: &Path,
+    secret: &BackupSecret,
+    progress_fn: Option<&dyn Fn(usize, usize, &str)>,
+) -> Result<BackupMetadata> {
+    let (mut metadata, zip) =
+        write_notebook_zip(notebook_dir, notebook, Cursor::new(Vec::new()), progress_fn)?;
+    let zip_bytes = zip.into_inner();
+    metadata.encrypted = true;
+
+    let salt = match secret {
+        BackupSecret::Password(_) => Some(crypto::generate_salt()),
+        BackupSecret::Keyfile(_) => None,
+    };
+    let key = backup_key(secret, salt.as_deref())?;
+    let (ciphertext, nonce) = crypto::encrypt(&zip_bytes, &key)?;
+
+    let header = EncryptedBackupHeader {
+        format_version: ENCRYPTED_BACKUP_VERSION,
+        metadata: metadata.clone(),
+        key_source: match secret {
+            BackupSecret::Password(_) => BackupKeySource::Password,
+            BackupSecret::Keyfile(_) => BackupKeySource::Keyfile,
+        },
+        salt,
+        nonce: BASE64.encode(nonce),
+        sha256: hex::encode(Sha256::digest(&zip_bytes)),
+    };
+    let header_json = serde_json::to_vec(&header)?;
+
+    let mut out = Vec::with_capacity(12 + header_json.len() + ciphertext.len());
+    out.extend_from_slice(ENCRYPTED_BACKUP_MAGIC);
+    out.extend_from_slice(&(header_json.len() as u32).to_le_bytes());
+    out.extend_from_slice(&header_json);
+    out.extend_from_slice(&ciphertext);
+    super::atomic::write(output_path, &out)?;
+
+    Ok(metadata)
+}
+
+/// Write a notebook directory as a ZIP archive into `writer`
+fn write_notebook_zip<W: Write + Seek>(
+    notebook_dir: &Path,
+    notebook: &Notebook,
+    writer: W,
+    progress_fn: Option<&dyn Fn(usize, usize, &str)>,
+) -> Result<(BackupMetadata, W)> {
     // Pre-count total files for progress reporting
     let total_files = WalkDir::new(notebook_dir)
         .into_iter()
@@ -118,8 +351,7 @@ pub fn export_notebook_to_zip(
         .filter(|e| e.path().is_file())
         .count();
 
-    let file = File::create(output_path)?;
-    let mut zip = ZipWriter::new(file);
+    let mut zip = ZipWriter::new(writer);
     let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
 
     let mut page_count = 0;
@@ -160,13 +392,14 @@ pub fn export_notebook_to_zip(
                 asset_count += 1;
             }
 
-            // Add file to ZIP
+            // Add file to ZIP, copying it through in chunks
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            let file_options = options
+                .compression_method(entry_compression(path))
+                .large_file(size >= ZIP64_ENTRY_THRESHOLD);
             let name = relative_path.to_string_lossy();
-            zip.start_file(name.as_ref(), options)?;
-
-            let mut file_content = Vec::new();
-            File::open(path)?.read_to_end(&mut file_content)?;
-            zip.write_all(&file_content)?;
+            zip.start_file(name.as_ref(), file_options)?;
+            std::io::copy(&mut File::open(path)?, &mut zip)?;
 
             files_processed += 1;
             if let Some(ref cb) = progress_fn {
@@ -187,15 +420,166 @@ pub fn export_notebook_to_zip(
         notebook_name: notebook.name.clone(),
         page_count,
         asset_count,
+        encrypted: false,
     };
 
     let metadata_json = serde_json::to_string_pretty(&metadata)?;
     zip.start_file("_backup_metadata.json", options)?;
     zip.write_all(metadata_json.as_bytes())?;
 
-    zip.finish()?;
+    let writer = zip.finish()?;
 
-    Ok(metadata)
+    Ok((metadata, writer))
+}
+
+/// Compression for an archive entry: media that is already compressed is
+/// stored, everything else deflated
+fn entry_compression(path: &Path) -> CompressionMethod {
+    let stored = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| STORED_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+    if stored {
+        CompressionMethod::Stored
+    } else {
+        CompressionMethod::Deflated
+    }
+}
+
+/// Derive the key for an encrypted backup
+fn backup_key(secret: &BackupSecret, salt: Option<&str>) -> Result<EncryptionKey> {
+    match secret {
+        BackupSecret::Password(password) => {
+            let salt = salt.ok_or(EncryptionError::InvalidFormat)?;
+            Ok(crypto::derive_key(
+                password,
+                salt,
+                &Argon2Params::default(),
+            )?)
+        }
+        BackupSecret::Keyfile(path) => {
+            let bytes = fs::read(path)?;
+            if bytes.len() < MIN_KEYFILE_SIZE {
+                return Err(StorageError::InvalidOperation(format!(
+                    "Keyfile must be at least {} bytes",
+                    MIN_KEYFILE_SIZE
+                )));
+            }
+            let mut hasher = Sha256::new();
+            hasher.update(b"NOUS_BACKUP_KEYFILE"); // Domain separation
+            hasher.update(&bytes);
+            Ok(EncryptionKey::new(hasher.finalize().into()))
+        }
+    }
+}
+
+/// Write a new random keyfile for encrypted backups. Refuses to overwrite
+/// an existing file, since that would make older backups unreadable.
+pub fn generate_backup_keyfile(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(StorageError::InvalidOperation(format!(
+            "Keyfile already exists: {}",
+            path.display()
+        )));
+    }
+    let mut bytes = [0u8; 64];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    super::atomic::write(path, &bytes)?;
+    Ok(())
+}
+
+/// Check whether a file is an encrypted backup
+pub fn is_encrypted_backup(path: &Path) -> Result<bool> {
+    let mut magic = [0u8; 8];
+    let mut file = File::open(path)?;
+    match file.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == ENCRYPTED_BACKUP_MAGIC),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Split an encrypted backup into its header and ciphertext
+fn parse_encrypted_backup(bytes: &[u8]) -> Result<(EncryptedBackupHeader, &[u8])> {
+    let invalid = || StorageError::Encryption(EncryptionError::InvalidFormat);
+    let rest = bytes
+        .strip_prefix(ENCRYPTED_BACKUP_MAGIC.as_slice())
+        .ok_or_else(invalid)?;
+    let (len_bytes, rest) = rest.split_first_chunk::<4>().ok_or_else(invalid)?;
+    let header_len = u32::from_le_bytes(*len_bytes) as usize;
+    if rest.len() < header_len {
+        return Err(invalid());
+    }
+    let (header_json, ciphertext) = rest.split_at(header_len);
+    let header: EncryptedBackupHeader = serde_json::from_slice(header_json)?;
+    if header.format_version > ENCRYPTED_BACKUP_VERSION {
+        return Err(StorageError::InvalidOperation(format!(
+            "Backup format version {} is newer than this app supports",
+            header.format_version
+        )));
+    }
+    Ok((header, ciphertext))
+}
+
+/// Read the header of an encrypted backup
+fn read_encrypted_backup_header(path: &Path) -> Result<EncryptedBackupHeader> {
+    let mut file = File::open(path)?;
+    let mut prefix = [0u8; 12];
+    file.read_exact(&mut prefix)?;
+    let header_len = u32::from_le_bytes([prefix[8], prefix[9], prefix[10], prefix[11]]) as usize;
+    if header_len > MAX_BACKUP_HEADER_SIZE {
+        return Err(EncryptionError::InvalidFormat.into());
+    }
+    let mut bytes = prefix.to_vec();
+    bytes.resize(12 + header_len, 0);
+    file.read_exact(&mut bytes[12..])?;
+    Ok(parse_encrypted_backup(&bytes)?.0)
+}
+
+/// Decrypt an encrypted backup and verify its integrity, returning the
+/// plaintext ZIP
+pub fn decrypt_backup(path: &Path, secret: &BackupSecret) -> Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    let (header, ciphertext) = parse_encrypted_backup(&bytes)?;
+
+    let expected_source = match secret {
+        BackupSecret::Password(_) => BackupKeySource::Password,
+        BackupSecret::Keyfile(_) => BackupKeySource::Keyfile,
+    };
+    if header.key_source != expected_source {
+        return Err(StorageError::InvalidOperation(match header.key_source {
+            BackupKeySource::Password => "This backup is protected with a password".to_string(),
+            BackupKeySource::Keyfile => "This backup is protected with a keyfile".to_string(),
+        }));
+    }
+
+    let key = backup_key(secret, header.salt.as_deref())?;
+    let nonce = BASE64
+        .decode(&header.nonce)
+        .map_err(EncryptionError::Base64Error)?;
+    let zip_bytes = crypto::decrypt(ciphertext, &key, &nonce).map_err(|_| {
+        StorageError::InvalidOperation(
+            "Could not decrypt backup: wrong password or keyfile, or the file is damaged"
+                .to_string(),
+        )
+    })?;
+
+    if hex::encode(Sha256::digest(&zip_bytes)) != header.sha256 {
+        return Err(StorageError::InvalidOperation(
+            "Backup integrity check failed: checksum mismatch".to_string(),
+        ));
+    }
+
+    // The notebook inside must be the one the header describes
+    let mut archive = ZipArchive::new(Cursor::new(&zip_bytes))?;
+    let notebook = read_archive_notebook(&mut archive)?;
+    if notebook.id != header.metadata.notebook_id {
+        return Err(StorageError::InvalidOperation(
+            "Backup integrity check failed: notebook does not match header".to_string(),
+        ));
+    }
+
+    Ok(zip_bytes)
 }
 
 /// Import a notebook from a ZIP file
@@ -205,21 +589,54 @@ pub fn import_notebook_from_zip(
     notebooks_dir: &Path,
     existing_notebook_ids: &[Uuid],
 ) -> Result<Notebook> {
-    let file = File::open(zip_path)?;
-    let mut archive = ZipArchive::new(file)?;
+    import_notebook_from_backup(zip_path, notebooks_dir, existing_notebook_ids, None)
+}
 
-    // First, read the notebook.json to get notebook info
-    let notebook_json_index = archive.index_for_name("notebook.json");
-    let notebook: Notebook = if let Some(index) = notebook_json_index {
+/// Import a notebook from a plain or encrypted backup. Encrypted backups
+/// need `secret` and are verified before anything is written.
+pub fn import_notebook_from_backup(
+    backup_path: &Path,
+    notebooks_dir: &Path,
+    existing_notebook_ids: &[Uuid],
+    secret: Option<&BackupSecret>,
+) -> Result<Notebook> {
+    if is_encrypted_backup(backup_path)? {
+        let secret = secret.ok_or_else(|| {
+            StorageError::InvalidOperation(
+                "This backup is encrypted; a password or keyfile is required".to_string(),
+            )
+        })?;
+        let zip_bytes = decrypt_backup(backup_path, secret)?;
+        let archive = ZipArchive::new(Cursor::new(zip_bytes))?;
+        return import_notebook_from_archive(archive, notebooks_dir, existing_notebook_ids);
+    }
+
+    let file = File::open(backup_path)?;
+    let archive = ZipArchive::new(file)?;
+    import_notebook_from_archive(archive, notebooks_dir, existing_notebook_ids)
+}
+
+/// Read `notebook.json` from a backup archive
+fn read_archive_notebook<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Notebook> {
+    if let Some(index) = archive.index_for_name("notebook.json") {
         let mut notebook_file = archive.by_index(index)?;
         let mut contents = String::new();
         notebook_file.read_to_string(&mut contents)?;
-        serde_json::from_str(&contents)?
+        Ok(serde_json::from_str(&contents)?)
     } else {
-        return Err(StorageError::Io(std::io::Error::other(
+        Err(StorageError::Io(std::io::Error::other(
             "Invalid backup: notebook.json not found",
-        )));
-    };
+        )))
+    }
+}
+
+fn import_notebook_from_archive<R: Read + Seek>(
+    mut archive: ZipArchive<R>,
+    notebooks_dir: &Path,
+    existing_notebook_ids: &[Uuid],
+) -> Result<Notebook> {
+    // First, read the notebook.json to get notebook info
+    let notebook = read_archive_notebook(&mut archive)?;
 
     // Generate new ID if the notebook already exists
     let new_id = if existing_notebook_ids.contains(&notebook.id) {
@@ -293,8 +710,15 @@ pub fn import_notebook_from_zip(
     Ok(imported_notebook)
 }
 
-/// Get backup metadata from a ZIP file without extracting
+/// Get backup metadata from a ZIP file without extracting. Encrypted
+/// backups report the metadata from their plaintext header.
 pub fn get_backup_info(zip_path: &Path) -> Result<BackupMetadata> {
+    if is_encrypted_backup(zip_path)? {
+        let mut metadata = read_encrypted_backup_header(zip_path)?.metadata;
+        metadata.encrypted = true;
+        return Ok(metadata);
+    }
+
     let file = File::open(zip_path)?;
     let mut archive = ZipArchive::new(file)?;
 
@@ -308,16 +732,7 @@ pub fn get_backup_info(zip_path: &Path) -> Result<BackupMetadata> {
     }
 
     // Fall back to reading notebook.json and counting files
-    let notebook: Notebook = if let Some(index) = archive.index_for_name("notebook.json") {
-        let mut notebook_file = archive.by_index(index)?;
-        let mut contents = String::new();
-        notebook_file.read_to_string(&mut contents)?;
-        serde_json::from_str(&contents)?
-    } else {
-        return Err(StorageError::Io(std::io::Error::other(
-            "Invalid backup: notebook.json not found",
-        )));
-    };
+    let notebook = read_archive_notebook(&mut archive)?;
 
     // Count pages and assets
     let mut page_count = 0;
@@ -340,22 +755,218 @@ pub fn get_backup_info(zip_path: &Path) -> Result<BackupMetadata> {
         notebook_name: notebook.name,
         page_count,
         asset_count,
+        encrypted: false,
     })
 }
 
+/// A page that differs between a backup and the current notebook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestorePreviewPage {
+    pub page_id: String,
+    pub title: String,
+}
+
+/// What restoring a backup would change, compared with the notebook as it
+/// is now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestorePreview {
+    pub backup: BackupMetadata,
+    /// The notebook still exists, so the restore is imported as a copy
+    /// ("(Restored)") instead of replacing it
+    pub restores_as_copy: bool,
+    /// Pages in the backup that the current notebook no longer has
+    pub pages_only_in_backup: Vec<RestorePreviewPage>,
+    /// Pages added since the backup was taken
+    pub pages_only_in_current: Vec<RestorePreviewPage>,
+    /// Pages whose content differs
+    pub pages_changed: Vec<RestorePreviewPage>,
+    pub pages_unchanged: usize,
+    pub assets_only_in_backup: usize,
+    pub assets_only_in_current: usize,
+    pub assets_changed: usize,
+}
+
+/// Title of a page file, or its file stem when it can't be read (e.g. an
+/// encrypted page)
+fn page_title(bytes: &[u8], name: &str) -> String {
+    serde_json::from_slice::<Page>(bytes)
+        .map(|page| page.title)
+        .unwrap_or_else(|_| {
+            Path::new(name)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+}
+
+/// Diff a backup against the current state of its notebook without
+/// restoring anything. Encrypted backups need `secret`.
+pub fn preview_restore(
+    backup_path: &Path,
+    notebooks_dir: &Path,
+    secret: Option<&BackupSecret>,
+) -> Result<RestorePreview> {
+    let (mut archive, encrypted) = if is_encrypted_backup(backup_path)? {
+        let secret = secret.ok_or_else(|| {
+            StorageError::InvalidOperation(
+                "This backup is encrypted; a password or keyfile is required".to_string(),
+            )
+        })?;
+        let zip_bytes = decrypt_backup(backup_path, secret)?;
+        (ZipArchive::new(Cursor::new(zip_bytes))?, true)
+    } else {
+        let bytes = fs::read(backup_path)?;
+        (ZipArchive::new(Cursor::new(bytes))?, false)
+    };
+
+    let notebook = read_archive_notebook(&mut archive)?;
+    let mut backup = match archive.index_for_name("_backup_metadata.json") {
+        Some(index) => {
+            let mut contents = String::new();
+            archive.by_index(index)?.read_to_string(&mut contents)?;
+            serde_json::from_str(&contents)?
+        }
+        None => get_backup_info(backup_path)?,
+    };
+    backup.encrypted = encrypted;
+
+    // Page and asset files in the backup, keyed by relative path
+    let mut backup_pages: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut backup_assets: HashMap<String, String> = HashMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name().to_string();
+        if name.ends_with('/') {
+            continue;
+        }
+        if name.starts_with("pages/") && name.ends_with(".json") {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            backup_pages.insert(name, bytes);
+        } else if name.starts_with("assets/") {
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut file, &mut hasher)?;
+            backup_assets.insert(name, hex::encode(hasher.finalize()));
+        }
+    }
+
+    let notebook_dir = notebooks_dir.join(notebook.id.to_string());
+    let restores_as_copy = notebook_dir.join("notebook.json").exists();
+
+    let mut current_pages: HashMap<String, Vec<u8>> = HashMap::new();
+    let pages_dir = notebook_dir.join("pages");
+    if pages_dir.is_dir() {
+        for entry in fs::read_dir(&pages_dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().map_or(false, |e| e == "json") {
+                let name = format!("pages/{}", path.file_name().unwrap().to_string_lossy());
+                current_pages.insert(name, fs::read(&path)?);
+            }
+        }
+    }
+
+    let mut current_assets: HashMap<String, String> = HashMap::new();
+    let assets_dir = notebook_dir.join("assets");
+    if assets_dir.is_dir() {
+        for entry in WalkDir::new(&assets_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.path().is_file() {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(&notebook_dir) else {
+                continue;
+            };
+            let name = relative.to_string_lossy().replace('\\', "/");
+            let bytes = fs::read(entry.path())?;
+            current_assets.insert(name, hex::encode(Sha256::digest(&bytes)));
+        }
+    }
+
+    let mut preview = RestorePreview {
+        backup,
+        restores_as_copy,
+        pages_only_in_backup: Vec::new(),
+        pages_only_in_current: Vec::new(),
+        pages_changed: Vec::new(),
+        pages_unchanged: 0,
+        assets_only_in_backup: 0,
+        assets_only_in_current: 0,
+        assets_changed: 0,
+    };
+
+    let entry = |name: &str, bytes: &[u8]| RestorePreviewPage {
+        page_id: Path::new(name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        title: page_title(bytes, name),
+    };
+    for (name, bytes) in &backup_pages {
+        match current_pages.get(name) {
+            None => preview.pages_only_in_backup.push(entry(name, bytes)),
+            Some(current) if current != bytes => preview.pages_changed.push(entry(name, current)),
+            Some(_) => preview.pages_unchanged += 1,
+        }
+    }
+    for (name, bytes) in &current_pages {
+        if !backup_pages.contains_key(name) {
+            preview.pages_only_in_current.push(entry(name, bytes));
+        }
+    }
+    for list in [
+        &mut preview.pages_only_in_backup,
+        &mut preview.pages_only_in_current,
+        &mut preview.pages_changed,
+    ] {
+        list.sort_by(|a, b| a.title.cmp(&b.title));
+    }
+
+    for (name, hash) in &backup_assets {
+        match current_assets.get(name) {
+            None => preview.assets_only_in_backup += 1,
+            Some(current) if current != hash => preview.assets_changed += 1,
+            Some(_) => {}
+        }
+    }
+    preview.assets_only_in_current = current_assets
+        .keys()
+        .filter(|name| !backup_assets.contains_key(*name))
+        .count();
+
+    Ok(preview)
+}
+
 /// Get the auto-backup directory
 pub fn get_auto_backup_dir(data_dir: &Path) -> PathBuf {
     data_dir.join("backups")
 }
 
-/// Create an auto-backup for a notebook
+/// Whether a file in the backup directory looks like a backup
+fn is_backup_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map_or(false, |e| e == "zip" || e == ENCRYPTED_BACKUP_EXTENSION)
+}
+
+/// Result of an auto-backup
+#[derive(Debug, Clone)]
+pub struct AutoBackup {
+    pub path: PathBuf,
+    /// Older backups of the same notebook removed by the retention policy
+    pub pruned: Vec<PathBuf>,
+}
+
+/// Create an auto-backup for a notebook, encrypted when `secret` is given
 pub fn create_auto_backup(
     notebook_dir: &Path,
     notebook: &Notebook,
     data_dir: &Path,
-    max_backups: usize,
+    retention: &RetentionPolicy,
+    secret: Option<&BackupSecret>,
     progress_fn: Option<&dyn Fn(usize, usize, &str)>,
-) -> Result<PathBuf> {
+) -> Result<AutoBackup> {
     let backup_dir = get_auto_backup_dir(data_dir);
     fs::create_dir_all(&backup_dir)?;
 
@@ -364,27 +975,46 @@ pub fn create_auto_backup(
     let safe_name = notebook
         .name
         .replace(|c: char| !c.is_alphanumeric() && c != '-' && c != '_', "_");
-    let filename = format!("{}_{}.nous.zip", safe_name, timestamp);
+    let filename = match secret {
+        Some(_) => format!("{}_{}.{}", safe_name, timestamp, ENCRYPTED_BACKUP_EXTENSION),
+        None => format!("{}_{}.nous.zip", safe_name, timestamp),
+    };
     let backup_path = backup_dir.join(&filename);
 
     // Create the backup
-    export_notebook_to_zip(notebook_dir, notebook, &backup_path, progress_fn)?;
+    match secret {
+        Some(secret) => export_notebook_to_encrypted_backup(
+            notebook_dir,
+            notebook,
+            &backup_path,
+            secret,
+            progress_fn,
+        )?,
+        None => export_notebook_to_zip(notebook_dir, notebook, &backup_path, progress_fn)?,
+    };
 
     // Clean up old backups for this notebook
-    cleanup_old_backups(&backup_dir, &notebook.id, max_backups)?;
+    let pruned = cleanup_old_backups(&backup_dir, &notebook.id, retention)?;
 
-    Ok(backup_path)
+    Ok(AutoBackup {
+        path: backup_path,
+        pruned,
+    })
 }
 
-/// Remove old backups keeping only the most recent `max_backups`
-fn cleanup_old_backups(backup_dir: &Path, notebook_id: &Uuid, max_backups: usize) -> Result<()> {
+/// Remove old backups not kept by the retention policy, returning their paths
+fn cleanup_old_backups(
+    backup_dir: &Path,
+    notebook_id: &Uuid,
+    retention: &RetentionPolicy,
+) -> Result<Vec<PathBuf>> {
     let mut backups: Vec<(PathBuf, BackupMetadata)> = Vec::new();
 
     for entry in fs::read_dir(backup_dir)? {
         let entry = entry?;
         let path = entry.path();
 
-        if path.is_file() && path.extension().map_or(false, |e| e == "zip") {
+        if is_backup_file(&path) {
             if let Ok(metadata) = get_backup_info(&path) {
                 if metadata.notebook_id == *notebook_id {
                     backups.push((path, metadata));
@@ -393,15 +1023,19 @@ fn cleanup_old_backups(backup_dir: &Path, notebook_id: &Uuid, max_backups: usize
         }
     }
 
-    // Sort by creation date, newest first
-    backups.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
+    let created: Vec<DateTime<Utc>> = backups.iter().map(|(_, m)| m.created_at).collect();
+    let keep = retention.select(&created);
 
     // Remove old backups
-    for (path, _) in backups.into_iter().skip(max_backups) {
-        fs::remove_file(path)?;
+    let mut pruned = Vec::new();
+    for (i, (path, _)) in backups.into_iter().enumerate() {
+        if !keep.contains(&i) {
+            fs::remove_file(&path)?;
+            pruned.push(path);
+        }
     }
 
-    Ok(())
+    Ok(pruned)
 }
 
 /// List all auto-backups
@@ -418,7 +1052,7 @@ pub fn list_auto_backups(data_dir: &Path) -> Result<Vec<(PathBuf, BackupMetadata
         let entry = entry?;
         let path = entry.path();
 
-        if path.is_file() && path.extension().map_or(false, |e| e == "zip") {
+        if is_backup_file(&path) {
             if let Ok(metadata) = get_backup_info(&path) {
                 backups.push((path, metadata));
             }
@@ -533,3 +1167,195 @@ pub fn is_backup_due(settings: &BackupSettings) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::NotebookType;
+    use tempfile::TempDir;
+
+    fn notebook_fixture(dir: &Path) -> (Notebook, PathBuf) {
+        let notebook = Notebook::new("Journal".to_string(), NotebookType::Standard);
+        let notebook_dir = dir.join("source").join(notebook.id.to_string());
+        fs::create_dir_all(notebook_dir.join("pages")).unwrap();
+        fs::write(
+            notebook_dir.join("notebook.json"),
+            serde_json::to_string(&notebook).unwrap(),
+        )
+        .unwrap();
+        fs::write(notebook_dir.join("assets.txt"), "secret diary").unwrap();
+        (notebook, notebook_dir)
+    }
+
+    #[test]
+    fn encrypted_backup_roundtrip_with_password() {
+        let dir = TempDir::new().unwrap();
+        let (notebook, notebook_dir) = notebook_fixture(dir.path());
+        let backup = dir.path().join("journal.nousbak");
+        let secret = BackupSecret::Password("hunter2".to_string());
+
+        export_notebook_to_encrypted_backup(&notebook_dir, &notebook, &backup, &secret, None)
+            .unwrap();
+
+        let raw = fs::read(&backup).unwrap();
+        assert!(raw.starts_with(ENCRYPTED_BACKUP_MAGIC));
+        assert!(!raw.windows(12).any(|w| w == b"secret diary"));
+
+        let info = get_backup_info(&backup).unwrap();
+        assert!(info.encrypted);
+        assert_eq!(info.notebook_id, notebook.id);
+
+        let notebooks_dir = dir.path().join("notebooks");
+        assert!(import_notebook_from_backup(&backup, &notebooks_dir, &[], None).is_err());
+        let wrong = BackupSecret::Password("wrong".to_string());
+        assert!(import_notebook_from_backup(&backup, &notebooks_dir, &[], Some(&wrong)).is_err());
+
+        let restored =
+            import_notebook_from_backup(&backup, &notebooks_dir, &[], Some(&secret)).unwrap();
+        assert_eq!(restored.id, notebook.id);
+        let restored_dir = notebooks_dir.join(notebook.id.to_string());
+        assert_eq!(
+            fs::read_to_string(restored_dir.join("assets.txt")).unwrap(),
+            "secret diary"
+        );
+    }
+
+    #[test]
+    fn zip_export_streams_to_output() {
+        let dir = TempDir::new().unwrap();
+        let (notebook, notebook_dir) = notebook_fixture(dir.path());
+        fs::create_dir_all(notebook_dir.join("assets")).unwrap();
+        fs::write(notebook_dir.join("assets/clip.mp4"), vec![7u8; 64 * 1024]).unwrap();
+        let backup = dir.path().join("journal.nous.zip");
+
+        let metadata = export_notebook_to_zip(&notebook_dir, &notebook, &backup, None).unwrap();
+        assert_eq!(metadata.asset_count, 1);
+        assert!(!dir.path().join("journal.nous.zip.part").exists());
+
+        let mut archive = ZipArchive::new(File::open(&backup).unwrap()).unwrap();
+        let clip = archive.by_name("assets/clip.mp4").unwrap();
+        assert_eq!(clip.compression(), CompressionMethod::Stored);
+        assert_eq!(clip.size(), 64 * 1024);
+        drop(clip);
+        let notes = archive.by_name("assets.txt").unwrap();
+        assert_eq!(notes.compression(), CompressionMethod::Deflated);
+    }
+
+    #[test]
+    fn keyfile_backup_detects_tampering() {
+        let dir = TempDir::new().unwrap();
+        let (notebook, notebook_dir) = notebook_fixture(dir.path());
+        let keyfile = dir.path().join("backup.key");
+        generate_backup_keyfile(&keyfile).unwrap();
+        assert!(generate_backup_keyfile(&keyfile).is_err());
+
+        let backup = dir.path().join("journal.nousbak");
+        let secret = BackupSecret::Keyfile(keyfile);
+        export_notebook_to_encrypted_backup(&notebook_dir, &notebook, &backup, &secret, None)
+            .unwrap();
+        assert!(decrypt_backup(&backup, &secret).is_ok());
+
+        let password = BackupSecret::Password("hunter2".to_string());
+        assert!(decrypt_backup(&backup, &password).is_err());
+
+        let mut raw = fs::read(&backup).unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0xff;
+        fs::write(&backup, raw).unwrap();
+        assert!(decrypt_backup(&backup, &secret).is_err());
+    }
+
+    fn at(days_ago: i64) -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap() - chrono::Duration::days(days_ago)
+    }
+
+    #[test]
+    fn keep_last_keeps_newest() {
+        let created = vec![at(2), at(0), at(1), at(3)];
+        let keep = RetentionPolicy::KeepLast(2).select(&created);
+        assert_eq!(keep, HashSet::from([1, 2]));
+    }
+
+    #[test]
+    fn rotating_keeps_one_per_day_week_and_month() {
+        // One backup a day for 90 days, plus a second one today
+        let mut created: Vec<DateTime<Utc>> = (0..90).map(at).collect();
+        created.push(at(0) - chrono::Duration::hours(1));
+        let policy = RetentionPolicy::Rotating(BackupRetention {
+            keep_daily: 3,
+            keep_weekly: 2,
+            keep_monthly: 3,
+        });
+        let keep = policy.select(&created);
+
+        // Newest of each of the last 3 days
+        assert!(keep.contains(&0) && keep.contains(&1) && keep.contains(&2));
+        assert!(!keep.contains(&90));
+        // Newest of the previous ISO week (2026-03-31 is a Tuesday, so the
+        // week before ends on Sunday the 29th)
+        assert!(keep.contains(&2));
+        // Newest of February and January
+        assert!(keep.contains(&31));
+        assert!(keep.contains(&59));
+        assert_eq!(keep.len(), 5);
+    }
+
+    #[test]
+    fn preview_reports_differences() {
+        let dir = TempDir::new().unwrap();
+        let (notebook, notebook_dir) = notebook_fixture(dir.path());
+        let kept = Page::new(notebook.id, "Kept".to_string());
+        let edited = Page::new(notebook.id, "Edited".to_string());
+        let deleted = Page::new(notebook.id, "Deleted".to_string());
+        let pages_dir = notebook_dir.join("pages");
+        for page in [&kept, &edited, &deleted] {
+            fs::write(
+                pages_dir.join(format!("{}.json", page.id)),
+                serde_json::to_string(page).unwrap(),
+            )
+            .unwrap();
+        }
+        let backup = dir.path().join("journal.nous.zip");
+        export_notebook_to_zip(&notebook_dir, &notebook, &backup, None).unwrap();
+
+        let mut edited = edited.clone();
+        edited.title = "Edited later".to_string();
+        fs::write(
+            pages_dir.join(format!("{}.json", edited.id)),
+            serde_json::to_string(&edited).unwrap(),
+        )
+        .unwrap();
+        fs::remove_file(pages_dir.join(format!("{}.json", deleted.id))).unwrap();
+        let added = Page::new(notebook.id, "Added".to_string());
+        fs::write(
+            pages_dir.join(format!("{}.json", added.id)),
+            serde_json::to_string(&added).unwrap(),
+        )
+        .unwrap();
+
+        let notebooks_dir = notebook_dir.parent().unwrap();
+        let preview = preview_restore(&backup, notebooks_dir, None).unwrap();
+        assert!(preview.restores_as_copy);
+        assert_eq!(preview.pages_unchanged, 1);
+        assert_eq!(preview.pages_only_in_backup[0].title, "Deleted");
+        assert_eq!(preview.pages_only_in_current[0].title, "Added");
+        assert_eq!(preview.pages_changed[0].title, "Edited later");
+
+        let elsewhere = dir.path().join("empty");
+        let preview = preview_restore(&backup, &elsewhere, None).unwrap();
+        assert!(!preview.restores_as_copy);
+        assert_eq!(preview.pages_only_in_backup.len(), 3);
+    }
+
+    #[test]
+    fn plain_zip_is_not_encrypted() {
+        let dir = TempDir::new().unwrap();
+        let (notebook, notebook_dir) = notebook_fixture(dir.path());
+        let backup = dir.path().join("journal.nous.zip");
+        export_notebook_to_zip(&notebook_dir, &notebook, &backup, None).unwrap();
+
+        assert!(!is_encrypted_backup(&backup).unwrap());
+        assert!(!get_backup_info(&backup).unwrap().encrypted);
+    }
+}
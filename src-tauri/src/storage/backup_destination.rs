@@ -0,0 +1,348 @@
+//! Off-machine copies of auto-backups.
+//!
+//! A library can name a destination that each new auto-backup is copied to:
+//! another folder (an external drive, a cloud-synced folder), a WebDAV server
+//! or an S3-compatible bucket. Backups removed locally by the retention policy
+//! are deleted from the destination too, so it follows the same rotation.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::share::s3_signer;
+use crate::sync::config::SyncCredentials;
+use crate::sync::webdav::WebDAVClient;
+
+/// Where a library's auto-backups are copied
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BackupDestination {
+    /// A local or mounted folder
+    #[serde(rename_all = "camelCase")]
+    Folder { path: String },
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        server_url: String,
+        /// Folder on the server backups are uploaded to
+        remote_path: String,
+    },
+    /// AWS S3, Cloudflare R2, MinIO or any S3-compatible endpoint
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        endpoint_url: String,
+        bucket: String,
+        /// AWS region (use "auto" for Cloudflare R2)
+        region: String,
+        /// Key prefix for uploaded backups (e.g. "nous-backups/")
+        path_prefix: String,
+    },
+}
+
+impl BackupDestination {
+    /// WebDAV and S3 destinations need stored credentials
+    pub fn needs_credentials(&self) -> bool {
+        !matches!(self, BackupDestination::Folder { .. })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            BackupDestination::Folder { path } => {
+                if path.trim().is_empty() || !Path::new(path).is_absolute() {
+                    return Err("Backup folder must be an absolute path".to_string());
+                }
+            }
+            BackupDestination::WebDav { server_url, .. } => {
+                if !server_url.starts_with("http://") && !server_url.starts_with("https://") {
+                    return Err("WebDAV URL must start with http:// or https://".to_string());
+                }
+            }
+            BackupDestination::S3 {
+                endpoint_url,
+                bucket,
+                ..
+            } => {
+                if !endpoint_url.starts_with("http://") && !endpoint_url.starts_with("https://") {
+                    return Err("S3 endpoint must start with http:// or https://".to_string());
+                }
+                if bucket.trim().is_empty() {
+                    return Err("S3 bucket is required".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// WebDAV username/password, or S3 access key ID/secret access key
+#[derive(Debug, Clone)]
+pub struct DestinationCredentials {
+    pub username: String,
+    pub secret: String,
+}
+
+const SERVICE_NAME: &str = "nous-backup-destination";
+
+/// Path to file-based credential store.
+fn credentials_file_path(data_dir: &Path, library_id: Uuid) -> PathBuf {
+    data_dir
+        .join(".credentials")
+        .join(SERVICE_NAME)
+        .join(library_id.to_string())
+}
+
+/// Store destination credentials for a library (file-based store with
+/// keyring as best-effort backup, like sync and share credentials).
+pub fn store_destination_credentials(
+    data_dir: &Path,
+    library_id: Uuid,
+    credentials: &DestinationCredentials,
+) -> Result<(), String> {
+    let value = format!("{}:{}", credentials.username, credentials.secret);
+
+    let file_path = credentials_file_path(data_dir, library_id);
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create credentials dir: {}", e))?;
+    }
+    super::atomic::write_str(&file_path, &value)
+        .map_err(|e| format!("Failed to write credentials: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o600));
+    }
+
+    if let Ok(entry) = keyring::Entry::new(SERVICE_NAME, &library_id.to_string()) {
+        let _ = entry.set_password(&value);
+    }
+
+    Ok(())
+}
+
+/// Get destination credentials for a library.
+pub fn get_destination_credentials(
+    data_dir: &Path,
+    library_id: Uuid,
+) -> Result<DestinationCredentials, String> {
+    let file_path = credentials_file_path(data_dir, library_id);
+    if let Ok(data) = std::fs::read_to_string(&file_path) {
+        return parse_credentials(data.trim());
+    }
+
+    let entry = keyring::Entry::new(SERVICE_NAME, &library_id.to_string())
+        .map_err(|e| format!("Keyring error: {}", e))?;
+    let password = entry
+        .get_password()
+        .map_err(|_| "Backup destination credentials not found".to_string())?;
+    parse_credentials(&password)
+}
+
+/// Delete destination credentials for a library.
+pub fn delete_destination_credentials(data_dir: &Path, library_id: Uuid) {
+    let _ = std::fs::remove_file(credentials_file_path(data_dir, library_id));
+    if let Ok(entry) = keyring::Entry::new(SERVICE_NAME, &library_id.to_string()) {
+        let _ = entry.delete_credential();
+    }
+}
+
+fn parse_credentials(data: &str) -> Result<DestinationCredentials, String> {
+    let (username, secret) = data
+        .split_once(':')
+        .ok_or_else(|| "Invalid credential format".to_string())?;
+    Ok(DestinationCredentials {
+        username: username.to_string(),
+        secret: secret.to_string(),
+    })
+}
+
+fn file_name(path: &Path) -> Result<String, String> {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Not a file: {}", path.display()))
+}
+
+fn remote_join(base: &str, name: &str) -> String {
+    let base = base.trim_matches('/');
+    if base.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base, name)
+    }
+}
+
+/// Copy new backups to `destination` and delete pruned ones from it
+pub async fn push_backups(
+    destination: &BackupDestination,
+    credentials: Option<&DestinationCredentials>,
+    created: &[PathBuf],
+    pruned: &[PathBuf],
+) -> Result<(), String> {
+    let credentials = if destination.needs_credentials() {
+        Some(credentials.ok_or("Backup destination credentials not found")?)
+    } else {
+        None
+    };
+
+    match destination {
+        BackupDestination::Folder { path } => {
+            let dir = Path::new(path);
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            for backup in created {
+                let target = dir.join(file_name(backup)?);
+                std::fs::copy(backup, &target)
+                    .map_err(|e| format!("Failed to copy to {}: {}", target.display(), e))?;
+            }
+            for backup in pruned {
+                let _ = std::fs::remove_file(dir.join(file_name(backup)?));
+            }
+        }
+        BackupDestination::WebDav {
+            server_url,
+            remote_path,
+        } => {
+            let credentials = credentials.expect("checked above");
+            let client = WebDAVClient::new(
+                server_url.clone(),
+                SyncCredentials {
+                    username: credentials.username.clone(),
+                    password: credentials.secret.clone(),
+                },
+            )
+            .map_err(|e| e.to_string())?;
+            client
+                .mkdir_p(remote_path)
+                .await
+                .map_err(|e| e.to_string())?;
+            for backup in created {
+                let body = std::fs::read(backup)
+                    .map_err(|e| format!("Failed to read {}: {}", backup.display(), e))?;
+                let remote = remote_join(remote_path, &file_name(backup)?);
+                client
+                    .put(&remote, &body, None)
+                    .await
+                    .map_err(|e| format!("WebDAV upload of {} failed: {}", remote, e))?;
+            }
+            for backup in pruned {
+                let remote = remote_join(remote_path, &file_name(backup)?);
+                if let Err(e) = client.delete(&remote).await {
+                    log::warn!("Failed to delete old backup {} from WebDAV: {}", remote, e);
+                }
+            }
+        }
+        BackupDestination::S3 {
+            endpoint_url,
+            bucket,
+            region,
+            path_prefix,
+        } => {
+            let credentials = credentials.expect("checked above");
+            let client = reqwest::Client::new();
+            for backup in created {
+                let body = std::fs::read(backup)
+                    .map_err(|e| format!("Failed to read {}: {}", backup.display(), e))?;
+                let key = format!("{}{}", path_prefix, file_name(backup)?);
+                let (url, headers) = s3_signer::sign_put_object(
+                    endpoint_url,
+                    bucket,
+                    &key,
+                    region,
+                    &credentials.username,
+                    &credentials.secret,
+                    &body,
+                    "application/octet-stream",
+                );
+                let mut request = client.put(&url).body(body);
+                for (name, value) in &headers {
+                    request = request.header(name.as_str(), value.as_str());
+                }
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|e| format!("S3 upload of {} failed: {}", key, e))?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(format!(
+                        "S3 upload of {} failed ({}): {}",
+                        key, status, body
+                    ));
+                }
+            }
+            for backup in pruned {
+                let key = format!("{}{}", path_prefix, file_name(backup)?);
+                let (url, headers) = s3_signer::sign_delete_object(
+                    endpoint_url,
+                    bucket,
+                    &key,
+                    region,
+                    &credentials.username,
+                    &credentials.secret,
+                );
+                let mut request = client.delete(&url);
+                for (name, value) in &headers {
+                    request = request.header(name.as_str(), value.as_str());
+                }
+                if let Err(e) = request.send().await {
+                    log::warn!("Failed to delete old backup {} from S3: {}", key, e);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn folder_destination_follows_rotation() {
+        let dir = TempDir::new().unwrap();
+        let local = dir.path().join("backups");
+        std::fs::create_dir_all(&local).unwrap();
+        let old = local.join("Notes_20260101_020000.nous.zip");
+        let new = local.join("Notes_20260102_020000.nous.zip");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&new, "new").unwrap();
+
+        let target = dir.path().join("external");
+        let destination = BackupDestination::Folder {
+            path: target.to_string_lossy().to_string(),
+        };
+        destination.validate().unwrap();
+
+        push_backups(&destination, None, &[old.clone()], &[])
+            .await
+            .unwrap();
+        push_backups(&destination, None, &[new.clone()], &[old.clone()])
+            .await
+            .unwrap();
+
+        assert!(!target.join("Notes_20260101_020000.nous.zip").exists());
+        assert_eq!(
+            std::fs::read_to_string(target.join("Notes_20260102_020000.nous.zip")).unwrap(),
+            "new"
+        );
+    }
+
+    #[test]
+    fn remote_destinations_need_credentials() {
+        let destination = BackupDestination::S3 {
+            endpoint_url: "https://s3.example.com".to_string(),
+            bucket: "backups".to_string(),
+            region: "auto".to_string(),
+            path_prefix: "nous/".to_string(),
+        };
+        assert!(destination.needs_credentials());
+        assert!(BackupDestination::Folder {
+            path: "relative".to_string()
+        }
+        .validate()
+        .is_err());
+    }
+}
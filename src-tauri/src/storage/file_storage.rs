@@ -1,5 +1,7 @@
 use std::fs;
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use chrono::Utc;
 use thiserror::Error;
@@ -9,6 +11,11 @@ use super::models::{
     EditorBlock, EditorData, FileStorageMode, Folder, FolderType, Notebook, NotebookType, Page,
     PageType, Section, SystemPromptMode,
 };
+use super::page_index::{PageIndex, PageIndexCheck};
+use super::page_order::{PageContainer, PageOrder};
+use super::page_stats::{self, NotebookStats, PageStats};
+use crate::activity::{ActivityEntry, ActivityKind, ActivityLog};
+use crate::diagnostics::{self, Category};
 use crate::encryption::{
     decrypt_json, encrypt_json, is_encrypted_file, EncryptedContainer, EncryptionError,
     EncryptionKey,
@@ -66,11 +73,45 @@ pub type Result<T> = std::result::Result<T, StorageError>;
 
 pub struct FileStorage {
     base_path: PathBuf,
+    /// Opened on first use; `None` when the database can't be opened, in
+    /// which case listings fall back to reading the page files
+    page_index: OnceLock<Option<PageIndex>>,
+    activity: ActivityLog,
+}
+
+/// A [`FileStorage`] that may create, change and delete files. Everything
+/// that writes lives here; reads come through `Deref`. Shared storage hands
+/// one out only through [`super::StorageLock::write`], so writers can't skip
+/// the lock; storage nothing else uses is wrapped with
+/// [`FileStorageWriter::new`].
+pub struct FileStorageWriter(FileStorage);
+
+impl FileStorageWriter {
+    pub fn new(storage: FileStorage) -> Self {
+        Self(storage)
+    }
+}
+
+impl Deref for FileStorageWriter {
+    type Target = FileStorage;
+
+    fn deref(&self) -> &FileStorage {
+        &self.0
+    }
 }
 
 impl FileStorage {
     pub fn new(base_path: PathBuf) -> Self {
-        Self { base_path }
+        Self {
+            activity: ActivityLog::new(base_path.clone()),
+            base_path,
+            page_index: OnceLock::new(),
+        }
+    }
+
+    /// The library's activity feed, which page saves here are recorded in
+    pub fn activity(&self) -> &ActivityLog {
+        &self.activity
     }
 
     /// Get the default data directory
@@ -79,14 +120,75 @@ impl FileStorage {
             .map(|p| p.join("nous"))
             .ok_or(StorageError::DataDirNotFound)
     }
+}
 
+impl FileStorageWriter {
     /// Initialize storage directories
     pub fn init(&self) -> Result<()> {
         let notebooks_path = self.base_path.join("notebooks");
         fs::create_dir_all(&notebooks_path)?;
+
+        // Finish (or roll back) page saves interrupted by a crash
+        let recovered = super::journal::recover(&self.base_path);
+        if !recovered.is_empty() {
+            log::info!("Resolved {} leftover journal entries", recovered.len());
+        }
         Ok(())
     }
+}
+
+impl FileStorage {
+    /// Resolve the write-ahead journal and check/repair every notebook's files
+    pub fn verify_integrity(&self) -> Result<super::integrity::IntegrityReport> {
+        let report = super::integrity::verify_library(&self.base_path)?;
+        // Repairs may have replaced or moved page files
+        match self.check_page_index() {
+            Ok(check) if !check.is_clean() => log::info!(
+                "Page index: {} entries refreshed, {} removed",
+                check.reindexed,
+                check.removed
+            ),
+            Ok(_) => {}
+            Err(e) => log::warn!("Page index check failed: {}", e),
+        }
+        Ok(report)
+    }
+
+    fn page_index(&self) -> Option<&PageIndex> {
+        self.page_index
+            .get_or_init(|| {
+                let path = self.base_path.join(".nous").join("page-index.db");
+                PageIndex::open(&path)
+                    .map_err(|e| log::warn!("Page index unavailable at {:?}: {}", path, e))
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// Compare the page index with every notebook's page files and fix what
+    /// differs
+    pub fn check_page_index(&self) -> Result<PageIndexCheck> {
+        let Some(index) = self.page_index() else {
+            return Ok(PageIndexCheck::default());
+        };
+        let mut total = PageIndexCheck::default();
+        for notebook in self.list_notebooks()? {
+            let pages_dir = self.pages_dir(notebook.id);
+            if !pages_dir.exists() {
+                continue;
+            }
+            let check = index
+                .rescan(notebook.id, &pages_dir)
+                .map_err(|e| StorageError::InvalidOperation(e.to_string()))?;
+            total.files += check.files;
+            total.reindexed += check.reindexed;
+            total.removed += check.removed;
+        }
+        Ok(total)
+    }
+}
 
+impl FileStorageWriter {
     /// Crash-atomic, durable write of string content.
     ///
     /// Delegates to [`super::atomic::write_str`]: unique temp file → fsync →
@@ -97,6 +199,33 @@ impl FileStorage {
         Ok(())
     }
 
+    /// Write a page file through the write-ahead journal: the new content is
+    /// recorded first, so a crash before the atomic write lands is replayed
+    /// by [`super::journal::recover`] at the next startup.
+    fn write_page_file(&self, notebook_id: Uuid, page_id: Uuid, content: &str) -> Result<()> {
+        let pages_dir = self.pages_dir(notebook_id);
+        let dir_mtime = self.page_index().and_then(|index| index.dir_mtime(&pages_dir));
+        let entry = super::journal::begin(&self.base_path, notebook_id, page_id, content)?;
+        // Clear the entry even on failure: the caller sees the error, so the
+        // save must not be silently replayed later
+        let result = Self::atomic_write(&self.page_path(notebook_id, page_id), content);
+        super::journal::commit(&entry);
+
+        if result.is_ok() {
+            if let (Some(index), Ok(page)) =
+                (self.page_index(), serde_json::from_str::<Page>(content))
+            {
+                let file_name = format!("{}.json", page_id);
+                if let Err(e) = index.record_write(notebook_id, &pages_dir, &file_name, page, dir_mtime)
+                {
+                    // The next listing rescans the directory instead
+                    log::debug!("Page index not updated for {}: {}", page_id, e);
+                }
+            }
+        }
+        result
+    }
+
     /// Keep a one-deep backup (`<name>.bak`) of an existing non-empty file before
     /// it is overwritten, so a corrupt or semantically-empty write is
     /// recoverable. atomic_write prevents torn writes; this guards against a bad
@@ -110,7 +239,9 @@ impl FileStorage {
             }
         }
     }
+}
 
+impl FileStorage {
     // ===== Notebook Operations =====
 
     /// Get the notebooks directory path
@@ -219,7 +350,9 @@ impl FileStorage {
         let notebook: Notebook = serde_json::from_str(&content)?;
         Ok(notebook)
     }
+}
 
+impl FileStorageWriter {
     pub fn create_notebook(&self, name: String, notebook_type: NotebookType) -> Result<Notebook> {
         let notebook = Notebook::new(name, notebook_type);
 
@@ -287,7 +420,9 @@ impl FileStorage {
 
         Ok(())
     }
+}
 
+impl FileStorage {
     // ===== Page Operations =====
 
     fn page_path(&self, notebook_id: Uuid, page_id: Uuid) -> PathBuf {
@@ -296,6 +431,7 @@ impl FileStorage {
     }
 
     pub fn list_pages(&self, notebook_id: Uuid) -> Result<Vec<Page>> {
+        let _timer = diagnostics::time(Category::Storage, "list_pages");
         let pages_dir = self.pages_dir(notebook_id);
 
         if !pages_dir.exists() {
@@ -330,7 +466,32 @@ impl FileStorage {
         Ok(pages)
     }
 
+    /// Every page in the notebook (file-based pages included) without its
+    /// content, newest first. Served from the page index, so it doesn't read
+    /// the page files unless they changed behind the index's back.
+    pub fn list_page_summaries(&self, notebook_id: Uuid) -> Result<Vec<Page>> {
+        let _timer = diagnostics::time(Category::Storage, "list_page_summaries");
+        let pages_dir = self.pages_dir(notebook_id);
+        if !pages_dir.exists() {
+            return Err(StorageError::NotebookNotFound(notebook_id));
+        }
+
+        if let Some(index) = self.page_index() {
+            match index.list(notebook_id, &pages_dir) {
+                Ok(pages) => return Ok(pages),
+                Err(e) => log::warn!("Page index failed for notebook {}: {}", notebook_id, e),
+            }
+        }
+
+        let mut pages = self.list_all_pages(notebook_id)?;
+        for page in &mut pages {
+            page.content = EditorData::default();
+        }
+        Ok(pages)
+    }
+
     pub fn get_page(&self, notebook_id: Uuid, page_id: Uuid) -> Result<Page> {
+        let _timer = diagnostics::time(Category::Storage, "get_page");
         let page_path = self.page_path(notebook_id, page_id);
 
         if !page_path.exists() {
@@ -342,7 +503,55 @@ impl FileStorage {
         Ok(page)
     }
 
+    /// Writing statistics of a page; `None` for encrypted and file-based
+    /// pages. Pages not saved since stats were added are counted now.
+    pub fn page_stats(&self, notebook_id: Uuid, page_id: Uuid) -> Result<Option<PageStats>> {
+        let page = self.get_page_any_type(notebook_id, page_id)?;
+        Ok(self.stats_of(&page))
+    }
+
+    /// Writing statistics summed over a notebook's pages, trash excluded.
+    /// Stored stats come from the page index; only pages without them are read.
+    pub fn notebook_stats(&self, notebook_id: Uuid) -> Result<NotebookStats> {
+        let _timer = diagnostics::time(Category::Storage, "notebook_stats");
+        let mut totals = NotebookStats::default();
+        for summary in self.list_page_summaries(notebook_id)? {
+            if summary.deleted_at.is_some() {
+                continue;
+            }
+            if summary.is_encrypted() {
+                totals.encrypted_pages += 1;
+                continue;
+            }
+            let stats = match summary.stats {
+                Some(stats) => Some(stats),
+                None => match self.get_page(notebook_id, summary.id) {
+                    Ok(page) => self.stats_of(&page),
+                    Err(_) => None,
+                },
+            };
+            if let Some(stats) = stats {
+                totals.add(&stats);
+            }
+        }
+        Ok(totals)
+    }
+
+    fn stats_of(&self, page: &Page) -> Option<PageStats> {
+        if !page_stats::has_countable_content(page) {
+            return None;
+        }
+        page.stats.or_else(|| {
+            let oplog_file = super::oplog::oplog_path(&self.pages_dir(page.notebook_id), page.id);
+            let revisions = page_stats::count_revisions(&super::oplog::read_entries(&oplog_file));
+            Some(PageStats::of(&page.content, revisions))
+        })
+    }
+}
+
+impl FileStorageWriter {
     pub fn create_page(&self, notebook_id: Uuid, title: String) -> Result<Page> {
+        let _timer = diagnostics::time(Category::Storage, "create_page");
         // Verify notebook exists
         if !self.notebook_dir(notebook_id).exists() {
             return Err(StorageError::NotebookNotFound(notebook_id));
@@ -350,26 +559,36 @@ impl FileStorage {
 
         let page = Page::new(notebook_id, title);
 
-        let page_path = self.page_path(notebook_id, page.id);
         let content = super::content_format::page_to_disk_json(&page)?;
-        Self::atomic_write(&page_path, &content)?;
+        self.write_page_file(notebook_id, page.id, &content)?;
 
         self.oplog_record_create(&page);
+        self.activity
+            .record_one(ActivityEntry::local(notebook_id, page.id, &page.title, ActivityKind::Created));
         Ok(page)
     }
 
     /// Create a page from an existing Page struct (used for import)
-    pub fn create_page_from(&self, page: Page) -> Result<Page> {
+    pub fn create_page_from(&self, mut page: Page) -> Result<Page> {
         // Verify notebook exists
         if !self.notebook_dir(page.notebook_id).exists() {
             return Err(StorageError::NotebookNotFound(page.notebook_id));
         }
 
-        let page_path = self.page_path(page.notebook_id, page.id);
+        if page_stats::has_countable_content(&page) {
+            page.stats = Some(PageStats::of(&page.content, 1));
+        }
+
         let content = super::content_format::page_to_disk_json(&page)?;
-        Self::atomic_write(&page_path, &content)?;
+        self.write_page_file(page.notebook_id, page.id, &content)?;
 
         self.oplog_record_create(&page);
+        self.activity.record_one(ActivityEntry::local(
+            page.notebook_id,
+            page.id,
+            &page.title,
+            ActivityKind::Imported,
+        ));
         Ok(page)
     }
 
@@ -380,14 +599,23 @@ impl FileStorage {
             return Err(StorageError::NotebookNotFound(notebook_id));
         }
 
-        let page_path = self.page_path(notebook_id, page.id);
         let content = super::content_format::page_to_disk_json(page)?;
-        Self::atomic_write(&page_path, &content)?;
+        self.write_page_file(notebook_id, page.id, &content)?;
 
         self.oplog_record_create(page);
         Ok(page.clone())
     }
+}
 
+impl FileStorage {
+    /// The page's latest oplog entry, if it has an oplog
+    pub fn last_page_op(&self, notebook_id: Uuid, page_id: Uuid) -> Option<super::oplog::OplogEntry> {
+        let oplog_file = super::oplog::oplog_path(&self.pages_dir(notebook_id), page_id);
+        super::oplog::read_last_n_entries(&oplog_file, 1).pop()
+    }
+}
+
+impl FileStorageWriter {
     /// Record a "create" oplog entry for a newly created page (best-effort).
     fn oplog_record_create(&self, page: &Page) {
         let pages_dir = self.pages_dir(page.notebook_id);
@@ -420,12 +648,59 @@ impl FileStorage {
     }
 
     pub fn update_page(&self, page: &Page) -> Result<()> {
+        let old_page = self.write_page(page)?;
+
+        let kind = match &old_page {
+            Some(old) if old.deleted_at.is_none() && page.deleted_at.is_some() => {
+                ActivityKind::Deleted
+            }
+            Some(old)
+                if old.title != page.title
+                    || super::oplog::content_hash(&old.content)
+                        != super::oplog::content_hash(&page.content) =>
+            {
+                ActivityKind::Edited
+            }
+            // Moves, tags and other metadata aren't activity
+            Some(_) => return Ok(()),
+            // The old copy was unreadable
+            None => ActivityKind::Edited,
+        };
+        self.activity
+            .record_one(ActivityEntry::local(page.notebook_id, page.id, &page.title, kind));
+        Ok(())
+    }
+
+    /// Save a page that sync pulled or merged. Unlike [`Self::update_page`]
+    /// this isn't recorded as local activity; sync records it under the
+    /// device that made the change.
+    pub fn apply_synced_page(&self, page: &Page) -> Result<()> {
+        if matches!(page.page_type, PageType::Standard | PageType::Sql) {
+            self.write_page(page)?;
+            Ok(())
+        } else {
+            self.update_page_metadata(page)
+        }
+    }
+
+    /// Write a page over its existing file. Returns the page as it was.
+    fn write_page(&self, page: &Page) -> Result<Option<Page>> {
+        let _timer = diagnostics::time(Category::Storage, "update_page");
         let page_path = self.page_path(page.notebook_id, page.id);
 
         if !page_path.exists() {
             return Err(StorageError::PageNotFound(page.id));
         }
 
+        // An encrypted page keeps its content sealed; refusing plaintext here
+        // stops block-level edits from writing it around the encryption.
+        if page.is_encrypted() && !page.content.blocks.is_empty() {
+            return Err(StorageError::InvalidOperation(format!(
+                "Page {} is encrypted; its content must be sealed before saving",
+                page.id
+            )));
+        }
+
         // Read the old page for oplog diffing AND pre-overwrite snapshotting
         // (best-effort — never fail the save because the old copy is unreadable).
         let old_page = fs::read_to_string(&page_path)
@@ -441,7 +716,9 @@ impl FileStorage {
         // state is always recoverable — periodic snapshots alone can miss a wipe
         // and only capture post-overwrite content. Deduped against the latest
         // snapshot so repeated shrinks don't pile up identical copies.
-        if let Some(ref old) = old_page {
+        // Sealing a page empties its content on purpose; snapshotting the
+        // plaintext it replaces would defeat the encryption.
+        if let Some(old) = old_page.as_ref().filter(|_| !page.is_encrypted()) {
             // Cheap block-count check first; only hash the old content (and dedup)
             // when an actual destructive shrink is detected, so normal saves stay
             // cheap.
@@ -472,15 +749,35 @@ impl FileStorage {
             }
         }
 
-        let content = super::content_format::page_to_disk_json(page)?;
-        Self::atomic_write(&page_path, &content)?;
+        let oplog_file = super::oplog::oplog_path(&pages_dir, page.id);
+
+        // Stats are recomputed on every save; callers don't keep them current
+        let stats = page_stats::has_countable_content(page).then(|| {
+            let previous = match old_page.as_ref().and_then(|p| p.stats) {
+                Some(stats) => stats.revision_count,
+                // Saved before stats existed: start from the oplog's history
+                None => page_stats::count_revisions(&super::oplog::read_entries(
+                    &oplog_file,
+                )),
+            };
+            let changed = old_content
+                .as_ref()
+                .map_or(true, |old| super::oplog::content_hash(old) != new_hash);
+            PageStats::of(&page.content, previous + usize::from(changed))
+        });
+        let content = if stats == page.stats {
+            super::content_format::page_to_disk_json(page)?
+        } else {
+            let mut stored = page.clone();
+            stored.stats = stats;
+            super::content_format::page_to_disk_json(&stored)?
+        };
+        self.write_page_file(page.notebook_id, page.id, &content)?;
 
         // Append oplog entry (best-effort — never fail the save for oplog issues).
         // DL-37: read_last_hash + append run under a cross-process lock inside
         // append_chained so the Tauri app and the daemon can't fork the hash
         // chain or tear a line when they write the same page concurrently.
-        let oplog_file = super::oplog::oplog_path(&pages_dir, page.id);
-
         let block_changes = match &old_content {
             Some(old) => super::oplog::diff_blocks(old, &page.content),
             None => Vec::new(),
@@ -531,11 +828,12 @@ impl FileStorage {
             }
         }
 
-        Ok(())
+        Ok(old_page)
     }
 
     /// Soft delete a page by moving it to trash (sets deleted_at timestamp)
     pub fn delete_page(&self, notebook_id: Uuid, page_id: Uuid) -> Result<()> {
+        let _timer = diagnostics::time(Category::Storage, "delete_page");
         let mut page = self.get_page(notebook_id, page_id)?;
 
         // Set deleted_at timestamp
@@ -573,13 +871,17 @@ impl FileStorage {
         self.update_page(&page)?;
         Ok(page)
     }
+}
 
+impl FileStorage {
     /// List all pages in trash for a notebook
     pub fn list_trash(&self, notebook_id: Uuid) -> Result<Vec<Page>> {
         let pages = self.list_pages(notebook_id)?;
         Ok(pages.into_iter().filter(|p| p.deleted_at.is_some()).collect())
     }
+}
 
+impl FileStorageWriter {
     /// Purge pages that have been in trash for more than the specified days
     pub fn purge_old_trash(&self, notebook_id: Uuid, days: i64) -> Result<usize> {
         let cutoff = Utc::now() - chrono::Duration::days(days);
@@ -679,9 +981,8 @@ impl FileStorage {
 
         // Save page in target notebook (atomic — the target is fully written or
         // not at all, so a failure here returns early with the source intact).
-        let target_page_path = self.page_path(target_notebook_id, page.id);
         let content = super::content_format::page_to_disk_json(&page)?;
-        Self::atomic_write(&target_page_path, &content)?;
+        self.write_page_file(target_notebook_id, page.id, &content)?;
 
         // DL-34: carry the page's recovery history (oplog + snapshots) to the
         // target notebook. Without this the moved page starts with empty history
@@ -740,6 +1041,22 @@ impl FileStorage {
         let _ = fs::remove_file(PathBuf::from(src_lock));
     }
 
+    /// Delete a page's recovery history (its `.oplog` and `.snapshots/` dir).
+    /// Used after a page is encrypted so no plaintext copy of its content
+    /// survives in older snapshots or oplog block diffs.
+    pub fn purge_page_history(&self, notebook_id: Uuid, page_id: Uuid) -> Result<()> {
+        let pages_dir = self.pages_dir(notebook_id);
+        let oplog = super::oplog::oplog_path(&pages_dir, page_id);
+        if oplog.exists() {
+            fs::remove_file(&oplog)?;
+        }
+        let snapshots = super::snapshots::snapshots_dir(&pages_dir, page_id);
+        if snapshots.exists() {
+            fs::remove_dir_all(&snapshots)?;
+        }
+        Ok(())
+    }
+
     /// Move a file or directory, preferring an atomic same-filesystem rename and
     /// falling back to copy-then-delete across filesystems.
     fn move_path(src: &Path, dst: &Path) -> std::io::Result<()> {
@@ -775,7 +1092,9 @@ impl FileStorage {
         }
         Ok(())
     }
+}
 
+impl FileStorage {
     /// Extract asset file references from page content
     fn extract_asset_references(&self, content: &EditorData) -> Vec<String> {
         let mut refs = Vec::new();
@@ -873,7 +1192,7 @@ impl FileStorage {
         let notebooks = self.list_notebooks()?;
 
         for notebook in notebooks {
-            let pages = self.list_pages(notebook.id)?;
+            let pages = self.list_page_summaries(notebook.id)?;
             for page in pages {
                 for tag in page.tags {
                     let normalized = tag.to_lowercase().trim().to_string();
@@ -894,7 +1213,7 @@ impl FileStorage {
         let mut tag_counts: std::collections::HashMap<String, usize> =
             std::collections::HashMap::new();
 
-        let pages = self.list_pages(notebook_id)?;
+        let pages = self.list_page_summaries(notebook_id)?;
         for page in pages {
             for tag in page.tags {
                 let normalized = tag.to_lowercase().trim().to_string();
@@ -908,7 +1227,9 @@ impl FileStorage {
         tags.sort_by(|a, b| b.1.cmp(&a.1));
         Ok(tags)
     }
+}
 
+impl FileStorageWriter {
     /// Rename a tag across all pages in a notebook
     pub fn rename_tag(&self, notebook_id: Uuid, old_tag: &str, new_tag: &str) -> Result<usize> {
         let old_normalized = old_tag.to_lowercase().trim().to_string();
@@ -1009,7 +1330,9 @@ impl FileStorage {
 
         Ok(count)
     }
+}
 
+impl FileStorage {
     // ===== Folder Operations =====
 
     /// List all folders in a notebook
@@ -1039,7 +1362,9 @@ impl FileStorage {
             .find(|f| f.id == folder_id)
             .ok_or(StorageError::FolderNotFound(folder_id))
     }
+}
 
+impl FileStorageWriter {
     /// Save all folders for a notebook (public, for use by import commands)
     pub fn save_folders_public(&self, notebook_id: Uuid, folders: &[Folder]) -> Result<()> {
         self.save_folders(notebook_id, folders)
@@ -1210,7 +1535,9 @@ impl FileStorage {
 
         Ok(archive)
     }
+}
 
+impl FileStorage {
     // ===== Section Operations =====
 
     /// List all sections in a notebook
@@ -1241,7 +1568,9 @@ impl FileStorage {
             .find(|s| s.id == section_id)
             .ok_or(StorageError::SectionNotFound(section_id))
     }
+}
 
+impl FileStorageWriter {
     /// Save all sections for a notebook
     fn save_sections(&self, notebook_id: Uuid, sections: &[Section]) -> Result<()> {
         let sections_path = self.sections_path(notebook_id);
@@ -1341,6 +1670,7 @@ impl FileStorage {
                 system_prompt_mode: crate::storage::models::SystemPromptMode::default(),
                 ai_model: None,
                 page_sort_by: None,
+                pinned_page_ids: Vec::new(),
                 position: max_pos + 1 + i as i32,
                 created_at: now,
                 updated_at: now,
@@ -1513,6 +1843,7 @@ impl FileStorage {
             system_prompt_mode: source_section.system_prompt_mode.clone(),
             ai_model: source_section.ai_model.clone(),
             page_sort_by: source_section.page_sort_by.clone(),
+            pinned_page_ids: source_section.pinned_page_ids.clone(),
             position: max_position + 1,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
@@ -1544,6 +1875,9 @@ impl FileStorage {
                 position: source_folder.position,
                 folder_type: source_folder.folder_type.clone(),
                 is_archived: source_folder.is_archived,
+                // Moved pages keep their ids, so the pins still apply
+                page_sort_by: source_folder.page_sort_by.clone(),
+                pinned_page_ids: source_folder.pinned_page_ids.clone(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
@@ -1668,6 +2002,9 @@ impl FileStorage {
                 position: src_folder.position,
                 folder_type: src_folder.folder_type.clone(),
                 is_archived: src_folder.is_archived,
+                // Moved pages keep their ids, so the pins still apply
+                page_sort_by: src_folder.page_sort_by.clone(),
+                pinned_page_ids: src_folder.pinned_page_ids.clone(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
@@ -1839,6 +2176,7 @@ impl FileStorage {
                     system_prompt_mode: SystemPromptMode::default(),
                     ai_model: None,
                     page_sort_by: None,
+                    pinned_page_ids: source.pinned_page_ids.clone(),
                     position: max_pos + 1,
                     created_at: chrono::Utc::now(),
                     updated_at: chrono::Utc::now(),
@@ -1942,6 +2280,7 @@ impl FileStorage {
                 system_prompt_mode: SystemPromptMode::default(),
                 ai_model: None,
                 page_sort_by: None,
+                pinned_page_ids: source.pinned_page_ids.clone(),
                 position: max_pos + 1,
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
@@ -2042,6 +2381,9 @@ impl FileStorage {
                 position: src_folder.position,
                 folder_type: src_folder.folder_type.clone(),
                 is_archived: src_folder.is_archived,
+                // Moved pages keep their ids, so the pins still apply
+                page_sort_by: src_folder.page_sort_by.clone(),
+                pinned_page_ids: src_folder.pinned_page_ids.clone(),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
@@ -2186,7 +2528,9 @@ impl FileStorage {
 
         Ok(())
     }
+}
 
+impl FileStorage {
     // ===== Cover Page Operations =====
 
     /// Get the cover page for a notebook, if it exists
@@ -2194,7 +2538,9 @@ impl FileStorage {
         let pages = self.list_pages(notebook_id)?;
         Ok(pages.into_iter().find(|p| p.is_cover))
     }
+}
 
+impl FileStorageWriter {
     /// Create a cover page for a notebook
     pub fn create_cover_page(&self, notebook_id: Uuid) -> Result<Page> {
         // Check if cover already exists
@@ -2456,6 +2802,71 @@ impl FileStorage {
         }
         Ok(())
     }
+}
+
+impl FileStorage {
+    /// Pages of a notebook in listing order: grouped by folder, section or
+    /// notebook root, each group with its pinned pages first and the rest in
+    /// the group's sort (see `page_order`)
+    pub fn list_pages_in_order(&self, notebook_id: Uuid) -> Result<Vec<Page>> {
+        let mut pages = self.list_pages(notebook_id)?;
+        let notebook = self.get_notebook(notebook_id)?;
+        let folders = self.list_folders(notebook_id)?;
+        let sections = self.list_sections(notebook_id)?;
+        PageOrder::new(&notebook, &folders, &sections).apply(&mut pages);
+        Ok(pages)
+    }
+}
+
+impl FileStorageWriter {
+    /// Pin a page to the top of the folder, section or notebook root it's in,
+    /// or unpin it. Pins of pages that have left the container since are
+    /// dropped along the way.
+    pub fn set_page_pinned(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        pinned: bool,
+    ) -> Result<PageContainer> {
+        let page = self.get_page_any_type(notebook_id, page_id)?;
+        let container = PageContainer::of(&page);
+        let members: std::collections::HashSet<Uuid> = self
+            .list_page_summaries(notebook_id)?
+            .into_iter()
+            .filter(|p| p.deleted_at.is_none() && PageContainer::of(p) == container)
+            .map(|p| p.id)
+            .collect();
+        let repin = |pins: &mut Vec<Uuid>| {
+            pins.retain(|id| *id != page_id && members.contains(id));
+            if pinned {
+                pins.push(page_id);
+            }
+        };
+
+        // Bumping updated_at lets the change win the sync merge
+        let now = Utc::now();
+        match container {
+            PageContainer::Folder(folder_id) => {
+                let mut folder = self.get_folder(notebook_id, folder_id)?;
+                repin(&mut folder.pinned_page_ids);
+                folder.updated_at = now;
+                self.update_folder(&folder)?;
+            }
+            PageContainer::Section(section_id) => {
+                let mut section = self.get_section(notebook_id, section_id)?;
+                repin(&mut section.pinned_page_ids);
+                section.updated_at = now;
+                self.update_section(&section)?;
+            }
+            PageContainer::Root => {
+                let mut notebook = self.get_notebook(notebook_id)?;
+                repin(&mut notebook.pinned_page_ids);
+                notebook.updated_at = now;
+                self.update_notebook(&notebook)?;
+            }
+        }
+        Ok(container)
+    }
 
     /// Reorder folders within a parent
     pub fn reorder_folders(
@@ -2491,7 +2902,9 @@ impl FileStorage {
 
         Ok(())
     }
+}
 
+impl FileStorage {
     // ===== File-Based Page Operations =====
 
     /// Get path for a native text file (markdown, ics)
@@ -2532,7 +2945,9 @@ impl FileStorage {
             _ => None,
         }
     }
+}
 
+impl FileStorageWriter {
     /// Import a file as a new page
     pub fn import_file_as_page(
         &self,
@@ -2553,7 +2968,7 @@ impl FileStorage {
             .and_then(|e| e.to_str())
             .ok_or_else(|| StorageError::UnsupportedFileType("No file extension".to_string()))?;
 
-        let page_type = Self::page_type_from_extension(ext)
+        let page_type = FileStorage::page_type_from_extension(ext)
             .ok_or_else(|| StorageError::UnsupportedFileType(ext.to_string()))?;
 
         // Get title from filename
@@ -2613,6 +3028,9 @@ impl FileStorage {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: now,
             updated_at: now,
         };
@@ -2622,9 +3040,13 @@ impl FileStorage {
         let content = super::content_format::page_to_disk_json(&page)?;
         Self::atomic_write(&metadata_path, &content)?;
 
+        self.activity
+            .record_one(ActivityEntry::local(notebook_id, page.id, &page.title, ActivityKind::Imported));
         Ok(page)
     }
+}
 
+impl FileStorage {
     /// Read content of a native text file (markdown, ics)
     pub fn read_native_file_content(&self, page: &Page) -> Result<String> {
         let source_file = page.source_file.as_ref().ok_or_else(|| {
@@ -2648,7 +3070,9 @@ impl FileStorage {
         let content = fs::read_to_string(&file_path)?;
         Ok(content)
     }
+}
 
+impl FileStorageWriter {
     /// Write content to a native text file (markdown, ics)
     pub fn write_native_file_content(&self, page: &Page, content: &str) -> Result<()> {
         let source_file = page.source_file.as_ref().ok_or_else(|| {
@@ -2720,7 +3144,9 @@ impl FileStorage {
             );
         }
     }
+}
 
+impl FileStorage {
     /// Get the absolute path to a file-based page's content
     pub fn get_file_path(&self, page: &Page) -> Result<PathBuf> {
         let source_file = page.source_file.as_ref().ok_or_else(|| {
@@ -2776,11 +3202,13 @@ impl FileStorage {
             Ok(true) // No last sync, assume modified
         }
     }
+}
 
+impl FileStorageWriter {
     /// Update page metadata (for file-based pages stored in metadata.json)
     pub fn update_page_metadata(&self, page: &Page) -> Result<()> {
-        if page.page_type == PageType::Standard {
-            // Standard pages use the regular page file
+        if matches!(page.page_type, PageType::Standard | PageType::Sql) {
+            // Block-based pages use the regular page file
             return self.update_page(page);
         }
 
@@ -2789,7 +3217,9 @@ impl FileStorage {
         Self::atomic_write(&metadata_path, &content)?;
         Ok(())
     }
+}
 
+impl FileStorage {
     /// Get a page by ID, checking both standard and metadata files
     pub fn get_page_any_type(&self, notebook_id: Uuid, page_id: Uuid) -> Result<Page> {
         // First try standard page path
@@ -2857,7 +3287,9 @@ impl FileStorage {
 
         Ok(pages)
     }
+}
 
+impl FileStorageWriter {
     /// Delete a file-based page and its associated files
     pub fn delete_file_page(&self, notebook_id: Uuid, page_id: Uuid) -> Result<()> {
         let page = self.get_page_any_type(notebook_id, page_id)?;
@@ -2886,7 +3318,9 @@ impl FileStorage {
 
         Ok(())
     }
+}
 
+impl FileStorage {
     // ===== Encrypted Page Operations =====
 
     /// Get a page, automatically decrypting if necessary
@@ -2920,7 +3354,9 @@ impl FileStorage {
             Ok(page)
         }
     }
+}
 
+impl FileStorageWriter {
     /// Update a page, optionally encrypting the content
     ///
     /// If a key is provided, the page will be encrypted before writing.
@@ -2943,7 +3379,7 @@ impl FileStorage {
             super::content_format::page_to_disk_json(page)?
         };
 
-        Self::atomic_write(&page_path, &content)?;
+        self.write_page_file(page.notebook_id, page.id, &content)?;
         Ok(())
     }
 
@@ -2960,7 +3396,6 @@ impl FileStorage {
         }
 
         let page = Page::new(notebook_id, title);
-        let page_path = self.page_path(notebook_id, page.id);
 
         let content = if let Some(key) = key {
             let container = encrypt_json(&page, key)?;
@@ -2969,10 +3404,12 @@ impl FileStorage {
             super::content_format::page_to_disk_json(&page)?
         };
 
-        Self::atomic_write(&page_path, &content)?;
+        self.write_page_file(notebook_id, page.id, &content)?;
         Ok(page)
     }
+}
 
+impl FileStorage {
     /// List pages, automatically decrypting if necessary
     ///
     /// If a key is provided, encrypted pages will be decrypted.
@@ -3033,7 +3470,9 @@ impl FileStorage {
         let content = fs::read_to_string(&page_path)?;
         Ok(is_encrypted_file(&content))
     }
+}
 
+impl FileStorageWriter {
     /// Encrypt all pages in a notebook
     ///
     /// Re-encrypts all pages with the given key. Used when enabling encryption.
@@ -3182,7 +3621,9 @@ impl FileStorage {
 
         Ok(asset_path)
     }
+}
 
+impl FileStorage {
     /// Decrypt an asset file (binary data)
     pub fn decrypt_asset(
         &self,
@@ -3230,7 +3671,7 @@ mod tests {
     #[test]
     fn test_asset_path_generation() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
 
         let notebook_id = Uuid::new_v4();
@@ -3241,6 +3682,31 @@ mod tests {
         assert!(assets_dir.to_string_lossy().contains(&notebook_id.to_string()));
     }
 
+    #[test]
+    fn page_summaries_follow_saves() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let mut page = storage
+            .create_page(notebook.id, "Plans".to_string())
+            .unwrap();
+
+        let summaries = storage.list_page_summaries(notebook.id).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].title, "Plans");
+
+        page.title = "Launch plans".to_string();
+        page.tags = vec!["Q3".to_string()];
+        storage.update_page(&page).unwrap();
+        let summaries = storage.list_page_summaries(notebook.id).unwrap();
+        assert_eq!(summaries[0].title, "Launch plans");
+        assert_eq!(storage.get_notebook_tags(notebook.id).unwrap(), [("q3".to_string(), 1)]);
+        assert!(storage.check_page_index().unwrap().is_clean());
+    }
+
     /// Test that the default data directory on Linux contains hidden directories.
     ///
     /// This documents why the asset protocol scope needs `requireLiteralLeadingDot: false`
@@ -3266,7 +3732,7 @@ mod tests {
     #[test]
     fn test_asset_paths_are_absolute() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
 
         let notebook_id = Uuid::new_v4();
@@ -3300,7 +3766,7 @@ mod tests {
     #[test]
     fn destructive_shrink_snapshots_old_content() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
         let nb = storage
             .create_notebook("N".into(), NotebookType::default())
@@ -3336,13 +3802,47 @@ mod tests {
         assert_eq!(storage.get_page(nb.id, page.id).unwrap().content.blocks.len(), 1);
     }
 
+    /// Sealing a page must not leave its plaintext behind: no pre-overwrite
+    /// snapshot, history purged, and later plaintext saves refused.
+    #[test]
+    fn encrypted_page_keeps_no_plaintext() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let nb = storage
+            .create_notebook("N".into(), NotebookType::default())
+            .unwrap();
+
+        let mut page = storage.create_page(nb.id, "P".into()).unwrap();
+        page.content = blocks(6);
+        storage.update_page(&page).unwrap();
+
+        let key = crate::encryption::EncryptionKey::new([3; 32]);
+        let config = crate::encryption::EncryptionConfig::new_page("s".into(), "h".into(), None);
+        crate::encryption::encrypt_page(&mut page, &key, config).unwrap();
+        storage.update_page(&page).unwrap();
+        storage.purge_page_history(nb.id, page.id).unwrap();
+
+        let pages_dir = storage.pages_dir(nb.id);
+        assert!(!crate::storage::snapshots::snapshots_dir(&pages_dir, page.id).exists());
+        assert!(!crate::storage::oplog::oplog_path(&pages_dir, page.id).exists());
+        let on_disk = fs::read_to_string(storage.page_path(nb.id, page.id)).unwrap();
+        assert!(!on_disk.contains("line 0"));
+
+        page.content = blocks(1);
+        assert!(matches!(
+            storage.update_page(&page),
+            Err(StorageError::InvalidOperation(_))
+        ));
+    }
+
     /// DL-34: a cross-notebook move must carry the page's recovery history
     /// (oplog + snapshots) into the target instead of orphaning it in the source,
     /// so the moved page keeps its version history.
     #[test]
     fn cross_notebook_move_carries_oplog_and_snapshots() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
         let src = storage
             .create_notebook("Src".into(), NotebookType::default())
@@ -3417,7 +3917,7 @@ mod tests {
     #[test]
     fn native_file_write_is_atomic() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
         let nb = storage
             .create_notebook("N".into(), NotebookType::default())
@@ -3453,7 +3953,7 @@ mod tests {
     #[test]
     fn native_write_appends_chained_oplog_entry() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
         let nb = storage
             .create_notebook("N".into(), NotebookType::default())
@@ -3502,7 +4002,7 @@ mod tests {
     #[test]
     fn first_native_write_without_oplog_is_create() {
         let temp_dir = TempDir::new().unwrap();
-        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
         storage.init().unwrap();
         let nb = storage
             .create_notebook("N".into(), NotebookType::default())
@@ -3528,4 +4028,100 @@ mod tests {
         assert_eq!(entries[0].prev_hash, "genesis");
         assert!(super::super::oplog::verify_chain(&oplog_file).is_ok());
     }
+    #[test]
+    fn saves_keep_page_stats_current() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let mut page = storage
+            .create_page(notebook.id, "Plans".to_string())
+            .unwrap();
+        let other = storage
+            .create_page(notebook.id, "Ideas".to_string())
+            .unwrap();
+
+        page.content.blocks.push(EditorBlock {
+            id: "b1".to_string(),
+            block_type: "paragraph".to_string(),
+            data: serde_json::json!({"text": "Ship the release on Friday"}),
+        });
+        storage.update_page(&page).unwrap();
+        let stats = storage.page_stats(notebook.id, page.id).unwrap().unwrap();
+        assert_eq!(stats.word_count, 5);
+        assert_eq!(stats.reading_time_minutes, 1);
+        // Creation and the save that added the paragraph
+        assert_eq!(stats.revision_count, 2);
+
+        // A title change isn't a revision of the content
+        page.title = "Launch plans".to_string();
+        storage.update_page(&page).unwrap();
+        let stored = storage.get_page(notebook.id, page.id).unwrap().stats.unwrap();
+        assert_eq!(stored.revision_count, 2);
+
+        let totals = storage.notebook_stats(notebook.id).unwrap();
+        assert_eq!(totals.page_count, 2);
+        assert_eq!(totals.word_count, 5);
+        // The untouched page has no stored stats and is counted from its oplog
+        assert_eq!(totals.revision_count, 3);
+
+        storage.delete_page(notebook.id, other.id).unwrap();
+        assert_eq!(storage.notebook_stats(notebook.id).unwrap().page_count, 1);
+    }
+
+    #[test]
+    fn pinned_pages_lead_their_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(temp_dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), NotebookType::Standard)
+            .unwrap();
+        let mut folder = storage
+            .create_folder(notebook.id, "Projects".to_string(), None)
+            .unwrap();
+        folder.page_sort_by = Some("name-asc".to_string());
+        storage.update_folder(&folder).unwrap();
+
+        let mut in_folder = Vec::new();
+        for title in ["Beta", "Alpha", "Gamma"] {
+            let mut page = storage.create_page(notebook.id, title.to_string()).unwrap();
+            page.folder_id = Some(folder.id);
+            storage.update_page(&page).unwrap();
+            in_folder.push(page);
+        }
+        let root = storage.create_page(notebook.id, "Inbox".to_string()).unwrap();
+
+        let container = storage.set_page_pinned(notebook.id, in_folder[2].id, true).unwrap();
+        assert_eq!(container, PageContainer::Folder(folder.id));
+        storage.set_page_pinned(notebook.id, root.id, true).unwrap();
+        assert_eq!(storage.get_notebook(notebook.id).unwrap().pinned_page_ids, [root.id]);
+
+        let folder_titles = |storage: &FileStorageWriter| -> Vec<String> {
+            storage
+                .list_pages_in_order(notebook.id)
+                .unwrap()
+                .into_iter()
+                .filter(|p| p.folder_id == Some(folder.id))
+                .map(|p| p.title)
+                .collect()
+        };
+        assert_eq!(folder_titles(&storage), ["Gamma", "Alpha", "Beta"]);
+
+        // A page that leaves the folder loses its pin on the next change
+        let mut moved = storage.get_page(notebook.id, in_folder[2].id).unwrap();
+        moved.folder_id = None;
+        storage.update_page(&moved).unwrap();
+        storage.set_page_pinned(notebook.id, in_folder[0].id, true).unwrap();
+        assert_eq!(
+            storage.get_folder(notebook.id, folder.id).unwrap().pinned_page_ids,
+            [in_folder[0].id]
+        );
+        assert_eq!(folder_titles(&storage), ["Beta", "Alpha"]);
+
+        storage.set_page_pinned(notebook.id, in_folder[0].id, false).unwrap();
+        assert_eq!(folder_titles(&storage), ["Alpha", "Beta"]);
+    }
 }
@@ -0,0 +1,338 @@
+//! Library integrity check and repair.
+//!
+//! Resolves any unfinished page saves left in the [`super::journal`], then
+//! walks every notebook checking that its structural files and page files
+//! still parse. Damaged files are restored from the newest usable source (a
+//! `.json.bak` for folders/sections, the newest readable snapshot for pages).
+//! A page with nothing to restore from is moved aside as `*.corrupt` so it no
+//! longer breaks page listing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::journal::{self, RecoveryOutcome};
+use super::models::{Folder, Notebook, Page, Section};
+use crate::encryption::is_encrypted_file;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairAction {
+    /// An unfinished save was re-applied from the journal
+    JournalReplayed,
+    /// An incomplete journal entry was discarded
+    JournalRolledBack,
+    /// A damaged file was replaced with its `.json.bak`
+    RestoredFromBackup,
+    /// A damaged page was replaced with its newest readable snapshot
+    RestoredFromSnapshot,
+    /// A damaged page with no usable snapshot was moved aside
+    Quarantined,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRepair {
+    pub path: String,
+    pub notebook_id: Option<Uuid>,
+    pub page_id: Option<Uuid>,
+    pub action: RepairAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// A damaged file that could not be repaired automatically
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityProblem {
+    pub path: String,
+    pub notebook_id: Option<Uuid>,
+    pub problem: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub checked_at: DateTime<Utc>,
+    pub notebooks_checked: usize,
+    pub pages_checked: usize,
+    pub repairs: Vec<StorageRepair>,
+    pub problems: Vec<IntegrityProblem>,
+}
+
+impl IntegrityReport {
+    /// True when nothing needed repairing and nothing is left broken
+    pub fn is_clean(&self) -> bool {
+        self.repairs.is_empty() && self.problems.is_empty()
+    }
+}
+
+/// Check (and repair) every notebook in the library at `library_path`
+pub fn verify_library(library_path: &Path) -> std::io::Result<IntegrityReport> {
+    let mut report = IntegrityReport {
+        checked_at: Utc::now(),
+        notebooks_checked: 0,
+        pages_checked: 0,
+        repairs: Vec::new(),
+        problems: Vec::new(),
+    };
+
+    for recovered in journal::recover(library_path) {
+        let action = match recovered.outcome {
+            RecoveryOutcome::Replayed => RepairAction::JournalReplayed,
+            RecoveryOutcome::RolledBack => RepairAction::JournalRolledBack,
+            RecoveryOutcome::AlreadyApplied | RecoveryOutcome::Superseded => continue,
+        };
+        report.repairs.push(StorageRepair {
+            path: journal::journal_dir(library_path)
+                .join(&recovered.entry)
+                .to_string_lossy()
+                .to_string(),
+            notebook_id: recovered.notebook_id,
+            page_id: recovered.page_id,
+            action,
+            detail: None,
+        });
+    }
+
+    let notebooks_dir = library_path.join("notebooks");
+    if !notebooks_dir.exists() {
+        return Ok(report);
+    }
+
+    for entry in fs::read_dir(&notebooks_dir)?.flatten() {
+        let dir = entry.path();
+        let Some(notebook_id) = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| Uuid::parse_str(n).ok())
+        else {
+            continue;
+        };
+        if !dir.is_dir() {
+            continue;
+        }
+        report.notebooks_checked += 1;
+
+        super::atomic::cleanup_temp_files(&dir);
+        check_structural::<Notebook>(&dir.join("notebook.json"), notebook_id, &mut report);
+        check_structural::<Vec<Folder>>(&dir.join("folders.json"), notebook_id, &mut report);
+        check_structural::<Vec<Section>>(&dir.join("sections.json"), notebook_id, &mut report);
+
+        let pages_dir = dir.join("pages");
+        if pages_dir.exists() {
+            check_pages(&pages_dir, notebook_id, &mut report)?;
+        }
+    }
+
+    Ok(report)
+}
+
+fn parses<T: DeserializeOwned>(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .is_some_and(|s| serde_json::from_str::<T>(&s).is_ok())
+}
+
+fn check_structural<T: DeserializeOwned>(
+    path: &Path,
+    notebook_id: Uuid,
+    report: &mut IntegrityReport,
+) {
+    if !path.exists() || parses::<T>(path) {
+        return;
+    }
+
+    let bak = path.with_extension("json.bak");
+    if parses::<T>(&bak) {
+        let restored =
+            fs::read_to_string(&bak).and_then(|content| super::atomic::write_str(path, &content));
+        if restored.is_ok() {
+            report.repairs.push(StorageRepair {
+                path: path.to_string_lossy().to_string(),
+                notebook_id: Some(notebook_id),
+                page_id: None,
+                action: RepairAction::RestoredFromBackup,
+                detail: None,
+            });
+            return;
+        }
+    }
+
+    report.problems.push(IntegrityProblem {
+        path: path.to_string_lossy().to_string(),
+        notebook_id: Some(notebook_id),
+        problem: "File is not valid JSON and no usable backup exists".to_string(),
+    });
+}
+
+/// A page file is healthy if it parses as a page or is an encryption
+/// container (which can't be checked further without the key)
+fn page_is_readable(content: &str) -> bool {
+    if is_encrypted_file(content) {
+        return serde_json::from_str::<serde_json::Value>(content).is_ok();
+    }
+    serde_json::from_str::<Page>(content).is_ok()
+}
+
+fn check_pages(
+    pages_dir: &Path,
+    notebook_id: Uuid,
+    report: &mut IntegrityReport,
+) -> std::io::Result<()> {
+    super::atomic::cleanup_temp_files(pages_dir);
+
+    for entry in fs::read_dir(pages_dir)?.flatten() {
+        let path = entry.path();
+        // Only standard page files ({uuid}.json); skips .metadata.json,
+        // snapshots dirs and oplogs
+        let Some(page_id) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".json"))
+            .and_then(|stem| Uuid::parse_str(stem).ok())
+        else {
+            continue;
+        };
+        if !path.is_file() {
+            continue;
+        }
+        report.pages_checked += 1;
+
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        if page_is_readable(&content) {
+            continue;
+        }
+
+        let quarantined = quarantine(&path)?;
+        let snap_dir = super::snapshots::snapshots_dir(pages_dir, page_id);
+        let restored = super::snapshots::list_snapshots(&snap_dir)
+            .into_iter()
+            .rev()
+            .find_map(|name| super::snapshots::read_snapshot(&snap_dir, &name).map(|p| (name, p)));
+
+        let (action, detail) = match restored {
+            Some((name, page)) => {
+                let json = super::content_format::page_to_disk_json(&page)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+                super::atomic::write_str(&path, &json)?;
+                (
+                    RepairAction::RestoredFromSnapshot,
+                    format!(
+                        "Restored snapshot {}; damaged file kept as {}",
+                        name,
+                        quarantined.display()
+                    ),
+                )
+            }
+            None => (
+                RepairAction::Quarantined,
+                format!(
+                    "No usable snapshot; damaged file moved to {}",
+                    quarantined.display()
+                ),
+            ),
+        };
+        log::warn!("Integrity: page {} {:?}: {}", page_id, action, detail);
+        report.repairs.push(StorageRepair {
+            path: path.to_string_lossy().to_string(),
+            notebook_id: Some(notebook_id),
+            page_id: Some(page_id),
+            action,
+            detail: Some(detail),
+        });
+    }
+
+    Ok(())
+}
+
+/// Move a damaged file out of the way, keeping it for manual recovery
+fn quarantine(path: &Path) -> std::io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = path.with_file_name(format!(
+        "{}.{}.corrupt",
+        name,
+        Utc::now().format("%Y%m%dT%H%M%S")
+    ));
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damaged_files_are_restored_or_quarantined() {
+        let lib = std::env::temp_dir().join(format!("nous_integrity_{}", Uuid::new_v4()));
+        let notebook_id = Uuid::new_v4();
+        let nb_dir = lib.join("notebooks").join(notebook_id.to_string());
+        let pages_dir = nb_dir.join("pages");
+        fs::create_dir_all(&pages_dir).unwrap();
+
+        // Healthy page
+        let healthy = Page::new(notebook_id, "Fine".to_string());
+        fs::write(
+            pages_dir.join(format!("{}.json", healthy.id)),
+            serde_json::to_string(&healthy).unwrap(),
+        )
+        .unwrap();
+
+        // Damaged page with a snapshot to fall back on
+        let snapshotted = Page::new(notebook_id, "Snapshotted".to_string());
+        super::super::snapshots::take_snapshot(&pages_dir, &snapshotted).unwrap();
+        fs::write(
+            pages_dir.join(format!("{}.json", snapshotted.id)),
+            "{\"id\":",
+        )
+        .unwrap();
+
+        // Damaged page with nothing to restore from
+        let lost = Uuid::new_v4();
+        fs::write(pages_dir.join(format!("{}.json", lost)), "").unwrap();
+
+        // Damaged folders.json with a good backup
+        fs::write(nb_dir.join("folders.json"), "[{").unwrap();
+        fs::write(nb_dir.join("folders.json.bak"), "[]").unwrap();
+
+        let report = verify_library(&lib).unwrap();
+        assert_eq!(report.notebooks_checked, 1);
+        assert_eq!(report.pages_checked, 3);
+        assert!(report.problems.is_empty());
+
+        let action_for = |id: Uuid| {
+            report
+                .repairs
+                .iter()
+                .find(|r| r.page_id == Some(id))
+                .map(|r| r.action)
+        };
+        assert_eq!(action_for(healthy.id), None);
+        assert_eq!(
+            action_for(snapshotted.id),
+            Some(RepairAction::RestoredFromSnapshot)
+        );
+        assert_eq!(action_for(lost), Some(RepairAction::Quarantined));
+        assert!(report
+            .repairs
+            .iter()
+            .any(|r| r.action == RepairAction::RestoredFromBackup));
+
+        let restored: Page = serde_json::from_str(
+            &fs::read_to_string(pages_dir.join(format!("{}.json", snapshotted.id))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(restored.title, "Snapshotted");
+        assert!(!pages_dir.join(format!("{}.json", lost)).exists());
+
+        assert!(verify_library(&lib).unwrap().is_clean());
+        let _ = fs::remove_dir_all(&lib);
+    }
+}
@@ -0,0 +1,290 @@
+//! Write-ahead journal for page saves.
+//!
+//! [`super::atomic`] guarantees a page file is never torn, but a crash between
+//! "the user hit save" and the rename still loses the save silently. Every
+//! page write therefore first records the full new content as a journal entry
+//! in `{library}/journal/`, then performs the atomic write, then removes the
+//! entry. An entry that survives to the next startup is a write that may not
+//! have landed, and [`recover`] resolves it:
+//!
+//! - target already holds the entry's content → the write landed; drop entry
+//! - target modified after the entry was created → a later write superseded
+//!   it; drop entry
+//! - otherwise → replay the entry onto the target
+//! - entry unreadable or its content fails its hash/JSON check → roll back
+//!   (drop the entry, keep the target as it was)
+//!
+//! Entries carry the writer's PID and a sequence number, so the app and the
+//! daemon never collide on an entry name.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Process-monotonic counter so concurrent saves never share an entry name.
+static ENTRY_SEQ: AtomicU64 = AtomicU64::new(0);
+
+const ENTRY_SUFFIX: &str = ".wal.json";
+
+/// A pending page write
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// `sha256:` hash of `content`, checked before replay
+    pub content_hash: String,
+    /// Exact bytes destined for the page file
+    pub content: String,
+}
+
+/// What recovery did with one leftover entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryOutcome {
+    /// The write had landed before the crash
+    AlreadyApplied,
+    /// A later write replaced the target, so the entry was stale
+    Superseded,
+    /// The write had not landed and was re-applied
+    Replayed,
+    /// The entry itself was incomplete; the target was left untouched
+    RolledBack,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredEntry {
+    pub entry: String,
+    pub notebook_id: Option<Uuid>,
+    pub page_id: Option<Uuid>,
+    pub outcome: RecoveryOutcome,
+}
+
+/// Get the journal directory for a library
+pub fn journal_dir(library_path: &Path) -> PathBuf {
+    library_path.join("journal")
+}
+
+fn page_file(library_path: &Path, notebook_id: Uuid, page_id: Uuid) -> PathBuf {
+    library_path
+        .join("notebooks")
+        .join(notebook_id.to_string())
+        .join("pages")
+        .join(format!("{}.json", page_id))
+}
+
+/// Record a pending write. Returns the entry path to pass to [`commit`] once
+/// the page file has been written.
+pub fn begin(
+    library_path: &Path,
+    notebook_id: Uuid,
+    page_id: Uuid,
+    content: &str,
+) -> std::io::Result<PathBuf> {
+    let entry = JournalEntry {
+        notebook_id,
+        page_id,
+        created_at: Utc::now(),
+        content_hash: super::oplog::content_hash_str(content),
+        content: content.to_string(),
+    };
+    let json = serde_json::to_string(&entry)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    let seq = ENTRY_SEQ.fetch_add(1, Ordering::Relaxed);
+    let path = journal_dir(library_path).join(format!(
+        "{}.{}.{}{}",
+        page_id,
+        std::process::id(),
+        seq,
+        ENTRY_SUFFIX
+    ));
+    super::atomic::write_str(&path, &json)?;
+    Ok(path)
+}
+
+/// Mark a write as complete (or abandoned) by removing its entry. Best-effort:
+/// a leftover entry is resolved harmlessly by [`recover`].
+pub fn commit(entry_path: &Path) {
+    if let Err(e) = fs::remove_file(entry_path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            log::warn!("Failed to clear journal entry {:?}: {}", entry_path, e);
+        }
+    }
+}
+
+/// Resolve every entry left in the journal by a crash. Oldest entries are
+/// handled first so replays land in write order.
+pub fn recover(library_path: &Path) -> Vec<RecoveredEntry> {
+    let dir = journal_dir(library_path);
+    let Ok(read_dir) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    super::atomic::cleanup_temp_files(&dir);
+
+    let mut entries: Vec<(PathBuf, Option<JournalEntry>)> = read_dir
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(ENTRY_SUFFIX))
+        })
+        .map(|p| {
+            let entry = fs::read_to_string(&p)
+                .ok()
+                .and_then(|s| serde_json::from_str::<JournalEntry>(&s).ok());
+            (p, entry)
+        })
+        .collect();
+    entries.sort_by_key(|(_, e)| e.as_ref().map(|e| e.created_at));
+
+    entries
+        .into_iter()
+        .map(|(path, entry)| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let recovered = match entry {
+                Some(entry) => RecoveredEntry {
+                    entry: name,
+                    notebook_id: Some(entry.notebook_id),
+                    page_id: Some(entry.page_id),
+                    outcome: resolve(library_path, &entry),
+                },
+                None => RecoveredEntry {
+                    entry: name,
+                    notebook_id: None,
+                    page_id: None,
+                    outcome: RecoveryOutcome::RolledBack,
+                },
+            };
+            match recovered.outcome {
+                RecoveryOutcome::Replayed => log::warn!(
+                    "Journal: replayed unfinished save of page {:?}",
+                    recovered.page_id
+                ),
+                RecoveryOutcome::RolledBack => {
+                    log::warn!("Journal: rolled back incomplete entry {}", recovered.entry)
+                }
+                _ => {}
+            }
+            commit(&path);
+            recovered
+        })
+        .collect()
+}
+
+fn resolve(library_path: &Path, entry: &JournalEntry) -> RecoveryOutcome {
+    if super::oplog::content_hash_str(&entry.content) != entry.content_hash
+        || serde_json::from_str::<serde_json::Value>(&entry.content).is_err()
+    {
+        return RecoveryOutcome::RolledBack;
+    }
+
+    let target = page_file(library_path, entry.notebook_id, entry.page_id);
+    if let Ok(current) = fs::read_to_string(&target) {
+        if super::oplog::content_hash_str(&current) == entry.content_hash {
+            return RecoveryOutcome::AlreadyApplied;
+        }
+        let modified = fs::metadata(&target)
+            .and_then(|m| m.modified())
+            .map(DateTime::<Utc>::from);
+        if matches!(modified, Ok(m) if m > entry.created_at) {
+            return RecoveryOutcome::Superseded;
+        }
+    }
+
+    // Never resurrect a page into a notebook that has since been deleted
+    match target.parent() {
+        Some(pages_dir) if pages_dir.exists() => {}
+        _ => return RecoveryOutcome::RolledBack,
+    }
+
+    match super::atomic::write_str(&target, &entry.content) {
+        Ok(()) => RecoveryOutcome::Replayed,
+        Err(e) => {
+            log::error!(
+                "Journal: failed to replay save of page {}: {}",
+                entry.page_id,
+                e
+            );
+            RecoveryOutcome::RolledBack
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library() -> (PathBuf, Uuid) {
+        let dir = std::env::temp_dir().join(format!("nous_journal_{}", Uuid::new_v4()));
+        let notebook_id = Uuid::new_v4();
+        fs::create_dir_all(
+            dir.join("notebooks")
+                .join(notebook_id.to_string())
+                .join("pages"),
+        )
+        .unwrap();
+        (dir, notebook_id)
+    }
+
+    #[test]
+    fn committed_writes_leave_nothing_to_recover() {
+        let (lib, nb) = library();
+        let page = Uuid::new_v4();
+        let entry = begin(&lib, nb, page, "{\"v\":1}").unwrap();
+        super::super::atomic::write_str(&page_file(&lib, nb, page), "{\"v\":1}").unwrap();
+        commit(&entry);
+
+        assert!(recover(&lib).is_empty());
+        let _ = fs::remove_dir_all(&lib);
+    }
+
+    #[test]
+    fn recovery_replays_applies_and_rolls_back() {
+        let (lib, nb) = library();
+
+        // Crash before the rename: target still holds the old content
+        let lost = Uuid::new_v4();
+        fs::write(page_file(&lib, nb, lost), "{\"v\":\"old\"}").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        begin(&lib, nb, lost, "{\"v\":\"new\"}").unwrap();
+
+        // Crash after the rename but before the entry was removed
+        let landed = Uuid::new_v4();
+        begin(&lib, nb, landed, "{\"v\":2}").unwrap();
+        fs::write(page_file(&lib, nb, landed), "{\"v\":2}").unwrap();
+
+        // A torn entry
+        fs::write(journal_dir(&lib).join("x.1.1.wal.json"), "{\"notebookId\":").unwrap();
+
+        let outcomes = recover(&lib);
+        let outcome_for = |id: Uuid| {
+            outcomes
+                .iter()
+                .find(|r| r.page_id == Some(id))
+                .map(|r| r.outcome)
+        };
+        assert_eq!(outcome_for(lost), Some(RecoveryOutcome::Replayed));
+        assert_eq!(outcome_for(landed), Some(RecoveryOutcome::AlreadyApplied));
+        assert!(outcomes
+            .iter()
+            .any(|r| r.page_id.is_none() && r.outcome == RecoveryOutcome::RolledBack));
+
+        assert_eq!(
+            fs::read_to_string(page_file(&lib, nb, lost)).unwrap(),
+            "{\"v\":\"new\"}"
+        );
+        assert!(recover(&lib).is_empty(), "journal is drained");
+        let _ = fs::remove_dir_all(&lib);
+    }
+}
@@ -0,0 +1,144 @@
+//! Shared access to a [`FileStorage`].
+//!
+//! Readers don't wait for each other or for writers: every file the storage
+//! writes lands through [`super::atomic`] (temp file + rename), so a reader
+//! always sees a complete old or new file. Writers are serialized among
+//! themselves so read-modify-write sequences (update a page, rewrite a
+//! folder list) don't lose each other's changes. Only the write guard hands
+//! out the [`FileStorageWriter`] that the methods creating, changing or
+//! deleting files live on, so a read guard can't write; take
+//! [`StorageLock::read`] for everything else — a long `list_all_pages` during
+//! sync then no longer holds up the commands the UI is waiting on.
+//!
+//! Pointing the lock at another library ([`StorageLock::replace`]) is the one
+//! operation that waits for readers as well.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard};
+
+use super::{FileStorage, FileStorageWriter};
+
+pub struct StorageLock {
+    storage: RwLock<FileStorageWriter>,
+    writer: Mutex<()>,
+}
+
+/// A thread panicked while holding the lock
+#[derive(Debug)]
+pub struct StoragePoisoned;
+
+impl fmt::Display for StoragePoisoned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "storage lock poisoned")
+    }
+}
+
+impl std::error::Error for StoragePoisoned {}
+
+pub struct StorageReadGuard<'a> {
+    storage: RwLockReadGuard<'a, FileStorageWriter>,
+}
+
+pub struct StorageWriteGuard<'a> {
+    storage: RwLockReadGuard<'a, FileStorageWriter>,
+    _writer: MutexGuard<'a, ()>,
+}
+
+impl StorageLock {
+    pub fn new(storage: FileStorageWriter) -> Self {
+        Self {
+            storage: RwLock::new(storage),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Access for reading; runs alongside other readers and a writer
+    pub fn read(&self) -> Result<StorageReadGuard<'_>, StoragePoisoned> {
+        let storage = self.storage.read().map_err(|_| StoragePoisoned)?;
+        Ok(StorageReadGuard { storage })
+    }
+
+    /// Access for changing files; waits for other writers only
+    pub fn write(&self) -> Result<StorageWriteGuard<'_>, StoragePoisoned> {
+        let writer = self.writer.lock().map_err(|_| StoragePoisoned)?;
+        let storage = self.storage.read().map_err(|_| StoragePoisoned)?;
+        Ok(StorageWriteGuard {
+            storage,
+            _writer: writer,
+        })
+    }
+
+    /// Swap in a storage for another library, once current readers and
+    /// writers are done
+    pub fn replace(&self, storage: FileStorageWriter) -> Result<(), StoragePoisoned> {
+        let _writer = self.writer.lock().map_err(|_| StoragePoisoned)?;
+        *self.storage.write().map_err(|_| StoragePoisoned)? = storage;
+        Ok(())
+    }
+}
+
+impl Deref for StorageReadGuard<'_> {
+    type Target = FileStorage;
+
+    fn deref(&self) -> &FileStorage {
+        &self.storage
+    }
+}
+
+impl Deref for StorageWriteGuard<'_> {
+    type Target = FileStorageWriter;
+
+    fn deref(&self) -> &FileStorageWriter {
+        &self.storage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn readers_do_not_wait_for_a_writer() {
+        let dir = TempDir::new().unwrap();
+        let lock = Arc::new(StorageLock::new(FileStorageWriter::new(FileStorage::new(
+            dir.path().to_path_buf(),
+        ))));
+        let _writer = lock.write().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let reader = Arc::clone(&lock);
+        thread::spawn(move || {
+            let storage = reader.read().unwrap();
+            tx.send(storage.notebooks_base_dir()).unwrap();
+        });
+        assert_eq!(
+            rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+            dir.path().join("notebooks")
+        );
+    }
+
+    #[test]
+    fn writers_take_turns() {
+        let dir = TempDir::new().unwrap();
+        let lock = Arc::new(StorageLock::new(FileStorageWriter::new(FileStorage::new(
+            dir.path().to_path_buf(),
+        ))));
+        let first = lock.write().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let other = Arc::clone(&lock);
+        thread::spawn(move || {
+            let _second = other.write().unwrap();
+            tx.send(()).unwrap();
+        });
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(first);
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+}
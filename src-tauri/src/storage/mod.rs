@@ -1,12 +1,22 @@
 pub mod atomic;
 pub mod backup;
+pub mod backup_destination;
 pub mod content_format;
 mod file_storage;
 pub mod html_utils;
+pub mod integrity;
+pub mod journal;
+mod lock;
 pub mod migration;
 mod models;
 pub mod oplog;
+pub mod page_index;
+pub mod page_order;
+pub mod page_stats;
 pub mod snapshots;
 
-pub use file_storage::{FileStorage, StorageError};
+pub use file_storage::{FileStorage, FileStorageWriter, StorageError};
+pub use lock::{StorageLock, StoragePoisoned, StorageReadGuard, StorageWriteGuard};
 pub use models::*;
+pub use page_order::{PageContainer, PageOrder, PageSort};
+pub use page_stats::{NotebookStats, PageStats};
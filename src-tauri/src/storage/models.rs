@@ -1,10 +1,14 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::encryption::EncryptionConfig;
+use crate::encryption::{EncryptedContainer, EncryptionConfig};
 use crate::sync::config::SyncConfig;
 
+use super::page_stats::PageStats;
+
 /// Configuration for daily notes in a notebook
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -88,6 +92,9 @@ pub enum PageType {
     Database,
     /// HTML page (from website mirror import)
     Html,
+    /// Block-based page whose code blocks are SQL run over the notebook's
+    /// datasets (see `sql_notebook`)
+    Sql,
 }
 
 impl Default for PageType {
@@ -131,6 +138,9 @@ pub struct Section {
     /// Page sort preference for this section (overrides notebook-level setting)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub page_sort_by: Option<String>,
+    /// Pages pinned to the top of the section's root, in pin order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_page_ids: Vec<Uuid>,
     /// Position for ordering sections
     #[serde(default)]
     pub position: i32,
@@ -151,6 +161,7 @@ impl Section {
             system_prompt_mode: SystemPromptMode::default(),
             ai_model: None,
             page_sort_by: None,
+            pinned_page_ids: Vec::new(),
             position: 0,
             created_at: now,
             updated_at: now,
@@ -183,6 +194,13 @@ pub struct Folder {
     /// Position for ordering within parent
     #[serde(default)]
     pub position: i32,
+    /// Page sort preference for this folder (overrides section and notebook
+    /// settings)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_sort_by: Option<String>,
+    /// Pages pinned to the top of the folder, in pin order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_page_ids: Vec<Uuid>,
     #[serde(default = "chrono::Utc::now")]
     pub created_at: DateTime<Utc>,
     #[serde(default = "chrono::Utc::now")]
@@ -202,6 +220,8 @@ impl Folder {
             is_archived: false,
             color: None,
             position: 0,
+            page_sort_by: None,
+            pinned_page_ids: Vec::new(),
             created_at: now,
             updated_at: now,
         }
@@ -219,6 +239,8 @@ impl Folder {
             is_archived: false,
             color: None,
             position: i32::MAX, // Always last
+            page_sort_by: None,
+            pinned_page_ids: Vec::new(),
             created_at: now,
             updated_at: now,
         }
@@ -254,6 +276,10 @@ pub struct Notebook {
     /// AI model override for this notebook (e.g., "gpt-4o", "claude-sonnet-4-20250514")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ai_model: Option<String>,
+    /// Library AI profile for this notebook (see `ai_profiles`); takes the
+    /// place of `ai_provider`/`ai_model` when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_profile_id: Option<String>,
     /// Sync configuration for this notebook
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sync_config: Option<SyncConfig>,
@@ -269,6 +295,10 @@ pub struct Notebook {
     /// Page sort preference for this notebook (overrides global setting)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub page_sort_by: Option<String>,
+    /// Pages pinned to the top of the notebook's root (pages outside any
+    /// folder or section), in pin order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_page_ids: Vec<Uuid>,
     /// Daily notes configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub daily_notes_config: Option<DailyNotesConfig>,
@@ -278,6 +308,12 @@ pub struct Notebook {
     /// Path to the website mirror directory (for re-scan support)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mirror_path: Option<String>,
+    /// Keep this notebook's pages out of the daily review feed
+    #[serde(default)]
+    pub exclude_from_review: bool,
+    /// Which code block languages can be run, and how (see `code_exec`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_exec: Option<crate::code_exec::CodeExecConfig>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -297,14 +333,18 @@ impl Notebook {
             system_prompt_mode: SystemPromptMode::default(),
             ai_provider: None,
             ai_model: None,
+            ai_profile_id: None,
             sync_config: None,
             encryption_config: None,
             is_pinned: false,
             position: 0,
             page_sort_by: None,
+            pinned_page_ids: Vec::new(),
             daily_notes_config: None,
             cover_image: None,
             mirror_path: None,
+            exclude_from_review: false,
+            code_exec: None,
             created_at: now,
             updated_at: now,
         }
@@ -477,10 +517,30 @@ pub struct Page {
     /// Opaque JSON data for plugin page types
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub plugin_data: Option<serde_json::Value>,
+    /// Free-form key/value metadata (e.g. a summary written by an AI bulk
+    /// operation)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub properties: BTreeMap<String, String>,
+    /// Per-page encryption; while set, `content` is empty on disk
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub encryption: Option<PageEncryption>,
+    /// Writing statistics, recomputed on every save
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub stats: Option<PageStats>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Encryption state of a single page inside an unencrypted notebook. The
+/// title, tags and other metadata stay readable; only the content is sealed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageEncryption {
+    pub config: EncryptionConfig,
+    /// The page's `EditorData`, encrypted with the page key
+    pub sealed_content: EncryptedContainer,
+}
+
 impl Page {
     pub fn new(notebook_id: Uuid, title: String) -> Self {
         let now = Utc::now();
@@ -512,11 +572,22 @@ impl Page {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Check if this page has its own encryption
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption
+            .as_ref()
+            .map(|e| e.config.enabled)
+            .unwrap_or(false)
+    }
+
     pub fn new_in_folder(notebook_id: Uuid, title: String, folder_id: Option<Uuid>) -> Self {
         let mut page = Self::new(notebook_id, title);
         page.folder_id = folder_id;
@@ -0,0 +1,399 @@
+//! Persistent index of page metadata.
+//!
+//! `{library}/.nous/page-index.db` keeps every page file's metadata (the page
+//! without its content) with the file's size and mtime, so listing pages,
+//! sorting them and counting tags read one table instead of opening and
+//! parsing every page file.
+//!
+//! A notebook's rows are trusted while its `pages/` directory still has the
+//! mtime recorded at the last scan: page files are only ever replaced by an
+//! atomic rename, which bumps it. Otherwise the directory is rescanned — each
+//! file is stat'ed and only files whose size or mtime changed are parsed
+//! again. Saves made through `FileStorage` update their row directly and keep
+//! the recorded mtime current, so they don't cause a rescan. The app and the
+//! daemon share the database, so each sees the other's saves.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::models::{EditorData, Page};
+
+#[derive(Error, Debug)]
+pub enum PageIndexError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error in {file}: {source}")]
+    Json {
+        file: String,
+        source: serde_json::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, PageIndexError>;
+
+/// Outcome of a full consistency check
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageIndexCheck {
+    pub files: usize,
+    /// Rows added or refreshed because the file differed from the index
+    pub reindexed: usize,
+    /// Rows dropped because their file is gone
+    pub removed: usize,
+}
+
+impl PageIndexCheck {
+    pub fn is_clean(&self) -> bool {
+        self.reindexed == 0 && self.removed == 0
+    }
+}
+
+pub struct PageIndex {
+    conn: Mutex<Connection>,
+}
+
+/// Size and mtime (nanoseconds since the epoch) of a file or directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    size: i64,
+    mtime: i64,
+}
+
+impl Stamp {
+    fn of(meta: &fs::Metadata) -> Self {
+        let mtime = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as i64)
+            .unwrap_or(0);
+        Self {
+            size: meta.len() as i64,
+            mtime,
+        }
+    }
+}
+
+/// `{id}.json` and `{id}.metadata.json` (file-based pages)
+fn is_page_file(name: &str) -> bool {
+    name.ends_with(".json")
+}
+
+fn mtime_of(pages_dir: &Path) -> Result<i64> {
+    Ok(Stamp::of(&fs::metadata(pages_dir)?).mtime)
+}
+
+/// A page as stored in the index: everything but the content
+fn without_content(mut page: Page) -> Page {
+    page.content = EditorData::default();
+    page
+}
+
+impl PageIndex {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)?;
+        // The app and the daemon open the same file
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS page_files (
+                notebook_id TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                page_id TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                mtime INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                meta TEXT NOT NULL,
+                PRIMARY KEY (notebook_id, file_name)
+            );
+
+            -- mtime of each notebook's pages/ directory at its last scan
+            CREATE TABLE IF NOT EXISTS notebooks (
+                notebook_id TEXT PRIMARY KEY,
+                dir_mtime INTEGER NOT NULL
+            );
+            "#,
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        // A panic mid-statement leaves nothing half-done in SQLite
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Metadata of every page file in `pages_dir`, newest first. A page with
+    /// both a `.json` and a `.metadata.json` file is listed once.
+    pub fn list(&self, notebook_id: Uuid, pages_dir: &Path) -> Result<Vec<Page>> {
+        let current = mtime_of(pages_dir)?;
+        let recorded: Option<i64> = self
+            .conn()
+            .query_row(
+                "SELECT dir_mtime FROM notebooks WHERE notebook_id = ?1",
+                params![notebook_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if recorded != Some(current) {
+            self.rescan(notebook_id, pages_dir)?;
+        }
+
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT file_name, meta FROM page_files WHERE notebook_id = ?1
+             ORDER BY updated_at DESC, file_name",
+        )?;
+        let rows = stmt
+            .query_map(params![notebook_id.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut pages: Vec<Page> = Vec::with_capacity(rows.len());
+        let mut seen = HashSet::new();
+        for (file, meta) in rows {
+            let page: Page = serde_json::from_str(&meta)
+                .map_err(|source| PageIndexError::Json { file, source })?;
+            if seen.insert(page.id) {
+                pages.push(page);
+            }
+        }
+        Ok(pages)
+    }
+
+    /// Bring the notebook's rows in line with its files, whatever the
+    /// directory mtime says
+    pub fn rescan(&self, notebook_id: Uuid, pages_dir: &Path) -> Result<PageIndexCheck> {
+        let nb = notebook_id.to_string();
+        // Taken before reading the directory: a change made during the scan
+        // leaves the mtimes different, so the next listing scans again
+        let scanned_at = mtime_of(pages_dir)?;
+
+        let indexed: HashMap<String, Stamp> = {
+            let conn = self.conn();
+            let mut stmt = conn
+                .prepare("SELECT file_name, size, mtime FROM page_files WHERE notebook_id = ?1")?;
+            let rows = stmt
+                .query_map(params![nb], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        Stamp {
+                            size: row.get(1)?,
+                            mtime: row.get(2)?,
+                        },
+                    ))
+                })?
+                .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+            rows
+        };
+
+        let mut check = PageIndexCheck::default();
+        let mut changed: Vec<(String, Stamp, Page)> = Vec::new();
+        let mut present = HashSet::new();
+        for entry in fs::read_dir(pages_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !is_page_file(&name) {
+                continue;
+            }
+            let meta = entry.metadata()?;
+            if !meta.is_file() {
+                continue;
+            }
+            check.files += 1;
+            let stamp = Stamp::of(&meta);
+            present.insert(name.clone());
+            if indexed.get(&name) == Some(&stamp) {
+                continue;
+            }
+            let content = fs::read_to_string(entry.path())?;
+            let page: Page =
+                serde_json::from_str(&content).map_err(|source| PageIndexError::Json {
+                    file: name.clone(),
+                    source,
+                })?;
+            changed.push((name, stamp, without_content(page)));
+        }
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        for (name, stamp, page) in &changed {
+            upsert(&tx, notebook_id, name, *stamp, page)?;
+        }
+        for name in indexed.keys() {
+            if !present.contains(name) {
+                tx.execute(
+                    "DELETE FROM page_files WHERE notebook_id = ?1 AND file_name = ?2",
+                    params![nb, name],
+                )?;
+                check.removed += 1;
+            }
+        }
+        tx.execute(
+            "INSERT INTO notebooks (notebook_id, dir_mtime) VALUES (?1, ?2)
+             ON CONFLICT(notebook_id) DO UPDATE SET dir_mtime = excluded.dir_mtime",
+            params![nb, scanned_at],
+        )?;
+        tx.commit()?;
+
+        check.reindexed = changed.len();
+        Ok(check)
+    }
+
+    /// Record a page file `FileStorage` just wrote. `dir_mtime_before` is the
+    /// directory's mtime from just before the write: when the index was
+    /// current then, it stays current now.
+    pub fn record_write(
+        &self,
+        notebook_id: Uuid,
+        pages_dir: &Path,
+        file_name: &str,
+        page: Page,
+        dir_mtime_before: Option<i64>,
+    ) -> Result<()> {
+        let stamp = Stamp::of(&fs::metadata(pages_dir.join(file_name))?);
+        let after = mtime_of(pages_dir)?;
+        let nb = notebook_id.to_string();
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        upsert(&tx, notebook_id, file_name, stamp, &without_content(page))?;
+        if let Some(before) = dir_mtime_before {
+            tx.execute(
+                "UPDATE notebooks SET dir_mtime = ?2 WHERE notebook_id = ?1 AND dir_mtime = ?3",
+                params![nb, after, before],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// mtime of `pages_dir`, for [`PageIndex::record_write`]
+    pub fn dir_mtime(&self, pages_dir: &Path) -> Option<i64> {
+        mtime_of(pages_dir).ok()
+    }
+}
+
+fn upsert(
+    conn: &Connection,
+    notebook_id: Uuid,
+    file_name: &str,
+    stamp: Stamp,
+    page: &Page,
+) -> Result<()> {
+    let meta = serde_json::to_string(page).map_err(|source| PageIndexError::Json {
+        file: file_name.to_string(),
+        source,
+    })?;
+    conn.execute(
+        "INSERT INTO page_files (notebook_id, file_name, page_id, size, mtime, updated_at, meta)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(notebook_id, file_name) DO UPDATE SET
+            page_id = excluded.page_id, size = excluded.size, mtime = excluded.mtime,
+            updated_at = excluded.updated_at, meta = excluded.meta",
+        params![
+            notebook_id.to_string(),
+            file_name,
+            page.id.to_string(),
+            stamp.size,
+            stamp.mtime,
+            page.updated_at.timestamp_micros(),
+            meta
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_page(dir: &Path, page: &Page) {
+        fs::write(
+            dir.join(format!("{}.json", page.id)),
+            serde_json::to_string(page).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn lists_from_the_index_and_follows_file_changes() {
+        let dir = TempDir::new().unwrap();
+        let pages_dir = dir.path().join("pages");
+        fs::create_dir_all(&pages_dir).unwrap();
+        let notebook_id = Uuid::new_v4();
+        let index = PageIndex::open(&dir.path().join("index.db")).unwrap();
+
+        let mut page = Page::new(notebook_id, "First".to_string());
+        page.tags = vec!["draft".to_string()];
+        write_page(&pages_dir, &page);
+
+        let listed = index.list(notebook_id, &pages_dir).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].title, "First");
+        assert_eq!(listed[0].tags, ["draft"]);
+        assert!(listed[0].content.blocks.is_empty());
+
+        // Changed outside the index: picked up by the full check
+        page.title = "Renamed".to_string();
+        page.tags.push("longer-so-the-size-changes".to_string());
+        write_page(&pages_dir, &page);
+        let check = index.rescan(notebook_id, &pages_dir).unwrap();
+        assert_eq!(check.reindexed, 1);
+        assert_eq!(
+            index.list(notebook_id, &pages_dir).unwrap()[0].title,
+            "Renamed"
+        );
+
+        fs::remove_file(pages_dir.join(format!("{}.json", page.id))).unwrap();
+        let check = index.rescan(notebook_id, &pages_dir).unwrap();
+        assert_eq!(check.removed, 1);
+        assert!(index.list(notebook_id, &pages_dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn recorded_writes_keep_the_index_current() {
+        let dir = TempDir::new().unwrap();
+        let pages_dir = dir.path().join("pages");
+        fs::create_dir_all(&pages_dir).unwrap();
+        let notebook_id = Uuid::new_v4();
+        let index = PageIndex::open(&dir.path().join("index.db")).unwrap();
+        index.list(notebook_id, &pages_dir).unwrap();
+
+        let page = Page::new(notebook_id, "New".to_string());
+        let before = index.dir_mtime(&pages_dir);
+        write_page(&pages_dir, &page);
+        index
+            .record_write(
+                notebook_id,
+                &pages_dir,
+                &format!("{}.json", page.id),
+                page.clone(),
+                before,
+            )
+            .unwrap();
+
+        let listed = index.list(notebook_id, &pages_dir).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(index.rescan(notebook_id, &pages_dir).unwrap().is_clean());
+    }
+}
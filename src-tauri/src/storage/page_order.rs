@@ -0,0 +1,261 @@
+//! Order of pages within the folder, section or notebook root they're in.
+//!
+//! Each of these containers sorts its pages by its own rule, falling back
+//! from the folder's override to its section's, then the notebook's, then
+//! manual position. Pages the container pins come first, in pin order. Sort
+//! overrides and pins are stored with the container (folders.json,
+//! sections.json, notebook.json), so they sync along with it.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::models::{Folder, Notebook, Page, Section};
+
+/// How pages in a container are sorted; the same values the frontend uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageSort {
+    #[default]
+    Position,
+    NameAsc,
+    NameDesc,
+    /// Most recently updated first
+    Updated,
+    /// Most recently created first
+    Created,
+}
+
+impl PageSort {
+    pub const ALL: [PageSort; 5] = [
+        PageSort::Position,
+        PageSort::NameAsc,
+        PageSort::NameDesc,
+        PageSort::Updated,
+        PageSort::Created,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PageSort::Position => "position",
+            PageSort::NameAsc => "name-asc",
+            PageSort::NameDesc => "name-desc",
+            PageSort::Updated => "updated",
+            PageSort::Created => "created",
+        }
+    }
+
+    fn compare(self, a: &Page, b: &Page) -> Ordering {
+        let by_name = || {
+            a.title
+                .to_lowercase()
+                .cmp(&b.title.to_lowercase())
+                .then_with(|| a.title.cmp(&b.title))
+        };
+        match self {
+            PageSort::Position => a.position.cmp(&b.position),
+            PageSort::NameAsc => by_name(),
+            PageSort::NameDesc => by_name().reverse(),
+            PageSort::Updated => b.updated_at.cmp(&a.updated_at),
+            PageSort::Created => b.created_at.cmp(&a.created_at),
+        }
+    }
+}
+
+/// What a page is listed in, and what keeps its pin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "camelCase")]
+pub enum PageContainer {
+    Folder(Uuid),
+    /// The section's root, outside its folders
+    Section(Uuid),
+    /// The notebook's root, outside any folder or section
+    Root,
+}
+
+impl PageContainer {
+    pub fn of(page: &Page) -> Self {
+        match (page.folder_id, page.section_id) {
+            (Some(folder_id), _) => PageContainer::Folder(folder_id),
+            (None, Some(section_id)) => PageContainer::Section(section_id),
+            (None, None) => PageContainer::Root,
+        }
+    }
+}
+
+/// A notebook's containers, for looking up their sort rules and pins
+pub struct PageOrder<'a> {
+    notebook: &'a Notebook,
+    folders: HashMap<Uuid, &'a Folder>,
+    sections: HashMap<Uuid, &'a Section>,
+}
+
+impl<'a> PageOrder<'a> {
+    pub fn new(notebook: &'a Notebook, folders: &'a [Folder], sections: &'a [Section]) -> Self {
+        Self {
+            notebook,
+            folders: folders.iter().map(|f| (f.id, f)).collect(),
+            sections: sections.iter().map(|s| (s.id, s)).collect(),
+        }
+    }
+
+    /// The sort a container's pages are listed in
+    pub fn sort(&self, container: PageContainer) -> PageSort {
+        let section_id = match container {
+            PageContainer::Folder(id) => {
+                let folder = self.folders.get(&id);
+                if let Some(sort) = folder.and_then(|f| parse_sort(&f.page_sort_by)) {
+                    return sort;
+                }
+                folder.and_then(|f| f.section_id)
+            }
+            PageContainer::Section(id) => Some(id),
+            PageContainer::Root => None,
+        };
+        section_id
+            .and_then(|id| self.sections.get(&id))
+            .and_then(|s| parse_sort(&s.page_sort_by))
+            .or_else(|| parse_sort(&self.notebook.page_sort_by))
+            .unwrap_or_default()
+    }
+
+    /// A container's pinned pages, in pin order
+    pub fn pinned(&self, container: PageContainer) -> &'a [Uuid] {
+        match container {
+            PageContainer::Folder(id) => self
+                .folders
+                .get(&id)
+                .copied()
+                .map_or(&[][..], |f| f.pinned_page_ids.as_slice()),
+            PageContainer::Section(id) => self
+                .sections
+                .get(&id)
+                .copied()
+                .map_or(&[][..], |s| s.pinned_page_ids.as_slice()),
+            PageContainer::Root => &self.notebook.pinned_page_ids,
+        }
+    }
+
+    /// Sort `pages` so each container's pages are together and in the
+    /// container's order. Containers keep the order they first appear in.
+    pub fn apply(&self, pages: &mut Vec<Page>) {
+        let mut container_rank: HashMap<PageContainer, usize> = HashMap::new();
+        let keys: Vec<(usize, usize, PageSort)> = pages
+            .iter()
+            .map(|page| {
+                let container = PageContainer::of(page);
+                let next_rank = container_rank.len();
+                let rank = *container_rank.entry(container).or_insert(next_rank);
+                let pin = self
+                    .pinned(container)
+                    .iter()
+                    .position(|id| *id == page.id)
+                    .unwrap_or(usize::MAX);
+                (rank, pin, self.sort(container))
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..pages.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (rank_a, pin_a, sort) = keys[a];
+            let (rank_b, pin_b, _) = keys[b];
+            rank_a
+                .cmp(&rank_b)
+                .then(pin_a.cmp(&pin_b))
+                .then_with(|| sort.compare(&pages[a], &pages[b]))
+        });
+
+        let mut slots: Vec<Option<Page>> = std::mem::take(pages).into_iter().map(Some).collect();
+        pages.extend(order.into_iter().filter_map(|i| slots[i].take()));
+    }
+}
+
+fn parse_sort(value: &Option<String>) -> Option<PageSort> {
+    value.as_deref().and_then(PageSort::parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::NotebookType;
+
+    fn page(notebook: &Notebook, title: &str, folder_id: Option<Uuid>, position: i32) -> Page {
+        let mut page = Page::new(notebook.id, title.to_string());
+        page.folder_id = folder_id;
+        page.position = position;
+        page
+    }
+
+    fn titles(pages: &[Page]) -> Vec<&str> {
+        pages.iter().map(|p| p.title.as_str()).collect()
+    }
+
+    #[test]
+    fn folder_override_and_pins_order_its_pages() {
+        let mut notebook = Notebook::new("Work".to_string(), NotebookType::Standard);
+        notebook.page_sort_by = Some("name-desc".to_string());
+        let mut folder = Folder::new(notebook.id, "Projects".to_string(), None);
+        folder.page_sort_by = Some("position".to_string());
+
+        let mut pages = vec![
+            page(&notebook, "apple", None, 0),
+            page(&notebook, "Charlie", Some(folder.id), 2),
+            page(&notebook, "banana", None, 1),
+            page(&notebook, "Alpha", Some(folder.id), 1),
+            page(&notebook, "Bravo", Some(folder.id), 0),
+            page(&notebook, "cherry", None, 2),
+        ];
+        folder.pinned_page_ids = vec![pages[1].id];
+        notebook.pinned_page_ids = vec![pages[2].id];
+
+        let folders = [folder];
+        PageOrder::new(&notebook, &folders, &[]).apply(&mut pages);
+        assert_eq!(
+            titles(&pages),
+            ["banana", "cherry", "apple", "Charlie", "Bravo", "Alpha"]
+        );
+    }
+
+    #[test]
+    fn folder_without_override_uses_its_section_sort() {
+        let notebook = Notebook::new("Work".to_string(), NotebookType::Standard);
+        let mut section = Section::new(notebook.id, "Q3".to_string());
+        section.page_sort_by = Some("name-asc".to_string());
+        let mut folder = Folder::new(notebook.id, "Projects".to_string(), None);
+        folder.section_id = Some(section.id);
+        let other = Folder::new(notebook.id, "Misc".to_string(), None);
+
+        let order = PageOrder::new(
+            &notebook,
+            std::slice::from_ref(&folder),
+            std::slice::from_ref(&section),
+        );
+        assert_eq!(
+            order.sort(PageContainer::Folder(folder.id)),
+            PageSort::NameAsc
+        );
+        assert_eq!(
+            order.sort(PageContainer::Section(section.id)),
+            PageSort::NameAsc
+        );
+        // Unknown folders and an unset notebook sort fall back to position
+        assert_eq!(
+            order.sort(PageContainer::Folder(other.id)),
+            PageSort::Position
+        );
+        assert_eq!(order.sort(PageContainer::Root), PageSort::Position);
+    }
+
+    #[test]
+    fn sort_values_round_trip() {
+        for sort in PageSort::ALL {
+            assert_eq!(PageSort::parse(sort.as_str()), Some(sort));
+        }
+        assert_eq!(PageSort::parse("random"), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! Writing statistics for pages.
+//!
+//! Every save recomputes a page's word and character counts and reading time
+//! from its blocks and stores them with the page, so notebook totals can be
+//! summed from the page index without reading any content. The revision
+//! count goes up by one for each save that changed the content; pages saved
+//! before stats existed start from the content changes in their oplog.
+
+use serde::{Deserialize, Serialize};
+
+use super::models::{EditorBlock, EditorData, Page};
+use super::oplog::OplogEntry;
+
+/// Average silent reading speed the reading time is based on
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// Statistics of one page's content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageStats {
+    pub word_count: usize,
+    /// Characters, not counting whitespace
+    pub character_count: usize,
+    /// Minutes, rounded up
+    pub reading_time_minutes: usize,
+    /// Saves that changed the content
+    pub revision_count: usize,
+}
+
+impl PageStats {
+    /// Count the words and characters of `content`
+    pub fn of(content: &EditorData, revision_count: usize) -> Self {
+        let mut text = String::new();
+        for block in &content.blocks {
+            block_text(block, &mut text);
+        }
+        let word_count = text
+            .split_whitespace()
+            .filter(|w| w.chars().any(char::is_alphanumeric))
+            .count();
+        Self {
+            word_count,
+            character_count: text.chars().filter(|c| !c.is_whitespace()).count(),
+            reading_time_minutes: word_count.div_ceil(WORDS_PER_MINUTE),
+            revision_count,
+        }
+    }
+}
+
+/// Totals over a notebook's pages
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookStats {
+    pub page_count: usize,
+    pub word_count: usize,
+    pub character_count: usize,
+    pub reading_time_minutes: usize,
+    pub revision_count: usize,
+    /// Pages left out because their content is encrypted
+    pub encrypted_pages: usize,
+}
+
+impl NotebookStats {
+    pub fn add(&mut self, stats: &PageStats) {
+        self.page_count += 1;
+        self.word_count += stats.word_count;
+        self.character_count += stats.character_count;
+        self.reading_time_minutes += stats.reading_time_minutes;
+        self.revision_count += stats.revision_count;
+    }
+}
+
+/// Whether a page's content is block content that stats can be counted for
+pub fn has_countable_content(page: &Page) -> bool {
+    page.page_type == super::models::PageType::Standard && !page.is_encrypted()
+}
+
+/// Content-changing saves recorded in an oplog: the entries whose content
+/// hash differs from the one before
+pub fn count_revisions(entries: &[OplogEntry]) -> usize {
+    let mut previous: Option<&str> = None;
+    let mut count = 0;
+    for entry in entries {
+        if previous != Some(entry.content_hash.as_str()) {
+            count += 1;
+        }
+        previous = Some(entry.content_hash.as_str());
+    }
+    count
+}
+
+fn block_text(block: &EditorBlock, out: &mut String) {
+    let data = &block.data;
+    match block.block_type.as_str() {
+        "paragraph" | "header" | "quote" | "callout" => {
+            if let Some(text) = data.get("text").and_then(|v| v.as_str()) {
+                push_html(text, out);
+            }
+        }
+        "list" | "checklist" => {
+            if let Some(items) = data.get("items").and_then(|v| v.as_array()) {
+                list_text(items, out);
+            }
+        }
+        "table" => {
+            for row in data
+                .get("content")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+            {
+                for cell in row.as_array().into_iter().flatten() {
+                    if let Some(cell) = cell.as_str() {
+                        push_html(cell, out);
+                    }
+                }
+            }
+        }
+        "code" => {
+            if let Some(code) = data.get("code").and_then(|v| v.as_str()) {
+                out.push_str(code);
+                out.push('\n');
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Items are strings, `{text}` (checklist) or `{content, items}` (nested list)
+fn list_text(items: &[serde_json::Value], out: &mut String) {
+    for item in items {
+        let text = item
+            .as_str()
+            .or_else(|| item.get("text").and_then(|v| v.as_str()))
+            .or_else(|| item.get("content").and_then(|v| v.as_str()));
+        if let Some(text) = text {
+            push_html(text, out);
+        }
+        if let Some(nested) = item.get("items").and_then(|v| v.as_array()) {
+            list_text(nested, out);
+        }
+    }
+}
+
+/// Append the text of inline HTML; line breaks and block tags separate words,
+/// formatting tags don't
+fn push_html(html: &str, out: &mut String) {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    let mut tag = String::new();
+    for ch in html.chars() {
+        if !in_tag {
+            if ch == '<' {
+                in_tag = true;
+                tag.clear();
+            } else {
+                text.push(ch);
+            }
+        } else if ch == '>' {
+            in_tag = false;
+            let name = tag.trim_start_matches('/').to_ascii_lowercase();
+            let name = name.split([' ', '/']).next().unwrap_or("");
+            if matches!(name, "br" | "p" | "div" | "li" | "td" | "tr") {
+                text.push(' ');
+            }
+        } else {
+            tag.push(ch);
+        }
+    }
+    out.push_str(
+        &text
+            .replace("&nbsp;", " ")
+            .replace("&amp;", "&")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\""),
+    );
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn block(block_type: &str, data: serde_json::Value) -> EditorBlock {
+        EditorBlock {
+            id: block_type.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn entry(hash: &str) -> OplogEntry {
+        OplogEntry {
+            ts: chrono::Utc::now(),
+            client_id: "test".to_string(),
+            op: super::super::oplog::OpType::Modify,
+            content_hash: hash.to_string(),
+            prev_hash: String::new(),
+            block_changes: Vec::new(),
+            block_count: 0,
+            git_commit_id: None,
+        }
+    }
+
+    #[test]
+    fn counts_words_across_block_types() {
+        let content = EditorData {
+            blocks: vec![
+                block("header", json!({"text": "Weekly plan", "level": 2})),
+                block(
+                    "paragraph",
+                    json!({"text": "Ship the <b>new</b> re<i>lease</i>&nbsp;today.<br>Now"}),
+                ),
+                block(
+                    "list",
+                    json!({"items": [
+                        {"content": "Write notes", "items": [{"content": "draft", "items": []}]},
+                    ]}),
+                ),
+                block(
+                    "checklist",
+                    json!({"items": [{"text": "Review", "checked": false}]}),
+                ),
+                block(
+                    "table",
+                    json!({"content": [["Owner", "Due"], ["Ana", "Friday"]]}),
+                ),
+                block("image", json!({"caption": "ignored"})),
+                block("delimiter", json!({})),
+                block("paragraph", json!({"text": "— & —"})),
+            ],
+            ..Default::default()
+        };
+        let stats = PageStats::of(&content, 3);
+        // 2 + 6 + 3 + 1 + 4; dashes and ampersands aren't words
+        assert_eq!(stats.word_count, 16);
+        assert_eq!(stats.reading_time_minutes, 1);
+        assert_eq!(stats.revision_count, 3);
+        assert_eq!(
+            stats.character_count,
+            "Weeklyplan".len()
+                + "Shipthenewreleasetoday.Now".len()
+                + "Writenotesdraft".len()
+                + "Review".len()
+                + "OwnerDueAnaFriday".len()
+                + "—&—".chars().count()
+        );
+    }
+
+    #[test]
+    fn empty_page_takes_no_time_to_read() {
+        let stats = PageStats::of(&EditorData::default(), 0);
+        assert_eq!(stats, PageStats::default());
+    }
+
+    #[test]
+    fn reading_time_rounds_up() {
+        let words = vec!["word"; WORDS_PER_MINUTE + 1].join(" ");
+        let content = EditorData {
+            blocks: vec![block("paragraph", json!({ "text": words }))],
+            ..Default::default()
+        };
+        assert_eq!(PageStats::of(&content, 0).reading_time_minutes, 2);
+    }
+
+    #[test]
+    fn revisions_skip_saves_without_content_changes() {
+        let entries = ["a", "b", "b", "c", "a"].map(entry);
+        assert_eq!(count_revisions(&entries), 4);
+        assert_eq!(count_revisions(&[]), 0);
+    }
+}
@@ -357,6 +357,9 @@ mod tests {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
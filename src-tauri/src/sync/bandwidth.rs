@@ -0,0 +1,184 @@
+//! Sync transfer limits and metered-connection detection
+//!
+//! Rate limiting is a simple reservation scheme: each transfer books
+//! `bytes / rate` seconds on a shared timeline and waits until its slot
+//! starts, so concurrent page and asset transfers to the same server share
+//! one budget.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::config::SyncBandwidth;
+
+/// Paces transfers in one direction to a configured rate
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Bytes per second; 0 = unlimited
+    bytes_per_sec: AtomicU64,
+    next_free: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(kbps: Option<u32>) -> Self {
+        let limiter = Self {
+            bytes_per_sec: AtomicU64::new(0),
+            next_free: Mutex::new(Instant::now()),
+        };
+        limiter.set_rate(kbps);
+        limiter
+    }
+
+    pub fn set_rate(&self, kbps: Option<u32>) {
+        let rate = kbps.map(|k| u64::from(k) * 1024).unwrap_or(0);
+        self.bytes_per_sec.store(rate, Ordering::Relaxed);
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.bytes_per_sec.load(Ordering::Relaxed) > 0
+    }
+
+    /// Book `bytes` on the timeline and return how long to wait before
+    /// sending them
+    fn reserve(&self, bytes: u64, now: Instant) -> Duration {
+        let rate = self.bytes_per_sec.load(Ordering::Relaxed);
+        if rate == 0 || bytes == 0 {
+            return Duration::ZERO;
+        }
+        let mut next_free = self.next_free.lock().unwrap();
+        let start = (*next_free).max(now);
+        *next_free = start + Duration::from_secs_f64(bytes as f64 / rate as f64);
+        start - now
+    }
+
+    /// Wait for a slot to transfer `bytes`
+    pub async fn acquire(&self, bytes: u64) {
+        let wait = self.reserve(bytes, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Upload and download limiters for one server
+#[derive(Debug)]
+pub struct Throttle {
+    pub upload: RateLimiter,
+    pub download: RateLimiter,
+}
+
+impl Throttle {
+    pub fn new(bandwidth: &SyncBandwidth) -> Self {
+        Self {
+            upload: RateLimiter::new(bandwidth.max_upload_kbps),
+            download: RateLimiter::new(bandwidth.max_download_kbps),
+        }
+    }
+
+    pub fn update(&self, bandwidth: &SyncBandwidth) {
+        self.upload.set_rate(bandwidth.max_upload_kbps);
+        self.download.set_rate(bandwidth.max_download_kbps);
+    }
+}
+
+/// Whether the active network connection is metered (mobile hotspot,
+/// tethering, a connection the user marked as metered). `None` when the
+/// platform can't tell.
+pub fn is_metered_connection() -> Option<bool> {
+    platform::is_metered()
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    /// NetworkManager's global `Metered` property (NMMetered: 1 = yes,
+    /// 2 = no, 3 = guess yes, 4 = guess no)
+    pub fn is_metered() -> Option<bool> {
+        let output = std::process::Command::new("busctl")
+            .args([
+                "--system",
+                "get-property",
+                "org.freedesktop.NetworkManager",
+                "/org/freedesktop/NetworkManager",
+                "org.freedesktop.NetworkManager",
+                "Metered",
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        // Output looks like "u 4"
+        let value = String::from_utf8_lossy(&output.stdout);
+        match value.split_whitespace().nth(1)? {
+            "1" | "3" => Some(true),
+            "2" | "4" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Networking::Connectivity::{NetworkCostType, NetworkInformation};
+
+    pub fn is_metered() -> Option<bool> {
+        let profile = NetworkInformation::GetInternetConnectionProfile().ok()?;
+        let cost = profile.GetConnectionCost().ok()?;
+        let cost_type = cost.NetworkCostType().ok()?;
+        if cost_type == NetworkCostType::Unknown {
+            return None;
+        }
+        let roaming = cost.Roaming().unwrap_or(false);
+        let over_limit = cost.OverDataLimit().unwrap_or(false);
+        Some(cost_type != NetworkCostType::Unrestricted || roaming || over_limit)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+mod platform {
+    pub fn is_metered() -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_never_waits() {
+        let limiter = RateLimiter::new(None);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(10 * 1024 * 1024, now), Duration::ZERO);
+        assert!(!limiter.is_limited());
+    }
+
+    #[test]
+    fn reservations_queue_behind_each_other() {
+        let limiter = RateLimiter::new(Some(100));
+        let now = Instant::now();
+
+        // 100 KiB at 100 KiB/s books one second
+        assert_eq!(limiter.reserve(100 * 1024, now), Duration::ZERO);
+        let wait = limiter.reserve(50 * 1024, now);
+        assert_eq!(wait, Duration::from_secs(1));
+        let wait = limiter.reserve(1, now);
+        assert_eq!(wait, Duration::from_millis(1500));
+
+        // Idle time isn't banked
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(1024, later), Duration::ZERO);
+    }
+
+    #[test]
+    fn rate_can_be_changed_and_removed() {
+        let throttle = Throttle::new(&SyncBandwidth::default());
+        assert!(!throttle.upload.is_limited());
+        throttle.update(&SyncBandwidth {
+            max_upload_kbps: Some(64),
+            ..Default::default()
+        });
+        assert!(throttle.upload.is_limited());
+        assert!(!throttle.download.is_limited());
+    }
+}
@@ -0,0 +1,222 @@
+//! Read-only browsing of a library on the sync server
+//!
+//! Lists notebooks and pages straight from the remote metadata files
+//! (`notebook-meta.json`, `.sync-manifest.json`, `pages-meta.json`) and
+//! renders single pages from their `.crdt` on demand. Nothing is written
+//! locally, so a new or constrained device can look around a library before
+//! (or instead of) pulling all of it.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::config::{NotebookMeta, PageMeta, SyncManifest};
+use crate::storage::{EditorData, Folder, NotebookType, PageType, Section};
+
+/// A notebook on the server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteNotebook {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub notebook_type: NotebookType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    pub archived: bool,
+    /// Pages listed in the remote manifest
+    pub page_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// The notebook also exists on this device
+    pub local: bool,
+}
+
+impl RemoteNotebook {
+    pub fn new(
+        id: Uuid,
+        meta: Option<NotebookMeta>,
+        manifest: Option<&SyncManifest>,
+        local: bool,
+    ) -> Self {
+        let page_count = manifest.map(|m| m.pages.len()).unwrap_or(0);
+        match meta {
+            Some(meta) => Self {
+                id,
+                name: meta.name,
+                notebook_type: meta.notebook_type,
+                icon: meta.icon,
+                color: meta.color,
+                archived: meta.archived,
+                page_count,
+                updated_at: Some(meta.updated_at),
+                local,
+            },
+            None => Self {
+                id,
+                name: format!("Synced Notebook {}", &id.to_string()[..8]),
+                notebook_type: NotebookType::default(),
+                icon: None,
+                color: None,
+                archived: false,
+                page_count,
+                updated_at: None,
+                local,
+            },
+        }
+    }
+}
+
+/// A page in a remote notebook listing
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePageSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_page_id: Option<Uuid>,
+    pub page_type: PageType,
+    pub is_archived: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// The page also exists on this device
+    pub local: bool,
+}
+
+/// Result of `browse_remote_notebook`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteNotebookListing {
+    pub notebook_id: Uuid,
+    pub pages: Vec<RemotePageSummary>,
+    pub folders: Vec<Folder>,
+    pub sections: Vec<Section>,
+}
+
+/// A page rendered from the server's copy
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePage {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub content: EditorData,
+}
+
+/// Build the page list from `pages-meta.json` and the manifest. Deleted pages
+/// are left out; pages the manifest has but the metadata doesn't (pushed by
+/// a device that hadn't uploaded its metadata yet) are listed untitled.
+pub fn page_summaries(
+    meta: HashMap<Uuid, PageMeta>,
+    manifest: Option<&SyncManifest>,
+    local_ids: &HashSet<Uuid>,
+) -> Vec<RemotePageSummary> {
+    let mut listed: Vec<(i32, RemotePageSummary)> = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (id, page) in meta {
+        seen.insert(id);
+        if page.deleted_at.is_some() {
+            continue;
+        }
+        listed.push((
+            page.position,
+            RemotePageSummary {
+                id,
+                title: page.title,
+                tags: page.tags,
+                folder_id: page.folder_id,
+                section_id: page.section_id,
+                parent_page_id: page.parent_page_id,
+                page_type: page.page_type,
+                is_archived: page.is_archived,
+                updated_at: Some(page.updated_at),
+                local: local_ids.contains(&id),
+            },
+        ));
+    }
+
+    if let Some(manifest) = manifest {
+        for (id, state) in &manifest.pages {
+            if seen.contains(id) {
+                continue;
+            }
+            listed.push((
+                i32::MAX,
+                RemotePageSummary {
+                    id: *id,
+                    title: "Untitled".to_string(),
+                    tags: Vec::new(),
+                    folder_id: None,
+                    section_id: None,
+                    parent_page_id: None,
+                    page_type: PageType::default(),
+                    is_archived: false,
+                    updated_at: Some(state.last_modified),
+                    local: local_ids.contains(id),
+                },
+            ));
+        }
+    }
+
+    listed.sort_by(|(a_pos, a), (b_pos, b)| {
+        a_pos
+            .cmp(b_pos)
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+    });
+    listed.into_iter().map(|(_, page)| page).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::config::PageSyncState;
+
+    fn meta(title: &str, position: i32) -> PageMeta {
+        serde_json::from_value(serde_json::json!({
+            "title": title,
+            "position": position,
+            "createdAt": "2026-01-01T00:00:00Z",
+            "updatedAt": "2026-01-02T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn lists_pages_in_order_without_deleted_ones() {
+        let [first, second, deleted, unlisted, local] = [(); 5].map(|_| Uuid::new_v4());
+        let mut gone = meta("Gone", 0);
+        gone.deleted_at = Some(Utc::now());
+        let pages = HashMap::from([
+            (second, meta("beta", 1)),
+            (first, meta("Alpha", 1)),
+            (local, meta("Zeta", 0)),
+            (deleted, gone),
+        ]);
+
+        let mut manifest = SyncManifest::new(Uuid::new_v4(), "client".to_string());
+        manifest.pages.insert(
+            unlisted,
+            PageSyncState {
+                etag: "e".to_string(),
+                last_modified: Utc::now(),
+                size: 0,
+            },
+        );
+
+        let listed = page_summaries(pages, Some(&manifest), &HashSet::from([local]));
+        let ids: Vec<Uuid> = listed.iter().map(|p| p.id).collect();
+        assert_eq!(ids, [local, first, second, unlisted]);
+        assert!(listed[0].local);
+        assert!(!listed[1].local);
+        assert_eq!(listed[3].title, "Untitled");
+    }
+}
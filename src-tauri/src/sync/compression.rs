@@ -0,0 +1,105 @@
+//! Compression for sync payloads
+//!
+//! Page CRDTs and JSON metadata files can be stored on the server
+//! compressed. The file names don't change: readers recognise zstd and gzip
+//! by their magic bytes and pass anything else through, so remotes written
+//! before compression existed (or by devices with it turned off) still read
+//! fine. Older app versions can't read compressed files, which is why
+//! compression is opt-in per library.
+
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// zstd level: fast, and most of the gain on CRDT/JSON payloads
+const ZSTD_LEVEL: i32 = 3;
+
+/// How sync payloads are compressed on upload
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncCompression {
+    #[default]
+    None,
+    Zstd,
+    Gzip,
+}
+
+/// Remote files that are compressed when compression is on. Assets are
+/// left alone: they are streamed, and usually already compressed.
+pub fn is_compressible(remote_path: &str) -> bool {
+    remote_path.ends_with(".crdt")
+        || remote_path.ends_with(super::delta::DELTA_EXT)
+        || remote_path.ends_with(".json")
+}
+
+/// Compress `data` for upload
+pub fn encode(data: &[u8], compression: SyncCompression) -> std::io::Result<Vec<u8>> {
+    match compression {
+        SyncCompression::None => Ok(data.to_vec()),
+        SyncCompression::Zstd => zstd::encode_all(data, ZSTD_LEVEL),
+        SyncCompression::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+    }
+}
+
+/// Decompress a downloaded payload, passing uncompressed data through
+pub fn decode(data: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    if data.starts_with(&ZSTD_MAGIC) {
+        zstd::decode_all(data.as_slice())
+    } else if data.starts_with(&GZIP_MAGIC) {
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(data.as_slice()).read_to_end(&mut decoded)?;
+        Ok(decoded)
+    } else {
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrips_every_codec() {
+        let payload = serde_json::to_vec(&serde_json::json!({
+            "pages": vec!["the same page title over and over"; 50],
+        }))
+        .unwrap();
+
+        for compression in [
+            SyncCompression::None,
+            SyncCompression::Zstd,
+            SyncCompression::Gzip,
+        ] {
+            let encoded = encode(&payload, compression).unwrap();
+            if compression != SyncCompression::None {
+                assert!(encoded.len() < payload.len() / 4);
+            }
+            assert_eq!(decode(encoded).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn uncompressed_data_passes_through() {
+        // A yrs update and a JSON manifest as written by older versions
+        let crdt = vec![0x01, 0x02, 0xaa, 0x00];
+        let json = br#"{"version":3}"#.to_vec();
+        assert_eq!(decode(crdt.clone()).unwrap(), crdt);
+        assert_eq!(decode(json.clone()).unwrap(), json);
+    }
+
+    #[test]
+    fn only_crdt_and_json_files_are_compressed() {
+        assert!(is_compressible("nb/pages/abc.crdt"));
+        assert!(is_compressible("nb/.sync-manifest.json"));
+        assert!(!is_compressible("nb/assets/photo.png"));
+        assert!(!is_compressible("nb/.sync-sentinel"));
+    }
+}
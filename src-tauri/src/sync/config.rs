@@ -4,6 +4,7 @@ use uuid::Uuid;
 
 use std::collections::HashMap;
 
+use super::compression::SyncCompression;
 use crate::storage::{FileStorageMode, Notebook, NotebookType, Page, PageType, SystemPromptMode};
 
 /// Detected server type for change notification optimization
@@ -69,6 +70,18 @@ pub struct SyncConfig {
     /// See `docs/incident-2026-05-01-webdav-sync-data-loss.md`.
     #[serde(default)]
     pub allow_destructive_sync: bool,
+    /// Transfer limits (copied from the library for library-managed notebooks)
+    #[serde(default)]
+    pub bandwidth: SyncBandwidth,
+    /// Compression for uploaded CRDTs and metadata files
+    #[serde(default)]
+    pub compression: SyncCompression,
+    /// Exchange page CRDT deltas instead of full `.crdt` files
+    #[serde(default)]
+    pub delta_sync: bool,
+    /// Which assets are downloaded during sync
+    #[serde(default)]
+    pub asset_policy: AssetSyncPolicy,
 }
 
 impl Default for SyncConfig {
@@ -84,10 +97,43 @@ impl Default for SyncConfig {
             managed_by_library: None,
             server_type: ServerType::default(),
             allow_destructive_sync: false,
+            bandwidth: SyncBandwidth::default(),
+            compression: SyncCompression::None,
+            delta_sync: false,
+            asset_policy: AssetSyncPolicy::All,
         }
     }
 }
 
+/// Which remote assets a device downloads during sync. Uploads are not
+/// affected: every device still pushes the assets it has.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetSyncPolicy {
+    /// Download every asset
+    #[default]
+    All,
+    /// Download an asset the first time a page that uses it is opened
+    OnDemand,
+    /// Never download assets on this device
+    Never,
+}
+
+/// Transfer limits for sync
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncBandwidth {
+    /// Maximum upload rate in KiB/s (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_upload_kbps: Option<u32>,
+    /// Maximum download rate in KiB/s (None = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_download_kbps: Option<u32>,
+    /// Sync pages but leave assets queued while on a metered connection
+    #[serde(default)]
+    pub skip_assets_on_metered: bool,
+}
+
 /// Authentication type for WebDAV
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -197,6 +243,9 @@ pub struct SyncResult {
     /// Number of assets pulled from remote
     #[serde(default)]
     pub assets_pulled: usize,
+    /// Asset sync was skipped because the connection is metered
+    #[serde(default)]
+    pub assets_deferred: bool,
 }
 
 impl SyncResult {
@@ -217,6 +266,7 @@ impl SyncResult {
             duration_ms,
             assets_pushed,
             assets_pulled,
+            assets_deferred: false,
         }
     }
 
@@ -230,6 +280,7 @@ impl SyncResult {
             duration_ms: 0,
             assets_pushed: 0,
             assets_pulled: 0,
+            assets_deferred: false,
         }
     }
 }
@@ -263,6 +314,17 @@ pub struct LibrarySyncConfig {
     /// Detected server type (for change notification optimization)
     #[serde(default)]
     pub server_type: ServerType,
+    /// Transfer limits, applied to every notebook in the library
+    #[serde(default)]
+    pub bandwidth: SyncBandwidth,
+    /// Compression for uploads. Only turn on once every device runs a
+    /// version that can read compressed files.
+    #[serde(default)]
+    pub compression: SyncCompression,
+    /// Push only the CRDT updates the server is missing instead of whole
+    /// pages. Like compression, older versions don't read delta files.
+    #[serde(default)]
+    pub delta_sync: bool,
 }
 
 /// Input for configuring library-level sync (includes credentials)
@@ -431,12 +493,16 @@ pub struct NotebookMeta {
     pub ai_provider: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ai_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ai_profile_id: Option<String>,
     #[serde(default)]
     pub is_pinned: bool,
     #[serde(default)]
     pub position: i32,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub page_sort_by: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub pinned_page_ids: Vec<Uuid>,
     pub updated_at: DateTime<Utc>,
 }
 
@@ -453,9 +519,11 @@ impl From<&Notebook> for NotebookMeta {
             system_prompt_mode: notebook.system_prompt_mode.clone(),
             ai_provider: notebook.ai_provider.clone(),
             ai_model: notebook.ai_model.clone(),
+            ai_profile_id: notebook.ai_profile_id.clone(),
             is_pinned: notebook.is_pinned,
             position: notebook.position,
             page_sort_by: notebook.page_sort_by.clone(),
+            pinned_page_ids: notebook.pinned_page_ids.clone(),
             updated_at: notebook.updated_at,
         }
     }
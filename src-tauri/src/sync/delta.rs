@@ -0,0 +1,134 @@
+//! Delta sync for page CRDTs
+//!
+//! With delta sync on, a page's `pages/{id}.crdt` on the server is a base
+//! snapshot, and each device uploads only the Yrs updates the server is
+//! missing (the diff against the state vector it last synced) as small
+//! immutable files under `deltas/{page_id}/`. Delta files are never
+//! overwritten, so pushes can't conflict. Once a page has collected enough
+//! deltas, the device that notices folds them into a new base (guarded by
+//! the base's ETag) and deletes the ones it folded in.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Extension of delta files (compressed like `.crdt` when compression is on)
+pub const DELTA_EXT: &str = ".upd";
+
+/// Compact a page once this many deltas have piled up on the server
+pub const COMPACT_AFTER: usize = 20;
+
+/// `{notebook}/deltas`
+pub fn deltas_root(remote_path: &str) -> String {
+    format!("{}/deltas", remote_path)
+}
+
+/// `{notebook}/deltas/{page_id}`
+pub fn page_deltas_dir(remote_path: &str, page_id: Uuid) -> String {
+    format!("{}/{}", deltas_root(remote_path), page_id)
+}
+
+/// Name for a new delta file. The zero-padded timestamp comes first so
+/// names sort in upload order; the client id keeps devices apart.
+pub fn delta_file_name(client_id: &str, at: DateTime<Utc>) -> String {
+    timestamped_file_name(client_id, at, DELTA_EXT)
+}
+
+/// `{millis}-{client}{ext}`, as used for delta and version files
+pub fn timestamped_file_name(client_id: &str, at: DateTime<Utc>, ext: &str) -> String {
+    let client: String = client_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!(
+        "{:013}-{}{}",
+        at.timestamp_millis().max(0),
+        client,
+        ext
+    )
+}
+
+/// Group remote file paths (as listed under [`deltas_root`]) by page. Only
+/// `.../{page_id}/{name}.upd` entries are kept; names come back sorted.
+pub fn group_by_page<'a>(paths: impl IntoIterator<Item = &'a str>) -> HashMap<Uuid, Vec<String>> {
+    let mut pages: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for path in paths {
+        let mut segments = path.trim_end_matches('/').rsplit('/');
+        let (Some(name), Some(dir)) = (segments.next(), segments.next()) else {
+            continue;
+        };
+        if !name.ends_with(DELTA_EXT) {
+            continue;
+        }
+        if let Ok(page_id) = Uuid::parse_str(dir) {
+            pages.entry(page_id).or_default().push(name.to_string());
+        }
+    }
+    for names in pages.values_mut() {
+        names.sort();
+    }
+    pages
+}
+
+/// Remote deltas this device hasn't applied yet, in upload order
+pub fn unapplied<'a>(remote: &'a [String], applied: &[String]) -> Vec<&'a str> {
+    let applied: HashSet<&str> = applied.iter().map(String::as_str).collect();
+    remote
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !applied.contains(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_sort_in_upload_order_and_are_path_safe() {
+        let early = delta_file_name(
+            "my laptop/1-abc",
+            DateTime::from_timestamp(1_800_000_000, 0).unwrap(),
+        );
+        let late = delta_file_name(
+            "desktop-ff",
+            DateTime::from_timestamp(1_800_000_001, 0).unwrap(),
+        );
+        assert_eq!(early, "1800000000000-my_laptop_1-abc.upd");
+        assert!(early < late);
+    }
+
+    #[test]
+    fn groups_listing_by_page() {
+        let page = Uuid::new_v4();
+        let a = format!("/dav/nb/deltas/{}/0000000000002-b.upd", page);
+        let b = format!("/dav/nb/deltas/{}/0000000000001-a.upd", page);
+        let stray = format!("/dav/nb/deltas/{}/notes.txt", page);
+        let grouped = group_by_page([
+            a.as_str(),
+            b.as_str(),
+            stray.as_str(),
+            "/dav/nb/deltas/not-a-page/0000000000003-c.upd",
+        ]);
+
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(
+            grouped[&page],
+            ["0000000000001-a.upd", "0000000000002-b.upd"]
+        );
+    }
+
+    #[test]
+    fn unapplied_skips_known_deltas() {
+        let remote = vec!["1-a.upd".to_string(), "2-b.upd".to_string()];
+        assert_eq!(unapplied(&remote, &["1-a.upd".to_string()]), ["2-b.upd"]);
+        assert!(unapplied(&remote, &remote).is_empty());
+    }
+}
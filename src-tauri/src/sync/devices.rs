@@ -0,0 +1,174 @@
+//! Devices syncing a notebook
+//!
+//! Every device syncs a notebook under its own client id (see
+//! `LocalSyncState::client_id`). The registry in `{notebook}/.devices.json`
+//! on the server maps those ids to a name and platform and records when each
+//! device last synced, so users can see what is syncing and revoke a device
+//! they no longer trust.
+//!
+//! There is no server-side logic to enforce a revocation, so clients do: a
+//! revoked device refuses to sync, and other devices reject a manifest it
+//! wrote after the revocation and ignore its changelog entries from then on.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::config::{Changelog, SyncManifest};
+
+/// Name of the registry file in a notebook's remote directory
+pub const FILE_NAME: &str = ".devices.json";
+
+/// One device that has synced the notebook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDevice {
+    pub client_id: String,
+    pub name: String,
+    /// `std::env::consts::OS` of the device
+    pub platform: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_sync: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// The registry file of a notebook, keyed by client id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRegistry {
+    #[serde(default)]
+    pub devices: HashMap<String, SyncDevice>,
+}
+
+impl DeviceRegistry {
+    /// Record a sync by this device, registering it on its first
+    pub fn record_sync(&mut self, client_id: &str, name: &str, platform: &str, now: DateTime<Utc>) {
+        let device = self
+            .devices
+            .entry(client_id.to_string())
+            .or_insert_with(|| SyncDevice {
+                client_id: client_id.to_string(),
+                name: name.to_string(),
+                platform: platform.to_string(),
+                first_seen: now,
+                last_sync: now,
+                revoked_at: None,
+            });
+        device.name = name.to_string();
+        device.platform = platform.to_string();
+        device.last_sync = now;
+    }
+
+    /// Revoke a device. Returns false if it isn't registered or was already
+    /// revoked.
+    pub fn revoke(&mut self, client_id: &str, now: DateTime<Utc>) -> bool {
+        match self.devices.get_mut(client_id) {
+            Some(device) if device.revoked_at.is_none() => {
+                device.revoked_at = Some(now);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn revoked_at(&self, client_id: &str) -> Option<DateTime<Utc>> {
+        self.devices.get(client_id)?.revoked_at
+    }
+
+    /// Whether a write by `client_id` at `at` came after it was revoked
+    pub fn rejects(&self, client_id: &str, at: DateTime<Utc>) -> bool {
+        self.revoked_at(client_id)
+            .is_some_and(|revoked_at| at >= revoked_at)
+    }
+
+    /// Check that a manifest wasn't written by a revoked device. Fails with
+    /// the device that wrote it if it was.
+    pub fn validate_manifest(&self, manifest: &SyncManifest) -> Result<(), &SyncDevice> {
+        if self.rejects(&manifest.last_client_id, manifest.updated_at) {
+            return Err(&self.devices[&manifest.last_client_id]);
+        }
+        Ok(())
+    }
+
+    /// Drop changelog entries revoked devices made after their revocation.
+    /// Returns how many were dropped.
+    pub fn filter_changelog(&self, changelog: &mut Changelog) -> usize {
+        let before = changelog.entries.len();
+        changelog
+            .entries
+            .retain(|entry| !self.rejects(&entry.client_id, entry.timestamp));
+        before - changelog.entries.len()
+    }
+}
+
+/// A registered device as the frontend sees it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDeviceInfo {
+    #[serde(flatten)]
+    pub device: SyncDevice,
+    /// The device the app is running on
+    pub this_device: bool,
+}
+
+/// The name other devices see for this one
+pub fn device_name() -> String {
+    hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "Unknown device".to_string())
+}
+
+/// The platform recorded for this device
+pub fn platform() -> &'static str {
+    std::env::consts::OS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    use crate::sync::config::ChangeOperation;
+
+    #[test]
+    fn revoked_devices_are_rejected_from_revocation_on() {
+        let start = Utc::now() - Duration::hours(3);
+        let mut registry = DeviceRegistry::default();
+        registry.record_sync("laptop-1", "Laptop", "macos", start);
+        registry.record_sync("phone-1", "Phone", "ios", start);
+        registry.record_sync("laptop-1", "Laptop", "macos", start + Duration::hours(1));
+        assert_eq!(registry.devices["laptop-1"].first_seen, start);
+
+        let revoked = start + Duration::hours(2);
+        assert!(registry.revoke("phone-1", revoked));
+        assert!(!registry.revoke("phone-1", revoked));
+        assert!(!registry.revoke("tablet-1", revoked));
+
+        let notebook_id = Uuid::new_v4();
+        let mut manifest = SyncManifest::new(notebook_id, "phone-1".to_string());
+        manifest.updated_at = start + Duration::hours(1);
+        assert!(registry.validate_manifest(&manifest).is_ok());
+        manifest.updated_at = revoked + Duration::minutes(5);
+        assert_eq!(
+            registry.validate_manifest(&manifest).unwrap_err().name,
+            "Phone"
+        );
+
+        let mut changelog = Changelog::new(notebook_id);
+        changelog.append(
+            "phone-1".to_string(),
+            ChangeOperation::Updated,
+            Uuid::new_v4(),
+        );
+        changelog.append(
+            "laptop-1".to_string(),
+            ChangeOperation::Updated,
+            Uuid::new_v4(),
+        );
+        assert_eq!(registry.filter_changelog(&mut changelog), 1);
+        assert_eq!(changelog.entries[0].client_id, "laptop-1");
+    }
+}
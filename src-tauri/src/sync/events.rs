@@ -3,9 +3,10 @@
 //! Decouples sync from Tauri so it can run in the daemon (headless) context.
 
 use super::manager::{
-    SyncConflictDetected, SyncContactsUpdated, SyncEnergyUpdated, SyncGoalsUpdated,
-    SyncInboxUpdated, SyncPagesUpdated, SyncProgress,
+    SyncActivity, SyncConflictDetected, SyncContactsUpdated, SyncEnergyUpdated, SyncGoalsUpdated,
+    SyncInboxUpdated, SyncLibraryDocsUpdated, SyncPagesUpdated, SyncProgress,
 };
+use uuid::Uuid;
 
 /// Trait for emitting sync events to a frontend or log sink.
 pub trait SyncEventEmitter: Send + Sync {
@@ -16,9 +17,19 @@ pub trait SyncEventEmitter: Send + Sync {
     fn emit_sync_inbox_updated(&self, payload: &SyncInboxUpdated);
     fn emit_sync_contacts_updated(&self, payload: &SyncContactsUpdated);
     fn emit_sync_energy_updated(&self, payload: &SyncEnergyUpdated);
+    fn emit_sync_library_docs_updated(&self, payload: &SyncLibraryDocsUpdated);
+    /// Emitted when sync pulls scratchpad edits from another device
+    fn emit_sync_scratchpad_updated(&self, library_id: Uuid);
+    /// Emitted when sync finds page locks taken, released or expired
+    fn emit_sync_page_locks_updated(&self, notebook_id: Uuid);
+    /// Emitted when sync records changes other devices made in the
+    /// activity feed
+    fn emit_activity_recorded(&self, notebook_id: Uuid, entries: usize);
     /// Emitted when the destructive-sync guard pauses a page (merge/delete
     /// refused). The frontend surfaces this as a toast/banner.
     fn emit_sync_conflict(&self, payload: &SyncConflictDetected);
+    /// Emitted when overall activity changes (idle/syncing/error/paused)
+    fn emit_sync_activity(&self, _activity: &SyncActivity) {}
 }
 
 /// Emitter that sends events via Tauri's `AppHandle` (GUI context).
@@ -38,6 +49,15 @@ impl SyncEventEmitter for TauriEmitter {
         let _ = self.app_handle.emit("sync-progress", payload);
     }
 
+    fn emit_sync_activity(&self, activity: &SyncActivity) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit("sync-activity", activity);
+        crate::tray::set_sync_activity(&self.app_handle, activity);
+        if let SyncActivity::Error { message } = activity {
+            crate::notifications::notify_sync_error(&self.app_handle, message);
+        }
+    }
+
     fn emit_sync_pages_updated(&self, payload: &SyncPagesUpdated) {
         use tauri::Emitter;
         let _ = self.app_handle.emit("sync-pages-updated", payload);
@@ -71,6 +91,35 @@ impl SyncEventEmitter for TauriEmitter {
         let _ = self.app_handle.emit("sync-energy-updated", payload);
     }
 
+    fn emit_sync_library_docs_updated(&self, payload: &SyncLibraryDocsUpdated) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit("sync-library-docs-updated", payload);
+    }
+
+    fn emit_sync_scratchpad_updated(&self, library_id: Uuid) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit(
+            "sync-scratchpad-updated",
+            serde_json::json!({ "libraryId": library_id }),
+        );
+    }
+
+    fn emit_sync_page_locks_updated(&self, notebook_id: Uuid) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit(
+            "sync-page-locks-updated",
+            serde_json::json!({ "notebookId": notebook_id }),
+        );
+    }
+
+    fn emit_activity_recorded(&self, notebook_id: Uuid, entries: usize) {
+        use tauri::Emitter;
+        let _ = self.app_handle.emit(
+            "activity-recorded",
+            serde_json::json!({ "notebookId": notebook_id, "entries": entries }),
+        );
+    }
+
     fn emit_sync_conflict(&self, payload: &SyncConflictDetected) {
         use tauri::Emitter;
         let _ = self.app_handle.emit("sync-conflict", payload);
@@ -134,6 +183,26 @@ impl SyncEventEmitter for LogEmitter {
         );
     }
 
+    fn emit_sync_library_docs_updated(&self, payload: &SyncLibraryDocsUpdated) {
+        log::info!(
+            "sync-library-docs-updated: actions_changed={} settings_changed={}",
+            payload.actions_changed,
+            payload.settings_changed,
+        );
+    }
+
+    fn emit_sync_scratchpad_updated(&self, library_id: Uuid) {
+        log::info!("sync-scratchpad-updated: library={}", library_id);
+    }
+
+    fn emit_sync_page_locks_updated(&self, notebook_id: Uuid) {
+        log::info!("sync-page-locks-updated: notebook={}", notebook_id);
+    }
+
+    fn emit_activity_recorded(&self, notebook_id: Uuid, entries: usize) {
+        log::info!("activity-recorded: notebook={} entries={}", notebook_id, entries);
+    }
+
     fn emit_sync_conflict(&self, payload: &SyncConflictDetected) {
         log::warn!(
             "sync-conflict: notebook={} page={} {}→{} blocks kind={} preserved={:?}",
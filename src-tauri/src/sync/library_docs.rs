@@ -0,0 +1,281 @@
+//! Library-level JSON documents
+//!
+//! Stores that aren't notebooks (custom actions, library settings, and later
+//! templates or saved searches) sync as collections of small JSON documents.
+//! Each collection is one file on the server, `{library}/library/{name}.json`,
+//! and documents merge last-writer-wins on `updated_at`. Deletions travel as
+//! tombstones so a device that still has the document drops it instead of
+//! pushing it back.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Format of the collection files. Devices leave collections written by a
+/// newer format alone.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Tombstones older than this are dropped from the remote file
+const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+/// One document in a collection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedDoc {
+    pub id: String,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl SyncedDoc {
+    pub fn new(id: impl Into<String>, updated_at: DateTime<Utc>, data: serde_json::Value) -> Self {
+        Self {
+            id: id.into(),
+            updated_at,
+            deleted: false,
+            data,
+        }
+    }
+
+    pub fn tombstone(id: impl Into<String>, deleted_at: DateTime<Utc>) -> Self {
+        Self {
+            id: id.into(),
+            updated_at: deleted_at,
+            deleted: true,
+            data: serde_json::Value::Null,
+        }
+    }
+}
+
+/// Contents of a collection file on the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocCollection {
+    pub version: u32,
+    pub docs: Vec<SyncedDoc>,
+}
+
+impl Default for DocCollection {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            docs: Vec::new(),
+        }
+    }
+}
+
+/// Outcome of merging a device's documents with the server's
+#[derive(Debug, Default)]
+pub struct LwwMerge {
+    /// Everything that belongs in the remote file, tombstones included
+    pub docs: Vec<SyncedDoc>,
+    /// Remote documents (or tombstones) that won over the local copy
+    pub pull: Vec<SyncedDoc>,
+    /// The remote file is missing something this device has
+    pub push: bool,
+}
+
+/// Merge `local` (live documents plus tombstones for local deletions) with
+/// `remote`. The newer `updated_at` wins; on a tie a deletion wins.
+pub fn merge(local: &[SyncedDoc], remote: &[SyncedDoc], now: DateTime<Utc>) -> LwwMerge {
+    let cutoff = now - Duration::days(TOMBSTONE_RETENTION_DAYS);
+    let remote_map: HashMap<&str, &SyncedDoc> =
+        remote.iter().map(|doc| (doc.id.as_str(), doc)).collect();
+    let mut merged: BTreeMap<String, SyncedDoc> = remote
+        .iter()
+        .map(|doc| (doc.id.clone(), doc.clone()))
+        .collect();
+
+    let mut result = LwwMerge::default();
+    for doc in local {
+        match remote_map.get(doc.id.as_str()) {
+            None => {
+                // A tombstone for something the server never had is noise
+                if !doc.deleted {
+                    merged.insert(doc.id.clone(), doc.clone());
+                    result.push = true;
+                }
+            }
+            Some(remote_doc) => {
+                let remote_wins = remote_doc.updated_at > doc.updated_at
+                    || (remote_doc.updated_at == doc.updated_at
+                        && remote_doc.deleted
+                        && !doc.deleted);
+                if remote_wins {
+                    result.pull.push((*remote_doc).clone());
+                } else if *remote_doc != doc {
+                    merged.insert(doc.id.clone(), doc.clone());
+                    result.push = true;
+                }
+            }
+        }
+    }
+
+    // Remote documents this device has never seen
+    let local_ids: HashSet<&str> = local.iter().map(|doc| doc.id.as_str()).collect();
+    result.pull.extend(
+        remote
+            .iter()
+            .filter(|doc| !doc.deleted && !local_ids.contains(doc.id.as_str()))
+            .cloned(),
+    );
+
+    let before = merged.len();
+    merged.retain(|_, doc| !doc.deleted || doc.updated_at > cutoff);
+    if merged.len() != before {
+        result.push = true;
+    }
+
+    result.docs = merged.into_values().collect();
+    result
+}
+
+/// Ids of the live documents each collection had after its last sync, so a
+/// document missing locally can be told apart from one not yet pulled
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDocsState {
+    #[serde(default)]
+    pub synced_ids: HashMap<String, HashSet<String>>,
+}
+
+impl LibraryDocsState {
+    /// `{library}/sync/library_docs.json`
+    pub fn path(library_path: &Path) -> PathBuf {
+        library_path.join("sync").join("library_docs.json")
+    }
+
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+
+    /// Add tombstones for documents synced before but gone from `live`
+    pub fn with_tombstones(
+        &self,
+        collection: &str,
+        mut live: Vec<SyncedDoc>,
+        now: DateTime<Utc>,
+    ) -> Vec<SyncedDoc> {
+        if let Some(previous) = self.synced_ids.get(collection) {
+            let present: HashSet<String> = live.iter().map(|doc| doc.id.clone()).collect();
+            live.extend(
+                previous
+                    .iter()
+                    .filter(|id| !present.contains(*id))
+                    .map(|id| SyncedDoc::tombstone(id.clone(), now)),
+            );
+        }
+        live
+    }
+
+    pub fn record(&mut self, collection: &str, docs: &[SyncedDoc]) {
+        let live = docs
+            .iter()
+            .filter(|doc| !doc.deleted)
+            .map(|doc| doc.id.clone())
+            .collect();
+        self.synced_ids.insert(collection.to_string(), live);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_800_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn newer_side_wins() {
+        let local = vec![
+            SyncedDoc::new("a", at(10), json!("local a")),
+            SyncedDoc::new("b", at(10), json!("local b")),
+            SyncedDoc::new("c", at(10), json!("local only")),
+        ];
+        let remote = vec![
+            SyncedDoc::new("a", at(20), json!("remote a")),
+            SyncedDoc::new("b", at(5), json!("remote b")),
+            SyncedDoc::new("d", at(5), json!("remote only")),
+        ];
+
+        let merged = merge(&local, &remote, at(30));
+        let pulled: Vec<&str> = merged.pull.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(pulled, ["a", "d"]);
+        assert!(merged.push);
+
+        let data: HashMap<&str, &serde_json::Value> = merged
+            .docs
+            .iter()
+            .map(|d| (d.id.as_str(), &d.data))
+            .collect();
+        assert_eq!(data["a"], &json!("remote a"));
+        assert_eq!(data["b"], &json!("local b"));
+        assert_eq!(data["c"], &json!("local only"));
+        assert_eq!(data["d"], &json!("remote only"));
+    }
+
+    #[test]
+    fn identical_sides_need_no_push() {
+        let docs = vec![SyncedDoc::new("a", at(10), json!({"x": 1}))];
+        let merged = merge(&docs, &docs, at(20));
+        assert!(!merged.push);
+        assert!(merged.pull.is_empty());
+    }
+
+    #[test]
+    fn deletions_propagate_both_ways() {
+        let mut state = LibraryDocsState::default();
+        state.record(
+            "actions",
+            &[
+                SyncedDoc::new("gone", at(0), json!(1)),
+                SyncedDoc::new("kept", at(0), json!(2)),
+            ],
+        );
+
+        // Deleted here since the last sync
+        let local = state.with_tombstones(
+            "actions",
+            vec![SyncedDoc::new("kept", at(0), json!(2))],
+            at(50),
+        );
+        let remote = vec![
+            SyncedDoc::new("gone", at(0), json!(1)),
+            SyncedDoc::new("kept", at(0), json!(2)),
+        ];
+        let merged = merge(&local, &remote, at(60));
+        assert!(merged.push);
+        assert!(merged.docs.iter().any(|d| d.id == "gone" && d.deleted));
+
+        // Another device that still has it receives the tombstone
+        let other = vec![SyncedDoc::new("gone", at(0), json!(1))];
+        let merged = merge(&other, &merged.docs, at(70));
+        assert_eq!(merged.pull.len(), 1);
+        assert!(merged.pull[0].deleted);
+    }
+
+    #[test]
+    fn old_tombstones_are_pruned() {
+        let remote = vec![SyncedDoc::tombstone("old", at(0))];
+        let merged = merge(&[], &remote, at(0) + Duration::days(91));
+        assert!(merged.docs.is_empty());
+        assert!(merged.push);
+    }
+}
@@ -0,0 +1,182 @@
+//! Advisory page locks
+//!
+//! Pages merge through CRDTs, so two devices editing the same page never
+//! lose text, but the result can still be a mess. A device can claim a page
+//! before a long edit; the claim is stored on the server in
+//! `{notebook}/.page-locks.json` next to the manifest, and other devices pick
+//! it up on their next sync and show who holds it and for how long. Each
+//! device keeps the locks it last saw in `{notebook}/sync/page_locks.json`.
+//!
+//! Locks are advisory: nothing stops a sync from pushing a locked page. A
+//! lock expires [`LOCK_TTL_MINUTES`] after it was last taken, so a device
+//! that goes offline mid-edit doesn't leave the page claimed forever; the
+//! holder takes it again to keep it.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Name of the lock file in a notebook's remote directory
+pub const FILE_NAME: &str = ".page-locks.json";
+
+/// How long a lock lasts without being taken again
+pub const LOCK_TTL_MINUTES: i64 = 30;
+
+/// A claim on a page by one device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageLock {
+    pub page_id: Uuid,
+    /// Sync client id of the holder (see `LocalSyncState::client_id`)
+    pub client_id: String,
+    /// Shown to other devices
+    pub device_name: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl PageLock {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// The lock file of a notebook
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageLocks {
+    #[serde(default)]
+    pub locks: HashMap<Uuid, PageLock>,
+}
+
+impl PageLocks {
+    /// Drop expired locks. Returns true if any were dropped.
+    pub fn prune_expired(&mut self, now: DateTime<Utc>) -> bool {
+        let before = self.locks.len();
+        self.locks.retain(|_, lock| !lock.is_expired(now));
+        self.locks.len() != before
+    }
+
+    /// Take or refresh the lock on `page_id` for `client_id`. Fails with the
+    /// current lock if another device holds it.
+    pub fn acquire(
+        &mut self,
+        page_id: Uuid,
+        client_id: &str,
+        device_name: &str,
+        now: DateTime<Utc>,
+    ) -> Result<PageLock, PageLock> {
+        self.prune_expired(now);
+        if let Some(existing) = self.locks.get(&page_id) {
+            if existing.client_id != client_id {
+                return Err(existing.clone());
+            }
+        }
+        let lock = PageLock {
+            page_id,
+            client_id: client_id.to_string(),
+            device_name: device_name.to_string(),
+            // A refresh keeps the original time so the age stays meaningful
+            acquired_at: self
+                .locks
+                .get(&page_id)
+                .map(|l| l.acquired_at)
+                .unwrap_or(now),
+            expires_at: now + Duration::minutes(LOCK_TTL_MINUTES),
+        };
+        self.locks.insert(page_id, lock.clone());
+        Ok(lock)
+    }
+
+    /// Release `client_id`'s lock on `page_id`. Returns false if it didn't
+    /// hold one.
+    pub fn release(&mut self, page_id: Uuid, client_id: &str) -> bool {
+        match self.locks.get(&page_id) {
+            Some(lock) if lock.client_id == client_id => {
+                self.locks.remove(&page_id);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The locks this device last saw; none if never synced
+pub fn load_cache(path: &Path) -> PageLocks {
+    std::fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_cache(path: &Path, locks: &PageLocks) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(locks)?)
+}
+
+/// A lock as the frontend sees it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageLockInfo {
+    pub page_id: Uuid,
+    pub device_name: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Seconds since the lock was taken
+    pub age_secs: i64,
+    /// Held by this device
+    pub mine: bool,
+}
+
+impl PageLockInfo {
+    pub fn new(lock: &PageLock, client_id: &str, now: DateTime<Utc>) -> Self {
+        Self {
+            page_id: lock.page_id,
+            device_name: lock.device_name.clone(),
+            acquired_at: lock.acquired_at,
+            expires_at: lock.expires_at,
+            age_secs: (now - lock.acquired_at).num_seconds().max(0),
+            mine: lock.client_id == client_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locks_are_exclusive_until_released_or_expired() {
+        let page = Uuid::new_v4();
+        let start = Utc::now();
+        let mut locks = PageLocks::default();
+
+        let mine = locks.acquire(page, "laptop-1", "Laptop", start).unwrap();
+        let held = locks
+            .acquire(page, "phone-1", "Phone", start + Duration::minutes(1))
+            .unwrap_err();
+        assert_eq!(held.device_name, "Laptop");
+
+        // Refreshing extends the lock but keeps its age
+        let later = start + Duration::minutes(20);
+        let refreshed = locks.acquire(page, "laptop-1", "Laptop", later).unwrap();
+        assert_eq!(refreshed.acquired_at, mine.acquired_at);
+        assert!(refreshed.expires_at > mine.expires_at);
+        assert_eq!(
+            PageLockInfo::new(&refreshed, "phone-1", later).age_secs,
+            20 * 60
+        );
+
+        assert!(!locks.release(page, "phone-1"));
+        let stale = later + Duration::minutes(LOCK_TTL_MINUTES);
+        assert!(locks.acquire(page, "phone-1", "Phone", stale).is_ok());
+        assert!(locks.release(page, "phone-1"));
+        assert!(locks.locks.is_empty());
+    }
+}
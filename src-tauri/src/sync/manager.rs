@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 use uuid::Uuid;
@@ -10,32 +10,46 @@ use uuid::Uuid;
 use futures_util::StreamExt;
 use tokio::sync::Semaphore;
 
+use super::bandwidth::{self, Throttle};
+use super::browse::{self, RemoteNotebook, RemoteNotebookListing, RemotePage};
 use super::events::SyncEventEmitter;
 
+use crate::actions::{Action, ActionStorage};
+use crate::comments::{comments_hash, merge_comments, Comment, CommentsStorage};
 use crate::contacts::{Contact, ContactActivity, ContactsStorage};
+use crate::activity;
+use crate::diagnostics::{self, Category};
 use crate::energy::{EnergyCheckIn, EnergyStorage};
 use crate::goals::{Goal, GoalProgress, GoalsStorage};
 use crate::inbox::{InboxItem, InboxStorage};
-use crate::library::LibraryStorage;
+use crate::library::{LibrarySettings, LibraryStorage};
+use crate::secrets::{self, SecretStore};
 use crate::storage::oplog::{diff_blocks, BlockOp};
 use crate::storage::EditorData;
 use crate::storage::Page;
-use crate::storage::FileStorage;
+use crate::storage::StorageLock;
 
 use crate::storage::{Folder, Notebook, NotebookType, Section};
 
 use super::config::{
-    AssetManifest, AssetManifestEntry, Changelog, ChangeOperation, LibrarySyncConfig,
-    LibrarySyncConfigInput, NotebookMeta, PageMeta, ServerType, SyncConfig, SyncConfigInput,
-    SyncCredentials, SyncManifest, SyncState, SyncStatus, SyncResult,
+    AssetManifest, AssetManifestEntry, AssetSyncPolicy, Changelog, ChangeOperation,
+    LibrarySyncConfig, LibrarySyncConfigInput, NotebookMeta, PageMeta, ServerType, SyncConfig,
+    SyncConfigInput, SyncCredentials, SyncManifest, SyncState, SyncStatus, SyncResult,
 };
 use super::crdt::{CrdtStore, PageDocument};
-use super::metadata::LocalSyncState;
+use super::delta;
+use super::devices::{self, DeviceRegistry, SyncDeviceInfo};
+use super::library_docs::{self, DocCollection, LibraryDocsState, SyncedDoc};
+use super::locks::{self, PageLock, PageLockInfo, PageLocks};
+use super::metadata::{LocalCommentsState, LocalSyncState};
 use super::queue::{SyncOperation, SyncQueue};
+use super::scratchpad;
+use super::verify::{self, RepairAction, SyncRepair, SyncVerifyReport};
+use super::versions::{self, RemotePageVersion};
 use super::webdav::{WebDAVClient, WebDAVError};
 
 /// Type alias for shared storage
-pub type SharedStorage = Arc<Mutex<FileStorage>>;
+pub type SharedStorage = Arc<StorageLock>;
 
 /// Type alias for shared library storage
 pub type SharedLibraryStorage = Arc<Mutex<LibraryStorage>>;
@@ -52,6 +66,9 @@ pub type SharedContactsStorage = Arc<Mutex<ContactsStorage>>;
 /// Type alias for shared energy storage
 pub type SharedEnergyStorage = Arc<Mutex<EnergyStorage>>;
 
+/// Type alias for shared action storage
+pub type SharedActionStorage = Arc<Mutex<ActionStorage>>;
+
 #[derive(Error, Debug)]
 pub enum SyncError {
     #[error("WebDAV error: {0}")]
@@ -74,6 +91,8 @@ pub enum SyncError {
     PageNotFound(Uuid),
     #[error("Keyring error: {0}")]
     Keyring(String),
+    #[error("Secrets error: {0}")]
+    Secrets(#[from] crate::secrets::SecretError),
     #[error(
         "Sync would shrink page {page_id} from {local_blocks} to {merged_blocks} blocks. \
          Conflict not auto-resolved. Local content preserved."
@@ -87,6 +106,20 @@ pub enum SyncError {
         /// Remote ETag at the time the destructive merge was refused.
         remote_etag: Option<String>,
     },
+    #[error("Comment storage error: {0}")]
+    Comments(#[from] crate::comments::CommentStorageError),
+    #[error("Sync is paused")]
+    Paused,
+    #[error(
+        "This device was removed from sync on {revoked_at}. Disable and re-enable sync to \
+         add it again."
+    )]
+    DeviceRevoked { revoked_at: DateTime<Utc> },
+    #[error("Page is being edited on {device_name}")]
+    PageLocked {
+        device_name: String,
+        acquired_at: DateTime<Utc>,
+    },
     #[error("{0}")]
     Other(String),
 }
@@ -106,6 +139,12 @@ struct PageSyncInfo {
     needs_sync: bool,
     remote_etag: Option<String>,
     _never_synced: bool,
+    /// State vector recorded at the last sync (delta sync diffs against it)
+    synced_state_vector: Option<Vec<u8>>,
+    /// Delta files on the server for this page (delta sync)
+    remote_deltas: Vec<String>,
+    /// Delta files already merged locally
+    applied_deltas: Vec<String>,
 }
 
 /// Outcome of syncing a single page concurrently
@@ -114,6 +153,8 @@ struct PageSyncOutcome {
     result: Result<PageSyncResult, SyncError>,
     /// (etag, state_vector) to pass to mark_page_synced
     sync_mark: Option<(Option<String>, Vec<u8>)>,
+    /// Delta files merged into the page after this sync (delta sync only)
+    applied_deltas: Option<Vec<String>>,
 }
 
 /// Outcome of pulling a remote-only page concurrently
@@ -224,6 +265,26 @@ pub struct SyncEnergyUpdated {
     pub energy_changed: bool,
 }
 
+/// Event payload emitted when library document sync (custom actions,
+/// library settings) pulls changes.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncLibraryDocsUpdated {
+    pub actions_changed: bool,
+    pub settings_changed: bool,
+}
+
+/// Overall sync activity, for status indicators (tray icon, status bar).
+/// A pause outranks in-flight syncs, which outrank a previous error.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum SyncActivity {
+    Idle,
+    Syncing { notebooks: usize },
+    Error { message: String },
+    Paused,
+}
+
 /// Sentinel file content written after successful push
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -276,6 +337,17 @@ pub struct SyncManager {
     crdt_store: Arc<Mutex<Option<Arc<CrdtStore>>>>,
     /// Collab storage for checking active real-time sessions (set after app initialization)
     collab_storage: Arc<Mutex<Option<Arc<Mutex<crate::collab::storage::CollabStorage>>>>>,
+    /// User-requested pause; syncs are refused with `SyncError::Paused`
+    paused: AtomicBool,
+    /// Notebook syncs in flight
+    active_syncs: AtomicUsize,
+    /// Error from the last failed sync, cleared by the next success
+    last_error: Mutex<Option<String>>,
+    /// Bandwidth limiters per server URL, shared by concurrent notebook syncs
+    throttles: Mutex<HashMap<String, Arc<Throttle>>>,
+    /// Secrets store for WebDAV passwords (set after app initialization);
+    /// without one they stay in `.credentials/` files
+    secrets: Mutex<Option<Arc<SecretStore>>>,
 }
 
 impl SyncManager {
@@ -293,9 +365,57 @@ impl SyncManager {
             onsave_debounce: Arc::new(Mutex::new(HashMap::new())),
             crdt_store: Arc::new(Mutex::new(None)),
             collab_storage: Arc::new(Mutex::new(None)),
+            paused: AtomicBool::new(false),
+            active_syncs: AtomicUsize::new(0),
+            last_error: Mutex::new(None),
+            throttles: Mutex::new(HashMap::new()),
+            secrets: Mutex::new(None),
+        }
+    }
+
+    /// Pause or resume sync. Syncs already running finish normally.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        log::info!("Sync {}", if paused { "paused" } else { "resumed" });
+        self.emit_activity();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn activity(&self) -> SyncActivity {
+        if self.is_paused() {
+            return SyncActivity::Paused;
+        }
+        let notebooks = self.active_syncs.load(Ordering::SeqCst);
+        if notebooks > 0 {
+            return SyncActivity::Syncing { notebooks };
+        }
+        match self.last_error.lock().unwrap().clone() {
+            Some(message) => SyncActivity::Error { message },
+            None => SyncActivity::Idle,
+        }
+    }
+
+    fn emit_activity(&self) {
+        let activity = self.activity();
+        if let Some(e) = self.emitter.lock().unwrap().as_ref() {
+            e.emit_sync_activity(&activity);
         }
     }
 
+    fn begin_activity(&self) {
+        self.active_syncs.fetch_add(1, Ordering::SeqCst);
+        self.emit_activity();
+    }
+
+    fn end_activity(&self, result: &Result<SyncResult, SyncError>) {
+        self.active_syncs.fetch_sub(1, Ordering::SeqCst);
+        *self.last_error.lock().unwrap() = result.as_ref().err().map(|e| e.to_string());
+        self.emit_activity();
+    }
+
     /// Set the event emitter (called after app initialization).
     /// In the GUI, pass a `TauriEmitter`; in the daemon, pass a `LogEmitter`.
     pub fn set_emitter(&self, emitter: Arc<dyn SyncEventEmitter>) {
@@ -303,6 +423,12 @@ impl SyncManager {
         *guard = Some(emitter);
     }
 
+    /// Set the secrets store credentials are kept in (called after app
+    /// initialization)
+    pub fn set_secrets(&self, secrets: Arc<SecretStore>) {
+        *self.secrets.lock().unwrap() = Some(secrets);
+    }
+
     /// Set the CRDT store for live page state (called after app initialization)
     pub fn set_crdt_store(&self, store: Arc<CrdtStore>) {
         let mut guard = self.crdt_store.lock().unwrap();
@@ -431,7 +557,7 @@ impl SyncManager {
         syncing.remove(&notebook_id);
     }
 
-    // ===== Credential storage (file-based with keyring fallback) =====
+    // ===== Credential storage (secrets store, legacy file and keyring) =====
 
     /// Path to file-based credential store
     fn credentials_file_path(&self, service: &str, id: Uuid) -> PathBuf {
@@ -450,36 +576,77 @@ impl SyncManager {
         })
     }
 
-    /// Get credentials: try file first, then keyring
-    fn get_credentials(&self, notebook_id: Uuid) -> Result<SyncCredentials, SyncError> {
+    /// The secrets store, if one is set and unlocked
+    fn unlocked_secrets(&self) -> Option<Arc<SecretStore>> {
+        self.secrets
+            .lock()
+            .unwrap()
+            .clone()
+            .filter(|secrets| secrets.is_unlocked())
+    }
+
+    /// Read credentials from the secrets store, falling back to the legacy
+    /// file and keyring entry. Legacy credentials move into the store the
+    /// first time they're read with it unlocked.
+    fn read_credentials(
+        &self,
+        service: &str,
+        id: Uuid,
+        secret_id: &str,
+    ) -> Result<SyncCredentials, SyncError> {
+        let secrets = self.unlocked_secrets();
+        if let Some(secrets) = &secrets {
+            if let Some(value) = secrets.get(secret_id)? {
+                return Self::parse_credentials(&value);
+            }
+        }
+
+        let value = self.read_legacy_credentials(service, id)?;
+        if let Some(secrets) = &secrets {
+            match secrets.set(secret_id, &value) {
+                Ok(_) => self.delete_legacy_credentials(service, id),
+                Err(e) => log::warn!("Could not move {} credentials into the secrets store: {}", id, e),
+            }
+        }
+        Self::parse_credentials(&value)
+    }
+
+    /// Legacy credentials: try file first, then keyring
+    fn read_legacy_credentials(&self, service: &str, id: Uuid) -> Result<String, SyncError> {
         // Try file-based store first
-        let file_path = self.credentials_file_path("nous-sync", notebook_id);
+        let file_path = self.credentials_file_path(service, id);
         if let Ok(data) = std::fs::read_to_string(&file_path) {
-            return Self::parse_credentials(data.trim());
+            return Ok(data.trim().to_string());
         }
 
         // Fall back to keyring
-        let entry = keyring::Entry::new("nous-sync", &notebook_id.to_string())
+        let entry = keyring::Entry::new(service, &id.to_string())
             .map_err(|e| SyncError::Keyring(e.to_string()))?;
 
-        let password = entry
+        entry
             .get_password()
-            .map_err(|_| SyncError::CredentialsNotFound)?;
-
-        Self::parse_credentials(&password)
+            .map_err(|_| SyncError::CredentialsNotFound)
     }
 
-    /// Store credentials: write to file, also try keyring
-    fn store_credentials(
+    /// Store credentials in the secrets store, or without an unlocked one,
+    /// in a file (also trying the keyring)
+    fn write_credentials(
         &self,
-        notebook_id: Uuid,
+        service: &str,
+        id: Uuid,
+        secret_id: &str,
         username: &str,
         password: &str,
     ) -> Result<(), SyncError> {
         let value = format!("{}:{}", username, password);
 
-        // Always write to file-based store
-        let file_path = self.credentials_file_path("nous-sync", notebook_id);
+        if let Some(secrets) = self.unlocked_secrets() {
+            secrets.set(secret_id, &value)?;
+            self.delete_legacy_credentials(service, id);
+            return Ok(());
+        }
+
+        let file_path = self.credentials_file_path(service, id);
         if let Some(parent) = file_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
@@ -492,23 +659,59 @@ impl SyncManager {
         }
 
         // Also try keyring (best-effort)
-        if let Ok(entry) = keyring::Entry::new("nous-sync", &notebook_id.to_string()) {
+        if let Ok(entry) = keyring::Entry::new(service, &id.to_string()) {
             let _ = entry.set_password(&value);
         }
 
         Ok(())
     }
 
-    /// Delete credentials from both stores
-    fn delete_credentials(&self, notebook_id: Uuid) -> Result<(), SyncError> {
-        // Delete file
-        let file_path = self.credentials_file_path("nous-sync", notebook_id);
-        let _ = std::fs::remove_file(&file_path);
+    /// Delete credentials from every store
+    fn remove_credentials(&self, service: &str, id: Uuid, secret_id: &str) {
+        if let Some(secrets) = self.secrets.lock().unwrap().clone() {
+            if let Err(e) = secrets.delete(secret_id) {
+                log::warn!("Failed to delete secret {}: {}", secret_id, e);
+            }
+        }
+        self.delete_legacy_credentials(service, id);
+    }
 
-        // Delete from keyring
-        if let Ok(entry) = keyring::Entry::new("nous-sync", &notebook_id.to_string()) {
+    fn delete_legacy_credentials(&self, service: &str, id: Uuid) {
+        let _ = std::fs::remove_file(self.credentials_file_path(service, id));
+        if let Ok(entry) = keyring::Entry::new(service, &id.to_string()) {
             let _ = entry.delete_credential();
         }
+    }
+
+    fn get_credentials(&self, notebook_id: Uuid) -> Result<SyncCredentials, SyncError> {
+        self.read_credentials(
+            "nous-sync",
+            notebook_id,
+            &secrets::notebook_sync_key_id(notebook_id),
+        )
+    }
+
+    fn store_credentials(
+        &self,
+        notebook_id: Uuid,
+        username: &str,
+        password: &str,
+    ) -> Result<(), SyncError> {
+        self.write_credentials(
+            "nous-sync",
+            notebook_id,
+            &secrets::notebook_sync_key_id(notebook_id),
+            username,
+            password,
+        )
+    }
+
+    fn delete_credentials(&self, notebook_id: Uuid) -> Result<(), SyncError> {
+        self.remove_credentials(
+            "nous-sync",
+            notebook_id,
+            &secrets::notebook_sync_key_id(notebook_id),
+        );
         Ok(())
     }
 
@@ -519,8 +722,29 @@ impl SyncManager {
         config: &SyncConfig,
     ) -> Result<WebDAVClient, SyncError> {
         let credentials = self.get_credentials(notebook_id)?;
-        let client = WebDAVClient::new(config.server_url.clone(), credentials)?;
-        Ok(client)
+        let client = WebDAVClient::new(config.server_url.clone(), credentials)?
+            .with_compression(config.compression);
+        Ok(match self.throttle_for(config) {
+            Some(throttle) => client.with_throttle(throttle),
+            None => client,
+        })
+    }
+
+    /// The shared limiter for `config`'s server, or None when unlimited
+    fn throttle_for(&self, config: &SyncConfig) -> Option<Arc<Throttle>> {
+        let bandwidth = &config.bandwidth;
+        let mut throttles = self.throttles.lock().unwrap();
+        if bandwidth.max_upload_kbps.is_none() && bandwidth.max_download_kbps.is_none() {
+            if let Some(throttle) = throttles.get(&config.server_url) {
+                throttle.update(bandwidth);
+            }
+            return None;
+        }
+        let throttle = throttles
+            .entry(config.server_url.clone())
+            .or_insert_with(|| Arc::new(Throttle::new(bandwidth)));
+        throttle.update(bandwidth);
+        Some(Arc::clone(throttle))
     }
 
     /// Test WebDAV connection
@@ -568,7 +792,7 @@ impl SyncManager {
         self.store_credentials(notebook_id, &input.username, &input.password)?;
 
         // Create sync config
-        let config = SyncConfig {
+        let mut config = SyncConfig {
             enabled: true,
             server_url: input.server_url,
             remote_path: input.remote_path,
@@ -579,12 +803,20 @@ impl SyncManager {
             managed_by_library: None,
             server_type: ServerType::default(),
             allow_destructive_sync: false,
+            bandwidth: Default::default(),
+            compression: Default::default(),
+            delta_sync: false,
+            asset_policy: Default::default(),
         };
 
         // Update notebook (lock only during synchronous operation)
         {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.write().unwrap();
             let mut notebook = storage_guard.get_notebook(notebook_id)?;
+            // The asset policy is chosen per notebook; keep it on reconfigure
+            if let Some(existing) = &notebook.sync_config {
+                config.asset_policy = existing.asset_policy;
+            }
             notebook.sync_config = Some(config.clone());
             storage_guard.update_notebook(&notebook)?;
         } // Lock released here before async operations
@@ -655,7 +887,7 @@ impl SyncManager {
     ) {
         // Check notebook's sync_config for OnSave mode
         let should_sync = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             storage_guard
                 .get_notebook(notebook_id)
                 .ok()
@@ -932,6 +1164,10 @@ impl SyncManager {
         notebook_id: Uuid,
         storage: &SharedStorage,
     ) -> Result<SyncResult, SyncError> {
+        if self.is_paused() {
+            return Err(SyncError::Paused);
+        }
+
         // Prevent concurrent sync of the same notebook within this process.
         if !self.try_acquire_notebook_guard(notebook_id) {
             log::info!("Sync: notebook {} already syncing in-process, skipping", notebook_id);
@@ -971,12 +1207,15 @@ impl SyncManager {
                     "Sync: could not open cross-process lock {:?}: {} — proceeding with in-process guard only",
                     lock_path, e,
                 );
+                self.begin_activity();
                 let result = self.sync_notebook_inner(notebook_id, storage).await;
+                self.end_activity(&result);
                 self.release_notebook_guard(notebook_id);
                 return result;
             }
         };
 
+        self.begin_activity();
         let result = match xproc_lock.try_write() {
             // Hold `_xproc_guard` across the whole sync (released at arm end).
             Ok(_xproc_guard) => self.sync_notebook_inner(notebook_id, storage).await,
@@ -997,6 +1236,7 @@ impl SyncManager {
             }
         };
 
+        self.end_activity(&result);
         self.release_notebook_guard(notebook_id);
         result
     }
@@ -1008,6 +1248,7 @@ impl SyncManager {
         storage: &SharedStorage,
     ) -> Result<SyncResult, SyncError> {
         let start = std::time::Instant::now();
+        let _timer = diagnostics::time(Category::Sync, "sync_notebook");
         log::info!("Sync: starting notebook {}", notebook_id);
 
         // Get emitter for event emission
@@ -1015,7 +1256,7 @@ impl SyncManager {
 
         // 1. Get notebook config + local pages (short lock)
         let (config, local_pages, notebook_name) = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             let notebook = storage_guard.get_notebook(notebook_id)?;
             let name = notebook.name.clone();
             let config = notebook
@@ -1048,6 +1289,9 @@ impl SyncManager {
         // 2b. Ensure remote directory structure exists (idempotent, handles fresh/empty remote)
         let _ = client.mkdir_p(&format!("{}/pages", config.remote_path)).await;
         let _ = client.mkdir_p(&format!("{}/assets", config.remote_path)).await;
+        if config.delta_sync {
+            let _ = client.mkdir_p(&delta::deltas_root(&config.remote_path)).await;
+        }
 
         let mut pages_pulled = 0;
         let mut pages_pushed = 0;
@@ -1062,25 +1306,57 @@ impl SyncManager {
         let mut synced_page_ids: Vec<(Uuid, PageSyncResult)> = Vec::new();
 
         // 3. Fetch remote manifest + changelog + pages-meta IN PARALLEL
+        let phase_start = std::time::Instant::now();
         let client_m = client.clone();
         let client_c = client.clone();
         let client_pm = client.clone();
+        let client_d = client.clone();
         let remote_path_m = config.remote_path.clone();
         let remote_path_c = config.remote_path.clone();
         let remote_path_pm = config.remote_path.clone();
+        let remote_path_d = config.remote_path.clone();
 
-        let (manifest_result, changelog_result, remote_pages_meta) = tokio::join!(
+        let (manifest_result, changelog_result, remote_pages_meta, devices_result) = tokio::join!(
             Self::fetch_manifest_static(&client_m, &remote_path_m),
             Self::fetch_changelog_static(&client_c, &remote_path_c, notebook_id),
             Self::fetch_pages_meta_static(&client_pm, &remote_path_pm),
+            Self::fetch_devices(&client_d, &remote_path_d),
         );
 
         // DL-13: abort the sync on a transient/parse error rather than treating
         // the remote manifest/changelog as empty (which would overwrite them and
         // drop every page's state vector). A genuine 404 → None/empty is fine.
-        let manifest_opt = manifest_result?;
+        let mut manifest_opt = manifest_result?;
         let mut changelog = changelog_result?;
 
+        // 3b. Enforce device revocations: a revoked device stops here, before
+        //     touching the remote; anything it wrote since is not trusted.
+        //     A rejected manifest is rebuilt from this device's state, as on
+        //     a first sync.
+        let (mut devices, devices_etag) = devices_result?;
+        if let Some(revoked_at) = devices.revoked_at(&local_state.client_id) {
+            log::warn!("Sync: this device was revoked for notebook {}", notebook_id);
+            return Err(SyncError::DeviceRevoked { revoked_at });
+        }
+        if let Some(m) = &manifest_opt {
+            if let Err(device) = devices.validate_manifest(m) {
+                log::warn!(
+                    "Sync: rejecting manifest v{} written by revoked device '{}' ({})",
+                    m.version,
+                    device.name,
+                    device.client_id,
+                );
+                manifest_opt = None;
+            }
+        }
+        let rejected_entries = devices.filter_changelog(&mut changelog);
+        if rejected_entries > 0 {
+            log::warn!(
+                "Sync: ignoring {} changelog entries from revoked devices",
+                rejected_entries,
+            );
+        }
+
         match &manifest_opt {
             Some(m) => log::info!("Sync: remote manifest has {} pages", m.pages.len()),
             None => log::debug!("Sync: no remote manifest (first sync?)"),
@@ -1101,6 +1377,7 @@ impl SyncManager {
             HashMap::new()
         });
         log::info!("Sync: remote pages-meta has {} entries", remote_pages_meta.len());
+        diagnostics::record(Category::Sync, "fetch_remote_metadata", phase_start.elapsed());
 
         // 4. Determine remote_changed_pages
         let remote_changed_pages: HashSet<Uuid> = if local_state.last_changelog_seq > 0
@@ -1124,7 +1401,19 @@ impl SyncManager {
             page_ids
         };
 
+        // 4b. With delta sync, list every page's delta files in one pass. A
+        //     failed listing aborts like a failed manifest fetch (DL-13).
+        let remote_deltas: HashMap<Uuid, Vec<String>> = if config.delta_sync {
+            let files = client
+                .list_files_recursive(&delta::deltas_root(&config.remote_path))
+                .await?;
+            delta::group_by_page(files.iter().map(|f| f.path.as_str()))
+        } else {
+            HashMap::new()
+        };
+
         // 5. Sync each local page CONCURRENTLY
+        let phase_start = std::time::Instant::now();
         let total_pages = local_pages.len();
         let progress_counter = Arc::new(AtomicUsize::new(0));
 
@@ -1162,10 +1451,18 @@ impl SyncManager {
                         !ms.etag.is_empty() && local_etag != Some(ms.etag.as_str())
                     })
                     .unwrap_or(false);
+                let has_new_deltas = remote_deltas.get(&page.id)
+                    .map(|names| {
+                        let applied = local_state.pages.get(&page.id)
+                            .map(|s| s.applied_deltas.as_slice())
+                            .unwrap_or_default();
+                        !delta::unapplied(names, applied).is_empty()
+                    })
+                    .unwrap_or(false);
 
                 if !local_needs_sync && !never_synced && !updated_since_sync
                     && !remote_may_have_changed && !missing_from_remote
-                    && !manifest_etag_changed
+                    && !manifest_etag_changed && !has_new_deltas
                 {
                     log::debug!("Sync: skipping page '{}' ({}) — no changes", page.title, page.id);
                     return false;
@@ -1179,13 +1476,17 @@ impl SyncManager {
                 true
             })
             .map(|page| {
+                let page_state = local_state.pages.get(&page.id);
                 let info = PageSyncInfo {
                     // Force needs_sync when the page is missing from the remote manifest,
                     // even if local state thinks it's up to date.
                     needs_sync: local_state.page_needs_sync(page.id)
                         || !manifest.pages.contains_key(&page.id),
-                    remote_etag: local_state.pages.get(&page.id).and_then(|s| s.remote_etag.clone()),
-                    _never_synced: !local_state.pages.contains_key(&page.id),
+                    remote_etag: page_state.and_then(|s| s.remote_etag.clone()),
+                    _never_synced: page_state.is_none(),
+                    synced_state_vector: page_state.and_then(|s| s.synced_state_vector.clone()),
+                    remote_deltas: remote_deltas.get(&page.id).cloned().unwrap_or_default(),
+                    applied_deltas: page_state.map(|s| s.applied_deltas.clone()).unwrap_or_default(),
                 };
                 (page.clone(), info)
             })
@@ -1210,6 +1511,7 @@ impl SyncManager {
                 let nb_name = notebook_name.clone();
                 let page_title = page.title.clone();
                 let crdt_store_ref = crdt_store_opt.clone();
+                let client_id = local_state.client_id.clone();
                 async move {
                     let _permit = sem.acquire().await.unwrap();
                     let idx = counter.fetch_add(1, Ordering::Relaxed);
@@ -1225,7 +1527,7 @@ impl SyncManager {
                     }
                     Self::sync_page_concurrent(
                         &data_dir, &client, &config, &info,
-                        &storage, notebook_id, &page, crdt_store_ref.as_ref(),
+                        &storage, notebook_id, &page, crdt_store_ref.as_ref(), &client_id,
                     ).await
                 }
             })
@@ -1242,6 +1544,9 @@ impl SyncManager {
             if let Some((etag, sv)) = outcome.sync_mark {
                 local_state.mark_page_synced(outcome.page_id, etag, sv);
             }
+            if let Some(applied) = outcome.applied_deltas {
+                local_state.set_applied_deltas(outcome.page_id, applied);
+            }
             match outcome.result {
                 Ok(ref r) => {
                     match r {
@@ -1396,16 +1701,20 @@ impl SyncManager {
                 self.apply_pages_meta(storage, notebook_id, &remote_pages_meta, &config);
         }
 
-        // 6d. Pull and apply notebook metadata (pinned, position, sort order)
+        diagnostics::record(Category::Sync, "sync_pages", phase_start.elapsed());
+
+        // 6d. Pull and apply notebook metadata (pinned, position, sort order,
+        // pinned root pages)
         let mut notebook_meta_changed = false;
         match self.fetch_notebook_meta(&client, &config.remote_path).await {
             Ok(remote_meta) => {
-                let mut storage_guard = storage.lock().unwrap();
+                let storage_guard = storage.write().unwrap();
                 if let Ok(mut notebook) = storage_guard.get_notebook(notebook_id) {
                     if remote_meta.updated_at > notebook.updated_at {
                         notebook.is_pinned = remote_meta.is_pinned;
                         notebook.position = remote_meta.position;
                         notebook.page_sort_by = remote_meta.page_sort_by;
+                        notebook.pinned_page_ids = remote_meta.pinned_page_ids;
                         notebook.name = remote_meta.name;
                         notebook.icon = remote_meta.icon;
                         notebook.color = remote_meta.color;
@@ -1415,6 +1724,7 @@ impl SyncManager {
                         notebook.system_prompt_mode = remote_meta.system_prompt_mode;
                         notebook.ai_provider = remote_meta.ai_provider;
                         notebook.ai_model = remote_meta.ai_model;
+                        notebook.ai_profile_id = remote_meta.ai_profile_id;
                         notebook.updated_at = remote_meta.updated_at;
                         if let Err(e) = storage_guard.update_notebook(&notebook) {
                             log::warn!("Sync: failed to apply notebook metadata: {}", e);
@@ -1470,7 +1780,7 @@ impl SyncManager {
         // Push notebook metadata for remote discovery
         {
             let notebook = {
-                let storage_guard = storage.lock().unwrap();
+                let storage_guard = storage.read().unwrap();
                 storage_guard.get_notebook(notebook_id)?
             };
             if let Err(e) = self.push_notebook_meta(&client, &config.remote_path, &notebook).await {
@@ -1510,6 +1820,30 @@ impl SyncManager {
             }
         }
 
+        // 8b. Keep a version of each pushed page on the remote now and then,
+        //     so a bad edit can be rolled back from any device
+        let now = Utc::now();
+        for (page_id, result) in &synced_page_ids {
+            if !matches!(result, PageSyncResult::Pushed | PageSyncResult::Merged) {
+                continue;
+            }
+            let last_version_at = local_state.pages.get(page_id).and_then(|p| p.last_version_at);
+            if !versions::due(last_version_at, now) {
+                continue;
+            }
+            match self
+                .push_page_version(&client, &config.remote_path, notebook_id, *page_id, &local_state.client_id)
+                .await
+            {
+                Ok(()) => {
+                    if let Some(state) = local_state.pages.get_mut(page_id) {
+                        state.last_version_at = Some(now);
+                    }
+                }
+                Err(e) => log::warn!("Sync: failed to upload a version of page {}: {}", page_id, e),
+            }
+        }
+
         // 9. Sync assets (with CAS when possible, fallback to legacy)
         if let Some(ref e) = emitter {
             e.emit_sync_progress(&SyncProgress {
@@ -1523,11 +1857,20 @@ impl SyncManager {
         }
 
         // Determine library base path for CAS
+        let phase_start = std::time::Instant::now();
         let library_base_path = config.remote_path
             .rsplit_once('/')
             .map(|(base, _)| base.to_string());
 
-        let asset_result = if let Some(ref lib_base) = library_base_path {
+        let assets_deferred = config.bandwidth.skip_assets_on_metered
+            && bandwidth::is_metered_connection() == Some(true);
+        let asset_result = if assets_deferred {
+            log::info!(
+                "Sync: metered connection, deferring asset sync for notebook {}",
+                notebook_id,
+            );
+            AssetSyncResult::default()
+        } else if let Some(ref lib_base) = library_base_path {
             // Try CAS sync first, fall back to legacy
             match self
                 .sync_assets_cas(&client, &config, &mut local_state, notebook_id, lib_base)
@@ -1553,6 +1896,54 @@ impl SyncManager {
                 })
         };
 
+        diagnostics::record(Category::Sync, "sync_assets", phase_start.elapsed());
+
+        // 9b. Sync page comments (merged per comment by timestamp)
+        let phase_start = std::time::Instant::now();
+        match self
+            .sync_comments(&client, &config.remote_path, notebook_id, &mut local_state)
+            .await
+        {
+            Ok(0) => {}
+            Ok(n) => log::info!("Sync: merged remote comments into {} pages", n),
+            Err(e) => log::warn!("Sync: comment sync failed for notebook {}: {}", notebook_id, e),
+        }
+        diagnostics::record(Category::Sync, "sync_comments", phase_start.elapsed());
+
+        // 9c. Pick up page locks taken or released on other devices
+        let page_locks_changed = match self
+            .sync_page_locks(&client, &config.remote_path, notebook_id)
+            .await
+        {
+            Ok(changed) => changed,
+            Err(e) => {
+                log::warn!("Sync: page lock refresh failed for notebook {}: {}", notebook_id, e);
+                false
+            }
+        };
+
+        // 9d. Record this sync in the device registry. Losing the If-Match
+        //     race (e.g. to a revocation) just skips it until the next sync.
+        devices.record_sync(
+            &local_state.client_id,
+            &devices::device_name(),
+            devices::platform(),
+            Utc::now(),
+        );
+        match serde_json::to_vec_pretty(&devices) {
+            Ok(data) => {
+                let path = format!("{}/{}", config.remote_path, devices::FILE_NAME);
+                match client.put(&path, &data, devices_etag.as_deref()).await {
+                    Ok(response) if response.conflict => {
+                        log::info!("Sync: device registry changed during sync; not updated")
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Sync: failed to update device registry: {}", e),
+                }
+            }
+            Err(e) => log::warn!("Sync: failed to encode device registry: {}", e),
+        }
+
         // Push sentinel if we pushed anything
         if any_pushed || asset_result.assets_pushed > 0 {
             if let Some(ref lib_base) = library_base_path {
@@ -1571,6 +1962,22 @@ impl SyncManager {
             queue.complete(item_id);
         }
 
+        // 10b. Collect what other devices changed since the last sync for
+        //      the activity feed. A first sync has nothing to compare to.
+        let remote_activity = if local_state.last_changelog_seq > 0 {
+            activity::from_changelog(
+                notebook_id,
+                &changelog,
+                local_state.last_changelog_seq,
+                &local_state.client_id,
+                &devices,
+                &remote_pages_meta,
+                &local_page_ids,
+            )
+        } else {
+            Vec::new()
+        };
+
         // 11. Update local state
         local_state.last_changelog_seq = if changelog.next_seq > 0 {
             changelog.next_seq - 1
@@ -1584,12 +1991,13 @@ impl SyncManager {
 
         // Update notebook config with last sync time (short lock)
         {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.write().unwrap();
             let mut notebook = storage_guard.get_notebook(notebook_id)?;
             if let Some(config) = &mut notebook.sync_config {
                 config.last_sync = Some(Utc::now());
             }
             storage_guard.update_notebook(&notebook)?;
+            storage_guard.activity().record(&remote_activity);
         } // Lock released
 
         // Notify frontend of pages that were updated from remote so it can
@@ -1619,6 +2027,14 @@ impl SyncManager {
                 e.emit_sync_notebook_updated(&notebook_id.to_string());
             }
 
+            if page_locks_changed {
+                e.emit_sync_page_locks_updated(notebook_id);
+            }
+
+            if !remote_activity.is_empty() {
+                e.emit_activity_recorded(notebook_id, remote_activity.len());
+            }
+
             e.emit_sync_progress(&SyncProgress {
                 notebook_id: notebook_id.to_string(),
                 notebook_name: notebook_name.clone(),
@@ -1647,6 +2063,7 @@ impl SyncManager {
             asset_result.assets_pushed,
             asset_result.assets_pulled,
         );
+        result.assets_deferred = assets_deferred;
         if page_errors > 0 || conflicts_blocked > 0 {
             let mut parts = Vec::new();
             // Blocked merges are also counted in page_errors; subtract them so
@@ -1730,6 +2147,36 @@ impl SyncManager {
         Ok(())
     }
 
+    /// Upload the page's local CRDT state as a new remote version, then
+    /// delete the versions past [`versions::KEEP_VERSIONS`]
+    async fn push_page_version(
+        &self,
+        client: &WebDAVClient,
+        remote_path: &str,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        client_id: &str,
+    ) -> Result<(), SyncError> {
+        let state = std::fs::read(Self::crdt_path_for(&self.data_dir, notebook_id, page_id))?;
+        let dir = versions::page_versions_dir(remote_path, page_id);
+        client.mkdir_p(&dir).await?;
+        let name = versions::version_file_name(client_id, Utc::now());
+        client.put(&format!("{}/{}", dir, name), &state, None).await?;
+
+        let names: Vec<String> = client
+            .list_files_recursive(&dir)
+            .await?
+            .iter()
+            .filter_map(|f| f.path.trim_end_matches('/').rsplit('/').next().map(str::to_string))
+            .collect();
+        for old in versions::to_prune(&names) {
+            if let Err(e) = client.delete(&format!("{}/{}", dir, old)).await {
+                log::debug!("Sync: failed to delete old version {}: {}", old, e);
+            }
+        }
+        Ok(())
+    }
+
     /// Push changelog to remote (last-writer-wins)
     async fn push_changelog(
         &self,
@@ -1779,8 +2226,8 @@ impl SyncManager {
         notebook_id: Uuid,
     ) -> Result<(), SyncError> {
         let (pages, folders, sections) = {
-            let storage_guard = storage.lock().unwrap();
-            let pages = storage_guard.list_all_pages(notebook_id)?;
+            let storage_guard = storage.read().unwrap();
+            let pages = storage_guard.list_page_summaries(notebook_id)?;
             let folders = storage_guard.list_folders(notebook_id)?;
             let sections = storage_guard.list_sections(notebook_id)?;
             (pages, folders, sections)
@@ -1841,7 +2288,7 @@ impl SyncManager {
                 match serde_json::from_slice::<Vec<Folder>>(&data) {
                     Ok(folders) => {
                         log::info!("Sync: pulled {} folders from remote, applying", folders.len());
-                        let storage_guard = storage.lock().unwrap();
+                        let storage_guard = storage.write().unwrap();
                         storage_guard.save_folders_for_sync(notebook_id, &folders)?;
                     }
                     Err(e) => log::warn!("Sync: failed to parse remote folders.json: {}", e),
@@ -1857,7 +2304,7 @@ impl SyncManager {
                 match serde_json::from_slice::<Vec<Section>>(&data) {
                     Ok(sections) => {
                         log::info!("Sync: pulled {} sections from remote, applying", sections.len());
-                        let storage_guard = storage.lock().unwrap();
+                        let storage_guard = storage.write().unwrap();
                         storage_guard.save_sections_for_sync(notebook_id, &sections)?;
                         // Repair any orphaned sections (pages referencing non-existent sections)
                         match storage_guard.repair_orphaned_sections(notebook_id) {
@@ -1898,7 +2345,7 @@ impl SyncManager {
 
         // Read page list under brief lock
         let local_pages = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             match storage_guard.list_all_pages(notebook_id) {
                 Ok(pages) => pages,
                 Err(e) => {
@@ -1919,7 +2366,7 @@ impl SyncManager {
                 }
 
                 // Acquire/release lock per-page update
-                let storage_guard = storage.lock().unwrap();
+                let storage_guard = storage.write().unwrap();
                 match storage_guard.get_page(notebook_id, page.id) {
                     Ok(mut page) => {
                         // Destructive-delete guard: remote wants to delete (or
@@ -1980,7 +2427,7 @@ impl SyncManager {
                         page.ai_model = meta.ai_model.clone();
                         page.created_at = meta.created_at;
                         page.updated_at = meta.updated_at;
-                        if let Err(e) = storage_guard.update_page(&page) {
+                        if let Err(e) = storage_guard.apply_synced_page(&page) {
                             log::warn!("Sync: failed to apply metadata to page {}: {}", page.id, e);
                         } else {
                             applied += 1;
@@ -2126,20 +2573,30 @@ impl SyncManager {
         notebook_id: Uuid,
         page: &Page,
         crdt_store: Option<&Arc<CrdtStore>>,
+        client_id: &str,
     ) -> PageSyncOutcome {
         let page_id = page.id;
-        match Self::sync_page_concurrent_inner(
-            data_dir, client, config, sync_info, storage, notebook_id, page, crdt_store,
-        ).await {
-            Ok((result, sync_mark)) => PageSyncOutcome {
+        let synced = if config.delta_sync {
+            Self::sync_page_delta_inner(
+                data_dir, client, config, sync_info, storage, notebook_id, page, crdt_store, client_id,
+            ).await
+        } else {
+            Self::sync_page_concurrent_inner(
+                data_dir, client, config, sync_info, storage, notebook_id, page, crdt_store,
+            ).await.map(|(result, sync_mark)| (result, sync_mark, None))
+        };
+        match synced {
+            Ok((result, sync_mark, applied_deltas)) => PageSyncOutcome {
                 page_id,
                 result: Ok(result),
                 sync_mark,
+                applied_deltas,
             },
             Err(e) => PageSyncOutcome {
                 page_id,
                 result: Err(e),
                 sync_mark: None,
+                applied_deltas: None,
             },
         }
     }
@@ -2408,12 +2865,12 @@ impl SyncManager {
                 if sync_info.needs_sync {
                     // Merge case
                     {
-                        let storage_guard = storage.lock().unwrap();
+                        let storage_guard = storage.write().unwrap();
                         let mut updated_page = page.clone();
                         updated_page.content = merged_content;
-                        // Use update_page_metadata to handle both standard (.json)
-                        // and file-based (.metadata.json) pages correctly.
-                        storage_guard.update_page_metadata(&updated_page)?;
+                        // Handles both standard (.json) and file-based
+                        // (.metadata.json) pages correctly.
+                        storage_guard.apply_synced_page(&updated_page)?;
                     }
 
                     let merged_state = local_doc.encode_state();
@@ -2426,12 +2883,12 @@ impl SyncManager {
                 } else {
                     // Pull only
                     {
-                        let storage_guard = storage.lock().unwrap();
+                        let storage_guard = storage.write().unwrap();
                         let mut updated_page = page.clone();
                         updated_page.content = merged_content;
-                        // Use update_page_metadata to handle both standard (.json)
-                        // and file-based (.metadata.json) pages correctly.
-                        storage_guard.update_page_metadata(&updated_page)?;
+                        // Handles both standard (.json) and file-based
+                        // (.metadata.json) pages correctly.
+                        storage_guard.apply_synced_page(&updated_page)?;
                     }
 
                     let state = local_doc.encode_state();
@@ -2537,10 +2994,10 @@ impl SyncManager {
                     // then the .json, and record the new etag.
                     crate::storage::atomic::write(&crdt_path, &merged_state)?;
                     {
-                        let storage_guard = storage.lock().unwrap();
+                        let storage_guard = storage.write().unwrap();
                         let mut updated_page = page.clone();
                         updated_page.content = merged_content;
-                        storage_guard.update_page_metadata(&updated_page)?;
+                        storage_guard.apply_synced_page(&updated_page)?;
                     }
                     let sv = local_doc.state_vector();
                     return Ok((PageSyncResult::Merged, Some((put.etag, sv))));
@@ -2554,6 +3011,192 @@ impl SyncManager {
         Ok((PageSyncResult::Unchanged, None))
     }
 
+    /// Delta-sync a page (see [`delta`]): merge the base if another device
+    /// compacted it and any delta files not merged yet, then upload only the
+    /// updates made here since the last sync as a new delta file. Pages with
+    /// no remote base or no local CRDT history to diff against go through the
+    /// full-file path in [`Self::sync_page_concurrent_inner`] instead.
+    ///
+    /// Returns the sync mark plus the delta files now merged locally.
+    async fn sync_page_delta_inner(
+        data_dir: &Path,
+        client: &WebDAVClient,
+        config: &SyncConfig,
+        sync_info: &PageSyncInfo,
+        storage: &SharedStorage,
+        notebook_id: Uuid,
+        page: &Page,
+        crdt_store: Option<&Arc<CrdtStore>>,
+        client_id: &str,
+    ) -> Result<(PageSyncResult, Option<(Option<String>, Vec<u8>)>, Option<Vec<String>>), SyncError> {
+        let crdt_path = Self::crdt_path_for(data_dir, notebook_id, page.id);
+        let remote_path = format!("{}/pages/{}.crdt", config.remote_path, page.id);
+        let page_is_live = crdt_store
+            .map(|cs| cs.is_live(page.id))
+            .unwrap_or(false);
+        let synced_sv = sync_info
+            .synced_state_vector
+            .as_deref()
+            .filter(|sv| !sv.is_empty());
+
+        let base_etag = if synced_sv.is_some() && (page_is_live || crdt_path.exists()) {
+            match client.head(&remote_path).await {
+                Ok(head) if head.exists => head.etag,
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let (Some(mut base_etag), Some(synced_sv)) = (base_etag, synced_sv) else {
+            let (result, sync_mark) = Self::sync_page_concurrent_inner(
+                data_dir, client, config, sync_info, storage, notebook_id, page, crdt_store,
+            ).await?;
+            return Ok((result, sync_mark, None));
+        };
+
+        // Shared history is guaranteed here: the doc comes from the live
+        // store or the on-disk .crdt.
+        let (local_doc, _) = Self::prepare_local_doc_for_sync(
+            &crdt_path,
+            page.id,
+            &page.content,
+            page_is_live,
+            crdt_store,
+        )?;
+
+        // What the server is missing from this device, taken before anything
+        // remote is merged in
+        let local_delta = if sync_info.needs_sync {
+            Some(local_doc.encode_diff(synced_sv)?)
+        } else {
+            None
+        };
+
+        // Remote changes: a new base and delta files not merged yet
+        let mut remote_updates: Vec<Vec<u8>> = Vec::new();
+        if sync_info.remote_etag.as_deref() != Some(base_etag.as_str()) {
+            let (data, fetched_etag) = client.get_with_etag(&remote_path).await?;
+            if let Some(etag) = fetched_etag {
+                base_etag = etag;
+            }
+            remote_updates.push(data);
+        }
+
+        let deltas_dir = delta::page_deltas_dir(&config.remote_path, page.id);
+        let mut applied: Vec<String> = sync_info
+            .applied_deltas
+            .iter()
+            .filter(|name| sync_info.remote_deltas.contains(name))
+            .cloned()
+            .collect();
+        for name in delta::unapplied(&sync_info.remote_deltas, &sync_info.applied_deltas) {
+            match client.get(&format!("{}/{}", deltas_dir, name)).await {
+                Ok(data) => {
+                    remote_updates.push(data);
+                    applied.push(name.to_string());
+                }
+                // Compacted into the base since the listing; the base's new
+                // ETag brings it in on the next sync
+                Err(WebDAVError::NotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let pulled = !remote_updates.is_empty();
+        if pulled {
+            for update in &remote_updates {
+                local_doc.apply_update(update)?;
+            }
+
+            // Destructive-sync guard before any write, as in the full path
+            let merged_content = local_doc.to_editor_data()?;
+            if Self::guard_blocks_merge(
+                config,
+                page.content.blocks.len(),
+                merged_content.blocks.len(),
+            ) {
+                return Err(Self::block_destructive_merge(
+                    data_dir,
+                    notebook_id,
+                    page,
+                    &merged_content,
+                    Some(base_etag),
+                ));
+            }
+
+            for update in &remote_updates {
+                Self::push_remote_to_live_if_live(crdt_store, page.id, update);
+            }
+
+            let storage_guard = storage.write().unwrap();
+            let mut updated_page = page.clone();
+            updated_page.content = merged_content;
+            storage_guard.apply_synced_page(&updated_page)?;
+        }
+
+        if pulled || local_delta.is_some() {
+            std::fs::create_dir_all(crdt_path.parent().unwrap())?;
+            crate::storage::atomic::write(&crdt_path, &local_doc.encode_state())?;
+        }
+
+        // Delta files are never overwritten, so no If-Match is needed
+        if let Some(update) = &local_delta {
+            if sync_info.remote_deltas.is_empty() {
+                let _ = client.mkcol(&deltas_dir).await;
+            }
+            let name = delta::delta_file_name(client_id, Utc::now());
+            client
+                .put(&format!("{}/{}", deltas_dir, name), update, None)
+                .await?;
+            applied.push(name);
+        }
+
+        // Compact: fold every delta merged here into a new base. If-Match on
+        // the base makes a concurrent compaction on another device win; the
+        // deltas it didn't see stay on the server either way.
+        let mut etag = Some(base_etag.clone());
+        let mut compacted = false;
+        if applied.len() >= delta::COMPACT_AFTER {
+            let state = local_doc.encode_state();
+            match client.put(&remote_path, &state, Some(&base_etag)).await {
+                Ok(put) if !put.conflict => {
+                    log::info!(
+                        "Sync: compacted {} deltas into base for page {}",
+                        applied.len(),
+                        page.id,
+                    );
+                    etag = put.etag;
+                    compacted = true;
+                    let mut kept = Vec::new();
+                    for name in applied {
+                        if let Err(e) = client.delete(&format!("{}/{}", deltas_dir, name)).await {
+                            log::warn!("Sync: failed to delete compacted delta {}: {}", name, e);
+                            kept.push(name);
+                        }
+                    }
+                    applied = kept;
+                }
+                Ok(_) => log::info!(
+                    "Sync: base of page {} changed during compaction, retrying next sync",
+                    page.id,
+                ),
+                Err(e) => log::warn!("Sync: failed to compact page {}: {}", page.id, e),
+            }
+        }
+
+        let result = match (pulled, local_delta.is_some()) {
+            (true, true) => PageSyncResult::Merged,
+            (true, false) => PageSyncResult::Pulled,
+            (false, true) => PageSyncResult::Pushed,
+            (false, false) => PageSyncResult::Unchanged,
+        };
+        let sync_mark = match result {
+            PageSyncResult::Unchanged if !compacted => None,
+            _ => Some((etag, local_doc.state_vector())),
+        };
+        Ok((result, sync_mark, Some(applied)))
+    }
+
     /// Pull a page from remote concurrently. Returns outcome with sync_mark.
     async fn pull_page_concurrent(
         data_dir: &Path,
@@ -2624,13 +3267,16 @@ impl SyncManager {
             daily_note_date: None,
             plugin_page_type: None,
             plugin_data: None,
+            properties: Default::default(),
+            encryption: None,
+            stats: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
 
         // Save page (short lock)
         {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.write().unwrap();
             storage_guard.create_page_with_id(notebook_id, &page)?;
         }
 
@@ -2720,6 +3366,21 @@ impl SyncManager {
         }
     }
 
+    /// Whether the asset policy leaves this remote asset for
+    /// `ensure_asset_local` instead of pulling it now. `files/…` keys are
+    /// page sources rather than media, so they are always pulled.
+    fn defer_asset_pull(policy: AssetSyncPolicy, key: &str, locally_present: bool) -> bool {
+        if key.starts_with("files/") {
+            return false;
+        }
+        match policy {
+            AssetSyncPolicy::All => false,
+            // Assets already on this device keep receiving updates
+            AssetSyncPolicy::OnDemand => !locally_present,
+            AssetSyncPolicy::Never => true,
+        }
+    }
+
     // ===== Legacy asset sync (parallel) =====
 
     /// Sync assets between local and remote for a notebook (parallelized)
@@ -2863,6 +3524,9 @@ impl SyncManager {
             .filter_map(|(relative_path, remote_info)| {
                 let local_path = assets_dir.join(relative_path);
                 let is_locally_present = local_path.exists();
+                if Self::defer_asset_pull(config.asset_policy, relative_path, is_locally_present) {
+                    return None;
+                }
 
                 let should_pull = if !is_locally_present {
                     true
@@ -3120,6 +3784,9 @@ impl SyncManager {
             // Route `files/…` keys back under the notebook's files/ dir (DL-12).
             let local_path =
                 Self::asset_key_to_local_path(&files_dir, &assets_dir, relative_path);
+            if Self::defer_asset_pull(config.asset_policy, relative_path, local_path.exists()) {
+                continue;
+            }
 
             if local_path.exists() {
                 // Check if local file hash matches
@@ -3250,12 +3917,23 @@ impl SyncManager {
 
         // Update notebook config
         {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.write().unwrap();
             let mut notebook = storage_guard.get_notebook(notebook_id)?;
             notebook.sync_config = None;
             storage_guard.update_notebook(&notebook)?;
         }
 
+        // Re-enabling registers this device anew, which is how a revoked
+        // device rejoins
+        let state_path = self.local_state_path(notebook_id);
+        if state_path.exists() {
+            let mut local_state = self.get_local_state(notebook_id);
+            local_state.reset_client_id();
+            if let Err(e) = local_state.save(&state_path) {
+                log::warn!("Sync: failed to reset client id for {}: {}", notebook_id, e);
+            }
+        }
+
         // Remove from local states
         {
             let mut states = self.local_states.lock().unwrap();
@@ -3281,64 +3959,657 @@ impl SyncManager {
             .collect()
     }
 
-    // ===== Library-level sync methods =====
-
-    /// Get library credentials: try file first, then keyring
-    fn get_library_credentials(&self, library_id: Uuid) -> Result<SyncCredentials, SyncError> {
-        // Try file-based store first
-        let file_path = self.credentials_file_path("nous-library-sync", library_id);
-        if let Ok(data) = std::fs::read_to_string(&file_path) {
-            return Self::parse_credentials(data.trim());
-        }
-
-        // Fall back to keyring
-        let entry = keyring::Entry::new("nous-library-sync", &library_id.to_string())
-            .map_err(|e| SyncError::Keyring(e.to_string()))?;
-
-        let password = entry
-            .get_password()
-            .map_err(|_| SyncError::CredentialsNotFound)?;
-
-        Self::parse_credentials(&password)
+    /// Pending operations for every notebook, with their ages
+    pub fn get_queue_details(&self) -> Vec<super::queue::NotebookQueueDetails> {
+        self.queue.lock().unwrap().details(Utc::now())
     }
 
-    /// Store library credentials: write to file, also try keyring
-    fn store_library_credentials(
+    // ===== Health check =====
+
+    /// Compare local state, the remote manifest and the remote page files
+    /// for a notebook
+    pub async fn verify_notebook(
         &self,
-        library_id: Uuid,
-        username: &str,
-        password: &str,
-    ) -> Result<(), SyncError> {
-        let value = format!("{}:{}", username, password);
+        notebook_id: Uuid,
+        storage: &SharedStorage,
+    ) -> Result<SyncVerifyReport, SyncError> {
+        let (config, local_pages) = {
+            let storage_guard = storage.read().unwrap();
+            let notebook = storage_guard.get_notebook(notebook_id)?;
+            let config = notebook
+                .sync_config
+                .filter(|c| c.enabled)
+                .ok_or(SyncError::NotConfigured)?;
+            let pages: HashMap<Uuid, String> = storage_guard
+                .list_page_summaries(notebook_id)?
+                .into_iter()
+                .map(|page| (page.id, page.title))
+                .collect();
+            (config, pages)
+        };
 
-        // Always write to file-based store
-        let file_path = self.credentials_file_path("nous-library-sync", library_id);
-        if let Some(parent) = file_path.parent() {
-            std::fs::create_dir_all(parent)?;
+        let client = self.get_client(notebook_id, &config)?;
+        let local_state = self.get_local_state(notebook_id);
+        let manifest = Self::fetch_manifest_static(&client, &config.remote_path).await?;
+
+        let pages_path = format!("{}/pages", config.remote_path);
+        let remote_files: HashMap<Uuid, Option<String>> =
+            match client.propfind(&pages_path, 1).await {
+                Ok(entries) => entries
+                    .into_iter()
+                    .filter(|e| !e.is_collection)
+                    .filter_map(|e| {
+                        let filename = e.path.trim_end_matches('/').rsplit('/').next()?;
+                        let id = Uuid::parse_str(filename.strip_suffix(".crdt")?).ok()?;
+                        Some((id, e.etag.map(|etag| etag.trim_matches('"').to_string())))
+                    })
+                    .collect(),
+                Err(WebDAVError::NotFound(_)) => HashMap::new(),
+                Err(e) => return Err(e.into()),
+            };
+
+        let report = verify::compare(
+            notebook_id,
+            &local_pages,
+            &local_state,
+            manifest.as_ref(),
+            &remote_files,
+        );
+        log::info!(
+            "Sync verify: notebook {} has {} discrepancies",
+            notebook_id,
+            report.discrepancies.len(),
+        );
+        Ok(report)
+    }
+
+    /// Apply repairs from a `verify_notebook` report, then sync the notebook
+    pub async fn repair_notebook(
+        &self,
+        notebook_id: Uuid,
+        storage: &SharedStorage,
+        repairs: &[SyncRepair],
+    ) -> Result<SyncResult, SyncError> {
+        let config = {
+            let storage_guard = storage.read().unwrap();
+            storage_guard
+                .get_notebook(notebook_id)?
+                .sync_config
+                .filter(|c| c.enabled)
+                .ok_or(SyncError::NotConfigured)?
+        };
+
+        let mut local_state = self.get_local_state(notebook_id);
+        let mut pruned: Vec<Uuid> = Vec::new();
+        for repair in repairs {
+            match repair.action {
+                // Pushing is driven by the page needing sync
+                RepairAction::Push => local_state.mark_page_modified(repair.page_id),
+                // Forgetting the synced ETag makes the next sync fetch and
+                // merge the remote copy. Pages missing locally are pulled by
+                // the remote enumeration step of every sync.
+                RepairAction::Pull => {
+                    if let Some(page_state) = local_state.pages.get_mut(&repair.page_id) {
+                        page_state.remote_etag = None;
+                    }
+                    if local_state.pages.contains_key(&repair.page_id) {
+                        local_state.mark_page_modified(repair.page_id);
+                    }
+                }
+                RepairAction::PruneManifest => pruned.push(repair.page_id),
+            }
         }
-        std::fs::write(&file_path, &value)?;
-        #[cfg(unix)]
+        self.save_local_state(notebook_id, &local_state)?;
+
+        if !pruned.is_empty() {
+            let client = self.get_client(notebook_id, &config)?;
+            if let Some(mut manifest) =
+                Self::fetch_manifest_static(&client, &config.remote_path).await?
+            {
+                for page_id in &pruned {
+                    manifest.pages.remove(page_id);
+                    manifest.page_state_vectors.remove(page_id);
+                }
+                manifest.version += 1;
+                manifest.last_client_id = local_state.client_id.clone();
+                manifest.updated_at = Utc::now();
+                self.push_manifest(&client, &config.remote_path, &manifest)
+                    .await?;
+            }
+        }
+
+        self.sync_notebook(notebook_id, storage).await
+    }
+
+    // ===== On-demand assets =====
+
+    /// Make sure an asset (path relative to the notebook's `assets/` dir) is
+    /// on this device, downloading it if the asset policy skipped it during
+    /// sync. Returns the local path and whether it had to be downloaded.
+    pub async fn ensure_asset_local(
+        &self,
+        notebook_id: Uuid,
+        storage: &SharedStorage,
+        relative_path: &str,
+    ) -> Result<(PathBuf, bool), SyncError> {
+        let relative_path = relative_path.trim_start_matches('/');
+        let rel = Path::new(relative_path);
+        if relative_path.is_empty()
+            || rel
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
         {
-            use std::os::unix::fs::PermissionsExt;
-            let _ = std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o600));
+            return Err(SyncError::Other(format!("Invalid asset path: {}", relative_path)));
         }
 
-        // Also try keyring (best-effort)
-        if let Ok(entry) = keyring::Entry::new("nous-library-sync", &library_id.to_string()) {
-            let _ = entry.set_password(&value);
+        let local_path = self.assets_dir(notebook_id).join(rel);
+        if local_path.exists() {
+            return Ok((local_path, false));
+        }
+
+        let config = {
+            let storage_guard = storage.read().unwrap();
+            storage_guard
+                .get_notebook(notebook_id)?
+                .sync_config
+                .filter(|c| c.enabled)
+                .ok_or(SyncError::NotConfigured)?
+        };
+        if config.asset_policy == AssetSyncPolicy::Never {
+            return Err(SyncError::Other(
+                "Asset downloads are turned off for this notebook".to_string(),
+            ));
+        }
+
+        let client = self.get_client(notebook_id, &config)?;
+        let _permit = self.webdav_semaphore.acquire().await.unwrap();
+
+        // CAS-synced notebooks list the asset's content hash in the asset
+        // manifest; older notebooks keep it under {remote}/assets/.
+        let manifest_path = format!("{}/asset-manifest.json", config.remote_path);
+        let cas_entry = match config.remote_path.rsplit_once('/') {
+            Some((lib_base, _)) => match client.get(&manifest_path).await {
+                Ok(data) => serde_json::from_slice::<AssetManifest>(&data)
+                    .unwrap_or_default()
+                    .remove(relative_path)
+                    .map(|entry| (lib_base.to_string(), entry)),
+                Err(WebDAVError::NotFound(_)) => None,
+                Err(e) => return Err(e.into()),
+            },
+            None => None,
+        };
+
+        let (etag, content_hash) = match cas_entry {
+            Some((lib_base, entry)) => {
+                let cas_path = Self::cas_remote_path(&lib_base, &entry.hash, &entry.ext);
+                log::info!("On-demand asset: pulling {} from {}", relative_path, cas_path);
+                (client.get_to_file(&cas_path, &local_path).await?, Some(entry.hash))
+            }
+            None => {
+                let remote = format!("{}/assets/{}", config.remote_path, relative_path);
+                log::info!("On-demand asset: pulling {}", relative_path);
+                (client.get_to_file(&remote, &local_path).await?, None)
+            }
+        };
+
+        let metadata = std::fs::metadata(&local_path).ok();
+        let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+        let mtime = metadata
+            .and_then(|m| m.modified().ok())
+            .map(DateTime::<Utc>::from);
+        let mut local_state = self.get_local_state(notebook_id);
+        local_state.mark_asset_synced(relative_path, etag, size, mtime);
+        if let Some(asset_state) = local_state.assets.get_mut(relative_path) {
+            asset_state.content_hash = content_hash;
+        }
+        self.save_local_state(notebook_id, &local_state)?;
+
+        Ok((local_path, true))
+    }
+
+    // ===== Comment sync =====
+
+    /// Sync per-page comment files (`{remote}/comments/{page_id}.json`).
+    /// A page is only fetched and merged when its remote ETag moved or its
+    /// local comments changed since the last sync. Returns the number of
+    /// pages whose local comments changed.
+    async fn sync_comments(
+        &self,
+        client: &WebDAVClient,
+        remote_path: &str,
+        notebook_id: Uuid,
+        local_state: &mut LocalSyncState,
+    ) -> Result<usize, SyncError> {
+        let comments_storage = CommentsStorage::new(self.data_dir.join("notebooks"));
+        let remote_dir = format!("{}/comments", remote_path);
+
+        let remote_etags: HashMap<Uuid, Option<String>> =
+            match client.propfind(&remote_dir, 1).await {
+                Ok(entries) => entries
+                    .iter()
+                    .filter(|e| !e.is_collection)
+                    .filter_map(|e| {
+                        let filename = e.path.trim_end_matches('/').rsplit('/').next()?;
+                        let id = Uuid::parse_str(filename.strip_suffix(".json")?).ok()?;
+                        Some((id, e.etag.clone()))
+                    })
+                    .collect(),
+                Err(WebDAVError::NotFound(_)) => HashMap::new(),
+                Err(e) => return Err(e.into()),
+            };
+
+        let mut page_ids: HashSet<Uuid> = remote_etags.keys().copied().collect();
+        page_ids.extend(comments_storage.list_commented_pages(notebook_id)?);
+
+        let mut changed = 0;
+        for page_id in page_ids {
+            let local = comments_storage.load_comments(notebook_id, page_id)?;
+            let local_hash = comments_hash(&local);
+            let remote_exists = remote_etags.contains_key(&page_id);
+            let remote_etag = remote_etags.get(&page_id).cloned().flatten();
+
+            let last = local_state.comments.get(&page_id);
+            let remote_moved = remote_exists && last.map_or(true, |s| s.remote_etag != remote_etag);
+            let local_moved = last.map_or(true, |s| s.content_hash != local_hash);
+            if !remote_moved && !local_moved {
+                continue;
+            }
+
+            let file_path = format!("{}/{}.json", remote_dir, page_id);
+            let remote: Vec<Comment> = if remote_exists {
+                match client.get(&file_path).await {
+                    Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+                    Err(WebDAVError::NotFound(_)) => Vec::new(),
+                    Err(e) => return Err(e.into()),
+                }
+            } else {
+                Vec::new()
+            };
+
+            let mut merged = merge_comments(&local, &remote);
+            let mut merged_hash = comments_hash(&merged);
+            if merged_hash != local_hash {
+                // Re-read right before writing so a comment added while the
+                // remote copy was downloading isn't dropped.
+                let fresh = comments_storage.load_comments(notebook_id, page_id)?;
+                merged = merge_comments(&fresh, &merged);
+                merged_hash = comments_hash(&merged);
+                comments_storage.replace_comments(notebook_id, page_id, &merged)?;
+                changed += 1;
+            }
+
+            let mut etag = remote_etag;
+            if merged_hash != comments_hash(&remote) {
+                let _ = client.mkdir_p(&remote_dir).await;
+                let data = serde_json::to_vec_pretty(&merged)?;
+                etag = client.put(&file_path, &data, None).await?.etag;
+            }
+            local_state.comments.insert(
+                page_id,
+                LocalCommentsState {
+                    remote_etag: etag,
+                    content_hash: merged_hash,
+                },
+            );
+        }
+
+        Ok(changed)
+    }
+
+    // ===== Page locks =====
+
+    /// Local copy of the notebook's page locks
+    fn page_locks_path(&self, notebook_id: Uuid) -> PathBuf {
+        self.sync_dir(notebook_id).join("page_locks.json")
+    }
+
+    /// Fetch a notebook's lock file and its ETag. A missing file means no
+    /// locks.
+    async fn fetch_page_locks(
+        client: &WebDAVClient,
+        remote_path: &str,
+    ) -> Result<(PageLocks, Option<String>), SyncError> {
+        let path = format!("{}/{}", remote_path, locks::FILE_NAME);
+        match client.get_with_etag(&path).await {
+            Ok((data, etag)) => Ok((serde_json::from_slice(&data)?, etag)),
+            Err(WebDAVError::NotFound(_)) => Ok((PageLocks::default(), None)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Refresh the local copy of the page locks during a sync. Expired
+    /// locks are left on the server for the next lock change to drop.
+    /// Returns true if the locks changed.
+    async fn sync_page_locks(
+        &self,
+        client: &WebDAVClient,
+        remote_path: &str,
+        notebook_id: Uuid,
+    ) -> Result<bool, SyncError> {
+        let (mut remote, _) = Self::fetch_page_locks(client, remote_path).await?;
+        remote.prune_expired(Utc::now());
+        let path = self.page_locks_path(notebook_id);
+        let mut cached = locks::load_cache(&path);
+        cached.prune_expired(Utc::now());
+        if cached.locks == remote.locks {
+            return Ok(false);
         }
+        locks::save_cache(&path, &remote)?;
+        Ok(true)
+    }
+
+    /// Take or release this device's lock on a page. The lock file is
+    /// replaced with If-Match, so two devices racing for a page can't both
+    /// win. Returns this device's lock after the change, if it holds one.
+    async fn change_page_lock(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        storage: &SharedStorage,
+        acquire: bool,
+    ) -> Result<Option<PageLock>, SyncError> {
+        if self.is_paused() {
+            return Err(SyncError::Paused);
+        }
+        let (client, remote_path) = self.notebook_client(notebook_id, storage)?;
+        let client_id = self.get_local_state(notebook_id).client_id;
+        let device_name = devices::device_name();
+        let lock_path = format!("{}/{}", remote_path, locks::FILE_NAME);
+
+        for _ in 0..3 {
+            let (mut remote, etag) = Self::fetch_page_locks(&client, &remote_path).await?;
+            let pruned = remote.prune_expired(Utc::now());
+            let (changed, held) = if acquire {
+                match remote.acquire(page_id, &client_id, &device_name, Utc::now()) {
+                    Ok(lock) => (true, Some(lock)),
+                    Err(holder) => {
+                        locks::save_cache(&self.page_locks_path(notebook_id), &remote)?;
+                        return Err(SyncError::PageLocked {
+                            device_name: holder.device_name,
+                            acquired_at: holder.acquired_at,
+                        });
+                    }
+                }
+            } else {
+                (remote.release(page_id, &client_id), None)
+            };
+
+            if changed || pruned {
+                let data = serde_json::to_vec_pretty(&remote)?;
+                let response = client.put(&lock_path, &data, etag.as_deref()).await?;
+                if response.conflict {
+                    log::info!("Sync: page locks changed on the server during update; retrying");
+                    continue;
+                }
+            }
+            locks::save_cache(&self.page_locks_path(notebook_id), &remote)?;
+            return Ok(held);
+        }
+        Err(SyncError::Other("page locks kept changing on the server".to_string()))
+    }
 
+    /// Claim a page for editing, or extend this device's claim. Fails with
+    /// `SyncError::PageLocked` if another device holds it.
+    pub async fn lock_page(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        storage: &SharedStorage,
+    ) -> Result<PageLockInfo, SyncError> {
+        let client_id = self.get_local_state(notebook_id).client_id;
+        let lock = self
+            .change_page_lock(notebook_id, page_id, storage, true)
+            .await?
+            .ok_or_else(|| SyncError::Other("page lock was not recorded".to_string()))?;
+        log::info!("Sync: locked page {} until {}", page_id, lock.expires_at);
+        Ok(PageLockInfo::new(&lock, &client_id, Utc::now()))
+    }
+
+    /// Release this device's lock on a page; a no-op if it holds none
+    pub async fn release_page_lock(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        storage: &SharedStorage,
+    ) -> Result<(), SyncError> {
+        self.change_page_lock(notebook_id, page_id, storage, false)
+            .await?;
+        log::info!("Sync: released lock on page {}", page_id);
         Ok(())
     }
 
-    /// Delete library credentials from both stores
-    fn delete_library_credentials(&self, library_id: Uuid) -> Result<(), SyncError> {
-        let file_path = self.credentials_file_path("nous-library-sync", library_id);
-        let _ = std::fs::remove_file(&file_path);
+    /// Unexpired page locks in a notebook as of the last sync or lock
+    /// change, on any device
+    pub fn page_locks(&self, notebook_id: Uuid) -> Vec<PageLockInfo> {
+        let client_id = self.get_local_state(notebook_id).client_id;
+        let now = Utc::now();
+        let mut locks: Vec<PageLockInfo> = locks::load_cache(&self.page_locks_path(notebook_id))
+            .locks
+            .values()
+            .filter(|lock| !lock.is_expired(now))
+            .map(|lock| PageLockInfo::new(lock, &client_id, now))
+            .collect();
+        locks.sort_by_key(|lock| lock.acquired_at);
+        locks
+    }
 
-        if let Ok(entry) = keyring::Entry::new("nous-library-sync", &library_id.to_string()) {
-            let _ = entry.delete_credential();
+    // ===== Devices =====
+
+    /// Fetch a notebook's device registry and its ETag. A missing file means
+    /// no devices have registered yet.
+    async fn fetch_devices(
+        client: &WebDAVClient,
+        remote_path: &str,
+    ) -> Result<(DeviceRegistry, Option<String>), SyncError> {
+        let path = format!("{}/{}", remote_path, devices::FILE_NAME);
+        match client.get_with_etag(&path).await {
+            Ok((data, etag)) => Ok((serde_json::from_slice(&data)?, etag)),
+            Err(WebDAVError::NotFound(_)) => Ok((DeviceRegistry::default(), None)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Client for a sync-enabled notebook, plus its remote path
+    fn notebook_client(
+        &self,
+        notebook_id: Uuid,
+        storage: &SharedStorage,
+    ) -> Result<(WebDAVClient, String), SyncError> {
+        let config = {
+            let storage_guard = storage.read().unwrap();
+            let notebook = storage_guard.get_notebook(notebook_id)?;
+            notebook
+                .sync_config
+                .filter(|c| c.enabled)
+                .ok_or(SyncError::NotConfigured)?
+        };
+        let client = self.get_client(notebook_id, &config)?;
+        Ok((client, config.remote_path))
+    }
+
+    /// Devices that have synced a notebook, most recently synced first
+    pub async fn list_sync_devices(
+        &self,
+        notebook_id: Uuid,
+        storage: &SharedStorage,
+    ) -> Result<Vec<SyncDeviceInfo>, SyncError> {
+        let (client, remote_path) = self.notebook_client(notebook_id, storage)?;
+        let client_id = self.get_local_state(notebook_id).client_id;
+        let (registry, _) = Self::fetch_devices(&client, &remote_path).await?;
+        let mut devices: Vec<SyncDeviceInfo> = registry
+            .devices
+            .into_values()
+            .map(|device| SyncDeviceInfo {
+                this_device: device.client_id == client_id,
+                device,
+            })
+            .collect();
+        devices.sort_by(|a, b| b.device.last_sync.cmp(&a.device.last_sync));
+        Ok(devices)
+    }
+
+    /// Versions of a page kept on the server, newest first
+    pub async fn list_remote_page_versions(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        storage: &SharedStorage,
+    ) -> Result<Vec<RemotePageVersion>, SyncError> {
+        let (client, remote_path) = self.notebook_client(notebook_id, storage)?;
+        let files = client
+            .list_files_recursive(&versions::page_versions_dir(&remote_path, page_id))
+            .await?;
+        let (registry, _) = Self::fetch_devices(&client, &remote_path).await?;
+
+        let mut list: Vec<RemotePageVersion> = files
+            .iter()
+            .filter_map(|file| {
+                let name = file.path.trim_end_matches('/').rsplit('/').next()?;
+                let (created_at, client_id) = versions::parse_version_name(name)?;
+                Some(RemotePageVersion {
+                    name: name.to_string(),
+                    created_at,
+                    device: registry.devices.get(&client_id).map(|d| d.name.clone()),
+                    client_id,
+                    size: file.content_length,
+                })
+            })
+            .collect();
+        list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(list)
+    }
+
+    /// Content of a page version on the server, for previewing it
+    pub async fn fetch_remote_page_version(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        name: &str,
+        storage: &SharedStorage,
+    ) -> Result<EditorData, SyncError> {
+        // Only names of the version format, so a name can't point elsewhere
+        if versions::parse_version_name(name).is_none() || name.contains('/') {
+            return Err(SyncError::Other(format!("Unknown page version: {}", name)));
+        }
+        let (client, remote_path) = self.notebook_client(notebook_id, storage)?;
+        let path = format!("{}/{}", versions::page_versions_dir(&remote_path, page_id), name);
+        let data = match client.get(&path).await {
+            Ok(data) => data,
+            Err(WebDAVError::NotFound(_)) => {
+                return Err(SyncError::Other(format!("Page version {} is no longer on the server", name)));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(PageDocument::from_state(&data)?.to_editor_data()?)
+    }
+
+    /// Put a page's content back to a version from the server. The current
+    /// content is snapshotted locally first, and the restore syncs to the
+    /// other devices as a new edit.
+    pub async fn restore_remote_page_version(
+        &self,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        name: &str,
+        storage: &SharedStorage,
+    ) -> Result<Page, SyncError> {
+        let content = self
+            .fetch_remote_page_version(notebook_id, page_id, name, storage)
+            .await?;
+
+        let page = {
+            let storage_guard = storage.write().unwrap();
+            let mut page = storage_guard.get_page(notebook_id, page_id)?;
+            if page.is_encrypted() {
+                return Err(SyncError::Other(
+                    "Encrypted pages can't be restored from server versions".to_string(),
+                ));
+            }
+            let pages_dir = storage_guard.get_notebook_path(notebook_id).join("pages");
+            if let Err(e) = crate::storage::snapshots::take_snapshot(&pages_dir, &page) {
+                log::warn!("Sync: failed to snapshot page {} before restoring a version: {}", page_id, e);
+            }
+            page.content = content;
+            page.updated_at = Utc::now();
+            storage_guard.update_page(&page)?;
+            page
+        };
+
+        log::info!("Sync: restored page {} to remote version {}", page_id, name);
+        self.queue_page_update(notebook_id, page_id);
+        Ok(page)
+    }
+
+    /// Revoke another device's access to a notebook. It can't sync the
+    /// notebook again until sync is re-enabled on it, which registers it
+    /// as a new device.
+    pub async fn revoke_sync_device(
+        &self,
+        notebook_id: Uuid,
+        client_id: &str,
+        storage: &SharedStorage,
+    ) -> Result<(), SyncError> {
+        if self.is_paused() {
+            return Err(SyncError::Paused);
+        }
+        if client_id == self.get_local_state(notebook_id).client_id {
+            return Err(SyncError::Other(
+                "This device can't revoke itself; disable sync instead".to_string(),
+            ));
         }
+        let (client, remote_path) = self.notebook_client(notebook_id, storage)?;
+        let path = format!("{}/{}", remote_path, devices::FILE_NAME);
+
+        for _ in 0..3 {
+            let (mut registry, etag) = Self::fetch_devices(&client, &remote_path).await?;
+            if !registry.devices.contains_key(client_id) {
+                return Err(SyncError::Other(format!("Unknown device: {}", client_id)));
+            }
+            if !registry.revoke(client_id, Utc::now()) {
+                return Ok(());
+            }
+            let data = serde_json::to_vec_pretty(&registry)?;
+            let response = client.put(&path, &data, etag.as_deref()).await?;
+            if !response.conflict {
+                log::info!("Sync: revoked device {} for notebook {}", client_id, notebook_id);
+                return Ok(());
+            }
+            log::info!("Sync: device registry changed during revocation; retrying");
+        }
+        Err(SyncError::Other("device registry kept changing on the server".to_string()))
+    }
+
+    // ===== Library-level sync methods =====
+
+    fn get_library_credentials(&self, library_id: Uuid) -> Result<SyncCredentials, SyncError> {
+        self.read_credentials(
+            "nous-library-sync",
+            library_id,
+            &secrets::library_sync_key_id(library_id),
+        )
+    }
+
+    fn store_library_credentials(
+        &self,
+        library_id: Uuid,
+        username: &str,
+        password: &str,
+    ) -> Result<(), SyncError> {
+        self.write_credentials(
+            "nous-library-sync",
+            library_id,
+            &secrets::library_sync_key_id(library_id),
+            username,
+            password,
+        )
+    }
+
+    fn delete_library_credentials(&self, library_id: Uuid) -> Result<(), SyncError> {
+        self.remove_credentials(
+            "nous-library-sync",
+            library_id,
+            &secrets::library_sync_key_id(library_id),
+        );
         Ok(())
     }
 
@@ -3371,6 +4642,17 @@ impl SyncManager {
         let server_type = Self::detect_server_type(&detect_client, &input.server_url).await;
         log::info!("configure_library_sync: detected server type: {:?}", server_type);
 
+        // Keep bandwidth, compression and delta settings when sync is reconfigured
+        let (bandwidth, compression, delta_sync) = {
+            let lib_storage = library_storage.lock().unwrap();
+            lib_storage
+                .get_library(library_id)
+                .ok()
+                .and_then(|lib| lib.sync_config)
+                .map(|config| (config.bandwidth, config.compression, config.delta_sync))
+                .unwrap_or_default()
+        };
+
         // Build library sync config
         let config = LibrarySyncConfig {
             enabled: true,
@@ -3380,6 +4662,9 @@ impl SyncManager {
             sync_mode: input.sync_mode.clone(),
             sync_interval: input.sync_interval,
             server_type,
+            bandwidth,
+            compression,
+            delta_sync,
         };
 
         // Save config on library
@@ -3397,7 +4682,7 @@ impl SyncManager {
 
         // Apply to all existing notebooks
         let notebooks = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             storage_guard.list_notebooks().unwrap_or_default()
         };
 
@@ -3454,7 +4739,7 @@ impl SyncManager {
 
         // Build per-notebook sync config
         let remote_path = format!("{}/{}", library_config.remote_base_path, notebook_id);
-        let config = SyncConfig {
+        let mut config = SyncConfig {
             enabled: true,
             server_url: library_config.server_url.clone(),
             remote_path: remote_path.clone(),
@@ -3465,12 +4750,20 @@ impl SyncManager {
             managed_by_library: Some(true),
             server_type: library_config.server_type.clone(),
             allow_destructive_sync: false,
+            bandwidth: library_config.bandwidth.clone(),
+            compression: library_config.compression,
+            delta_sync: library_config.delta_sync,
+            asset_policy: Default::default(),
         };
 
         // Update notebook
         {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.write().unwrap();
             let mut notebook = storage_guard.get_notebook(notebook_id)?;
+            // The asset policy is chosen per notebook, not by the library
+            if let Some(existing) = &notebook.sync_config {
+                config.asset_policy = existing.asset_policy;
+            }
             notebook.sync_config = Some(config.clone());
             storage_guard.update_notebook(&notebook)?;
         }
@@ -3506,7 +4799,7 @@ impl SyncManager {
     ) -> Result<(), SyncError> {
         // Get all notebooks and disable sync for library-managed ones
         let notebooks = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             storage_guard.list_notebooks().unwrap_or_default()
         };
 
@@ -3621,7 +4914,7 @@ impl SyncManager {
 
         // Get local notebook IDs
         let local_notebook_ids: HashSet<Uuid> = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             storage_guard
                 .list_notebooks()
                 .unwrap_or_default()
@@ -3678,10 +4971,11 @@ impl SyncManager {
                 notebook.system_prompt_mode = meta.system_prompt_mode;
                 notebook.ai_provider = meta.ai_provider;
                 notebook.ai_model = meta.ai_model;
+                notebook.ai_profile_id = meta.ai_profile_id;
             }
 
             {
-                let storage_guard = storage.lock().unwrap();
+                let storage_guard = storage.write().unwrap();
                 if let Err(e) = storage_guard.create_notebook_with_id(&notebook) {
                     log::error!(
                         "discover_remote_notebooks: failed to create notebook {}: {}",
@@ -3715,6 +5009,189 @@ impl SyncManager {
         Ok(created)
     }
 
+    // ===== Remote browsing (read-only) =====
+
+    /// Sync config and a client for browsing a library's remote
+    fn library_browse_client(
+        &self,
+        library_id: Uuid,
+        library_storage: &SharedLibraryStorage,
+    ) -> Result<(LibrarySyncConfig, WebDAVClient), SyncError> {
+        let config = {
+            let lib_storage = library_storage.lock().unwrap();
+            lib_storage
+                .get_library(library_id)
+                .ok()
+                .and_then(|lib| lib.sync_config)
+                .ok_or(SyncError::NotConfigured)?
+        };
+        let creds = self.get_library_credentials(library_id)?;
+        let client = WebDAVClient::new(config.server_url.clone(), creds)?;
+        Ok((config, client))
+    }
+
+    /// List the notebooks on a library's server without syncing anything
+    pub async fn browse_remote_library(
+        &self,
+        library_id: Uuid,
+        library_storage: &SharedLibraryStorage,
+        storage: &SharedStorage,
+    ) -> Result<Vec<RemoteNotebook>, SyncError> {
+        let (config, client) = self.library_browse_client(library_id, library_storage)?;
+
+        let notebook_ids: Vec<Uuid> = client
+            .propfind(&config.remote_base_path, 1)
+            .await?
+            .iter()
+            .filter(|e| e.is_collection)
+            .filter_map(|e| {
+                let name = e.path.trim_end_matches('/').rsplit('/').next()?;
+                Uuid::parse_str(name).ok()
+            })
+            .collect();
+
+        let local_ids: HashSet<Uuid> = {
+            let storage_guard = storage.read().unwrap();
+            storage_guard
+                .list_notebooks()
+                .unwrap_or_default()
+                .iter()
+                .map(|n| n.id)
+                .collect()
+        };
+
+        let mut notebooks: Vec<RemoteNotebook> = futures_util::stream::iter(notebook_ids)
+            .map(|notebook_id| {
+                let client = client.clone();
+                let remote_path = format!("{}/{}", config.remote_base_path, notebook_id);
+                let local = local_ids.contains(&notebook_id);
+                async move {
+                    let (meta, manifest) = tokio::join!(
+                        self.fetch_notebook_meta(&client, &remote_path),
+                        Self::fetch_manifest_static(&client, &remote_path),
+                    );
+                    let manifest = manifest.unwrap_or_else(|e| {
+                        log::debug!("Browse: no manifest for notebook {}: {}", notebook_id, e);
+                        None
+                    });
+                    RemoteNotebook::new(notebook_id, meta.ok(), manifest.as_ref(), local)
+                }
+            })
+            .buffer_unordered(DEFAULT_WEBDAV_CONCURRENCY)
+            .collect()
+            .await;
+
+        notebooks.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(notebooks)
+    }
+
+    /// List a remote notebook's pages, folders and sections
+    pub async fn browse_remote_notebook(
+        &self,
+        library_id: Uuid,
+        notebook_id: Uuid,
+        library_storage: &SharedLibraryStorage,
+        storage: &SharedStorage,
+    ) -> Result<RemoteNotebookListing, SyncError> {
+        let (config, client) = self.library_browse_client(library_id, library_storage)?;
+        let remote_path = format!("{}/{}", config.remote_base_path, notebook_id);
+
+        let folders_path = format!("{}/folders.json", remote_path);
+        let sections_path = format!("{}/sections.json", remote_path);
+        let (pages_meta, manifest, folders, sections) = tokio::join!(
+            Self::fetch_pages_meta_static(&client, &remote_path),
+            Self::fetch_manifest_static(&client, &remote_path),
+            client.get(&folders_path),
+            client.get(&sections_path),
+        );
+
+        let manifest = manifest?;
+        let pages_meta = match pages_meta {
+            Ok(meta) => meta,
+            Err(SyncError::WebDAV(WebDAVError::NotFound(_))) => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+        if manifest.is_none() && pages_meta.is_empty() {
+            return Err(SyncError::Other(format!(
+                "Notebook {} not found on the server",
+                notebook_id
+            )));
+        }
+        let folders: Vec<Folder> = match folders {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(WebDAVError::NotFound(_)) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let sections: Vec<Section> = match sections {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(WebDAVError::NotFound(_)) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let local_ids: HashSet<Uuid> = {
+            let storage_guard = storage.read().unwrap();
+            storage_guard
+                .list_page_summaries(notebook_id)
+                .unwrap_or_default()
+                .iter()
+                .map(|p| p.id)
+                .collect()
+        };
+
+        Ok(RemoteNotebookListing {
+            notebook_id,
+            pages: browse::page_summaries(pages_meta, manifest.as_ref(), &local_ids),
+            folders,
+            sections,
+        })
+    }
+
+    /// Render one page from the server's copy, including any delta files
+    /// not yet compacted into it. Nothing is stored locally.
+    pub async fn fetch_remote_page(
+        &self,
+        library_id: Uuid,
+        notebook_id: Uuid,
+        page_id: Uuid,
+        library_storage: &SharedLibraryStorage,
+    ) -> Result<RemotePage, SyncError> {
+        let (config, client) = self.library_browse_client(library_id, library_storage)?;
+        let remote_path = format!("{}/{}", config.remote_base_path, notebook_id);
+
+        let data = match client.get(&format!("{}/pages/{}.crdt", remote_path, page_id)).await {
+            Ok(data) => data,
+            Err(WebDAVError::NotFound(_)) => {
+                return Err(SyncError::Other(format!(
+                    "Page {} not found on the server",
+                    page_id
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let doc = PageDocument::from_state(&data)?;
+
+        if config.delta_sync {
+            let deltas_dir = delta::page_deltas_dir(&remote_path, page_id);
+            let files = client.list_files_recursive(&deltas_dir).await?;
+            let names = delta::group_by_page(files.iter().map(|f| f.path.as_str()))
+                .remove(&page_id)
+                .unwrap_or_default();
+            for name in names {
+                match client.get(&format!("{}/{}", deltas_dir, name)).await {
+                    Ok(update) => doc.apply_update(&update)?,
+                    Err(WebDAVError::NotFound(_)) => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Ok(RemotePage {
+            notebook_id,
+            page_id,
+            content: doc.to_editor_data()?,
+        })
+    }
+
     // ===== Goal sync methods =====
 
     /// Sync goal definitions with remote.
@@ -3987,6 +5464,75 @@ impl SyncManager {
         Ok(merged_changed)
     }
 
+    /// Sync only the inbox of a library. The mobile app uses this, as it
+    /// keeps no notebooks. Returns true if the local inbox changed.
+    pub async fn sync_library_inbox(
+        &self,
+        library_id: Uuid,
+        library_storage: &SharedLibraryStorage,
+        inbox_storage: &SharedInboxStorage,
+    ) -> Result<bool, SyncError> {
+        if self.is_paused() {
+            return Err(SyncError::Paused);
+        }
+        let (config, client) = self.library_browse_client(library_id, library_storage)?;
+        let inbox_changed = self
+            .sync_inbox(&client, &config.remote_base_path, inbox_storage)
+            .await?;
+
+        if inbox_changed {
+            let emitter_guard = self.emitter.lock().unwrap();
+            if let Some(ref e) = *emitter_guard {
+                e.emit_sync_inbox_updated(&SyncInboxUpdated { inbox_changed });
+            }
+        }
+        Ok(inbox_changed)
+    }
+
+    /// Write the daily note appends queued by the mobile app into the
+    /// notebooks this device has, and queue the pages for upload
+    fn apply_mobile_daily_appends(
+        &self,
+        storage: &SharedStorage,
+        inbox_storage: &SharedInboxStorage,
+    ) {
+        let pages = {
+            let storage_guard = storage.write().unwrap();
+            let inbox_guard = inbox_storage.lock().unwrap();
+            crate::mobile::apply_daily_appends(&storage_guard, &inbox_guard)
+        };
+        let pages = match pages {
+            Ok(pages) => pages,
+            Err(e) => {
+                log::warn!("Library sync: applying mobile daily note appends failed: {}", e);
+                return;
+            }
+        };
+
+        if pages.is_empty() {
+            return;
+        }
+        log::info!("Library sync: applied mobile daily note appends to {} pages", pages.len());
+
+        let mut by_notebook: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for page in &pages {
+            self.queue_page_update(page.notebook_id, page.id);
+            by_notebook
+                .entry(page.notebook_id)
+                .or_default()
+                .push(page.id.to_string());
+        }
+        let emitter_guard = self.emitter.lock().unwrap();
+        if let Some(ref e) = *emitter_guard {
+            for (notebook_id, page_ids) in by_notebook {
+                e.emit_sync_pages_updated(&SyncPagesUpdated {
+                    notebook_id: notebook_id.to_string(),
+                    page_ids,
+                });
+            }
+        }
+    }
+
     // ===== Contacts sync methods =====
 
     /// Sync contacts with remote.
@@ -4227,6 +5773,200 @@ impl SyncManager {
         Ok(merged_changed)
     }
 
+    // ===== Library document sync =====
+
+    /// Merge one document collection with `{base}/library/{name}.json` and
+    /// return the documents this device has to apply.
+    async fn sync_doc_collection(
+        &self,
+        client: &WebDAVClient,
+        library_base_path: &str,
+        name: &str,
+        local: Vec<SyncedDoc>,
+        state: &mut LibraryDocsState,
+    ) -> Result<Vec<SyncedDoc>, SyncError> {
+        let remote_path = format!("{}/library/{}.json", library_base_path, name);
+
+        let remote: DocCollection = match client.get(&remote_path).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+            Err(WebDAVError::NotFound(_)) => DocCollection::default(),
+            Err(e) => return Err(e.into()),
+        };
+        // Don't rewrite a file this version can't fully understand
+        if remote.version > library_docs::FORMAT_VERSION {
+            return Err(SyncError::Other(format!(
+                "library {} were synced by a newer version of Nous",
+                name
+            )));
+        }
+
+        let merged = library_docs::merge(&local, &remote.docs, Utc::now());
+        if merged.push {
+            let data = serde_json::to_vec_pretty(&DocCollection {
+                version: library_docs::FORMAT_VERSION,
+                docs: merged.docs.clone(),
+            })?;
+            let _ = client.mkdir_p(&format!("{}/library", library_base_path)).await;
+            client.put(&remote_path, &data, None).await?;
+        }
+
+        state.record(name, &merged.docs);
+        Ok(merged.pull)
+    }
+
+    /// Sync custom actions. Built-in actions are regenerated on every device
+    /// and stay local. Returns true if local actions were changed.
+    async fn sync_actions(
+        &self,
+        client: &WebDAVClient,
+        library_base_path: &str,
+        action_storage: &SharedActionStorage,
+        state: &mut LibraryDocsState,
+    ) -> Result<bool, SyncError> {
+        let local = {
+            let storage = action_storage.lock().unwrap();
+            let mut docs = Vec::new();
+            for action in storage.list_actions()? {
+                if !action.is_built_in {
+                    let data = serde_json::to_value(&action)?;
+                    docs.push(SyncedDoc::new(action.id.to_string(), action.updated_at, data));
+                }
+            }
+            docs
+        };
+        let local = state.with_tombstones("actions", local, Utc::now());
+
+        let pulled = self
+            .sync_doc_collection(client, library_base_path, "actions", local, state)
+            .await?;
+        if pulled.is_empty() {
+            return Ok(false);
+        }
+
+        let storage = action_storage.lock().unwrap();
+        for doc in &pulled {
+            let Ok(action_id) = Uuid::parse_str(&doc.id) else {
+                continue;
+            };
+            if doc.deleted {
+                if let Err(e) = storage.delete_action(action_id) {
+                    log::warn!("Library sync: failed to delete action {}: {}", action_id, e);
+                }
+                continue;
+            }
+            let mut action: Action = match serde_json::from_value(doc.data.clone()) {
+                Ok(action) => action,
+                Err(e) => {
+                    log::warn!("Library sync: skipping unreadable action {}: {}", action_id, e);
+                    continue;
+                }
+            };
+            // Run times are per device; the scheduler recomputes next_run
+            // from the (possibly changed) schedule
+            action.last_run = storage
+                .get_action(action_id)
+                .ok()
+                .and_then(|existing| existing.last_run);
+            action.next_run = None;
+            storage.create_action(action)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Sync library settings key by key. Returns true if local settings
+    /// were changed.
+    async fn sync_library_settings(
+        &self,
+        client: &WebDAVClient,
+        library_base_path: &str,
+        library_path: &Path,
+        state: &mut LibraryDocsState,
+    ) -> Result<bool, SyncError> {
+        // Settings are never deleted, so no tombstones
+        let local: Vec<SyncedDoc> = LibrarySettings::load(library_path)
+            .entries()
+            .iter()
+            .map(|(key, entry)| SyncedDoc::new(key.clone(), entry.updated_at, entry.value.clone()))
+            .collect();
+
+        let pulled = self
+            .sync_doc_collection(client, library_base_path, "settings", local, state)
+            .await?;
+        if pulled.is_empty() {
+            return Ok(false);
+        }
+
+        let mut settings = LibrarySettings::load(library_path);
+        for doc in pulled.iter().filter(|doc| !doc.deleted) {
+            settings.put(&doc.id, doc.data.clone(), doc.updated_at);
+        }
+        settings
+            .save()
+            .map_err(|e| SyncError::Other(e.to_string()))?;
+        Ok(true)
+    }
+
+    /// Merge the library scratchpad with the server's copy and push it if
+    /// this device has edits the server hasn't got. Returns true if the
+    /// local text changed.
+    pub async fn sync_scratchpad(
+        &self,
+        library_id: Uuid,
+        library_storage: &SharedLibraryStorage,
+    ) -> Result<bool, SyncError> {
+        if self.is_paused() {
+            return Err(SyncError::Paused);
+        }
+        let library = {
+            let lib_storage = library_storage.lock().unwrap();
+            lib_storage.get_library(library_id).ok()
+        };
+        let Some((lib_config, library_path)) =
+            library.and_then(|lib| lib.sync_config.map(|config| (config, lib.path)))
+        else {
+            return Err(SyncError::NotConfigured);
+        };
+        let creds = self.get_library_credentials(library_id)?;
+        let client = WebDAVClient::new(lib_config.server_url.clone(), creds)?;
+        let base_path = &lib_config.remote_base_path;
+        let remote_path = format!("{}/library/{}", base_path, scratchpad::FILE_NAME);
+
+        let mut changed = false;
+        // A push loses its If-Match race when another device pushed in
+        // between; merging theirs and trying again settles it
+        for _ in 0..3 {
+            let pushed_edits = scratchpad::pending_edits(&library_path);
+            let (remote, etag) = match client.get_with_etag(&remote_path).await {
+                Ok((data, etag)) => (Some(data), etag),
+                Err(WebDAVError::NotFound(_)) => (None, None),
+                Err(e) => return Err(e.into()),
+            };
+            let (pulled, state) = scratchpad::merge_remote(&library_path, remote.as_deref())?;
+            changed |= pulled;
+            if pulled {
+                let emitter_guard = self.emitter.lock().unwrap();
+                if let Some(ref e) = *emitter_guard {
+                    e.emit_sync_scratchpad_updated(library_id);
+                }
+            }
+            if pushed_edits == 0 {
+                return Ok(changed);
+            }
+
+            if remote.is_none() {
+                let _ = client.mkdir_p(&format!("{}/library", base_path)).await;
+            }
+            let response = client.put(&remote_path, &state, etag.as_deref()).await?;
+            if !response.conflict {
+                scratchpad::mark_pushed(&library_path, pushed_edits)?;
+                return Ok(changed);
+            }
+            log::info!("Scratchpad changed on the server during push; merging again");
+        }
+        Err(SyncError::Other("scratchpad kept changing on the server".to_string()))
+    }
+
     /// Sync all notebooks in a library (notebooks synced concurrently)
     pub async fn sync_library(
         &self,
@@ -4237,8 +5977,13 @@ impl SyncManager {
         inbox_storage: &SharedInboxStorage,
         contacts_storage: &SharedContactsStorage,
         energy_storage: &SharedEnergyStorage,
+        action_storage: &SharedActionStorage,
     ) -> Result<SyncResult, SyncError> {
+        if self.is_paused() {
+            return Err(SyncError::Paused);
+        }
         let start = std::time::Instant::now();
+        let _timer = diagnostics::time(Category::Sync, "sync_library");
 
         // Get library sync config
         let library_config = {
@@ -4272,7 +6017,7 @@ impl SyncManager {
 
         // Re-read notebook list (includes any newly discovered notebooks)
         let notebooks = {
-            let storage_guard = storage.lock().unwrap();
+            let storage_guard = storage.read().unwrap();
             storage_guard.list_notebooks().unwrap_or_default()
         };
 
@@ -4357,6 +6102,7 @@ impl SyncManager {
         let total_conflicts = Arc::new(AtomicUsize::new(0));
         let total_assets_pushed = Arc::new(AtomicUsize::new(0));
         let total_assets_pulled = Arc::new(AtomicUsize::new(0));
+        let any_assets_deferred = Arc::new(AtomicBool::new(false));
         let errors: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
 
         let results: Vec<()> = futures_util::stream::iter(notebook_ids_to_sync)
@@ -4367,10 +6113,11 @@ impl SyncManager {
                 let tc = Arc::clone(&total_conflicts);
                 let tap = Arc::clone(&total_assets_pushed);
                 let tapl = Arc::clone(&total_assets_pulled);
+                let deferred = Arc::clone(&any_assets_deferred);
                 let errs = Arc::clone(&errors);
                 async move {
                     let nb_name = {
-                        let sg = storage.lock().unwrap();
+                        let sg = storage.read().unwrap();
                         sg.get_notebook(notebook_id)
                             .map(|n| n.name.clone())
                             .unwrap_or_else(|_| notebook_id.to_string())
@@ -4394,6 +6141,9 @@ impl SyncManager {
                             tc.fetch_add(result.conflicts_resolved, Ordering::Relaxed);
                             tap.fetch_add(result.assets_pushed, Ordering::Relaxed);
                             tapl.fetch_add(result.assets_pulled, Ordering::Relaxed);
+                            if result.assets_deferred {
+                                deferred.store(true, Ordering::Relaxed);
+                            }
                         }
                         Err(e) => {
                             log::warn!("Failed to sync notebook {}: {}", notebook_id, e);
@@ -4414,6 +6164,7 @@ impl SyncManager {
         let total_conflicts = total_conflicts.load(Ordering::Relaxed);
         let total_assets_pushed = total_assets_pushed.load(Ordering::Relaxed);
         let total_assets_pulled = total_assets_pulled.load(Ordering::Relaxed);
+        let assets_deferred = any_assets_deferred.load(Ordering::Relaxed);
         let errors = errors.lock().unwrap().clone();
 
         // Sync goals after notebooks
@@ -4517,6 +6268,8 @@ impl SyncManager {
                                     e.emit_sync_inbox_updated(&event_payload);
                                 }
                             }
+
+                            self.apply_mobile_daily_appends(storage, inbox_storage);
                         }
                         Err(e) => {
                             log::warn!("Library sync: failed to create WebDAV client for inbox: {}", e);
@@ -4630,6 +6383,83 @@ impl SyncManager {
             }
         }
 
+        // Sync library documents (custom actions, settings) after energy
+        let library_path = {
+            let lib_storage = library_storage.lock().unwrap();
+            lib_storage.get_library(library_id).ok().map(|lib| lib.path)
+        };
+        if let (Some(lib_config), Some(library_path)) = (&library_config, library_path) {
+            match self.get_library_credentials(library_id) {
+                Ok(creds) => {
+                    match WebDAVClient::new(lib_config.server_url.clone(), creds) {
+                        Ok(docs_client) => {
+                            let base_path = &lib_config.remote_base_path;
+                            let state_path = LibraryDocsState::path(&library_path);
+                            let mut docs_state = LibraryDocsState::load(&state_path);
+
+                            let actions_changed = match self
+                                .sync_actions(&docs_client, base_path, action_storage, &mut docs_state)
+                                .await
+                            {
+                                Ok(changed) => {
+                                    log::info!("Library sync: actions sync complete, changed={}", changed);
+                                    changed
+                                }
+                                Err(e) => {
+                                    log::warn!("Library sync: actions sync failed: {}", e);
+                                    false
+                                }
+                            };
+
+                            let settings_changed = match self
+                                .sync_library_settings(&docs_client, base_path, &library_path, &mut docs_state)
+                                .await
+                            {
+                                Ok(changed) => {
+                                    log::info!("Library sync: settings sync complete, changed={}", changed);
+                                    changed
+                                }
+                                Err(e) => {
+                                    log::warn!("Library sync: settings sync failed: {}", e);
+                                    false
+                                }
+                            };
+
+                            if let Err(e) = docs_state.save(&state_path) {
+                                log::warn!("Library sync: failed to save library document state: {}", e);
+                            }
+
+                            if actions_changed || settings_changed {
+                                let event_payload = SyncLibraryDocsUpdated {
+                                    actions_changed,
+                                    settings_changed,
+                                };
+
+                                let emitter_guard = self.emitter.lock().unwrap();
+                                if let Some(ref e) = *emitter_guard {
+                                    e.emit_sync_library_docs_updated(&event_payload);
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            log::warn!("Library sync: failed to create WebDAV client for library documents: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Library sync: failed to get credentials for library documents: {}", e);
+                }
+            }
+        }
+
+        // The scratchpad also syncs on every edit; this catches edits from
+        // other devices and pushes that failed
+        if library_config.is_some() {
+            if let Err(e) = self.sync_scratchpad(library_id, library_storage).await {
+                log::warn!("Library sync: scratchpad sync failed: {}", e);
+            }
+        }
+
         log::info!(
             "Library sync complete: pulled={}, pushed={}, conflicts={}, errors={}",
             total_pulled,
@@ -4641,14 +6471,16 @@ impl SyncManager {
         let duration_ms = start.elapsed().as_millis() as u64;
 
         if errors.is_empty() {
-            Ok(SyncResult::success(
+            let mut result = SyncResult::success(
                 total_pulled,
                 total_pushed,
                 total_conflicts,
                 duration_ms,
                 total_assets_pushed,
                 total_assets_pulled,
-            ))
+            );
+            result.assets_deferred = assets_deferred;
+            Ok(result)
         } else {
             Ok(SyncResult {
                 success: false,
@@ -4659,6 +6491,7 @@ impl SyncManager {
                 duration_ms,
                 assets_pushed: total_assets_pushed,
                 assets_pulled: total_assets_pulled,
+                assets_deferred,
             })
         }
     }
@@ -4727,6 +6560,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn asset_policy_defers_media_but_not_page_files() {
+        use AssetSyncPolicy::*;
+        assert!(!SyncManager::defer_asset_pull(All, "images/x.png", false));
+        assert!(SyncManager::defer_asset_pull(OnDemand, "video/talk.mp4", false));
+        // Already-downloaded assets keep syncing
+        assert!(!SyncManager::defer_asset_pull(OnDemand, "video/talk.mp4", true));
+        assert!(SyncManager::defer_asset_pull(Never, "pdf/paper.pdf", true));
+        assert!(!SyncManager::defer_asset_pull(Never, "files/abc.database", false));
+    }
+
     /// DL-21: two SyncManager instances sharing one data_dir (the Tauri app +
     /// the daemon) must not sync the same notebook concurrently. Exercises the
     /// exact lock file `sync_notebook()` contends on: while one holds the
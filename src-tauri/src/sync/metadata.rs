@@ -29,6 +29,20 @@ pub struct LocalSyncState {
     /// ETag of the .sync-sentinel file (for change notification)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sentinel_etag: Option<String>,
+    /// Per-page comment file sync state
+    #[serde(default)]
+    pub comments: HashMap<Uuid, LocalCommentsState>,
+}
+
+/// Local sync state for a page's comment file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalCommentsState {
+    /// ETag of the remote comment file after the last sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_etag: Option<String>,
+    /// Content hash of the comments both sides agreed on at the last sync
+    pub content_hash: String,
 }
 
 /// Local sync state for a single asset
@@ -65,6 +79,13 @@ pub struct LocalPageState {
     /// State vector from last sync
     #[serde(skip_serializing_if = "Option::is_none")]
     pub synced_state_vector: Option<Vec<u8>>,
+    /// Remote delta files already merged into the local CRDT (delta sync)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub applied_deltas: Vec<String>,
+    /// When this device last uploaded a version of the page (see
+    /// `sync::versions`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_version_at: Option<DateTime<Utc>>,
 }
 
 impl LocalSyncState {
@@ -79,9 +100,15 @@ impl LocalSyncState {
             assets: HashMap::new(),
             last_changelog_seq: 0,
             sentinel_etag: None,
+            comments: HashMap::new(),
         }
     }
 
+    /// Sync as a new device from now on (see `sync::devices`)
+    pub fn reset_client_id(&mut self) {
+        self.client_id = generate_client_id();
+    }
+
     /// Load from file
     pub fn load(path: &Path) -> Result<Self, std::io::Error> {
         let data = std::fs::read_to_string(path)?;
@@ -145,6 +172,8 @@ impl LocalSyncState {
             local_modified: Utc::now(),
             last_synced: None,
             synced_state_vector: None,
+            applied_deltas: Vec::new(),
+            last_version_at: None,
         });
         entry.local_modified = Utc::now();
     }
@@ -157,12 +186,21 @@ impl LocalSyncState {
             local_modified: now,
             last_synced: None,
             synced_state_vector: None,
+            applied_deltas: Vec::new(),
+            last_version_at: None,
         });
         entry.remote_etag = etag;
         entry.last_synced = Some(now);
         entry.synced_state_vector = Some(state_vector);
     }
 
+    /// Record which remote delta files are merged into a synced page
+    pub fn set_applied_deltas(&mut self, page_id: Uuid, deltas: Vec<String>) {
+        if let Some(entry) = self.pages.get_mut(&page_id) {
+            entry.applied_deltas = deltas;
+        }
+    }
+
     /// Remove page from sync state
     pub fn remove_page(&mut self, page_id: Uuid) {
         self.pages.remove(&page_id);
@@ -1,28 +1,45 @@
+pub mod bandwidth;
+pub mod browse;
+pub mod compression;
 pub mod config;
 pub mod crdt;
+pub mod delta;
+pub mod devices;
 pub mod events;
+pub mod library_docs;
+pub mod locks;
 pub mod metadata;
 pub mod notify;
 pub mod queue;
 pub mod scheduler;
+pub mod scratchpad;
+pub mod verify;
+pub mod versions;
 pub mod webdav;
 
 mod manager;
 
 pub use config::{
-    AssetManifest, AssetManifestEntry, AuthType, Changelog, ChangelogEntry, ChangeOperation,
-    LibrarySyncConfig, LibrarySyncConfigInput, NotebookMeta, PageMeta, PageSyncState, ServerType,
-    SyncConfig, SyncConfigInput, SyncCredentials, SyncManifest, SyncMode, SyncResult, SyncState,
+    AssetManifest, AssetManifestEntry, AssetSyncPolicy, AuthType, Changelog, ChangelogEntry,
+    ChangeOperation, LibrarySyncConfig, LibrarySyncConfigInput, NotebookMeta, PageMeta,
+    PageSyncState, ServerType,
+    SyncBandwidth, SyncConfig, SyncConfigInput, SyncCredentials, SyncManifest, SyncMode, SyncResult, SyncState,
     SyncStatus,
 };
+pub use browse::{RemoteNotebook, RemoteNotebookListing, RemotePage, RemotePageSummary};
+pub use compression::SyncCompression;
 pub use crdt::{CRDTError, CrdtStore, PageDocument};
+pub use devices::{SyncDevice, SyncDeviceInfo};
 pub use events::{LogEmitter, SyncEventEmitter, TauriEmitter};
+pub use locks::{PageLock, PageLockInfo};
 pub use manager::{
-    SyncContactsUpdated, SyncEnergyUpdated, SyncGoalsUpdated, SyncInboxUpdated, SyncManager,
-    SyncPagesUpdated, SyncProgress,
+    SyncActivity, SyncContactsUpdated, SyncEnergyUpdated, SyncGoalsUpdated, SyncInboxUpdated,
+    SyncError, SyncLibraryDocsUpdated, SyncManager, SyncPagesUpdated, SyncProgress,
 };
-pub use metadata::{LocalAssetState, LocalPageState, LocalSyncState};
+pub use metadata::{LocalAssetState, LocalCommentsState, LocalPageState, LocalSyncState};
 pub use notify::NotifyPushListener;
-pub use queue::{QueueItem, SyncOperation, SyncQueue};
+pub use queue::{NotebookQueueDetails, QueueItem, QueuedOperation, SyncOperation, SyncQueue};
 pub use scheduler::{SyncScheduler, SyncSchedulerMessage};
+pub use verify::{DiscrepancyKind, RepairAction, SyncDiscrepancy, SyncRepair, SyncVerifyReport};
+pub use versions::RemotePageVersion;
 pub use webdav::{HeadResponse, PutResponse, ResourceInfo, WebDAVClient, WebDAVError};
@@ -48,6 +48,31 @@ pub enum SyncOperation {
     DeleteAsset { asset_path: String },
 }
 
+/// A queued operation with how long it has been waiting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedOperation {
+    pub id: Uuid,
+    pub operation: SyncOperation,
+    pub created_at: DateTime<Utc>,
+    pub age_secs: i64,
+    pub retries: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Pending operations for one notebook, oldest first
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookQueueDetails {
+    pub notebook_id: Uuid,
+    /// Filled in by the caller, which has access to storage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook_name: Option<String>,
+    pub oldest_age_secs: i64,
+    pub items: Vec<QueuedOperation>,
+}
+
 impl SyncQueue {
     pub fn new() -> Self {
         Self { items: Vec::new() }
@@ -128,6 +153,41 @@ impl SyncQueue {
         }
     }
 
+    /// Pending operations grouped by notebook, the notebook with the oldest
+    /// operation first
+    pub fn details(&self, now: DateTime<Utc>) -> Vec<NotebookQueueDetails> {
+        let mut notebooks: Vec<NotebookQueueDetails> = Vec::new();
+        for item in &self.items {
+            let operation = QueuedOperation {
+                id: item.id,
+                operation: item.operation.clone(),
+                created_at: item.created_at,
+                age_secs: (now - item.created_at).num_seconds().max(0),
+                retries: item.retries,
+                last_error: item.last_error.clone(),
+            };
+            match notebooks
+                .iter_mut()
+                .find(|n| n.notebook_id == item.notebook_id)
+            {
+                Some(notebook) => notebook.items.push(operation),
+                None => notebooks.push(NotebookQueueDetails {
+                    notebook_id: item.notebook_id,
+                    notebook_name: None,
+                    oldest_age_secs: 0,
+                    items: vec![operation],
+                }),
+            }
+        }
+
+        for notebook in &mut notebooks {
+            notebook.items.sort_by_key(|item| item.created_at);
+            notebook.oldest_age_secs = notebook.items[0].age_secs;
+        }
+        notebooks.sort_by(|a, b| b.oldest_age_secs.cmp(&a.oldest_age_secs));
+        notebooks
+    }
+
     /// Remove all items for a notebook
     pub fn clear_notebook(&mut self, notebook_id: Uuid) {
         self.items.retain(|item| item.notebook_id != notebook_id);
@@ -184,4 +244,30 @@ mod tests {
         // Should have two items
         assert_eq!(queue.pending_count(notebook_id), 2);
     }
+
+    #[test]
+    fn test_queue_details_grouped_with_ages() {
+        let mut queue = SyncQueue::new();
+        let recent_notebook = Uuid::new_v4();
+        let stale_notebook = Uuid::new_v4();
+        let now = Utc::now();
+
+        queue.enqueue(recent_notebook, SyncOperation::UpdateFolders);
+        queue.enqueue(stale_notebook, SyncOperation::UpdateSections);
+        queue.enqueue(stale_notebook, SyncOperation::UpdateNotebook);
+        queue.items[0].created_at = now - chrono::Duration::minutes(5);
+        queue.items[1].created_at = now - chrono::Duration::minutes(30);
+        queue.items[2].created_at = now - chrono::Duration::hours(2);
+
+        let details = queue.details(now);
+        assert_eq!(details.len(), 2);
+        assert_eq!(details[0].notebook_id, stale_notebook);
+        assert_eq!(details[0].oldest_age_secs, 2 * 60 * 60);
+        assert!(matches!(
+            details[0].items[0].operation,
+            SyncOperation::UpdateNotebook
+        ));
+        assert_eq!(details[0].items[1].age_secs, 30 * 60);
+        assert_eq!(details[1].oldest_age_secs, 5 * 60);
+    }
 }
@@ -5,7 +5,7 @@ use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use super::config::SyncMode;
-use super::manager::{SharedContactsStorage, SharedEnergyStorage, SharedGoalsStorage, SharedInboxStorage, SharedLibraryStorage, SharedStorage, SyncManager};
+use super::manager::{SharedActionStorage, SharedContactsStorage, SharedEnergyStorage, SharedGoalsStorage, SharedInboxStorage, SharedLibraryStorage, SharedStorage, SyncManager};
 
 /// Messages to control the sync scheduler
 #[derive(Debug)]
@@ -91,12 +91,13 @@ pub fn start_sync_scheduler(
     inbox_storage: SharedInboxStorage,
     contacts_storage: SharedContactsStorage,
     energy_storage: SharedEnergyStorage,
+    action_storage: SharedActionStorage,
     should_yield: Option<ShouldYield>,
 ) -> SyncScheduler {
     let (tx, rx) = mpsc::channel(32);
 
     tauri::async_runtime::spawn(async move {
-        sync_scheduler_loop(sync_manager, storage, library_storage, goals_storage, inbox_storage, contacts_storage, energy_storage, should_yield, rx).await;
+        sync_scheduler_loop(sync_manager, storage, library_storage, goals_storage, inbox_storage, contacts_storage, energy_storage, action_storage, should_yield, rx).await;
     });
 
     // Trigger initial scan
@@ -107,16 +108,22 @@ pub fn start_sync_scheduler(
 
 /// Collect all items that have periodic sync enabled
 fn collect_periodic_items(
-    storage: &Arc<Mutex<crate::storage::FileStorage>>,
+    storage: &Arc<crate::storage::StorageLock>,
     library_storage: &Arc<Mutex<crate::library::LibraryStorage>>,
 ) -> Vec<SyncItem> {
     let mut items = Vec::new();
 
-    // Collect libraries with periodic sync
-    let libraries = {
+    // Collect libraries with periodic sync. Only the library `storage` points
+    // at: each open library runs its own scheduler, and syncing another
+    // library through this storage would mix their notebooks.
+    let notebooks_dir = storage.read().unwrap().notebooks_base_dir();
+    let libraries: Vec<_> = {
         let lib_store = library_storage.lock().unwrap();
         lib_store.list_libraries().unwrap_or_default()
-    };
+    }
+    .into_iter()
+    .filter(|library| library.path.join("notebooks") == notebooks_dir)
+    .collect();
 
     let mut library_synced_ids = std::collections::HashSet::new();
 
@@ -144,7 +151,7 @@ fn collect_periodic_items(
 
     // Collect standalone notebooks with periodic sync
     let notebooks = {
-        let store = storage.lock().unwrap();
+        let store = storage.read().unwrap();
         store.list_notebooks().unwrap_or_default()
     };
 
@@ -176,9 +183,9 @@ fn collect_periodic_items(
 /// This serves as a proxy for "when the library was last synced".
 fn find_library_last_sync(
     _library_id: Uuid,
-    storage: &Arc<Mutex<crate::storage::FileStorage>>,
+    storage: &Arc<crate::storage::StorageLock>,
 ) -> Option<chrono::DateTime<Utc>> {
-    let store = storage.lock().unwrap();
+    let store = storage.read().unwrap();
     let notebooks = store.list_notebooks().unwrap_or_default();
 
     let mut oldest: Option<chrono::DateTime<Utc>> = None;
@@ -249,6 +256,7 @@ async fn sync_scheduler_loop(
     inbox_storage: SharedInboxStorage,
     contacts_storage: SharedContactsStorage,
     energy_storage: SharedEnergyStorage,
+    action_storage: SharedActionStorage,
     should_yield: Option<ShouldYield>,
     mut receiver: mpsc::Receiver<SyncSchedulerMessage>,
 ) {
@@ -384,6 +392,13 @@ async fn sync_scheduler_loop(
                         continue;
                     }
 
+                    // Paused from the tray/settings: same treatment as yielding
+                    if sync_manager.is_paused() {
+                        log::debug!("Sync scheduler: sync paused — skipping scheduled sync for {}", id);
+                        last_checked.insert(id, Utc::now());
+                        continue;
+                    }
+
                     if is_library {
                         // Check sentinel before running full sync
                         if !should_sync_library(&sync_manager, id, &library_storage).await {
@@ -395,7 +410,7 @@ async fn sync_scheduler_loop(
 
                         log::info!("Sync scheduler: running periodic sync for library {}", id);
                         match sync_manager
-                            .sync_library(id, &library_storage, &storage, &goals_storage, &inbox_storage, &contacts_storage, &energy_storage)
+                            .sync_library(id, &library_storage, &storage, &goals_storage, &inbox_storage, &contacts_storage, &energy_storage, &action_storage)
                             .await
                         {
                             Ok(result) => {
@@ -459,9 +474,16 @@ async fn sync_scheduler_loop(
                             );
                             continue;
                         }
+                        if sync_manager.is_paused() {
+                            log::debug!(
+                                "Sync scheduler: sync paused — ignoring remote-change trigger for library {}",
+                                library_id,
+                            );
+                            continue;
+                        }
                         log::info!("Sync scheduler: remote change detected for library {}, triggering sync", library_id);
                         match sync_manager
-                            .sync_library(library_id, &library_storage, &storage, &goals_storage, &inbox_storage, &contacts_storage, &energy_storage)
+                            .sync_library(library_id, &library_storage, &storage, &goals_storage, &inbox_storage, &contacts_storage, &energy_storage, &action_storage)
                             .await
                         {
                             Ok(result) => {
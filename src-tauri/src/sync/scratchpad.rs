@@ -0,0 +1,319 @@
+//! Per-library scratchpad
+//!
+//! A single plain-text document for moving snippets between devices without
+//! making pages. It is a Yrs text, so edits made on two devices before they
+//! sync interleave instead of one overwriting the other. The state lives in
+//! `{library}/sync/scratchpad.crdt` and on the server as
+//! `{base}/library/scratchpad.crdt`; the manager syncs it after every local
+//! change and with the rest of the library.
+//!
+//! Yrs state vectors don't cover deletions, so they can't tell whether the
+//! server is behind. Local edits instead bump a counter in
+//! `scratchpad.pending`, and the file is removed once a push of that edit
+//! succeeds.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use yrs::updates::decoder::Decode;
+use yrs::{
+    types::ToJson, Any, GetString, Map, OffsetKind, Options, ReadTxn, StateVector, Text, Transact,
+    Update, WriteTxn,
+};
+
+use super::crdt::CRDTError;
+
+/// Name of the scratchpad file, locally and on the server
+pub const FILE_NAME: &str = "scratchpad.crdt";
+
+/// Longest scratchpad accepted, in bytes
+pub const MAX_LEN: usize = 256 * 1024;
+
+/// Serializes reads and writes of the scratchpad files between commands
+/// and sync
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// The scratchpad as the frontend sees it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScratchpadContent {
+    pub text: String,
+    /// Last edit on any device
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Local edits the server hasn't got yet
+    pub pending_push: bool,
+}
+
+/// The scratchpad document
+pub struct Scratchpad {
+    doc: yrs::Doc,
+}
+
+impl Scratchpad {
+    pub fn new() -> Self {
+        // Byte offsets, to match the prefix/suffix diff in `set_text`
+        let doc = yrs::Doc::with_options(Options {
+            offset_kind: OffsetKind::Bytes,
+            ..Options::default()
+        });
+        Self { doc }
+    }
+
+    pub fn from_state(state: &[u8]) -> Result<Self, CRDTError> {
+        let scratchpad = Self::new();
+        scratchpad.apply_update(state)?;
+        Ok(scratchpad)
+    }
+
+    pub fn text(&self) -> String {
+        let txn = self.doc.transact();
+        txn.get_text("text")
+            .map(|text| text.get_string(&txn))
+            .unwrap_or_default()
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        let txn = self.doc.transact();
+        let millis = match txn.get_map("meta")?.get(&txn, "updatedAt")?.to_json(&txn) {
+            Any::BigInt(millis) => millis,
+            Any::Number(millis) => millis as i64,
+            _ => return None,
+        };
+        Utc.timestamp_millis_opt(millis).single()
+    }
+
+    /// Replace the text, as one edit covering only the span that changed so
+    /// concurrent edits elsewhere in the text survive the merge. Returns
+    /// false if nothing changed.
+    pub fn set_text(&self, new: &str, now: DateTime<Utc>) -> bool {
+        let old = self.text();
+        if old == new {
+            return false;
+        }
+        let (start, old_end, new_end) = changed_span(&old, new);
+
+        let mut txn = self.doc.transact_mut();
+        let text = txn.get_or_insert_text("text");
+        if old_end > start {
+            text.remove_range(&mut txn, start as u32, (old_end - start) as u32);
+        }
+        if new_end > start {
+            text.insert(&mut txn, start as u32, &new[start..new_end]);
+        }
+        let meta = txn.get_or_insert_map("meta");
+        meta.insert(&mut txn, "updatedAt", now.timestamp_millis());
+        true
+    }
+
+    pub fn encode_state(&self) -> Vec<u8> {
+        let txn = self.doc.transact();
+        txn.encode_state_as_update_v1(&StateVector::default())
+    }
+
+    fn state_vector(&self) -> StateVector {
+        self.doc.transact().state_vector()
+    }
+
+    /// Merge an update (or a full state) from another device. Returns true
+    /// if it had anything this document didn't.
+    pub fn apply_update(&self, update: &[u8]) -> Result<bool, CRDTError> {
+        let before = self.state_vector();
+        let before_text = self.text();
+        let update =
+            Update::decode_v1(update).map_err(|e| CRDTError::DecodeError(e.to_string()))?;
+        self.doc
+            .transact_mut()
+            .apply_update(update)
+            .map_err(|e| CRDTError::DecodeError(format!("Failed to apply update: {:?}", e)))?;
+        // Deletions don't move the state vector, so compare the text too
+        Ok(self.state_vector() != before || self.text() != before_text)
+    }
+}
+
+impl Default for Scratchpad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Byte offsets of the span that differs between `old` and `new`: it starts
+/// at `start` in both and ends at `old_end` in `old`, `new_end` in `new`.
+/// Offsets fall on character boundaries.
+fn changed_span(old: &str, new: &str) -> (usize, usize, usize) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| old.len().min(new.len()));
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    (prefix, old.len() - suffix, new.len() - suffix)
+}
+
+/// `{library}/sync/scratchpad.crdt`
+pub fn path(library_path: &Path) -> PathBuf {
+    library_path.join("sync").join(FILE_NAME)
+}
+
+fn pending_path(library_path: &Path) -> PathBuf {
+    library_path.join("sync").join("scratchpad.pending")
+}
+
+fn load(library_path: &Path) -> io::Result<Scratchpad> {
+    match fs::read(path(library_path)) {
+        Ok(state) => Scratchpad::from_state(&state)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Scratchpad::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn save(library_path: &Path, scratchpad: &Scratchpad) -> io::Result<()> {
+    let path = path(library_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension("crdt.tmp");
+    fs::write(&temp, scratchpad.encode_state())?;
+    fs::rename(&temp, &path)
+}
+
+/// Local edits since the last successful push, 0 if none
+pub fn pending_edits(library_path: &Path) -> u64 {
+    fs::read_to_string(pending_path(library_path))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn content(library_path: &Path, scratchpad: &Scratchpad) -> ScratchpadContent {
+    ScratchpadContent {
+        text: scratchpad.text(),
+        updated_at: scratchpad.updated_at(),
+        pending_push: pending_edits(library_path) > 0,
+    }
+}
+
+/// The library's scratchpad; empty if it has never been written
+pub fn read(library_path: &Path) -> io::Result<ScratchpadContent> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(content(library_path, &load(library_path)?))
+}
+
+/// Replace the scratchpad text and mark it for pushing
+pub fn write(library_path: &Path, text: &str) -> io::Result<ScratchpadContent> {
+    if text.len() > MAX_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("scratchpad is limited to {} KB", MAX_LEN / 1024),
+        ));
+    }
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let scratchpad = load(library_path)?;
+    if scratchpad.set_text(text, Utc::now()) {
+        save(library_path, &scratchpad)?;
+        fs::write(
+            pending_path(library_path),
+            (pending_edits(library_path) + 1).to_string(),
+        )?;
+    }
+    Ok(content(library_path, &scratchpad))
+}
+
+/// Merge the server's state into the local scratchpad. Returns whether the
+/// local text changed and the merged state to push.
+pub fn merge_remote(library_path: &Path, remote: Option<&[u8]>) -> io::Result<(bool, Vec<u8>)> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let scratchpad = load(library_path)?;
+    let changed = match remote {
+        Some(remote) => scratchpad
+            .apply_update(remote)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?,
+        None => false,
+    };
+    if changed {
+        save(library_path, &scratchpad)?;
+    }
+    Ok((changed, scratchpad.encode_state()))
+}
+
+/// Record a successful push of everything up to `pushed_edits` (the value
+/// of [`pending_edits`] before merging). Edits made during the push stay
+/// pending.
+pub fn mark_pushed(library_path: &Path, pushed_edits: u64) -> io::Result<()> {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if pending_edits(library_path) == pushed_edits {
+        if let Err(e) = fs::remove_file(pending_path(library_path)) {
+            if e.kind() != io::ErrorKind::NotFound {
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_span_keeps_common_prefix_and_suffix() {
+        assert_eq!(changed_span("hello world", "hello brave world"), (6, 6, 12));
+        assert_eq!(changed_span("abc", "ac"), (1, 2, 1));
+        assert_eq!(changed_span("", "new"), (0, 0, 3));
+        // Never splits a multi-byte character
+        assert_eq!(changed_span("café", "cafe"), (3, 5, 4));
+        assert_eq!(changed_span("aaa", "aa"), (2, 3, 2));
+    }
+
+    #[test]
+    fn concurrent_edits_merge() {
+        let base = Scratchpad::new();
+        base.set_text("shopping: milk", Utc::now());
+
+        let laptop = Scratchpad::from_state(&base.encode_state()).unwrap();
+        let phone = Scratchpad::from_state(&base.encode_state()).unwrap();
+        laptop.set_text("todo\nshopping: milk", Utc::now());
+        phone.set_text("shopping: milk, eggs", Utc::now());
+
+        assert!(laptop.apply_update(&phone.encode_state()).unwrap());
+        assert!(phone.apply_update(&laptop.encode_state()).unwrap());
+        assert_eq!(laptop.text(), "todo\nshopping: milk, eggs");
+        assert_eq!(phone.text(), laptop.text());
+        assert!(!phone.apply_update(&laptop.encode_state()).unwrap());
+    }
+
+    #[test]
+    fn pending_edits_survive_a_push_that_raced_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path();
+
+        let written = write(library, "link: https://example.com").unwrap();
+        assert!(written.pending_push);
+        assert!(written.updated_at.is_some());
+
+        let pushed = pending_edits(library);
+        let (changed, state) = merge_remote(library, None).unwrap();
+        assert!(!changed);
+        write(library, "link: https://example.org").unwrap();
+        mark_pushed(library, pushed).unwrap();
+        assert!(read(library).unwrap().pending_push);
+
+        let other = Scratchpad::from_state(&state).unwrap();
+        other.set_text("from the phone", Utc::now());
+        let (changed, _) = merge_remote(library, Some(&other.encode_state())).unwrap();
+        assert!(changed);
+        mark_pushed(library, pending_edits(library)).unwrap();
+        assert!(!read(library).unwrap().pending_push);
+    }
+}
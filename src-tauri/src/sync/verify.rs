@@ -0,0 +1,277 @@
+//! Sync health checks
+//!
+//! Compares three views of a notebook's pages: what this device thinks it
+//! last synced (`LocalSyncState`), what the remote manifest claims, and the
+//! `.crdt` files actually on the server. Each disagreement comes with the
+//! repair the sync engine should apply; repairs are carried out by marking
+//! pages in the local state and running a normal sync, so they go through
+//! the same CRDT merge and destructive-sync guard as any other sync.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::config::SyncManifest;
+use super::metadata::LocalSyncState;
+
+/// What is wrong with a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiscrepancyKind {
+    /// Page exists locally but has no file on the server
+    MissingRemote,
+    /// Page file is on the server but not on this device
+    MissingLocal,
+    /// Page file is on the server but the manifest doesn't list it
+    NotInManifest,
+    /// Manifest lists a page that exists neither locally nor on the server
+    StaleManifestEntry,
+    /// The server's file changed since this device last synced it
+    RemoteChanged,
+    /// The manifest's ETag doesn't match the server's file
+    ManifestEtagMismatch,
+}
+
+/// How to fix a discrepancy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairAction {
+    /// Upload the local page (also rewrites its manifest entry)
+    Push,
+    /// Download and merge the server's page
+    Pull,
+    /// Remove the entry from the remote manifest
+    PruneManifest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDiscrepancy {
+    pub page_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub kind: DiscrepancyKind,
+    pub repair: RepairAction,
+}
+
+/// Result of `sync_verify`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncVerifyReport {
+    pub notebook_id: Uuid,
+    pub checked_at: DateTime<Utc>,
+    pub local_pages: usize,
+    pub remote_files: usize,
+    pub manifest_pages: usize,
+    /// The remote manifest couldn't be found (nothing has been pushed yet)
+    pub manifest_missing: bool,
+    pub discrepancies: Vec<SyncDiscrepancy>,
+}
+
+impl SyncVerifyReport {
+    pub fn is_healthy(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// A repair requested by the user, usually one taken from a report
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRepair {
+    pub page_id: Uuid,
+    pub action: RepairAction,
+}
+
+/// Compare local pages (id → title), local sync state, the remote manifest
+/// and the remote page files (id → ETag)
+pub fn compare(
+    notebook_id: Uuid,
+    local_pages: &HashMap<Uuid, String>,
+    local_state: &LocalSyncState,
+    manifest: Option<&SyncManifest>,
+    remote_files: &HashMap<Uuid, Option<String>>,
+) -> SyncVerifyReport {
+    let manifest_pages = manifest.map(|m| &m.pages);
+    let mut ids: BTreeSet<Uuid> = local_pages.keys().copied().collect();
+    ids.extend(remote_files.keys().copied());
+    if let Some(pages) = manifest_pages {
+        ids.extend(pages.keys().copied());
+    }
+
+    let mut discrepancies = Vec::new();
+    for page_id in ids {
+        let title = local_pages.get(&page_id).cloned();
+        let is_local = title.is_some();
+        let remote = remote_files.get(&page_id);
+        let manifest_entry = manifest_pages.and_then(|pages| pages.get(&page_id));
+
+        let found = match (is_local, remote) {
+            (true, None) => Some((DiscrepancyKind::MissingRemote, RepairAction::Push)),
+            (false, Some(_)) => Some((DiscrepancyKind::MissingLocal, RepairAction::Pull)),
+            (false, None) => Some((
+                DiscrepancyKind::StaleManifestEntry,
+                RepairAction::PruneManifest,
+            )),
+            (true, Some(remote_etag)) => {
+                let synced_etag = local_state
+                    .pages
+                    .get(&page_id)
+                    .and_then(|state| state.remote_etag.as_ref());
+                match manifest_entry {
+                    None if manifest.is_some() => {
+                        Some((DiscrepancyKind::NotInManifest, RepairAction::Push))
+                    }
+                    _ if remote_etag.is_some()
+                        && synced_etag.is_some()
+                        && remote_etag.as_ref() != synced_etag =>
+                    {
+                        Some((DiscrepancyKind::RemoteChanged, RepairAction::Pull))
+                    }
+                    Some(entry)
+                        if !entry.etag.is_empty()
+                            && remote_etag.is_some()
+                            && remote_etag.as_deref() != Some(entry.etag.as_str()) =>
+                    {
+                        Some((DiscrepancyKind::ManifestEtagMismatch, RepairAction::Push))
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some((kind, repair)) = found {
+            discrepancies.push(SyncDiscrepancy {
+                page_id,
+                title,
+                kind,
+                repair,
+            });
+        }
+    }
+
+    SyncVerifyReport {
+        notebook_id,
+        checked_at: Utc::now(),
+        local_pages: local_pages.len(),
+        remote_files: remote_files.len(),
+        manifest_pages: manifest_pages.map(|pages| pages.len()).unwrap_or(0),
+        manifest_missing: manifest.is_none(),
+        discrepancies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::config::PageSyncState;
+
+    fn manifest_entry(etag: &str) -> PageSyncState {
+        PageSyncState {
+            etag: etag.to_string(),
+            last_modified: Utc::now(),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn healthy_notebook_has_no_discrepancies() {
+        let notebook_id = Uuid::new_v4();
+        let page = Uuid::new_v4();
+        let mut state = LocalSyncState::new(notebook_id);
+        state.mark_page_synced(page, Some("e1".to_string()), Vec::new());
+        let mut manifest = SyncManifest::new(notebook_id, state.client_id.clone());
+        manifest.pages.insert(page, manifest_entry("e1"));
+
+        let report = compare(
+            notebook_id,
+            &HashMap::from([(page, "Page".to_string())]),
+            &state,
+            Some(&manifest),
+            &HashMap::from([(page, Some("e1".to_string()))]),
+        );
+        assert!(report.is_healthy());
+        assert_eq!(report.manifest_pages, 1);
+    }
+
+    #[test]
+    fn reports_each_kind_of_drift() {
+        let notebook_id = Uuid::new_v4();
+        let [missing_remote, missing_local, unlisted, stale, changed, mismatched] =
+            [(); 6].map(|_| Uuid::new_v4());
+
+        let mut state = LocalSyncState::new(notebook_id);
+        state.mark_page_synced(changed, Some("old".to_string()), Vec::new());
+        state.mark_page_synced(mismatched, Some("m2".to_string()), Vec::new());
+
+        let mut manifest = SyncManifest::new(notebook_id, state.client_id.clone());
+        manifest.pages.insert(missing_remote, manifest_entry("x"));
+        manifest.pages.insert(missing_local, manifest_entry("y"));
+        manifest.pages.insert(stale, manifest_entry("z"));
+        manifest.pages.insert(changed, manifest_entry("new"));
+        manifest.pages.insert(mismatched, manifest_entry("m1"));
+
+        let local: HashMap<Uuid, String> = [missing_remote, unlisted, changed, mismatched]
+            .into_iter()
+            .map(|id| (id, id.to_string()))
+            .collect();
+        let remote = HashMap::from([
+            (missing_local, Some("y".to_string())),
+            (unlisted, Some("u".to_string())),
+            (changed, Some("new".to_string())),
+            (mismatched, Some("m2".to_string())),
+        ]);
+
+        let report = compare(notebook_id, &local, &state, Some(&manifest), &remote);
+        let found: HashMap<Uuid, (DiscrepancyKind, RepairAction)> = report
+            .discrepancies
+            .iter()
+            .map(|d| (d.page_id, (d.kind, d.repair)))
+            .collect();
+
+        assert_eq!(found.len(), 6);
+        assert_eq!(
+            found[&missing_remote],
+            (DiscrepancyKind::MissingRemote, RepairAction::Push)
+        );
+        assert_eq!(
+            found[&missing_local],
+            (DiscrepancyKind::MissingLocal, RepairAction::Pull)
+        );
+        assert_eq!(
+            found[&unlisted],
+            (DiscrepancyKind::NotInManifest, RepairAction::Push)
+        );
+        assert_eq!(
+            found[&stale],
+            (
+                DiscrepancyKind::StaleManifestEntry,
+                RepairAction::PruneManifest
+            )
+        );
+        assert_eq!(
+            found[&changed],
+            (DiscrepancyKind::RemoteChanged, RepairAction::Pull)
+        );
+        assert_eq!(
+            found[&mismatched],
+            (DiscrepancyKind::ManifestEtagMismatch, RepairAction::Push)
+        );
+    }
+
+    #[test]
+    fn no_manifest_only_reports_missing_files() {
+        let notebook_id = Uuid::new_v4();
+        let page = Uuid::new_v4();
+        let report = compare(
+            notebook_id,
+            &HashMap::from([(page, "Page".to_string())]),
+            &LocalSyncState::new(notebook_id),
+            None,
+            &HashMap::new(),
+        );
+        assert!(report.manifest_missing);
+        assert_eq!(report.discrepancies[0].kind, DiscrepancyKind::MissingRemote);
+    }
+}
@@ -0,0 +1,109 @@
+//! Page versions kept on the sync server
+//!
+//! The server only holds each page's current CRDT, so a bad edit made on
+//! another device arrives here with nothing to go back to. Now and then, when
+//! a device pushes a page, it also uploads the page's full CRDT state as a
+//! version under `versions/{page_id}/`, named like delta files (timestamp
+//! first, then the client id that wrote it). The newest [`KEEP_VERSIONS`] of
+//! each page are kept.
+//!
+//! Restoring a version writes its content over the local page like any other
+//! edit, so sync pushes it to the other devices as a new change.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::delta;
+
+/// Extension of version files (compressed like `.crdt` when compression is on)
+pub const VERSION_EXT: &str = ".crdt";
+
+/// Upload a new version of a page at most this often
+pub const VERSION_INTERVAL_MINUTES: i64 = 60;
+
+/// Versions kept per page; older ones are deleted after an upload
+pub const KEEP_VERSIONS: usize = 20;
+
+/// `{notebook}/versions`
+pub fn versions_root(remote_path: &str) -> String {
+    format!("{}/versions", remote_path)
+}
+
+/// `{notebook}/versions/{page_id}`
+pub fn page_versions_dir(remote_path: &str, page_id: Uuid) -> String {
+    format!("{}/{}", versions_root(remote_path), page_id)
+}
+
+/// Name for a version uploaded by `client_id` at `at`
+pub fn version_file_name(client_id: &str, at: DateTime<Utc>) -> String {
+    delta::timestamped_file_name(client_id, at, VERSION_EXT)
+}
+
+/// Upload time and (path-safe) client id of a version file name
+pub fn parse_version_name(name: &str) -> Option<(DateTime<Utc>, String)> {
+    let stem = name.strip_suffix(VERSION_EXT)?;
+    let (millis, client) = stem.split_once('-')?;
+    let at = DateTime::from_timestamp_millis(millis.parse().ok()?)?;
+    Some((at, client.to_string()))
+}
+
+/// Whether a pushed page is due a new version
+pub fn due(last_version_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    last_version_at.map_or(true, |at| now - at >= Duration::minutes(VERSION_INTERVAL_MINUTES))
+}
+
+/// Version names to delete so only the newest [`KEEP_VERSIONS`] remain
+pub fn to_prune(names: &[String]) -> Vec<&str> {
+    let mut sorted: Vec<&str> = names
+        .iter()
+        .map(String::as_str)
+        .filter(|name| parse_version_name(name).is_some())
+        .collect();
+    sorted.sort_unstable();
+    let excess = sorted.len().saturating_sub(KEEP_VERSIONS);
+    sorted.truncate(excess);
+    sorted
+}
+
+/// A version of a page on the server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePageVersion {
+    /// File name, which identifies the version for a restore
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub client_id: String,
+    /// Name of the device that uploaded it, from the device registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip_and_prune_oldest() {
+        let at = DateTime::from_timestamp_millis(1_800_000_000_123).unwrap();
+        let name = version_file_name("laptop-1", at);
+        assert_eq!(name, "1800000000123-laptop-1.crdt");
+        assert_eq!(parse_version_name(&name), Some((at, "laptop-1".to_string())));
+        assert_eq!(parse_version_name("notes.txt"), None);
+
+        let mut names: Vec<String> = (0..KEEP_VERSIONS as i64 + 2)
+            .map(|i| version_file_name("a", at + Duration::hours(i)))
+            .collect();
+        names.reverse();
+        names.push("stray.txt".to_string());
+        let pruned = to_prune(&names);
+        assert_eq!(pruned.len(), 2);
+        assert_eq!(pruned[0], version_file_name("a", at));
+
+        assert!(due(None, at));
+        assert!(!due(Some(at), at + Duration::minutes(5)));
+        assert!(due(Some(at), at + Duration::hours(2)));
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use futures_util::StreamExt;
 use reqwest::{Client, Method, StatusCode};
@@ -6,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::io::AsyncWriteExt;
 
+use crate::sync::bandwidth::Throttle;
+use crate::sync::compression::{self, SyncCompression};
 use crate::sync::config::SyncCredentials;
 
 /// WebDAV client for sync operations
@@ -16,6 +19,10 @@ pub struct WebDAVClient {
     client: Client,
     base_url: String,
     credentials: SyncCredentials,
+    /// Bandwidth limits shared with other clients for the same server
+    throttle: Option<Arc<Throttle>>,
+    /// Applied to CRDT and JSON uploads; downloads are always decoded
+    compression: SyncCompression,
 }
 
 #[derive(Error, Debug)]
@@ -99,9 +106,44 @@ impl WebDAVClient {
             client,
             base_url,
             credentials,
+            throttle: None,
+            compression: SyncCompression::None,
         })
     }
 
+    /// Compress CRDT and JSON uploads with `compression`
+    pub fn with_compression(mut self, compression: SyncCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Undo upload compression on a downloaded payload
+    fn decode_payload(path: &str, data: Vec<u8>) -> Result<Vec<u8>, WebDAVError> {
+        if compression::is_compressible(path) {
+            Ok(compression::decode(data)?)
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Pace uploads and downloads with `throttle`
+    pub fn with_throttle(mut self, throttle: Arc<Throttle>) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    async fn throttle_upload(&self, bytes: u64) {
+        if let Some(throttle) = &self.throttle {
+            throttle.upload.acquire(bytes).await;
+        }
+    }
+
+    async fn throttle_download(&self, bytes: u64) {
+        if let Some(throttle) = &self.throttle {
+            throttle.download.acquire(bytes).await;
+        }
+    }
+
     /// Build full URL for a path
     fn url(&self, path: &str) -> String {
         let path = path.trim_start_matches('/');
@@ -180,7 +222,9 @@ impl WebDAVClient {
             _ => {}
         }
 
-        Ok(response.bytes().await?.to_vec())
+        let data = response.bytes().await?.to_vec();
+        self.throttle_download(data.len() as u64).await;
+        Self::decode_payload(path, data)
     }
 
     /// GET with ETag - Download file contents and return ETag
@@ -216,7 +260,8 @@ impl WebDAVClient {
             .map(|s| s.trim_matches('"').to_string());
 
         let data = response.bytes().await?.to_vec();
-        Ok((data, etag))
+        self.throttle_download(data.len() as u64).await;
+        Ok((Self::decode_payload(path, data)?, etag))
     }
 
     /// HEAD - Check resource existence and get metadata without downloading content
@@ -278,6 +323,17 @@ impl WebDAVClient {
             std::time::Duration::from_millis(2000),
         ];
 
+        let compressed;
+        let data = if self.compression != SyncCompression::None
+            && compression::is_compressible(path)
+        {
+            compressed = compression::encode(data, self.compression)?;
+            compressed.as_slice()
+        } else {
+            data
+        };
+        self.throttle_upload(data.len() as u64).await;
+
         for attempt in 0..=retry_delays.len() {
             let mut request = self.client
                 .put(&url)
@@ -483,6 +539,7 @@ impl WebDAVClient {
             let mut file = tokio::fs::File::create(&tmp_path).await?;
             while let Some(chunk) = stream.next().await {
                 let chunk = chunk.map_err(WebDAVError::Http)?;
+                self.throttle_download(chunk.len() as u64).await;
                 file.write_all(&chunk).await?;
                 written += chunk.len() as u64;
             }
@@ -543,6 +600,9 @@ impl WebDAVClient {
             let file = tokio::fs::File::open(local_path).await?;
             let metadata = file.metadata().await?;
             let file_size = metadata.len();
+            if attempt == 0 {
+                self.throttle_upload(file_size).await;
+            }
 
             let stream = tokio_util::io::ReaderStream::new(file);
             let body = reqwest::Body::wrap_stream(stream);
@@ -0,0 +1,15 @@
+//! Library-wide tag registry
+//!
+//! Tags stay plain strings on pages. The registry adds what doesn't fit
+//! there: descriptions, colors and aliases, stored once per library in
+//! `tags.json`. A `/` in a tag makes a hierarchy (`project/alpha` is a
+//! child of `project`), and queries and stats roll children up to their
+//! parents. `rules` adds tags to pages automatically.
+
+mod models;
+mod registry;
+mod rules;
+
+pub use models::*;
+pub use registry::*;
+pub use rules::*;
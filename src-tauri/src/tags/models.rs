@@ -0,0 +1,61 @@
+//! Tag registry data models
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Metadata for a tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagDefinition {
+    /// Normalized tag name, e.g. `project/alpha`
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// CSS color
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Other names that mean this tag; pages tagged with an alias count
+    /// towards it
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl TagDefinition {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            description: None,
+            color: None,
+            aliases: Vec::new(),
+        }
+    }
+}
+
+/// A tag in the hierarchy with its page counts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagNode {
+    /// Full path, e.g. `project/alpha`
+    pub name: String,
+    /// Last path segment, e.g. `alpha`
+    pub label: String,
+    /// Pages tagged with exactly this tag (or one of its aliases)
+    pub count: usize,
+    /// Pages tagged with this tag or any tag below it
+    pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    pub children: Vec<TagNode>,
+}
+
+/// A page found by a tag query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaggedPage {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    pub tags: Vec<String>,
+}
@@ -0,0 +1,347 @@
+//! Tag registry storage and hierarchy queries
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::models::*;
+use crate::storage::FileStorage;
+
+const REGISTRY_FILE: &str = "tags.json";
+
+#[derive(Error, Debug)]
+pub enum TagError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] crate::storage::StorageError),
+
+    #[error("Invalid tag: {0}")]
+    Invalid(String),
+
+    #[error("Tag not in registry: {0}")]
+    NotFound(String),
+}
+
+type Result<T> = std::result::Result<T, TagError>;
+
+/// Lowercase, drop a leading `#`, and tidy the `/` separators, so
+/// `#Project / Alpha/` becomes `project/alpha`
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .split('/')
+        .map(|segment| segment.trim().to_lowercase())
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The tag and every tag above it: `a/b/c` gives `a`, `a/b`, `a/b/c`
+pub fn tag_with_ancestors(tag: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for (i, c) in tag.char_indices() {
+        if c == '/' {
+            paths.push(tag[..i].to_string());
+        }
+    }
+    paths.push(tag.to_string());
+    paths
+}
+
+fn parent_of(tag: &str) -> Option<&str> {
+    tag.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// Descriptions, colors and aliases for the tags of one library
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRegistry {
+    #[serde(default)]
+    pub tags: Vec<TagDefinition>,
+}
+
+impl TagRegistry {
+    fn path(library_path: &Path) -> PathBuf {
+        library_path.join(REGISTRY_FILE)
+    }
+
+    /// Load the registry of a library; an empty one if there's none yet
+    pub fn load(library_path: &Path) -> Result<Self> {
+        let path = Self::path(library_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, library_path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path(library_path), json)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TagDefinition> {
+        let name = normalize_tag(name);
+        self.tags.iter().find(|t| t.name == name)
+    }
+
+    /// The registered name for a tag, following aliases
+    pub fn canonical(&self, tag: &str) -> String {
+        let tag = normalize_tag(tag);
+        self.tags
+            .iter()
+            .find(|t| t.aliases.contains(&tag))
+            .map_or(tag, |t| t.name.clone())
+    }
+
+    /// Add or replace a definition. Names and aliases are normalized, and an
+    /// alias may not be another tag's name or alias.
+    pub fn upsert(&mut self, mut definition: TagDefinition) -> Result<TagDefinition> {
+        definition.name = normalize_tag(&definition.name);
+        if definition.name.is_empty() {
+            return Err(TagError::Invalid("tag name is empty".to_string()));
+        }
+        let mut aliases: Vec<String> = definition
+            .aliases
+            .iter()
+            .map(|a| normalize_tag(a))
+            .filter(|a| !a.is_empty() && *a != definition.name)
+            .collect();
+        aliases.sort();
+        aliases.dedup();
+        definition.aliases = aliases;
+        definition.description = definition.description.filter(|d| !d.trim().is_empty());
+        definition.color = definition.color.filter(|c| !c.trim().is_empty());
+
+        for other in self.tags.iter().filter(|t| t.name != definition.name) {
+            if other.aliases.contains(&definition.name) {
+                return Err(TagError::Invalid(format!(
+                    "'{}' is already an alias of '{}'",
+                    definition.name, other.name
+                )));
+            }
+            if let Some(alias) = definition
+                .aliases
+                .iter()
+                .find(|a| **a == other.name || other.aliases.contains(a))
+            {
+                return Err(TagError::Invalid(format!(
+                    "'{}' is already used by '{}'",
+                    alias, other.name
+                )));
+            }
+        }
+
+        match self.tags.iter_mut().find(|t| t.name == definition.name) {
+            Some(existing) => *existing = definition.clone(),
+            None => self.tags.push(definition.clone()),
+        }
+        self.tags.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(definition)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<()> {
+        let name = normalize_tag(name);
+        let before = self.tags.len();
+        self.tags.retain(|t| t.name != name);
+        if self.tags.len() == before {
+            return Err(TagError::NotFound(name));
+        }
+        Ok(())
+    }
+
+    /// Whether a page tag falls under `query`: the same tag after aliases,
+    /// or (with `include_children`) one below it
+    pub fn matches(&self, page_tag: &str, query: &str, include_children: bool) -> bool {
+        let tag = self.canonical(page_tag);
+        let query = self.canonical(query);
+        tag == query
+            || (include_children
+                && tag.len() > query.len()
+                && tag.starts_with(&query)
+                && tag.as_bytes()[query.len()] == b'/')
+    }
+
+    /// Build the tag hierarchy from the tags of each page. A page counts
+    /// once towards each tag it has and once towards every ancestor of
+    /// those. Registered tags show up even when no page uses them.
+    pub fn tree<'a>(&self, pages: impl IntoIterator<Item = &'a [String]>) -> Vec<TagNode> {
+        // name -> (count, total)
+        let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for definition in &self.tags {
+            for path in tag_with_ancestors(&definition.name) {
+                counts.entry(path).or_default();
+            }
+        }
+        for tags in pages {
+            let own: HashSet<String> = tags
+                .iter()
+                .map(|t| self.canonical(t))
+                .filter(|t| !t.is_empty())
+                .collect();
+            let rolled_up: HashSet<String> =
+                own.iter().flat_map(|t| tag_with_ancestors(t)).collect();
+            for tag in &own {
+                counts.entry(tag.clone()).or_default().0 += 1;
+            }
+            for tag in rolled_up {
+                counts.entry(tag).or_default().1 += 1;
+            }
+        }
+
+        let mut children: HashMap<Option<&str>, Vec<&str>> = HashMap::new();
+        for name in counts.keys() {
+            children.entry(parent_of(name)).or_default().push(name);
+        }
+        self.build_nodes(None, &children, &counts)
+    }
+
+    fn build_nodes(
+        &self,
+        parent: Option<&str>,
+        children: &HashMap<Option<&str>, Vec<&str>>,
+        counts: &BTreeMap<String, (usize, usize)>,
+    ) -> Vec<TagNode> {
+        let Some(names) = children.get(&parent) else {
+            return Vec::new();
+        };
+        names
+            .iter()
+            .map(|name| {
+                let (count, total) = counts[*name];
+                let definition = self.tags.iter().find(|t| t.name == *name);
+                TagNode {
+                    name: name.to_string(),
+                    label: name.rsplit('/').next().unwrap_or(name).to_string(),
+                    count,
+                    total,
+                    description: definition.and_then(|d| d.description.clone()),
+                    color: definition.and_then(|d| d.color.clone()),
+                    children: self.build_nodes(Some(name), children, counts),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The tag hierarchy across every notebook in `storage`
+pub fn library_tag_tree(storage: &FileStorage, registry: &TagRegistry) -> Result<Vec<TagNode>> {
+    let mut page_tags = Vec::new();
+    for notebook in storage.list_notebooks()? {
+        for page in storage.list_pages(notebook.id)? {
+            if page.deleted_at.is_none() {
+                page_tags.push(page.tags);
+            }
+        }
+    }
+    Ok(registry.tree(page_tags.iter().map(|tags| tags.as_slice())))
+}
+
+/// Pages tagged with `tag`, its aliases and (with `include_children`) any
+/// tag below it
+pub fn pages_with_tag(
+    storage: &FileStorage,
+    registry: &TagRegistry,
+    tag: &str,
+    include_children: bool,
+) -> Result<Vec<TaggedPage>> {
+    let mut found = Vec::new();
+    for notebook in storage.list_notebooks()? {
+        for page in storage.list_pages(notebook.id)? {
+            if page.deleted_at.is_some()
+                || !page
+                    .tags
+                    .iter()
+                    .any(|t| registry.matches(t, tag, include_children))
+            {
+                continue;
+            }
+            found.push(TaggedPage {
+                notebook_id: page.notebook_id,
+                page_id: page.id,
+                title: page.title,
+                tags: page.tags,
+            });
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> TagRegistry {
+        let mut registry = TagRegistry::default();
+        let mut project = TagDefinition::new("Project".to_string());
+        project.aliases = vec!["proj".to_string()];
+        registry.upsert(project).unwrap();
+        let mut ml = TagDefinition::new("#research / ML".to_string());
+        ml.aliases = vec!["machine-learning".to_string()];
+        ml.color = Some("#3366ff".to_string());
+        registry.upsert(ml).unwrap();
+        registry
+    }
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(normalize_tag("  #Project / Alpha/ "), "project/alpha");
+        assert_eq!(normalize_tag("a//b"), "a/b");
+        assert_eq!(
+            tag_with_ancestors("a/b/c"),
+            vec!["a".to_string(), "a/b".to_string(), "a/b/c".to_string()]
+        );
+    }
+
+    #[test]
+    fn aliases_resolve_and_cannot_collide() {
+        let mut registry = registry();
+        assert_eq!(registry.canonical("Machine-Learning"), "research/ml");
+        assert_eq!(registry.canonical("other"), "other");
+
+        let mut clash = TagDefinition::new("ideas".to_string());
+        clash.aliases = vec!["proj".to_string()];
+        assert!(registry.upsert(clash).is_err());
+        assert!(registry
+            .upsert(TagDefinition::new("PROJ".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn children_roll_up_to_parents() {
+        let registry = registry();
+        assert!(registry.matches("project/alpha", "project", true));
+        assert!(registry.matches("proj", "project", false));
+        assert!(!registry.matches("project/alpha", "project", false));
+        assert!(!registry.matches("projects", "project", true));
+    }
+
+    #[test]
+    fn tree_counts_each_page_once_per_ancestor() {
+        let registry = registry();
+        let pages: Vec<Vec<String>> = vec![
+            vec!["project/alpha".into(), "project/beta".into()],
+            vec!["proj".into()],
+            vec!["machine-learning".into()],
+        ];
+        let tree = registry.tree(pages.iter().map(|t| t.as_slice()));
+
+        let project = tree.iter().find(|n| n.name == "project").unwrap();
+        assert_eq!((project.count, project.total), (1, 2));
+        assert_eq!(project.children.len(), 2);
+        assert_eq!(project.children[0].label, "alpha");
+
+        let research = tree.iter().find(|n| n.name == "research").unwrap();
+        assert_eq!((research.count, research.total), (0, 1));
+        assert_eq!(research.children[0].color.as_deref(), Some("#3366ff"));
+    }
+}
@@ -0,0 +1,414 @@
+//! Auto-tagging rules
+//!
+//! Deterministic rules ("title matches /standup/ → #meetings", "pages in
+//! folder Research → #research") kept in `tag_rules.json` in the library
+//! directory and evaluated whenever a page is created or saved. Rules only
+//! ever add tags; they never remove one. A tag removed by hand comes back
+//! on the next save while the rule still matches, so turn the rule off to
+//! stop that.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::registry::{normalize_tag, TagError};
+use crate::storage::{FileStorage, Page};
+
+const RULES_FILE: &str = "tag_rules.json";
+
+type Result<T> = std::result::Result<T, TagError>;
+
+/// What a page must match for a rule to apply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleCondition {
+    /// Case-insensitive regex against the title
+    TitleMatches { pattern: String },
+    /// Case-insensitive regex against the page text
+    ContentMatches { pattern: String },
+    /// The page's folder has this name (case-insensitive)
+    InFolder { folder: String },
+    /// The page is in this notebook
+    InNotebook {
+        #[serde(rename = "notebookId")]
+        notebook_id: Uuid,
+    },
+    /// The page already has this tag or one below it
+    HasTag { tag: String },
+}
+
+/// A rule adding tags to pages that match a condition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRule {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: RuleCondition,
+    pub add_tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl TagRule {
+    pub fn new(name: String, condition: RuleCondition, add_tags: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            enabled: true,
+            condition,
+            add_tags,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Check the rule can be evaluated (tags given, regexes compile)
+    pub fn validate(&self) -> Result<()> {
+        if self.add_tags.iter().all(|t| normalize_tag(t).is_empty()) {
+            return Err(TagError::Invalid(format!(
+                "rule '{}' adds no tags",
+                self.name
+            )));
+        }
+        self.compile().map(|_| ())
+    }
+
+    fn compile(&self) -> Result<Option<Regex>> {
+        match &self.condition {
+            RuleCondition::TitleMatches { pattern } | RuleCondition::ContentMatches { pattern } => {
+                RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map(Some)
+                    .map_err(|e| TagError::Invalid(format!("bad pattern '{}': {}", pattern, e)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// What a rule looks at on a page
+pub struct PageFacts<'a> {
+    pub page: &'a Page,
+    /// Name of the page's folder, if it's in one
+    pub folder_name: Option<&'a str>,
+}
+
+fn page_text(page: &Page) -> String {
+    let mut text = String::new();
+    for block in &page.content.blocks {
+        collect_text(&block.data, &mut text);
+    }
+    text
+}
+
+fn collect_text(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::String(s) => {
+            out.push_str(s);
+            out.push('\n');
+        }
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_text(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| collect_text(v, out)),
+        _ => {}
+    }
+}
+
+fn has_tag(page: &Page, tag: &str) -> bool {
+    page.tags.iter().any(|t| {
+        let t = normalize_tag(t);
+        t == tag
+            || t.strip_prefix(tag)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Tags `rules` would add to a page, without changing it
+pub fn tags_to_add(rules: &[TagRule], facts: &PageFacts) -> Vec<String> {
+    let page = facts.page;
+    let mut text: Option<String> = None;
+    let mut added: Vec<String> = Vec::new();
+
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let matched = match (&rule.condition, rule.compile()) {
+            (_, Err(e)) => {
+                log::warn!("Skipping tag rule '{}': {}", rule.name, e);
+                false
+            }
+            (RuleCondition::TitleMatches { .. }, Ok(Some(re))) => re.is_match(&page.title),
+            (RuleCondition::ContentMatches { .. }, Ok(Some(re))) => {
+                re.is_match(text.get_or_insert_with(|| page_text(page)))
+            }
+            (RuleCondition::InFolder { folder }, _) => facts
+                .folder_name
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case(folder.trim())),
+            (RuleCondition::InNotebook { notebook_id }, _) => page.notebook_id == *notebook_id,
+            (RuleCondition::HasTag { tag }, _) => has_tag(page, &normalize_tag(tag)),
+            _ => false,
+        };
+        if !matched {
+            continue;
+        }
+        for tag in &rule.add_tags {
+            let tag = normalize_tag(tag);
+            if !tag.is_empty()
+                && !added.contains(&tag)
+                && !page.tags.iter().any(|t| normalize_tag(t) == tag)
+            {
+                added.push(tag);
+            }
+        }
+    }
+    added
+}
+
+/// The auto-tagging rules of one library
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRuleSet {
+    #[serde(default)]
+    pub rules: Vec<TagRule>,
+}
+
+impl TagRuleSet {
+    fn path(library_path: &Path) -> PathBuf {
+        library_path.join(RULES_FILE)
+    }
+
+    pub fn load(library_path: &Path) -> Result<Self> {
+        let path = Self::path(library_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, library_path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(Self::path(library_path), json)?;
+        Ok(())
+    }
+
+    /// Add or replace a rule (matched by ID)
+    pub fn upsert(&mut self, rule: TagRule) -> Result<TagRule> {
+        rule.validate()?;
+        match self.rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => self.rules.push(rule.clone()),
+        }
+        Ok(rule)
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Result<()> {
+        let before = self.rules.len();
+        self.rules.retain(|r| r.id != id);
+        if self.rules.len() == before {
+            return Err(TagError::NotFound(id.to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn folder_name(storage: &FileStorage, page: &Page) -> Option<String> {
+    let folder_id = page.folder_id?;
+    storage
+        .list_folders(page.notebook_id)
+        .ok()?
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .map(|f| f.name)
+}
+
+/// Apply the library's rules to a page about to be saved. Returns the tags
+/// that were added. Failures are logged, never surfaced, so a broken rules
+/// file can't block saving.
+pub fn apply_tag_rules(storage: &FileStorage, library_path: &Path, page: &mut Page) -> Vec<String> {
+    let rules = match TagRuleSet::load(library_path) {
+        Ok(set) if set.rules.iter().any(|r| r.enabled) => set.rules,
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            log::warn!("Failed to load tag rules: {}", e);
+            return Vec::new();
+        }
+    };
+    let folder = folder_name(storage, page);
+    let added = tags_to_add(
+        &rules,
+        &PageFacts {
+            page,
+            folder_name: folder.as_deref(),
+        },
+    );
+    page.tags.extend(added.iter().cloned());
+    added
+}
+
+/// A page a rule would tag, from a dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleMatch {
+    pub notebook_id: Uuid,
+    pub page_id: Uuid,
+    pub title: String,
+    pub tags_to_add: Vec<String>,
+}
+
+/// Run one rule over every page in the library without saving anything.
+/// Pages that already have all the rule's tags are left out.
+pub fn test_rule(storage: &FileStorage, rule: &TagRule, limit: usize) -> Result<Vec<RuleMatch>> {
+    rule.validate()?;
+    let mut rule = rule.clone();
+    rule.enabled = true;
+    let rules = [rule];
+
+    let mut matches = Vec::new();
+    for notebook in storage.list_notebooks()? {
+        if notebook.is_encrypted() {
+            continue;
+        }
+        let folders = storage.list_folders(notebook.id).unwrap_or_default();
+        for page in storage.list_pages(notebook.id)? {
+            if page.deleted_at.is_some() {
+                continue;
+            }
+            let folder = page
+                .folder_id
+                .and_then(|id| folders.iter().find(|f| f.id == id))
+                .map(|f| f.name.as_str());
+            let tags = tags_to_add(
+                &rules,
+                &PageFacts {
+                    page: &page,
+                    folder_name: folder,
+                },
+            );
+            if tags.is_empty() {
+                continue;
+            }
+            matches.push(RuleMatch {
+                notebook_id: page.notebook_id,
+                page_id: page.id,
+                title: page.title,
+                tags_to_add: tags,
+            });
+            if matches.len() >= limit {
+                return Ok(matches);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::EditorBlock;
+
+    fn page(title: &str, text: &str, tags: &[&str]) -> Page {
+        let mut page = Page::new(Uuid::new_v4(), title.to_string());
+        page.tags = tags.iter().map(|t| t.to_string()).collect();
+        page.content.blocks = vec![EditorBlock {
+            id: "b1".to_string(),
+            block_type: "paragraph".to_string(),
+            data: serde_json::json!({ "text": text }),
+        }];
+        page
+    }
+
+    fn rule(condition: RuleCondition, tags: &[&str]) -> TagRule {
+        TagRule::new(
+            "test".to_string(),
+            condition,
+            tags.iter().map(|t| t.to_string()).collect(),
+        )
+    }
+
+    fn added(rules: &[TagRule], page: &Page, folder: Option<&str>) -> Vec<String> {
+        tags_to_add(
+            rules,
+            &PageFacts {
+                page,
+                folder_name: folder,
+            },
+        )
+    }
+
+    #[test]
+    fn title_content_and_folder_rules() {
+        let rules = [
+            rule(
+                RuleCondition::TitleMatches {
+                    pattern: "stand-?up".to_string(),
+                },
+                &["#Meetings"],
+            ),
+            rule(
+                RuleCondition::ContentMatches {
+                    pattern: r"\bTODO\b".to_string(),
+                },
+                &["todo"],
+            ),
+            rule(
+                RuleCondition::InFolder {
+                    folder: "research".to_string(),
+                },
+                &["research"],
+            ),
+        ];
+
+        let standup = page("Daily Standup", "todo: send notes", &[]);
+        assert_eq!(added(&rules, &standup, None), vec!["meetings", "todo"]);
+
+        let paper = page("Paper", "nothing", &[]);
+        assert_eq!(added(&rules, &paper, Some("Research")), vec!["research"]);
+        assert!(added(&rules, &paper, Some("Archive")).is_empty());
+    }
+
+    #[test]
+    fn existing_tags_and_disabled_rules_are_skipped() {
+        let mut off = rule(
+            RuleCondition::TitleMatches {
+                pattern: ".*".to_string(),
+            },
+            &["everything"],
+        );
+        off.enabled = false;
+        let child = rule(
+            RuleCondition::HasTag {
+                tag: "project".to_string(),
+            },
+            &["Work", "project/alpha"],
+        );
+
+        let tagged = page("Plan", "", &["Project/Alpha"]);
+        assert_eq!(added(&[off, child], &tagged, None), vec!["work"]);
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let bad_pattern = rule(
+            RuleCondition::TitleMatches {
+                pattern: "(unclosed".to_string(),
+            },
+            &["x"],
+        );
+        assert!(bad_pattern.validate().is_err());
+        let no_tags = rule(
+            RuleCondition::InFolder {
+                folder: "x".to_string(),
+            },
+            &[" "],
+        );
+        assert!(no_tags.validate().is_err());
+        assert!(TagRuleSet::default().upsert(no_tags).is_err());
+    }
+}
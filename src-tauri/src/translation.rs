@@ -0,0 +1,430 @@
+//! Machine translation of pages
+//!
+//! A page is translated block by block: the text of every translatable
+//! field (paragraph text, list items, table cells, captions, ...) is
+//! collected as a segment, the segments are sent to the AI in chunks as a
+//! JSON array, and the translations are written back into the same fields.
+//! The block structure, ids and non-text data (code, images, drawings,
+//! embeds) are never touched, so nothing is lost the way a Markdown round
+//! trip would lose it.
+//!
+//! The AI also names the language of the text it was given; the first
+//! chunk's answer is taken as the page's source language.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::python_bridge::ChatMessage;
+use crate::storage::{EditorBlock, Page, PageType};
+
+/// Characters of segment text sent per request
+const CHUNK_CHARS: usize = 6_000;
+
+/// Property on a translated copy holding the id of the page it was
+/// translated from
+pub const TRANSLATION_OF_PROPERTY: &str = "translationOf";
+/// Property holding the language a page was translated into
+pub const LANGUAGE_PROPERTY: &str = "language";
+/// Prefix of the properties on the original page that point at its
+/// translated copies, e.g. `translation.German`
+pub const TRANSLATION_PROPERTY_PREFIX: &str = "translation.";
+
+#[derive(Error, Debug)]
+pub enum TranslationError {
+    #[error("page is encrypted")]
+    Encrypted,
+    #[error("not a block page")]
+    NotBlockPage,
+    #[error("page has no text to translate")]
+    NothingToTranslate,
+    #[error("AI reply is not valid translation JSON: {0}")]
+    BadReply(String),
+    #[error("AI returned {got} translations for {expected} segments")]
+    CountMismatch { expected: usize, got: usize },
+}
+
+pub type TranslationResult<T> = Result<T, TranslationError>;
+
+/// Whether a translation overwrites the page or goes into a new page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TranslationMode {
+    #[default]
+    Replace,
+    Copy,
+}
+
+/// One piece of translatable text: the page title, or a string inside a
+/// block's data
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Index into the page's blocks, None for the title
+    pub block: Option<usize>,
+    /// JSON pointer to the string within the block's data
+    pub pointer: String,
+    pub text: String,
+}
+
+/// Parsed AI reply for one chunk of segments
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationReply {
+    #[serde(default)]
+    pub source_language: String,
+    pub translations: Vec<String>,
+}
+
+/// Check that `page` can be translated
+pub fn check_page(page: &Page) -> TranslationResult<()> {
+    if page.is_encrypted() {
+        return Err(TranslationError::Encrypted);
+    }
+    if page.page_type != PageType::Standard || page.plugin_page_type.is_some() {
+        return Err(TranslationError::NotBlockPage);
+    }
+    Ok(())
+}
+
+/// The page title followed by the text of every translatable block field,
+/// in page order. Empty strings are left out.
+pub fn collect_segments(page: &Page) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    if !page.title.trim().is_empty() {
+        segments.push(Segment {
+            block: None,
+            pointer: String::new(),
+            text: page.title.clone(),
+        });
+    }
+    for (index, block) in page.content.blocks.iter().enumerate() {
+        for pointer in text_pointers(block) {
+            if let Some(text) = block.data.pointer(&pointer).and_then(|v| v.as_str()) {
+                if !text.trim().is_empty() {
+                    segments.push(Segment {
+                        block: Some(index),
+                        pointer,
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    segments
+}
+
+/// JSON pointers of the text fields in `block`. Block types not listed here
+/// (code, images without captions, drawings, embeds, ...) are left as-is.
+fn text_pointers(block: &EditorBlock) -> Vec<String> {
+    let data = &block.data;
+    let mut pointers = Vec::new();
+    match block.block_type.as_str() {
+        "paragraph" | "header" => pointers.push("/text".to_string()),
+        "quote" => {
+            pointers.push("/text".to_string());
+            pointers.push("/caption".to_string());
+        }
+        "callout" => {
+            pointers.push("/title".to_string());
+            pointers.push("/content".to_string());
+        }
+        "image" | "video" => pointers.push("/caption".to_string()),
+        "list" => list_pointers(data.get("items"), "/items", &mut pointers),
+        "checklist" => {
+            if let Some(items) = data.get("items").and_then(|v| v.as_array()) {
+                for i in 0..items.len() {
+                    pointers.push(format!("/items/{}/text", i));
+                }
+            }
+        }
+        "table" => {
+            if let Some(rows) = data.get("content").and_then(|v| v.as_array()) {
+                for (r, row) in rows.iter().enumerate() {
+                    let cells = row.as_array().map(Vec::len).unwrap_or(0);
+                    for c in 0..cells {
+                        pointers.push(format!("/content/{}/{}", r, c));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    pointers
+}
+
+/// List items are either plain strings or `{content, items}` objects (older
+/// pages use `text` instead of `content`), nested to any depth
+fn list_pointers(items: Option<&serde_json::Value>, prefix: &str, pointers: &mut Vec<String>) {
+    let Some(items) = items.and_then(|v| v.as_array()) else {
+        return;
+    };
+    for (i, item) in items.iter().enumerate() {
+        let path = format!("{}/{}", prefix, i);
+        if item.is_string() {
+            pointers.push(path);
+            continue;
+        }
+        if item.get("content").is_some() {
+            pointers.push(format!("{}/content", path));
+        } else if item.get("text").is_some() {
+            pointers.push(format!("{}/text", path));
+        }
+        list_pointers(item.get("items"), &format!("{}/items", path), pointers);
+    }
+}
+
+/// Split segments into consecutive runs of at most `CHUNK_CHARS` characters
+/// each. A single segment longer than that gets a chunk of its own.
+pub fn chunk_segments(segments: &[Segment]) -> Vec<std::ops::Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut size = 0;
+    for (i, segment) in segments.iter().enumerate() {
+        let len = segment.text.chars().count();
+        if i > start && size + len > CHUNK_CHARS {
+            chunks.push(start..i);
+            start = i;
+            size = 0;
+        }
+        size += len;
+    }
+    if start < segments.len() {
+        chunks.push(start..segments.len());
+    }
+    chunks
+}
+
+/// The chat request translating `segments` into `target_language`
+pub fn translation_messages(segments: &[Segment], target_language: &str) -> Vec<ChatMessage> {
+    let texts: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+    let system = format!(
+        "You translate note text into {}. The user sends a JSON array of text \
+         segments from one note, in order. Segments may contain inline HTML \
+         such as <b>, <i>, <a href=\"...\"> or <code>; keep the tags, URLs and \
+         code unchanged and translate only the text. Reply with JSON only, in \
+         the form {{\"sourceLanguage\": \"<English name of the language the \
+         segments are written in>\", \"translations\": [...]}}, with exactly \
+         one translation per segment in the same order.",
+        target_language.trim()
+    );
+    vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: system,
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: serde_json::to_string(&texts).unwrap_or_default(),
+        },
+    ]
+}
+
+/// Parse the AI's reply to a chunk of `expected` segments
+pub fn parse_reply(reply: &str, expected: usize) -> TranslationResult<TranslationReply> {
+    // Models wrap JSON in fences or add a sentence around it; take the
+    // outermost object
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err(TranslationError::BadReply("no JSON object".to_string())),
+    };
+    let parsed: TranslationReply =
+        serde_json::from_str(json).map_err(|e| TranslationError::BadReply(e.to_string()))?;
+    if parsed.translations.len() != expected {
+        return Err(TranslationError::CountMismatch {
+            expected,
+            got: parsed.translations.len(),
+        });
+    }
+    Ok(parsed)
+}
+
+/// Write `translations` (one per segment) into `page`
+pub fn apply_translations(page: &mut Page, segments: &[Segment], translations: &[String]) {
+    for (segment, translation) in segments.iter().zip(translations) {
+        match segment.block {
+            None => page.title = translation.trim().to_string(),
+            Some(index) => {
+                if let Some(value) = page
+                    .content
+                    .blocks
+                    .get_mut(index)
+                    .and_then(|b| b.data.pointer_mut(&segment.pointer))
+                {
+                    *value = serde_json::Value::String(translation.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Whether the detected source language is already the target
+pub fn same_language(source: &str, target: &str) -> bool {
+    let source = source.trim();
+    !source.is_empty() && source.eq_ignore_ascii_case(target.trim())
+}
+
+/// A new page holding `translated` content of `original`, placed right after
+/// it and linked to it through properties
+pub fn translated_copy(original: &Page, translated: &Page, target_language: &str) -> Page {
+    let mut copy = Page::new(original.notebook_id, translated.title.clone());
+    copy.content = translated.content.clone();
+    copy.tags = original.tags.clone();
+    copy.folder_id = original.folder_id;
+    copy.parent_page_id = original.parent_page_id;
+    copy.section_id = original.section_id;
+    copy.position = original.position + 1;
+    copy.properties = original.properties.clone();
+    copy.properties
+        .retain(|key, _| !key.starts_with(TRANSLATION_PROPERTY_PREFIX));
+    copy.properties
+        .insert(TRANSLATION_OF_PROPERTY.to_string(), original.id.to_string());
+    copy.properties.insert(
+        LANGUAGE_PROPERTY.to_string(),
+        target_language.trim().to_string(),
+    );
+    copy
+}
+
+/// Point `original` at its translated copy `copy_id`
+pub fn link_translation(original: &mut Page, copy_id: Uuid, target_language: &str) {
+    original.properties.insert(
+        format!("{}{}", TRANSLATION_PROPERTY_PREFIX, target_language.trim()),
+        copy_id.to_string(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn block(id: &str, block_type: &str, data: serde_json::Value) -> EditorBlock {
+        EditorBlock {
+            id: id.to_string(),
+            block_type: block_type.to_string(),
+            data,
+        }
+    }
+
+    fn page() -> Page {
+        let mut page = Page::new(Uuid::new_v4(), "Plan".to_string());
+        page.content.blocks = vec![
+            block("p1", "paragraph", json!({"text": "Ship it <b>friday</b>"})),
+            block(
+                "l1",
+                "list",
+                json!({"style": "unordered", "items": [
+                    {"content": "one", "items": [{"content": "nested", "items": []}]},
+                    "two"
+                ]}),
+            ),
+            block(
+                "c1",
+                "code",
+                json!({"code": "let x = 1;", "language": "rust"}),
+            ),
+            block("t1", "table", json!({"content": [["a", ""], ["b", "c"]]})),
+        ];
+        page
+    }
+
+    #[test]
+    fn collects_text_fields_in_page_order() {
+        let texts: Vec<String> = collect_segments(&page())
+            .into_iter()
+            .map(|s| s.text)
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Plan",
+                "Ship it <b>friday</b>",
+                "one",
+                "nested",
+                "two",
+                "a",
+                "b",
+                "c"
+            ]
+        );
+    }
+
+    #[test]
+    fn translations_go_back_into_the_same_fields() {
+        let mut page = page();
+        let segments = collect_segments(&page);
+        let translations: Vec<String> = segments.iter().map(|s| s.text.to_uppercase()).collect();
+        apply_translations(&mut page, &segments, &translations);
+
+        assert_eq!(page.title, "PLAN");
+        assert_eq!(
+            page.content.blocks[1].data["items"][0]["items"][0]["content"],
+            "NESTED"
+        );
+        assert_eq!(page.content.blocks[1].data["items"][1], "TWO");
+        assert_eq!(page.content.blocks[2].data["code"], "let x = 1;");
+        assert_eq!(page.content.blocks[3].data["content"][0][1], "");
+        assert_eq!(page.content.blocks[1].data["style"], "unordered");
+    }
+
+    #[test]
+    fn reply_parsing_tolerates_fences_and_checks_the_count() {
+        let reply =
+            "```json\n{\"sourceLanguage\": \"English\", \"translations\": [\"Hallo\"]}\n```";
+        let parsed = parse_reply(reply, 1).unwrap();
+        assert_eq!(parsed.source_language, "English");
+        assert_eq!(parsed.translations, vec!["Hallo"]);
+
+        assert!(matches!(
+            parse_reply(reply, 2),
+            Err(TranslationError::CountMismatch {
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert!(matches!(
+            parse_reply("Sorry, I can't.", 1),
+            Err(TranslationError::BadReply(_))
+        ));
+    }
+
+    #[test]
+    fn long_pages_are_split_into_chunks() {
+        let segments: Vec<Segment> = (0..5)
+            .map(|_| Segment {
+                block: Some(0),
+                pointer: "/text".to_string(),
+                text: "x".repeat(CHUNK_CHARS / 2),
+            })
+            .collect();
+        assert_eq!(chunk_segments(&segments), vec![0..2, 2..4, 4..5]);
+        assert!(chunk_segments(&[]).is_empty());
+    }
+
+    #[test]
+    fn copy_links_back_to_the_original() {
+        let mut original = page();
+        original
+            .properties
+            .insert("translation.French".to_string(), "x".to_string());
+        let mut translated = original.clone();
+        translated.title = "Plan (DE)".to_string();
+
+        let copy = translated_copy(&original, &translated, "German");
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.title, "Plan (DE)");
+        assert_eq!(copy.properties["translationOf"], original.id.to_string());
+        assert_eq!(copy.properties["language"], "German");
+        assert!(!copy.properties.contains_key("translation.French"));
+
+        link_translation(&mut original, copy.id, "German");
+        assert_eq!(
+            original.properties["translation.German"],
+            copy.id.to_string()
+        );
+        assert!(same_language(" german", "German"));
+        assert!(!same_language("", "German"));
+    }
+}
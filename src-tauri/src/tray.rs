@@ -0,0 +1,255 @@
+//! System tray icon.
+//!
+//! The menu shows the sync state and offers Sync Now, a Pause Sync toggle,
+//! quick capture, recently edited pages and Quit. Sync state comes from
+//! `SyncManager` through `TauriEmitter::emit_sync_activity`. Syncs run by
+//! the daemon aren't reflected here; the daemon reports those over its own
+//! event stream.
+//!
+//! Building the recent-pages list reads every notebook's pages, so the full
+//! menu is only rebuilt at startup, every few minutes and on
+//! `refresh_tray_menu`. Activity changes just update the status item.
+//!
+//! The tray belongs to the app rather than a window, so it only covers the
+//! current library (the main window's, `AppState::storage`); libraries open
+//! in their own windows don't show up in it.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::menu::{
+    CheckMenuItem, Menu, MenuBuilder, MenuEvent, MenuItem, PredefinedMenuItem, SubmenuBuilder,
+};
+use tauri::tray::{TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+use uuid::Uuid;
+
+use crate::deep_link;
+use crate::quick_capture;
+use crate::sync::SyncActivity;
+use crate::AppState;
+
+const TRAY_ID: &str = "main";
+const RECENT_PAGES: usize = 8;
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Handles kept so activity changes can update the menu in place
+#[derive(Default)]
+pub struct TrayState {
+    status_item: Mutex<Option<MenuItem<Wry>>>,
+    pause_item: Mutex<Option<CheckMenuItem<Wry>>>,
+}
+
+fn status_text(activity: &SyncActivity) -> String {
+    match activity {
+        SyncActivity::Idle => "Sync: up to date".to_string(),
+        SyncActivity::Syncing { notebooks: 1 } => "Sync: syncing…".to_string(),
+        SyncActivity::Syncing { notebooks } => format!("Sync: syncing {} notebooks…", notebooks),
+        SyncActivity::Error { message } => {
+            let short: String = message.chars().take(60).collect();
+            format!("Sync error: {}", short)
+        }
+        SyncActivity::Paused => "Sync: paused".to_string(),
+    }
+}
+
+fn tooltip(activity: &SyncActivity) -> String {
+    format!("Nous — {}", status_text(activity))
+}
+
+/// Recently edited pages in the current library: (notebook, page, title)
+fn recent_pages(app: &AppHandle) -> Vec<(Uuid, Uuid, String)> {
+    let state = app.state::<AppState>();
+    let storage = state.storage.read().unwrap();
+    let mut pages: Vec<_> = storage
+        .list_notebooks()
+        .unwrap_or_default()
+        .iter()
+        .filter(|nb| !nb.archived)
+        .flat_map(|nb| storage.list_pages(nb.id).unwrap_or_default())
+        .filter(|p| !p.is_archived)
+        .collect();
+    pages.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    pages
+        .into_iter()
+        .take(RECENT_PAGES)
+        .map(|p| (p.notebook_id, p.id, p.title))
+        .collect()
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let activity = app.state::<AppState>().sync_manager.activity();
+    let tray_state = app.state::<TrayState>();
+
+    let status = MenuItem::with_id(
+        app,
+        "sync-status",
+        status_text(&activity),
+        false,
+        None::<&str>,
+    )?;
+    let pause = CheckMenuItem::with_id(
+        app,
+        "sync-pause",
+        "Pause Sync",
+        true,
+        activity == SyncActivity::Paused,
+        None::<&str>,
+    )?;
+
+    let mut recent = SubmenuBuilder::new(app, "Recent Pages");
+    let pages = recent_pages(app);
+    if pages.is_empty() {
+        recent = recent.item(&MenuItem::with_id(
+            app,
+            "recent-none",
+            "No pages yet",
+            false,
+            None::<&str>,
+        )?);
+    }
+    for (notebook_id, page_id, title) in pages {
+        let label: String = title.chars().take(50).collect();
+        recent = recent.text(format!("recent:{}:{}", notebook_id, page_id), label);
+    }
+
+    let menu = MenuBuilder::new(app)
+        .item(&status)
+        .text("sync-now", "Sync Now")
+        .item(&pause)
+        .item(&PredefinedMenuItem::separator(app)?)
+        .text("capture", "Quick Capture…")
+        .item(&recent.build()?)
+        .item(&PredefinedMenuItem::separator(app)?)
+        .text("open", "Open Nous")
+        .text("quit", "Quit Nous")
+        .build()?;
+
+    *tray_state.status_item.lock().unwrap() = Some(status);
+    *tray_state.pause_item.lock().unwrap() = Some(pause);
+    Ok(menu)
+}
+
+/// Rebuild the whole menu, including recent pages
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::warn!("Failed to rebuild tray menu: {}", e),
+    }
+}
+
+/// Reflect a sync activity change; called from `TauriEmitter`
+pub fn set_sync_activity(app: &AppHandle, activity: &SyncActivity) {
+    let Some(tray_state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    if let Some(item) = tray_state.status_item.lock().unwrap().as_ref() {
+        let _ = item.set_text(status_text(activity));
+    }
+    if let Some(item) = tray_state.pause_item.lock().unwrap().as_ref() {
+        let _ = item.set_checked(*activity == SyncActivity::Paused);
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tooltip(activity)));
+    }
+}
+
+fn sync_now(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let library_id = {
+            let libraries = state.library_storage.lock().unwrap();
+            match libraries.get_current_library() {
+                Ok(library) => library.id,
+                Err(e) => {
+                    log::warn!("Tray sync: no current library: {}", e);
+                    return;
+                }
+            }
+        };
+        let result = state
+            .sync_manager
+            .sync_library(
+                library_id,
+                &state.library_storage,
+                &state.storage,
+                &state.goals_storage,
+                &state.inbox_storage,
+                &state.contacts_storage,
+                &state.energy_storage,
+                &state.action_storage,
+            )
+            .await;
+        match result {
+            Ok(_) => refresh(&app),
+            Err(e) => log::warn!("Tray sync failed: {}", e),
+        }
+    });
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    match id {
+        "sync-now" => sync_now(app),
+        "sync-pause" => {
+            let sync_manager = &app.state::<AppState>().sync_manager;
+            sync_manager.set_paused(!sync_manager.is_paused());
+        }
+        "capture" => quick_capture::show_window(app),
+        "open" => quick_capture::show_main_window(app),
+        "quit" => app.exit(0),
+        _ => {
+            let Some((notebook_id, page_id)) = id
+                .strip_prefix("recent:")
+                .and_then(|rest| rest.split_once(':'))
+                .and_then(|(nb, page)| Some((nb.parse().ok()?, page.parse().ok()?)))
+            else {
+                return;
+            };
+            quick_capture::show_main_window(app);
+            deep_link::dispatch(app, &deep_link::page_deep_link(page_id, notebook_id, None));
+        }
+    }
+}
+
+/// Create the tray icon; called once from `setup`
+pub fn init(app: &AppHandle) -> tauri::Result<TrayIcon> {
+    app.manage(TrayState::default());
+    let activity = app.state::<AppState>().sync_manager.activity();
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&build_menu(app)?)
+        .tooltip(tooltip(&activity))
+        .show_menu_on_left_click(true)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            // Not emitted on Linux; there the periodic refresh covers it
+            if let TrayIconEvent::Enter { .. } = event {
+                refresh(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    let tray = builder.build(app)?;
+
+    let handle = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(REFRESH_INTERVAL);
+        refresh(&handle);
+    });
+
+    Ok(tray)
+}
+
+/// Rebuild the tray menu, e.g. after the frontend saved a page
+#[tauri::command]
+pub fn refresh_tray_menu(app: AppHandle) {
+    refresh(&app);
+}
@@ -27,6 +27,8 @@ pub struct VideoServerState {
     pub token: String,
     /// Allowed base directories for serving videos (dynamically updatable).
     pub allowed_dirs: Arc<RwLock<Vec<PathBuf>>>,
+    /// Podcast feed of generated audio, once published.
+    pub feed: Arc<RwLock<Option<String>>>,
 }
 
 /// Query parameters for video requests.
@@ -38,6 +40,13 @@ pub struct VideoQuery {
     pub token: String,
 }
 
+/// Query parameters for feed requests.
+#[derive(serde::Deserialize)]
+pub struct FeedQuery {
+    /// Access token for authorization.
+    pub token: String,
+}
+
 /// Video server handle for managing the server lifecycle.
 pub struct VideoServer {
     /// Port the server is listening on.
@@ -48,6 +57,8 @@ pub struct VideoServer {
     shutdown_tx: Option<oneshot::Sender<()>>,
     /// Shared reference to the allowed directories list.
     allowed_dirs: Arc<RwLock<Vec<PathBuf>>>,
+    /// Shared reference to the published podcast feed.
+    feed: Arc<RwLock<Option<String>>>,
 }
 
 impl VideoServer {
@@ -77,6 +88,19 @@ impl VideoServer {
         }
     }
 
+    /// Serve `xml` as the podcast feed, replacing any earlier one.
+    pub fn set_feed(&self, xml: String) {
+        if let Ok(mut feed) = self.feed.write() {
+            *feed = Some(xml);
+        }
+    }
+
+    /// URL of the podcast feed. Valid until the app quits, since the port and
+    /// token change on every start.
+    pub fn feed_url(&self) -> String {
+        format!("{}/feed.xml?token={}", self.base_url(), self.token)
+    }
+
     /// Stop the server gracefully.
     pub fn stop(&mut self) {
         if let Some(tx) = self.shutdown_tx.take() {
@@ -97,11 +121,19 @@ const VIDEO_MIME_TYPES: &[(&str, &str)] = &[
     ("ogv", "video/ogg"),
 ];
 
-/// Get MIME type for a video file based on extension.
+/// Allowed audio MIME types, for podcast feed enclosures.
+const AUDIO_MIME_TYPES: &[(&str, &str)] = &[
+    ("mp3", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("m4b", "audio/mp4"),
+];
+
+/// Get MIME type for a video (or audio) file based on extension.
 fn get_video_mime_type(path: &PathBuf) -> Option<&'static str> {
     let ext = path.extension()?.to_str()?.to_lowercase();
     VIDEO_MIME_TYPES
         .iter()
+        .chain(AUDIO_MIME_TYPES)
         .find(|(e, _)| *e == ext)
         .map(|(_, mime)| *mime)
 }
@@ -188,7 +220,7 @@ async fn stream_video(
     // Check if it's a video file
     let mime_type = match get_video_mime_type(&video_path) {
         Some(m) => m,
-        None => return (StatusCode::BAD_REQUEST, "Not a supported video format").into_response(),
+        None => return (StatusCode::BAD_REQUEST, "Not a supported media format").into_response(),
     };
 
     // Open the file
@@ -311,6 +343,29 @@ async fn stream_video(
     }
 }
 
+/// Serve the published podcast feed.
+async fn serve_feed(
+    State(state): State<Arc<VideoServerState>>,
+    Query(query): Query<FeedQuery>,
+) -> Response {
+    if query.token != state.token {
+        return (StatusCode::UNAUTHORIZED, "Invalid token").into_response();
+    }
+
+    let feed = match state.feed.read() {
+        Ok(feed) => feed.clone(),
+        Err(_) => None,
+    };
+    match feed {
+        Some(xml) => (
+            [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+            xml,
+        )
+            .into_response(),
+        None => (StatusCode::NOT_FOUND, "No feed published").into_response(),
+    }
+}
+
 /// Start the video streaming server.
 ///
 /// Returns a VideoServer handle that can be used to get the port, token, and stop the server.
@@ -325,13 +380,16 @@ pub async fn start_server(
         .collect();
 
     let shared_dirs = Arc::new(RwLock::new(allowed_dirs));
+    let shared_feed = Arc::new(RwLock::new(None));
     let state = Arc::new(VideoServerState {
         token: token.clone(),
         allowed_dirs: Arc::clone(&shared_dirs),
+        feed: Arc::clone(&shared_feed),
     });
 
     let app = Router::new()
         .route("/video", get(stream_video))
+        .route("/feed.xml", get(serve_feed))
         .with_state(state);
 
     // Bind to random available port on localhost
@@ -363,6 +421,7 @@ pub async fn start_server(
         token,
         shutdown_tx: Some(shutdown_tx),
         allowed_dirs: shared_dirs,
+        feed: shared_feed,
     })
 }
 
@@ -393,6 +452,10 @@ mod tests {
             get_video_mime_type(&PathBuf::from("video.webm")),
             Some("video/webm")
         );
+        assert_eq!(
+            get_video_mime_type(&PathBuf::from("narration.mp3")),
+            Some("audio/mpeg")
+        );
         assert_eq!(get_video_mime_type(&PathBuf::from("video.txt")), None);
     }
 }
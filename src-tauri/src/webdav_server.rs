@@ -0,0 +1,930 @@
+//! Embedded WebDAV server that shares the library with other apps.
+//!
+//! The inverse of sync: while it runs, WebDAV clients on this machine (or on
+//! the LAN, when enabled) browse the library live, laid out like the
+//! Markdown mirror (`<notebook>/<section>/<folder>/…/<title>.md`) with each
+//! notebook's attachments under `<notebook>/_assets/`. The layout is built
+//! on every request, so there is nothing to keep up to date. Encrypted
+//! notebooks are left out, as in the mirror.
+//!
+//! A read-write share also takes edits: saving a `.md` file updates its page
+//! (or creates a page in a notebook or folder directory), deleting one moves
+//! the page to the trash, renaming one in place retitles the page, and files
+//! under `_assets` are written as they are. Anything else is refused.
+//!
+//! Clients sign in with the video server's token as the password (any user
+//! name), so a share ends when the app quits.
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{header, HeaderMap, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::external_editor::apply_edited_markdown;
+use crate::markdown::import_markdown_to_page;
+use crate::markdown::mirror::{self, MirrorReport};
+use crate::storage::{FileStorage, PageType, StorageError, StorageLock};
+use crate::sync::SyncManager;
+
+/// Directory holding a notebook's attachments, next to its pages
+pub const ASSETS_DIR: &str = "_assets";
+
+/// User name shown to clients; any name is accepted
+pub const USERNAME: &str = "nous";
+
+/// WebDAV server settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavConfig {
+    /// Refuse every change
+    #[serde(default = "default_read_only")]
+    pub read_only: bool,
+    /// Listen on every interface instead of localhost only
+    #[serde(default)]
+    pub lan: bool,
+    /// Port to listen on; 0 picks a free one
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Also share archived pages and notebooks
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+fn default_read_only() -> bool {
+    true
+}
+
+fn default_port() -> u16 {
+    7668
+}
+
+impl Default for WebDavConfig {
+    fn default() -> Self {
+        Self {
+            read_only: default_read_only(),
+            lan: false,
+            port: default_port(),
+            include_archived: false,
+        }
+    }
+}
+
+/// What clients need to connect
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavStatus {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub read_only: bool,
+    pub lan: bool,
+}
+
+/// Server state shared across requests
+struct ServerState {
+    token: String,
+    read_only: bool,
+    include_archived: bool,
+    storage: Arc<StorageLock>,
+    sync_manager: Arc<SyncManager>,
+}
+
+/// Handle for a running WebDAV server
+pub struct WebDavServer {
+    pub url: String,
+    pub config: WebDavConfig,
+    token: String,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl WebDavServer {
+    pub fn status(&self) -> WebDavStatus {
+        WebDavStatus {
+            url: self.url.clone(),
+            username: USERNAME.to_string(),
+            password: self.token.clone(),
+            read_only: self.config.read_only,
+            lan: self.config.lan,
+        }
+    }
+
+    /// Stop the server gracefully
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Start sharing the library in `storage`. `token` is the password clients
+/// sign in with.
+pub async fn start_server(
+    config: WebDavConfig,
+    token: String,
+    storage: Arc<StorageLock>,
+    sync_manager: Arc<SyncManager>,
+) -> Result<WebDavServer, Box<dyn std::error::Error + Send + Sync>> {
+    let bind_ip = if config.lan {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    };
+    let listener = TcpListener::bind(SocketAddr::new(bind_ip, config.port)).await?;
+    let port = listener.local_addr()?.port();
+    let host = if config.lan {
+        lan_address().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    } else {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    };
+    let url = format!("http://{}/", SocketAddr::new(host, port));
+
+    let state = Arc::new(ServerState {
+        token: token.clone(),
+        read_only: config.read_only,
+        include_archived: config.include_archived,
+        storage,
+        sync_manager,
+    });
+    let app = Router::new().fallback(handle).with_state(state);
+
+    log::info!(
+        "WebDAV server started on {} ({})",
+        url,
+        if config.read_only {
+            "read-only"
+        } else {
+            "read-write"
+        }
+    );
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+                log::info!("WebDAV server shutting down");
+            })
+            .await
+            .ok();
+    });
+
+    Ok(WebDavServer {
+        url,
+        config,
+        token,
+        shutdown_tx: Some(shutdown_tx),
+    })
+}
+
+/// The address other devices reach this one at. Connecting a UDP socket
+/// sends nothing; it only picks the outgoing interface.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+// ===== Library layout =====
+
+#[derive(Debug, Clone)]
+enum Node {
+    /// The root, a notebook directory, or a section or folder directory.
+    /// `sibling` is a page directly inside it, whose section and folder new
+    /// pages there take.
+    Dir {
+        notebook_id: Option<Uuid>,
+        sibling: Option<Uuid>,
+    },
+    Page {
+        notebook_id: Uuid,
+        page_id: Uuid,
+        markdown: String,
+        updated_at: DateTime<Utc>,
+    },
+    AssetDir {
+        notebook_id: Uuid,
+        file: PathBuf,
+    },
+    Asset {
+        file: PathBuf,
+    },
+}
+
+/// Every path in the share, `/`-separated without leading or trailing
+/// slashes; the root is ""
+struct Tree {
+    nodes: BTreeMap<String, Node>,
+}
+
+fn parent_of(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+fn name_of(path: &str) -> &str {
+    path.rsplit_once('/').map_or(path, |(_, name)| name)
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+impl Tree {
+    fn build(storage: &FileStorage, include_archived: bool) -> Result<Self, StorageError> {
+        let layout =
+            mirror::collect_pages(storage, include_archived, &mut MirrorReport::default())?;
+        let mut nodes = BTreeMap::new();
+        nodes.insert(
+            String::new(),
+            Node::Dir {
+                notebook_id: None,
+                sibling: None,
+            },
+        );
+        for (notebook_id, dir) in &layout.notebooks {
+            nodes.insert(
+                dir.clone(),
+                Node::Dir {
+                    notebook_id: Some(*notebook_id),
+                    sibling: None,
+                },
+            );
+        }
+
+        for page in layout.pages {
+            // Section and folder directories
+            let mut dir = parent_of(&page.path).to_string();
+            let mut sibling = Some(page.id);
+            while !dir.is_empty() {
+                match nodes.get_mut(&dir) {
+                    Some(Node::Dir { sibling: s, .. }) => {
+                        if s.is_none() {
+                            *s = sibling;
+                        }
+                        break;
+                    }
+                    _ => {
+                        nodes.insert(
+                            dir.clone(),
+                            Node::Dir {
+                                notebook_id: Some(page.notebook_id),
+                                sibling,
+                            },
+                        );
+                    }
+                }
+                sibling = None;
+                dir = parent_of(&dir).to_string();
+            }
+            nodes.insert(
+                page.path,
+                Node::Page {
+                    notebook_id: page.notebook_id,
+                    page_id: page.id,
+                    markdown: page.markdown,
+                    updated_at: page.updated_at,
+                },
+            );
+        }
+
+        for (notebook_id, dir) in &layout.notebooks {
+            let assets = storage.notebook_assets_dir(*notebook_id);
+            let assets_path = join(dir, ASSETS_DIR);
+            nodes.entry(assets_path.clone()).or_insert(Node::AssetDir {
+                notebook_id: *notebook_id,
+                file: assets.clone(),
+            });
+            for entry in walkdir::WalkDir::new(&assets)
+                .min_depth(1)
+                .into_iter()
+                .filter_map(Result::ok)
+            {
+                let Ok(relative) = entry.path().strip_prefix(&assets) else {
+                    continue;
+                };
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let file = entry.path().to_path_buf();
+                let node = if entry.file_type().is_dir() {
+                    Node::AssetDir {
+                        notebook_id: *notebook_id,
+                        file,
+                    }
+                } else {
+                    Node::Asset { file }
+                };
+                nodes.entry(join(&assets_path, &relative)).or_insert(node);
+            }
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// Direct children of a directory
+    fn children<'a>(&'a self, dir: &'a str) -> impl Iterator<Item = (&'a String, &'a Node)> {
+        self.nodes
+            .iter()
+            .filter(move |(path, _)| !path.is_empty() && *path != dir && parent_of(path) == dir)
+    }
+}
+
+// ===== Request handling =====
+
+fn text(status: StatusCode, message: &str) -> Response {
+    (status, message.to_string()).into_response()
+}
+
+/// Accept the token as a Basic password, a Bearer token or `?token=`
+fn authorized(token: &str, headers: &HeaderMap, query: Option<&str>) -> bool {
+    let from_header = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            if let Some(basic) = v.strip_prefix("Basic ") {
+                let decoded = base64::engine::general_purpose::STANDARD
+                    .decode(basic.trim())
+                    .ok()?;
+                let decoded = String::from_utf8(decoded).ok()?;
+                decoded
+                    .split_once(':')
+                    .map(|(_, password)| password.to_string())
+            } else {
+                v.strip_prefix("Bearer ").map(|t| t.trim().to_string())
+            }
+        });
+    let from_query = query.and_then(|q| {
+        q.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "token")
+            .map(|(_, value)| value.to_string())
+    });
+    from_header.or(from_query).is_some_and(|t| t == token)
+}
+
+/// Decode a request path into a tree path, rejecting `.` and `..`
+fn tree_path(uri_path: &str) -> Option<String> {
+    let decoded = urlencoding::decode(uri_path).ok()?;
+    let segments: Vec<&str> = decoded.split('/').filter(|s| !s.is_empty()).collect();
+    if segments.iter().any(|s| *s == "." || *s == "..") {
+        return None;
+    }
+    Some(segments.join("/"))
+}
+
+fn href(path: &str, is_dir: bool) -> String {
+    let mut href = String::from("/");
+    href.push_str(
+        &path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| urlencoding::encode(s).into_owned())
+            .collect::<Vec<_>>()
+            .join("/"),
+    );
+    if is_dir && !path.is_empty() {
+        href.push('/');
+    }
+    href
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn mime_for_path(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "md" => "text/markdown; charset=utf-8",
+        "txt" => "text/plain; charset=utf-8",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// One `<D:response>` of a PROPFIND reply
+fn prop_response(path: &str, node: &Node) -> String {
+    let name = if path.is_empty() {
+        "Nous"
+    } else {
+        name_of(path)
+    };
+    let mut props = format!("<D:displayname>{}</D:displayname>", xml_escape(name));
+    match node {
+        Node::Dir { .. } | Node::AssetDir { .. } => {
+            props.push_str("<D:resourcetype><D:collection/></D:resourcetype>");
+        }
+        Node::Page {
+            markdown,
+            updated_at,
+            page_id,
+            ..
+        } => {
+            props.push_str(&format!(
+                "<D:resourcetype/><D:getcontentlength>{}</D:getcontentlength>\
+                 <D:getcontenttype>text/markdown</D:getcontenttype>\
+                 <D:getlastmodified>{}</D:getlastmodified>\
+                 <D:getetag>\"{}-{}\"</D:getetag>",
+                markdown.len(),
+                http_date(*updated_at),
+                page_id,
+                updated_at.timestamp_millis()
+            ));
+        }
+        Node::Asset { file, .. } => {
+            let meta = std::fs::metadata(file).ok();
+            let modified: Option<DateTime<Utc>> = meta
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .map(Into::into);
+            props.push_str(&format!(
+                "<D:resourcetype/><D:getcontentlength>{}</D:getcontentlength>\
+                 <D:getcontenttype>{}</D:getcontenttype>",
+                meta.map_or(0, |m| m.len()),
+                mime_for_path(file)
+            ));
+            if let Some(modified) = modified {
+                props.push_str(&format!(
+                    "<D:getlastmodified>{}</D:getlastmodified>",
+                    http_date(modified)
+                ));
+            }
+        }
+    }
+    let is_dir = matches!(node, Node::Dir { .. } | Node::AssetDir { .. });
+    format!(
+        "<D:response><D:href>{}</D:href><D:propstat><D:prop>{}</D:prop>\
+         <D:status>HTTP/1.1 200 OK</D:status></D:propstat></D:response>",
+        xml_escape(&href(path, is_dir)),
+        props
+    )
+}
+
+fn propfind(tree: &Tree, path: &str, headers: &HeaderMap) -> Response {
+    let Some(node) = tree.nodes.get(path) else {
+        return text(StatusCode::NOT_FOUND, "Not found");
+    };
+    // "infinity" is answered as 1, as most servers do
+    let depth_zero = headers
+        .get("depth")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|d| d.trim() == "0");
+
+    let mut body =
+        String::from(r#"<?xml version="1.0" encoding="utf-8"?><D:multistatus xmlns:D="DAV:">"#);
+    body.push_str(&prop_response(path, node));
+    if !depth_zero {
+        for (child, node) in tree.children(path) {
+            body.push_str(&prop_response(child, node));
+        }
+    }
+    body.push_str("</D:multistatus>");
+
+    Response::builder()
+        .status(StatusCode::MULTI_STATUS)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .body(Body::from(body))
+        .unwrap_or_else(|_| {
+            text(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to build response",
+            )
+        })
+}
+
+/// Pages as Markdown, assets as files, directories as a plain HTML index
+/// for browsers
+fn get(tree: &Tree, path: &str, head: bool) -> Response {
+    let (content_type, bytes): (&str, Vec<u8>) = match tree.nodes.get(path) {
+        None => return text(StatusCode::NOT_FOUND, "Not found"),
+        Some(Node::Page { markdown, .. }) => {
+            ("text/markdown; charset=utf-8", markdown.as_bytes().to_vec())
+        }
+        Some(Node::Asset { file, .. }) => match std::fs::read(file) {
+            Ok(bytes) => (mime_for_path(file), bytes),
+            Err(_) => return text(StatusCode::NOT_FOUND, "Not found"),
+        },
+        Some(Node::Dir { .. } | Node::AssetDir { .. }) => {
+            let mut html = format!(
+                "<!DOCTYPE html><meta charset=\"utf-8\"><title>{0}</title><h1>{0}</h1><ul>",
+                xml_escape(if path.is_empty() {
+                    "Nous"
+                } else {
+                    name_of(path)
+                })
+            );
+            for (child, node) in tree.children(path) {
+                let is_dir = matches!(node, Node::Dir { .. } | Node::AssetDir { .. });
+                html.push_str(&format!(
+                    "<li><a href=\"{}\">{}{}</a></li>",
+                    xml_escape(&href(child, is_dir)),
+                    xml_escape(name_of(child)),
+                    if is_dir { "/" } else { "" }
+                ));
+            }
+            html.push_str("</ul>");
+            ("text/html; charset=utf-8", html.into_bytes())
+        }
+    };
+
+    let length = bytes.len();
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_LENGTH, length)
+        .body(if head {
+            Body::empty()
+        } else {
+            Body::from(bytes)
+        })
+        .unwrap_or_else(|_| {
+            text(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to build response",
+            )
+        })
+}
+
+/// The file an asset path maps to, if `path` is inside an `_assets`
+/// directory
+fn asset_file(tree: &Tree, path: &str) -> Option<(Uuid, PathBuf)> {
+    let mut dir = parent_of(path);
+    let mut rest = vec![name_of(path)];
+    loop {
+        match tree.nodes.get(dir) {
+            Some(Node::AssetDir { notebook_id, file }) => {
+                rest.reverse();
+                return mirror::resolve(file, &rest.join("/")).map(|f| (*notebook_id, f));
+            }
+            Some(_) | None if dir.is_empty() => return None,
+            _ => {
+                rest.push(name_of(dir));
+                dir = parent_of(dir);
+            }
+        }
+    }
+}
+
+fn storage_error(e: impl std::fmt::Display) -> Response {
+    log::warn!("WebDAV write failed: {}", e);
+    text(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+}
+
+fn put(state: &ServerState, tree: &Tree, path: &str, body: &[u8]) -> Response {
+    if let Some(Node::Page {
+        notebook_id,
+        page_id,
+        ..
+    }) = tree.nodes.get(path)
+    {
+        let Ok(markdown) = std::str::from_utf8(body) else {
+            return text(StatusCode::BAD_REQUEST, "Pages must be UTF-8 Markdown");
+        };
+        let storage = state.storage.write().unwrap();
+        let result = storage.get_page(*notebook_id, *page_id).and_then(|page| {
+            if page.page_type == PageType::Markdown {
+                storage.write_native_file_content(&page, markdown)
+            } else {
+                storage.update_page(&apply_edited_markdown(&page, markdown))
+            }
+        });
+        return match result {
+            Ok(()) => {
+                state.sync_manager.queue_page_update(*notebook_id, *page_id);
+                StatusCode::NO_CONTENT.into_response()
+            }
+            Err(e) => storage_error(e),
+        };
+    }
+
+    if let Some((_, file)) = asset_file(tree, path) {
+        let written = file
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| crate::storage::atomic::write(&file, body));
+        return match written {
+            Ok(()) => StatusCode::CREATED.into_response(),
+            Err(e) => storage_error(e),
+        };
+    }
+
+    // A new page in a notebook, section or folder directory
+    let Some(stem) = name_of(path).strip_suffix(".md") else {
+        return text(
+            StatusCode::FORBIDDEN,
+            "Only .md pages and files under _assets can be written",
+        );
+    };
+    let Some(Node::Dir {
+        notebook_id: Some(notebook_id),
+        sibling,
+    }) = tree.nodes.get(parent_of(path))
+    else {
+        return text(
+            StatusCode::CONFLICT,
+            "Pages can only be added inside a notebook",
+        );
+    };
+    let in_notebook_dir = !parent_of(path).contains('/');
+    if sibling.is_none() && !in_notebook_dir {
+        return text(
+            StatusCode::CONFLICT,
+            "Add pages next to an existing page or in the notebook directory",
+        );
+    }
+    let Ok(markdown) = std::str::from_utf8(body) else {
+        return text(StatusCode::BAD_REQUEST, "Pages must be UTF-8 Markdown");
+    };
+
+    let storage = state.storage.write().unwrap();
+    let mut page = import_markdown_to_page(markdown, *notebook_id, stem);
+    if let Some(sibling) = sibling.and_then(|id| storage.get_page(*notebook_id, id).ok()) {
+        page.folder_id = sibling.folder_id;
+        page.section_id = sibling.section_id;
+    }
+    match storage.create_page_from(page) {
+        Ok(page) => {
+            state
+                .sync_manager
+                .queue_page_update(page.notebook_id, page.id);
+            StatusCode::CREATED.into_response()
+        }
+        Err(e) => storage_error(e),
+    }
+}
+
+fn delete(state: &ServerState, tree: &Tree, path: &str) -> Response {
+    match tree.nodes.get(path) {
+        Some(Node::Page {
+            notebook_id,
+            page_id,
+            ..
+        }) => match state
+            .storage
+            .write()
+            .unwrap()
+            .delete_page(*notebook_id, *page_id)
+        {
+            Ok(()) => {
+                state.sync_manager.queue_page_delete(*notebook_id, *page_id);
+                StatusCode::NO_CONTENT.into_response()
+            }
+            Err(e) => storage_error(e),
+        },
+        Some(Node::Asset { file, .. }) => match std::fs::remove_file(file) {
+            Ok(()) => StatusCode::NO_CONTENT.into_response(),
+            Err(e) => storage_error(e),
+        },
+        Some(_) => text(StatusCode::FORBIDDEN, "Directories can't be deleted here"),
+        None => text(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+/// Rename a page (same directory only) or move an asset
+fn move_to(state: &ServerState, tree: &Tree, path: &str, headers: &HeaderMap) -> Response {
+    let Some(destination) = headers
+        .get("destination")
+        .and_then(|v| v.to_str().ok())
+        .map(|d| {
+            d.parse::<Uri>()
+                .map(|u| u.path().to_string())
+                .unwrap_or_default()
+        })
+        .and_then(|d| tree_path(&d))
+    else {
+        return text(StatusCode::BAD_REQUEST, "Missing or invalid Destination");
+    };
+
+    match tree.nodes.get(path) {
+        Some(Node::Page {
+            notebook_id,
+            page_id,
+            ..
+        }) => {
+            let Some(title) = name_of(&destination).strip_suffix(".md") else {
+                return text(StatusCode::FORBIDDEN, "Pages must keep the .md extension");
+            };
+            if parent_of(&destination) != parent_of(path) {
+                return text(StatusCode::FORBIDDEN, "Pages can only be renamed in place");
+            }
+            let storage = state.storage.write().unwrap();
+            let result = storage
+                .get_page(*notebook_id, *page_id)
+                .and_then(|mut page| {
+                    page.title = title.to_string();
+                    page.updated_at = Utc::now();
+                    storage.update_page(&page)
+                });
+            match result {
+                Ok(()) => {
+                    state.sync_manager.queue_page_update(*notebook_id, *page_id);
+                    StatusCode::CREATED.into_response()
+                }
+                Err(e) => storage_error(e),
+            }
+        }
+        Some(Node::Asset { file, .. }) => {
+            let Some((_, target)) = asset_file(tree, &destination) else {
+                return text(StatusCode::FORBIDDEN, "Assets can only move within _assets");
+            };
+            let moved = target
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::rename(file, &target));
+            match moved {
+                Ok(()) => StatusCode::CREATED.into_response(),
+                Err(e) => storage_error(e),
+            }
+        }
+        Some(_) => text(StatusCode::FORBIDDEN, "Directories can't be moved here"),
+        None => text(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+/// Finder and Windows won't write without locks. Nothing is actually
+/// locked; saves are last-writer-wins like any other edit.
+fn lock(path: &str) -> Response {
+    let token = format!("opaquelocktoken:{}", Uuid::new_v4());
+    let body = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><D:prop xmlns:D="DAV:"><D:lockdiscovery><D:activelock><D:locktype><D:write/></D:locktype><D:lockscope><D:exclusive/></D:lockscope><D:depth>0</D:depth><D:timeout>Second-3600</D:timeout><D:locktoken><D:href>{}</D:href></D:locktoken><D:lockroot><D:href>{}</D:href></D:lockroot></D:activelock></D:lockdiscovery></D:prop>"#,
+        token,
+        xml_escape(&href(path, false))
+    );
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+        .header("Lock-Token", format!("<{}>", token))
+        .body(Body::from(body))
+        .unwrap_or_else(|_| {
+            text(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to build response",
+            )
+        })
+}
+
+async fn handle(
+    State(state): State<Arc<ServerState>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !authorized(&state.token, &headers, uri.query()) {
+        return Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .header(header::WWW_AUTHENTICATE, "Basic realm=\"Nous\"")
+            .body(Body::empty())
+            .unwrap_or_else(|_| text(StatusCode::UNAUTHORIZED, "Unauthorized"));
+    }
+
+    if method == Method::OPTIONS {
+        let (dav, allow) = if state.read_only {
+            ("1", "OPTIONS, PROPFIND, GET, HEAD")
+        } else {
+            (
+                "1, 2",
+                "OPTIONS, PROPFIND, GET, HEAD, PUT, DELETE, MOVE, LOCK, UNLOCK",
+            )
+        };
+        return Response::builder()
+            .status(StatusCode::OK)
+            .header("DAV", dav)
+            .header(header::ALLOW, allow)
+            .body(Body::empty())
+            .unwrap_or_else(|_| {
+                text(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to build response",
+                )
+            });
+    }
+
+    let Some(path) = tree_path(uri.path()) else {
+        return text(StatusCode::BAD_REQUEST, "Invalid path");
+    };
+    let tree = {
+        let storage = state.storage.read().unwrap();
+        match Tree::build(&storage, state.include_archived) {
+            Ok(tree) => tree,
+            Err(e) => return storage_error(e),
+        }
+    };
+
+    match method.as_str() {
+        "PROPFIND" => propfind(&tree, &path, &headers),
+        "GET" => get(&tree, &path, false),
+        "HEAD" => get(&tree, &path, true),
+        "PUT" | "DELETE" | "MOVE" | "LOCK" | "UNLOCK" | "MKCOL" | "PROPPATCH"
+            if state.read_only =>
+        {
+            text(StatusCode::FORBIDDEN, "This share is read-only")
+        }
+        "PUT" => put(&state, &tree, &path, &body),
+        "DELETE" => delete(&state, &tree, &path),
+        "MOVE" => move_to(&state, &tree, &path, &headers),
+        "LOCK" => lock(&path),
+        "UNLOCK" => StatusCode::NO_CONTENT.into_response(),
+        _ => text(StatusCode::METHOD_NOT_ALLOWED, "Not supported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FileStorageWriter;
+
+    #[test]
+    fn accepts_the_token_as_basic_password_bearer_or_query() {
+        let mut headers = HeaderMap::new();
+        assert!(!authorized("secret", &headers, None));
+        assert!(authorized("secret", &headers, Some("a=1&token=secret")));
+
+        let basic = base64::engine::general_purpose::STANDARD.encode("anyone:secret");
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Basic {}", basic).parse().unwrap(),
+        );
+        assert!(authorized("secret", &headers, None));
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!authorized("secret", &headers, None));
+    }
+
+    #[test]
+    fn maps_request_paths_and_hrefs() {
+        assert_eq!(
+            tree_path("/Work/Q3%20plan.md").as_deref(),
+            Some("Work/Q3 plan.md")
+        );
+        assert_eq!(tree_path("/").as_deref(), Some(""));
+        assert_eq!(tree_path("/Work/../secrets"), None);
+        assert_eq!(href("Work/Q3 plan.md", false), "/Work/Q3%20plan.md");
+        assert_eq!(href("Work", true), "/Work/");
+        assert_eq!(href("", true), "/");
+    }
+
+    #[test]
+    fn builds_the_mirror_layout_with_asset_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FileStorageWriter::new(FileStorage::new(dir.path().to_path_buf()));
+        storage.init().unwrap();
+        let notebook = storage
+            .create_notebook("Work".to_string(), crate::storage::NotebookType::Standard)
+            .unwrap();
+        let page = storage
+            .create_page(notebook.id, "Q3 plan".to_string())
+            .unwrap();
+        let assets = storage.notebook_assets_dir(notebook.id);
+        std::fs::create_dir_all(&assets).unwrap();
+        std::fs::write(assets.join("chart.png"), b"png").unwrap();
+
+        let tree = Tree::build(&storage, false).unwrap();
+        assert!(matches!(
+            tree.nodes.get("Work/Q3 plan.md"),
+            Some(Node::Page { page_id, .. }) if *page_id == page.id
+        ));
+        let children: Vec<&String> = tree.children("Work").map(|(p, _)| p).collect();
+        assert_eq!(children, ["Work/Q3 plan.md", "Work/_assets"]);
+        assert_eq!(
+            asset_file(&tree, "Work/_assets/new/diagram.svg").map(|(_, f)| f),
+            Some(assets.join("new/diagram.svg"))
+        );
+        assert!(asset_file(&tree, "Work/notes.txt").is_none());
+    }
+}
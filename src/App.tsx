@@ -1,5 +1,6 @@
 import { useState, useCallback, useEffect } from "react";
 import { getCurrentWindow } from "./platform/window";
+import { listen } from "./platform/event";
 import { Layout } from "./components/Layout/Layout";
 import { CommandPalette } from "./components/CommandPalette/CommandPalette";
 import { GraphView } from "./components/Graph/GraphView";
@@ -10,7 +11,9 @@ import { ConfirmDialog } from "./components/ConfirmDialog";
 import { TemplateDialog } from "./components/TemplateDialog";
 import { TagManager } from "./components/Tags";
 import { BackupDialog } from "./components/Backup";
-import { PublishDialog } from "./components/Publish";
+import { PublishDialog, AudioFeedDialog } from "./components/Publish";
+import { ScratchpadDialog } from "./components/Scratchpad";
+import { ActivityFeedDialog } from "./components/Activity";
 import { ShareDialog } from "./components/Share";
 import { CollabDialog, LiveSessionsDialog } from "./components/Collab";
 import { ActionLibrary, ActionEditor } from "./components/Actions";
@@ -29,6 +32,7 @@ import { DropZoneOverlay } from "./components/Import/DropZoneOverlay";
 import { FileImportDialog, type ImportConfig, type ImportProgress } from "./components/Import/FileImportDialog";
 import { useAppInit } from "./hooks/useAppInit";
 import { useKeyboardShortcuts } from "./hooks/useKeyboardShortcuts";
+import { useDeepLinks } from "./hooks/useDeepLinks";
 import { useMainThreadWatchdog, getWatchdogLog, clearWatchdogLog } from "./hooks/useMainThreadWatchdog";
 import { readCrumbs } from "./utils/breadcrumbs";
 import { classifyFile, ALL_SUPPORTED_EXTENSIONS } from "./utils/fileImport";
@@ -38,6 +42,7 @@ import { flushAllSaves } from "./utils/saveCoordinator";
 import { useNotebookStore } from "./stores/notebookStore";
 import { usePageStore } from "./stores/pageStore";
 import { useThemeStore } from "./stores/themeStore";
+import { useStartupStore } from "./stores/startupStore";
 import { useActionStore } from "./stores/actionStore";
 import { useInboxStore } from "./stores/inboxStore";
 import { useAIStore } from "./stores/aiStore";
@@ -55,6 +60,7 @@ function App() {
   useAppInit();
   useMainThreadWatchdog({ thresholdMs: 500 });
   useCloudAutoSync();
+  useDeepLinks();
 
   // DL-22/26: flush pending editor saves before the window closes, and replay
   // any saves that failed in a previous session (queued in the outbox). On a
@@ -94,6 +100,14 @@ function App() {
     };
   }, []);
 
+  // Track the subsystems the backend starts in the background
+  useEffect(() => {
+    const unlisten = useStartupStore.getState().init();
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // On startup, dump diagnostics from the previous session
   useEffect(() => {
     const log = getWatchdogLog();
@@ -140,8 +154,12 @@ function App() {
   }, [library, isSecondaryWindow]);
 
   const [showCommandPalette, setShowCommandPalette] = useState(false);
+  const [paletteQuery, setPaletteQuery] = useState<string | undefined>(undefined);
   const [showBackup, setShowBackup] = useState(false);
   const [showPublish, setShowPublish] = useState(false);
+  const [showAudioFeed, setShowAudioFeed] = useState(false);
+  const [showScratchpad, setShowScratchpad] = useState(false);
+  const [showActivity, setShowActivity] = useState(false);
   const [showShare, setShowShare] = useState(false);
   const [sharePageId, setSharePageId] = useState<string | undefined>(undefined);
   const [shareNotebookId, setShareNotebookId] = useState<string | undefined>(undefined);
@@ -165,7 +183,10 @@ function App() {
 
   // Listen for custom event to open the command palette (e.g. vim <leader>)
   useEffect(() => {
-    const handleOpenPalette = () => setShowCommandPalette(true);
+    const handleOpenPalette = (e: Event) => {
+      setPaletteQuery((e as CustomEvent<{ query?: string }>).detail?.query);
+      setShowCommandPalette(true);
+    };
     window.addEventListener("open-command-palette", handleOpenPalette);
     return () =>
       window.removeEventListener("open-command-palette", handleOpenPalette);
@@ -185,6 +206,43 @@ function App() {
     return () => window.removeEventListener("open-publish-dialog", handleOpenPublish);
   }, []);
 
+  // Listen for custom event to open podcast feed dialog
+  useEffect(() => {
+    const handleOpenAudioFeed = () => setShowAudioFeed(true);
+    window.addEventListener("open-audio-feed-dialog", handleOpenAudioFeed);
+    return () => window.removeEventListener("open-audio-feed-dialog", handleOpenAudioFeed);
+  }, []);
+
+  // Listen for custom event to open the scratchpad
+  useEffect(() => {
+    const handleOpenScratchpad = () => setShowScratchpad(true);
+    window.addEventListener("open-scratchpad", handleOpenScratchpad);
+    return () => window.removeEventListener("open-scratchpad", handleOpenScratchpad);
+  }, []);
+
+  // Listen for custom event to open the activity feed, and offer it when a
+  // sync brings in changes from other devices
+  useEffect(() => {
+    const handleOpenActivity = () => setShowActivity(true);
+    window.addEventListener("open-activity-feed", handleOpenActivity);
+    const unlisten = listen<{ notebookId: string; entries: number }>(
+      "activity-recorded",
+      (event) => {
+        const count = event.payload.entries;
+        useToastStore.getState().addToast({
+          type: "info",
+          message: `${count} ${count === 1 ? "change" : "changes"} synced from other devices`,
+          duration: 8000,
+          action: { label: "Show", onClick: () => setShowActivity(true) },
+        });
+      }
+    );
+    return () => {
+      window.removeEventListener("open-activity-feed", handleOpenActivity);
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   // Listen for custom event to open share dialog
   useEffect(() => {
     const handleOpenShare = (e: Event) => {
@@ -528,10 +586,14 @@ function App() {
       {/* Command Palette */}
       <CommandPalette
         isOpen={showCommandPalette}
-        onClose={() => setShowCommandPalette(false)}
+        onClose={() => {
+          setShowCommandPalette(false);
+          setPaletteQuery(undefined);
+        }}
         onOpenGraph={() => setShowGraph(true)}
         onNewPage={handleNewPage}
         onOpenBackup={() => setShowBackup(true)}
+        initialQuery={paletteQuery}
       />
 
       {/* Graph View */}
@@ -601,6 +663,24 @@ function App() {
         onClose={() => setShowPublish(false)}
       />
 
+      {/* Podcast Feed Dialog */}
+      <AudioFeedDialog
+        isOpen={showAudioFeed}
+        onClose={() => setShowAudioFeed(false)}
+      />
+
+      {/* Scratchpad */}
+      <ScratchpadDialog
+        isOpen={showScratchpad}
+        onClose={() => setShowScratchpad(false)}
+      />
+
+      {/* Activity Feed */}
+      <ActivityFeedDialog
+        isOpen={showActivity}
+        onClose={() => setShowActivity(false)}
+      />
+
       {/* Share Dialog */}
       <ShareDialog
         isOpen={showShare}
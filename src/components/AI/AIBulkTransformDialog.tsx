@@ -0,0 +1,552 @@
+import { useState, useEffect, useMemo } from "react";
+import type { Page } from "../../types/page";
+import { useFocusTrap } from "../../hooks/useFocusTrap";
+import { useAIStore } from "../../stores/aiStore";
+import { listen, type UnlistenFn } from "../../platform/event";
+import { computeLineDiff } from "../../utils/diff";
+import * as api from "../../utils/api";
+import type {
+  BulkApplyResult,
+  BulkOperation,
+  BulkPageChange,
+  BulkSkipped,
+} from "../../utils/api";
+
+type Step = "configure" | "running" | "review" | "done";
+
+type OperationKind = BulkOperation["kind"];
+
+const OPERATIONS: { kind: OperationKind; label: string; description: string }[] = [
+  { kind: "retitle", label: "Retitle", description: "Give each page a descriptive title" },
+  { kind: "summarize", label: "Summarize", description: "Write a short summary into a page property" },
+  { kind: "translate", label: "Translate", description: "Translate the content into another language" },
+  { kind: "fixFormatting", label: "Fix formatting", description: "Tidy headings, lists and spacing" },
+  { kind: "custom", label: "Custom", description: "Rewrite each page following your instruction" },
+];
+
+interface AIBulkTransformDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+  notebookId: string;
+  /** Pages that can be selected */
+  pages: Page[];
+  /** Pages selected when the dialog opens (all when omitted) */
+  initialPageIds?: string[];
+  onCompleted?: () => void;
+}
+
+export function AIBulkTransformDialog({
+  isOpen,
+  onClose,
+  notebookId,
+  pages,
+  initialPageIds,
+  onCompleted,
+}: AIBulkTransformDialogProps) {
+  const [step, setStep] = useState<Step>("configure");
+  const [kind, setKind] = useState<OperationKind>("retitle");
+  const [parameter, setParameter] = useState("");
+  const [selectedPages, setSelectedPages] = useState<Set<string>>(new Set());
+  const [progress, setProgress] = useState<{ done: number; total: number } | null>(null);
+  const [taskId, setTaskId] = useState<string | null>(null);
+  const [changes, setChanges] = useState<BulkPageChange[]>([]);
+  const [skipped, setSkipped] = useState<BulkSkipped[]>([]);
+  const [accepted, setAccepted] = useState<Set<string>>(new Set());
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const [result, setResult] = useState<BulkApplyResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [isBusy, setIsBusy] = useState(false);
+
+  const focusTrapRef = useFocusTrap(isOpen);
+
+  useEffect(() => {
+    if (isOpen) {
+      setStep("configure");
+      setError(null);
+      setChanges([]);
+      setSkipped([]);
+      setResult(null);
+      setProgress(null);
+      setSelectedPages(new Set(initialPageIds ?? pages.map((p) => p.id)));
+    }
+    // Only when the dialog opens; `pages` changes as pages are saved
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen]);
+
+  useEffect(() => {
+    if (!taskId) return;
+    let unlisten: UnlistenFn | undefined;
+    listen<{ taskId: string | null; done: number; total: number }>(
+      "ai-bulk-progress",
+      (event) => {
+        if (event.payload.taskId === taskId) {
+          setProgress({ done: event.payload.done, total: event.payload.total });
+        }
+      }
+    ).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, [taskId]);
+
+  const operation = useMemo((): BulkOperation => {
+    const value = parameter.trim();
+    switch (kind) {
+      case "summarize":
+        return { kind, property: value || "summary" };
+      case "translate":
+        return { kind, language: value };
+      case "custom":
+        return { kind, prompt: value };
+      default:
+        return { kind };
+    }
+  }, [kind, parameter]);
+
+  const needsParameter = kind === "translate" || kind === "custom";
+
+  const handleClose = () => {
+    if (step === "running" && taskId) {
+      api.cancelAiTask(taskId);
+    }
+    onClose();
+  };
+
+  const togglePage = (pageId: string) => {
+    setSelectedPages((prev) => {
+      const next = new Set(prev);
+      if (next.has(pageId)) next.delete(pageId);
+      else next.add(pageId);
+      return next;
+    });
+  };
+
+  const toggleAccepted = (pageId: string) => {
+    setAccepted((prev) => {
+      const next = new Set(prev);
+      if (next.has(pageId)) next.delete(pageId);
+      else next.add(pageId);
+      return next;
+    });
+  };
+
+  const handlePreview = async () => {
+    if (selectedPages.size === 0) {
+      setError("Select at least one page");
+      return;
+    }
+    if (needsParameter && !parameter.trim()) {
+      setError(kind === "translate" ? "Enter a target language" : "Enter an instruction");
+      return;
+    }
+
+    const id = crypto.randomUUID();
+    const ai = useAIStore.getState();
+    setTaskId(id);
+    setProgress({ done: 0, total: selectedPages.size });
+    setStep("running");
+    setError(null);
+
+    try {
+      const preview = await api.aiBulkTransform(
+        notebookId,
+        pages.filter((p) => selectedPages.has(p.id)).map((p) => p.id),
+        operation,
+        {
+          providerType: ai.getActiveProviderType(),
+          apiKey: ai.getActiveApiKey() || undefined,
+          model: ai.getActiveModel(),
+          taskId: id,
+        }
+      );
+      setChanges(preview.changes);
+      setSkipped(preview.skipped);
+      setAccepted(new Set(preview.changes.map((c) => c.pageId)));
+      setExpanded(null);
+      setStep("review");
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String((e as { message?: string }).message ?? e));
+      setStep("configure");
+    } finally {
+      setTaskId(null);
+    }
+  };
+
+  const handleApply = async () => {
+    const toApply = changes.filter((c) => accepted.has(c.pageId));
+    if (toApply.length === 0) return;
+    setIsBusy(true);
+    setError(null);
+    try {
+      const applied = await api.applyAiBulkTransform(operation, toApply);
+      setResult(applied);
+      setStep("done");
+      onCompleted?.();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String((e as { message?: string }).message ?? e));
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleUndo = async () => {
+    if (!result) return;
+    if (
+      !window.confirm(
+        "Undo restores the whole library to the snapshot taken before these changes, including any edits made since. Continue?"
+      )
+    ) {
+      return;
+    }
+    setIsBusy(true);
+    try {
+      await api.rollbackToSnapshot(result.snapshot.id);
+      try {
+        await api.rebuildSearchIndex();
+      } catch (err) {
+        console.warn("Search index rebuild after undo failed:", err);
+      }
+      window.location.reload();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String((e as { message?: string }).message ?? e));
+      setIsBusy(false);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  const muted = { color: "var(--color-text-muted)" };
+  const primary = { color: "var(--color-text-primary)" };
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={(e) => {
+        if (e.target === e.currentTarget && step !== "running") handleClose();
+      }}
+      role="presentation"
+    >
+      <div
+        ref={focusTrapRef}
+        role="dialog"
+        aria-modal="true"
+        aria-labelledby="ai-bulk-title"
+        className="w-full max-w-2xl rounded-xl border p-6 shadow-2xl"
+        style={{
+          backgroundColor: "var(--color-bg-secondary)",
+          borderColor: "var(--color-border)",
+          maxHeight: "80vh",
+          display: "flex",
+          flexDirection: "column",
+        }}
+      >
+        <h2 id="ai-bulk-title" className="mb-4 text-lg font-semibold" style={primary}>
+          AI Bulk Edit
+        </h2>
+
+        {error && (
+          <div
+            className="mb-3 rounded-lg px-3 py-2 text-sm"
+            style={{ backgroundColor: "rgba(239, 68, 68, 0.1)", color: "#ef4444" }}
+          >
+            {error}
+          </div>
+        )}
+
+        {step === "configure" && (
+          <div className="flex min-h-0 flex-1 flex-col gap-4">
+            <div className="grid grid-cols-2 gap-2">
+              {OPERATIONS.map((op) => (
+                <button
+                  key={op.kind}
+                  onClick={() => {
+                    setKind(op.kind);
+                    setParameter("");
+                  }}
+                  className="rounded-lg border p-3 text-left transition-colors"
+                  style={{
+                    borderColor: kind === op.kind ? "var(--color-accent)" : "var(--color-border)",
+                    backgroundColor: kind === op.kind ? "var(--color-bg-tertiary)" : "transparent",
+                  }}
+                >
+                  <div className="text-sm font-medium" style={primary}>
+                    {op.label}
+                  </div>
+                  <div className="text-xs" style={muted}>
+                    {op.description}
+                  </div>
+                </button>
+              ))}
+            </div>
+
+            {kind !== "retitle" && kind !== "fixFormatting" && (
+              <input
+                type="text"
+                value={parameter}
+                onChange={(e) => setParameter(e.target.value)}
+                placeholder={
+                  kind === "summarize"
+                    ? "Property name (default: summary)"
+                    : kind === "translate"
+                      ? "Target language, e.g. French"
+                      : "Instruction, e.g. Rewrite in plain English"
+                }
+                className="rounded-lg border px-3 py-2 text-sm"
+                style={{
+                  backgroundColor: "var(--color-bg-primary)",
+                  borderColor: "var(--color-border)",
+                  color: "var(--color-text-primary)",
+                }}
+              />
+            )}
+
+            <div className="flex items-center justify-between text-xs" style={muted}>
+              <span>
+                {selectedPages.size} of {pages.length} pages selected
+              </span>
+              <span className="flex gap-3">
+                <button
+                  className="hover:underline"
+                  style={{ color: "var(--color-accent)" }}
+                  onClick={() => setSelectedPages(new Set(pages.map((p) => p.id)))}
+                >
+                  Select all
+                </button>
+                <button className="hover:underline" onClick={() => setSelectedPages(new Set())}>
+                  Clear
+                </button>
+              </span>
+            </div>
+            <div
+              className="min-h-0 flex-1 overflow-y-auto rounded-lg border"
+              style={{ borderColor: "var(--color-border)" }}
+            >
+              {pages.map((page) => (
+                <label
+                  key={page.id}
+                  className="flex cursor-pointer items-center gap-2 px-3 py-1.5 text-sm hover:bg-[--color-bg-tertiary]"
+                  style={primary}
+                >
+                  <input
+                    type="checkbox"
+                    checked={selectedPages.has(page.id)}
+                    onChange={() => togglePage(page.id)}
+                  />
+                  <span className="truncate">{page.title || "Untitled"}</span>
+                </label>
+              ))}
+            </div>
+
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={handleClose}
+                className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Cancel
+              </button>
+              <button
+                onClick={handlePreview}
+                disabled={selectedPages.size === 0}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                Preview Changes
+              </button>
+            </div>
+          </div>
+        )}
+
+        {step === "running" && (
+          <div className="flex flex-col items-center gap-4 py-8">
+            <div className="text-sm" style={muted}>
+              Working on page {Math.min((progress?.done ?? 0) + 1, progress?.total ?? 1)} of{" "}
+              {progress?.total ?? selectedPages.size}...
+            </div>
+            <div
+              className="h-2 w-full overflow-hidden rounded-full"
+              style={{ backgroundColor: "var(--color-bg-tertiary)" }}
+            >
+              <div
+                className="h-full transition-all"
+                style={{
+                  width: `${progress && progress.total ? (progress.done / progress.total) * 100 : 0}%`,
+                  backgroundColor: "var(--color-accent)",
+                }}
+              />
+            </div>
+            <button
+              onClick={() => taskId && api.cancelAiTask(taskId)}
+              className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              Stop
+            </button>
+          </div>
+        )}
+
+        {step === "review" && (
+          <div className="flex min-h-0 flex-1 flex-col gap-3">
+            <div className="text-sm" style={muted}>
+              {changes.length} proposed change{changes.length !== 1 ? "s" : ""}
+              {skipped.length > 0 && `, ${skipped.length} skipped`}. A snapshot of the
+              library is taken before anything is saved.
+            </div>
+            <div className="min-h-0 flex-1 space-y-2 overflow-y-auto">
+              {changes.map((change) => (
+                <ChangeRow
+                  key={change.pageId}
+                  change={change}
+                  accepted={accepted.has(change.pageId)}
+                  expanded={expanded === change.pageId}
+                  onToggle={() => toggleAccepted(change.pageId)}
+                  onExpand={() =>
+                    setExpanded((prev) => (prev === change.pageId ? null : change.pageId))
+                  }
+                />
+              ))}
+              {skipped.length > 0 && <SkippedList skipped={skipped} />}
+            </div>
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={() => setStep("configure")}
+                className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Back
+              </button>
+              <button
+                onClick={handleApply}
+                disabled={isBusy || accepted.size === 0}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                {isBusy ? "Applying..." : `Apply ${accepted.size} Change${accepted.size !== 1 ? "s" : ""}`}
+              </button>
+            </div>
+          </div>
+        )}
+
+        {step === "done" && result && (
+          <div className="flex min-h-0 flex-1 flex-col gap-3">
+            <div className="text-sm" style={primary}>
+              Updated {result.applied.length} page{result.applied.length !== 1 ? "s" : ""}.
+            </div>
+            {result.skipped.length > 0 && <SkippedList skipped={result.skipped} />}
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={handleUndo}
+                disabled={isBusy}
+                className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary] disabled:opacity-50"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Undo All
+              </button>
+              <button
+                onClick={handleClose}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                Done
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
+
+function ChangeRow({
+  change,
+  accepted,
+  expanded,
+  onToggle,
+  onExpand,
+}: {
+  change: BulkPageChange;
+  accepted: boolean;
+  expanded: boolean;
+  onToggle: () => void;
+  onExpand: () => void;
+}) {
+  const diff = useMemo(
+    () =>
+      expanded && change.content
+        ? computeLineDiff(change.beforeMarkdown.split("\n"), change.afterMarkdown.split("\n"))
+        : [],
+    [expanded, change]
+  );
+
+  return (
+    <div className="rounded-lg border" style={{ borderColor: "var(--color-border)" }}>
+      <div className="flex items-start gap-2 p-3">
+        <input type="checkbox" checked={accepted} onChange={onToggle} className="mt-1" />
+        <div className="min-w-0 flex-1 text-sm">
+          <div className="truncate font-medium" style={{ color: "var(--color-text-primary)" }}>
+            {change.title || "Untitled"}
+          </div>
+          {change.newTitle && (
+            <div style={{ color: "var(--color-text-secondary)" }}>
+              <span style={{ color: "var(--color-text-muted)" }}>New title: </span>
+              {change.newTitle}
+            </div>
+          )}
+          {change.property && (
+            <div style={{ color: "var(--color-text-secondary)" }}>
+              <span style={{ color: "var(--color-text-muted)" }}>{change.property.name}: </span>
+              {change.property.value}
+            </div>
+          )}
+        </div>
+        {change.content && (
+          <button
+            onClick={onExpand}
+            className="text-xs hover:underline"
+            style={{ color: "var(--color-accent)" }}
+          >
+            {expanded ? "Hide diff" : "Show diff"}
+          </button>
+        )}
+      </div>
+      {expanded && (
+        <pre
+          className="max-h-64 overflow-auto border-t px-3 py-2 text-xs whitespace-pre-wrap"
+          style={{ borderColor: "var(--color-border)" }}
+        >
+          {diff.map((line, i) => (
+            <div
+              key={i}
+              style={{
+                color:
+                  line.type === "added"
+                    ? "#22c55e"
+                    : line.type === "removed"
+                      ? "#ef4444"
+                      : "var(--color-text-muted)",
+              }}
+            >
+              {line.type === "added" ? "+ " : line.type === "removed" ? "- " : "  "}
+              {line.text}
+            </div>
+          ))}
+        </pre>
+      )}
+    </div>
+  );
+}
+
+function SkippedList({ skipped }: { skipped: BulkSkipped[] }) {
+  return (
+    <div className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+      <div className="mb-1 font-medium">Skipped</div>
+      <ul className="list-disc space-y-0.5 pl-5">
+        {skipped.map((s) => (
+          <li key={s.pageId}>
+            {s.title || "Untitled"}: {s.reason}
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
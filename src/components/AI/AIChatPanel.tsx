@@ -15,13 +15,16 @@ import { useToastStore } from "../../stores/toastStore";
 import * as api from "../../utils/api";
 import {
   aiChatStream,
+  checkToolPermission,
   createNotebook as apiCreateNotebook,
   createPage as apiCreatePage,
   updatePage as apiUpdatePage,
+  recordAiAction,
   runBrowserTask,
 } from "../../utils/api";
 import { transcribeAudio, synthesizeText, saveAudioRecording } from "../../utils/audioApi";
 import { useAudioStore } from "../../stores/audioStore";
+import { useSecretsStore } from "../../stores/secretsStore";
 import { convertFileSrc } from "../../platform/core";
 import type { ChatMessage, PageContext, AIAction, CreateNotebookArgs, CreatePageArgs } from "../../types/ai";
 import type { EditorData } from "../../types/page";
@@ -32,6 +35,9 @@ interface AIChatPanelProps {
   onOpenSettings?: () => void;
 }
 
+// Tools executed by the frontend rather than the Python chat loop
+const FRONTEND_TOOLS = new Set(["create_notebook", "create_page", "run_action", "browse_web"]);
+
 // Tool call status tracking
 type ToolCallStatus = "pending" | "executing" | "success" | "error";
 
@@ -131,6 +137,7 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
   } = useRAGStore();
   const toast = useToastStore();
   const audioSettings = useAudioStore((s) => s.settings);
+  const storedSecrets = useSecretsStore((s) => s.stored);
 
   // Use props if provided, otherwise use store state
   const isOpen = isOpenProp !== undefined ? isOpenProp : panel.isOpen;
@@ -661,6 +668,16 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
     toast.success(`Created subpage "${title}" under "${currentPage.title}"`);
   }, [selectedNotebookId, currentPage, markdownToBlocks, createSubpage, updatePageContent, loadPages, toast]);
 
+  // Record what an action created in the AI action log, so it can be undone
+  const logAiAction = useCallback((
+    action: AIAction,
+    created: { notebookIds?: string[]; pages?: { notebookId: string; pageId: string }[] },
+  ) => {
+    recordAiAction(action, { ...created, sessionId: currentSessionRef.current?.id }).catch((error) =>
+      console.warn("Failed to log AI action:", error)
+    );
+  }, []);
+
   // Execute AI actions (create notebooks/pages) with per-action progress reporting
   const executeActions = useCallback(async (
     actions: AIAction[],
@@ -679,12 +696,25 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
       onProgress?.(action.toolCallId, "executing");
 
       try {
+        // Tools run here are checked here; Python checks the ones it runs
+        if (FRONTEND_TOOLS.has(action.tool)) {
+          const check = await checkToolPermission(action);
+          const allowed = check.decision === "allow" || (
+            check.decision === "ask" &&
+            window.confirm(`Allow the AI to run ${action.tool}?\n\n${check.reason}`)
+          );
+          if (!allowed) {
+            throw new Error(`${action.tool} was not run (${check.reason})`);
+          }
+        }
+
         if (action.tool === "create_notebook") {
           const args = action.arguments as unknown as CreateNotebookArgs;
           const newNotebook = await apiCreateNotebook(args.name);
           notebooksSnapshot.push(newNotebook);
           needsNotebookRefresh = true;
           record.result = `Created notebook "${args.name}"`;
+          logAiAction(action, { notebookIds: [newNotebook.id] });
         } else if (action.tool === "run_action") {
           const args = action.arguments as unknown as { action_name: string; variables?: Record<string, string> };
           const { runActionByName } = await import("../../utils/api");
@@ -731,6 +761,7 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
           const args = action.arguments as unknown as CreatePageArgs;
           let targetNotebookId = selectedNotebookId;
           let targetNotebookName = currentNotebook?.name || "current notebook";
+          const createdNotebookIds: string[] = [];
 
           if (args.notebook_name !== "current") {
             const targetNotebook = notebooksSnapshot.find(
@@ -743,6 +774,7 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
               const newNotebook = await apiCreateNotebook(args.notebook_name);
               notebooksSnapshot.push(newNotebook);
               needsNotebookRefresh = true;
+              createdNotebookIds.push(newNotebook.id);
               targetNotebookId = newNotebook.id;
               targetNotebookName = newNotebook.name;
             }
@@ -768,6 +800,10 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
           }
           await apiUpdatePage(targetNotebookId, newPage.id, updates);
           record.result = `Created page "${args.title}" in ${targetNotebookName}`;
+          logAiAction(action, {
+            notebookIds: createdNotebookIds,
+            pages: [{ notebookId: targetNotebookId, pageId: newPage.id }],
+          });
         }
 
         onProgress?.(action.toolCallId, "success", record.result);
@@ -789,7 +825,7 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
     }
 
     return records;
-  }, [selectedNotebookId, currentNotebook, notebooks, loadNotebooks, loadPages]);
+  }, [selectedNotebookId, currentNotebook, notebooks, loadNotebooks, loadPages, logAiAction]);
 
   // Toggle thinking expansion
   const toggleThinking = (index: number) => {
@@ -1119,6 +1155,29 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
             });
             break;
 
+          case "toolPermission":
+            // Python did not run this tool call; nothing is left to execute
+            pendingActions = pendingActions.filter(a => a.toolCallId !== data.toolCallId);
+            setDisplayMessages(prev => {
+              const newMessages = [...prev];
+              if (newMessages[assistantMsgIndex]) {
+                const tcs = newMessages[assistantMsgIndex].toolCalls?.map(tc =>
+                  tc.toolCallId === data.toolCallId
+                    ? {
+                        ...tc,
+                        status: "error" as const,
+                        error: data.decision === "ask"
+                          ? `Needs approval (${data.reason})`
+                          : `Not permitted (${data.reason})`,
+                      }
+                    : tc
+                );
+                newMessages[assistantMsgIndex] = { ...newMessages[assistantMsgIndex], toolCalls: tcs };
+              }
+              return newMessages;
+            });
+            break;
+
           case "done":
             console.log("[AI Stream] Done event - model:", data.model, "tokens:", data.tokensUsed);
             responseModel = data.model;
@@ -1171,6 +1230,14 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
         name: n.name,
       }));
 
+      // The notebook's AI profile stands in for its own prompt and model
+      const notebookProfile = currentNotebook?.aiProfileId
+        ? await api
+            .listAiProfiles()
+            .then((profiles) => profiles.find((p) => p.id === currentNotebook.aiProfileId))
+            .catch(() => undefined)
+        : undefined;
+
       // Resolve system prompt with inheritance and concatenation support
       // Hierarchy: page -> section -> notebook -> app
       // Each level can either override higher-level prompts or concatenate with them
@@ -1181,7 +1248,7 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
         const appPrompt = settings.systemPrompt;
 
         // Check notebook level
-        const notebookPrompt = currentNotebook?.systemPrompt;
+        const notebookPrompt = currentNotebook?.systemPrompt || notebookProfile?.systemPrompt;
         const notebookMode = currentNotebook?.systemPromptMode || "override";
 
         // Check section level
@@ -1255,15 +1322,22 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
         if (chatModelOverride) return chatModelOverride;
         if (currentPage?.aiModel) return currentPage.aiModel;
         if (currentSection?.aiModel) return currentSection.aiModel;
+        if (notebookProfile?.model) return notebookProfile.model;
         if (currentNotebook?.aiModel) return currentNotebook.aiModel;
         return effectiveDefaultModel;
       };
 
       const resolvedModel = resolveModel();
+      const activeProfile =
+        notebookProfile && resolvedModel === notebookProfile.model ? notebookProfile : undefined;
 
-      // Resolve the provider from the model being sent
-      const resolvedProvider = getProviderForModel(resolvedModel);
-      const resolvedProviderConfig = getProviderConfig(resolvedProvider);
+      // Resolve the provider from the model being sent (or the profile that chose it)
+      const resolvedProvider = activeProfile
+        ? (activeProfile.providerType as ReturnType<typeof getProviderForModel>)
+        : getProviderForModel(resolvedModel);
+      const resolvedProviderConfig = getProviderConfig(
+        (activeProfile?.apiKeyRef as typeof resolvedProvider | undefined) || resolvedProvider
+      );
 
       // Start the streaming request - command now waits for completion
       await aiChatStream(userMessage.content, {
@@ -1284,10 +1358,10 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
         currentNotebookId: selectedNotebookId || undefined,
         providerType: resolvedProvider,
         apiKey: resolvedProviderConfig?.apiKey || undefined,
-        baseUrl: resolvedProviderConfig?.baseUrl || undefined,
+        baseUrl: activeProfile?.baseUrl || resolvedProviderConfig?.baseUrl || undefined,
         model: resolvedModel || undefined,
-        temperature: settings.temperature,
-        maxTokens: settings.maxTokens,
+        temperature: activeProfile?.temperature ?? settings.temperature,
+        maxTokens: activeProfile?.maxTokens ?? settings.maxTokens,
         systemPrompt: resolvedSystemPrompt,
       });
 
@@ -1916,7 +1990,7 @@ export function AIChatPanel({ isOpen: isOpenProp, onClose: onCloseProp, onOpenSe
         const activeModel = chatModelOverride || currentPage?.aiModel || currentSection?.aiModel || currentNotebook?.aiModel || effectiveDefaultModel;
         const activeProvider = getProviderForModel(activeModel);
         const activeProviderConfig = getProviderConfig(activeProvider);
-        const needsKey = activeProvider !== "ollama" && activeProvider !== "lmstudio" && !activeProviderConfig?.apiKey;
+        const needsKey = activeProvider !== "ollama" && activeProvider !== "lmstudio" && !activeProviderConfig?.apiKey && !storedSecrets[api.secretIds.ai(activeProvider)];
         return needsKey ? (
           <button
             onClick={onOpenSettings}
@@ -0,0 +1,383 @@
+import { useState, useEffect } from "react";
+import type { Folder } from "../../types/page";
+import { useFocusTrap } from "../../hooks/useFocusTrap";
+import { useAIStore } from "../../stores/aiStore";
+import { listen, type UnlistenFn } from "../../platform/event";
+import * as api from "../../utils/api";
+import type { FolderTranslation, TranslationMode } from "../../utils/api";
+
+type Step = "configure" | "running" | "done";
+
+const COMMON_LANGUAGES = [
+  "English",
+  "Spanish",
+  "French",
+  "German",
+  "Italian",
+  "Portuguese",
+  "Dutch",
+  "Polish",
+  "Russian",
+  "Ukrainian",
+  "Turkish",
+  "Arabic",
+  "Hindi",
+  "Chinese (Simplified)",
+  "Japanese",
+  "Korean",
+];
+
+interface TranslateDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+  notebookId: string;
+  /** The open page, offered as the default target */
+  pageId?: string | null;
+  pageTitle?: string;
+  folders: Folder[];
+  onCompleted?: () => void;
+  /** Open a translated copy */
+  onOpenPage?: (pageId: string) => void;
+}
+
+export function TranslateDialog({
+  isOpen,
+  onClose,
+  notebookId,
+  pageId,
+  pageTitle,
+  folders,
+  onCompleted,
+  onOpenPage,
+}: TranslateDialogProps) {
+  const [step, setStep] = useState<Step>("configure");
+  const [scope, setScope] = useState<"page" | "folder">("page");
+  const [folderId, setFolderId] = useState("");
+  const [language, setLanguage] = useState("");
+  const [mode, setMode] = useState<TranslationMode>("copy");
+  const [taskId, setTaskId] = useState<string | null>(null);
+  const [progress, setProgress] = useState<{ done: number; total: number } | null>(null);
+  const [result, setResult] = useState<FolderTranslation | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const focusTrapRef = useFocusTrap(isOpen);
+
+  useEffect(() => {
+    if (isOpen) {
+      setStep("configure");
+      setScope(pageId ? "page" : "folder");
+      setFolderId((prev) => prev || folders[0]?.id || "");
+      setResult(null);
+      setProgress(null);
+      setError(null);
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen]);
+
+  useEffect(() => {
+    if (!taskId) return;
+    let unlisten: UnlistenFn | undefined;
+    listen<{ taskId: string | null; done: number; total: number }>(
+      "translation-progress",
+      (event) => {
+        if (event.payload.taskId === taskId) {
+          setProgress({ done: event.payload.done, total: event.payload.total });
+        }
+      }
+    ).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, [taskId]);
+
+  const handleClose = () => {
+    if (step === "running" && taskId) {
+      api.cancelAiTask(taskId);
+    }
+    onClose();
+  };
+
+  const handleTranslate = async () => {
+    const target = language.trim();
+    if (!target) {
+      setError("Enter a target language");
+      return;
+    }
+    if (scope === "folder" && !folderId) {
+      setError("Choose a folder");
+      return;
+    }
+
+    const id = crypto.randomUUID();
+    const ai = useAIStore.getState();
+    const options = {
+      providerType: ai.getActiveProviderType(),
+      apiKey: ai.getActiveApiKey() || undefined,
+      model: ai.getActiveModel(),
+      taskId: id,
+    };
+    setTaskId(id);
+    setProgress(null);
+    setStep("running");
+    setError(null);
+
+    try {
+      if (scope === "page" && pageId) {
+        const translated = await api.translatePage(notebookId, pageId, target, mode, options);
+        setResult({ translated: [translated], skipped: [] });
+      } else {
+        setResult(await api.translateFolder(notebookId, folderId, target, mode, options));
+      }
+      setStep("done");
+      onCompleted?.();
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String((e as { message?: string }).message ?? e));
+      setStep("configure");
+    } finally {
+      setTaskId(null);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  const muted = { color: "var(--color-text-muted)" };
+  const primary = { color: "var(--color-text-primary)" };
+  const inputStyle = {
+    backgroundColor: "var(--color-bg-primary)",
+    borderColor: "var(--color-border)",
+    color: "var(--color-text-primary)",
+  };
+  const optionStyle = (active: boolean) => ({
+    borderColor: active ? "var(--color-accent)" : "var(--color-border)",
+    backgroundColor: active ? "var(--color-bg-tertiary)" : "transparent",
+  });
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={(e) => {
+        if (e.target === e.currentTarget && step !== "running") handleClose();
+      }}
+      role="presentation"
+    >
+      <div
+        ref={focusTrapRef}
+        role="dialog"
+        aria-modal="true"
+        aria-labelledby="translate-title"
+        className="w-full max-w-lg rounded-xl border p-6 shadow-2xl"
+        style={{
+          backgroundColor: "var(--color-bg-secondary)",
+          borderColor: "var(--color-border)",
+          maxHeight: "80vh",
+          display: "flex",
+          flexDirection: "column",
+        }}
+      >
+        <h2 id="translate-title" className="mb-4 text-lg font-semibold" style={primary}>
+          Translate
+        </h2>
+
+        {error && (
+          <div
+            className="mb-3 rounded-lg px-3 py-2 text-sm"
+            style={{ backgroundColor: "rgba(239, 68, 68, 0.1)", color: "#ef4444" }}
+          >
+            {error}
+          </div>
+        )}
+
+        {step === "configure" && (
+          <div className="flex flex-col gap-4">
+            <div className="grid grid-cols-2 gap-2">
+              <button
+                onClick={() => setScope("page")}
+                disabled={!pageId}
+                className="rounded-lg border p-3 text-left disabled:opacity-50"
+                style={optionStyle(scope === "page")}
+              >
+                <div className="text-sm font-medium" style={primary}>
+                  This page
+                </div>
+                <div className="truncate text-xs" style={muted}>
+                  {pageId ? pageTitle || "Untitled" : "No page open"}
+                </div>
+              </button>
+              <button
+                onClick={() => setScope("folder")}
+                disabled={folders.length === 0}
+                className="rounded-lg border p-3 text-left disabled:opacity-50"
+                style={optionStyle(scope === "folder")}
+              >
+                <div className="text-sm font-medium" style={primary}>
+                  A folder
+                </div>
+                <div className="text-xs" style={muted}>
+                  Every page in it and its subfolders
+                </div>
+              </button>
+            </div>
+
+            {scope === "folder" && (
+              <select
+                value={folderId}
+                onChange={(e) => setFolderId(e.target.value)}
+                className="rounded-lg border px-3 py-2 text-sm"
+                style={inputStyle}
+              >
+                {folders.map((folder) => (
+                  <option key={folder.id} value={folder.id}>
+                    {folder.name}
+                  </option>
+                ))}
+              </select>
+            )}
+
+            <input
+              type="text"
+              list="translate-languages"
+              value={language}
+              onChange={(e) => setLanguage(e.target.value)}
+              placeholder="Target language, e.g. French"
+              className="rounded-lg border px-3 py-2 text-sm"
+              style={inputStyle}
+            />
+            <datalist id="translate-languages">
+              {COMMON_LANGUAGES.map((lang) => (
+                <option key={lang} value={lang} />
+              ))}
+            </datalist>
+
+            <div className="space-y-2 text-sm" style={primary}>
+              <label className="flex cursor-pointer items-start gap-2">
+                <input
+                  type="radio"
+                  checked={mode === "copy"}
+                  onChange={() => setMode("copy")}
+                  className="mt-1"
+                />
+                <span>
+                  Create a translated copy
+                  <span className="block text-xs" style={muted}>
+                    Placed next to the original and linked to it
+                  </span>
+                </span>
+              </label>
+              <label className="flex cursor-pointer items-start gap-2">
+                <input
+                  type="radio"
+                  checked={mode === "replace"}
+                  onChange={() => setMode("replace")}
+                  className="mt-1"
+                />
+                <span>
+                  Replace the content
+                  <span className="block text-xs" style={muted}>
+                    The previous version stays in page history
+                  </span>
+                </span>
+              </label>
+            </div>
+
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={handleClose}
+                className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Cancel
+              </button>
+              <button
+                onClick={handleTranslate}
+                disabled={!language.trim()}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                Translate
+              </button>
+            </div>
+          </div>
+        )}
+
+        {step === "running" && (
+          <div className="flex flex-col items-center gap-4 py-8">
+            <div className="text-sm" style={muted}>
+              {progress
+                ? `Translating page ${Math.min(progress.done + 1, progress.total)} of ${progress.total}...`
+                : "Translating..."}
+            </div>
+            {progress && (
+              <div
+                className="h-2 w-full overflow-hidden rounded-full"
+                style={{ backgroundColor: "var(--color-bg-tertiary)" }}
+              >
+                <div
+                  className="h-full transition-all"
+                  style={{
+                    width: `${progress.total ? (progress.done / progress.total) * 100 : 0}%`,
+                    backgroundColor: "var(--color-accent)",
+                  }}
+                />
+              </div>
+            )}
+            <button
+              onClick={() => taskId && api.cancelAiTask(taskId)}
+              className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              Stop
+            </button>
+          </div>
+        )}
+
+        {step === "done" && result && (
+          <div className="flex min-h-0 flex-1 flex-col gap-3">
+            <div className="text-sm" style={primary}>
+              Translated {result.translated.length} page
+              {result.translated.length !== 1 ? "s" : ""}.
+            </div>
+            <div className="min-h-0 flex-1 space-y-1 overflow-y-auto text-sm">
+              {result.translated.map((t) => (
+                <div key={t.pageId} className="flex items-center gap-2">
+                  <span className="min-w-0 flex-1 truncate" style={primary}>
+                    {t.title || "Untitled"}
+                  </span>
+                  <span className="text-xs" style={muted}>
+                    {t.sourceLanguage || "?"} → {t.targetLanguage}
+                  </span>
+                  {t.mode === "copy" && onOpenPage && (
+                    <button
+                      onClick={() => {
+                        onOpenPage(t.translatedPageId);
+                        onClose();
+                      }}
+                      className="text-xs hover:underline"
+                      style={{ color: "var(--color-accent)" }}
+                    >
+                      Open
+                    </button>
+                  )}
+                </div>
+              ))}
+              {result.skipped.map((s) => (
+                <div key={s.pageId} className="flex gap-2 text-xs" style={muted}>
+                  <span className="truncate">{s.title || "Untitled"}</span>
+                  <span>— {s.reason}</span>
+                </div>
+              ))}
+            </div>
+            <div className="flex justify-end">
+              <button
+                onClick={handleClose}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                Done
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
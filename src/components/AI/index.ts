@@ -1 +1,3 @@
 export { AIChatPanel } from "./AIChatPanel";
+export { AIBulkTransformDialog } from "./AIBulkTransformDialog";
+export { TranslateDialog } from "./TranslateDialog";
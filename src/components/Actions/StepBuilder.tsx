@@ -7,6 +7,7 @@ import type {
 import { STEP_TYPES } from "../../types/action";
 import { useExternalSourceStore } from "../../stores/externalSourceStore";
 import { usePluginStore } from "../../stores/pluginStore";
+import { listAiProfiles, type AiProfile } from "../../utils/api";
 
 interface StepBuilderProps {
   steps: ActionStep[];
@@ -1097,6 +1098,58 @@ function AiSummarizeEditor({
           }}
         />
       </div>
+
+      <AiProfileSelect
+        value={step.aiProfile}
+        onChange={(aiProfile) => onUpdate({ aiProfile })}
+      />
+    </div>
+  );
+}
+
+// Library AI profile for an AI step; hidden until the library has profiles
+function AiProfileSelect({
+  value,
+  onChange,
+}: {
+  value: string | undefined;
+  onChange: (profileId: string | undefined) => void;
+}) {
+  const [profiles, setProfiles] = useState<AiProfile[]>([]);
+
+  useEffect(() => {
+    listAiProfiles()
+      .then(setProfiles)
+      .catch(() => setProfiles([]));
+  }, []);
+
+  if (profiles.length === 0 && !value) return null;
+
+  return (
+    <div>
+      <label
+        className="mb-1.5 block text-xs font-medium"
+        style={{ color: "var(--color-text-secondary)" }}
+      >
+        AI Profile
+      </label>
+      <select
+        value={value || ""}
+        onChange={(e) => onChange(e.target.value || undefined)}
+        className="w-full rounded-lg border px-3 py-2 text-sm outline-none transition-colors focus:border-[--color-accent]"
+        style={{
+          backgroundColor: "var(--color-bg-tertiary)",
+          borderColor: "var(--color-border)",
+          color: "var(--color-text-primary)",
+        }}
+      >
+        <option value="">Default AI settings</option>
+        {profiles.map((profile) => (
+          <option key={profile.id} value={profile.id}>
+            {profile.name}
+          </option>
+        ))}
+      </select>
     </div>
   );
 }
@@ -1465,6 +1518,11 @@ function ProcessExternalSourceEditor({
           }}
         />
       </div>
+
+      <AiProfileSelect
+        value={step.aiProfile}
+        onChange={(aiProfile) => onUpdate({ aiProfile })}
+      />
     </div>
   );
 }
@@ -1724,6 +1782,8 @@ function createDefaultStep(type: ActionStep["type"]): ActionStep | null {
         incremental: false,
         tags: [],
       };
+    case "spacedReview":
+      return { type: "spacedReview" };
     case "plugin":
       return {
         type: "plugin",
@@ -1768,6 +1828,10 @@ function getStepSummary(step: ActionStep): string {
       return `If condition then ${step.thenSteps.length} steps`;
     case "processExternalSource":
       return `Process ${step.sourceId ? "source" : step.inlinePath || "files"} → "${step.titleTemplate}"`;
+    case "spacedReview":
+      return step.notebook
+        ? "Review old notes in one notebook"
+        : "Review old notes in notebooks with review on";
     case "plugin":
       return `Plugin ${step.pluginId || "?"} → ${step.function || "?"}()`;
     default:
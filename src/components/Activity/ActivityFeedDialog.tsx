@@ -0,0 +1,161 @@
+import { useEffect, useState } from "react";
+import { listen } from "../../platform/event";
+import { useNotebookStore } from "../../stores/notebookStore";
+import { usePageStore } from "../../stores/pageStore";
+import { useToastStore } from "../../stores/toastStore";
+import {
+  getActivityFeed,
+  getActivityLastSeen,
+  markActivitySeen,
+  type ActivityEntry,
+  type ActivityKind,
+} from "../../utils/api";
+
+interface ActivityFeedDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+const KIND_LABELS: Record<ActivityKind, string> = {
+  created: "Created",
+  edited: "Edited",
+  deleted: "Deleted",
+  imported: "Imported",
+};
+
+/**
+ * What changed since the feed was last looked at, on this device and the
+ * others syncing with it. Closing the dialog marks the feed seen.
+ */
+export function ActivityFeedDialog({ isOpen, onClose }: ActivityFeedDialogProps) {
+  const [entries, setEntries] = useState<ActivityEntry[] | null>(null);
+  const [lastSeen, setLastSeen] = useState<string | null>(null);
+  const [showAll, setShowAll] = useState(false);
+  const [otherDevicesOnly, setOtherDevicesOnly] = useState(false);
+  const notebooks = useNotebookStore((s) => s.notebooks);
+  const toast = useToastStore();
+
+  const load = async () => {
+    try {
+      const seen = await getActivityLastSeen();
+      setLastSeen(seen);
+      setEntries(
+        await getActivityFeed(showAll ? undefined : (seen ?? undefined), {
+          source: otherDevicesOnly ? "sync" : undefined,
+        })
+      );
+    } catch (err) {
+      toast.error(`${err}`);
+    }
+  };
+
+  useEffect(() => {
+    if (!isOpen) return;
+    load();
+    const unlisten = listen("activity-recorded", () => load());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen, showAll, otherDevicesOnly]);
+
+  const handleClose = () => {
+    markActivitySeen().catch(() => {});
+    setShowAll(false);
+    onClose();
+  };
+
+  const openPage = async (entry: ActivityEntry) => {
+    if (entry.kind === "deleted") return;
+    useNotebookStore.getState().selectNotebook(entry.notebookId);
+    await usePageStore.getState().selectPage(entry.pageId);
+    handleClose();
+  };
+
+  if (!isOpen) return null;
+
+  const notebookName = (id: string) => notebooks.find((n) => n.id === id)?.name ?? "Unknown notebook";
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center"
+      style={{ backgroundColor: "rgba(0, 0, 0, 0.5)" }}
+      onClick={(e) => {
+        if (e.target === e.currentTarget) handleClose();
+      }}
+      onKeyDown={(e) => {
+        if (e.key === "Escape") handleClose();
+      }}
+    >
+      <div
+        className="flex max-h-[80vh] w-full max-w-lg flex-col rounded-lg border shadow-xl"
+        style={{
+          backgroundColor: "var(--color-bg-primary)",
+          borderColor: "var(--color-border)",
+        }}
+      >
+        <div
+          className="flex items-center justify-between border-b px-5 py-4"
+          style={{ borderColor: "var(--color-border)" }}
+        >
+          <div>
+            <h2 className="text-base font-semibold" style={{ color: "var(--color-text-primary)" }}>
+              {showAll ? "Recent activity" : "What changed"}
+            </h2>
+            {!showAll && lastSeen && (
+              <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+                Since {new Date(lastSeen).toLocaleString()}
+              </p>
+            )}
+          </div>
+          <div className="flex items-center gap-3 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+            <label className="flex items-center gap-1">
+              <input
+                type="checkbox"
+                checked={otherDevicesOnly}
+                onChange={(e) => setOtherDevicesOnly(e.target.checked)}
+              />
+              Other devices
+            </label>
+            <label className="flex items-center gap-1">
+              <input
+                type="checkbox"
+                checked={showAll}
+                onChange={(e) => setShowAll(e.target.checked)}
+              />
+              Everything
+            </label>
+          </div>
+        </div>
+        <div className="flex-1 space-y-1 overflow-y-auto px-5 py-4">
+          {entries?.length === 0 && (
+            <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+              Nothing has changed.
+            </p>
+          )}
+          {entries?.map((entry) => (
+            <button
+              key={`${entry.pageId}-${entry.timestamp}-${entry.device}`}
+              onClick={() => openPage(entry)}
+              disabled={entry.kind === "deleted"}
+              className="flex w-full items-center justify-between rounded-md px-2.5 py-1.5 text-left text-xs"
+              style={{ backgroundColor: "var(--color-bg-tertiary)" }}
+            >
+              <div className="min-w-0">
+                <div className="truncate font-medium" style={{ color: "var(--color-text-primary)" }}>
+                  {entry.pageTitle}
+                </div>
+                <div style={{ color: "var(--color-text-muted)" }}>
+                  {KIND_LABELS[entry.kind]} on {entry.device} · {notebookName(entry.notebookId)}
+                </div>
+              </div>
+              <span className="ml-3 shrink-0" style={{ color: "var(--color-text-muted)" }}>
+                {new Date(entry.timestamp).toLocaleString()}
+              </span>
+            </button>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1 @@
+export { ActivityFeedDialog } from "./ActivityFeedDialog";
@@ -0,0 +1,380 @@
+import { useState, useEffect } from "react";
+import type { MeetingNotesResult, MeetingProgress, MeetingStage } from "../../types/audio";
+import { useFocusTrap } from "../../hooks/useFocusTrap";
+import { useAIStore } from "../../stores/aiStore";
+import { useContactStore } from "../../stores/contactStore";
+import { usePageStore } from "../../stores/pageStore";
+import { useTasksStore } from "../../stores/tasksStore";
+import { useToastStore } from "../../stores/toastStore";
+import { open } from "../../platform/dialog";
+import { listen, type UnlistenFn } from "../../platform/event";
+import { cancelAiTask } from "../../utils/api";
+import { processMeetingRecording } from "../../utils/audioApi";
+
+const AUDIO_EXTENSIONS = ["mp3", "m4a", "wav", "ogg", "webm", "flac", "aac", "mp4"];
+
+const STAGE_LABELS: Record<MeetingStage, string> = {
+  transcribing: "Transcribing the recording...",
+  identifyingSpeakers: "Identifying speakers...",
+  summarizing: "Summarizing and finding action items...",
+  creatingPage: "Creating the meeting page...",
+};
+
+interface MeetingNotesDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+  notebookId: string;
+}
+
+function errorMessage(err: unknown): string {
+  return err instanceof Error ? err.message : String((err as { message?: string }).message ?? err);
+}
+
+export function MeetingNotesDialog({ isOpen, onClose, notebookId }: MeetingNotesDialogProps) {
+  const contacts = useContactStore((s) => s.contacts);
+  const [audioPath, setAudioPath] = useState<string | null>(null);
+  const [title, setTitle] = useState("");
+  const [attendeeIds, setAttendeeIds] = useState<string[]>([]);
+  const [attendeeFilter, setAttendeeFilter] = useState("");
+  const [linkDailyNote, setLinkDailyNote] = useState(true);
+  const [createTasks, setCreateTasks] = useState(true);
+  const [taskId, setTaskId] = useState<string | null>(null);
+  const [stage, setStage] = useState<MeetingStage | null>(null);
+  const [result, setResult] = useState<MeetingNotesResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const focusTrapRef = useFocusTrap(isOpen);
+
+  useEffect(() => {
+    if (isOpen) {
+      setAudioPath(null);
+      setTitle("");
+      setAttendeeIds([]);
+      setAttendeeFilter("");
+      setStage(null);
+      setResult(null);
+      setError(null);
+      useContactStore.getState().loadContacts();
+    }
+  }, [isOpen]);
+
+  useEffect(() => {
+    if (!taskId) return;
+    let unlisten: UnlistenFn | undefined;
+    listen<MeetingProgress>("meeting-progress", (event) => {
+      if (event.payload.taskId === taskId) {
+        setStage(event.payload.stage);
+      }
+    }).then((fn) => {
+      unlisten = fn;
+    });
+    return () => unlisten?.();
+  }, [taskId]);
+
+  if (!isOpen) return null;
+
+  const running = taskId !== null;
+
+  const handleClose = () => {
+    if (taskId) {
+      cancelAiTask(taskId);
+    }
+    onClose();
+  };
+
+  const handlePickFile = async () => {
+    const selected = await open({
+      multiple: false,
+      filters: [{ name: "Audio", extensions: AUDIO_EXTENSIONS }],
+    });
+    if (typeof selected === "string") {
+      setAudioPath(selected);
+    }
+  };
+
+  const toggleAttendee = (id: string) =>
+    setAttendeeIds((prev) => (prev.includes(id) ? prev.filter((a) => a !== id) : [...prev, id]));
+
+  const handleProcess = async () => {
+    if (!audioPath) return;
+    const id = crypto.randomUUID();
+    const ai = useAIStore.getState();
+    setTaskId(id);
+    setStage(null);
+    setError(null);
+    try {
+      const processed = await processMeetingRecording(notebookId, audioPath, {
+        attendeeIds,
+        title: title.trim() || undefined,
+        linkDailyNote,
+        providerType: ai.getActiveProviderType(),
+        apiKey: ai.getActiveApiKey() || undefined,
+        model: ai.getActiveModel(),
+        taskId: id,
+      });
+      // Tasks live in the task list, not in the library, so they're added here
+      if (createTasks) {
+        const { createTask } = useTasksStore.getState();
+        for (const item of processed.actionItems) {
+          createTask({
+            title: item.text,
+            description: [
+              `From meeting "${processed.page.title}"`,
+              item.assignee ? `Assignee: ${item.assignee}` : null,
+            ]
+              .filter(Boolean)
+              .join("\n"),
+            priority: "medium",
+            dueDate: item.dueDate ?? undefined,
+            tags: ["meeting"],
+          });
+        }
+      }
+      setResult(processed);
+      await usePageStore.getState().loadPages(notebookId);
+      useToastStore.getState().success(`Created meeting notes "${processed.page.title}"`);
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setTaskId(null);
+    }
+  };
+
+  const handleOpenPage = () => {
+    if (!result) return;
+    usePageStore.getState().selectPage(result.page.id);
+    onClose();
+  };
+
+  const muted = { color: "var(--color-text-muted)" };
+  const primary = { color: "var(--color-text-primary)" };
+  const inputStyle = {
+    backgroundColor: "var(--color-bg-primary)",
+    borderColor: "var(--color-border)",
+    color: "var(--color-text-primary)",
+  };
+  const filter = attendeeFilter.trim().toLowerCase();
+  const visibleContacts = contacts.filter(
+    (c) => !filter || c.name.toLowerCase().includes(filter) || attendeeIds.includes(c.id)
+  );
+  const fileName = audioPath?.split(/[\\/]/).pop();
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={(e) => {
+        if (e.target === e.currentTarget && !running) handleClose();
+      }}
+      role="presentation"
+    >
+      <div
+        ref={focusTrapRef}
+        role="dialog"
+        aria-modal="true"
+        aria-labelledby="meeting-notes-title"
+        className="w-full max-w-lg rounded-xl border p-6 shadow-2xl"
+        style={{
+          backgroundColor: "var(--color-bg-secondary)",
+          borderColor: "var(--color-border)",
+          maxHeight: "80vh",
+          display: "flex",
+          flexDirection: "column",
+        }}
+      >
+        <h2 id="meeting-notes-title" className="mb-4 text-lg font-semibold" style={primary}>
+          Meeting Notes from a Recording
+        </h2>
+
+        {error && (
+          <div
+            className="mb-3 rounded-lg px-3 py-2 text-sm"
+            style={{ backgroundColor: "rgba(239, 68, 68, 0.1)", color: "#ef4444" }}
+          >
+            {error}
+          </div>
+        )}
+
+        {!running && !result && (
+          <div className="flex min-h-0 flex-col gap-4">
+            <div className="flex items-center gap-2">
+              <button
+                onClick={handlePickFile}
+                className="rounded-lg px-3 py-2 text-sm"
+                style={{
+                  backgroundColor: "var(--color-bg-tertiary)",
+                  color: "var(--color-text-secondary)",
+                }}
+              >
+                Choose Recording...
+              </button>
+              <span className="min-w-0 flex-1 truncate text-sm" style={fileName ? primary : muted}>
+                {fileName ?? "No file chosen"}
+              </span>
+            </div>
+
+            <input
+              type="text"
+              value={title}
+              onChange={(e) => setTitle(e.target.value)}
+              placeholder="Title (suggested by the AI if empty)"
+              className="rounded-lg border px-3 py-2 text-sm"
+              style={inputStyle}
+            />
+
+            <div className="flex min-h-0 flex-col gap-2">
+              <div className="text-sm font-medium" style={primary}>
+                Attendees
+                {attendeeIds.length > 0 && (
+                  <span className="ml-2 text-xs font-normal" style={muted}>
+                    {attendeeIds.length} selected
+                  </span>
+                )}
+              </div>
+              {contacts.length === 0 ? (
+                <p className="text-xs" style={muted}>
+                  No contacts yet. Attendees are picked from People.
+                </p>
+              ) : (
+                <>
+                  <input
+                    type="text"
+                    value={attendeeFilter}
+                    onChange={(e) => setAttendeeFilter(e.target.value)}
+                    placeholder="Filter contacts"
+                    className="rounded-lg border px-3 py-1.5 text-sm"
+                    style={inputStyle}
+                  />
+                  <div
+                    className="max-h-40 overflow-y-auto rounded-lg border"
+                    style={{ borderColor: "var(--color-border)" }}
+                  >
+                    {visibleContacts.map((contact) => (
+                      <label
+                        key={contact.id}
+                        className="flex cursor-pointer items-center gap-2 px-3 py-1.5 text-sm"
+                        style={primary}
+                      >
+                        <input
+                          type="checkbox"
+                          checked={attendeeIds.includes(contact.id)}
+                          onChange={() => toggleAttendee(contact.id)}
+                        />
+                        <span className="truncate">{contact.name}</span>
+                      </label>
+                    ))}
+                  </div>
+                </>
+              )}
+            </div>
+
+            <div className="space-y-2 text-sm" style={primary}>
+              <label className="flex cursor-pointer items-center gap-2">
+                <input
+                  type="checkbox"
+                  checked={linkDailyNote}
+                  onChange={(e) => setLinkDailyNote(e.target.checked)}
+                />
+                Link from the daily note of the recording's day
+              </label>
+              <label className="flex cursor-pointer items-center gap-2">
+                <input
+                  type="checkbox"
+                  checked={createTasks}
+                  onChange={(e) => setCreateTasks(e.target.checked)}
+                />
+                Add action items to Tasks
+              </label>
+            </div>
+
+            <p className="text-xs" style={muted}>
+              The transcript is split by speaker when pyannote.audio is installed and a
+              Hugging Face token is set (HF_TOKEN).
+            </p>
+
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={handleClose}
+                className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Cancel
+              </button>
+              <button
+                onClick={handleProcess}
+                disabled={!audioPath}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                Process
+              </button>
+            </div>
+          </div>
+        )}
+
+        {running && (
+          <div className="flex flex-col items-center gap-4 py-8">
+            <div className="text-sm" style={muted}>
+              {stage ? STAGE_LABELS[stage] : "Starting..."}
+            </div>
+            <button
+              onClick={() => taskId && cancelAiTask(taskId)}
+              className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              Stop
+            </button>
+          </div>
+        )}
+
+        {result && (
+          <div className="flex min-h-0 flex-col gap-3">
+            <p className="text-sm" style={primary}>
+              Created <strong>{result.page.title}</strong>
+              {result.dailyNoteId && ", linked from the daily note"}.
+            </p>
+            {!result.speakersIdentified && (
+              <p className="text-xs" style={muted}>
+                Speakers weren't identified; the transcript isn't split by speaker.
+              </p>
+            )}
+            {result.actionItems.length > 0 && (
+              <div className="min-h-0 overflow-y-auto">
+                <div className="mb-1 text-sm font-medium" style={primary}>
+                  Action items{createTasks && " (added to Tasks)"}
+                </div>
+                <ul className="list-disc space-y-1 pl-5 text-sm" style={primary}>
+                  {result.actionItems.map((item, i) => (
+                    <li key={i}>
+                      {item.text}
+                      {(item.assignee || item.dueDate) && (
+                        <span className="ml-1 text-xs" style={muted}>
+                          {[item.assignee, item.dueDate].filter(Boolean).join(" · ")}
+                        </span>
+                      )}
+                    </li>
+                  ))}
+                </ul>
+              </div>
+            )}
+            <div className="flex justify-end gap-3">
+              <button
+                onClick={onClose}
+                className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Close
+              </button>
+              <button
+                onClick={handleOpenPage}
+                className="rounded-lg px-4 py-2 text-sm font-medium text-white"
+                style={{ backgroundColor: "var(--color-accent)" }}
+              >
+                Open Page
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,410 @@
+import { useState, useEffect, useRef, useCallback } from "react";
+import type { Page } from "../../types/page";
+import type { ReadingQueue } from "../../types/audio";
+import { useFocusTrap } from "../../hooks/useFocusTrap";
+import { useAudioStore } from "../../stores/audioStore";
+import { useToastStore } from "../../stores/toastStore";
+import { convertFileSrc } from "../../platform/core";
+import {
+  createReadingQueue,
+  deleteReadingQueue,
+  generateReadingQueueAudio,
+  listReadingQueues,
+  setReadingPosition,
+  updateReadingQueue,
+} from "../../utils/audioApi";
+
+/** How often the playback position is saved while playing */
+const POSITION_SAVE_MS = 10_000;
+
+interface ReadingQueueDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+  notebookId: string;
+  /** Pages that can be queued */
+  pages: Page[];
+}
+
+function formatTime(seconds: number): string {
+  const s = Math.floor(seconds);
+  const h = Math.floor(s / 3600);
+  const m = Math.floor((s % 3600) / 60);
+  const rest = String(s % 60).padStart(2, "0");
+  return h > 0 ? `${h}:${String(m).padStart(2, "0")}:${rest}` : `${m}:${rest}`;
+}
+
+function errorMessage(err: unknown): string {
+  return err instanceof Error ? err.message : String((err as { message?: string }).message ?? err);
+}
+
+export function ReadingQueueDialog({ isOpen, onClose, notebookId, pages }: ReadingQueueDialogProps) {
+  const [queues, setQueues] = useState<ReadingQueue[]>([]);
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [newName, setNewName] = useState("");
+  const [addPageId, setAddPageId] = useState("");
+  const [busy, setBusy] = useState<"generate" | "save" | null>(null);
+  const audioRef = useRef<HTMLAudioElement | null>(null);
+  const lastSavedRef = useRef(0);
+
+  const focusTrapRef = useFocusTrap(isOpen);
+  const selected = queues.find((q) => q.id === selectedId) ?? null;
+  const pageTitle = (id: string) => pages.find((p) => p.id === id)?.title || "Untitled";
+
+  const load = useCallback(async () => {
+    try {
+      const list = await listReadingQueues(notebookId);
+      setQueues(list);
+      setSelectedId((prev) => prev ?? list[0]?.id ?? null);
+    } catch (err) {
+      useToastStore.getState().error(`Failed to load reading queues: ${errorMessage(err)}`);
+    }
+  }, [notebookId]);
+
+  useEffect(() => {
+    if (isOpen) {
+      setSelectedId(null);
+      load();
+      useAudioStore.getState().loadProviders();
+    }
+  }, [isOpen, load]);
+
+  const replaceQueue = (queue: ReadingQueue) =>
+    setQueues((prev) => prev.map((q) => (q.id === queue.id ? queue : q)));
+
+  const savePosition = useCallback(
+    (force = false) => {
+      const audio = audioRef.current;
+      if (!audio || !selected?.audio) return;
+      const now = Date.now();
+      if (!force && now - lastSavedRef.current < POSITION_SAVE_MS) return;
+      lastSavedRef.current = now;
+      setReadingPosition(selected.id, audio.currentTime).catch((err) =>
+        console.warn("Failed to save reading position:", err)
+      );
+    },
+    [selected]
+  );
+
+  // Save where the user stopped when switching queues or closing
+  useEffect(() => () => savePosition(true), [savePosition]);
+
+  if (!isOpen) return null;
+
+  const handleCreate = async () => {
+    if (!newName.trim()) return;
+    try {
+      const queue = await createReadingQueue(notebookId, newName.trim(), []);
+      setQueues((prev) => [...prev, queue]);
+      setSelectedId(queue.id);
+      setNewName("");
+    } catch (err) {
+      useToastStore.getState().error(`Failed to create queue: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleSetPages = async (pageIds: string[]) => {
+    if (!selected) return;
+    setBusy("save");
+    try {
+      replaceQueue(await updateReadingQueue(selected.id, { pageIds }));
+    } catch (err) {
+      useToastStore.getState().error(`Failed to update queue: ${errorMessage(err)}`);
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const movePage = (index: number, delta: number) => {
+    if (!selected) return;
+    const ids = [...selected.pageIds];
+    const target = index + delta;
+    if (target < 0 || target >= ids.length) return;
+    [ids[index], ids[target]] = [ids[target], ids[index]];
+    handleSetPages(ids);
+  };
+
+  const handleGenerate = async () => {
+    if (!selected) return;
+    const { settings } = useAudioStore.getState();
+    setBusy("generate");
+    try {
+      const queue = await generateReadingQueueAudio(selected.id, {
+        provider: settings.ttsProvider,
+        voice: settings.ttsVoice,
+        apiKey: settings.ttsApiKey || null,
+        baseUrl: settings.ttsBaseUrl || null,
+        model: settings.ttsModel || null,
+        speed: settings.ttsSpeed,
+      });
+      replaceQueue(queue);
+      useToastStore.getState().success(`Narrated "${queue.name}"`);
+    } catch (err) {
+      useToastStore.getState().error(`Narration failed: ${errorMessage(err)}`);
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleDelete = async () => {
+    if (!selected || !window.confirm(`Delete reading queue "${selected.name}" and its audio?`)) {
+      return;
+    }
+    try {
+      await deleteReadingQueue(selected.id);
+      setQueues((prev) => prev.filter((q) => q.id !== selected.id));
+      setSelectedId(null);
+    } catch (err) {
+      useToastStore.getState().error(`Failed to delete queue: ${errorMessage(err)}`);
+    }
+  };
+
+  const seek = (seconds: number) => {
+    const audio = audioRef.current;
+    if (!audio) return;
+    audio.currentTime = seconds;
+    audio.play();
+  };
+
+  const muted = { color: "var(--color-text-muted)" };
+  const primary = { color: "var(--color-text-primary)" };
+  const inputStyle = {
+    backgroundColor: "var(--color-bg-primary)",
+    borderColor: "var(--color-border)",
+    color: "var(--color-text-primary)",
+  };
+  const available = pages.filter((p) => !selected?.pageIds.includes(p.id));
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={(e) => {
+        if (e.target === e.currentTarget) onClose();
+      }}
+      role="presentation"
+    >
+      <div
+        ref={focusTrapRef}
+        role="dialog"
+        aria-modal="true"
+        aria-labelledby="reading-queue-title"
+        className="flex w-full max-w-3xl gap-4 rounded-xl border p-6 shadow-2xl"
+        style={{
+          backgroundColor: "var(--color-bg-secondary)",
+          borderColor: "var(--color-border)",
+          height: "70vh",
+        }}
+      >
+        <div className="flex w-56 shrink-0 flex-col gap-3">
+          <h2 id="reading-queue-title" className="text-lg font-semibold" style={primary}>
+            Reading Queues
+          </h2>
+          <div className="min-h-0 flex-1 space-y-1 overflow-y-auto">
+            {queues.length === 0 && (
+              <p className="text-sm" style={muted}>
+                No queues yet.
+              </p>
+            )}
+            {queues.map((queue) => (
+              <button
+                key={queue.id}
+                onClick={() => setSelectedId(queue.id)}
+                className="w-full truncate rounded-lg px-3 py-2 text-left text-sm"
+                style={{
+                  ...primary,
+                  backgroundColor:
+                    queue.id === selectedId ? "var(--color-bg-tertiary)" : "transparent",
+                }}
+              >
+                {queue.name}
+              </button>
+            ))}
+          </div>
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={newName}
+              onChange={(e) => setNewName(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && handleCreate()}
+              placeholder="New queue"
+              className="min-w-0 flex-1 rounded-lg border px-2 py-1.5 text-sm"
+              style={inputStyle}
+            />
+            <button
+              onClick={handleCreate}
+              disabled={!newName.trim()}
+              className="rounded-lg px-3 text-sm font-medium text-white disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)" }}
+            >
+              Add
+            </button>
+          </div>
+        </div>
+
+        <div className="flex min-w-0 flex-1 flex-col gap-3">
+          {!selected ? (
+            <p className="text-sm" style={muted}>
+              Create a queue, add pages in the order to hear them, then narrate it into one
+              audio file with a chapter per page.
+            </p>
+          ) : (
+            <>
+              <div className="flex items-center justify-between">
+                <h3 className="truncate font-medium" style={primary}>
+                  {selected.name}
+                </h3>
+                <button
+                  onClick={handleDelete}
+                  className="text-xs hover:underline"
+                  style={{ color: "var(--color-error, #ef4444)" }}
+                >
+                  Delete
+                </button>
+              </div>
+
+              <div
+                className="min-h-0 flex-1 overflow-y-auto rounded-lg border"
+                style={{ borderColor: "var(--color-border)" }}
+              >
+                {selected.pageIds.map((pageId, index) => {
+                  const chapter = selected.audioCurrent
+                    ? selected.audio?.chapters.find((c) => c.pageId === pageId)
+                    : undefined;
+                  return (
+                    <div
+                      key={pageId}
+                      className="flex items-center gap-2 px-3 py-1.5 text-sm"
+                      style={primary}
+                    >
+                      <span className="w-5 text-xs" style={muted}>
+                        {index + 1}
+                      </span>
+                      {chapter ? (
+                        <button
+                          onClick={() => seek(chapter.startSeconds)}
+                          className="min-w-0 flex-1 truncate text-left hover:underline"
+                        >
+                          {pageTitle(pageId)}
+                          <span className="ml-2 text-xs" style={muted}>
+                            {formatTime(chapter.startSeconds)}
+                          </span>
+                        </button>
+                      ) : (
+                        <span className="min-w-0 flex-1 truncate">{pageTitle(pageId)}</span>
+                      )}
+                      <button
+                        onClick={() => movePage(index, -1)}
+                        disabled={busy !== null || index === 0}
+                        className="px-1 disabled:opacity-30"
+                        style={muted}
+                        aria-label="Move up"
+                      >
+                        ↑
+                      </button>
+                      <button
+                        onClick={() => movePage(index, 1)}
+                        disabled={busy !== null || index === selected.pageIds.length - 1}
+                        className="px-1 disabled:opacity-30"
+                        style={muted}
+                        aria-label="Move down"
+                      >
+                        ↓
+                      </button>
+                      <button
+                        onClick={() =>
+                          handleSetPages(selected.pageIds.filter((id) => id !== pageId))
+                        }
+                        disabled={busy !== null}
+                        className="px-1 disabled:opacity-30"
+                        style={muted}
+                        aria-label="Remove"
+                      >
+                        ×
+                      </button>
+                    </div>
+                  );
+                })}
+              </div>
+
+              <div className="flex gap-2">
+                <select
+                  value={addPageId}
+                  onChange={(e) => setAddPageId(e.target.value)}
+                  className="min-w-0 flex-1 rounded-lg border px-2 py-1.5 text-sm"
+                  style={inputStyle}
+                >
+                  <option value="">Add a page...</option>
+                  {available.map((page) => (
+                    <option key={page.id} value={page.id}>
+                      {page.title || "Untitled"}
+                    </option>
+                  ))}
+                </select>
+                <button
+                  onClick={() => {
+                    if (!addPageId) return;
+                    handleSetPages([...selected.pageIds, addPageId]);
+                    setAddPageId("");
+                  }}
+                  disabled={!addPageId || busy !== null}
+                  className="rounded-lg px-3 text-sm disabled:opacity-50"
+                  style={{
+                    backgroundColor: "var(--color-bg-tertiary)",
+                    color: "var(--color-text-secondary)",
+                  }}
+                >
+                  Add
+                </button>
+              </div>
+
+              {selected.audio && selected.audioPath && (
+                <div className="space-y-1">
+                  <audio
+                    key={selected.audio.generatedAt}
+                    ref={audioRef}
+                    controls
+                    className="w-full"
+                    src={`${convertFileSrc(selected.audioPath)}?v=${encodeURIComponent(selected.audio.generatedAt)}`}
+                    onLoadedMetadata={(e) => {
+                      if (selected.position) {
+                        e.currentTarget.currentTime = selected.position.positionSeconds;
+                      }
+                    }}
+                    onTimeUpdate={() => savePosition()}
+                    onPause={() => savePosition(true)}
+                  />
+                  <div className="text-xs" style={muted}>
+                    {formatTime(selected.audio.durationSeconds)} ·{" "}
+                    {selected.audio.chapters.length} chapters
+                    {!selected.audioCurrent && " · pages changed since narrating"}
+                  </div>
+                </div>
+              )}
+
+              <div className="flex justify-end gap-3">
+                <button
+                  onClick={onClose}
+                  className="rounded-lg px-4 py-2 text-sm hover:bg-[--color-bg-tertiary]"
+                  style={{ color: "var(--color-text-secondary)" }}
+                >
+                  Close
+                </button>
+                <button
+                  onClick={handleGenerate}
+                  disabled={busy !== null || selected.pageIds.length === 0}
+                  className="rounded-lg px-4 py-2 text-sm font-medium text-white disabled:opacity-50"
+                  style={{ backgroundColor: "var(--color-accent)" }}
+                >
+                  {busy === "generate"
+                    ? "Narrating..."
+                    : selected.audio
+                      ? "Regenerate Audio"
+                      : "Generate Audio"}
+                </button>
+              </div>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1 +1,3 @@
 export { AudioGenerateDialog } from "./AudioGenerateDialog";
+export { ReadingQueueDialog } from "./ReadingQueueDialog";
+export { MeetingNotesDialog } from "./MeetingNotesDialog";
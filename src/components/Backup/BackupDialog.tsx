@@ -110,17 +110,21 @@ export function BackupDialog({ isOpen, onClose }: BackupDialogProps) {
     }
   }, [isOpen, activeTab]);
 
-  // Listen for import progress events
+  // Listen for import and export progress events
   useEffect(() => {
-    let unlisten: UnlistenFn | null = null;
+    const unlisteners: UnlistenFn[] = [];
 
     const setupListener = async () => {
-      unlisten = await listen<{ current: number; total: number; message: string }>(
-        "import-progress",
-        (event) => {
-          setImportProgress(event.payload);
-        }
-      );
+      for (const name of ["import-progress", "notebook-export-progress"]) {
+        unlisteners.push(
+          await listen<{ current: number; total: number; message: string }>(
+            name,
+            (event) => {
+              setImportProgress(event.payload);
+            }
+          )
+        );
+      }
     };
 
     if (isOpen) {
@@ -128,9 +132,7 @@ export function BackupDialog({ isOpen, onClose }: BackupDialogProps) {
     }
 
     return () => {
-      if (unlisten) {
-        unlisten();
-      }
+      unlisteners.forEach((unlisten) => unlisten());
       setImportProgress(null);
     };
   }, [isOpen]);
@@ -171,6 +173,7 @@ export function BackupDialog({ isOpen, onClose }: BackupDialogProps) {
       toast.error(message);
     } finally {
       setIsLoading(false);
+      setImportProgress(null);
     }
   };
 
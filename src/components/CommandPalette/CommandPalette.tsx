@@ -15,13 +15,14 @@ import { useRAGStore } from "../../stores/ragStore";
 import { useSectionStore } from "../../stores/sectionStore";
 import { useThemeStore } from "../../stores/themeStore";
 import { useToastStore } from "../../stores/toastStore";
-import { searchPages, exportPageToFile, importMarkdownFile, convertDocument, importMarkdown } from "../../utils/api";
+import { searchPages, getSearchSuggestions, exportPageToFile, importMarkdownFile, convertDocument, importMarkdown, getPageDeepLink } from "../../utils/api";
 import { DAEMON_BASE_URL, daemonPost } from "../../utils/daemon";
 import { save, open } from "../../platform/dialog";
 import { highlightText } from "../../utils/highlightText";
 import { isTauri } from "../../utils/platform";
 import { rankCommands, rankSearchResults, isCommandVisible } from "./rankCommands";
 import type { SearchResult, PageType } from "../../types/page";
+import type { SearchSuggestion } from "../../utils/api";
 
 // Backend search scores are coarse (daemon: 1.0 title hit / 0.5 content-only;
 // RAG: 0–1). Keep the floor low so it only trims a near-zero semantic tail and
@@ -34,7 +35,7 @@ interface Command {
   subtitle?: string;
   snippet?: string;
   icon: React.ReactNode;
-  category: "page" | "action" | "notebook" | "search" | "recent" | "recent-page" | "automation";
+  category: "page" | "action" | "notebook" | "search" | "recent" | "suggestion" | "recent-page" | "automation";
   action: () => void;
   keywords?: string[];
   score?: number;
@@ -48,6 +49,8 @@ interface CommandPaletteProps {
   onOpenGraph: () => void;
   onNewPage?: () => void;
   onOpenBackup?: () => void;
+  /** Pre-filled search query (e.g. from a nous://search link) */
+  initialQuery?: string;
 }
 
 export function CommandPalette({
@@ -56,11 +59,13 @@ export function CommandPalette({
   onOpenGraph,
   onNewPage,
   onOpenBackup,
+  initialQuery,
 }: CommandPaletteProps) {
   const [query, setQuery] = useState("");
   const [selectedIndex, setSelectedIndex] = useState(0);
   const [searchResults, setSearchResults] = useState<SearchResult[]>([]);
   const [isSearching, setIsSearching] = useState(false);
+  const [suggestions, setSuggestions] = useState<SearchSuggestion[]>([]);
   const inputRef = useRef<HTMLInputElement>(null);
   const listRef = useRef<HTMLDivElement>(null);
   const searchTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
@@ -68,7 +73,7 @@ export function CommandPalette({
   const { notebooks, selectedNotebookId, selectNotebook, createNotebook } =
     useNotebookStore();
   const { pages, selectPage, createPage, getRecentPages } = usePageStore();
-  const { recentSearches, searchScope, searchMode, addRecentSearch, setSearchScope, setSearchMode, clearRecentSearches } =
+  const { recentSearches, searchScope, searchMode, loadRecentSearches, addRecentSearch, setSearchScope, setSearchMode, clearRecentSearches } =
     useSearchStore();
   const { actions, runAction: executeAction, openActionLibrary } = useActionStore();
   const { commands: pluginCommands, fetchCommands: fetchPluginCommands, executeCommand: executePluginCommand, exportFormats: pluginExportFormats, fetchExportFormats: fetchPluginExportFormats, executeExport: executePluginExport, importFormats: pluginImportFormats, fetchImportFormats: fetchPluginImportFormats, executeImport: executePluginImport } = usePluginStore();
@@ -84,6 +89,34 @@ export function CommandPalette({
     }
   }, [isOpen, fetchPluginCommands, fetchPluginExportFormats, fetchPluginImportFormats]);
 
+  // Search history is per library, so reload it whenever the palette opens
+  useEffect(() => {
+    if (isOpen) {
+      loadRecentSearches();
+    }
+  }, [isOpen, loadRecentSearches]);
+
+  // Query completions from past searches, page titles and tags (desktop only)
+  useEffect(() => {
+    if (!isTauri() || !query.trim()) {
+      setSuggestions([]);
+      return;
+    }
+    let cancelled = false;
+    const timeout = setTimeout(async () => {
+      try {
+        const result = await getSearchSuggestions(query, 5);
+        if (!cancelled) setSuggestions(result);
+      } catch (error) {
+        console.error("Suggestion error:", error);
+      }
+    }, 100);
+    return () => {
+      cancelled = true;
+      clearTimeout(timeout);
+    };
+  }, [query]);
+
   // Debounced search
   useEffect(() => {
     if (searchTimeoutRef.current) {
@@ -342,6 +375,49 @@ export function CommandPalette({
       expert: true,
     });
 
+    cmds.push({
+      id: "action-audio-feed",
+      desktopOnly: true,
+      title: "Publish Podcast Feed",
+      subtitle: "Subscribe to generated audio in a podcast app",
+      icon: <IconPublish />,
+      category: "action",
+      action: () => {
+        window.dispatchEvent(new CustomEvent("open-audio-feed-dialog"));
+        onClose();
+      },
+      keywords: ["podcast", "rss", "feed", "audio", "narration", "subscribe"],
+      expert: true,
+    });
+
+    cmds.push({
+      id: "action-scratchpad",
+      desktopOnly: true,
+      title: "Open Scratchpad",
+      subtitle: "Move snippets between devices without making a page",
+      icon: <IconDocument />,
+      category: "action",
+      action: () => {
+        window.dispatchEvent(new CustomEvent("open-scratchpad"));
+        onClose();
+      },
+      keywords: ["scratchpad", "clipboard", "handoff", "snippet", "devices", "paste"],
+    });
+
+    cmds.push({
+      id: "action-activity-feed",
+      desktopOnly: true,
+      title: "Show Activity",
+      subtitle: "What changed since you were last here, on every device",
+      icon: <IconDocument />,
+      category: "action",
+      action: () => {
+        window.dispatchEvent(new CustomEvent("open-activity-feed"));
+        onClose();
+      },
+      keywords: ["activity", "changes", "recent", "devices", "sync", "feed"],
+    });
+
     cmds.push({
       id: "action-share-link",
       desktopOnly: true,
@@ -367,6 +443,36 @@ export function CommandPalette({
       expert: true,
     });
 
+    cmds.push({
+      id: "action-copy-deep-link",
+      desktopOnly: true,
+      title: "Copy Link to Page",
+      subtitle: "Copy a nous:// link that opens this page",
+      icon: (
+        <svg width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+          <path d="M10 13a5 5 0 0 0 7.54.54l3-3a5 5 0 0 0-7.07-7.07l-1.72 1.71" />
+          <path d="M14 11a5 5 0 0 0-7.54-.54l-3 3a5 5 0 0 0 7.07 7.07l1.71-1.71" />
+        </svg>
+      ),
+      category: "action",
+      action: async () => {
+        const pageId = usePageStore.getState().selectedPageId;
+        if (selectedNotebookId && pageId) {
+          try {
+            const link = await getPageDeepLink(selectedNotebookId, pageId);
+            await navigator.clipboard.writeText(link);
+            useToastStore.getState().success("Link copied");
+          } catch (err) {
+            useToastStore.getState().error(
+              `Failed to copy link: ${err instanceof Error ? err.message : String(err)}`
+            );
+          }
+        }
+        onClose();
+      },
+      keywords: ["copy", "link", "deep link", "nous://", "url"],
+    });
+
     cmds.push({
       id: "action-collab",
       desktopOnly: true,
@@ -657,6 +763,63 @@ export function CommandPalette({
         keywords: ["organize", "sort", "move", "ai", "classify", "smart"],
         expert: true,
       });
+
+      cmds.push({
+        id: "action-ai-bulk-edit",
+      desktopOnly: true,
+        title: "AI Bulk Edit",
+        subtitle: "Retitle, summarize, translate or rewrite many pages",
+        icon: <IconWand />,
+        category: "action",
+        action: () => {
+          onClose();
+          window.dispatchEvent(new CustomEvent("ai-bulk-transform-open"));
+        },
+        keywords: ["ai", "bulk", "batch", "retitle", "summarize", "translate", "rewrite", "format"],
+        expert: true,
+      });
+
+      cmds.push({
+        id: "action-translate",
+      desktopOnly: true,
+        title: "Translate Page or Folder",
+        subtitle: "Translate into another language, in place or as a linked copy",
+        icon: <IconWand />,
+        category: "action",
+        action: () => {
+          onClose();
+          window.dispatchEvent(new CustomEvent("translate-open"));
+        },
+        keywords: ["ai", "translate", "translation", "language"],
+      });
+
+      cmds.push({
+        id: "action-reading-queue",
+      desktopOnly: true,
+        title: "Reading Queues",
+        subtitle: "Narrate several pages into one audio file with chapters",
+        icon: <IconBook />,
+        category: "action",
+        action: () => {
+          onClose();
+          window.dispatchEvent(new CustomEvent("reading-queue-open"));
+        },
+        keywords: ["audio", "tts", "speech", "listen", "narrate", "queue", "podcast", "chapters"],
+      });
+
+      cmds.push({
+        id: "action-meeting-notes",
+      desktopOnly: true,
+        title: "Process Meeting Recording",
+        subtitle: "Transcribe, summarize and extract action items into a meeting page",
+        icon: <IconWand />,
+        category: "action",
+        action: () => {
+          onClose();
+          window.dispatchEvent(new CustomEvent("meeting-notes-open"));
+        },
+        keywords: ["meeting", "recording", "transcribe", "minutes", "action items", "summary", "audio"],
+      });
     }
 
     // Smart Collections
@@ -932,6 +1095,37 @@ export function CommandPalette({
     }));
   }, [recentSearches, query]);
 
+  // Completions of the typed query: past searches and tags fill in the
+  // query, page titles open the page
+  const suggestionCommands = useMemo<Command[]>(() => {
+    if (!query.trim()) return [];
+
+    return suggestions.map((suggestion, index) => ({
+      id: `suggestion-${index}`,
+      title: suggestion.kind === "tag" ? `#${suggestion.text}` : suggestion.text,
+      subtitle:
+        suggestion.kind === "history"
+          ? "Past search"
+          : suggestion.kind === "tag"
+            ? "Tag"
+            : "Page",
+      icon: suggestion.kind === "page" ? <IconPage /> : <IconHistory />,
+      category: "suggestion" as const,
+      action: () => {
+        const { notebookId, pageId } = suggestion;
+        if (suggestion.kind === "page" && notebookId && pageId) {
+          if (notebookId !== selectedNotebookId) {
+            selectNotebook(notebookId);
+          }
+          selectPage(pageId);
+          onClose();
+        } else {
+          setQuery(suggestion.text);
+        }
+      },
+    }));
+  }, [suggestions, query, selectedNotebookId, selectNotebook, selectPage, onClose]);
+
   // Recent pages as commands (shown when no query)
   const recentPageCommands = useMemo<Command[]>(() => {
     if (query.trim()) return [];
@@ -966,8 +1160,8 @@ export function CommandPalette({
 
   // Combine filtered commands with search results and recent searches
   const allCommands = useMemo(() => {
-    return [...recentPageCommands, ...recentCommands, ...filteredCommands, ...searchCommands];
-  }, [recentPageCommands, recentCommands, filteredCommands, searchCommands]);
+    return [...recentPageCommands, ...recentCommands, ...suggestionCommands, ...filteredCommands, ...searchCommands];
+  }, [recentPageCommands, recentCommands, suggestionCommands, filteredCommands, searchCommands]);
 
   // Group filtered commands by category
   const groupedCommands = useMemo(() => {
@@ -975,6 +1169,7 @@ export function CommandPalette({
 
     const recentPageMatches = allCommands.filter((c) => c.category === "recent-page");
     const recentMatches = allCommands.filter((c) => c.category === "recent");
+    const suggestionMatches = allCommands.filter((c) => c.category === "suggestion");
     const searchMatches = allCommands.filter((c) => c.category === "search");
     const actionCommands = allCommands.filter((c) => c.category === "action");
     const automationCommands = allCommands.filter((c) => c.category === "automation");
@@ -989,6 +1184,9 @@ export function CommandPalette({
     if (recentMatches.length > 0) {
       groups.push({ category: "Recent Searches", commands: recentMatches });
     }
+    if (suggestionMatches.length > 0) {
+      groups.push({ category: "Suggestions", commands: suggestionMatches });
+    }
     if (actionCommands.length > 0) {
       groups.push({ category: "Actions", commands: actionCommands });
     }
@@ -1012,12 +1210,12 @@ export function CommandPalette({
   // Reset state when opening
   useEffect(() => {
     if (isOpen) {
-      setQuery("");
+      setQuery(initialQuery ?? "");
       setSelectedIndex(0);
       setSearchResults([]);
       setTimeout(() => inputRef.current?.focus(), 0);
     }
-  }, [isOpen]);
+  }, [isOpen, initialQuery]);
 
   // Reset selected index when filtered results change
   useEffect(() => {
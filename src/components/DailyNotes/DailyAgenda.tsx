@@ -0,0 +1,86 @@
+import { useEffect, useState } from "react";
+import { getDailyAgenda, type PageReminder } from "../../utils/api";
+import { useNotebookStore } from "../../stores/notebookStore";
+import { usePageStore } from "../../stores/pageStore";
+
+interface DailyAgendaProps {
+  date: string; // "YYYY-MM-DD"
+}
+
+function formatTime(iso: string): string {
+  return new Date(iso).toLocaleTimeString(undefined, {
+    hour: "numeric",
+    minute: "2-digit",
+  });
+}
+
+/** Page reminders falling on the selected day */
+export function DailyAgenda({ date }: DailyAgendaProps) {
+  const [reminders, setReminders] = useState<PageReminder[]>([]);
+  const selectNotebook = useNotebookStore((s) => s.selectNotebook);
+  const selectPage = usePageStore((s) => s.selectPage);
+
+  useEffect(() => {
+    let cancelled = false;
+    getDailyAgenda(date)
+      .then((items) => {
+        if (!cancelled) setReminders(items);
+      })
+      .catch((err) => {
+        console.error("Failed to load agenda:", err);
+        if (!cancelled) setReminders([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [date]);
+
+  if (reminders.length === 0) return null;
+
+  return (
+    <div className="border-b px-4 py-3" style={{ borderColor: "var(--color-border)" }}>
+      <div
+        className="mb-2 text-xs font-medium uppercase tracking-wide"
+        style={{ color: "var(--color-text-muted)" }}
+      >
+        Agenda
+      </div>
+      <div className="space-y-1">
+        {reminders.map((reminder) => (
+          <button
+            key={reminder.id}
+            onClick={() => {
+              selectNotebook(reminder.notebookId);
+              selectPage(reminder.pageId);
+            }}
+            className="flex w-full items-baseline gap-3 rounded-md px-2 py-1 text-left transition-colors hover:bg-[--color-bg-tertiary]"
+            style={{ opacity: reminder.firedAt ? 0.6 : 1 }}
+          >
+            <span
+              className="w-14 shrink-0 text-xs tabular-nums"
+              style={{ color: "var(--color-text-muted)" }}
+            >
+              {formatTime(reminder.remindAt)}
+            </span>
+            <span className="min-w-0 flex-1">
+              <span
+                className="block truncate text-sm"
+                style={{ color: "var(--color-text-primary)" }}
+              >
+                {reminder.pageTitle}
+              </span>
+              {reminder.note && (
+                <span
+                  className="block truncate text-xs"
+                  style={{ color: "var(--color-text-muted)" }}
+                >
+                  {reminder.note}
+                </span>
+              )}
+            </span>
+          </button>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -11,6 +11,7 @@ import { MoodHabitChart } from "./MoodHabitChart";
 import { RollupDialog } from "./RollupDialog";
 import { ReflectionPrompts } from "./ReflectionPrompts";
 import { DigestPanel } from "./DigestPanel";
+import { DailyAgenda } from "./DailyAgenda";
 import { EnergyCheckInDialog } from "../Energy/EnergyCheckInDialog";
 import { EnergyCalendar } from "../Energy/EnergyCalendar";
 import { localToday } from "../../utils/dateLocal";
@@ -223,15 +224,19 @@ export function DailyNotesPanel({ isOpen: isOpenProp, onClose: onCloseProp }: Da
             (t) => t.id === note.templateId
           );
           if (template && template.content.blocks.length > 0) {
-            // Deep clone the content and generate new block IDs
+            // Deep clone the content and generate new block IDs, keeping any
+            // items carried forward into the new note after the template
             const contentWithNewIds: EditorData = {
               time: Date.now(),
               version: template.content.version,
-              blocks: template.content.blocks.map((block) => ({
-                ...block,
-                id: crypto.randomUUID(),
-                data: { ...block.data },
-              })),
+              blocks: [
+                ...template.content.blocks.map((block) => ({
+                  ...block,
+                  id: crypto.randomUUID(),
+                  data: { ...block.data },
+                })),
+                ...(note.content?.blocks ?? []),
+              ],
             };
             // Apply the template content
             await updatePageContent(selectedNotebookId, note.id, contentWithNewIds);
@@ -600,6 +605,9 @@ export function DailyNotesPanel({ isOpen: isOpenProp, onClose: onCloseProp }: Da
         />
       </div>
 
+      {/* Page reminders for the day */}
+      <DailyAgenda date={selectedDate} />
+
       {/* Open/Create button */}
       <div className="border-b px-4 py-3" style={{ borderColor: "var(--color-border)" }}>
         <button
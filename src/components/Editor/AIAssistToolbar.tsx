@@ -2,9 +2,39 @@ import { useState, useEffect, useRef, useCallback } from "react";
 import { aiChatStream } from "../../utils/api";
 import { listenAiStream } from "../../utils/aiStream";
 import { useAIStore } from "../../stores/aiStore";
+import { useFlashcardStore } from "../../stores/flashcardStore";
+import { useToastStore } from "../../stores/toastStore";
 
 interface AIAssistToolbarProps {
   containerRef: React.RefObject<HTMLElement | null>;
+  /** Enables turning the selection into a cloze card */
+  notebookId?: string;
+  pageId?: string;
+}
+
+/** The selection as a character range of the block it lies in */
+function selectionInBlock(range: Range) {
+  const start =
+    range.startContainer instanceof Element
+      ? range.startContainer
+      : range.startContainer.parentElement;
+  const block = start?.closest<HTMLElement>(
+    '[data-node-type="blockContainer"][data-id]'
+  );
+  const content = block?.querySelector<HTMLElement>(":scope > .bn-block-content");
+  if (!block || !content || !content.contains(range.endContainer)) return null;
+
+  const before = document.createRange();
+  before.setStart(content, 0);
+  before.setEnd(range.startContainer, range.startOffset);
+  // Offsets are in characters, as the backend counts them
+  const startChar = Array.from(before.toString()).length;
+  return {
+    blockId: block.getAttribute("data-id") ?? "",
+    text: content.textContent ?? "",
+    start: startChar,
+    end: startChar + Array.from(range.toString()).length,
+  };
 }
 
 type ToolbarState = "idle" | "toolbar" | "processing" | "result";
@@ -58,7 +88,11 @@ const AI_ACTIONS = [
   },
 ] as const;
 
-export function AIAssistToolbar({ containerRef }: AIAssistToolbarProps) {
+export function AIAssistToolbar({
+  containerRef,
+  notebookId,
+  pageId,
+}: AIAssistToolbarProps) {
   const [state, setState] = useState<ToolbarState>("idle");
   const [position, setPosition] = useState<Position>({ top: 0, left: 0 });
   const [selectedText, setSelectedText] = useState("");
@@ -207,6 +241,41 @@ export function AIAssistToolbar({ containerRef }: AIAssistToolbarProps) {
     [selectedText, getActiveProviderType, getActiveApiKey, getActiveModel]
   );
 
+  const handleCloze = useCallback(async () => {
+    const range = savedRangeRef.current;
+    const target = range && selectionInBlock(range);
+    hide();
+    if (!notebookId || !pageId || !target?.blockId) return;
+
+    const toast = useToastStore.getState();
+    const flashcards = useFlashcardStore.getState();
+    if (flashcards.decks.length === 0) {
+      await flashcards.loadDecks(notebookId);
+    }
+    const { decks, selectedDeckId } = useFlashcardStore.getState();
+    const deckId = selectedDeckId ?? decks[0]?.id;
+    if (!deckId) {
+      toast.error("Create a flashcard deck first");
+      return;
+    }
+
+    const cards = await flashcards.createCardFromBlock(
+      notebookId,
+      deckId,
+      pageId,
+      target.blockId,
+      target.text,
+      "",
+      { ranges: [{ start: target.start, end: target.end }] }
+    );
+    if (cards.length > 0) {
+      const deck = decks.find((d) => d.id === deckId);
+      toast.success(`Cloze card added to ${deck?.name ?? "deck"}`);
+    } else {
+      toast.error(useFlashcardStore.getState().error ?? "Failed to create cloze card");
+    }
+  }, [notebookId, pageId, hide]);
+
   const handleReplace = useCallback(() => {
     const range = savedRangeRef.current;
     if (!range || !resultText) {
@@ -295,6 +364,35 @@ export function AIAssistToolbar({ containerRef }: AIAssistToolbarProps) {
               <span>{action.label}</span>
             </button>
           ))}
+          {notebookId && pageId && (
+            <button
+              onClick={handleCloze}
+              style={{
+                display: "flex",
+                alignItems: "center",
+                gap: "4px",
+                padding: "4px 8px",
+                border: "none",
+                background: "transparent",
+                color: "var(--color-text-primary)",
+                borderRadius: "6px",
+                cursor: "pointer",
+                fontSize: "12px",
+                whiteSpace: "nowrap",
+              }}
+              onMouseEnter={(e) =>
+                (e.currentTarget.style.background =
+                  "var(--color-bg-tertiary)")
+              }
+              onMouseLeave={(e) =>
+                (e.currentTarget.style.background = "transparent")
+              }
+              title="Hide the selection in a cloze flashcard"
+            >
+              <span>[…]</span>
+              <span>Cloze</span>
+            </button>
+          )}
         </div>
       )}
 
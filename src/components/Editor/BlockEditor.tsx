@@ -808,7 +808,7 @@ export const BlockEditor = memo(forwardRef<BlockEditorRef, BlockEditorProps>(fun
       )}
       {/* Link preview tooltip for external URLs */}
       <LinkPreview containerRef={containerRef} />
-      {!readOnly && <AIAssistToolbar containerRef={containerRef as React.RefObject<HTMLElement | null>} />}
+      {!readOnly && <AIAssistToolbar containerRef={containerRef as React.RefObject<HTMLElement | null>} notebookId={notebookId} pageId={pageId} />}
       {isVimModeEnabled && (
         <div className="pointer-events-none fixed bottom-16 left-4 z-50">
           <VimModeIndicator mode={vimMode} pendingKeys={pendingKeys} />
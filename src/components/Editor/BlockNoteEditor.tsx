@@ -622,6 +622,19 @@ export const BlockNoteEditor = memo(
               group: c.group ?? "Custom",
               icon: c.icon ? <c.icon /> : undefined,
             }));
+        // Chart blocks draw a table or CSV file through the backend
+        customBlockItems.push({
+          title: "Chart",
+          onItemClick: () => {
+            editor.insertBlocks(
+              [{ type: "chart", props: {} } as never],
+              editor.getTextCursorPosition().block,
+              "after",
+            );
+          },
+          aliases: ["chart", "graph", "plot", "visualize"],
+          group: "Custom",
+        });
 
         return async (query: string) => {
           const defaultItems = getDefaultReactSlashMenuItems(editor);
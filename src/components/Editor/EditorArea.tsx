@@ -21,6 +21,10 @@ import { ResizeHandle } from "../Layout/ResizeHandle";
 import { MovePageDialog } from "../Move/MovePageDialog";
 import { MoveFolderDialog } from "../Move/MoveFolderDialog";
 import { SmartOrganizeDialog } from "../SmartOrganize/SmartOrganizeDialog";
+import { AIBulkTransformDialog } from "../AI/AIBulkTransformDialog";
+import { TranslateDialog } from "../AI/TranslateDialog";
+import { ReadingQueueDialog } from "../Audio/ReadingQueueDialog";
+import { MeetingNotesDialog } from "../Audio/MeetingNotesDialog";
 import type { EditorData, Page } from "../../types/page";
 import * as api from "../../utils/api";
 import { downloadTranscript } from "../../utils/videoApi";
@@ -143,6 +147,38 @@ export function EditorArea() {
     return () => window.removeEventListener("smart-organize-open", handleSmartOrganizeOpen);
   }, []);
 
+  // AI bulk edit dialog, opened from the Command Palette
+  const [aiBulkOpen, setAiBulkOpen] = useState(false);
+  useEffect(() => {
+    const handleAiBulkOpen = () => setAiBulkOpen(true);
+    window.addEventListener("ai-bulk-transform-open", handleAiBulkOpen);
+    return () => window.removeEventListener("ai-bulk-transform-open", handleAiBulkOpen);
+  }, []);
+
+  // Translate dialog, opened from the Command Palette
+  const [translateOpen, setTranslateOpen] = useState(false);
+  useEffect(() => {
+    const handleTranslateOpen = () => setTranslateOpen(true);
+    window.addEventListener("translate-open", handleTranslateOpen);
+    return () => window.removeEventListener("translate-open", handleTranslateOpen);
+  }, []);
+
+  // Reading queue dialog, opened from the Command Palette
+  const [readingQueueOpen, setReadingQueueOpen] = useState(false);
+  useEffect(() => {
+    const handleReadingQueueOpen = () => setReadingQueueOpen(true);
+    window.addEventListener("reading-queue-open", handleReadingQueueOpen);
+    return () => window.removeEventListener("reading-queue-open", handleReadingQueueOpen);
+  }, []);
+
+  // Meeting notes dialog, opened from the Command Palette
+  const [meetingNotesOpen, setMeetingNotesOpen] = useState(false);
+  useEffect(() => {
+    const handleMeetingNotesOpen = () => setMeetingNotesOpen(true);
+    window.addEventListener("meeting-notes-open", handleMeetingNotesOpen);
+    return () => window.removeEventListener("meeting-notes-open", handleMeetingNotesOpen);
+  }, []);
+
   const selectedNotebook = notebooks.find((n) => n.id === selectedNotebookId);
 
   // Load pages, folders, and sections when notebook selection changes
@@ -623,6 +659,65 @@ export function EditorArea() {
           }}
         />
       )}
+
+      {/* AI Bulk Edit Dialog */}
+      {selectedNotebook && (
+        <AIBulkTransformDialog
+          isOpen={aiBulkOpen}
+          onClose={() => setAiBulkOpen(false)}
+          notebookId={selectedNotebook.id}
+          pages={notebookPages.filter(
+            (p) => !p.isArchived && !p.deletedAt && p.pageType === "standard"
+          )}
+          initialPageIds={
+            selectedSectionId
+              ? notebookPages
+                  .filter((p) => p.sectionId === selectedSectionId && !p.isArchived && !p.deletedAt)
+                  .map((p) => p.id)
+              : undefined
+          }
+          onCompleted={() => {
+            loadPages(selectedNotebook.id, showArchived);
+          }}
+        />
+      )}
+
+      {/* Translate Dialog */}
+      {selectedNotebook && (
+        <TranslateDialog
+          isOpen={translateOpen}
+          onClose={() => setTranslateOpen(false)}
+          notebookId={selectedNotebook.id}
+          pageId={notebookPages.some((p) => p.id === selectedPageId) ? selectedPageId : null}
+          pageTitle={notebookPages.find((p) => p.id === selectedPageId)?.title}
+          folders={folders.filter((f) => f.folderType !== "archive" && !f.isArchived)}
+          onCompleted={() => {
+            loadPages(selectedNotebook.id, showArchived);
+          }}
+          onOpenPage={selectPage}
+        />
+      )}
+
+      {/* Reading Queue Dialog */}
+      {selectedNotebook && (
+        <ReadingQueueDialog
+          isOpen={readingQueueOpen}
+          onClose={() => setReadingQueueOpen(false)}
+          notebookId={selectedNotebook.id}
+          pages={notebookPages.filter(
+            (p) => !p.isArchived && !p.deletedAt && p.pageType === "standard"
+          )}
+        />
+      )}
+
+      {/* Meeting Notes Dialog */}
+      {selectedNotebook && (
+        <MeetingNotesDialog
+          isOpen={meetingNotesOpen}
+          onClose={() => setMeetingNotesOpen(false)}
+          notebookId={selectedNotebook.id}
+        />
+      )}
     </div>
   );
 }
@@ -93,8 +93,11 @@ export function EditorPaneContent({
   const editorRef = useRef<BlockEditorRef | BlockNoteEditorRef>(null);
 
   const selectedPage = pages.find((p) => p.id === pane.pageId);
+  // SQL notebook pages are block pages too; their code blocks run as SQL
   const isStandardPage =
-    selectedPage?.pageType === "standard" || !selectedPage?.pageType;
+    selectedPage?.pageType === "standard" ||
+    selectedPage?.pageType === "sql" ||
+    !selectedPage?.pageType;
 
   // Tabs belonging to other notebooks stay in the store (they return on
   // switch-back) but are hidden from this pane's tab bar.
@@ -1011,8 +1014,7 @@ export function EditorPaneContent({
                         className="min-h-[calc(100vh-300px)]"
                       />
                     )}
-                    {(selectedPage.pageType === "standard" ||
-                      !selectedPage.pageType) && (
+                    {isStandardPage && (
                       <>
                         {collab.isActive && (
                           <CollabStatusBar
@@ -26,18 +26,10 @@ import { useSectionStore } from "../../stores/sectionStore";
 import { useThemeStore, type PageSortOption as ThemePageSortOption } from "../../stores/themeStore";
 import * as api from "../../utils/api";
 import { FolderTreeItem, DraggablePageItem } from "./FolderTreeItem";
-import { sortFolders } from "./folderTreeUtils";
+import { sortFolders, PAGE_SORT_OPTIONS } from "./folderTreeUtils";
 import { ObjectTypePicker, ObjectTypeManager } from "../Database/ObjectTypeManager";
 import { ALL_SUPPORTED_EXTENSIONS } from "../../utils/fileImport";
 
-const PAGE_SORT_OPTIONS: { value: PageSortOption; label: string }[] = [
-  { value: "position", label: "Manual" },
-  { value: "name-asc", label: "Name (A-Z)" },
-  { value: "name-desc", label: "Name (Z-A)" },
-  { value: "updated", label: "Recently updated" },
-  { value: "created", label: "Recently created" },
-];
-
 // Droppable section component for drag-and-drop to sections
 function DroppableSection({
   section,
@@ -183,9 +175,10 @@ export function FolderTree({
     deleteFolder: deleteFolderApi,
     archiveFolder: archiveFolderApi,
     unarchiveFolder: unarchiveFolderApi,
+    loadFolders,
   } = useFolderStore();
-  const { updateNotebook } = useNotebookStore();
-  const { updateSection: updateSectionStore } = useSectionStore();
+  const { updateNotebook, loadNotebooks } = useNotebookStore();
+  const { updateSection: updateSectionStore, loadSections } = useSectionStore();
   const autoHidePanels = useThemeStore((state) => state.autoHidePanels);
   const setAutoHidePanels = useThemeStore((state) => state.setAutoHidePanels);
   const globalPageSortBy = useThemeStore((state) => state.pageSortBy);
@@ -219,10 +212,39 @@ export function FolderTree({
   const [showPageSortMenu, setShowPageSortMenu] = useState(false);
   const [showNewPageMenu, setShowNewPageMenu] = useState(false);
 
-  // Helper function to sort pages based on current sort option
-  const sortPages = useCallback((pagesToSort: Page[]) => {
+  // Pins of the container a page is listed in: its folder, its section's
+  // root, or the notebook root
+  const getContainerPins = useCallback(
+    (page: Page): string[] => {
+      if (page.folderId) {
+        return folders.find((f) => f.id === page.folderId)?.pinnedPageIds ?? [];
+      }
+      if (page.sectionId) {
+        return sections.find((s) => s.id === page.sectionId)?.pinnedPageIds ?? [];
+      }
+      return notebook?.pinnedPageIds ?? [];
+    },
+    [folders, sections, notebook]
+  );
+
+  const pinnedPageIds = useMemo(
+    () => new Set(pages.filter((p) => getContainerPins(p).includes(p.id)).map((p) => p.id)),
+    [pages, getContainerPins]
+  );
+
+  // Helper function to sort pages based on current sort option; a folder's
+  // own sort overrides it, and pinned pages come first in pin order
+  const sortPages = useCallback((pagesToSort: Page[], folderId: string | null = null) => {
+    const folderSortBy = folderId ? folders.find((f) => f.id === folderId)?.pageSortBy : null;
+    const sortBy = folderSortBy ?? pageSortBy;
+    const pinRank = (page: Page) => {
+      const index = getContainerPins(page).indexOf(page.id);
+      return index === -1 ? Number.MAX_SAFE_INTEGER : index;
+    };
     return [...pagesToSort].sort((a, b) => {
-      switch (pageSortBy) {
+      const pinned = pinRank(a) - pinRank(b);
+      if (pinned !== 0) return pinned;
+      switch (sortBy) {
         case "name-asc":
           return a.title.localeCompare(b.title);
         case "name-desc":
@@ -236,7 +258,7 @@ export function FolderTree({
           return a.position - b.position;
       }
     });
-  }, [pageSortBy]);
+  }, [pageSortBy, folders, getContainerPins]);
   const [newFolderName, setNewFolderName] = useState("");
   const [activePageId, setActivePageId] = useState<string | null>(null);
   const [activeFolderId, setActiveFolderId] = useState<string | null>(null);
@@ -359,7 +381,7 @@ export function FolderTree({
 
         return (p.folderId ?? null) === folderId && noParent;
       });
-      const sorted = sortPages(filtered);
+      const sorted = sortPages(filtered, folderId);
       return sorted;
     },
     [visiblePages, visibleFolderIds, sortPages]
@@ -372,7 +394,7 @@ export function FolderTree({
       const filtered = visiblePages.filter((p) =>
         p.parentPageId && p.parentPageId === parentPageId
       );
-      const sorted = sortPages(filtered);
+      const sorted = sortPages(filtered, filtered[0]?.folderId ?? null);
       // Debug: Check for duplicates
       const ids = sorted.map(p => p.id);
       const duplicates = ids.filter((id, i) => ids.indexOf(id) !== i);
@@ -674,6 +696,27 @@ END:VCALENDAR`;
     [notebookId, toggleFavorite]
   );
 
+  // Handle pinning a page to the top of its folder or section
+  const handleTogglePinned = useCallback(
+    async (pageId: string) => {
+      try {
+        await api.setPagePinned(notebookId, pageId, !pinnedPageIds.has(pageId));
+        await Promise.all([loadFolders(notebookId), loadSections(notebookId), loadNotebooks()]);
+      } catch (err) {
+        console.error("Failed to pin page:", err);
+      }
+    },
+    [notebookId, pinnedPageIds, loadFolders, loadSections, loadNotebooks]
+  );
+
+  // Handle setting a folder's own page sort (null uses the section/notebook sort)
+  const handleSetFolderSort = useCallback(
+    (folderId: string, pageSortBy: PageSortOption | null) => {
+      updateFolder(notebookId, folderId, { pageSortBy });
+    },
+    [notebookId, updateFolder]
+  );
+
   // Handle archiving a page
   const handleArchivePage = useCallback(
     (pageId: string) => {
@@ -972,6 +1015,9 @@ END:VCALENDAR`;
           onCreateSubpage={handleCreateSubpage}
           onDeletePage={handleDeletePage}
           onToggleFavorite={handleToggleFavorite}
+          pinnedPageIds={pinnedPageIds}
+          onTogglePinned={handleTogglePinned}
+          onSetFolderSort={handleSetFolderSort}
           onRenameFolder={handleRenameFolder}
           onDeleteFolder={handleDeleteFolder}
           renderFolder={renderFolder}
@@ -1010,6 +1056,9 @@ END:VCALENDAR`;
       handleCreateSubpage,
       handleDeletePage,
       handleToggleFavorite,
+      pinnedPageIds,
+      handleTogglePinned,
+      handleSetFolderSort,
       handleRenameFolder,
       handleDeleteFolder,
       sectionsEnabled,
@@ -1573,6 +1622,8 @@ END:VCALENDAR`;
                   onCreateSubpage={handleCreateSubpage}
                   onDeletePage={handleDeletePage}
                   onToggleFavorite={handleToggleFavorite}
+                  pinnedPageIds={pinnedPageIds}
+                  onTogglePinned={handleTogglePinned}
                   sections={sectionsEnabled ? sortedSections : undefined}
                   onMoveToSection={onMovePageToSection}
                   onMoveToNotebook={onMoveToNotebook}
@@ -1,6 +1,8 @@
 import { useState, useRef, useEffect, useLayoutEffect, memo, useCallback } from "react";
 import { useDraggable, useDroppable } from "@dnd-kit/core";
 import type { Folder, Page, PageType, Section } from "../../types/page";
+import type { PageSortOption } from "../../types/notebook";
+import { PAGE_SORT_OPTIONS } from "./folderTreeUtils";
 
 // Icon component for different page types
 function PageTypeIcon({ pageType }: { pageType: PageType }) {
@@ -94,6 +96,9 @@ interface FolderTreeItemProps {
   onCreateSubpage?: (parentPageId: string) => void;
   onDeletePage?: (pageId: string, pageTitle: string) => void;
   onToggleFavorite?: (pageId: string) => void;
+  pinnedPageIds?: Set<string>;
+  onTogglePinned?: (pageId: string) => void;
+  onSetFolderSort?: (folderId: string, pageSortBy: PageSortOption | null) => void;
   onRenameFolder: (folderId: string, newName: string) => void;
   onDeleteFolder: (folderId: string) => void;
   renderFolder: (folder: Folder, depth: number) => React.ReactNode;
@@ -130,6 +135,9 @@ export const FolderTreeItem = memo(function FolderTreeItem({
   onCreateSubpage,
   onDeletePage,
   onToggleFavorite,
+  pinnedPageIds,
+  onTogglePinned,
+  onSetFolderSort,
   onRenameFolder,
   onDeleteFolder,
   renderFolder,
@@ -546,6 +554,8 @@ export const FolderTreeItem = memo(function FolderTreeItem({
               onCreateSubpage={onCreateSubpage}
               onDeletePage={onDeletePage}
               onToggleFavorite={onToggleFavorite}
+              pinnedPageIds={pinnedPageIds}
+              onTogglePinned={onTogglePinned}
               sections={sections}
               onMoveToSection={onMoveToSection}
               onMoveToNotebook={onMoveToNotebook}
@@ -657,6 +667,41 @@ export const FolderTreeItem = memo(function FolderTreeItem({
             </button>
           </>
         )}
+        {onSetFolderSort && (
+          <>
+            {((sections && sections.length > 0 && onMoveFolderToSection) || onMoveFolderToNotebook) && (
+              <div
+                className="my-1 border-t"
+                style={{ borderColor: "var(--color-border)" }}
+              />
+            )}
+            <div
+              className="px-3 py-1.5 text-xs font-semibold uppercase tracking-wider"
+              style={{ color: "var(--color-text-muted)" }}
+            >
+              Sort Pages
+            </div>
+            {[{ value: null, label: "Section Default" }, ...PAGE_SORT_OPTIONS].map((option) => (
+              <button
+                key={option.value ?? "default"}
+                onClick={(e) => {
+                  e.stopPropagation();
+                  setShowContextMenu(false);
+                  onSetFolderSort(folder.id, option.value);
+                }}
+                className="flex w-full items-center gap-2 px-3 py-1.5 text-sm transition-colors hover:bg-[var(--color-bg-tertiary)]"
+                style={{
+                  color:
+                    (folder.pageSortBy ?? null) === option.value
+                      ? "var(--color-accent)"
+                      : "var(--color-text-primary)",
+                }}
+              >
+                {option.label}
+              </button>
+            ))}
+          </>
+        )}
         {/* Share as Mini-Site */}
         <div
           className="my-1 border-t"
@@ -840,6 +885,8 @@ interface DraggablePageItemProps {
   onDuplicateDatabase?: (pageId: string, includeRows: boolean) => void;
   onDeletePage?: (pageId: string, pageTitle: string) => void; // Delete the page
   onToggleFavorite?: (pageId: string) => void; // Toggle favorite status
+  pinnedPageIds?: Set<string>; // Pages pinned to the top of their folder or section
+  onTogglePinned?: (pageId: string) => void; // Pin or unpin the page
   sections?: Section[];
   onMoveToSection?: (pageId: string, sectionId: string | null) => void;
   onMoveToNotebook?: (pageId: string, pageTitle: string) => void; // Move page to another notebook
@@ -866,6 +913,8 @@ const DraggablePageItem = memo(function DraggablePageItem({
   onDuplicateDatabase,
   onDeletePage,
   onToggleFavorite,
+  pinnedPageIds,
+  onTogglePinned,
   sections,
   onMoveToSection,
   onMoveToNotebook,
@@ -1007,6 +1056,14 @@ const DraggablePageItem = memo(function DraggablePageItem({
     setShowContextMenu(false);
   }, [onToggleFavorite, page.id]);
 
+  const isPinned = pinnedPageIds?.has(page.id) ?? false;
+
+  const handleTogglePinned = useCallback((e: React.MouseEvent) => {
+    e.stopPropagation();
+    onTogglePinned?.(page.id);
+    setShowContextMenu(false);
+  }, [onTogglePinned, page.id]);
+
   return (
     <>
       <li
@@ -1089,6 +1146,15 @@ const DraggablePageItem = memo(function DraggablePageItem({
               />
             )}
             <span className="flex-1 min-w-0 truncate text-sm">{page.title}</span>
+            {isPinned && (
+              <span
+                className="flex-shrink-0"
+                style={{ color: "var(--color-text-muted)" }}
+                title="Pinned"
+              >
+                <IconPin />
+              </span>
+            )}
             {page.isFavorite && !isHovered && (
               <span
                 className="flex-shrink-0"
@@ -1147,6 +1213,8 @@ const DraggablePageItem = memo(function DraggablePageItem({
                 onCreateSubpage={onCreateSubpage}
                 onDeletePage={onDeletePage}
                 onToggleFavorite={onToggleFavorite}
+                pinnedPageIds={pinnedPageIds}
+                onTogglePinned={onTogglePinned}
                 sections={sections}
                 onMoveToSection={onMoveToSection}
                 onMoveToNotebook={onMoveToNotebook}
@@ -1309,6 +1377,18 @@ const DraggablePageItem = memo(function DraggablePageItem({
             </>
           )}
 
+          {/* Pin option */}
+          {onTogglePinned && (
+            <button
+              onClick={handleTogglePinned}
+              className="flex w-full items-center gap-2 px-3 py-1.5 text-sm transition-colors hover:bg-[var(--color-bg-tertiary)]"
+              style={{ color: isPinned ? "var(--color-accent)" : "var(--color-text-primary)" }}
+            >
+              <IconPin />
+              {isPinned ? "Unpin" : "Pin to Top"}
+            </button>
+          )}
+
           {/* Section options */}
           {sections && sections.length > 0 && onMoveToSection && (
             <>
@@ -1654,4 +1734,23 @@ function IconStarFilled() {
   );
 }
 
+function IconPin() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="14"
+      height="14"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <path d="M12 17v5" />
+      <path d="M9 10.76a2 2 0 0 1-1.11 1.79l-1.78.9A2 2 0 0 0 5 15.24V17h14v-1.76a2 2 0 0 0-1.11-1.79l-1.78-.9A2 2 0 0 1 15 10.76V7a1 1 0 0 1 1-1 2 2 0 0 0 0-4H8a2 2 0 0 0 0 4 1 1 0 0 1 1 1z" />
+    </svg>
+  );
+}
+
 export { PageItem, DraggablePageItem };
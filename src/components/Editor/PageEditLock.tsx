@@ -0,0 +1,138 @@
+import { useEffect, useState } from "react";
+import type { Page } from "../../types/page";
+import { listen } from "../../platform/event";
+import { isTauri } from "../../utils/platform";
+import { useNotebookStore } from "../../stores/notebookStore";
+import { useToastStore } from "../../stores/toastStore";
+import {
+  getPageLocks,
+  lockPageForEditing,
+  releasePageLock,
+  type PageLockInfo,
+} from "../../utils/api";
+import { formatRelativeTime } from "../VersionHistory/VersionHistoryDialog";
+
+/** Re-take a held lock this often; locks expire after 30 minutes */
+const REFRESH_MS = 10 * 60 * 1000;
+
+interface PageEditLockProps {
+  page: Page;
+}
+
+/**
+ * Advisory edit lock for a page in a synced notebook: shows who else is
+ * editing it, or lets this device claim it. A claim is kept fresh while the
+ * page is open and lapses on its own once it's closed.
+ */
+export function PageEditLock({ page }: PageEditLockProps) {
+  const synced = useNotebookStore(
+    (state) => state.notebooks.find((n) => n.id === page.notebookId)?.syncConfig?.enabled ?? false
+  );
+  const [lock, setLock] = useState<PageLockInfo | null>(null);
+  const [busy, setBusy] = useState(false);
+  const toast = useToastStore();
+
+  const enabled = isTauri() && synced;
+
+  const load = async () => {
+    try {
+      const locks = await getPageLocks(page.notebookId);
+      setLock(locks.find((l) => l.pageId === page.id) ?? null);
+    } catch {
+      setLock(null);
+    }
+  };
+
+  useEffect(() => {
+    if (!enabled) return;
+    load();
+    const unlisten = listen<{ notebookId: string }>("sync-page-locks-updated", (event) => {
+      if (event.payload.notebookId === page.notebookId) load();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [enabled, page.notebookId, page.id]);
+
+  const mine = lock?.mine ?? false;
+  useEffect(() => {
+    if (!enabled || !mine) return;
+    const interval = setInterval(() => {
+      lockPageForEditing(page.notebookId, page.id).then(setLock).catch(() => load());
+    }, REFRESH_MS);
+    return () => clearInterval(interval);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [enabled, mine, page.notebookId, page.id]);
+
+  if (!enabled) return null;
+
+  const toggle = async () => {
+    setBusy(true);
+    try {
+      if (mine) {
+        await releasePageLock(page.notebookId, page.id);
+        setLock(null);
+      } else {
+        setLock(await lockPageForEditing(page.notebookId, page.id));
+      }
+    } catch (err) {
+      toast.error(`${err}`);
+      load();
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (lock && !lock.mine) {
+    return (
+      <span
+        className="ml-2 flex items-center gap-1 rounded-full px-2 py-0.5 text-xs whitespace-nowrap"
+        style={{
+          backgroundColor: "rgba(245, 158, 11, 0.15)",
+          color: "var(--color-warning)",
+        }}
+        title={`Locked ${formatRelativeTime(lock.acquiredAt)}; expires ${new Date(lock.expiresAt).toLocaleTimeString()}`}
+      >
+        <LockIcon />
+        Editing on {lock.deviceName}
+      </span>
+    );
+  }
+
+  return (
+    <button
+      onClick={toggle}
+      disabled={busy}
+      className="ml-2 flex items-center gap-1 rounded-md px-2 py-1 text-xs transition-colors hover:bg-[--color-bg-tertiary] disabled:opacity-50"
+      style={{ color: mine ? "var(--color-accent)" : "var(--color-text-muted)" }}
+      title={
+        mine
+          ? "Other devices see this page as being edited here. Click to release."
+          : "Let other devices know you're editing this page"
+      }
+    >
+      <LockIcon />
+      {mine ? "Locked" : "Lock"}
+    </button>
+  );
+}
+
+function LockIcon() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="12"
+      height="12"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <rect x="3" y="11" width="18" height="11" rx="2" ry="2" />
+      <path d="M7 11V7a5 5 0 0110 0v4" />
+    </svg>
+  );
+}
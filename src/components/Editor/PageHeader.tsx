@@ -1,6 +1,7 @@
 import { useState, useRef, useEffect, useCallback } from "react";
 import type { Page, EditorData } from "../../types/page";
 import { usePageStore } from "../../stores/pageStore";
+import { useNotebookStore } from "../../stores/notebookStore";
 import { useTemplateStore } from "../../stores/templateStore";
 import { useThemeStore } from "../../stores/themeStore";
 import {
@@ -38,6 +39,7 @@ import { usePluginStore, type PluginExportFormat } from "../../stores/pluginStor
 import { useDrawingStore } from "../../stores/drawingStore";
 import { WritingGoalProgress } from "./WritingGoalProgress";
 import { WritingGoalSettings } from "./WritingGoalSettings";
+import { PageEditLock } from "./PageEditLock";
 import { localToday } from "../../utils/dateLocal";
 import type { PageStats } from "../../utils/pageStats";
 
@@ -117,6 +119,9 @@ export function PageHeader({
   const toast = useToastStore();
   const { openAnnotationOverlay } = useDrawingStore();
   const { exportFormats, fetchExportFormats, executeExport } = usePluginStore();
+  const syncEnabled = useNotebookStore(
+    (state) => state.notebooks.find((n) => n.id === page.notebookId)?.syncConfig?.enabled ?? false
+  );
 
   // Fetch plugin export formats when menu opens
   useEffect(() => {
@@ -1484,6 +1489,8 @@ export function PageHeader({
           </div>
         )}
 
+        <PageEditLock page={page} />
+
         {/* Writing goal progress */}
         <div className="ml-2">
           <WritingGoalProgress onOpenSettings={() => setShowWritingGoalSettings(true)} />
@@ -1711,6 +1718,7 @@ export function PageHeader({
         isOpen={showVersionHistory}
         page={page}
         onClose={() => setShowVersionHistory(false)}
+        syncEnabled={syncEnabled}
       />
 
       {/* Writing Assistance Panel */}
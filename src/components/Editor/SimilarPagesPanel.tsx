@@ -3,7 +3,15 @@ import { usePageStore } from "../../stores/pageStore";
 import { useLinkStore } from "../../stores/linkStore";
 import { useAIStore } from "../../stores/aiStore";
 import { useRAGStore } from "../../stores/ragStore";
-import { aiSuggestRelatedPages, type RelatedPageSuggestion } from "../../utils/api";
+import { hasAiKey, useHasAiKey } from "../../stores/secretsStore";
+import {
+  aiSuggestRelatedPages,
+  applyLinkSuggestions,
+  rebuildSearchIndex,
+  rollbackToSnapshot,
+  type LinkApplyResult,
+  type RelatedPageSuggestion,
+} from "../../utils/api";
 import type { Page } from "../../types/page";
 import type { SemanticSearchResult } from "../../types/rag";
 
@@ -56,27 +64,30 @@ export function SimilarPagesPanel({ page, notebookId, allPages }: SimilarPagesPa
   const [error, setError] = useState<string | null>(null);
   const [hasLoaded, setHasLoaded] = useState(false);
   const [mode, setMode] = useState<SearchMode>("semantic");
+  const [isLinking, setIsLinking] = useState(false);
+  const [linkResult, setLinkResult] = useState<LinkApplyResult | null>(null);
 
-  const { selectPage } = usePageStore();
+  const { selectPage, refreshPages } = usePageStore();
   const { outgoingLinks } = useLinkStore();
   const { getActiveProviderType, getActiveApiKey, getActiveModel } = useAIStore();
   const { findSimilarPages, isConfigured: ragConfigured, settings: ragSettings } = useRAGStore();
 
-  const activeApiKey = getActiveApiKey();
+  const hasActiveKey = useHasAiKey(getActiveProviderType(), getActiveApiKey());
   const ragAvailable = ragConfigured && ragSettings.ragEnabled;
 
   // Auto-select mode based on availability
   useEffect(() => {
-    if (mode === "semantic" && !ragAvailable && activeApiKey) {
+    if (mode === "semantic" && !ragAvailable && hasActiveKey) {
       setMode("ai");
-    } else if (mode === "ai" && !activeApiKey && ragAvailable) {
+    } else if (mode === "ai" && !hasActiveKey && ragAvailable) {
       setMode("semantic");
     }
-  }, [ragAvailable, activeApiKey, mode]);
+  }, [ragAvailable, hasActiveKey, mode]);
 
   const fetchSuggestions = useCallback(async () => {
     setIsLoading(true);
     setError(null);
+    setLinkResult(null);
 
     try {
       if (mode === "semantic") {
@@ -98,7 +109,7 @@ export function SimilarPagesPanel({ page, notebookId, allPages }: SimilarPagesPa
       } else {
         // AI mode
         const apiKey = getActiveApiKey();
-        if (!apiKey) {
+        if (!hasAiKey(getActiveProviderType(), apiKey)) {
           setError("Configure AI in Settings to get suggestions");
           return;
         }
@@ -171,18 +182,69 @@ export function SimilarPagesPanel({ page, notebookId, allPages }: SimilarPagesPa
     setResults([]);
     setHasLoaded(false);
     setError(null);
+    setLinkResult(null);
   }, [page.id]);
 
+  // Link every suggestion from this page: at a mention of its title, or
+  // under a "Related" heading
+  const handleLinkAll = useCallback(async () => {
+    setIsLinking(true);
+    setError(null);
+    try {
+      const applied = await applyLinkSuggestions([
+        {
+          notebookId,
+          pageId: page.id,
+          targets: results.map((r) => ({ pageId: r.id, title: r.title })),
+        },
+      ]);
+      setLinkResult(applied);
+      if (applied.skipped.length > 0) {
+        setError(`Couldn't add links: ${applied.skipped[0].reason}`);
+      }
+      await refreshPages(
+        applied.linked.map((p) => p.pageId),
+        notebookId
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Failed to add links");
+    } finally {
+      setIsLinking(false);
+    }
+  }, [notebookId, page.id, results, refreshPages]);
+
+  const handleUndoLinks = useCallback(async () => {
+    if (!linkResult) return;
+    if (
+      !window.confirm(
+        "Undo restores the whole library to the snapshot taken before adding these links, including any edits made since. Continue?"
+      )
+    ) {
+      return;
+    }
+    try {
+      await rollbackToSnapshot(linkResult.snapshot.id);
+      try {
+        await rebuildSearchIndex();
+      } catch (err) {
+        console.warn("Search index rebuild after undo failed:", err);
+      }
+      window.location.reload();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Failed to undo");
+    }
+  }, [linkResult]);
+
   const handlePageClick = (pageId: string) => {
     selectPage(pageId);
   };
 
   // Don't show if neither mode is available
-  if (!activeApiKey && !ragAvailable && !hasLoaded) {
+  if (!hasActiveKey && !ragAvailable && !hasLoaded) {
     return null;
   }
 
-  const canSearch = mode === "semantic" ? ragAvailable : !!activeApiKey;
+  const canSearch = mode === "semantic" ? ragAvailable : hasActiveKey;
 
   return (
     <div className="mt-6 border-t pt-6" style={{ borderColor: "var(--color-border)" }}>
@@ -210,7 +272,7 @@ export function SimilarPagesPanel({ page, notebookId, allPages }: SimilarPagesPa
         </h3>
         <div className="flex items-center gap-1">
           {/* Mode toggle */}
-          {ragAvailable && activeApiKey && (
+          {ragAvailable && hasActiveKey && (
             <div
               className="mr-1 flex rounded text-xs"
               style={{ border: "1px solid var(--color-border)" }}
@@ -369,6 +431,41 @@ export function SimilarPagesPanel({ page, notebookId, allPages }: SimilarPagesPa
           ))}
         </ul>
       )}
+
+      {results.length > 0 && (
+        <div className="mt-2 flex items-center justify-end gap-2 text-xs">
+          {linkResult ? (
+            <>
+              <span style={{ color: "var(--color-text-muted)" }}>
+                {linkResult.linked.length > 0
+                  ? `Added ${
+                      linkResult.linked[0].inline.length + linkResult.linked[0].related.length
+                    } links`
+                  : "Already linked"}
+              </span>
+              {linkResult.linked.length > 0 && (
+                <button
+                  onClick={handleUndoLinks}
+                  className="rounded px-2 py-1 transition-colors hover:bg-white/10"
+                  style={{ color: "var(--color-text-secondary)" }}
+                >
+                  Undo
+                </button>
+              )}
+            </>
+          ) : (
+            <button
+              onClick={handleLinkAll}
+              disabled={isLinking}
+              className="rounded px-2 py-1 transition-colors hover:bg-white/10 disabled:opacity-50"
+              style={{ color: "var(--color-accent)" }}
+              title="Link each page where its title is mentioned, or under a Related heading"
+            >
+              {isLinking ? "Linking..." : "Link All"}
+            </button>
+          )}
+        </div>
+      )}
     </div>
   );
 }
@@ -0,0 +1,382 @@
+/**
+ * Custom chart block — a chart drawn from a table block on the page or a CSV
+ * asset. The backend (`compute_chart_data`) filters, groups and draws the
+ * data; this block only edits the spec and shows the SVG it gets back, the
+ * same one publish and print use.
+ *
+ * On disk: `data.{title, source, spec}`; the source and spec live in
+ * JSON-string props here.
+ */
+import { createReactBlockSpec } from "@blocknote/react";
+import { useEffect, useMemo, useState } from "react";
+import {
+  computeChartData,
+  type ChartAggregate,
+  type ChartFilter,
+  type ChartKind,
+  type ChartSort,
+  type ChartSource,
+  type ChartSpec,
+  type ComputedChart,
+} from "../../../utils/api";
+import { getCustomBlockEditorContext } from "../../../plugin-sdk/custom-block-spec";
+
+const KINDS: ChartKind[] = ["bar", "line", "area", "pie", "scatter"];
+const AGGREGATES: ChartAggregate[] = ["none", "sum", "avg", "min", "max", "count"];
+const FILTER_OPS: ChartFilter["op"][] = ["eq", "ne", "gt", "gte", "lt", "lte", "contains"];
+const FILTER_OP_LABELS: Record<ChartFilter["op"], string> = {
+  eq: "=",
+  ne: "≠",
+  gt: ">",
+  gte: "≥",
+  lt: "<",
+  lte: "≤",
+  contains: "contains",
+};
+
+const EMPTY_SPEC: ChartSpec = {
+  kind: "bar",
+  x: "",
+  y: [],
+  aggregate: "none",
+  filters: [],
+};
+
+function parseJson<T>(json: string, fallback: T): T {
+  if (!json) return fallback;
+  try {
+    return JSON.parse(json) as T;
+  } catch {
+    return fallback;
+  }
+}
+
+interface ChartEditorProps {
+  source: ChartSource | null;
+  spec: ChartSpec;
+  title: string;
+  tables: Array<{ id: string; label: string; key: string }>;
+  notebookId?: string;
+  pageId?: string;
+  readOnly: boolean;
+  onChange: (patch: { source?: ChartSource | null; spec?: ChartSpec; title?: string }) => void;
+}
+
+function ChartEditor({
+  source,
+  spec,
+  title,
+  tables,
+  notebookId,
+  pageId,
+  readOnly,
+  onChange,
+}: ChartEditorProps) {
+  const [chart, setChart] = useState<ComputedChart | null>(null);
+  const [columns, setColumns] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [showConfig, setShowConfig] = useState(!source || !spec.x);
+
+  // Table sources are read from the saved page, so recompute a moment after
+  // the table changes to give the save time to land.
+  const tableKey =
+    source?.type === "table" ? tables.find((t) => t.id === source.blockId)?.key ?? "" : "";
+
+  useEffect(() => {
+    if (!source || !notebookId || !pageId) return;
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      // Until columns are picked this only fetches the columns to pick from
+      computeChartData(notebookId, pageId, source, spec, title)
+        .then((result) => {
+          if (cancelled) return;
+          setColumns(result.columns);
+          setChart(result.series.length > 0 ? result : null);
+          setError(null);
+        })
+        .catch((e: unknown) => {
+          if (cancelled) return;
+          setChart(null);
+          setError(e instanceof Error ? e.message : String(e));
+        });
+    }, tableKey ? 800 : 0);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [source, spec, title, notebookId, pageId, tableKey]);
+
+  const updateSpec = (patch: Partial<ChartSpec>) => onChange({ spec: { ...spec, ...patch } });
+  const updateFilter = (idx: number, patch: Partial<ChartFilter>) =>
+    updateSpec({ filters: spec.filters.map((f, i) => (i === idx ? { ...f, ...patch } : f)) });
+  const sourceValue = source
+    ? source.type === "table"
+      ? `table:${source.blockId}`
+      : "csv"
+    : "";
+
+  return (
+    <div className="chart-block">
+      <div className="lq-header">
+        <span className="lq-title">
+          Chart
+          {chart && (
+            <span className="lq-count">
+              {chart.rowCount} row{chart.rowCount !== 1 ? "s" : ""}
+            </span>
+          )}
+        </span>
+        {!readOnly && (
+          <div className="lq-header-actions">
+            <button
+              className="lq-config-toggle"
+              onClick={() => setShowConfig((v) => !v)}
+              title="Toggle chart settings"
+            >
+              ⚙
+            </button>
+          </div>
+        )}
+      </div>
+
+      {showConfig && !readOnly && (
+        <div className="lq-config-panel">
+          <div className="lq-sort-row">
+            <span className="lq-sort-label">Title</span>
+            <input
+              type="text"
+              value={title}
+              placeholder="Chart title"
+              onChange={(e) => onChange({ title: e.target.value })}
+            />
+          </div>
+          <div className="lq-sort-row">
+            <span className="lq-sort-label">Data</span>
+            <select
+              value={sourceValue}
+              onChange={(e) => {
+                const value = e.target.value;
+                if (value.startsWith("table:")) {
+                  onChange({ source: { type: "table", blockId: value.slice(6) } });
+                } else if (value === "csv") {
+                  onChange({ source: { type: "csv", asset: "" } });
+                } else {
+                  onChange({ source: null });
+                }
+              }}
+            >
+              <option value="">Choose…</option>
+              {tables.map((t) => (
+                <option key={t.id} value={`table:${t.id}`}>
+                  {t.label}
+                </option>
+              ))}
+              <option value="csv">CSV file in assets</option>
+            </select>
+            {source?.type === "csv" && (
+              <input
+                type="text"
+                value={source.asset}
+                placeholder="data/sales.csv"
+                onChange={(e) => onChange({ source: { type: "csv", asset: e.target.value } })}
+              />
+            )}
+          </div>
+          <div className="lq-sort-row">
+            <span className="lq-sort-label">Type</span>
+            <select
+              value={spec.kind}
+              onChange={(e) => updateSpec({ kind: e.target.value as ChartKind })}
+            >
+              {KINDS.map((k) => (
+                <option key={k} value={k}>
+                  {k}
+                </option>
+              ))}
+            </select>
+            <span className="lq-sort-label">X</span>
+            <select value={spec.x} onChange={(e) => updateSpec({ x: e.target.value })}>
+              <option value="">Column…</option>
+              {columns.map((c) => (
+                <option key={c} value={c}>
+                  {c}
+                </option>
+              ))}
+            </select>
+            <span className="lq-sort-label">Combine</span>
+            <select
+              value={spec.aggregate}
+              onChange={(e) => updateSpec({ aggregate: e.target.value as ChartAggregate })}
+            >
+              {AGGREGATES.map((a) => (
+                <option key={a} value={a}>
+                  {a}
+                </option>
+              ))}
+            </select>
+          </div>
+          {spec.aggregate !== "count" && (
+            <div className="lq-sort-row chart-series">
+              <span className="lq-sort-label">Values</span>
+              {columns
+                .filter((c) => c !== spec.x)
+                .map((c) => (
+                  <label key={c}>
+                    <input
+                      type="checkbox"
+                      checked={spec.y.includes(c)}
+                      onChange={(e) =>
+                        updateSpec({
+                          y: e.target.checked ? [...spec.y, c] : spec.y.filter((y) => y !== c),
+                        })
+                      }
+                    />
+                    {c}
+                  </label>
+                ))}
+            </div>
+          )}
+          <div className="lq-filters">
+            {spec.filters.map((filter, idx) => (
+              <div key={idx} className="lq-filter-row">
+                <select
+                  value={filter.column}
+                  onChange={(e) => updateFilter(idx, { column: e.target.value })}
+                >
+                  {columns.map((c) => (
+                    <option key={c} value={c}>
+                      {c}
+                    </option>
+                  ))}
+                </select>
+                <select
+                  value={filter.op}
+                  onChange={(e) => updateFilter(idx, { op: e.target.value as ChartFilter["op"] })}
+                >
+                  {FILTER_OPS.map((op) => (
+                    <option key={op} value={op}>
+                      {FILTER_OP_LABELS[op]}
+                    </option>
+                  ))}
+                </select>
+                <input
+                  type="text"
+                  value={filter.value}
+                  placeholder="Value..."
+                  onChange={(e) => updateFilter(idx, { value: e.target.value })}
+                />
+                <button
+                  className="lq-filter-remove"
+                  onClick={() =>
+                    updateSpec({ filters: spec.filters.filter((_, i) => i !== idx) })
+                  }
+                >
+                  ×
+                </button>
+              </div>
+            ))}
+            <button
+              className="lq-add-filter"
+              disabled={columns.length === 0}
+              onClick={() =>
+                updateSpec({
+                  filters: [...spec.filters, { column: columns[0], op: "eq", value: "" }],
+                })
+              }
+            >
+              + Add filter
+            </button>
+          </div>
+          <div className="lq-sort-row">
+            <span className="lq-sort-label">Sort</span>
+            <select
+              value={spec.sort ?? ""}
+              onChange={(e) => updateSpec({ sort: (e.target.value || null) as ChartSort | null })}
+            >
+              <option value="">As in data</option>
+              <option value="x">By x</option>
+              <option value="valueAsc">Smallest first</option>
+              <option value="valueDesc">Largest first</option>
+            </select>
+            <span className="lq-sort-label">Limit</span>
+            <input
+              type="number"
+              min={1}
+              value={spec.limit ?? ""}
+              onChange={(e) =>
+                updateSpec({ limit: e.target.value ? Number(e.target.value) : null })
+              }
+            />
+          </div>
+        </div>
+      )}
+
+      {error ? (
+        <div className="chart-error">{error}</div>
+      ) : chart ? (
+        // eslint-disable-next-line react/no-danger -- SVG drawn and escaped by the backend
+        <div className="chart-svg" dangerouslySetInnerHTML={{ __html: chart.svg }} />
+      ) : (
+        <div className="lq-empty">
+          {source ? "Choose the columns to chart" : "Choose a table or CSV file to chart"}
+        </div>
+      )}
+    </div>
+  );
+}
+
+export const ChartBlock = createReactBlockSpec(
+  {
+    type: "chart",
+    propSchema: {
+      title: { default: "" },
+      // Serialized JSON to fit BlockNote's string-only props
+      sourceJson: { default: "" },
+      specJson: { default: "" },
+    },
+    content: "none",
+  },
+  {
+    render: (props) => {
+      const { title, sourceJson, specJson } = props.block.props;
+      const source = useMemo(() => parseJson<ChartSource | null>(sourceJson, null), [sourceJson]);
+      const spec = useMemo(
+        () => ({ ...EMPTY_SPEC, ...parseJson<Partial<ChartSpec>>(specJson, {}) }),
+        [specJson],
+      );
+      const { notebookId, pageId } = getCustomBlockEditorContext(props.editor);
+      const tables = props.editor.document
+        .filter((b) => b.type === "table")
+        .map((b, i) => ({
+          id: b.id,
+          label: `Table ${i + 1}`,
+          key: JSON.stringify(b.content),
+        }));
+
+      return (
+        <div className="chart-block-wrapper" contentEditable={false}>
+          <ChartEditor
+            source={source}
+            spec={spec}
+            title={title}
+            tables={tables}
+            notebookId={notebookId}
+            pageId={pageId}
+            readOnly={!props.editor.isEditable}
+            onChange={(patch) =>
+              props.editor.updateBlock(props.block, {
+                props: {
+                  ...(patch.title !== undefined && { title: patch.title }),
+                  ...(patch.source !== undefined && {
+                    sourceJson: patch.source ? JSON.stringify(patch.source) : "",
+                  }),
+                  ...(patch.spec && { specJson: JSON.stringify(patch.spec) }),
+                },
+              })
+            }
+          />
+        </div>
+      );
+    },
+  },
+);
@@ -1,6 +1,14 @@
 import type { Folder } from "../../types/page";
 import type { PageSortOption } from "../../stores/themeStore";
 
+export const PAGE_SORT_OPTIONS: { value: PageSortOption; label: string }[] = [
+  { value: "position", label: "Manual" },
+  { value: "name-asc", label: "Name (A-Z)" },
+  { value: "name-desc", label: "Name (Z-A)" },
+  { value: "updated", label: "Recently updated" },
+  { value: "created", label: "Recently created" },
+];
+
 // Sort sibling folders with the same option used for pages, so the tree
 // orders consistently. The archive folder always sorts last regardless.
 export function sortFolders(folders: Folder[], sortBy: PageSortOption): Folder[] {
@@ -20,6 +20,7 @@ import { DelimiterBlock } from "./blocks/DelimiterBlock";
 import { FlashcardBlock } from "./blocks/FlashcardBlock";
 import { DatabaseBlock } from "./blocks/DatabaseBlock";
 import { LiveQueryBlock } from "./blocks/LiveQueryBlock";
+import { ChartBlock } from "./blocks/ChartBlock";
 import { BlockEmbedBlock } from "./blocks/BlockEmbedBlock";
 import { EmbedBlock } from "./blocks/EmbedBlock";
 import { PDFBlock } from "./blocks/PDFBlock";
@@ -56,6 +57,7 @@ const baseSchema = BlockNoteSchema.create({
     flashcard: FlashcardBlock(),
     database: DatabaseBlock(),
     liveQuery: LiveQueryBlock(),
+    chart: ChartBlock(),
     blockEmbed: BlockEmbedBlock(),
     embed: EmbedBlock(),
     pdf: PDFBlock(),
@@ -0,0 +1,180 @@
+import { useEffect, useMemo } from "react";
+import { useFlashcardStore } from "../../stores/flashcardStore";
+import type { DailyReviews } from "../../types/flashcard";
+
+interface DeckStatsProps {
+  notebookId: string;
+  deckId?: string;
+}
+
+// The panel is narrow, so the heatmap shows the last 17 weeks
+const HEATMAP_WEEKS = 17;
+
+function heatColor(day: DailyReviews, max: number): string {
+  if (day.reviews === 0) return "var(--color-bg-tertiary)";
+  const level = Math.ceil((day.reviews / Math.max(max, 1)) * 4);
+  return `color-mix(in srgb, var(--color-accent) ${level * 25}%, transparent)`;
+}
+
+function SectionTitle({ children }: { children: React.ReactNode }) {
+  return (
+    <h4
+      className="mb-1.5 text-xs font-medium uppercase tracking-wide"
+      style={{ color: "var(--color-text-muted)" }}
+    >
+      {children}
+    </h4>
+  );
+}
+
+function Bars({
+  values,
+  labels,
+  title,
+}: {
+  values: number[];
+  labels: string[];
+  title: (index: number) => string;
+}) {
+  const max = Math.max(...values, 1);
+  return (
+    <div className="flex h-16 items-end gap-px">
+      {values.map((value, i) => (
+        <div
+          key={labels[i]}
+          className="flex-1 rounded-t"
+          title={title(i)}
+          style={{
+            height: `${Math.max((value / max) * 100, value > 0 ? 4 : 1)}%`,
+            backgroundColor: value > 0 ? "var(--color-accent)" : "var(--color-border)",
+          }}
+        />
+      ))}
+    </div>
+  );
+}
+
+export function DeckStats({ notebookId, deckId }: DeckStatsProps) {
+  const { analytics, leeches, loadAnalytics } = useFlashcardStore();
+
+  useEffect(() => {
+    loadAnalytics(notebookId, deckId);
+  }, [notebookId, deckId, loadAnalytics]);
+
+  const heatmap = useMemo(() => {
+    if (!analytics) return [];
+    const days = analytics.heatmap.slice(-HEATMAP_WEEKS * 7);
+    // Start the first column on a Sunday so rows are weekdays
+    const firstWeekday = days.length ? new Date(`${days[0].date}T00:00`).getDay() : 0;
+    return [...Array<DailyReviews | null>(firstWeekday).fill(null), ...days];
+  }, [analytics]);
+
+  if (!analytics) return null;
+
+  const maxReviews = Math.max(...heatmap.map((d) => d?.reviews ?? 0));
+  const totalReviews = analytics.heatmap.reduce((sum, d) => sum + d.reviews, 0);
+  const totalCorrect = analytics.heatmap.reduce((sum, d) => sum + d.correct, 0);
+  const dueThisWeek = analytics.workload.slice(0, 7).reduce((sum, d) => sum + d.due, 0);
+
+  return (
+    <div className="space-y-4 px-4 py-3 text-sm" style={{ color: "var(--color-text-primary)" }}>
+      <div>
+        <SectionTitle>Reviews</SectionTitle>
+        <div className="grid grid-flow-col grid-rows-7 gap-0.5" style={{ width: "fit-content" }}>
+          {heatmap.map((day, i) =>
+            day ? (
+              <div
+                key={day.date}
+                className="h-3 w-3 rounded-sm"
+                title={`${day.date}: ${day.reviews} reviews, ${day.correct} correct`}
+                style={{ backgroundColor: heatColor(day, maxReviews) }}
+              />
+            ) : (
+              <div key={`pad-${i}`} className="h-3 w-3" />
+            )
+          )}
+        </div>
+        <p className="mt-1 text-xs" style={{ color: "var(--color-text-muted)" }}>
+          {totalReviews} reviews in the last year
+          {totalReviews > 0 && `, ${Math.round((totalCorrect / totalReviews) * 100)}% correct`}
+        </p>
+      </div>
+
+      <div>
+        <SectionTitle>Retention by card age</SectionTitle>
+        <div className="space-y-1">
+          {analytics.retentionByAge.map((bucket) => (
+            <div key={bucket.label} className="flex items-center gap-2 text-xs">
+              <span className="w-24 shrink-0" style={{ color: "var(--color-text-secondary)" }}>
+                {bucket.label}
+              </span>
+              <div
+                className="h-2 flex-1 overflow-hidden rounded"
+                style={{ backgroundColor: "var(--color-bg-tertiary)" }}
+              >
+                <div
+                  className="h-full"
+                  style={{
+                    width: `${(bucket.rate ?? 0) * 100}%`,
+                    backgroundColor: "var(--color-accent)",
+                  }}
+                />
+              </div>
+              <span className="w-16 text-right" style={{ color: "var(--color-text-muted)" }}>
+                {bucket.rate === undefined
+                  ? "–"
+                  : `${Math.round(bucket.rate * 100)}% (${bucket.reviews})`}
+              </span>
+            </div>
+          ))}
+        </div>
+      </div>
+
+      <div>
+        <SectionTitle>Ease</SectionTitle>
+        <Bars
+          values={analytics.easeDistribution.map((b) => b.cards)}
+          labels={analytics.easeDistribution.map((b) => b.min.toFixed(1))}
+          title={(i) => {
+            const bucket = analytics.easeDistribution[i];
+            const last = i === analytics.easeDistribution.length - 1;
+            return `${bucket.min.toFixed(1)}${last ? "+" : `–${(bucket.min + 0.2).toFixed(1)}`}: ${bucket.cards} cards`;
+          }}
+        />
+      </div>
+
+      <div>
+        <SectionTitle>Next 30 days</SectionTitle>
+        <Bars
+          values={analytics.workload.map((d) => d.due)}
+          labels={analytics.workload.map((d) => d.date)}
+          title={(i) => `${analytics.workload[i].date}: ${analytics.workload[i].due} due`}
+        />
+        <p className="mt-1 text-xs" style={{ color: "var(--color-text-muted)" }}>
+          {analytics.workload[0]?.due ?? 0} due today, {dueThisWeek} this week
+        </p>
+      </div>
+
+      {leeches.length > 0 && (
+        <div>
+          <SectionTitle>Leeches ({analytics.leechCount})</SectionTitle>
+          <ul className="space-y-1">
+            {leeches.slice(0, 10).map((leech) => (
+              <li
+                key={leech.card.id}
+                className="flex items-center justify-between gap-2 text-xs"
+              >
+                <span className="truncate" title={leech.card.front}>
+                  {leech.card.front}
+                </span>
+                <span className="shrink-0" style={{ color: "var(--color-error, #ef4444)" }}>
+                  {leech.lapses} lapses
+                </span>
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -7,6 +7,8 @@ import { DeckDialog } from "./DeckDialog";
 import { CardEditor } from "./CardEditor";
 import { ReviewMode } from "./ReviewMode";
 import { ReviewPanel } from "./ReviewPanel";
+import { DeckStats } from "./DeckStats";
+import { SharedDecksDialog } from "./SharedDecksDialog";
 import { FlashcardGenerator } from "../StudyTools/FlashcardGenerator";
 import type { Deck, Flashcard } from "../../types/flashcard";
 import type { StudyPageContent } from "../../types/studyTools";
@@ -43,6 +45,8 @@ export function FlashcardPanel() {
   const [reviewDeckName, setReviewDeckName] = useState<string | undefined>();
   const [showAIGenerator, setShowAIGenerator] = useState(false);
   const [aiGeneratorDeckId, setAiGeneratorDeckId] = useState<string | null>(null);
+  const [showStats, setShowStats] = useState(false);
+  const [showSharedDecks, setShowSharedDecks] = useState(false);
 
   // Get current page for AI generation
   const { selectedPageId, pages } = usePageStore();
@@ -287,25 +291,45 @@ export function FlashcardPanel() {
             >
               Flashcards
             </h2>
-            <button
-              onClick={closePanel}
-              className="p-1 rounded hover:bg-[--color-bg-tertiary]"
-              style={{ color: "var(--color-text-muted)" }}
-            >
-              <svg
-                xmlns="http://www.w3.org/2000/svg"
-                width="16"
-                height="16"
-                viewBox="0 0 24 24"
-                fill="none"
-                stroke="currentColor"
-                strokeWidth="2"
-                strokeLinecap="round"
-                strokeLinejoin="round"
+            <div className="flex items-center gap-1">
+              <button
+                onClick={() => setShowStats((show) => !show)}
+                className="rounded px-2 py-0.5 text-xs hover:bg-[--color-bg-tertiary]"
+                style={{
+                  color: showStats ? "var(--color-accent)" : "var(--color-text-muted)",
+                }}
+                title={showStats ? "Hide statistics" : "Show statistics for the selected deck"}
+              >
+                Stats
+              </button>
+              <button
+                onClick={() => setShowSharedDecks(true)}
+                className="rounded px-2 py-0.5 text-xs hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-muted)" }}
+                title="Share decks and import decks others have shared"
               >
-                <path d="M18 6 6 18M6 6l12 12" />
-              </svg>
-            </button>
+                Shared
+              </button>
+              <button
+                onClick={closePanel}
+                className="p-1 rounded hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-muted)" }}
+              >
+                <svg
+                  xmlns="http://www.w3.org/2000/svg"
+                  width="16"
+                  height="16"
+                  viewBox="0 0 24 24"
+                  fill="none"
+                  stroke="currentColor"
+                  strokeWidth="2"
+                  strokeLinecap="round"
+                  strokeLinejoin="round"
+                >
+                  <path d="M18 6 6 18M6 6l12 12" />
+                </svg>
+              </button>
+            </div>
           </div>
 
           {/* Deck manager */}
@@ -319,9 +343,29 @@ export function FlashcardPanel() {
               onAIGenerate={handleOpenAIGenerator}
             />
           </div>
+
+          {/* Deck statistics */}
+          {showStats && (
+            <div
+              className="max-h-[50%] overflow-y-auto border-t"
+              style={{ borderColor: "var(--color-border)" }}
+            >
+              <DeckStats
+                notebookId={selectedNotebookId}
+                deckId={selectedDeckId ?? undefined}
+              />
+            </div>
+          )}
         </div>
       )}
 
+      {/* Shared decks */}
+      <SharedDecksDialog
+        isOpen={showSharedDecks}
+        notebookId={selectedNotebookId}
+        onClose={() => setShowSharedDecks(false)}
+      />
+
       {/* Deck dialog */}
       <DeckDialog
         isOpen={showDeckDialog}
@@ -0,0 +1,368 @@
+import { useState, useEffect, useCallback } from "react";
+import { open, save } from "../../platform/dialog";
+import { listen } from "../../platform/event";
+import { useFlashcardStore } from "../../stores/flashcardStore";
+import { useToastStore } from "../../stores/toastStore";
+import * as api from "../../utils/flashcardApi";
+import type {
+  DeckBundleInfo,
+  DeckImportResult,
+  ImportCollision,
+} from "../../types/flashcard";
+
+interface SharedDecksDialogProps {
+  isOpen: boolean;
+  notebookId: string;
+  onClose: () => void;
+}
+
+const COLLISION_LABELS: Record<ImportCollision, string> = {
+  merge: "Merge into existing",
+  copy: "Import as a copy",
+  replace: "Replace existing",
+  skip: "Skip",
+};
+
+function defaultCollision(info: DeckBundleInfo): ImportCollision {
+  // Merging only makes sense for the same deck; a deck that merely shares
+  // the name is kept apart
+  return info.existingDeck?.sameDeck ? "merge" : "copy";
+}
+
+function describeImport(name: string, result: DeckImportResult): string {
+  if (!result.deck) return `Skipped "${name}"`;
+  const parts = [`${result.added} added`];
+  if (result.updated) parts.push(`${result.updated} updated`);
+  if (result.unchanged) parts.push(`${result.unchanged} unchanged`);
+  if (result.mediaRenamed) parts.push(`${result.mediaRenamed} media renamed`);
+  return `Imported "${result.deck.name}": ${parts.join(", ")}`;
+}
+
+export function SharedDecksDialog({ isOpen, notebookId, onClose }: SharedDecksDialogProps) {
+  const { decks, loadDecks, loadStats } = useFlashcardStore();
+  const toast = useToastStore();
+
+  const [folder, setFolder] = useState("");
+  const [bundles, setBundles] = useState<DeckBundleInfo[]>([]);
+  const [collisions, setCollisions] = useState<Record<string, ImportCollision>>({});
+  const [exportDeckId, setExportDeckId] = useState("");
+  const [includeScheduling, setIncludeScheduling] = useState(false);
+  const [withScheduling, setWithScheduling] = useState(true);
+  const [busy, setBusy] = useState(false);
+
+  const refresh = useCallback(async () => {
+    try {
+      const [folder, bundles] = await Promise.all([
+        api.getSharedDecksFolder(),
+        api.listSharedDecks(notebookId),
+      ]);
+      setFolder(folder);
+      setBundles(bundles);
+    } catch (err) {
+      // Not via the hook: a toast would otherwise re-trigger the refresh
+      useToastStore.getState().error(`Failed to load shared decks: ${err}`);
+    }
+  }, [notebookId]);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    refresh();
+    const unlisten = listen("shared-decks-changed", () => {
+      refresh();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isOpen, refresh]);
+
+  useEffect(() => {
+    if (!decks.some((d) => d.id === exportDeckId)) {
+      setExportDeckId(decks[0]?.id ?? "");
+    }
+  }, [decks, exportDeckId]);
+
+  const handleChangeFolder = async (reset: boolean) => {
+    let path: string | null = null;
+    if (!reset) {
+      const picked = await open({ directory: true, title: "Shared decks folder" });
+      if (typeof picked !== "string") return;
+      path = picked;
+    }
+    try {
+      setFolder(await api.setSharedDecksFolder(path));
+      setBundles(await api.listSharedDecks(notebookId));
+    } catch (err) {
+      toast.error(`Failed to change folder: ${err}`);
+    }
+  };
+
+  const handleExport = async (toFile: boolean) => {
+    const deck = decks.find((d) => d.id === exportDeckId);
+    if (!deck) return;
+    let path: string | undefined;
+    if (toFile) {
+      const picked = await save({
+        defaultPath: `${deck.name}.nousdeck`,
+        filters: [{ name: "Nous deck", extensions: ["nousdeck"] }],
+      });
+      if (!picked) return;
+      path = picked;
+    }
+    setBusy(true);
+    try {
+      const info = await api.exportDeckBundle(notebookId, deck.id, {
+        path,
+        includeScheduling,
+      });
+      toast.success(
+        toFile
+          ? `Exported "${deck.name}" (${info.cardCount} cards)`
+          : `Shared "${deck.name}" as ${info.fileName}`
+      );
+      if (!toFile) await refresh();
+    } catch (err) {
+      toast.error(`Export failed: ${err}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleOpenFile = async () => {
+    const picked = await open({
+      filters: [{ name: "Nous deck", extensions: ["nousdeck"] }],
+    });
+    if (typeof picked !== "string") return;
+    try {
+      const info = await api.inspectDeckBundle(notebookId, picked);
+      setBundles((current) => [info, ...current.filter((b) => b.path !== info.path)]);
+    } catch (err) {
+      toast.error(`Not a deck bundle: ${err}`);
+    }
+  };
+
+  const handleImport = async (info: DeckBundleInfo) => {
+    setBusy(true);
+    try {
+      const result = await api.importDeckBundle(notebookId, info.path, {
+        collision: collisions[info.path] ?? defaultCollision(info),
+        withScheduling,
+      });
+      toast.success(describeImport(info.name, result));
+      await loadDecks(notebookId);
+      loadStats(notebookId);
+      await refresh();
+    } catch (err) {
+      toast.error(`Import failed: ${err}`);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (!isOpen) return null;
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center p-4"
+      style={{ backgroundColor: "rgba(0, 0, 0, 0.5)" }}
+      onClick={(e) => {
+        if (e.target === e.currentTarget) onClose();
+      }}
+    >
+      <div
+        className="flex max-h-[85vh] w-full max-w-lg flex-col rounded-xl p-6 shadow-xl"
+        style={{
+          backgroundColor: "var(--color-bg-secondary)",
+          border: "1px solid var(--color-border)",
+          color: "var(--color-text-primary)",
+        }}
+      >
+        {/* Header */}
+        <div className="mb-4 flex items-center justify-between">
+          <h2 className="text-lg font-semibold">Shared Decks</h2>
+          <button
+            onClick={onClose}
+            className="flex h-8 w-8 items-center justify-center rounded-lg transition-colors hover:bg-[--color-bg-tertiary]"
+            style={{ color: "var(--color-text-muted)" }}
+          >
+            <svg
+              xmlns="http://www.w3.org/2000/svg"
+              width="16"
+              height="16"
+              viewBox="0 0 24 24"
+              fill="none"
+              stroke="currentColor"
+              strokeWidth="2"
+              strokeLinecap="round"
+              strokeLinejoin="round"
+            >
+              <path d="M18 6 6 18M6 6l12 12" />
+            </svg>
+          </button>
+        </div>
+
+        {/* Folder */}
+        <div className="mb-4 text-xs" style={{ color: "var(--color-text-muted)" }}>
+          <div className="truncate" title={folder}>
+            Folder: {folder || "…"}
+          </div>
+          <div className="mt-1 flex gap-3">
+            <button className="hover:underline" onClick={() => handleChangeFolder(false)}>
+              Change…
+            </button>
+            <button className="hover:underline" onClick={() => handleChangeFolder(true)}>
+              Use default
+            </button>
+            <button className="hover:underline" onClick={handleOpenFile}>
+              Open bundle file…
+            </button>
+          </div>
+        </div>
+
+        {/* Export */}
+        <div
+          className="mb-4 space-y-2 rounded-lg border p-3 text-sm"
+          style={{ borderColor: "var(--color-border)" }}
+        >
+          <div className="flex gap-2">
+            <select
+              value={exportDeckId}
+              onChange={(e) => setExportDeckId(e.target.value)}
+              className="flex-1 rounded-lg border px-2 py-1.5 text-sm"
+              style={{
+                backgroundColor: "var(--color-bg-primary)",
+                borderColor: "var(--color-border)",
+                color: "var(--color-text-primary)",
+              }}
+            >
+              {decks.map((deck) => (
+                <option key={deck.id} value={deck.id}>
+                  {deck.name} ({deck.cardCount})
+                </option>
+              ))}
+            </select>
+            <button
+              onClick={() => handleExport(false)}
+              disabled={busy || !exportDeckId}
+              className="rounded-lg px-3 py-1.5 text-sm font-medium text-white disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)" }}
+            >
+              Share
+            </button>
+            <button
+              onClick={() => handleExport(true)}
+              disabled={busy || !exportDeckId}
+              className="rounded-lg border px-3 py-1.5 text-sm disabled:opacity-50"
+              style={{ borderColor: "var(--color-border)" }}
+            >
+              Export…
+            </button>
+          </div>
+          <label className="flex items-center gap-2 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+            <input
+              type="checkbox"
+              checked={includeScheduling}
+              onChange={(e) => setIncludeScheduling(e.target.checked)}
+            />
+            Include my review scheduling
+          </label>
+        </div>
+
+        {/* Bundles */}
+        <div className="flex-1 space-y-2 overflow-y-auto">
+          {bundles.length === 0 && (
+            <p className="py-6 text-center text-sm" style={{ color: "var(--color-text-muted)" }}>
+              No decks in the shared folder yet. Share one, or drop .nousdeck files
+              into the folder.
+            </p>
+          )}
+          {bundles.map((info) => (
+            <div
+              key={info.path}
+              className="rounded-lg border p-3 text-sm"
+              style={{ borderColor: "var(--color-border)" }}
+            >
+              <div className="flex items-start justify-between gap-2">
+                <div className="min-w-0">
+                  <div className="flex items-center gap-2 font-medium">
+                    {info.color && (
+                      <span
+                        className="h-2.5 w-2.5 shrink-0 rounded-full"
+                        style={{ backgroundColor: info.color }}
+                      />
+                    )}
+                    <span className="truncate">{info.name}</span>
+                  </div>
+                  <div className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+                    {info.cardCount} cards
+                    {info.mediaCount > 0 && ` · ${info.mediaCount} media`}
+                    {info.hasScheduling && " · with scheduling"}
+                    {" · "}
+                    {new Date(info.exportedAt).toLocaleDateString()}
+                  </div>
+                  {info.description && (
+                    <div className="mt-1 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+                      {info.description}
+                    </div>
+                  )}
+                </div>
+                <button
+                  onClick={() => handleImport(info)}
+                  disabled={busy}
+                  className="shrink-0 rounded-lg px-3 py-1 text-xs font-medium text-white disabled:opacity-50"
+                  style={{ backgroundColor: "var(--color-accent)" }}
+                >
+                  Import
+                </button>
+              </div>
+              {info.existingDeck && (
+                <div
+                  className="mt-2 flex items-center gap-2 text-xs"
+                  style={{ color: "var(--color-warning, #d97706)" }}
+                >
+                  <span className="flex-1">
+                    {info.existingDeck.sameDeck
+                      ? `Already imported as "${info.existingDeck.name}"`
+                      : `A deck named "${info.existingDeck.name}" exists`}
+                  </span>
+                  <select
+                    value={collisions[info.path] ?? defaultCollision(info)}
+                    onChange={(e) =>
+                      setCollisions((current) => ({
+                        ...current,
+                        [info.path]: e.target.value as ImportCollision,
+                      }))
+                    }
+                    className="rounded border px-1 py-0.5 text-xs"
+                    style={{
+                      backgroundColor: "var(--color-bg-primary)",
+                      borderColor: "var(--color-border)",
+                      color: "var(--color-text-primary)",
+                    }}
+                  >
+                    {(Object.keys(COLLISION_LABELS) as ImportCollision[]).map((mode) => (
+                      <option key={mode} value={mode}>
+                        {COLLISION_LABELS[mode]}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+
+        <label
+          className="mt-3 flex items-center gap-2 text-xs"
+          style={{ color: "var(--color-text-secondary)" }}
+        >
+          <input
+            type="checkbox"
+            checked={withScheduling}
+            onChange={(e) => setWithScheduling(e.target.checked)}
+          />
+          Keep scheduling from bundles that include it
+        </label>
+      </div>
+    </div>
+  );
+}
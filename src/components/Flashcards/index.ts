@@ -4,3 +4,5 @@ export { CardEditor } from "./CardEditor";
 export { ReviewMode } from "./ReviewMode";
 export { ReviewPanel } from "./ReviewPanel";
 export { FlashcardPanel } from "./FlashcardPanel";
+export { DeckStats } from "./DeckStats";
+export { SharedDecksDialog } from "./SharedDecksDialog";
@@ -21,6 +21,7 @@ export function MediaLibrary({ notebookId, isOpen, onClose }: MediaLibraryProps)
   const [selectedPaths, setSelectedPaths] = useState<Set<string>>(new Set());
   const [isBatchMode, setIsBatchMode] = useState(false);
   const [isExporting, setIsExporting] = useState(false);
+  const [regeneratingId, setRegeneratingId] = useState<string | null>(null);
   const toast = useToastStore();
 
   useEffect(() => {
@@ -58,6 +59,42 @@ export function MediaLibrary({ notebookId, isOpen, onClose }: MediaLibraryProps)
     }
   };
 
+  const handleRegenerate = async (asset: MediaAssetInfo) => {
+    if (!asset.mediaId) return;
+    setRegeneratingId(asset.mediaId);
+    try {
+      await api.regenerateMedia(notebookId, asset.mediaId);
+      toast.success(`Regenerated ${asset.mediaType}`);
+      await loadAssets();
+    } catch (error) {
+      toast.error(`Failed to regenerate: ${error}`);
+    } finally {
+      setRegeneratingId(null);
+    }
+  };
+
+  const handleCleanup = async () => {
+    try {
+      const preview = await api.cleanupGeneratedMedia(notebookId, true);
+      if (preview.removedFiles.length === 0 && preview.removedEntries.length === 0) {
+        toast.success("No orphaned media");
+        return;
+      }
+      const count = preview.removedFiles.length;
+      if (
+        !confirm(
+          `Delete ${count} orphaned file${count !== 1 ? "s" : ""} (${formatSize(preview.freedBytes)}) left by deleted pages or failed generations?`
+        )
+      )
+        return;
+      const report = await api.cleanupGeneratedMedia(notebookId);
+      toast.success(`Freed ${formatSize(report.freedBytes)}`);
+      await loadAssets();
+    } catch (error) {
+      toast.error(`Cleanup failed: ${error}`);
+    }
+  };
+
   const toggleSelect = useCallback((path: string) => {
     setSelectedPaths((prev) => {
       const next = new Set(prev);
@@ -253,6 +290,14 @@ export function MediaLibrary({ notebookId, isOpen, onClose }: MediaLibraryProps)
                   : "Select all"}
               </button>
             )}
+            <button
+              onClick={handleCleanup}
+              className="px-2.5 py-1 rounded-lg text-xs font-medium transition-colors hover:bg-[--color-bg-tertiary]"
+              style={{ color: "var(--color-text-muted)" }}
+              title="Delete outputs of deleted pages and unrecorded generator files"
+            >
+              Clean up
+            </button>
             <button
               onClick={() => {
                 setIsBatchMode(!isBatchMode);
@@ -414,6 +459,19 @@ export function MediaLibrary({ notebookId, isOpen, onClose }: MediaLibraryProps)
                       >
                         Export
                       </button>
+                      {asset.mediaId && (
+                        <button
+                          onClick={(e) => {
+                            e.stopPropagation();
+                            handleRegenerate(asset);
+                          }}
+                          disabled={regeneratingId !== null}
+                          className="flex-1 px-2 py-1 rounded text-xs transition-colors hover:bg-[--color-bg-tertiary] disabled:opacity-40"
+                          style={{ color: "var(--color-text-secondary)" }}
+                        >
+                          {regeneratingId === asset.mediaId ? "Regenerating…" : "Regenerate"}
+                        </button>
+                      )}
                       <button
                         onClick={(e) => {
                           e.stopPropagation();
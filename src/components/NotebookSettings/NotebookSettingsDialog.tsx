@@ -28,11 +28,15 @@ import {
   getCoverPage,
   createCoverPage,
   syncUpdateConfig,
+  listAiProfiles,
+  type AiProfile,
   type GitStatus,
   type MergeResult,
 } from "../../utils/api";
 import { InlineColorPicker } from "../ColorPicker/ColorPicker";
 import { GitConflictDialog } from "./GitConflictDialog";
+import { SpacedReviewSettings } from "./SpacedReviewSettings";
+import { SyncDevicesList } from "./SyncDevicesList";
 import { uploadCoverImage } from "../../utils/coverImageUpload";
 
 const AI_PROVIDERS: { value: AIProviderType; label: string }[] = [
@@ -76,6 +80,8 @@ export function NotebookSettingsDialog({
   const [systemPromptMode, setSystemPromptMode] = useState<SystemPromptMode>("override");
   const [aiProvider, setAiProvider] = useState<AIProviderType | undefined>(undefined);
   const [aiModel, setAiModel] = useState<string | undefined>(undefined);
+  const [aiProfileId, setAiProfileId] = useState<string | undefined>(undefined);
+  const [aiProfiles, setAiProfiles] = useState<AiProfile[]>([]);
   const [useAppDefault, setUseAppDefault] = useState(true);
   const [isSaving, setIsSaving] = useState(false);
   const [showDeleteConfirm, setShowDeleteConfirm] = useState(false);
@@ -244,6 +250,10 @@ export function NotebookSettingsDialog({
       setAiProvider(notebook.aiProvider);
       setAiModel(notebook.aiModel);
       setUseAppDefault(!notebook.aiProvider);
+      setAiProfileId(notebook.aiProfileId);
+      listAiProfiles()
+        .then(setAiProfiles)
+        .catch((err) => console.error("Failed to load AI profiles:", err));
       loadGitStatus();
       loadCoverStatus();
       loadSyncStatusData();
@@ -326,6 +336,10 @@ export function NotebookSettingsDialog({
         aiProvider: useAppDefault ? undefined : aiProvider,
         aiModel: useAppDefault ? undefined : aiModel,
       };
+      // Empty string clears the profile on the backend
+      if ((aiProfileId || undefined) !== (notebook.aiProfileId || undefined)) {
+        updates.aiProfileId = aiProfileId || "";
+      }
       // Include coverImage if it changed (empty string clears it on the backend)
       if ((coverImage || undefined) !== (notebook.coverImage || undefined)) {
         updates.coverImage = coverImage || "";
@@ -362,7 +376,8 @@ export function NotebookSettingsDialog({
     (systemPrompt || "") !== (notebook.systemPrompt || "") ||
     systemPromptMode !== (notebook.systemPromptMode || "override") ||
     (useAppDefault ? undefined : aiProvider) !== notebook.aiProvider ||
-    (useAppDefault ? undefined : aiModel) !== notebook.aiModel;
+    (useAppDefault ? undefined : aiModel) !== notebook.aiModel ||
+    (aiProfileId || undefined) !== (notebook.aiProfileId || undefined);
 
   return (
     <div
@@ -659,6 +674,39 @@ export function NotebookSettingsDialog({
               </button>
             </div>
 
+            {aiProfiles.length > 0 && (
+              <div className="mb-3">
+                <label
+                  className="mb-1.5 block text-xs font-medium"
+                  style={{ color: "var(--color-text-secondary)" }}
+                >
+                  Profile
+                </label>
+                <select
+                  value={aiProfileId || ""}
+                  onChange={(e) => setAiProfileId(e.target.value || undefined)}
+                  className="w-full rounded-md border px-2.5 py-1.5 text-sm outline-none transition-colors focus:border-[--color-accent]"
+                  style={{
+                    backgroundColor: "var(--color-bg-tertiary)",
+                    borderColor: "var(--color-border)",
+                    color: "var(--color-text-primary)",
+                  }}
+                >
+                  <option value="">No profile</option>
+                  {aiProfiles.map((profile) => (
+                    <option key={profile.id} value={profile.id}>
+                      {profile.name}
+                    </option>
+                  ))}
+                </select>
+                {aiProfileId && (
+                  <p className="mt-1 text-xs" style={{ color: "var(--color-text-muted)" }}>
+                    The profile's provider, model and prompt take the place of the settings below.
+                  </p>
+                )}
+              </div>
+            )}
+
             {useAppDefault ? (
               <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
                 Using app default: <strong style={{ color: "var(--color-text-secondary)" }}>
@@ -1845,6 +1893,8 @@ export function NotebookSettingsDialog({
                   </div>
                 )}
 
+                <SyncDevicesList notebookId={notebook.id} />
+
                 {/* Library-managed hint */}
                 {notebook.syncConfig.managedByLibrary && (
                   <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
@@ -1866,6 +1916,9 @@ export function NotebookSettingsDialog({
             )}
           </div>
 
+          {/* Spaced Writing Review */}
+          <SpacedReviewSettings notebookId={notebook.id} />
+
           {/* Encryption */}
           <EncryptionSettings
             type="notebook"
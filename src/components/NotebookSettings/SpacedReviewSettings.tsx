@@ -0,0 +1,168 @@
+/**
+ * SpacedReviewSettings - Per-notebook settings for the "Spaced Writing Review"
+ * action, which asks recall questions about old notes every morning
+ */
+
+import { useState, useEffect, useCallback } from "react";
+import {
+  getSpacedReviewSettings,
+  updateSpacedReviewSettings,
+  type NotebookReviewSettings,
+} from "../../utils/api";
+
+const DEFAULT_DECK_NAME = "Spaced Review";
+
+interface SpacedReviewSettingsProps {
+  notebookId: string;
+}
+
+export function SpacedReviewSettings({ notebookId }: SpacedReviewSettingsProps) {
+  const [settings, setSettings] = useState<NotebookReviewSettings | null>(null);
+  const [deckName, setDeckName] = useState(DEFAULT_DECK_NAME);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    getSpacedReviewSettings(notebookId)
+      .then((loaded) => {
+        if (cancelled) return;
+        setSettings(loaded);
+        if (loaded.destination.type === "flashcards") {
+          setDeckName(loaded.destination.deckName);
+        }
+      })
+      .catch((err) => {
+        if (!cancelled) setError(String(err));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [notebookId]);
+
+  const save = useCallback(
+    async (next: NotebookReviewSettings) => {
+      setSettings(next);
+      setError(null);
+      try {
+        setSettings(await updateSpacedReviewSettings(notebookId, next));
+      } catch (err) {
+        setError(String(err));
+      }
+    },
+    [notebookId]
+  );
+
+  if (!settings) return null;
+
+  const selectStyle = {
+    backgroundColor: "var(--color-bg-tertiary)",
+    borderColor: "var(--color-border)",
+    color: "var(--color-text-primary)",
+  };
+
+  return (
+    <div className="rounded-lg border p-4" style={{ borderColor: "var(--color-border)" }}>
+      <label className="flex items-center justify-between cursor-pointer">
+        <span className="text-sm font-medium" style={{ color: "var(--color-text-primary)" }}>
+          Spaced Writing Review
+        </span>
+        <input
+          type="checkbox"
+          checked={settings.enabled}
+          onChange={(e) => save({ ...settings, enabled: e.target.checked })}
+          className="rounded"
+          style={{ accentColor: "var(--color-accent)" }}
+        />
+      </label>
+      <p className="mt-1.5 text-xs" style={{ color: "var(--color-text-muted)" }}>
+        Each morning, old pages due for review get a few AI recall questions. A page
+        isn't asked about again for 30 days.
+      </p>
+
+      {settings.enabled && (
+        <div className="mt-3 space-y-3">
+          <div className="flex items-center justify-between gap-3">
+            <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+              File questions in
+            </span>
+            <select
+              value={settings.destination.type}
+              onChange={(e) =>
+                save({
+                  ...settings,
+                  destination:
+                    e.target.value === "flashcards"
+                      ? { type: "flashcards", deckName }
+                      : { type: "dailyNote" },
+                })
+              }
+              className="rounded-lg border px-2 py-1 text-sm outline-none"
+              style={selectStyle}
+            >
+              <option value="dailyNote">Today's daily note</option>
+              <option value="flashcards">A flashcard deck</option>
+            </select>
+          </div>
+
+          {settings.destination.type === "flashcards" && (
+            <div className="flex items-center justify-between gap-3">
+              <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+                Deck
+              </span>
+              <input
+                type="text"
+                value={deckName}
+                onChange={(e) => setDeckName(e.target.value)}
+                onBlur={() => save({ ...settings, destination: { type: "flashcards", deckName } })}
+                className="w-48 rounded-lg border px-2 py-1 text-sm outline-none"
+                style={selectStyle}
+              />
+            </div>
+          )}
+
+          <div className="flex items-center justify-between gap-3">
+            <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+              Questions per page
+            </span>
+            <select
+              value={settings.questionsPerPage}
+              onChange={(e) => save({ ...settings, questionsPerPage: Number(e.target.value) })}
+              className="rounded-lg border px-2 py-1 text-sm outline-none"
+              style={selectStyle}
+            >
+              {[1, 2, 3, 4, 5].map((n) => (
+                <option key={n} value={n}>
+                  {n}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="flex items-center justify-between gap-3">
+            <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+              Pages per day
+            </span>
+            <select
+              value={settings.pagesPerRun}
+              onChange={(e) => save({ ...settings, pagesPerRun: Number(e.target.value) })}
+              className="rounded-lg border px-2 py-1 text-sm outline-none"
+              style={selectStyle}
+            >
+              {[1, 2, 3, 5, 10].map((n) => (
+                <option key={n} value={n}>
+                  {n}
+                </option>
+              ))}
+            </select>
+          </div>
+        </div>
+      )}
+
+      {error && (
+        <p className="mt-2 text-xs" style={{ color: "var(--color-error)" }}>
+          {error}
+        </p>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,118 @@
+/**
+ * SyncDevicesList - Devices syncing a notebook, from the registry on the
+ * sync server, with a way to revoke ones the user no longer trusts
+ */
+
+import { useState, useEffect, useCallback } from "react";
+import {
+  listSyncDevices,
+  revokeSyncDevice,
+  type SyncDeviceInfo,
+} from "../../utils/api";
+
+const PLATFORM_LABELS: Record<string, string> = {
+  macos: "macOS",
+  windows: "Windows",
+  linux: "Linux",
+  ios: "iOS",
+  android: "Android",
+};
+
+interface SyncDevicesListProps {
+  notebookId: string;
+}
+
+export function SyncDevicesList({ notebookId }: SyncDevicesListProps) {
+  const [devices, setDevices] = useState<SyncDeviceInfo[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [revoking, setRevoking] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    try {
+      setDevices(await listSyncDevices(notebookId));
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+    }
+  }, [notebookId]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  const revoke = async (device: SyncDeviceInfo) => {
+    if (
+      !confirm(
+        `Revoke "${device.name}"? It will stop syncing this notebook, and anything it pushes from now on will be ignored.`
+      )
+    ) {
+      return;
+    }
+    setRevoking(device.clientId);
+    try {
+      await revokeSyncDevice(notebookId, device.clientId);
+      await load();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setRevoking(null);
+    }
+  };
+
+  if (!devices && !error) return null;
+
+  return (
+    <div className="space-y-1.5">
+      <div className="text-xs font-medium" style={{ color: "var(--color-text-secondary)" }}>
+        Devices
+      </div>
+      {devices?.length === 0 && (
+        <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+          No devices have synced yet.
+        </p>
+      )}
+      {devices?.map((device) => (
+        <div
+          key={device.clientId}
+          className="flex items-center justify-between rounded-md px-2.5 py-1.5 text-xs"
+          style={{
+            backgroundColor: "var(--color-bg-tertiary)",
+            opacity: device.revokedAt ? 0.6 : 1,
+          }}
+        >
+          <div className="min-w-0">
+            <div className="truncate" style={{ color: "var(--color-text-primary)" }}>
+              {device.name}
+              {device.thisDevice && (
+                <span style={{ color: "var(--color-text-muted)" }}> (this device)</span>
+              )}
+            </div>
+            <div style={{ color: "var(--color-text-muted)" }}>
+              {PLATFORM_LABELS[device.platform] ?? device.platform} · last sync{" "}
+              {new Date(device.lastSync).toLocaleString()}
+            </div>
+          </div>
+          {device.revokedAt ? (
+            <span style={{ color: "var(--color-error)" }}>Revoked</span>
+          ) : (
+            !device.thisDevice && (
+              <button
+                onClick={() => revoke(device)}
+                disabled={revoking === device.clientId}
+                className="flex-shrink-0 rounded px-2 py-0.5 transition-colors hover:opacity-80 disabled:opacity-50"
+                style={{ color: "var(--color-error)" }}
+              >
+                {revoking === device.clientId ? "Revoking..." : "Revoke"}
+              </button>
+            )
+          )}
+        </div>
+      ))}
+      {error && (
+        <p className="text-xs" style={{ color: "var(--color-error)" }}>
+          {error}
+        </p>
+      )}
+    </div>
+  );
+}
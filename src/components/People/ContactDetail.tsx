@@ -1,4 +1,6 @@
 import type { Contact, ContactActivity } from "../../types/contact";
+import { useNotebookStore } from "../../stores/notebookStore";
+import { usePageStore } from "../../stores/pageStore";
 
 interface ContactDetailProps {
   contact: Contact;
@@ -70,11 +72,26 @@ function ActivityIcon({ type }: { type: string }) {
           <rect x="1" y="5" width="15" height="14" rx="2" ry="2" />
         </svg>
       );
+    case "meeting":
+      return (
+        <svg xmlns="http://www.w3.org/2000/svg" width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round" style={iconStyle}>
+          <path d="M17 21v-2a4 4 0 0 0-4-4H5a4 4 0 0 0-4 4v2" />
+          <circle cx="9" cy="7" r="4" />
+          <path d="M23 21v-2a4 4 0 0 0-3-3.87" />
+          <path d="M16 3.13a4 4 0 0 1 0 7.75" />
+        </svg>
+      );
     default:
       return null;
   }
 }
 
+function openMeetingPage(activity: ContactActivity) {
+  if (!activity.pageId || !activity.notebookId) return;
+  useNotebookStore.getState().selectNotebook(activity.notebookId);
+  usePageStore.getState().selectPage(activity.pageId);
+}
+
 function DirectionArrow({ direction }: { direction: string }) {
   if (direction === "outgoing") {
     return (
@@ -194,10 +211,14 @@ export function ContactDetail({
             {activities.map((activity) => (
               <div
                 key={activity.id}
-                className="flex items-start gap-2.5 px-4 py-2.5"
+                className={`flex items-start gap-2.5 px-4 py-2.5${activity.pageId ? " cursor-pointer hover:bg-[--color-bg-tertiary]" : ""}`}
+                onClick={activity.pageId ? () => openMeetingPage(activity) : undefined}
+                title={activity.pageId ? "Open meeting notes" : undefined}
               >
                 <div className="mt-0.5 flex items-center gap-1">
-                  <DirectionArrow direction={activity.direction} />
+                  {activity.activityType !== "meeting" && (
+                    <DirectionArrow direction={activity.direction} />
+                  )}
                   <ActivityIcon type={activity.activityType} />
                 </div>
                 <div className="min-w-0 flex-1">
@@ -2,8 +2,18 @@ import { useState, useEffect, useCallback } from "react";
 import { save } from "../../platform/dialog";
 import { writeTextFile } from "../../platform/fs";
 import { useToastStore } from "../../stores/toastStore";
+import { useAIStore } from "../../stores/aiStore";
 import type { Page } from "../../types/page";
-import { generatePresentation } from "./api";
+import {
+  exportPresentationPptx,
+  generatePresentation,
+  getPresentationSlides,
+  savePresentationSlideEdits,
+  type NotesSource,
+  type PptxSlide,
+  type PptxTemplate,
+  type SlideEdit,
+} from "./api";
 
 interface PresentationDialogProps {
   isOpen: boolean;
@@ -21,6 +31,24 @@ const THEMES = [
 
 const TRANSITIONS = ["slide", "fade", "convex", "none"] as const;
 
+const PPTX_TEMPLATES: { id: PptxTemplate; label: string }[] = [
+  { id: "standard", label: "Standard" },
+  { id: "accent_bar", label: "Accent bar" },
+  { id: "sections", label: "Section dividers" },
+];
+
+const NOTES_SOURCES: { id: NotesSource; label: string }[] = [
+  { id: "content", label: "Page content" },
+  { id: "ai", label: "AI talking points" },
+  { id: "none", label: "No notes" },
+];
+
+const fieldStyle = {
+  backgroundColor: "var(--color-bg-secondary)",
+  borderColor: "var(--color-border)",
+  color: "var(--color-text-primary)",
+};
+
 export function PresentationDialog({
   isOpen,
   onClose,
@@ -31,15 +59,116 @@ export function PresentationDialog({
   const [presentationHtml, setPresentationHtml] = useState<string | null>(null);
   const [isPresenting, setIsPresenting] = useState(false);
   const [isGenerating, setIsGenerating] = useState(false);
+  const [template, setTemplate] = useState<PptxTemplate>("standard");
+  const [notesSource, setNotesSource] = useState<NotesSource>("content");
+  const [slides, setSlides] = useState<PptxSlide[] | null>(null);
+  const [isExportingPptx, setIsExportingPptx] = useState(false);
   const toast = useToastStore();
 
   useEffect(() => {
     if (isOpen) {
       setPresentationHtml(null);
       setIsPresenting(false);
+      setSlides(null);
     }
   }, [isOpen]);
 
+  const loadSlides = useCallback(async () => {
+    try {
+      setSlides(
+        await getPresentationSlides(page.notebookId, page.id, {
+          theme,
+          template,
+          notes: notesSource,
+        })
+      );
+    } catch (err) {
+      toast.error("Failed to load slides: " + String(err));
+    }
+  }, [page.notebookId, page.id, theme, template, notesSource, toast]);
+
+  // Edits are kept per slide, so only the changed slides go to the sidecar
+  const saveEdits = async (next: PptxSlide[]) => {
+    const edits: Record<string, SlideEdit> = {};
+    for (const slide of next) {
+      if (slide.edit) edits[slide.key] = slide.edit;
+    }
+    try {
+      await savePresentationSlideEdits(page.notebookId, page.id, edits);
+    } catch (err) {
+      toast.error("Failed to save slide edits: " + String(err));
+    }
+  };
+
+  const updateSlide = (key: string, change: Partial<SlideEdit>) => {
+    if (!slides) return;
+    const next = slides.map((slide) => {
+      if (slide.key !== key) return slide;
+      const edit: SlideEdit = {
+        hidden: slide.hidden,
+        ...slide.edit,
+        ...change,
+      };
+      return {
+        ...slide,
+        title: edit.title ?? slide.title,
+        bullets: edit.bullets ?? slide.bullets,
+        notes: edit.notes ?? slide.notes,
+        hidden: edit.hidden,
+        edit,
+      };
+    });
+    setSlides(next);
+    void saveEdits(next);
+  };
+
+  const resetSlide = async (key: string) => {
+    if (!slides) return;
+    await saveEdits(
+      slides.map((slide) =>
+        slide.key === key ? { ...slide, edit: undefined } : slide
+      )
+    );
+    await loadSlides();
+  };
+
+  const handleExportPptx = async () => {
+    const path = await save({
+      defaultPath: `${page.title || "presentation"}.pptx`,
+      filters: [{ name: "PowerPoint", extensions: ["pptx"] }],
+    });
+    if (!path) return;
+
+    setIsExportingPptx(true);
+    try {
+      const ai = useAIStore.getState();
+      const result = await exportPresentationPptx(
+        page.notebookId,
+        page.id,
+        path,
+        { theme, template, notes: notesSource },
+        notesSource === "ai"
+          ? {
+              providerType: ai.getActiveProviderType(),
+              apiKey: ai.getActiveApiKey() || undefined,
+              model: ai.getActiveModel(),
+            }
+          : undefined
+      );
+      toast.success(
+        `Exported ${result.slides} slides` +
+          (result.editedSlides > 0
+            ? ` (${result.editedSlides} with your edits)`
+            : "")
+      );
+      if (slides) await loadSlides();
+    } catch (err) {
+      toast.error("Failed to export PPTX: " + String(err));
+    } finally {
+      setIsExportingPptx(false);
+    }
+  };
+
   const handleGenerate = useCallback(async () => {
     setIsGenerating(true);
     try {
@@ -162,7 +291,9 @@ export function PresentationDialog({
     >
       <div className="absolute inset-0 bg-black/50 backdrop-blur-sm" />
       <div
-        className="relative w-full max-w-md rounded-xl border p-6 shadow-2xl"
+        className={`relative w-full ${
+          slides ? "max-w-2xl" : "max-w-md"
+        } max-h-[90vh] overflow-y-auto rounded-xl border p-6 shadow-2xl`}
         style={{
           backgroundColor: "var(--color-bg-panel)",
           borderColor: "var(--color-border)",
@@ -281,6 +412,148 @@ export function PresentationDialog({
           or space to navigate.
         </p>
 
+        {/* PPTX export */}
+        <div className="grid grid-cols-2 gap-3 mb-3">
+          <div>
+            <label
+              className="block text-sm font-medium mb-2"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              PPTX template
+            </label>
+            <select
+              value={template}
+              onChange={(e) => setTemplate(e.target.value as PptxTemplate)}
+              className="w-full rounded-lg border px-3 py-2 text-sm outline-none"
+              style={fieldStyle}
+            >
+              {PPTX_TEMPLATES.map((t) => (
+                <option key={t.id} value={t.id}>
+                  {t.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label
+              className="block text-sm font-medium mb-2"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              Speaker notes
+            </label>
+            <select
+              value={notesSource}
+              onChange={(e) => {
+                setNotesSource(e.target.value as NotesSource);
+                setSlides(null);
+              }}
+              className="w-full rounded-lg border px-3 py-2 text-sm outline-none"
+              style={fieldStyle}
+            >
+              {NOTES_SOURCES.map((n) => (
+                <option key={n.id} value={n.id}>
+                  {n.label}
+                </option>
+              ))}
+            </select>
+          </div>
+        </div>
+
+        <button
+          onClick={() => (slides ? setSlides(null) : void loadSlides())}
+          className="text-xs mb-3 hover:underline"
+          style={{ color: "var(--color-accent)" }}
+        >
+          {slides ? "Hide slides" : "Edit slides for PPTX..."}
+        </button>
+
+        {slides && (
+          <div className="space-y-3 mb-4">
+            <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+              Changes here only affect the PPTX export and are kept for the
+              next one. Slides stay matched to the page by their heading.
+            </p>
+            {slides.map((slide, i) => (
+              <div
+                key={slide.key}
+                className="rounded-lg border p-3 space-y-2"
+                style={{
+                  borderColor: slide.edit
+                    ? "var(--color-accent)"
+                    : "var(--color-border)",
+                  opacity: slide.hidden ? 0.5 : 1,
+                }}
+              >
+                <div className="flex items-center gap-2">
+                  <span
+                    className="text-xs"
+                    style={{ color: "var(--color-text-muted)" }}
+                  >
+                    {i + 1}
+                  </span>
+                  <input
+                    value={slide.title}
+                    onChange={(e) =>
+                      updateSlide(slide.key, { title: e.target.value })
+                    }
+                    className="flex-1 rounded border px-2 py-1 text-sm outline-none"
+                    style={fieldStyle}
+                  />
+                  <label
+                    className="flex items-center gap-1 text-xs"
+                    style={{ color: "var(--color-text-muted)" }}
+                  >
+                    <input
+                      type="checkbox"
+                      checked={slide.hidden}
+                      onChange={(e) =>
+                        updateSlide(slide.key, { hidden: e.target.checked })
+                      }
+                    />
+                    Hide
+                  </label>
+                  {slide.edit && (
+                    <button
+                      onClick={() => void resetSlide(slide.key)}
+                      className="text-xs hover:underline"
+                      style={{ color: "var(--color-text-muted)" }}
+                      title="Use the page's version of this slide again"
+                    >
+                      Reset
+                    </button>
+                  )}
+                </div>
+                <textarea
+                  value={slide.bullets.join("\n")}
+                  onChange={(e) =>
+                    updateSlide(slide.key, {
+                      bullets: e.target.value.split("\n"),
+                    })
+                  }
+                  rows={Math.min(6, Math.max(2, slide.bullets.length))}
+                  placeholder="One bullet per line"
+                  className="w-full rounded border px-2 py-1 text-xs outline-none"
+                  style={fieldStyle}
+                />
+                <textarea
+                  value={slide.notes}
+                  onChange={(e) =>
+                    updateSlide(slide.key, { notes: e.target.value })
+                  }
+                  rows={2}
+                  placeholder={
+                    notesSource === "ai"
+                      ? "Talking points are written on export"
+                      : "Speaker notes"
+                  }
+                  className="w-full rounded border px-2 py-1 text-xs outline-none"
+                  style={fieldStyle}
+                />
+              </div>
+            ))}
+          </div>
+        )}
+
         {/* Action buttons */}
         <div className="flex justify-end gap-2">
           <button
@@ -294,6 +567,17 @@ export function PresentationDialog({
           >
             Export HTML
           </button>
+          <button
+            onClick={handleExportPptx}
+            disabled={isGenerating || isExportingPptx}
+            className="rounded-lg px-4 py-2 text-sm font-medium transition-colors hover:opacity-80"
+            style={{
+              backgroundColor: "var(--color-bg-tertiary)",
+              color: "var(--color-text-secondary)",
+            }}
+          >
+            {isExportingPptx ? "Exporting..." : "Export PPTX"}
+          </button>
           <button
             onClick={handlePresent}
             disabled={isGenerating}
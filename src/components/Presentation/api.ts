@@ -16,3 +16,76 @@ export async function generatePresentation(
     options,
   });
 }
+
+export type PptxTemplate = "standard" | "accent_bar" | "sections";
+export type NotesSource = "none" | "content" | "ai";
+
+export interface PptxOptions {
+  theme: string;
+  template: PptxTemplate;
+  notes: NotesSource;
+}
+
+export interface PptxSlide {
+  key: string;
+  title: string;
+  bullets: string[];
+  notes: string;
+  hidden: boolean;
+  edited: boolean;
+}
+
+export interface SlideEdit {
+  title?: string;
+  bullets?: string[];
+  notes?: string;
+  hidden: boolean;
+}
+
+export interface PptxExportResult {
+  path: string;
+  slides: number;
+  editedSlides: number;
+}
+
+export async function getPresentationSlides(
+  notebookId: string,
+  pageId: string,
+  options: PptxOptions
+): Promise<PptxSlide[]> {
+  return invoke<PptxSlide[]>("get_presentation_slides", {
+    notebookId,
+    pageId,
+    options,
+  });
+}
+
+export async function savePresentationSlideEdits(
+  notebookId: string,
+  pageId: string,
+  edits: Record<string, SlideEdit>
+): Promise<void> {
+  return invoke("save_presentation_slide_edits", {
+    notebookId,
+    pageId,
+    edits,
+  });
+}
+
+export async function exportPresentationPptx(
+  notebookId: string,
+  pageId: string,
+  outputPath: string,
+  options: PptxOptions,
+  ai?: { providerType?: string; apiKey?: string; model?: string }
+): Promise<PptxExportResult> {
+  return invoke<PptxExportResult>("export_presentation_pptx", {
+    notebookId,
+    pageId,
+    outputPath,
+    options,
+    providerType: ai?.providerType,
+    apiKey: ai?.apiKey,
+    model: ai?.model,
+  });
+}
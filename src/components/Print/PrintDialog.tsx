@@ -42,6 +42,33 @@ export function PrintDialog({ isOpen, onClose, page }: PrintDialogProps) {
     }
   }, [isOpen, generate]);
 
+  // The print HTML links KaTeX from a CDN, which the app's CSP blocks inside
+  // the preview, so typeset its formulas here with the bundled copy instead.
+  const renderPreviewMath = async () => {
+    const doc = iframeRef.current?.contentDocument;
+    if (!doc?.body || !doc.querySelector(".math")) return;
+    try {
+      const [{ default: renderMathInElement }, { default: cssUrl }] =
+        await Promise.all([
+          import("katex/contrib/auto-render"),
+          import("katex/dist/katex.min.css?url"),
+        ]);
+      const link = doc.createElement("link");
+      link.rel = "stylesheet";
+      link.href = new URL(cssUrl, window.location.href).href;
+      doc.head.appendChild(link);
+      renderMathInElement(doc.body, {
+        delimiters: [
+          { left: "\\[", right: "\\]", display: true },
+          { left: "\\(", right: "\\)", display: false },
+        ],
+        throwOnError: false,
+      });
+    } catch (err) {
+      console.warn("Failed to render math in print preview:", err);
+    }
+  };
+
   const handlePrint = () => {
     if (iframeRef.current?.contentWindow) {
       iframeRef.current.contentWindow.print();
@@ -176,6 +203,7 @@ export function PrintDialog({ isOpen, onClose, page }: PrintDialogProps) {
             <iframe
               ref={iframeRef}
               srcDoc={printHtml}
+              onLoad={renderPreviewMath}
               style={{
                 width: "100%",
                 height: 400,
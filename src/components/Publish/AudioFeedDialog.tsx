@@ -0,0 +1,228 @@
+import { useState, useEffect, useCallback } from "react";
+import { open } from "../../platform/dialog";
+import { useNotebookStore } from "../../stores/notebookStore";
+import { useToastStore } from "../../stores/toastStore";
+import { publishAudioFeed, type AudioFeedResult } from "./api";
+import "./publish-styles.css";
+
+interface AudioFeedDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+type FeedScope = "notebook" | "library";
+type FeedTarget = "server" | "export";
+type DialogState = "configure" | "publishing" | "success";
+
+export function AudioFeedDialog({ isOpen, onClose }: AudioFeedDialogProps) {
+  const [scope, setScope] = useState<FeedScope>("notebook");
+  const [target, setTarget] = useState<FeedTarget>("server");
+  const [title, setTitle] = useState("");
+  const [outputDir, setOutputDir] = useState<string | null>(null);
+  const [baseUrl, setBaseUrl] = useState("");
+  const [dialogState, setDialogState] = useState<DialogState>("configure");
+  const [result, setResult] = useState<AudioFeedResult | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const { selectedNotebookId, notebooks } = useNotebookStore();
+  const toastStore = useToastStore();
+
+  const selectedNotebook = notebooks.find((n) => n.id === selectedNotebookId);
+
+  useEffect(() => {
+    if (isOpen) {
+      setDialogState("configure");
+      setError(null);
+      setResult(null);
+      setScope(selectedNotebookId ? "notebook" : "library");
+    }
+  }, [isOpen, selectedNotebookId]);
+
+  const handlePickDir = useCallback(async () => {
+    const dir = await open({ directory: true, multiple: false });
+    if (dir) {
+      setOutputDir(dir as string);
+    }
+  }, []);
+
+  const handlePublish = useCallback(async () => {
+    setDialogState("publishing");
+    setError(null);
+    try {
+      const feed = await publishAudioFeed(
+        scope === "notebook" ? selectedNotebookId : null,
+        title.trim() || null,
+        target === "server"
+          ? { kind: "server" }
+          : { kind: "export", outputDir: outputDir ?? "", baseUrl: baseUrl.trim() }
+      );
+      setResult(feed);
+      setDialogState("success");
+    } catch (err) {
+      setError(String(err));
+      setDialogState("configure");
+    }
+  }, [scope, selectedNotebookId, title, target, outputDir, baseUrl]);
+
+  const handleCopy = useCallback(async () => {
+    if (!result) return;
+    await navigator.clipboard.writeText(result.feedUrl);
+    toastStore.success("Feed URL copied");
+  }, [result, toastStore]);
+
+  if (!isOpen) return null;
+
+  const canPublish =
+    (scope === "library" || !!selectedNotebookId) &&
+    (target === "server" || (!!outputDir && /^https?:\/\//.test(baseUrl.trim())));
+
+  return (
+    <div className="publish-overlay" onClick={onClose}>
+      <div className="publish-dialog" onClick={(e) => e.stopPropagation()}>
+        <div className="publish-header">
+          <h2>Podcast Feed</h2>
+          <button className="publish-close-btn" onClick={onClose}>
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none">
+              <path
+                d="M4 4L12 12M12 4L4 12"
+                stroke="currentColor"
+                strokeWidth="1.5"
+                strokeLinecap="round"
+              />
+            </svg>
+          </button>
+        </div>
+
+        <div className="publish-body">
+          {dialogState === "configure" && (
+            <>
+              <div className="publish-mode-toggle">
+                <button
+                  className={`publish-mode-btn${scope === "notebook" ? " active" : ""}`}
+                  onClick={() => setScope("notebook")}
+                  disabled={!selectedNotebookId}
+                >
+                  This Notebook
+                </button>
+                <button
+                  className={`publish-mode-btn${scope === "library" ? " active" : ""}`}
+                  onClick={() => setScope("library")}
+                >
+                  All Notebooks
+                </button>
+              </div>
+
+              <div>
+                <div className="publish-section-label">Feed Title</div>
+                <input
+                  type="text"
+                  className="publish-input"
+                  placeholder={
+                    scope === "notebook" && selectedNotebook
+                      ? `${selectedNotebook.name} audio`
+                      : "Nous audio"
+                  }
+                  value={title}
+                  onChange={(e) => setTitle(e.target.value)}
+                />
+              </div>
+
+              <div className="publish-mode-toggle">
+                <button
+                  className={`publish-mode-btn${target === "server" ? " active" : ""}`}
+                  onClick={() => setTarget("server")}
+                >
+                  Serve Locally
+                </button>
+                <button
+                  className={`publish-mode-btn${target === "export" ? " active" : ""}`}
+                  onClick={() => setTarget("export")}
+                >
+                  Export Files
+                </button>
+              </div>
+
+              {target === "server" ? (
+                <div className="publish-option-label" style={{ color: "var(--color-text-muted)" }}>
+                  For podcast apps on this computer. The feed URL changes each time Nous starts.
+                </div>
+              ) : (
+                <>
+                  <div>
+                    <div className="publish-section-label">Output Directory</div>
+                    <div className="publish-dir-row">
+                      <div className="publish-dir-path">{outputDir || "Choose a folder..."}</div>
+                      <button className="publish-dir-btn" onClick={handlePickDir}>
+                        Browse
+                      </button>
+                    </div>
+                  </div>
+                  <div>
+                    <div className="publish-section-label">Hosted At</div>
+                    <input
+                      type="text"
+                      className="publish-input"
+                      placeholder="https://example.com/private/audio"
+                      value={baseUrl}
+                      onChange={(e) => setBaseUrl(e.target.value)}
+                    />
+                  </div>
+                </>
+              )}
+
+              {error && <div className="publish-error">{error}</div>}
+            </>
+          )}
+
+          {dialogState === "publishing" && (
+            <div className="publish-progress">
+              <div className="publish-progress-text">Building feed...</div>
+            </div>
+          )}
+
+          {dialogState === "success" && result && (
+            <div className="publish-success">
+              <h3>Feed Published</h3>
+              <p>
+                {result.episodes} episode{result.episodes !== 1 ? "s" : ""}. Subscribe in your
+                podcast app with:
+              </p>
+              <code
+                style={{
+                  fontSize: "0.8rem",
+                  color: "var(--color-text-secondary)",
+                  wordBreak: "break-all",
+                }}
+              >
+                {result.feedUrl}
+              </code>
+              {result.feedPath && (
+                <p style={{ fontSize: "0.8rem", color: "var(--color-text-muted)" }}>
+                  Upload the contents of {outputDir} to the address above.
+                </p>
+              )}
+            </div>
+          )}
+        </div>
+
+        <div className="publish-footer">
+          {dialogState === "configure" && (
+            <button className="publish-btn primary" onClick={handlePublish} disabled={!canPublish}>
+              Publish
+            </button>
+          )}
+          {dialogState === "success" && (
+            <>
+              <button className="publish-btn" onClick={handleCopy}>
+                Copy URL
+              </button>
+              <button className="publish-btn primary" onClick={onClose}>
+                Done
+              </button>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
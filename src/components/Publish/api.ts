@@ -53,3 +53,26 @@ export async function previewPublishPage(
     theme,
   });
 }
+
+export type AudioFeedTarget =
+  | { kind: "server" }
+  | { kind: "export"; outputDir: string; baseUrl: string };
+
+export interface AudioFeedResult {
+  feedUrl: string;
+  feedPath: string | null;
+  episodes: number;
+}
+
+/** Publish generated narrations and reading queues as a private podcast feed */
+export async function publishAudioFeed(
+  notebookId: string | null,
+  title: string | null,
+  target: AudioFeedTarget
+): Promise<AudioFeedResult> {
+  return invoke("publish_audio_feed", {
+    notebookId,
+    title,
+    target,
+  });
+}
@@ -1 +1,2 @@
 export { PublishDialog } from "./PublishDialog";
+export { AudioFeedDialog } from "./AudioFeedDialog";
@@ -0,0 +1,131 @@
+import { useEffect, useRef, useState } from "react";
+import { listen } from "../../platform/event";
+import { useWindowLibrary } from "../../contexts/WindowContext";
+import { useToastStore } from "../../stores/toastStore";
+import { getScratchpad, updateScratchpad, type ScratchpadContent } from "../../utils/api";
+
+interface ScratchpadDialogProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+/** Wait this long after the last keystroke before saving (and syncing) */
+const SAVE_DELAY_MS = 400;
+
+export function ScratchpadDialog({ isOpen, onClose }: ScratchpadDialogProps) {
+  const { library } = useWindowLibrary();
+  const [text, setText] = useState("");
+  const [content, setContent] = useState<ScratchpadContent | null>(null);
+  const saveTimeout = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const dirty = useRef(false);
+  const toast = useToastStore();
+
+  const libraryId = library?.id;
+
+  const load = async () => {
+    if (!libraryId) return;
+    try {
+      const loaded = await getScratchpad(libraryId);
+      setContent(loaded);
+      // Don't clobber what's being typed; the next save merges it
+      if (!dirty.current) setText(loaded.text);
+    } catch (err) {
+      toast.error(`${err}`);
+    }
+  };
+
+  useEffect(() => {
+    if (!isOpen) return;
+    dirty.current = false;
+    load();
+    const unlisten = listen<{ libraryId: string }>("sync-scratchpad-updated", (event) => {
+      if (event.payload.libraryId === libraryId) load();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen, libraryId]);
+
+  const save = async (next: string) => {
+    if (!libraryId) return;
+    try {
+      const saved = await updateScratchpad(libraryId, next);
+      setContent(saved);
+      dirty.current = false;
+    } catch (err) {
+      toast.error(`${err}`);
+    }
+  };
+
+  const handleChange = (next: string) => {
+    setText(next);
+    dirty.current = true;
+    if (saveTimeout.current) clearTimeout(saveTimeout.current);
+    saveTimeout.current = setTimeout(() => save(next), SAVE_DELAY_MS);
+  };
+
+  const handleClose = () => {
+    if (saveTimeout.current) {
+      clearTimeout(saveTimeout.current);
+      saveTimeout.current = null;
+      if (dirty.current) save(text);
+    }
+    onClose();
+  };
+
+  if (!isOpen) return null;
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-center justify-center"
+      style={{ backgroundColor: "rgba(0, 0, 0, 0.5)" }}
+      onClick={(e) => {
+        if (e.target === e.currentTarget) handleClose();
+      }}
+      onKeyDown={(e) => {
+        if (e.key === "Escape") handleClose();
+      }}
+    >
+      <div
+        className="flex w-full max-w-lg flex-col rounded-lg border shadow-xl"
+        style={{
+          backgroundColor: "var(--color-bg-primary)",
+          borderColor: "var(--color-border)",
+        }}
+      >
+        <div
+          className="flex items-center justify-between border-b px-5 py-4"
+          style={{ borderColor: "var(--color-border)" }}
+        >
+          <h2 className="text-base font-semibold" style={{ color: "var(--color-text-primary)" }}>
+            Scratchpad
+          </h2>
+          <span className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+            {!library?.syncConfig
+              ? "This device only"
+              : content?.pendingPush
+                ? "Syncing..."
+                : content?.updatedAt
+                  ? `Synced · edited ${new Date(content.updatedAt).toLocaleString()}`
+                  : "Synced"}
+          </span>
+        </div>
+        <div className="px-5 py-4">
+          <textarea
+            autoFocus
+            value={text}
+            onChange={(e) => handleChange(e.target.value)}
+            placeholder="Paste a link or a snippet to pick it up on another device"
+            className="h-64 w-full resize-none rounded-lg border px-3 py-2 font-mono text-sm outline-none"
+            style={{
+              backgroundColor: "var(--color-bg-secondary)",
+              borderColor: "var(--color-border)",
+              color: "var(--color-text-primary)",
+            }}
+          />
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,259 @@
+import { useState, useEffect } from "react";
+import {
+  listAiProfiles,
+  saveAiProfile,
+  deleteAiProfile,
+  type AiProfile,
+} from "../../utils/api";
+import { useToastStore } from "../../stores/toastStore";
+
+const PROVIDERS = [
+  { value: "openai", label: "OpenAI" },
+  { value: "anthropic", label: "Anthropic" },
+  { value: "ollama", label: "Ollama" },
+  { value: "lmstudio", label: "LM Studio" },
+];
+
+const EMPTY_PROFILE: AiProfile = {
+  id: "",
+  name: "",
+  providerType: "openai",
+};
+
+const inputStyle = {
+  backgroundColor: "var(--color-bg-tertiary)",
+  borderColor: "var(--color-border)",
+  color: "var(--color-text-primary)",
+};
+
+export function AIProfilesSettings() {
+  const [profiles, setProfiles] = useState<AiProfile[]>([]);
+  const [editing, setEditing] = useState<AiProfile | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+  const toast = useToastStore();
+
+  const loadProfiles = async () => {
+    try {
+      setProfiles(await listAiProfiles());
+    } catch (err) {
+      console.error("Failed to load AI profiles:", err);
+    }
+  };
+
+  useEffect(() => {
+    loadProfiles();
+  }, []);
+
+  const update = <K extends keyof AiProfile>(key: K, value: AiProfile[K]) => {
+    setEditing((prev) => (prev ? { ...prev, [key]: value } : prev));
+  };
+
+  const handleSave = async () => {
+    if (!editing) return;
+    try {
+      setIsSaving(true);
+      await saveAiProfile({
+        ...editing,
+        model: editing.model?.trim() || undefined,
+        baseUrl: editing.baseUrl?.trim() || undefined,
+        apiKeyRef: editing.apiKeyRef?.trim() || undefined,
+        systemPrompt: editing.systemPrompt?.trim() || undefined,
+      });
+      setEditing(null);
+      await loadProfiles();
+    } catch (err) {
+      toast.error(`Failed to save profile: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleDelete = async (profile: AiProfile) => {
+    try {
+      await deleteAiProfile(profile.id);
+      await loadProfiles();
+    } catch (err) {
+      toast.error(`Failed to delete profile: ${err}`);
+    }
+  };
+
+  return (
+    <div className="space-y-6">
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        Named provider and model settings for this library. Assign a profile to a
+        notebook in its settings, or to an AI step of an action. Profiles sync with
+        the library; API keys stay on each device.
+      </p>
+
+      <div className="space-y-2">
+        {profiles.length === 0 && !editing && (
+          <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+            No profiles yet.
+          </p>
+        )}
+        {profiles.map((profile) => (
+          <div
+            key={profile.id}
+            className="flex items-center justify-between rounded-lg border p-3"
+            style={{
+              borderColor: "var(--color-border)",
+              backgroundColor: "var(--color-bg-secondary)",
+            }}
+          >
+            <div className="min-w-0">
+              <div className="font-medium" style={{ color: "var(--color-text-primary)" }}>
+                {profile.name}
+              </div>
+              <div className="truncate text-xs" style={{ color: "var(--color-text-muted)" }}>
+                {profile.providerType}
+                {profile.model ? ` / ${profile.model}` : ""}
+                {profile.temperature !== undefined ? ` · temperature ${profile.temperature}` : ""}
+              </div>
+            </div>
+            <div className="flex gap-2 text-xs">
+              <button
+                onClick={() => setEditing(profile)}
+                className="rounded px-2 py-1"
+                style={{ color: "var(--color-text-secondary)" }}
+              >
+                Edit
+              </button>
+              <button
+                onClick={() => handleDelete(profile)}
+                className="rounded px-2 py-1"
+                style={{ color: "var(--color-error)" }}
+              >
+                Delete
+              </button>
+            </div>
+          </div>
+        ))}
+      </div>
+
+      {editing ? (
+        <div
+          className="space-y-3 rounded-lg border p-4"
+          style={{ borderColor: "var(--color-border)" }}
+        >
+          <div className="grid grid-cols-2 gap-3">
+            <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              Name
+              <input
+                value={editing.name}
+                onChange={(e) => update("name", e.target.value)}
+                placeholder="Local drafts"
+                className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+                style={inputStyle}
+              />
+            </label>
+            <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              Provider
+              <select
+                value={editing.providerType}
+                onChange={(e) => update("providerType", e.target.value)}
+                className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+                style={inputStyle}
+              >
+                {PROVIDERS.map((p) => (
+                  <option key={p.value} value={p.value}>
+                    {p.label}
+                  </option>
+                ))}
+              </select>
+            </label>
+            <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              Model
+              <input
+                value={editing.model ?? ""}
+                onChange={(e) => update("model", e.target.value)}
+                placeholder="Provider default"
+                className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+                style={inputStyle}
+              />
+            </label>
+            <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              Temperature
+              <input
+                type="number"
+                min={0}
+                max={2}
+                step={0.1}
+                value={editing.temperature ?? ""}
+                onChange={(e) =>
+                  update("temperature", e.target.value === "" ? undefined : Number(e.target.value))
+                }
+                placeholder="Default"
+                className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+                style={inputStyle}
+              />
+            </label>
+            <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              Base URL
+              <input
+                value={editing.baseUrl ?? ""}
+                onChange={(e) => update("baseUrl", e.target.value)}
+                placeholder="Provider default"
+                className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+                style={inputStyle}
+              />
+            </label>
+            <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              Use API key of
+              <select
+                value={editing.apiKeyRef ?? ""}
+                onChange={(e) => update("apiKeyRef", e.target.value || undefined)}
+                className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+                style={inputStyle}
+              >
+                <option value="">Same provider</option>
+                {PROVIDERS.map((p) => (
+                  <option key={p.value} value={p.value}>
+                    {p.label}
+                  </option>
+                ))}
+              </select>
+            </label>
+          </div>
+          <label className="block text-xs" style={{ color: "var(--color-text-secondary)" }}>
+            System prompt
+            <textarea
+              value={editing.systemPrompt ?? ""}
+              onChange={(e) => update("systemPrompt", e.target.value)}
+              rows={3}
+              className="mt-1 w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+              style={inputStyle}
+            />
+          </label>
+          <div className="flex justify-end gap-2">
+            <button
+              onClick={() => setEditing(null)}
+              className="rounded-lg px-4 py-2 text-sm"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              Cancel
+            </button>
+            <button
+              onClick={handleSave}
+              disabled={isSaving || !editing.name.trim()}
+              className="rounded-lg px-4 py-2 text-sm font-medium disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+            >
+              {isSaving ? "Saving..." : "Save Profile"}
+            </button>
+          </div>
+        </div>
+      ) : (
+        <button
+          onClick={() => setEditing({ ...EMPTY_PROFILE })}
+          className="rounded-lg px-4 py-2.5 text-sm font-medium"
+          style={{
+            backgroundColor: "var(--color-bg-tertiary)",
+            color: "var(--color-text-secondary)",
+          }}
+        >
+          New Profile
+        </button>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,151 @@
+import { useState } from "react";
+import { save, open } from "../../platform/dialog";
+import {
+  exportAppConfig,
+  importAppConfig,
+  type AppConfigCounts,
+  type AppConfigImport,
+} from "../../utils/api";
+import { isTauri } from "../../utils/platform";
+import { useTemplateStore, type PageTemplate } from "../../stores/templateStore";
+import { useActionStore } from "../../stores/actionStore";
+import { useToastStore } from "../../stores/toastStore";
+
+function describe(counts: AppConfigCounts): string {
+  const parts = [
+    `${counts.settings} settings`,
+    `${counts.actions} actions`,
+    `${counts.externalSources} external sources`,
+    `${counts.notebookSync} sync configs`,
+    `${counts.templates} templates`,
+  ];
+  if (counts.mcpServers) parts.push("MCP servers");
+  return parts.join(", ");
+}
+
+export function AppConfigTransferSettings() {
+  const [isBusy, setIsBusy] = useState(false);
+  const [lastImport, setLastImport] = useState<AppConfigImport | null>(null);
+  const toast = useToastStore();
+
+  if (!isTauri()) {
+    return (
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        Exporting and importing settings is only available in the desktop app.
+      </p>
+    );
+  }
+
+  const handleExport = async () => {
+    const path = await save({
+      defaultPath: `nous-settings-${new Date().toISOString().slice(0, 10)}.json`,
+      filters: [{ name: "Nous settings", extensions: ["json"] }],
+    });
+    if (!path) return;
+    try {
+      setIsBusy(true);
+      const templates = useTemplateStore.getState().templates.filter((t) => !t.isBuiltIn);
+      const counts = await exportAppConfig(path, templates);
+      toast.success(`Exported ${describe(counts)}`);
+    } catch (err) {
+      toast.error(`Export failed: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const handleImport = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: "Nous settings", extensions: ["json"] }],
+    });
+    if (!path || Array.isArray(path)) return;
+    try {
+      setIsBusy(true);
+      const result = await importAppConfig(path);
+      useTemplateStore.getState().importTemplates(result.templates as PageTemplate[]);
+      await useActionStore.getState().loadActions();
+      setLastImport(result);
+      toast.success(`Imported ${describe(result.imported)}`);
+    } catch (err) {
+      toast.error(`Import failed: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  return (
+    <div className="space-y-6">
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        Save this library's settings, actions, external sources, sync
+        configuration, MCP servers and your page templates to one file, to set
+        up a new machine or restore after a reset. Notes are not included, and
+        neither are passwords or API keys.
+      </p>
+
+      <div className="flex gap-3">
+        <button
+          onClick={handleExport}
+          disabled={isBusy}
+          className="rounded-lg px-4 py-2.5 text-sm font-medium disabled:opacity-50"
+          style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+        >
+          Export Settings...
+        </button>
+        <button
+          onClick={handleImport}
+          disabled={isBusy}
+          className="rounded-lg px-4 py-2.5 text-sm font-medium disabled:opacity-50"
+          style={{
+            backgroundColor: "var(--color-bg-tertiary)",
+            color: "var(--color-text-secondary)",
+          }}
+        >
+          Import Settings...
+        </button>
+      </div>
+
+      <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+        Importing replaces entries with the same name or id and keeps everything
+        else.
+      </p>
+
+      {lastImport &&
+        (lastImport.needsCredentials.length > 0 || lastImport.warnings.length > 0) && (
+          <div
+            className="space-y-3 rounded-lg border p-4 text-sm"
+            style={{ borderColor: "var(--color-border)" }}
+          >
+            {lastImport.needsCredentials.length > 0 && (
+              <div>
+                <div className="font-medium" style={{ color: "var(--color-text-primary)" }}>
+                  Sync needs a password
+                </div>
+                <p className="mt-1 text-xs" style={{ color: "var(--color-text-muted)" }}>
+                  These were imported with sync turned off. Enter the password in
+                  their sync settings to turn it back on.
+                </p>
+                <ul className="mt-1 list-disc pl-5" style={{ color: "var(--color-text-secondary)" }}>
+                  {lastImport.needsCredentials.map((name) => (
+                    <li key={name}>{name}</li>
+                  ))}
+                </ul>
+              </div>
+            )}
+            {lastImport.warnings.length > 0 && (
+              <div>
+                <div className="font-medium" style={{ color: "var(--color-warning, #f59e0b)" }}>
+                  Not imported
+                </div>
+                <ul className="mt-1 list-disc pl-5" style={{ color: "var(--color-text-secondary)" }}>
+                  {lastImport.warnings.map((warning) => (
+                    <li key={warning}>{warning}</li>
+                  ))}
+                </ul>
+              </div>
+            )}
+          </div>
+        )}
+    </div>
+  );
+}
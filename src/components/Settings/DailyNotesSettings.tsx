@@ -2,7 +2,13 @@ import { useDailyNotesStore } from "../../stores/dailyNotesStore";
 import { useTemplateStore } from "../../stores/templateStore";
 
 export function DailyNotesSettings() {
-  const { settings, setUseTemplate, setTemplateId } = useDailyNotesStore();
+  const {
+    settings,
+    setUseTemplate,
+    setTemplateId,
+    setRollover,
+    setRolloverLookbackDays,
+  } = useDailyNotesStore();
   const { templates } = useTemplateStore();
 
   // Get available templates
@@ -148,6 +154,73 @@ export function DailyNotesSettings() {
         </div>
       )}
 
+      {/* Rollover Toggle */}
+      <div>
+        <div className="flex items-center justify-between">
+          <div>
+            <label
+              className="text-sm font-medium"
+              style={{ color: "var(--color-text-primary)" }}
+            >
+              Carry Forward Unfinished Items
+            </label>
+            <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+              Move unchecked items from recent daily notes into today's new note
+            </p>
+          </div>
+          <button
+            onClick={() => setRollover(!settings.rollover)}
+            className="relative h-6 w-11 rounded-full transition-colors"
+            style={{
+              backgroundColor: settings.rollover
+                ? "var(--color-accent)"
+                : "var(--color-bg-tertiary)",
+            }}
+          >
+            <span
+              className="absolute left-0 top-0.5 h-5 w-5 rounded-full bg-white shadow transition-transform"
+              style={{
+                transform: settings.rollover
+                  ? "translateX(22px)"
+                  : "translateX(2px)",
+              }}
+            />
+          </button>
+        </div>
+        {settings.rollover && (
+          <div className="mt-3 flex items-center gap-2">
+            <label
+              htmlFor="rollover-lookback"
+              className="text-xs"
+              style={{ color: "var(--color-text-muted)" }}
+            >
+              Look back
+            </label>
+            <input
+              id="rollover-lookback"
+              type="number"
+              min={1}
+              max={90}
+              value={settings.rolloverLookbackDays ?? 7}
+              onChange={(e) => {
+                const days = parseInt(e.target.value, 10);
+                if (days >= 1) setRolloverLookbackDays(Math.min(days, 90));
+              }}
+              className="w-16 rounded-lg border px-2 py-1 text-sm outline-none focus:border-[--color-accent]"
+              style={{
+                backgroundColor: "var(--color-bg-secondary)",
+                borderColor: "var(--color-border)",
+                color: "var(--color-text-primary)",
+              }}
+            />
+            <span className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+              days. Carried items are checked off and marked "(carried forward)" where
+              they came from.
+            </span>
+          </div>
+        )}
+      </div>
+
       {/* Keyboard Shortcut Info */}
       <div
         className="rounded-lg border p-4"
@@ -0,0 +1,257 @@
+import { useState, useEffect } from "react";
+import {
+  createDiagnosticBundle,
+  getLogSettings,
+  getRecentLogs,
+  setLogSettings,
+  type DiagnosticBundleSummary,
+  type LogEntry,
+  type LogLevel,
+  type LogSettingsInfo,
+} from "../../utils/diagnosticsApi";
+import { useToastStore } from "../../stores/toastStore";
+
+const LEVELS: LogLevel[] = ["off", "error", "warn", "info", "debug", "trace"];
+
+const LEVEL_COLORS: Partial<Record<LogLevel, string>> = {
+  error: "var(--color-error, #ef4444)",
+  warn: "var(--color-warning, #f59e0b)",
+};
+
+const inputStyle = {
+  backgroundColor: "var(--color-bg-tertiary)",
+  borderColor: "var(--color-border)",
+  color: "var(--color-text-primary)",
+};
+
+const SUBSYSTEM_LABELS: Record<string, string> = {
+  sync: "Sync",
+  python_bridge: "Python / AI",
+  storage: "Storage",
+};
+
+export function LogSettings() {
+  const [info, setInfo] = useState<LogSettingsInfo | null>(null);
+  const [entries, setEntries] = useState<LogEntry[]>([]);
+  const [minLevel, setMinLevel] = useState<LogLevel>("info");
+  const [filter, setFilter] = useState("");
+  const [bundle, setBundle] = useState<DiagnosticBundleSummary | null>(null);
+  const [creatingBundle, setCreatingBundle] = useState(false);
+  const toast = useToastStore();
+
+  const loadEntries = async () => {
+    try {
+      setEntries(await getRecentLogs(500, minLevel, filter.trim() || undefined));
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  useEffect(() => {
+    getLogSettings()
+      .then(setInfo)
+      .catch((err) => toast.error(`${(err as { message?: string }).message ?? err}`));
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  useEffect(() => {
+    const timeout = setTimeout(loadEntries, 200);
+    return () => clearTimeout(timeout);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [minLevel, filter]);
+
+  if (!info) return null;
+  const { settings } = info;
+
+  const save = async (changes: Partial<typeof settings>) => {
+    const next = { ...settings, ...changes };
+    try {
+      await setLogSettings(next);
+      setInfo({ ...info, settings: next });
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  const handleCreateBundle = async () => {
+    setCreatingBundle(true);
+    try {
+      const summary = await createDiagnosticBundle();
+      setBundle(summary);
+      toast.success("Diagnostic bundle created");
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setCreatingBundle(false);
+    }
+  };
+
+  const setModuleLevel = (module: string, level: LogLevel | "") => {
+    const modules = { ...settings.modules };
+    if (level) {
+      modules[module] = level;
+    } else {
+      delete modules[module];
+    }
+    save({ modules });
+  };
+
+  return (
+    <div className="space-y-6">
+      <div className="space-y-3">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium" style={{ color: "var(--color-text-primary)" }}>
+              Log Level
+            </label>
+            <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+              Logs are written to {info.logDir}. API keys and passwords are redacted.
+            </p>
+          </div>
+          <select
+            value={settings.level}
+            onChange={(e) => save({ level: e.target.value as LogLevel })}
+            className="rounded-lg border px-3 py-1.5 text-sm outline-none dark-select"
+            style={inputStyle}
+          >
+            {LEVELS.map((level) => (
+              <option key={level} value={level}>
+                {level}
+              </option>
+            ))}
+          </select>
+        </div>
+
+        {info.subsystems.map((module) => (
+          <div key={module} className="flex items-center justify-between pl-3">
+            <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+              {SUBSYSTEM_LABELS[module] ?? module}
+            </span>
+            <select
+              value={settings.modules[module] ?? ""}
+              onChange={(e) => setModuleLevel(module, e.target.value as LogLevel | "")}
+              className="rounded-lg border px-3 py-1.5 text-sm outline-none dark-select"
+              style={inputStyle}
+            >
+              <option value="">default</option>
+              {LEVELS.map((level) => (
+                <option key={level} value={level}>
+                  {level}
+                </option>
+              ))}
+            </select>
+          </div>
+        ))}
+
+        <div className="flex items-center justify-between">
+          <span className="text-sm" style={{ color: "var(--color-text-secondary)" }}>
+            Keep
+          </span>
+          <select
+            value={settings.maxFiles}
+            onChange={(e) => save({ maxFiles: parseInt(e.target.value, 10) })}
+            className="rounded-lg border px-3 py-1.5 text-sm outline-none dark-select"
+            style={inputStyle}
+          >
+            {[1, 3, 5, 10].map((n) => (
+              <option key={n} value={n}>
+                {n} old file{n === 1 ? "" : "s"} of {Math.round(settings.maxFileBytes / 1024 / 1024)} MB
+              </option>
+            ))}
+          </select>
+        </div>
+      </div>
+
+      <div>
+        <div className="mb-2 flex items-center gap-2">
+          <input
+            value={filter}
+            onChange={(e) => setFilter(e.target.value)}
+            placeholder="Filter by module or text"
+            className="flex-1 rounded-lg border px-3 py-1.5 text-sm outline-none"
+            style={inputStyle}
+          />
+          <select
+            value={minLevel}
+            onChange={(e) => setMinLevel(e.target.value as LogLevel)}
+            className="rounded-lg border px-3 py-1.5 text-sm outline-none dark-select"
+            style={inputStyle}
+          >
+            {LEVELS.filter((l) => l !== "off").map((level) => (
+              <option key={level} value={level}>
+                {level} and above
+              </option>
+            ))}
+          </select>
+          <button
+            onClick={loadEntries}
+            className="rounded px-2 py-1 text-xs"
+            style={{ color: "var(--color-accent)" }}
+          >
+            Refresh
+          </button>
+        </div>
+        <div
+          className="h-72 overflow-auto rounded-lg border p-2 font-mono text-xs"
+          style={{
+            borderColor: "var(--color-border)",
+            backgroundColor: "var(--color-bg-secondary)",
+          }}
+        >
+          {entries.length === 0 && (
+            <div style={{ color: "var(--color-text-muted)" }}>No log entries.</div>
+          )}
+          {entries.map((entry, i) => (
+            <div key={i} className="whitespace-pre-wrap break-all">
+              <span style={{ color: "var(--color-text-muted)" }}>
+                {new Date(entry.timestamp).toLocaleTimeString()}{" "}
+              </span>
+              <span style={{ color: LEVEL_COLORS[entry.level] ?? "var(--color-text-secondary)" }}>
+                {entry.level.toUpperCase()}{" "}
+              </span>
+              <span style={{ color: "var(--color-text-muted)" }}>{entry.target} </span>
+              <span style={{ color: "var(--color-text-primary)" }}>{entry.message}</span>
+            </div>
+          ))}
+        </div>
+      </div>
+
+      <div className="space-y-2">
+        <div className="flex items-center justify-between">
+          <div>
+            <label className="text-sm font-medium" style={{ color: "var(--color-text-primary)" }}>
+              Diagnostic Bundle
+            </label>
+            <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+              A ZIP of recent logs, library and sync checks and the Python environment to attach
+              to a bug report. Secrets are scrubbed and nothing is uploaded.
+            </p>
+          </div>
+          <button
+            onClick={handleCreateBundle}
+            disabled={creatingBundle}
+            className="flex-shrink-0 rounded-lg px-3 py-1.5 text-sm font-medium disabled:opacity-50"
+            style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+          >
+            {creatingBundle ? "Creating..." : "Create Bundle"}
+          </button>
+        </div>
+        {bundle && (
+          <div className="rounded-lg border p-2 text-xs" style={{ borderColor: "var(--color-border)" }}>
+            <div className="break-all font-mono" style={{ color: "var(--color-text-primary)" }}>
+              {bundle.path}
+            </div>
+            <div style={{ color: "var(--color-text-muted)" }}>
+              {bundle.files.length} files, {Math.max(1, Math.round(bundle.sizeBytes / 1024))} KB
+            </div>
+            {bundle.errors.map((e) => (
+              <div key={e} style={{ color: "var(--color-warning, #f59e0b)" }}>
+                {e}
+              </div>
+            ))}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,231 @@
+import { useState, useEffect } from "react";
+import { listen, type UnlistenFn } from "../../platform/event";
+import {
+  ollamaListModels,
+  ollamaPullModel,
+  ollamaDeleteModel,
+  ollamaHealth,
+  type OllamaHealth,
+  type OllamaModel,
+  type OllamaPullProgress,
+} from "../../utils/api";
+import { useToastStore } from "../../stores/toastStore";
+
+interface OllamaModelsSettingsProps {
+  baseUrl?: string;
+  /** Called after a pull or delete so the provider's model list can refresh */
+  onModelsChanged?: () => void;
+}
+
+const inputStyle = {
+  backgroundColor: "var(--color-bg-tertiary)",
+  borderColor: "var(--color-border)",
+  color: "var(--color-text-primary)",
+};
+
+function formatSize(bytes: number): string {
+  if (bytes >= 1e9) return `${(bytes / 1e9).toFixed(1)} GB`;
+  if (bytes >= 1e6) return `${(bytes / 1e6).toFixed(0)} MB`;
+  return `${(bytes / 1e3).toFixed(0)} KB`;
+}
+
+export function OllamaModelsSettings({ baseUrl, onModelsChanged }: OllamaModelsSettingsProps) {
+  const [health, setHealth] = useState<OllamaHealth | null>(null);
+  const [models, setModels] = useState<OllamaModel[]>([]);
+  const [pullName, setPullName] = useState("");
+  const [progress, setProgress] = useState<OllamaPullProgress | null>(null);
+  const [isPulling, setIsPulling] = useState(false);
+  const toast = useToastStore();
+
+  const refresh = async () => {
+    const result = await ollamaHealth(baseUrl || undefined);
+    setHealth(result);
+    if (!result.reachable) {
+      setModels([]);
+      return;
+    }
+    try {
+      setModels(await ollamaListModels(baseUrl || undefined));
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  useEffect(() => {
+    refresh();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [baseUrl]);
+
+  // Listen for download progress while pulling
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+
+    const setupListener = async () => {
+      unlisten = await listen<OllamaPullProgress>("ollama-pull-progress", (event) => {
+        setProgress(event.payload);
+      });
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  const handlePull = async () => {
+    const name = pullName.trim();
+    if (!name) return;
+    try {
+      setIsPulling(true);
+      setProgress(null);
+      await ollamaPullModel(name, baseUrl || undefined);
+      toast.success(`Pulled ${name}`);
+      setPullName("");
+      await refresh();
+      onModelsChanged?.();
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setIsPulling(false);
+      setProgress(null);
+    }
+  };
+
+  const handleDelete = async (model: OllamaModel) => {
+    if (!window.confirm(`Delete ${model.name} (${formatSize(model.size)}) from Ollama?`)) return;
+    try {
+      await ollamaDeleteModel(model.name, baseUrl || undefined);
+      await refresh();
+      onModelsChanged?.();
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  const totalSize = models.reduce((sum, m) => sum + m.size, 0);
+
+  return (
+    <div className="mt-4 space-y-3">
+      <div className="flex items-center justify-between">
+        <label className="text-xs font-medium" style={{ color: "var(--color-text-muted)" }}>
+          Installed Models
+        </label>
+        <button
+          onClick={refresh}
+          className="rounded px-2 py-1 text-xs"
+          style={{ color: "var(--color-accent)" }}
+        >
+          Refresh
+        </button>
+      </div>
+
+      {health && (
+        <div className="flex items-center gap-2 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+          <span
+            className="h-2 w-2 rounded-full"
+            style={{
+              backgroundColor: health.reachable
+                ? "var(--color-success, #22c55e)"
+                : "var(--color-error, #ef4444)",
+            }}
+          />
+          {health.reachable
+            ? `Ollama ${health.version} · ${health.latencyMs} ms`
+            : health.error ?? `Ollama is not running at ${health.baseUrl}`}
+        </div>
+      )}
+
+      {health?.reachable && (
+        <>
+          <div
+            className="divide-y rounded-lg border text-xs"
+            style={{ borderColor: "var(--color-border)" }}
+          >
+            {models.length === 0 && (
+              <div className="px-3 py-2" style={{ color: "var(--color-text-muted)" }}>
+                No models installed yet.
+              </div>
+            )}
+            {models.map((model) => (
+              <div
+                key={model.name}
+                className="flex items-center justify-between px-3 py-1.5"
+                style={{ borderColor: "var(--color-border)" }}
+              >
+                <span style={{ color: "var(--color-text-primary)" }}>
+                  {model.name}
+                  {model.parameterSize && (
+                    <span style={{ color: "var(--color-text-muted)" }}>
+                      {" "}
+                      · {model.parameterSize}
+                      {model.quantizationLevel ? ` ${model.quantizationLevel}` : ""}
+                    </span>
+                  )}
+                </span>
+                <span className="flex items-center gap-3">
+                  <span style={{ color: "var(--color-text-muted)" }}>{formatSize(model.size)}</span>
+                  <button
+                    onClick={() => handleDelete(model)}
+                    disabled={isPulling}
+                    className="rounded px-1.5 py-0.5 disabled:opacity-50"
+                    style={{ color: "var(--color-error)" }}
+                  >
+                    Delete
+                  </button>
+                </span>
+              </div>
+            ))}
+          </div>
+          {models.length > 0 && (
+            <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+              {models.length} model{models.length === 1 ? "" : "s"} · {formatSize(totalSize)} on disk
+            </p>
+          )}
+
+          <div className="flex gap-2">
+            <input
+              value={pullName}
+              onChange={(e) => setPullName(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && handlePull()}
+              placeholder="Model to pull, e.g. llama3.2 or qwen2.5:7b"
+              disabled={isPulling}
+              className="flex-1 rounded-lg border px-3 py-2 text-sm outline-none"
+              style={inputStyle}
+            />
+            <button
+              onClick={handlePull}
+              disabled={isPulling || !pullName.trim()}
+              className="rounded-lg px-4 py-2 text-sm font-medium disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+            >
+              {isPulling ? "Pulling..." : "Pull"}
+            </button>
+          </div>
+
+          {isPulling && progress && (
+            <div className="space-y-1 text-xs" style={{ color: "var(--color-text-secondary)" }}>
+              <div>{progress.status}</div>
+              {progress.percent != null && (
+                <div
+                  className="h-1.5 overflow-hidden rounded-full"
+                  style={{ backgroundColor: "var(--color-bg-tertiary)" }}
+                >
+                  <div
+                    className="h-full rounded-full"
+                    style={{
+                      width: `${progress.percent}%`,
+                      backgroundColor: "var(--color-accent)",
+                    }}
+                  />
+                </div>
+              )}
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,218 @@
+import { useState, useEffect, useRef } from "react";
+import { listen, type UnlistenFn } from "../../platform/event";
+import {
+  checkPythonEnvironment,
+  repairPythonEnvironment,
+  type PackageState,
+  type PythonHealthReport,
+} from "../../utils/api";
+import { useToastStore } from "../../stores/toastStore";
+
+const INSTALL_LABELS: Record<PythonHealthReport["install"], string> = {
+  bundled: "Bundled with the app",
+  venv: "Development venv",
+  system: "System interpreter",
+};
+
+const STATE_COLORS: Record<PackageState, string> = {
+  ok: "var(--color-success, #22c55e)",
+  missing: "var(--color-error, #ef4444)",
+  outdated: "var(--color-warning, #f59e0b)",
+};
+
+export function PythonEnvironmentSettings() {
+  const [report, setReport] = useState<PythonHealthReport | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+  const [isRepairing, setIsRepairing] = useState(false);
+  const [output, setOutput] = useState<string[]>([]);
+  const outputRef = useRef<HTMLPreElement>(null);
+  const toast = useToastStore();
+
+  const runCheck = async () => {
+    try {
+      setIsChecking(true);
+      setReport(await checkPythonEnvironment());
+    } catch (err) {
+      toast.error(`Failed to check Python environment: ${err}`);
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  useEffect(() => {
+    runCheck();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  // Listen for installer output while repairing
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+
+    const setupListener = async () => {
+      unlisten = await listen<{ line: string }>("python-env-repair-output", (event) => {
+        setOutput((prev) => [...prev, event.payload.line]);
+      });
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  useEffect(() => {
+    outputRef.current?.scrollTo(0, outputRef.current.scrollHeight);
+  }, [output]);
+
+  const handleRepair = async () => {
+    try {
+      setIsRepairing(true);
+      setOutput([]);
+      const result = await repairPythonEnvironment();
+      setReport(result);
+      if (result.healthy) {
+        toast.success("Python environment repaired");
+      } else {
+        toast.info("Repair finished, but some problems remain");
+      }
+    } catch (err) {
+      toast.error(`${err}`);
+    } finally {
+      setIsRepairing(false);
+    }
+  };
+
+  const required = report?.packages.filter((p) => p.group === null) ?? [];
+  const optional = report?.packages.filter((p) => p.group !== null) ?? [];
+
+  return (
+    <div className="space-y-6">
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        The AI, RAG and audio features run on the bundled Python package. Check
+        that its interpreter and dependencies are installed, and reinstall them
+        if not.
+      </p>
+
+      {report && (
+        <div
+          className="space-y-1 rounded-lg border p-4 text-sm"
+          style={{
+            borderColor: "var(--color-border)",
+            backgroundColor: "var(--color-bg-secondary)",
+          }}
+        >
+          <div className="flex items-center justify-between">
+            <span className="font-medium" style={{ color: "var(--color-text-primary)" }}>
+              {report.healthy ? "Environment is healthy" : "Environment needs attention"}
+            </span>
+            <span
+              className="h-2.5 w-2.5 rounded-full"
+              style={{ backgroundColor: report.healthy ? STATE_COLORS.ok : STATE_COLORS.missing }}
+            />
+          </div>
+          <div style={{ color: "var(--color-text-secondary)" }}>
+            Python {report.pythonVersion} · {INSTALL_LABELS[report.install]} ·{" "}
+            {report.mode === "subprocess" ? "subprocess" : "in-process"}
+          </div>
+          <div className="truncate text-xs" style={{ color: "var(--color-text-muted)" }}>
+            {report.venvPath ?? report.nousPyPath}
+          </div>
+          {report.problems.length > 0 && (
+            <ul className="mt-2 list-disc pl-5 text-xs" style={{ color: STATE_COLORS.missing }}>
+              {report.problems.map((problem) => (
+                <li key={problem}>{problem}</li>
+              ))}
+            </ul>
+          )}
+        </div>
+      )}
+
+      {[
+        { title: "Required packages", packages: required },
+        { title: "Optional packages", packages: optional },
+      ]
+        .filter((section) => section.packages.length > 0)
+        .map((section) => (
+          <div key={section.title}>
+            <div
+              className="mb-2 text-sm font-medium"
+              style={{ color: "var(--color-text-primary)" }}
+            >
+              {section.title}
+            </div>
+            <div
+              className="divide-y rounded-lg border text-xs"
+              style={{ borderColor: "var(--color-border)" }}
+            >
+              {section.packages.map((pkg) => (
+                <div
+                  key={`${pkg.group ?? ""}:${pkg.name}`}
+                  className="flex items-center justify-between px-3 py-1.5"
+                  style={{ borderColor: "var(--color-border)" }}
+                >
+                  <span style={{ color: "var(--color-text-secondary)" }}>
+                    {pkg.requirement}
+                    {pkg.group && (
+                      <span style={{ color: "var(--color-text-muted)" }}> ({pkg.group})</span>
+                    )}
+                  </span>
+                  <span style={{ color: STATE_COLORS[pkg.state] }}>
+                    {pkg.installed ?? "not installed"}
+                  </span>
+                </div>
+              ))}
+            </div>
+          </div>
+        ))}
+
+      {output.length > 0 && (
+        <pre
+          ref={outputRef}
+          className="max-h-48 overflow-auto rounded-lg border p-3 text-xs"
+          style={{
+            borderColor: "var(--color-border)",
+            backgroundColor: "var(--color-bg-tertiary)",
+            color: "var(--color-text-secondary)",
+          }}
+        >
+          {output.join("\n")}
+        </pre>
+      )}
+
+      <div className="flex gap-3">
+        <button
+          onClick={runCheck}
+          disabled={isChecking || isRepairing}
+          className="flex items-center justify-center gap-2 rounded-lg px-4 py-2.5 text-sm font-medium transition-colors disabled:opacity-50"
+          style={{
+            backgroundColor: "var(--color-bg-tertiary)",
+            color: "var(--color-text-secondary)",
+          }}
+        >
+          {isChecking ? "Checking..." : "Check Again"}
+        </button>
+        {report && report.install !== "bundled" && (
+          <button
+            onClick={handleRepair}
+            disabled={isChecking || isRepairing || report.healthy}
+            className="flex flex-1 items-center justify-center gap-2 rounded-lg px-4 py-2.5 text-sm font-medium transition-colors disabled:opacity-50"
+            style={{
+              backgroundColor: report.healthy ? "var(--color-bg-tertiary)" : "var(--color-accent)",
+              color: report.healthy ? "var(--color-text-muted)" : "white",
+            }}
+          >
+            {isRepairing ? "Repairing..." : "Repair Environment"}
+          </button>
+        )}
+      </div>
+      {report?.install === "bundled" && !report.healthy && (
+        <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+          The bundled Python can't be modified. Reinstall the app to restore it.
+        </p>
+      )}
+    </div>
+  );
+}
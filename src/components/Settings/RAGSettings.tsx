@@ -1,9 +1,12 @@
 import { useState, useEffect } from "react";
 import { useRAGStore } from "../../stores/ragStore";
+import { usePageStore } from "../../stores/pageStore";
+import { useNotebookStore } from "../../stores/notebookStore";
 import {
   EMBEDDING_MODELS,
   EMBEDDING_PROVIDER_INFO,
   DEFAULT_EMBEDDING_BASE_URLS,
+  type ChunkingStrategy,
   type EmbeddingProvider,
 } from "../../types/rag";
 
@@ -31,11 +34,25 @@ export function RAGSettings() {
     getStats,
     clearError,
     discoverModels,
+    chunking,
+    setChunking,
+    previewChunks,
+    rechunkLibrary,
   } = useRAGStore();
+  const selectedPageId = usePageStore((state) => state.selectedPageId);
+  const selectedNotebookId = useNotebookStore((state) => state.selectedNotebookId);
 
   const [showApiKey, setShowApiKey] = useState(false);
   const [isSaving, setIsSaving] = useState(false);
   const [isRebuildingIndex, setIsRebuildingIndex] = useState(false);
+  const [chunkingDraft, setChunkingDraft] = useState<ChunkingStrategy>(chunking);
+  const [chunkPreview, setChunkPreview] = useState<string[] | null>(null);
+
+  useEffect(() => {
+    setChunkingDraft(chunking);
+  }, [chunking]);
+
+  const chunkingChanged = JSON.stringify(chunkingDraft) !== JSON.stringify(chunking);
 
   const providerInfo = EMBEDDING_PROVIDER_INFO[settings.provider];
   // Use discovered models if available, otherwise fall back to static list
@@ -72,6 +89,40 @@ export function RAGSettings() {
     }
   };
 
+  const updateChunkingDraft = (update: Partial<ChunkingStrategy>) => {
+    setChunkingDraft((draft) => ({ ...draft, ...update }));
+    setChunkPreview(null);
+  };
+
+  const handlePreviewChunks = async () => {
+    if (!selectedNotebookId || !selectedPageId) return;
+    clearError();
+    try {
+      setChunkPreview(await previewChunks(selectedNotebookId, selectedPageId, chunkingDraft));
+    } catch (error) {
+      useRAGStore.setState({ lastError: String(error) });
+    }
+  };
+
+  // Save the strategy and re-chunk everything with it
+  const handleRechunk = async () => {
+    if (!isConfigured) return;
+    clearError();
+    try {
+      if (chunkingChanged) {
+        await setChunking(chunkingDraft);
+      }
+      const summary = await rechunkLibrary();
+      if (summary.failed.length > 0) {
+        useRAGStore.setState({
+          lastError: `${summary.failed.length} pages could not be re-embedded and kept their old chunks`,
+        });
+      }
+    } catch (error) {
+      console.error("Failed to re-chunk library:", error);
+    }
+  };
+
   const handleRebuildIndex = async () => {
     if (!isConfigured) return;
     setIsRebuildingIndex(true);
@@ -474,6 +525,146 @@ export function RAGSettings() {
         )}
       </div>
 
+      {/* Chunking */}
+      {isConfigured && (
+        <div
+          className="rounded-lg border p-4 space-y-4"
+          style={{
+            backgroundColor: "var(--color-bg-secondary)",
+            borderColor: "var(--color-border)",
+          }}
+        >
+          <h4
+            className="text-sm font-medium"
+            style={{ color: "var(--color-text-primary)" }}
+          >
+            Chunking
+          </h4>
+
+          <div className="grid grid-cols-2 gap-4">
+            <label className="text-sm" style={{ color: "var(--color-text-primary)" }}>
+              Chunk size (tokens)
+              <input
+                type="number"
+                min={64}
+                max={8192}
+                step={64}
+                value={chunkingDraft.chunkSize}
+                onChange={(e) => updateChunkingDraft({ chunkSize: parseInt(e.target.value) || 0 })}
+                className="mt-1 w-full rounded-lg border px-3 py-2 text-sm"
+                style={{
+                  backgroundColor: "var(--color-bg-tertiary)",
+                  borderColor: "var(--color-border)",
+                  color: "var(--color-text-primary)",
+                }}
+              />
+            </label>
+            <label className="text-sm" style={{ color: "var(--color-text-primary)" }}>
+              Overlap (tokens)
+              <input
+                type="number"
+                min={0}
+                step={10}
+                value={chunkingDraft.overlap}
+                onChange={(e) => updateChunkingDraft({ overlap: parseInt(e.target.value) || 0 })}
+                className="mt-1 w-full rounded-lg border px-3 py-2 text-sm"
+                style={{
+                  backgroundColor: "var(--color-bg-tertiary)",
+                  borderColor: "var(--color-border)",
+                  color: "var(--color-text-primary)",
+                }}
+              />
+            </label>
+          </div>
+
+          {(
+            [
+              ["headingAware", "Split at Headings", "Start a new chunk at each heading and label chunks with it"],
+              ["codeAware", "Keep Code Blocks Whole", "Chunk code separately and only split it between lines"],
+            ] as const
+          ).map(([key, title, description]) => (
+            <div key={key} className="flex items-center justify-between">
+              <div>
+                <div className="text-sm" style={{ color: "var(--color-text-primary)" }}>
+                  {title}
+                </div>
+                <div className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+                  {description}
+                </div>
+              </div>
+              <button
+                onClick={() => updateChunkingDraft({ [key]: !chunkingDraft[key] })}
+                className="relative h-5 w-9 rounded-full transition-colors"
+                style={{
+                  backgroundColor: chunkingDraft[key]
+                    ? "var(--color-accent)"
+                    : "var(--color-bg-tertiary)",
+                }}
+              >
+                <span
+                  className="absolute left-0 top-0.5 h-4 w-4 rounded-full bg-white shadow transition-transform"
+                  style={{
+                    transform: chunkingDraft[key] ? "translateX(18px)" : "translateX(2px)",
+                  }}
+                />
+              </button>
+            </div>
+          ))}
+
+          <div className="flex gap-2">
+            <button
+              onClick={handlePreviewChunks}
+              disabled={!selectedPageId || !selectedNotebookId}
+              title={selectedPageId ? undefined : "Open a page to preview its chunks"}
+              className="flex-1 rounded-lg border px-4 py-2 text-sm font-medium transition-colors disabled:opacity-50"
+              style={{
+                borderColor: "var(--color-border)",
+                color: "var(--color-text-primary)",
+              }}
+            >
+              Preview Current Page
+            </button>
+            <button
+              onClick={handleRechunk}
+              disabled={isIndexing || isRebuildingIndex}
+              className="flex-1 rounded-lg px-4 py-2 text-sm font-medium transition-colors disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+            >
+              {isIndexing && indexingProgress
+                ? `Re-chunking ${indexingProgress.current}/${indexingProgress.total}...`
+                : chunkingChanged
+                  ? "Save & Re-chunk Library"
+                  : "Re-chunk Library"}
+            </button>
+          </div>
+
+          {chunkPreview && (
+            <div className="space-y-2">
+              <div className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+                {chunkPreview.length} chunk{chunkPreview.length === 1 ? "" : "s"}
+              </div>
+              <div className="max-h-64 space-y-2 overflow-y-auto">
+                {chunkPreview.map((chunk, i) => (
+                  <div
+                    key={i}
+                    className="whitespace-pre-wrap rounded border p-2 text-xs"
+                    style={{
+                      borderColor: "var(--color-border)",
+                      color: "var(--color-text-secondary)",
+                    }}
+                  >
+                    <div className="mb-1" style={{ color: "var(--color-text-muted)" }}>
+                      #{i + 1} · ~{Math.round(chunk.length / 4)} tokens
+                    </div>
+                    {chunk}
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
+        </div>
+      )}
+
       {/* Index Statistics */}
       {isConfigured && stats && (
         <div
@@ -0,0 +1,191 @@
+import { useEffect, useState } from "react";
+import { useSearchStore } from "../../stores/searchStore";
+import { useToastStore } from "../../stores/toastStore";
+import { getSearchHistory, removeSearchHistoryEntry, type SearchHistoryEntry } from "../../utils/api";
+import { isTauri } from "../../utils/platform";
+
+const inputStyle = {
+  backgroundColor: "var(--color-bg-tertiary)",
+  borderColor: "var(--color-border)",
+  color: "var(--color-text-primary)",
+};
+
+export function SearchHistorySettings() {
+  const { historySettings, loadRecentSearches, clearRecentSearches, updateHistorySettings } =
+    useSearchStore();
+  const [entries, setEntries] = useState<SearchHistoryEntry[]>([]);
+  const toast = useToastStore();
+
+  const refresh = async () => {
+    await loadRecentSearches();
+    try {
+      setEntries(await getSearchHistory(50));
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  useEffect(() => {
+    if (isTauri()) refresh();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  if (!isTauri()) {
+    return (
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        Search history is kept in this browser. Clear it from the search palette.
+      </p>
+    );
+  }
+
+  if (!historySettings) return null;
+
+  const save = async (changes: Partial<typeof historySettings>) => {
+    const next = { ...historySettings, ...changes };
+    if (!next.enabled && historySettings.enabled && entries.length > 0) {
+      if (!window.confirm("Turning off search history forgets all past searches. Continue?")) return;
+    }
+    try {
+      await updateHistorySettings(next);
+      await refresh();
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  const handleRemove = async (query: string) => {
+    try {
+      await removeSearchHistoryEntry(query);
+      await refresh();
+    } catch (err) {
+      toast.error(`${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  const handleClear = async () => {
+    if (!window.confirm(`Forget all ${entries.length} past searches?`)) return;
+    clearRecentSearches();
+    setEntries([]);
+  };
+
+  return (
+    <div className="space-y-6">
+      <div className="flex items-center justify-between">
+        <div>
+          <label className="text-sm font-medium" style={{ color: "var(--color-text-primary)" }}>
+            Remember Searches
+          </label>
+          <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+            Past searches are suggested in the search palette and the terminal UI. They are
+            stored with this library and never leave the device.
+          </p>
+        </div>
+        <button
+          onClick={() => save({ enabled: !historySettings.enabled })}
+          className="relative h-6 w-11 flex-shrink-0 rounded-full transition-colors"
+          style={{
+            backgroundColor: historySettings.enabled
+              ? "var(--color-accent)"
+              : "var(--color-bg-tertiary)",
+          }}
+        >
+          <span
+            className="absolute left-0 top-0.5 h-5 w-5 rounded-full bg-white shadow transition-transform"
+            style={{
+              transform: historySettings.enabled ? "translateX(22px)" : "translateX(2px)",
+            }}
+          />
+        </button>
+      </div>
+
+      {historySettings.enabled && (
+        <div className="grid grid-cols-2 gap-4">
+          <div>
+            <label className="mb-1 block text-xs font-medium" style={{ color: "var(--color-text-muted)" }}>
+              Keep at most
+            </label>
+            <select
+              value={historySettings.maxEntries}
+              onChange={(e) => save({ maxEntries: parseInt(e.target.value, 10) })}
+              className="w-full rounded-lg border px-3 py-2 text-sm outline-none dark-select"
+              style={inputStyle}
+            >
+              {[50, 100, 200, 500, 1000].map((n) => (
+                <option key={n} value={n}>
+                  {n} searches
+                </option>
+              ))}
+            </select>
+          </div>
+          <div>
+            <label className="mb-1 block text-xs font-medium" style={{ color: "var(--color-text-muted)" }}>
+              Forget searches after
+            </label>
+            <select
+              value={historySettings.retentionDays ?? ""}
+              onChange={(e) =>
+                save({ retentionDays: e.target.value ? parseInt(e.target.value, 10) : null })
+              }
+              className="w-full rounded-lg border px-3 py-2 text-sm outline-none dark-select"
+              style={inputStyle}
+            >
+              <option value="7">7 days</option>
+              <option value="30">30 days</option>
+              <option value="90">90 days</option>
+              <option value="365">1 year</option>
+              <option value="">Never</option>
+            </select>
+          </div>
+        </div>
+      )}
+
+      {historySettings.enabled && (
+        <div>
+          <div className="mb-2 flex items-center justify-between">
+            <label className="text-xs font-medium" style={{ color: "var(--color-text-muted)" }}>
+              Recent Searches
+            </label>
+            {entries.length > 0 && (
+              <button
+                onClick={handleClear}
+                className="rounded px-2 py-1 text-xs"
+                style={{ color: "var(--color-error)" }}
+              >
+                Clear all
+              </button>
+            )}
+          </div>
+          <div className="divide-y rounded-lg border text-xs" style={{ borderColor: "var(--color-border)" }}>
+            {entries.length === 0 && (
+              <div className="px-3 py-2" style={{ color: "var(--color-text-muted)" }}>
+                No searches yet.
+              </div>
+            )}
+            {entries.map((entry) => (
+              <div
+                key={entry.query}
+                className="flex items-center justify-between px-3 py-1.5"
+                style={{ borderColor: "var(--color-border)" }}
+              >
+                <span className="truncate" style={{ color: "var(--color-text-primary)" }}>
+                  {entry.query}
+                  <span style={{ color: "var(--color-text-muted)" }}>
+                    {" "}
+                    · {entry.searchCount}× · {new Date(entry.lastSearchedAt).toLocaleDateString()}
+                  </span>
+                </span>
+                <button
+                  onClick={() => handleRemove(entry.query)}
+                  className="rounded px-1.5 py-0.5"
+                  style={{ color: "var(--color-text-muted)" }}
+                >
+                  Remove
+                </button>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
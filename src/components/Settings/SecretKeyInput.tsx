@@ -0,0 +1,129 @@
+import { useState, type ReactNode } from "react";
+import { useSecretsStore } from "../../stores/secretsStore";
+import { useToastStore } from "../../stores/toastStore";
+import { isTauri } from "../../utils/platform";
+
+/**
+ * API key field backed by the secrets store. A stored key shows only its
+ * hint, with Replace and Remove. Without the desktop app, or while the
+ * store is locked, `children` (the plain localStorage input) is shown
+ * instead.
+ */
+export function SecretKeyInput({
+  secretId,
+  placeholder,
+  children,
+}: {
+  secretId: string;
+  placeholder: string;
+  children: ReactNode;
+}) {
+  const status = useSecretsStore((s) => s.status);
+  const info = useSecretsStore((s) => s.stored[secretId]);
+  const save = useSecretsStore((s) => s.save);
+  const remove = useSecretsStore((s) => s.remove);
+  const toast = useToastStore();
+  const [value, setValue] = useState("");
+  const [isReplacing, setIsReplacing] = useState(false);
+  const [isSaving, setIsSaving] = useState(false);
+
+  if (!isTauri() || !status?.unlocked) return <>{children}</>;
+
+  const handleSave = async () => {
+    if (!value.trim()) return;
+    try {
+      setIsSaving(true);
+      await save(secretId, value.trim());
+      setValue("");
+      setIsReplacing(false);
+    } catch (err) {
+      toast.error(`Failed to store key: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const handleRemove = async () => {
+    try {
+      await remove(secretId);
+    } catch (err) {
+      toast.error(`Failed to remove key: ${(err as { message?: string }).message ?? err}`);
+    }
+  };
+
+  if (info && !isReplacing) {
+    return (
+      <div
+        className="flex items-center justify-between rounded-lg border px-3 py-2 text-sm"
+        style={{
+          backgroundColor: "var(--color-bg-tertiary)",
+          borderColor: "var(--color-border)",
+        }}
+      >
+        <span style={{ color: "var(--color-text-secondary)" }}>
+          Stored securely · ends in{" "}
+          <span className="font-mono">{info.hint}</span>
+        </span>
+        <div className="flex gap-2 text-xs">
+          <button
+            type="button"
+            onClick={() => setIsReplacing(true)}
+            className="rounded px-2 py-1"
+            style={{ color: "var(--color-text-secondary)" }}
+          >
+            Replace
+          </button>
+          <button
+            type="button"
+            onClick={handleRemove}
+            className="rounded px-2 py-1"
+            style={{ color: "var(--color-error)" }}
+          >
+            Remove
+          </button>
+        </div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="flex gap-2">
+      <input
+        type="password"
+        value={value}
+        onChange={(e) => setValue(e.target.value)}
+        onKeyDown={(e) => e.key === "Enter" && handleSave()}
+        placeholder={placeholder}
+        autoComplete="off"
+        className="w-full rounded-lg border px-3 py-2 text-sm outline-none transition-colors focus:border-[--color-accent]"
+        style={{
+          backgroundColor: "var(--color-bg-tertiary)",
+          borderColor: "var(--color-border)",
+          color: "var(--color-text-primary)",
+        }}
+      />
+      {isReplacing && (
+        <button
+          type="button"
+          onClick={() => {
+            setValue("");
+            setIsReplacing(false);
+          }}
+          className="rounded-lg px-3 py-2 text-sm"
+          style={{ color: "var(--color-text-secondary)" }}
+        >
+          Cancel
+        </button>
+      )}
+      <button
+        type="button"
+        onClick={handleSave}
+        disabled={isSaving || !value.trim()}
+        className="rounded-lg px-3 py-2 text-sm font-medium disabled:opacity-50"
+        style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+      >
+        {isSaving ? "Saving..." : "Save"}
+      </button>
+    </div>
+  );
+}
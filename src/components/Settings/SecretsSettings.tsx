@@ -0,0 +1,175 @@
+import { useState, useEffect } from "react";
+import { useSecretsStore } from "../../stores/secretsStore";
+import { useToastStore } from "../../stores/toastStore";
+import { isTauri } from "../../utils/platform";
+
+const inputStyle = {
+  backgroundColor: "var(--color-bg-tertiary)",
+  borderColor: "var(--color-border)",
+  color: "var(--color-text-primary)",
+};
+
+export function SecretsSettings() {
+  const { status, stored, load, unlock, setProtection, remove } = useSecretsStore();
+  const [passphrase, setPassphrase] = useState("");
+  const [confirm, setConfirm] = useState("");
+  const [isBusy, setIsBusy] = useState(false);
+  const toast = useToastStore();
+
+  useEffect(() => {
+    load().catch((err) => console.error("Failed to load secrets:", err));
+  }, [load]);
+
+  if (!isTauri()) {
+    return (
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        The encrypted secrets store is only available in the desktop app. In the
+        browser, API keys are kept in this browser's local storage.
+      </p>
+    );
+  }
+
+  const run = async (action: () => Promise<void>, failure: string) => {
+    try {
+      setIsBusy(true);
+      await action();
+      setPassphrase("");
+      setConfirm("");
+    } catch (err) {
+      toast.error(`${failure}: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const secrets = Object.values(stored).sort((a, b) => a.id.localeCompare(b.id));
+  const usesPassphrase = status?.protection === "passphrase";
+
+  return (
+    <div className="space-y-6">
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        API keys and sync passwords are encrypted on this device. Their values
+        are never shown again once stored; replace or remove them where they're
+        used.
+      </p>
+
+      {status && !status.unlocked ? (
+        <div className="space-y-2 rounded-lg border p-4" style={{ borderColor: "var(--color-border)" }}>
+          <div className="text-sm font-medium" style={{ color: "var(--color-text-primary)" }}>
+            Secrets are locked
+          </div>
+          <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+            {usesPassphrase
+              ? "Enter your passphrase to use stored keys this session."
+              : "The OS keyring is unavailable. Protect secrets with a passphrase instead."}
+          </p>
+          <div className="flex gap-2">
+            <input
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder="Passphrase"
+              className="w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+              style={inputStyle}
+            />
+            <button
+              onClick={() =>
+                run(
+                  usesPassphrase ? () => unlock(passphrase) : () => setProtection(passphrase),
+                  "Failed to unlock secrets"
+                )
+              }
+              disabled={isBusy || !passphrase}
+              className="rounded-lg px-4 py-1.5 text-sm font-medium disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+            >
+              {usesPassphrase ? "Unlock" : "Set"}
+            </button>
+          </div>
+        </div>
+      ) : (
+        <div className="space-y-2 rounded-lg border p-4" style={{ borderColor: "var(--color-border)" }}>
+          <div className="text-sm font-medium" style={{ color: "var(--color-text-primary)" }}>
+            Protection
+          </div>
+          <p className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+            {usesPassphrase
+              ? "Protected by a passphrase, asked for once per session."
+              : "Protected by a key in the OS keyring, unlocked automatically."}
+          </p>
+          <div className="flex gap-2">
+            <input
+              type="password"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              placeholder={usesPassphrase ? "New passphrase" : "Passphrase"}
+              className="w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+              style={inputStyle}
+            />
+            <input
+              type="password"
+              value={confirm}
+              onChange={(e) => setConfirm(e.target.value)}
+              placeholder="Confirm"
+              className="w-full rounded-md border px-2.5 py-1.5 text-sm outline-none"
+              style={inputStyle}
+            />
+            <button
+              onClick={() => run(() => setProtection(passphrase), "Failed to set passphrase")}
+              disabled={isBusy || !passphrase || passphrase !== confirm}
+              className="whitespace-nowrap rounded-lg px-4 py-1.5 text-sm font-medium disabled:opacity-50"
+              style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+            >
+              {usesPassphrase ? "Change" : "Use Passphrase"}
+            </button>
+          </div>
+          {usesPassphrase && (
+            <button
+              onClick={() => run(() => setProtection(null), "Failed to switch to the keyring")}
+              disabled={isBusy}
+              className="text-xs disabled:opacity-50"
+              style={{ color: "var(--color-text-secondary)" }}
+            >
+              Use the OS keyring instead
+            </button>
+          )}
+        </div>
+      )}
+
+      <div className="space-y-2">
+        {secrets.length === 0 && (
+          <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+            {status?.unlocked ? "No secrets stored." : `${status?.count ?? 0} stored secrets.`}
+          </p>
+        )}
+        {secrets.map((secret) => (
+          <div
+            key={secret.id}
+            className="flex items-center justify-between rounded-lg border p-3"
+            style={{
+              borderColor: "var(--color-border)",
+              backgroundColor: "var(--color-bg-secondary)",
+            }}
+          >
+            <div className="min-w-0">
+              <div className="font-mono text-sm" style={{ color: "var(--color-text-primary)" }}>
+                {secret.id}
+              </div>
+              <div className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+                ends in {secret.hint} · updated {new Date(secret.updatedAt).toLocaleDateString()}
+              </div>
+            </div>
+            <button
+              onClick={() => run(() => remove(secret.id), "Failed to remove secret")}
+              disabled={isBusy}
+              className="rounded px-2 py-1 text-xs"
+              style={{ color: "var(--color-error)" }}
+            >
+              Remove
+            </button>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -1,20 +1,29 @@
 import { useState, useEffect } from "react";
 import { useAIStore, DEFAULT_SYSTEM_PROMPT } from "../../stores/aiStore";
-import { getAiDaemonConfig } from "../../utils/api";
+import { getAiDaemonConfig, secretIds } from "../../utils/api";
 import { useWebResearchStore } from "../../stores/webResearchStore";
 import { useAudioStore } from "../../stores/audioStore";
 import { useThemeStore } from "../../stores/themeStore";
 import { ThemeSettings } from "./ThemeSettings";
 import { KeybindingsSettings } from "./KeybindingsSettings";
 import { MCPServersSettings } from "./MCPServersSettings";
+import { OllamaModelsSettings } from "./OllamaModelsSettings";
 import { RAGSettings } from "./RAGSettings";
 import { BackupScheduleSettings } from "./BackupScheduleSettings";
+import { AppConfigTransferSettings } from "./AppConfigTransferSettings";
+import { SnapshotSettings } from "./SnapshotSettings";
 import { ExternalSourcesSettings } from "./ExternalSourcesSettings";
 import { DailyNotesSettings } from "./DailyNotesSettings";
 import { MonitorSettings } from "./MonitorSettings";
+import { PythonEnvironmentSettings } from "./PythonEnvironmentSettings";
+import { AIProfilesSettings } from "./AIProfilesSettings";
+import { SecretsSettings } from "./SecretsSettings";
+import { SecretKeyInput } from "./SecretKeyInput";
 import { PluginSettings } from "./PluginSettings";
 import { ShareUploadSettings } from "./ShareUploadSettings";
 import { CloudSettings } from "./CloudSettings";
+import { SearchHistorySettings } from "./SearchHistorySettings";
+import { LogSettings } from "./LogSettings";
 import { LibrarySettingsPanel } from "../Library";
 import type { ProviderType, ProviderConfig } from "../../types/ai";
 import type { TTSProviderType } from "../../types/audio";
@@ -24,7 +33,9 @@ interface SettingsDialogProps {
   onClose: () => void;
   initialTab?:
     | "ai"
+    | "ai-profiles"
     | "web-research"
+    | "python"
     | "theme"
     | "system-prompt"
     | "libraries"
@@ -43,20 +54,27 @@ interface SettingsDialogProps {
 
 type TabId =
   | "ai"
+  | "ai-profiles"
+  | "secrets"
   | "web-research"
+  | "python"
   | "theme"
   | "system-prompt"
   | "libraries"
   | "keybindings"
   | "mcp"
   | "rag"
+  | "search-history"
   | "backup"
+  | "snapshots"
+  | "config-transfer"
   | "sharing"
   | "audio"
   | "voice"
   | "external-sources"
   | "daily-notes"
   | "monitor"
+  | "logs"
   | "plugins"
   | "cloud";
 
@@ -79,6 +97,8 @@ const TAB_CATEGORIES: TabCategory[] = [
     tabs: [
       { id: "libraries", label: "Libraries", icon: <IconLibrary /> },
       { id: "backup", label: "Backup", icon: <IconBackup /> },
+      { id: "snapshots", label: "Snapshots", icon: <IconSnapshot /> },
+      { id: "config-transfer", label: "Export Settings", icon: <IconTransfer /> },
       { id: "sharing", label: "Sharing", icon: <IconShare /> },
       { id: "external-sources", label: "External Sources", icon: <IconFileImport /> },
       { id: "cloud", label: "Cloud Sync", icon: <IconCloud /> },
@@ -88,9 +108,13 @@ const TAB_CATEGORIES: TabCategory[] = [
     name: "AI & Search",
     tabs: [
       { id: "ai", label: "AI Models", icon: <IconSparkles /> },
+      { id: "ai-profiles", label: "AI Profiles", icon: <IconLayers /> },
+      { id: "secrets", label: "API Keys", icon: <IconKey /> },
       { id: "system-prompt", label: "System Prompt", icon: <IconPrompt /> },
       { id: "rag", label: "Knowledge Base", icon: <IconBrain /> },
+      { id: "search-history", label: "Search History", icon: <IconSearchHistory /> },
       { id: "web-research", label: "Web Research", icon: <IconGlobe /> },
+      { id: "python", label: "Python Environment", icon: <IconTerminal /> },
     ],
   },
   {
@@ -106,6 +130,7 @@ const TAB_CATEGORIES: TabCategory[] = [
       { id: "mcp", label: "MCP Servers", icon: <IconPlug /> },
       { id: "plugins", label: "Plugins", icon: <IconPuzzle /> },
       { id: "monitor", label: "Monitor", icon: <IconMonitor /> },
+      { id: "logs", label: "Logs", icon: <IconLogs /> },
     ],
   },
 ];
@@ -276,18 +301,25 @@ export function SettingsDialog({
             {activeTab === "daily-notes" && <DailyNotesSettings />}
             {activeTab === "libraries" && <LibrarySettingsPanel />}
             {activeTab === "backup" && <BackupScheduleSettings />}
+            {activeTab === "snapshots" && <SnapshotSettings />}
+            {activeTab === "config-transfer" && <AppConfigTransferSettings />}
             {activeTab === "ai" && <AISettingsContent />}
+            {activeTab === "ai-profiles" && <AIProfilesSettings />}
+            {activeTab === "secrets" && <SecretsSettings />}
             {activeTab === "rag" && <RAGSettings />}
+            {activeTab === "search-history" && <SearchHistorySettings />}
             {activeTab === "system-prompt" && <SystemPromptSettingsContent />}
             {activeTab === "audio" && <AudioSettingsContent />}
             {activeTab === "voice" && <VoiceSettingsContent />}
             {activeTab === "mcp" && <MCPServersSettings />}
             {activeTab === "web-research" && <WebResearchSettingsContent />}
+            {activeTab === "python" && <PythonEnvironmentSettings />}
             {activeTab === "sharing" && <ShareUploadSettings />}
             {activeTab === "external-sources" && <ExternalSourcesSettings />}
             {activeTab === "plugins" && <PluginSettings />}
             {activeTab === "cloud" && <CloudSettings />}
             {activeTab === "monitor" && <MonitorSettings />}
+            {activeTab === "logs" && <LogSettings />}
           </div>
         </div>
       </div>
@@ -798,30 +830,37 @@ function ProviderAccordion({
               >
                 {provider.type === "bedrock" ? "AWS Credentials" : "API Key"}
               </label>
-              <div className="relative">
-                <input
-                  type={showApiKey ? "text" : "password"}
-                  value={provider.apiKey || ""}
-                  onChange={(e) => onApiKeyChange(e.target.value)}
-                  placeholder={
-                    info.apiKeyPlaceholder || `Enter your ${info.label} API key`
-                  }
-                  className="w-full rounded-lg border px-3 py-2 pr-10 text-sm outline-none transition-colors focus:border-[--color-accent]"
-                  style={{
-                    backgroundColor: "var(--color-bg-tertiary)",
-                    borderColor: "var(--color-border)",
-                    color: "var(--color-text-primary)",
-                  }}
-                />
-                <button
-                  type="button"
-                  onClick={onToggleApiKeyVisibility}
-                  className="absolute right-2 top-1/2 -translate-y-1/2 rounded p-1 transition-colors hover:bg-[--color-bg-secondary]"
-                  style={{ color: "var(--color-text-muted)" }}
-                >
-                  {showApiKey ? <IconEyeOff /> : <IconEye />}
-                </button>
-              </div>
+              <SecretKeyInput
+                secretId={secretIds.ai(provider.type)}
+                placeholder={
+                  info.apiKeyPlaceholder || `Enter your ${info.label} API key`
+                }
+              >
+                <div className="relative">
+                  <input
+                    type={showApiKey ? "text" : "password"}
+                    value={provider.apiKey || ""}
+                    onChange={(e) => onApiKeyChange(e.target.value)}
+                    placeholder={
+                      info.apiKeyPlaceholder || `Enter your ${info.label} API key`
+                    }
+                    className="w-full rounded-lg border px-3 py-2 pr-10 text-sm outline-none transition-colors focus:border-[--color-accent]"
+                    style={{
+                      backgroundColor: "var(--color-bg-tertiary)",
+                      borderColor: "var(--color-border)",
+                      color: "var(--color-text-primary)",
+                    }}
+                  />
+                  <button
+                    type="button"
+                    onClick={onToggleApiKeyVisibility}
+                    className="absolute right-2 top-1/2 -translate-y-1/2 rounded p-1 transition-colors hover:bg-[--color-bg-secondary]"
+                    style={{ color: "var(--color-text-muted)" }}
+                  >
+                    {showApiKey ? <IconEyeOff /> : <IconEye />}
+                  </button>
+                </div>
+              </SecretKeyInput>
               {daemonHasKey && !provider.apiKey && (
                 <p className="mt-1 text-xs" style={{ color: "var(--color-accent)" }}>
                   A key for this provider is stored on the daemon and used
@@ -1037,6 +1076,14 @@ function ProviderAccordion({
               </button>
             </div>
           </div>
+
+          {/* Local model management */}
+          {provider.type === "ollama" && (
+            <OllamaModelsSettings
+              baseUrl={provider.baseUrl}
+              onModelsChanged={onDiscoverModels}
+            />
+          )}
         </div>
       )}
     </div>
@@ -1217,28 +1264,33 @@ function AudioSettingsContent() {
           >
             API Key
           </label>
-          <div className="relative">
-            <input
-              type={showApiKey ? "text" : "password"}
-              value={settings.ttsApiKey}
-              onChange={(e) => setTtsApiKey(e.target.value)}
-              placeholder={`Enter your ${currentProviderInfo.label} API key`}
-              className="w-full rounded-lg border px-3 py-2.5 pr-10 text-sm outline-none transition-colors focus:border-[--color-accent]"
-              style={{
-                backgroundColor: "var(--color-bg-secondary)",
-                borderColor: "var(--color-border)",
-                color: "var(--color-text-primary)",
-              }}
-            />
-            <button
-              type="button"
-              onClick={() => setShowApiKey(!showApiKey)}
-              className="absolute right-2 top-1/2 -translate-y-1/2 rounded p-1 transition-colors hover:bg-[--color-bg-tertiary]"
-              style={{ color: "var(--color-text-muted)" }}
-            >
-              {showApiKey ? <IconEyeOff /> : <IconEye />}
-            </button>
-          </div>
+          <SecretKeyInput
+            secretId={secretIds.tts(settings.ttsProvider)}
+            placeholder={`Enter your ${currentProviderInfo.label} API key`}
+          >
+            <div className="relative">
+              <input
+                type={showApiKey ? "text" : "password"}
+                value={settings.ttsApiKey}
+                onChange={(e) => setTtsApiKey(e.target.value)}
+                placeholder={`Enter your ${currentProviderInfo.label} API key`}
+                className="w-full rounded-lg border px-3 py-2.5 pr-10 text-sm outline-none transition-colors focus:border-[--color-accent]"
+                style={{
+                  backgroundColor: "var(--color-bg-secondary)",
+                  borderColor: "var(--color-border)",
+                  color: "var(--color-text-primary)",
+                }}
+              />
+              <button
+                type="button"
+                onClick={() => setShowApiKey(!showApiKey)}
+                className="absolute right-2 top-1/2 -translate-y-1/2 rounded p-1 transition-colors hover:bg-[--color-bg-tertiary]"
+                style={{ color: "var(--color-text-muted)" }}
+              >
+                {showApiKey ? <IconEyeOff /> : <IconEye />}
+              </button>
+            </div>
+          </SecretKeyInput>
           <p
             className="mt-1 text-xs"
             style={{ color: "var(--color-text-muted)" }}
@@ -1684,28 +1736,30 @@ function WebResearchSettingsContent() {
         >
           Tavily API Key
         </label>
-        <div className="relative">
-          <input
-            type={showApiKey ? "text" : "password"}
-            value={settings.tavilyApiKey}
-            onChange={(e) => setTavilyApiKey(e.target.value)}
-            placeholder="Enter your Tavily API key"
-            className="w-full rounded-lg border px-3 py-2.5 pr-10 text-sm outline-none transition-colors focus:border-[--color-accent]"
-            style={{
-              backgroundColor: "var(--color-bg-secondary)",
-              borderColor: "var(--color-border)",
-              color: "var(--color-text-primary)",
-            }}
-          />
-          <button
-            type="button"
-            onClick={() => setShowApiKey(!showApiKey)}
-            className="absolute right-2 top-1/2 -translate-y-1/2 rounded p-1 transition-colors hover:bg-[--color-bg-tertiary]"
-            style={{ color: "var(--color-text-muted)" }}
-          >
-            {showApiKey ? <IconEyeOff /> : <IconEye />}
-          </button>
-        </div>
+        <SecretKeyInput secretId={secretIds.tavily} placeholder="Enter your Tavily API key">
+          <div className="relative">
+            <input
+              type={showApiKey ? "text" : "password"}
+              value={settings.tavilyApiKey}
+              onChange={(e) => setTavilyApiKey(e.target.value)}
+              placeholder="Enter your Tavily API key"
+              className="w-full rounded-lg border px-3 py-2.5 pr-10 text-sm outline-none transition-colors focus:border-[--color-accent]"
+              style={{
+                backgroundColor: "var(--color-bg-secondary)",
+                borderColor: "var(--color-border)",
+                color: "var(--color-text-primary)",
+              }}
+            />
+            <button
+              type="button"
+              onClick={() => setShowApiKey(!showApiKey)}
+              className="absolute right-2 top-1/2 -translate-y-1/2 rounded p-1 transition-colors hover:bg-[--color-bg-tertiary]"
+              style={{ color: "var(--color-text-muted)" }}
+            >
+              {showApiKey ? <IconEyeOff /> : <IconEye />}
+            </button>
+          </div>
+        </SecretKeyInput>
         <p
           className="mt-1.5 text-xs"
           style={{ color: "var(--color-text-muted)" }}
@@ -2261,6 +2315,67 @@ function IconLayers() {
   );
 }
 
+function IconLogs() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="16"
+      height="16"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <path d="M14 2H6a2 2 0 0 0-2 2v16a2 2 0 0 0 2 2h12a2 2 0 0 0 2-2V8z" />
+      <path d="M14 2v6h6" />
+      <path d="M8 13h8" />
+      <path d="M8 17h5" />
+    </svg>
+  );
+}
+
+function IconSearchHistory() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="16"
+      height="16"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <path d="M3 12a9 9 0 1 0 9-9 9.75 9.75 0 0 0-6.74 2.74L3 8" />
+      <path d="M3 3v5h5" />
+      <path d="M12 7v5l4 2" />
+    </svg>
+  );
+}
+
+function IconKey() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="16"
+      height="16"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <circle cx="7.5" cy="15.5" r="5.5" />
+      <path d="m21 2-9.6 9.6" />
+      <path d="m15.5 7.5 3 3L22 7l-3-3" />
+    </svg>
+  );
+}
+
 function IconLibrary() {
   return (
     <svg
@@ -2383,6 +2498,25 @@ function IconPlug() {
   );
 }
 
+function IconTerminal() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="16"
+      height="16"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <polyline points="4 17 10 11 4 5" />
+      <line x1="12" x2="20" y1="19" y2="19" />
+    </svg>
+  );
+}
+
 function IconPuzzle() {
   return (
     <svg
@@ -2464,6 +2598,47 @@ function IconBackup() {
   );
 }
 
+function IconSnapshot() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="16"
+      height="16"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <path d="M3 12a9 9 0 1 0 3-6.7L3 8" />
+      <polyline points="3 3 3 8 8 8" />
+      <polyline points="12 7 12 12 15 15" />
+    </svg>
+  );
+}
+
+function IconTransfer() {
+  return (
+    <svg
+      xmlns="http://www.w3.org/2000/svg"
+      width="16"
+      height="16"
+      viewBox="0 0 24 24"
+      fill="none"
+      stroke="currentColor"
+      strokeWidth="2"
+      strokeLinecap="round"
+      strokeLinejoin="round"
+    >
+      <polyline points="17 1 21 5 17 9" />
+      <path d="M3 11V9a4 4 0 0 1 4-4h14" />
+      <polyline points="7 23 3 19 7 15" />
+      <path d="M21 13v2a4 4 0 0 1-4 4H3" />
+    </svg>
+  );
+}
+
 function IconMic() {
   return (
     <svg
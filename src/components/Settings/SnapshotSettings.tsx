@@ -0,0 +1,181 @@
+import { useCallback, useEffect, useState } from "react";
+import {
+  createLibrarySnapshot,
+  deleteLibrarySnapshot,
+  listSnapshots,
+  rebuildSearchIndex,
+  rollbackToSnapshot,
+  type LibrarySnapshot,
+} from "../../utils/api";
+import { isTauri } from "../../utils/platform";
+import { useToastStore } from "../../stores/toastStore";
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  return `${(bytes / (1024 * 1024 * 1024)).toFixed(1)} GB`;
+}
+
+export function SnapshotSettings() {
+  const [snapshots, setSnapshots] = useState<LibrarySnapshot[]>([]);
+  const [label, setLabel] = useState("");
+  const [busy, setBusy] = useState<string | null>(null);
+  const toast = useToastStore();
+
+  const load = useCallback(async () => {
+    try {
+      setSnapshots(await listSnapshots());
+    } catch (err) {
+      useToastStore
+        .getState()
+        .error(`Failed to load snapshots: ${(err as { message?: string }).message ?? err}`);
+    }
+  }, []);
+
+  useEffect(() => {
+    if (isTauri()) load();
+  }, [load]);
+
+  if (!isTauri()) {
+    return (
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        Library snapshots are only available in the desktop app.
+      </p>
+    );
+  }
+
+  const handleCreate = async () => {
+    try {
+      setBusy("create");
+      const snapshot = await createLibrarySnapshot(label || undefined);
+      setLabel("");
+      toast.success(`Snapshot "${snapshot.label}" created`);
+      await load();
+    } catch (err) {
+      toast.error(`Snapshot failed: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleRollback = async (snapshot: LibrarySnapshot) => {
+    const when = new Date(snapshot.createdAt).toLocaleString();
+    if (
+      !window.confirm(
+        `Put the library back to "${snapshot.label}" (${when})? The current state is snapshotted first, so this can be undone.`
+      )
+    ) {
+      return;
+    }
+    try {
+      setBusy(snapshot.id);
+      await rollbackToSnapshot(snapshot.id);
+      try {
+        await rebuildSearchIndex();
+      } catch (err) {
+        console.warn("Search index rebuild after rollback failed:", err);
+      }
+      window.location.reload();
+    } catch (err) {
+      toast.error(`Rollback failed: ${(err as { message?: string }).message ?? err}`);
+      setBusy(null);
+    }
+  };
+
+  const handleDelete = async (snapshot: LibrarySnapshot) => {
+    if (!window.confirm(`Delete snapshot "${snapshot.label}"?`)) return;
+    try {
+      setBusy(snapshot.id);
+      await deleteLibrarySnapshot(snapshot.id);
+      await load();
+    } catch (err) {
+      toast.error(`Delete failed: ${(err as { message?: string }).message ?? err}`);
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <div className="space-y-6">
+      <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+        A snapshot freezes the whole library — pages, indexes and settings — so
+        it can be put back in one step. Take one before a large import or an AI
+        bulk edit. Files that haven't changed are shared between snapshots, so
+        they take little extra space.
+      </p>
+
+      <div className="flex gap-3">
+        <input
+          type="text"
+          value={label}
+          onChange={(e) => setLabel(e.target.value)}
+          placeholder="Label (optional)"
+          className="flex-1 rounded-lg border px-3 py-2 text-sm"
+          style={{
+            backgroundColor: "var(--color-bg-secondary)",
+            borderColor: "var(--color-border)",
+            color: "var(--color-text-primary)",
+          }}
+        />
+        <button
+          onClick={handleCreate}
+          disabled={busy !== null}
+          className="rounded-lg px-4 py-2.5 text-sm font-medium disabled:opacity-50"
+          style={{ backgroundColor: "var(--color-accent)", color: "white" }}
+        >
+          {busy === "create" ? "Taking Snapshot..." : "Take Snapshot"}
+        </button>
+      </div>
+
+      {snapshots.length === 0 ? (
+        <p className="text-sm" style={{ color: "var(--color-text-muted)" }}>
+          No snapshots yet.
+        </p>
+      ) : (
+        <div
+          className="divide-y rounded-lg border"
+          style={{ borderColor: "var(--color-border)" }}
+        >
+          {snapshots.map((snapshot) => (
+            <div key={snapshot.id} className="flex items-center gap-3 p-3">
+              <div className="min-w-0 flex-1">
+                <div
+                  className="truncate text-sm font-medium"
+                  style={{ color: "var(--color-text-primary)" }}
+                >
+                  {snapshot.label}
+                </div>
+                <div className="text-xs" style={{ color: "var(--color-text-muted)" }}>
+                  {new Date(snapshot.createdAt).toLocaleString()} ·{" "}
+                  {snapshot.fileCount} files · {formatBytes(snapshot.totalBytes)}
+                  {snapshot.sharedBytes > 0 &&
+                    ` (${formatBytes(snapshot.totalBytes - snapshot.sharedBytes)} new)`}
+                </div>
+              </div>
+              <button
+                onClick={() => handleRollback(snapshot)}
+                disabled={busy !== null}
+                className="rounded-lg px-3 py-1.5 text-xs font-medium disabled:opacity-50"
+                style={{
+                  backgroundColor: "var(--color-bg-tertiary)",
+                  color: "var(--color-text-secondary)",
+                }}
+              >
+                {busy === snapshot.id ? "Working..." : "Roll Back"}
+              </button>
+              <button
+                onClick={() => handleDelete(snapshot)}
+                disabled={busy !== null}
+                className="rounded-lg px-3 py-1.5 text-xs font-medium disabled:opacity-50"
+                style={{ color: "var(--color-error, #ef4444)" }}
+              >
+                Delete
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { invoke } from "../../platform/core";
 import { isTauri } from "../../utils/platform";
 import { daemonPost } from "../../utils/daemon";
+import type { Page } from "../../types/page";
 
 export type ShareType =
   | { type: "single_page"; pageId: string }
@@ -41,6 +42,37 @@ export async function sharePage(
   });
 }
 
+export interface EncryptedShareResponse {
+  outputPath: string;
+  assetCount: number;
+  sizeBytes: number;
+}
+
+/**
+ * Write a page as a single password-protected HTML file (assets inlined)
+ * that opens in any browser. Desktop only; nothing is stored or served.
+ */
+export async function sharePageEncrypted(
+  notebookId: string,
+  pageId: string,
+  password: string,
+  outputPath: string,
+  theme?: string
+): Promise<EncryptedShareResponse> {
+  return invoke("share_page_encrypted", {
+    request: { notebookId, pageId, password, outputPath, theme },
+  });
+}
+
+/** Decrypt a file from {@link sharePageEncrypted} into a new page. */
+export async function importSharedPage(
+  path: string,
+  password: string,
+  notebookId: string
+): Promise<Page> {
+  return invoke("import_shared_page", { path, password, notebookId });
+}
+
 export interface PublishToNousResponse {
   share: ShareRecord;
   url: string;
@@ -3,6 +3,7 @@ import type { Page } from "../../types/page";
 import { usePageStore } from "../../stores/pageStore";
 import { useAIStore } from "../../stores/aiStore";
 import { useTagStore } from "../../stores/tagStore";
+import { hasAiKey, useHasAiKey } from "../../stores/secretsStore";
 import { aiSuggestTags } from "../../utils/api";
 
 interface TagEditorProps {
@@ -51,7 +52,7 @@ export function TagEditor({ page, onTagsChange }: TagEditorProps) {
   const allStoreTags = useTagStore((s) => s.getTagsByFrequency);
 
   // Get active API key for UI checks
-  const activeApiKey = getActiveApiKey();
+  const hasActiveKey = useHasAiKey(getActiveProviderType(), getActiveApiKey());
 
   const tags = page.tags || [];
 
@@ -132,7 +133,7 @@ export function TagEditor({ page, onTagsChange }: TagEditorProps) {
 
   const fetchAISuggestions = useCallback(async () => {
     const apiKey = getActiveApiKey();
-    if (!apiKey) {
+    if (!hasAiKey(getActiveProviderType(), apiKey)) {
       setSuggestions([]);
       return;
     }
@@ -162,7 +163,7 @@ export function TagEditor({ page, onTagsChange }: TagEditorProps) {
 
   const handleShowSuggestions = () => {
     setShowSuggestions(true);
-    if (suggestions.length === 0 && activeApiKey) {
+    if (suggestions.length === 0 && hasActiveKey) {
       fetchAISuggestions();
     }
   };
@@ -343,7 +344,7 @@ export function TagEditor({ page, onTagsChange }: TagEditorProps) {
               className="py-4 text-center text-xs"
               style={{ color: "var(--color-text-muted)" }}
             >
-              {activeApiKey
+              {hasActiveKey
                 ? "No suggestions available"
                 : "Configure AI in Settings"}
             </div>
@@ -383,7 +384,7 @@ export function TagEditor({ page, onTagsChange }: TagEditorProps) {
 
           <button
             onClick={fetchAISuggestions}
-            disabled={isLoadingSuggestions || !activeApiKey}
+            disabled={isLoadingSuggestions || !hasActiveKey}
             className="mt-2 w-full rounded py-1 text-xs transition-colors hover:bg-white/10 disabled:opacity-50"
             style={{ color: "var(--color-text-muted)" }}
           >
@@ -4,7 +4,11 @@ import {
   getPageVersions,
   getPageVersion,
   restorePageVersion,
+  listRemotePageVersions,
+  getRemotePageVersion,
+  restoreRemotePageVersion,
   type PageVersion,
+  type RemotePageVersion,
 } from "../../utils/api";
 import { usePageStore } from "../../stores/pageStore";
 import { HistoryBlockRenderer } from "../PageHistory/HistoryBlockRenderer";
@@ -13,10 +17,44 @@ import { computeBlockDiff, type BlockDiff } from "../../utils/diff";
 
 type ViewMode = "preview" | "changes";
 
+/** Where versions come from: this device's snapshots or the sync server */
+type VersionSource = "local" | "server";
+
+/** A version as listed, whichever source it came from */
+interface ListedVersion {
+  name: string;
+  ts: string;
+  /** Right-hand label: block count or the device that uploaded it */
+  detail: string;
+  preview?: string;
+  changesSince: number;
+}
+
+function fromLocal(v: PageVersion): ListedVersion {
+  return {
+    name: v.name,
+    ts: v.ts,
+    detail: `${v.blockCount} block${v.blockCount !== 1 ? "s" : ""}`,
+    preview: v.preview,
+    changesSince: v.changesSince,
+  };
+}
+
+function fromServer(v: RemotePageVersion): ListedVersion {
+  return {
+    name: v.name,
+    ts: v.createdAt,
+    detail: v.device ?? "Unknown device",
+    changesSince: 0,
+  };
+}
+
 interface VersionHistoryDialogProps {
   isOpen: boolean;
   page: Page | null;
   onClose: () => void;
+  /** The page's notebook syncs, so server versions can be browsed too */
+  syncEnabled?: boolean;
 }
 
 /** Human-friendly relative time (e.g. "2 hours ago"). Pure — exported for tests. */
@@ -41,17 +79,21 @@ export function formatRelativeTime(iso: string, now: number = Date.now()): strin
  * Version History panel sourced from a page's always-on local snapshots + oplog
  * (distinct from the Git-based PageHistoryDialog). Lists restorable snapshots,
  * previews each (or diffs it against the current page), and restores on demand.
+ * For synced notebooks it can also browse the versions kept on the sync server,
+ * which cover edits made on other devices.
  */
 export function VersionHistoryDialog({
   isOpen,
   page,
   onClose,
+  syncEnabled = false,
 }: VersionHistoryDialogProps) {
   const refreshPages = usePageStore((s) => s.refreshPages);
 
   const [isLoading, setIsLoading] = useState(true);
-  const [versions, setVersions] = useState<PageVersion[]>([]);
-  const [selected, setSelected] = useState<PageVersion | null>(null);
+  const [source, setSource] = useState<VersionSource>("local");
+  const [versions, setVersions] = useState<ListedVersion[]>([]);
+  const [selected, setSelected] = useState<ListedVersion | null>(null);
   const [snapshotBlocks, setSnapshotBlocks] = useState<EditorBlock[] | null>(null);
   const [snapshotTitle, setSnapshotTitle] = useState<string>("");
   const [isLoadingPreview, setIsLoadingPreview] = useState(false);
@@ -72,7 +114,10 @@ export function VersionHistoryDialog({
       setViewMode("preview");
       setConfirmingRestore(false);
       try {
-        const list = await getPageVersions(page.notebookId, page.id);
+        const list =
+          source === "server"
+            ? (await listRemotePageVersions(page.notebookId, page.id)).map(fromServer)
+            : (await getPageVersions(page.notebookId, page.id)).map(fromLocal);
         if (!cancelled) setVersions(list);
       } catch (err) {
         if (!cancelled)
@@ -84,20 +129,31 @@ export function VersionHistoryDialog({
     return () => {
       cancelled = true;
     };
-  }, [isOpen, page]);
+  }, [isOpen, page, source]);
+
+  // Reopening starts from this device's snapshots again.
+  useEffect(() => {
+    if (!isOpen) setSource("local");
+  }, [isOpen]);
 
   // Load a selected snapshot's full content for preview / diff.
   const selectVersion = useCallback(
-    async (version: PageVersion) => {
+    async (version: ListedVersion) => {
       if (!page) return;
       setSelected(version);
       setConfirmingRestore(false);
       setIsLoadingPreview(true);
       setError(null);
       try {
-        const snap = await getPageVersion(page.notebookId, page.id, version.name);
-        setSnapshotBlocks(snap.content?.blocks ?? []);
-        setSnapshotTitle(snap.title || "Untitled");
+        if (source === "server") {
+          const data = await getRemotePageVersion(page.notebookId, page.id, version.name);
+          setSnapshotBlocks(data.blocks ?? []);
+          setSnapshotTitle(page.title || "Untitled");
+        } else {
+          const snap = await getPageVersion(page.notebookId, page.id, version.name);
+          setSnapshotBlocks(snap.content?.blocks ?? []);
+          setSnapshotTitle(snap.title || "Untitled");
+        }
       } catch (err) {
         setError(err instanceof Error ? err.message : "Failed to load snapshot");
         setSnapshotBlocks(null);
@@ -105,7 +161,7 @@ export function VersionHistoryDialog({
         setIsLoadingPreview(false);
       }
     },
-    [page]
+    [page, source]
   );
 
   const currentBlocks = page?.content?.blocks ?? [];
@@ -125,7 +181,11 @@ export function VersionHistoryDialog({
     setIsRestoring(true);
     setError(null);
     try {
-      await restorePageVersion(page.notebookId, page.id, selected.name);
+      if (source === "server") {
+        await restoreRemotePageVersion(page.notebookId, page.id, selected.name);
+      } else {
+        await restorePageVersion(page.notebookId, page.id, selected.name);
+      }
       // Refresh so every open pane re-renders with the restored content.
       await refreshPages([page.id]);
       onClose();
@@ -135,7 +195,7 @@ export function VersionHistoryDialog({
       setIsRestoring(false);
       setConfirmingRestore(false);
     }
-  }, [page, selected, confirmingRestore, refreshPages, onClose]);
+  }, [page, selected, source, confirmingRestore, refreshPages, onClose]);
 
   // Escape closes; arrows / j-k navigate the list.
   useEffect(() => {
@@ -189,9 +249,31 @@ export function VersionHistoryDialog({
               Version History
             </h2>
             <p className="mt-0.5 text-sm" style={{ color: "var(--color-text-muted)" }}>
-              {page.title || "Untitled"} · local snapshots
+              {page.title || "Untitled"} ·{" "}
+              {source === "server" ? "sync server" : "local snapshots"}
             </p>
           </div>
+          {syncEnabled && (
+            <div
+              className="ml-auto mr-3 flex shrink-0 gap-1 rounded-lg p-0.5"
+              style={{ backgroundColor: "var(--color-bg-tertiary)" }}
+            >
+              {(["local", "server"] as VersionSource[]).map((s) => (
+                <button
+                  key={s}
+                  onClick={() => setSource(s)}
+                  className="rounded-md px-3 py-1 text-xs font-medium transition-colors"
+                  style={{
+                    backgroundColor:
+                      source === s ? "rgba(139, 92, 246, 0.15)" : "transparent",
+                    color: source === s ? "var(--color-accent)" : "var(--color-text-muted)",
+                  }}
+                >
+                  {s === "local" ? "This device" : "Sync server"}
+                </button>
+              ))}
+            </div>
+          )}
           <button
             onClick={onClose}
             aria-label="Close"
@@ -218,7 +300,9 @@ export function VersionHistoryDialog({
               >
                 No saved versions yet.
                 <br />
-                Snapshots are captured automatically as you edit.
+                {source === "server"
+                  ? "Versions are uploaded at most hourly when this page syncs."
+                  : "Snapshots are captured automatically as you edit."}
               </p>
             </div>
           ) : (
@@ -254,7 +338,7 @@ export function VersionHistoryDialog({
                           className="shrink-0 text-[10px]"
                           style={{ color: "var(--color-text-muted)" }}
                         >
-                          {v.blockCount} block{v.blockCount !== 1 ? "s" : ""}
+                          {v.detail}
                         </span>
                       </div>
                       {v.preview && (
@@ -2,6 +2,7 @@ import { useState, useEffect } from "react";
 import { transcribeVideo } from "../../utils/videoApi";
 import { aiChat } from "../../utils/api";
 import { useAIStore } from "../../stores/aiStore";
+import { hasAiKey } from "../../stores/secretsStore";
 import type { TranscriptionResult } from "../../types/video";
 
 interface TranscriptionDialogProps {
@@ -80,7 +81,7 @@ export function TranscriptionDialog({
           const apiKey = getActiveApiKey();
           const model = getActiveModel();
 
-          if (!apiKey) {
+          if (!hasAiKey(providerType, apiKey)) {
             console.warn("No API key configured for AI provider. Skipping summary generation.");
             console.warn("Please configure your AI API key in Settings > AI.");
           } else {
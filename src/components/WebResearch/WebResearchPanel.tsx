@@ -4,7 +4,8 @@ import { useWebResearchStore } from "../../stores/webResearchStore";
 import { useAIStore } from "../../stores/aiStore";
 import { useNotebookStore } from "../../stores/notebookStore";
 import { usePageStore } from "../../stores/pageStore";
-import { webSearch, scrapeUrl, summarizeResearch } from "../../utils/api";
+import { useSecretsStore } from "../../stores/secretsStore";
+import { webSearch, scrapeUrl, summarizeResearch, secretIds } from "../../utils/api";
 import { createPage } from "../../utils/api";
 import type { SearchResult, ScrapedContent } from "../../types/webResearch";
 
@@ -45,6 +46,8 @@ export function WebResearchPanel({ isOpen, onClose, onOpenSettings }: WebResearc
   const { getActiveProviderType, getActiveApiKey, getActiveModel } = useAIStore();
   const { selectedNotebookId } = useNotebookStore();
   const { loadPages } = usePageStore();
+  const tavilyKeyStored = useSecretsStore((s) => secretIds.tavily in s.stored);
+  const hasTavilyKey = !!settings.tavilyApiKey || tavilyKeyStored;
 
   // Focus input when panel opens
   useEffect(() => {
@@ -56,7 +59,7 @@ export function WebResearchPanel({ isOpen, onClose, onOpenSettings }: WebResearc
   const handleSearch = async () => {
     if (!query.trim() || isSearching) return;
 
-    if (!settings.tavilyApiKey) {
+    if (!hasTavilyKey) {
       setError("Please configure your Tavily API key in settings");
       onOpenSettings?.();
       return;
@@ -67,7 +70,7 @@ export function WebResearchPanel({ isOpen, onClose, onOpenSettings }: WebResearc
     setError(null);
 
     try {
-      const response = await webSearch(query, settings.tavilyApiKey, {
+      const response = await webSearch(query, settings.tavilyApiKey || undefined, {
         maxResults: settings.maxResults,
         searchDepth: settings.searchDepth,
         includeAnswer: settings.includeAnswer,
@@ -276,7 +279,7 @@ export function WebResearchPanel({ isOpen, onClose, onOpenSettings }: WebResearc
       </div>
 
       {/* API Key Warning */}
-      {!settings.tavilyApiKey && (
+      {!hasTavilyKey && (
         <button
           onClick={onOpenSettings}
           className="flex w-full items-center gap-2 border-b px-5 py-3 text-left text-sm transition-all hover:bg-[--color-bg-tertiary]"
@@ -327,7 +330,7 @@ export function WebResearchPanel({ isOpen, onClose, onOpenSettings }: WebResearc
           />
           <button
             onClick={handleSearch}
-            disabled={!query.trim() || isSearching || !settings.tavilyApiKey}
+            disabled={!query.trim() || isSearching || !hasTavilyKey}
             className="flex h-12 w-12 items-center justify-center rounded-xl text-white shadow-md transition-all disabled:opacity-50"
             style={{
               background:
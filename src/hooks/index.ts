@@ -1,4 +1,5 @@
 export { useAppInit } from "./useAppInit";
+export { useDeepLinks } from "./useDeepLinks";
 export { useKeyboardShortcuts } from "./useKeyboardShortcuts";
 export { useWindowContext } from "./useWindowContext";
 export type { WindowContext } from "./useWindowContext";
@@ -7,8 +7,10 @@ import { useFolderStore } from "../stores/folderStore";
 import { useGoalsStore } from "../stores/goalsStore";
 import { useContactStore } from "../stores/contactStore";
 import { useEnergyStore } from "../stores/energyStore";
+import { useActionStore } from "../stores/actionStore";
 import { useInboxStore } from "../stores/inboxStore";
 import { usePluginStore } from "../stores/pluginStore";
+import { useSecretsStore } from "../stores/secretsStore";
 import { useToastStore } from "../stores/toastStore";
 import { useWindowLibrary } from "../contexts/WindowContext";
 import { initDaemonClient } from "../utils/daemon";
@@ -35,14 +37,20 @@ export function useAppInit() {
   const { loadGoals, checkAutoGoals, loadSummary } = useGoalsStore();
   const { loadContacts: loadContactsFromStore } = useContactStore();
   const loadTodayCheckIn = useEnergyStore((s) => s.loadTodayCheckIn);
+  const loadActions = useActionStore((s) => s.loadActions);
   const { library } = useWindowLibrary();
   const restoredForNotebookRef = useRef<string | null>(null);
 
   const loadAllFavorites = usePageStore((s) => s.loadAllFavorites);
 
-  // Sync AI config to plugin host at startup
+  // Hand any plaintext API keys over to the secrets store, then sync AI
+  // config to the plugin host
   useEffect(() => {
-    usePluginStore.getState().syncAiConfig();
+    useSecretsStore
+      .getState()
+      .migratePlaintextKeys()
+      .catch((err) => console.warn("[useAppInit] Secrets migration failed:", err))
+      .finally(() => usePluginStore.getState().syncAiConfig());
   }, []);
 
   // Preload the daemon API key so the first HTTP request doesn't block on it,
@@ -360,6 +368,28 @@ export function useAppInit() {
     };
   }, [loadTodayCheckIn]);
 
+  // Listen for sync-library-docs-updated events from the backend.
+  // When sync pulls custom actions from remote, refresh the action list.
+  useEffect(() => {
+    let unlisten: UnlistenFn | null = null;
+
+    const setup = async () => {
+      unlisten = await listen<{ actionsChanged: boolean }>(
+        "sync-library-docs-updated",
+        (event) => {
+          console.log("[sync] Library documents updated by sync");
+          if (event.payload.actionsChanged) loadActions();
+        }
+      );
+    };
+
+    setup();
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, [loadActions]);
+
   // Listen for sync-conflict events from the backend.
   // The destructive-sync guard refused a merge/delete that would have
   // catastrophically shrunk (or removed) a page. Surface a warning toast so
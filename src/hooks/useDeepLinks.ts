@@ -0,0 +1,99 @@
+import { useEffect } from "react";
+import { listen } from "../platform/event";
+import { isTauri } from "../utils/platform";
+import * as api from "../utils/api";
+import type { DeepLink } from "../utils/api";
+import { useNotebookStore } from "../stores/notebookStore";
+import { usePageStore } from "../stores/pageStore";
+import { useLibraryStore } from "../stores/libraryStore";
+import { useToastStore } from "../stores/toastStore";
+
+function pageLinkUrl(link: Extract<DeepLink, { action: "page" }>): string {
+  const params = new URLSearchParams();
+  if (link.notebookId) params.set("notebook", link.notebookId);
+  if (link.libraryId) params.set("library", link.libraryId);
+  const query = params.toString();
+  return `nous://page/${link.pageId}${query ? `?${query}` : ""}`;
+}
+
+async function handleDeepLink(link: DeepLink): Promise<void> {
+  const toast = useToastStore.getState();
+
+  switch (link.action) {
+    case "page": {
+      const url = pageLinkUrl(link);
+      const resolved = await api.resolvePageDeepLink(url);
+      if (!resolved.isCurrentLibrary) {
+        // Switching reloads the window; re-queue so the reloaded app opens it
+        await api.queueDeepLink(url);
+        await useLibraryStore.getState().switchLibrary(resolved.libraryId);
+        window.location.reload();
+        return;
+      }
+      const { selectedNotebookId, selectNotebook } = useNotebookStore.getState();
+      if (resolved.notebookId !== selectedNotebookId) {
+        selectNotebook(resolved.notebookId);
+      }
+      await usePageStore.getState().selectPage(resolved.pageId);
+      return;
+    }
+    case "search": {
+      if (link.notebookId) {
+        useNotebookStore.getState().selectNotebook(link.notebookId);
+      }
+      window.dispatchEvent(
+        new CustomEvent("open-command-palette", {
+          detail: { query: link.query },
+        })
+      );
+      return;
+    }
+    case "capture": {
+      const content = link.url
+        ? link.text
+          ? `Source: ${link.url}\n\n${link.text}`
+          : `Source: ${link.url}`
+        : link.text;
+      const title =
+        link.title || link.text.split("\n")[0].slice(0, 80) || link.url || "Capture";
+      await api.inboxCapture({ title, content, tags: link.tags });
+      toast.success("Captured to inbox");
+      return;
+    }
+  }
+}
+
+/**
+ * Handle nous:// links. The backend queues every incoming link and emits
+ * "deep-link"; draining on mount also picks up the link the app was
+ * launched with.
+ */
+export function useDeepLinks() {
+  useEffect(() => {
+    if (!isTauri()) return;
+
+    let cancelled = false;
+    const drain = async () => {
+      const links = await api.takePendingDeepLinks().catch(() => []);
+      for (const link of links) {
+        if (cancelled) return;
+        try {
+          await handleDeepLink(link);
+        } catch (err) {
+          useToastStore
+            .getState()
+            .error(
+              `Couldn't open link: ${err instanceof Error ? err.message : String(err)}`
+            );
+        }
+      }
+    };
+
+    void drain();
+    const unlisten = listen("deep-link", () => void drain());
+    return () => {
+      cancelled = true;
+      void unlisten.then((fn) => fn());
+    };
+  }, []);
+}
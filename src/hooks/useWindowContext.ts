@@ -36,7 +36,9 @@ function getLibraryIdFromUrl(): string | null {
 /**
  * Hook to get the window's library context.
  *
- * - If ?library=<id> is in URL, switches the backend to that library and loads it
+ * - If ?library=<id> is in URL, loads that library. The backend keeps a
+ *   separate state container for each library window, so the main window's
+ *   library is left alone.
  * - Otherwise, uses the current library from backend
  */
 export function useWindowContext(): WindowContext {
@@ -55,9 +57,9 @@ export function useWindowContext(): WindowContext {
 
       try {
         if (libraryId) {
-          // For secondary windows, switch the backend to use this library
-          // This ensures all subsequent API calls use the correct library
-          const lib = await api.switchLibrary(libraryId);
+          // Commands from this window already resolve to its own library
+          // on the backend (opened by openLibraryWindow)
+          const lib = await api.getLibrary(libraryId);
           setLibrary(lib);
         } else {
           // Load current library (for main window)
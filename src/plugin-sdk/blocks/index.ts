@@ -9,6 +9,7 @@
  */
 import { registerCustomBlock } from "../custom-block";
 import { mermaidBlock } from "./mermaid";
+import { mathBlock } from "./math";
 import { animationBlock } from "./animation";
 import { externalDataBlock } from "./externalData";
 
@@ -21,4 +22,5 @@ export function registerBuiltinBlocks(): void {
   registerCustomBlock(mermaidBlock);
   registerCustomBlock(animationBlock);
   registerCustomBlock(externalDataBlock);
+  registerCustomBlock(mathBlock);
 }
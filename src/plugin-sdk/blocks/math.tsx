@@ -0,0 +1,155 @@
+/**
+ * Math block — a display formula typeset with KaTeX.
+ *
+ * KaTeX and its stylesheet are dynamically imported on first render so they
+ * land in their own bundle chunk. The LaTeX source lives in the `latex` prop
+ * (string, Editor.js `data.latex` on disk), the same field markdown import
+ * fills from a `$$ ... $$` block. Double-click to edit; blur commits.
+ *
+ * Inline styles only — this file is also bundled by the guest editor.
+ */
+import { useEffect, useRef, useState } from "react";
+import type {
+  CustomBlockContribution,
+  CustomBlockRenderProps,
+} from "../custom-block";
+
+let katexModule: Promise<typeof import("katex")> | null = null;
+
+function loadKatex() {
+  if (!katexModule) {
+    katexModule = Promise.all([
+      import("katex"),
+      import("katex/dist/katex.min.css"),
+    ]).then(([k]) => k);
+  }
+  return katexModule;
+}
+
+function MathRender({ props, updateProps, readOnly }: CustomBlockRenderProps) {
+  const latex = props.latex ?? "";
+  const [html, setHtml] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [editing, setEditing] = useState(false);
+  const [draft, setDraft] = useState(latex);
+  const textareaRef = useRef<HTMLTextAreaElement | null>(null);
+
+  useEffect(() => {
+    if (!latex.trim()) {
+      setHtml("");
+      setError(null);
+      return;
+    }
+    let cancelled = false;
+    loadKatex()
+      .then((k) => {
+        const out = k.default.renderToString(latex, {
+          displayMode: true,
+          throwOnError: true,
+        });
+        if (!cancelled) {
+          setHtml(out);
+          setError(null);
+        }
+      })
+      .catch((e: unknown) => {
+        if (!cancelled) setError(e instanceof Error ? e.message : String(e));
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [latex]);
+
+  useEffect(() => {
+    if (editing) {
+      setDraft(latex);
+      textareaRef.current?.focus();
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps -- reset draft only when opening
+  }, [editing]);
+
+  const commit = () => {
+    setEditing(false);
+    if (draft !== latex) updateProps({ latex: draft });
+  };
+
+  if (editing && !readOnly) {
+    return (
+      <div contentEditable={false} style={{ width: "100%" }}>
+        <textarea
+          ref={textareaRef}
+          value={draft}
+          onChange={(e) => setDraft(e.target.value)}
+          onBlur={commit}
+          onKeyDown={(e) => {
+            if (e.key === "Escape") commit();
+            e.stopPropagation();
+          }}
+          rows={Math.max(2, draft.split("\n").length + 1)}
+          placeholder={"\\int_0^1 x^2 \\, dx = \\frac{1}{3}"}
+          style={{
+            width: "100%",
+            fontFamily: "monospace",
+            fontSize: "0.85em",
+            padding: "8px",
+            border: "1px solid var(--color-border, #8884)",
+            borderRadius: "6px",
+            background: "var(--color-bg-secondary, transparent)",
+            color: "inherit",
+            resize: "vertical",
+          }}
+        />
+      </div>
+    );
+  }
+
+  return (
+    <div
+      contentEditable={false}
+      onDoubleClick={readOnly ? undefined : () => setEditing(true)}
+      title={readOnly ? undefined : "Double-click to edit formula"}
+      style={{ width: "100%", cursor: readOnly ? "default" : "pointer" }}
+    >
+      {error ? (
+        <div
+          style={{
+            padding: "8px 10px",
+            border: "1px solid #d9534f88",
+            borderRadius: "6px",
+            fontSize: "0.85em",
+            color: "#d9534f",
+          }}
+        >
+          LaTeX error: {error}
+        </div>
+      ) : html ? (
+        // eslint-disable-next-line react/no-danger -- KaTeX's own HTML output
+        <div style={{ overflowX: "auto" }} dangerouslySetInnerHTML={{ __html: html }} />
+      ) : (
+        <div
+          style={{
+            padding: "8px 10px",
+            border: "1px dashed var(--color-border, #8884)",
+            borderRadius: "6px",
+            fontSize: "0.85em",
+            color: "var(--color-text-muted, #888)",
+          }}
+        >
+          Empty formula — double-click to edit
+        </div>
+      )}
+    </div>
+  );
+}
+
+export const mathBlock: CustomBlockContribution = {
+  id: "math",
+  title: "Math Formula",
+  group: "Custom",
+  keywords: ["math", "latex", "katex", "formula", "equation"],
+  propSchema: {
+    latex: { default: "" },
+  },
+  content: "none",
+  Render: MathRender,
+};
@@ -35,6 +35,11 @@ export function setCustomBlockEditorContext(
   editorContexts.set(editor, ctx);
 }
 
+/** The notebook/page an editor instance was registered with, if any. */
+export function getCustomBlockEditorContext(editor: object): EditorCtx {
+  return editorContexts.get(editor) ?? {};
+}
+
 // ─── Render wrapper ─────────────────────────────────────────────────────────
 
 interface HostProps {
@@ -6,6 +6,7 @@ import {
   createDailyNote,
   listDailyNotes,
   getOrCreateTodayDailyNote,
+  type DailyNoteRollover,
 } from "../utils/api";
 import { localToday, localDateStr, parseLocalDate } from "../utils/dateLocal";
 
@@ -13,13 +14,23 @@ import { localToday, localDateStr, parseLocalDate } from "../utils/dateLocal";
 interface DailyNotesSettings {
   useTemplate: boolean; // Whether to use a template when creating daily notes
   templateId: string | null; // Which template to use (null = no template)
+  rollover?: boolean; // Carry unchecked items into today's new note
+  rolloverLookbackDays?: number; // How many days back to carry items from
 }
 
 const DEFAULT_SETTINGS: DailyNotesSettings = {
   useTemplate: false,
   templateId: "daily-journal", // Default to daily-journal when enabled
+  rollover: false,
+  rolloverLookbackDays: 7,
 };
 
+// Rollover options for creating a note, if enabled
+function rolloverOptions(settings: DailyNotesSettings): DailyNoteRollover | undefined {
+  if (!settings.rollover) return undefined;
+  return { lookbackDays: settings.rolloverLookbackDays ?? DEFAULT_SETTINGS.rolloverLookbackDays };
+}
+
 interface DailyNotesState {
   isPanelOpen: boolean;
   isLoading: boolean;
@@ -54,6 +65,8 @@ interface DailyNotesActions {
   // Settings
   setUseTemplate: (useTemplate: boolean) => void;
   setTemplateId: (templateId: string | null) => void;
+  setRollover: (rollover: boolean) => void;
+  setRolloverLookbackDays: (days: number) => void;
 
   // Error handling
   clearError: () => void;
@@ -166,7 +179,12 @@ export const useDailyNotesStore = create<DailyNotesStore>()(
           if (!note) {
             // Create new daily note, optionally with template
             const templateId = settings.useTemplate && settings.templateId ? settings.templateId : undefined;
-            note = await createDailyNote(notebookId, targetDate, templateId);
+            note = await createDailyNote(
+              notebookId,
+              targetDate,
+              templateId,
+              rolloverOptions(settings)
+            );
 
             // Update datesWithNotes set
             set((state) => {
@@ -200,7 +218,11 @@ export const useDailyNotesStore = create<DailyNotesStore>()(
 
         try {
           const templateId = settings.useTemplate && settings.templateId ? settings.templateId : undefined;
-          const note = await getOrCreateTodayDailyNote(notebookId, templateId);
+          const note = await getOrCreateTodayDailyNote(
+            notebookId,
+            templateId,
+            rolloverOptions(settings)
+          );
           const today = getTodayDate();
 
           // Update datesWithNotes set
@@ -234,6 +256,16 @@ export const useDailyNotesStore = create<DailyNotesStore>()(
           settings: { ...state.settings, templateId },
         })),
 
+      setRollover: (rollover: boolean) =>
+        set((state) => ({
+          settings: { ...state.settings, rollover },
+        })),
+
+      setRolloverLookbackDays: (rolloverLookbackDays: number) =>
+        set((state) => ({
+          settings: { ...state.settings, rolloverLookbackDays },
+        })),
+
       // Error handling
       clearError: () => set({ error: null }),
     }),
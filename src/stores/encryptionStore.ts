@@ -8,6 +8,8 @@
 import { create } from "zustand";
 import { invoke } from "../platform/core";
 import type {
+  BiometricSettings,
+  BiometricStatus,
   EncryptionConfig,
   EncryptionStats,
   UnlockResult,
@@ -50,6 +52,19 @@ interface EncryptionActions {
   ) => Promise<EncryptionConfig>;
   getNotebookPasswordHint: (notebookId: string) => Promise<string | null>;
 
+  // Biometric unlock (falls back to the password when it returns false)
+  getBiometricStatus: (notebookId: string) => Promise<BiometricStatus>;
+  enableBiometricUnlock: (
+    notebookId: string,
+    password: string
+  ) => Promise<BiometricStatus>;
+  disableBiometricUnlock: (notebookId: string) => Promise<void>;
+  unlockNotebookBiometric: (notebookId: string) => Promise<boolean>;
+  getBiometricSettings: () => Promise<BiometricSettings>;
+  updateBiometricSettings: (
+    settings: BiometricSettings
+  ) => Promise<BiometricSettings>;
+
   // Library encryption
   enableLibraryEncryption: (
     libraryId: string,
@@ -209,6 +224,70 @@ export const useEncryptionStore = create<EncryptionStore>((set, get) => ({
     }
   },
 
+  // Biometric unlock actions
+  getBiometricStatus: async (notebookId) => {
+    return await invoke<BiometricStatus>("get_notebook_biometric_status", {
+      notebookId,
+    });
+  },
+
+  enableBiometricUnlock: async (notebookId, password) => {
+    set({ isLoading: true, error: null });
+    try {
+      const status = await invoke<BiometricStatus>(
+        "enable_notebook_biometric_unlock",
+        { notebookId, password }
+      );
+      set({ isLoading: false });
+      return status;
+    } catch (err) {
+      const message =
+        err instanceof Error
+          ? err.message
+          : "Failed to enable biometric unlock";
+      set({ error: message, isLoading: false });
+      throw new Error(message);
+    }
+  },
+
+  disableBiometricUnlock: async (notebookId) => {
+    await invoke("disable_notebook_biometric_unlock", { notebookId });
+  },
+
+  unlockNotebookBiometric: async (notebookId) => {
+    set({ isLoading: true, error: null });
+    try {
+      const result = await invoke<UnlockResult>("unlock_notebook_biometric", {
+        notebookId,
+      });
+      if (result.success) {
+        set((state) => ({
+          unlockedNotebooks: new Set([...state.unlockedNotebooks, notebookId]),
+          isLoading: false,
+        }));
+        return true;
+      } else {
+        set({ error: result.error || "Enter your password", isLoading: false });
+        return false;
+      }
+    } catch (err) {
+      const message =
+        err instanceof Error ? err.message : "Failed to unlock notebook";
+      set({ error: message, isLoading: false });
+      return false;
+    }
+  },
+
+  getBiometricSettings: async () => {
+    return await invoke<BiometricSettings>("get_biometric_settings");
+  },
+
+  updateBiometricSettings: async (settings) => {
+    return await invoke<BiometricSettings>("update_biometric_settings", {
+      settings,
+    });
+  },
+
   // Library encryption actions
   enableLibraryEncryption: async (libraryId, password, hint) => {
     set({ isLoading: true, error: null });
@@ -5,6 +5,10 @@ import type {
   CardWithState,
   ReviewStats,
   CardType,
+  ReadingItem,
+  ReviewQueueItem,
+  DeckAnalytics,
+  LeechCard,
 } from "../types/flashcard";
 import * as api from "../utils/flashcardApi";
 
@@ -26,8 +30,14 @@ interface FlashcardState {
   reviewMode: "fullscreen" | "panel" | null;
   intervalPreview: [number, number, number, number] | null;
 
+  // Incremental reading
+  readingItems: ReadingItem[];
+  reviewQueue: ReviewQueueItem[];
+
   // Statistics
   stats: ReviewStats | null;
+  analytics: DeckAnalytics | null;
+  leeches: LeechCard[];
 
   // Loading states
   isLoading: boolean;
@@ -78,8 +88,9 @@ interface FlashcardActions {
     pageId: string,
     blockId: string,
     front: string,
-    back: string
-  ) => Promise<Flashcard | null>;
+    back: string,
+    cloze?: { ranges?: { start: number; end: number }[] }
+  ) => Promise<Flashcard[]>;
   updateCard: (
     notebookId: string,
     cardId: string,
@@ -101,8 +112,25 @@ interface FlashcardActions {
   previousCard: () => void;
   loadIntervalPreview: (notebookId: string, cardId: string) => Promise<void>;
 
+  // Incremental reading
+  loadReadingItems: (notebookId: string) => Promise<void>;
+  addReadingItem: (
+    notebookId: string,
+    pageId: string,
+    options?: { text?: string; blockIds?: string[]; title?: string; priority?: number }
+  ) => Promise<ReadingItem | null>;
+  removeReadingItem: (notebookId: string, itemId: string) => Promise<void>;
+  loadReviewQueue: (notebookId: string, deckId?: string) => Promise<void>;
+  gradeReadingItem: (
+    notebookId: string,
+    itemId: string,
+    rating: number,
+    options?: { priority?: number; done?: boolean }
+  ) => Promise<void>;
+
   // Statistics
   loadStats: (notebookId: string, deckId?: string) => Promise<void>;
+  loadAnalytics: (notebookId: string, deckId?: string) => Promise<void>;
 
   // State management
   clearCards: () => void;
@@ -122,7 +150,11 @@ export const useFlashcardStore = create<FlashcardStore>()((set, get) => ({
   isReviewing: false,
   reviewMode: null,
   intervalPreview: null,
+  readingItems: [],
+  reviewQueue: [],
   stats: null,
+  analytics: null,
+  leeches: [],
   isLoading: false,
   error: null,
 
@@ -228,29 +260,30 @@ export const useFlashcardStore = create<FlashcardStore>()((set, get) => ({
     }
   },
 
-  createCardFromBlock: async (notebookId, deckId, pageId, blockId, front, back) => {
+  createCardFromBlock: async (notebookId, deckId, pageId, blockId, front, back, cloze) => {
     set({ error: null });
     try {
-      const card = await api.createCardFromBlock(
+      const created = await api.createCardFromBlock(
         notebookId,
         deckId,
         pageId,
         blockId,
         front,
-        back
+        back,
+        cloze
       );
       set((state) => ({
-        cards: [...state.cards, card],
+        cards: [...state.cards, ...created],
         decks: state.decks.map((d) =>
-          d.id === deckId ? { ...d, cardCount: d.cardCount + 1 } : d
+          d.id === deckId ? { ...d, cardCount: d.cardCount + created.length } : d
         ),
       }));
-      return card;
+      return created;
     } catch (err) {
       set({
         error: err instanceof Error ? err.message : "Failed to create card from block",
       });
-      return null;
+      return [];
     }
   },
 
@@ -298,6 +331,8 @@ export const useFlashcardStore = create<FlashcardStore>()((set, get) => ({
   loadDueCards: async (notebookId, deckId) => {
     set({ isLoading: true, error: null });
     try {
+      // Cards made from blocks follow edits to the blocks before review
+      await api.syncBlockCards(notebookId).catch(() => undefined);
       const dueCards = await api.getDueCards(notebookId, deckId);
       set({ dueCards, currentCardIndex: 0, isLoading: false });
     } catch (err) {
@@ -367,6 +402,80 @@ export const useFlashcardStore = create<FlashcardStore>()((set, get) => ({
     }
   },
 
+  // Incremental reading
+  loadReadingItems: async (notebookId) => {
+    set({ error: null });
+    try {
+      const readingItems = await api.listIrItems(notebookId);
+      set({ readingItems });
+    } catch (err) {
+      set({
+        error: err instanceof Error ? err.message : "Failed to load reading items",
+      });
+    }
+  },
+
+  addReadingItem: async (notebookId, pageId, options) => {
+    set({ error: null });
+    try {
+      const item = await api.addIrExtract(notebookId, pageId, options);
+      set((state) => ({ readingItems: [...state.readingItems, item] }));
+      return item;
+    } catch (err) {
+      set({
+        error: err instanceof Error ? err.message : "Failed to add reading item",
+      });
+      return null;
+    }
+  },
+
+  removeReadingItem: async (notebookId, itemId) => {
+    set({ error: null });
+    try {
+      await api.deleteIrItem(notebookId, itemId);
+      set((state) => ({
+        readingItems: state.readingItems.filter((item) => item.id !== itemId),
+        reviewQueue: state.reviewQueue.filter(
+          (entry) => entry.kind !== "reading" || entry.id !== itemId
+        ),
+      }));
+    } catch (err) {
+      set({
+        error: err instanceof Error ? err.message : "Failed to remove reading item",
+      });
+    }
+  },
+
+  loadReviewQueue: async (notebookId, deckId) => {
+    set({ isLoading: true, error: null });
+    try {
+      const reviewQueue = await api.getIrQueue(notebookId, deckId);
+      set({ reviewQueue, isLoading: false });
+    } catch (err) {
+      set({
+        error: err instanceof Error ? err.message : "Failed to load review queue",
+        isLoading: false,
+      });
+    }
+  },
+
+  gradeReadingItem: async (notebookId, itemId, rating, options) => {
+    set({ error: null });
+    try {
+      const item = await api.gradeIrItem(notebookId, itemId, rating, options);
+      set((state) => ({
+        readingItems: state.readingItems.map((r) => (r.id === itemId ? item : r)),
+        reviewQueue: state.reviewQueue.filter(
+          (entry) => entry.kind !== "reading" || entry.id !== itemId
+        ),
+      }));
+    } catch (err) {
+      set({
+        error: err instanceof Error ? err.message : "Failed to grade reading item",
+      });
+    }
+  },
+
   // Statistics
   loadStats: async (notebookId, deckId) => {
     set({ error: null });
@@ -380,6 +489,21 @@ export const useFlashcardStore = create<FlashcardStore>()((set, get) => ({
     }
   },
 
+  loadAnalytics: async (notebookId, deckId) => {
+    set({ error: null });
+    try {
+      const [analytics, leeches] = await Promise.all([
+        api.getDeckAnalytics(notebookId, deckId),
+        api.listLeechCards(notebookId, deckId),
+      ]);
+      set({ analytics, leeches });
+    } catch (err) {
+      set({
+        error: err instanceof Error ? err.message : "Failed to load analytics",
+      });
+    }
+  },
+
   // State management
   clearCards: () => {
     set({ cards: [], dueCards: [], currentCardIndex: 0 });
@@ -26,7 +26,13 @@ interface FolderActions {
   updateFolder: (
     notebookId: string,
     folderId: string,
-    updates: { name?: string; parentId?: string | null; color?: string | null; sectionId?: string | null }
+    updates: {
+      name?: string;
+      parentId?: string | null;
+      color?: string | null;
+      sectionId?: string | null;
+      pageSortBy?: string | null;
+    }
   ) => Promise<void>;
   deleteFolder: (
     notebookId: string,
@@ -7,6 +7,7 @@ import type {
   InfographicAvailability,
 } from "../types/infographic";
 import * as api from "../utils/api";
+import { useStudyToolsStore } from "./studyToolsStore";
 
 interface InfographicSettings {
   theme: InfographicTheme;
@@ -112,7 +113,9 @@ export const useInfographicStore = create<InfographicStore>()(
               theme: settings.theme,
               title,
               accentColor: settings.accentColor,
-            }
+            },
+            undefined,
+            useStudyToolsStore.getState().selectedPageIds
           );
           set({ result, isGenerating: false });
           return result;
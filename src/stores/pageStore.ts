@@ -2,7 +2,9 @@ import { create } from "zustand";
 import { persist } from "zustand/middleware";
 import type { Page, EditorData, FavoritePageEntry } from "../types/page";
 import * as api from "../utils/api";
+import { findNotebookAssetRefs } from "../utils/assetUrl";
 import { crumb, resetCrumbs } from "../utils/breadcrumbs";
+import { isTauri } from "../utils/platform";
 import { enqueueFailedSave, dequeueSave } from "../utils/saveOutbox";
 import { useRAGStore } from "./ragStore";
 
@@ -53,6 +55,32 @@ export function setPendingSavePromise(p: Promise<void> | null) {
   _pendingSavePromise = p;
 }
 
+// Assets ensurePageAssets has already found on disk or downloaded
+const _localAssets = new Set<string>();
+
+// Download media a page uses that an on-demand asset sync policy skipped.
+// The browser build gets this from the daemon's asset route instead.
+// Resolves true if anything was downloaded.
+async function ensurePageAssets(page: Page): Promise<boolean> {
+  if (!isTauri()) return false;
+  const refs = findNotebookAssetRefs(JSON.stringify(page.content ?? {}));
+  const downloads = await Promise.all(
+    refs.map(async ({ notebookId, relPath }) => {
+      const key = `${notebookId}/${relPath}`;
+      if (_localAssets.has(key)) return false;
+      try {
+        const result = await api.ensureAssetLocal(notebookId, relPath);
+        _localAssets.add(key);
+        return result.downloaded;
+      } catch {
+        // Not synced, downloads turned off, or offline — retry next open
+        return false;
+      }
+    })
+  );
+  return downloads.some(Boolean);
+}
+
 // Recent page entry for tracking access history
 export interface RecentPageEntry {
   pageId: string;
@@ -819,6 +847,10 @@ export const usePageStore = create<PageStore>()(
         });
 
         crumb("selectPage:set-done");
+        if (page) {
+          // Feeds the review feed's last-viewed times; never blocks navigation
+          api.recordPageView(page.notebookId, page.id).catch(() => {});
+        }
         const t1 = performance.now();
         if (t1 - t0 > 50) {
           console.warn(`[Perf] selectPage sync set() took ${Math.round(t1 - t0)}ms`);
@@ -832,6 +864,13 @@ export const usePageStore = create<PageStore>()(
             .getPage(page.notebookId, id)
             .then((freshPage) => {
               crumb("selectPage:fetch-done");
+              void ensurePageAssets(freshPage).then((downloaded) => {
+                if (downloaded) {
+                  set((state) => ({
+                    pageDataVersion: state.pageDataVersion + 1,
+                  }));
+                }
+              });
               const fetchTime = performance.now() - fetchStart;
               const current = get().pages.find((p) => p.id === id);
               if (!hasContentChanged(current, freshPage)) {
@@ -1,14 +1,18 @@
 import { create } from "zustand";
 import { persist } from "zustand/middleware";
 import { invoke } from "../platform/core";
+import { listen } from "../platform/event";
 import type {
+  ChunkingStrategy,
   EmbeddingConfig,
   EmbeddingProvider,
   SemanticSearchResult,
   VectorIndexStats,
   DiscoveredModel,
+  RechunkProgress,
+  RechunkSummary,
 } from "../types/rag";
-import { EMBEDDING_MODELS, DEFAULT_EMBEDDING_BASE_URLS, getModelDimensions, EMBEDDING_PROVIDER_INFO } from "../types/rag";
+import { DEFAULT_CHUNKING_STRATEGY, EMBEDDING_MODELS, DEFAULT_EMBEDDING_BASE_URLS, getModelDimensions, EMBEDDING_PROVIDER_INFO } from "../types/rag";
 import type { SearchResult } from "../types/page";
 import { listNotebooks, listPages } from "../utils/api";
 
@@ -30,6 +34,8 @@ interface RAGState {
   settings: RAGSettings;
   // Runtime state (not persisted)
   isConfigured: boolean;
+  // Chunking strategy of the library's index (stored with its config)
+  chunking: ChunkingStrategy;
   isIndexing: boolean;
   isDiscoveringModels: boolean;
   indexingProgress: { current: number; total: number } | null;
@@ -81,6 +87,15 @@ interface RAGActions {
     limit?: number
   ) => Promise<SemanticSearchResult[]>;
 
+  // Chunking
+  setChunking: (strategy: ChunkingStrategy) => Promise<void>;
+  previewChunks: (
+    notebookId: string,
+    pageId: string,
+    strategy: ChunkingStrategy
+  ) => Promise<string[]>;
+  rechunkLibrary: () => Promise<RechunkSummary>;
+
   // Index operations
   indexPage: (notebookId: string, pageId: string) => Promise<void>;
   removePage: (pageId: string) => Promise<void>;
@@ -115,6 +130,7 @@ export const useRAGStore = create<RAGStore>()(
       // Initial state
       settings: defaultSettings,
       isConfigured: false,
+      chunking: DEFAULT_CHUNKING_STRATEGY,
       isIndexing: false,
       isDiscoveringModels: false,
       indexingProgress: null,
@@ -141,6 +157,7 @@ export const useRAGStore = create<RAGStore>()(
                 baseUrl: config.baseUrl || "",
               },
               isConfigured: true,
+              chunking: config.chunking ?? DEFAULT_CHUNKING_STRATEGY,
             }));
           }
         } catch (error) {
@@ -367,6 +384,50 @@ export const useRAGStore = create<RAGStore>()(
         }
       },
 
+      // Change the chunking strategy; indexed pages keep their chunks until re-chunked
+      setChunking: async (strategy) => {
+        try {
+          await invoke("set_chunking_strategy", { strategy });
+          set({ chunking: strategy, lastError: null });
+        } catch (error) {
+          console.error("Failed to set chunking strategy:", error);
+          set({ lastError: String(error) });
+          throw error;
+        }
+      },
+
+      // Chunk a page with a strategy without indexing it
+      previewChunks: async (notebookId, pageId, strategy) => {
+        return invoke<string[]>("get_page_chunks", { notebookId, pageId, strategy });
+      },
+
+      // Re-chunk and re-embed every page with the current strategy
+      rechunkLibrary: async () => {
+        const state = get();
+        if (!state.isConfigured) {
+          throw new Error("RAG not configured");
+        }
+
+        const unlisten = await listen<RechunkProgress>("rag-rechunk-progress", (event) => {
+          set({ indexingProgress: { current: event.payload.done, total: event.payload.total } });
+        });
+        try {
+          set({ isIndexing: true, indexingProgress: null, lastError: null });
+          const summary = await invoke<RechunkSummary>("rechunk_library", {
+            taskId: "rag-rechunk",
+          });
+          await get().getStats();
+          return summary;
+        } catch (error) {
+          console.error("Failed to re-chunk library:", error);
+          set({ lastError: String(error) });
+          throw error;
+        } finally {
+          unlisten();
+          set({ isIndexing: false, indexingProgress: null });
+        }
+      },
+
       // Remove a page from the index
       removePage: async (pageId) => {
         try {
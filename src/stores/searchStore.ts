@@ -1,18 +1,31 @@
 import { create } from "zustand";
 import { persist } from "zustand/middleware";
+import {
+  clearSearchHistory,
+  getSearchHistory,
+  getSearchHistorySettings,
+  recordSearch,
+  setSearchHistorySettings,
+  type SearchHistorySettings,
+} from "../utils/api";
+import { isTauri } from "../utils/platform";
 
 export type SearchMode = "keyword" | "hybrid" | "semantic";
 
 interface SearchState {
   recentSearches: string[];
+  /** Desktop search history settings; null until loaded (or on the web) */
+  historySettings: SearchHistorySettings | null;
   searchScope: "all" | "current";
   searchMode: SearchMode;
   selectedNotebookFilter: string | null;
 }
 
 interface SearchActions {
+  loadRecentSearches: () => Promise<void>;
   addRecentSearch: (query: string) => void;
   clearRecentSearches: () => void;
+  updateHistorySettings: (settings: SearchHistorySettings) => Promise<void>;
   setSearchScope: (scope: "all" | "current") => void;
   setSearchMode: (mode: SearchMode) => void;
   setNotebookFilter: (notebookId: string | null) => void;
@@ -24,14 +37,34 @@ const MAX_RECENT_SEARCHES = 10;
 
 export const useSearchStore = create<SearchStore>()(
   persist(
-    (set) => ({
+    (set, get) => ({
       recentSearches: [],
+      historySettings: null,
       searchScope: "all",
       searchMode: "keyword" as SearchMode,
       selectedNotebookFilter: null,
 
+      // On desktop the history lives with the library (shared with the TUI);
+      // the web build keeps it in local storage
+      loadRecentSearches: async () => {
+        if (!isTauri()) return;
+        try {
+          const [entries, historySettings] = await Promise.all([
+            getSearchHistory(MAX_RECENT_SEARCHES),
+            getSearchHistorySettings(),
+          ]);
+          set({
+            recentSearches: entries.map((e) => e.query),
+            historySettings,
+          });
+        } catch (error) {
+          console.error("Failed to load search history:", error);
+        }
+      },
+
       addRecentSearch: (query: string) => {
         if (!query.trim() || query.length < 2) return;
+        if (get().historySettings?.enabled === false) return;
 
         set((state) => {
           // Remove if already exists, then add to front
@@ -42,10 +75,26 @@ export const useSearchStore = create<SearchStore>()(
             recentSearches: [query, ...filtered].slice(0, MAX_RECENT_SEARCHES),
           };
         });
+        recordSearch(query).catch((error) =>
+          console.error("Failed to record search:", error)
+        );
       },
 
       clearRecentSearches: () => {
         set({ recentSearches: [] });
+        if (isTauri()) {
+          clearSearchHistory().catch((error) =>
+            console.error("Failed to clear search history:", error)
+          );
+        }
+      },
+
+      updateHistorySettings: async (settings) => {
+        await setSearchHistorySettings(settings);
+        set({ historySettings: settings });
+        if (!settings.enabled) {
+          set({ recentSearches: [] });
+        }
       },
 
       setSearchScope: (scope) => {
@@ -63,7 +112,7 @@ export const useSearchStore = create<SearchStore>()(
     {
       name: "nous-search",
       partialize: (state) => ({
-        recentSearches: state.recentSearches,
+        recentSearches: isTauri() ? [] : state.recentSearches,
         searchScope: state.searchScope,
         searchMode: state.searchMode,
       }),
@@ -0,0 +1,133 @@
+/**
+ * Secrets Store
+ *
+ * Tracks which API keys are held by the encrypted secrets store. Values
+ * never come back to the frontend; only ids and hints do. In the desktop
+ * app, keys typed into settings go straight to the store and the old
+ * localStorage copies are handed over on startup.
+ */
+
+import { create } from "zustand";
+import {
+  getSecretsStatus,
+  listSecrets,
+  setSecret,
+  rotateSecret,
+  deleteSecret,
+  unlockSecrets,
+  setSecretsProtection,
+  importPlaintextSecrets,
+  secretIds,
+  type SecretInfo,
+  type SecretsStatus,
+} from "../utils/api";
+import { isTauri } from "../utils/platform";
+import { useAIStore } from "./aiStore";
+import { useWebResearchStore } from "./webResearchStore";
+import { useAudioStore } from "./audioStore";
+
+interface SecretsState {
+  status: SecretsStatus | null;
+  stored: Record<string, SecretInfo>;
+}
+
+interface SecretsActions {
+  load: () => Promise<void>;
+  isStored: (id: string) => boolean;
+  save: (id: string, value: string) => Promise<void>;
+  remove: (id: string) => Promise<void>;
+  unlock: (passphrase: string) => Promise<void>;
+  setProtection: (passphrase: string | null) => Promise<void>;
+  migratePlaintextKeys: () => Promise<void>;
+}
+
+type SecretsStore = SecretsState & SecretsActions;
+
+export const useSecretsStore = create<SecretsStore>((set, get) => ({
+  status: null,
+  stored: {},
+
+  load: async () => {
+    if (!isTauri()) return;
+    const [status, secrets] = await Promise.all([getSecretsStatus(), listSecrets()]);
+    set({
+      status,
+      stored: Object.fromEntries(secrets.map((s) => [s.id, s])),
+    });
+  },
+
+  isStored: (id) => id in get().stored,
+
+  save: async (id, value) => {
+    const info = get().isStored(id)
+      ? await rotateSecret(id, value)
+      : await setSecret(id, value);
+    set((state) => ({ stored: { ...state.stored, [id]: info } }));
+  },
+
+  remove: async (id) => {
+    await deleteSecret(id);
+    set((state) => {
+      const stored = { ...state.stored };
+      delete stored[id];
+      return { stored };
+    });
+  },
+
+  unlock: async (passphrase) => {
+    await unlockSecrets(passphrase);
+    await get().migratePlaintextKeys();
+  },
+
+  setProtection: async (passphrase) => {
+    const status = await setSecretsProtection(passphrase);
+    set({ status });
+  },
+
+  migratePlaintextKeys: async () => {
+    if (!isTauri()) return;
+
+    const ai = useAIStore.getState();
+    const webResearch = useWebResearchStore.getState();
+    const audio = useAudioStore.getState();
+
+    const keys: Record<string, string> = {};
+    for (const provider of ai.settings.providers) {
+      if (provider.apiKey) keys[secretIds.ai(provider.type)] = provider.apiKey;
+    }
+    if (webResearch.settings.tavilyApiKey) {
+      keys[secretIds.tavily] = webResearch.settings.tavilyApiKey;
+    }
+    const ttsId = secretIds.tts(audio.settings.ttsProvider);
+    if (audio.settings.ttsApiKey) keys[ttsId] = audio.settings.ttsApiKey;
+
+    if (Object.keys(keys).length > 0) {
+      // Only ids the store now holds are cleared; a locked store keeps
+      // the local copies working until it's unlocked
+      const imported = new Set(await importPlaintextSecrets(keys));
+      for (const provider of ai.settings.providers) {
+        if (imported.has(secretIds.ai(provider.type))) {
+          ai.setProviderApiKey(provider.type, "");
+        }
+      }
+      if (imported.has(secretIds.tavily)) webResearch.setTavilyApiKey("");
+      if (imported.has(ttsId)) audio.setTtsApiKey("");
+    }
+
+    await get().load();
+  },
+}));
+
+/**
+ * Whether an AI provider has a key, either in local settings or in the
+ * secrets store
+ */
+export function hasAiKey(providerType: string, localKey?: string): boolean {
+  return !!localKey || useSecretsStore.getState().isStored(secretIds.ai(providerType));
+}
+
+/** Hook form of {@link hasAiKey} that re-renders when the store changes */
+export function useHasAiKey(providerType: string, localKey?: string): boolean {
+  const stored = useSecretsStore((s) => secretIds.ai(providerType) in s.stored);
+  return !!localKey || stored;
+}
@@ -0,0 +1,55 @@
+import { create } from "zustand";
+import { invoke } from "../platform/core";
+import { listen, type UnlistenFn } from "../platform/event";
+import { isTauri } from "../utils/platform";
+
+/** Subsystems the backend starts after the window is up */
+export type Subsystem = "vector_index" | "python" | "schedulers";
+
+export type Readiness = "pending" | "ready" | "failed";
+
+export interface SubsystemStatus {
+  subsystem: Subsystem;
+  readiness: Readiness;
+  error?: string;
+  elapsedMs?: number;
+}
+
+interface StartupState {
+  statuses: Partial<Record<Subsystem, SubsystemStatus>>;
+  /** Follow `subsystem-ready` events and catch up on ones already sent */
+  init: () => Promise<UnlistenFn>;
+  isReady: (subsystem: Subsystem) => boolean;
+}
+
+export const useStartupStore = create<StartupState>()((set, get) => ({
+  statuses: {},
+
+  init: async () => {
+    if (!isTauri()) return () => {};
+    const unlisten = await listen<SubsystemStatus>("subsystem-ready", (event) => {
+      set((state) => ({
+        statuses: { ...state.statuses, [event.payload.subsystem]: event.payload },
+      }));
+    });
+    try {
+      const snapshot = await invoke<SubsystemStatus[]>("get_startup_status");
+      set((state) => {
+        const statuses = { ...state.statuses };
+        for (const status of snapshot) {
+          // An event may have arrived while the snapshot was in flight
+          const current = statuses[status.subsystem];
+          if (current && current.readiness !== "pending") continue;
+          statuses[status.subsystem] = status;
+        }
+        return { statuses };
+      });
+    } catch (error) {
+      console.error("Failed to get startup status:", error);
+    }
+    return unlisten;
+  },
+
+  // Outside the desktop app there is nothing to wait for
+  isReady: (subsystem) => !isTauri() || get().statuses[subsystem]?.readiness === "ready",
+}));
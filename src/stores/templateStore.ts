@@ -22,6 +22,8 @@ interface TemplateActions {
   addTemplate: (template: Omit<PageTemplate, "id" | "isBuiltIn">) => void;
   updateTemplate: (id: string, updates: Partial<PageTemplate>) => void;
   deleteTemplate: (id: string) => void;
+  /** Add custom templates from a settings export, replacing same-id ones */
+  importTemplates: (templates: PageTemplate[]) => void;
   openTemplateDialog: (notebookId: string) => void;
   closeTemplateDialog: () => void;
   getTemplateForPage: (pageId: string) => PageTemplate | undefined;
@@ -796,6 +798,19 @@ export const useTemplateStore = create<TemplateStore>()(
         }));
       },
 
+      importTemplates: (templates) => {
+        const imported = templates
+          .filter((t) => t.id && t.name && !t.isBuiltIn)
+          .map((t) => ({ ...t, isBuiltIn: false }));
+        const ids = new Set(imported.map((t) => t.id));
+        set((state) => ({
+          templates: [
+            ...state.templates.filter((t) => t.isBuiltIn || !ids.has(t.id)),
+            ...imported,
+          ],
+        }));
+      },
+
       openTemplateDialog: (notebookId) => {
         set({ showTemplateDialog: true, pendingNotebookId: notebookId });
       },
@@ -10,6 +10,7 @@ import type {
 } from "../types/videoGenerate";
 import * as api from "../utils/api";
 import { useAudioStore } from "./audioStore";
+import { useStudyToolsStore } from "./studyToolsStore";
 
 interface VideoGenerateSettings {
   theme: VideoTheme;
@@ -199,7 +200,8 @@ export const useVideoGenerateStore = create<VideoGenerateStore>()(
               transition: settings.transition,
               title,
               accentColor: settings.accentColor,
-            }
+            },
+            useStudyToolsStore.getState().selectedPageIds
           );
 
           set({ result, isGenerating: false, progress: 100 });
@@ -228,6 +228,7 @@ export const AiSummarizeStepSchema = z.object({
   outputTarget: SummaryOutputSchema,
   customPrompt: z.string().optional(),
   summaryStyle: SummaryStyleSchema.optional().default("concise"),
+  aiProfile: z.string().optional(),
 });
 
 export const CarryForwardItemsStepSchema = z.object({
@@ -263,6 +264,7 @@ export const GoalBrainstormStepSchema = z.object({
   titleTemplate: z.string(),
   lookbackDays: z.number().optional(),
   customPrompt: z.string().optional(),
+  aiProfile: z.string().optional(),
 });
 
 export const ProcessExternalSourceStepSchema = z.object({
@@ -276,6 +278,13 @@ export const ProcessExternalSourceStepSchema = z.object({
   includeSourceLink: z.boolean().default(true),
   incremental: z.boolean().default(false),
   tags: z.array(z.string()).default([]),
+  aiProfile: z.string().optional(),
+});
+
+export const SpacedReviewStepSchema = z.object({
+  type: z.literal("spacedReview"),
+  notebook: NotebookTargetSchema.optional(),
+  aiProfile: z.string().optional(),
 });
 
 export const PluginStepSchema = z.object({
@@ -336,6 +345,7 @@ export const ActionStepSchema: z.ZodType<ActionStep> = z.lazy(() =>
     GoalNudgeStepSchema,
     GoalBrainstormStepSchema,
     ProcessExternalSourceStepSchema,
+    SpacedReviewStepSchema,
     PluginStepSchema,
   ])
 );
@@ -356,6 +366,7 @@ export type ActionStep =
   | z.infer<typeof GoalNudgeStepSchema>
   | z.infer<typeof GoalBrainstormStepSchema>
   | z.infer<typeof ProcessExternalSourceStepSchema>
+  | z.infer<typeof SpacedReviewStepSchema>
   | z.infer<typeof PluginStepSchema>;
 
 // ===== Variable Types =====
@@ -620,6 +631,12 @@ export const STEP_TYPES: StepTypeInfo[] = [
     description: "Import and summarize external files with AI",
     icon: "file-import",
   },
+  {
+    type: "spacedReview",
+    name: "Spaced Writing Review",
+    description: "Ask AI recall questions about old notes due for review",
+    icon: "brain",
+  },
   {
     type: "plugin",
     name: "Plugin",
@@ -149,6 +149,39 @@ export const AIActionSchema = z.object({
 
 export type AIAction = z.infer<typeof AIActionSchema>;
 
+// AI tool permissions: tools are read/write/destructive, and policies
+// (library-wide or per notebook) allow, ask or deny each class
+export const ToolDecisionSchema = z.enum(["allow", "ask", "deny"]);
+
+export type ToolDecision = z.infer<typeof ToolDecisionSchema>;
+
+export const ToolPolicySchema = z.object({
+  read: ToolDecisionSchema.default("allow"),
+  write: ToolDecisionSchema.default("allow"),
+  destructive: ToolDecisionSchema.default("ask"),
+  tools: z.record(z.string(), ToolDecisionSchema).optional(),
+});
+
+export type ToolPolicy = z.infer<typeof ToolPolicySchema>;
+
+export const ToolPermissionsSchema = z.object({
+  default: ToolPolicySchema,
+  notebooks: z.record(z.string(), ToolPolicySchema).optional(),
+});
+
+export type ToolPermissions = z.infer<typeof ToolPermissionsSchema>;
+
+export const ToolPermissionCheckSchema = z.object({
+  tool: z.string(),
+  class: z.enum(["read", "write", "destructive"]),
+  decision: ToolDecisionSchema,
+  notebookId: z.string().optional(),
+  reason: z.string(),
+  toolCallId: z.string().optional(),
+});
+
+export type ToolPermissionCheck = z.infer<typeof ToolPermissionCheckSchema>;
+
 // Chat response with actions
 export const ChatResponseWithActionsSchema = z.object({
   content: z.string(),
@@ -158,10 +191,49 @@ export const ChatResponseWithActionsSchema = z.object({
   finishReason: z.string().nullable().optional(),
   actions: z.array(AIActionSchema),
   thinking: z.string().nullable().optional(),
+  permissionDenials: z.array(ToolPermissionCheckSchema).optional(),
 });
 
 export type ChatResponseWithActions = z.infer<typeof ChatResponseWithActionsSchema>;
 
+// AI action log: what applied tool calls changed, for audit and undo
+export const AiPageChangeSchema = z.object({
+  notebookId: z.string(),
+  pageId: z.string(),
+  title: z.string(),
+  kind: z.enum(["created", "updated"]),
+  diff: z.array(
+    z.object({
+      blockId: z.string(),
+      op: z.enum(["insert", "modify", "delete", "move"]),
+      blockType: z.string().optional(),
+      afterBlockId: z.string().optional(),
+    })
+  ),
+  afterHash: z.string(),
+});
+
+export type AiPageChange = z.infer<typeof AiPageChangeSchema>;
+
+export const AiActionEntrySchema = z.object({
+  id: z.string(),
+  sessionId: z.string().optional(),
+  tool: z.string(),
+  arguments: z.unknown(),
+  toolCallId: z.string(),
+  appliedAt: z.string(),
+  changes: z.array(AiPageChangeSchema),
+  createdNotebooks: z.array(z.string()).optional(),
+  undoneAt: z.string().optional(),
+});
+
+export type AiActionEntry = z.infer<typeof AiActionEntrySchema>;
+
+export interface AiSessionUndo {
+  undone: AiActionEntry[];
+  failed: { entryId: string; tool: string; message: string }[];
+}
+
 // Create notebook action arguments
 export const CreateNotebookArgsSchema = z.object({
   name: z.string(),
@@ -214,9 +286,14 @@ export type StreamErrorEvent = {
   message: string;
 };
 
+export type StreamToolPermissionEvent = {
+  type: "toolPermission";
+} & ToolPermissionCheck;
+
 export type StreamEvent =
   | StreamChunkEvent
   | StreamThinkingEvent
   | StreamActionEvent
   | StreamDoneEvent
-  | StreamErrorEvent;
+  | StreamErrorEvent
+  | StreamToolPermissionEvent;
@@ -1,4 +1,5 @@
 import { z } from "zod";
+import type { Page } from "./page";
 
 // TTS provider types
 export const TTSProviderTypeSchema = z.enum([
@@ -103,3 +104,70 @@ export interface AudioBlockData {
   showTranscript?: boolean;
   recordedAt?: string;
 }
+
+// A page's chapter in a narrated reading queue
+export interface QueueChapter {
+  pageId: string;
+  title: string;
+  startSeconds: number;
+  endSeconds: number;
+}
+
+// Where the user stopped listening to a reading queue
+export interface PlaybackPosition {
+  queueId: string;
+  positionSeconds: number;
+  audioGeneratedAt: string;
+  updatedAt: string;
+}
+
+// Pages narrated into one audio file with a chapter per page
+export interface ReadingQueue {
+  id: string;
+  notebookId: string;
+  name: string;
+  pageIds: string[];
+  audio?: {
+    fileName: string;
+    durationSeconds: number;
+    fileSizeBytes: number;
+    chapters: QueueChapter[];
+    generatedAt: string;
+  };
+  createdAt: string;
+  updatedAt: string;
+  /** Absolute path of the narration, if generated */
+  audioPath: string | null;
+  /** False once the pages changed after narrating */
+  audioCurrent: boolean;
+  position: PlaybackPosition | null;
+}
+
+// Stage of processing a meeting recording, from the "meeting-progress" event
+export type MeetingStage =
+  | "transcribing"
+  | "identifyingSpeakers"
+  | "summarizing"
+  | "creatingPage";
+
+export interface MeetingProgress {
+  taskId: string | null;
+  stage: MeetingStage;
+}
+
+// An action item pulled out of a meeting, for the task list
+export interface MeetingActionItem {
+  text: string;
+  assignee?: string | null;
+  /** YYYY-MM-DD */
+  dueDate?: string | null;
+}
+
+export interface MeetingNotesResult {
+  page: Page;
+  actionItems: MeetingActionItem[];
+  /** Whether the transcript is split by speaker */
+  speakersIdentified: boolean;
+  /** Daily note the meeting was linked from */
+  dailyNoteId: string | null;
+}
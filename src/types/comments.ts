@@ -0,0 +1,33 @@
+import { z } from "zod";
+
+// A single comment. Thread roots have no parentId; replies point at their
+// thread's root and share its block anchor.
+export const CommentSchema = z.object({
+  id: z.string().uuid(),
+  pageId: z.string().uuid(),
+  blockId: z.string().optional(),
+  parentId: z.string().uuid().optional(),
+  author: z.string(),
+  body: z.string(),
+  resolved: z.boolean().default(false),
+  resolvedBy: z.string().optional(),
+  resolvedAt: z.string().optional(),
+  deleted: z.boolean().default(false),
+  createdAt: z.string(),
+  updatedAt: z.string(),
+});
+export type Comment = z.infer<typeof CommentSchema>;
+
+// A thread root with its replies, oldest first
+export const CommentThreadSchema = z.object({
+  root: CommentSchema,
+  replies: z.array(CommentSchema),
+});
+export type CommentThread = z.infer<typeof CommentThreadSchema>;
+
+export interface NewComment {
+  blockId?: string;
+  parentId?: string;
+  author?: string;
+  body: string;
+}
@@ -6,6 +6,7 @@ export const ActivityTypeSchema = z.enum([
   "faceTimeAudio",
   "faceTimeVideo",
   "missedCall",
+  "meeting",
 ]);
 export type ActivityType = z.infer<typeof ActivityTypeSchema>;
 
@@ -33,6 +34,9 @@ export const ContactActivitySchema = z.object({
   timestamp: z.string(),
   preview: z.string().nullable().optional(),
   durationSeconds: z.number().nullable().optional(),
+  /** Meeting notes page, for meeting activities */
+  pageId: z.string().uuid().nullable().optional(),
+  notebookId: z.string().uuid().nullable().optional(),
 });
 export type ContactActivity = z.infer<typeof ContactActivitySchema>;
 
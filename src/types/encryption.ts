@@ -1,7 +1,12 @@
 import { z } from "zod";
 
-// Encryption level: none, notebook, or library
-export const EncryptionLevelSchema = z.enum(["none", "notebook", "library"]);
+// Encryption level: none, notebook, library, or a single page
+export const EncryptionLevelSchema = z.enum([
+  "none",
+  "notebook",
+  "library",
+  "page",
+]);
 export type EncryptionLevel = z.infer<typeof EncryptionLevelSchema>;
 
 // Encryption configuration stored with notebooks/libraries
@@ -27,10 +32,25 @@ export type UnlockResult = z.infer<typeof UnlockResultSchema>;
 export const EncryptionStatsSchema = z.object({
   unlockedNotebooks: z.number().int().nonnegative(),
   unlockedLibraries: z.number().int().nonnegative(),
+  unlockedPages: z.number().int().nonnegative(),
   autoLockTimeoutSecs: z.number().int().positive(),
 });
 export type EncryptionStats = z.infer<typeof EncryptionStatsSchema>;
 
+// Biometric (TouchID / Windows Hello) unlock state of a notebook
+export const BiometricStatusSchema = z.object({
+  available: z.boolean(),
+  enrolled: z.boolean(),
+  passwordRequired: z.boolean(),
+});
+export type BiometricStatus = z.infer<typeof BiometricStatusSchema>;
+
+// Biometric unlock settings (0 hours = never ask for the password again)
+export const BiometricSettingsSchema = z.object({
+  requirePasswordAfterHours: z.number().int().nonnegative(),
+});
+export type BiometricSettings = z.infer<typeof BiometricSettingsSchema>;
+
 // Request to enable encryption
 export interface EnableEncryptionRequest {
   password: string;
@@ -28,6 +28,28 @@ export const ProcessedFileInfoSchema = z.object({
 
 export type ProcessedFileInfo = z.infer<typeof ProcessedFileInfoSchema>;
 
+// ===== Watch Folder =====
+
+export const WatchConfigSchema = z.object({
+  notebookId: z.string(),
+  folderId: z.string().nullable().optional(),
+  tags: z.array(z.string()).default([]),
+  doneFolder: z.string().default("done"),
+});
+
+export type WatchConfig = z.infer<typeof WatchConfigSchema>;
+
+/** Payload of the `watch-folder-import` event */
+export interface WatchImportEvent {
+  sourceId: string;
+  file: string;
+  notebookId: string;
+  pageId: string | null;
+  pageTitle: string | null;
+  movedTo: string | null;
+  error: string | null;
+}
+
 // ===== External Source =====
 
 export const ExternalSourceSchema = z.object({
@@ -38,6 +60,7 @@ export const ExternalSourceSchema = z.object({
   enabled: z.boolean().default(true),
   lastProcessed: z.string().optional(),
   processedFiles: z.array(ProcessedFileInfoSchema).default([]),
+  watch: WatchConfigSchema.optional(),
   createdAt: z.string(),
   updatedAt: z.string(),
 });
@@ -11,6 +11,7 @@ export const CardSourceSchema = z.discriminatedUnion("type", [
     type: z.literal("blockRef"),
     pageId: z.string().uuid(),
     blockId: z.string(),
+    sourceText: z.string().optional(),
   }),
 ]);
 export type CardSource = z.infer<typeof CardSourceSchema>;
@@ -34,6 +35,14 @@ export const DeckSchema = z.object({
 });
 export type Deck = z.infer<typeof DeckSchema>;
 
+// The cloze a cloze card hides; front/back are rendered from it
+export const ClozeSchema = z.object({
+  text: z.string(), // full text with {{cN::...}} markup
+  number: z.number(),
+  extra: z.string().optional(),
+});
+export type Cloze = z.infer<typeof ClozeSchema>;
+
 // Flashcard - can be standalone or linked to editor block
 export const FlashcardSchema = z.object({
   id: z.string().uuid(),
@@ -44,6 +53,8 @@ export const FlashcardSchema = z.object({
   tags: z.array(z.string()).default([]),
   source: CardSourceSchema.default({ type: "standalone" }),
   position: z.number().default(0),
+  cloze: ClozeSchema.optional(),
+  sourceChanged: z.boolean().default(false), // source block changed or deleted
   createdAt: z.string().datetime(),
   updatedAt: z.string().datetime(),
 });
@@ -57,6 +68,7 @@ export const CardStateSchema = z.object({
   dueDate: z.string().datetime(),
   reviewCount: z.number().default(0),
   correctCount: z.number().default(0),
+  lapses: z.number().default(0), // Times forgotten after being learned
   status: CardStatusSchema.default("new"),
 });
 export type CardState = z.infer<typeof CardStateSchema>;
@@ -85,6 +97,49 @@ export const ReviewStatsSchema = z.object({
 });
 export type ReviewStats = z.infer<typeof ReviewStatsSchema>;
 
+// Reviews done on one day, for the review heatmap
+export interface DailyReviews {
+  date: string; // YYYY-MM-DD, local
+  reviews: number;
+  correct: number;
+}
+
+// Share of correct answers for cards of an age (in days) at review time
+export interface RetentionBucket {
+  label: string;
+  minAgeDays: number;
+  maxAgeDays?: number;
+  reviews: number;
+  correct: number;
+  rate?: number; // Missing without reviews
+}
+
+// Reviewed cards with an ease factor from `min` to `min + 0.2`
+export interface EaseBucket {
+  min: number;
+  cards: number;
+}
+
+// Cards falling due on one day
+export interface DailyWorkload {
+  date: string;
+  due: number;
+}
+
+export interface DeckAnalytics {
+  heatmap: DailyReviews[];
+  retentionByAge: RetentionBucket[];
+  easeDistribution: EaseBucket[];
+  workload: DailyWorkload[]; // Next 30 days, overdue cards counted today
+  leechCount: number;
+}
+
+// Result of updating block cards after their blocks were edited
+export interface BlockCardSync {
+  updated: string[];
+  flagged: string[];
+}
+
 // Card with its current state - used for review sessions
 export const CardWithStateSchema = z.object({
   card: FlashcardSchema,
@@ -92,6 +147,83 @@ export const CardWithStateSchema = z.object({
 });
 export type CardWithState = z.infer<typeof CardWithStateSchema>;
 
+// What an incremental reading item points at
+export const ReadingSourceSchema = z.discriminatedUnion("type", [
+  z.object({ type: z.literal("page"), pageId: z.string().uuid() }),
+  z.object({
+    type: z.literal("extract"),
+    pageId: z.string().uuid(),
+    blockIds: z.array(z.string()).default([]),
+    text: z.string(),
+  }),
+]);
+export type ReadingSource = z.infer<typeof ReadingSourceSchema>;
+
+// A page or extract scheduled for re-reading (incremental reading)
+export const ReadingItemSchema = z.object({
+  id: z.string().uuid(),
+  title: z.string(),
+  source: ReadingSourceSchema,
+  priority: z.number().min(1).max(5).default(3), // 1 = highest
+  state: CardStateSchema,
+  done: z.boolean().default(false),
+  createdAt: z.string().datetime(),
+  updatedAt: z.string().datetime(),
+});
+export type ReadingItem = z.infer<typeof ReadingItemSchema>;
+
+// A card forgotten many times
+export type LeechCard = CardWithState & {
+  lapses: number;
+  lastLapse?: string;
+};
+
+// How to import a deck bundle whose deck is already in the notebook
+export type ImportCollision = "merge" | "copy" | "replace" | "skip";
+
+// A deck a bundle collides with; sameDeck is false when only names match
+export interface ExistingDeck {
+  id: string;
+  name: string;
+  cardCount: number;
+  sameDeck: boolean;
+}
+
+// A .nousdeck bundle, described without importing it
+export interface DeckBundleInfo {
+  path: string;
+  fileName: string;
+  deckId: string;
+  name: string;
+  description?: string;
+  color?: string;
+  cardCount: number;
+  hasScheduling: boolean;
+  mediaCount: number;
+  exportedAt: string;
+  existingDeck?: ExistingDeck;
+}
+
+export interface DeckImportResult {
+  deck?: Deck; // Missing when skipped
+  added: number;
+  updated: number;
+  unchanged: number;
+  mediaCopied: number;
+  mediaRenamed: number;
+}
+
+// Payload of the `shared-decks-changed` event
+export interface SharedDecksChanged {
+  folder: string;
+  files: string[];
+}
+
+// Entry in a review session mixing due cards and reading items
+export type ReviewQueueItem =
+  | ({ kind: "card" } & CardWithState)
+  | ({ kind: "reading" } & ReadingItem);
+
 // Rating options shown to user during review
 export type ReviewRating = 1 | 2 | 3 | 4; // Again, Hard, Good, Easy
 
@@ -12,6 +12,11 @@ export const CaptureSourceSchema = z.union([
     target_name: z.string(),
     target_id: z.string(),
   }),
+  z.object({
+    type: z.literal("mobile"),
+    platform: z.string(),
+    url: z.string().nullable(),
+  }),
 ]);
 
 export type CaptureSource = z.infer<typeof CaptureSourceSchema>;
@@ -37,6 +42,12 @@ export const ClassificationActionSchema = z.discriminatedUnion("type", [
     suggested_name: z.string(),
     suggested_icon: z.string().optional(),
   }),
+  z.object({
+    type: z.literal("AppendToDailyNote"),
+    notebook_id: z.string().uuid(),
+    notebook_name: z.string(),
+    date: z.string(),
+  }),
   z.object({
     type: z.literal("KeepInInbox"),
     reason: z.string(),
@@ -64,6 +75,7 @@ export const InboxItemSchema = z.object({
   source: CaptureSourceSchema,
   classification: InboxClassificationSchema.nullable().optional(),
   is_processed: z.boolean(),
+  snoozed_until: z.string().datetime().optional(),
 });
 
 export type InboxItem = z.infer<typeof InboxItemSchema>;
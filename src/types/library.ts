@@ -8,6 +8,49 @@ import { z } from "zod";
 import { EncryptionConfigSchema } from "./encryption";
 import { LibrarySyncConfigSchema } from "./sync";
 
+/**
+ * Scheduled one-way Markdown mirror of a library
+ */
+export const MarkdownMirrorConfigSchema = z.object({
+  enabled: z.boolean(),
+  targetDir: z.string(),
+  intervalMinutes: z.number().default(15),
+  includeArchived: z.boolean().default(false),
+});
+
+export type MarkdownMirrorConfig = z.infer<typeof MarkdownMirrorConfigSchema>;
+
+/**
+ * Result of a Markdown mirror run
+ */
+export interface MirrorReport {
+  written: number;
+  unchanged: number;
+  removed: number;
+  errors: string[];
+}
+
+/**
+ * Where a library's auto-backups are copied
+ */
+export const BackupDestinationSchema = z.discriminatedUnion("type", [
+  z.object({ type: z.literal("folder"), path: z.string() }),
+  z.object({
+    type: z.literal("webDav"),
+    serverUrl: z.string(),
+    remotePath: z.string(),
+  }),
+  z.object({
+    type: z.literal("s3"),
+    endpointUrl: z.string(),
+    bucket: z.string(),
+    region: z.string(),
+    pathPrefix: z.string(),
+  }),
+]);
+
+export type BackupDestination = z.infer<typeof BackupDestinationSchema>;
+
 /**
  * Library schema - represents a notebook storage location
  */
@@ -20,6 +63,9 @@ export const LibrarySchema = z.object({
   color: z.string().optional(),
   encryptionConfig: EncryptionConfigSchema.optional(),
   syncConfig: LibrarySyncConfigSchema.optional(),
+  markdownMirror: MarkdownMirrorConfigSchema.optional(),
+  backupDestination: BackupDestinationSchema.nullish(),
+  sharedDecksFolder: z.string().optional(), // Default: shared-decks/ in the library
   createdAt: z.string(),
   updatedAt: z.string(),
 });
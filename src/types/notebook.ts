@@ -25,13 +25,16 @@ export const NotebookSchema = z.object({
   systemPromptMode: SystemPromptModeSchema.default("override"),
   aiProvider: AIProviderTypeSchema.optional(),
   aiModel: z.string().optional(),
+  aiProfileId: z.string().optional(),
   syncConfig: SyncConfigSchema.optional(),
   encryptionConfig: EncryptionConfigSchema.optional(),
   isPinned: z.boolean().default(false),
   position: z.number().default(0),
   pageSortBy: PageSortOptionSchema.optional(),
+  pinnedPageIds: z.array(z.string().uuid()).optional(), // Pinned to the top of the notebook root, in order
   dailyNotesConfig: DailyNotesConfigSchema.optional(),
   coverImage: z.string().optional(),
+  excludeFromReview: z.boolean().optional(),
   mirrorPath: z.string().optional(),
   createdAt: z.string().datetime(),
   updatedAt: z.string().datetime(),
@@ -37,6 +37,7 @@ export const PageTypeSchema = z.enum([
   "canvas", // Infinite canvas/whiteboard (.canvas)
   "database", // Database/table view (.database)
   "html", // HTML page (from website mirror import)
+  "sql", // Block page whose code blocks are SQL over the notebook's datasets
 ]);
 export type PageType = z.infer<typeof PageTypeSchema>;
 
@@ -58,6 +59,7 @@ export const SectionSchema = z.object({
   systemPromptMode: SystemPromptModeSchema.default("override"),
   aiModel: z.string().optional(), // Model override (format: "provider:model" or just "model")
   pageSortBy: z.enum(["position", "name-asc", "name-desc", "updated", "created"]).optional(),
+  pinnedPageIds: z.array(z.string().uuid()).optional(), // Pinned to the top of the section root, in order
   position: z.number().default(0),
   createdAt: z.string().datetime(),
   updatedAt: z.string().datetime(),
@@ -76,12 +78,34 @@ export const FolderSchema = z.object({
   isArchived: z.boolean().default(false),
   color: z.string().optional(),
   position: z.number().default(0),
+  // Overrides the section/notebook sort for this folder's pages
+  pageSortBy: z.enum(["position", "name-asc", "name-desc", "updated", "created"]).nullable().optional(),
+  pinnedPageIds: z.array(z.string().uuid()).optional(), // Pinned to the top of the folder, in order
   createdAt: z.string().datetime(),
   updatedAt: z.string().datetime(),
 });
 
 export type Folder = z.infer<typeof FolderSchema>;
 
+// Writing statistics, recomputed by the backend on every save
+export const PageStatsSchema = z.object({
+  wordCount: z.number(),
+  characterCount: z.number(), // Not counting whitespace
+  readingTimeMinutes: z.number(),
+  revisionCount: z.number(), // Saves that changed the content
+});
+
+export type PageStats = z.infer<typeof PageStatsSchema>;
+
+export interface NotebookStats {
+  pageCount: number;
+  wordCount: number;
+  characterCount: number;
+  readingTimeMinutes: number;
+  revisionCount: number;
+  encryptedPages: number; // Left out of the totals
+}
+
 // Page schema
 export const PageSchema = z.object({
   id: z.string().uuid(),
@@ -117,6 +141,19 @@ export const PageSchema = z.object({
   // Plugin page type
   pluginPageType: z.string().nullable().optional(), // Plugin type identifier (e.g. "kanban")
   pluginData: z.unknown().nullable().optional(), // Opaque JSON data for plugin page types
+  // Free-form key/value metadata (e.g. an AI-written summary)
+  properties: z.record(z.string(), z.string()).optional(),
+  // Per-page encryption - content is empty while the page is locked
+  encryption: z
+    .object({
+      config: z
+        .object({ password_hint: z.string().nullable().optional() })
+        .passthrough(),
+      sealedContent: z.unknown(),
+    })
+    .nullable()
+    .optional(),
+  stats: PageStatsSchema.nullable().optional(),
   createdAt: z.string().datetime(),
   updatedAt: z.string().datetime(),
 });
@@ -167,6 +204,21 @@ export const SearchResultSchema = z.object({
 
 export type SearchResult = z.infer<typeof SearchResultSchema>;
 
+// Segment-level hit from the video transcript index
+export const TranscriptHitSchema = z.object({
+  pageId: z.string(),
+  notebookId: z.string(),
+  pageTitle: z.string(),
+  blockId: z.string(),
+  videoName: z.string(),
+  start: z.number(), // seconds
+  end: z.number(), // seconds
+  text: z.string(),
+  score: z.number(),
+});
+
+export type TranscriptHit = z.infer<typeof TranscriptHitSchema>;
+
 // Cross-notebook favorite page entry (lightweight — no content)
 export const FavoritePageEntrySchema = z.object({
   id: z.string().uuid(),
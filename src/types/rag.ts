@@ -5,12 +5,30 @@ export const EmbeddingProviderSchema = z.enum(["openai", "ollama", "lmstudio", "
 export type EmbeddingProvider = z.infer<typeof EmbeddingProviderSchema>;
 
 // Embedding configuration schema
+// How pages are split into chunks; sizes are in approximate tokens
+export const ChunkingStrategySchema = z.object({
+  chunkSize: z.number(),
+  overlap: z.number(),
+  headingAware: z.boolean(),
+  codeAware: z.boolean(),
+});
+
+export type ChunkingStrategy = z.infer<typeof ChunkingStrategySchema>;
+
+export const DEFAULT_CHUNKING_STRATEGY: ChunkingStrategy = {
+  chunkSize: 512,
+  overlap: 50,
+  headingAware: false,
+  codeAware: false,
+};
+
 export const EmbeddingConfigSchema = z.object({
   provider: EmbeddingProviderSchema,
   model: z.string(),
   dimensions: z.number(),
   apiKey: z.string().optional(),
   baseUrl: z.string().optional(),
+  chunking: ChunkingStrategySchema.optional(),
 });
 
 export type EmbeddingConfig = z.infer<typeof EmbeddingConfigSchema>;
@@ -23,6 +41,9 @@ export const SemanticSearchResultSchema = z.object({
   title: z.string(),
   content: z.string(),
   score: z.number(),
+  // Set for video transcript chunks: block to open and where to seek (seconds)
+  mediaBlockId: z.string().optional(),
+  mediaStart: z.number().optional(),
 });
 
 export type SemanticSearchResult = z.infer<typeof SemanticSearchResultSchema>;
@@ -37,6 +58,22 @@ export const VectorIndexStatsSchema = z.object({
 
 export type VectorIndexStats = z.infer<typeof VectorIndexStatsSchema>;
 
+// Progress of a library re-chunk, emitted as "rag-rechunk-progress"
+export interface RechunkProgress {
+  taskId: string | null;
+  pageId: string;
+  done: number;
+  total: number;
+}
+
+export interface RechunkSummary {
+  pages: number;
+  chunks: number;
+  // Pages whose embeddings failed; they keep their old chunks
+  failed: string[];
+  cancelled: boolean;
+}
+
 // Available embedding models per provider
 export const EMBEDDING_MODELS: Record<
   EmbeddingProvider,
@@ -161,6 +161,8 @@ export const RAGChunkSchema = z.object({
   title: z.string(),
   content: z.string(),
   score: z.number(),
+  libraryId: z.string().optional(),
+  libraryName: z.string().optional(),
 });
 export type RAGChunk = z.infer<typeof RAGChunkSchema>;
 
@@ -170,6 +172,9 @@ export const CitationSchema = z.object({
   pageTitle: z.string(),
   excerpt: z.string(),
   relevanceScore: z.number(),
+  // Set when the chat retrieved across libraries
+  libraryId: z.string().optional(),
+  libraryName: z.string().optional(),
 });
 export type Citation = z.infer<typeof CitationSchema>;
 
@@ -179,6 +184,12 @@ export const CitedResponseSchema = z.object({
 });
 export type CitedResponse = z.infer<typeof CitedResponseSchema>;
 
+/** Which libraries a cited chat retrieves sources from */
+export type RagScope =
+  | { type: "currentLibrary" }
+  | { type: "libraries"; libraryIds: string[] }
+  | { type: "allLibraries" };
+
 // ===== Generation Options =====
 
 export interface StudyToolsGenerationOptions {
@@ -1,4 +1,5 @@
 import { z } from "zod";
+import type { EditorData, Folder, PageType, Section } from "./page";
 
 // ===== Auth Types =====
 
@@ -12,6 +13,11 @@ export type SyncMode = z.infer<typeof SyncModeSchema>;
 
 // ===== Sync Config =====
 
+// Which remote assets this device downloads; "onDemand" fetches an asset
+// the first time a page using it is opened
+export const AssetSyncPolicySchema = z.enum(["all", "onDemand", "never"]);
+export type AssetSyncPolicy = z.infer<typeof AssetSyncPolicySchema>;
+
 export const SyncConfigSchema = z.object({
   enabled: z.boolean(),
   serverUrl: z.string().url(),
@@ -21,6 +27,7 @@ export const SyncConfigSchema = z.object({
   syncInterval: z.number().optional(), // seconds, for periodic mode
   lastSync: z.string().datetime().optional(),
   managedByLibrary: z.boolean().optional(),
+  assetPolicy: AssetSyncPolicySchema.default("all"),
 });
 
 export type SyncConfig = z.infer<typeof SyncConfigSchema>;
@@ -74,6 +81,7 @@ export const SyncResultSchema = z.object({
   duration: z.number().optional(), // milliseconds
   assetsPushed: z.number().default(0),
   assetsPulled: z.number().default(0),
+  assetsDeferred: z.boolean().default(false), // skipped on metered connection
 });
 
 export type SyncResult = z.infer<typeof SyncResultSchema>;
@@ -122,6 +130,122 @@ export const QueueItemSchema = z.object({
 
 export type QueueItem = z.infer<typeof QueueItemSchema>;
 
+export interface QueuedOperation {
+  id: string;
+  operation: SyncOperation;
+  createdAt: string;
+  ageSecs: number;
+  retries: number;
+  lastError?: string;
+}
+
+export interface NotebookQueueDetails {
+  notebookId: string;
+  notebookName?: string;
+  oldestAgeSecs: number;
+  items: QueuedOperation[];
+}
+
+export interface SyncQueueDetails {
+  metered: boolean | null; // null when the platform can't tell
+  notebooks: NotebookQueueDetails[];
+}
+
+export interface EnsuredAsset {
+  path: string;
+  downloaded: boolean; // false when the asset was already on this device
+}
+
+// ===== Sync Health =====
+
+export type DiscrepancyKind =
+  | "missingRemote"
+  | "missingLocal"
+  | "notInManifest"
+  | "staleManifestEntry"
+  | "remoteChanged"
+  | "manifestEtagMismatch";
+
+export type RepairAction = "push" | "pull" | "pruneManifest";
+
+export interface SyncDiscrepancy {
+  pageId: string;
+  title?: string;
+  kind: DiscrepancyKind;
+  repair: RepairAction;
+}
+
+export interface SyncVerifyReport {
+  notebookId: string;
+  checkedAt: string;
+  localPages: number;
+  remoteFiles: number;
+  manifestPages: number;
+  manifestMissing: boolean;
+  discrepancies: SyncDiscrepancy[];
+}
+
+export interface SyncRepair {
+  pageId: string;
+  action: RepairAction;
+}
+
+// ===== Remote Browsing (read-only, no sync) =====
+
+export interface RemoteNotebook {
+  id: string;
+  name: string;
+  type: "standard" | "zettelkasten";
+  icon?: string;
+  color?: string;
+  archived: boolean;
+  pageCount: number;
+  updatedAt?: string;
+  local: boolean; // also exists on this device
+}
+
+export interface RemotePageSummary {
+  id: string;
+  title: string;
+  tags: string[];
+  folderId?: string;
+  sectionId?: string;
+  parentPageId?: string;
+  pageType: PageType;
+  isArchived: boolean;
+  updatedAt?: string;
+  local: boolean;
+}
+
+export interface RemoteNotebookListing {
+  notebookId: string;
+  pages: RemotePageSummary[];
+  folders: Folder[];
+  sections: Section[];
+}
+
+export interface RemotePage {
+  notebookId: string;
+  pageId: string;
+  content: EditorData;
+}
+
+// ===== Sync Bandwidth =====
+
+export const SyncBandwidthSchema = z.object({
+  maxUploadKbps: z.number().optional(), // KiB/s, unset = unlimited
+  maxDownloadKbps: z.number().optional(),
+  skipAssetsOnMetered: z.boolean().default(false),
+});
+
+export type SyncBandwidth = z.infer<typeof SyncBandwidthSchema>;
+
+// Upload compression for CRDTs and metadata; every device must be on a
+// version that reads compressed files before turning it on
+export const SyncCompressionSchema = z.enum(["none", "zstd", "gzip"]);
+
+export type SyncCompression = z.infer<typeof SyncCompressionSchema>;
+
 // ===== Library Sync Config =====
 
 export const LibrarySyncConfigSchema = z.object({
@@ -131,6 +255,10 @@ export const LibrarySyncConfigSchema = z.object({
   authType: AuthTypeSchema,
   syncMode: SyncModeSchema.default("manual"),
   syncInterval: z.number().optional(),
+  bandwidth: SyncBandwidthSchema.optional(),
+  compression: SyncCompressionSchema.default("none"),
+  // Push only missing CRDT updates; needs the same support on every device
+  deltaSync: z.boolean().default(false),
 });
 
 export type LibrarySyncConfig = z.infer<typeof LibrarySyncConfigSchema>;
@@ -9,10 +9,13 @@ import type {
   Page,
   EditorData,
   SearchResult,
+  TranscriptHit,
   Folder,
   Section,
   BlockHistoryEntry,
   FavoritePageEntry,
+  PageStats,
+  NotebookStats,
 } from "../types/page";
 import type {
   SyncConfigInput,
@@ -20,6 +23,16 @@ import type {
   SyncResult,
   QueueItem,
   LibrarySyncConfigInput,
+  AssetSyncPolicy,
+  EnsuredAsset,
+  SyncBandwidth,
+  SyncCompression,
+  SyncQueueDetails,
+  SyncRepair,
+  SyncVerifyReport,
+  RemoteNotebook,
+  RemoteNotebookListing,
+  RemotePage,
 } from "../types/sync";
 import type {
   OrganizeSuggestion,
@@ -59,9 +72,11 @@ export async function updateNotebook(
     systemPromptMode?: string;
     aiProvider?: string;
     aiModel?: string;
+    aiProfileId?: string;
     isPinned?: boolean;
     pageSortBy?: string;
     coverImage?: string;
+    excludeFromReview?: boolean;
   }
 ): Promise<Notebook> {
   // Daemon expects snake_case keys; omit absent fields so "no change" stays
@@ -79,9 +94,12 @@ export async function updateNotebook(
     body.system_prompt_mode = updates.systemPromptMode;
   if (updates.aiProvider !== undefined) body.ai_provider = updates.aiProvider;
   if (updates.aiModel !== undefined) body.ai_model = updates.aiModel;
+  if (updates.aiProfileId !== undefined) body.ai_profile_id = updates.aiProfileId;
   if (updates.isPinned !== undefined) body.is_pinned = updates.isPinned;
   if (updates.pageSortBy !== undefined) body.page_sort_by = updates.pageSortBy;
   if (updates.coverImage !== undefined) body.cover_image = updates.coverImage;
+  if (updates.excludeFromReview !== undefined)
+    body.exclude_from_review = updates.excludeFromReview;
   return daemonPut<Notebook>(`/api/notebooks/${notebookId}`, body);
 }
 
@@ -270,6 +288,49 @@ export async function closePaneForPage(
   daemonEventBus.paneClose(pageId, paneId);
 }
 
+// Per-page encryption (daemon). While locked a page's content is empty and
+// it is left out of search; unlockPage returns it with the content decrypted.
+export async function encryptPage(
+  notebookId: string,
+  pageId: string,
+  password: string,
+  passwordHint?: string
+): Promise<Page> {
+  return daemonPost<Page>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/encrypt`,
+    { password, passwordHint }
+  );
+}
+
+export async function decryptPage(
+  notebookId: string,
+  pageId: string,
+  password: string
+): Promise<Page> {
+  return daemonPost<Page>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/decrypt`,
+    { password }
+  );
+}
+
+export async function unlockPage(
+  notebookId: string,
+  pageId: string,
+  password: string
+): Promise<Page> {
+  return daemonPost<Page>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/unlock`,
+    { password }
+  );
+}
+
+export async function lockPage(
+  notebookId: string,
+  pageId: string
+): Promise<Page> {
+  return daemonPost<Page>(`/api/notebooks/${notebookId}/pages/${pageId}/lock`);
+}
+
 export async function deletePage(
   notebookId: string,
   pageId: string
@@ -316,6 +377,81 @@ export async function movePageToParent(
   });
 }
 
+/** Pages written by a block move, extraction or page merge */
+export interface BlockEditResult {
+  /** Changed pages, including pages whose links were updated */
+  updated: Page[];
+  /** Page an extract created */
+  created: Page | null;
+  /** Pages a split created, in page order */
+  subpages: Page[];
+  /** Page a merge moved to trash */
+  trashedPageId: string | null;
+}
+
+/**
+ * Move blocks to another page (or within this one) at `position` (default:
+ * the end). Block refs to them follow.
+ */
+export async function moveBlocks(
+  notebookId: string,
+  pageId: string,
+  blockIds: string[],
+  targetPageId: string,
+  position?: number
+): Promise<BlockEditResult> {
+  return daemonPost<BlockEditResult>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/move-blocks`,
+    { block_ids: blockIds, target_page_id: targetPageId, position }
+  );
+}
+
+/**
+ * Move blocks into a new page next to this one, leaving a link to it in
+ * their place unless `leaveLink` is false
+ */
+export async function extractBlocksToNewPage(
+  notebookId: string,
+  pageId: string,
+  blockIds: string[],
+  options: { title?: string; leaveLink?: boolean } = {}
+): Promise<BlockEditResult> {
+  return daemonPost<BlockEditResult>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/extract-blocks`,
+    { block_ids: blockIds, title: options.title, leave_link: options.leaveLink }
+  );
+}
+
+/**
+ * Append a page to another and move it to trash; links to it and its
+ * subpages move to the target
+ */
+export async function mergePageInto(
+  notebookId: string,
+  pageId: string,
+  targetPageId: string
+): Promise<BlockEditResult> {
+  return daemonPost<BlockEditResult>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/merge-into`,
+    { target_page_id: targetPageId }
+  );
+}
+
+/**
+ * Turn each H1/H2 section of a page into a subpage, leaving a table of
+ * contents of links (or transclusions) on the page
+ */
+export async function splitPageByHeadings(
+  notebookId: string,
+  pageId: string,
+  options: { maxLevel?: 1 | 2; tocStyle?: "links" | "transclusions" } = {}
+): Promise<BlockEditResult> {
+  return daemonPost<BlockEditResult>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/split-by-headings`,
+    { max_level: options.maxLevel, toc_style: options.tocStyle }
+  );
+}
+
 export async function movePageToNotebook(
   sourceNotebookId: string,
   pageId: string,
@@ -382,6 +518,7 @@ export async function updateFolder(
     parentId?: string | null;
     color?: string | null;
     sectionId?: string | null;
+    pageSortBy?: string | null;
   }
 ): Promise<Folder> {
   // Triple-state: omit field for "no change", set null for "clear",
@@ -392,6 +529,7 @@ export async function updateFolder(
   if (updates.parentId !== undefined) body.parent_id = updates.parentId;
   if (updates.color !== undefined) body.color = updates.color;
   if (updates.sectionId !== undefined) body.section_id = updates.sectionId;
+  if (updates.pageSortBy !== undefined) body.page_sort_by = updates.pageSortBy;
   return daemonPut<Folder>(
     `/api/notebooks/${notebookId}/folders/${folderId}`,
     body
@@ -476,6 +614,26 @@ export async function reorderPages(
   });
 }
 
+/** Where a page's pin is kept: its folder, its section's root or the notebook root */
+export type PageContainer =
+  | { kind: "folder"; id: string }
+  | { kind: "section"; id: string }
+  | { kind: "root" };
+
+/**
+ * Pin a page to the top of its folder, section or notebook root, or unpin it
+ */
+export async function setPagePinned(
+  notebookId: string,
+  pageId: string,
+  pinned: boolean
+): Promise<PageContainer> {
+  return daemonPost<PageContainer>(
+    `/api/notebooks/${notebookId}/pages/${pageId}/pin`,
+    { pinned }
+  );
+}
+
 export async function ensureArchiveFolder(notebookId: string): Promise<Folder> {
   return invoke<Folder>("ensure_archive_folder", { notebookId });
 }
@@ -515,15 +673,33 @@ export async function searchPages(
   return daemonGet<SearchResult[]>(`/api/search?${params.toString()}`);
 }
 
+/**
+ * Fuzzy title search, ranked by the library's ranking boosts; pages in
+ * `currentNotebookId` rank higher
+ */
 export async function fuzzySearchPages(
   query: string,
-  limit?: number
+  limit?: number,
+  currentNotebookId?: string
 ): Promise<SearchResult[]> {
   const params = new URLSearchParams({ q: query, fuzzy: "1" });
   if (limit !== undefined) params.set("limit", String(limit));
+  if (currentNotebookId) params.set("current_notebook_id", currentNotebookId);
   return daemonGet<SearchResult[]>(`/api/search?${params.toString()}`);
 }
 
+export async function searchTranscripts(
+  query: string,
+  options?: { notebookId?: string; limit?: number }
+): Promise<TranscriptHit[]> {
+  const params = new URLSearchParams({ q: query });
+  if (options?.notebookId) params.set("notebook_id", options.notebookId);
+  if (options?.limit !== undefined) params.set("limit", String(options.limit));
+  return daemonGet<TranscriptHit[]>(
+    `/api/search/transcripts?${params.toString()}`
+  );
+}
+
 export async function rebuildSearchIndex(): Promise<void> {
   await daemonPost<{ ok: boolean; indexed: number }>(
     "/api/search/rebuild",
@@ -534,12 +710,18 @@ export async function rebuildSearchIndex(): Promise<void> {
 // ===== AI API =====
 
 import type {
+  AIAction,
+  AiActionEntry,
+  AiSessionUndo,
   ChatMessage,
   ChatResponse,
   ChatResponseWithActions,
   NotebookInfo,
   PageContext,
+  ToolPermissionCheck,
+  ToolPermissions,
 } from "../types/ai";
+import type { MCPToolResult } from "../types/mcp";
 
 export async function aiChat(
   messages: ChatMessage[],
@@ -549,6 +731,8 @@ export async function aiChat(
     model?: string;
     temperature?: number;
     maxTokens?: number;
+    /** Lets the request be cancelled with cancelAiTask */
+    taskId?: string;
   }
 ): Promise<ChatResponse> {
   const args = {
@@ -558,6 +742,7 @@ export async function aiChat(
     model: options?.model,
     temperature: options?.temperature,
     maxTokens: options?.maxTokens,
+    taskId: options?.taskId,
   };
   if (!isTauri()) return daemonPost<ChatResponse>("/api/ai/chat", args);
   return invoke<ChatResponse>("ai_chat", args);
@@ -573,6 +758,8 @@ export async function aiChatWithContext(
     model?: string;
     temperature?: number;
     maxTokens?: number;
+    /** Lets the request be cancelled with cancelAiTask */
+    taskId?: string;
   }
 ): Promise<ChatResponse> {
   const args = {
@@ -584,6 +771,7 @@ export async function aiChatWithContext(
     model: options?.model,
     temperature: options?.temperature,
     maxTokens: options?.maxTokens,
+    taskId: options?.taskId,
   };
   if (!isTauri()) return daemonPost<ChatResponse>("/api/ai/chat-context", args);
   return invoke<ChatResponse>("ai_chat_with_context", args);
@@ -671,6 +859,45 @@ export async function aiSuggestRelatedPages(
   return invoke<RelatedPageSuggestion[]>("ai_suggest_related_pages", args);
 }
 
+export interface LinkTarget {
+  pageId: string;
+  title: string;
+}
+
+/** Accepted related-page suggestions for one page */
+export interface PageLinks {
+  notebookId: string;
+  pageId: string;
+  targets: LinkTarget[];
+}
+
+export interface LinkedPage {
+  notebookId: string;
+  pageId: string;
+  title: string;
+  /** Targets linked at a mention in the text */
+  inline: string[];
+  /** Targets listed under the page's "Related" heading */
+  related: string[];
+  /** Targets the page already linked to */
+  existing: string[];
+}
+
+export interface LinkApplyResult {
+  /** Roll back to this snapshot to undo the links */
+  snapshot: LibrarySnapshot;
+  linked: LinkedPage[];
+  skipped: BulkSkipped[];
+}
+
+/**
+ * Add wiki-links for accepted suggestions across many pages at once. Each
+ * target is linked where its title is mentioned, or listed under "Related".
+ */
+export async function applyLinkSuggestions(pages: PageLinks[]): Promise<LinkApplyResult> {
+  return invoke<LinkApplyResult>("apply_link_suggestions", { pages });
+}
+
 export interface PageSummaryInput {
   title: string;
   content: string;
@@ -695,6 +922,8 @@ export async function aiSummarizePages(
     providerType?: string;
     apiKey?: string;
     model?: string;
+    /** Lets the request be cancelled with cancelAiTask */
+    taskId?: string;
   }
 ): Promise<PagesSummaryResult> {
   const args = {
@@ -704,6 +933,7 @@ export async function aiSummarizePages(
     providerType: options?.providerType,
     apiKey: options?.apiKey,
     model: options?.model,
+    taskId: options?.taskId,
   };
   if (!isTauri()) {
     return daemonPost<PagesSummaryResult>("/api/ai/summarize-pages", args);
@@ -711,6 +941,163 @@ export async function aiSummarizePages(
   return invoke<PagesSummaryResult>("ai_summarize_pages", args);
 }
 
+/**
+ * Cancel an AI request started with a taskId. Returns whether it was found;
+ * a request that already started finishes but its result is dropped.
+ */
+export async function cancelAiTask(taskId: string): Promise<boolean> {
+  return invoke<boolean>("cancel_ai_task", { taskId });
+}
+
+// ===== AI Bulk Operations =====
+
+export type BulkOperation =
+  | { kind: "retitle" }
+  | { kind: "summarize"; property: string }
+  | { kind: "translate"; language: string }
+  | { kind: "fixFormatting" }
+  | { kind: "custom"; prompt: string };
+
+export interface BulkPageChange {
+  pageId: string;
+  notebookId: string;
+  baseUpdatedAt: string;
+  title: string;
+  newTitle: string | null;
+  property: { name: string; oldValue: string | null; value: string } | null;
+  beforeMarkdown: string;
+  afterMarkdown: string;
+  content: EditorData | null;
+}
+
+export interface BulkSkipped {
+  pageId: string;
+  title: string;
+  reason: string;
+}
+
+export interface BulkTransformPreview {
+  changes: BulkPageChange[];
+  skipped: BulkSkipped[];
+}
+
+export interface BulkApplyResult {
+  /** Roll back to this snapshot to undo the whole batch */
+  snapshot: LibrarySnapshot;
+  applied: string[];
+  skipped: BulkSkipped[];
+}
+
+/**
+ * Preview an AI bulk operation over pages of a notebook. Nothing is saved;
+ * progress arrives as `ai-bulk-progress` events.
+ */
+export async function aiBulkTransform(
+  notebookId: string,
+  pageIds: string[],
+  operation: BulkOperation,
+  options?: {
+    providerType?: string;
+    apiKey?: string;
+    model?: string;
+    /** Lets the batch be cancelled with cancelAiTask */
+    taskId?: string;
+  }
+): Promise<BulkTransformPreview> {
+  return invoke<BulkTransformPreview>("ai_bulk_transform", {
+    notebookId,
+    pageIds,
+    operation,
+    providerType: options?.providerType,
+    apiKey: options?.apiKey,
+    model: options?.model,
+    taskId: options?.taskId,
+  });
+}
+
+export async function applyAiBulkTransform(
+  operation: BulkOperation,
+  changes: BulkPageChange[]
+): Promise<BulkApplyResult> {
+  return invoke<BulkApplyResult>("apply_ai_bulk_transform", {
+    operation,
+    changes,
+  });
+}
+
+// ===== Page Translation =====
+
+/** `replace` overwrites the page; `copy` creates a linked translated copy */
+export type TranslationMode = "replace" | "copy";
+
+export interface PageTranslation {
+  pageId: string;
+  title: string;
+  /** Language the AI detected the page to be written in */
+  sourceLanguage: string;
+  targetLanguage: string;
+  mode: TranslationMode;
+  /** The page itself when replacing, the new copy otherwise */
+  translatedPageId: string;
+  segments: number;
+}
+
+export interface FolderTranslation {
+  translated: PageTranslation[];
+  skipped: { pageId: string; title: string; reason: string }[];
+}
+
+interface TranslationOptions {
+  providerType?: string;
+  apiKey?: string;
+  model?: string;
+  /** Lets the translation be cancelled with cancelAiTask */
+  taskId?: string;
+}
+
+/** Translate a page block by block, detecting its source language */
+export async function translatePage(
+  notebookId: string,
+  pageId: string,
+  targetLanguage: string,
+  mode: TranslationMode,
+  options?: TranslationOptions
+): Promise<PageTranslation> {
+  return invoke<PageTranslation>("translate_page", {
+    notebookId,
+    pageId,
+    targetLanguage,
+    mode,
+    providerType: options?.providerType,
+    apiKey: options?.apiKey,
+    model: options?.model,
+    taskId: options?.taskId,
+  });
+}
+
+/**
+ * Translate every page in a folder and its subfolders. Progress arrives as
+ * `translation-progress` events.
+ */
+export async function translateFolder(
+  notebookId: string,
+  folderId: string,
+  targetLanguage: string,
+  mode: TranslationMode,
+  options?: TranslationOptions
+): Promise<FolderTranslation> {
+  return invoke<FolderTranslation>("translate_folder", {
+    notebookId,
+    folderId,
+    targetLanguage,
+    mode,
+    providerType: options?.providerType,
+    apiKey: options?.apiKey,
+    model: options?.model,
+    taskId: options?.taskId,
+  });
+}
+
 export async function aiChatWithTools(
   userMessage: string,
   options?: {
@@ -723,6 +1110,10 @@ export async function aiChatWithTools(
     model?: string;
     temperature?: number;
     maxTokens?: number;
+    /** Lets the request be cancelled with cancelAiTask */
+    taskId?: string;
+    /** Chat session the applied actions are logged under */
+    sessionId?: string;
   }
 ): Promise<ChatResponseWithActions> {
   const args = {
@@ -736,6 +1127,8 @@ export async function aiChatWithTools(
     model: options?.model,
     temperature: options?.temperature,
     maxTokens: options?.maxTokens,
+    taskId: options?.taskId,
+    sessionId: options?.sessionId,
   };
   if (!isTauri()) {
     return daemonPost<ChatResponseWithActions>("/api/ai/chat-tools", args);
@@ -743,6 +1136,90 @@ export async function aiChatWithTools(
   return invoke<ChatResponseWithActions>("ai_chat_with_tools", args);
 }
 
+// AI action log (audit and undo of applied tool calls)
+
+export async function listAiActions(options?: {
+  sessionId?: string;
+  limit?: number;
+}): Promise<AiActionEntry[]> {
+  return invoke<AiActionEntry[]>("list_ai_actions", {
+    sessionId: options?.sessionId,
+    limit: options?.limit,
+  });
+}
+
+/** Log pages/notebooks the frontend created for an AI tool call */
+export async function recordAiAction(
+  action: AIAction,
+  created: {
+    sessionId?: string;
+    notebookIds?: string[];
+    pages?: { notebookId: string; pageId: string }[];
+  }
+): Promise<AiActionEntry> {
+  return invoke<AiActionEntry>("record_ai_action", {
+    sessionId: created.sessionId,
+    tool: action.tool,
+    arguments: action.arguments,
+    toolCallId: action.toolCallId,
+    notebookIds: created.notebookIds,
+    pages: created.pages?.map((p) => [p.notebookId, p.pageId]),
+  });
+}
+
+export async function undoAiAction(id: string): Promise<AiActionEntry> {
+  return invoke<AiActionEntry>("undo_ai_action", { id });
+}
+
+export async function undoAiSession(sessionId: string): Promise<AiSessionUndo> {
+  return invoke<AiSessionUndo>("undo_ai_session", { sessionId });
+}
+
+export type AiCacheKind = "summary" | "tags" | "embedding" | "studyTool";
+
+export interface AiCacheStats {
+  kind: AiCacheKind;
+  entries: number;
+  bytes: number;
+  hits: number;
+}
+
+/** Empty the AI response cache (or one kind of it); returns entries removed */
+export async function clearAiCache(kind?: AiCacheKind): Promise<number> {
+  return invoke<number>("clear_ai_cache", { kind });
+}
+
+export async function getAiCacheStats(): Promise<AiCacheStats[]> {
+  return invoke<AiCacheStats[]>("get_ai_cache_stats");
+}
+
+export async function getToolPermissions(): Promise<ToolPermissions> {
+  return invoke<ToolPermissions>("get_tool_permissions");
+}
+
+export async function saveToolPermissions(permissions: ToolPermissions): Promise<void> {
+  return invoke("save_tool_permissions", { permissions });
+}
+
+/** Check an AI tool call against the library's tool permissions */
+export async function checkToolPermission(action: AIAction): Promise<ToolPermissionCheck> {
+  return invoke<ToolPermissionCheck>("check_tool_permission", {
+    tool: action.tool,
+    arguments: action.arguments,
+    toolCallId: action.toolCallId,
+  });
+}
+
+/** Call an MCP tool; `approved` runs a tool whose policy asks for approval */
+export async function mcpCallTool(
+  serverName: string,
+  toolName: string,
+  args: Record<string, unknown>,
+  approved?: boolean
+): Promise<MCPToolResult> {
+  return invoke<MCPToolResult>("mcp_call_tool", { serverName, toolName, arguments: args, approved });
+}
+
 export async function aiChatStream(
   userMessage: string,
   options?: {
@@ -846,6 +1323,106 @@ export async function deleteTag(
   return resp.pagesUpdated;
 }
 
+export interface TagDefinition {
+  name: string;
+  description?: string | null;
+  color?: string | null;
+  aliases: string[];
+}
+
+export interface TagNode {
+  name: string;
+  label: string;
+  /** Pages with exactly this tag (or an alias) */
+  count: number;
+  /** Pages with this tag or any tag below it */
+  total: number;
+  description?: string;
+  color?: string;
+  children: TagNode[];
+}
+
+export interface TaggedPage {
+  notebookId: string;
+  pageId: string;
+  title: string;
+  tags: string[];
+}
+
+export async function getTagRegistry(): Promise<TagDefinition[]> {
+  return daemonGet<TagDefinition[]>("/api/tags/registry");
+}
+
+export async function saveTagDefinition(
+  definition: TagDefinition
+): Promise<TagDefinition> {
+  return daemonPut<TagDefinition>("/api/tags/registry", definition);
+}
+
+export async function deleteTagDefinition(name: string): Promise<void> {
+  await daemonDelete(`/api/tags/registry/${encodeURIComponent(name)}`);
+}
+
+/** Library tag hierarchy with child counts rolled up into parents */
+export async function getTagTree(): Promise<TagNode[]> {
+  return daemonGet<TagNode[]>("/api/tags/tree");
+}
+
+export async function getPagesByTag(
+  tag: string,
+  includeChildren = true
+): Promise<TaggedPage[]> {
+  const params = new URLSearchParams({
+    tag,
+    include_children: String(includeChildren),
+  });
+  return daemonGet<TaggedPage[]>(`/api/tags/pages?${params}`);
+}
+
+export type TagRuleCondition =
+  | { type: "titleMatches"; pattern: string }
+  | { type: "contentMatches"; pattern: string }
+  | { type: "inFolder"; folder: string }
+  | { type: "inNotebook"; notebookId: string }
+  | { type: "hasTag"; tag: string };
+
+/** Adds `addTags` to pages matching `condition` when they're saved */
+export interface TagRule {
+  id: string;
+  name: string;
+  enabled: boolean;
+  condition: TagRuleCondition;
+  addTags: string[];
+  createdAt: string;
+}
+
+export interface TagRuleMatch {
+  notebookId: string;
+  pageId: string;
+  title: string;
+  tagsToAdd: string[];
+}
+
+export async function listTagRules(): Promise<TagRule[]> {
+  return daemonGet<TagRule[]>("/api/tags/rules");
+}
+
+export async function saveTagRule(rule: TagRule): Promise<TagRule> {
+  return daemonPut<TagRule>("/api/tags/rules", rule);
+}
+
+export async function deleteTagRule(ruleId: string): Promise<void> {
+  await daemonDelete(`/api/tags/rules/${ruleId}`);
+}
+
+/** Pages a rule would tag, without changing anything */
+export async function testTagRule(
+  rule: TagRule,
+  limit?: number
+): Promise<TagRuleMatch[]> {
+  return daemonPost<TagRuleMatch[]>("/api/tags/rules/test", { rule, limit });
+}
+
 // ===== Markdown Import/Export API =====
 
 export async function exportPageToMarkdown(
@@ -910,7 +1487,7 @@ import type {
 
 export async function webSearch(
   query: string,
-  apiKey: string,
+  apiKey: string | undefined,
   options?: {
     maxResults?: number;
     searchDepth?: "basic" | "advanced";
@@ -963,21 +1540,50 @@ export async function clipWebPage(url: string): Promise<ClippedContent> {
   return invoke<ClippedContent>("clip_web_page", { url });
 }
 
-// ===== Browser Automation API =====
+// Browser-extension pairing (the extension itself talks to /api/clipper/*
+// with its own clip_ token)
 
-export async function runBrowserTask(
-  task: string,
-  providerType: string,
-  apiKey: string,
-  model: string,
-  captureScreenshot = false
-): Promise<BrowserTaskResult> {
-  return invoke<BrowserTaskResult>("browser_run_task", {
-    task,
-    providerType,
-    apiKey,
+export interface ClipperPairingCode {
+  code: string;
+  expiresAt: string;
+}
+
+export interface ClipperClient {
+  id: string;
+  name: string;
+  pairedAt: string;
+  lastUsedAt: string | null;
+}
+
+export async function startClipperPairing(): Promise<ClipperPairingCode> {
+  return daemonPost<ClipperPairingCode>("/api/clipper/pairing");
+}
+
+export async function listClipperClients(): Promise<ClipperClient[]> {
+  return daemonGet<ClipperClient[]>("/api/clipper/clients");
+}
+
+export async function revokeClipperClient(clientId: string): Promise<void> {
+  await daemonDelete(`/api/clipper/clients/${clientId}`);
+}
+
+// ===== Browser Automation API =====
+
+export async function runBrowserTask(
+  task: string,
+  providerType: string,
+  apiKey: string,
+  model: string,
+  captureScreenshot = false,
+  taskId?: string
+): Promise<BrowserTaskResult> {
+  return invoke<BrowserTaskResult>("browser_run_task", {
+    task,
+    providerType,
+    apiKey,
     model,
     captureScreenshot,
+    taskId,
   });
 }
 
@@ -991,23 +1597,76 @@ export interface BackupInfo {
   notebookName: string;
   pageCount: number;
   assetCount: number;
+  encrypted: boolean;
+}
+
+// Password or keyfile for an encrypted backup; omit both for a plain ZIP
+export interface BackupSecretOptions {
+  password?: string;
+  keyfilePath?: string;
 }
 
 export async function exportNotebookZip(
   notebookId: string,
-  outputPath: string
+  outputPath: string,
+  secret: BackupSecretOptions = {}
 ): Promise<BackupInfo> {
-  return invoke<BackupInfo>("export_notebook_zip", { notebookId, outputPath });
+  return invoke<BackupInfo>("export_notebook_zip", {
+    notebookId,
+    outputPath,
+    password: secret.password,
+    keyfilePath: secret.keyfilePath,
+  });
+}
+
+export async function importNotebookZip(
+  zipPath: string,
+  secret: BackupSecretOptions = {}
+): Promise<Notebook> {
+  return invoke<Notebook>("import_notebook_zip", {
+    zipPath,
+    password: secret.password,
+    keyfilePath: secret.keyfilePath,
+  });
 }
 
-export async function importNotebookZip(zipPath: string): Promise<Notebook> {
-  return invoke<Notebook>("import_notebook_zip", { zipPath });
+export async function createBackupKeyfile(path: string): Promise<void> {
+  return invoke("create_backup_keyfile", { path });
 }
 
 export async function getBackupMetadata(zipPath: string): Promise<BackupInfo> {
   return invoke<BackupInfo>("get_backup_metadata", { zipPath });
 }
 
+export interface RestorePreviewPage {
+  pageId: string;
+  title: string;
+}
+
+// What restoring a backup would change compared with the current notebook
+export interface RestorePreview {
+  backup: BackupInfo;
+  restoresAsCopy: boolean; // notebook still exists, restore imports a copy
+  pagesOnlyInBackup: RestorePreviewPage[];
+  pagesOnlyInCurrent: RestorePreviewPage[];
+  pagesChanged: RestorePreviewPage[];
+  pagesUnchanged: number;
+  assetsOnlyInBackup: number;
+  assetsOnlyInCurrent: number;
+  assetsChanged: number;
+}
+
+export async function previewBackupRestore(
+  backupPath: string,
+  secret: BackupSecretOptions = {}
+): Promise<RestorePreview> {
+  return invoke<RestorePreview>("preview_backup_restore", {
+    backupPath,
+    password: secret.password,
+    keyfilePath: secret.keyfilePath,
+  });
+}
+
 export async function createNotebookBackup(
   notebookId: string
 ): Promise<BackupInfo> {
@@ -1022,20 +1681,58 @@ export async function deleteBackup(backupPath: string): Promise<void> {
   return invoke("delete_backup", { backupPath });
 }
 
+// ===== Storage Integrity API =====
+
+export type StorageRepairAction =
+  | "journalReplayed"
+  | "journalRolledBack"
+  | "restoredFromBackup"
+  | "restoredFromSnapshot"
+  | "quarantined";
+
+export interface StorageRepair {
+  path: string;
+  notebookId: string | null;
+  pageId: string | null;
+  action: StorageRepairAction;
+  detail?: string;
+}
+
+export interface IntegrityReport {
+  checkedAt: string;
+  notebooksChecked: number;
+  pagesChecked: number;
+  repairs: StorageRepair[];
+  problems: { path: string; notebookId: string | null; problem: string }[];
+}
+
+export async function verifyStorageIntegrity(): Promise<IntegrityReport> {
+  return invoke<IntegrityReport>("verify_storage_integrity");
+}
+
 // ===== Backup Settings API =====
 
 export type BackupFrequency = "daily" | "weekly" | "monthly";
 
+// Grandfather-father-son retention
+export interface BackupRetention {
+  keepDaily: number;
+  keepWeekly: number;
+  keepMonthly: number;
+}
+
 export interface BackupSettings {
   enabled: boolean;
   frequency: BackupFrequency;
   time: string; // "HH:MM" format
   dayOfWeek?: number; // 0-6 for weekly (Sunday=0)
   dayOfMonth?: number; // 1-31 for monthly
-  maxBackupsPerNotebook: number;
+  maxBackupsPerNotebook: number; // used when retention is not set
+  retention?: BackupRetention | null;
   notebookIds: string[]; // empty = all notebooks
   lastBackup?: string; // ISO date string
   nextBackup?: string; // ISO date string
+  keyfilePath?: string | null; // encrypt scheduled backups with this keyfile
 }
 
 export async function getBackupSettings(): Promise<BackupSettings> {
@@ -1052,6 +1749,110 @@ export async function runScheduledBackup(): Promise<BackupInfo[]> {
   return invoke<BackupInfo[]>("run_scheduled_backup");
 }
 
+// Set or clear where the current library's auto-backups are copied.
+// `username`/`secret` are the WebDAV login or the S3 access key pair.
+export async function setLibraryBackupDestination(
+  destination: BackupDestination | null,
+  username?: string,
+  secret?: string
+): Promise<Library> {
+  return invoke<Library>("set_library_backup_destination", {
+    destination,
+    username,
+    secret,
+  });
+}
+
+// ===== Library Snapshots API =====
+
+// A point-in-time copy of the whole library folder
+export interface LibrarySnapshot {
+  id: string;
+  label: string;
+  createdAt: string; // ISO date string
+  fileCount: number;
+  totalBytes: number;
+  sharedBytes: number; // linked or cloned rather than copied
+}
+
+export async function createLibrarySnapshot(
+  label?: string
+): Promise<LibrarySnapshot> {
+  return invoke<LibrarySnapshot>("create_library_snapshot", { label });
+}
+
+export async function listSnapshots(): Promise<LibrarySnapshot[]> {
+  return invoke<LibrarySnapshot[]>("list_snapshots");
+}
+
+export async function deleteLibrarySnapshot(snapshotId: string): Promise<void> {
+  return invoke("delete_library_snapshot", { snapshotId });
+}
+
+// Returns the snapshot taken of the state that was replaced
+export async function rollbackToSnapshot(
+  snapshotId: string
+): Promise<LibrarySnapshot> {
+  return invoke<LibrarySnapshot>("rollback_to_snapshot", { snapshotId });
+}
+
+// ===== Folder Sync API =====
+
+export interface FolderSyncStatus {
+  notebookId: string;
+  path: string;
+  enabled: boolean;
+  lastSync?: string; // ISO date string
+  trackedPages: number;
+}
+
+export interface FolderSyncConflict {
+  pageId: string;
+  title: string;
+  conflictFile: string;
+}
+
+export interface FolderSyncReport {
+  notebookId: string;
+  filesWritten: number;
+  pagesUpdated: string[];
+  pagesCreated: string[];
+  pagesTrashed: string[];
+  filesDeleted: number;
+  conflicts: FolderSyncConflict[];
+  errors: string[];
+}
+
+export async function getFolderSyncStatus(
+  notebookId: string
+): Promise<FolderSyncStatus | null> {
+  return daemonGet<FolderSyncStatus | null>(
+    `/api/notebooks/${notebookId}/folder-sync`
+  );
+}
+
+export async function enableFolderSync(
+  notebookId: string,
+  path: string
+): Promise<FolderSyncReport> {
+  return daemonPost<FolderSyncReport>(
+    `/api/notebooks/${notebookId}/folder-sync`,
+    { path }
+  );
+}
+
+export async function disableFolderSync(notebookId: string): Promise<void> {
+  await daemonDelete(`/api/notebooks/${notebookId}/folder-sync`);
+}
+
+export async function runFolderSync(
+  notebookId: string
+): Promise<FolderSyncReport> {
+  return daemonPost<FolderSyncReport>(
+    `/api/notebooks/${notebookId}/folder-sync/run`
+  );
+}
+
 // ===== Notion Import API =====
 
 export interface NotionPagePreview {
@@ -1261,6 +2062,102 @@ export async function importJoplin(
   return invoke<Notebook>("import_joplin_cmd", { path, notebookName });
 }
 
+// ===== Google Keep Import API =====
+
+export type KeepDuplicateStrategy = "skip" | "rename" | "replace";
+
+export interface KeepNotePreview {
+  title: string;
+  labels: string[];
+  isChecklist: boolean;
+  color: string | null;
+  attachmentCount: number;
+}
+
+export interface KeepImportPreview {
+  noteCount: number;
+  checklistCount: number;
+  archivedCount: number;
+  trashedCount: number;
+  attachmentCount: number;
+  labels: string[];
+  duplicateTitles: string[];
+  notes: KeepNotePreview[];
+  suggestedName: string;
+  warnings: string[];
+}
+
+export interface KeepImportResult {
+  notebook: Notebook;
+  imported: number;
+  replaced: number;
+  skipped: number;
+  warnings: string[];
+}
+
+/** Preview a Google Takeout zip or extracted Keep folder */
+export async function previewKeepImport(
+  path: string,
+  notebookId?: string
+): Promise<KeepImportPreview> {
+  return invoke<KeepImportPreview>("preview_keep_import_cmd", {
+    path,
+    notebookId,
+  });
+}
+
+/** Import Keep notes into `notebookId`, or a new notebook when omitted */
+export async function importKeep(
+  path: string,
+  options: {
+    notebookId?: string;
+    notebookName?: string;
+    duplicateStrategy?: KeepDuplicateStrategy;
+  } = {}
+): Promise<KeepImportResult> {
+  return invoke<KeepImportResult>("import_keep_cmd", { path, ...options });
+}
+
+// ===== Roam / Logseq Import API =====
+
+export type RoamImportSource =
+  | "roamJson"
+  | "logseqJson"
+  | "logseqEdn"
+  | "logseqGraph";
+
+export interface RoamPagePreview {
+  title: string;
+  blockCount: number;
+  dailyNoteDate: string | null;
+}
+
+export interface RoamImportPreview {
+  source: RoamImportSource;
+  pageCount: number;
+  dailyNoteCount: number;
+  blockCount: number;
+  blockRefCount: number;
+  assetCount: number;
+  pages: RoamPagePreview[];
+  suggestedName: string;
+  warnings: string[];
+}
+
+/** Preview a Roam JSON export, Logseq JSON/EDN export or Logseq graph folder */
+export async function previewRoamImport(
+  path: string
+): Promise<RoamImportPreview> {
+  return invoke<RoamImportPreview>("preview_roam_import_cmd", { path });
+}
+
+export async function importRoam(
+  path: string,
+  notebookName?: string
+): Promise<Notebook> {
+  return invoke<Notebook>("import_roam_cmd", { path, notebookName });
+}
+
 // ===== OneNote Import API =====
 
 export interface OneNoteSectionPreview {
@@ -1438,6 +2335,7 @@ import type {
   ExternalSource,
   ExternalFileFormat,
   ResolvedFileInfo,
+  WatchConfig,
 } from "../types/externalSource";
 
 export async function listExternalSources(): Promise<ExternalSource[]> {
@@ -1479,6 +2377,17 @@ export async function updateExternalSource(
   });
 }
 
+/** Enable watch-folder auto-import for a source, or disable it with null */
+export async function setExternalSourceWatch(
+  sourceId: string,
+  watch: WatchConfig | null
+): Promise<ExternalSource> {
+  return invoke<ExternalSource>("set_external_source_watch", {
+    sourceId,
+    watch,
+  });
+}
+
 export async function deleteExternalSource(sourceId: string): Promise<void> {
   return invoke<void>("delete_external_source", { sourceId });
 }
@@ -1532,115 +2441,420 @@ export async function inboxCapture(
   });
 }
 
-export async function inboxList(): Promise<InboxItem[]> {
-  return daemonGet<InboxItem[]>("/api/inbox");
+// Quick capture window: writes straight to the inbox from the Tauri side so
+// it works without the daemon and before the main window has loaded.
+
+export interface QuickCaptureSettings {
+  enabled: boolean;
+  shortcut: string;
 }
 
-export async function inboxListUnprocessed(): Promise<InboxItem[]> {
-  if (!isTauri()) {
-    const items = await daemonGet<InboxItem[]>("/api/inbox");
-    return items.filter((i) => !i.is_processed);
-  }
-  return invoke<InboxItem[]>("inbox_list_unprocessed");
+export async function captureQuickNote(
+  title: string,
+  content: string,
+  tags?: string[]
+): Promise<InboxItem> {
+  return invoke<InboxItem>("capture_quick_note", { title, content, tags });
 }
 
-export async function inboxSummary(): Promise<InboxSummary> {
-  if (!isTauri()) {
-    // No summary endpoint on the daemon — derive from the list, using the
-    // field names the UI consumes. (The Tauri command's wire shape uses
-    // total_items/processed_count and never had unprocessed_count — see
-    // Agent Feedback: InboxSummary field mismatch.)
-    const items = await daemonGet<InboxItem[]>("/api/inbox");
-    const processed = items.filter((i) => i.is_processed).length;
-    const classified = items.filter(
-      (i) => !i.is_processed && i.classification != null
-    ).length;
-    return {
-      total_count: items.length,
-      unprocessed_count: items.length - processed,
-      classified_count: classified,
-      unclassified_count: items.length - processed - classified,
-    };
-  }
-  return invoke<InboxSummary>("inbox_summary");
+export async function hideQuickCapture(): Promise<void> {
+  return invoke("hide_quick_capture");
 }
 
-export async function inboxClassify(itemIds?: string[]): Promise<InboxItem[]> {
-  return invoke<InboxItem[]>("inbox_classify", { itemIds });
+export async function openMainWindow(): Promise<void> {
+  return invoke("open_main_window");
 }
 
-export async function inboxApplyActions(
-  request: ApplyActionsRequest
-): Promise<ApplyActionsResult> {
-  return invoke<ApplyActionsResult>("inbox_apply_actions", { request });
+export async function getQuickCaptureSettings(): Promise<QuickCaptureSettings> {
+  return invoke<QuickCaptureSettings>("get_quick_capture_settings");
 }
 
-export async function inboxDelete(itemId: string): Promise<void> {
-  await daemonDelete(`/api/inbox/${itemId}`);
+export async function updateQuickCaptureSettings(
+  settings: QuickCaptureSettings
+): Promise<QuickCaptureSettings> {
+  return invoke<QuickCaptureSettings>("update_quick_capture_settings", {
+    settings,
+  });
 }
 
-export async function inboxClearProcessed(): Promise<number> {
-  return invoke<number>("inbox_clear_processed");
+export type NotificationCategory =
+  | "flashcardsDue"
+  | "goalCheckIn"
+  | "actionCompleted"
+  | "syncError"
+  | "pageReminder"
+  | "snoozeEnded";
+
+export interface NotificationPreferences {
+  enabled: boolean;
+  flashcardsDue: boolean;
+  flashcardReminderTime: string;
+  goalCheckIns: boolean;
+  actionCompletions: boolean;
+  syncErrors: boolean;
+  pageReminders: boolean;
+  snoozedItems: boolean;
 }
 
-// ========== Chat Session Operations ==========
+export interface PendingNotification {
+  id: string;
+  category: NotificationCategory;
+  title: string;
+  body: string;
+  dueAt: string;
+}
 
-export async function chatSessionCreate(
-  title?: string
-): Promise<import("../types/chatSession").ChatSession> {
-  return invoke("chat_session_create", { title });
+export interface PageReminder {
+  id: string;
+  notebookId: string;
+  pageId: string;
+  pageTitle: string;
+  remindAt: string;
+  note?: string | null;
+  createdAt: string;
+  firedAt?: string | null;
+  resurface?: boolean;
 }
 
-export async function chatSessionSave(
-  session: import("../types/chatSession").ChatSession
-): Promise<void> {
-  return invoke("chat_session_save", { session });
+/** Resolve "next tuesday 9am" etc. to a timestamp, for previewing */
+export async function parseReminderTime(when: string): Promise<string> {
+  return invoke<string>("parse_reminder_time", { when });
 }
 
-export async function chatSessionGet(
-  id: string
-): Promise<import("../types/chatSession").ChatSession> {
-  return invoke("chat_session_get", { id });
+export async function setPageReminder(
+  notebookId: string,
+  pageId: string,
+  when: string,
+  note?: string
+): Promise<PageReminder> {
+  return invoke<PageReminder>("set_page_reminder", {
+    notebookId,
+    pageId,
+    when,
+    note,
+  });
 }
 
-export async function chatSessionList(): Promise<
-  import("../types/chatSession").ChatSessionSummary[]
-> {
-  return invoke("chat_session_list");
+/** Resurface a page with a notification later ("in 3 months") */
+export async function snoozePage(
+  notebookId: string,
+  pageId: string,
+  until: string
+): Promise<PageReminder> {
+  return invoke<PageReminder>("snooze_page", { notebookId, pageId, until });
 }
 
-export async function chatSessionDelete(id: string): Promise<void> {
-  return invoke("chat_session_delete", { id });
+export async function listPageReminders(
+  pageId?: string,
+  includeFired?: boolean
+): Promise<PageReminder[]> {
+  return invoke<PageReminder[]>("list_page_reminders", {
+    pageId,
+    includeFired,
+  });
 }
 
-export async function chatSessionUpdateTitle(
-  id: string,
-  title: string
-): Promise<void> {
-  return invoke("chat_session_update_title", { id, title });
+export async function deletePageReminder(id: string): Promise<void> {
+  return invoke("delete_page_reminder", { id });
 }
 
-export interface MigratedSession {
-  oldId: string;
-  newPageId: string;
+/** Reminders on a day ("YYYY-MM-DD"), for the daily note agenda */
+export async function getDailyAgenda(date: string): Promise<PageReminder[]> {
+  return invoke<PageReminder[]>("get_daily_agenda", { date });
+}
+
+export interface ReviewItem {
+  notebookId: string;
+  pageId: string;
   title: string;
+  updatedAt: string;
+  lastViewedAt?: string | null;
+  inboundLinks: number;
+  reason: string;
 }
 
-export async function migrateChatSessionsToPages(
+/** Remember that a page was opened (desktop only; used by the review feed) */
+export async function recordPageView(
   notebookId: string,
-  folderId: string
-): Promise<MigratedSession[]> {
-  return invoke<MigratedSession[]>("migrate_chat_sessions_to_pages", {
-    notebookId,
-    folderId,
-  });
+  pageId: string
+): Promise<void> {
+  if (!isTauri()) return;
+  await invoke("record_page_view", { notebookId, pageId });
 }
 
-// ========== Git Operations ==========
+/** Today's rotating selection of old notes to revisit */
+export async function getReviewFeed(limit?: number): Promise<ReviewItem[]> {
+  return invoke<ReviewItem[]>("get_review_feed", { limit });
+}
 
-export interface GitStatus {
-  is_repo: boolean;
-  is_dirty: boolean;
+export interface SearchHistoryEntry {
+  query: string;
+  lastSearchedAt: string;
+  searchCount: number;
+}
+
+export interface SearchHistorySettings {
+  enabled: boolean;
+  maxEntries: number;
+  /** Forget searches older than this many days; null keeps them */
+  retentionDays: number | null;
+}
+
+export interface SearchSuggestion {
+  text: string;
+  kind: "history" | "page" | "tag";
+  notebookId?: string;
+  pageId?: string;
+}
+
+/** Remember a search (desktop only; a no-op while history is off) */
+export async function recordSearch(
+  query: string
+): Promise<SearchHistoryEntry | null> {
+  if (!isTauri()) return null;
+  return invoke<SearchHistoryEntry | null>("record_search", { query });
+}
+
+/** Past searches, most recent first */
+export async function getSearchHistory(
+  limit?: number
+): Promise<SearchHistoryEntry[]> {
+  return invoke<SearchHistoryEntry[]>("get_search_history", { limit });
+}
+
+export async function removeSearchHistoryEntry(query: string): Promise<boolean> {
+  return invoke<boolean>("remove_search_history_entry", { query });
+}
+
+/** Forget all past searches; returns how many were removed */
+export async function clearSearchHistory(): Promise<number> {
+  return invoke<number>("clear_search_history");
+}
+
+export async function getSearchHistorySettings(): Promise<SearchHistorySettings> {
+  return invoke<SearchHistorySettings>("get_search_history_settings");
+}
+
+/** Change what the search history keeps; turning it off clears it */
+export async function setSearchHistorySettings(
+  settings: SearchHistorySettings
+): Promise<void> {
+  return invoke("set_search_history_settings", { settings });
+}
+
+/** Completions of a partly typed query from history, page titles and tags */
+export async function getSearchSuggestions(
+  prefix: string,
+  limit?: number
+): Promise<SearchSuggestion[]> {
+  return invoke<SearchSuggestion[]>("get_search_suggestions", { prefix, limit });
+}
+
+export type ReviewDestination =
+  | { type: "flashcards"; deckName: string }
+  | { type: "dailyNote" };
+
+export interface NotebookReviewSettings {
+  enabled: boolean;
+  destination: ReviewDestination;
+  questionsPerPage: number;
+  pagesPerRun: number;
+}
+
+/** A notebook's spaced-writing review settings */
+export async function getSpacedReviewSettings(
+  notebookId: string
+): Promise<NotebookReviewSettings> {
+  return invoke<NotebookReviewSettings>("get_spaced_review_settings", {
+    notebookId,
+  });
+}
+
+/** Save a notebook's spaced-writing review settings */
+export async function updateSpacedReviewSettings(
+  notebookId: string,
+  settings: NotebookReviewSettings
+): Promise<NotebookReviewSettings> {
+  return invoke<NotebookReviewSettings>("update_spaced_review_settings", {
+    notebookId,
+    settings,
+  });
+}
+
+export async function listPendingNotifications(): Promise<
+  PendingNotification[]
+> {
+  return invoke<PendingNotification[]>("list_pending_notifications");
+}
+
+export async function getNotificationPreferences(): Promise<NotificationPreferences> {
+  return invoke<NotificationPreferences>("get_notification_preferences");
+}
+
+export async function updateNotificationPreferences(
+  preferences: NotificationPreferences
+): Promise<NotificationPreferences> {
+  return invoke<NotificationPreferences>("update_notification_preferences", {
+    preferences,
+  });
+}
+
+export interface OsSearchSettings {
+  enabled: boolean;
+  directory?: string | null;
+}
+
+export interface OsSearchExportResult {
+  directory: string;
+  written: number;
+  removed: number;
+  unchanged: number;
+}
+
+export async function getOsSearchSettings(): Promise<OsSearchSettings> {
+  return invoke<OsSearchSettings>("get_os_search_settings");
+}
+
+export async function updateOsSearchSettings(
+  settings: OsSearchSettings
+): Promise<OsSearchSettings> {
+  return invoke<OsSearchSettings>("update_os_search_settings", { settings });
+}
+
+export async function exportOsSearchIndex(): Promise<OsSearchExportResult> {
+  return invoke<OsSearchExportResult>("export_os_search_index");
+}
+
+export async function inboxList(): Promise<InboxItem[]> {
+  return daemonGet<InboxItem[]>("/api/inbox");
+}
+
+export async function inboxListUnprocessed(): Promise<InboxItem[]> {
+  if (!isTauri()) {
+    const items = await daemonGet<InboxItem[]>("/api/inbox");
+    const now = Date.now();
+    return items.filter(
+      (i) =>
+        !i.is_processed &&
+        !(i.snoozed_until && Date.parse(i.snoozed_until) > now)
+    );
+  }
+  return invoke<InboxItem[]>("inbox_list_unprocessed");
+}
+
+export async function inboxListSnoozed(): Promise<InboxItem[]> {
+  return invoke<InboxItem[]>("inbox_list_snoozed");
+}
+
+/** Hide an inbox item until a natural-language time ("monday 9am") */
+export async function snoozeInboxItem(
+  id: string,
+  until: string
+): Promise<InboxItem> {
+  return invoke<InboxItem>("snooze_inbox_item", { id, until });
+}
+
+export async function unsnoozeInboxItem(id: string): Promise<InboxItem> {
+  return invoke<InboxItem>("unsnooze_inbox_item", { id });
+}
+
+export async function inboxSummary(): Promise<InboxSummary> {
+  if (!isTauri()) {
+    // No summary endpoint on the daemon — derive from the list, using the
+    // field names the UI consumes. (The Tauri command's wire shape uses
+    // total_items/processed_count and never had unprocessed_count — see
+    // Agent Feedback: InboxSummary field mismatch.)
+    const items = await daemonGet<InboxItem[]>("/api/inbox");
+    const processed = items.filter((i) => i.is_processed).length;
+    const classified = items.filter(
+      (i) => !i.is_processed && i.classification != null
+    ).length;
+    return {
+      total_count: items.length,
+      unprocessed_count: items.length - processed,
+      classified_count: classified,
+      unclassified_count: items.length - processed - classified,
+    };
+  }
+  return invoke<InboxSummary>("inbox_summary");
+}
+
+export async function inboxClassify(itemIds?: string[]): Promise<InboxItem[]> {
+  return invoke<InboxItem[]>("inbox_classify", { itemIds });
+}
+
+export async function inboxApplyActions(
+  request: ApplyActionsRequest
+): Promise<ApplyActionsResult> {
+  return invoke<ApplyActionsResult>("inbox_apply_actions", { request });
+}
+
+export async function inboxDelete(itemId: string): Promise<void> {
+  await daemonDelete(`/api/inbox/${itemId}`);
+}
+
+export async function inboxClearProcessed(): Promise<number> {
+  return invoke<number>("inbox_clear_processed");
+}
+
+// ========== Chat Session Operations ==========
+
+export async function chatSessionCreate(
+  title?: string
+): Promise<import("../types/chatSession").ChatSession> {
+  return invoke("chat_session_create", { title });
+}
+
+export async function chatSessionSave(
+  session: import("../types/chatSession").ChatSession
+): Promise<void> {
+  return invoke("chat_session_save", { session });
+}
+
+export async function chatSessionGet(
+  id: string
+): Promise<import("../types/chatSession").ChatSession> {
+  return invoke("chat_session_get", { id });
+}
+
+export async function chatSessionList(): Promise<
+  import("../types/chatSession").ChatSessionSummary[]
+> {
+  return invoke("chat_session_list");
+}
+
+export async function chatSessionDelete(id: string): Promise<void> {
+  return invoke("chat_session_delete", { id });
+}
+
+export async function chatSessionUpdateTitle(
+  id: string,
+  title: string
+): Promise<void> {
+  return invoke("chat_session_update_title", { id, title });
+}
+
+export interface MigratedSession {
+  oldId: string;
+  newPageId: string;
+  title: string;
+}
+
+export async function migrateChatSessionsToPages(
+  notebookId: string,
+  folderId: string
+): Promise<MigratedSession[]> {
+  return invoke<MigratedSession[]>("migrate_chat_sessions_to_pages", {
+    notebookId,
+    folderId,
+  });
+}
+
+// ========== Git Operations ==========
+
+export interface GitStatus {
+  is_repo: boolean;
+  is_dirty: boolean;
   branch: string | null;
   ahead: number;
   behind: number;
@@ -1886,6 +3100,18 @@ export async function getExternalEditors(): Promise<EditorConfig[]> {
   return invoke<EditorConfig[]>("get_external_editors");
 }
 
+/**
+ * Editors the user added on this device. `args` may use `{file}` and
+ * `{line}` placeholders; without `{file}` the path goes last.
+ */
+export async function getCustomEditors(): Promise<EditorConfig[]> {
+  return invoke<EditorConfig[]>("get_custom_editors");
+}
+
+export async function saveCustomEditors(editors: EditorConfig[]): Promise<void> {
+  return invoke("save_custom_editors", { editors });
+}
+
 export async function openPageInEditor(
   notebookId: string,
   pageId: string,
@@ -1933,6 +3159,70 @@ export async function cleanupExternalEditSessions(): Promise<void> {
   return invoke("cleanup_external_edit_sessions");
 }
 
+export interface NotebookEditConflict {
+  path: string;
+  pageId: string;
+  title: string;
+}
+
+export interface NotebookEditSession {
+  notebookId: string;
+  dir: string;
+  startedAt: string;
+  files: Record<string, { pageId: string; fileHash: string; pageHash: string }>;
+  conflicts: NotebookEditConflict[];
+}
+
+/** Emitted as `external-notebook-sync` while a notebook is open in an editor */
+export interface NotebookSyncReport {
+  notebookId: string;
+  pagesUpdated: string[];
+  conflicts: NotebookEditConflict[];
+  errors: string[];
+}
+
+export async function openNotebookInEditor(
+  notebookId: string,
+  editorConfig?: EditorConfig
+): Promise<NotebookEditSession> {
+  return invoke<NotebookEditSession>("open_notebook_in_editor", {
+    notebookId,
+    editorConfig,
+  });
+}
+
+export async function syncNotebookFromExternalEditor(
+  notebookId: string
+): Promise<NotebookSyncReport> {
+  return invoke<NotebookSyncReport>("sync_notebook_from_external_editor", {
+    notebookId,
+  });
+}
+
+export async function resolveExternalEditConflict(
+  notebookId: string,
+  path: string,
+  resolution: "keepFile" | "keepPage"
+): Promise<NotebookEditSession> {
+  return invoke<NotebookEditSession>("resolve_external_edit_conflict", {
+    notebookId,
+    path,
+    resolution,
+  });
+}
+
+export async function getNotebookEditSession(
+  notebookId: string
+): Promise<NotebookEditSession | null> {
+  return invoke<NotebookEditSession | null>("get_notebook_edit_session", {
+    notebookId,
+  });
+}
+
+export async function endNotebookEditSession(notebookId: string): Promise<void> {
+  return invoke("end_notebook_edit_session", { notebookId });
+}
+
 // ========== Section Operations ==========
 
 export async function listSections(notebookId: string): Promise<Section[]> {
@@ -2077,10 +3367,63 @@ export async function syncQueueStatus(
   return invoke<QueueItem[]>("sync_queue_status", { notebookId });
 }
 
+export async function syncVerify(
+  notebookId: string
+): Promise<SyncVerifyReport> {
+  return invoke<SyncVerifyReport>("sync_verify", { notebookId });
+}
+
+export async function syncRepair(
+  notebookId: string,
+  repairs: SyncRepair[]
+): Promise<SyncResult> {
+  return invoke<SyncResult>("sync_repair", { notebookId, repairs });
+}
+
+export async function syncSetAssetPolicy(
+  notebookId: string,
+  policy: AssetSyncPolicy
+): Promise<void> {
+  return invoke("sync_set_asset_policy", { notebookId, policy });
+}
+
+/** Download an asset the notebook's asset policy skipped */
+export async function ensureAssetLocal(
+  notebookId: string,
+  assetPath: string
+): Promise<EnsuredAsset> {
+  return invoke<EnsuredAsset>("ensure_asset_local", {
+    notebookId,
+    assetPath,
+  });
+}
+
+export async function getSyncQueueDetails(): Promise<SyncQueueDetails> {
+  return invoke<SyncQueueDetails>("get_sync_queue_details");
+}
+
 export async function syncDisable(notebookId: string): Promise<void> {
   return invoke("sync_disable", { notebookId });
 }
 
+export type SyncActivity =
+  | { state: "idle" }
+  | { state: "syncing"; notebooks: number }
+  | { state: "error"; message: string }
+  | { state: "paused" };
+
+export async function syncGetActivity(): Promise<SyncActivity> {
+  return invoke<SyncActivity>("sync_get_activity");
+}
+
+export async function syncSetPaused(paused: boolean): Promise<SyncActivity> {
+  return invoke<SyncActivity>("sync_set_paused", { paused });
+}
+
+export async function refreshTrayMenu(): Promise<void> {
+  return invoke("refresh_tray_menu");
+}
+
 // ===== Library Sync API =====
 
 export async function librarySyncConfigure(
@@ -2098,6 +3441,24 @@ export async function librarySyncNow(libraryId: string): Promise<SyncResult> {
   return invoke<SyncResult>("library_sync_now", { libraryId });
 }
 
+// ===== Scratchpad =====
+
+export interface ScratchpadContent {
+  text: string;
+  /** Last edit on any device */
+  updatedAt: string | null;
+  /** Local edits not yet pushed to the sync server */
+  pendingPush: boolean;
+}
+
+export async function getScratchpad(libraryId: string): Promise<ScratchpadContent> {
+  return invoke<ScratchpadContent>("get_scratchpad", { libraryId });
+}
+
+export async function updateScratchpad(libraryId: string, text: string): Promise<ScratchpadContent> {
+  return invoke<ScratchpadContent>("update_scratchpad", { libraryId, text });
+}
+
 export async function librarySyncConfigureNotebook(
   libraryId: string,
   notebookId: string
@@ -2125,6 +3486,188 @@ export async function librarySyncUpdateConfig(
   });
 }
 
+export async function librarySyncUpdateBandwidth(
+  libraryId: string,
+  bandwidth: SyncBandwidth
+): Promise<void> {
+  return invoke("library_sync_update_bandwidth", { libraryId, bandwidth });
+}
+
+export async function librarySyncSetCompression(
+  libraryId: string,
+  compression: SyncCompression
+): Promise<void> {
+  return invoke("library_sync_set_compression", { libraryId, compression });
+}
+
+export async function librarySyncSetDeltaSync(
+  libraryId: string,
+  enabled: boolean
+): Promise<void> {
+  return invoke("library_sync_set_delta_sync", { libraryId, enabled });
+}
+
+export async function browseRemoteLibrary(
+  libraryId: string
+): Promise<RemoteNotebook[]> {
+  return invoke<RemoteNotebook[]>("browse_remote_library", { libraryId });
+}
+
+export async function browseRemoteNotebook(
+  libraryId: string,
+  notebookId: string
+): Promise<RemoteNotebookListing> {
+  return invoke<RemoteNotebookListing>("browse_remote_notebook", {
+    libraryId,
+    notebookId,
+  });
+}
+
+export async function fetchRemotePage(
+  libraryId: string,
+  notebookId: string,
+  pageId: string
+): Promise<RemotePage> {
+  return invoke<RemotePage>("fetch_remote_page", {
+    libraryId,
+    notebookId,
+    pageId,
+  });
+}
+
+// ===== Page Locks =====
+
+/** An advisory claim on a page by one synced device */
+export interface PageLockInfo {
+  pageId: string;
+  deviceName: string;
+  acquiredAt: string;
+  expiresAt: string;
+  ageSecs: number;
+  /** Held by this device */
+  mine: boolean;
+}
+
+export async function lockPageForEditing(
+  notebookId: string,
+  pageId: string
+): Promise<PageLockInfo> {
+  return invoke<PageLockInfo>("lock_page_for_editing", { notebookId, pageId });
+}
+
+export async function releasePageLock(
+  notebookId: string,
+  pageId: string
+): Promise<void> {
+  return invoke("release_page_lock", { notebookId, pageId });
+}
+
+export async function getPageLocks(notebookId: string): Promise<PageLockInfo[]> {
+  return invoke<PageLockInfo[]>("get_page_locks", { notebookId });
+}
+
+// ===== Sync Devices =====
+
+/** A device in a notebook's sync registry */
+export interface SyncDeviceInfo {
+  clientId: string;
+  name: string;
+  platform: string;
+  firstSeen: string;
+  lastSync: string;
+  revokedAt?: string;
+  /** The device the app is running on */
+  thisDevice: boolean;
+}
+
+export async function listSyncDevices(notebookId: string): Promise<SyncDeviceInfo[]> {
+  return invoke<SyncDeviceInfo[]>("list_sync_devices", { notebookId });
+}
+
+export async function revokeSyncDevice(
+  notebookId: string,
+  clientId: string
+): Promise<void> {
+  return invoke("revoke_sync_device", { notebookId, clientId });
+}
+
+/** A version of a page kept on the sync server */
+export interface RemotePageVersion {
+  /** File name; also its id for fetch/restore */
+  name: string;
+  createdAt: string;
+  clientId: string;
+  /** Name of the device that uploaded it */
+  device?: string;
+  size?: number;
+}
+
+/** List a page's versions on the sync server, newest first */
+export async function listRemotePageVersions(
+  notebookId: string,
+  pageId: string
+): Promise<RemotePageVersion[]> {
+  return invoke<RemotePageVersion[]>("list_remote_page_versions", { notebookId, pageId });
+}
+
+export async function getRemotePageVersion(
+  notebookId: string,
+  pageId: string,
+  version: string
+): Promise<EditorData> {
+  return invoke<EditorData>("get_remote_page_version", { notebookId, pageId, version });
+}
+
+/** Restore a server version as the page's current content */
+export async function restoreRemotePageVersion(
+  notebookId: string,
+  pageId: string,
+  version: string
+): Promise<Page> {
+  return invoke<Page>("restore_remote_page_version", { notebookId, pageId, version });
+}
+
+// ===== Activity Feed API =====
+
+export type ActivityKind = "created" | "edited" | "deleted" | "imported";
+
+export interface ActivityEntry {
+  timestamp: string;
+  notebookId: string;
+  pageId: string;
+  pageTitle: string;
+  kind: ActivityKind;
+  /** "local" for this device, "sync" for changes pulled from another */
+  source: "local" | "sync";
+  /** Name of the device the change was made on */
+  device: string;
+  clientId?: string;
+}
+
+export interface ActivityFilter {
+  notebookId?: string;
+  kinds?: ActivityKind[];
+  source?: "local" | "sync";
+  device?: string;
+  limit?: number;
+}
+
+export async function getActivityFeed(
+  since?: string,
+  filter?: ActivityFilter
+): Promise<ActivityEntry[]> {
+  return invoke<ActivityEntry[]>("get_activity_feed", { since, filter });
+}
+
+export async function getActivityLastSeen(): Promise<string | null> {
+  return invoke<string | null>("get_activity_last_seen");
+}
+
+/** Mark the feed seen now; returns when it was last seen before */
+export async function markActivitySeen(): Promise<string | null> {
+  return invoke<string | null>("mark_activity_seen");
+}
+
 // ===== Document Conversion API (markitdown) =====
 
 export interface DocumentConversionResult {
@@ -2175,7 +3718,13 @@ export async function isSupportedDocument(filePath: string): Promise<boolean> {
 
 // ===== Library API =====
 
-import type { Library, LibraryStats } from "../types/library";
+import type {
+  BackupDestination,
+  Library,
+  LibraryStats,
+  MarkdownMirrorConfig,
+  MirrorReport,
+} from "../types/library";
 
 /**
  * List all libraries
@@ -2214,6 +3763,65 @@ export async function getCurrentLibrary(): Promise<Library> {
   return invoke<Library>("get_current_library");
 }
 
+// ===== Deep Links (nous://) =====
+
+export type DeepLink =
+  | {
+      action: "page";
+      pageId: string;
+      notebookId: string | null;
+      libraryId: string | null;
+    }
+  | { action: "search"; query: string; notebookId: string | null }
+  | {
+      action: "capture";
+      title: string | null;
+      text: string;
+      url: string | null;
+      tags: string[];
+    };
+
+export interface ResolvedPageLink {
+  libraryId: string;
+  libraryName: string;
+  notebookId: string;
+  pageId: string;
+  isCurrentLibrary: boolean;
+}
+
+/**
+ * Drain nous:// links received since the last call
+ */
+export async function takePendingDeepLinks(): Promise<DeepLink[]> {
+  return invoke<DeepLink[]>("take_pending_deep_links");
+}
+
+/**
+ * Re-queue a link so it's handled after a library switch reloads the window
+ */
+export async function queueDeepLink(url: string): Promise<void> {
+  return invoke<void>("queue_deep_link", { url });
+}
+
+/**
+ * Shareable nous:// link for a page
+ */
+export async function getPageDeepLink(
+  notebookId: string,
+  pageId: string
+): Promise<string> {
+  return invoke<string>("get_page_deep_link", { notebookId, pageId });
+}
+
+/**
+ * Find the library and notebook holding the page behind a nous://page link
+ */
+export async function resolvePageDeepLink(
+  url: string
+): Promise<ResolvedPageLink> {
+  return invoke<ResolvedPageLink>("resolve_page_deep_link", { url });
+}
+
 /**
  * Create a new library
  */
@@ -2273,18 +3881,236 @@ export async function pickLibraryFolder(): Promise<string | null> {
 }
 
 /**
- * Move a notebook from one library to another
+ * Move a notebook from one library to another
+ */
+export async function moveNotebookToLibrary(
+  notebookId: string,
+  sourceLibraryId: string,
+  targetLibraryId: string
+): Promise<string> {
+  return invoke<string>("move_notebook_to_library", {
+    notebookId,
+    sourceLibraryId,
+    targetLibraryId,
+  });
+}
+
+/**
+ * Set or clear a library's scheduled Markdown mirror
+ */
+export async function updateLibraryMarkdownMirror(
+  libraryId: string,
+  config: MarkdownMirrorConfig | null
+): Promise<Library> {
+  return invoke<Library>("update_library_markdown_mirror", {
+    libraryId,
+    config,
+  });
+}
+
+/**
+ * Bring a library's Markdown mirror up to date now
+ */
+export async function runLibraryMarkdownMirror(
+  libraryId: string
+): Promise<MirrorReport> {
+  return invoke<MirrorReport>("run_library_markdown_mirror", { libraryId });
+}
+
+/**
+ * Settings stored with the current library (synced by library sync)
+ */
+export async function getLibrarySettings(): Promise<Record<string, unknown>> {
+  return invoke<Record<string, unknown>>("get_library_settings");
+}
+
+export async function setLibrarySetting(
+  key: string,
+  value: unknown
+): Promise<void> {
+  return invoke("set_library_setting", { key, value });
+}
+
+/**
+ * Named AI provider profile, shared by the library's notebooks and action
+ * steps. Holds no API key; apiKeyRef names the provider whose key to use.
+ */
+export interface AiProfile {
+  id: string;
+  name: string;
+  providerType: string;
+  model?: string;
+  baseUrl?: string;
+  temperature?: number;
+  maxTokens?: number;
+  apiKeyRef?: string;
+  systemPrompt?: string;
+}
+
+export async function listAiProfiles(): Promise<AiProfile[]> {
+  return invoke<AiProfile[]>("list_ai_profiles");
+}
+
+/**
+ * Create (empty id) or update an AI profile
+ */
+export async function saveAiProfile(profile: AiProfile): Promise<AiProfile> {
+  return invoke<AiProfile>("save_ai_profile", { profile });
+}
+
+export async function deleteAiProfile(profileId: string): Promise<boolean> {
+  return invoke<boolean>("delete_ai_profile", { profileId });
+}
+
+// ===== Ollama API =====
+
+export interface OllamaModel {
+  name: string;
+  /** Size on disk in bytes */
+  size: number;
+  digest: string;
+  modifiedAt?: string;
+  family?: string;
+  parameterSize?: string;
+  quantizationLevel?: string;
+}
+
+/** Payload of `ollama-pull-progress` events */
+export interface OllamaPullProgress {
+  model: string;
+  status: string;
+  digest?: string;
+  total?: number;
+  completed?: number;
+  percent?: number;
+  done: boolean;
+}
+
+export interface OllamaHealth {
+  baseUrl: string;
+  reachable: boolean;
+  version?: string;
+  latencyMs?: number;
+  error?: string;
+}
+
+export async function ollamaListModels(baseUrl?: string): Promise<OllamaModel[]> {
+  return invoke<OllamaModel[]>("ollama_list_models", { baseUrl });
+}
+
+/** Pull a model; progress arrives as `ollama-pull-progress` events */
+export async function ollamaPullModel(model: string, baseUrl?: string): Promise<void> {
+  return invoke("ollama_pull_model", { model, baseUrl });
+}
+
+export async function ollamaDeleteModel(model: string, baseUrl?: string): Promise<void> {
+  return invoke("ollama_delete_model", { model, baseUrl });
+}
+
+export async function ollamaHealth(baseUrl?: string): Promise<OllamaHealth> {
+  return invoke<OllamaHealth>("ollama_health", { baseUrl });
+}
+
+// ===== Secrets API =====
+
+/**
+ * Secret ids: "ai.<provider>", "tts.<provider>", "search.tavily",
+ * "sync.notebook.<id>", "sync.library.<id>"
+ */
+export const secretIds = {
+  ai: (provider: string) => `ai.${provider}`,
+  tts: (provider: string) => `tts.${provider}`,
+  tavily: "search.tavily",
+};
+
+export interface SecretInfo {
+  id: string;
+  hint: string;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface SecretsStatus {
+  protection: "keyring" | "passphrase";
+  unlocked: boolean;
+  count: number;
+}
+
+export async function getSecretsStatus(): Promise<SecretsStatus> {
+  return invoke<SecretsStatus>("get_secrets_status");
+}
+
+export async function listSecrets(): Promise<SecretInfo[]> {
+  return invoke<SecretInfo[]>("list_secrets");
+}
+
+export async function setSecret(id: string, value: string): Promise<SecretInfo> {
+  return invoke<SecretInfo>("set_secret", { id, value });
+}
+
+export async function rotateSecret(id: string, value: string): Promise<SecretInfo> {
+  return invoke<SecretInfo>("rotate_secret", { id, value });
+}
+
+export async function deleteSecret(id: string): Promise<boolean> {
+  return invoke<boolean>("delete_secret", { id });
+}
+
+export async function unlockSecrets(passphrase: string): Promise<void> {
+  return invoke("unlock_secrets", { passphrase });
+}
+
+/**
+ * Protect secrets with a passphrase, or with the OS keyring when null
+ */
+export async function setSecretsProtection(
+  passphrase: string | null
+): Promise<SecretsStatus> {
+  return invoke<SecretsStatus>("set_secrets_protection", { passphrase });
+}
+
+/**
+ * Hand plaintext keys to the secrets store. Returns the ids it now holds.
+ */
+export async function importPlaintextSecrets(
+  keys: Record<string, string>
+): Promise<string[]> {
+  return invoke<string[]>("import_plaintext_secrets", { keys });
+}
+
+// ===== App Config Export API =====
+
+export interface AppConfigCounts {
+  settings: number;
+  actions: number;
+  externalSources: number;
+  notebookSync: number;
+  mcpServers: boolean;
+  templates: number;
+}
+
+export interface AppConfigImport {
+  imported: AppConfigCounts;
+  templates: unknown[];
+  /** Sync configs imported disabled until their password is entered */
+  needsCredentials: string[];
+  warnings: string[];
+}
+
+/**
+ * Write the library's configuration, actions, external sources, sync
+ * configs (without passwords), MCP servers and the given templates to a
+ * JSON bundle at `path`
  */
-export async function moveNotebookToLibrary(
-  notebookId: string,
-  sourceLibraryId: string,
-  targetLibraryId: string
-): Promise<string> {
-  return invoke<string>("move_notebook_to_library", {
-    notebookId,
-    sourceLibraryId,
-    targetLibraryId,
-  });
+export async function exportAppConfig(
+  path: string,
+  templates: unknown[]
+): Promise<AppConfigCounts> {
+  return invoke<AppConfigCounts>("export_app_config", { path, templates });
+}
+
+export async function importAppConfig(path: string): Promise<AppConfigImport> {
+  return invoke<AppConfigImport>("import_app_config", { path });
 }
 
 // ===== File-Based Page API =====
@@ -2606,6 +4432,50 @@ export async function deletePdfAnnotations(
   return invoke<void>("delete_pdf_annotations", { notebookId, pageId });
 }
 
+export interface AnnotatedPdfExport {
+  outputPath: string;
+  annotationCount: number;
+  skipped: number;
+}
+
+/**
+ * Write a copy of a PDF page's file with its highlights and notes embedded
+ * as real PDF annotations
+ */
+export async function exportAnnotatedPdf(
+  notebookId: string,
+  pageId: string,
+  outputPath: string
+): Promise<AnnotatedPdfExport> {
+  return invoke<AnnotatedPdfExport>("export_annotated_pdf", {
+    notebookId,
+    pageId,
+    outputPath,
+  });
+}
+
+export interface PdfAnnotationImport {
+  annotations: PDFPageAnnotations;
+  imported: number;
+  duplicates: number;
+}
+
+/**
+ * Import highlights and notes from an externally annotated PDF. Reads the
+ * page's own file unless sourcePath is given.
+ */
+export async function importPdfAnnotations(
+  notebookId: string,
+  pageId: string,
+  sourcePath?: string
+): Promise<PdfAnnotationImport> {
+  return invoke<PdfAnnotationImport>("import_pdf_annotations", {
+    notebookId,
+    pageId,
+    sourcePath,
+  });
+}
+
 // ===== Jupyter Cell Execution =====
 
 /**
@@ -2658,6 +4528,43 @@ export async function checkPythonExecutionAvailable(): Promise<PythonEnvironment
   return invoke<PythonEnvironmentInfo>("check_python_execution_available");
 }
 
+export type PackageState = "ok" | "missing" | "outdated";
+
+export interface PythonPackageStatus {
+  name: string;
+  requirement: string;
+  /** Optional dependency group; null for required packages */
+  group: string | null;
+  installed: string | null;
+  state: PackageState;
+}
+
+export interface PythonHealthReport {
+  healthy: boolean;
+  install: "bundled" | "venv" | "system";
+  mode: "inProcess" | "subprocess";
+  pythonVersion: string;
+  nousPyPath: string;
+  venvPath: string | null;
+  packages: PythonPackageStatus[];
+  problems: string[];
+}
+
+/**
+ * Check the Python interpreter and the packages the AI features need
+ */
+export async function checkPythonEnvironment(): Promise<PythonHealthReport> {
+  return invoke<PythonHealthReport>("check_python_environment");
+}
+
+/**
+ * Install missing packages into the development venv. Installer output is
+ * emitted line by line as `python-env-repair-output` ({ line }).
+ */
+export async function repairPythonEnvironment(): Promise<PythonHealthReport> {
+  return invoke<PythonHealthReport>("repair_python_environment");
+}
+
 // ===== Window API =====
 
 /**
@@ -3026,6 +4933,7 @@ import type {
   Timeline,
   ConceptGraph,
   RAGChunk,
+  RagScope,
   CitedResponse,
   StudyToolsGenerationOptions,
 } from "../types/studyTools";
@@ -3131,16 +5039,22 @@ export async function extractConcepts(
   });
 }
 
+/**
+ * Answer with citations. With a `scope`, sources are also retrieved from the
+ * libraries in it and citations carry the library they came from.
+ */
 export async function chatWithCitations(
   query: string,
   contextChunks: RAGChunk[],
   maxCitations?: number,
-  options?: StudyToolsGenerationOptions
+  options?: StudyToolsGenerationOptions,
+  scope?: RagScope
 ): Promise<CitedResponse> {
   return invoke<CitedResponse>("chat_with_citations", {
     query,
     contextChunks,
     maxCitations,
+    scope,
     providerType: options?.providerType,
     apiKey: options?.apiKey,
     model: options?.model,
@@ -3173,7 +5087,8 @@ export async function generateInfographic(
   template: string,
   data: Record<string, unknown>,
   config?: InfographicConfig,
-  exportPng?: boolean
+  exportPng?: boolean,
+  pageIds?: string[]
 ): Promise<InfographicResult> {
   return invoke<InfographicResult>("generate_infographic", {
     notebookId,
@@ -3181,6 +5096,7 @@ export async function generateInfographic(
     data,
     config,
     exportPng,
+    pageIds,
   });
 }
 
@@ -3228,13 +5144,15 @@ export async function generateStudyVideo(
   notebookId: string,
   slides: SlideContent[],
   ttsConfig: VideoTTSConfig,
-  videoConfig?: VideoConfig
+  videoConfig?: VideoConfig,
+  pageIds?: string[]
 ): Promise<VideoGenerationResult> {
   return invoke<VideoGenerationResult>("generate_study_video", {
     notebookId,
     slides,
     ttsConfig,
     videoConfig,
+    pageIds,
   });
 }
 
@@ -3255,6 +5173,8 @@ export interface MediaAssetInfo {
   mediaType: "video" | "infographic";
   sizeBytes: number;
   createdAt: string | null;
+  /** Generated media entry the file belongs to, if recorded */
+  mediaId: string | null;
 }
 
 export async function listNotebookMediaAssets(
@@ -3270,6 +5190,53 @@ export async function deleteNotebookMediaAsset(
   return invoke("delete_notebook_media_asset", { notebookId, assetPath });
 }
 
+/** One generation of an infographic or study video */
+export interface GeneratedMedia {
+  id: string;
+  notebookId: string;
+  /** Pages the content was generated from */
+  pageIds: string[];
+  kind: "infographic" | "study_video";
+  /** Template and data, or slides and voice, used to generate it */
+  options: Record<string, unknown>;
+  /** Output files, relative to the notebook's assets directory */
+  files: string[];
+  createdAt: string;
+  regeneratedFrom?: string;
+  /** Absolute paths of the outputs that still exist */
+  paths: string[];
+}
+
+export interface MediaCleanupReport {
+  removedEntries: string[];
+  removedFiles: string[];
+  freedBytes: number;
+}
+
+export async function listGeneratedMedia(
+  notebookId: string,
+  pageId?: string
+): Promise<GeneratedMedia[]> {
+  return invoke<GeneratedMedia[]>("list_generated_media", { notebookId, pageId });
+}
+
+/** Generate media again with its original options; `replace` deletes the old output */
+export async function regenerateMedia(
+  notebookId: string,
+  mediaId: string,
+  replace?: boolean
+): Promise<GeneratedMedia> {
+  return invoke<GeneratedMedia>("regenerate_media", { notebookId, mediaId, replace });
+}
+
+/** Delete orphaned generated media; with `dryRun` only report it */
+export async function cleanupGeneratedMedia(
+  notebookId: string,
+  dryRun?: boolean
+): Promise<MediaCleanupReport> {
+  return invoke<MediaCleanupReport>("cleanup_generated_media", { notebookId, dryRun });
+}
+
 // ===== Daily Notes API =====
 
 /**
@@ -3292,18 +5259,33 @@ export async function getDailyNote(
   }
 }
 
+/** Carrying unchecked items of recent daily notes into a new one for today */
+export interface DailyNoteRollover {
+  /** Days back to look for unchecked items (default 7) */
+  lookbackDays?: number;
+  /** Heading to put the carried items after (default: end of the note) */
+  insertAfterSection?: string;
+}
+
 /**
  * Create a daily note for a specific date
  */
 export async function createDailyNote(
   notebookId: string,
   date: string, // "YYYY-MM-DD" format
-  templateId?: string
+  templateId?: string,
+  rollover?: DailyNoteRollover
 ): Promise<Page> {
-  return daemonPost<Page>(
-    `/api/notebooks/${notebookId}/daily-notes/${date}`,
-    templateId !== undefined ? { template_id: templateId } : {}
-  );
+  return daemonPost<Page>(`/api/notebooks/${notebookId}/daily-notes/${date}`, {
+    ...(templateId !== undefined ? { template_id: templateId } : {}),
+    ...(rollover
+      ? {
+          rollover: true,
+          rollover_lookback_days: rollover.lookbackDays,
+          rollover_after_section: rollover.insertAfterSection,
+        }
+      : {}),
+  });
 }
 
 /**
@@ -3328,15 +5310,16 @@ export async function listDailyNotes(
  */
 export async function getOrCreateTodayDailyNote(
   notebookId: string,
-  templateId?: string
+  templateId?: string,
+  rollover?: DailyNoteRollover
 ): Promise<Page> {
   if (!isTauri()) {
     // The daemon's POST daily-notes/{date} is create-or-get; "today" is
     // computed browser-side in local time (same host/tz as the daemon in
     // the current single-user setup).
-    return createDailyNote(notebookId, localToday(), templateId);
+    return createDailyNote(notebookId, localToday(), templateId, rollover);
   }
-  return invoke<Page>("get_or_create_today_daily_note", { notebookId, templateId });
+  return invoke<Page>("get_or_create_today_daily_note", { notebookId, templateId, rollover });
 }
 
 /**
@@ -3360,6 +5343,392 @@ export async function unmarkDailyNote(
   return invoke<Page>("unmark_daily_note", { notebookId, pageId });
 }
 
+// ===== Page Stats API =====
+
+/**
+ * Word count, reading time and revision count of a page (null for encrypted
+ * and file-based pages)
+ */
+export async function getPageStats(
+  notebookId: string,
+  pageId: string
+): Promise<PageStats | null> {
+  return invoke<PageStats | null>("get_page_stats", { notebookId, pageId });
+}
+
+/**
+ * Writing statistics summed over a notebook's pages
+ */
+export async function getNotebookStats(notebookId: string): Promise<NotebookStats> {
+  return invoke<NotebookStats>("get_notebook_stats", { notebookId });
+}
+
+// ===== Content Lint API =====
+
+export type LintKind =
+  | "brokenLink"
+  | "missingAsset"
+  | "emptyPage"
+  | "duplicateTitle"
+  | "oversizedImage"
+  | "malformedBlock";
+
+export type LintFix =
+  | { action: "relink"; pageId: string; title: string }
+  | { action: "unlink" }
+  | { action: "newBlockId" };
+
+export interface LintDiagnostic {
+  /** Stays the same between runs while the problem is unchanged */
+  id: string;
+  kind: LintKind;
+  severity: "error" | "warning" | "info";
+  pageId: string;
+  pageTitle: string;
+  blockId?: string;
+  message: string;
+  /** Offered for problems that can be fixed without losing content */
+  fix?: LintFix;
+}
+
+export interface LintReport {
+  notebookId: string;
+  checkedAt: string;
+  pagesChecked: number;
+  /** Encrypted pages, whose content can't be checked */
+  pagesSkipped: number;
+  diagnostics: LintDiagnostic[];
+}
+
+export interface LintFixResult {
+  fixed: string[];
+  updatedPageIds: string[];
+  /** The notebook linted again after the fixes */
+  report: LintReport;
+}
+
+/**
+ * Check a notebook's pages for broken links, missing assets, empty pages,
+ * duplicate titles, oversized images and malformed blocks
+ */
+export async function lintNotebook(
+  notebookId: string,
+  maxImageBytes?: number
+): Promise<LintReport> {
+  return invoke<LintReport>("lint_notebook", { notebookId, maxImageBytes });
+}
+
+/**
+ * Apply the fixes offered with the given diagnostics and lint again
+ */
+export async function fixLintDiagnostics(
+  notebookId: string,
+  diagnosticIds: string[],
+  maxImageBytes?: number
+): Promise<LintFixResult> {
+  return invoke<LintFixResult>("fix_lint_diagnostics", {
+    notebookId,
+    diagnosticIds,
+    maxImageBytes,
+  });
+}
+
+// ===== Find and Replace API =====
+
+export type ReplaceScope =
+  | { type: "library" }
+  | { type: "notebook"; notebookId: string }
+  | { type: "section"; notebookId: string; sectionId: string }
+  | { type: "page"; notebookId: string; pageId: string };
+
+export interface PageReplacement {
+  notebookId: string;
+  pageId: string;
+  title: string;
+  matches: number;
+  /** Changed texts, as plain text before and after */
+  changes: { blockId: string; before: string; after: string }[];
+}
+
+export interface SearchReplaceResult {
+  pages: PageReplacement[];
+  totalMatches: number;
+  encryptedPagesSkipped: number;
+  /** Snapshot to roll back to for undo; null for a dry run */
+  snapshot: LibrarySnapshot | null;
+}
+
+/**
+ * Replace text in the text blocks of the pages in `scope`. With `dryRun`
+ * nothing is written and the result is a preview; otherwise a library
+ * snapshot is taken first. Code is left alone unless `includeCode` is set.
+ */
+export async function searchAndReplace(
+  query: string,
+  replacement: string,
+  scope: ReplaceScope,
+  options: {
+    regex?: boolean;
+    dryRun?: boolean;
+    caseSensitive?: boolean;
+    includeCode?: boolean;
+    /** Only these pages, e.g. those kept from a preview */
+    pageIds?: string[];
+  } = {}
+): Promise<SearchReplaceResult> {
+  return invoke<SearchReplaceResult>("search_and_replace", {
+    query,
+    replacement,
+    scope,
+    regex: options.regex ?? false,
+    dryRun: options.dryRun ?? false,
+    caseSensitive: options.caseSensitive,
+    includeCode: options.includeCode,
+    pageIds: options.pageIds,
+  });
+}
+
+// ===== Proofreading API =====
+
+export interface TextSpan {
+  blockId: string;
+  /** Path of the text field in the block's data, e.g. "text" or "items/0/content" */
+  field: string;
+  /** UTF-16 offsets into the field's text content */
+  start: number;
+  end: number;
+}
+
+export interface Misspelling {
+  span: TextSpan;
+  word: string;
+  suggestions: string[];
+}
+
+export interface GrammarSuggestion {
+  span: TextSpan;
+  text: string;
+  issue: string;
+  replacement: string;
+}
+
+export interface ProofreadReport {
+  pageId: string;
+  /** Dictionary used for spelling; null when none is installed */
+  language: string | null;
+  misspellings: Misspelling[];
+  grammarChecked: boolean;
+  grammar: GrammarSuggestion[];
+}
+
+/**
+ * Spell check a page against the local dictionary and the library's own
+ * words; with `grammar`, the AI also looks for grammar mistakes.
+ */
+export async function proofreadPage(
+  notebookId: string,
+  pageId: string,
+  options: {
+    language?: string;
+    grammar?: boolean;
+    providerType?: string;
+    apiKey?: string;
+    model?: string;
+    taskId?: string;
+  } = {}
+): Promise<ProofreadReport> {
+  return invoke<ProofreadReport>("proofread_page", {
+    notebookId,
+    pageId,
+    ...options,
+  });
+}
+
+export async function listSpellcheckLanguages(): Promise<string[]> {
+  return invoke<string[]>("list_spellcheck_languages");
+}
+
+export async function listDictionaryWords(): Promise<string[]> {
+  return invoke<string[]>("list_dictionary_words");
+}
+
+export async function addDictionaryWord(word: string): Promise<string[]> {
+  return invoke<string[]>("add_dictionary_word", { word });
+}
+
+export async function removeDictionaryWord(word: string): Promise<string[]> {
+  return invoke<string[]>("remove_dictionary_word", { word });
+}
+
+// ===== Code Execution API =====
+
+export type CodeLanguage = "shell" | "javascript" | "sql";
+
+export interface CodeRunnerInfo {
+  language: CodeLanguage;
+  available: boolean;
+}
+
+export interface CodeSandboxOptions {
+  timeoutSecs: number;
+  maxOutputBytes: number;
+  cleanEnv: boolean;
+  scratchDir: boolean;
+  noNetwork: boolean;
+  readOnlyDatabase: boolean;
+}
+
+export interface CodeExecConfig {
+  enabledLanguages: CodeLanguage[];
+  sandbox: CodeSandboxOptions;
+}
+
+export interface CodeCellResult {
+  blockId: string;
+  language: CodeLanguage;
+  success: boolean;
+  /** Jupyter-format outputs (stream, execute_result, error) */
+  outputs: unknown[];
+  executionCount: number;
+  durationMs: number;
+}
+
+export async function listCodeRunners(): Promise<CodeRunnerInfo[]> {
+  return invoke<CodeRunnerInfo[]>("list_code_runners");
+}
+
+export async function getCodeExecConfig(
+  notebookId: string
+): Promise<CodeExecConfig> {
+  return invoke<CodeExecConfig>("get_code_exec_config", { notebookId });
+}
+
+export async function setCodeExecConfig(
+  notebookId: string,
+  config: CodeExecConfig
+): Promise<CodeExecConfig> {
+  return invoke<CodeExecConfig>("set_code_exec_config", { notebookId, config });
+}
+
+/**
+ * Run a code block in a notebook that has enabled its language. The result
+ * is also stored on the block; SQL runs against `database`, a SQLite file
+ * relative to the notebook's assets.
+ */
+export async function executeCodeBlock(
+  notebookId: string,
+  pageId: string,
+  blockId: string,
+  options: { language?: string; database?: string } = {}
+): Promise<CodeCellResult> {
+  return invoke<CodeCellResult>("execute_code_block", {
+    notebookId,
+    pageId,
+    blockId,
+    ...options,
+  });
+}
+
+export async function clearCodeOutputs(
+  notebookId: string,
+  pageId: string
+): Promise<boolean> {
+  return invoke<boolean>("clear_code_outputs", { notebookId, pageId });
+}
+
+// ===== SQL Notebook API =====
+
+export interface SqlDataset {
+  name: string;
+  file: string;
+  format: "csv" | "parquet";
+  size: number;
+  addedAt: string;
+}
+
+export async function executeSqlCell(
+  notebookId: string,
+  pageId: string,
+  blockId: string
+): Promise<CodeCellResult> {
+  return invoke<CodeCellResult>("execute_sql_cell", {
+    notebookId,
+    pageId,
+    blockId,
+  });
+}
+
+export async function listDatasets(notebookId: string): Promise<SqlDataset[]> {
+  return invoke<SqlDataset[]>("list_datasets", { notebookId });
+}
+
+export async function addDataset(
+  notebookId: string,
+  path: string,
+  name?: string
+): Promise<SqlDataset> {
+  return invoke<SqlDataset>("add_dataset", { notebookId, path, name });
+}
+
+export async function removeDataset(
+  notebookId: string,
+  name: string
+): Promise<void> {
+  return invoke("remove_dataset", { notebookId, name });
+}
+
+// ===== Chart API =====
+
+export type ChartSource =
+  | { type: "table"; blockId: string }
+  | { type: "csv"; asset: string };
+
+export type ChartKind = "bar" | "line" | "area" | "pie" | "scatter";
+export type ChartAggregate = "none" | "sum" | "avg" | "min" | "max" | "count";
+export type ChartSort = "x" | "valueAsc" | "valueDesc";
+
+export interface ChartFilter {
+  column: string;
+  op: "eq" | "ne" | "gt" | "gte" | "lt" | "lte" | "contains";
+  value: string;
+}
+
+export interface ChartSpec {
+  kind: ChartKind;
+  x: string;
+  y: string[];
+  aggregate: ChartAggregate;
+  filters: ChartFilter[];
+  sort?: ChartSort | null;
+  limit?: number | null;
+}
+
+export interface ComputedChart {
+  kind: ChartKind;
+  columns: string[];
+  x: string;
+  labels: string[];
+  series: Array<{ name: string; values: Array<number | null> }>;
+  rowCount: number;
+  svg: string;
+}
+
+export async function computeChartData(
+  notebookId: string,
+  pageId: string,
+  source: ChartSource,
+  spec: ChartSpec,
+  title?: string
+): Promise<ComputedChart> {
+  return invoke<ComputedChart>("compute_chart_data", {
+    notebookId,
+    pageId,
+    source,
+    spec,
+    title,
+  });
+}
+
 // ===== Block History API =====
 
 export async function getBlockVersionCounts(
@@ -3469,3 +5838,34 @@ export async function monitorListWindows(): Promise<WindowInfo[]> {
 export async function monitorUnreadCount(): Promise<number> {
   return invoke<number>("monitor_unread_count");
 }
+
+// ===== WebDAV Share API =====
+
+export interface WebDavConfig {
+  readOnly?: boolean;
+  lan?: boolean;
+  port?: number;
+  includeArchived?: boolean;
+}
+
+export interface WebDavStatus {
+  url: string;
+  username: string;
+  password: string;
+  readOnly: boolean;
+  lan: boolean;
+}
+
+export async function startWebDavServer(
+  config?: WebDavConfig
+): Promise<WebDavStatus> {
+  return invoke<WebDavStatus>("webdav_server_start", { config });
+}
+
+export async function stopWebDavServer(): Promise<void> {
+  return invoke("webdav_server_stop");
+}
+
+export async function getWebDavServerStatus(): Promise<WebDavStatus | null> {
+  return invoke<WebDavStatus | null>("webdav_server_status");
+}
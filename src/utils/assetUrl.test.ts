@@ -16,6 +16,7 @@ vi.hoisted(() => {
 
 import {
   buildDaemonAssetUrl,
+  findNotebookAssetRefs,
   resolveAssetUrl,
   unresolveAssetUrl,
 } from "./assetUrl";
@@ -142,3 +143,21 @@ describe("unresolveAssetUrl", () => {
     expect(unresolveAssetUrl(resolveAssetUrl(stored))).toBe(stored);
   });
 });
+
+describe("findNotebookAssetRefs", () => {
+  it("finds assets in every stored form, once each", () => {
+    const abs = `/home/u/nous/notebooks/${NB}/assets/video/talk.mp4`;
+    const content = JSON.stringify({
+      blocks: [
+        { data: { url: `asset://${NB}/images/a%20b.png` } },
+        { data: { url: `asset://localhost/${encodeURIComponent(abs)}` } },
+        { data: { file: abs } },
+        { data: { url: "https://example.com/pic.png" } },
+      ],
+    });
+    expect(findNotebookAssetRefs(content)).toEqual([
+      { notebookId: NB, relPath: "images/a b.png" },
+      { notebookId: NB, relPath: "video/talk.mp4" },
+    ]);
+  });
+});
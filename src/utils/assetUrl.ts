@@ -97,6 +97,38 @@ export function resolveAssetUrl(url: string): string {
   return url;
 }
 
+/** A media file under a notebook's assets/ directory */
+export interface NotebookAssetRef {
+  notebookId: string;
+  relPath: string;
+}
+
+/**
+ * Find every notebook asset referenced anywhere in `text` (typically a
+ * page's serialized content), in any of the stored forms above.
+ */
+export function findNotebookAssetRefs(text: string): NotebookAssetRef[] {
+  // Up to the end of the quoted string, link or query; convertFileSrc URLs
+  // are percent-encoded, slashes included
+  const rest = `([^"'?#)<>\\\\\\n]+)`;
+  const sep = "(?:/|%2F)";
+  const patterns = [
+    new RegExp(`asset://(${UUID})/${rest}`, "gi"),
+    new RegExp(
+      `${sep}notebooks${sep}(${UUID})${sep}assets${sep}${rest}`,
+      "gi"
+    ),
+  ];
+  const found = new Map<string, NotebookAssetRef>();
+  for (const re of patterns) {
+    for (const m of text.matchAll(re)) {
+      const relPath = safeDecode(m[2]);
+      found.set(`${m[1]}/${relPath}`, { notebookId: m[1], relPath });
+    }
+  }
+  return [...found.values()];
+}
+
 /**
  * Reverse of resolveAssetUrl for persistence: a daemon asset URL (with or
  * without host and ?token=) becomes the stable asset://{nb}/{path} form.
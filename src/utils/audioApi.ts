@@ -1,6 +1,8 @@
 import { invoke } from "../platform/core";
 import type { TTSConfig, AudioGenerationResult } from "../types/audio";
 import type { TranscriptionResult } from "../types/audio";
+import type { PlaybackPosition, ReadingQueue } from "../types/audio";
+import type { MeetingNotesResult } from "../types/audio";
 
 export async function transcribeAudio(
   audioPath: string,
@@ -40,3 +42,68 @@ export async function saveAudioRecording(
     format,
   });
 }
+
+// ===== Reading Queues =====
+
+export async function listReadingQueues(notebookId?: string): Promise<ReadingQueue[]> {
+  return invoke<ReadingQueue[]>("list_reading_queues", { notebookId });
+}
+
+export async function createReadingQueue(
+  notebookId: string,
+  name: string,
+  pageIds: string[],
+): Promise<ReadingQueue> {
+  return invoke<ReadingQueue>("create_reading_queue", { notebookId, name, pageIds });
+}
+
+export async function updateReadingQueue(
+  queueId: string,
+  updates: { name?: string; pageIds?: string[] },
+): Promise<ReadingQueue> {
+  return invoke<ReadingQueue>("update_reading_queue", { queueId, ...updates });
+}
+
+export async function deleteReadingQueue(queueId: string): Promise<void> {
+  return invoke<void>("delete_reading_queue", { queueId });
+}
+
+/** Narrate the queue into one MP3 with a chapter per page */
+export async function generateReadingQueueAudio(
+  queueId: string,
+  ttsConfig: TTSConfig,
+): Promise<ReadingQueue> {
+  return invoke<ReadingQueue>("generate_reading_queue_audio", { queueId, ttsConfig });
+}
+
+export async function setReadingPosition(
+  queueId: string,
+  positionSeconds: number,
+): Promise<PlaybackPosition> {
+  return invoke<PlaybackPosition>("set_reading_position", { queueId, positionSeconds });
+}
+
+export interface MeetingRecordingOptions {
+  /** Contact ids of the attendees */
+  attendeeIds?: string[];
+  title?: string;
+  modelSize?: string;
+  language?: string;
+  linkDailyNote?: boolean;
+  providerType?: string;
+  apiKey?: string;
+  model?: string;
+  taskId?: string;
+}
+
+export async function processMeetingRecording(
+  notebookId: string,
+  audioPath: string,
+  options: MeetingRecordingOptions = {},
+): Promise<MeetingNotesResult> {
+  return invoke<MeetingNotesResult>("process_meeting_recording", {
+    notebookId,
+    audioPath,
+    ...options,
+  });
+}
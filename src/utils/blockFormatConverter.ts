@@ -23,6 +23,47 @@ export type BNInlineContent = Record<string, any>;
 export type BNDocument = BNBlock[];
 /* eslint-enable @typescript-eslint/no-explicit-any */
 
+// ─── Code block run results ─────────────────────────────────────────────────
+//
+// Running a code block (`execute_code_block`) stores Jupyter-style outputs,
+// an execution count and, for SQL, the database on the Editor.js block.
+// BlockNote's code block only has a `language` prop, so these ride alongside
+// it by block id and are put back on save — the outputs only while the code
+// is unchanged, since they describe that code.
+
+interface CodeBlockResult {
+  code: string;
+  outputs?: unknown[];
+  executionCount?: number;
+  database?: string;
+}
+
+const codeBlockResults = new Map<string, CodeBlockResult>();
+
+/** Record the stored result of a code block (after loading or running it). */
+export function rememberCodeBlockResult(
+  blockId: string,
+  result: CodeBlockResult
+): void {
+  codeBlockResults.set(blockId, result);
+}
+
+function codeBlockExtras(
+  blockId: string,
+  code: string
+): Record<string, unknown> {
+  const result = codeBlockResults.get(blockId);
+  if (!result) return {};
+  const extras: Record<string, unknown> = {};
+  if (result.database) extras.database = result.database;
+  if (result.code === code) {
+    if (result.outputs) extras.outputs = result.outputs;
+    if (result.executionCount !== undefined)
+      extras.executionCount = result.executionCount;
+  }
+  return extras;
+}
+
 // ─── Editor.js → BlockNote ──────────────────────────────────────────────────
 
 /**
@@ -119,6 +160,14 @@ function convertBlock(block: EditorBlock): BNBlock | BNBlock[] {
           props: { html: (data.code as string) ?? "" },
         };
       }
+      if (data.outputs || data.database) {
+        rememberCodeBlockResult(block.id, {
+          code: (data.code as string) ?? "",
+          outputs: data.outputs as unknown[] | undefined,
+          executionCount: data.executionCount as number | undefined,
+          database: data.database as string | undefined,
+        });
+      }
       return {
         id: block.id,
         type: "codeBlock",
@@ -185,6 +234,17 @@ function convertBlock(block: EditorBlock): BNBlock | BNBlock[] {
         },
       };
 
+    case "chart":
+      return {
+        id: block.id,
+        type: "chart",
+        props: {
+          title: (data.title as string) ?? "",
+          sourceJson: data.source ? JSON.stringify(data.source) : "",
+          specJson: data.spec ? JSON.stringify(data.spec) : "",
+        },
+      };
+
     case "blockEmbed":
       return {
         id: block.id,
@@ -752,20 +812,24 @@ function convertBlockToEditorJs(
     case "checkListItem":
       return collectChecklistItems(doc, index);
 
-    case "codeBlock":
+    case "codeBlock": {
+      const id = block.id ?? generateId();
+      const code = extractPlainText(block.content ?? []);
       return {
         blocks: [
           {
-            id: block.id ?? generateId(),
+            id,
             type: "code",
             data: {
-              code: extractPlainText(block.content ?? []),
+              code,
               language: block.props?.language ?? "plaintext",
+              ...codeBlockExtras(id, code),
             },
           },
         ],
         nextIndex: index + 1,
       };
+    }
 
     case "quote":
       return {
@@ -903,6 +967,26 @@ function convertBlockToEditorJs(
         nextIndex: index + 1,
       };
 
+    case "chart":
+      return {
+        blocks: [
+          {
+            id: block.id ?? generateId(),
+            type: "chart",
+            data: {
+              title: block.props?.title ?? "",
+              source: block.props?.sourceJson
+                ? JSON.parse(block.props.sourceJson)
+                : undefined,
+              spec: block.props?.specJson
+                ? JSON.parse(block.props.specJson)
+                : undefined,
+            },
+          },
+        ],
+        nextIndex: index + 1,
+      };
+
     case "blockEmbed":
       return {
         blocks: [
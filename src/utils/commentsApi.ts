@@ -0,0 +1,69 @@
+import { invoke } from "../platform/core";
+import type { Comment, CommentThread, NewComment } from "../types/comments";
+
+export async function listPageComments(
+  notebookId: string,
+  pageId: string,
+  includeResolved = false
+): Promise<CommentThread[]> {
+  return invoke<CommentThread[]>("list_page_comments", {
+    notebookId,
+    pageId,
+    includeResolved,
+  });
+}
+
+export async function addComment(
+  notebookId: string,
+  pageId: string,
+  request: NewComment
+): Promise<Comment> {
+  return invoke<Comment>("add_comment", { notebookId, pageId, request });
+}
+
+export async function updateComment(
+  notebookId: string,
+  pageId: string,
+  commentId: string,
+  body: string
+): Promise<Comment> {
+  return invoke<Comment>("update_comment", {
+    notebookId,
+    pageId,
+    commentId,
+    body,
+  });
+}
+
+export async function deleteComment(
+  notebookId: string,
+  pageId: string,
+  commentId: string
+): Promise<void> {
+  return invoke("delete_comment", { notebookId, pageId, commentId });
+}
+
+export async function resolveCommentThread(
+  notebookId: string,
+  pageId: string,
+  commentId: string,
+  resolved: boolean,
+  resolvedBy?: string
+): Promise<Comment> {
+  return invoke<Comment>("resolve_comment_thread", {
+    notebookId,
+    pageId,
+    commentId,
+    resolved,
+    resolvedBy,
+  });
+}
+
+/** Unresolved thread count per page id (pages without open threads omitted). */
+export async function getUnresolvedCommentCounts(
+  notebookId: string
+): Promise<Record<string, number>> {
+  return invoke<Record<string, number>>("get_unresolved_comment_counts", {
+    notebookId,
+  });
+}
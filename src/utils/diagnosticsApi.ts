@@ -0,0 +1,98 @@
+import { invoke } from "../platform/core";
+
+export type DiagnosticsCategory = "storage" | "sync" | "search" | "python";
+
+export interface OperationStats {
+  category: DiagnosticsCategory;
+  operation: string;
+  count: number;
+  totalMs: number;
+  meanMs: number;
+  minMs: number;
+  maxMs: number;
+  p50Ms: number;
+  p95Ms: number;
+  p99Ms: number;
+  /** Non-empty buckets; `leMs` is null for the overflow bucket */
+  buckets: { leMs: number | null; count: number }[];
+}
+
+export interface PerformanceReport {
+  generatedAt: string;
+  collectingSince: string;
+  process: string;
+  appVersion: string;
+  os: string;
+  arch: string;
+  operations: OperationStats[];
+  traceFile?: string;
+}
+
+export async function getPerformanceReport(): Promise<PerformanceReport> {
+  return invoke<PerformanceReport>("get_performance_report");
+}
+
+export async function resetPerformanceMetrics(): Promise<void> {
+  return invoke("reset_performance_metrics");
+}
+
+/** Start/stop the JSONL trace; resolves to the trace file path */
+export async function setPerformanceTrace(
+  enabled: boolean
+): Promise<string | null> {
+  return invoke<string | null>("set_performance_trace", { enabled });
+}
+
+export type LogLevel = "off" | "error" | "warn" | "info" | "debug" | "trace";
+
+export interface LogEntry {
+  timestamp: string;
+  level: LogLevel;
+  target: string;
+  /** Credentials are redacted before lines are kept or written */
+  message: string;
+}
+
+export interface LogSettings {
+  level: LogLevel;
+  /** Overrides by subsystem ("sync") or log target prefix */
+  modules: Record<string, LogLevel>;
+  maxFileBytes: number;
+  maxFiles: number;
+}
+
+export interface LogSettingsInfo {
+  settings: LogSettings;
+  logDir: string;
+  subsystems: string[];
+}
+
+/** Latest app log entries, oldest first, down to `level` in severity */
+export async function getRecentLogs(
+  limit?: number,
+  level?: LogLevel,
+  filter?: string
+): Promise<LogEntry[]> {
+  return invoke<LogEntry[]>("get_recent_logs", { limit, level, filter });
+}
+
+export async function getLogSettings(): Promise<LogSettingsInfo> {
+  return invoke<LogSettingsInfo>("get_log_settings");
+}
+
+export async function setLogSettings(settings: LogSettings): Promise<void> {
+  return invoke("set_log_settings", { settings });
+}
+
+export interface DiagnosticBundleSummary {
+  path: string;
+  files: string[];
+  sizeBytes: number;
+  /** Reports that couldn't be created, by file */
+  errors: string[];
+}
+
+/** Write a scrubbed ZIP of logs and health reports to attach to a bug report */
+export async function createDiagnosticBundle(outputPath?: string): Promise<DiagnosticBundleSummary> {
+  return invoke<DiagnosticBundleSummary>("create_diagnostic_bundle", { outputPath });
+}
@@ -6,6 +6,14 @@ import type {
   ReviewStats,
   CardWithState,
   CardType,
+  BlockCardSync,
+  ReadingItem,
+  ReviewQueueItem,
+  DeckAnalytics,
+  LeechCard,
+  DeckBundleInfo,
+  DeckImportResult,
+  ImportCollision,
 } from "../types/flashcard";
 
 // ===== Deck API =====
@@ -87,18 +95,32 @@ export async function createCardFromBlock(
   pageId: string,
   blockId: string,
   front: string,
-  back: string
-): Promise<Flashcard> {
-  return invoke<Flashcard>("create_card_from_block", {
+  back: string,
+  cloze?: {
+    // Character ranges of `front` to hide; omit when `front` has {{c1::...}} markup
+    ranges?: { start: number; end: number }[];
+  },
+  cardType?: CardType
+): Promise<Flashcard[]> {
+  return invoke<Flashcard[]>("create_card_from_block", {
     notebookId,
     deckId,
     pageId,
     blockId,
     front,
     back,
+    cardType: cloze ? "cloze" : cardType,
+    clozeRanges: cloze?.ranges,
   });
 }
 
+export async function syncBlockCards(
+  notebookId: string,
+  pageId?: string
+): Promise<BlockCardSync> {
+  return invoke<BlockCardSync>("sync_block_cards", { notebookId, pageId });
+}
+
 export async function updateCard(
   notebookId: string,
   cardId: string,
@@ -147,6 +169,31 @@ export async function getReviewStats(
   return invoke<ReviewStats>("get_review_stats", { notebookId, deckId });
 }
 
+export async function getDeckAnalytics(
+  notebookId: string,
+  deckId?: string,
+  options?: { days?: number; leechThreshold?: number }
+): Promise<DeckAnalytics> {
+  return invoke<DeckAnalytics>("get_deck_analytics", {
+    notebookId,
+    deckId,
+    days: options?.days,
+    leechThreshold: options?.leechThreshold,
+  });
+}
+
+export async function listLeechCards(
+  notebookId: string,
+  deckId?: string,
+  threshold?: number
+): Promise<LeechCard[]> {
+  return invoke<LeechCard[]>("list_leech_cards", {
+    notebookId,
+    deckId,
+    threshold,
+  });
+}
+
 export async function getCardState(
   notebookId: string,
   cardId: string
@@ -163,3 +210,107 @@ export async function previewReviewIntervals(
     cardId,
   });
 }
+
+// ===== Incremental Reading API =====
+
+export async function addIrExtract(
+  notebookId: string,
+  pageId: string,
+  options?: {
+    text?: string;
+    blockIds?: string[];
+    title?: string;
+    priority?: number;
+  }
+): Promise<ReadingItem> {
+  return invoke<ReadingItem>("add_ir_extract", {
+    notebookId,
+    pageId,
+    text: options?.text,
+    blockIds: options?.blockIds,
+    title: options?.title,
+    priority: options?.priority,
+  });
+}
+
+export async function listIrItems(notebookId: string): Promise<ReadingItem[]> {
+  return invoke<ReadingItem[]>("list_ir_items", { notebookId });
+}
+
+export async function deleteIrItem(notebookId: string, itemId: string): Promise<void> {
+  return invoke("delete_ir_item", { notebookId, itemId });
+}
+
+export async function getIrQueue(
+  notebookId: string,
+  deckId?: string,
+  cardsPerReading?: number
+): Promise<ReviewQueueItem[]> {
+  return invoke<ReviewQueueItem[]>("get_ir_queue", {
+    notebookId,
+    deckId,
+    cardsPerReading,
+  });
+}
+
+export async function gradeIrItem(
+  notebookId: string,
+  itemId: string,
+  rating: number,
+  options?: { priority?: number; done?: boolean }
+): Promise<ReadingItem> {
+  return invoke<ReadingItem>("grade_ir_item", {
+    notebookId,
+    itemId,
+    rating,
+    priority: options?.priority,
+    done: options?.done,
+  });
+}
+
+// ===== Shared Deck API =====
+
+export async function getSharedDecksFolder(): Promise<string> {
+  return invoke<string>("get_shared_decks_folder");
+}
+
+export async function setSharedDecksFolder(path: string | null): Promise<string> {
+  return invoke<string>("set_shared_decks_folder", { path });
+}
+
+export async function exportDeckBundle(
+  notebookId: string,
+  deckId: string,
+  options?: { path?: string; includeScheduling?: boolean }
+): Promise<DeckBundleInfo> {
+  return invoke<DeckBundleInfo>("export_deck_bundle", {
+    notebookId,
+    deckId,
+    path: options?.path,
+    includeScheduling: options?.includeScheduling,
+  });
+}
+
+export async function inspectDeckBundle(
+  notebookId: string,
+  path: string
+): Promise<DeckBundleInfo> {
+  return invoke<DeckBundleInfo>("inspect_deck_bundle", { notebookId, path });
+}
+
+export async function listSharedDecks(notebookId: string): Promise<DeckBundleInfo[]> {
+  return invoke<DeckBundleInfo[]>("list_shared_decks", { notebookId });
+}
+
+export async function importDeckBundle(
+  notebookId: string,
+  path: string,
+  options?: { collision?: ImportCollision; withScheduling?: boolean }
+): Promise<DeckImportResult> {
+  return invoke<DeckImportResult>("import_deck_bundle", {
+    notebookId,
+    path,
+    collision: options?.collision,
+    withScheduling: options?.withScheduling,
+  });
+}